@@ -0,0 +1,259 @@
+//! Fetcher for a single thread from 4chan or a site serving the same JSON API.
+//!
+//! Accepts a thread's page URL (`https://boards.4chan.org/g/thread/123`) or its
+//! API URL (`https://a.4cdn.org/g/thread/123.json`). Other hosts are asked for
+//! `<url>.json`, which covers vichan's API (`/b/res/123.json`) and archives
+//! that mirror 4chan's. Files are downloaded next to each other in a scratch
+//! directory, so `writer.rs` reads them like any other import.
+//!
+//! Requests are spaced one second apart, as 4chan's API rules ask.
+
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::Context;
+use chrono::DateTime;
+use serde::Deserialize;
+
+use crate::source::{strip_html, SourceFile, SourcePost, SourceThread};
+
+/// Minimum gap between two requests to the same site.
+const REQUEST_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Where a thread and its files are fetched from.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ThreadUrl {
+    /// The thread's JSON.
+    pub api:        String,
+    /// Source board URI, e.g. `g`.
+    pub board:      String,
+    /// Base URL that `<tim><ext>` is appended to for originals.
+    pub media_base: String,
+    /// Whether `<tim>s.jpg` thumbnails sit next to the originals (4chan's layout).
+    pub thumbs:     bool,
+}
+
+impl ThreadUrl {
+    /// Work out the API and media URLs for the thread page or API URL `url`.
+    /// `media_base` overrides where files are downloaded from.
+    pub fn parse(url: &str, media_base: Option<&str>) -> anyhow::Result<Self> {
+        let url = url.split(['#', '?']).next().unwrap_or_default();
+        let (scheme, rest) = url.split_once("://").context("thread URL must start with http:// or https://")?;
+        let (host, path) = rest.split_once('/').context("thread URL has no path")?;
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+        let (board, number) = match segments.as_slice() {
+            [board, "thread" | "res", number, ..] => (*board, *number),
+            _ => anyhow::bail!("expected a thread URL like https://boards.4chan.org/<board>/thread/<number>"),
+        };
+        let number = number.trim_end_matches(".json").trim_end_matches(".html");
+        if number.is_empty() || !number.bytes().all(|b| b.is_ascii_digit()) {
+            anyhow::bail!("{number:?} is not a thread number");
+        }
+
+        let is_4chan = ["4chan.org", "4channel.org", "4cdn.org"].iter().any(|d| host.ends_with(d));
+        let api = if is_4chan {
+            format!("https://a.4cdn.org/{board}/thread/{number}.json")
+        } else {
+            let kind = if segments[1] == "res" { "res" } else { "thread" };
+            format!("{scheme}://{host}/{board}/{kind}/{number}.json")
+        };
+        let default_media = if is_4chan {
+            format!("https://i.4cdn.org/{board}/")
+        } else {
+            format!("{scheme}://{host}/{board}/src/")
+        };
+        let media_base = media_base.map_or(default_media, |base| format!("{}/", base.trim_end_matches('/')));
+        Ok(Self { api, board: board.to_owned(), thumbs: is_4chan || media_base.contains("4cdn.org"), media_base })
+    }
+}
+
+/// The thread JSON: every post, OP first.
+#[derive(Deserialize)]
+struct ThreadJson {
+    posts: Vec<PostJson>,
+}
+
+/// One post in 4chan's thread JSON. Absent flags mean false.
+#[derive(Deserialize)]
+struct PostJson {
+    no:          u64,
+    time:        i64,
+    #[serde(default)]
+    name:        Option<String>,
+    #[serde(default)]
+    trip:        Option<String>,
+    #[serde(default)]
+    sub:         Option<String>,
+    /// Comment as HTML.
+    #[serde(default)]
+    com:         Option<String>,
+    #[serde(default)]
+    sticky:      u8,
+    #[serde(default)]
+    closed:      u8,
+    /// Server-side filename (a timestamp); vichan writes it as a string.
+    #[serde(default)]
+    tim:         Option<serde_json::Value>,
+    #[serde(default)]
+    filename:    Option<String>,
+    #[serde(default)]
+    ext:         Option<String>,
+    #[serde(default)]
+    w:           Option<u32>,
+    #[serde(default)]
+    h:           Option<u32>,
+    #[serde(default)]
+    spoiler:     u8,
+    #[serde(default)]
+    filedeleted: u8,
+}
+
+/// Download the thread at `url` and its files into `dir`.
+///
+/// Files that fail to download are left out of `dir`; the writer then skips
+/// them and counts them as missing.
+pub async fn fetch(client: &reqwest::Client, url: &ThreadUrl, dir: &Path, files: bool) -> anyhow::Result<SourceThread> {
+    let json: ThreadJson = client
+        .get(&url.api)
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .with_context(|| format!("failed to fetch {}", url.api))?
+        .json()
+        .await
+        .with_context(|| format!("{} is not a thread in 4chan's JSON format", url.api))?;
+    let thread = thread(json, url, dir)?;
+    if !files {
+        return Ok(thread);
+    }
+
+    for post in &thread.posts {
+        for file in &post.files {
+            let name = file.path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+            download(client, &format!("{}{name}", url.media_base), &file.path).await;
+            if let Some(thumb) = &file.thumb {
+                let name = thumb.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+                download(client, &format!("{}{name}", url.media_base), thumb).await;
+            }
+        }
+    }
+    Ok(thread)
+}
+
+/// Save `url` to `path`, logging instead of failing.
+async fn download(client: &reqwest::Client, url: &str, path: &Path) {
+    tokio::time::sleep(REQUEST_INTERVAL).await;
+    let result = async {
+        let bytes = client.get(url).send().await?.error_for_status()?.bytes().await?;
+        tokio::fs::write(path, bytes).await?;
+        anyhow::Ok(())
+    };
+    if let Err(e) = result.await {
+        tracing::warn!(url, error = %e, "download failed; importing the post without it");
+    }
+}
+
+fn thread(json: ThreadJson, url: &ThreadUrl, dir: &Path) -> anyhow::Result<SourceThread> {
+    let op = json.posts.first().context("the thread has no posts")?;
+    let (sticky, locked) = (op.sticky != 0, op.closed != 0);
+    let posts = json
+        .posts
+        .into_iter()
+        .map(|p| {
+            let created_at = DateTime::from_timestamp(p.time, 0).with_context(|| format!("post {}: invalid time", p.no))?;
+            let files = match (&p.tim, &p.ext) {
+                (Some(tim), Some(ext)) if p.filedeleted == 0 => {
+                    let tim = match tim {
+                        serde_json::Value::String(s) => s.clone(),
+                        other => other.to_string(),
+                    };
+                    vec![SourceFile {
+                        original_name: format!("{}{ext}", p.filename.as_deref().map_or_else(|| tim.clone(), strip_html)),
+                        path:          dir.join(format!("{tim}{ext}")),
+                        thumb:         url.thumbs.then(|| dir.join(format!("{tim}s.jpg"))),
+                        width:         p.w,
+                        height:        p.h,
+                        spoiler:       p.spoiler != 0,
+                    }]
+                }
+                _ => Vec::new(),
+            };
+            Ok(SourcePost {
+                number: p.no,
+                created_at,
+                name: p.name.filter(|n| !n.is_empty()),
+                tripcode: p.trip,
+                email: None,
+                subject: p.sub.map(|s| strip_html(&s)).filter(|s| !s.is_empty()),
+                body: p.com.as_deref().map(strip_html).unwrap_or_default(),
+                ip: None,
+                files,
+            })
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    Ok(SourceThread { sticky, locked, cycle: false, bumped_at: None, posts })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn page_urls_map_to_the_4chan_api() {
+        let url = ThreadUrl::parse("https://boards.4chan.org/g/thread/12345/some-slug#p12346", None).unwrap();
+        assert_eq!(url, ThreadUrl {
+            api:        "https://a.4cdn.org/g/thread/12345.json".to_owned(),
+            board:      "g".to_owned(),
+            media_base: "https://i.4cdn.org/g/".to_owned(),
+            thumbs:     true,
+        });
+        let api = ThreadUrl::parse("https://a.4cdn.org/g/thread/12345.json", None).unwrap();
+        assert_eq!(api.api, url.api);
+    }
+
+    #[test]
+    fn other_hosts_use_their_own_json_and_src() {
+        let url = ThreadUrl::parse("https://example.net/b/res/77.html", None).unwrap();
+        assert_eq!(url.api, "https://example.net/b/res/77.json");
+        assert_eq!(url.media_base, "https://example.net/b/src/");
+        assert!(!url.thumbs);
+
+        let url = ThreadUrl::parse("https://example.net/b/res/77.html", Some("https://cdn.example.net/b")).unwrap();
+        assert_eq!(url.media_base, "https://cdn.example.net/b/");
+    }
+
+    #[test]
+    fn non_thread_urls_are_rejected() {
+        assert!(ThreadUrl::parse("https://boards.4chan.org/g/catalog", None).is_err());
+        assert!(ThreadUrl::parse("boards.4chan.org/g/thread/1", None).is_err());
+        assert!(ThreadUrl::parse("https://boards.4chan.org/g/thread/abc", None).is_err());
+    }
+
+    #[test]
+    fn posts_and_files_are_read_from_the_json() {
+        let json: ThreadJson = serde_json::from_str(
+            r##"{"posts":[
+                {"no":100,"resto":0,"closed":1,"time":1700000000,"name":"Anonymous","sub":"Old &amp; gold","com":"first<br>line","tim":1700000000123,"filename":"cat","ext":".png","w":640,"h":480,"spoiler":1},
+                {"no":101,"resto":100,"time":1700000060,"name":"Anonymous","trip":"!abc","com":"<a href=\"#p100\" class=\"quotelink\">&gt;&gt;100</a><br><span class=\"quote\">&gt;implying</span>"},
+                {"no":102,"resto":100,"time":1700000120,"tim":1700000120456,"filename":"gone","ext":".jpg","filedeleted":1}
+            ]}"##,
+        )
+        .unwrap();
+        let url = ThreadUrl::parse("https://boards.4chan.org/g/thread/100", None).unwrap();
+        let thread = thread(json, &url, Path::new("/scratch")).unwrap();
+
+        assert!(thread.locked && !thread.sticky);
+        let op = &thread.posts[0];
+        assert_eq!(op.subject.as_deref(), Some("Old & gold"));
+        assert_eq!(op.body, "first\nline");
+        let [file] = op.files.as_slice() else { panic!("expected one file") };
+        assert_eq!(file.original_name, "cat.png");
+        assert_eq!(file.path, Path::new("/scratch/1700000000123.png"));
+        assert_eq!(file.thumb.as_deref(), Some(Path::new("/scratch/1700000000123s.jpg")));
+        assert!(file.spoiler);
+
+        assert_eq!(thread.posts[1].body, ">>100\n>implying");
+        assert_eq!(thread.posts[1].tripcode.as_deref(), Some("!abc"));
+        assert!(thread.posts[2].files.is_empty());
+    }
+}
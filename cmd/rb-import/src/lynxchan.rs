@@ -0,0 +1,298 @@
+//! Reader for LynxChan MongoDB exports.
+//!
+//! Takes a directory holding `boards.json`, `threads.json` and `posts.json`, as
+//! written by `mongoexport --collection <name> --out <name>.json` (one document
+//! per line, or a single array with `--jsonArray`). Extended-JSON wrappers such
+//! as `{"$date": …}` and `{"$numberLong": …}` are unwrapped.
+//!
+//! LynxChan keeps uploads in GridFS under their `/.media/…` paths; extract them
+//! (e.g. with `mongofiles get`) into a directory so that each file sits at
+//! `<media-root>/.media/…`.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+use crate::source::{SourceBoard, SourceFile, SourcePost, SourceThread};
+
+/// Read every board in the export at `dir`, or only those named in `only`
+/// when non-empty.
+pub fn read(dir: &Path, media_root: &Path, only: &[String]) -> anyhow::Result<Vec<SourceBoard>> {
+    let wanted = |uri: &str| only.is_empty() || only.iter().any(|o| o == uri);
+
+    let mut boards: Vec<SourceBoard> = Vec::new();
+    for doc in collection(dir, "boards")? {
+        let uri = string(&doc, "boardUri").context("board without a boardUri")?;
+        if !wanted(&uri) {
+            continue;
+        }
+        boards.push(SourceBoard {
+            title: string(&doc, "boardName").unwrap_or_else(|| uri.clone()),
+            rules: string(&doc, "boardDescription").unwrap_or_default(),
+            uri,
+            threads: Vec::new(),
+        });
+    }
+    if let Some(missing) = only.iter().find(|uri| !boards.iter().any(|b| &b.uri == *uri)) {
+        anyhow::bail!("board /{missing}/ is not in the export");
+    }
+
+    // (board, thread ID) → thread, with the OP as its first post.
+    let mut threads: HashMap<(String, u64), SourceThread> = HashMap::new();
+    for doc in collection(dir, "threads")? {
+        let Some(uri) = string(&doc, "boardUri").filter(|uri| wanted(uri)) else { continue };
+        let id = number(&doc, "threadId").with_context(|| format!("/{uri}/: thread without a threadId"))?;
+        let op = read_post(&doc, id, media_root).with_context(|| format!("/{uri}/{id}"))?;
+        threads.insert((uri, id), SourceThread {
+            sticky: flag(&doc, "pinned"),
+            locked: flag(&doc, "locked"),
+            cycle: flag(&doc, "cyclic"),
+            bumped_at: date(&doc, "lastBump"),
+            posts: vec![op],
+        });
+    }
+
+    for doc in collection(dir, "posts")? {
+        let Some(uri) = string(&doc, "boardUri").filter(|uri| wanted(uri)) else { continue };
+        let id = number(&doc, "postId").with_context(|| format!("/{uri}/: post without a postId"))?;
+        let post = read_post(&doc, id, media_root).with_context(|| format!("/{uri}/{id}"))?;
+        let thread = number(&doc, "threadId").unwrap_or_default();
+        match threads.get_mut(&(uri.clone(), thread)) {
+            Some(t) => t.posts.push(post),
+            None => tracing::warn!(board = %uri, thread, post = id, "reply to a missing thread skipped"),
+        }
+    }
+
+    for ((uri, _), mut thread) in threads {
+        thread.posts[1..].sort_by_key(|p| p.number);
+        if let Some(board) = boards.iter_mut().find(|b| b.uri == uri) {
+            board.threads.push(thread);
+        }
+    }
+    for board in &mut boards {
+        board.threads.sort_by_key(|t| t.posts[0].number);
+    }
+    Ok(boards)
+}
+
+fn read_post(doc: &Value, number: u64, media_root: &Path) -> anyhow::Result<SourcePost> {
+    let created_at = date(doc, "creation").context("missing creation date")?;
+    let files = match doc.get("files") {
+        Some(Value::Array(files)) => files.iter().map(|f| read_file(f, media_root)).collect::<anyhow::Result<_>>()?,
+        _ => Vec::new(),
+    };
+    Ok(SourcePost {
+        number,
+        created_at,
+        name: string(doc, "name"),
+        tripcode: string(doc, "trip"),
+        email: string(doc, "email"),
+        subject: string(doc, "subject"),
+        body: string(doc, "message").unwrap_or_default(),
+        ip: doc.get("ip").and_then(ip),
+        files,
+    })
+}
+
+fn read_file(doc: &Value, media_root: &Path) -> anyhow::Result<SourceFile> {
+    let path = string(doc, "path").context("file without a path")?;
+    // Spoilered files point at the board's or the site's spoiler image
+    // instead of a thumbnail of their own.
+    let thumb = string(doc, "thumb");
+    let spoiler = thumb.as_deref().is_some_and(|t| !t.starts_with("/.media/"));
+    let media = |p: &str| media_root.join(p.trim_start_matches('/'));
+    Ok(SourceFile {
+        original_name: string(doc, "originalName").unwrap_or_else(|| path.rsplit('/').next().unwrap_or_default().to_owned()),
+        path: media(&path),
+        thumb: thumb.filter(|_| !spoiler).filter(|t| *t != path).map(|t| media(&t)),
+        width: number(doc, "width").and_then(|n| u32::try_from(n).ok()),
+        height: number(doc, "height").and_then(|n| u32::try_from(n).ok()),
+        spoiler,
+    })
+}
+
+/// The documents of `<dir>/<name>.json`; empty when the file is absent.
+fn collection(dir: &Path, name: &str) -> anyhow::Result<Vec<Value>> {
+    let path = dir.join(format!("{name}.json"));
+    let text = match std::fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            tracing::warn!(path = %path.display(), "collection export not found; skipping it");
+            return Ok(Vec::new());
+        }
+        Err(e) => return Err(e).with_context(|| format!("failed to read {}", path.display())),
+    };
+    parse_documents(&text).with_context(|| format!("invalid export {}", path.display()))
+}
+
+/// Documents written one per line or as a single array.
+fn parse_documents(text: &str) -> anyhow::Result<Vec<Value>> {
+    if text.trim_start().starts_with('[') {
+        return Ok(serde_json::from_str(text)?);
+    }
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| serde_json::from_str(line).with_context(|| format!("line {}", i + 1)))
+        .collect()
+}
+
+fn string(doc: &Value, field: &str) -> Option<String> {
+    doc.get(field).and_then(Value::as_str).filter(|s| !s.is_empty()).map(str::to_owned)
+}
+
+/// A number, plain or wrapped as `{"$numberInt": "…"}` / `{"$numberLong": "…"}`.
+fn number(doc: &Value, field: &str) -> Option<u64> {
+    unwrap_number(doc.get(field)?)
+}
+
+fn unwrap_number(value: &Value) -> Option<u64> {
+    match value {
+        Value::Number(n) => n.as_u64(),
+        Value::Object(o) => o
+            .get("$numberInt")
+            .or_else(|| o.get("$numberLong"))
+            .or_else(|| o.get("$numberDouble"))
+            .and_then(Value::as_str)
+            .and_then(|s| s.parse::<f64>().ok())
+            .filter(|n| *n >= 0.0)
+            .map(|n| n as u64),
+        _ => None,
+    }
+}
+
+fn flag(doc: &Value, field: &str) -> bool {
+    doc.get(field).and_then(Value::as_bool).unwrap_or(false)
+}
+
+/// A date written as `{"$date": "<RFC 3339>"}`, `{"$date": <millis>}` or
+/// `{"$date": {"$numberLong": "<millis>"}}`.
+fn date(doc: &Value, field: &str) -> Option<DateTime<Utc>> {
+    let value = doc.get(field)?;
+    let value = value.get("$date").unwrap_or(value);
+    match value {
+        Value::String(s) => DateTime::parse_from_rfc3339(s).ok().map(|d| d.with_timezone(&Utc)),
+        other => unwrap_number(other).and_then(|ms| DateTime::from_timestamp_millis(i64::try_from(ms).ok()?)),
+    }
+}
+
+/// LynxChan stores addresses as arrays of octets (4 for IPv4, 16 for IPv6).
+fn ip(value: &Value) -> Option<String> {
+    let octets: Vec<u8> = value
+        .as_array()?
+        .iter()
+        .map(|o| unwrap_number(o).and_then(|n| u8::try_from(n).ok()))
+        .collect::<Option<_>>()?;
+    match octets.len() {
+        4 => Some(std::net::Ipv4Addr::new(octets[0], octets[1], octets[2], octets[3]).to_string()),
+        16 => Some(std::net::Ipv6Addr::from(<[u8; 16]>::try_from(octets).ok()?).to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A throwaway export directory, removed on drop.
+    struct Export(std::path::PathBuf);
+
+    impl Export {
+        fn path(&self) -> &Path {
+            &self.0
+        }
+    }
+
+    impl Drop for Export {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn export() -> Export {
+        let dir = Export(std::env::temp_dir().join(format!("rb-import-lynxchan-{}", uuid::Uuid::new_v4())));
+        std::fs::create_dir(dir.path()).unwrap();
+        std::fs::write(
+            dir.path().join("boards.json"),
+            r#"[{"boardUri":"a","boardName":"Anime","boardDescription":"Cartoons"},{"boardUri":"b","boardName":"Random"}]"#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("threads.json"),
+            concat!(
+                r#"{"boardUri":"a","threadId":{"$numberInt":"10"},"subject":"First","name":"Anon","message":">>>/b/1 hi","creation":{"$date":"2023-01-02T03:04:05.000Z"},"lastBump":{"$date":{"$numberLong":"1672700000000"}},"pinned":true,"cyclic":false,"ip":[10,0,0,1],"files":[{"originalName":"cat.png","path":"/.media/abc.png","thumb":"/.media/t_abc","mime":"image/png","width":640,"height":480}]}"#,
+                "\n",
+                r#"{"boardUri":"b","threadId":1,"message":"other board","creation":{"$date":1672600000000}}"#,
+                "\n",
+            ),
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("posts.json"),
+            concat!(
+                r#"{"boardUri":"a","threadId":10,"postId":12,"message":"second","creation":{"$date":"2023-01-02T03:06:00Z"},"files":[{"originalName":"s.jpg","path":"/.media/def.jpg","thumb":"/spoiler.png"}]}"#,
+                "\n",
+                r#"{"boardUri":"a","threadId":10,"postId":11,"message":"first","creation":{"$date":"2023-01-02T03:05:00Z"}}"#,
+                "\n",
+            ),
+        )
+        .unwrap();
+        dir
+    }
+
+    #[test]
+    fn reads_boards_threads_and_posts() {
+        let dir = export();
+        let boards = read(dir.path(), Path::new("/media"), &[]).unwrap();
+        assert_eq!(boards.len(), 2);
+
+        let a = &boards[0];
+        assert_eq!((a.uri.as_str(), a.title.as_str(), a.rules.as_str()), ("a", "Anime", "Cartoons"));
+        let [thread] = a.threads.as_slice() else { panic!("expected one thread") };
+        assert!(thread.sticky && !thread.cycle);
+        assert_eq!(thread.bumped_at, DateTime::from_timestamp_millis(1_672_700_000_000));
+        let numbers: Vec<u64> = thread.posts.iter().map(|p| p.number).collect();
+        assert_eq!(numbers, [10, 11, 12]);
+
+        let op = &thread.posts[0];
+        assert_eq!(op.subject.as_deref(), Some("First"));
+        assert_eq!(op.body, ">>>/b/1 hi");
+        assert_eq!(op.ip.as_deref(), Some("10.0.0.1"));
+        assert_eq!(op.created_at, DateTime::parse_from_rfc3339("2023-01-02T03:04:05Z").unwrap());
+    }
+
+    #[test]
+    fn resolves_media_under_the_media_root() {
+        let dir = export();
+        let boards = read(dir.path(), Path::new("/media"), &["a".to_owned()]).unwrap();
+        let posts = &boards[0].threads[0].posts;
+
+        let [file] = posts[0].files.as_slice() else { panic!("expected one file") };
+        assert_eq!(file.path, Path::new("/media/.media/abc.png"));
+        assert_eq!(file.thumb.as_deref(), Some(Path::new("/media/.media/t_abc")));
+        assert_eq!((file.width, file.height), (Some(640), Some(480)));
+
+        let [spoiler] = posts[2].files.as_slice() else { panic!("expected one file") };
+        assert!(spoiler.spoiler);
+        assert_eq!(spoiler.thumb, None);
+    }
+
+    #[test]
+    fn board_filter_drops_other_boards() {
+        let dir = export();
+        let boards = read(dir.path(), Path::new("/media"), &["b".to_owned()]).unwrap();
+        assert_eq!(boards.len(), 1);
+        assert_eq!(boards[0].threads[0].posts[0].body, "other board");
+
+        assert!(read(dir.path(), Path::new("/media"), &["v".to_owned()]).is_err());
+    }
+
+    #[test]
+    fn ipv6_addresses_are_decoded() {
+        let octets: Vec<Value> = [0x20, 0x01, 0x0d, 0xb8].into_iter().chain([0; 11]).chain([1]).map(Value::from).collect();
+        assert_eq!(ip(&Value::Array(octets)).as_deref(), Some("2001:db8::1"));
+    }
+}
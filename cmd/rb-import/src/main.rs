@@ -0,0 +1,336 @@
+//! `rb-import` — migrate a vichan or LynxChan imageboard into rusty-board,
+//! or preserve a single 4chan thread.
+//!
+//! Reads the other software's database export, then creates each board with
+//! its threads, posts and uploads through the same repository and media
+//! storage ports the server uses (see `writer.rs`). The target database must
+//! already be migrated (`rusty-board migrate`); boards that already exist
+//! there are refused before anything is written. A 4chan thread is fetched
+//! over HTTP instead and added to an existing board as an archived thread.
+//! `--site <host>` targets a site other than the default one.
+//!
+//! Usage:
+//!   rb-import vichan --dump vichan.sql --root /var/www/vichan [--board b]... [--dry-run]
+//!   rb-import lynxchan --dir export/ --media-root gridfs/ [--board b]... [--dry-run]
+//!   rb-import 4chan https://boards.4chan.org/g/thread/123 --board preserve [--dry-run]
+//!
+//! Database and media settings are loaded like the server's: `--config
+//! <path>`, `RUSTY_BOARD_CONFIG`, then environment variables. Build with the
+//! server's `media-*` feature and use its `MEDIA_BACKEND`, so uploads land in
+//! the same backend.
+
+mod fourchan;
+mod lynxchan;
+mod source;
+mod vichan;
+mod writer;
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Context;
+use clap::{Parser, Subcommand};
+use configs::Settings;
+use domains::models::{SiteId, Slug};
+use domains::ports::{MediaStorage, SiteRepository};
+use secrecy::ExposeSecret;
+use storage_adapters::postgres::{
+    connection::create_pool,
+    repositories::{
+        archive_repository::PgArchiveRepository, PgBoardRepository, PgPostRepository, PgSiteRepository,
+        PgThreadRepository,
+    },
+};
+use storage_adapters::registry::{BoxFuture, Registry};
+use writer::{ImportReport, Importer};
+
+#[cfg(feature = "media-local")]
+use storage_adapters::media::local_fs::LocalFsMediaStorage;
+
+#[cfg(feature = "media-s3")]
+use storage_adapters::media::s3::S3MediaStorage;
+
+#[cfg(feature = "media-ipfs")]
+use storage_adapters::media::ipfs::IpfsMediaStorage;
+
+#[derive(Debug, Parser)]
+#[command(name = "rb-import", version, about = "Import boards from vichan or LynxChan into rusty-board")]
+struct Cli {
+    /// TOML config file, as for `rusty-board --config`.
+    #[arg(long, global = true, value_name = "PATH")]
+    config: Option<PathBuf>,
+
+    /// Read the export and report what would be imported, without writing.
+    #[arg(long, global = true)]
+    dry_run: bool,
+
+    /// Host of the site to import into. Default: the default site.
+    #[arg(long, global = true, value_name = "HOST")]
+    site: Option<String>,
+
+    #[command(subcommand)]
+    source: Source,
+}
+
+#[derive(Debug, Subcommand)]
+enum Source {
+    /// A `mysqldump` of a vichan (or Tinyboard, infinity, lainchan) database.
+    Vichan {
+        /// The SQL dump.
+        #[arg(long, value_name = "FILE")]
+        dump: PathBuf,
+        /// vichan's web root, holding `<board>/src` and `<board>/thumb`.
+        #[arg(long, value_name = "DIR")]
+        root: PathBuf,
+        /// Import only this board (repeatable). Default: every board.
+        #[arg(long = "board", value_name = "URI")]
+        boards: Vec<String>,
+    },
+    /// A `mongoexport` of a LynxChan database.
+    Lynxchan {
+        /// Directory holding `boards.json`, `threads.json` and `posts.json`.
+        #[arg(long, value_name = "DIR")]
+        dir: PathBuf,
+        /// Directory the GridFS files were extracted to, holding `.media/`.
+        #[arg(long, value_name = "DIR")]
+        media_root: PathBuf,
+        /// Import only this board (repeatable). Default: every board.
+        #[arg(long = "board", value_name = "URI")]
+        boards: Vec<String>,
+    },
+    /// One thread from 4chan or a site with the same JSON API, added to an
+    /// existing board as an archived thread.
+    #[command(name = "4chan")]
+    Fourchan {
+        /// The thread's page or JSON URL.
+        url: String,
+        /// Slug of the board to add the thread to.
+        #[arg(long, value_name = "SLUG")]
+        board: String,
+        /// Download files from here instead of the site's usual media host.
+        #[arg(long, value_name = "URL")]
+        media_base: Option<String>,
+    },
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    // aws-sdk-s3 uses rustls; install ring as the process-default provider
+    // (see `rusty-board`'s main.rs).
+    let _ = rustls::crypto::ring::default_provider().install_default();
+
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env().unwrap_or_else(|_| "rb_import=info".into()),
+        )
+        .init();
+
+    let cli = Cli::parse();
+    let boards = match &cli.source {
+        Source::Vichan { dump, root, boards } => {
+            let dump = std::fs::read(dump).with_context(|| format!("failed to read {}", dump.display()))?;
+            vichan::read(&String::from_utf8_lossy(&dump), root, boards)?
+        }
+        Source::Lynxchan { dir, media_root, boards } => lynxchan::read(dir, media_root, boards)?,
+        Source::Fourchan { url, board, media_base } => {
+            return import_thread(&cli, url, board, media_base.as_deref()).await;
+        }
+    };
+
+    let mut report = ImportReport::default();
+    if cli.dry_run {
+        for board in &boards {
+            report.tally(board);
+        }
+        print_report("would import", &report);
+        return Ok(());
+    }
+
+    let importer = importer(&cli).await?;
+    importer.check_free(&boards).await?;
+    for board in &boards {
+        println!("importing /{}/ ({} threads) ...", board.uri, board.threads.len());
+        importer
+            .import(board, &mut report)
+            .await
+            .with_context(|| format!("import of /{}/ failed; delete the partial board before retrying", board.uri))?;
+    }
+    print_report("imported", &report);
+    Ok(())
+}
+
+/// Fetch the thread at `url` and add it to `/board/` as an archived thread.
+async fn import_thread(cli: &Cli, url: &str, board: &str, media_base: Option<&str>) -> anyhow::Result<()> {
+    let slug = Slug::new(board).with_context(|| format!("{board:?} is not a valid board slug"))?;
+    let url = fourchan::ThreadUrl::parse(url, media_base)?;
+    let client = reqwest::Client::builder()
+        .user_agent(concat!("rb-import/", env!("CARGO_PKG_VERSION")))
+        .timeout(Duration::from_secs(60))
+        .build()?;
+
+    // Files are downloaded here, stored by the writer, then discarded.
+    let scratch = std::env::temp_dir().join(format!("rb-import-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir(&scratch).with_context(|| format!("failed to create {}", scratch.display()))?;
+    let result = async {
+        println!("fetching /{}/ thread from {} ...", url.board, url.api);
+        let thread = fourchan::fetch(&client, &url, &scratch, !cli.dry_run).await?;
+        let files: usize = thread.posts.iter().map(|p| p.files.len()).sum();
+        if cli.dry_run {
+            println!("would import 1 thread with {} posts and {files} files into /{slug}/", thread.posts.len());
+            return Ok(());
+        }
+        let mut report = ImportReport::default();
+        let imported = importer(cli).await?.import_archived_thread(&slug, &thread, &mut report).await?;
+        println!("archived as /board/{slug}/thread/{}", imported.id);
+        print_report("imported", &report);
+        anyhow::Ok(())
+    }
+    .await;
+    let _ = std::fs::remove_dir_all(&scratch);
+    result
+}
+
+/// An importer writing to the database and media storage in the settings.
+async fn importer(
+    cli: &Cli,
+) -> anyhow::Result<Importer<PgBoardRepository, PgThreadRepository, PgPostRepository, impl MediaStorage, PgArchiveRepository>>
+{
+    let settings = Settings::load_layered(cli.config.as_deref(), &[]).context("failed to load settings")?;
+    let pool = create_pool(settings.db_url.expose_secret(), 2, 1)
+        .await
+        .context("failed to connect to PostgreSQL")?;
+    let site = match &cli.site {
+        Some(host) => {
+            let normalized = services::site::normalize_host(host)
+                .with_context(|| format!("{host:?} is not a host name"))?;
+            PgSiteRepository::new(pool.clone())
+                .find_by_host(&normalized)
+                .await?
+                .with_context(|| format!("no site is served on {normalized}; add it with `rusty-board site add`"))?
+        }
+        None => PgSiteRepository::new(pool.clone()).find_by_id(SiteId::DEFAULT).await?,
+    };
+    Ok(Importer {
+        site,
+        boards:  PgBoardRepository::new(pool.clone()),
+        threads: PgThreadRepository::new(pool.clone()),
+        posts:   PgPostRepository::new(pool.clone()),
+        media:   build_media_storage(&settings).await?,
+        archive: PgArchiveRepository::new(pool),
+    })
+}
+
+fn print_report(verb: &str, report: &ImportReport) {
+    println!(
+        "{verb} {} boards, {} threads, {} posts, {} files",
+        report.boards, report.threads, report.posts, report.files,
+    );
+    if report.files_missing > 0 {
+        println!("{} files were not found on disk and were skipped", report.files_missing);
+    }
+}
+
+/// Media storage backends compiled into this build; the first is the default.
+fn media_backends() -> Registry<Settings, Arc<dyn MediaStorage>> {
+    let registry = Registry::new("media storage");
+    #[cfg(feature = "media-local")]
+    let registry = registry.register("local", local_media_storage);
+    #[cfg(feature = "media-s3")]
+    let registry = registry.register("s3", s3_media_storage);
+    #[cfg(feature = "media-ipfs")]
+    let registry = registry.register("ipfs", ipfs_media_storage);
+    registry
+}
+
+/// Build the media storage backend `MEDIA_BACKEND` names.
+async fn build_media_storage(settings: &Settings) -> anyhow::Result<Arc<dyn MediaStorage>> {
+    let (name, storage) = media_backends().build(settings.media_backend.as_deref(), settings).await?;
+    tracing::info!(backend = name, "writing media");
+    Ok(storage)
+}
+
+#[cfg(feature = "media-local")]
+fn local_media_storage(settings: &Settings) -> BoxFuture<'_, anyhow::Result<Arc<dyn MediaStorage>>> {
+    Box::pin(async move {
+        let storage: Arc<dyn MediaStorage> = Arc::new(LocalFsMediaStorage::new(
+            settings.media_path.clone(),
+            settings.media_url_base.clone(),
+        ));
+        Ok(storage)
+    })
+}
+
+#[cfg(feature = "media-s3")]
+fn s3_media_storage(settings: &Settings) -> BoxFuture<'_, anyhow::Result<Arc<dyn MediaStorage>>> {
+    Box::pin(async move {
+        let s3_cfg = settings
+            .s3
+            .as_ref()
+            .context("MEDIA_BACKEND=s3 needs S3__BUCKET, S3__REGION, S3__ACCESS_KEY and S3__SECRET_KEY")?;
+        let aws_config = aws_config::defaults(aws_config::BehaviorVersion::latest())
+            .region(aws_sdk_s3::config::Region::new(s3_cfg.region.clone()))
+            .credentials_provider(aws_sdk_s3::config::Credentials::new(
+                s3_cfg.access_key.expose_secret(),
+                s3_cfg.secret_key.expose_secret(),
+                None,
+                None,
+                "static",
+            ))
+            .load()
+            .await;
+        let client = if let Some(endpoint) = &s3_cfg.endpoint {
+            aws_sdk_s3::Client::from_conf(
+                aws_sdk_s3::config::Builder::from(&aws_config)
+                    .endpoint_url(endpoint)
+                    .build(),
+            )
+        } else {
+            aws_sdk_s3::Client::new(&aws_config)
+        };
+        let storage: Arc<dyn MediaStorage> =
+            Arc::new(S3MediaStorage::new(client, s3_cfg.bucket.clone(), s3_cfg.endpoint.clone()));
+        Ok(storage)
+    })
+}
+
+#[cfg(feature = "media-ipfs")]
+fn ipfs_media_storage(settings: &Settings) -> BoxFuture<'_, anyhow::Result<Arc<dyn MediaStorage>>> {
+    Box::pin(async move {
+        let ipfs = &settings.ipfs;
+        let storage: Arc<dyn MediaStorage> = Arc::new(IpfsMediaStorage::new(
+            ipfs.api_url.clone(),
+            ipfs.gateway_url.clone(),
+            ipfs.mfs_root.clone(),
+        ));
+        Ok(storage)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn cli_is_consistent() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn board_filter_is_repeatable() {
+        let cli = Cli::try_parse_from([
+            "rb-import", "vichan", "--dump", "d.sql", "--root", "/www", "--board", "b", "--board", "g", "--dry-run",
+        ])
+        .unwrap();
+        let Source::Vichan { boards, .. } = cli.source else { panic!("expected vichan") };
+        assert_eq!(boards, ["b", "g"]);
+        assert!(cli.dry_run);
+    }
+
+    #[test]
+    fn thread_import_needs_a_target_board() {
+        let err = Cli::try_parse_from(["rb-import", "4chan", "https://boards.4chan.org/g/thread/1"]).unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::MissingRequiredArgument);
+    }
+}
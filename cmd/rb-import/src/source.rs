@@ -0,0 +1,82 @@
+//! What the readers extract from another imageboard, before it is mapped to
+//! rusty-board's models by `writer.rs`.
+
+use std::path::PathBuf;
+
+use chrono::{DateTime, Utc};
+
+/// A board and every thread on it.
+#[derive(Debug, Default)]
+pub struct SourceBoard {
+    /// The board's URI, used as the rusty-board slug.
+    pub uri:     String,
+    pub title:   String,
+    /// Subtitle or description; becomes the board rules text.
+    pub rules:   String,
+    pub threads: Vec<SourceThread>,
+}
+
+#[derive(Debug)]
+pub struct SourceThread {
+    pub sticky:    bool,
+    pub locked:    bool,
+    pub cycle:     bool,
+    /// Last bump; defaults to the newest post's time when the source has none.
+    pub bumped_at: Option<DateTime<Utc>>,
+    /// The OP first, then replies in posting order.
+    pub posts:     Vec<SourcePost>,
+}
+
+#[derive(Debug)]
+pub struct SourcePost {
+    /// Board-scoped post number, kept so `>>N` quotes still resolve.
+    pub number:     u64,
+    pub created_at: DateTime<Utc>,
+    pub name:       Option<String>,
+    pub tripcode:   Option<String>,
+    pub email:      Option<String>,
+    pub subject:    Option<String>,
+    /// Raw markup as typed by the poster, not rendered HTML.
+    pub body:       String,
+    /// Poster's IP address, hashed on import like rusty-board's own posts.
+    pub ip:         Option<String>,
+    pub files:      Vec<SourceFile>,
+}
+
+#[derive(Debug)]
+pub struct SourceFile {
+    pub original_name: String,
+    /// Path of the original on disk.
+    pub path:          PathBuf,
+    /// Path of the source's own thumbnail, copied when present.
+    pub thumb:         Option<PathBuf>,
+    pub width:         Option<u32>,
+    pub height:        Option<u32>,
+    pub spoiler:       bool,
+}
+
+/// Recover the typed text from rendered post HTML: tags are dropped, `<br>`
+/// becomes a newline and the common entities are decoded.
+pub fn strip_html(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        text.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('>') else {
+            rest = &rest[start..];
+            break;
+        };
+        let tag = rest[start + 1..start + end].trim_start_matches('/').to_ascii_lowercase();
+        if tag.starts_with("br") {
+            text.push('\n');
+        }
+        rest = &rest[start + end + 1..];
+    }
+    text.push_str(rest);
+    text.replace("&gt;", ">")
+        .replace("&lt;", "<")
+        .replace("&quot;", "\"")
+        .replace("&#039;", "'")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
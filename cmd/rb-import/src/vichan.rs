@@ -0,0 +1,556 @@
+//! Reader for vichan (and its forks: Tinyboard, infinity, lainchan) MySQL dumps.
+//!
+//! Takes the output of `mysqldump` for a vichan database. Board metadata comes
+//! from the `boards` table and posts from each board's `posts_<uri>` table;
+//! column positions are read from the `CREATE TABLE` statements, or from the
+//! column list of `--complete-insert` dumps. Uploads are read from the vichan
+//! web root: the `file_path` recorded for each file, else `<uri>/src/<file>`.
+//!
+//! Only the `files` JSON column of vichan 5 and later is understood; posts from
+//! older schemas keep their text but lose their files.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use crate::source::{strip_html, SourceBoard, SourceFile, SourcePost, SourceThread};
+
+/// One row of an `INSERT`: `None` for SQL `NULL`.
+type Row = Vec<Option<String>>;
+
+/// Read every board in `dump`, or only those named in `only` when non-empty.
+pub fn read(dump: &str, root: &Path, only: &[String]) -> anyhow::Result<Vec<SourceBoard>> {
+    let tables = parse_dump(dump)?;
+    let Some(boards) = tables.get("boards") else {
+        anyhow::bail!("the dump has no `boards` table; is it a vichan database?");
+    };
+
+    let mut out = Vec::new();
+    for row in &boards.rows {
+        let uri = boards.get(row, "uri").context("`boards` row without a uri")?.to_owned();
+        if !only.is_empty() && !only.contains(&uri) {
+            continue;
+        }
+        let mut board = SourceBoard {
+            title: boards.get(row, "title").unwrap_or(&uri).to_owned(),
+            rules: boards.get(row, "subtitle").unwrap_or_default().to_owned(),
+            uri,
+            threads: Vec::new(),
+        };
+        match tables.get(&format!("posts_{}", board.uri)) {
+            Some(posts) => board.threads = read_threads(posts, &board.uri, root)?,
+            None => tracing::warn!(board = %board.uri, "no posts table in the dump; importing the board empty"),
+        }
+        out.push(board);
+    }
+    if let Some(missing) = only.iter().find(|uri| !out.iter().any(|b| &b.uri == *uri)) {
+        anyhow::bail!("board /{missing}/ is not in the dump");
+    }
+    Ok(out)
+}
+
+fn read_threads(posts: &Table, uri: &str, root: &Path) -> anyhow::Result<Vec<SourceThread>> {
+    // OP number → thread, in post order so threads keep their original order.
+    let mut threads: BTreeMap<u64, SourceThread> = BTreeMap::new();
+    let mut replies: BTreeMap<u64, Vec<SourcePost>> = BTreeMap::new();
+
+    for row in &posts.rows {
+        let post = read_post(posts, row, uri, root)?;
+        match posts.get(row, "thread") {
+            None => {
+                let flag = |column| posts.get(row, column).is_some_and(|v| v != "0");
+                let bumped_at = posts.get(row, "bump").and_then(|v| v.parse().ok()).and_then(timestamp);
+                threads.insert(post.number, SourceThread {
+                    sticky: flag("sticky"),
+                    locked: flag("locked"),
+                    cycle: flag("cycle"),
+                    bumped_at,
+                    posts: vec![post],
+                });
+            }
+            Some(op) => {
+                let op = op.parse().with_context(|| format!("/{uri}/{}: invalid thread {op:?}", post.number))?;
+                replies.entry(op).or_default().push(post);
+            }
+        }
+    }
+
+    for (op, mut posts) in replies {
+        match threads.get_mut(&op) {
+            Some(thread) => {
+                posts.sort_by_key(|p| p.number);
+                thread.posts.extend(posts);
+            }
+            None => tracing::warn!(board = %uri, thread = op, replies = posts.len(), "replies to a missing thread skipped"),
+        }
+    }
+    Ok(threads.into_values().collect())
+}
+
+fn read_post(posts: &Table, row: &Row, uri: &str, root: &Path) -> anyhow::Result<SourcePost> {
+    let text = |column| posts.get(row, column).filter(|v| !v.is_empty()).map(str::to_owned);
+    let number: u64 = posts
+        .get(row, "id")
+        .and_then(|v| v.parse().ok())
+        .with_context(|| format!("/{uri}/: post without a numeric id"))?;
+    let created_at = posts
+        .get(row, "time")
+        .and_then(|v| v.parse().ok())
+        .and_then(timestamp)
+        .with_context(|| format!("/{uri}/{number}: invalid time"))?;
+    let body = match posts.get(row, "body_nomarkup") {
+        Some(raw) => raw.to_owned(),
+        None => strip_html(posts.get(row, "body").unwrap_or_default()),
+    };
+    let files = match posts.get(row, "files") {
+        Some(json) => read_files(json, uri, root).with_context(|| format!("/{uri}/{number}: invalid files"))?,
+        None => Vec::new(),
+    };
+    Ok(SourcePost {
+        number,
+        created_at,
+        name: text("name"),
+        tripcode: text("trip"),
+        email: text("email"),
+        subject: text("subject"),
+        body,
+        ip: text("ip"),
+        files,
+    })
+}
+
+/// An entry of vichan's `files` column.
+#[derive(Deserialize)]
+struct VichanFile {
+    /// Stored filename, or `"deleted"` once the file was removed.
+    file:       String,
+    /// Stored thumbnail filename, or `"spoiler"` / `"file"` for the stock images.
+    #[serde(default)]
+    thumb:      Option<String>,
+    #[serde(default)]
+    file_path:  Option<String>,
+    #[serde(default)]
+    thumb_path: Option<String>,
+    /// Name as uploaded.
+    #[serde(default)]
+    name:       Option<String>,
+    #[serde(default)]
+    width:      Option<serde_json::Value>,
+    #[serde(default)]
+    height:     Option<serde_json::Value>,
+}
+
+fn read_files(json: &str, uri: &str, root: &Path) -> anyhow::Result<Vec<SourceFile>> {
+    let files: Vec<VichanFile> = serde_json::from_str(json)?;
+    Ok(files
+        .into_iter()
+        .filter(|f| f.file != "deleted")
+        .map(|f| {
+            let spoiler = f.thumb.as_deref() == Some("spoiler");
+            let thumb = match (f.thumb_path, f.thumb.as_deref()) {
+                (Some(path), _) => Some(root.join(path)),
+                (None, Some("spoiler" | "file") | None) => None,
+                (None, Some(thumb)) => Some(root.join(uri).join("thumb").join(thumb)),
+            };
+            SourceFile {
+                original_name: f.name.unwrap_or_else(|| f.file.clone()),
+                path: f.file_path.map_or_else(|| root.join(uri).join("src").join(&f.file), |p| root.join(p)),
+                thumb,
+                width: f.width.as_ref().and_then(dimension),
+                height: f.height.as_ref().and_then(dimension),
+                spoiler,
+            }
+        })
+        .collect())
+}
+
+/// vichan writes dimensions as numbers or numeric strings depending on version.
+fn dimension(value: &serde_json::Value) -> Option<u32> {
+    match value {
+        serde_json::Value::Number(n) => n.as_u64().and_then(|n| u32::try_from(n).ok()),
+        serde_json::Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+fn timestamp(secs: i64) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp(secs, 0)
+}
+
+// ── mysqldump parsing ────────────────────────────────────────────────────────
+
+/// A table's columns and the rows inserted into it.
+#[derive(Debug, Default)]
+struct Table {
+    columns: HashMap<String, usize>,
+    rows:    Vec<Row>,
+}
+
+impl Table {
+    /// The value of `column` in `row`; `None` for `NULL` or an unknown column.
+    fn get<'a>(&self, row: &'a Row, column: &str) -> Option<&'a str> {
+        self.columns.get(column).and_then(|&i| row.get(i)).and_then(|v| v.as_deref())
+    }
+}
+
+fn parse_dump(dump: &str) -> anyhow::Result<HashMap<String, Table>> {
+    let mut tables: HashMap<String, Table> = HashMap::new();
+    for statement in statements(dump) {
+        let statement = strip_comments(statement);
+        let upper = statement.get(..12).unwrap_or(statement).to_ascii_uppercase();
+        if upper.starts_with("CREATE TABLE") {
+            let (name, columns) = parse_create(statement)?;
+            tables.entry(name).or_default().columns = columns;
+        } else if upper.starts_with("INSERT INTO") {
+            let (name, columns, rows) = parse_insert(statement)?;
+            let table = tables.entry(name.clone()).or_default();
+            if let Some(columns) = columns {
+                table.columns = columns;
+            }
+            if table.columns.is_empty() {
+                anyhow::bail!("INSERT INTO `{name}` before its CREATE TABLE and without a column list");
+            }
+            table.rows.extend(rows);
+        }
+    }
+    Ok(tables)
+}
+
+/// Split `dump` on the semicolons that end statements, skipping quoted text.
+fn statements(dump: &str) -> impl Iterator<Item = &str> {
+    let bytes = dump.as_bytes();
+    let mut start = 0;
+    let mut quote: Option<u8> = None;
+    let mut i = 0;
+    std::iter::from_fn(move || {
+        while i < bytes.len() {
+            let b = bytes[i];
+            i += 1;
+            match quote {
+                Some(_) if b == b'\\' => i += 1,
+                Some(q) if b == q => quote = None,
+                Some(_) => {}
+                None if b == b'\'' || b == b'"' || b == b'`' => quote = Some(b),
+                // `--` and `#` comments run to the end of the line and may hold quotes.
+                None if (b == b'-' && bytes.get(i) == Some(&b'-')) || b == b'#' => {
+                    i = bytes[i..].iter().position(|&c| c == b'\n').map_or(bytes.len(), |p| i + p);
+                }
+                None if b == b';' => {
+                    let statement = &dump[start..i - 1];
+                    start = i;
+                    return Some(statement);
+                }
+                None => {}
+            }
+        }
+        (start < bytes.len()).then(|| {
+            let statement = &dump[start..];
+            start = bytes.len();
+            statement
+        })
+    })
+}
+
+/// Drop the leading comment lines mysqldump writes before each statement.
+fn strip_comments(mut statement: &str) -> &str {
+    loop {
+        statement = statement.trim_start();
+        if statement.starts_with("--") || statement.starts_with('#') {
+            statement = statement.find('\n').map_or("", |i| &statement[i + 1..]);
+        } else if statement.starts_with("/*") {
+            statement = statement.find("*/").map_or("", |i| &statement[i + 2..]);
+        } else {
+            return statement;
+        }
+    }
+}
+
+fn parse_create(statement: &str) -> anyhow::Result<(String, HashMap<String, usize>)> {
+    let mut p = Parser::new(&statement["CREATE TABLE".len()..]);
+    p.keyword("IF NOT EXISTS");
+    let name = p.identifier().context("CREATE TABLE without a table name")?;
+    let body = statement.find('(').map_or("", |i| &statement[i + 1..]);
+    // mysqldump puts each column definition on its own line, starting with its
+    // quoted name; keys and constraints start with a keyword.
+    let columns = body
+        .lines()
+        .filter(|line| line.trim_start().starts_with('`'))
+        .filter_map(|line| Parser::new(line).identifier())
+        .enumerate()
+        .map(|(i, column)| (column, i))
+        .collect();
+    Ok((name, columns))
+}
+
+type Insert = (String, Option<HashMap<String, usize>>, Vec<Row>);
+
+fn parse_insert(statement: &str) -> anyhow::Result<Insert> {
+    let mut p = Parser::new(&statement["INSERT INTO".len()..]);
+    let name = p.identifier().context("INSERT without a table name")?;
+    let columns = if p.eat('(') {
+        let mut columns = HashMap::new();
+        loop {
+            let column = p.identifier().with_context(|| format!("INSERT INTO `{name}`: invalid column list"))?;
+            let index = columns.len();
+            columns.insert(column, index);
+            if !p.eat(',') {
+                break;
+            }
+        }
+        if !p.eat(')') {
+            anyhow::bail!("INSERT INTO `{name}`: unterminated column list");
+        }
+        Some(columns)
+    } else {
+        None
+    };
+    if !p.keyword("VALUES") {
+        anyhow::bail!("INSERT INTO `{name}`: only INSERT … VALUES is supported");
+    }
+    let mut rows = Vec::new();
+    loop {
+        if !p.eat('(') {
+            anyhow::bail!("INSERT INTO `{name}`: expected a row");
+        }
+        let mut row = Vec::new();
+        loop {
+            row.push(p.value().with_context(|| format!("INSERT INTO `{name}`: invalid value in row {}", rows.len() + 1))?);
+            if !p.eat(',') {
+                break;
+            }
+        }
+        if !p.eat(')') {
+            anyhow::bail!("INSERT INTO `{name}`: unterminated row {}", rows.len() + 1);
+        }
+        rows.push(row);
+        if !p.eat(',') {
+            break;
+        }
+    }
+    Ok((name, columns, rows))
+}
+
+/// Cursor over the SQL of a single statement.
+struct Parser<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Parser<'a> {
+    fn new(sql: &'a str) -> Self {
+        Self { rest: sql }
+    }
+
+    fn skip_space(&mut self) {
+        self.rest = self.rest.trim_start();
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_space();
+        match self.rest.strip_prefix(c) {
+            Some(rest) => {
+                self.rest = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Consume `words` (case-insensitive, single spaces) if they come next.
+    fn keyword(&mut self, words: &str) -> bool {
+        self.skip_space();
+        match self.rest.get(..words.len()) {
+            Some(head) if head.eq_ignore_ascii_case(words) => {
+                self.rest = &self.rest[words.len()..];
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// A backquoted or bare identifier.
+    fn identifier(&mut self) -> Option<String> {
+        self.skip_space();
+        if let Some(rest) = self.rest.strip_prefix('`') {
+            let end = rest.find('`')?;
+            self.rest = &rest[end + 1..];
+            return Some(rest[..end].to_owned());
+        }
+        let end = self.rest.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_')).unwrap_or(self.rest.len());
+        if end == 0 {
+            return None;
+        }
+        let ident = &self.rest[..end];
+        self.rest = &self.rest[end..];
+        Some(ident.to_owned())
+    }
+
+    /// A literal: `NULL`, a number, a quoted string or a hex blob.
+    fn value(&mut self) -> anyhow::Result<Option<String>> {
+        self.skip_space();
+        if self.keyword("NULL") {
+            return Ok(None);
+        }
+        // mysqldump marks blobs with `_binary 'text'` or writes them as `0x…`.
+        self.keyword("_binary");
+        self.skip_space();
+        if let Some(hex) = self.rest.strip_prefix("0x") {
+            let end = hex.find(|c: char| !c.is_ascii_hexdigit()).unwrap_or(hex.len());
+            let bytes = hex::decode(&hex[..end]).context("invalid hex literal")?;
+            self.rest = &hex[end..];
+            return Ok(Some(String::from_utf8_lossy(&bytes).into_owned()));
+        }
+        match self.rest.chars().next() {
+            Some(q @ ('\'' | '"')) => self.string(q).map(Some),
+            Some(_) => {
+                let end = self.rest.find([',', ')']).unwrap_or(self.rest.len());
+                let literal = self.rest[..end].trim();
+                self.rest = &self.rest[end..];
+                if literal.is_empty() {
+                    anyhow::bail!("missing value");
+                }
+                Ok(Some(literal.to_owned()))
+            }
+            None => anyhow::bail!("unexpected end of statement"),
+        }
+    }
+
+    /// A quoted string with MySQL's backslash escapes and doubled quotes.
+    fn string(&mut self, quote: char) -> anyhow::Result<String> {
+        let body = &self.rest[1..];
+        let mut out = String::new();
+        let mut chars = body.char_indices().peekable();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '\\' => match chars.next().map(|(_, c)| c) {
+                    Some('n') => out.push('\n'),
+                    Some('r') => out.push('\r'),
+                    Some('t') => out.push('\t'),
+                    Some('0') => out.push('\0'),
+                    Some('Z') => out.push('\x1a'),
+                    Some('b') => out.push('\x08'),
+                    Some(other) => out.push(other),
+                    None => break,
+                },
+                c if c == quote => {
+                    if chars.next_if(|&(_, next)| next == quote).is_some() {
+                        out.push(quote);
+                        continue;
+                    }
+                    self.rest = &body[i + 1..];
+                    return Ok(out);
+                }
+                c => out.push(c),
+            }
+        }
+        anyhow::bail!("unterminated string")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DUMP: &str = r##"
+-- MySQL dump 10.13
+/*!40101 SET NAMES utf8mb4 */;
+
+DROP TABLE IF EXISTS `boards`;
+CREATE TABLE `boards` (
+  `uri` varchar(58) NOT NULL,
+  `title` tinytext NOT NULL,
+  `subtitle` tinytext,
+  PRIMARY KEY (`uri`)
+) ENGINE=MyISAM DEFAULT CHARSET=utf8mb4;
+
+INSERT INTO `boards` VALUES ('b','Random','Anything; really'),('g','Technology',NULL);
+
+CREATE TABLE `posts_b` (
+  `id` int(11) unsigned NOT NULL AUTO_INCREMENT,
+  `thread` int(11) DEFAULT NULL,
+  `subject` varchar(100) DEFAULT NULL,
+  `email` varchar(30) DEFAULT NULL,
+  `name` varchar(35) DEFAULT NULL,
+  `trip` varchar(15) DEFAULT NULL,
+  `body` text NOT NULL,
+  `body_nomarkup` text,
+  `time` int(11) NOT NULL,
+  `bump` int(11) DEFAULT NULL,
+  `files` text,
+  `ip` varchar(39) NOT NULL,
+  `sticky` int(1) NOT NULL,
+  `locked` int(1) NOT NULL,
+  `cycle` int(1) NOT NULL,
+  PRIMARY KEY (`id`),
+  KEY `thread_id` (`thread`,`id`)
+) ENGINE=MyISAM;
+
+INSERT INTO `posts_b` VALUES (1,NULL,'Hello','','Anonymous',NULL,'<p>it\'s</p>','it\'s\nhere',1700000000,1700000100,'[{\"file\":\"17.png\",\"thumb\":\"17s.png\",\"name\":\"cat.png\",\"width\":\"640\",\"height\":480,\"file_path\":\"b/src/17.png\",\"thumb_path\":\"b/thumb/17s.png\"},{\"file\":\"deleted\"}]','10.0.0.1',1,0,0),(3,1,NULL,'sage','Anonymous','!trip','<a href="#1">&gt;&gt;1</a><br/>ok',NULL,1700000100,NULL,NULL,'10.0.0.2',0,0,0),(2,1,NULL,NULL,NULL,NULL,'','first',1700000050,NULL,'[{\"file\":\"18.webm\",\"thumb\":\"spoiler\"}]','10.0.0.3',0,0,0);
+"##;
+
+    #[test]
+    fn reads_boards_threads_and_posts() {
+        let boards = read(DUMP, Path::new("/www"), &[]).unwrap();
+        assert_eq!(boards.len(), 2);
+
+        let b = &boards[0];
+        assert_eq!((b.uri.as_str(), b.title.as_str(), b.rules.as_str()), ("b", "Random", "Anything; really"));
+        assert_eq!(b.threads.len(), 1);
+        let thread = &b.threads[0];
+        assert!(thread.sticky && !thread.locked);
+        assert_eq!(thread.bumped_at, DateTime::from_timestamp(1_700_000_100, 0));
+        let numbers: Vec<u64> = thread.posts.iter().map(|p| p.number).collect();
+        assert_eq!(numbers, [1, 2, 3]);
+
+        let op = &thread.posts[0];
+        assert_eq!(op.subject.as_deref(), Some("Hello"));
+        assert_eq!(op.email, None);
+        assert_eq!(op.body, "it's\nhere");
+        assert_eq!(op.ip.as_deref(), Some("10.0.0.1"));
+
+        assert!(boards[1].threads.is_empty());
+    }
+
+    #[test]
+    fn falls_back_to_the_rendered_body() {
+        let boards = read(DUMP, Path::new("/www"), &["b".to_owned()]).unwrap();
+        let reply = &boards[0].threads[0].posts[2];
+        assert_eq!(reply.body, ">>1\nok");
+        assert_eq!(reply.tripcode.as_deref(), Some("!trip"));
+    }
+
+    #[test]
+    fn resolves_files_and_skips_deleted_ones() {
+        let boards = read(DUMP, Path::new("/www"), &["b".to_owned()]).unwrap();
+        let posts = &boards[0].threads[0].posts;
+
+        let [file] = posts[0].files.as_slice() else { panic!("expected one file") };
+        assert_eq!(file.original_name, "cat.png");
+        assert_eq!(file.path, Path::new("/www/b/src/17.png"));
+        assert_eq!(file.thumb.as_deref(), Some(Path::new("/www/b/thumb/17s.png")));
+        assert_eq!((file.width, file.height), (Some(640), Some(480)));
+        assert!(!file.spoiler);
+
+        let [spoiler] = posts[1].files.as_slice() else { panic!("expected one file") };
+        assert_eq!(spoiler.path, Path::new("/www/b/src/18.webm"));
+        assert_eq!(spoiler.thumb, None);
+        assert!(spoiler.spoiler);
+    }
+
+    #[test]
+    fn unknown_board_filter_is_an_error() {
+        let err = read(DUMP, Path::new("/www"), &["v".to_owned()]).unwrap_err();
+        assert!(err.to_string().contains("/v/"), "{err}");
+    }
+
+    #[test]
+    fn complete_inserts_carry_their_own_columns() {
+        let dump = "INSERT INTO `boards` (`title`, `uri`) VALUES ('It''s', 'a'), (0x4869, 'b');";
+        let tables = parse_dump(dump).unwrap();
+        let boards = &tables["boards"];
+        let titles: Vec<_> = boards.rows.iter().map(|r| boards.get(r, "title")).collect();
+        assert_eq!(titles, [Some("It's"), Some("Hi")]);
+    }
+}
@@ -0,0 +1,493 @@
+//! Writes imported boards through rusty-board's repository and media ports.
+//!
+//! A board imported whole keeps its post numbers, timestamps and thread flags,
+//! so quote links and ordering survive the move. A single thread added to a
+//! live board is renumbered from the board's counter instead, closed, and
+//! recorded in the archive. Raw IPs are hashed with the salt of the day the
+//! post was made, as rusty-board does for its own posts. Uploads are copied
+//! into the configured `MediaStorage` under the target site's media prefix,
+//! deduplicated by content hash, together with the source's thumbnails; files
+//! missing on disk are skipped with a warning.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::Context;
+use bytes::Bytes;
+use chrono::Utc;
+use domains::errors::DomainError;
+use domains::models::{
+    Attachment, Board, BoardId, ContentHash, IpHash, MediaKey, MediaMeta, Post, PostId, Site, Slug, Thread,
+    ThreadId,
+};
+use domains::ports::{ArchiveRepository, BoardRepository, MediaStorage, PostRepository, ThreadRepository};
+use md5::{Digest, Md5};
+use services::common::utils::{hash_content, hash_ip, sniff_mime};
+use uuid::Uuid;
+
+use crate::source::{SourceBoard, SourceFile, SourcePost, SourceThread};
+
+/// What an import wrote.
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    pub boards:        usize,
+    pub threads:       usize,
+    pub posts:         usize,
+    pub files:         usize,
+    pub files_missing: usize,
+}
+
+impl ImportReport {
+    /// Count what `board` holds, for `--dry-run`.
+    pub fn tally(&mut self, board: &SourceBoard) {
+        self.boards += 1;
+        self.threads += board.threads.len();
+        for post in board.threads.iter().flat_map(|t| &t.posts) {
+            self.posts += 1;
+            for file in &post.files {
+                if file.path.is_file() {
+                    self.files += 1;
+                } else {
+                    self.files_missing += 1;
+                }
+            }
+        }
+    }
+}
+
+/// How an imported thread enters its board.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Mode {
+    /// Part of a board imported whole: post numbers and flags are kept.
+    Board,
+    /// Added to a live board: posts are renumbered and the thread is closed.
+    Archived,
+}
+
+pub struct Importer<B, T, P, M, A> {
+    /// The site boards are created on and looked up in.
+    pub site:    Site,
+    pub boards:  B,
+    pub threads: T,
+    pub posts:   P,
+    pub media:   M,
+    pub archive: A,
+}
+
+impl<B, T, P, M, A> Importer<B, T, P, M, A>
+where
+    B: BoardRepository,
+    T: ThreadRepository,
+    P: PostRepository,
+    M: MediaStorage,
+    A: ArchiveRepository,
+{
+    /// Fail if any of `boards` already exists here, before anything is written.
+    pub async fn check_free(&self, boards: &[SourceBoard]) -> anyhow::Result<()> {
+        for board in boards {
+            let slug = slug(board)?;
+            match self.boards.find_by_slug(self.site.id, &slug).await {
+                Ok(_) => anyhow::bail!("board /{slug}/ already exists; delete it or leave it out with --board"),
+                Err(DomainError::NotFound { .. }) => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(())
+    }
+
+    /// Create `source` as a new board with all its threads and posts.
+    pub async fn import(&self, source: &SourceBoard, report: &mut ImportReport) -> anyhow::Result<()> {
+        let board = Board {
+            id:         BoardId::new(),
+            site_id:    self.site.id,
+            slug:       slug(source)?,
+            title:      source.title.clone(),
+            rules:      source.rules.clone(),
+            created_at: source
+                .threads
+                .iter()
+                .filter_map(|t| t.posts.first())
+                .map(|p| p.created_at)
+                .min()
+                .unwrap_or_else(Utc::now),
+        };
+        self.boards.save(&board).await?;
+        report.boards += 1;
+
+        for thread in &source.threads {
+            self.write_thread(&board, thread, Mode::Board, report).await?;
+        }
+        Ok(())
+    }
+
+    /// Add `source` to the existing board `slug` as a closed, archived thread.
+    ///
+    /// Posts get new numbers from the board's counter, and quotes between
+    /// them are rewritten to match.
+    pub async fn import_archived_thread(
+        &self,
+        slug: &Slug,
+        source: &SourceThread,
+        report: &mut ImportReport,
+    ) -> anyhow::Result<Thread> {
+        let board = match self.boards.find_by_slug(self.site.id, slug).await {
+            Ok(board) => board,
+            Err(DomainError::NotFound { .. }) => anyhow::bail!("board /{slug}/ does not exist; create it first"),
+            Err(e) => return Err(e.into()),
+        };
+        let thread = self.write_thread(&board, source, Mode::Archived, report).await?;
+        self.archive.archive_thread(&thread).await?;
+        Ok(thread)
+    }
+
+    async fn write_thread(
+        &self,
+        board: &Board,
+        source: &SourceThread,
+        mode: Mode,
+        report: &mut ImportReport,
+    ) -> anyhow::Result<Thread> {
+        let Some(op) = source.posts.first() else {
+            anyhow::bail!("/{}/: thread without posts", board.slug);
+        };
+        let mut thread = Thread {
+            id:          ThreadId::new(),
+            board_id:    board.id,
+            op_post_id:  None,
+            reply_count: (source.posts.len() - 1) as u32,
+            bumped_at:   source
+                .bumped_at
+                .or_else(|| source.posts.iter().map(|p| p.created_at).max())
+                .unwrap_or(op.created_at),
+            sticky:      source.sticky && mode == Mode::Board,
+            closed:      source.locked || mode == Mode::Archived,
+            cycle:       source.cycle && mode == Mode::Board,
+            created_at:  op.created_at,
+        };
+        // The OP is linked once its post exists.
+        self.threads.save(&thread).await?;
+
+        // Source post number → number here, for rewriting quotes.
+        let mut numbers: HashMap<u64, u64> = HashMap::new();
+        for source_post in &source.posts {
+            let mut post = post(thread.id, source_post);
+            match mode {
+                Mode::Board => self
+                    .posts
+                    .restore(&post)
+                    .await
+                    .with_context(|| format!("/{}/{}: failed to save post", board.slug, post.post_number))?,
+                Mode::Archived => {
+                    post.body = rewrite_quotes(&post.body, &numbers);
+                    let (_, number) = self
+                        .posts
+                        .save(&post)
+                        .await
+                        .with_context(|| format!("/{}/: failed to save post {}", board.slug, source_post.number))?;
+                    numbers.insert(source_post.number, number);
+                }
+            }
+            thread.op_post_id.get_or_insert(post.id);
+
+            let mut attachments = Vec::with_capacity(source_post.files.len());
+            for file in &source_post.files {
+                match self.attachment(post.id, file).await? {
+                    Some(attachment) => {
+                        attachments.push(attachment);
+                        report.files += 1;
+                    }
+                    None => report.files_missing += 1,
+                }
+            }
+            if !attachments.is_empty() {
+                self.posts.save_attachments(&attachments).await?;
+            }
+            report.posts += 1;
+        }
+        if let Some(op_id) = thread.op_post_id {
+            self.threads.set_op_post(thread.id, op_id).await?;
+        }
+        report.threads += 1;
+        Ok(thread)
+    }
+
+    /// Store `file` and describe it; `None` when it is missing on disk.
+    async fn attachment(&self, post_id: PostId, file: &SourceFile) -> anyhow::Result<Option<Attachment>> {
+        let data = match tokio::fs::read(&file.path).await {
+            Ok(data) => Bytes::from(data),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                tracing::warn!(path = %file.path.display(), "file not found; importing the post without it");
+                return Ok(None);
+            }
+            Err(e) => return Err(e).with_context(|| format!("failed to read {}", file.path.display())),
+        };
+        let hash = hash_content(&data);
+        let mime = mime_of(&data, &file.path);
+        let (media_key, thumbnail_key) = match self.posts.find_attachment_by_hash(&hash).await? {
+            Some(existing) => (existing.media_key, existing.thumbnail_key),
+            None => self.store(&hash, &data, &mime, file).await?,
+        };
+
+        Ok(Some(Attachment {
+            id: Uuid::new_v4(),
+            post_id,
+            filename: file.original_name.clone(),
+            hash,
+            size_kb: data.len().div_ceil(1024).min(u32::MAX as usize) as u32,
+            media_key,
+            thumbnail_key,
+            spoiler: file.spoiler,
+            duration_ms: None,
+            page_count: None,
+            md5: Some(hex::encode(Md5::digest(&data))),
+            phash: None,
+            meta: Some(MediaMeta {
+                original_filename: file.original_name.clone(),
+                size_bytes:        data.len() as u64,
+                width:             file.width,
+                height:            file.height,
+                original_mime:     None,
+                labels:            Vec::new(),
+            }),
+            mime,
+        }))
+    }
+
+    /// Copy an original and its thumbnail into media storage.
+    async fn store(
+        &self,
+        hash: &ContentHash,
+        data: &Bytes,
+        mime: &str,
+        file: &SourceFile,
+    ) -> anyhow::Result<(MediaKey, Option<MediaKey>)> {
+        let id = Uuid::new_v4();
+        let prefix = &self.site.media_prefix;
+        let key = MediaKey::new(format!("{id}.{}", extension(mime, &file.path))).with_prefix(prefix);
+        self.media.store(&key, data.clone(), mime).await?;
+        tracing::debug!(key = %key.0, hash = %hash.0, "stored original");
+
+        let thumb = match &file.thumb {
+            Some(path) => match tokio::fs::read(path).await {
+                Ok(thumb) => {
+                    let thumb_mime = mime_of(&thumb, path);
+                    let thumb_key =
+                        MediaKey::new(format!("{id}_thumb.{}", extension(&thumb_mime, path))).with_prefix(prefix);
+                    self.media.store(&thumb_key, Bytes::from(thumb), &thumb_mime).await?;
+                    Some(thumb_key)
+                }
+                Err(e) => {
+                    tracing::warn!(path = %path.display(), error = %e, "thumbnail unreadable; storing the file without one");
+                    None
+                }
+            },
+            None => None,
+        };
+        Ok((key, thumb))
+    }
+}
+
+fn slug(board: &SourceBoard) -> anyhow::Result<Slug> {
+    Slug::new(&board.uri).with_context(|| format!("/{}/ is not a valid rusty-board slug", board.uri))
+}
+
+fn post(thread_id: ThreadId, source: &SourcePost) -> Post {
+    let salt = source.created_at.format("%Y-%m-%d").to_string();
+    // A post without a recorded address gets a hash no other post shares, so
+    // IP-based moderation never groups such posts together.
+    let ip_hash = match &source.ip {
+        Some(ip) => hash_ip(ip, &salt),
+        None => IpHash::new(hash_content(Uuid::new_v4().as_bytes()).0),
+    };
+    // rusty-board posts have no subject line; keep it as the first line.
+    let body = match &source.subject {
+        Some(subject) if source.body.is_empty() => subject.clone(),
+        Some(subject) => format!("{subject}\n\n{}", source.body),
+        None => source.body.clone(),
+    };
+    Post {
+        id: PostId::new(),
+        thread_id,
+        body,
+        ip_hash,
+        name: source.name.clone(),
+        tripcode: source.tripcode.clone(),
+        email: source.email.clone(),
+        created_at: source.created_at,
+        post_number: source.number,
+        pinned: false,
+        tags: Vec::new(),
+        commands: Vec::new(),
+        subject: None,
+        capcode: None,
+        deleted: None,
+        edited_at: None,
+    }
+}
+
+/// Point `>>N` quotes at the renumbered posts in `numbers`. Quotes of posts
+/// outside the thread, and cross-board `>>>/b/N` links, are left as written.
+fn rewrite_quotes(body: &str, numbers: &HashMap<u64, u64>) -> String {
+    let mut out = String::with_capacity(body.len());
+    let mut rest = body;
+    while let Some(at) = rest.find(">>") {
+        let (before, quote) = rest.split_at(at + 2);
+        out.push_str(before);
+        let digits = quote.find(|c: char| !c.is_ascii_digit()).unwrap_or(quote.len());
+        let cross_board = before.ends_with(">>>") || quote.starts_with('>');
+        match quote[..digits].parse::<u64>().ok().and_then(|n| numbers.get(&n)) {
+            Some(number) if !cross_board => out.push_str(&number.to_string()),
+            _ => out.push_str(&quote[..digits]),
+        }
+        rest = &quote[digits..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// The MIME type sniffed from `data`, else guessed from the file extension.
+fn mime_of(data: &[u8], path: &Path) -> String {
+    sniff_mime(data)
+        .map(str::to_owned)
+        .unwrap_or_else(|| mime_guess::from_path(path).first_or_octet_stream().to_string())
+}
+
+fn extension(mime: &str, path: &Path) -> String {
+    mime_guess::get_mime_extensions_str(mime)
+        .and_then(|exts| exts.first())
+        .map(|ext| (*ext).to_owned())
+        .or_else(|| path.extension().and_then(|e| e.to_str()).map(str::to_ascii_lowercase))
+        .unwrap_or_else(|| "bin".to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::DateTime;
+    use domains::ports::{
+        MockArchiveRepository, MockBoardRepository, MockMediaStorage, MockPostRepository, MockThreadRepository,
+    };
+
+    fn source_post(ip: Option<&str>, subject: Option<&str>) -> SourcePost {
+        SourcePost {
+            number:     7,
+            created_at: DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+            name:       None,
+            tripcode:   None,
+            email:      None,
+            subject:    subject.map(str::to_owned),
+            body:       "hello".to_owned(),
+            ip:         ip.map(str::to_owned),
+            files:      Vec::new(),
+        }
+    }
+
+    #[test]
+    fn ips_are_hashed_with_the_day_of_the_post() {
+        let post = post(ThreadId::new(), &source_post(Some("10.0.0.1"), None));
+        assert_eq!(post.ip_hash, hash_ip("10.0.0.1", "2023-11-14"));
+        assert_eq!(post.post_number, 7);
+    }
+
+    #[test]
+    fn posts_without_an_ip_do_not_share_a_hash() {
+        let a = post(ThreadId::new(), &source_post(None, None));
+        let b = post(ThreadId::new(), &source_post(None, None));
+        assert_ne!(a.ip_hash, b.ip_hash);
+    }
+
+    #[test]
+    fn subject_becomes_the_first_line() {
+        let post = post(ThreadId::new(), &source_post(None, Some("Topic")));
+        assert_eq!(post.body, "Topic\n\nhello");
+    }
+
+    #[test]
+    fn quotes_follow_renumbered_posts() {
+        let numbers = HashMap::from([(100, 7), (101, 8)]);
+        assert_eq!(
+            rewrite_quotes(">>100 >>101\n>>999 >>>/g/100 >>100x", &numbers),
+            ">>7 >>8\n>>999 >>>/g/100 >>7x",
+        );
+    }
+
+    #[test]
+    fn extension_follows_the_sniffed_type() {
+        assert_eq!(extension("image/png", Path::new("x.jpeg")), "png");
+        assert_eq!(extension("application/x-unknown", Path::new("x.FOO")), "foo");
+    }
+
+    #[tokio::test]
+    async fn existing_board_is_refused_before_writing() {
+        let mut boards = MockBoardRepository::new();
+        boards.expect_find_by_slug().returning(|site, slug| {
+            Ok(Board {
+                id:         BoardId::new(),
+                site_id:    site,
+                slug:       slug.clone(),
+                title:      "Random".to_owned(),
+                rules:      String::new(),
+                created_at: Utc::now(),
+            })
+        });
+        boards.expect_save().never();
+        let importer = Importer {
+            site: Site::fallback(),
+            boards,
+            threads: MockThreadRepository::new(),
+            posts:   MockPostRepository::new(),
+            media:   MockMediaStorage::new(),
+            archive: MockArchiveRepository::new(),
+        };
+        let source = SourceBoard { uri: "b".to_owned(), ..SourceBoard::default() };
+        let err = importer.check_free(&[source]).await.unwrap_err();
+        assert!(err.to_string().contains("/b/ already exists"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn thread_is_renumbered_closed_and_archived() {
+        let mut boards = MockBoardRepository::new();
+        boards.expect_find_by_slug().returning(|site, slug| {
+            Ok(Board {
+                id:         BoardId::new(),
+                site_id:    site,
+                slug:       slug.clone(),
+                title:      "Preserve".to_owned(),
+                rules:      String::new(),
+                created_at: Utc::now(),
+            })
+        });
+        let mut threads = MockThreadRepository::new();
+        threads.expect_save().withf(|t| t.closed && !t.sticky).returning(|t| Ok(t.id));
+        threads.expect_set_op_post().times(1).returning(|_, _| Ok(()));
+        let mut posts = MockPostRepository::new();
+        let next = std::sync::atomic::AtomicU64::new(41);
+        let bodies = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let seen = bodies.clone();
+        posts.expect_restore().never();
+        posts.expect_save().returning(move |p| {
+            seen.lock().unwrap().push(p.body.clone());
+            Ok((p.id, next.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1))
+        });
+        let mut archive = MockArchiveRepository::new();
+        archive.expect_archive_thread().withf(|t| t.closed && t.op_post_id.is_some()).times(1).returning(|_| Ok(()));
+        let importer = Importer { site: Site::fallback(), boards, threads, posts, media: MockMediaStorage::new(), archive };
+
+        let mut reply = source_post(None, None);
+        reply.number = 9001;
+        reply.body = ">>7 hi".to_owned();
+        let source = SourceThread {
+            sticky:    true,
+            locked:    false,
+            cycle:     false,
+            bumped_at: None,
+            posts:     vec![source_post(None, None), reply],
+        };
+        let mut report = ImportReport::default();
+        let slug = Slug::new("preserve").unwrap();
+        importer.import_archived_thread(&slug, &source, &mut report).await.unwrap();
+
+        assert_eq!(*bodies.lock().unwrap(), ["hello", ">>42 hi"]);
+        assert_eq!((report.threads, report.posts), (1, 2));
+    }
+}
@@ -0,0 +1,480 @@
+//! `rusty-board export` and `rusty-board import`: a portable copy of an instance.
+//!
+//! The archive is a zstd-compressed tar:
+//!
+//! | Entry | Contents |
+//! |-------|----------|
+//! | `manifest.json` | Archive format version, exporting release, export time |
+//! | `users.jsonl` | Staff accounts, with their password hashes |
+//! | `sites.jsonl` | Sites, the default one included |
+//! | `boards/<slug>/board.json` | The board, its `BoardConfig`, owners and volunteers |
+//! | `boards/<slug>/threads.jsonl` | Its threads |
+//! | `boards/<slug>/posts.jsonl` | Its posts, each with its attachments |
+//! | `sites/<site-id>/boards/<slug>/…` | The same, for boards on other sites |
+//! | `bans.jsonl`, `asn_bans.jsonl`, `hash_bans.jsonl` | Bans, expired ones included |
+//! | `media/<key>` | Every original and thumbnail an exported attachment references |
+//!
+//! Records are the domain models as JSON, one per line, read and written
+//! through the repository and media storage ports. An archive exported from
+//! one backend therefore imports into any other. IDs, post numbers and
+//! timestamps are kept, so links and `>>N` quotes survive the move.
+//!
+//! Sessions, reports, the audit log, staff requests and messages, and
+//! archived threads are not exported.
+//!
+//! Import writes into an instance with no boards. Staff accounts whose
+//! username already exists are not overwritten; their bans and board roles
+//! are attached to the existing account.
+
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::Context;
+use bytes::Bytes;
+use domains::errors::DomainError;
+use domains::models::{
+    AsnBan, Attachment, Ban, Board, BoardConfig, BoardId, HashBan, MediaKey, Page, Paginated, Post, PostId,
+    Site, SiteId, Thread, ThreadId, User, UserId,
+};
+use domains::ports::{
+    AsnBanRepository, BanRepository, BoardRepository, HashBanRepository, MediaStorage, PostRepository,
+    SiteRepository, ThreadRepository, UserRepository,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+/// Identifies the archive layout; bumped when it changes incompatibly.
+/// Version 2 added `sites.jsonl` and `sites/<site-id>/boards/`.
+const FORMAT: &str = "rusty-board-export";
+const FORMAT_VERSION: u32 = 2;
+
+/// The ports an export reads from or an import writes to.
+pub struct Stores {
+    pub sites:     Arc<dyn SiteRepository>,
+    pub boards:    Arc<dyn BoardRepository>,
+    pub threads:   Arc<dyn ThreadRepository>,
+    pub posts:     Arc<dyn PostRepository>,
+    pub users:     Arc<dyn UserRepository>,
+    pub bans:      Arc<dyn BanRepository>,
+    pub asn_bans:  Arc<dyn AsnBanRepository>,
+    pub hash_bans: Arc<dyn HashBanRepository>,
+    pub media:     Arc<dyn MediaStorage>,
+}
+
+/// What an export or import copied.
+#[derive(Debug, Default)]
+pub struct BackupReport {
+    pub users:         usize,
+    pub sites:         usize,
+    pub boards:        usize,
+    pub threads:       usize,
+    pub posts:         usize,
+    pub bans:          usize,
+    pub media:         usize,
+    /// Referenced media objects absent from storage, skipped on export.
+    pub media_missing: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Manifest {
+    format:      String,
+    version:     u32,
+    /// Release of rusty-board that wrote the archive.
+    exported_by: String,
+    exported_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BoardRecord {
+    board:      Board,
+    config:     BoardConfig,
+    owners:     Vec<UserId>,
+    volunteers: Vec<UserId>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PostRecord {
+    post:        Post,
+    attachments: Vec<Attachment>,
+}
+
+/// Write every board, thread, post, ban, staff account and referenced media
+/// object to a new archive at `out`.
+pub async fn export(stores: &Stores, out: &Path) -> anyhow::Result<BackupReport> {
+    let file = File::create(out).with_context(|| format!("failed to create {}", out.display()))?;
+    let mut archive = ArchiveWriter::new(BufWriter::new(file))?;
+    let mut report = BackupReport::default();
+
+    archive.add_json("manifest.json", &Manifest {
+        format:      FORMAT.to_owned(),
+        version:     FORMAT_VERSION,
+        exported_by: env!("CARGO_PKG_VERSION").to_owned(),
+        exported_at: chrono::Utc::now(),
+    })?;
+
+    let users = all_pages(|page| stores.users.find_all(page)).await?;
+    let mut owners: HashMap<BoardId, Vec<UserId>> = HashMap::new();
+    let mut volunteers: HashMap<BoardId, Vec<UserId>> = HashMap::new();
+    for user in &users {
+        for board in stores.users.find_owned_boards(user.id).await? {
+            owners.entry(board).or_default().push(user.id);
+        }
+        for board in stores.users.find_volunteer_boards(user.id).await? {
+            volunteers.entry(board).or_default().push(user.id);
+        }
+    }
+    archive.add_jsonl("users.jsonl", &users)?;
+    report.users = users.len();
+
+    // Before the boards, so import creates each site before its boards.
+    let sites = stores.sites.find_all().await?;
+    archive.add_jsonl("sites.jsonl", &sites)?;
+    report.sites = sites.len();
+
+    let mut media_keys = HashSet::new();
+    for board in all_pages(|page| stores.boards.find_all(page)).await? {
+        let dir = board_dir(&board);
+        let threads = all_pages(|page| stores.threads.find_by_board(board.id, page)).await?;
+        let mut posts = Vec::new();
+        for thread in &threads {
+            let thread_posts = stores.posts.find_all_by_thread(thread.id).await?;
+            let ids: Vec<PostId> = thread_posts.iter().map(|p| p.id).collect();
+            let mut attachments = stores.posts.find_attachments_by_post_ids(&ids).await?;
+            for post in thread_posts {
+                let attachments = attachments.remove(&post.id).unwrap_or_default();
+                for a in &attachments {
+                    media_keys.insert(a.media_key.clone());
+                    media_keys.extend(a.thumbnail_key.clone());
+                }
+                posts.push(PostRecord { post, attachments });
+            }
+        }
+        archive.add_json(&format!("{dir}/board.json"), &BoardRecord {
+            config:     stores.boards.find_config(board.id).await?,
+            owners:     owners.remove(&board.id).unwrap_or_default(),
+            volunteers: volunteers.remove(&board.id).unwrap_or_default(),
+            board,
+        })?;
+        archive.add_jsonl(&format!("{dir}/threads.jsonl"), &threads)?;
+        archive.add_jsonl(&format!("{dir}/posts.jsonl"), &posts)?;
+        report.boards += 1;
+        report.threads += threads.len();
+        report.posts += posts.len();
+    }
+
+    let bans = all_pages(|page| stores.bans.find_all(page)).await?;
+    let asn_bans = all_pages(|page| stores.asn_bans.find_all(page)).await?;
+    let hash_bans = all_pages(|page| stores.hash_bans.find_all(page)).await?;
+    archive.add_jsonl("bans.jsonl", &bans)?;
+    archive.add_jsonl("asn_bans.jsonl", &asn_bans)?;
+    archive.add_jsonl("hash_bans.jsonl", &hash_bans)?;
+    report.bans = bans.len() + asn_bans.len() + hash_bans.len();
+
+    for key in media_keys {
+        match stores.media.fetch(&key).await {
+            Ok(data) => {
+                archive.add(&format!("media/{key}"), &data)?;
+                report.media += 1;
+            }
+            Err(DomainError::NotFound { .. }) => {
+                tracing::warn!(key = %key, "referenced media object is missing; not exported");
+                report.media_missing += 1;
+            }
+            Err(e) => return Err(e).with_context(|| format!("failed to read media object {key}")),
+        }
+    }
+
+    archive.finish()?;
+    Ok(report)
+}
+
+/// Restore an archive written by `export` into an instance with no boards.
+pub async fn import(stores: &Stores, input: &Path) -> anyhow::Result<BackupReport> {
+    let existing = stores.boards.find_all(Page::default()).await?.total;
+    if existing > 0 {
+        anyhow::bail!("this instance already has {existing} boards; import into an empty instance");
+    }
+    let file = File::open(input).with_context(|| format!("failed to open {}", input.display()))?;
+    let mut archive = open_archive(BufReader::new(file))?;
+    let mut entries = archive.entries()?.map(read_entry);
+    let mut report = BackupReport::default();
+
+    let (path, data) = entries.next().context("the archive is empty")??;
+    if path != "manifest.json" {
+        anyhow::bail!("not a rusty-board export: first entry is {path}");
+    }
+    check_manifest(&serde_json::from_slice(&data).context("invalid manifest.json")?)?;
+
+    // Archive user ID → ID of the account the records are attached to here.
+    let mut user_ids: HashMap<UserId, UserId> = HashMap::new();
+    // Thread → OP, set once the thread's posts exist.
+    let mut op_posts: Vec<(ThreadId, PostId)> = Vec::new();
+
+    for entry in entries {
+        let (path, data) = entry?;
+        let user = |id: UserId| user_ids.get(&id).copied().unwrap_or(id);
+        match path.as_str() {
+            "users.jsonl" => {
+                for u in parse_jsonl::<User>(&path, &data)? {
+                    match stores.users.find_by_username(&u.username).await {
+                        Ok(existing) => {
+                            tracing::info!(username = %u.username, "staff account exists; keeping it");
+                            user_ids.insert(u.id, existing.id);
+                        }
+                        Err(DomainError::NotFound { .. }) => {
+                            stores.users.save(&u).await?;
+                            report.users += 1;
+                        }
+                        Err(e) => return Err(e.into()),
+                    }
+                }
+            }
+            "sites.jsonl" => {
+                for site in parse_jsonl::<Site>(&path, &data)? {
+                    stores.sites.save(&site).await?;
+                    report.sites += 1;
+                }
+            }
+            "bans.jsonl" => {
+                for ban in parse_jsonl::<Ban>(&path, &data)? {
+                    stores.bans.save(&Ban { banned_by: user(ban.banned_by), ..ban }).await?;
+                    report.bans += 1;
+                }
+            }
+            "asn_bans.jsonl" => {
+                for ban in parse_jsonl::<AsnBan>(&path, &data)? {
+                    stores.asn_bans.save(&AsnBan { banned_by: user(ban.banned_by), ..ban }).await?;
+                    report.bans += 1;
+                }
+            }
+            "hash_bans.jsonl" => {
+                for ban in parse_jsonl::<HashBan>(&path, &data)? {
+                    stores.hash_bans.save(&HashBan { banned_by: user(ban.banned_by), ..ban }).await?;
+                    report.bans += 1;
+                }
+            }
+            _ if path.starts_with("media/") => {
+                let key = MediaKey::new(&path["media/".len()..]);
+                let content_type = mime_guess::from_path(&key.0).first_or_octet_stream();
+                stores.media.store(&key, Bytes::from(data), content_type.as_ref()).await?;
+                report.media += 1;
+            }
+            _ if is_board_entry(&path) && path.ends_with("/board.json") => {
+                let record: BoardRecord =
+                    serde_json::from_slice(&data).with_context(|| format!("invalid {path}"))?;
+                stores.boards.save(&record.board).await?;
+                stores.boards.save_config(record.board.id, &record.config).await?;
+                for owner in record.owners {
+                    stores.users.add_board_owner(record.board.id, user(owner)).await?;
+                }
+                for volunteer in record.volunteers {
+                    stores.users.add_volunteer(record.board.id, user(volunteer)).await?;
+                }
+                report.boards += 1;
+            }
+            _ if is_board_entry(&path) && path.ends_with("/threads.jsonl") => {
+                for thread in parse_jsonl::<Thread>(&path, &data)? {
+                    // The OP is linked after its post is restored.
+                    op_posts.extend(thread.op_post_id.map(|op| (thread.id, op)));
+                    stores.threads.save(&Thread { op_post_id: None, ..thread }).await?;
+                    report.threads += 1;
+                }
+            }
+            _ if is_board_entry(&path) && path.ends_with("/posts.jsonl") => {
+                for record in parse_jsonl::<PostRecord>(&path, &data)? {
+                    stores.posts.restore(&record.post).await?;
+                    if !record.attachments.is_empty() {
+                        stores.posts.save_attachments(&record.attachments).await?;
+                    }
+                    report.posts += 1;
+                }
+                for (thread, op) in op_posts.drain(..) {
+                    stores.threads.set_op_post(thread, op).await?;
+                }
+            }
+            _ => tracing::warn!(entry = %path, "unknown archive entry skipped"),
+        }
+    }
+    Ok(report)
+}
+
+/// The archive directory of a board's entries. Slugs are unique per site, so
+/// boards on sites other than the default one are nested under the site's ID.
+fn board_dir(board: &Board) -> String {
+    match board.site_id {
+        SiteId::DEFAULT => format!("boards/{}", board.slug),
+        site => format!("sites/{site}/boards/{}", board.slug),
+    }
+}
+
+/// Whether `path` lies in a directory written by `board_dir`.
+fn is_board_entry(path: &str) -> bool {
+    path.starts_with("boards/") || (path.starts_with("sites/") && path.contains("/boards/"))
+}
+
+fn check_manifest(manifest: &Manifest) -> anyhow::Result<()> {
+    if manifest.format != FORMAT {
+        anyhow::bail!("not a rusty-board export (format {:?})", manifest.format);
+    }
+    if manifest.version > FORMAT_VERSION {
+        anyhow::bail!(
+            "archive format {} was written by rusty-board {}; this release reads format {FORMAT_VERSION} and older",
+            manifest.version,
+            manifest.exported_by,
+        );
+    }
+    Ok(())
+}
+
+/// Every item of a paginated listing.
+async fn all_pages<T, F, Fut>(mut fetch: F) -> Result<Vec<T>, DomainError>
+where
+    F: FnMut(Page) -> Fut,
+    Fut: std::future::Future<Output = Result<Paginated<T>, DomainError>>,
+{
+    let mut all = Vec::new();
+    let mut page = Page::default();
+    loop {
+        let batch = fetch(page).await?;
+        let done = batch.items.is_empty() || all.len() + batch.items.len() >= batch.total as usize;
+        all.extend(batch.items);
+        if done {
+            return Ok(all);
+        }
+        page = Page::new(page.0 + 1);
+    }
+}
+
+fn parse_jsonl<T: DeserializeOwned>(path: &str, data: &[u8]) -> anyhow::Result<Vec<T>> {
+    data.split(|&b| b == b'\n')
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(i, line)| serde_json::from_slice(line).with_context(|| format!("{path}:{}: invalid record", i + 1)))
+        .collect()
+}
+
+/// Appends entries to a zstd-compressed tar.
+struct ArchiveWriter<W: Write> {
+    tar: tar::Builder<zstd::Encoder<'static, W>>,
+}
+
+impl<W: Write> ArchiveWriter<W> {
+    fn new(out: W) -> anyhow::Result<Self> {
+        Ok(Self { tar: tar::Builder::new(zstd::Encoder::new(out, 0)?) })
+    }
+
+    fn add(&mut self, path: &str, data: &[u8]) -> anyhow::Result<()> {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        header.set_mtime(chrono::Utc::now().timestamp().max(0) as u64);
+        self.tar
+            .append_data(&mut header, path, data)
+            .with_context(|| format!("failed to write {path}"))
+    }
+
+    fn add_json<T: Serialize>(&mut self, path: &str, value: &T) -> anyhow::Result<()> {
+        self.add(path, &serde_json::to_vec_pretty(value)?)
+    }
+
+    fn add_jsonl<T: Serialize>(&mut self, path: &str, items: &[T]) -> anyhow::Result<()> {
+        let mut data = Vec::new();
+        for item in items {
+            serde_json::to_writer(&mut data, item)?;
+            data.push(b'\n');
+        }
+        self.add(path, &data)
+    }
+
+    /// Write the tar trailer and the end of the zstd frame.
+    fn finish(self) -> anyhow::Result<W> {
+        let mut out = self.tar.into_inner()?.finish()?;
+        out.flush()?;
+        Ok(out)
+    }
+}
+
+fn open_archive<R: Read>(input: R) -> anyhow::Result<tar::Archive<zstd::Decoder<'static, BufReader<R>>>> {
+    Ok(tar::Archive::new(zstd::Decoder::new(input)?))
+}
+
+/// An archive entry's path and contents.
+fn read_entry<R: Read>(entry: std::io::Result<tar::Entry<'_, R>>) -> anyhow::Result<(String, Vec<u8>)> {
+    let mut entry = entry.context("corrupt archive")?;
+    let path = entry.path()?.to_string_lossy().into_owned();
+    let mut data = Vec::with_capacity(entry.size() as usize);
+    entry.read_to_end(&mut data).with_context(|| format!("failed to read {path}"))?;
+    Ok((path, data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(format: &str, version: u32) -> Manifest {
+        Manifest {
+            format:      format.to_owned(),
+            version,
+            exported_by: "9.9.9".to_owned(),
+            exported_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn archive_entries_round_trip_in_order() {
+        let mut writer = ArchiveWriter::new(Vec::new()).unwrap();
+        writer.add_json("manifest.json", &manifest(FORMAT, FORMAT_VERSION)).unwrap();
+        writer.add_jsonl("numbers.jsonl", &[1, 2, 3]).unwrap();
+        writer.add("media/ab/cd.png", b"\x89PNG").unwrap();
+        let bytes = writer.finish().unwrap();
+
+        let mut archive = open_archive(bytes.as_slice()).unwrap();
+        let mut entries = archive.entries().unwrap().map(read_entry);
+        let (path, data) = entries.next().unwrap().unwrap();
+        assert_eq!(path, "manifest.json");
+        let read: Manifest = serde_json::from_slice(&data).unwrap();
+        assert_eq!(read.version, FORMAT_VERSION);
+
+        let (path, data) = entries.next().unwrap().unwrap();
+        assert_eq!(path, "numbers.jsonl");
+        assert_eq!(parse_jsonl::<u32>(&path, &data).unwrap(), vec![1, 2, 3]);
+
+        let (path, data) = entries.next().unwrap().unwrap();
+        assert_eq!((path.as_str(), data.as_slice()), ("media/ab/cd.png", b"\x89PNG".as_slice()));
+        assert!(entries.next().is_none());
+    }
+
+    #[test]
+    fn boards_on_other_sites_are_nested_under_the_site() {
+        let board = Board {
+            id:         BoardId::new(),
+            site_id:    SiteId::DEFAULT,
+            slug:       domains::models::Slug::new("b").unwrap(),
+            title:      "Random".to_owned(),
+            rules:      String::new(),
+            created_at: chrono::Utc::now(),
+        };
+        assert_eq!(board_dir(&board), "boards/b");
+        let site = SiteId::new();
+        let dir = board_dir(&Board { site_id: site, ..board });
+        assert_eq!(dir, format!("sites/{site}/boards/b"));
+        assert!(is_board_entry(&format!("{dir}/board.json")));
+        assert!(is_board_entry("boards/b/posts.jsonl"));
+        assert!(!is_board_entry("sites.jsonl"));
+    }
+
+    #[test]
+    fn invalid_jsonl_line_is_reported_with_its_number() {
+        let err = parse_jsonl::<u32>("bans.jsonl", b"1\n\nnope\n").unwrap_err();
+        assert_eq!(err.to_string(), "bans.jsonl:3: invalid record");
+    }
+
+    #[test]
+    fn manifests_from_other_formats_or_newer_releases_are_rejected() {
+        assert!(check_manifest(&manifest(FORMAT, FORMAT_VERSION)).is_ok());
+        assert!(check_manifest(&manifest("something-else", 1)).is_err());
+        assert!(check_manifest(&manifest(FORMAT, FORMAT_VERSION + 1)).is_err());
+    }
+}
@@ -0,0 +1,252 @@
+//! Command-line interface.
+//!
+//! With no subcommand the binary serves HTTP, so existing deployments that run
+//! the bare binary keep working. Every other subcommand connects to the
+//! database, does one job, and exits; see `commands.rs`.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use clap::{Parser, Subcommand};
+
+/// rusty-board — an imageboard server.
+#[derive(Debug, Parser)]
+#[command(name = "rusty-board", version, about)]
+pub struct Cli {
+    /// TOML config file. Default: `$RUSTY_BOARD_CONFIG`, then
+    /// `rusty-board.toml` if present.
+    #[arg(long, global = true, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// Override one setting, e.g. `--set port=9000` or `--set s3.bucket=media`.
+    /// Repeatable; wins over the config file and the environment.
+    #[arg(long = "set", global = true, value_name = "KEY=VALUE", value_parser = parse_override)]
+    pub overrides: Vec<(String, String)>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Run the HTTP server (the default).
+    Serve,
+    /// Apply pending database migrations and exit.
+    Migrate,
+    /// Add, list and remove sites served from this process.
+    #[command(subcommand)]
+    Site(SiteCommand),
+    /// Create, list and delete boards.
+    #[command(subcommand)]
+    Board(BoardCommand),
+    /// Manage admin accounts.
+    #[command(subcommand)]
+    Admin(AdminCommand),
+    /// Issue and lift IP bans.
+    #[command(subcommand)]
+    Ban(BanCommand),
+    /// Prune threads on boards holding more than their `max_threads`.
+    Prune {
+        /// Only prune this board.
+        #[arg(long, value_name = "SLUG")]
+        board: Option<String>,
+        /// Site of `--board`. Default: the default site.
+        #[arg(long, value_name = "HOST", requires = "board")]
+        site:  Option<String>,
+    },
+    /// Delete stored media no post references.
+    GcMedia {
+        /// Count orphans without deleting them.
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Re-render every thumbnail with the current thumbnail settings.
+    RegenThumbs,
+    /// Audit stored media.
+    #[command(subcommand)]
+    Storage(StorageCommand),
+    /// Write boards, threads, posts, bans, staff accounts and their media to
+    /// a portable archive.
+    Export {
+        /// Archive to create, e.g. `dump.tar.zst`.
+        #[arg(long, value_name = "PATH")]
+        out: PathBuf,
+    },
+    /// Restore an archive written by `export` into an instance with no boards.
+    Import {
+        /// Archive to read.
+        path: PathBuf,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SiteCommand {
+    /// Serve a new, empty imageboard on another host name.
+    Add {
+        /// Host name, e.g. `boards.example.org`.
+        host:         String,
+        /// Display name. Default: the host.
+        #[arg(long)]
+        name:         Option<String>,
+        /// Directory its uploads are stored under. Default: the host with
+        /// dots replaced by dashes.
+        #[arg(long, value_name = "DIR")]
+        media_prefix: Option<String>,
+    },
+    /// List every site. Hosts no site claims are served by the default site.
+    List,
+    /// Remove a site. Its boards must be deleted first.
+    Remove {
+        host: String,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum BoardCommand {
+    /// Create a board with the default configuration.
+    Create {
+        /// URL slug, e.g. `tech`.
+        slug:  String,
+        /// Display title.
+        title: String,
+        /// Board rules shown above the post form.
+        #[arg(long, default_value = "")]
+        rules: String,
+        /// Host of the site to create it on. Default: the default site.
+        #[arg(long, value_name = "HOST")]
+        site:  Option<String>,
+    },
+    /// List a site's boards.
+    List {
+        /// Host of the site. Default: the default site.
+        #[arg(long, value_name = "HOST")]
+        site: Option<String>,
+    },
+    /// Delete a board and everything posted on it.
+    Delete {
+        slug: String,
+        /// Host of the board's site. Default: the default site.
+        #[arg(long, value_name = "HOST")]
+        site: Option<String>,
+        /// Confirm the deletion; without it nothing is deleted.
+        #[arg(long)]
+        yes:  bool,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum AdminCommand {
+    /// Create an admin account. The password is read from the first line of
+    /// stdin and hashed with argon2id.
+    Create {
+        username: String,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum BanCommand {
+    /// Ban an IP address, or an IP hash taken from the moderation dashboard.
+    ///
+    /// A raw IP is hashed with today's salt, so the ban matches posts made
+    /// from it today.
+    Add {
+        /// IP address to ban.
+        #[arg(required_unless_present = "ip_hash")]
+        ip:       Option<String>,
+        /// Ban this IP hash instead of an address.
+        #[arg(long, conflicts_with = "ip")]
+        ip_hash:  Option<String>,
+        /// Reason shown to the banned poster.
+        #[arg(long)]
+        reason:   String,
+        /// Ban length such as `30m`, `12h`, `7d` or `2w`. Default: permanent.
+        #[arg(long, value_parser = parse_duration)]
+        duration: Option<Duration>,
+        /// Staff username recorded as issuing the ban.
+        #[arg(long, value_name = "USERNAME")]
+        by:       String,
+    },
+    /// Expire a ban immediately.
+    Remove {
+        /// Ban ID.
+        ban_id: uuid::Uuid,
+        /// Staff username recorded as lifting the ban.
+        #[arg(long, value_name = "USERNAME")]
+        by:     String,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum StorageCommand {
+    /// Re-hash stored originals and find missing thumbnails and orphans.
+    /// Exits non-zero when an original is corrupt or missing.
+    Verify {
+        /// Regenerate missing thumbnails and delete orphans.
+        #[arg(long)]
+        fix: bool,
+    },
+}
+
+/// Parse `key=value` for `--set`. Keys are matched in lower case.
+fn parse_override(arg: &str) -> Result<(String, String), String> {
+    let (key, value) = arg.split_once('=').ok_or_else(|| format!("`{arg}` must be `key=value`"))?;
+    Ok((key.trim().to_ascii_lowercase(), value.to_owned()))
+}
+
+/// Parse a length such as `90s`, `30m`, `12h`, `7d` or `2w`.
+fn parse_duration(arg: &str) -> Result<Duration, String> {
+    let arg = arg.trim();
+    let split = arg.find(|c: char| !c.is_ascii_digit()).unwrap_or(arg.len());
+    let (count, unit) = arg.split_at(split);
+    let count: u64 = count.parse().map_err(|_| format!("`{arg}` must start with a number"))?;
+    let secs = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3_600,
+        "d" => 86_400,
+        "w" => 604_800,
+        _ => return Err(format!("`{arg}` needs a unit: s, m, h, d or w")),
+    };
+    Ok(Duration::from_secs(count.saturating_mul(secs)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+    use std::path::Path;
+
+    #[test]
+    fn cli_definition_is_valid() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn durations_need_a_unit() {
+        assert_eq!(parse_duration("7d"), Ok(Duration::from_secs(7 * 86_400)));
+        assert_eq!(parse_duration("90s"), Ok(Duration::from_secs(90)));
+        assert!(parse_duration("7").is_err());
+        assert!(parse_duration("d").is_err());
+    }
+
+    #[test]
+    fn global_flags_work_after_the_subcommand() {
+        let cli = Cli::try_parse_from(["rusty-board", "gc-media", "--dry-run", "--set", "Port=9000"]).unwrap();
+        assert_eq!(cli.overrides, vec![("port".to_owned(), "9000".to_owned())]);
+        assert!(matches!(cli.command, Some(Command::GcMedia { dry_run: true })));
+    }
+
+    #[test]
+    fn site_selects_the_board_to_prune() {
+        assert!(Cli::try_parse_from(["rusty-board", "prune", "--site", "a.example"]).is_err());
+        let cli = Cli::try_parse_from(["rusty-board", "prune", "--board", "b", "--site", "a.example"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Prune { board: Some(_), site: Some(_) })));
+    }
+
+    #[test]
+    fn export_needs_an_output_path() {
+        assert!(Cli::try_parse_from(["rusty-board", "export"]).is_err());
+        let cli = Cli::try_parse_from(["rusty-board", "export", "--out", "dump.tar.zst"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Export { out }) if out == Path::new("dump.tar.zst")));
+    }
+}
@@ -0,0 +1,273 @@
+//! Subcommands that manage an instance without serving HTTP.
+//!
+//! Each one connects through `composition`, does its job once, and prints a
+//! short summary to stdout. Errors propagate to `main` and exit non-zero.
+
+use std::io::{BufRead, IsTerminal, Write};
+
+use anyhow::Context;
+use configs::Settings;
+use domains::models::{IpHash, Role, SiteId};
+
+use crate::backup;
+use crate::cli::{AdminCommand, BanCommand, BoardCommand, Command, SiteCommand, StorageCommand};
+use crate::composition::{self, AdminContext};
+use crate::jobs::all_boards;
+
+/// Run one non-server subcommand.
+pub async fn run(command: Command, settings: &Settings) -> anyhow::Result<()> {
+    match command {
+        Command::Serve => unreachable!("`serve` is handled by main"),
+        Command::Migrate => {
+            composition::run_migrations(settings).await?;
+            println!("migrations applied");
+        }
+        Command::Site(command) => site(command, &composition::admin_context(settings).await?).await?,
+        Command::Board(command) => board(command, &composition::admin_context(settings).await?).await?,
+        Command::Admin(command) => admin(command, &composition::admin_context(settings).await?).await?,
+        Command::Ban(command) => ban(command, &composition::admin_context(settings).await?).await?,
+        Command::Prune { board, site } => {
+            let ctx = composition::admin_context(settings).await?;
+            let board = match board {
+                Some(slug) => Some((site_id(&ctx, site.as_deref()).await?, slug)),
+                None => None,
+            };
+            prune(board, &ctx).await?
+        }
+        Command::GcMedia { dry_run } => {
+            let report = composition::run_media_gc(settings, dry_run)
+                .await
+                .context("orphaned media sweep failed")?;
+            println!(
+                "scanned {} objects, {} orphaned, {} deleted{}",
+                report.scanned,
+                report.orphaned,
+                report.deleted,
+                if dry_run { " (dry run)" } else { "" },
+            );
+        }
+        Command::RegenThumbs => {
+            let report = composition::run_thumbnail_regen(settings)
+                .await
+                .context("thumbnail regeneration failed")?;
+            println!(
+                "scanned {} originals, {} regenerated, {} skipped, {} failed",
+                report.scanned, report.regenerated, report.skipped, report.failed,
+            );
+        }
+        Command::Storage(StorageCommand::Verify { fix }) => storage_verify(settings, fix).await?,
+        Command::Export { out } => {
+            let report = backup::export(&composition::export_stores(settings).await?, &out)
+                .await
+                .context("export failed")?;
+            println!(
+                "exported {} sites, {} boards, {} threads, {} posts, {} bans, {} staff accounts and {} media objects to {}",
+                report.sites, report.boards, report.threads, report.posts, report.bans, report.users, report.media,
+                out.display(),
+            );
+            if report.media_missing > 0 {
+                println!("{} referenced media objects were missing from storage", report.media_missing);
+            }
+        }
+        Command::Import { path } => {
+            let report = backup::import(&composition::import_stores(settings).await?, &path)
+                .await
+                .context("import failed")?;
+            println!(
+                "imported {} sites, {} boards, {} threads, {} posts, {} bans, {} staff accounts and {} media objects",
+                report.sites, report.boards, report.threads, report.posts, report.bans, report.users, report.media,
+            );
+        }
+    }
+    Ok(())
+}
+
+async fn site(command: SiteCommand, ctx: &AdminContext) -> anyhow::Result<()> {
+    match command {
+        SiteCommand::Add { host, name, media_prefix } => {
+            let name = name.unwrap_or_else(|| host.clone());
+            let site = ctx.sites.create_site(&host, &name, media_prefix.as_deref()).await?;
+            println!("added {} ({}), media under {}/", site.host, site.id, site.media_prefix);
+            println!("running servers pick it up within the config cache TTL, or at once on SIGHUP");
+        }
+        SiteCommand::List => {
+            let sites = ctx.sites.list_sites().await?;
+            for site in &sites {
+                let host = if site.id == SiteId::DEFAULT { "(default)" } else { site.host.as_str() };
+                println!("{host}\t{}\t{}\t{}", site.name, site.id, site.media_prefix);
+            }
+            println!("{} sites", sites.len());
+        }
+        SiteCommand::Remove { host } => {
+            let site = ctx.sites.delete_site(&host).await?;
+            println!("removed {}", site.host);
+        }
+    }
+    Ok(())
+}
+
+/// The site claiming `host`, or the default site when no host is given.
+async fn site_id(ctx: &AdminContext, host: Option<&str>) -> anyhow::Result<SiteId> {
+    match host {
+        Some(host) => Ok(ctx.sites.get_by_host(host).await?.id),
+        None => Ok(SiteId::DEFAULT),
+    }
+}
+
+async fn board(command: BoardCommand, ctx: &AdminContext) -> anyhow::Result<()> {
+    match command {
+        BoardCommand::Create { slug, title, rules, site } => {
+            let site = site_id(ctx, site.as_deref()).await?;
+            let board = ctx.boards.create_board(site, &slug, &title, &rules).await?;
+            println!("created /{}/ ({})", board.slug, board.id);
+        }
+        BoardCommand::List { site } => {
+            let site = site_id(ctx, site.as_deref()).await?;
+            let boards = all_boards(&ctx.boards).await?;
+            let boards: Vec<_> = boards.into_iter().filter(|b| b.site_id == site).collect();
+            for board in &boards {
+                println!("/{}/\t{}\t{}\t{}", board.slug, board.title, board.id, board.created_at.format("%Y-%m-%d"));
+            }
+            println!("{} boards", boards.len());
+        }
+        BoardCommand::Delete { slug, site, yes } => {
+            let site = site_id(ctx, site.as_deref()).await?;
+            let board = ctx.boards.get_by_slug(site, &slug).await?;
+            if !yes {
+                anyhow::bail!("deleting /{}/ removes every thread and post on it; re-run with --yes", board.slug);
+            }
+            ctx.boards.delete_board(board.id).await?;
+            println!("deleted /{}/", board.slug);
+        }
+    }
+    Ok(())
+}
+
+async fn admin(command: AdminCommand, ctx: &AdminContext) -> anyhow::Result<()> {
+    match command {
+        AdminCommand::Create { username } => {
+            let password = read_password()?;
+            let user = ctx.users.create_user(&username, &password, Role::Admin).await?;
+            println!("created admin {} ({})", user.username, user.id);
+        }
+    }
+    Ok(())
+}
+
+async fn ban(command: BanCommand, ctx: &AdminContext) -> anyhow::Result<()> {
+    match command {
+        BanCommand::Add { ip, ip_hash, reason, duration, by } => {
+            let actor = staff_account(ctx, &by).await?;
+            let ip_hash = match (ip, ip_hash) {
+                (_, Some(hash)) => IpHash::new(hash),
+                (Some(ip), None) => {
+                    let ip: std::net::IpAddr = ip.parse().with_context(|| format!("`{ip}` is not an IP address"))?;
+                    // Same daily salt as the posting handlers.
+                    let daily_salt = chrono::Utc::now().format("%Y-%m-%d").to_string();
+                    services::common::utils::hash_ip(&ip.to_string(), &daily_salt)
+                }
+                (None, None) => anyhow::bail!("give an IP address or --ip-hash"),
+            };
+            let expires_at = duration
+                .map(|d| chrono::Duration::from_std(d).map(|d| chrono::Utc::now() + d))
+                .transpose()
+                .context("ban duration is too long")?;
+            let ban_id = ctx.moderation.ban_ip(ip_hash, reason, expires_at, actor.id).await?;
+            match expires_at {
+                Some(at) => println!("ban {ban_id} issued until {}", at.format("%Y-%m-%d %H:%M UTC")),
+                None => println!("ban {ban_id} issued (permanent)"),
+            }
+        }
+        BanCommand::Remove { ban_id, by } => {
+            let actor = staff_account(ctx, &by).await?;
+            ctx.moderation.expire_ban(domains::models::BanId(ban_id), actor.id).await?;
+            println!("ban {ban_id} expired");
+        }
+    }
+    Ok(())
+}
+
+async fn prune(board: Option<(SiteId, String)>, ctx: &AdminContext) -> anyhow::Result<()> {
+    let boards = match board {
+        Some((site, slug)) => vec![ctx.boards.get_by_slug(site, &slug).await?],
+        None => all_boards(&ctx.boards).await?,
+    };
+    let mut total = 0;
+    for board in boards {
+        let config = ctx.boards.get_config(board.id).await?;
+        let pruned = ctx
+            .threads
+            .prune_with_archive(board.id, config.max_threads, config.archive_enabled)
+            .await?;
+        if pruned > 0 {
+            println!("/{}/: pruned {pruned} threads", board.slug);
+        }
+        total += pruned;
+    }
+    println!("pruned {total} threads");
+    Ok(())
+}
+
+async fn storage_verify(settings: &Settings, fix: bool) -> anyhow::Result<()> {
+    let report = composition::run_storage_verify(settings, fix)
+        .await
+        .context("storage audit failed")?;
+    let audit = &report.audit;
+    println!(
+        "scanned {} objects: {} originals, {} verified, {} corrupt, {} missing, {} unreadable",
+        audit.stored,
+        audit.originals,
+        audit.verified,
+        audit.corrupt.len(),
+        audit.missing_originals.len(),
+        audit.unreadable,
+    );
+    for key in &audit.corrupt {
+        println!("  corrupt: {key}");
+    }
+    for key in &audit.missing_originals {
+        println!("  missing: {key}");
+    }
+    match &report.thumbnails {
+        Some(regen) => println!(
+            "{} thumbnails missing, {} regenerated, {} failed",
+            audit.missing_thumbnails.len(), regen.regenerated, regen.skipped + regen.failed,
+        ),
+        None => println!("{} thumbnails missing", audit.missing_thumbnails.len()),
+    }
+    println!(
+        "{} orphaned objects, {} deleted{}",
+        report.orphans.orphaned,
+        report.orphans.deleted,
+        if fix { "" } else { " (run with --fix to remove)" },
+    );
+    if !audit.corrupt.is_empty() || !audit.missing_originals.is_empty() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// The janitor or admin account named by `--by`, recorded in the audit log.
+async fn staff_account(ctx: &AdminContext, username: &str) -> anyhow::Result<domains::models::User> {
+    let user = ctx.users.get_by_username(username).await?;
+    if !matches!(user.role, Role::Janitor | Role::Admin) {
+        anyhow::bail!("{username} is a {}; site-wide bans need a janitor or admin", user.role);
+    }
+    Ok(user)
+}
+
+/// Read a password from the first line of stdin, prompting when interactive.
+fn read_password() -> anyhow::Result<String> {
+    let stdin = std::io::stdin();
+    if stdin.is_terminal() {
+        eprint!("Password (shown as typed; pipe it in to hide it): ");
+        std::io::stderr().flush()?;
+    }
+    let mut line = String::new();
+    stdin.lock().read_line(&mut line).context("failed to read password")?;
+    let password = line.trim_end_matches(['\r', '\n']);
+    if password.is_empty() {
+        anyhow::bail!("no password given on stdin");
+    }
+    Ok(password.to_owned())
+}
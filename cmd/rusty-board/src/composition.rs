@@ -6,7 +6,8 @@
 //! 2. Constructs all concrete adapter instances based on active features
 //! 3. Injects adapters into generic service structs (monomorphization)
 //! 4. Defines `*Deps` type aliases for multi-parameter services
-//! 5. Returns the configured `axum::Router`
+//! 5. Returns the configured `axum::Router`, plus the `Background` work to wind
+//!    down on shutdown
 //!
 //! # INVARIANT
 //! Feature flag branches appear **only** here. Services, handlers, and adapters
@@ -17,12 +18,19 @@ use std::time::Duration;
 
 use anyhow::Context;
 use configs::Settings;
+use domains::models::{ThumbnailFormat, ThumbnailSpec};
+use domains::ports::{ErrorReporter, MediaStorage, RateLimiter};
+use storage_adapters::registry::{BoxFuture, Registry};
 
 // ── Services ─────────────────────────────────────────────────────────────────
 use services::board::BoardService;
+use services::media_gc::{GcReport, MediaGcService};
 use services::moderation::ModerationService;
 use services::post::PostService;
+use services::stats::StatsService;
+use services::storage_audit::{AuditReport, StorageAuditService};
 use services::thread::ThreadService;
+use services::thumbnails::{RegenReport, ThumbnailRegenService};
 
 // ── Health probes ─────────────────────────────────────────────────────────────
 // Implemented here so composition can borrow the concrete pool types without
@@ -39,6 +47,16 @@ impl api_adapters::axum::health::DatabaseProbe for PgProbe {
     }
 }
 
+/// Health probe of the `memory` cache backend, which has nothing to reach.
+struct InProcessProbe;
+
+#[async_trait::async_trait]
+impl api_adapters::axum::health::RedisProbe for InProcessProbe {
+    async fn ping(&self) -> bool {
+        true
+    }
+}
+
 #[cfg(feature = "redis")]
 struct RedisProbeImpl(deadpool_redis::Pool);
 
@@ -62,9 +80,11 @@ use services::user::UserService;
 use storage_adapters::postgres::{
     connection::create_pool,
     repositories::{
-        PgAuditRepository, PgBanRepository, PgBoardRepository, PgFlagRepository,
-        PgPostRepository, PgSessionRepository, PgStaffMessageRepository,
-        PgStaffRequestRepository, PgThreadRepository, PgUserRepository,
+        PgAnnouncementRepository, PgApiKeyRepository, PgAsnBanRepository, PgAuditRepository, PgBanRepository, PgBannerRepository, PgBoardRepository,
+        PgExternalIdentityRepository, PgFlagRepository, PgFollowerRepository, PgHashBanRepository, PgLastModifiedRepository, PgPollRepository, PgPostRepository,
+        PgReactionRepository, PgSessionRepository, PgSiteRepository, PgStaffMessageRepository,
+        PgStaffRequestRepository, PgStaticPageRepository, PgStatsRepository, PgThreadRepository, PgTotpRepository, PgUserRepository, PgWarningRepository,
+        PgWatchRepository,
     },
 };
 
@@ -74,52 +94,47 @@ use storage_adapters::media::local_fs::LocalFsMediaStorage;
 #[cfg(feature = "media-s3")]
 use storage_adapters::media::s3::S3MediaStorage;
 
+#[cfg(feature = "media-ipfs")]
+use storage_adapters::media::ipfs::IpfsMediaStorage;
+
 #[cfg(feature = "redis")]
-use storage_adapters::redis::{connection::create_pool as create_redis_pool, RedisRateLimiter};
+use storage_adapters::redis::{connection::create_pool as create_redis_pool, RedisCacheStore, RedisRateLimiter};
 
-use storage_adapters::media::ImageMediaProcessor;
+use storage_adapters::media::cdn::{CdnMediaStorage, MediaUrls};
+use storage_adapters::media::{
+    CompositeMediaProcessor as AppMediaProcessor, ImageMediaProcessor, ThumbnailQueue,
+};
 
 // ── Auth adapters (feature-gated) ─────────────────────────────────────────────
 #[cfg(feature = "auth-jwt")]
 use auth_adapters::jwt_bearer::JwtAuthProvider;
 
 // ── Cache ─────────────────────────────────────────────────────────────────────
-use storage_adapters::cache::BoardConfigCache;
+use storage_adapters::cache::{BoardConfigCache, SiteCache};
 
 // ── API adapters (feature-gated) ──────────────────────────────────────────────
 #[cfg(feature = "web-axum")]
 use axum::Router;
 
-// ─── Type aliases for readable service instantiation ─────────────────────────
+use tokio_util::sync::CancellationToken;
 
-/// Concrete `PostService` type — local-fs media storage variant.
-#[cfg(all(feature = "db-postgres", feature = "media-local", feature = "redis"))]
-#[allow(dead_code)]
-type AppPostService = PostService<
-    PgPostRepository,
-    PgThreadRepository,
-    PgBanRepository,
-    LocalFsMediaStorage,
-    RedisRateLimiter,
-    ImageMediaProcessor,
->;
+// ─── Type aliases for readable service instantiation ─────────────────────────
 
-/// Concrete `PostService` type — S3 media storage variant.
-/// Only active when `media-s3` is enabled but `media-local` is not.
+/// Concrete `PostService` type. Media storage and the rate limiter are
+/// chosen at runtime (`MEDIA_BACKEND`, `CACHE_BACKEND`).
+#[cfg(feature = "db-postgres")]
 #[allow(dead_code)]
-#[cfg(all(feature = "db-postgres", feature = "media-s3", feature = "redis", not(feature = "media-local")))]
 type AppPostService = PostService<
     PgPostRepository,
     PgThreadRepository,
     PgBanRepository,
-    S3MediaStorage,
-    RedisRateLimiter,
-    ImageMediaProcessor,
+    CdnMediaStorage<Arc<dyn MediaStorage>>,
+    Arc<dyn RateLimiter>,
+    AppMediaProcessor,
 >;
 
 /// Concrete `ModerationService` type (same repo types regardless of media backend).
 #[cfg(feature = "db-postgres")]
-#[allow(dead_code)]
 type AppModerationService = ModerationService<
     PgBanRepository,
     PgPostRepository,
@@ -129,6 +144,982 @@ type AppModerationService = ModerationService<
     PgUserRepository,
 >;
 
+/// Concrete `BoardService` type.
+#[cfg(feature = "db-postgres")]
+type AppBoardService = BoardService<PgBoardRepository>;
+
+/// Concrete `SiteService` type.
+#[cfg(feature = "db-postgres")]
+type AppSiteService = services::site::SiteService<PgSiteRepository>;
+
+/// Concrete `ThreadService` type.
+#[cfg(feature = "db-postgres")]
+type AppThreadService = ThreadService<PgThreadRepository, PgPostRepository>;
+
+/// Concrete auth provider — cookie sessions replace JWT when `auth-cookie` is active.
+#[cfg(all(feature = "auth-jwt", not(feature = "auth-cookie")))]
+type AppAuthProvider = JwtAuthProvider;
+
+/// Concrete auth provider — cookie sessions replace JWT when `auth-cookie` is active.
+#[cfg(all(feature = "db-postgres", feature = "auth-cookie"))]
+type AppAuthProvider = auth_adapters::cookie_session::CookieAuthProvider<PgSessionRepository>;
+
+/// Concrete `UserService` type.
+#[cfg(feature = "db-postgres")]
+type AppUserService = UserService<PgUserRepository, AppAuthProvider>;
+
+/// Public media URLs: `MEDIA_CDN_URL`, else `MEDIA_URL_BASE`, signed when
+/// `MEDIA_URL_SIGNING_KEY` is set.
+fn build_media_urls(settings: &Settings) -> MediaUrls {
+    use secrecy::ExposeSecret;
+    let urls = MediaUrls::new(settings.media_cdn_url.clone().unwrap_or_else(|| settings.media_url_base.clone()));
+    match &settings.media_url_signing_key {
+        Some(key) => urls.with_signing(key.expose_secret().as_bytes(), Duration::from_secs(settings.media_url_ttl_secs)),
+        None => urls,
+    }
+}
+
+/// Media storage backends compiled into this build; the first is the default.
+fn media_backends() -> Registry<Settings, Arc<dyn MediaStorage>> {
+    let registry = Registry::new("media storage");
+    #[cfg(feature = "media-local")]
+    let registry = registry.register("local", local_media_storage);
+    #[cfg(feature = "media-s3")]
+    let registry = registry.register("s3", s3_media_storage);
+    #[cfg(feature = "media-ipfs")]
+    let registry = registry.register("ipfs", ipfs_media_storage);
+    registry
+}
+
+/// Build the media storage backend `MEDIA_BACKEND` names.
+async fn build_media_storage(settings: &Settings) -> anyhow::Result<Arc<dyn MediaStorage>> {
+    Ok(media_backends().build(settings.media_backend.as_deref(), settings).await?.1)
+}
+
+#[cfg(feature = "media-local")]
+fn local_media_storage(settings: &Settings) -> BoxFuture<'_, anyhow::Result<Arc<dyn MediaStorage>>> {
+    Box::pin(async move {
+        let storage: Arc<dyn MediaStorage> = Arc::new(LocalFsMediaStorage::new(
+            settings.media_path.clone(),
+            settings.media_url_base.clone(),
+        ));
+        Ok(storage)
+    })
+}
+
+#[cfg(feature = "media-s3")]
+fn s3_media_storage(settings: &Settings) -> BoxFuture<'_, anyhow::Result<Arc<dyn MediaStorage>>> {
+    Box::pin(async move {
+        use secrecy::ExposeSecret;
+        let s3_cfg = settings
+            .s3
+            .as_ref()
+            .context("MEDIA_BACKEND=s3 needs S3__BUCKET, S3__REGION, S3__ACCESS_KEY and S3__SECRET_KEY")?;
+        let aws_config = aws_config::defaults(aws_config::BehaviorVersion::latest())
+            .region(aws_sdk_s3::config::Region::new(s3_cfg.region.clone()))
+            .credentials_provider(aws_sdk_s3::config::Credentials::new(
+                s3_cfg.access_key.expose_secret(),
+                s3_cfg.secret_key.expose_secret(),
+                None,
+                None,
+                "static",
+            ))
+            .load()
+            .await;
+        let client = if let Some(endpoint) = &s3_cfg.endpoint {
+            aws_sdk_s3::Client::from_conf(
+                aws_sdk_s3::config::Builder::from(&aws_config)
+                    .endpoint_url(endpoint)
+                    .build(),
+            )
+        } else {
+            aws_sdk_s3::Client::new(&aws_config)
+        };
+        let storage: Arc<dyn MediaStorage> =
+            Arc::new(S3MediaStorage::new(client, s3_cfg.bucket.clone(), s3_cfg.endpoint.clone()));
+        Ok(storage)
+    })
+}
+
+#[cfg(feature = "media-ipfs")]
+fn ipfs_media_storage(settings: &Settings) -> BoxFuture<'_, anyhow::Result<Arc<dyn MediaStorage>>> {
+    Box::pin(async move {
+        let ipfs = &settings.ipfs;
+        let storage: Arc<dyn MediaStorage> = Arc::new(IpfsMediaStorage::new(
+            ipfs.api_url.clone(),
+            ipfs.gateway_url.clone(),
+            ipfs.mfs_root.clone(),
+        ));
+        Ok(storage)
+    })
+}
+
+/// Rate limiter, shared cache store and health probe of one cache backend.
+struct CacheBackend {
+    rate_limiter: Arc<dyn RateLimiter>,
+    store:        Arc<dyn domains::ports::CacheStore>,
+    probe:        Arc<dyn api_adapters::axum::health::RedisProbe>,
+}
+
+/// Cache backends compiled into this build; the first is the default.
+fn cache_backends() -> Registry<Settings, CacheBackend> {
+    let registry = Registry::new("cache");
+    #[cfg(feature = "redis")]
+    let registry = registry.register("redis", redis_cache);
+    registry.register("memory", memory_cache)
+}
+
+// Both limiters default to 3 posts/window; BoardConfig overrides it per board.
+
+#[cfg(feature = "redis")]
+fn redis_cache(settings: &Settings) -> BoxFuture<'_, anyhow::Result<CacheBackend>> {
+    Box::pin(async move {
+        use secrecy::ExposeSecret;
+        let url = settings.redis_url.as_ref().context("CACHE_BACKEND=redis needs REDIS_URL")?;
+        let pool = create_redis_pool(url.expose_secret()).context("failed to create Redis pool")?;
+        Ok(CacheBackend {
+            rate_limiter: Arc::new(RedisRateLimiter::new(pool.clone(), 3)),
+            store:        Arc::new(RedisCacheStore::new(pool.clone())),
+            probe:        Arc::new(RedisProbeImpl(pool)),
+        })
+    })
+}
+
+/// Per-process cache and rate limits: still shared by worker threads, but
+/// not by other instances.
+fn memory_cache(_: &Settings) -> BoxFuture<'_, anyhow::Result<CacheBackend>> {
+    Box::pin(async {
+        Ok(CacheBackend {
+            rate_limiter: Arc::new(storage_adapters::in_memory::InMemoryRateLimiter::new(3)),
+            store:        Arc::new(storage_adapters::in_memory::InMemoryCacheStore::new()),
+            probe:        Arc::new(InProcessProbe),
+        })
+    })
+}
+
+/// Single-connection pool for the maintenance subcommands.
+#[cfg(feature = "db-postgres")]
+async fn maintenance_pool(settings: &Settings) -> anyhow::Result<sqlx::PgPool> {
+    use secrecy::ExposeSecret;
+    create_pool(settings.db_url.expose_secret(), 1, 1)
+        .await
+        .context("failed to connect to PostgreSQL")
+}
+
+/// The pepper for `##` secure tripcodes: `TRIPCODE_PEPPER` when set, otherwise
+/// a secret generated once and kept in the database so trips survive restarts.
+#[cfg(feature = "db-postgres")]
+async fn tripcode_pepper(settings: &Settings, pool: &sqlx::PgPool) -> anyhow::Result<String> {
+    match settings.tripcode_pepper.as_deref() {
+        Some(pepper) if !pepper.is_empty() => Ok(pepper.to_owned()),
+        _ => storage_adapters::postgres::secrets::load_or_create_secret(pool, "tripcode_pepper")
+            .await
+            .context("failed to load the tripcode pepper"),
+    }
+}
+
+/// Apply pending migrations to `pool`.
+#[cfg(feature = "db-postgres")]
+async fn migrate(pool: &sqlx::PgPool) -> anyhow::Result<()> {
+    sqlx::migrate!("../../crates/storage-adapters/src/migrations")
+        .run(pool)
+        .await
+        .context("failed to run database migrations")
+}
+
+/// One-off migration run for `rusty-board migrate`.
+#[cfg(feature = "db-postgres")]
+pub async fn run_migrations(settings: &Settings) -> anyhow::Result<()> {
+    migrate(&maintenance_pool(settings).await?).await
+}
+
+/// Work that outlives a single request: reloaded by `main` on SIGHUP, and
+/// wound down after the server stops accepting connections and in-flight
+/// requests have drained.
+pub struct Background {
+    /// Cached board configs, cleared on reload.
+    board_configs: Arc<BoardConfigCache>,
+    /// Cached site list, cleared on reload.
+    sites:         Arc<SiteCache>,
+    /// Queue of thumbnails rendered after their post was saved.
+    thumbnails:    ThumbnailQueue,
+    /// Scheduled maintenance jobs (see `jobs.rs`).
+    jobs:          Vec<tokio::task::JoinHandle<()>>,
+    /// Stops `jobs` between runs.
+    stop:          CancellationToken,
+    /// Flushed last, so failures during shutdown are still sent.
+    error_reporter: Arc<dyn ErrorReporter>,
+    #[cfg(feature = "db-postgres")]
+    pool:          sqlx::PgPool,
+}
+
+/// What `Background::shutdown` left undone.
+#[derive(Debug, Default)]
+pub struct ShutdownSummary {
+    /// Thumbnails still queued or rendering when the timeout expired.
+    pub thumbnails_abandoned: usize,
+    /// Scheduled jobs still running at the timeout, which were aborted.
+    pub jobs_aborted:         usize,
+}
+
+impl Background {
+    /// A callback that drops cached board configs and sites so they are
+    /// re-read from the database (see `BoardConfigCache::clear`), returning
+    /// how many boards and sites were cached.
+    pub fn reloader(&self) -> impl Fn() -> (usize, usize) + Send + 'static {
+        let board_configs = self.board_configs.clone();
+        let sites = self.sites.clone();
+        move || (board_configs.clear(), sites.clear())
+    }
+
+    /// Flush the thumbnail queue, stop scheduled jobs, close the database
+    /// pool, and send queued error reports, waiting at most `timeout` for the
+    /// first two together and again for the reports.
+    pub async fn shutdown(self, timeout: Duration) -> ShutdownSummary {
+        let deadline = tokio::time::Instant::now() + timeout;
+        self.stop.cancel();
+        let mut summary = ShutdownSummary {
+            thumbnails_abandoned: self.thumbnails.drain(timeout).await,
+            ..ShutdownSummary::default()
+        };
+        for mut job in self.jobs {
+            if tokio::time::timeout_at(deadline, &mut job).await.is_err() {
+                job.abort();
+                summary.jobs_aborted += 1;
+            }
+        }
+        #[cfg(feature = "db-postgres")]
+        self.pool.close().await;
+        let reporter = self.error_reporter;
+        let _ = tokio::task::spawn_blocking(move || reporter.flush(timeout)).await;
+        summary
+    }
+}
+
+/// One-off orphaned-media sweep for `rusty-board gc-media [--dry-run]`.
+///
+/// Connects to the database and media storage only — no migrations, no
+/// router. Uses `MEDIA_GC_GRACE_SECS` like the scheduled sweep.
+#[cfg(feature = "db-postgres")]
+pub async fn run_media_gc(settings: &Settings, dry_run: bool) -> anyhow::Result<GcReport> {
+    let pool = maintenance_pool(settings).await?;
+    let gc = MediaGcService::new(PgPostRepository::new(pool), build_media_storage(settings).await?);
+    let grace = Duration::from_secs(settings.media_gc_grace_secs);
+    Ok(gc.sweep(grace, dry_run).await?)
+}
+
+/// The repositories and media storage read by `rusty-board export`.
+///
+/// Connects without migrating, so an export never changes the source.
+#[cfg(feature = "db-postgres")]
+pub async fn export_stores(settings: &Settings) -> anyhow::Result<crate::backup::Stores> {
+    backup_stores(maintenance_pool(settings).await?, settings).await
+}
+
+/// The repositories and media storage written by `rusty-board import`.
+///
+/// Applies pending migrations first, so an archive can be restored into a
+/// freshly created database.
+#[cfg(feature = "db-postgres")]
+pub async fn import_stores(settings: &Settings) -> anyhow::Result<crate::backup::Stores> {
+    let pool = maintenance_pool(settings).await?;
+    migrate(&pool).await?;
+    backup_stores(pool, settings).await
+}
+
+#[cfg(feature = "db-postgres")]
+async fn backup_stores(pool: sqlx::PgPool, settings: &Settings) -> anyhow::Result<crate::backup::Stores> {
+    Ok(crate::backup::Stores {
+        sites:     Arc::new(PgSiteRepository::new(pool.clone())),
+        boards:    Arc::new(PgBoardRepository::new(pool.clone())),
+        threads:   Arc::new(PgThreadRepository::new(pool.clone())),
+        posts:     Arc::new(PgPostRepository::new(pool.clone())),
+        users:     Arc::new(PgUserRepository::new(pool.clone())),
+        bans:      Arc::new(PgBanRepository::new(pool.clone())),
+        asn_bans:  Arc::new(PgAsnBanRepository::new(pool.clone())),
+        hash_bans: Arc::new(PgHashBanRepository::new(pool)),
+        media:     build_media_storage(settings).await?,
+    })
+}
+
+/// One-off thumbnail regeneration for `rusty-board regen-thumbs`.
+///
+/// Re-renders every stored thumbnail with the current `THUMBNAIL_*` settings
+/// and per-board overrides. Thumbnails are rendered inline, not queued, so the
+/// run finishes only when every attachment has been updated.
+#[cfg(feature = "db-postgres")]
+pub async fn run_thumbnail_regen(settings: &Settings) -> anyhow::Result<RegenReport> {
+    let pool = maintenance_pool(settings).await?;
+    let regen = ThumbnailRegenService::new(
+        PgPostRepository::new(pool.clone()),
+        PgBoardRepository::new(pool),
+        build_media_storage(settings).await?,
+        build_media_processor(settings, None),
+    );
+    Ok(regen.regenerate().await?)
+}
+
+/// Services behind the administration subcommands (`site`, `board`, `admin`,
+/// `ban`, `prune`).
+#[cfg(feature = "db-postgres")]
+pub struct AdminContext {
+    pub sites:      AppSiteService,
+    pub boards:     AppBoardService,
+    pub threads:    AppThreadService,
+    pub users:      AppUserService,
+    pub moderation: AppModerationService,
+}
+
+/// Connect to the database and build the administration services.
+///
+/// No migrations or router. Pruned threads are archived on boards with
+/// `archive_enabled`, with their static pages, as they are when the server
+/// prunes.
+#[cfg(feature = "db-postgres")]
+pub async fn admin_context(settings: &Settings) -> anyhow::Result<AdminContext> {
+    let pool = maintenance_pool(settings).await?;
+    let archive: Arc<dyn domains::ports::ArchiveRepository> = Arc::new(
+        api_adapters::axum::static_archive::StaticArchiveRepository::new(
+            Arc::new(storage_adapters::postgres::repositories::archive_repository::PgArchiveRepository::new(pool.clone())),
+            Arc::new(PgBoardRepository::new(pool.clone())),
+            Arc::new(PgThreadRepository::new(pool.clone())),
+            Arc::new(PgPostRepository::new(pool.clone())),
+            build_media_storage(settings).await?,
+        ),
+    );
+    Ok(AdminContext {
+        sites:      services::site::SiteService::new(PgSiteRepository::new(pool.clone())),
+        boards:     BoardService::new(PgBoardRepository::new(pool.clone())),
+        threads:    ThreadService::new(PgThreadRepository::new(pool.clone()), PgPostRepository::new(pool.clone()))
+            .with_archive(archive),
+        users:      UserService::new(
+            PgUserRepository::new(pool.clone()),
+            build_auth_provider(settings, &pool),
+            settings.jwt_ttl_secs,
+        ),
+        moderation: ModerationService::new(
+            PgBanRepository::new(pool.clone()),
+            PgPostRepository::new(pool.clone()),
+            PgThreadRepository::new(pool.clone()),
+            PgFlagRepository::new(pool.clone()),
+            PgAuditRepository::new(pool.clone()),
+            PgUserRepository::new(pool),
+        ),
+    })
+}
+
+/// Build the auth provider selected by feature flags.
+#[cfg(all(feature = "auth-jwt", not(feature = "auth-cookie")))]
+fn build_auth_provider(settings: &Settings, _pool: &sqlx::PgPool) -> AppAuthProvider {
+    use secrecy::ExposeSecret;
+    JwtAuthProvider::new(
+        settings.jwt_secret.expose_secret().as_bytes(),
+        settings.argon2_m_cost,
+        settings.argon2_t_cost,
+        settings.argon2_p_cost,
+    )
+}
+
+/// Build the auth provider selected by feature flags.
+#[cfg(feature = "auth-cookie")]
+fn build_auth_provider(settings: &Settings, pool: &sqlx::PgPool) -> AppAuthProvider {
+    auth_adapters::cookie_session::CookieAuthProvider::new(
+        PgSessionRepository::new(pool.clone()),
+        settings.cookie_session_ttl_secs.unwrap_or(604_800) as i64,
+        settings.argon2_m_cost,
+        settings.argon2_t_cost,
+        settings.argon2_p_cost,
+    )
+}
+
+/// Outcome of `rusty-board storage verify`.
+pub struct StorageVerifyReport {
+    /// Hash, original and thumbnail checks.
+    pub audit:      AuditReport,
+    /// Missing thumbnails re-rendered; `None` unless `--fix` was given.
+    pub thumbnails: Option<RegenReport>,
+    /// Orphaned objects; only deleted with `--fix`.
+    pub orphans:    GcReport,
+}
+
+/// One-off storage audit for `rusty-board storage verify [--fix]`.
+///
+/// Re-hashes every referenced original and looks for missing thumbnails, then
+/// counts orphans with the `gc-media` sweep. With `fix`, missing thumbnails are
+/// regenerated and orphans deleted; corrupt or missing originals can only be
+/// reported.
+#[cfg(feature = "db-postgres")]
+pub async fn run_storage_verify(settings: &Settings, fix: bool) -> anyhow::Result<StorageVerifyReport> {
+    let pool = maintenance_pool(settings).await?;
+    let post_repo = PgPostRepository::new(pool.clone());
+    let storage = build_media_storage(settings).await?;
+
+    let audit = StorageAuditService::new(post_repo.clone(), storage.clone()).verify().await?;
+    let thumbnails = if fix && !audit.missing_thumbnails.is_empty() {
+        let regen = ThumbnailRegenService::new(
+            post_repo.clone(),
+            PgBoardRepository::new(pool),
+            storage.clone(),
+            build_media_processor(settings, None),
+        );
+        let keys = audit.missing_thumbnails.iter().cloned().collect();
+        Some(regen.regenerate_only(&keys).await?)
+    } else {
+        None
+    };
+    let grace = Duration::from_secs(settings.media_gc_grace_secs);
+    let orphans = MediaGcService::new(post_repo, storage).sweep(grace, !fix).await?;
+    Ok(StorageVerifyReport { audit, thumbnails, orphans })
+}
+
+/// Build the media processor for every compiled-in media type.
+///
+/// Image thumbnails go to `queue` when given and are rendered inline otherwise.
+/// Thumbnail size, format and quality come from `THUMBNAIL_*`; boards with
+/// their own `BoardConfig::thumbnail` override them per upload.
+fn build_media_processor(settings: &Settings, queue: Option<ThumbnailQueue>) -> AppMediaProcessor {
+    let thumbnail = ThumbnailSpec {
+        max_width:  settings.thumbnail_width_px,
+        max_height: settings.thumbnail_height_px,
+        format:     match settings.thumbnail_format {
+            configs::ThumbnailFormat::Png => ThumbnailFormat::Png,
+            configs::ThumbnailFormat::Jpeg => ThumbnailFormat::Jpeg,
+            configs::ThumbnailFormat::Webp => ThumbnailFormat::Webp,
+        },
+        quality:    settings.thumbnail_quality,
+    };
+    let image_processor = ImageMediaProcessor::new().with_thumbnail(thumbnail);
+    let image_processor = match queue {
+        Some(queue) => image_processor.with_queue(queue),
+        None => image_processor,
+    };
+    let reencode_format = match settings.reencode_format {
+        configs::ReencodeFormat::Off => None,
+        configs::ReencodeFormat::Webp => Some(storage_adapters::media::ReencodeFormat::WebP),
+        configs::ReencodeFormat::Avif => Some(storage_adapters::media::ReencodeFormat::Avif),
+    };
+    let image_processor = match reencode_format {
+        Some(format) => image_processor.with_reencode(storage_adapters::media::Reencode {
+            format,
+            quality:   settings.reencode_quality,
+            min_bytes: settings.reencode_min_kb * 1024,
+        }),
+        None => image_processor,
+    };
+    #[cfg(feature = "thumbs-vips")]
+    let image_processor = image_processor.with_thumbnailer(storage_adapters::media::Thumbnailer::Vips(
+        storage_adapters::media::vips::VipsThumbnailer::with_binary(&settings.vips_path),
+    ));
+    let media_processor = AppMediaProcessor::new(image_processor);
+    #[cfg(feature = "video")]
+    let media_processor = media_processor.with(
+        storage_adapters::media::videos::VideoMediaProcessor::with_binaries(&settings.ffmpeg_path, &settings.ffprobe_path)
+            .with_thumbnail(thumbnail),
+    );
+    #[cfg(feature = "audio")]
+    let media_processor = media_processor.with(
+        storage_adapters::media::audio::AudioMediaProcessor::new().with_thumbnail(thumbnail),
+    );
+    #[cfg(feature = "documents")]
+    let media_processor = media_processor.with(
+        storage_adapters::media::documents::DocumentMediaProcessor::new(settings.pdfium_library_path.as_deref())
+            .with_thumbnail(thumbnail),
+    );
+    media_processor
+}
+
+/// Workspace crates at INFO, plus `tower_http`'s per-request events.
+const DEFAULT_LOG_FILTER: &str =
+    "rusty_board=info,api_adapters=info,services=info,storage_adapters=info,auth_adapters=info,tower_http=debug";
+
+/// Install the global tracing subscriber: JSON logs, plus OTLP span export
+/// when `OTLP_ENDPOINT` is set (feature: `otel`).
+///
+/// `RUST_LOG` (default `DEFAULT_LOG_FILTER`) filters both. The default keeps
+/// every span of the workspace crates, so a trace follows a request from the
+/// router through services and repositories to media storage.
+pub fn init_tracing(settings: &Settings) -> anyhow::Result<Telemetry> {
+    use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+
+    let registry = tracing_subscriber::registry()
+        .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| DEFAULT_LOG_FILTER.into()))
+        .with(tracing_subscriber::fmt::layer().json());
+
+    #[cfg(feature = "otel")]
+    if let Some(ref endpoint) = settings.otlp_endpoint {
+        use opentelemetry::trace::TracerProvider as _;
+        use opentelemetry_otlp::WithExportConfig;
+        use opentelemetry_sdk::trace::{Sampler, SdkTracerProvider};
+
+        let exporter = opentelemetry_otlp::SpanExporter::builder()
+            .with_http()
+            .with_endpoint(format!("{}/v1/traces", endpoint.trim_end_matches('/')))
+            .build()
+            .context("failed to build the OTLP exporter")?;
+        let provider = SdkTracerProvider::builder()
+            .with_batch_exporter(exporter)
+            .with_sampler(Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(
+                settings.otel_sample_ratio,
+            ))))
+            .with_resource(
+                opentelemetry_sdk::Resource::builder()
+                    .with_service_name(settings.otel_service_name.clone())
+                    .build(),
+            )
+            .build();
+        registry
+            .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("rusty-board")))
+            .init();
+        tracing::info!(endpoint = %endpoint, "exporting traces over OTLP");
+        return Ok(Telemetry { provider: Some(provider) });
+    }
+
+    registry.init();
+    #[cfg(not(feature = "otel"))]
+    if settings.otlp_endpoint.is_some() {
+        tracing::warn!("OTLP_ENDPOINT is set but this build lacks the `otel` feature; traces are not exported");
+    }
+    Ok(Telemetry::default())
+}
+
+/// The trace exporter installed by `init_tracing`, flushed on shutdown.
+#[derive(Default)]
+pub struct Telemetry {
+    #[cfg(feature = "otel")]
+    provider: Option<opentelemetry_sdk::trace::SdkTracerProvider>,
+}
+
+impl Telemetry {
+    /// Export any spans still buffered and stop the exporter.
+    pub async fn shutdown(self) {
+        #[cfg(feature = "otel")]
+        if let Some(provider) = self.provider {
+            // The batch exporter blocks on its own thread until the flush is done.
+            let flushed = tokio::task::spawn_blocking(move || provider.shutdown()).await;
+            if let Ok(Err(e)) = flushed {
+                tracing::warn!(error = %e, "failed to flush exported traces");
+            }
+        }
+    }
+}
+
+/// The error reporter: Sentry when `SENTRY_DSN` is set (feature: `sentry`),
+/// otherwise reports are dropped. An invalid DSN fails startup.
+pub fn build_error_reporter(settings: &Settings) -> anyhow::Result<Arc<dyn ErrorReporter>> {
+    #[cfg(feature = "sentry")]
+    if let Some(ref dsn) = settings.sentry_dsn {
+        let reporter = storage_adapters::error_reporting::SentryErrorReporter::new(
+            dsn,
+            settings.sentry_environment.clone(),
+        )?;
+        tracing::info!("reporting errors to Sentry");
+        return Ok(Arc::new(reporter));
+    }
+    #[cfg(not(feature = "sentry"))]
+    if settings.sentry_dsn.is_some() {
+        tracing::warn!("SENTRY_DSN is set but this build lacks the `sentry` feature; errors are not reported");
+    }
+    Ok(Arc::new(storage_adapters::error_reporting::NoopErrorReporter))
+}
+
+/// Load the post filters and event hooks of every plugin kind: WASM plugins
+/// first, then Lua scripts, each in file name order.
+fn build_plugins(
+    settings: &Settings,
+    events: &dyn domains::ports::EventBus,
+) -> anyhow::Result<Vec<Arc<dyn domains::ports::PostFilter>>> {
+    let mut filters = build_wasm_plugins(settings, events)?;
+    filters.extend(build_lua_filters(settings)?);
+    Ok(filters)
+}
+
+/// Load the plugins in `WASM_PLUGIN_DIR` (feature: `plugins-wasm`): event
+/// hooks are subscribed to `events`, post filters are returned in file name
+/// order. A plugin that fails to load is skipped; an unreadable directory
+/// fails startup.
+fn build_wasm_plugins(
+    settings: &Settings,
+    events: &dyn domains::ports::EventBus,
+) -> anyhow::Result<Vec<Arc<dyn domains::ports::PostFilter>>> {
+    let Some(ref dir) = settings.wasm_plugin_dir else {
+        return Ok(Vec::new());
+    };
+    #[cfg(feature = "plugins-wasm")]
+    {
+        use storage_adapters::plugins::{load_dir, WasmEventSubscriber, WasmLimits, WasmPostFilter};
+        let limits = WasmLimits {
+            fuel:         settings.wasm_plugin_fuel,
+            memory_bytes: settings.wasm_plugin_memory_mb.saturating_mul(1024 * 1024),
+        };
+        let mut filters: Vec<Arc<dyn domains::ports::PostFilter>> = Vec::new();
+        for plugin in load_dir(dir, limits)? {
+            if plugin.handles_events() {
+                events.subscribe(Arc::new(WasmEventSubscriber(plugin.clone())));
+            }
+            if plugin.filters_posts() {
+                filters.push(Arc::new(WasmPostFilter(plugin)));
+            }
+        }
+        Ok(filters)
+    }
+    #[cfg(not(feature = "plugins-wasm"))]
+    {
+        let _ = events;
+        tracing::warn!(dir = %dir.display(), "WASM_PLUGIN_DIR is set but this build lacks the `plugins-wasm` feature; no plugins loaded");
+        Ok(Vec::new())
+    }
+}
+
+/// Load the scripts in `LUA_SCRIPT_DIR` (feature: `plugins-lua`) as post
+/// filters, in file name order. A script that fails to load is skipped; an
+/// unreadable directory fails startup.
+fn build_lua_filters(settings: &Settings) -> anyhow::Result<Vec<Arc<dyn domains::ports::PostFilter>>> {
+    let Some(ref dir) = settings.lua_script_dir else {
+        return Ok(Vec::new());
+    };
+    #[cfg(feature = "plugins-lua")]
+    {
+        use storage_adapters::plugins::{load_scripts, LuaLimits, LuaPostFilter};
+        let limits = LuaLimits {
+            timeout:      std::time::Duration::from_millis(settings.lua_script_timeout_ms),
+            memory_bytes: settings.lua_script_memory_mb.saturating_mul(1024 * 1024),
+        };
+        Ok(load_scripts(dir, limits)?
+            .into_iter()
+            .map(|script| Arc::new(LuaPostFilter(script)) as Arc<dyn domains::ports::PostFilter>)
+            .collect())
+    }
+    #[cfg(not(feature = "plugins-lua"))]
+    {
+        tracing::warn!(dir = %dir.display(), "LUA_SCRIPT_DIR is set but this build lacks the `plugins-lua` feature; no scripts loaded");
+        Ok(Vec::new())
+    }
+}
+
+/// Subscribe each `[[webhooks]]` entry (feature: `webhooks`) to `events`.
+/// A malformed URL, empty secret or unknown event name fails startup.
+fn subscribe_webhooks(settings: &Settings, events: &dyn domains::ports::EventBus) -> anyhow::Result<()> {
+    if settings.webhooks.is_empty() {
+        return Ok(());
+    }
+    #[cfg(feature = "webhooks")]
+    {
+        use secrecy::ExposeSecret;
+        use storage_adapters::webhooks::{WebhookPolicy, WebhookSubscriber};
+        let policy = WebhookPolicy {
+            max_attempts: settings.webhook_max_attempts,
+            backoff:      std::time::Duration::from_secs(settings.webhook_backoff_secs),
+            timeout:      std::time::Duration::from_secs(settings.webhook_timeout_secs),
+        };
+        for hook in &settings.webhooks {
+            let webhook =
+                WebhookSubscriber::new(&hook.url, hook.secret.expose_secret().as_bytes(), &hook.events, policy)?;
+            tracing::info!(webhook = domains::ports::EventSubscriber::name(&webhook), "webhook subscribed");
+            events.subscribe(Arc::new(webhook));
+        }
+        Ok(())
+    }
+    #[cfg(not(feature = "webhooks"))]
+    {
+        let _ = events;
+        tracing::warn!(
+            count = settings.webhooks.len(),
+            "webhooks are configured but this build lacks the `webhooks` feature; none subscribed"
+        );
+        Ok(())
+    }
+}
+
+/// Subscribe the `rb-notify-discord` plugin (feature: `notify-discord`) when
+/// the config has a `[plugins.rb-notify-discord]` section. A malformed
+/// section fails startup.
+fn subscribe_notify_discord(
+    settings: &Settings,
+    events: &dyn domains::ports::EventBus,
+    boards: Arc<dyn domains::ports::BoardRepository>,
+    posts: Arc<dyn domains::ports::PostRepository>,
+    urls: &MediaUrls,
+) -> anyhow::Result<()> {
+    #[cfg(feature = "notify-discord")]
+    {
+        use storage_adapters::plugins::{notify_discord::NAME, NotifyConfig, NotifyDiscord};
+        let Some(config) = settings.plugin_config::<NotifyConfig>(NAME)? else {
+            return Ok(());
+        };
+        let plugin = NotifyDiscord::new(config, boards, posts, urls.clone())?;
+        if plugin.has_targets() {
+            tracing::info!(plugin = NAME, "new-thread announcements enabled");
+            events.subscribe(Arc::new(plugin));
+        }
+        Ok(())
+    }
+    #[cfg(not(feature = "notify-discord"))]
+    {
+        let _ = (events, boards, posts, urls);
+        if settings.plugins.contains_key("rb-notify-discord") {
+            tracing::warn!("[plugins.rb-notify-discord] is set but this build lacks the `notify-discord` feature; no announcements");
+        }
+        Ok(())
+    }
+}
+
+/// Email moderators about reports (feature: `notify-email`): subscribe a
+/// `ReportAlerts` sending through an `SmtpNotifier` to every `[email]`
+/// recipient. Returns it for the digest job when `reports = "digest"`. A
+/// malformed sender or an empty recipient list fails startup.
+fn subscribe_report_alerts<FR: domains::ports::FlagRepository>(
+    settings: &Settings,
+    events: &dyn domains::ports::EventBus,
+    flag_repo: FR,
+) -> anyhow::Result<Option<Arc<services::report_alerts::ReportAlerts<FR>>>> {
+    let Some(config) = settings.email.as_ref() else {
+        return Ok(None);
+    };
+    #[cfg(feature = "notify-email")]
+    {
+        use configs::{ReportEmails, SmtpTls};
+        use secrecy::ExposeSecret;
+        use services::report_alerts::{ReportAlertPolicy, ReportAlerts, ReportMode};
+        use storage_adapters::email::{SmtpNotifier, SmtpSecurity, SmtpSettings};
+
+        let recipients = config.recipient_list();
+        if recipients.is_empty() {
+            anyhow::bail!("[email] recipients is empty");
+        }
+        let notifier = SmtpNotifier::new(SmtpSettings {
+            host:        config.smtp_host.clone(),
+            port:        config.smtp_port,
+            security:    match config.smtp_tls {
+                SmtpTls::Starttls => SmtpSecurity::StartTls,
+                SmtpTls::Tls => SmtpSecurity::Tls,
+                SmtpTls::None => SmtpSecurity::None,
+            },
+            credentials: config.smtp_username.clone().map(|username| {
+                let password = config.smtp_password.as_ref().map(|p| p.expose_secret().to_owned());
+                (username, password.unwrap_or_default())
+            }),
+            from:        config.from.clone(),
+            site_url:    config.site_url.clone(),
+            timeout:     Duration::from_secs(config.timeout_secs),
+        })?;
+        let mode = match config.reports {
+            ReportEmails::Each => ReportMode::Each,
+            ReportEmails::Digest => ReportMode::Digest,
+            ReportEmails::None => ReportMode::Off,
+        };
+        let policy = ReportAlertPolicy { mode, threshold: config.report_threshold };
+        let alerts = Arc::new(ReportAlerts::new(Arc::new(notifier), flag_repo, recipients, policy));
+        events.subscribe(alerts.clone());
+        tracing::info!(smtp = %config.smtp_host, reports = ?config.reports, "report emails enabled");
+        Ok((mode == ReportMode::Digest).then_some(alerts))
+    }
+    #[cfg(not(feature = "notify-email"))]
+    {
+        let _ = (config, events, flag_repo);
+        tracing::warn!("[email] is set but this build lacks the `notify-email` feature; no report emails sent");
+        Ok(None)
+    }
+}
+
+/// Join the `[matrix]` rooms and start relaying between them and their
+/// threads (feature: `matrix-bridge`): a `MatrixRelay` subscriber for posts,
+/// and a spawned `MatrixBridge` for room messages, stopped by `stop`. A bad
+/// thread ID or a room the bridge cannot join fails startup.
+async fn start_matrix_bridge<PR, TR, BR, MS, RL, MP>(
+    settings: &Settings,
+    events: &dyn domains::ports::EventBus,
+    posts: Arc<PostService<PR, TR, BR, MS, RL, MP>>,
+    boards: Arc<dyn domains::ports::BoardRepository>,
+    threads: Arc<dyn domains::ports::ThreadRepository>,
+    stop: CancellationToken,
+) -> anyhow::Result<Option<tokio::task::JoinHandle<()>>>
+where
+    PR: domains::ports::PostRepository + 'static,
+    TR: domains::ports::ThreadRepository + 'static,
+    BR: domains::ports::BanRepository + 'static,
+    MS: domains::ports::MediaStorage + 'static,
+    RL: domains::ports::RateLimiter + 'static,
+    MP: domains::ports::MediaProcessor + 'static,
+{
+    let Some(config) = settings.matrix.as_ref() else {
+        return Ok(None);
+    };
+    #[cfg(feature = "matrix-bridge")]
+    {
+        use std::collections::HashMap;
+        use domains::models::ThreadId;
+        use secrecy::ExposeSecret;
+        use storage_adapters::matrix::{MatrixClient, MatrixRelay};
+        use crate::matrix_bridge::{BridgedThread, MatrixBridge};
+
+        let client = MatrixClient::new(&config.homeserver, config.access_token.expose_secret())?;
+        let user_id = client.whoami().await.context("matrix access token rejected")?;
+        let mut bridged = HashMap::new();
+        let mut rooms: HashMap<ThreadId, Vec<String>> = HashMap::new();
+        for entry in &config.rooms {
+            let thread_id = entry
+                .thread_id
+                .parse()
+                .map(ThreadId)
+                .with_context(|| format!("matrix room {}: invalid thread_id", entry.room))?;
+            let thread = threads
+                .find_by_id(thread_id)
+                .await
+                .with_context(|| format!("matrix room {}: thread {}", entry.room, entry.thread_id))?;
+            let room_id =
+                client.join(&entry.room).await.with_context(|| format!("joining matrix room {}", entry.room))?;
+            bridged.insert(room_id.clone(), BridgedThread { board_id: thread.board_id, thread_id });
+            rooms.entry(thread_id).or_default().push(room_id);
+        }
+        if bridged.is_empty() {
+            tracing::warn!("[matrix] has no rooms; nothing bridged");
+            return Ok(None);
+        }
+        tracing::info!(user = %user_id, rooms = bridged.len(), "matrix bridge started");
+        events.subscribe(Arc::new(MatrixRelay::new(client.clone(), rooms)));
+        let bridge = MatrixBridge {
+            client,
+            user_id,
+            threads: bridged,
+            posts,
+            boards,
+            sync_timeout: Duration::from_secs(config.sync_timeout_secs),
+        };
+        Ok(Some(tokio::spawn(bridge.run(stop))))
+    }
+    #[cfg(not(feature = "matrix-bridge"))]
+    {
+        let _ = (config, events, posts, boards, threads, stop);
+        tracing::warn!("[matrix] is set but this build lacks the `matrix-bridge` feature; no rooms bridged");
+        Ok(None)
+    }
+}
+
+/// Serve the boards of the `[nntp] domain` site as read-only newsgroups
+/// (feature: `nntp`) on `[nntp] listen`, until `stop`. A listen address that
+/// cannot be bound fails startup.
+async fn start_nntp(
+    settings: &Settings,
+    sites: Arc<dyn domains::ports::SiteRepository>,
+    boards: Arc<dyn domains::ports::BoardRepository>,
+    threads: Arc<dyn domains::ports::ThreadRepository>,
+    posts: Arc<dyn domains::ports::PostRepository>,
+    stop: CancellationToken,
+) -> anyhow::Result<Option<tokio::task::JoinHandle<()>>> {
+    let Some(config) = settings.nntp.as_ref() else {
+        return Ok(None);
+    };
+    #[cfg(feature = "nntp")]
+    {
+        use rb_nntp::{NntpServer, Options, Repositories};
+
+        let site = sites.find_by_host(&config.domain).await?;
+        let site_id = site.map_or(domains::models::SiteId::DEFAULT, |site| site.id);
+        let listener = tokio::net::TcpListener::bind(&config.listen)
+            .await
+            .with_context(|| format!("binding NNTP listener on {}", config.listen))?;
+        let options = Options {
+            domain:          config.domain.clone(),
+            group_prefix:    config.group_prefix.clone(),
+            max_connections: config.max_connections,
+        };
+        let server = Arc::new(NntpServer::new(site_id, Repositories { boards, threads, posts }, options));
+        tracing::info!(listen = %config.listen, domain = %config.domain, "NNTP gateway started");
+        Ok(Some(tokio::spawn(server.run(listener, stop))))
+    }
+    #[cfg(not(feature = "nntp"))]
+    {
+        let _ = (config, sites, boards, threads, posts, stop);
+        tracing::warn!("[nntp] is set but this build lacks the `nntp` feature; no newsgroups served");
+        Ok(None)
+    }
+}
+
+/// Federate the boards with `federation_enabled` over ActivityPub (feature:
+/// `federation-activitypub`): subscribe an `ActivityPubFederationSync` to
+/// post events and return its routes. The boards are those of the site
+/// `[federation] base_url` is served from. Without `[federation]`, an empty
+/// router.
+#[cfg(feature = "web-axum")]
+#[allow(clippy::too_many_arguments)]
+async fn build_federation<PR, TR, BR, MS, RL, MP>(
+    settings: &Settings,
+    events: &dyn domains::ports::EventBus,
+    posts: Arc<PostService<PR, TR, BR, MS, RL, MP>>,
+    sites: Arc<dyn domains::ports::SiteRepository>,
+    boards: Arc<dyn domains::ports::BoardRepository>,
+    threads: Arc<dyn domains::ports::ThreadRepository>,
+    post_repo: Arc<dyn domains::ports::PostRepository>,
+    followers: Arc<dyn domains::ports::FollowerRepository>,
+) -> anyhow::Result<Router>
+where
+    PR: domains::ports::PostRepository + 'static,
+    TR: domains::ports::ThreadRepository + 'static,
+    BR: domains::ports::BanRepository + 'static,
+    MS: domains::ports::MediaStorage + 'static,
+    RL: domains::ports::RateLimiter + 'static,
+    MP: domains::ports::MediaProcessor + 'static,
+{
+    let Some(config) = settings.federation.as_ref() else {
+        return Ok(Router::new());
+    };
+    #[cfg(feature = "federation-activitypub")]
+    {
+        use rb_federation::{ActivityPubFederationSync, InstanceKey, Repositories, Urls};
+
+        let urls = Urls::new(&config.base_url)?;
+        let site = sites.find_by_host(urls.host()).await?;
+        let site_id = site.map_or(domains::models::SiteId::DEFAULT, |site| site.id);
+        let key = InstanceKey::load_or_create(&config.key_path)?;
+        let repos = Repositories { boards, threads, posts: post_repo, followers };
+        let federation = Arc::new(ActivityPubFederationSync::new(urls, site_id, key, repos, posts)?);
+        events.subscribe(federation.clone());
+        tracing::info!(base_url = %config.base_url, "ActivityPub federation enabled");
+        Ok(rb_federation::routes::routes(federation))
+    }
+    #[cfg(not(feature = "federation-activitypub"))]
+    {
+        let _ = (config, events, posts, sites, boards, threads, post_repo, followers);
+        tracing::warn!("[federation] is set but this build lacks the `federation-activitypub` feature; no boards federated");
+        Ok(Router::new())
+    }
+}
+
+/// Log staff in through an OpenID Connect provider (feature: `auth-oidc`)
+/// when the config has a `[plugins.rb-auth-oidc]` section: its routes, and
+/// the button it adds to the login page. A malformed section fails startup.
+#[cfg(feature = "web-axum")]
+fn build_oidc<UR, AP>(
+    settings: &Settings,
+    users: Arc<UserService<UR, AP>>,
+) -> anyhow::Result<(Router, Option<api_adapters::axum::handlers::auth_handlers::SsoLogin>)>
+where
+    UR: domains::ports::UserRepository + 'static,
+    AP: domains::ports::AuthProvider + 'static,
+{
+    #[cfg(feature = "auth-oidc")]
+    {
+        use api_adapters::axum::handlers::auth_handlers::SsoLogin;
+        use rb_auth_oidc::{OidcConfig, OidcLogin, NAME};
+
+        let Some(config) = settings.plugin_config::<OidcConfig>(NAME)? else {
+            return Ok((Router::new(), None));
+        };
+        let oidc = Arc::new(OidcLogin::new(config, users)?);
+        let sso = SsoLogin { label: oidc.label().to_owned(), href: "/auth/oidc/login".to_owned() };
+        tracing::info!(plugin = NAME, "SSO staff login enabled");
+        Ok((rb_auth_oidc::routes::routes(oidc), Some(sso)))
+    }
+    #[cfg(not(feature = "auth-oidc"))]
+    {
+        let _ = users;
+        if settings.plugins.contains_key("rb-auth-oidc") {
+            tracing::warn!("[plugins.rb-auth-oidc] is set but this build lacks the `auth-oidc` feature; no SSO login");
+        }
+        Ok((Router::new(), None))
+    }
+}
+
 /// Compose all adapters and services, and return the configured router.
 ///
 /// This is called once from `main.rs`. The returned `Router` is ready to serve requests.
@@ -136,7 +1127,10 @@ type AppModerationService = ModerationService<
 /// # Panics
 /// Panics on startup misconfiguration (missing env vars, unreachable DB, etc.).
 /// This is intentional — a misconfigured application must not start silently.
-pub async fn compose(settings: &Settings) -> anyhow::Result<Router> {
+pub async fn compose(settings: &Settings) -> anyhow::Result<(Router, Background)> {
+    // ── Error reporter ────────────────────────────────────────────────────────
+    let error_reporter = build_error_reporter(settings)?;
+
     // ── Database pool ─────────────────────────────────────────────────────────
     #[cfg(feature = "db-postgres")]
     let pool = {
@@ -152,12 +1146,7 @@ pub async fn compose(settings: &Settings) -> anyhow::Result<Router> {
 
     // Run migrations
     #[cfg(feature = "db-postgres")]
-    {
-        sqlx::migrate!("../../crates/storage-adapters/src/migrations")
-            .run(&pool)
-            .await
-            .context("failed to run database migrations")?;
-    }
+    migrate(&pool).await?;
 
     // ── Repositories (Postgres) ───────────────────────────────────────────────
     #[cfg(feature = "db-postgres")]
@@ -179,50 +1168,44 @@ pub async fn compose(settings: &Settings) -> anyhow::Result<Router> {
     };
 
     // ── Media storage ─────────────────────────────────────────────────────────
-    #[cfg(feature = "media-local")]
-    let media_storage = LocalFsMediaStorage::new(
-        settings.media_path.clone(),
-        settings.media_url_base.clone(),
+    // Once a CDN or URL signing is configured, get_url answers with those URLs
+    // instead of the backend's own.
+    let media_urls = build_media_urls(settings);
+    let (media_backend, storage) = media_backends().build(settings.media_backend.as_deref(), settings).await?;
+    let media_storage = CdnMediaStorage::new(
+        storage,
+        (settings.media_cdn_url.is_some() || media_urls.is_signed()).then(|| media_urls.clone()),
     );
 
-    #[cfg(feature = "media-s3")]
-    let media_storage = {
-        let s3_cfg = &settings.s3;
-        use secrecy::ExposeSecret;
-        let aws_config = aws_config::defaults(aws_config::BehaviorVersion::latest())
-            .region(aws_sdk_s3::config::Region::new(s3_cfg.region.clone()))
-            .credentials_provider(aws_sdk_s3::config::Credentials::new(
-                s3_cfg.access_key.expose_secret(),
-                s3_cfg.secret_key.expose_secret(),
-                None,
-                None,
-                "static",
-            ))
-            .load()
-            .await;
-        let client = if let Some(endpoint) = &s3_cfg.endpoint {
-            aws_sdk_s3::Client::from_conf(
-                aws_sdk_s3::config::Builder::from(&aws_config)
-                    .endpoint_url(endpoint)
-                    .build(),
-            )
-        } else {
-            aws_sdk_s3::Client::new(&aws_config)
-        };
-        S3MediaStorage::new(client, s3_cfg.bucket.clone(), s3_cfg.endpoint.clone())
-    };
-
     // ── Media processor ───────────────────────────────────────────────────────
-    let media_processor = ImageMediaProcessor::new();
+    // Image thumbnails are rendered by background workers writing to the same storage.
+    let thumbnail_queue = ThumbnailQueue::start(
+        Arc::new(media_storage.clone()),
+        settings.thumbnail_workers,
+        settings.thumbnail_queue_capacity,
+    );
+    let media_processor = build_media_processor(settings, Some(thumbnail_queue.clone()));
 
-    // ── Rate limiter ──────────────────────────────────────────────────────────
-    #[cfg(feature = "redis")]
-    let rate_limiter = {
-        use secrecy::ExposeSecret;
-        let redis_pool = create_redis_pool(settings.redis_url.expose_secret())
-            .context("failed to create Redis pool")?;
-        RedisRateLimiter::new(redis_pool, 3) // default 3 posts/window; overridden per-board by BoardConfig
-    };
+    // ── Rate limiter + shared cache store ─────────────────────────────────────
+    let (cache_backend, cache) = cache_backends().build(settings.cache_backend.as_deref(), settings).await?;
+    tracing::info!(media = media_backend, cache = cache_backend, "storage backends selected");
+
+    // ── Event bus ─────────────────────────────────────────────────────────────
+    // Services publish post, thread, ban, flag and upload events here;
+    // plugins and webhooks subscribe to it instead of being called from
+    // handlers.
+    let event_bus: Arc<dyn domains::ports::EventBus> =
+        Arc::new(storage_adapters::in_memory::InMemoryEventBus::new(settings.event_bus_capacity));
+    let post_filters = build_plugins(settings, event_bus.as_ref())?;
+    subscribe_webhooks(settings, event_bus.as_ref())?;
+    subscribe_notify_discord(
+        settings,
+        event_bus.as_ref(),
+        Arc::new(board_repo.clone()),
+        Arc::new(post_repo.clone()),
+        &media_urls,
+    )?;
+    let report_digest = subscribe_report_alerts(settings, event_bus.as_ref(), flag_repo.clone())?;
 
     // ── Auth provider ─────────────────────────────────────────────────────────
     #[cfg(feature = "auth-jwt")]
@@ -252,38 +1235,171 @@ pub async fn compose(settings: &Settings) -> anyhow::Result<Router> {
     // ── BoardConfig cache ─────────────────────────────────────────────────────
     let board_config_cache = Arc::new(BoardConfigCache::new(Duration::from_secs(settings.config_cache_ttl_secs)));
 
+    // ── Site resolution ───────────────────────────────────────────────────────
+    // Same TTL as board configs; both are cleared together on reload.
+    let site_cache = Arc::new(SiteCache::new(Duration::from_secs(settings.config_cache_ttl_secs)));
+    #[cfg(feature = "db-postgres")]
+    let site_repo: Arc<dyn domains::ports::SiteRepository> = Arc::new(PgSiteRepository::new(pool.clone()));
+
     // ── Shared archive repository ─────────────────────────────────────────────
     #[cfg(feature = "db-postgres")]
     let archive_svc = std::sync::Arc::new(
         storage_adapters::postgres::repositories::archive_repository::PgArchiveRepository::new(pool.clone())
     );
+    // Archiving through this also writes the thread's static page to media
+    // storage, and staff edits to an archived thread write it again.
+    #[cfg(feature = "db-postgres")]
+    let static_archive = Arc::new(
+        api_adapters::axum::static_archive::StaticArchiveRepository::new(
+            archive_svc.clone(),
+            Arc::new(board_repo.clone()),
+            Arc::new(thread_repo.clone()),
+            Arc::new(post_repo.clone()),
+            Arc::new(media_storage.clone()),
+        )
+    );
+    #[cfg(feature = "db-postgres")]
+    event_bus.subscribe(static_archive.clone());
+    #[cfg(feature = "db-postgres")]
+    let static_archive: Arc<dyn domains::ports::ArchiveRepository> = static_archive;
+
+    // ── Shared ASN ban repository ─────────────────────────────────────────────
+    #[cfg(feature = "db-postgres")]
+    let asn_ban_repo: std::sync::Arc<dyn domains::ports::AsnBanRepository> =
+        std::sync::Arc::new(PgAsnBanRepository::new(pool.clone()));
+
+    // ── Shared upload hash ban repository ─────────────────────────────────────
+    #[cfg(feature = "db-postgres")]
+    let hash_ban_repo: std::sync::Arc<dyn domains::ports::HashBanRepository> =
+        std::sync::Arc::new(PgHashBanRepository::new(pool.clone()));
+
+    // ── Moderator warnings (issued by ModerationService, checked by PostService)
+    #[cfg(feature = "db-postgres")]
+    let warning_repo: std::sync::Arc<dyn domains::ports::WarningRepository> =
+        std::sync::Arc::new(PgWarningRepository::new(pool.clone()));
+
+    // ── Thread polls (written by PostService, read by ThreadService) ──────────
+    #[cfg(feature = "db-postgres")]
+    let poll_repo = PgPollRepository::new(pool.clone());
+
+    // ── Post reactions (written by ReactionService, read by ThreadService) ────
+    #[cfg(feature = "db-postgres")]
+    let reaction_repo = PgReactionRepository::new(pool.clone());
+
+    // ── Thread watch lists (written by WatchService, marked read by ThreadService)
+    #[cfg(feature = "db-postgres")]
+    let watch_repo = PgWatchRepository::new(pool.clone());
+
+    // ── Blotter entries (written by AnnouncementService, read by ThreadService)
+    #[cfg(feature = "db-postgres")]
+    let announcement_repo = PgAnnouncementRepository::new(pool.clone());
+
+    // ── Board statistics (written by the rollup job, read by the stats pages) ─
+    #[cfg(feature = "db-postgres")]
+    let stats_repo = PgStatsRepository::new(pool.clone());
 
     // ── Services ──────────────────────────────────────────────────────────────
-    let board_service = BoardService::new(board_repo.clone());
+    let board_service = BoardService::new(board_repo.clone())
+        .with_audit(Arc::new(audit_repo.clone()));
     let thread_service = {
         let svc = ThreadService::new(thread_repo.clone(), post_repo.clone());
         // Attach archive store — threads are archived instead of deleted when
         // board_config.archive_enabled = true and the archive store is wired.
-        let svc = svc.with_archive(archive_svc.clone() as std::sync::Arc<dyn domains::ports::ArchiveRepository>);
-        svc
+        svc.with_archive(static_archive.clone())
+            .with_polls(Arc::new(poll_repo.clone()))
+            .with_reactions(Arc::new(reaction_repo.clone()))
+            .with_watches(Arc::new(watch_repo.clone()))
+            .with_announcements(Arc::new(announcement_repo.clone()))
     };
+    let watch_service = services::watch::WatchService::new(watch_repo, thread_repo.clone());
+    let announcement_service = services::announcement::AnnouncementService::new(announcement_repo);
+    let page_service = services::page::PageService::new(PgStaticPageRepository::new(pool.clone()));
+    let stats_service = StatsService::new(stats_repo.clone());
+    let banner_service = services::banner::BannerService::new(
+        PgBannerRepository::new(pool.clone()),
+        media_storage.clone(),
+    );
+    let reaction_service = services::reaction::ReactionService::new(
+        reaction_repo,
+        post_repo.clone(),
+        thread_repo.clone(),
+        cache.rate_limiter.clone(),
+    );
     let post_service = {
         let svc = PostService::new(
             post_repo.clone(),
             thread_repo.clone(),
             ban_repo.clone(),
-            media_storage,
-            rate_limiter,
+            media_storage.clone(),
+            cache.rate_limiter,
             media_processor,
-            settings.tripcode_pepper.clone().unwrap_or_default(),
+            tripcode_pepper(settings, &pool).await?,
         );
-        // Attach DNSBL checker when the spam-dnsbl feature is enabled.
+        // Attach the IP reputation checker (DNSBL zones + Tor exit list, behind a
+        // TTL cache) when the spam-dnsbl feature is enabled. Boards decide what a
+        // listing means via BoardConfig.dnsbl_action.
         #[cfg(feature = "spam-dnsbl")]
-        let svc = svc.with_dnsbl(std::sync::Arc::new(
-            storage_adapters::dnsbl::SpamhausDnsblChecker::new(),
-        ));
-        let svc = svc.with_archive_repo(archive_svc.clone() as std::sync::Arc<dyn domains::ports::ArchiveRepository>);
-        svc
+        let svc = {
+            use storage_adapters::dnsbl::{CachedDnsblChecker, CompositeDnsblChecker, TorExitListChecker};
+            let ttl = std::time::Duration::from_secs(settings.dnsbl_cache_ttl_secs);
+            let mut reputation = CompositeDnsblChecker::new().with_zones(settings.dnsbl_zone_list());
+            if let Some(ref path) = settings.tor_exit_list_path {
+                reputation = reputation.with(std::sync::Arc::new(TorExitListChecker::new(path.clone(), ttl)));
+            }
+            if reputation.is_empty() {
+                svc
+            } else {
+                tracing::info!(sources = reputation.len(), "IP reputation checks enabled");
+                svc.with_dnsbl(std::sync::Arc::new(CachedDnsblChecker::new(std::sync::Arc::new(reputation), ttl)))
+            }
+        };
+        // Enforce ASN bans when the geoip-asn feature is enabled and an ASN
+        // database is configured. A database that fails to load disables
+        // enforcement rather than aborting startup.
+        #[cfg(feature = "geoip-asn")]
+        let svc = match settings.asn_db_path {
+            Some(ref path) => match storage_adapters::geoip::MaxmindAsnResolver::open(path) {
+                Ok(resolver) => {
+                    tracing::info!(path = %path.display(), "ASN ban enforcement enabled");
+                    svc.with_asn_bans(std::sync::Arc::new(resolver), asn_ban_repo.clone())
+                }
+                Err(e) => {
+                    tracing::error!(error = %e, "ASN database failed to load; ASN bans not enforced");
+                    svc
+                }
+            },
+            None => svc,
+        };
+        // Classify uploads for boards with a classifier_action. Without the
+        // nsfw-onnx feature (or a configured model) no labels are produced.
+        let classifier: std::sync::Arc<dyn domains::ports::MediaClassifier> = {
+            #[cfg(feature = "nsfw-onnx")]
+            {
+                match settings.nsfw_model_path {
+                    Some(ref model) => {
+                        tracing::info!(model = %model.display(), "NSFW classification enabled");
+                        std::sync::Arc::new(storage_adapters::classifier::OnnxNsfwClassifier::new(
+                            &settings.nsfw_classifier_cmd,
+                            model,
+                        ))
+                    }
+                    None => std::sync::Arc::new(storage_adapters::classifier::NoopMediaClassifier),
+                }
+            }
+            #[cfg(not(feature = "nsfw-onnx"))]
+            {
+                std::sync::Arc::new(storage_adapters::classifier::NoopMediaClassifier)
+            }
+        };
+        let svc = svc.with_classifier(classifier, std::sync::Arc::new(flag_repo.clone()));
+        let svc = svc.with_hash_bans(hash_ban_repo.clone(), settings.hash_ban_max_distance);
+        // Boards with media_quota_mb > 0 reject (or prune for) uploads past it.
+        let svc = svc.with_media_quotas(std::sync::Arc::new(board_repo.clone()));
+        svc.with_archive_repo(static_archive.clone())
+            .with_events(event_bus.clone())
+            .with_filters(post_filters)
+            .with_polls(Arc::new(poll_repo.clone()))
+            .with_warnings(warning_repo.clone())
     };
     let moderation_service = ModerationService::new(
         ban_repo.clone(),
@@ -292,29 +1408,152 @@ pub async fn compose(settings: &Settings) -> anyhow::Result<Router> {
         flag_repo.clone(),
         audit_repo.clone(),
         user_repo.clone(),
-    );
+    )
+    .with_asn_bans(asn_ban_repo.clone())
+    .with_hash_bans(hash_ban_repo.clone())
+    .with_warnings(warning_repo.clone())
+    // Deleting a post purges its files unless another post shares them.
+    .with_media_storage(Arc::new(media_storage.clone()))
+    .with_events(event_bus.clone());
     let user_service = UserService::new(
         user_repo.clone(),
         auth_provider.clone(),
         settings.jwt_ttl_secs,
-    );
+    )
+    .with_totp(Arc::new(PgTotpRepository::new(pool.clone())))
+    .with_external_identities(Arc::new(PgExternalIdentityRepository::new(pool.clone())));
+    // Shared with the SSO plugin, which logs its staff in through it.
+    let user_service = Arc::new(user_service);
 
     // ── Prometheus metrics registry ───────────────────────────────────────────
     let mut metrics_registry = prometheus_client::registry::Registry::default();
-    let _app_metrics = api_adapters::axum::metrics::AppMetrics::new(&mut metrics_registry);
+    let app_metrics = api_adapters::axum::metrics::AppMetrics::new(&mut metrics_registry);
     let metrics_registry = Arc::new(metrics_registry);
 
+    // ── Scheduled jobs ────────────────────────────────────────────────────────
+    let stop = CancellationToken::new();
+    #[cfg(feature = "db-postgres")]
+    let jobs = {
+        use crate::jobs::{
+            BoardStats, ExpireBans, ExpireSessions, ExpireStaffMessages, MediaGc, PruneThreads, PurgeDeletedPosts,
+            RollupStats, SendReportDigest,
+        };
+        let secs = Duration::from_secs;
+        let expire = secs(settings.expire_interval_secs);
+        let scheduler = crate::scheduler::Scheduler::new()
+            .with_error_reporter(error_reporter.clone())
+            .every(secs(settings.media_gc_interval_secs), MediaGc {
+                gc:    MediaGcService::new(post_repo.clone(), media_storage.clone()),
+                grace: secs(settings.media_gc_grace_secs),
+            })
+            .every(secs(settings.prune_interval_secs), PruneThreads {
+                boards:  BoardService::new(board_repo.clone()),
+                threads: ThreadService::new(thread_repo.clone(), post_repo.clone())
+                    .with_archive(static_archive.clone()),
+                metrics: app_metrics.clone(),
+            })
+            // BAN_RETENTION_DAYS=0 keeps expired bans forever.
+            .every(if settings.ban_retention_days > 0 { expire } else { Duration::ZERO }, ExpireBans {
+                moderation:     ModerationService::new(
+                    ban_repo.clone(),
+                    post_repo.clone(),
+                    thread_repo.clone(),
+                    flag_repo.clone(),
+                    audit_repo.clone(),
+                    user_repo.clone(),
+                ),
+                retention_days: settings.ban_retention_days,
+            })
+            // DELETED_POST_RETENTION_DAYS=0 keeps tombstones forever.
+            .every(
+                if settings.deleted_post_retention_days > 0 { expire } else { Duration::ZERO },
+                PurgeDeletedPosts {
+                    moderation:     ModerationService::new(
+                        ban_repo.clone(),
+                        post_repo.clone(),
+                        thread_repo.clone(),
+                        flag_repo.clone(),
+                        audit_repo.clone(),
+                        user_repo.clone(),
+                    ),
+                    retention_days: settings.deleted_post_retention_days,
+                },
+            )
+            .every(expire, ExpireSessions(session_repo.clone()))
+            .every(expire, ExpireStaffMessages(services::staff_message::StaffMessageService::new(
+                staff_message_repo.clone(),
+            )))
+            .every(
+                secs(settings.stats_interval_secs),
+                BoardStats::new(BoardService::new(board_repo.clone()), thread_repo.clone(), app_metrics),
+            )
+            .every(
+                secs(settings.stats_rollup_interval_secs),
+                RollupStats::new(StatsService::new(stats_repo.clone())),
+            );
+        let scheduler = match (report_digest, settings.email.as_ref()) {
+            (Some(alerts), Some(email)) => scheduler.every(secs(email.digest_interval_secs), SendReportDigest(alerts)),
+            _ => scheduler,
+        };
+        tracing::info!(jobs = scheduler.len(), "scheduled jobs started");
+        scheduler.start(stop.clone())
+    };
+    #[cfg(not(feature = "db-postgres"))]
+    let jobs = Vec::new();
+
+    // ── Matrix bridge ─────────────────────────────────────────────────────────
+    // Shares the post service with the router; its sync loop is stopped with
+    // the scheduled jobs.
+    let post_service = Arc::new(post_service);
+    let mut jobs = jobs;
+    jobs.extend(
+        start_matrix_bridge(
+            settings,
+            event_bus.as_ref(),
+            post_service.clone(),
+            Arc::new(board_repo.clone()),
+            Arc::new(thread_repo.clone()),
+            stop.clone(),
+        )
+        .await?,
+    );
+
+    // ── NNTP gateway ──────────────────────────────────────────────────────────
+    jobs.extend(
+        start_nntp(
+            settings,
+            site_repo.clone(),
+            Arc::new(board_repo.clone()),
+            Arc::new(thread_repo.clone()),
+            Arc::new(post_repo.clone()),
+            stop.clone(),
+        )
+        .await?,
+    );
+
+    // ── ActivityPub federation ────────────────────────────────────────────────
+    #[cfg(feature = "web-axum")]
+    let federation_routes = build_federation(
+        settings,
+        event_bus.as_ref(),
+        post_service.clone(),
+        site_repo.clone(),
+        Arc::new(board_repo.clone()),
+        Arc::new(thread_repo.clone()),
+        Arc::new(post_repo.clone()),
+        Arc::new(PgFollowerRepository::new(pool.clone())),
+    )
+    .await?;
+
+    // ── SSO login ─────────────────────────────────────────────────────────────
+    #[cfg(feature = "web-axum")]
+    let (oidc_routes, sso_login) = build_oidc(settings, user_service.clone())?;
+
     // ── Health state (DB + Redis probes) ─────────────────────────────────────
-    #[cfg(all(feature = "db-postgres", feature = "redis"))]
-    let health_state = {
-        use api_adapters::axum::health::HealthState;
-        use secrecy::ExposeSecret;
-        HealthState {
-            db:    Arc::new(PgProbe(pool.clone())),
-            redis: Arc::new(RedisProbeImpl(create_redis_pool(
-                settings.redis_url.expose_secret(),
-            ).context("health: failed to create redis pool")?)),
-        }
+    #[cfg(feature = "db-postgres")]
+    let health_state = api_adapters::axum::health::HealthState {
+        db:    Arc::new(PgProbe(pool.clone())),
+        redis: cache.probe,
     };
 
     // ── StaffRequestService — now backed by PgStaffRequestRepository ──────────
@@ -328,6 +1567,23 @@ pub async fn compose(settings: &Settings) -> anyhow::Result<Router> {
         staff_message_repo,
     );
 
+    // ── Client addresses behind reverse proxies ───────────────────────────────
+    #[cfg(feature = "web-axum")]
+    let trusted_proxies = api_adapters::axum::middleware::client_ip::TrustedProxies::parse(
+        &settings.trusted_proxies,
+        settings.trust_cf_connecting_ip,
+    )
+    .map_err(|entry| anyhow::anyhow!("TRUSTED_PROXIES: {entry:?} is not an IP address or CIDR range"))?;
+
+    // ── Request body and multipart limits ─────────────────────────────────────
+    #[cfg(feature = "web-axum")]
+    let multipart_limits = api_adapters::axum::multipart::MultipartLimits::from_kb(
+        settings.max_body_kb,
+        settings.max_upload_kb,
+        settings.max_text_field_kb,
+        settings.max_form_fields,
+    );
+
     // ── Build router ──────────────────────────────────────────────────────────
     #[cfg(feature = "web-axum")]
     let router = build_axum_router(
@@ -339,15 +1595,62 @@ pub async fn compose(settings: &Settings) -> anyhow::Result<Router> {
         user_service,
         staff_request_svc,
         staff_message_svc,
-        board_config_cache,
+        services::poll::PollService::new(poll_repo),
+        reaction_service,
+        watch_service,
+        banner_service,
+        announcement_service,
+        page_service,
+        stats_service,
+        services::api_key::ApiKeyService::new(PgApiKeyRepository::new(pool.clone())),
+        board_config_cache.clone(),
+        cache.store,
+        Duration::from_secs(settings.config_cache_ttl_secs),
+        Duration::from_secs(settings.render_cache_ttl_secs),
+        api_adapters::axum::middleware::site::SiteState { repo: site_repo, cache: site_cache.clone() },
         Arc::new(auth_provider),
         metrics_registry,
         health_state,
         settings.open_registration,
         archive_svc,
+        Arc::new(media_storage.clone()),
+        media_urls,
+        local_media_root(settings, media_backend),
+        federation_routes.merge(oidc_routes),
+        sso_login,
+        error_reporter.clone(),
+        warning_repo,
+        settings.compression,
+        settings.compression_min_bytes,
+        trusted_proxies,
+        Arc::new(PgLastModifiedRepository::new(pool.clone())),
+        multipart_limits,
     );
 
-    Ok(router)
+    let background = Background {
+        board_configs: board_config_cache,
+        sites: site_cache,
+        thumbnails: thumbnail_queue,
+        jobs,
+        stop,
+        error_reporter,
+        #[cfg(feature = "db-postgres")]
+        pool,
+    };
+    Ok((router, background))
+}
+
+/// Directory served at `/media`: the local storage root, or `None` when media
+/// lives elsewhere (`backend` is not `local`) and is reached through
+/// `MediaStorage::get_url`.
+#[cfg(feature = "web-axum")]
+fn local_media_root(settings: &Settings, backend: &str) -> Option<std::path::PathBuf> {
+    #[cfg(feature = "media-local")]
+    if backend == "local" {
+        return Some(settings.media_path.clone());
+    }
+    let _ = (settings, backend);
+    None
 }
 
 /// Build the Axum router with all routes and middleware.
@@ -361,21 +1664,45 @@ pub async fn compose(settings: &Settings) -> anyhow::Result<Router> {
 // introducing a new struct that pushes the complexity elsewhere. This function
 // is called exactly once at startup.
 #[allow(clippy::too_many_arguments)]
-fn build_axum_router<BS, PR, TR, BR, MS, RL, MP, FR, AR, UR, AP, RR, MR>(
+fn build_axum_router<BS, PR, TR, BR, MS, RL, MP, FR, AR, UR, AP, RR, MR, PL, RX, WR, BN, AN, SP, ST, AK>(
     board_service:         BS,
-    post_service:          PostService<PR, TR, BR, MS, RL, MP>,
+    post_svc:              Arc<PostService<PR, TR, BR, MS, RL, MP>>,
     post_repo:             PR,
     thread_service:        services::thread::ThreadService<TR, PR>,
     moderation_service:    ModerationService<BR, PR, TR, FR, AR, UR>,
-    user_service:          UserService<UR, AP>,
+    user_service:          Arc<UserService<UR, AP>>,
     staff_request_service: services::staff_request::StaffRequestService<RR, UR>,
     staff_message_service: services::staff_message::StaffMessageService<MR>,
+    poll_service:          services::poll::PollService<PL>,
+    reaction_service:      services::reaction::ReactionService<RX, PR, TR, RL>,
+    watch_service:         services::watch::WatchService<WR, TR>,
+    banner_service:        services::banner::BannerService<BN, MS>,
+    announcement_service:  services::announcement::AnnouncementService<AN>,
+    page_service:          services::page::PageService<SP>,
+    stats_service:         StatsService<ST>,
+    api_key_service:       services::api_key::ApiKeyService<AK>,
     board_config_cache:    Arc<BoardConfigCache>,
+    cache_store:           Arc<dyn domains::ports::CacheStore>,
+    config_cache_ttl:      Duration,
+    render_cache_ttl:      Duration,
+    site_state:            api_adapters::axum::middleware::site::SiteState,
     auth_provider:         Arc<dyn domains::ports::AuthProvider>,
     metrics_registry:      Arc<prometheus_client::registry::Registry>,
     health_state:          api_adapters::axum::health::HealthState,
     open_registration:     bool,
     archive_svc:           Arc<storage_adapters::postgres::repositories::archive_repository::PgArchiveRepository>,
+    media_storage:         Arc<dyn domains::ports::MediaStorage>,
+    media_urls:            storage_adapters::media::cdn::MediaUrls,
+    media_root:            Option<std::path::PathBuf>,
+    plugin_routes:         Router,
+    sso_login:             Option<api_adapters::axum::handlers::auth_handlers::SsoLogin>,
+    error_reporter:        Arc<dyn ErrorReporter>,
+    warnings:              Arc<dyn domains::ports::WarningRepository>,
+    compression:           configs::Compression,
+    compression_min_bytes: u16,
+    trusted_proxies:       api_adapters::axum::middleware::client_ip::TrustedProxies,
+    last_modified:         Arc<dyn domains::ports::LastModifiedRepository>,
+    multipart_limits:      api_adapters::axum::multipart::MultipartLimits,
 ) -> Router
 where
     // Board service
@@ -400,59 +1727,118 @@ where
     RR: domains::ports::StaffRequestRepository + 'static,
     // Staff message repository
     MR: domains::ports::StaffMessageRepository + 'static,
+    // Thread poll repository
+    PL: domains::ports::PollRepository + 'static,
+    // Post reaction repository
+    RX: domains::ports::ReactionRepository + 'static,
+    // Thread watch list repository
+    WR: domains::ports::WatchRepository + 'static,
+    // Board banner repository
+    BN: domains::ports::BannerRepository + 'static,
+    // Blotter announcement repository
+    AN: domains::ports::AnnouncementRepository + 'static,
+    // Static page repository
+    SP: domains::ports::StaticPageRepository + 'static,
+    // Board statistics repository
+    ST: domains::ports::StatsRepository + 'static,
+    // API key repository
+    AK: domains::ports::ApiKeyRepository + 'static,
 {
     use axum::{routing::get, Router};
     use tower_http::services::ServeDir;
-    use tower_http::compression::CompressionLayer;
     use api_adapters::axum::{
+        compression::compression_layer,
         health::health_check,
         metrics::metrics_handler,
         middleware::{
-            board_config::{BoardConfigState, board_config_middleware},
+            api_key::api_key_middleware,
+            board_config::{BoardConfigState, CachedBoardConfigSource, board_config_middleware},
+            color_scheme::color_scheme_middleware,
+            conditional_get::conditional_get_middleware,
+            csrf::csrf_middleware,
+            error_envelope::error_envelope_middleware,
+            error_page::error_page_middleware,
+            error_report::error_report_middleware,
             login_guard::LoginGuard,
-            security_headers::security_headers_middleware,
+            render_cache::{RenderCacheState, render_cache_middleware},
+            security_headers::{SecurityHeaders, security_headers_middleware},
+            site::site_middleware,
+            site_notice::site_notice_middleware,
+            warning_notice::warning_notice_middleware,
         },
         routes::{
             admin_routes::admin_routes,
+            announcement_routes::announcement_routes,
+            api_key_routes::api_key_routes,
             auth_routes::auth_routes,
+            banner_routes::banner_routes,
             board_owner_routes::board_owner_routes,
-            board_routes::{board_admin_routes, board_public_routes},
-            moderation_routes::moderation_routes,
-            overboard_routes::overboard_routes,
+            board_routes::{archived_thread_routes, board_admin_routes, board_public_routes},
+            home_routes::home_routes,
+            moderation_routes::{board_log_routes, moderation_routes},
+            notice_routes::notice_routes,
+            overboard_routes::{all_threads_routes, overboard_routes},
+            page_routes::{board_page_routes, page_routes},
+            poll_routes::poll_routes,
             post_routes::post_routes,
+            reaction_routes::reaction_routes,
             staff_message_routes::staff_message_routes,
+            stats_routes::{board_stats_routes, stats_routes},
             thread_routes::thread_routes,
             user_routes::user_routes,
+            watch_routes::{watch_list_routes, watch_routes},
         },
     };
-    use tower_http::{
-        request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer},
-        trace::TraceLayer,
-    };
+    use api_adapters::axum::middleware::request_id::request_id_middleware;
+    use tower_http::trace::TraceLayer;
     use axum::middleware as axum_middleware;
-    use axum::extract::DefaultBodyLimit;
 
     let board_svc  = Arc::new(board_service);
     let thread_svc = Arc::new(thread_service);
-    let post_svc   = Arc::new(post_service);
     let mod_svc    = Arc::new(moderation_service);
-    let user_svc         = Arc::new(user_service);
+    let user_svc         = user_service;
     let request_svc      = Arc::new(staff_request_service);
     let message_svc      = Arc::new(staff_message_service);
+    let watch_svc        = Arc::new(watch_service);
 
-    // Board config middleware state — resolves `:slug` → BoardId + BoardConfig
-    let board_config_source: Arc<dyn api_adapters::axum::middleware::board_config::BoardConfigSource> =
-        board_svc.clone();
+    // Board config middleware state — resolves `:slug` → BoardId + BoardConfig.
+    // Process-local misses go to the shared cache store before the database.
     let board_config_state = BoardConfigState {
-        source: board_config_source,
+        source: Arc::new(CachedBoardConfigSource {
+            inner: board_svc.clone(),
+            store: cache_store.clone(),
+            ttl:   config_cache_ttl,
+        }),
         cache:  board_config_cache.clone(),
     };
+    let render_cache_state = RenderCacheState {
+        pages: Arc::new(storage_adapters::cache::PageCache::new(render_cache_ttl)),
+        store: cache_store,
+        ttl:   render_cache_ttl,
+    };
 
-    // Board-scoped routes need the board_config middleware to inject ExtractedBoardConfig
+    // Board-scoped routes need the board_config middleware to inject ExtractedBoardConfig.
+    // The render cache layer is added first, so it runs inside board_config;
+    // conditional GET runs between them, so a 304 skips the cache too.
+    let page_svc = Arc::new(page_service);
+    let stats_svc = Arc::new(stats_service);
     let board_scoped = Router::new()
         .merge(thread_routes(thread_svc.clone()))
         .merge(post_routes(post_svc.clone()))
+        .merge(poll_routes(Arc::new(poll_service)))
+        .merge(reaction_routes(Arc::new(reaction_service)))
+        .merge(watch_routes(watch_svc.clone()))
+        .merge(banner_routes(Arc::new(banner_service)))
         .merge(board_owner_routes(board_svc.clone(), request_svc.clone()))
+        .merge(archived_thread_routes(media_storage))
+        .merge(board_page_routes(page_svc.clone()))
+        .merge(board_stats_routes(stats_svc.clone()))
+        .merge(board_log_routes(mod_svc.clone()))
+        .route_layer(axum_middleware::from_fn_with_state(
+            render_cache_state.clone(),
+            render_cache_middleware,
+        ))
+        .route_layer(axum_middleware::from_fn_with_state(last_modified, conditional_get_middleware))
         .route_layer(axum_middleware::from_fn_with_state(
             board_config_state,
             board_config_middleware,
@@ -460,20 +1846,47 @@ where
 
     // Build route groups
     let public_routes = Router::new()
-        .route("/", get(|| async {
-            axum::response::Redirect::to("/overboard")
-        }))
         .route("/healthz", get(health_check).with_state(health_state))
         .merge(board_public_routes(board_svc.clone(), post_repo.clone(), archive_svc.clone()))
+        .merge(home_routes(board_svc.clone(), post_svc.clone()))
         .merge(overboard_routes(board_svc.clone(), post_svc.clone()))
+        .merge(all_threads_routes(board_svc.clone(), thread_svc.clone()))
+        .merge(watch_list_routes(watch_svc))
+        .merge(page_routes(page_svc, board_svc.clone()))
+        .merge(stats_routes(stats_svc, board_svc.clone()))
         .merge(board_scoped);
 
     let auth_router   = auth_routes(user_svc.clone(), open_registration);
-    let admin_router  = admin_routes(user_svc.clone(), board_svc.clone(), request_svc.clone(), message_svc.clone());
+    let auth_router   = match sso_login {
+        Some(sso) => auth_router.layer(axum::Extension(sso)),
+        None => auth_router,
+    };
+    let admin_router  = admin_routes(
+        user_svc.clone(), board_svc.clone(), request_svc.clone(), message_svc.clone(), board_config_cache.clone(),
+        site_state.cache.clone(),
+    );
     let board_admin_r = board_admin_routes(board_svc.clone());
+    let api_key_svc   = Arc::new(api_key_service);
+    let api_key_router = api_key_routes(api_key_svc.clone());
+    let blotter_router = announcement_routes(Arc::new(announcement_service), board_svc.clone());
     let mod_router    = moderation_routes(mod_svc.clone(), board_svc.clone());
+    // Deletions look up the post's board to drop its cached pages; skip the
+    // lookups when nothing is cached.
+    // Likewise for board blotter entries.
+    let (mod_router, blotter_router) = if render_cache_ttl.is_zero() {
+        (mod_router, blotter_router)
+    } else {
+        (
+            mod_router.layer(axum::Extension(render_cache_state.clone())),
+            blotter_router.layer(axum::Extension(render_cache_state)),
+        )
+    };
     let user_router   = user_routes(user_svc.clone(), request_svc.clone());
     let msg_router    = staff_message_routes(message_svc.clone());
+    let notice_router = notice_routes(
+        Arc::new(services::site::SiteService::new(site_state.repo.clone())),
+        site_state.cache.clone(),
+    );
 
     let auth_for_middleware = auth_provider.clone();
 
@@ -482,10 +1895,14 @@ where
         // Static assets: CSS, JS, favicon
         .nest_service("/static", ServeDir::new("static"));
 
-    #[cfg(feature = "media-local")]
-    let base_router = base_router
-        // Local media files (only active with media-local feature; S3 uses signed URLs)
-        .nest_service("/media", ServeDir::new("media"));
+    // Local media files (only with media-local; S3 uses signed URLs).
+    // Thumbnails still being generated fall back to a placeholder.
+    let base_router = match media_root {
+        Some(root) => base_router.merge(api_adapters::axum::routes::media_routes::media_routes(root, media_urls.clone(), Arc::new(post_repo.clone()))),
+        None => base_router,
+    };
+    // The CSP lets pages load media from the CDN origin, if there is one.
+    let security_headers = SecurityHeaders::new(&media_urls);
 
     base_router
         // Prometheus metrics — scoped state so it doesn't pollute the parent router
@@ -498,9 +1915,16 @@ where
         .merge(auth_router)
         .merge(admin_router)
         .merge(board_admin_r)
+        .merge(api_key_router)
+        .merge(blotter_router)
         .merge(mod_router)
         .merge(user_router)
         .merge(msg_router)
+        .merge(notice_router)
+        .merge(plugin_routes)
+        // API keys of bots and bridges — injects ApiClient for a valid key,
+        // refuses invalid ones; requests without a key pass through.
+        .layer(axum_middleware::from_fn_with_state(api_key_svc, api_key_middleware::<AK>))
         // Soft auth middleware — injects CurrentUser into extensions if token valid.
         // Never rejects — individual extractors (AuthenticatedUser, ModeratorUser, AdminUser)
         // enforce role requirements per-route.
@@ -510,23 +1934,47 @@ where
                 api_adapters::axum::middleware::auth::auth_middleware(provider, req, next).await
             }
         }))
-        // Security response headers on every response
-        .layer(axum_middleware::from_fn(security_headers_middleware))
-        // Allow multipart uploads up to 12 MB (board max is 10 MB; the extra
-        // 2 MB covers multipart boundary overhead and multiple small files).
-        // Without this, Axum's default 2 MB limit rejects image uploads silently.
-        .layer(DefaultBodyLimit::max(12 * 1024 * 1024))
+        // Adds the site notice to pages and JSON objects; needs the Site, and
+        // stays outside the render cache so cached pages go without it.
+        .layer(axum_middleware::from_fn(site_notice_middleware))
+        // Shows a warned poster their pending warning; per visitor, so it too
+        // stays outside the render cache.
+        .layer(axum_middleware::from_fn_with_state(warnings, warning_notice_middleware))
+        // Refuses form posts without the visitor's CSRF token and adds the
+        // token to every POST form, including the notices' forms above.
+        .layer(axum_middleware::from_fn(csrf_middleware))
+        // Resolves the Host header to a Site before any board lookup, including
+        // the board_config route layer above.
+        .layer(axum_middleware::from_fn_with_state(site_state, site_middleware))
+        // Points each page's stylesheet at the viewer's theme. Outside the
+        // render cache, so cached pages stay the same for every viewer.
+        .layer(axum_middleware::from_fn(color_scheme_middleware))
+        // MAX_BODY_KB for every body, replacing axum's 2 MB default; the
+        // other limits are read by the LimitedMultipart extractor.
+        .layer(multipart_limits.body_limit())
+        .layer(axum::Extension(multipart_limits))
         // Login brute-force guard — shared across all routes via Extension.
         .layer(axum::Extension(LoginGuard::new()))
-        .layer(CompressionLayer::new())
-        .layer(TraceLayer::new_for_http())
-        .layer(SetRequestIdLayer::new(
-            axum::http::HeaderName::from_static("x-request-id"),
-            MakeRequestUuid,
-        ))
-        .layer(PropagateRequestIdLayer::new(
-            axum::http::HeaderName::from_static("x-request-id"),
-        ))
+        // Proxies whose forwarding headers name the client (ClientIp extractor).
+        .layer(axum::Extension(trusted_proxies))
+        // Attachment links in pages (MediaLinks extractor).
+        .layer(axum::Extension(media_urls))
+        // Panics become 500s; panics and 500s go to the error reporter.
+        .layer(axum_middleware::from_fn_with_state(error_reporter, error_report_middleware))
+        // Plain-text and empty error responses (extractor rejections, panics)
+        // get the same JSON error envelope as ApiError.
+        .layer(axum_middleware::from_fn(error_envelope_middleware))
+        // Browsers get an error.html page instead of a JSON or empty error body.
+        .layer(axum_middleware::from_fn(error_page_middleware))
+        // Security response headers on every response, and the CSP nonce in
+        // every page's scripts; outside error_page so error pages get it too.
+        .layer(axum_middleware::from_fn_with_state(security_headers, security_headers_middleware))
+        // Brotli/gzip for pages and JSON; media is sent as stored.
+        .layer(compression_layer(compression.gzip(), compression.br(), compression_min_bytes))
+        // Latency/status events only; the span is opened by request_id_middleware.
+        .layer(TraceLayer::new_for_http().make_span_with(|_: &axum::http::Request<_>| tracing::Span::none()))
+        // Outermost: every log line of the request carries its X-Request-Id.
+        .layer(axum_middleware::from_fn(request_id_middleware))
         // Redirect trailing-slash URLs to their canonical (no-slash) equivalent.
         // Using .fallback() rather than middleware because axum 0.8 Router::layer()
         // wraps matched handlers; unmatched /board/b/ never triggers middleware.
@@ -0,0 +1,304 @@
+//! The maintenance jobs run by the `Scheduler` (see `scheduler.rs`).
+//!
+//! | Job | Setting | Work |
+//! |-----|---------|------|
+//! | `media-gc` | `MEDIA_GC_INTERVAL_SECS` | Delete orphaned media objects |
+//! | `prune-threads` | `PRUNE_INTERVAL_SECS` | Prune boards over `max_threads`, archiving where enabled |
+//! | `expire-bans` | `EXPIRE_INTERVAL_SECS` | Delete bans expired more than `BAN_RETENTION_DAYS` ago |
+//! | `purge-deleted-posts` | `EXPIRE_INTERVAL_SECS` | Remove post tombstones older than `DELETED_POST_RETENTION_DAYS` |
+//! | `expire-sessions` | `EXPIRE_INTERVAL_SECS` | Delete expired login sessions |
+//! | `expire-staff-messages` | `EXPIRE_INTERVAL_SECS` | Delete staff messages older than 14 days |
+//! | `stats` | `STATS_INTERVAL_SECS` | Refresh the per-board gauges on `/metrics` |
+//! | `rollup-stats` | `STATS_ROLLUP_INTERVAL_SECS` | Count recent posts into the board statistics buckets |
+//! | `report-digest` | `[email] digest_interval_secs` | Email the reports filed since the last digest |
+//!
+//! Jobs are generic over the ports they use; `composition.rs` instantiates
+//! them with the concrete adapters.
+
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use api_adapters::axum::metrics::{AppMetrics, BoardLabels};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domains::models::{Board, Page};
+use domains::ports::{
+    AuditRepository, BanRepository, BoardRepository, FlagRepository, MediaStorage, PostRepository,
+    SessionRepository, StaffMessageRepository, StatsRepository, ThreadRepository, UserRepository,
+};
+use services::board::BoardService;
+use services::media_gc::MediaGcService;
+use services::moderation::ModerationService;
+use services::report_alerts::ReportAlerts;
+use services::staff_message::StaffMessageService;
+use services::stats::StatsService;
+use services::thread::ThreadService;
+
+use crate::scheduler::Job;
+
+/// Days a staff message is kept, matching `POST /staff/messages/purge`.
+const STAFF_MESSAGE_RETENTION_DAYS: u32 = 14;
+
+/// Every board on every site, across all pages.
+pub async fn all_boards<BR: BoardRepository>(boards: &BoardService<BR>) -> anyhow::Result<Vec<Board>> {
+    let mut all = Vec::new();
+    let mut page = Page::default();
+    loop {
+        let batch = boards.list_all_boards(page).await?;
+        let done = batch.items.is_empty() || all.len() + batch.items.len() >= batch.total as usize;
+        all.extend(batch.items);
+        if done {
+            return Ok(all);
+        }
+        page = Page::new(page.0 + 1);
+    }
+}
+
+/// Sweep media objects no attachment references (see `MediaGcService::sweep`).
+pub struct MediaGc<PR: PostRepository, MS: MediaStorage> {
+    pub gc:    MediaGcService<PR, MS>,
+    /// Objects younger than this are never swept.
+    pub grace: Duration,
+}
+
+#[async_trait]
+impl<PR: PostRepository, MS: MediaStorage> Job for MediaGc<PR, MS> {
+    fn name(&self) -> &'static str {
+        "media-gc"
+    }
+
+    async fn run(&self) -> anyhow::Result<()> {
+        self.gc.sweep(self.grace, false).await?;
+        Ok(())
+    }
+}
+
+/// Prune every board down to its `max_threads`, archiving the removed threads
+/// on boards with `archive_enabled`.
+///
+/// Posting a new thread already prunes its board; this catches boards whose
+/// limit was lowered, or that were filled by an import.
+pub struct PruneThreads<BR: BoardRepository, TR: ThreadRepository, PR: PostRepository> {
+    pub boards:  BoardService<BR>,
+    pub threads: ThreadService<TR, PR>,
+    pub metrics: AppMetrics,
+}
+
+#[async_trait]
+impl<BR, TR, PR> Job for PruneThreads<BR, TR, PR>
+where
+    BR: BoardRepository,
+    TR: ThreadRepository,
+    PR: PostRepository,
+{
+    fn name(&self) -> &'static str {
+        "prune-threads"
+    }
+
+    async fn run(&self) -> anyhow::Result<()> {
+        for board in all_boards(&self.boards).await? {
+            let config = self.boards.get_config(board.id).await?;
+            let pruned = self
+                .threads
+                .prune_with_archive(board.id, config.max_threads, config.archive_enabled)
+                .await?;
+            self.metrics.thread_prunes_total.inc_by(pruned.into());
+        }
+        Ok(())
+    }
+}
+
+/// Delete bans that expired more than `retention_days` ago.
+pub struct ExpireBans<BR, PR, TR, FR, AR, UR>
+where
+    BR: BanRepository,
+    PR: PostRepository,
+    TR: ThreadRepository,
+    FR: FlagRepository,
+    AR: AuditRepository,
+    UR: UserRepository,
+{
+    pub moderation:     ModerationService<BR, PR, TR, FR, AR, UR>,
+    pub retention_days: u32,
+}
+
+#[async_trait]
+impl<BR, PR, TR, FR, AR, UR> Job for ExpireBans<BR, PR, TR, FR, AR, UR>
+where
+    BR: BanRepository,
+    PR: PostRepository,
+    TR: ThreadRepository,
+    FR: FlagRepository,
+    AR: AuditRepository,
+    UR: UserRepository,
+{
+    fn name(&self) -> &'static str {
+        "expire-bans"
+    }
+
+    async fn run(&self) -> anyhow::Result<()> {
+        self.moderation.purge_expired_bans(self.retention_days).await?;
+        Ok(())
+    }
+}
+
+/// Remove post tombstones deleted more than `retention_days` days ago.
+pub struct PurgeDeletedPosts<BR, PR, TR, FR, AR, UR>
+where
+    BR: BanRepository,
+    PR: PostRepository,
+    TR: ThreadRepository,
+    FR: FlagRepository,
+    AR: AuditRepository,
+    UR: UserRepository,
+{
+    pub moderation:     ModerationService<BR, PR, TR, FR, AR, UR>,
+    pub retention_days: u32,
+}
+
+#[async_trait]
+impl<BR, PR, TR, FR, AR, UR> Job for PurgeDeletedPosts<BR, PR, TR, FR, AR, UR>
+where
+    BR: BanRepository,
+    PR: PostRepository,
+    TR: ThreadRepository,
+    FR: FlagRepository,
+    AR: AuditRepository,
+    UR: UserRepository,
+{
+    fn name(&self) -> &'static str {
+        "purge-deleted-posts"
+    }
+
+    async fn run(&self) -> anyhow::Result<()> {
+        self.moderation.purge_deleted_posts(self.retention_days).await?;
+        Ok(())
+    }
+}
+
+/// Delete expired login sessions.
+pub struct ExpireSessions<SR: SessionRepository>(pub SR);
+
+#[async_trait]
+impl<SR: SessionRepository> Job for ExpireSessions<SR> {
+    fn name(&self) -> &'static str {
+        "expire-sessions"
+    }
+
+    async fn run(&self) -> anyhow::Result<()> {
+        self.0.purge_expired().await?;
+        Ok(())
+    }
+}
+
+/// Delete staff messages past their retention window.
+pub struct ExpireStaffMessages<MR: StaffMessageRepository>(pub StaffMessageService<MR>);
+
+#[async_trait]
+impl<MR: StaffMessageRepository> Job for ExpireStaffMessages<MR> {
+    fn name(&self) -> &'static str {
+        "expire-staff-messages"
+    }
+
+    async fn run(&self) -> anyhow::Result<()> {
+        self.0.purge_expired(STAFF_MESSAGE_RETENTION_DAYS).await?;
+        Ok(())
+    }
+}
+
+/// Email the reports collected since the last digest.
+pub struct SendReportDigest<FR: FlagRepository>(pub Arc<ReportAlerts<FR>>);
+
+#[async_trait]
+impl<FR: FlagRepository> Job for SendReportDigest<FR> {
+    fn name(&self) -> &'static str {
+        "report-digest"
+    }
+
+    async fn run(&self) -> anyhow::Result<()> {
+        self.0.send_digest().await?;
+        Ok(())
+    }
+}
+
+/// Recompute the `board_threads` and `board_media_bytes` gauges.
+///
+/// Gauges of boards deleted since the last run are removed, so `/metrics`
+/// only lists boards that exist.
+pub struct BoardStats<BR: BoardRepository, TR: ThreadRepository> {
+    boards:  BoardService<BR>,
+    threads: TR,
+    metrics: AppMetrics,
+    /// Slugs reported by the previous run.
+    seen:    Mutex<HashSet<String>>,
+}
+
+impl<BR: BoardRepository, TR: ThreadRepository> BoardStats<BR, TR> {
+    pub fn new(boards: BoardService<BR>, threads: TR, metrics: AppMetrics) -> Self {
+        Self { boards, threads, metrics, seen: Mutex::default() }
+    }
+}
+
+#[async_trait]
+impl<BR: BoardRepository, TR: ThreadRepository> Job for BoardStats<BR, TR> {
+    fn name(&self) -> &'static str {
+        "stats"
+    }
+
+    async fn run(&self) -> anyhow::Result<()> {
+        let mut current = HashSet::new();
+        for board in all_boards(&self.boards).await? {
+            let threads = self.threads.count_by_board(board.id).await?;
+            let media_bytes = self.boards.media_usage(board.id).await?;
+            let labels = BoardLabels { board: board.slug.to_string() };
+            self.metrics.board_threads.get_or_create(&labels).set(threads.into());
+            self.metrics
+                .board_media_bytes
+                .get_or_create(&labels)
+                .set(i64::try_from(media_bytes).unwrap_or(i64::MAX));
+            current.insert(labels.board);
+        }
+        let mut seen = self.seen.lock().unwrap_or_else(|e| e.into_inner());
+        for gone in seen.difference(&current) {
+            let labels = BoardLabels { board: gone.clone() };
+            self.metrics.board_threads.remove(&labels);
+            self.metrics.board_media_bytes.remove(&labels);
+        }
+        *seen = current;
+        Ok(())
+    }
+}
+
+/// Count the posts since the previous run into the board statistics buckets.
+///
+/// Each run recounts the hour and day the previous run started in, so posts
+/// made while it ran are not missed. The first run after startup recounts the
+/// last day; a longer outage leaves its hours empty.
+pub struct RollupStats<SR: StatsRepository> {
+    stats:    StatsService<SR>,
+    /// When the previous run started.
+    last_run: Mutex<Option<DateTime<Utc>>>,
+}
+
+impl<SR: StatsRepository> RollupStats<SR> {
+    pub fn new(stats: StatsService<SR>) -> Self {
+        Self { stats, last_run: Mutex::default() }
+    }
+}
+
+#[async_trait]
+impl<SR: StatsRepository> Job for RollupStats<SR> {
+    fn name(&self) -> &'static str {
+        "rollup-stats"
+    }
+
+    async fn run(&self) -> anyhow::Result<()> {
+        let now = Utc::now();
+        let last_run = *self.last_run.lock().unwrap_or_else(|e| e.into_inner());
+        let since = last_run.unwrap_or(now - chrono::Duration::days(1));
+        self.stats.rollup(since).await?;
+        *self.last_run.lock().unwrap_or_else(|e| e.into_inner()) = Some(now);
+        Ok(())
+    }
+}
@@ -0,0 +1,118 @@
+//! Choosing the socket the server accepts on.
+//!
+//! In order of preference:
+//! 1. A socket passed in by systemd socket activation (`LISTEN_FDS`). systemd
+//!    keeps it open across restarts, so connections arriving while the
+//!    service restarts wait in the backlog instead of being refused.
+//! 2. `UNIX_SOCKET_PATH`, bound by us and removed on shutdown.
+//! 3. `HOST:PORT` over TCP.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use configs::Settings;
+
+/// A bound listening socket.
+pub enum Listener {
+    Tcp {
+        listener:  tokio::net::TcpListener,
+        /// Passed in by systemd rather than bound from settings.
+        activated: bool,
+    },
+    #[cfg(unix)]
+    Unix {
+        listener: tokio::net::UnixListener,
+        /// Socket file to remove on shutdown; `None` when systemd owns it.
+        cleanup:  Option<PathBuf>,
+    },
+}
+
+impl Listener {
+    /// True for Unix sockets, where client addresses come from proxy headers.
+    pub fn is_unix(&self) -> bool {
+        !matches!(self, Self::Tcp { .. })
+    }
+}
+
+/// Take the systemd-activated socket if there is one, otherwise bind the
+/// socket named by `settings`.
+pub async fn bind(settings: &Settings) -> anyhow::Result<Listener> {
+    if let Some(listener) = from_systemd()? {
+        return Ok(listener);
+    }
+    match &settings.unix_socket_path {
+        Some(path) => bind_unix(path),
+        None => {
+            let addr = format!("{}:{}", settings.host, settings.port);
+            let listener = tokio::net::TcpListener::bind(&addr)
+                .await
+                .with_context(|| format!("failed to bind to {addr}"))?;
+            Ok(Listener::Tcp { listener, activated: false })
+        }
+    }
+}
+
+/// The socket systemd passed in, if this process was socket-activated.
+///
+/// `LISTEN_FDS` and `LISTEN_PID` are cleared so child processes do not
+/// inherit them. Exactly one `ListenStream=` socket (TCP or Unix) is accepted.
+#[cfg(unix)]
+fn from_systemd() -> anyhow::Result<Option<Listener>> {
+    let mut fds = listenfd::ListenFd::from_env();
+    match fds.len() {
+        0 => return Ok(None),
+        1 => {}
+        n => anyhow::bail!("systemd passed {n} sockets; rusty-board listens on exactly one"),
+    }
+    let listener = match fds.take_unix_listener(0) {
+        Ok(Some(listener)) => {
+            listener.set_nonblocking(true)?;
+            Listener::Unix { listener: tokio::net::UnixListener::from_std(listener)?, cleanup: None }
+        }
+        _ => {
+            let listener = fds
+                .take_tcp_listener(0)
+                .context("systemd socket is not a TCP or Unix stream socket")?
+                .context("systemd socket was already taken")?;
+            listener.set_nonblocking(true)?;
+            Listener::Tcp { listener: tokio::net::TcpListener::from_std(listener)?, activated: true }
+        }
+    };
+    Ok(Some(listener))
+}
+
+#[cfg(not(unix))]
+fn from_systemd() -> anyhow::Result<Option<Listener>> {
+    Ok(None)
+}
+
+/// Bind a Unix socket at `path`.
+///
+/// A socket file left by a previous run is replaced, but binding fails if
+/// another process is still accepting on it or the path is not a socket. The
+/// socket is made group-writable (0660) so a proxy sharing the group can
+/// connect.
+#[cfg(unix)]
+fn bind_unix(path: &Path) -> anyhow::Result<Listener> {
+    use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+
+    if let Ok(meta) = std::fs::symlink_metadata(path) {
+        if !meta.file_type().is_socket() {
+            anyhow::bail!("{} exists and is not a socket", path.display());
+        }
+        if std::os::unix::net::UnixStream::connect(path).is_ok() {
+            anyhow::bail!("{} is in use by another process", path.display());
+        }
+        std::fs::remove_file(path).with_context(|| format!("failed to remove stale socket {}", path.display()))?;
+    }
+    let listener = tokio::net::UnixListener::bind(path)
+        .with_context(|| format!("failed to bind to {}", path.display()))?;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o660))
+        .with_context(|| format!("failed to set permissions on {}", path.display()))?;
+    Ok(Listener::Unix { listener, cleanup: Some(path.to_owned()) })
+}
+
+#[cfg(not(unix))]
+fn bind_unix(_: &Path) -> anyhow::Result<Listener> {
+    anyhow::bail!("UNIX_SOCKET_PATH is only supported on Unix")
+}
@@ -1,20 +1,43 @@
 //! `rusty-board` — main entry point.
 //!
 //! Responsibilities:
-//! 1. Load `Settings` from environment variables
+//! 1. Load `Settings` from the config file, environment variables and flags
 //! 2. Initialise structured tracing
 //! 3. Call `composition::compose()` to build all concrete adapters and services
-//! 4. Start the HTTP server
-//! 5. Graceful shutdown on SIGTERM or Ctrl-C
+//! 4. Start the HTTP server on a systemd-activated socket, a Unix socket, or
+//!    `host:port` (HTTP or HTTPS); see `listener.rs`
+//! 5. Graceful shutdown on SIGTERM or Ctrl-C: stop accepting, drain in-flight
+//!    requests, flush background work, close pools (all within
+//!    `SHUTDOWN_TIMEOUT_SECS`)
+//!
+//! With a subcommand (see `cli.rs`) the binary runs one administrative job
+//! instead of serving: `migrate`, `board create|list|delete`, `admin create`,
+//! `ban add|remove`, `prune`, `gc-media`, `regen-thumbs`, `storage verify`,
+//! `export` and `import`.
+//! `--config <path>` and `--set key=value` apply to every subcommand.
 //!
 //! This file contains the tokio runtime and server binding. All adapter
 //! selection and dependency wiring lives in `composition.rs`.
 
+mod backup;
+mod cli;
+mod commands;
 mod composition;
+mod jobs;
+mod listener;
+#[cfg(feature = "matrix-bridge")]
+mod matrix_bridge;
+mod scheduler;
+
+use std::future::{Future, IntoFuture};
+use std::time::{Duration, Instant};
 
 use anyhow::Context;
+use clap::Parser;
 use configs::Settings;
-use tracing::info;
+use listener::Listener;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -24,47 +47,179 @@ async fn main() -> anyhow::Result<()> {
     // Install ring as the process-default before any network or JWT operations.
     let _ = rustls::crypto::ring::default_provider().install_default();
 
+    // ── Settings ──────────────────────────────────────────────────────────────
+    let cli = cli::Cli::parse();
+    let settings = Settings::load_layered(cli.config.as_deref(), &cli.overrides)
+        .context("failed to load settings")?;
+
     // ── Tracing ───────────────────────────────────────────────────────────────
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "rusty_board=info,tower_http=debug".into()),
-        )
-        .json()
-        .init();
+    let telemetry = composition::init_tracing(&settings)?;
 
-    // ── Settings ──────────────────────────────────────────────────────────────
-    let settings = Settings::load().context("failed to load settings")?;
+    // ── Administrative subcommands ────────────────────────────────────────────
+    match cli.command.unwrap_or(cli::Command::Serve) {
+        cli::Command::Serve => {}
+        command => {
+            let result = commands::run(command, &settings).await;
+            telemetry.shutdown().await;
+            return result;
+        }
+    }
 
     // Log which features are compiled in at startup
     log_compiled_features();
 
+    // ── TLS ───────────────────────────────────────────────────────────────────
+    // Loaded before composing so a bad certificate fails fast.
+    let tls = match settings.tls_paths().context("invalid TLS settings")? {
+        Some((cert, key)) => Some(
+            axum_server::tls_rustls::RustlsConfig::from_pem_file(cert, key)
+                .await
+                .with_context(|| format!("failed to load TLS certificate {} / key {}", cert.display(), key.display()))?,
+        ),
+        None => None,
+    };
+    if tls.is_some() && settings.unix_socket_path.is_some() {
+        anyhow::bail!("UNIX_SOCKET_PATH cannot be combined with TLS; let the proxy in front of the socket terminate TLS");
+    }
+
     // ── Compose ───────────────────────────────────────────────────────────────
-    let router = composition::compose(&settings).await.context("failed to compose application")?;
+    let (router, background) = composition::compose(&settings).await.context("failed to compose application")?;
+
+    // ── Shutdown trigger ──────────────────────────────────────────────────────
+    let shutdown = CancellationToken::new();
+    tokio::spawn({
+        let shutdown = shutdown.clone();
+        async move {
+            shutdown_signal().await;
+            shutdown.cancel();
+        }
+    });
+    let timeout = Duration::from_secs(settings.shutdown_timeout_secs);
+    #[cfg(unix)]
+    tokio::spawn(reload_on_sighup(background.reloader()));
 
     // ── Bind ──────────────────────────────────────────────────────────────────
-    let addr = format!("{}:{}", settings.host, settings.port);
-    let listener = tokio::net::TcpListener::bind(&addr)
-        .await
-        .with_context(|| format!("failed to bind to {addr}"))?;
-
-    info!(addr = %addr, "rusty-board started");
-
-    // ── Serve with graceful shutdown ──────────────────────────────────────────
-    axum::serve(
-        listener,
-        router.into_make_service_with_connect_info::<std::net::SocketAddr>(),
-    )
-    .with_graceful_shutdown(shutdown_signal(settings.shutdown_timeout_secs))
-    .await
-    .context("server error")?;
-
-    info!("rusty-board shut down cleanly");
+    let listener = listener::bind(&settings).await?;
+    if tls.is_some() && listener.is_unix() {
+        anyhow::bail!("a Unix socket cannot be combined with TLS; let the proxy in front of the socket terminate TLS");
+    }
+
+    // ── Serve until drained ───────────────────────────────────────────────────
+    let drain = match listener {
+        Listener::Tcp { listener, activated } => {
+            info!(addr = %listener.local_addr()?, tls = tls.is_some(), activated, "rusty-board started");
+
+            let app = router.into_make_service_with_connect_info::<std::net::SocketAddr>();
+            match tls {
+                None => {
+                    let server = axum::serve(listener, app).with_graceful_shutdown(shutdown.clone().cancelled_owned());
+                    until_drained(server.into_future(), &shutdown, timeout).await?
+                }
+                Some(tls) => {
+                    // axum-server completes TLS handshakes off the accept loop, so a
+                    // slow client cannot stall new connections.
+                    let handle = axum_server::Handle::new();
+                    tokio::spawn({
+                        let (handle, shutdown) = (handle.clone(), shutdown.clone());
+                        async move {
+                            shutdown.cancelled().await;
+                            handle.graceful_shutdown(None);
+                        }
+                    });
+                    let server = axum_server::from_tcp_rustls(listener.into_std()?, tls)?.handle(handle).serve(app);
+                    until_drained(server, &shutdown, timeout).await?
+                }
+            }
+        }
+        #[cfg(unix)]
+        Listener::Unix { listener, cleanup } => {
+            let socket = listener.local_addr()?.as_pathname().map(|p| p.display().to_string()).unwrap_or_default();
+            info!(socket, activated = cleanup.is_none(), "rusty-board started");
+
+            // Client addresses come from the proxy's forwarding headers.
+            let app = router.layer(axum::middleware::from_fn(
+                api_adapters::axum::middleware::proxy_peer::proxy_peer_middleware,
+            ));
+            let server = axum::serve(listener, app).with_graceful_shutdown(shutdown.clone().cancelled_owned());
+            let drain = until_drained(server.into_future(), &shutdown, timeout).await;
+            if let Some(path) = cleanup {
+                let _ = std::fs::remove_file(path);
+            }
+            drain?
+        }
+    };
+
+    // ── Background work and pools ─────────────────────────────────────────────
+    let summary = background.shutdown(timeout.saturating_sub(drain.took)).await;
+    info!(
+        requests_drained = drain.complete,
+        drain_ms = drain.took.as_millis() as u64,
+        thumbnails_abandoned = summary.thumbnails_abandoned,
+        jobs_aborted = summary.jobs_aborted,
+        "rusty-board shut down"
+    );
+    telemetry.shutdown().await;
     Ok(())
 }
 
-/// Wait for SIGTERM or Ctrl-C, then give in-flight requests time to drain.
-async fn shutdown_signal(timeout_secs: u64) {
+/// How draining in-flight requests went.
+struct Drain {
+    /// False when the timeout expired and remaining connections were dropped.
+    complete: bool,
+    /// Time from the shutdown signal until the server stopped.
+    took:     Duration,
+}
+
+/// Run `server` (already wired to stop accepting on `shutdown`) and, once
+/// `shutdown` fires, give in-flight requests up to `timeout` to finish.
+async fn until_drained<F>(server: F, shutdown: &CancellationToken, timeout: Duration) -> anyhow::Result<Drain>
+where
+    F: Future<Output = std::io::Result<()>>,
+{
+    tokio::pin!(server);
+    tokio::select! {
+        result = &mut server => {
+            result.context("server error")?;
+            return Ok(Drain { complete: true, took: Duration::ZERO });
+        }
+        _ = shutdown.cancelled() => {}
+    }
+    let began = Instant::now();
+    let complete = match tokio::time::timeout(timeout, server).await {
+        Ok(result) => {
+            result.context("server error")?;
+            true
+        }
+        Err(_) => {
+            warn!(timeout_secs = timeout.as_secs(), "in-flight requests still running at the shutdown timeout; dropping them");
+            false
+        }
+    };
+    Ok(Drain { complete, took: began.elapsed() })
+}
+
+/// Run `reload` on every SIGHUP, for the life of the process.
+///
+/// Only runtime state is refreshed — per-board settings such as rate limits
+/// and link blacklists, and the site list. `Settings` (environment and config file) are read
+/// once at startup; changing them still needs a restart.
+#[cfg(unix)]
+async fn reload_on_sighup(reload: impl Fn() -> (usize, usize)) {
+    let mut hangups = match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup()) {
+        Ok(hangups) => hangups,
+        Err(e) => {
+            warn!(error = %e, "failed to install SIGHUP handler; reload with POST /admin/reload instead");
+            return;
+        }
+    };
+    while hangups.recv().await.is_some() {
+        let (boards_cleared, sites_cleared) = reload();
+        info!(boards_cleared, sites_cleared, "SIGHUP received; board config reloaded");
+    }
+}
+
+/// Wait for SIGTERM or SIGINT (Ctrl-C).
+async fn shutdown_signal() {
     let ctrl_c = async {
         tokio::signal::ctrl_c()
             .await
@@ -87,11 +242,7 @@ async fn shutdown_signal(timeout_secs: u64) {
         _ = sigterm => {},
     }
 
-    info!(
-        timeout_secs,
-        "shutdown signal received; draining in-flight requests"
-    );
-    tokio::time::sleep(std::time::Duration::from_secs(timeout_secs)).await;
+    info!("shutdown signal received; no longer accepting connections, draining in-flight requests");
 }
 
 /// Log which Cargo features were compiled into this binary.
@@ -120,12 +271,20 @@ fn log_compiled_features() {
     features.push("media-s3");
     #[cfg(feature = "media-local")]
     features.push("media-local");
+    #[cfg(feature = "media-ipfs")]
+    features.push("media-ipfs");
     #[cfg(feature = "video")]
     features.push("video");
     #[cfg(feature = "documents")]
     features.push("documents");
     #[cfg(feature = "redis")]
     features.push("redis");
+    #[cfg(feature = "nsfw-onnx")]
+    features.push("nsfw-onnx");
+    #[cfg(feature = "otel")]
+    features.push("otel");
+    #[cfg(feature = "sentry")]
+    features.push("sentry");
 
     info!(features = ?features, "compiled features");
 }
@@ -0,0 +1,151 @@
+//! Matrix room → thread half of the Matrix bridge (feature: `matrix-bridge`).
+//!
+//! Long-polls `/sync` for the bridged rooms and posts each text message to
+//! the room's thread through `PostService`, like any other reply:
+//!
+//! - the name is the sender's Matrix ID (`@alice:example.org`);
+//! - the IP hash is the daily-salted hash of `matrix:<sender>`, so bans and
+//!   rate limits apply per Matrix user;
+//! - the post is tagged `matrix`, which keeps `MatrixRelay` from sending it
+//!   back to the room.
+//!
+//! A refused post is answered with an `m.notice` giving the reason. Messages
+//! sent while the server was down are not posted: the first sync only fetches
+//! the position to continue from.
+//!
+//! The thread → room half is `storage_adapters::matrix::MatrixRelay`.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use domains::models::{BoardId, ThreadId};
+use domains::ports::{
+    BanRepository, BoardRepository, MediaProcessor, MediaStorage, PostRepository, RateLimiter, ThreadRepository,
+};
+use services::common::utils::hash_ip;
+use services::post::{PostDraft, PostError, PostService};
+use storage_adapters::matrix::{MatrixClient, RoomMessage, BRIDGE_TAG};
+use tokio_util::sync::CancellationToken;
+
+/// First wait after a failed sync; doubles up to `MAX_BACKOFF`.
+const MIN_BACKOFF: Duration = Duration::from_secs(5);
+const MAX_BACKOFF: Duration = Duration::from_secs(300);
+
+/// A bridged room's thread.
+#[derive(Debug, Clone, Copy)]
+pub struct BridgedThread {
+    pub board_id:  BoardId,
+    pub thread_id: ThreadId,
+}
+
+/// Posts the messages of bridged rooms to their threads.
+pub struct MatrixBridge<PR, TR, BR, MS, RL, MP>
+where
+    PR: PostRepository,
+    TR: ThreadRepository,
+    BR: BanRepository,
+    MS: MediaStorage,
+    RL: RateLimiter,
+    MP: MediaProcessor,
+{
+    pub client:       MatrixClient,
+    /// The bridge's own Matrix ID; its messages are the relayed posts.
+    pub user_id:      String,
+    /// Thread of each bridged room ID.
+    pub threads:      HashMap<String, BridgedThread>,
+    pub posts:        Arc<PostService<PR, TR, BR, MS, RL, MP>>,
+    pub boards:       Arc<dyn BoardRepository>,
+    /// Long-poll wait of one sync.
+    pub sync_timeout: Duration,
+}
+
+impl<PR, TR, BR, MS, RL, MP> MatrixBridge<PR, TR, BR, MS, RL, MP>
+where
+    PR: PostRepository,
+    TR: ThreadRepository,
+    BR: BanRepository,
+    MS: MediaStorage,
+    RL: RateLimiter,
+    MP: MediaProcessor,
+{
+    /// Relay room messages until `stop` is cancelled.
+    pub async fn run(self, stop: CancellationToken) {
+        let rooms: Vec<String> = self.threads.keys().cloned().collect();
+        let mut since: Option<String> = None;
+        let mut backoff = MIN_BACKOFF;
+        loop {
+            let timeout = if since.is_some() { self.sync_timeout } else { Duration::ZERO };
+            let synced = tokio::select! {
+                _ = stop.cancelled() => return,
+                synced = self.client.sync(since.as_deref(), timeout, &rooms) => synced,
+            };
+            match synced {
+                Ok(batch) => {
+                    if since.is_some() {
+                        for message in &batch.messages {
+                            self.relay(message).await;
+                        }
+                    }
+                    since = Some(batch.next_batch);
+                    backoff = MIN_BACKOFF;
+                }
+                Err(e) => {
+                    tracing::warn!(error = %e, retry_in = ?backoff, "matrix sync failed");
+                    tokio::select! {
+                        _ = stop.cancelled() => return,
+                        _ = tokio::time::sleep(backoff) => {}
+                    }
+                    backoff = (backoff * 2).min(MAX_BACKOFF);
+                }
+            }
+        }
+    }
+
+    /// Post `message` to its room's thread, telling the room if it is refused.
+    async fn relay(&self, message: &RoomMessage) {
+        let Some(thread) = self.threads.get(&message.room_id) else {
+            return;
+        };
+        if message.sender == self.user_id || message.body.trim().is_empty() {
+            return;
+        }
+        let refusal = match self.post(thread, message).await {
+            Ok(()) => return,
+            Err(PostError::Internal(e)) => {
+                tracing::error!(error = %e, room = %message.room_id, "matrix message not posted");
+                "Not posted: server error".to_owned()
+            }
+            Err(PostError::MediaError { .. }) => "Not posted: server error".to_owned(),
+            Err(e) => format!("Not posted: {e}"),
+        };
+        if let Err(e) = self.client.send(&message.room_id, "m.notice", &refusal).await {
+            tracing::warn!(error = %e, room = %message.room_id, "matrix notice not sent");
+        }
+    }
+
+    async fn post(&self, thread: &BridgedThread, message: &RoomMessage) -> Result<(), PostError> {
+        let board_config = self.boards.find_config(thread.board_id).await?;
+        let daily_salt = chrono::Utc::now().format("%Y-%m-%d").to_string();
+        let draft = PostDraft {
+            board_id:     thread.board_id,
+            thread_id:    Some(thread.thread_id),
+            body:         message.body.clone(),
+            ip_hash:      hash_ip(&format!("matrix:{}", message.sender), &daily_salt),
+            raw_ip:       None,
+            name:         Some(message.sender.clone()),
+            email:        None,
+            subject:      None,
+            files:        Vec::new(),
+            spoiler:      false,
+            is_staff:     false,
+            poster_role:  None,
+            media_prefix: String::new(),
+            tags:         vec![BRIDGE_TAG.to_owned()],
+            poll:         None,
+            via_api_key:  false,
+        };
+        self.posts.create_post(draft, &board_config).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,259 @@
+//! Periodic background jobs.
+//!
+//! Each `Job` is registered with an interval and runs on its own tokio task:
+//! first at a random point in the second half of its first interval (so jobs
+//! registered together — and replicas started together — do not all hit the
+//! database at once), then once per interval. A run that overruns its interval delays the next
+//! one rather than queueing a burst. Failures are logged and retried on the
+//! next tick; they never stop the job. Failures, and panics inside a run, are
+//! also passed to the scheduler's `ErrorReporter`, tagged with the job name.
+//!
+//! The jobs themselves live in `jobs.rs`; `composition.rs` decides which are
+//! registered.
+
+use std::panic::AssertUnwindSafe;
+use std::sync::Arc;
+use std::time::Duration;
+
+use api_adapters::axum::middleware::error_report::panic_message;
+use async_trait::async_trait;
+use domains::ports::{ErrorReport, ErrorReporter};
+use futures_util::FutureExt;
+use storage_adapters::error_reporting::NoopErrorReporter;
+use tokio::task::JoinHandle;
+use tokio::time::{Instant, MissedTickBehavior};
+use tokio_util::sync::CancellationToken;
+
+/// A unit of periodic maintenance work.
+#[async_trait]
+pub trait Job: Send + Sync + 'static {
+    /// Short name used in logs (e.g. `"expire-bans"`).
+    fn name(&self) -> &'static str;
+
+    /// Do one round of work.
+    async fn run(&self) -> anyhow::Result<()>;
+}
+
+/// A set of jobs and their intervals, started together.
+pub struct Scheduler {
+    jobs:     Vec<(Arc<dyn Job>, Duration)>,
+    reporter: Arc<dyn ErrorReporter>,
+}
+
+impl Default for Scheduler {
+    fn default() -> Self {
+        Self { jobs: Vec::new(), reporter: Arc::new(NoopErrorReporter) }
+    }
+}
+
+impl Scheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Report failed and panicking runs to `reporter`.
+    pub fn with_error_reporter(mut self, reporter: Arc<dyn ErrorReporter>) -> Self {
+        self.reporter = reporter;
+        self
+    }
+
+    /// Run `job` every `interval`. A zero interval leaves the job disabled.
+    pub fn every(mut self, interval: Duration, job: impl Job) -> Self {
+        if interval.is_zero() {
+            tracing::info!(job = job.name(), "scheduled job disabled");
+        } else {
+            self.jobs.push((Arc::new(job), interval));
+        }
+        self
+    }
+
+    /// Number of enabled jobs.
+    pub fn len(&self) -> usize {
+        self.jobs.len()
+    }
+
+    /// Spawn every job, each running until `stop` is cancelled. A run already
+    /// in progress when `stop` fires finishes first.
+    pub fn start(self, stop: CancellationToken) -> Vec<JoinHandle<()>> {
+        self.jobs
+            .into_iter()
+            .map(|(job, interval)| tokio::spawn(run_every(job, interval, self.reporter.clone(), stop.clone())))
+            .collect()
+    }
+}
+
+async fn run_every(job: Arc<dyn Job>, interval: Duration, reporter: Arc<dyn ErrorReporter>, stop: CancellationToken) {
+    let mut ticker = tokio::time::interval_at(Instant::now() + first_delay(interval), interval);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    loop {
+        tokio::select! {
+            _ = stop.cancelled() => break,
+            _ = ticker.tick() => {}
+        }
+        let started = Instant::now();
+        let (message, panic) = match AssertUnwindSafe(job.run()).catch_unwind().await {
+            Ok(Ok(())) => {
+                tracing::debug!(job = job.name(), took_ms = started.elapsed().as_millis() as u64, "scheduled job finished");
+                continue;
+            }
+            Ok(Err(e)) => {
+                tracing::warn!(job = job.name(), error = %e, "scheduled job failed");
+                (format!("{e:#}"), false)
+            }
+            Err(payload) => {
+                let message = panic_message(payload.as_ref());
+                tracing::error!(job = job.name(), panic = %message, "scheduled job panicked");
+                (message, true)
+            }
+        };
+        reporter.report(&ErrorReport { message, panic, context: vec![("job", job.name().to_owned())] });
+    }
+}
+
+/// Delay before a job's first run: a random point in the second half of its
+/// first interval, keeping startup quiet while spreading jobs apart.
+fn first_delay(interval: Duration) -> Duration {
+    let half = interval / 2;
+    half + half.mul_f64(rand::random_range(0.0..1.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use std::sync::Mutex;
+
+    struct Counting {
+        runs: Arc<AtomicUsize>,
+        fail: bool,
+    }
+
+    #[derive(Default)]
+    struct Recording(Mutex<Vec<ErrorReport>>);
+
+    impl ErrorReporter for Recording {
+        fn report(&self, report: &ErrorReport) {
+            self.0.lock().unwrap().push(report.clone());
+        }
+    }
+
+    struct Panicking;
+
+    #[async_trait]
+    impl Job for Panicking {
+        fn name(&self) -> &'static str {
+            "panicking"
+        }
+
+        async fn run(&self) -> anyhow::Result<()> {
+            panic!("job blew up");
+        }
+    }
+
+    #[async_trait]
+    impl Job for Counting {
+        fn name(&self) -> &'static str {
+            "counting"
+        }
+
+        async fn run(&self) -> anyhow::Result<()> {
+            self.runs.fetch_add(1, Ordering::SeqCst);
+            if self.fail {
+                anyhow::bail!("always fails");
+            }
+            Ok(())
+        }
+    }
+
+    fn counting(fail: bool) -> (Counting, Arc<AtomicUsize>) {
+        let runs = Arc::new(AtomicUsize::new(0));
+        (Counting { runs: runs.clone(), fail }, runs)
+    }
+
+    #[test]
+    fn first_delay_falls_in_the_second_half_of_the_interval() {
+        let interval = Duration::from_secs(60);
+        for _ in 0..100 {
+            let delay = first_delay(interval);
+            assert!(delay >= Duration::from_secs(30) && delay <= interval, "{delay:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn job_repeats_until_stopped() {
+        let (job, runs) = counting(false);
+        let stop = CancellationToken::new();
+        let handles = Scheduler::new().every(Duration::from_millis(20), job).start(stop.clone());
+
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        assert!(runs.load(Ordering::SeqCst) >= 3);
+
+        stop.cancel();
+        for handle in handles {
+            handle.await.unwrap();
+        }
+        let stopped_at = runs.load(Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        assert_eq!(runs.load(Ordering::SeqCst), stopped_at);
+    }
+
+    #[tokio::test]
+    async fn failing_job_keeps_its_schedule() {
+        let (job, runs) = counting(true);
+        let stop = CancellationToken::new();
+        let _handles = Scheduler::new().every(Duration::from_millis(20), job).start(stop.clone());
+
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        assert!(runs.load(Ordering::SeqCst) >= 3);
+        stop.cancel();
+    }
+
+    #[tokio::test]
+    async fn failed_runs_are_reported_with_the_job_name() {
+        let (job, _runs) = counting(true);
+        let reporter = Arc::new(Recording::default());
+        let stop = CancellationToken::new();
+        let _handles = Scheduler::new()
+            .with_error_reporter(reporter.clone())
+            .every(Duration::from_millis(20), job)
+            .start(stop.clone());
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        stop.cancel();
+        let reports = reporter.0.lock().unwrap();
+        assert!(!reports.is_empty());
+        assert_eq!(reports[0].message, "always fails");
+        assert!(!reports[0].panic);
+        assert_eq!(reports[0].context, vec![("job", "counting".to_owned())]);
+    }
+
+    #[tokio::test]
+    async fn panicking_job_is_reported_and_keeps_its_schedule() {
+        let reporter = Arc::new(Recording::default());
+        let stop = CancellationToken::new();
+        let _handles = Scheduler::new()
+            .with_error_reporter(reporter.clone())
+            .every(Duration::from_millis(20), Panicking)
+            .start(stop.clone());
+
+        // Polled rather than slept: printing a panic's backtrace can take
+        // longer than the interval.
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while reporter.0.lock().unwrap().len() < 2 && Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        stop.cancel();
+        let reports = reporter.0.lock().unwrap();
+        assert!(reports.len() >= 2, "{} reports", reports.len());
+        assert!(reports.iter().all(|r| r.panic && r.message == "job blew up"));
+    }
+
+    #[tokio::test]
+    async fn zero_interval_disables_the_job() {
+        let (job, _runs) = counting(false);
+        let scheduler = Scheduler::new().every(Duration::ZERO, job);
+        assert_eq!(scheduler.len(), 0);
+        assert!(scheduler.start(CancellationToken::new()).is_empty());
+    }
+}
@@ -0,0 +1,276 @@
+# English UI strings — the source every other locale translates, and the
+# fallback for messages a translation lacks.
+#
+# Messages are plain text; templates escape them like any other value.
+
+## Site navigation (base.html)
+
+nav-overboard = [overboard]
+nav-all = [all]
+nav-watched = [watched]
+nav-settings = [settings]
+nav-login = [login]
+nav-catalog = [catalog]
+
+## Board navigation
+
+board-index = [Index]
+board-back-to-index = [← Index]
+board-catalog = [Catalog]
+board-archive = [Archive]
+board-stats = [Stats]
+board-log = [Mod Log]
+board-search = [Search]
+board-search-placeholder = Search /{ $board }/…
+board-rules = Rules:
+board-catalog-title = Catalog
+blotter-title = Blotter
+tag-sticky = [STICKY]
+tag-closed = [CLOSED]
+tag-archived = [ARCHIVED]
+
+## Post forms
+
+form-new-thread = New Thread
+form-reply-to-thread = Reply to Thread
+form-name = Name
+form-name-placeholder = Anonymous
+form-name-tripcode-placeholder = Anonymous  (use #pass for tripcode)
+form-options = Options
+form-subject = Subject
+form-comment = Comment
+form-reply-placeholder = Write your reply...
+form-file = File
+form-spoiler = Spoiler
+form-add-poll = Add a poll
+form-poll-question = Question
+form-poll-options = Options (one per line, 2–10)
+form-post = Post
+form-reply = Reply
+
+## Posts and threads
+
+post-anonymous = Anonymous
+post-capcode-title = Verified staff identity
+post-poster-id-title = Poster ID
+post-you = (You)
+post-link-title = Link to this post
+post-quote-title = Reply quoting this post
+post-flag = [Flag]
+post-spoiler-image = Spoiler image
+post-deleted-user = [Deleted by the poster]
+post-deleted-staff = [Deleted by staff]
+post-edited-by-staff = Edited by staff
+post-delete = [Delete]
+post-delete-title = Delete your post
+post-open-thread = [Open Thread →]
+post-view-thread = [View Thread →]
+thread-replies =
+    { $count ->
+        [one] { $count } reply
+       *[other] { $count } replies
+    }
+thread-closed-notice = This thread is closed.
+thread-back-to-board = [Back to /{ $board }/]
+thread-reply-link = [Reply]
+thread-auto-update = Auto-update
+thread-auto-update-title = Automatically check for new posts
+thread-watch = [Watch]
+thread-unwatch = [Unwatch]
+thread-watch-title = Track new replies to this thread on your watched threads page
+thread-default-title = Thread
+poll-vote = Vote
+poll-votes =
+    { $count ->
+        [one] { $count } vote
+       *[other] { $count } votes
+    }
+
+## Pagination
+
+page-prev = [← Prev]
+page-next = [Next →]
+page-of = Page { $page } of { $total }
+
+## Catalog
+
+catalog-no-image = No image
+catalog-thumbnail = Thread thumbnail
+catalog-sort = Sort by
+catalog-sort-bump = Bump order
+catalog-sort-reply-count = Reply count
+catalog-sort-creation = Creation date
+catalog-sort-random = Random
+catalog-filter-placeholder = Filter threads
+catalog-filter-button = Apply
+catalog-no-matches = No threads match the filter.
+
+## Archive
+
+archive-title = Archive
+archive-heading = Thread Archive
+archive-intro = Threads are archived here when the board reaches its thread limit. Archived threads are read-only.
+archive-empty = No archived threads yet.
+
+## Board statistics
+
+stats-title = Statistics
+stats-totals = Last 30 days — posts: { $posts }, files: { $files } ({ $size })
+stats-hourly = Last 24 hours
+stats-daily = Last 30 days
+stats-hour = Hour (UTC)
+stats-day = Day (UTC)
+stats-posts = Posts
+stats-posters = Posters
+stats-files = Files
+stats-size = Size
+stats-note = Posters are counted by IP per hour or day. Figures are updated every few minutes.
+
+## Public mod log
+
+log-title = Mod Log
+log-intro = Moderation actions on this board, newest first. Staff and posters are not named.
+log-empty = No moderation actions yet.
+log-when = When
+log-action = Action
+log-target = Target
+log-reason = Reason
+log-thread = Thread
+log-board = Board settings
+log-action-delete-post = Post deleted
+log-action-edit-post = Post edited
+log-action-delete-thread = Thread deleted
+log-action-sticky-thread = Thread stickied or unstickied
+log-action-close-thread = Thread locked or unlocked
+log-action-cycle-thread = Thread cycle toggled
+log-action-pin-post = Post pinned or unpinned
+log-action-move-posts = Posts moved
+log-action-ban-ip = Poster banned
+log-action-ban-hash = File banned
+log-action-update-board-config = Board settings changed
+
+## Homepage
+
+home-boards = Boards
+home-category-other = Other
+home-nsfw = NSFW
+home-no-boards = No boards yet.
+home-recent-posts = Recent posts
+home-no-posts = Nothing has been posted yet.
+home-more-posts = [More on the overboard →]
+home-stat-boards =
+    { $count ->
+        [one] { $count } board
+       *[other] { $count } boards
+    }
+home-stat-threads =
+    { $count ->
+        [one] { $count } thread
+       *[other] { $count } threads
+    }
+home-stat-posts =
+    { $count ->
+        [one] { $count } post
+       *[other] { $count } posts
+    }
+home-stat-posts-today = { $count } in the last 24 hours
+
+## Watched threads
+
+watched-title = Watched threads
+watched-empty = You are not watching any threads. Use [Watch] at the bottom of a thread to add it here.
+watched-unread =
+    { $count ->
+        [one] { $count } new reply
+       *[other] { $count } new replies
+    }
+watched-no-unread = No new replies
+
+## Overboard
+
+overboard-title = Overboard — Recent Posts
+all-title = /all/ — Recently Bumped Threads
+all-empty = No threads yet.
+
+## Search
+
+search-title = Search: { $query }
+search-heading = Search Results
+search-placeholder = Search posts…
+search-empty = No posts matched “{ $query }” on /{ $board }/.
+search-result-count =
+    { $count ->
+        [one] { $count } result for
+       *[other] { $count } results for
+    }
+
+## Ban notice
+
+ban-title = Banned
+ban-status-title = Ban status
+ban-heading = You are banned
+ban-intro = You have been banned from posting on { $boards } for the following reason:
+ban-scope-all = all boards
+ban-issued = Issued
+ban-expires = Expires
+ban-automatic = Automatic
+ban-permanent = Never (permanent)
+ban-browse = You can still browse the site. Posting is blocked until the ban expires.
+ban-none-heading = You are not banned
+ban-none = There is no active ban on your connection.
+ban-return-home = [Return home]
+
+## Moderator warning
+
+warning-heading = A moderator has warned you
+warning-intro = Please read the following before posting again:
+warning-acknowledge = I understand
+
+## Error pages
+
+error-403-heading = Forbidden
+error-403 = You do not have permission to view this page.
+error-404-heading = Not Found
+error-404 = There is nothing here. The thread may have been deleted or archived.
+error-429-heading = Slow Down
+error-429 =
+    { $count ->
+        [one] You are doing that too often. Try again in { $count } second.
+       *[other] You are doing that too often. Try again in { $count } seconds.
+    }
+error-500-heading = Server Error
+error-500 = Something went wrong on our end. Try again in a moment.
+error-return-home = [Return home]
+
+## Accounts
+
+login-title = Login
+login-heading = Staff Login
+account-username = Username
+account-password = Password
+login-button = Login
+login-otp = Two-factor code
+login-otp-placeholder = 6-digit code or a recovery code
+login-sso = Log in with { $provider }
+totp-title = Two-Factor Authentication
+totp-intro = Protect this account with a code from an authenticator app, asked for at every login after the password.
+totp-enabled = Two-factor authentication is on.
+totp-disabled = Two-factor authentication is off.
+totp-start = Set up
+totp-scan = Add this key to your authenticator app, or open the link on the device it runs on:
+totp-secret = Key
+totp-confirm = Enter the code the app shows to finish:
+totp-confirm-button = Turn on
+totp-recovery = Save these recovery codes somewhere safe. Each one logs you in once if you lose the app. They will not be shown again.
+totp-disable = Enter a code from the app, or a recovery code, to turn two-factor authentication off:
+totp-disable-button = Turn off
+totp-code = Code
+register-title = Register
+register-heading = Create Account
+register-intro = Register for a user account. Posting is anonymous — this account is for tracking your staff requests and receiving announcements.
+register-username-placeholder = 3–32 characters, letters/numbers/underscore
+register-password-placeholder = Minimum 12 characters
+register-confirm = Confirm Password
+register-confirm-placeholder = Repeat password
+register-button = Register
+register-have-account = Already have an account?
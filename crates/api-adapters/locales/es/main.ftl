@@ -0,0 +1,274 @@
+# Spanish UI strings. Message IDs match locales/en/main.ftl; anything missing
+# here is shown in English.
+
+## Site navigation (base.html)
+
+nav-overboard = [overboard]
+nav-all = [todo]
+nav-watched = [seguidos]
+nav-settings = [ajustes]
+nav-login = [entrar]
+nav-catalog = [catálogo]
+
+## Board navigation
+
+board-index = [Índice]
+board-back-to-index = [← Índice]
+board-catalog = [Catálogo]
+board-archive = [Archivo]
+board-stats = [Estadísticas]
+board-log = [Registro]
+board-search = [Buscar]
+board-search-placeholder = Buscar en /{ $board }/…
+board-rules = Reglas:
+board-catalog-title = Catálogo
+blotter-title = Tablón de avisos
+tag-sticky = [FIJO]
+tag-closed = [CERRADO]
+tag-archived = [ARCHIVADO]
+
+## Post forms
+
+form-new-thread = Nuevo hilo
+form-reply-to-thread = Responder al hilo
+form-name = Nombre
+form-name-placeholder = Anónimo
+form-name-tripcode-placeholder = Anónimo  (usa #clave para un tripcode)
+form-options = Opciones
+form-subject = Asunto
+form-comment = Comentario
+form-reply-placeholder = Escribe tu respuesta...
+form-file = Archivo
+form-spoiler = Spoiler
+form-add-poll = Añadir una encuesta
+form-poll-question = Pregunta
+form-poll-options = Opciones (una por línea, 2–10)
+form-post = Publicar
+form-reply = Responder
+
+## Posts and threads
+
+post-anonymous = Anónimo
+post-capcode-title = Identidad de staff verificada
+post-poster-id-title = ID del autor
+post-you = (Tú)
+post-link-title = Enlace a esta publicación
+post-quote-title = Responder citando esta publicación
+post-flag = [Reportar]
+post-spoiler-image = Imagen con spoiler
+post-deleted-user = [Borrado por su autor]
+post-deleted-staff = [Borrado por el staff]
+post-edited-by-staff = Editado por el staff
+post-delete = [Borrar]
+post-delete-title = Borrar tu publicación
+post-open-thread = [Abrir hilo →]
+post-view-thread = [Ver hilo →]
+thread-replies =
+    { $count ->
+        [one] { $count } respuesta
+       *[other] { $count } respuestas
+    }
+thread-closed-notice = Este hilo está cerrado.
+thread-back-to-board = [Volver a /{ $board }/]
+thread-reply-link = [Responder]
+thread-auto-update = Actualizar solo
+thread-auto-update-title = Buscar nuevas respuestas automáticamente
+thread-watch = [Seguir]
+thread-unwatch = [Dejar de seguir]
+thread-watch-title = Ver las nuevas respuestas a este hilo en tu página de hilos seguidos
+thread-default-title = Hilo
+poll-vote = Votar
+poll-votes =
+    { $count ->
+        [one] { $count } voto
+       *[other] { $count } votos
+    }
+
+## Pagination
+
+page-prev = [← Anterior]
+page-next = [Siguiente →]
+page-of = Página { $page } de { $total }
+
+## Catalog
+
+catalog-no-image = Sin imagen
+catalog-thumbnail = Miniatura del hilo
+catalog-sort = Ordenar por
+catalog-sort-bump = Último bump
+catalog-sort-reply-count = Número de respuestas
+catalog-sort-creation = Fecha de creación
+catalog-sort-random = Aleatorio
+catalog-filter-placeholder = Filtrar hilos
+catalog-filter-button = Aplicar
+catalog-no-matches = Ningún hilo coincide con el filtro.
+
+## Archive
+
+archive-title = Archivo
+archive-heading = Archivo de hilos
+archive-intro = Los hilos se archivan aquí cuando el tablón llega a su límite de hilos. Los hilos archivados son de solo lectura.
+archive-empty = Todavía no hay hilos archivados.
+
+## Board statistics
+
+stats-title = Estadísticas
+stats-totals = Últimos 30 días — publicaciones: { $posts }, archivos: { $files } ({ $size })
+stats-hourly = Últimas 24 horas
+stats-daily = Últimos 30 días
+stats-hour = Hora (UTC)
+stats-day = Día (UTC)
+stats-posts = Publicaciones
+stats-posters = Autores
+stats-files = Archivos
+stats-size = Tamaño
+stats-note = Los autores se cuentan por IP en cada hora o día. Las cifras se actualizan cada pocos minutos.
+
+## Public mod log
+
+log-title = Registro de moderación
+log-intro = Acciones de moderación en este tablón, de la más reciente a la más antigua. No se nombra al personal ni a los autores.
+log-empty = Todavía no hay acciones de moderación.
+log-when = Fecha
+log-action = Acción
+log-target = Objetivo
+log-reason = Motivo
+log-thread = Hilo
+log-board = Ajustes del tablón
+log-action-delete-post = Mensaje eliminado
+log-action-edit-post = Mensaje editado
+log-action-delete-thread = Hilo eliminado
+log-action-sticky-thread = Hilo fijado o desfijado
+log-action-close-thread = Hilo cerrado o reabierto
+log-action-cycle-thread = Modo cíclico del hilo cambiado
+log-action-pin-post = Mensaje anclado o desanclado
+log-action-move-posts = Mensajes movidos
+log-action-ban-ip = Autor baneado
+log-action-ban-hash = Archivo baneado
+log-action-update-board-config = Ajustes del tablón cambiados
+
+## Homepage
+
+home-boards = Tablones
+home-category-other = Otros
+home-nsfw = NSFW
+home-no-boards = Todavía no hay tablones.
+home-recent-posts = Publicaciones recientes
+home-no-posts = Todavía no se ha publicado nada.
+home-more-posts = [Más en el overboard →]
+home-stat-boards =
+    { $count ->
+        [one] { $count } tablón
+       *[other] { $count } tablones
+    }
+home-stat-threads =
+    { $count ->
+        [one] { $count } hilo
+       *[other] { $count } hilos
+    }
+home-stat-posts =
+    { $count ->
+        [one] { $count } publicación
+       *[other] { $count } publicaciones
+    }
+home-stat-posts-today = { $count } en las últimas 24 horas
+
+## Watched threads
+
+watched-title = Hilos seguidos
+watched-empty = No sigues ningún hilo. Usa [Seguir] al final de un hilo para añadirlo aquí.
+watched-unread =
+    { $count ->
+        [one] { $count } respuesta nueva
+       *[other] { $count } respuestas nuevas
+    }
+watched-no-unread = Sin respuestas nuevas
+
+## Overboard
+
+overboard-title = Overboard — Publicaciones recientes
+all-title = /all/ — Hilos con actividad reciente
+all-empty = Todavía no hay hilos.
+
+## Search
+
+search-title = Buscar: { $query }
+search-heading = Resultados de la búsqueda
+search-placeholder = Buscar publicaciones…
+search-empty = Ninguna publicación coincide con “{ $query }” en /{ $board }/.
+search-result-count =
+    { $count ->
+        [one] { $count } resultado para
+       *[other] { $count } resultados para
+    }
+
+## Ban notice
+
+ban-title = Baneado
+ban-status-title = Estado del baneo
+ban-heading = Estás baneado
+ban-intro = Se te ha prohibido publicar en { $boards } por el siguiente motivo:
+ban-scope-all = todos los tablones
+ban-issued = Emitido
+ban-expires = Expira
+ban-automatic = Automático
+ban-permanent = Nunca (permanente)
+ban-browse = Puedes seguir navegando por el sitio. No podrás publicar hasta que expire el baneo.
+ban-none-heading = No estás baneado
+ban-none = No hay ningún baneo activo sobre tu conexión.
+ban-return-home = [Volver al inicio]
+
+## Moderator warning
+
+warning-heading = Un moderador te ha advertido
+warning-intro = Lee lo siguiente antes de volver a publicar:
+warning-acknowledge = Entendido
+
+## Error pages
+
+error-403-heading = Prohibido
+error-403 = No tienes permiso para ver esta página.
+error-404-heading = No encontrado
+error-404 = Aquí no hay nada. Puede que el hilo se haya borrado o archivado.
+error-429-heading = Más despacio
+error-429 =
+    { $count ->
+        [one] Lo estás haciendo demasiado a menudo. Vuelve a intentarlo en { $count } segundo.
+       *[other] Lo estás haciendo demasiado a menudo. Vuelve a intentarlo en { $count } segundos.
+    }
+error-500-heading = Error del servidor
+error-500 = Algo ha fallado por nuestra parte. Vuelve a intentarlo en un momento.
+error-return-home = [Volver al inicio]
+
+## Accounts
+
+login-title = Entrar
+login-heading = Acceso del staff
+account-username = Usuario
+account-password = Contraseña
+login-button = Entrar
+login-otp = Código de verificación
+login-otp-placeholder = Código de 6 dígitos o de recuperación
+login-sso = Iniciar sesión con { $provider }
+totp-title = Verificación en dos pasos
+totp-intro = Protege esta cuenta con un código de una app de autenticación, que se pedirá en cada inicio de sesión después de la contraseña.
+totp-enabled = La verificación en dos pasos está activada.
+totp-disabled = La verificación en dos pasos está desactivada.
+totp-start = Configurar
+totp-scan = Añade esta clave a tu app de autenticación, o abre el enlace en el dispositivo donde la usas:
+totp-secret = Clave
+totp-confirm = Escribe el código que muestra la app para terminar:
+totp-confirm-button = Activar
+totp-recovery = Guarda estos códigos de recuperación en un lugar seguro. Cada uno permite entrar una vez si pierdes la app. No se volverán a mostrar.
+totp-disable = Escribe un código de la app, o uno de recuperación, para desactivar la verificación en dos pasos:
+totp-disable-button = Desactivar
+totp-code = Código
+register-title = Registro
+register-heading = Crear cuenta
+register-intro = Regístrate para tener una cuenta de usuario. Publicar sigue siendo anónimo: esta cuenta sirve para seguir tus solicitudes de staff y recibir anuncios.
+register-username-placeholder = 3–32 caracteres: letras, números o guion bajo
+register-password-placeholder = Mínimo 12 caracteres
+register-confirm = Confirmar contraseña
+register-confirm-placeholder = Repite la contraseña
+register-button = Registrarse
+register-have-account = ¿Ya tienes una cuenta?
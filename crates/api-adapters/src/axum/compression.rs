@@ -0,0 +1,46 @@
+//! Response compression.
+//!
+//! Thread pages with hundreds of posts run to hundreds of kilobytes of HTML,
+//! which brotli or gzip shrinks several-fold. Only text is compressed: pages,
+//! JSON, CSS, JavaScript, feeds and SVG. Images, video, audio and archives are
+//! compressed already and pass through untouched, as do streamed events and
+//! responses below the size threshold, where the encoding costs more than it
+//! saves. The encoding follows the client's `Accept-Encoding`.
+
+use axum::http::{header, Extensions, HeaderMap, StatusCode, Version};
+use tower_http::compression::{
+    predicate::{Predicate, SizeAbove},
+    CompressionLayer,
+};
+
+/// Compression of text responses larger than `min_bytes`, with the enabled
+/// encodings.
+pub fn compression_layer(gzip: bool, br: bool, min_bytes: u16) -> CompressionLayer<impl Predicate> {
+    let text_only = |_: StatusCode, _: Version, headers: &HeaderMap, _: &Extensions| is_compressible(headers);
+    CompressionLayer::new()
+        .gzip(gzip)
+        .br(br)
+        .compress_when(SizeAbove::new(min_bytes).and(text_only))
+}
+
+/// Whether the response's `Content-Type` is text worth compressing.
+pub fn is_compressible(headers: &HeaderMap) -> bool {
+    let Some(content_type) = headers.get(header::CONTENT_TYPE).and_then(|v| v.to_str().ok()) else {
+        return false;
+    };
+    let essence = content_type.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+    let Some((kind, subtype)) = essence.split_once('/') else {
+        return false;
+    };
+    match kind {
+        // Server-sent events are flushed as they happen; compression would hold them back.
+        "text" => subtype != "event-stream",
+        "application" => {
+            matches!(subtype, "json" | "javascript" | "xml" | "x-ndjson")
+                || subtype.ends_with("+json")
+                || subtype.ends_with("+xml")
+        }
+        "image" => subtype == "svg+xml",
+        _ => false,
+    }
+}
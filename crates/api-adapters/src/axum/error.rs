@@ -4,6 +4,7 @@ use axum::http::{HeaderValue, StatusCode};
 use axum::response::{IntoResponse, Response};
 use axum::Json;
 
+use crate::axum::middleware::error_report::InternalErrorCause;
 use crate::common::errors::{ApiError, ErrorBody};
 
 /// Extension trait for easy result conversion in handlers.
@@ -24,28 +25,34 @@ impl IntoResponse for ApiError {
             ApiError::Unauthorized => {
                 (StatusCode::UNAUTHORIZED, "UNAUTHORIZED", "authentication required".to_owned())
             }
+            ApiError::TwoFactor(msg) => {
+                (StatusCode::UNAUTHORIZED, "TOTP_REQUIRED", msg.clone())
+            }
             ApiError::Forbidden => {
                 (StatusCode::FORBIDDEN, "FORBIDDEN", "permission denied".to_owned())
             }
+            ApiError::Csrf => {
+                (StatusCode::FORBIDDEN, "CSRF_FAILED", self.to_string())
+            }
             ApiError::NotFound(resource) => {
                 (StatusCode::NOT_FOUND, "NOT_FOUND", format!("not found: {resource}"))
             }
             ApiError::Conflict(msg) => {
                 (StatusCode::CONFLICT, "CONFLICT", msg.clone())
             }
+            ApiError::PayloadTooLarge(msg) => {
+                (StatusCode::PAYLOAD_TOO_LARGE, "PAYLOAD_TOO_LARGE", msg.clone())
+            }
             ApiError::UnprocessableEntity(msg) => {
                 (StatusCode::UNPROCESSABLE_ENTITY, "VALIDATION_ERROR", msg.clone())
             }
             ApiError::RateLimited { retry_after_secs } => {
-                let mut resp = (
-                    StatusCode::TOO_MANY_REQUESTS,
-                    Json(ErrorBody {
-                        error:   "RATE_LIMITED".to_owned(),
-                        message: format!("rate limit exceeded; retry after {retry_after_secs}s"),
-                        details: None,
-                    }),
-                )
-                    .into_response();
+                let mut body = ErrorBody::new(
+                    "RATE_LIMITED",
+                    format!("rate limit exceeded; retry after {retry_after_secs}s"),
+                );
+                body.error.retry_after = Some(u64::from(*retry_after_secs));
+                let mut resp = (StatusCode::TOO_MANY_REQUESTS, Json(body)).into_response();
                 resp.headers_mut().insert(
                     axum::http::header::RETRY_AFTER,
                     HeaderValue::from_str(&retry_after_secs.to_string())
@@ -53,16 +60,13 @@ impl IntoResponse for ApiError {
                 );
                 return resp;
             }
-            ApiError::Banned { reason, expires_at } => {
-                return (
-                    StatusCode::FORBIDDEN,
-                    Json(ErrorBody {
-                        error:   "BANNED".to_owned(),
-                        message: format!("you are banned: {reason}"),
-                        details: Some(serde_json::json!({ "expires_at": expires_at })),
-                    }),
-                )
-                    .into_response();
+            ApiError::Banned { reason, issued_at, expires_at } => {
+                let mut body = ErrorBody::new("BANNED", format!("you are banned: {reason}"));
+                body.error.details = Some(serde_json::json!({
+                    "issued_at":  issued_at,
+                    "expires_at": expires_at,
+                }));
+                return (StatusCode::FORBIDDEN, Json(body)).into_response();
             }
             ApiError::Internal(msg) => {
                 tracing::error!(error = %msg, "internal server error");
@@ -80,14 +84,11 @@ impl IntoResponse for ApiError {
             }
         };
 
-        (
-            status,
-            Json(ErrorBody {
-                error:   code.to_owned(),
-                message,
-                details: None,
-            }),
-        )
-            .into_response()
+        let mut resp = (status, Json(ErrorBody::new(code, message))).into_response();
+        // The cause stays out of the body but reaches `error_report_middleware`.
+        if let ApiError::Internal(msg) = self {
+            resp.extensions_mut().insert(InternalErrorCause(msg));
+        }
+        resp
     }
 }
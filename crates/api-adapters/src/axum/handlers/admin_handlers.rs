@@ -13,7 +13,9 @@ use crate::common::{
     errors::ApiError,
     pagination::PageResponse,
 };
+use crate::axum::i18n::I18n;
 use crate::axum::middleware::auth::AdminUser;
+use crate::axum::middleware::site::CurrentSite;
 use domains::models::{BoardId, Page, User, UserId};
 use domains::ports::{AuthProvider, StaffRequestRepository};
 use services::staff_request::StaffRequestService;
@@ -144,6 +146,8 @@ where
 pub async fn admin_dashboard<UR, AP, BR, RR>(
     State(s): State<AdminDashboardState<UR, AP, BR, RR>>,
     admin: AdminUser,
+    CurrentSite(site): CurrentSite,
+    i18n: I18n,
 ) -> axum::response::Response
 where
     UR: domains::ports::UserRepository,
@@ -154,7 +158,7 @@ where
     use axum::response::IntoResponse;
     use crate::axum::templates::{DashboardTemplate, DashboardBoard, DashboardUser};
 
-    let all_boards = match s.board_svc.list_boards(domains::models::Page::new(1)).await {
+    let all_boards = match s.board_svc.list_boards(site.id, domains::models::Page::new(1)).await {
         Ok(p) => p.items,
         Err(_) => vec![],
     };
@@ -163,11 +167,15 @@ where
         Err(_) => vec![],
     };
 
-    let boards = all_boards.iter().map(|b| DashboardBoard {
-        board: b.clone(),
-        can_manage: true,
-        can_manage_volunteers: true,
-    }).collect();
+    let mut boards = Vec::with_capacity(all_boards.len());
+    for b in &all_boards {
+        boards.push(DashboardBoard {
+            board: b.clone(),
+            can_manage: true,
+            can_manage_volunteers: true,
+            media_usage: media_usage_line(s.board_svc.as_ref(), b.id).await,
+        });
+    }
 
     let staff = Some(all_users.iter().map(|u| DashboardUser {
         user: u.clone(),
@@ -185,9 +193,33 @@ where
         messages:          vec![],
         unread_count:      0,
         pending_requests:  Some(s.request_svc.list_pending().await.unwrap_or_default()),
+        i18n,
     }.into_response()
 }
 
+/// `"1.2 GB of 5.0 GB"` (or just the usage for unlimited boards). `None` when
+/// the lookup fails, so a slow query never breaks the dashboard.
+async fn media_usage_line<BR: services::board::BoardRepo>(boards: &BR, board_id: domains::models::BoardId) -> Option<String> {
+    let used = boards.media_usage(board_id).await.ok()?;
+    let quota = boards.get_config(board_id).await.ok().and_then(|c| c.media_quota_bytes());
+    Some(match quota {
+        Some(quota) => format!("{} of {}", format_bytes(used), format_bytes(quota)),
+        None => format_bytes(used),
+    })
+}
+
+/// Human-readable byte count: whole KB below 1 MB, else MB or GB with one decimal.
+fn format_bytes(n: u64) -> String {
+    const KB: u64 = 1024;
+    const MB: u64 = 1024 * KB;
+    const GB: u64 = 1024 * MB;
+    match n {
+        n if n < MB => format!("{} KB", n.div_ceil(KB)),
+        n if n < GB => format!("{:.1} MB", n as f64 / MB as f64),
+        n => format!("{:.1} GB", n as f64 / GB as f64),
+    }
+}
+
 
 /// `GET /admin/audit` — full audit log, paginated.
 ///
@@ -318,3 +350,22 @@ where
 
     Ok(Json(serde_json::json!({ "sent": sent })))
 }
+
+/// `POST /admin/reload` — drop cached board configs and sites so edits made
+/// outside this instance (another instance, `rusty-board site add`, or the
+/// database directly) apply immediately.
+///
+/// Rate limits, link blacklists, spam settings and every other `BoardConfig`
+/// field are re-read on each board's next request. Connections are untouched.
+/// Sending the process `SIGHUP` does the same.
+/// Returns `{ "boards_cleared": N, "sites_cleared": M }`.
+pub async fn reload_config(
+    State(cache): State<Arc<storage_adapters::cache::BoardConfigCache>>,
+    axum::Extension(sites): axum::Extension<Arc<storage_adapters::cache::SiteCache>>,
+    AdminUser(admin): AdminUser,
+) -> Json<serde_json::Value> {
+    let boards_cleared = cache.clear();
+    let sites_cleared = sites.clear();
+    tracing::info!(admin = %admin.username, boards_cleared, sites_cleared, "board config reloaded");
+    Json(serde_json::json!({ "boards_cleared": boards_cleared, "sites_cleared": sites_cleared }))
+}
@@ -0,0 +1,126 @@
+//! Blotter handlers: the site's announcements, managed by admins.
+//!
+//! Routes:
+//!   `GET    /admin/blotter`      — list every entry of the site (JSON)
+//!   `POST   /admin/blotter`      — post an entry (JSON `{message, board_id?}`)
+//!   `DELETE /admin/blotter/:id`  — delete an entry
+//!
+//! Entries for one board drop that board's cached pages; site-wide entries
+//! show up on cached pages once the render cache TTL runs out.
+
+use axum::{
+    extract::{Extension, Path, State},
+    http::StatusCode,
+    Json,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::axum::middleware::{
+    auth::AdminUser,
+    render_cache::{invalidate_board, RenderCacheState},
+    site::CurrentSite,
+};
+use crate::common::{dtos::AnnouncementResponse, errors::ApiError};
+use domains::models::{AnnouncementId, BoardId};
+use domains::ports::AnnouncementRepository;
+use services::announcement::AnnouncementService;
+use services::board::BoardRepo;
+
+// ─── State ────────────────────────────────────────────────────────────────────
+
+/// Shared state for the blotter handlers.
+pub struct BlotterState<AR, BR>
+where
+    AR: AnnouncementRepository,
+    BR: BoardRepo,
+{
+    /// The announcement service.
+    pub svc:    Arc<AnnouncementService<AR>>,
+    /// Resolves the board an entry is posted to.
+    pub boards: Arc<BR>,
+}
+
+impl<AR, BR> Clone for BlotterState<AR, BR>
+where
+    AR: AnnouncementRepository,
+    BR: BoardRepo,
+{
+    fn clone(&self) -> Self { Self { svc: self.svc.clone(), boards: self.boards.clone() } }
+}
+
+/// Body of `POST /admin/blotter`.
+#[derive(Debug, Deserialize)]
+pub struct PostAnnouncementRequest {
+    /// The entry's text.
+    pub message:  String,
+    /// The board to show it on; omitted for every board of the site.
+    #[serde(default)]
+    pub board_id: Option<uuid::Uuid>,
+}
+
+// ─── Handlers ─────────────────────────────────────────────────────────────────
+
+/// `GET /admin/blotter` — every entry of the site, newest first.
+pub async fn list_announcements<AR, BR>(
+    State(s): State<BlotterState<AR, BR>>,
+    _admin: AdminUser,
+    CurrentSite(site): CurrentSite,
+) -> Result<Json<Vec<AnnouncementResponse>>, ApiError>
+where
+    AR: AnnouncementRepository,
+    BR: BoardRepo,
+{
+    let entries = s.svc.list(site.id).await?;
+    Ok(Json(entries.into_iter().map(AnnouncementResponse::from).collect()))
+}
+
+/// `POST /admin/blotter` — post an entry to the site's blotter, or to one
+/// board's. Returns `201` with the entry.
+pub async fn post_announcement<AR, BR>(
+    State(s): State<BlotterState<AR, BR>>,
+    _admin: AdminUser,
+    CurrentSite(site): CurrentSite,
+    render_cache: Option<Extension<RenderCacheState>>,
+    Json(body): Json<PostAnnouncementRequest>,
+) -> Result<(StatusCode, Json<AnnouncementResponse>), ApiError>
+where
+    AR: AnnouncementRepository,
+    BR: BoardRepo,
+{
+    let board_id = match body.board_id {
+        Some(id) => {
+            let board = s.boards.get_by_id(BoardId(id)).await?;
+            // Boards of other sites are not the admin's to post to.
+            if board.site_id != site.id {
+                return Err(ApiError::NotFound(format!("board {id}")));
+            }
+            Some(board.id)
+        }
+        None => None,
+    };
+    let entry = s.svc.post(site.id, board_id, &body.message).await?;
+    if let (Some(Extension(cache)), Some(board)) = (render_cache, entry.board_id) {
+        invalidate_board(&cache, board).await;
+    }
+    Ok((StatusCode::CREATED, Json(AnnouncementResponse::from(entry))))
+}
+
+/// `DELETE /admin/blotter/:id` — delete an entry of the site.
+pub async fn delete_announcement<AR, BR>(
+    State(s): State<BlotterState<AR, BR>>,
+    _admin: AdminUser,
+    CurrentSite(site): CurrentSite,
+    render_cache: Option<Extension<RenderCacheState>>,
+    Path(id): Path<uuid::Uuid>,
+) -> Result<StatusCode, ApiError>
+where
+    AR: AnnouncementRepository,
+    BR: BoardRepo,
+{
+    let entry = s.svc.delete(site.id, AnnouncementId(id)).await?;
+    if let (Some(Extension(cache)), Some(board)) = (render_cache, entry.board_id) {
+        invalidate_board(&cache, board).await;
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,50 @@
+//! API key management handlers (admin).
+//!
+//! Routes:
+//!   `GET  /admin/api-keys`             — list every key (JSON)
+//!   `POST /admin/api-keys`             — issue a key; the only response that carries it
+//!   `POST /admin/api-keys/:id/revoke`  — revoke a key
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use std::sync::Arc;
+
+use crate::axum::middleware::auth::AdminUser;
+use crate::common::dtos::{ApiKeyResponse, CreateApiKeyRequest, IssuedApiKeyResponse};
+use crate::common::errors::ApiError;
+use domains::models::ApiKeyId;
+use domains::ports::ApiKeyRepository;
+use services::api_key::ApiKeyService;
+
+/// `GET /admin/api-keys` — every key, newest first, revoked ones included.
+pub async fn list_api_keys<AK: ApiKeyRepository>(
+    State(svc): State<Arc<ApiKeyService<AK>>>,
+    _admin: AdminUser,
+) -> Result<Json<Vec<ApiKeyResponse>>, ApiError> {
+    let keys = svc.list().await?;
+    Ok(Json(keys.into_iter().map(ApiKeyResponse::from).collect()))
+}
+
+/// `POST /admin/api-keys` — issue a key. Returns `201` with the key, which
+/// cannot be shown again.
+pub async fn create_api_key<AK: ApiKeyRepository>(
+    State(svc): State<Arc<ApiKeyService<AK>>>,
+    AdminUser(admin): AdminUser,
+    Json(body): Json<CreateApiKeyRequest>,
+) -> Result<(StatusCode, Json<IssuedApiKeyResponse>), ApiError> {
+    let (api_key, key) = svc.issue(&body.name, &body.scopes, body.rate_limit_per_min, admin.id).await?;
+    Ok((StatusCode::CREATED, Json(IssuedApiKeyResponse { key, api_key: api_key.into() })))
+}
+
+/// `POST /admin/api-keys/:id/revoke` — revoke a key; it stops working at once.
+pub async fn revoke_api_key<AK: ApiKeyRepository>(
+    State(svc): State<Arc<ApiKeyService<AK>>>,
+    _admin: AdminUser,
+    Path(id): Path<uuid::Uuid>,
+) -> Result<Json<ApiKeyResponse>, ApiError> {
+    let key = svc.revoke(ApiKeyId(id)).await?;
+    Ok(Json(key.into()))
+}
@@ -1,11 +1,15 @@
-//! Authentication handlers: `POST /auth/login`, `POST /auth/refresh`, `GET /auth/logout`.
+//! Authentication handlers: `POST /auth/login`, `POST /auth/refresh`, `GET /auth/logout`,
+//! and two-factor enrollment under `/auth/totp`.
 
 use axum::{extract::{Extension, State}, http::header, response::IntoResponse, Json};
 use std::sync::Arc;
 
+use crate::axum::i18n::I18n;
 use crate::axum::middleware::{auth::AuthenticatedUser, login_guard::LoginGuard};
 use crate::common::{
-    dtos::{LoginRequest, LoginResponse, RegisterRequest},
+    dtos::{
+        LoginRequest, LoginResponse, RegisterRequest, TotpCodeRequest, TotpConfirmResponse, TotpSetupResponse,
+    },
     errors::ApiError,
 };
 use domains::ports::AuthProvider;
@@ -13,9 +17,12 @@ use services::user::UserService;
 
 /// `POST /auth/login` — verify credentials, issue a JWT, and set an HttpOnly cookie.
 ///
-/// Accepts `Content-Type: application/json` with `{ "username": "...", "password": "..." }`.
+/// Accepts `Content-Type: application/json` with `{ "username": "...", "password": "..." }`,
+/// plus `"otp"` for accounts with two-factor auth enabled.
 /// On success: returns `200` with a `LoginResponse` body **and** sets the `token` cookie.
-/// On failure: returns `401`. After 5 consecutive failures the account is locked for 10 min.
+/// On failure: returns `401` (`TOTP_REQUIRED` when only the code is missing or wrong).
+/// After 5 consecutive failures the account is locked for 10 min; a missing
+/// code is not a failure, a wrong one is.
 ///
 /// The cookie approach lets browser-based sessions work without JavaScript
 /// needing to manually attach `Authorization` headers on every navigation.
@@ -33,7 +40,7 @@ where
         return Err(ApiError::RateLimited { retry_after_secs: secs as u32 });
     }
 
-    match user_service.login(&req.username, &req.password).await {
+    match user_service.login(&req.username, &req.password, req.otp.as_deref()).await {
         Ok((token, claims)) => {
             guard.record_success(&req.username);
             let ttl_secs = claims.exp - chrono::Utc::now().timestamp();
@@ -47,6 +54,7 @@ where
                 Json(LoginResponse { token: token.0, expires_at: claims.exp }),
             ).into_response())
         }
+        Err(e @ services::user::UserError::TotpRequired) => Err(ApiError::from(e)),
         Err(e) => {
             guard.record_failure(&req.username);
             Err(ApiError::from(e))
@@ -93,19 +101,30 @@ pub async fn logout() -> impl IntoResponse {
     )
 }
 
-/// `GET /auth/login` — render the login page (HTML).
-pub async fn login_page() -> axum::response::Response {
+/// A single sign-on option offered on the login page. The composition root
+/// adds it as an `Extension` on the auth routes when an SSO plugin is set up.
+#[derive(Debug, Clone)]
+pub struct SsoLogin {
+    /// Provider name shown on the button.
+    pub label: String,
+    /// Where the button starts the login, e.g. `/auth/oidc/login`.
+    pub href:  String,
+}
+
+/// `GET /auth/login` — render the login page (HTML), with a single sign-on
+/// button when `SsoLogin` is set.
+pub async fn login_page(i18n: I18n, sso: Option<Extension<SsoLogin>>) -> axum::response::Response {
     use crate::axum::templates::LoginTemplate;
-    LoginTemplate { error: None }.into_response()
+    LoginTemplate { error: None, sso: sso.map(|Extension(sso)| sso), i18n }.into_response()
 }
 
 /// `GET /auth/register` — render the registration page.
 ///
 /// Returns 404 (via redirect to login) when open registration is disabled;
 /// the gating is done in the route layer via `Settings.open_registration`.
-pub async fn register_page() -> axum::response::Response {
+pub async fn register_page(i18n: I18n) -> axum::response::Response {
     use crate::axum::templates::RegisterTemplate;
-    RegisterTemplate { error: None }.into_response()
+    RegisterTemplate { error: None, i18n }.into_response()
 }
 
 /// `POST /auth/register` — create a new `Role::User` account.
@@ -172,3 +191,72 @@ pub async fn me(
         }
     }
 }
+
+/// `GET /auth/totp` — the two-factor settings page for the logged-in account.
+pub async fn totp_page<UR, AP>(
+    State(user_service): State<Arc<UserService<UR, AP>>>,
+    AuthenticatedUser(current_user): AuthenticatedUser,
+    i18n: I18n,
+) -> Result<axum::response::Response, ApiError>
+where
+    UR: domains::ports::UserRepository,
+    AP: AuthProvider,
+{
+    let enabled = user_service.totp_enabled(current_user.user_id()).await.map_err(ApiError::from)?;
+    Ok(crate::axum::templates::TotpTemplate { enabled, i18n }.into_response())
+}
+
+/// `POST /auth/totp/setup` — start enrollment; returns the secret to add to an
+/// authenticator app. `400` if two-factor auth is already enabled.
+pub async fn totp_setup<UR, AP>(
+    State(user_service): State<Arc<UserService<UR, AP>>>,
+    AuthenticatedUser(current_user): AuthenticatedUser,
+) -> Result<Json<TotpSetupResponse>, ApiError>
+where
+    UR: domains::ports::UserRepository,
+    AP: AuthProvider,
+{
+    let enrollment = user_service
+        .begin_totp_enrollment(current_user.user_id())
+        .await
+        .map_err(ApiError::from)?;
+    Ok(Json(TotpSetupResponse { secret: enrollment.secret, uri: enrollment.uri }))
+}
+
+/// `POST /auth/totp/confirm` — finish enrollment with a code from the app.
+///
+/// Returns the recovery codes; they are not stored in plain text and cannot be
+/// shown again.
+pub async fn totp_confirm<UR, AP>(
+    State(user_service): State<Arc<UserService<UR, AP>>>,
+    AuthenticatedUser(current_user): AuthenticatedUser,
+    Json(req): Json<TotpCodeRequest>,
+) -> Result<Json<TotpConfirmResponse>, ApiError>
+where
+    UR: domains::ports::UserRepository,
+    AP: AuthProvider,
+{
+    let recovery_codes = user_service
+        .confirm_totp_enrollment(current_user.user_id(), &req.code)
+        .await
+        .map_err(ApiError::from)?;
+    Ok(Json(TotpConfirmResponse { recovery_codes }))
+}
+
+/// `POST /auth/totp/disable` — turn two-factor auth off, given a current TOTP
+/// or recovery code. Returns `204`.
+pub async fn totp_disable<UR, AP>(
+    State(user_service): State<Arc<UserService<UR, AP>>>,
+    AuthenticatedUser(current_user): AuthenticatedUser,
+    Json(req): Json<TotpCodeRequest>,
+) -> Result<axum::http::StatusCode, ApiError>
+where
+    UR: domains::ports::UserRepository,
+    AP: AuthProvider,
+{
+    user_service
+        .disable_totp(current_user.user_id(), &req.code)
+        .await
+        .map_err(ApiError::from)?;
+    Ok(axum::http::StatusCode::NO_CONTENT)
+}
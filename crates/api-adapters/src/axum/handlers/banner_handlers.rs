@@ -0,0 +1,169 @@
+//! Board banner handlers: the rotating banner and its management by board owners.
+//!
+//! Routes (all board-scoped, run through the board-config middleware):
+//!   `GET    /board/:slug/banner`       — redirect to a random banner image
+//!   `GET    /board/:slug/banners`      — list the board's banners (JSON)
+//!   `POST   /board/:slug/banners`      — upload a banner (multipart `file`)
+//!   `DELETE /board/:slug/banners/:id`  — remove a banner
+//!
+//! Management requires `can_manage_board_config` on the board.
+
+use axum::{
+    extract::{Path, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Redirect, Response},
+    Json,
+};
+use std::sync::Arc;
+
+use crate::axum::middleware::{
+    accept::WantsJson,
+    auth::AuthenticatedUser,
+    board_config::ExtractedBoardConfig,
+    media_urls::MediaLinks,
+};
+use crate::axum::multipart::LimitedMultipart;
+use crate::common::{dtos::BannerResponse, errors::ApiError};
+use domains::models::BannerId;
+use domains::ports::{BannerRepository, MediaStorage};
+use services::banner::BannerService;
+
+// ─── State ────────────────────────────────────────────────────────────────────
+
+/// Shared state for the banner handlers.
+pub struct BannerState<BR, MS>
+where
+    BR: BannerRepository,
+    MS: MediaStorage,
+{
+    /// The banner service.
+    pub svc: Arc<BannerService<BR, MS>>,
+}
+
+impl<BR, MS> Clone for BannerState<BR, MS>
+where
+    BR: BannerRepository,
+    MS: MediaStorage,
+{
+    fn clone(&self) -> Self { Self { svc: self.svc.clone() } }
+}
+
+// ─── Handlers ─────────────────────────────────────────────────────────────────
+
+/// `GET /board/:slug/banner` — redirect to one of the board's banners, picked
+/// at random on every request.
+///
+/// The redirect is marked `no-store` so each page load rotates. Returns `404`
+/// when the board has no banners; pages hide the image then.
+pub async fn random_banner<BR, MS>(
+    State(s): State<BannerState<BR, MS>>,
+    axum::extract::Extension(board_ctx): axum::extract::Extension<ExtractedBoardConfig>,
+    MediaLinks(links): MediaLinks,
+) -> Result<Response, ApiError>
+where
+    BR: BannerRepository,
+    MS: MediaStorage,
+{
+    let banner = s
+        .svc
+        .random(board_ctx.board_id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("banners of /{}/", board_ctx.slug)))?;
+    Ok((
+        [(header::CACHE_CONTROL, "no-store")],
+        Redirect::temporary(&links.url(&banner.media_key)),
+    )
+        .into_response())
+}
+
+/// `GET /board/:slug/banners` — the board's banners, oldest first.
+pub async fn list_banners<BR, MS>(
+    State(s): State<BannerState<BR, MS>>,
+    AuthenticatedUser(current): AuthenticatedUser,
+    axum::extract::Extension(board_ctx): axum::extract::Extension<ExtractedBoardConfig>,
+    MediaLinks(links): MediaLinks,
+) -> Result<Json<Vec<BannerResponse>>, ApiError>
+where
+    BR: BannerRepository,
+    MS: MediaStorage,
+{
+    if !current.can_manage_board_config(board_ctx.board_id) {
+        return Err(ApiError::Forbidden);
+    }
+    let banners = s.svc.list(board_ctx.board_id).await?;
+    Ok(Json(
+        banners
+            .into_iter()
+            .map(|b| BannerResponse {
+                id:           b.id,
+                url:          links.url(&b.media_key),
+                content_type: b.content_type,
+                created_at:   b.created_at,
+            })
+            .collect(),
+    ))
+}
+
+/// `POST /board/:slug/banners` — upload the multipart `file` field as a new banner.
+///
+/// Requests with `Accept: application/json` get `201` with the banner; plain
+/// form submissions are redirected back to the board dashboard.
+pub async fn upload_banner<BR, MS>(
+    State(s): State<BannerState<BR, MS>>,
+    AuthenticatedUser(current): AuthenticatedUser,
+    axum::extract::Extension(board_ctx): axum::extract::Extension<ExtractedBoardConfig>,
+    MediaLinks(links): MediaLinks,
+    WantsJson(wants_json): WantsJson,
+    mut multipart: LimitedMultipart,
+) -> Result<Response, ApiError>
+where
+    BR: BannerRepository,
+    MS: MediaStorage,
+{
+    if !current.can_manage_board_config(board_ctx.board_id) {
+        return Err(ApiError::Forbidden);
+    }
+
+    let mut data = None;
+    while let Some(field) = multipart.next_field().await? {
+        if field.name() == Some("file") {
+            data = Some(field.bytes(services::banner::MAX_BANNER_BYTES as u64).await?);
+        }
+    }
+    let data = data
+        .filter(|d| !d.is_empty())
+        .ok_or_else(|| ApiError::BadRequest("missing banner file".to_owned()))?;
+
+    let banner = s.svc.upload(board_ctx.board_id, data).await?;
+    if !wants_json {
+        return Ok(Redirect::to(&format!("/board/{}/dashboard", board_ctx.slug)).into_response());
+    }
+    Ok((
+        StatusCode::CREATED,
+        Json(BannerResponse {
+            id:           banner.id,
+            url:          links.url(&banner.media_key),
+            content_type: banner.content_type,
+            created_at:   banner.created_at,
+        }),
+    )
+        .into_response())
+}
+
+/// `DELETE /board/:slug/banners/:id` — remove a banner and its image.
+pub async fn remove_banner<BR, MS>(
+    State(s): State<BannerState<BR, MS>>,
+    AuthenticatedUser(current): AuthenticatedUser,
+    axum::extract::Extension(board_ctx): axum::extract::Extension<ExtractedBoardConfig>,
+    Path((_slug, id)): Path<(String, uuid::Uuid)>,
+) -> Result<StatusCode, ApiError>
+where
+    BR: BannerRepository,
+    MS: MediaStorage,
+{
+    if !current.can_manage_board_config(board_ctx.board_id) {
+        return Err(ApiError::Forbidden);
+    }
+    s.svc.remove(board_ctx.board_id, BannerId(id)).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -13,19 +13,22 @@ use crate::common::{
     errors::ApiError,
     pagination::PageResponse,
 };
+use crate::axum::i18n::AcceptLanguage;
 use crate::axum::middleware::auth::{AdminUser, AuthenticatedUser};
+use crate::axum::middleware::site::CurrentSite;
 use domains::models::Page;
 
-/// `GET /boards` — list all boards, paginated.
+/// `GET /boards` — list the site's boards, paginated.
 pub async fn list_boards<BR>(
     State(board_service): State<Arc<BR>>,
+    CurrentSite(site): CurrentSite,
     Query(q): Query<PaginationQuery>,
 ) -> Result<Json<PageResponse<domains::models::Board>>, ApiError>
 where
     BR: services::board::BoardRepo,
 {
     let page = Page::new(q.page);
-    let result = board_service.list_boards(page).await
+    let result = board_service.list_boards(site.id, page).await
         .map_err(ApiError::from)?;
     Ok(Json(result.into()))
 }
@@ -33,26 +36,28 @@ where
 /// `GET /board/:slug` — show board metadata (the handler feeds the template).
 pub async fn show_board<BR>(
     State(board_service): State<Arc<BR>>,
+    CurrentSite(site): CurrentSite,
     Path(slug): Path<String>,
 ) -> Result<Json<domains::models::Board>, ApiError>
 where
     BR: services::board::BoardRepo,
 {
-    let board = board_service.get_by_slug(&slug).await
+    let board = board_service.get_by_slug(site.id, &slug).await
         .map_err(ApiError::from)?;
     Ok(Json(board))
 }
 
-/// `POST /admin/boards` — create a board (admin only).
+/// `POST /admin/boards` — create a board on the request's site (admin only).
 pub async fn create_board<BR>(
     State(board_service): State<Arc<BR>>,
     _admin: AdminUser,
+    CurrentSite(site): CurrentSite,
     Json(req): Json<BoardCreate>,
 ) -> Result<(StatusCode, Json<domains::models::Board>), ApiError>
 where
     BR: services::board::BoardRepo,
 {
-    let board = board_service.create_board(&req.slug, &req.title, &req.rules).await
+    let board = board_service.create_board(site.id, &req.slug, &req.title, &req.rules).await
         .map_err(ApiError::from)?;
     Ok((StatusCode::CREATED, Json(board)))
 }
@@ -104,7 +109,7 @@ where
 /// `PUT /admin/boards/:id/config` — update board config by board UUID (admin).
 pub async fn update_board_config_by_id<BR>(
     State(board_service): State<Arc<BR>>,
-    _user: AdminUser,
+    AdminUser(user): AdminUser,
     Path(id): Path<Uuid>,
     Json(update): Json<BoardConfigUpdate>,
 ) -> Result<Json<domains::models::BoardConfig>, ApiError>
@@ -113,19 +118,20 @@ where
 {
     let current = board_service.get_config(domains::models::BoardId(id)).await.map_err(ApiError::from)?;
     let updated = update.apply_to(current);
-    let saved = board_service.update_config(domains::models::BoardId(id), updated).await.map_err(ApiError::from)?;
+    let saved = board_service.update_config(domains::models::BoardId(id), updated, user.id).await.map_err(ApiError::from)?;
     Ok(Json(saved))
 }
 /// `GET /board/:slug/config` — get board config (board owner or above).
 pub async fn get_board_config<BR>(
     State(board_service): State<Arc<BR>>,
     _user: AuthenticatedUser,
+    CurrentSite(site): CurrentSite,
     Path(slug): Path<String>,
 ) -> Result<Json<domains::models::BoardConfig>, ApiError>
 where
     BR: services::board::BoardRepo,
 {
-    let board = board_service.get_by_slug(&slug).await.map_err(ApiError::from)?;
+    let board = board_service.get_by_slug(site.id, &slug).await.map_err(ApiError::from)?;
     let config = board_service.get_config(board.id).await.map_err(ApiError::from)?;
     Ok(Json(config))
 }
@@ -133,17 +139,20 @@ where
 /// `PUT /board/:slug/config` — update board config (board owner or above).
 pub async fn update_board_config<BR>(
     State(board_service): State<Arc<BR>>,
-    _user: AuthenticatedUser,
+    AuthenticatedUser(user): AuthenticatedUser,
+    CurrentSite(site): CurrentSite,
     Path(slug): Path<String>,
     Json(update): Json<BoardConfigUpdate>,
 ) -> Result<Json<domains::models::BoardConfig>, ApiError>
 where
     BR: services::board::BoardRepo,
 {
-    let board = board_service.get_by_slug(&slug).await.map_err(ApiError::from)?;
+    let board = board_service.get_by_slug(site.id, &slug).await.map_err(ApiError::from)?;
     let current = board_service.get_config(board.id).await.map_err(ApiError::from)?;
+    // Quotas protect the host's disk, so board owners cannot change them.
+    let update = BoardConfigUpdate { media_quota_mb: None, quota_action: None, ..update };
     let updated = update.apply_to(current);
-    let saved = board_service.update_config(board.id, updated).await.map_err(ApiError::from)?;
+    let saved = board_service.update_config(board.id, updated, user.id).await.map_err(ApiError::from)?;
     Ok(Json(saved))
 }
 
@@ -187,7 +196,9 @@ fn default_page() -> u32 { 1 }
 /// Returns `400` if `q` is empty or missing.
 pub async fn search_board<BR, PR>(
     State(s): State<SearchState<BR, PR>>,
+    CurrentSite(site): CurrentSite,
     Path(slug): Path<String>,
+    accept_language: AcceptLanguage,
     Query(params): Query<SearchQuery>,
 ) -> Result<axum::response::Response, ApiError>
 where
@@ -198,7 +209,7 @@ where
         return Err(ApiError::BadRequest("search query `q` must not be empty".into()));
     }
 
-    let board = s.board_svc.get_by_slug(&slug).await
+    let board = s.board_svc.get_by_slug(site.id, &slug).await
         .map_err(ApiError::from)?;
 
     let config = s.board_svc.get_config(board.id).await
@@ -217,13 +228,20 @@ where
     let total_pages = paginated.total_pages() as u32;
     let total       = paginated.total;
 
+    let format = rb_format::Options::for_board(&config);
+    let results = paginated.items.into_iter().map(|post| {
+        let body_html = rb_format::render(&post.body, &format);
+        crate::axum::templates::SearchResultDisplay { post, body_html }
+    }).collect();
+
     let tmpl = crate::axum::templates::SearchResultsTemplate {
         board,
         query:        params.q,
-        results:      paginated.items,
+        results,
         total,
         current_page: params.page,
         total_pages,
+        i18n:         accept_language.i18n(config.default_locale),
     };
 
     use axum::response::IntoResponse;
@@ -261,14 +279,16 @@ where
 /// Returns `403` if `board_config.archive_enabled` is false.
 pub async fn show_archive<BR, AR>(
     State(s): State<ArchiveState<BR, AR>>,
+    CurrentSite(site): CurrentSite,
     Path(slug): Path<String>,
+    accept_language: AcceptLanguage,
     Query(q): Query<PaginationQuery>,
 ) -> Result<axum::response::Response, ApiError>
 where
     BR: services::board::BoardRepo,
     AR: domains::ports::ArchiveRepository,
 {
-    let board = s.board_svc.get_by_slug(&slug).await
+    let board = s.board_svc.get_by_slug(site.id, &slug).await
         .map_err(ApiError::from)?;
 
     let config = s.board_svc.get_config(board.id).await
@@ -290,7 +310,38 @@ where
         threads:      paginated.items,
         current_page: q.page,
         total_pages,
+        i18n:         accept_language.i18n(config.default_locale),
     };
     use axum::response::IntoResponse;
     Ok(tmpl.into_response())
 }
+
+/// `GET /board/:slug/archive/:thread_id` — the static page of an archived
+/// thread, read from media storage (see `static_archive`). `Accept:
+/// application/json` gets the JSON page.
+///
+/// Threads archived without a static page redirect to the thread view.
+pub async fn show_archived_thread(
+    State(media): State<Arc<dyn domains::ports::MediaStorage>>,
+    axum::extract::Extension(board_ctx): axum::extract::Extension<crate::axum::middleware::board_config::ExtractedBoardConfig>,
+    crate::axum::middleware::accept::WantsJson(wants_json): crate::axum::middleware::accept::WantsJson,
+    Path((_slug, thread_id)): Path<(String, Uuid)>,
+) -> Result<axum::response::Response, ApiError> {
+    use axum::response::IntoResponse;
+
+    let (ext, content_type) = if wants_json {
+        ("json", "application/json")
+    } else {
+        ("html", "text/html; charset=utf-8")
+    };
+    let thread_id = domains::models::ThreadId(thread_id);
+    let key = domains::models::MediaKey::archived_thread(board_ctx.board_id, thread_id, ext);
+    match media.fetch(&key).await {
+        Ok(body) => Ok(([(axum::http::header::CONTENT_TYPE, content_type)], body).into_response()),
+        Err(domains::errors::DomainError::NotFound { .. }) => {
+            let url = format!("/board/{}/thread/{}", board_ctx.board.slug, thread_id.0);
+            Ok(axum::response::Redirect::to(&url).into_response())
+        }
+        Err(e) => Err(ApiError::from(e)),
+    }
+}
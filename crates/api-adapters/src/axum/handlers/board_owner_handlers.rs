@@ -16,6 +16,7 @@ use axum::{
 };
 use std::sync::Arc;
 
+use crate::axum::i18n::I18n;
 use crate::axum::middleware::{
     auth::AuthenticatedUser,
     board_config::ExtractedBoardConfig,
@@ -33,6 +34,7 @@ pub async fn board_owner_dashboard<BR: BoardRepo>(
     State(_board_svc): State<Arc<BR>>,
     AuthenticatedUser(current): AuthenticatedUser,
     axum::extract::Extension(board_ctx): axum::extract::Extension<ExtractedBoardConfig>,
+    i18n: I18n,
 ) -> Response {
     // Permission check: only board owners and admins may view this page
     if !current.can_manage_board_config(board_ctx.board_id) {
@@ -42,6 +44,7 @@ pub async fn board_owner_dashboard<BR: BoardRepo>(
     BoardOwnerDashboardTemplate {
         board:  board_ctx.board,
         config: board_ctx.config,
+        i18n,
     }.into_response()
 }
 
@@ -94,6 +97,7 @@ where
 pub async fn board_owner_top_dashboard<BR, UR, RR>(
     State(s): State<BoardOwnerDashboardState<BR, UR, RR>>,
     AuthenticatedUser(current): AuthenticatedUser,
+    i18n: I18n,
 ) -> Response
 where
     BR: BoardRepo,
@@ -115,6 +119,7 @@ where
                 board,
                 can_manage: true,
                 can_manage_volunteers: true,
+                media_usage: None,
             });
         }
     }
@@ -170,5 +175,6 @@ where
         messages:         vec![],
         unread_count:     0,
         pending_requests: Some(pending),
+        i18n,
     }.into_response()
 }
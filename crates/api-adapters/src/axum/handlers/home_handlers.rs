@@ -0,0 +1,138 @@
+//! Homepage handler: board directory, recent posts and site stats.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use axum::{extract::State, response::IntoResponse};
+
+use crate::axum::i18n::I18n;
+use crate::axum::middleware::site::CurrentSite;
+use crate::axum::templates::{HomeBoardDisplay, HomeCategory, HomePostDisplay, HomeTemplate};
+use crate::common::errors::ApiError;
+use domains::models::{Board, Page, SiteActivity, SiteId};
+
+/// Number of recent posts shown on the homepage.
+const RECENT_POSTS: u32 = 10;
+
+/// Visible characters of each recent post's body.
+const EXCERPT_CHARS: usize = 140;
+
+/// Combined state for the homepage handler.
+pub struct HomeState<BR, PR> {
+    /// Board service used to list the site's boards and their configs.
+    pub board_service: Arc<BR>,
+    /// Post service used to load recent activity.
+    pub post_service:  Arc<PR>,
+}
+
+impl<BR, PR> Clone for HomeState<BR, PR> {
+    fn clone(&self) -> Self {
+        Self {
+            board_service: self.board_service.clone(),
+            post_service:  self.post_service.clone(),
+        }
+    }
+}
+
+/// `GET /` — the site's boards grouped by their `category`, the latest posts
+/// across them and site totals, rendered as HTML.
+pub async fn show_home<BR, PR>(
+    State(state): State<HomeState<BR, PR>>,
+    CurrentSite(site): CurrentSite,
+    i18n: I18n,
+) -> Result<impl IntoResponse, ApiError>
+where
+    BR: services::board::BoardRepo,
+    PR: SiteActivitySource,
+{
+    let boards = all_boards(state.board_service.as_ref(), site.id).await?;
+    let board_count = boards.len();
+
+    let mut named: BTreeMap<String, Vec<HomeBoardDisplay>> = BTreeMap::new();
+    let mut other = Vec::new();
+    let mut formats = HashMap::new();
+    for board in boards {
+        let config = state.board_service.get_config(board.id).await.map_err(ApiError::from)?;
+        formats.insert(board.slug.as_str().to_owned(), rb_format::Options::for_board(&config));
+        let entry = HomeBoardDisplay { board, nsfw: config.nsfw };
+        match config.category.as_str() {
+            "" => other.push(entry),
+            name => named.entry(name.to_owned()).or_default().push(entry),
+        }
+    }
+    let mut categories: Vec<HomeCategory> =
+        named.into_iter().map(|(name, boards)| HomeCategory { name, boards }).collect();
+    if !other.is_empty() {
+        categories.push(HomeCategory { name: String::new(), boards: other });
+    }
+    for category in &mut categories {
+        category.boards.sort_by(|a, b| a.board.slug.as_str().cmp(b.board.slug.as_str()));
+    }
+
+    let activity = state
+        .post_service
+        .site_activity(site.id, RECENT_POSTS)
+        .await
+        .map_err(ApiError::from)?;
+    let recent_posts = activity
+        .recent_posts
+        .into_iter()
+        .map(|post| {
+            // A board missing from the list above gets every toggle on.
+            let format = formats.get(&post.board_slug).copied().unwrap_or_default();
+            let excerpt_html = rb_format::snippet(&post.body, &format, EXCERPT_CHARS);
+            HomePostDisplay { post, excerpt_html }
+        })
+        .collect();
+
+    Ok(HomeTemplate {
+        i18n,
+        site_name: site.name,
+        categories,
+        board_count,
+        recent_posts,
+        total_threads: activity.total_threads,
+        total_posts: activity.total_posts,
+        posts_last_day: activity.posts_last_day,
+    })
+}
+
+/// Every board on `site`, across all pages of `list_boards`.
+async fn all_boards<BR: services::board::BoardRepo>(boards: &BR, site: SiteId) -> Result<Vec<Board>, ApiError> {
+    let mut all = Vec::new();
+    let mut page = Page::new(1);
+    loop {
+        let batch = boards.list_boards(site, page).await.map_err(ApiError::from)?;
+        let more = batch.has_next() && !batch.items.is_empty();
+        all.extend(batch.items);
+        if !more {
+            return Ok(all);
+        }
+        page = Page::new(page.0 + 1);
+    }
+}
+
+/// Minimal trait for sources of site-wide activity.
+///
+/// Implemented by `PostService` via a blanket impl below.
+#[async_trait::async_trait]
+pub trait SiteActivitySource: Send + Sync + 'static {
+    /// The `recent` newest posts across the site's boards and the site's totals.
+    async fn site_activity(&self, site: SiteId, recent: u32) -> Result<SiteActivity, services::post::PostError>;
+}
+
+#[async_trait::async_trait]
+impl<PR, TR, BR, MS, RL, MP> SiteActivitySource
+    for services::post::PostService<PR, TR, BR, MS, RL, MP>
+where
+    PR: domains::ports::PostRepository,
+    TR: domains::ports::ThreadRepository,
+    BR: domains::ports::BanRepository,
+    MS: domains::ports::MediaStorage,
+    RL: domains::ports::RateLimiter,
+    MP: domains::ports::MediaProcessor,
+{
+    async fn site_activity(&self, site: SiteId, recent: u32) -> Result<SiteActivity, services::post::PostError> {
+        self.site_activity(site, recent).await
+    }
+}
@@ -0,0 +1,230 @@
+//! Media handler: serves locally stored uploads.
+//!
+//! Keys are unique per upload and never rewritten, so responses are marked
+//! immutable and carry an ETag for revalidation. Byte ranges are honoured for
+//! seeking in audio and video. Only regular files under the media root are
+//! served — there are no directory listings, and dot or `..` segments are
+//! rejected before touching the filesystem.
+//!
+//! When media URLs are signed (`MEDIA_URL_SIGNING_KEY`), a request without a
+//! current `expires`/`sig` pair is refused with 403, so links lifted onto
+//! other sites stop working once they expire.
+
+use std::path::{Component, Path as FsPath, PathBuf};
+use std::sync::Arc;
+use std::time::UNIX_EPOCH;
+
+use axum::{
+    body::Body,
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode, Uri},
+    response::{IntoResponse, Response},
+};
+use domains::models::MediaKey;
+use domains::ports::PostRepository;
+use serde::Deserialize;
+use storage_adapters::media::cdn::MediaUrls;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use tokio_util::io::ReaderStream;
+
+/// Shown in place of a thumbnail whose background job has not finished.
+const PENDING_THUMBNAIL_SVG: &str = r##"<svg xmlns="http://www.w3.org/2000/svg" width="200" height="200" viewBox="0 0 200 200">
+  <rect width="200" height="200" fill="#777"/>
+  <text x="100" y="108" font-family="sans-serif" font-size="20" fill="#eee" text-anchor="middle">Processing…</text>
+</svg>
+"##;
+
+/// `Cache-Control` for stored media: keys are never reused, so a year is safe.
+const IMMUTABLE: &str = "public, max-age=31536000, immutable";
+
+/// State for the media route.
+#[derive(Clone)]
+pub struct MediaFiles {
+    /// Directory `LocalFsMediaStorage` writes to.
+    pub root: PathBuf,
+    /// Checks URL signatures, when they are enabled.
+    pub urls: MediaUrls,
+    /// Attachment records, for the MIME type each original was stored with.
+    pub posts: Arc<dyn PostRepository>,
+}
+
+/// Signature query parameters added by `MediaUrls`.
+#[derive(Debug, Deserialize)]
+pub struct MediaSignature {
+    pub expires: Option<u64>,
+    pub sig:     Option<String>,
+}
+
+/// `GET /media/{*key}` — serve a stored file.
+///
+/// - `Content-Type` is the MIME type the attachment was stored with; the
+///   key's extension is only a fallback (thumbnails, unknown keys)
+/// - `If-None-Match` with the current ETag returns 304
+/// - A single `Range: bytes=…` returns 206 (or 416 when unsatisfiable);
+///   multi-range requests get the whole file
+/// - Missing keys are 404, except pending thumbnails (see `pending_thumbnail`)
+/// - With signed URLs, a missing, expired or forged signature is 403
+pub async fn serve_media(
+    State(files): State<MediaFiles>,
+    Path(key): Path<String>,
+    Query(signature): Query<MediaSignature>,
+    headers: HeaderMap,
+) -> Response {
+    if !files.urls.verify(&key, signature.expires, signature.sig.as_deref()) {
+        return StatusCode::FORBIDDEN.into_response();
+    }
+    let Some(path) = resolve(&files.root, &key) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let meta = match tokio::fs::metadata(&path).await {
+        Ok(meta) if meta.is_file() => meta,
+        _ => return missing(&key),
+    };
+
+    let len = meta.len();
+    let mtime = meta
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_secs());
+    let etag = format!("\"{len:x}-{mtime:x}\"");
+
+    let mut response_headers = HeaderMap::new();
+    response_headers.insert(header::CACHE_CONTROL, HeaderValue::from_static(IMMUTABLE));
+    response_headers.insert(header::ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+    if let Ok(value) = HeaderValue::from_str(&etag) {
+        response_headers.insert(header::ETAG, value);
+    }
+
+    if etag_matches(&headers, &etag) {
+        return (StatusCode::NOT_MODIFIED, response_headers).into_response();
+    }
+
+    let content_type = match files.posts.find_media_mime(&MediaKey::new(key.as_str())).await {
+        Ok(Some(mime)) => mime,
+        _ => mime_guess::from_path(&path).first_or_octet_stream().to_string(),
+    };
+    if let Ok(value) = HeaderValue::from_str(&content_type) {
+        response_headers.insert(header::CONTENT_TYPE, value);
+    }
+
+    // A stale If-Range means the client's partial copy is outdated: send it all.
+    let range_applies = headers
+        .get(header::IF_RANGE)
+        .is_none_or(|v| v.to_str().is_ok_and(|v| v == etag));
+    let range = headers
+        .get(header::RANGE)
+        .filter(|_| range_applies)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| parse_range(v, len));
+
+    let (status, start, count) = match range {
+        None => (StatusCode::OK, 0, len),
+        Some(Err(())) => {
+            if let Ok(value) = HeaderValue::from_str(&format!("bytes */{len}")) {
+                response_headers.insert(header::CONTENT_RANGE, value);
+            }
+            return (StatusCode::RANGE_NOT_SATISFIABLE, response_headers).into_response();
+        }
+        Some(Ok((start, end))) => {
+            if let Ok(value) = HeaderValue::from_str(&format!("bytes {start}-{end}/{len}")) {
+                response_headers.insert(header::CONTENT_RANGE, value);
+            }
+            (StatusCode::PARTIAL_CONTENT, start, end - start + 1)
+        }
+    };
+
+    let mut file = match tokio::fs::File::open(&path).await {
+        Ok(file) => file,
+        Err(_) => return StatusCode::NOT_FOUND.into_response(),
+    };
+    if start > 0 && file.seek(std::io::SeekFrom::Start(start)).await.is_err() {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+    response_headers.insert(header::CONTENT_LENGTH, HeaderValue::from(count));
+    let body = Body::from_stream(ReaderStream::new(file.take(count)));
+    (status, response_headers, body).into_response()
+}
+
+/// Map `key` to a path under `root`, or `None` if it could escape it or names
+/// a hidden entry.
+fn resolve(root: &FsPath, key: &str) -> Option<PathBuf> {
+    if key.is_empty() || key.contains('\\') || key.contains('\0') {
+        return None;
+    }
+    let relative = FsPath::new(key);
+    let safe = relative.components().all(|c| match c {
+        Component::Normal(name) => !name.to_string_lossy().starts_with('.'),
+        _ => false,
+    });
+    safe.then(|| root.join(relative))
+}
+
+/// True when `If-None-Match` lists `etag` (weak comparison) or `*`.
+fn etag_matches(headers: &HeaderMap, etag: &str) -> bool {
+    let Some(value) = headers.get(header::IF_NONE_MATCH).and_then(|v| v.to_str().ok()) else {
+        return false;
+    };
+    value
+        .split(',')
+        .map(|tag| tag.trim().trim_start_matches("W/"))
+        .any(|tag| tag == "*" || tag == etag)
+}
+
+/// Parse a single-range `Range` header against a body of `len` bytes.
+///
+/// Returns `None` when the header should be ignored (malformed, another unit,
+/// or several ranges), `Some(Err(()))` when it cannot be satisfied, and
+/// otherwise the inclusive byte span.
+fn parse_range(value: &str, len: u64) -> Option<Result<(u64, u64), ()>> {
+    let spec = value.trim().strip_prefix("bytes=")?;
+    if spec.contains(',') {
+        return None;
+    }
+    let (first, last) = spec.split_once('-')?;
+    let (first, last) = (first.trim(), last.trim());
+    let span = if first.is_empty() {
+        // Suffix range: the final `last` bytes.
+        let suffix: u64 = last.parse().ok()?;
+        if suffix == 0 || len == 0 {
+            return Some(Err(()));
+        }
+        (len.saturating_sub(suffix), len - 1)
+    } else {
+        let start: u64 = first.parse().ok()?;
+        let end = match last {
+            "" => len.saturating_sub(1),
+            last => last.parse::<u64>().ok()?.min(len.saturating_sub(1)),
+        };
+        if start >= len || end < start {
+            return Some(Err(()));
+        }
+        (start, end)
+    };
+    Some(Ok(span))
+}
+
+/// Placeholder-or-404 for a media request that matched no file.
+///
+/// Image thumbnails are rendered in the background after the post is saved, so
+/// a page can reference a `*_thumb.<ext>` key before the file exists. Those
+/// requests get a placeholder with `Cache-Control: no-store`, so the real
+/// thumbnail is fetched on the next load. Anything else is a plain 404.
+pub async fn pending_thumbnail(uri: Uri) -> Response {
+    missing(uri.path())
+}
+
+fn missing(key: &str) -> Response {
+    let is_thumbnail = key.rsplit_once('.').is_some_and(|(stem, _)| stem.ends_with("_thumb"));
+    if !is_thumbnail {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    (
+        [
+            (header::CONTENT_TYPE, "image/svg+xml"),
+            (header::CACHE_CONTROL, "no-store"),
+        ],
+        PENDING_THUMBNAIL_SVG,
+    )
+        .into_response()
+}
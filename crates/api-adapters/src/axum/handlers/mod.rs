@@ -1,13 +1,24 @@
 //! Axum request handlers.
 pub mod admin_handlers;
+pub mod announcement_handlers;
+pub mod api_key_handlers;
 pub mod auth_handlers;
+pub mod banner_handlers;
 pub mod board_handlers;
 pub mod board_owner_handlers;
+pub mod home_handlers;
+pub mod media_handlers;
 pub mod moderation_handlers;
+pub mod notice_handlers;
 pub mod overboard_handlers;
+pub mod page_handlers;
+pub mod poll_handlers;
 pub mod post_handlers;
+pub mod reaction_handlers;
 pub mod staff_message_handlers;
+pub mod stats_handlers;
 pub mod thread_handlers;
 
 pub mod user_handlers;
 pub mod volunteer_handlers;
+pub mod watch_handlers;
@@ -1,7 +1,11 @@
-//! Moderation handlers: flags, bans, delete, sticky, close.
+//! Moderation handlers: flags, bans, delete, edit, sticky, close, IP history.
+
+// Handlers are generic over every service port; the `State<Arc<Service<..>>>`
+// extractor types are long by construction.
+#![allow(clippy::type_complexity)]
 
 use axum::{
-    extract::{Path, Query, State},
+    extract::{Extension, Path, Query, State},
     http::StatusCode,
     response::IntoResponse,
     Json,
@@ -11,13 +15,21 @@ use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::common::{
-    dtos::{CreateBanRequest, PaginationQuery, ResolveFlagRequest},
+    dtos::{
+        CreateAsnBanRequest, CreateBanRequest, CreateHashBanRequest, CreateWarningRequest, EditPostRequest,
+        PaginationQuery, ResolveFlagRequest,
+    },
     errors::ApiError,
 };
+use crate::axum::i18n::I18n;
 use crate::axum::middleware::auth::{AnyAuthenticatedUser, AuthenticatedUser, ModeratorUser};
-use domains::models::{BanId, FlagId, IpHash, Page, ThreadId};
+use crate::axum::middleware::board_config::ExtractedBoardConfig;
+use crate::axum::middleware::client_ip::ClientIp;
+use crate::axum::middleware::render_cache::{invalidate_board, RenderCacheState};
+use crate::axum::middleware::site::CurrentSite;
+use domains::models::{BanId, BoardId, FlagId, IpHash, Page, Paginated, PostId, ThreadId};
 use services::board::BoardRepo;
-use services::moderation::ModerationService;
+use services::moderation::{ModerationService, IP_HISTORY_DAYS};
 
 /// Body for `POST /mod/threads/:id/sticky` and `POST /mod/threads/:id/close`.
 #[derive(Deserialize)]
@@ -33,12 +45,50 @@ pub struct DeleteByIpRequest {
     pub ip_hash: String,
 }
 
+/// Body for `POST /mod/boards/:id/delete-by-ip`.
+#[derive(Deserialize)]
+pub struct BoardDeleteByIpRequest {
+    /// The ip_hash of the poster whose posts should be deleted from this board.
+    pub ip_hash: String,
+    /// How far back to delete, in hours.
+    pub hours:   u32,
+}
+
+/// Body for `POST /mod/threads/:id/move`.
+#[derive(Deserialize)]
+pub struct MovePostsRequest {
+    /// The posts to move, all from the thread in the path.
+    pub post_ids:  Vec<Uuid>,
+    /// The thread to move them into. Absent or `null` splits them off into a
+    /// new thread.
+    #[serde(default)]
+    pub thread_id: Option<Uuid>,
+}
+
+/// Query parameters of `GET /mod/ip-history`.
+#[derive(Deserialize)]
+pub struct IpHistoryQuery {
+    /// A raw IP address or an IP hash. Absent: the page shows only the form.
+    #[serde(default)]
+    pub ip:   String,
+    /// Page number to retrieve (1-indexed). Defaults to `1`.
+    pub page: Option<u32>,
+}
+
+/// Body for `POST /mod/ip-history/delete-all`.
+#[derive(Deserialize)]
+pub struct IpLookupRequest {
+    /// A raw IP address or an IP hash, as in `GET /mod/ip-history`.
+    pub ip: String,
+}
+
 /// `GET /mod/flags` — list pending flags, paginated.
 pub async fn list_flags<BR, PR, TR, FR, AR, UR>(
     State(svc): State<Arc<ModerationService<BR, PR, TR, FR, AR, UR>>>,
     _mod_user: ModeratorUser,
     Query(q): Query<PaginationQuery>,
     crate::axum::middleware::accept::WantsJson(wants_json): crate::axum::middleware::accept::WantsJson,
+    i18n: I18n,
 ) -> Result<axum::response::Response, ApiError>
 where
     BR: domains::ports::BanRepository,
@@ -61,6 +111,7 @@ where
             total_pages: result.total_pages() as u32,
             flags: result.items,
             page: q.page,
+            i18n,
         }.into_response())
     }
 }
@@ -90,6 +141,7 @@ where
 pub async fn delete_post<BR, PR, TR, FR, AR, UR>(
     State(svc): State<Arc<ModerationService<BR, PR, TR, FR, AR, UR>>>,
     ModeratorUser(current): ModeratorUser,
+    render_cache: Option<Extension<RenderCacheState>>,
     Path(id): Path<Uuid>,
 ) -> Result<StatusCode, ApiError>
 where
@@ -100,16 +152,94 @@ where
     AR: domains::ports::AuditRepository,
     UR: domains::ports::UserRepository,
 {
-    svc.delete_post(domains::models::PostId(id), current.user_id())
+    let post_id = domains::models::PostId(id);
+    // Resolve the board before the post is gone.
+    let board = match &render_cache {
+        Some(_) => {
+            let post = svc.get_post(post_id).await.map_err(ApiError::from)?;
+            Some(svc.get_thread(post.thread_id).await.map_err(ApiError::from)?.board_id)
+        }
+        None => None,
+    };
+    svc.delete_post(post_id, current.user_id())
         .await
         .map_err(ApiError::from)?;
+    if let (Some(Extension(cache)), Some(board)) = (render_cache, board) {
+        invalidate_board(&cache, board).await;
+    }
     Ok(StatusCode::NO_CONTENT)
 }
 
+/// `POST /mod/posts/:id/edit` — replace a post's body and subject, keeping
+/// the old text as a revision, and record an audit entry.
+pub async fn edit_post<BR, PR, TR, FR, AR, UR>(
+    State(svc): State<Arc<ModerationService<BR, PR, TR, FR, AR, UR>>>,
+    ModeratorUser(current): ModeratorUser,
+    render_cache: Option<Extension<RenderCacheState>>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<EditPostRequest>,
+) -> Result<StatusCode, ApiError>
+where
+    BR: domains::ports::BanRepository,
+    PR: domains::ports::PostRepository,
+    TR: domains::ports::ThreadRepository,
+    FR: domains::ports::FlagRepository,
+    AR: domains::ports::AuditRepository,
+    UR: domains::ports::UserRepository,
+{
+    let post_id = PostId(id);
+    svc.edit_post(post_id, req.body, req.subject, current.user_id())
+        .await
+        .map_err(ApiError::from)?;
+    if let Some(Extension(cache)) = render_cache {
+        let post = svc.get_post(post_id).await.map_err(ApiError::from)?;
+        invalidate_board(&cache, svc.get_thread(post.thread_id).await.map_err(ApiError::from)?.board_id).await;
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// `GET /mod/posts/:id/revisions` — a post as it reads now and the text each
+/// staff edit replaced, newest first.
+///
+/// HTML by default; the revisions alone as JSON with `Accept: application/json`.
+pub async fn post_revisions<BR, PR, TR, FR, AR, UR, BS>(
+    State(s): State<ModerationDashboardState<BR, PR, TR, FR, AR, UR, BS>>,
+    _mod_user: ModeratorUser,
+    crate::axum::middleware::accept::WantsJson(wants_json): crate::axum::middleware::accept::WantsJson,
+    Path(id): Path<Uuid>,
+    i18n: I18n,
+) -> Result<axum::response::Response, ApiError>
+where
+    BR: domains::ports::BanRepository,
+    PR: domains::ports::PostRepository,
+    TR: domains::ports::ThreadRepository,
+    FR: domains::ports::FlagRepository,
+    AR: domains::ports::AuditRepository,
+    UR: domains::ports::UserRepository,
+    BS: BoardRepo,
+{
+    use crate::axum::templates::PostRevisionsPageTemplate;
+    let post_id = PostId(id);
+    let revisions = s.mod_svc.post_revisions(post_id).await.map_err(ApiError::from)?;
+    if wants_json {
+        return Ok(Json(revisions).into_response());
+    }
+    let post = s.mod_svc.get_post(post_id).await.map_err(ApiError::from)?;
+    let board_id = s.mod_svc.get_thread(post.thread_id).await.map_err(ApiError::from)?.board_id;
+    let board = s.board_svc.get_by_id(board_id).await.map_err(ApiError::from)?;
+    Ok(PostRevisionsPageTemplate {
+        post,
+        board_slug: board.slug.as_str().to_owned(),
+        revisions,
+        i18n,
+    }.into_response())
+}
+
 /// `POST /mod/threads/:id/delete` — delete a thread and all its posts.
 pub async fn delete_thread<BR, PR, TR, FR, AR, UR>(
     State(svc): State<Arc<ModerationService<BR, PR, TR, FR, AR, UR>>>,
     ModeratorUser(current): ModeratorUser,
+    render_cache: Option<Extension<RenderCacheState>>,
     Path(id): Path<Uuid>,
 ) -> Result<StatusCode, ApiError>
 where
@@ -120,9 +250,17 @@ where
     AR: domains::ports::AuditRepository,
     UR: domains::ports::UserRepository,
 {
-    svc.delete_thread(domains::models::ThreadId(id), current.user_id())
+    let thread_id = ThreadId(id);
+    let board = match &render_cache {
+        Some(_) => Some(svc.get_thread(thread_id).await.map_err(ApiError::from)?.board_id),
+        None => None,
+    };
+    svc.delete_thread(thread_id, current.user_id())
         .await
         .map_err(ApiError::from)?;
+    if let (Some(Extension(cache)), Some(board)) = (render_cache, board) {
+        invalidate_board(&cache, board).await;
+    }
     Ok(StatusCode::NO_CONTENT)
 }
 
@@ -179,6 +317,7 @@ where
 pub async fn delete_posts_by_ip<BR, PR, TR, FR, AR, UR>(
     State(svc): State<Arc<ModerationService<BR, PR, TR, FR, AR, UR>>>,
     ModeratorUser(current): ModeratorUser,
+    render_cache: Option<Extension<RenderCacheState>>,
     Path(id): Path<Uuid>,
     Json(req): Json<DeleteByIpRequest>,
 ) -> Result<impl IntoResponse, ApiError>
@@ -190,13 +329,90 @@ where
     AR: domains::ports::AuditRepository,
     UR: domains::ports::UserRepository,
 {
+    let thread_id = ThreadId(id);
+    let board = match &render_cache {
+        Some(_) => Some(svc.get_thread(thread_id).await.map_err(ApiError::from)?.board_id),
+        None => None,
+    };
     let count = svc
-        .delete_posts_by_ip_in_thread(IpHash::new(req.ip_hash), ThreadId(id), current.user_id())
+        .delete_posts_by_ip_in_thread(IpHash::new(req.ip_hash), thread_id, current.user_id())
         .await
         .map_err(ApiError::from)?;
+    if let (Some(Extension(cache)), Some(board)) = (render_cache, board) {
+        invalidate_board(&cache, board).await;
+    }
     Ok(Json(serde_json::json!({ "deleted": count })))
 }
 
+/// `POST /mod/boards/:id/delete-by-ip` — delete all posts by an IP on this
+/// board in the last N hours.
+///
+/// Body: `{ "ip_hash": "<hex>", "hours": N }` with N in 1–720. The posts go in
+/// one transaction; an opening post takes its thread. Returns `{ "deleted": N }`.
+pub async fn delete_board_posts_by_ip<BR, PR, TR, FR, AR, UR>(
+    State(svc): State<Arc<ModerationService<BR, PR, TR, FR, AR, UR>>>,
+    ModeratorUser(current): ModeratorUser,
+    render_cache: Option<Extension<RenderCacheState>>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<BoardDeleteByIpRequest>,
+) -> Result<impl IntoResponse, ApiError>
+where
+    BR: domains::ports::BanRepository,
+    PR: domains::ports::PostRepository,
+    TR: domains::ports::ThreadRepository,
+    FR: domains::ports::FlagRepository,
+    AR: domains::ports::AuditRepository,
+    UR: domains::ports::UserRepository,
+{
+    let outcome = svc
+        .delete_posts_by_ip_on_board(IpHash::new(req.ip_hash), BoardId(id), req.hours, current.user_id())
+        .await
+        .map_err(ApiError::from)?;
+    if let Some(Extension(cache)) = render_cache {
+        for board in outcome.boards {
+            invalidate_board(&cache, board).await;
+        }
+    }
+    Ok(Json(serde_json::json!({ "deleted": outcome.deleted })))
+}
+
+/// `POST /mod/threads/:id/move` — move posts into another thread on the same
+/// board, or split them off into a new one.
+///
+/// Body: `{ "post_ids": ["<uuid>", …], "thread_id": "<uuid>" | null }`.
+/// Returns `{ "thread_id": "<uuid>", "moved": N }` with the destination thread.
+pub async fn move_posts<BR, PR, TR, FR, AR, UR, BS>(
+    State(s): State<ModerationDashboardState<BR, PR, TR, FR, AR, UR, BS>>,
+    ModeratorUser(current): ModeratorUser,
+    render_cache: Option<Extension<RenderCacheState>>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<MovePostsRequest>,
+) -> Result<impl IntoResponse, ApiError>
+where
+    BR: domains::ports::BanRepository,
+    PR: domains::ports::PostRepository,
+    TR: domains::ports::ThreadRepository,
+    FR: domains::ports::FlagRepository,
+    AR: domains::ports::AuditRepository,
+    UR: domains::ports::UserRepository,
+    BS: BoardRepo,
+{
+    let from = ThreadId(id);
+    let board_id = s.mod_svc.get_thread(from).await.map_err(ApiError::from)?.board_id;
+    // Quotes the move separates are rewritten as `>>>/slug/N`.
+    let board = s.board_svc.get_by_id(board_id).await.map_err(ApiError::from)?;
+    let post_ids: Vec<PostId> = req.post_ids.into_iter().map(PostId).collect();
+    let to = s
+        .mod_svc
+        .move_posts(from, &post_ids, req.thread_id.map(ThreadId), board.slug.as_str(), current.user_id())
+        .await
+        .map_err(ApiError::from)?;
+    if let Some(Extension(cache)) = render_cache {
+        invalidate_board(&cache, board_id).await;
+    }
+    Ok(Json(serde_json::json!({ "thread_id": to.0, "moved": post_ids.len() })))
+}
+
 /// `POST /mod/threads/:id/cycle` — toggle cycle mode on a thread.
 pub async fn toggle_cycle<BR, PR, TR, FR, AR, UR>(
     State(svc): State<Arc<ModerationService<BR, PR, TR, FR, AR, UR>>>,
@@ -240,7 +456,7 @@ where
 }
 
 
-/// `POST /mod/bans` — issue an IP ban.
+/// `POST /mod/bans` — issue an IP ban, or a shadowban with `"shadow": true`.
 pub async fn create_ban<BR, PR, TR, FR, AR, UR>(
     State(svc): State<Arc<ModerationService<BR, PR, TR, FR, AR, UR>>>,
     ModeratorUser(current): ModeratorUser,
@@ -254,10 +470,35 @@ where
     AR: domains::ports::AuditRepository,
     UR: domains::ports::UserRepository,
 {
-    svc.ban_ip(
+    let ip_hash = IpHash::new(req.ip_hash);
+    if req.shadow {
+        svc.shadowban_ip(ip_hash, req.reason, req.expires_at, current.user_id()).await
+    } else {
+        svc.ban_ip(ip_hash, req.reason, req.expires_at, current.user_id()).await
+    }
+    .map_err(ApiError::from)?;
+    Ok(StatusCode::CREATED)
+}
+
+/// `POST /mod/warnings` — warn an IP hash; the poster sees the warning on
+/// their next page load and cannot post until they acknowledge it.
+pub async fn create_warning<BR, PR, TR, FR, AR, UR>(
+    State(svc): State<Arc<ModerationService<BR, PR, TR, FR, AR, UR>>>,
+    ModeratorUser(current): ModeratorUser,
+    Json(req): Json<CreateWarningRequest>,
+) -> Result<StatusCode, ApiError>
+where
+    BR: domains::ports::BanRepository,
+    PR: domains::ports::PostRepository,
+    TR: domains::ports::ThreadRepository,
+    FR: domains::ports::FlagRepository,
+    AR: domains::ports::AuditRepository,
+    UR: domains::ports::UserRepository,
+{
+    svc.warn_ip(
         IpHash::new(req.ip_hash),
+        req.post_id.map(domains::models::PostId),
         req.reason,
-        req.expires_at,
         current.user_id(),
     )
     .await
@@ -292,6 +533,7 @@ pub async fn list_bans<BR, PR, TR, FR, AR, UR>(
     _mod_user: ModeratorUser,
     crate::axum::middleware::accept::WantsJson(wants_json): crate::axum::middleware::accept::WantsJson,
     Query(q): Query<PaginationQuery>,
+    i18n: I18n,
 ) -> Result<axum::response::Response, ApiError>
 where
     BR: domains::ports::BanRepository,
@@ -314,10 +556,218 @@ where
             total_pages: result.total_pages() as u32,
             bans: result.items,
             page: q.page,
+            i18n,
+        }.into_response())
+    }
+}
+
+/// `GET /mod/ip-history?ip=…` — the posts made from an IP across the site's
+/// boards, newest first, with delete and ban actions.
+///
+/// `ip` is a raw IP address (matched over the last `IP_HISTORY_DAYS` daily
+/// salts) or an IP hash. HTML by default; JSON with `Accept: application/json`.
+pub async fn ip_history<BR, PR, TR, FR, AR, UR>(
+    State(svc): State<Arc<ModerationService<BR, PR, TR, FR, AR, UR>>>,
+    _mod_user: ModeratorUser,
+    CurrentSite(site): CurrentSite,
+    crate::axum::middleware::accept::WantsJson(wants_json): crate::axum::middleware::accept::WantsJson,
+    Query(q): Query<IpHistoryQuery>,
+    i18n: I18n,
+) -> Result<axum::response::Response, ApiError>
+where
+    BR: domains::ports::BanRepository,
+    PR: domains::ports::PostRepository,
+    TR: domains::ports::ThreadRepository,
+    FR: domains::ports::FlagRepository,
+    AR: domains::ports::AuditRepository,
+    UR: domains::ports::UserRepository,
+{
+    use crate::axum::templates::IpHistoryPageTemplate;
+    use crate::common::pagination::PageResponse;
+    let page = Page::new(q.page.unwrap_or(1));
+    let ip = q.ip.trim().to_owned();
+    let result = if ip.is_empty() && !wants_json {
+        Paginated::empty(page, Page::DEFAULT_PAGE_SIZE)
+    } else {
+        svc.ip_history(site.id, &ip, page).await.map_err(ApiError::from)?
+    };
+    if wants_json {
+        let resp: PageResponse<domains::models::OverboardPost> = result.into();
+        Ok(Json(resp).into_response())
+    } else {
+        Ok(IpHistoryPageTemplate {
+            total_pages: result.total_pages() as u32,
+            total: result.total,
+            posts: result.items,
+            page: page.0,
+            days: IP_HISTORY_DAYS,
+            ip,
+            i18n,
         }.into_response())
     }
 }
 
+/// `POST /mod/ip-history/delete-all` — delete every post in an IP's history.
+///
+/// Body: `{ "ip": "<address or hash>" }`.
+/// Returns `{ "deleted": N }` with the count of removed posts.
+pub async fn delete_ip_history<BR, PR, TR, FR, AR, UR>(
+    State(svc): State<Arc<ModerationService<BR, PR, TR, FR, AR, UR>>>,
+    ModeratorUser(current): ModeratorUser,
+    CurrentSite(site): CurrentSite,
+    render_cache: Option<Extension<RenderCacheState>>,
+    Json(req): Json<IpLookupRequest>,
+) -> Result<impl IntoResponse, ApiError>
+where
+    BR: domains::ports::BanRepository,
+    PR: domains::ports::PostRepository,
+    TR: domains::ports::ThreadRepository,
+    FR: domains::ports::FlagRepository,
+    AR: domains::ports::AuditRepository,
+    UR: domains::ports::UserRepository,
+{
+    let outcome = svc
+        .delete_ip_history(site.id, &req.ip, current.user_id())
+        .await
+        .map_err(ApiError::from)?;
+    if let Some(Extension(cache)) = render_cache {
+        for board in outcome.boards {
+            invalidate_board(&cache, board).await;
+        }
+    }
+    Ok(Json(serde_json::json!({ "deleted": outcome.deleted })))
+}
+
+/// `POST /mod/asn-bans` — ban an entire autonomous system.
+///
+/// Returns 501 when no `AsnBanRepository` is wired.
+pub async fn create_asn_ban<BR, PR, TR, FR, AR, UR>(
+    State(svc): State<Arc<ModerationService<BR, PR, TR, FR, AR, UR>>>,
+    ModeratorUser(current): ModeratorUser,
+    Json(req): Json<CreateAsnBanRequest>,
+) -> Result<StatusCode, ApiError>
+where
+    BR: domains::ports::BanRepository,
+    PR: domains::ports::PostRepository,
+    TR: domains::ports::ThreadRepository,
+    FR: domains::ports::FlagRepository,
+    AR: domains::ports::AuditRepository,
+    UR: domains::ports::UserRepository,
+{
+    svc.ban_asn(req.asn, req.reason, req.expires_at, current.user_id())
+        .await
+        .map_err(ApiError::from)?;
+    Ok(StatusCode::CREATED)
+}
+
+/// `POST /mod/asn-bans/:id/expire` — immediately expire an ASN ban.
+pub async fn expire_asn_ban<BR, PR, TR, FR, AR, UR>(
+    State(svc): State<Arc<ModerationService<BR, PR, TR, FR, AR, UR>>>,
+    ModeratorUser(current): ModeratorUser,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError>
+where
+    BR: domains::ports::BanRepository,
+    PR: domains::ports::PostRepository,
+    TR: domains::ports::ThreadRepository,
+    FR: domains::ports::FlagRepository,
+    AR: domains::ports::AuditRepository,
+    UR: domains::ports::UserRepository,
+{
+    svc.expire_asn_ban(BanId(id), current.user_id()).await.map_err(ApiError::from)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// `GET /mod/asn-bans` — list all ASN bans (active + expired), paginated JSON.
+pub async fn list_asn_bans<BR, PR, TR, FR, AR, UR>(
+    State(svc): State<Arc<ModerationService<BR, PR, TR, FR, AR, UR>>>,
+    _mod_user: ModeratorUser,
+    Query(q): Query<PaginationQuery>,
+) -> Result<Json<crate::common::pagination::PageResponse<domains::models::AsnBan>>, ApiError>
+where
+    BR: domains::ports::BanRepository,
+    PR: domains::ports::PostRepository,
+    TR: domains::ports::ThreadRepository,
+    FR: domains::ports::FlagRepository,
+    AR: domains::ports::AuditRepository,
+    UR: domains::ports::UserRepository,
+{
+    let result = svc.list_asn_bans(Page::new(q.page)).await.map_err(ApiError::from)?;
+    Ok(Json(result.into()))
+}
+
+/// `POST /mod/hash-bans` — ban a post's attachments or a known MD5.
+///
+/// Returns 400 unless exactly one of `post_id` / `md5` is given or the MD5 is
+/// not 32 hex digits, and 501 when no `HashBanRepository` is wired.
+pub async fn create_hash_ban<BR, PR, TR, FR, AR, UR>(
+    State(svc): State<Arc<ModerationService<BR, PR, TR, FR, AR, UR>>>,
+    ModeratorUser(current): ModeratorUser,
+    Json(req): Json<CreateHashBanRequest>,
+) -> Result<StatusCode, ApiError>
+where
+    BR: domains::ports::BanRepository,
+    PR: domains::ports::PostRepository,
+    TR: domains::ports::ThreadRepository,
+    FR: domains::ports::FlagRepository,
+    AR: domains::ports::AuditRepository,
+    UR: domains::ports::UserRepository,
+{
+    match (req.post_id, req.md5) {
+        (Some(post_id), None) => {
+            svc.ban_post_media(domains::models::PostId(post_id), req.reason, req.expires_at, current.user_id())
+                .await
+                .map_err(ApiError::from)?;
+        }
+        (None, Some(md5)) => {
+            if md5.len() != 32 || !md5.bytes().all(|b| b.is_ascii_hexdigit()) {
+                return Err(ApiError::BadRequest("md5 must be 32 hex digits".to_owned()));
+            }
+            svc.ban_md5(md5, req.reason, req.expires_at, current.user_id())
+                .await
+                .map_err(ApiError::from)?;
+        }
+        _ => return Err(ApiError::BadRequest("give exactly one of post_id or md5".to_owned())),
+    }
+    Ok(StatusCode::CREATED)
+}
+
+/// `POST /mod/hash-bans/:id/expire` — immediately expire a hash ban.
+pub async fn expire_hash_ban<BR, PR, TR, FR, AR, UR>(
+    State(svc): State<Arc<ModerationService<BR, PR, TR, FR, AR, UR>>>,
+    ModeratorUser(current): ModeratorUser,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError>
+where
+    BR: domains::ports::BanRepository,
+    PR: domains::ports::PostRepository,
+    TR: domains::ports::ThreadRepository,
+    FR: domains::ports::FlagRepository,
+    AR: domains::ports::AuditRepository,
+    UR: domains::ports::UserRepository,
+{
+    svc.expire_hash_ban(BanId(id), current.user_id()).await.map_err(ApiError::from)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// `GET /mod/hash-bans` — list all hash bans (active + expired), paginated JSON.
+pub async fn list_hash_bans<BR, PR, TR, FR, AR, UR>(
+    State(svc): State<Arc<ModerationService<BR, PR, TR, FR, AR, UR>>>,
+    _mod_user: ModeratorUser,
+    Query(q): Query<PaginationQuery>,
+) -> Result<Json<crate::common::pagination::PageResponse<domains::models::HashBan>>, ApiError>
+where
+    BR: domains::ports::BanRepository,
+    PR: domains::ports::PostRepository,
+    TR: domains::ports::ThreadRepository,
+    FR: domains::ports::FlagRepository,
+    AR: domains::ports::AuditRepository,
+    UR: domains::ports::UserRepository,
+{
+    let result = svc.list_hash_bans(Page::new(q.page)).await.map_err(ApiError::from)?;
+    Ok(Json(result.into()))
+}
+
 /// Request body for `POST /board/:slug/thread/:id/flag`.
 #[derive(Debug, serde::Deserialize)]
 pub struct CreateFlagRequest {
@@ -331,7 +781,7 @@ pub struct CreateFlagRequest {
 /// The reporter's IP is hashed immediately with a daily salt; raw IPs are never stored.
 pub async fn create_flag<BR, PR, TR, FR, AR, UR>(
     State(svc): State<Arc<ModerationService<BR, PR, TR, FR, AR, UR>>>,
-    axum::extract::ConnectInfo(peer_addr): axum::extract::ConnectInfo<std::net::SocketAddr>,
+    ClientIp(client_ip): ClientIp,
     Path((_slug, thread_id)): Path<(String, Uuid)>,
     Json(req): Json<CreateFlagRequest>,
 ) -> Result<StatusCode, ApiError>
@@ -344,7 +794,7 @@ where
     UR: domains::ports::UserRepository,
 {
     // Hash reporter IP with daily salt — same approach as create_post
-    let raw_ip = peer_addr.ip().to_string();
+    let raw_ip = client_ip.to_string();
     let daily_salt = chrono::Utc::now().format("%Y-%m-%d").to_string();
     let ip_hash = services::common::utils::hash_ip(&raw_ip, &daily_salt);
 
@@ -405,6 +855,8 @@ where
 pub async fn janitor_dashboard<BR, PR, TR, FR, AR, UR, BS>(
     State(s): State<ModerationDashboardState<BR, PR, TR, FR, AR, UR, BS>>,
     crate::axum::middleware::auth::JanitorStaffUser(current): crate::axum::middleware::auth::JanitorStaffUser,
+    crate::axum::middleware::site::CurrentSite(site): crate::axum::middleware::site::CurrentSite,
+    i18n: I18n,
 ) -> axum::response::Response
 where
     BR: domains::ports::BanRepository,
@@ -422,15 +874,16 @@ where
     let recent_logs: Vec<domains::models::AuditEntry> =
         s.mod_svc.recent_audit_entries(10).await.unwrap_or_default();
 
-    // All boards — janitor sees site-wide, no manage controls
+    // All of this site's boards — janitor sees site-wide, no manage controls
     let all_boards: Vec<domains::models::Board> =
-        s.board_svc.list_boards(Page::new(1)).await
+        s.board_svc.list_boards(site.id, Page::new(1)).await
             .map(|p| p.items)
             .unwrap_or_default();
     let boards = all_boards.into_iter().map(|b| DashboardBoard {
         board: b,
         can_manage: false,
         can_manage_volunteers: false,
+        media_usage: None,
     }).collect();
 
     DashboardTemplate {
@@ -443,6 +896,7 @@ where
         messages:         vec![],
         unread_count:     0,
         pending_requests: None,
+        i18n,
     }.into_response()
 }
 
@@ -450,6 +904,7 @@ where
 pub async fn volunteer_dashboard<BR, PR, TR, FR, AR, UR, BS>(
     State(s): State<ModerationDashboardState<BR, PR, TR, FR, AR, UR, BS>>,
     AuthenticatedUser(current): AuthenticatedUser,
+    i18n: I18n,
 ) -> axum::response::Response
 where
     BR: domains::ports::BanRepository,
@@ -472,6 +927,7 @@ where
                 board,
                 can_manage: false,
                 can_manage_volunteers: false,
+                media_usage: None,
             });
         }
     }
@@ -486,6 +942,7 @@ where
         messages:         vec![],
         unread_count:     0,
         pending_requests: None,
+        i18n,
     }.into_response()
 }
 
@@ -514,6 +971,7 @@ pub async fn janitor_audit_log<BR, PR, TR, FR, AR, UR>(
     State(s): State<Arc<ModerationService<BR, PR, TR, FR, AR, UR>>>,
     crate::axum::middleware::auth::JanitorStaffUser(current): crate::axum::middleware::auth::JanitorStaffUser,
     Query(q): Query<crate::common::dtos::PaginationQuery>,
+    i18n: I18n,
 ) -> Result<axum::response::Response, ApiError>
 where
     BR: domains::ports::BanRepository,
@@ -531,6 +989,7 @@ where
         role_label:   "Janitor".into(),
         entries:      crate::common::pagination::PageResponse::from(entries),
         current_user: current,
+        i18n,
     }.into_response())
 }
 
@@ -543,6 +1002,7 @@ pub async fn board_owner_audit_log<BR, PR, TR, FR, AR, UR>(
     State(s): State<Arc<ModerationService<BR, PR, TR, FR, AR, UR>>>,
     crate::axum::middleware::auth::BoardOwnerUser(current): crate::axum::middleware::auth::BoardOwnerUser,
     Query(q): Query<crate::common::dtos::PaginationQuery>,
+    i18n: I18n,
 ) -> Result<axum::response::Response, ApiError>
 where
     BR: domains::ports::BanRepository,
@@ -565,6 +1025,7 @@ where
         role_label:   "Board Owner".into(),
         entries:      crate::common::pagination::PageResponse::from(entries),
         current_user: current,
+        i18n,
     }.into_response())
 }
 
@@ -573,6 +1034,7 @@ pub async fn volunteer_audit_log<BR, PR, TR, FR, AR, UR>(
     State(s): State<Arc<ModerationService<BR, PR, TR, FR, AR, UR>>>,
     crate::axum::middleware::auth::VolunteerUser(current): crate::axum::middleware::auth::VolunteerUser,
     Query(q): Query<crate::common::dtos::PaginationQuery>,
+    i18n: I18n,
 ) -> Result<axum::response::Response, ApiError>
 where
     BR: domains::ports::BanRepository,
@@ -594,5 +1056,47 @@ where
         role_label:   "Volunteer".into(),
         entries:      crate::common::pagination::PageResponse::from(entries),
         current_user: current,
+        i18n,
     }.into_response())
 }
+
+/// `GET /board/:slug/log` — the board's public mod log.
+///
+/// `404` unless the board's config has `public_log` set. Entries are limited
+/// to [`AuditAction::PUBLIC`](domains::models::AuditAction::PUBLIC) and carry
+/// no actor, IP hash or details. HTML by default; JSON with
+/// `Accept: application/json`.
+pub async fn public_board_log<BR, PR, TR, FR, AR, UR>(
+    State(svc): State<Arc<ModerationService<BR, PR, TR, FR, AR, UR>>>,
+    Extension(board_ctx): Extension<ExtractedBoardConfig>,
+    crate::axum::middleware::accept::WantsJson(wants_json): crate::axum::middleware::accept::WantsJson,
+    Query(q): Query<PaginationQuery>,
+    i18n: I18n,
+) -> Result<axum::response::Response, ApiError>
+where
+    BR: domains::ports::BanRepository,
+    PR: domains::ports::PostRepository,
+    TR: domains::ports::ThreadRepository,
+    FR: domains::ports::FlagRepository,
+    AR: domains::ports::AuditRepository,
+    UR: domains::ports::UserRepository,
+{
+    if !board_ctx.config.public_log {
+        return Err(ApiError::NotFound(format!("/{}/log", board_ctx.board.slug)));
+    }
+    let page = Page::new(q.page);
+    let entries = svc.public_log_for_board(board_ctx.board_id, page).await.map_err(ApiError::from)?;
+    if wants_json {
+        let resp: crate::common::pagination::PageResponse<domains::models::AuditEntry> = entries.into();
+        Ok(Json(resp).into_response())
+    } else {
+        Ok(crate::axum::templates::BoardLogTemplate {
+            total_pages: entries.total_pages() as u32,
+            page:        page.0,
+            entries:     entries.items,
+            board:       board_ctx.board,
+            config:      board_ctx.config,
+            i18n,
+        }.into_response())
+    }
+}
@@ -0,0 +1,61 @@
+//! Site notice handlers: the admin-set message shown on every page.
+//!
+//! Routes:
+//!   `GET /admin/notice` — the site's notice and whether it is shown
+//!   `PUT /admin/notice` — replace the notice and switch it on or off
+//!
+//! `site_notice_middleware` shows the notice; saving one clears the site
+//! cache so this instance shows it from the next request on.
+
+use axum::{extract::State, Json};
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::axum::middleware::{auth::AdminUser, site::CurrentSite};
+use crate::common::{dtos::SiteNoticeResponse, errors::ApiError};
+use domains::ports::SiteRepository;
+use services::site::SiteService;
+use storage_adapters::cache::SiteCache;
+
+// ─── State ────────────────────────────────────────────────────────────────────
+
+/// Shared state for the notice handlers.
+pub struct NoticeState<SR: SiteRepository> {
+    /// The site service.
+    pub svc:   Arc<SiteService<SR>>,
+    /// Cleared after a change, so the middleware sees it.
+    pub cache: Arc<SiteCache>,
+}
+
+impl<SR: SiteRepository> Clone for NoticeState<SR> {
+    fn clone(&self) -> Self { Self { svc: self.svc.clone(), cache: self.cache.clone() } }
+}
+
+/// Body of `PUT /admin/notice`.
+#[derive(Debug, Deserialize)]
+pub struct NoticeRequest {
+    /// Plain text, at most 500 characters.
+    #[serde(default)]
+    pub message: String,
+    pub enabled: bool,
+}
+
+// ─── Handlers ─────────────────────────────────────────────────────────────────
+
+/// `GET /admin/notice` — the site's notice, shown or not.
+pub async fn get_notice(_admin: AdminUser, CurrentSite(site): CurrentSite) -> Json<SiteNoticeResponse> {
+    Json(SiteNoticeResponse::from(&site))
+}
+
+/// `PUT /admin/notice` — replace the site's notice and switch it on or off.
+pub async fn set_notice<SR: SiteRepository>(
+    State(s): State<NoticeState<SR>>,
+    AdminUser(admin): AdminUser,
+    CurrentSite(site): CurrentSite,
+    Json(body): Json<NoticeRequest>,
+) -> Result<Json<SiteNoticeResponse>, ApiError> {
+    let site = s.svc.set_notice(site.id, &body.message, body.enabled).await?;
+    s.cache.clear();
+    tracing::info!(admin = %admin.username, enabled = site.notice_enabled, "site notice updated");
+    Ok(Json(SiteNoticeResponse::from(&site)))
+}
@@ -1,18 +1,28 @@
-//! Overboard handler: recent posts across all boards.
+//! Overboard handlers: recent posts across all boards (`/overboard`) and the
+//! catalog of recently bumped threads across all boards (`/all`).
 
 use axum::{
     extract::{Query, State},
     response::IntoResponse,
+    Json,
 };
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
 use std::sync::Arc;
 use sha2::{Digest, Sha256};
 
-use crate::axum::templates::{OverboardPostDisplay, OverboardTemplate};
+use crate::axum::i18n::I18n;
+use crate::axum::middleware::media_urls::MediaLinks;
+use crate::axum::middleware::site::CurrentSite;
+use crate::axum::templates::{AllThreadsTemplate, OverboardPostDisplay, OverboardTemplate, OverboardThreadDisplay};
 use crate::common::{
-    dtos::PaginationQuery,
+    dtos::{OverboardThreadResponse, PaginationQuery},
     errors::ApiError,
+    pagination::PageResponse,
 };
-use domains::models::{OverboardPost, Page, PostId};
+use domains::models::{BoardId, OverboardPost, Page, Paginated, PostId, SiteId};
+use services::board::BoardRepo;
+use services::thread::ThreadRepo;
 
 /// Combined state for the overboard handler.
 pub struct OverboardState<BR, PR> {
@@ -31,9 +41,12 @@ impl<BR, PR> Clone for OverboardState<BR, PR> {
     }
 }
 
-/// `GET /overboard` — recent posts across all boards, rendered as HTML.
+/// `GET /overboard` — recent posts across the site's boards, rendered as HTML.
 pub async fn show_overboard<BR, PR>(
     State(state): State<OverboardState<BR, PR>>,
+    CurrentSite(site): CurrentSite,
+    MediaLinks(media): MediaLinks,
+    i18n: I18n,
     Query(q): Query<PaginationQuery>,
 ) -> Result<impl IntoResponse, ApiError>
 where
@@ -44,14 +57,14 @@ where
 
     let boards = state
         .board_service
-        .list_boards(Page::new(1))
+        .list_boards(site.id, Page::new(1))
         .await
         .map_err(ApiError::from)?
         .items;
 
     let paginated = state
         .post_service
-        .list_overboard(page)
+        .list_overboard(site.id, page)
         .await
         .map_err(ApiError::from)?;
 
@@ -64,6 +77,14 @@ where
         .await
         .map_err(ApiError::from)?;
 
+    // Each post is formatted under its own board's toggles. A board missing
+    // from the list above gets every toggle on.
+    let mut formats = std::collections::HashMap::new();
+    for board in boards.iter().filter(|b| paginated.items.iter().any(|p| p.board_slug == b.slug.as_str())) {
+        let config = state.board_service.get_config(board.id).await.map_err(ApiError::from)?;
+        formats.insert(board.slug.as_str().to_owned(), rb_format::Options::for_board(&config));
+    }
+
     let recent_posts: Vec<OverboardPostDisplay> = paginated.items.into_iter().map(|post| {
         let attachments = attachments_map.remove(&post.id).unwrap_or_default();
 
@@ -82,7 +103,10 @@ where
 
         let ip_hash_short = post.ip_hash.0.chars().take(10).collect();
 
-        OverboardPostDisplay { post, attachments, poster_id, tripcode_level, ip_hash_short }
+        let format = formats.get(&post.board_slug).copied().unwrap_or_default();
+        let body_html = rb_format::render(&post.body, &format);
+
+        OverboardPostDisplay { post, attachments, poster_id, tripcode_level, ip_hash_short, body_html }
     }).collect();
 
     let tmpl = OverboardTemplate {
@@ -90,16 +114,123 @@ where
         recent_posts,
         current_page: q.page,
         total_pages,
+        media,
+        i18n,
     };
     Ok(tmpl)
 }
 
-/// Minimal trait for sources that can list posts across all boards.
+/// Combined state for the `/all` catalog handlers.
+pub struct AllThreadsState<BR, TR> {
+    /// Board service, for the navigation header and each board's formatting.
+    pub board_service:  Arc<BR>,
+    /// Thread service used to list threads across all boards.
+    pub thread_service: Arc<TR>,
+}
+
+impl<BR, TR> Clone for AllThreadsState<BR, TR> {
+    fn clone(&self) -> Self {
+        Self {
+            board_service:  self.board_service.clone(),
+            thread_service: self.thread_service.clone(),
+        }
+    }
+}
+
+/// One page of the `/all` catalog, with OP excerpts rendered under each
+/// thread's own board's formatting toggles.
+async fn all_threads_page<BR: BoardRepo, TR: ThreadRepo>(
+    state: &AllThreadsState<BR, TR>,
+    site: SiteId,
+    page: Page,
+) -> Result<Paginated<OverboardThreadDisplay>, ApiError> {
+    // Visible characters of the OP shown under each thumbnail, as in a board catalog.
+    const EXCERPT_CHARS: usize = 200;
+
+    let paginated = state
+        .thread_service
+        .list_overboard_threads(site, page)
+        .await
+        .map_err(ApiError::from)?;
+
+    let mut formats: HashMap<BoardId, rb_format::Options> = HashMap::new();
+    for thread in &paginated.items {
+        if let Entry::Vacant(slot) = formats.entry(thread.summary.board_id) {
+            let config = state.board_service.get_config(thread.summary.board_id).await.map_err(ApiError::from)?;
+            slot.insert(rb_format::Options::for_board(&config));
+        }
+    }
+
+    let items = paginated.items.into_iter().map(|thread| {
+        let format = formats[&thread.summary.board_id];
+        let excerpt_html = rb_format::snippet(&thread.summary.op_body, &format, EXCERPT_CHARS);
+        OverboardThreadDisplay { thread, excerpt_html }
+    }).collect();
+    Ok(Paginated::new(items, paginated.total, paginated.page, paginated.page_size))
+}
+
+/// `GET /all` — catalog of the most recently bumped threads across the
+/// site's boards, rendered as HTML. Boards hidden from the overboard are left out.
+pub async fn show_all_html<BR: BoardRepo, TR: ThreadRepo>(
+    State(state): State<AllThreadsState<BR, TR>>,
+    CurrentSite(site): CurrentSite,
+    MediaLinks(media): MediaLinks,
+    i18n: I18n,
+    Query(q): Query<PaginationQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let boards = state
+        .board_service
+        .list_boards(site.id, Page::new(1))
+        .await
+        .map_err(ApiError::from)?
+        .items;
+    let paginated = all_threads_page(&state, site.id, Page::new(q.page)).await?;
+
+    Ok(AllThreadsTemplate {
+        i18n,
+        boards,
+        total_pages:  paginated.total_pages() as u32,
+        current_page: paginated.page.0,
+        threads:      paginated.items,
+        media,
+    })
+}
+
+/// `GET /all.json` — the `/all` catalog as a JSON page of threads.
+pub async fn list_all_json<BR: BoardRepo, TR: ThreadRepo>(
+    State(state): State<AllThreadsState<BR, TR>>,
+    CurrentSite(site): CurrentSite,
+    MediaLinks(media): MediaLinks,
+    Query(q): Query<PaginationQuery>,
+) -> Result<Json<PageResponse<OverboardThreadResponse>>, ApiError> {
+    let paginated = all_threads_page(&state, site.id, Page::new(q.page)).await?;
+    let items = paginated.items.into_iter().map(|d| {
+        let t = d.thread.summary;
+        OverboardThreadResponse {
+            url:           format!("/board/{}/thread/{}", d.thread.board_slug, t.thread_id),
+            board_slug:    d.thread.board_slug,
+            board_title:   d.thread.board_title,
+            thread_id:     t.thread_id,
+            subject:       t.op_subject,
+            excerpt_html:  d.excerpt_html,
+            thumbnail_url: t.thumbnail_key.as_ref().filter(|_| !t.thumbnail_spoiler).map(|k| media.url(k)),
+            spoiler:       t.thumbnail_spoiler,
+            reply_count:   t.reply_count,
+            sticky:        t.sticky,
+            closed:        t.closed,
+            created_at:    t.op_created_at,
+            bumped_at:     t.bumped_at,
+        }
+    }).collect();
+    Ok(Json(Paginated::new(items, paginated.total, paginated.page, paginated.page_size).into()))
+}
+
+/// Minimal trait for sources that can list posts across a site's boards.
 ///
 /// Implemented by `PostService` via a blanket impl below.
 #[async_trait::async_trait]
 pub trait OverboardPostSource: Send + Sync + 'static {
-    async fn list_overboard(&self, page: Page) -> Result<domains::models::Paginated<OverboardPost>, services::post::PostError>;
+    async fn list_overboard(&self, site: SiteId, page: Page) -> Result<domains::models::Paginated<OverboardPost>, services::post::PostError>;
     /// Bulk-fetch attachments for a slice of post IDs. Used by the overboard view.
     async fn find_post_attachments(
         &self,
@@ -118,8 +249,8 @@ where
     RL: domains::ports::RateLimiter,
     MP: domains::ports::MediaProcessor,
 {
-    async fn list_overboard(&self, page: Page) -> Result<domains::models::Paginated<OverboardPost>, services::post::PostError> {
-        self.list_overboard(page).await
+    async fn list_overboard(&self, site: SiteId, page: Page) -> Result<domains::models::Paginated<OverboardPost>, services::post::PostError> {
+        self.list_overboard(site, page).await
     }
     async fn find_post_attachments(
         &self,
@@ -0,0 +1,165 @@
+//! Static page handlers: admin-edited pages (rules, FAQ) and their management.
+//!
+//! Routes:
+//!   `GET    /:page`               — a site page (HTML)
+//!   `GET    /board/:slug/:page`   — a board page (HTML; board-scoped)
+//!   `GET    /admin/pages`         — list the site's pages (JSON)
+//!   `POST   /admin/pages`         — create a page
+//!   `PUT    /admin/pages/:id`     — replace a page's slug, title and body
+//!   `DELETE /admin/pages/:id`     — delete a page
+//!
+//! Fixed routes such as `/overboard` or `/board/:slug/catalog` take priority
+//! over pages; `PageService` refuses the slugs they use.
+
+use axum::{
+    extract::{Extension, Path, State},
+    http::StatusCode,
+    Json,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::axum::i18n::I18n;
+use crate::axum::middleware::{auth::AdminUser, board_config::ExtractedBoardConfig, site::CurrentSite};
+use crate::axum::templates::PageTemplate;
+use crate::common::{dtos::PageResponse, errors::ApiError};
+use domains::models::{BoardId, PageId};
+use domains::ports::StaticPageRepository;
+use services::board::BoardRepo;
+use services::page::PageService;
+
+// ─── State ────────────────────────────────────────────────────────────────────
+
+/// Shared state for the page management handlers.
+pub struct PageAdminState<PR, BR>
+where
+    PR: StaticPageRepository,
+    BR: BoardRepo,
+{
+    /// The page service.
+    pub svc:    Arc<PageService<PR>>,
+    /// Resolves the board a page is created on.
+    pub boards: Arc<BR>,
+}
+
+impl<PR, BR> Clone for PageAdminState<PR, BR>
+where
+    PR: StaticPageRepository,
+    BR: BoardRepo,
+{
+    fn clone(&self) -> Self { Self { svc: self.svc.clone(), boards: self.boards.clone() } }
+}
+
+/// Body of `POST /admin/pages` and `PUT /admin/pages/:id`.
+#[derive(Debug, Deserialize)]
+pub struct PageRequest {
+    pub slug:     String,
+    pub title:    String,
+    /// Markdown.
+    #[serde(default)]
+    pub body:     String,
+    /// The board to create the page on; omitted for a site page. Ignored
+    /// by `PUT`: a page stays where it was created.
+    #[serde(default)]
+    pub board_id: Option<uuid::Uuid>,
+}
+
+// ─── Public pages ─────────────────────────────────────────────────────────────
+
+/// `GET /:page` — a site page.
+pub async fn show_site_page<PR: StaticPageRepository>(
+    State(svc): State<Arc<PageService<PR>>>,
+    CurrentSite(site): CurrentSite,
+    Path(slug): Path<String>,
+    i18n: I18n,
+) -> Result<PageTemplate, ApiError> {
+    let page = svc.get(site.id, None, &slug).await?;
+    let body_html = rb_format::page::render(&page.body);
+    Ok(PageTemplate { i18n, page, board: None, body_html })
+}
+
+/// `GET /board/:slug/:page` — a page of the board.
+pub async fn show_board_page<PR: StaticPageRepository>(
+    State(svc): State<Arc<PageService<PR>>>,
+    Extension(board_ctx): Extension<ExtractedBoardConfig>,
+    Path((_slug, page)): Path<(String, String)>,
+    i18n: I18n,
+) -> Result<PageTemplate, ApiError> {
+    let page = svc.get(board_ctx.board.site_id, Some(board_ctx.board_id), &page).await?;
+    let body_html = rb_format::page::render(&page.body);
+    Ok(PageTemplate { i18n, page, board: Some(board_ctx.board), body_html })
+}
+
+// ─── Management ───────────────────────────────────────────────────────────────
+
+/// `GET /admin/pages` — every page of the site, site pages first.
+pub async fn list_pages<PR, BR>(
+    State(s): State<PageAdminState<PR, BR>>,
+    _admin: AdminUser,
+    CurrentSite(site): CurrentSite,
+) -> Result<Json<Vec<PageResponse>>, ApiError>
+where
+    PR: StaticPageRepository,
+    BR: BoardRepo,
+{
+    let pages = s.svc.list(site.id).await?;
+    Ok(Json(pages.into_iter().map(PageResponse::from).collect()))
+}
+
+/// `POST /admin/pages` — create a site page, or a page of `board_id`.
+/// Returns `201` with the page.
+pub async fn create_page<PR, BR>(
+    State(s): State<PageAdminState<PR, BR>>,
+    _admin: AdminUser,
+    CurrentSite(site): CurrentSite,
+    Json(body): Json<PageRequest>,
+) -> Result<(StatusCode, Json<PageResponse>), ApiError>
+where
+    PR: StaticPageRepository,
+    BR: BoardRepo,
+{
+    let board_id = match body.board_id {
+        Some(id) => {
+            let board = s.boards.get_by_id(BoardId(id)).await?;
+            // Boards of other sites are not the admin's to add pages to.
+            if board.site_id != site.id {
+                return Err(ApiError::NotFound(format!("board {id}")));
+            }
+            Some(board.id)
+        }
+        None => None,
+    };
+    let page = s.svc.create(site.id, board_id, &body.slug, &body.title, &body.body).await?;
+    Ok((StatusCode::CREATED, Json(PageResponse::from(page))))
+}
+
+/// `PUT /admin/pages/:id` — replace a page's slug, title and body.
+pub async fn update_page<PR, BR>(
+    State(s): State<PageAdminState<PR, BR>>,
+    _admin: AdminUser,
+    CurrentSite(site): CurrentSite,
+    Path(id): Path<uuid::Uuid>,
+    Json(body): Json<PageRequest>,
+) -> Result<Json<PageResponse>, ApiError>
+where
+    PR: StaticPageRepository,
+    BR: BoardRepo,
+{
+    let page = s.svc.update(site.id, PageId(id), &body.slug, &body.title, &body.body).await?;
+    Ok(Json(PageResponse::from(page)))
+}
+
+/// `DELETE /admin/pages/:id` — delete a page of the site.
+pub async fn delete_page<PR, BR>(
+    State(s): State<PageAdminState<PR, BR>>,
+    _admin: AdminUser,
+    CurrentSite(site): CurrentSite,
+    Path(id): Path<uuid::Uuid>,
+) -> Result<StatusCode, ApiError>
+where
+    PR: StaticPageRepository,
+    BR: BoardRepo,
+{
+    s.svc.delete(site.id, PageId(id)).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,126 @@
+//! Thread poll handlers: live results and voting.
+//!
+//! Polls are created with their thread through `POST /board/:slug/post`
+//! (see `post_handlers::create_post`); these handlers only read and vote.
+
+use axum::{
+    extract::{Path, State},
+    response::{IntoResponse, Redirect, Response},
+    Form, Json,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::axum::middleware::board_config::ExtractedBoardConfig;
+use crate::axum::middleware::client_ip::ClientIp;
+use crate::common::errors::ApiError;
+use domains::models::{Poll, ThreadId};
+use services::common::utils::hash_ip;
+use services::poll::PollService;
+
+// ─── State ────────────────────────────────────────────────────────────────────
+
+/// Shared state for the poll handlers.
+pub struct PollState<PR: domains::ports::PollRepository> {
+    /// The poll service.
+    pub svc: Arc<PollService<PR>>,
+}
+
+impl<PR: domains::ports::PollRepository> Clone for PollState<PR> {
+    fn clone(&self) -> Self { Self { svc: self.svc.clone() } }
+}
+
+// ─── DTOs ─────────────────────────────────────────────────────────────────────
+
+/// Form body for `POST /board/:slug/thread/:id/poll/vote`.
+#[derive(Deserialize)]
+pub struct VoteForm {
+    /// Zero-based index of the chosen option.
+    pub option: usize,
+}
+
+/// One option in a `PollResults` response.
+#[derive(Serialize)]
+pub struct PollOptionResult {
+    /// The option text.
+    pub text:    String,
+    /// Votes for this option.
+    pub votes:   u64,
+    /// Share of all votes, rounded to a whole percent.
+    pub percent: u64,
+}
+
+/// JSON body returned by the results and vote endpoints.
+#[derive(Serialize)]
+pub struct PollResults {
+    /// The poll question.
+    pub question:    String,
+    /// The options, in display order.
+    pub options:     Vec<PollOptionResult>,
+    /// Votes across all options.
+    pub total_votes: u64,
+}
+
+impl From<Poll> for PollResults {
+    fn from(poll: Poll) -> Self {
+        let options = poll
+            .options
+            .iter()
+            .map(|o| PollOptionResult { text: o.text.clone(), votes: o.votes, percent: poll.percent(o) })
+            .collect();
+        Self { total_votes: poll.total_votes(), question: poll.question, options }
+    }
+}
+
+// ─── Handlers ─────────────────────────────────────────────────────────────────
+
+/// `GET /board/:slug/thread/:id/poll` — current results as JSON.
+///
+/// Polled by `thread.html` to keep the results live.
+pub async fn poll_results<PR>(
+    State(s): State<PollState<PR>>,
+    Path((_slug, thread_id)): Path<(String, uuid::Uuid)>,
+) -> Result<Json<PollResults>, ApiError>
+where
+    PR: domains::ports::PollRepository,
+{
+    let poll = s
+        .svc
+        .find(ThreadId(thread_id))
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("poll in thread {thread_id}")))?;
+    Ok(Json(poll.into()))
+}
+
+/// `POST /board/:slug/thread/:id/poll/vote` — vote once per poster ID.
+///
+/// The voter is the IP hash the thread's poster IDs are derived from.
+/// Requests with `Accept: application/json` get the updated results; plain
+/// form submissions are redirected back to the thread.
+pub async fn vote<PR>(
+    State(s): State<PollState<PR>>,
+    ClientIp(client_ip): ClientIp,
+    axum::extract::Extension(board_ctx): axum::extract::Extension<ExtractedBoardConfig>,
+    Path((slug, thread_id)): Path<(String, uuid::Uuid)>,
+    headers: axum::http::HeaderMap,
+    Form(form): Form<VoteForm>,
+) -> Result<Response, ApiError>
+where
+    PR: domains::ports::PollRepository,
+{
+    // Same daily salt as `create_post`, so the voter matches their poster ID.
+    let daily_salt = chrono::Utc::now().format("%Y-%m-%d").to_string();
+    let voter = hash_ip(&client_ip.to_string(), &daily_salt);
+
+    let poll = s.svc.vote(ThreadId(thread_id), form.option, &voter, &board_ctx.config).await?;
+
+    let wants_json = headers
+        .get(axum::http::header::ACCEPT)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.contains("application/json"));
+    if wants_json {
+        Ok(Json(PollResults::from(poll)).into_response())
+    } else {
+        Ok(Redirect::to(&format!("/board/{slug}/thread/{thread_id}")).into_response())
+    }
+}
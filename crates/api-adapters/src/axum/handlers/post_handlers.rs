@@ -1,29 +1,48 @@
-//! Post handlers: create post/thread.
+//! Post handlers: create post/thread, ban status page, warning acknowledgement.
+
+// Handlers are generic over every service port; the `State<Arc<Service<..>>>`
+// extractor types are long by construction.
+#![allow(clippy::type_complexity)]
 
 use axum::{
-    extract::{Multipart, Path, State},
+    extract::State,
     response::IntoResponse,
 };
-use bytes::Bytes;
 use mime::Mime;
+use md5::Md5;
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
 use std::str::FromStr;
 use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
 
+use crate::axum::i18n::{negotiate, I18n};
 use crate::axum::middleware::board_config::ExtractedBoardConfig;
+use crate::axum::middleware::client_ip::ClientIp;
+use crate::axum::middleware::site::CurrentSite;
+use crate::axum::multipart::{file_too_large, LimitedField, LimitedMultipart};
+use crate::axum::templates::BannedTemplate;
 use crate::common::errors::ApiError;
-use domains::models::ThreadId;
-use domains::ports::{BanRepository, MediaProcessor, MediaStorage, RateLimiter, RawMedia};
-use services::post::{PostDraft, PostService};
+use domains::models::{BanId, ContentHash, ThreadId};
+use domains::ports::{
+    BanRepository, MediaData, MediaProcessor, MediaStorage, RateLimiter, RawMedia, SpooledFile,
+};
+use services::poll::PollDraft;
+use services::post::{has_option, PostDraft, PostService};
 use services::common::utils::hash_ip;
 
 /// `POST /board/:slug/post` — create a new thread or reply.
 ///
 /// Accepts multipart/form-data with fields:
 /// - `thread_id` (optional Uuid) — if present, creates a reply; else starts a new thread
-/// - `name` (optional) — poster name
-/// - `email` (optional) — 'sage' to disable bump
+/// - `name` (optional) — poster name; `name#pass` for a tripcode
+/// - `email` (optional) — options: `sage` to disable bump, `nonoko` to return
+///   to the board instead of the thread
+/// - `subject` (optional, new threads only) — thread subject
 /// - `body` — post body text
 /// - `files` (0..N file parts) — attachments
+/// - `poll_question` / `poll_options` (optional, new threads only) — a poll;
+///   options are one per line. Ignored when the question is blank.
 ///
 /// **Response negotiation**:
 /// - Browser form submissions (`Accept: text/html`, default): 303 redirect to the new post
@@ -31,16 +50,26 @@ use services::common::utils::hash_ip;
 ///   `{ post_number, thread_id, redirect }` so the JS can store the number in
 ///   localStorage for (You) tracking before navigating.
 ///
+/// Requests authenticated with an API key (see `middleware::api_key`) are
+/// tagged `api-key:<name>` for staff and held to the key's rate limit instead
+/// of the board's per-IP one.
+///
 /// The real IP is extracted from the peer address (set by reverse proxy middleware),
 /// immediately SHA-256 hashed with a daily salt, and never stored raw.
+///
+/// File parts are streamed to temporary files (hashed as they are written) rather
+/// than buffered in memory; the files are removed when the request completes.
+/// The body, text fields and field count are held to `MultipartLimits`.
+#[allow(clippy::too_many_arguments)] // one per extractor
 pub async fn create_post<PR, TR, BR, MS, RL, MP>(
     State(post_service): State<Arc<PostService<PR, TR, BR, MS, RL, MP>>>,
-    axum::extract::ConnectInfo(peer_addr): axum::extract::ConnectInfo<std::net::SocketAddr>,
+    ClientIp(client_ip): ClientIp,
     axum::extract::Extension(board_ctx): axum::extract::Extension<ExtractedBoardConfig>,
     current_user: Option<axum::extract::Extension<domains::models::CurrentUser>>,
-    Path(_slug): Path<String>,
+    api_client: Option<axum::extract::Extension<domains::models::ApiClient>>,
+    CurrentSite(site): CurrentSite,
     headers: axum::http::HeaderMap,
-    mut multipart: Multipart,
+    mut multipart: LimitedMultipart,
 ) -> Result<axum::response::Response, ApiError>
 where
     PR: domains::ports::PostRepository,
@@ -50,14 +79,15 @@ where
     RL: RateLimiter,
     MP: MediaProcessor,
 {
-    let raw_ip = peer_addr.ip().to_string();
+    let raw_ip = client_ip.to_string();
     // INVARIANT: IP is hashed immediately; raw value is never stored.
     // Daily salt is derived from the UTC date — rotates at midnight without persistence.
     let daily_salt = chrono::Utc::now().format("%Y-%m-%d").to_string();
     let ip_hash = hash_ip(&raw_ip, &daily_salt);
 
     let is_staff = current_user.is_some();
-    let poster_role = current_user.as_ref().map(|ext| ext.0.role.clone());
+    let poster_role = current_user.as_ref().map(|ext| ext.0.role);
+    let api_tags = api_client.iter().map(|ext| format!("api-key:{}", ext.0.name)).collect();
 
     let mut draft = PostDraft {
         board_id:    board_ctx.board_id,
@@ -65,22 +95,28 @@ where
         body:        String::new(),
         name:        None,
         email:       None,
+        subject:     None,
         ip_hash,
         raw_ip:      Some(raw_ip),
         files:       Vec::new(),
+        spoiler:     false,
         is_staff,
         poster_role,
+        media_prefix: site.media_prefix,
+        tags:        api_tags,
+        poll:        None,
+        via_api_key: api_client.is_some(),
     };
+    // Keeps spooled uploads alive until `create_post` has stored them.
+    let mut spooled: Vec<TempUpload> = Vec::new();
+    let mut poll_question = String::new();
+    let mut poll_options = String::new();
 
-    while let Some(field) = multipart
-        .next_field()
-        .await
-        .map_err(|e| ApiError::BadRequest(format!("multipart error: {e}")))?
-    {
+    while let Some(field) = multipart.next_field().await? {
         let field_name = field.name().unwrap_or("").to_owned();
         match field_name.as_str() {
             "thread_id" => {
-                let val = field.text().await.map_err(|e| ApiError::BadRequest(e.to_string()))?;
+                let val = field.text().await?;
                 if !val.is_empty() {
                     let id = uuid::Uuid::from_str(&val)
                         .map_err(|_| ApiError::BadRequest("invalid thread_id UUID".to_owned()))?;
@@ -88,16 +124,31 @@ where
                 }
             }
             "body" => {
-                draft.body = field.text().await.map_err(|e| ApiError::BadRequest(e.to_string()))?;
+                draft.body = field.text().await?;
             }
             "name" => {
-                let val = field.text().await.map_err(|e| ApiError::BadRequest(e.to_string()))?;
+                let val = field.text().await?;
                 if !val.is_empty() { draft.name = Some(val); }
             }
             "email" => {
-                let val = field.text().await.map_err(|e| ApiError::BadRequest(e.to_string()))?;
+                let val = field.text().await?;
                 if !val.is_empty() { draft.email = Some(val); }
             }
+            "subject" => {
+                let val = field.text().await?;
+                if !val.is_empty() { draft.subject = Some(val); }
+            }
+            "spoiler" => {
+                // Checkbox: present (any non-empty value) means checked.
+                let val = field.text().await?;
+                draft.spoiler = !val.is_empty();
+            }
+            "poll_question" => {
+                poll_question = field.text().await?;
+            }
+            "poll_options" => {
+                poll_options = field.text().await?;
+            }
             "files" => {
                 let content_type = field
                     .content_type()
@@ -107,34 +158,33 @@ where
                     .file_name()
                     .map(|s| s.to_owned())
                     .unwrap_or_else(|| "file".to_owned());
-                let data: Bytes = field.bytes().await.map_err(|e| ApiError::BadRequest(e.to_string()))?;
+                let (guard, file) = spool_file_field(field, board_ctx.config.max_file_size.0).await?;
                 // Skip empty file fields — browsers submit an empty "files" part
                 // when no file is selected; treating it as an attachment causes a
                 // mime-type validation error.
-                if data.is_empty() { continue; }
+                if file.size == 0 { continue; }
+                spooled.push(guard);
                 let mime = Mime::from_str(&content_type).unwrap_or(mime::APPLICATION_OCTET_STREAM);
-                draft.files.push(RawMedia { filename, mime, data });
-            }
-            _ => {
-                // Ignore unknown fields
-                let _ = field.bytes().await;
+                // `strip_metadata` and `thumbnail` are overridden from BoardConfig by PostService.
+                draft.files.push(RawMedia {
+                    filename,
+                    mime,
+                    data: MediaData::Spooled(file),
+                    strip_metadata: true,
+                    thumbnail: None,
+                });
             }
+            // Unknown fields are skipped unread.
+            _ => {}
         }
     }
 
-    let board_slug = board_ctx.board.slug.as_str().to_owned();
-    let result = post_service
-        .create_post(draft, &board_ctx.config)
-        .await
-        .map_err(ApiError::from)?;
-
-    let thread_id = result.thread.id;
-    let post_num  = result.post.post_number;
-
-    let redirect_url = format!(
-        "/board/{}/thread/{}#post-{}",
-        board_slug, thread_id, post_num
-    );
+    if !poll_question.trim().is_empty() {
+        draft.poll = Some(PollDraft {
+            question: poll_question,
+            options:  poll_options.lines().map(str::to_owned).collect(),
+        });
+    }
 
     // Fetch from thread.html JS sends Accept: application/json so it can extract
     // the post_number for (You) localStorage tracking before navigating.
@@ -145,6 +195,35 @@ where
         .map(|v| v.contains("application/json"))
         .unwrap_or(false);
 
+    let board_slug = board_ctx.board.slug.as_str().to_owned();
+    let nonoko = has_option(draft.email.as_deref(), "nonoko");
+    let result = post_service.create_post(draft, &board_ctx.config).await;
+    drop(spooled);
+    let result = match result {
+        Ok(result) => result,
+        Err(e) => {
+            let err = ApiError::from(e);
+            // Browsers get the full ban page rather than a bare JSON 403.
+            if !wants_json {
+                let accept_language = headers.get(axum::http::header::ACCEPT_LANGUAGE).and_then(|v| v.to_str().ok());
+                let i18n = I18n::new(negotiate(accept_language, board_ctx.config.default_locale));
+                if let Some(page) = BannedTemplate::from_error(&err, i18n) {
+                    return Ok(page.into_response());
+                }
+            }
+            return Err(err);
+        }
+    };
+
+    let thread_id = result.thread.id;
+    let post_num  = result.post.post_number;
+
+    let redirect_url = if nonoko {
+        format!("/board/{board_slug}")
+    } else {
+        format!("/board/{}/thread/{}#post-{}", board_slug, thread_id, post_num)
+    };
+
     if wants_json {
         use axum::Json;
         Ok((
@@ -159,3 +238,225 @@ where
         Ok(axum::response::Redirect::to(&redirect_url).into_response())
     }
 }
+
+/// Stream one multipart file part to a temporary file, enforcing the board's
+/// `max_file_size`, capped by `MAX_UPLOAD_KB`, as chunks arrive.
+///
+/// The SHA-256, MD5, size, and leading bytes (for MIME sniffing) are captured while
+/// writing, so nothing downstream has to read the file back just to hash it.
+/// Oversized uploads are aborted at the first chunk past the limit; the partial
+/// file is removed and the remaining body is never read.
+async fn spool_file_field(
+    mut field: LimitedField<'_>,
+    max_kb: u32,
+) -> Result<(TempUpload, SpooledFile), ApiError> {
+    let max_bytes = (max_kb as u64 * 1024).min(field.max_file_bytes());
+    let guard = TempUpload(
+        std::env::temp_dir().join(format!("rb-upload-{}", uuid::Uuid::new_v4())),
+    );
+    let io_err = |e: std::io::Error| ApiError::Internal(format!("failed to spool upload: {e}"));
+    let mut out = tokio::fs::File::create(&guard.0).await.map_err(io_err)?;
+
+    let mut hasher = Sha256::new();
+    let mut md5 = Md5::new();
+    let mut head = bytes::BytesMut::new();
+    let mut size = 0u64;
+    while let Some(chunk) = field.chunk().await? {
+        if size + chunk.len() as u64 > max_bytes {
+            return Err(file_too_large(size + chunk.len() as u64, max_bytes));
+        }
+        if head.len() < SpooledFile::HEAD_LEN {
+            let take = (SpooledFile::HEAD_LEN - head.len()).min(chunk.len());
+            head.extend_from_slice(&chunk[..take]);
+        }
+        hasher.update(&chunk);
+        md5.update(&chunk);
+        out.write_all(&chunk).await.map_err(io_err)?;
+        size += chunk.len() as u64;
+    }
+    out.flush().await.map_err(io_err)?;
+
+    let file = SpooledFile {
+        path: guard.0.clone(),
+        size,
+        hash: ContentHash::new(hex::encode(hasher.finalize())),
+        md5:  hex::encode(md5.finalize()),
+        head: head.freeze(),
+    };
+    Ok((guard, file))
+}
+
+/// A spooled upload that is deleted when dropped.
+struct TempUpload(PathBuf);
+
+impl Drop for TempUpload {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+/// `GET /banned` — show the visitor's active ban, if any.
+///
+/// Uses the same daily-salted IP hash as `create_post`, so it reports exactly
+/// the ban a post attempt would hit.
+pub async fn ban_status<PR, TR, BR, MS, RL, MP>(
+    State(post_service): State<Arc<PostService<PR, TR, BR, MS, RL, MP>>>,
+    ClientIp(client_ip): ClientIp,
+    crate::axum::middleware::accept::WantsJson(wants_json): crate::axum::middleware::accept::WantsJson,
+    i18n: I18n,
+) -> Result<axum::response::Response, ApiError>
+where
+    PR: domains::ports::PostRepository,
+    TR: domains::ports::ThreadRepository,
+    BR: BanRepository,
+    MS: MediaStorage,
+    RL: RateLimiter,
+    MP: MediaProcessor,
+{
+    let daily_salt = chrono::Utc::now().format("%Y-%m-%d").to_string();
+    let raw_ip = client_ip.to_string();
+    let ip_hash = hash_ip(&raw_ip, &daily_salt);
+    // (reason, issued_at, expires_at) of the IP ban, else of the ASN ban.
+    let ban = match post_service.find_active_ban(&ip_hash).await.map_err(ApiError::from)? {
+        Some(ban) => Some((ban.reason, ban.created_at, ban.expires_at)),
+        None => post_service
+            .find_active_asn_ban(&raw_ip)
+            .await
+            .map_err(ApiError::from)?
+            .map(|ban| (ban.reason, ban.created_at, ban.expires_at)),
+    };
+
+    if wants_json {
+        let warning = post_service.find_pending_warning(&ip_hash).await.map_err(ApiError::from)?;
+        return Ok(axum::Json(serde_json::json!({
+            "banned":     ban.is_some(),
+            "reason":     ban.as_ref().map(|b| b.0.clone()),
+            "issued_at":  ban.as_ref().map(|b| b.1),
+            "expires_at": ban.as_ref().and_then(|b| b.2),
+            "warning":    warning.map(|w| serde_json::json!({
+                "id":         w.id,
+                "reason":     w.reason,
+                "created_at": w.created_at,
+            })),
+        })).into_response());
+    }
+
+    let page = match ban {
+        Some((reason, issued_at, expires_at)) => BannedTemplate {
+            banned:     true,
+            reason,
+            issued_at:  Some(issued_at),
+            expires_at,
+            boards:     i18n.t("ban-scope-all"),
+            i18n,
+        },
+        None => BannedTemplate {
+            banned:     false,
+            reason:     String::new(),
+            issued_at:  None,
+            expires_at: None,
+            boards:     String::new(),
+            i18n,
+        },
+    };
+    Ok(page.into_response())
+}
+
+/// `POST /warning/:id/ack` — acknowledge a moderator's warning.
+///
+/// The warning must be pending for the visitor's daily IP hash (the one
+/// `create_post` checks); otherwise 404. JSON clients get 204; the notice's
+/// form is redirected back to the page it was on, or `/`.
+pub async fn acknowledge_warning<PR, TR, BR, MS, RL, MP>(
+    State(post_service): State<Arc<PostService<PR, TR, BR, MS, RL, MP>>>,
+    ClientIp(client_ip): ClientIp,
+    axum::extract::Path(id): axum::extract::Path<uuid::Uuid>,
+    crate::axum::middleware::accept::WantsJson(wants_json): crate::axum::middleware::accept::WantsJson,
+    headers: axum::http::HeaderMap,
+) -> Result<axum::response::Response, ApiError>
+where
+    PR: domains::ports::PostRepository,
+    TR: domains::ports::ThreadRepository,
+    BR: BanRepository,
+    MS: MediaStorage,
+    RL: RateLimiter,
+    MP: MediaProcessor,
+{
+    let daily_salt = chrono::Utc::now().format("%Y-%m-%d").to_string();
+    let ip_hash = hash_ip(&client_ip.to_string(), &daily_salt);
+    post_service
+        .acknowledge_warning(BanId(id), &ip_hash)
+        .await
+        .map_err(ApiError::from)?;
+
+    if wants_json {
+        return Ok(axum::http::StatusCode::NO_CONTENT.into_response());
+    }
+    let back = headers
+        .get(axum::http::header::REFERER)
+        .and_then(|v| v.to_str().ok())
+        .map(referer_path)
+        .unwrap_or("/");
+    Ok(axum::response::Redirect::to(back).into_response())
+}
+
+/// `POST /board/:slug/post/:id/delete` — a poster deletes their own post.
+///
+/// The post must have been made from the visitor's daily IP hash (the one
+/// `create_post` stores), so a poster can take back a post from the same
+/// address on the same day; otherwise 403. It is left as a tombstone. JSON
+/// clients get 204; the thread's form is redirected back to the page it was
+/// on, or the board.
+pub async fn delete_own_post<PR, TR, BR, MS, RL, MP>(
+    State(post_service): State<Arc<PostService<PR, TR, BR, MS, RL, MP>>>,
+    ClientIp(client_ip): ClientIp,
+    axum::extract::Extension(board_ctx): axum::extract::Extension<ExtractedBoardConfig>,
+    axum::extract::Path((slug, post_id)): axum::extract::Path<(String, uuid::Uuid)>,
+    crate::axum::middleware::accept::WantsJson(wants_json): crate::axum::middleware::accept::WantsJson,
+    headers: axum::http::HeaderMap,
+) -> Result<axum::response::Response, ApiError>
+where
+    PR: domains::ports::PostRepository,
+    TR: domains::ports::ThreadRepository,
+    BR: BanRepository,
+    MS: MediaStorage,
+    RL: RateLimiter,
+    MP: MediaProcessor,
+{
+    let daily_salt = chrono::Utc::now().format("%Y-%m-%d").to_string();
+    let ip_hash = hash_ip(&client_ip.to_string(), &daily_salt);
+    post_service
+        .delete_own_post(domains::models::PostId(post_id), board_ctx.board_id, &ip_hash)
+        .await
+        .map_err(ApiError::from)?;
+
+    if wants_json {
+        return Ok(axum::http::StatusCode::NO_CONTENT.into_response());
+    }
+    let board = format!("/board/{slug}");
+    let back = headers
+        .get(axum::http::header::REFERER)
+        .and_then(|v| v.to_str().ok())
+        .map(referer_path)
+        .filter(|path| *path != "/")
+        .unwrap_or(&board);
+    Ok(axum::response::Redirect::to(back).into_response())
+}
+
+/// The path (and query) of a `Referer`, so a redirect never leaves the site.
+///
+/// Falls back to `/` for anything that is not a same-site path.
+fn referer_path(referer: &str) -> &str {
+    let path = match referer.find("://") {
+        Some(scheme_end) => {
+            let rest = &referer[scheme_end + 3..];
+            rest.find('/').map_or("/", |at| &rest[at..])
+        }
+        None => referer,
+    };
+    if path.starts_with('/') && !path.starts_with("//") && !path.starts_with("/\\") {
+        path
+    } else {
+        "/"
+    }
+}
@@ -0,0 +1,116 @@
+//! Post reaction handler.
+
+use axum::{
+    extract::{Path, State},
+    response::{IntoResponse, Redirect, Response},
+    Form, Json,
+};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::axum::middleware::board_config::ExtractedBoardConfig;
+use crate::axum::middleware::client_ip::ClientIp;
+use crate::common::errors::ApiError;
+use domains::models::{PostId, Reaction, ReactionCount};
+use domains::ports::{PostRepository, RateLimiter, ReactionRepository, ThreadRepository};
+use services::common::utils::hash_ip;
+use services::reaction::ReactionService;
+
+// ─── State ────────────────────────────────────────────────────────────────────
+
+/// Shared state for the reaction handler.
+pub struct ReactionState<RR, PR, TR, RL>
+where
+    RR: ReactionRepository,
+    PR: PostRepository,
+    TR: ThreadRepository,
+    RL: RateLimiter,
+{
+    /// The reaction service.
+    pub svc: Arc<ReactionService<RR, PR, TR, RL>>,
+}
+
+impl<RR, PR, TR, RL> Clone for ReactionState<RR, PR, TR, RL>
+where
+    RR: ReactionRepository,
+    PR: PostRepository,
+    TR: ThreadRepository,
+    RL: RateLimiter,
+{
+    fn clone(&self) -> Self { Self { svc: self.svc.clone() } }
+}
+
+// ─── DTOs ─────────────────────────────────────────────────────────────────────
+
+/// Form body for `POST /board/:slug/post/:post_id/react`.
+#[derive(Deserialize)]
+pub struct ReactForm {
+    /// A `Reaction` name, e.g. `"laugh"`.
+    pub reaction:  String,
+    /// The thread to return to after a plain form submission.
+    pub thread_id: Option<uuid::Uuid>,
+}
+
+/// One entry of the JSON response: a reaction and how many posters left it.
+#[derive(Serialize)]
+pub struct ReactionCountJson {
+    /// The `Reaction` name.
+    pub reaction: Reaction,
+    /// The emoji shown for it.
+    pub emoji:    &'static str,
+    /// How many posters left it.
+    pub count:    u64,
+}
+
+impl From<ReactionCount> for ReactionCountJson {
+    fn from(c: ReactionCount) -> Self {
+        Self { reaction: c.reaction, emoji: c.reaction.emoji(), count: c.count }
+    }
+}
+
+// ─── Handlers ─────────────────────────────────────────────────────────────────
+
+/// `POST /board/:slug/post/:post_id/react` — react once per post.
+///
+/// The reactor is identified by the same daily IP hash as `create_post`.
+/// Requests with `Accept: application/json` get the post's updated counts;
+/// plain form submissions are redirected to the form's thread, or the board.
+pub async fn react<RR, PR, TR, RL>(
+    State(s): State<ReactionState<RR, PR, TR, RL>>,
+    ClientIp(client_ip): ClientIp,
+    axum::extract::Extension(board_ctx): axum::extract::Extension<ExtractedBoardConfig>,
+    Path((slug, post_id)): Path<(String, uuid::Uuid)>,
+    headers: axum::http::HeaderMap,
+    Form(form): Form<ReactForm>,
+) -> Result<Response, ApiError>
+where
+    RR: ReactionRepository,
+    PR: PostRepository,
+    TR: ThreadRepository,
+    RL: RateLimiter,
+{
+    let reaction = Reaction::from_str(&form.reaction).map_err(ApiError::BadRequest)?;
+    let daily_salt = chrono::Utc::now().format("%Y-%m-%d").to_string();
+    let voter = hash_ip(&client_ip.to_string(), &daily_salt);
+
+    let counts = s
+        .svc
+        .react(PostId(post_id), reaction, &voter, board_ctx.board_id, &board_ctx.config)
+        .await?;
+
+    let wants_json = headers
+        .get(axum::http::header::ACCEPT)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.contains("application/json"));
+    if wants_json {
+        let counts: Vec<ReactionCountJson> = counts.into_iter().map(Into::into).collect();
+        Ok(Json(counts).into_response())
+    } else {
+        let back = match form.thread_id {
+            Some(thread_id) => format!("/board/{slug}/thread/{thread_id}"),
+            None => format!("/board/{slug}"),
+        };
+        Ok(Redirect::to(&back).into_response())
+    }
+}
@@ -12,6 +12,7 @@ use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::common::{dtos::PaginationQuery, errors::ApiError};
+use crate::axum::i18n::I18n;
 use crate::axum::middleware::auth::StaffUser;
 use domains::models::StaffMessageId;
 use services::staff_message::StaffMessageService;
@@ -55,6 +56,7 @@ pub async fn inbox<MR>(
     State(s): State<StaffMessageState<MR>>,
     StaffUser(current): StaffUser,
     Query(q): Query<PaginationQuery>,
+    i18n: I18n,
 ) -> Result<impl IntoResponse, ApiError>
 where
     MR: domains::ports::StaffMessageRepository,
@@ -69,6 +71,7 @@ where
         current_page: q.page,
         total_pages,
         total,
+        i18n,
     })
 }
 
@@ -78,9 +81,11 @@ where
 pub async fn compose_page(
     _staff: StaffUser,
     Query(q): Query<ComposeQuery>,
+    i18n: I18n,
 ) -> impl IntoResponse {
     crate::axum::templates::StaffComposeTemplate {
         to_user_id: q.to.unwrap_or_default(),
+        i18n,
     }
 }
 
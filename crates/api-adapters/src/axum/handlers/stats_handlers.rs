@@ -0,0 +1,99 @@
+//! Board statistics handlers: the public stats page and the admin overview.
+//!
+//! Routes:
+//!   `GET /board/:slug/stats` — a board's last 24 hours and 30 days (HTML; board-scoped)
+//!   `GET /admin/stats`       — daily activity of the site's boards (JSON; admin)
+//!
+//! Both read the buckets written by the stats rollup job, so the current hour
+//! lags by up to `STATS_ROLLUP_INTERVAL_SECS`.
+
+use axum::{
+    extract::{Extension, Query, State},
+    Json,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::axum::i18n::I18n;
+use crate::axum::middleware::{auth::AdminUser, board_config::ExtractedBoardConfig, site::CurrentSite};
+use crate::axum::templates::BoardStatsTemplate;
+use crate::common::{
+    dtos::{BoardStatsResponse, SiteStatsResponse},
+    errors::ApiError,
+};
+use domains::ports::StatsRepository;
+use services::board::{BoardError, BoardRepo};
+use services::stats::{StatsService, BOARD_STATS_DAYS};
+
+// ─── State ────────────────────────────────────────────────────────────────────
+
+/// Shared state for the admin overview.
+pub struct StatsAdminState<SR, BR>
+where
+    SR: StatsRepository,
+    BR: BoardRepo,
+{
+    /// The stats service.
+    pub svc:    Arc<StatsService<SR>>,
+    /// Names the boards in the overview.
+    pub boards: Arc<BR>,
+}
+
+impl<SR, BR> Clone for StatsAdminState<SR, BR>
+where
+    SR: StatsRepository,
+    BR: BoardRepo,
+{
+    fn clone(&self) -> Self { Self { svc: self.svc.clone(), boards: self.boards.clone() } }
+}
+
+/// Query parameters of `GET /admin/stats`.
+#[derive(Debug, Deserialize)]
+pub struct OverviewQuery {
+    /// Days to cover, today included; clamped to 1..=90. Default: 30.
+    pub days: Option<u32>,
+}
+
+// ─── Handlers ─────────────────────────────────────────────────────────────────
+
+/// `GET /board/:slug/stats` — the board's stats page.
+pub async fn show_board_stats<SR: StatsRepository>(
+    State(svc): State<Arc<StatsService<SR>>>,
+    Extension(board_ctx): Extension<ExtractedBoardConfig>,
+    i18n: I18n,
+) -> Result<BoardStatsTemplate, ApiError> {
+    let report = svc.board_report(board_ctx.board_id, Utc::now()).await?;
+    Ok(BoardStatsTemplate { i18n, board: board_ctx.board, config: board_ctx.config, report })
+}
+
+/// `GET /admin/stats?days=30` — the site's daily activity and the totals of
+/// each board, busiest first.
+pub async fn site_stats_overview<SR, BR>(
+    State(s): State<StatsAdminState<SR, BR>>,
+    _admin: AdminUser,
+    CurrentSite(site): CurrentSite,
+    Query(query): Query<OverviewQuery>,
+) -> Result<Json<SiteStatsResponse>, ApiError>
+where
+    SR: StatsRepository,
+    BR: BoardRepo,
+{
+    let overview = s.svc.site_overview(site.id, query.days.unwrap_or(BOARD_STATS_DAYS), Utc::now()).await?;
+    let mut boards = Vec::with_capacity(overview.boards.len());
+    for (board_id, totals) in overview.boards {
+        let board = match s.boards.get_by_id(board_id).await {
+            Ok(board) => board,
+            // Deleted since the buckets were read.
+            Err(BoardError::NotFound { .. }) => continue,
+            Err(e) => return Err(e.into()),
+        };
+        boards.push(BoardStatsResponse {
+            board_id,
+            slug: board.slug.to_string(),
+            title: board.title,
+            totals,
+        });
+    }
+    Ok(Json(SiteStatsResponse { days: overview.daily.len() as u32, daily: overview.daily, boards }))
+}
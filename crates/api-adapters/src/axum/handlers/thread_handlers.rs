@@ -12,16 +12,21 @@ use axum::{
 use std::sync::Arc;
 
 use crate::axum::{
-    middleware::board_config::ExtractedBoardConfig,
-    templates::{BoardTemplate, BoardThreadDisplay, CatalogTemplate, PostDisplay, ThreadTemplate},
+    handlers::watch_handlers::watcher_token,
+    i18n::I18n,
+    middleware::{board_config::ExtractedBoardConfig, client_ip::ClientIp, media_urls::MediaLinks},
+    templates::{BoardTemplate, BoardThreadDisplay, CatalogTemplate, CatalogThreadDisplay, PostDisplay, ThreadTemplate},
 };
 use sha2::{Digest, Sha256};
 use crate::common::{
-    dtos::PaginationQuery,
+    dtos::{
+        AttachmentResponse, CatalogQuery, PaginationQuery, PostsAfterQuery, ThreadIdsQuery, ThreadPostResponse,
+        ThreadQuery, ThreadSummaryResponse,
+    },
     errors::ApiError,
     pagination::PageResponse,
 };
-use domains::models::{Page, Thread, ThreadId, ThreadSummary};
+use domains::models::{CatalogSort, CurrentUser, Page, Thread, ThreadId, ThreadSummary, Viewer};
 
 // ── Public HTML views ─────────────────────────────────────────────────────────
 
@@ -29,11 +34,13 @@ use domains::models::{Page, Thread, ThreadId, ThreadSummary};
 pub async fn show_board_html<TR: services::thread::ThreadRepo>(
     State(thread_service): State<Arc<TR>>,
     axum::extract::Extension(board_ctx): axum::extract::Extension<ExtractedBoardConfig>,
+    MediaLinks(media): MediaLinks,
+    i18n: I18n,
     Query(q): Query<PaginationQuery>,
 ) -> Result<impl IntoResponse, ApiError>
 {
     let all_threads = thread_service
-        .get_catalog(board_ctx.board_id)
+        .get_catalog(board_ctx.board_id, CatalogSort::Bump, None)
         .await
         .map_err(ApiError::from)?;
 
@@ -43,6 +50,7 @@ pub async fn show_board_html<TR: services::thread::ThreadRepo>(
     let start     = page_idx * PAGE_SIZE;
     let total_pages = total.div_ceil(PAGE_SIZE).max(1) as u32;
 
+    let format = rb_format::Options::for_board(&board_ctx.config);
     let threads: Vec<BoardThreadDisplay> = all_threads
         .into_iter()
         .skip(start)
@@ -58,31 +66,69 @@ pub async fn show_board_html<TR: services::thread::ThreadRepo>(
                 else if tc.starts_with("!!") { "secure" }
                 else { "insecure" }
             });
-            BoardThreadDisplay { thread: t, poster_id, tripcode_level }
+            let op_body_html = rb_format::render(&t.op_body, &format);
+            BoardThreadDisplay { thread: t, poster_id, tripcode_level, op_body_html }
         })
         .collect();
 
+    let blotter = thread_service
+        .find_blotter(board_ctx.board.site_id, board_ctx.board_id)
+        .await
+        .map_err(ApiError::from)?;
+
     let tmpl = BoardTemplate {
         board:        board_ctx.board,
         config:       board_ctx.config,
         total_pages,
         threads,
         current_page: q.page,
+        media,
+        blotter,
+        i18n,
     };
     Ok(tmpl)
 }
 /// `GET /board/:slug/catalog` — catalog grid rendered as HTML.
+///
+/// `?sort=bump|reply_count|creation|random` picks the order and `?q=` keeps
+/// only threads whose OP subject or body contains the text.
 pub async fn show_catalog_html<TR: services::thread::ThreadRepo>(
     State(thread_service): State<Arc<TR>>,
     axum::extract::Extension(board_ctx): axum::extract::Extension<ExtractedBoardConfig>,
+    MediaLinks(media): MediaLinks,
+    i18n: I18n,
+    Query(q): Query<CatalogQuery>,
 ) -> Result<impl IntoResponse, ApiError>
 {
+    // Visible characters of the OP shown under each catalog thumbnail.
+    const EXCERPT_CHARS: usize = 200;
+
+    let format = rb_format::Options::for_board(&board_ctx.config);
     let threads = thread_service
-        .get_catalog(board_ctx.board_id)
+        .get_catalog(board_ctx.board_id, q.sort, q.q.as_deref())
+        .await
+        .map_err(ApiError::from)?
+        .into_iter()
+        .map(|thread| {
+            let excerpt_html = rb_format::snippet(&thread.op_body, &format, EXCERPT_CHARS);
+            CatalogThreadDisplay { thread, excerpt_html }
+        })
+        .collect();
+    let blotter = thread_service
+        .find_blotter(board_ctx.board.site_id, board_ctx.board_id)
         .await
         .map_err(ApiError::from)?;
 
-    let tmpl = CatalogTemplate { board: board_ctx.board, threads, config: board_ctx.config };
+    let tmpl = CatalogTemplate {
+        board:   board_ctx.board,
+        threads,
+        config:  board_ctx.config,
+        media,
+        i18n,
+        sort:    q.sort,
+        filter:  q.q.unwrap_or_default(),
+        blotter,
+    };
     Ok(tmpl)
 }
 
@@ -90,13 +136,22 @@ pub async fn show_catalog_html<TR: services::thread::ThreadRepo>(
 ///
 /// Shows all posts in the thread (up to bump limit, 500) without pagination.
 /// Staff with `can_delete()` receive the mod toolbar via `viewer_role`.
+/// `?quote=N` pre-fills the reply box with `>>N`. For viewers with a
+/// `watcher` cookie, a watched thread is marked read up to its last post.
+#[allow(clippy::too_many_arguments)] // one per extractor
 pub async fn show_thread_html<TR: services::thread::ThreadRepo>(
     State(thread_service): State<Arc<TR>>,
     axum::extract::Extension(board_ctx): axum::extract::Extension<ExtractedBoardConfig>,
     Path((_slug, thread_id)): Path<(String, uuid::Uuid)>,
+    Query(q): Query<ThreadQuery>,
+    MediaLinks(media): MediaLinks,
+    i18n: I18n,
+    headers: axum::http::HeaderMap,
     maybe_user: Option<axum::extract::Extension<domains::models::CurrentUser>>,
+    client_ip: Option<ClientIp>,
 ) -> Result<impl IntoResponse, ApiError>
 {
+    let viewer = thread_viewer(maybe_user.as_ref().map(|u| &u.0), client_ip.map(|ClientIp(ip)| ip));
     let viewer_role: Option<String> = maybe_user
         .and_then(|axum::extract::Extension(u)| {
             if u.can_delete() {
@@ -119,20 +174,93 @@ pub async fn show_thread_html<TR: services::thread::ThreadRepo>(
 
     // Load ALL posts — no pagination. Thread view shows every reply up to bump limit.
     let all_posts = thread_service
-        .list_all_posts(ThreadId(thread_id))
+        .list_all_posts(ThreadId(thread_id), &viewer)
         .await
         .map_err(ApiError::from)?;
+    // A shadowbanned OP hides its whole thread from everyone else.
+    if thread.op_post_id.is_some_and(|op| !all_posts.iter().any(|p| p.id == op)) {
+        return Err(ApiError::NotFound(format!("thread {thread_id}")));
+    }
 
     let is_closed = thread.closed;
-    let thread_id_str = thread_id.to_string();
+
+    // Viewing a watched thread marks every post on the page read.
+    let watched = match watcher_token(&headers) {
+        Some(watcher) => {
+            let last_post = all_posts.last().map_or(0, |p| p.post_number);
+            thread_service
+                .mark_watched_seen(watcher, thread.id, last_post)
+                .await
+                .map_err(ApiError::from)?
+        }
+        None => false,
+    };
 
     let post_ids: Vec<_> = all_posts.iter().map(|p| p.id).collect();
-    let mut attachments_map = thread_service
+    let attachments_map = thread_service
         .find_post_attachments(&post_ids)
         .await
         .map_err(ApiError::from)?;
+    let format = rb_format::Options::for_board(&board_ctx.config);
+    let mut posts = post_displays(thread.id, all_posts, attachments_map, &format);
+    let mut reactions = thread_service.find_reactions(&post_ids).await.map_err(ApiError::from)?;
+    for pd in &mut posts {
+        pd.reactions = reactions.remove(&pd.post.id).unwrap_or_default();
+    }
+    let poll = thread_service.find_poll(thread.id).await.map_err(ApiError::from)?;
+    let blotter = thread_service
+        .find_blotter(board_ctx.board.site_id, board_ctx.board_id)
+        .await
+        .map_err(ApiError::from)?;
+
+    let tmpl = ThreadTemplate {
+        board:       board_ctx.board,
+        thread:      thread.clone(),
+        posts,
+        is_closed,
+        is_cycle:    thread.cycle,
+        viewer_role,
+        config:      board_ctx.config,
+        media,
+        poll,
+        quote:       q.quote,
+        watched,
+        blotter,
+        own_ip_hash: match viewer {
+            Viewer::Poster(ip_hash) => Some(ip_hash),
+            Viewer::Staff | Viewer::Public => None,
+        },
+        i18n,
+    };
+    Ok(tmpl)
+}
 
-    let posts: Vec<PostDisplay> = all_posts.into_iter().map(|post| {
+/// Who a thread page is rendered for: staff who can delete posts see every
+/// post, other visitors are known by the same daily IP hash as `create_post`,
+/// so a shadowbanned poster still sees their own posts.
+fn thread_viewer(user: Option<&CurrentUser>, client_ip: Option<std::net::IpAddr>) -> Viewer {
+    if user.is_some_and(CurrentUser::can_delete) {
+        return Viewer::Staff;
+    }
+    match client_ip {
+        Some(ip) => {
+            let daily_salt = chrono::Utc::now().format("%Y-%m-%d").to_string();
+            Viewer::Poster(services::common::utils::hash_ip(&ip.to_string(), &daily_salt))
+        }
+        None => Viewer::Public,
+    }
+}
+
+/// Pair each post with its attachments and the derived display fields
+/// (poster ID, capcode, tripcode level, body HTML) used by `thread.html`.
+pub(crate) fn post_displays(
+    thread_id: ThreadId,
+    posts: Vec<domains::models::Post>,
+    mut attachments_map: std::collections::HashMap<domains::models::PostId, Vec<domains::models::Attachment>>,
+    format: &rb_format::Options,
+) -> Vec<PostDisplay> {
+    let thread_id_str = thread_id.0.to_string();
+    posts.into_iter().map(|post| {
         let mut hasher = Sha256::new();
         hasher.update(post.ip_hash.0.as_bytes());
         hasher.update(b"/");
@@ -140,33 +268,144 @@ pub async fn show_thread_html<TR: services::thread::ThreadRepo>(
         let hash_bytes = hasher.finalize();
         let poster_id  = hex::encode(&hash_bytes[..4]);
         let attachments = attachments_map.remove(&post.id).unwrap_or_default();
-        let capcode_role = post.tripcode.as_deref()
-            .and_then(services::common::tripcode::capcode_role_str)
-            .map(str::to_owned);
+        let capcode_role = post.capcode.clone();
         let capcode_css  = capcode_role.as_deref()
             .map(services::common::tripcode::capcode_css_class);
-        let tripcode_level = if capcode_role.is_some() {
-            None // capcode, not a tripcode
-        } else {
-            post.tripcode.as_deref().map(|t| {
-                if t.starts_with("!!!") { "super" }
-                else if t.starts_with("!!") { "secure" }
-                else { "insecure" }
-            })
-        };
+        let tripcode_level = post.tripcode.as_deref().map(|t| {
+            if t.starts_with("!!!") { "super" }
+            else if t.starts_with("!!") { "secure" }
+            else { "insecure" }
+        });
         let ip_hash_short = post.ip_hash.0.chars().take(10).collect();
-        PostDisplay { post, poster_id, attachments, capcode_role, capcode_css, tripcode_level, ip_hash_short }
-    }).collect();
+        let body_html = rb_format::render(&post.body, format);
+        PostDisplay {
+            post, poster_id, attachments, capcode_role, capcode_css, tripcode_level, ip_hash_short, body_html,
+            reactions: Vec::new(),
+        }
+    }).collect()
+}
 
-    let tmpl = ThreadTemplate {
-        board:       board_ctx.board,
-        thread:      thread.clone(),
-        posts,
-        is_closed,
-        is_cycle:    thread.cycle,
-        viewer_role,
-    };
-    Ok(tmpl)
+/// `GET /board/:slug/thread/:id/posts?after=N` — the thread's posts numbered
+/// above `N`, as JSON, with bodies rendered as on the thread page.
+///
+/// Lets a thread page load posts it does not have: replies made since it was
+/// rendered, and quoted posts for hover previews.
+pub async fn list_thread_posts_after<TR: services::thread::ThreadRepo>(
+    State(thread_service): State<Arc<TR>>,
+    axum::extract::Extension(board_ctx): axum::extract::Extension<ExtractedBoardConfig>,
+    Path((_slug, thread_id)): Path<(String, uuid::Uuid)>,
+    Query(q): Query<PostsAfterQuery>,
+    MediaLinks(media): MediaLinks,
+    maybe_user: Option<axum::extract::Extension<CurrentUser>>,
+    client_ip: Option<ClientIp>,
+) -> Result<Json<Vec<ThreadPostResponse>>, ApiError>
+{
+    let viewer = thread_viewer(maybe_user.as_ref().map(|u| &u.0), client_ip.map(|ClientIp(ip)| ip));
+    let is_staff = matches!(viewer, Viewer::Staff);
+    let thread = thread_service
+        .get_thread(ThreadId(thread_id))
+        .await
+        .map_err(ApiError::from)?;
+    if thread.board_id != board_ctx.board_id {
+        return Err(ApiError::NotFound(format!("thread {thread_id}")));
+    }
+
+    let posts: Vec<_> = thread_service
+        .list_all_posts(thread.id, &viewer)
+        .await
+        .map_err(ApiError::from)?
+        .into_iter()
+        .filter(|p| p.post_number > q.after)
+        .collect();
+    let post_ids: Vec<_> = posts.iter().map(|p| p.id).collect();
+    let attachments_map = thread_service
+        .find_post_attachments(&post_ids)
+        .await
+        .map_err(ApiError::from)?;
+    let format = rb_format::Options::for_board(&board_ctx.config);
+
+    let posts = post_displays(thread.id, posts, attachments_map, &format)
+        .into_iter()
+        .map(|pd| ThreadPostResponse {
+            post_number: pd.post.post_number,
+            name:        pd.post.name,
+            tripcode:    pd.post.tripcode,
+            capcode:     pd.capcode_role,
+            poster_id:   if pd.post.deleted.is_some() && !is_staff { String::new() } else { pd.poster_id },
+            subject:     pd.post.subject,
+            created_at:  pd.post.created_at,
+            body_html:   pd.body_html,
+            attachments: pd.attachments.into_iter().map(|att| AttachmentResponse {
+                url:           media.url(&att.media_key),
+                thumbnail_url: att.thumbnail_key.as_ref().map(|k| media.url(k)),
+                mime:          att.mime,
+                filename:      att.filename,
+                spoiler:       att.spoiler,
+            }).collect(),
+            deleted:     pd.post.deleted.map(|t| t.by),
+            edited_at:   pd.post.edited_at,
+        })
+        .collect();
+    Ok(Json(posts))
+}
+
+/// Most thread ids `GET /board/:slug/threads` answers for at once.
+pub const MAX_BULK_THREADS: usize = 100;
+
+/// `GET /board/:slug/threads?ids=a,b,c` — several threads of the board as
+/// JSON: OP excerpt, thumbnail and reply count, in the order asked for.
+///
+/// Lets the thread watcher and catalog clients refresh many threads in one
+/// request. Threads that are gone, on another board or hidden from the
+/// catalog are left out; a malformed id or more than `MAX_BULK_THREADS` is
+/// a `400`.
+pub async fn list_threads_by_id<TR: services::thread::ThreadRepo>(
+    State(thread_service): State<Arc<TR>>,
+    axum::extract::Extension(board_ctx): axum::extract::Extension<ExtractedBoardConfig>,
+    Query(q): Query<ThreadIdsQuery>,
+    MediaLinks(media): MediaLinks,
+) -> Result<Json<Vec<ThreadSummaryResponse>>, ApiError>
+{
+    // Visible characters of the OP in each excerpt, as in the catalog.
+    const EXCERPT_CHARS: usize = 200;
+
+    let ids = q
+        .ids
+        .split(',')
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(|id| {
+            uuid::Uuid::parse_str(id)
+                .map(ThreadId)
+                .map_err(|_| ApiError::BadRequest(format!("invalid thread id `{id}`")))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if ids.len() > MAX_BULK_THREADS {
+        return Err(ApiError::BadRequest(format!("at most {MAX_BULK_THREADS} thread ids per request")));
+    }
+
+    let format = rb_format::Options::for_board(&board_ctx.config);
+    let threads = thread_service
+        .get_summaries(board_ctx.board_id, &ids)
+        .await
+        .map_err(ApiError::from)?
+        .into_iter()
+        .map(|t| ThreadSummaryResponse {
+            url:            format!("/board/{}/thread/{}", board_ctx.board.slug, t.thread_id),
+            thread_id:      t.thread_id,
+            op_post_number: t.op_post_number,
+            excerpt_html:   rb_format::snippet(&t.op_body, &format, EXCERPT_CHARS),
+            subject:        t.op_subject,
+            thumbnail_url:  t.thumbnail_key.as_ref().filter(|_| !t.thumbnail_spoiler).map(|k| media.url(k)),
+            spoiler:        t.thumbnail_spoiler,
+            reply_count:    t.reply_count,
+            sticky:         t.sticky,
+            closed:         t.closed,
+            created_at:     t.op_created_at,
+            bumped_at:      t.bumped_at,
+        })
+        .collect();
+    Ok(Json(threads))
 }
 
 /// `GET /board/:slug/post/:post_number` — redirect to the thread containing this post.
@@ -216,16 +455,18 @@ where
     Ok(Json(result.into()))
 }
 
-/// `GET /board/:slug/catalog` — catalog view as JSON.
+/// `GET /board/:slug/catalog` — catalog view as JSON. Takes the same `sort`
+/// and `q` parameters as the HTML view.
 pub async fn show_catalog<TR>(
     State(thread_service): State<Arc<TR>>,
     axum::extract::Extension(board_ctx): axum::extract::Extension<ExtractedBoardConfig>,
+    Query(q): Query<CatalogQuery>,
 ) -> Result<Json<Vec<ThreadSummary>>, ApiError>
 where
     TR: services::thread::ThreadRepo,
 {
     let summaries = thread_service
-        .get_catalog(board_ctx.board_id)
+        .get_catalog(board_ctx.board_id, q.sort, q.q.as_deref())
         .await
         .map_err(ApiError::from)?;
     Ok(Json(summaries))
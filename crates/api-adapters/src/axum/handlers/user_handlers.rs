@@ -8,6 +8,7 @@ use axum::{extract::State, http::StatusCode, Json};
 use serde::Deserialize;
 use std::sync::Arc;
 
+use crate::axum::i18n::I18n;
 use crate::axum::middleware::auth::AnyAuthenticatedUser;
 use crate::axum::templates::UserDashboardTemplate;
 use crate::common::errors::ApiError;
@@ -53,6 +54,7 @@ where
 pub async fn user_dashboard<UR, AP, RR>(
     State(state): State<UserDashboardState<UR, AP, RR>>,
     AnyAuthenticatedUser(current): AnyAuthenticatedUser,
+    i18n: I18n,
 ) -> Result<axum::response::Response, ApiError>
 where
     UR: domains::ports::UserRepository,
@@ -80,6 +82,7 @@ where
         username:         current.username.clone(),
         joined_at,
         pending_requests: requests,
+        i18n,
     }
     .into_response())
 }
@@ -0,0 +1,150 @@
+//! Thread watcher handlers: watch, unwatch and the watch list.
+//!
+//! Watchers are anonymous. The first watch hands out a random `WatcherToken`
+//! in the `watcher` cookie, and every later request carrying it sees the same
+//! list.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Redirect, Response},
+    Json,
+};
+use std::sync::Arc;
+
+use crate::axum::i18n::I18n;
+use crate::axum::middleware::{accept::WantsJson, board_config::ExtractedBoardConfig};
+use crate::axum::templates::WatchedTemplate;
+use crate::common::{dtos::UnwatchQuery, errors::ApiError};
+use domains::models::{ThreadId, WatcherToken};
+use domains::ports::{ThreadRepository, WatchRepository};
+use services::watch::WatchService;
+
+/// Name of the cookie holding the viewer's `WatcherToken`.
+pub const WATCHER_COOKIE: &str = "watcher";
+
+/// Lifetime of the `watcher` cookie: a year, renewed by every watch.
+const WATCHER_COOKIE_MAX_AGE: u32 = 365 * 24 * 60 * 60;
+
+// ─── State ────────────────────────────────────────────────────────────────────
+
+/// Shared state for the watcher handlers.
+pub struct WatchState<WR, TR>
+where
+    WR: WatchRepository,
+    TR: ThreadRepository,
+{
+    /// The watch service.
+    pub svc: Arc<WatchService<WR, TR>>,
+}
+
+impl<WR, TR> Clone for WatchState<WR, TR>
+where
+    WR: WatchRepository,
+    TR: ThreadRepository,
+{
+    fn clone(&self) -> Self { Self { svc: self.svc.clone() } }
+}
+
+// ─── Handlers ─────────────────────────────────────────────────────────────────
+
+/// `POST /board/:slug/thread/:id/watch` — add the thread to the viewer's watch list.
+///
+/// Viewers without a `watcher` cookie get one. Requests with
+/// `Accept: application/json` get `204`; plain form submissions are
+/// redirected back to the thread.
+pub async fn watch<WR, TR>(
+    State(s): State<WatchState<WR, TR>>,
+    axum::extract::Extension(board_ctx): axum::extract::Extension<ExtractedBoardConfig>,
+    Path((slug, thread_id)): Path<(String, uuid::Uuid)>,
+    WantsJson(wants_json): WantsJson,
+    headers: HeaderMap,
+) -> Result<Response, ApiError>
+where
+    WR: WatchRepository,
+    TR: ThreadRepository,
+{
+    let watcher = watcher_token(&headers).unwrap_or_default();
+    s.svc.watch(watcher, ThreadId(thread_id), board_ctx.board_id).await?;
+
+    let cookie = format!(
+        "{WATCHER_COOKIE}={watcher}; HttpOnly; SameSite=Lax; Path=/; Max-Age={WATCHER_COOKIE_MAX_AGE}"
+    );
+    let mut response = back_to_thread(wants_json, &slug, thread_id);
+    if let Ok(value) = cookie.parse() {
+        response.headers_mut().insert(header::SET_COOKIE, value);
+    }
+    Ok(response)
+}
+
+/// `POST /board/:slug/thread/:id/unwatch` — remove the thread from the viewer's watch list.
+///
+/// Answers like `watch`, except that `?list=true` redirects to the watch
+/// list. A viewer without a `watcher` cookie has nothing to remove.
+pub async fn unwatch<WR, TR>(
+    State(s): State<WatchState<WR, TR>>,
+    Path((slug, thread_id)): Path<(String, uuid::Uuid)>,
+    Query(q): Query<UnwatchQuery>,
+    WantsJson(wants_json): WantsJson,
+    headers: HeaderMap,
+) -> Result<Response, ApiError>
+where
+    WR: WatchRepository,
+    TR: ThreadRepository,
+{
+    if let Some(watcher) = watcher_token(&headers) {
+        s.svc.unwatch(watcher, ThreadId(thread_id)).await?;
+    }
+    if q.list && !wants_json {
+        return Ok(Redirect::to("/watched").into_response());
+    }
+    Ok(back_to_thread(wants_json, &slug, thread_id))
+}
+
+/// `GET /watched` — the viewer's watched threads with unread reply counts.
+///
+/// Rendered as HTML, or as a JSON array of `WatchedThread` for requests with
+/// `Accept: application/json`. Viewers without a `watcher` cookie get an
+/// empty list.
+pub async fn show_watched<WR, TR>(
+    State(s): State<WatchState<WR, TR>>,
+    WantsJson(wants_json): WantsJson,
+    headers: HeaderMap,
+    i18n: I18n,
+) -> Result<Response, ApiError>
+where
+    WR: WatchRepository,
+    TR: ThreadRepository,
+{
+    let threads = match watcher_token(&headers) {
+        Some(watcher) => s.svc.list(watcher).await?,
+        None => Vec::new(),
+    };
+    if wants_json {
+        return Ok(Json(threads).into_response());
+    }
+    Ok(WatchedTemplate { i18n, threads }.into_response())
+}
+
+// ─── Helpers ──────────────────────────────────────────────────────────────────
+
+/// The `WatcherToken` in the request's `watcher` cookie, if it holds one.
+pub fn watcher_token(headers: &HeaderMap) -> Option<WatcherToken> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|cookies| cookies.split(';'))
+        .find_map(|part| part.trim().strip_prefix(WATCHER_COOKIE)?.strip_prefix('='))
+        .and_then(|value| uuid::Uuid::parse_str(value).ok())
+        .map(WatcherToken)
+}
+
+/// `204` for `fetch` callers, otherwise a redirect to the thread.
+fn back_to_thread(wants_json: bool, slug: &str, thread_id: uuid::Uuid) -> Response {
+    if wants_json {
+        StatusCode::NO_CONTENT.into_response()
+    } else {
+        Redirect::to(&format!("/board/{slug}/thread/{thread_id}")).into_response()
+    }
+}
@@ -0,0 +1,199 @@
+//! Translated UI strings.
+//!
+//! Every user-facing string in the public templates is a Fluent message in
+//! `locales/{locale}/main.ftl`, compiled into the binary. Templates hold an
+//! [`I18n`] for the request's locale and look messages up by ID:
+//!
+//! ```text
+//! <summary>{{ i18n.t("form-new-thread") }}</summary>
+//! {{ i18n.count("thread-replies", td.thread.reply_count) }}
+//! ```
+//!
+//! The locale is the first entry of the request's `Accept-Language` that is
+//! a supported [`Locale`], else the board's `default_locale`, else English.
+//! Messages missing from a translation fall back to English.
+//!
+//! axum 0.8: FromRequestParts uses RPITIT — plain async fn in impl, no #[async_trait].
+
+use std::sync::LazyLock;
+
+use axum::http::{header, request::Parts, Extensions, HeaderMap};
+use domains::models::Locale;
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource, FluentValue};
+
+use crate::axum::middleware::board_config::ExtractedBoardConfig;
+
+/// One compiled bundle per locale, in `Locale::ALL` order.
+static BUNDLES: LazyLock<Vec<(Locale, FluentBundle<FluentResource>)>> =
+    LazyLock::new(|| Locale::ALL.into_iter().map(|locale| (locale, bundle(locale))).collect());
+
+fn source(locale: Locale) -> &'static str {
+    match locale {
+        Locale::En => include_str!("../../locales/en/main.ftl"),
+        Locale::Es => include_str!("../../locales/es/main.ftl"),
+    }
+}
+
+fn bundle(locale: Locale) -> FluentBundle<FluentResource> {
+    let langid = locale.to_string().parse().expect("locale names are valid language tags");
+    let mut bundle = FluentBundle::new_concurrent(vec![langid]);
+    // Pages are HTML; Unicode isolation marks around arguments would only
+    // show up as stray characters in copied text.
+    bundle.set_use_isolating(false);
+    let resource = FluentResource::try_new(source(locale).to_owned())
+        .unwrap_or_else(|(_, errors)| panic!("locales/{locale}/main.ftl does not parse: {errors:?}"));
+    bundle.add_resource(resource).expect("each locale has one resource");
+    bundle
+}
+
+fn bundle_for(locale: Locale) -> &'static FluentBundle<FluentResource> {
+    BUNDLES
+        .iter()
+        .find_map(|(l, bundle)| (*l == locale).then_some(bundle))
+        .expect("every locale has a bundle")
+}
+
+/// The UI strings of one locale. Also an Axum extractor that negotiates the
+/// locale of the current request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct I18n {
+    locale: Locale,
+}
+
+impl I18n {
+    /// Strings for `locale`.
+    pub fn new(locale: Locale) -> Self {
+        Self { locale }
+    }
+
+    /// The locale strings are looked up in.
+    pub fn locale(&self) -> Locale {
+        self.locale
+    }
+
+    /// The message `id`, without arguments.
+    pub fn t(&self, id: &str) -> String {
+        self.format(id, None)
+    }
+
+    /// The message `id` with its `$count` argument set to `count`, for
+    /// messages that pluralize.
+    pub fn count(&self, id: &str, count: impl Count) -> String {
+        let mut args = FluentArgs::new();
+        args.set("count", count.value());
+        self.format(id, Some(&args))
+    }
+
+    /// The message `id` with named arguments, formatted as text. Askama
+    /// passes array literals through, so all values must share one type.
+    pub fn args<V: std::fmt::Display>(&self, id: &str, args: &[(&str, V)]) -> String {
+        let args = args.iter().map(|(name, value)| (*name, FluentValue::from(value.to_string()))).collect();
+        self.format(id, Some(&args))
+    }
+
+    /// Format `id` in this locale, then in English, then fall back to the ID
+    /// itself so a missing message shows up on the page instead of failing it.
+    fn format(&self, id: &str, args: Option<&FluentArgs<'_>>) -> String {
+        [self.locale, Locale::En]
+            .into_iter()
+            .find_map(|locale| {
+                let bundle = bundle_for(locale);
+                let pattern = bundle.get_message(id)?.value()?;
+                let mut errors = Vec::new();
+                let text = bundle.format_pattern(pattern, args, &mut errors);
+                if !errors.is_empty() {
+                    tracing::warn!(%locale, id, ?errors, "UI string formatted with errors");
+                }
+                Some(text.into_owned())
+            })
+            .unwrap_or_else(|| {
+                tracing::warn!(locale = %self.locale, id, "missing UI string");
+                id.to_owned()
+            })
+    }
+}
+
+/// A number `I18n::count` accepts. Askama passes fields by reference and
+/// method results by value, so both forms are implemented.
+pub trait Count {
+    /// The number as a Fluent argument.
+    fn value(&self) -> FluentValue<'static>;
+}
+
+macro_rules! impl_count {
+    ($($t:ty),*) => {$(
+        impl Count for $t {
+            fn value(&self) -> FluentValue<'static> { FluentValue::from(*self) }
+        }
+        impl Count for &$t {
+            fn value(&self) -> FluentValue<'static> { FluentValue::from(**self) }
+        }
+    )*};
+}
+
+impl_count!(u32, u64, usize);
+
+/// Pick the locale for an `Accept-Language` header value.
+///
+/// Entries are tried in order of their `q` weight (ties keep header order) and
+/// match on their primary language subtag, so `es-AR` selects Spanish. Falls
+/// back to `default` when the header is absent or names no supported locale.
+pub fn negotiate(accept_language: Option<&str>, default: Locale) -> Locale {
+    let Some(header) = accept_language else {
+        return default;
+    };
+    let mut ranges: Vec<(f32, Locale)> = header
+        .split(',')
+        .filter_map(|entry| {
+            let mut params = entry.split(';');
+            let tag = params.next()?.trim();
+            let q = params
+                .find_map(|p| p.trim().strip_prefix("q="))
+                .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())?;
+            let primary = tag.split('-').next()?.to_ascii_lowercase();
+            let locale = primary.parse().ok()?;
+            (q > 0.0).then_some((q, locale))
+        })
+        .collect();
+    ranges.sort_by(|a, b| b.0.total_cmp(&a.0));
+    ranges.first().map_or(default, |(_, locale)| *locale)
+}
+
+impl<S: Send + Sync> axum::extract::FromRequestParts<S> for I18n {
+    type Rejection = std::convert::Infallible;
+
+    /// Negotiates against the board's `default_locale` on board-scoped routes
+    /// (see `board_config_middleware`) and against English elsewhere.
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(I18n::new(request_locale(&parts.headers, &parts.extensions)))
+    }
+}
+
+/// The request's `Accept-Language` header, for handlers that load their board
+/// themselves instead of through `board_config_middleware`.
+pub struct AcceptLanguage(pub Option<String>);
+
+impl AcceptLanguage {
+    /// Strings for the locale negotiated against the board's `default`.
+    pub fn i18n(&self, default: Locale) -> I18n {
+        I18n::new(negotiate(self.0.as_deref(), default))
+    }
+}
+
+impl<S: Send + Sync> axum::extract::FromRequestParts<S> for AcceptLanguage {
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let value = parts.headers.get(header::ACCEPT_LANGUAGE).and_then(|v| v.to_str().ok());
+        Ok(AcceptLanguage(value.map(str::to_owned)))
+    }
+}
+
+/// The negotiated locale of a request. Shared with the render cache, which
+/// keys pages by it.
+pub fn request_locale(headers: &HeaderMap, extensions: &Extensions) -> Locale {
+    let default = extensions.get::<ExtractedBoardConfig>().map(|ctx| ctx.config.default_locale).unwrap_or_default();
+    let accept_language = headers.get(header::ACCEPT_LANGUAGE).and_then(|v| v.to_str().ok());
+    negotiate(accept_language, default)
+}
@@ -13,12 +13,17 @@
 //! | `spam_rejections_total` | Counter | Post creations rejected by spam filter |
 //! | `ban_checks_total` | Counter | Active ban checks performed |
 //! | `thread_prunes_total` | Counter | Threads pruned from boards at capacity |
+//! | `board_threads` | Gauge | Live threads per board, labelled by slug |
+//! | `board_media_bytes` | Gauge | Stored media bytes per board, labelled by slug |
+//!
+//! The `board_*` gauges are refreshed by the `stats` scheduled job, not per
+//! request.
 
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use prometheus_client::{
     encoding::text::encode,
-    metrics::{counter::Counter, family::Family, histogram::Histogram},
+    metrics::{counter::Counter, family::Family, gauge::Gauge, histogram::Histogram},
     registry::Registry,
 };
 use std::sync::Arc;
@@ -32,6 +37,13 @@ pub struct RequestLabels {
     pub status: String,
 }
 
+/// Labels used on the per-board gauges.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, prometheus_client::encoding::EncodeLabelSet)]
+pub struct BoardLabels {
+    /// Board slug (e.g. `"b"`).
+    pub board: String,
+}
+
 /// All application metrics, grouped for easy passing between functions.
 ///
 /// Constructed once in `composition.rs` and cloned into each service/handler
@@ -50,6 +62,10 @@ pub struct AppMetrics {
     pub ban_checks_total: Counter,
     /// Threads pruned because the board exceeded its thread capacity.
     pub thread_prunes_total: Counter,
+    /// Live threads per board.
+    pub board_threads: Family<BoardLabels, Gauge>,
+    /// Stored media bytes per board, as counted against `media_quota_mb`.
+    pub board_media_bytes: Family<BoardLabels, Gauge>,
 }
 
 impl AppMetrics {
@@ -100,6 +116,20 @@ impl AppMetrics {
             thread_prunes_total.clone(),
         );
 
+        let board_threads = Family::<BoardLabels, Gauge>::default();
+        registry.register(
+            "board_threads",
+            "Live threads per board",
+            board_threads.clone(),
+        );
+
+        let board_media_bytes = Family::<BoardLabels, Gauge>::default();
+        registry.register(
+            "board_media_bytes",
+            "Stored media bytes per board",
+            board_media_bytes.clone(),
+        );
+
         Self {
             http_requests_total,
             http_request_duration_seconds,
@@ -107,6 +137,8 @@ impl AppMetrics {
             spam_rejections_total,
             ban_checks_total,
             thread_prunes_total,
+            board_threads,
+            board_media_bytes,
         }
     }
 }
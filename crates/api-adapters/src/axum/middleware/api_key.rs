@@ -0,0 +1,50 @@
+//! API key authentication for bots and bridges.
+//!
+//! A request with `Authorization: Bearer rbk_...` is checked by
+//! `ApiKeyService::authorize`: `GET` and `HEAD` need the `read` scope, every
+//! other method the `post` scope. A request that passes carries the caller as
+//! an `ApiClient` extension; `create_post` tags the posts it makes and skips
+//! the per-IP rate limit, since the key has its own.
+//!
+//! An unknown or revoked key is refused with `401`, a missing scope with
+//! `403` and a used-up rate limit with `429`. Requests without a key, or with
+//! a staff JWT in the same header, are passed through untouched.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Request, State},
+    http::{header, Method},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use domains::models::ApiScope;
+use domains::ports::ApiKeyRepository;
+use services::api_key::{ApiKeyService, KEY_PREFIX};
+
+use crate::common::errors::ApiError;
+
+/// Axum middleware that authenticates API keys.
+pub async fn api_key_middleware<AK: ApiKeyRepository>(
+    State(keys): State<Arc<ApiKeyService<AK>>>,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    let Some(secret) = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|s| s.strip_prefix("Bearer "))
+        .filter(|s| s.starts_with(KEY_PREFIX))
+    else {
+        return next.run(req).await;
+    };
+    let scope = if matches!(*req.method(), Method::GET | Method::HEAD) { ApiScope::Read } else { ApiScope::Post };
+    match keys.authorize(secret, scope).await {
+        Ok(client) => {
+            req.extensions_mut().insert(client);
+            next.run(req).await
+        }
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
@@ -1,7 +1,8 @@
 //! Board config loader middleware.
 //!
 //! For any request with a `:slug` path segment, loads and caches the `BoardConfig`
-//! for that board and inserts it into request extensions. Handlers can then access
+//! for that board on the request's site (see `site` middleware) and inserts it
+//! into request extensions. Handlers can then access
 //! the config without a separate DB round-trip.
 //!
 //! # Usage
@@ -23,6 +24,7 @@
 //! ```
 
 use std::sync::Arc;
+use std::time::Duration;
 
 use axum::{
     extract::{Request, State},
@@ -30,7 +32,9 @@ use axum::{
     middleware::Next,
     response::{IntoResponse, Response},
 };
-use domains::models::{Board, BoardConfig, BoardId, Slug};
+use bytes::Bytes;
+use domains::models::{Board, BoardConfig, BoardId, Site, SiteId, Slug};
+use domains::ports::CacheStore;
 use storage_adapters::cache::BoardConfigCache;
 
 /// The board config + board ID injected into request extensions by the middleware.
@@ -63,9 +67,10 @@ pub struct BoardConfigState {
 /// Kept minimal here to avoid pulling the full `BoardRepo` trait into middleware code.
 #[async_trait::async_trait]
 pub trait BoardConfigSource: Send + Sync + 'static {
-    /// Fetch board, board ID and its config by slug. Returns `None` if the board doesn't exist.
+    /// Fetch board, board ID and its config by the site's slug. Returns `None` if the board doesn't exist.
     async fn config_by_slug(
         &self,
+        site: SiteId,
         slug: &Slug,
     ) -> Result<Option<(Board, BoardId, BoardConfig)>, Box<dyn std::error::Error + Send + Sync>>;
 }
@@ -79,12 +84,13 @@ pub trait BoardConfigSource: Send + Sync + 'static {
 impl<T: services::board::BoardRepo> BoardConfigSource for T {
     async fn config_by_slug(
         &self,
+        site: SiteId,
         slug: &Slug,
     ) -> Result<Option<(Board, BoardId, BoardConfig)>, Box<dyn std::error::Error + Send + Sync>> {
         use services::board::BoardError;
 
         // First look up the board to get its ID
-        let board = match self.get_by_slug(slug.as_str()).await {
+        let board = match self.get_by_slug(site, slug.as_str()).await {
             Ok(b) => b,
             Err(BoardError::NotFound { .. }) => return Ok(None),
             Err(e) => return Err(Box::new(e)),
@@ -97,6 +103,56 @@ impl<T: services::board::BoardRepo> BoardConfigSource for T {
     }
 }
 
+/// `CacheStore` key holding the settings lookup for `slug` on `site`.
+pub fn board_config_cache_key(site: SiteId, slug: &Slug) -> String {
+    format!("board-config:{site}:{slug}")
+}
+
+/// `BoardConfigSource` that consults a shared `CacheStore` before `inner`.
+///
+/// Sits behind the per-process `BoardConfigCache`: a process-local miss is
+/// answered from the shared store when another worker or instance already
+/// loaded the board. Unknown slugs are not cached. Store failures are logged
+/// and fall through to `inner`, so a Redis outage only costs the round-trip.
+pub struct CachedBoardConfigSource {
+    pub inner: Arc<dyn BoardConfigSource>,
+    pub store: Arc<dyn CacheStore>,
+    pub ttl:   Duration,
+}
+
+#[async_trait::async_trait]
+impl BoardConfigSource for CachedBoardConfigSource {
+    async fn config_by_slug(
+        &self,
+        site: SiteId,
+        slug: &Slug,
+    ) -> Result<Option<(Board, BoardId, BoardConfig)>, Box<dyn std::error::Error + Send + Sync>> {
+        let key = board_config_cache_key(site, slug);
+        match self.store.get(&key).await {
+            Ok(Some(raw)) => match serde_json::from_slice(&raw) {
+                Ok(entry) => return Ok(Some(entry)),
+                // Written by an older build with a different shape: reload below.
+                Err(e) => tracing::debug!(%key, error = %e, "discarding undecodable board config entry"),
+            },
+            Ok(None) => {}
+            Err(e) => tracing::warn!(%key, error = %e, "cache store read failed"),
+        }
+
+        let found = self.inner.config_by_slug(site, slug).await?;
+        if let Some(entry) = &found {
+            match serde_json::to_vec(entry) {
+                Ok(raw) => {
+                    if let Err(e) = self.store.set(&key, Bytes::from(raw), self.ttl).await {
+                        tracing::warn!(%key, error = %e, "cache store write failed");
+                    }
+                }
+                Err(e) => tracing::warn!(%key, error = %e, "failed to encode board config entry"),
+            }
+        }
+        Ok(found)
+    }
+}
+
 /// Axum middleware that resolves a board slug to `(BoardId, BoardConfig)` and inserts
 /// an `ExtractedBoardConfig` into request extensions.
 ///
@@ -134,14 +190,17 @@ pub async fn board_config_middleware(
         Err(_) => return (StatusCode::NOT_FOUND, "invalid board slug").into_response(),
     };
 
+    // Set by `site_middleware`; absent when the router is mounted without it.
+    let site = req.extensions().get::<Site>().map_or(SiteId::DEFAULT, |s| s.id);
+
     // Try in-process cache first
-    if let Some((board, board_id, config)) = state.cache.get_by_slug(&slug) {
+    if let Some((board, board_id, config)) = state.cache.get_by_slug(site, &slug) {
         req.extensions_mut().insert(ExtractedBoardConfig { board_id, config, slug: slug.clone(), board });
         return next.run(req).await;
     }
 
     // Cache miss → hit the repository
-    match state.source.config_by_slug(&slug).await {
+    match state.source.config_by_slug(site, &slug).await {
         Ok(Some((board, board_id, config))) => {
             state.cache.set_by_slug(slug.clone(), board.clone(), board_id, config.clone());
             req.extensions_mut().insert(ExtractedBoardConfig { board_id, config, slug, board });
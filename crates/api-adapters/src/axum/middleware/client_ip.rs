@@ -0,0 +1,121 @@
+//! `ClientIp` extractor — the visitor's address behind trusted reverse proxies.
+//!
+//! Behind nginx or Cloudflare the TCP peer is the proxy, so bans, poster IDs
+//! and rate limits keyed on it would lump every visitor together. The proxy
+//! names the client in a header instead, which anyone else can forge; so the
+//! header is believed only on requests whose peer is in `TrustedProxies`
+//! (`TRUSTED_PROXIES`, installed as an `Extension`):
+//!
+//! 1. A peer outside the list is the client.
+//! 2. With `TRUST_CF_CONNECTING_IP`, a valid `CF-Connecting-IP` names it.
+//! 3. Otherwise `X-Forwarded-For` is read from the right, past entries that
+//!    are themselves trusted proxies; the first other address is the client.
+//!    If the header is missing or has only trusted entries, the leftmost
+//!    trusted address reached is used; a malformed entry ends the walk there.
+//!
+//! On a Unix socket `proxy_peer_middleware` supplies the peer from the
+//! socket's proxy first, and the same rules apply to it.
+//!
+//! axum 0.8: FromRequestParts uses RPITIT — plain async fn in impl, no #[async_trait].
+
+use std::net::{IpAddr, SocketAddr};
+
+use axum::{
+    extract::ConnectInfo,
+    http::{request::Parts, Extensions, HeaderMap},
+};
+use ipnet::IpNet;
+
+use crate::common::errors::ApiError;
+
+/// Reverse proxies whose forwarding headers are believed.
+#[derive(Debug, Clone, Default)]
+pub struct TrustedProxies {
+    ranges:           Vec<IpNet>,
+    cf_connecting_ip: bool,
+}
+
+impl TrustedProxies {
+    /// Parse a comma-separated list of addresses and CIDR ranges, e.g.
+    /// `10.0.0.0/8, 127.0.0.1, ::1`. Returns the first entry that is neither.
+    pub fn parse(list: &str, cf_connecting_ip: bool) -> Result<Self, String> {
+        let ranges = list
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                entry
+                    .parse::<IpNet>()
+                    .or_else(|_| entry.parse::<IpAddr>().map(IpNet::from))
+                    .map_err(|_| entry.to_owned())
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { ranges, cf_connecting_ip })
+    }
+
+    /// Whether `ip` is one of the proxies.
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        let ip = ip.to_canonical();
+        self.ranges.iter().any(|range| range.contains(&ip))
+    }
+
+    /// The client behind `peer`, following the rules in the module docs.
+    pub fn client_ip(&self, peer: IpAddr, headers: &HeaderMap) -> IpAddr {
+        let peer = peer.to_canonical();
+        if !self.contains(peer) {
+            return peer;
+        }
+        if self.cf_connecting_ip {
+            let cf = headers.get("cf-connecting-ip").and_then(|v| v.to_str().ok());
+            if let Some(ip) = cf.and_then(|v| v.trim().parse::<IpAddr>().ok()) {
+                return ip.to_canonical();
+            }
+        }
+        let mut client = peer;
+        let hops = headers
+            .get_all("x-forwarded-for")
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','))
+            .collect::<Vec<_>>();
+        for hop in hops.into_iter().rev() {
+            let Ok(ip) = hop.trim().parse::<IpAddr>() else { break };
+            client = ip.to_canonical();
+            if !self.contains(client) {
+                break;
+            }
+        }
+        client
+    }
+}
+
+/// The client address of a request, or `None` without a peer address.
+pub fn client_ip(extensions: &Extensions, headers: &HeaderMap) -> Option<IpAddr> {
+    let peer = extensions.get::<ConnectInfo<SocketAddr>>()?.0.ip();
+    Some(match extensions.get::<TrustedProxies>() {
+        Some(proxies) => proxies.client_ip(peer, headers),
+        None => peer.to_canonical(),
+    })
+}
+
+/// The visitor's IP address, as resolved by `client_ip`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientIp(pub IpAddr);
+
+impl<S: Send + Sync> axum::extract::FromRequestParts<S> for ClientIp {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        client_ip(&parts.extensions, &parts.headers)
+            .map(ClientIp)
+            .ok_or_else(|| ApiError::Internal("no peer address for the request".to_owned()))
+    }
+}
+
+impl<S: Send + Sync> axum::extract::OptionalFromRequestParts<S> for ClientIp {
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Option<Self>, Self::Rejection> {
+        Ok(client_ip(&parts.extensions, &parts.headers).map(ClientIp))
+    }
+}
@@ -0,0 +1,139 @@
+//! Per-viewer stylesheet selection for HTML pages.
+//!
+//! Pages are rendered with their board's default theme so that the render
+//! cache can share them between viewers. This middleware runs outside the
+//! cache and rewrites the `theme-css` stylesheet link of each HTML response
+//! for the viewer, so the first paint already uses the right theme:
+//!
+//! 1. A `theme` cookie (set from the settings panel) always wins.
+//! 2. Otherwise, a `Sec-CH-Prefers-Color-Scheme: "dark"` client hint swaps a
+//!    light default (Futaba, Yotsuba) for the Dark theme.
+//! 3. Otherwise the board default stands.
+//!
+//! Every HTML response asks for the hint with `Accept-CH` and `Critical-CH`,
+//! so supporting browsers retry the first navigation with it, and varies on
+//! it and on `Cookie`. Browsers without client hints get the same rule from
+//! the `<head>` script in `base.html` via `prefers-color-scheme`.
+//!
+//! The link also gets a `data-default` attribute holding the board default, so
+//! the script and the settings panel's "Board default" option still know it
+//! after the `href` has been rewritten.
+
+use axum::{
+    body::Body,
+    extract::Request,
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use domains::models::Theme;
+
+use crate::axum::templates::theme_stylesheet;
+
+/// Client hint carrying the browser's `prefers-color-scheme`.
+const PREFERS_COLOR_SCHEME: &str = "sec-ch-prefers-color-scheme";
+
+/// Marks the stylesheet link in `base.html`; the link's `href` precedes it.
+const THEME_LINK_ID: &str = r#" id="theme-css""#;
+
+/// What the request says about the viewer's theme.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+struct ThemePreference {
+    /// Theme chosen in the settings panel (`theme` cookie).
+    chosen:       Option<Theme>,
+    /// The browser reported `prefers-color-scheme: dark`.
+    prefers_dark: bool,
+}
+
+impl ThemePreference {
+    /// Read the `theme` cookie and the color-scheme client hint.
+    fn from_headers(headers: &HeaderMap) -> Self {
+        let chosen = headers
+            .get_all(header::COOKIE)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|cookies| cookies.split(';'))
+            .find_map(|part| part.trim().strip_prefix("theme="))
+            .and_then(|value| value.parse().ok());
+        let prefers_dark = headers
+            .get(PREFERS_COLOR_SCHEME)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.trim().trim_matches('"').eq_ignore_ascii_case("dark"));
+        Self { chosen, prefers_dark }
+    }
+
+    /// The viewer's default for a page whose board default is `board_default`.
+    fn default_for(&self, board_default: Theme) -> Theme {
+        match board_default {
+            Theme::Futaba | Theme::Yotsuba if self.prefers_dark => Theme::Dark,
+            other => other,
+        }
+    }
+
+    /// The theme the viewer sees on a page whose board default is `board_default`.
+    fn resolve(&self, board_default: Theme) -> Theme {
+        self.chosen.unwrap_or_else(|| self.default_for(board_default))
+    }
+}
+
+/// Axum middleware that points each HTML page's stylesheet at the viewer's theme.
+pub async fn color_scheme_middleware(req: Request, next: Next) -> Response {
+    let preference = ThemePreference::from_headers(req.headers());
+    let mut response = next.run(req).await;
+
+    let is_html = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("text/html"));
+    if !is_html {
+        return response;
+    }
+
+    let headers = response.headers_mut();
+    headers.insert(header::HeaderName::from_static("accept-ch"), HeaderValue::from_static(PREFERS_COLOR_SCHEME));
+    headers.insert(header::HeaderName::from_static("critical-ch"), HeaderValue::from_static(PREFERS_COLOR_SCHEME));
+    headers.append(header::VARY, HeaderValue::from_static("Sec-CH-Prefers-Color-Scheme, Cookie"));
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::error!(error = %e, "failed to buffer page for theming");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let Some(page) = std::str::from_utf8(&bytes).ok().and_then(|html| apply_theme(html, &preference)) else {
+        return Response::from_parts(parts, Body::from(bytes));
+    };
+    parts.headers.remove(header::CONTENT_LENGTH);
+    Response::from_parts(parts, Body::from(page))
+}
+
+/// Rewrite the `theme-css` link in `html` for `preference`.
+///
+/// Returns `None` when the page has no theme link, its `href` is not one of
+/// ours, or the link already has a `data-default`.
+fn apply_theme(html: &str, preference: &ThemePreference) -> Option<String> {
+    let id_at = html.find(THEME_LINK_ID)?;
+    let href_at = html[..id_at].rfind(r#"href=""#)? + r#"href=""#.len();
+    let href_end = href_at + html[href_at..id_at].find('"')?;
+    if html[href_end..id_at].contains("data-default") {
+        return None;
+    }
+    let board_default = theme_from_stylesheet(&html[href_at..href_end])?;
+
+    let mut out = String::with_capacity(html.len() + 64);
+    out.push_str(&html[..href_at]);
+    out.push_str(&theme_stylesheet(&preference.resolve(board_default)));
+    out.push_str(r#"" data-default=""#);
+    out.push_str(&html[href_at..href_end]);
+    out.push_str(&html[href_end..]);
+    Some(out)
+}
+
+/// The theme whose stylesheet is at `href`, as produced by `theme_stylesheet`.
+fn theme_from_stylesheet(href: &str) -> Option<Theme> {
+    Theme::ALL.into_iter().find(|theme| theme_stylesheet(theme) == href)
+}
+
@@ -0,0 +1,160 @@
+//! Conditional GET for board pages and the thread updater.
+//!
+//! Board indexes, catalogs, thread pages and `GET /board/:slug/thread/:id/posts`
+//! carry an `ETag` and a `Last-Modified` built from `LastModifiedRepository`.
+//! A request whose `If-None-Match` (or, without one, `If-Modified-Since`)
+//! still matches is answered `304 Not Modified` before the render cache or the
+//! handler runs, so auto-updating thread pages and crawlers cost one indexed
+//! lookup per poll while nothing changes.
+//!
+//! The timestamp covers the content. The `ETag` also covers the viewer, since
+//! one URL differs between viewers: it hashes the URL, the timestamp, the
+//! client address (shadowbanned posters see their own posts), the site notice,
+//! and the `Cookie`, `Authorization`, `Accept-Language` and color-scheme hint
+//! headers (staff toolbar, CSRF token, theme, locale, watched threads). For a
+//! poster with a pending warning, `warning_notice_middleware` drops the
+//! conditional headers so that the page carrying the notice is sent.
+//!
+//! Validated responses get `Cache-Control: private, no-cache`: browsers
+//! revalidate on every load instead of guessing a lifetime from
+//! `Last-Modified`. The timestamp is read before the page is rendered, so a
+//! write in between leaves a validator older than the page and the next
+//! request is answered in full again, never the other way round. A failed
+//! lookup is logged and the request is served unconditionally.
+//!
+//! Must run inside `board_config_middleware`, which supplies the board ID.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::{header, HeaderMap, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+use domains::models::{Site, ThreadId};
+use domains::ports::LastModifiedRepository;
+use sha2::{Digest, Sha256};
+
+use super::board_config::ExtractedBoardConfig;
+use super::client_ip::client_ip;
+
+/// Request headers that change the response for the same URL.
+const VARIANT_HEADERS: [&str; 4] = ["cookie", "authorization", "accept-language", "sec-ch-prefers-color-scheme"];
+
+/// What a validated route shows.
+enum Target {
+    Board,
+    Thread(ThreadId),
+}
+
+/// Axum middleware that adds validators to board pages and answers
+/// conditional requests for them.
+pub async fn conditional_get_middleware(
+    State(repo): State<Arc<dyn LastModifiedRepository>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    if req.method() != Method::GET && req.method() != Method::HEAD {
+        return next.run(req).await;
+    }
+    let Some(board_id) = req.extensions().get::<ExtractedBoardConfig>().map(|ctx| ctx.board_id) else {
+        return next.run(req).await;
+    };
+    let Some(target) = target(&req) else {
+        return next.run(req).await;
+    };
+
+    let lookup = match target {
+        Target::Board => repo.board_modified_at(board_id).await,
+        Target::Thread(thread_id) => repo.thread_modified_at(board_id, thread_id).await,
+    };
+    let modified_at = match lookup {
+        Ok(Some(at)) => at,
+        Ok(None) => return next.run(req).await,
+        Err(e) => {
+            tracing::warn!(board_id = %board_id.0, error = %e, "last-modified lookup failed");
+            return next.run(req).await;
+        }
+    };
+
+    let etag = etag(&req, modified_at);
+    if not_modified(req.headers(), &etag, modified_at) {
+        let mut resp = StatusCode::NOT_MODIFIED.into_response();
+        set_validators(resp.headers_mut(), &etag, modified_at);
+        return resp;
+    }
+    let mut resp = next.run(req).await;
+    if resp.status() == StatusCode::OK {
+        set_validators(resp.headers_mut(), &etag, modified_at);
+    }
+    resp
+}
+
+/// The board or thread behind a validated route; `None` for other routes.
+fn target(req: &Request) -> Option<Target> {
+    match req.extensions().get::<MatchedPath>()?.as_str() {
+        "/board/{slug}" | "/board/{slug}/catalog" => Some(Target::Board),
+        "/board/{slug}/thread/{id}" | "/board/{slug}/thread/{id}/posts" => {
+            // ["", "board", slug, "thread", id, ..]
+            let id = req.uri().path().split('/').nth(4)?;
+            uuid::Uuid::parse_str(id).ok().map(|id| Target::Thread(ThreadId(id)))
+        }
+        _ => None,
+    }
+}
+
+/// A weak validator for this URL as this viewer sees it at `modified_at`.
+fn etag(req: &Request, modified_at: DateTime<Utc>) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(modified_at.timestamp_micros().to_be_bytes());
+    hasher.update(req.uri().path_and_query().map_or("", |pq| pq.as_str()).as_bytes());
+    for name in VARIANT_HEADERS {
+        hasher.update(b"\0");
+        for value in req.headers().get_all(name) {
+            hasher.update(value.as_bytes());
+            hasher.update(b"\n");
+        }
+    }
+    hasher.update(b"\0");
+    if let Some(ip) = client_ip(req.extensions(), req.headers()) {
+        hasher.update(ip.to_string().as_bytes());
+    }
+    hasher.update(b"\0");
+    if let Some(notice) = req.extensions().get::<Site>().and_then(Site::active_notice) {
+        hasher.update(notice.as_bytes());
+    }
+    format!("W/\"{}\"", hex::encode(&hasher.finalize()[..16]))
+}
+
+/// Whether the client's copy is current. `If-None-Match` decides when
+/// present (RFC 9110 §13.2.2), compared weakly; `If-Modified-Since` has
+/// one-second resolution.
+fn not_modified(headers: &HeaderMap, etag: &str, modified_at: DateTime<Utc>) -> bool {
+    if headers.contains_key(header::IF_NONE_MATCH) {
+        return headers
+            .get_all(header::IF_NONE_MATCH)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','))
+            .map(str::trim)
+            .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag.trim_start_matches("W/"));
+    }
+    headers
+        .get(header::IF_MODIFIED_SINCE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| DateTime::parse_from_rfc2822(v).ok())
+        .is_some_and(|since| modified_at.timestamp() <= since.timestamp())
+}
+
+fn set_validators(headers: &mut HeaderMap, etag: &str, modified_at: DateTime<Utc>) {
+    let last_modified = modified_at.format("%a, %d %b %Y %H:%M:%S GMT").to_string();
+    if let Ok(value) = HeaderValue::from_str(etag) {
+        headers.insert(header::ETAG, value);
+    }
+    if let Ok(value) = HeaderValue::from_str(&last_modified) {
+        headers.insert(header::LAST_MODIFIED, value);
+    }
+    headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("private, no-cache"));
+}
@@ -0,0 +1,263 @@
+//! CSRF protection with a double-submit cookie.
+//!
+//! Every visitor gets a random token in the `csrf` cookie. Pages carry it
+//! back: after the handler runs, each `<form method="post">` of an HTML
+//! response gets a hidden `_csrf` field right after its opening tag, and
+//! `base.html` wraps `fetch` to send the cookie as `X-CSRF-Token` on
+//! same-origin requests other than `GET`. A third-party page can make the
+//! browser send the cookie but cannot read it, so it cannot forge either.
+//!
+//! Checked are the requests a cross-site page can send without a CORS
+//! preflight: methods other than `GET`, `HEAD` and `OPTIONS` with no body
+//! type, or with `application/x-www-form-urlencoded`, `multipart/form-data`
+//! or `text/plain`. They need the cookie and the same token in the header, or
+//! in the `_csrf` field: anywhere in a urlencoded form, first in a multipart
+//! one (where the injected field sits). Requests with an `Authorization`
+//! header (staff tokens, API keys) and JSON bodies are not checked; browsers
+//! never send those cross-site unasked. A failed check is `ApiError::Csrf`.
+
+use axum::{
+    body::{Body, Bytes},
+    extract::Request,
+    http::{header, HeaderMap, HeaderValue, Method},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use futures_util::{stream, StreamExt};
+
+use crate::common::errors::ApiError;
+
+/// Cookie holding the visitor's token. Readable by scripts: `base.html`
+/// copies it into the header.
+pub const CSRF_COOKIE: &str = "csrf";
+
+/// Header `fetch` requests carry the token in.
+pub const CSRF_HEADER: &str = "x-csrf-token";
+
+/// Form field forms carry the token in.
+pub const CSRF_FIELD: &str = "_csrf";
+
+/// Largest urlencoded form read for its token.
+const MAX_FORM_BYTES: usize = 1 << 20;
+
+/// Most of a multipart body read while looking for its first field.
+const MAX_FIRST_PART_BYTES: usize = 8 * 1024;
+
+/// How long the cookie lasts, in seconds: a year, so pages left open or
+/// restored with the browser keep working.
+const COOKIE_MAX_AGE: u32 = 365 * 24 * 3600;
+
+/// Axum middleware that checks and hands out CSRF tokens.
+pub async fn csrf_middleware(req: Request, next: Next) -> Response {
+    let cookie = cookie_token(req.headers());
+    let req = if needs_check(&req) {
+        match check(req, cookie.as_deref()).await {
+            Ok(req) => req,
+            Err(e) => return e.into_response(),
+        }
+    } else {
+        req
+    };
+
+    let token = cookie.clone().unwrap_or_else(new_token);
+    let response = next.run(req).await;
+    let is_html = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|t| t.starts_with("text/html"));
+    if !is_html {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    if cookie.is_none() {
+        let set = format!("{CSRF_COOKIE}={token}; Path=/; SameSite=Lax; Max-Age={COOKIE_MAX_AGE}");
+        if let Ok(value) = HeaderValue::from_str(&set) {
+            parts.headers.append(header::SET_COOKIE, value);
+        }
+    }
+    let bytes = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::error!(error = %e, "failed to buffer response for CSRF tokens");
+            return ApiError::Internal(e.to_string()).into_response();
+        }
+    };
+    let Some(with_tokens) = std::str::from_utf8(&bytes).ok().and_then(|html| add_to_forms(html, &token)) else {
+        return Response::from_parts(parts, Body::from(bytes));
+    };
+    parts.headers.remove(header::CONTENT_LENGTH);
+    // The page now holds this visitor's token; shared caches must not keep it.
+    parts
+        .headers
+        .entry(header::CACHE_CONTROL)
+        .or_insert(HeaderValue::from_static("private"));
+    Response::from_parts(parts, Body::from(with_tokens))
+}
+
+/// Whether a cross-site page could have sent this request.
+fn needs_check(req: &Request) -> bool {
+    if matches!(*req.method(), Method::GET | Method::HEAD | Method::OPTIONS) {
+        return false;
+    }
+    if req.headers().contains_key(header::AUTHORIZATION) {
+        return false;
+    }
+    match content_type(req.headers()) {
+        None => true,
+        Some(t) => {
+            t.starts_with("application/x-www-form-urlencoded")
+                || t.starts_with("multipart/form-data")
+                || t.starts_with("text/plain")
+        }
+    }
+}
+
+/// The request, its body intact, if it carries the cookie's token.
+async fn check(req: Request, cookie: Option<&str>) -> Result<Request, ApiError> {
+    let Some(cookie) = cookie else {
+        return Err(ApiError::Csrf);
+    };
+    if let Some(sent) = req.headers().get(CSRF_HEADER).and_then(|v| v.to_str().ok()) {
+        return if same_token(sent, cookie) { Ok(req) } else { Err(ApiError::Csrf) };
+    }
+
+    let content_type = content_type(req.headers()).unwrap_or_default();
+    let (parts, body) = req.into_parts();
+    let (sent, body) = if content_type.starts_with("application/x-www-form-urlencoded") {
+        let bytes = axum::body::to_bytes(body, MAX_FORM_BYTES)
+            .await
+            .map_err(|_| ApiError::BadRequest("form too large".to_owned()))?;
+        (urlencoded_field(&bytes), Body::from(bytes))
+    } else if let Some(boundary) = boundary(&parts.headers) {
+        first_multipart_field(body, &boundary).await
+    } else {
+        (None, body)
+    };
+    match sent {
+        Some(sent) if same_token(&sent, cookie) => Ok(Request::from_parts(parts, body)),
+        _ => Err(ApiError::Csrf),
+    }
+}
+
+fn content_type(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(|t| t.trim().to_ascii_lowercase())
+}
+
+/// The visitor's token, if the `csrf` cookie holds a well-formed one.
+fn cookie_token(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find_map(|(name, value)| (name == CSRF_COOKIE).then_some(value))
+        .filter(|value| value.len() == 64 && value.bytes().all(|b| b.is_ascii_hexdigit()))
+        .map(str::to_owned)
+}
+
+fn new_token() -> String {
+    hex::encode(rand::random::<[u8; 32]>())
+}
+
+/// Compare tokens in time independent of where they differ.
+fn same_token(a: &str, b: &str) -> bool {
+    a.len() == b.len() && a.bytes().zip(b.bytes()).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// The `_csrf` field of a urlencoded form. Tokens are hex, so no decoding.
+fn urlencoded_field(body: &[u8]) -> Option<String> {
+    std::str::from_utf8(body)
+        .ok()?
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find_map(|(name, value)| (name == CSRF_FIELD).then(|| value.to_owned()))
+}
+
+/// The multipart boundary, as sent: unlike the media type, it is case-sensitive.
+fn boundary(headers: &HeaderMap) -> Option<String> {
+    let content_type = headers.get(header::CONTENT_TYPE)?.to_str().ok()?;
+    if !content_type.trim().to_ascii_lowercase().starts_with("multipart/form-data") {
+        return None;
+    }
+    content_type
+        .split(';')
+        .filter_map(|param| param.trim().split_once('='))
+        .find_map(|(name, value)| (name == "boundary").then(|| value.trim_matches('"').to_owned()))
+}
+
+/// The value of the first part of a multipart body if that part is the
+/// `_csrf` field, and the body to hand on: the chunks read so far followed by
+/// the rest, so uploads are still streamed.
+async fn first_multipart_field(body: Body, boundary: &str) -> (Option<String>, Body) {
+    let mut rest = body.into_data_stream();
+    let mut read: Vec<Bytes> = Vec::new();
+    let mut buffer = Vec::new();
+    let mut field = None;
+    while buffer.len() < MAX_FIRST_PART_BYTES {
+        match rest.next().await {
+            Some(Ok(chunk)) => {
+                buffer.extend_from_slice(&chunk);
+                read.push(chunk);
+            }
+            _ => break,
+        }
+        if let Some(found) = parse_first_part(&buffer, boundary) {
+            field = found;
+            break;
+        }
+    }
+    let replay = stream::iter(read.into_iter().map(Ok::<_, axum::Error>));
+    (field, Body::from_stream(replay.chain(rest)))
+}
+
+/// `None` while the first part is incomplete; then `Some(value)` if it is
+/// the `_csrf` field and `Some(None)` if it is another one.
+fn parse_first_part(buffer: &[u8], boundary: &str) -> Option<Option<String>> {
+    let delimiter = format!("--{boundary}\r\n");
+    let start = find(buffer, delimiter.as_bytes())? + delimiter.len();
+    let headers_end = start + find(&buffer[start..], b"\r\n\r\n")?;
+    let value_start = headers_end + 4;
+    let value_end = value_start + find(&buffer[value_start..], format!("\r\n--{boundary}").as_bytes())?;
+
+    let headers = String::from_utf8_lossy(&buffer[start..headers_end]).to_ascii_lowercase();
+    let is_token = headers
+        .lines()
+        .filter(|line| line.starts_with("content-disposition:"))
+        .any(|line| line.split(';').any(|param| param.trim() == format!("name=\"{CSRF_FIELD}\"")));
+    Some(is_token.then(|| String::from_utf8_lossy(&buffer[value_start..value_end]).into_owned()))
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+/// `html` with the token field in each POST form; `None` if it has none.
+fn add_to_forms(html: &str, token: &str) -> Option<String> {
+    let lower = html.to_ascii_lowercase();
+    let field = format!(r#"<input type="hidden" name="{CSRF_FIELD}" value="{token}">"#);
+    let mut out = String::new();
+    let mut copied = 0;
+    let mut from = 0;
+    while let Some(at) = lower[from..].find("<form").map(|i| from + i) {
+        let Some(end) = lower[at..].find('>').map(|i| at + i + 1) else { break };
+        from = end;
+        let tag = &lower[at..end];
+        let is_post = ["method=\"post\"", "method='post'", "method=post"].iter().any(|m| tag.contains(m));
+        if is_post {
+            out.push_str(&html[copied..end]);
+            out.push_str(&field);
+            copied = end;
+        }
+    }
+    if copied == 0 {
+        return None;
+    }
+    out.push_str(&html[copied..]);
+    Some(out)
+}
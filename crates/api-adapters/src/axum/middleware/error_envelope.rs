@@ -0,0 +1,93 @@
+//! JSON error envelope for every error response.
+//!
+//! `ApiError` renders as an `ErrorBody`. Errors raised before a handler runs
+//! do not: extractor rejections (malformed JSON or query strings, missing
+//! roles), oversized bodies, unknown methods, and the empty 500 left by a
+//! caught panic answer with plain text or nothing. This middleware wraps
+//! those in the same envelope, so API clients can always branch on
+//! `error.code`.
+//!
+//! A 4xx body's text becomes the message; a 5xx body is replaced by a generic
+//! one, as `ApiError::Internal` does. `Retry-After` is copied into
+//! `retry_after`. JSON and HTML responses are left alone, so the ban notice
+//! and `error_page_middleware`'s pages are kept; status, headers and
+//! extensions always are.
+
+use axum::{
+    body::Body,
+    extract::Request,
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+
+use crate::common::errors::ErrorBody;
+
+/// Largest plain-text body kept as the message; rejection texts are far shorter.
+const MAX_MESSAGE_BYTES: usize = 4096;
+
+/// Axum middleware that puts error responses without a JSON or HTML body in
+/// the `ErrorBody` envelope.
+pub async fn error_envelope_middleware(req: Request, next: Next) -> Response {
+    let response = next.run(req).await;
+    let status = response.status();
+    if !(status.is_client_error() || status.is_server_error()) || has_own_body(response.headers()) {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let text = match axum::body::to_bytes(body, MAX_MESSAGE_BYTES).await {
+        Ok(bytes) => String::from_utf8_lossy(&bytes).trim().to_owned(),
+        Err(_) => String::new(),
+    };
+    let message = if status.is_server_error() || text.is_empty() {
+        default_message(status)
+    } else {
+        text
+    };
+    let mut envelope = ErrorBody::new(code_for_status(status), message);
+    envelope.error.retry_after = parts
+        .headers
+        .get(header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse().ok());
+    let json = match serde_json::to_vec(&envelope) {
+        Ok(json) => json,
+        Err(e) => {
+            tracing::error!(error = %e, "failed to serialize error envelope");
+            return Response::from_parts(parts, Body::empty());
+        }
+    };
+
+    parts.headers.remove(header::CONTENT_LENGTH);
+    parts.headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    Response::from_parts(parts, Body::from(json))
+}
+
+/// The `ErrorBody` code for a status, matching the codes of `ApiError`.
+fn code_for_status(status: StatusCode) -> String {
+    match status {
+        StatusCode::UNPROCESSABLE_ENTITY => "VALIDATION_ERROR".to_owned(),
+        StatusCode::TOO_MANY_REQUESTS => "RATE_LIMITED".to_owned(),
+        StatusCode::INTERNAL_SERVER_ERROR => "INTERNAL_ERROR".to_owned(),
+        // "Payload Too Large" -> PAYLOAD_TOO_LARGE
+        _ => status
+            .canonical_reason()
+            .map_or_else(|| format!("HTTP_{}", status.as_u16()), |r| r.to_uppercase().replace([' ', '-'], "_")),
+    }
+}
+
+fn default_message(status: StatusCode) -> String {
+    match status {
+        StatusCode::INTERNAL_SERVER_ERROR => "an unexpected error occurred".to_owned(),
+        _ => status.canonical_reason().map_or_else(|| status.to_string(), str::to_lowercase),
+    }
+}
+
+/// Whether the body is already JSON or an HTML page.
+fn has_own_body(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/json") || v.starts_with("text/html"))
+}
@@ -0,0 +1,76 @@
+//! Styled error pages for browsers.
+//!
+//! Handlers report failures as `ApiError`, which renders as a JSON
+//! `ErrorBody`, and a few paths (unmatched routes, template render failures,
+//! panics caught by `error_report_middleware`) answer with an empty body or
+//! the same envelope from `error_envelope_middleware`. Neither is much use to
+//! someone following a link. For requests whose
+//! `Accept` header includes `text/html`, this middleware replaces the body of
+//! 403, 404, 429 and 500 responses with the `error.html` page in the
+//! request's locale.
+//!
+//! Status, headers (including `Retry-After`) and extensions are kept, so it
+//! can run outside `error_report_middleware` and still let it see the cause.
+//! Responses that are already HTML, such as the ban notice, are untouched, as
+//! are requests from API clients.
+
+use askama::Template;
+use axum::{
+    body::Body,
+    extract::Request,
+    http::{header, HeaderMap, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+
+use crate::axum::i18n::{request_locale, I18n};
+use crate::axum::templates::ErrorTemplate;
+
+/// Axum middleware that renders error responses to browsers as HTML pages.
+pub async fn error_page_middleware(req: Request, next: Next) -> Response {
+    let wants_html = accepts_html(req.headers());
+    let i18n = I18n::new(request_locale(req.headers(), req.extensions()));
+    let response = next.run(req).await;
+
+    if !wants_html || is_html(response.headers()) {
+        return response;
+    }
+    let retry_after_secs = response
+        .headers()
+        .get(header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+    let Some(page) = ErrorTemplate::for_status(response.status(), retry_after_secs, i18n) else {
+        return response;
+    };
+    let html = match page.render() {
+        Ok(html) => html,
+        Err(e) => {
+            tracing::error!(error = %e, "error page render error");
+            return response;
+        }
+    };
+
+    let (mut parts, _) = response.into_parts();
+    parts.headers.remove(header::CONTENT_LENGTH);
+    parts.headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("text/html; charset=utf-8"));
+    parts.headers.append(header::VARY, HeaderValue::from_static("Accept, Accept-Language"));
+    Response::from_parts(parts, Body::from(html))
+}
+
+/// Whether the request's `Accept` header lists `text/html`, as browser
+/// navigations do and `fetch` calls do not.
+fn accepts_html(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::ACCEPT)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.contains("text/html"))
+}
+
+fn is_html(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("text/html"))
+}
@@ -0,0 +1,92 @@
+//! Error-reporting middleware.
+//!
+//! Passes unexpected failures to the configured `ErrorReporter`:
+//!
+//! - a handler panic, which is turned into a plain 500 response instead of
+//!   dropping the connection;
+//! - any 500 response. When it came from `ApiError::Internal`, the report
+//!   carries the internal message (the response body only says "an unexpected
+//!   error occurred").
+//!
+//! Other 5xx statuses (502–504) describe upstream or capacity conditions
+//! rather than bugs and are not reported.
+//!
+//! Each report is tagged with the request ID, method, matched route and
+//! status. The layer must run inside `request_id_middleware` so the ID is set.
+
+use std::any::Any;
+use std::panic::AssertUnwindSafe;
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    extract::{MatchedPath, State},
+    http::{Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use domains::ports::{ErrorReport, ErrorReporter};
+use futures_util::FutureExt;
+
+use super::request_id::REQUEST_ID_HEADER;
+
+/// Response extension holding the message of an `ApiError::Internal`.
+///
+/// Inserted by `ApiError::into_response`; read here so the cause is reported
+/// without being sent to the client.
+#[derive(Clone, Debug)]
+pub struct InternalErrorCause(pub String);
+
+/// Report panics and 500 responses to `reporter`.
+pub async fn error_report_middleware(
+    State(reporter): State<Arc<dyn ErrorReporter>>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    let mut context = vec![
+        (
+            "request_id",
+            req.headers()
+                .get(&REQUEST_ID_HEADER)
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or_default()
+                .to_owned(),
+        ),
+        ("method", req.method().to_string()),
+        (
+            "route",
+            req.extensions()
+                .get::<MatchedPath>()
+                .map_or_else(|| req.uri().path().to_owned(), |p| p.as_str().to_owned()),
+        ),
+    ];
+
+    let (response, message, panic) = match AssertUnwindSafe(next.run(req)).catch_unwind().await {
+        Ok(response) => {
+            let cause = response.extensions().get::<InternalErrorCause>().map(|c| c.0.clone());
+            if response.status() != StatusCode::INTERNAL_SERVER_ERROR {
+                return response;
+            }
+            let message = cause.unwrap_or_else(|| "500 response without a recorded cause".to_owned());
+            (response, message, false)
+        }
+        Err(payload) => {
+            let message = panic_message(payload.as_ref());
+            tracing::error!(panic = %message, "request handler panicked");
+            (StatusCode::INTERNAL_SERVER_ERROR.into_response(), message, true)
+        }
+    };
+
+    context.push(("status", response.status().as_u16().to_string()));
+    reporter.report(&ErrorReport { message, panic, context });
+    response
+}
+
+/// The text of a panic payload: the `panic!` message when it has one.
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| (*s).to_owned())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "panic with a non-string payload".to_owned())
+}
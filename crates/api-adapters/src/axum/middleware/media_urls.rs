@@ -0,0 +1,23 @@
+//! `MediaLinks` extractor — the `MediaUrls` pages build attachment links with.
+//!
+//! The composition root adds `MediaUrls` as a router-wide `Extension`, set up
+//! from `MEDIA_CDN_URL` and `MEDIA_URL_SIGNING_KEY`.
+//!
+//! axum 0.8: FromRequestParts uses RPITIT — plain async fn in impl, no #[async_trait].
+
+use axum::http::request::Parts;
+use storage_adapters::media::cdn::MediaUrls;
+
+/// Axum extractor — the configured `MediaUrls`.
+///
+/// Never rejects: routers mounted without the extension (unit tests) get
+/// unsigned `/media/{key}` URLs.
+pub struct MediaLinks(pub MediaUrls);
+
+impl<S: Send + Sync> axum::extract::FromRequestParts<S> for MediaLinks {
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(MediaLinks(parts.extensions.get::<MediaUrls>().cloned().unwrap_or_default()))
+    }
+}
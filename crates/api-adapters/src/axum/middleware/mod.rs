@@ -1,8 +1,22 @@
 //! Axum middleware layers.
 pub mod accept;
+pub mod api_key;
 pub mod auth;
 pub mod board_config;
+pub mod client_ip;
+pub mod color_scheme;
+pub mod conditional_get;
 pub mod cors;
+pub mod csrf;
+pub mod error_envelope;
+pub mod error_page;
+pub mod error_report;
 pub mod login_guard;
+pub mod media_urls;
+pub mod proxy_peer;
+pub mod render_cache;
 pub mod request_id;
 pub mod security_headers;
+pub mod site;
+pub mod site_notice;
+pub mod warning_notice;
@@ -0,0 +1,41 @@
+//! Client address for connections accepted on a Unix socket.
+//!
+//! `ClientIp` starts from `ConnectInfo<SocketAddr>`. A Unix socket
+//! has no peer IP — every connection comes from the local reverse proxy — so
+//! when the server binds one, this middleware supplies `ConnectInfo` from the
+//! address the proxy forwards instead:
+//!
+//! 1. `X-Real-IP`, if it parses as an IP address
+//! 2. otherwise the last entry of `X-Forwarded-For` (the one the proxy appended)
+//! 3. otherwise `0.0.0.0`, so requests the proxy did not annotate (a local
+//!    `curl` of `/healthz`, say) still reach their handler
+//!
+//! Only the proxy can connect to the socket, so the headers are trusted as-is;
+//! if they name a proxy in `TrustedProxies`, `ClientIp` looks further.
+//! The middleware is never installed on TCP listeners, where a client could
+//! set them to anything.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use axum::{
+    body::Body,
+    extract::ConnectInfo,
+    http::{HeaderMap, Request},
+    middleware::Next,
+    response::Response,
+};
+
+/// Insert `ConnectInfo<SocketAddr>` built from the proxy's forwarding headers.
+pub async fn proxy_peer_middleware(mut req: Request<Body>, next: Next) -> Response {
+    let ip = forwarded_ip(req.headers()).unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED));
+    req.extensions_mut().insert(ConnectInfo(SocketAddr::new(ip, 0)));
+    next.run(req).await
+}
+
+/// The client IP named by `X-Real-IP` or, failing that, `X-Forwarded-For`.
+pub fn forwarded_ip(headers: &HeaderMap) -> Option<IpAddr> {
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    header("x-real-ip")
+        .and_then(|v| v.trim().parse().ok())
+        .or_else(|| header("x-forwarded-for")?.rsplit(',').next()?.trim().parse().ok())
+}
@@ -0,0 +1,207 @@
+//! Rendered page cache middleware.
+//!
+//! Caches the HTML of board indexes (`GET /board/:slug`, one entry per
+//! `?page=`) and catalogs (`GET /board/:slug/catalog`) at two levels: the
+//! in-process `PageCache`, keyed by (board, page, locale), then a shared
+//! `CacheStore` so that instances behind the same Redis render each page once
+//! per TTL. Both pages are identical for every viewer who negotiates the same
+//! UI locale (see `i18n`) — thread pages are not cached because staff see the
+//! mod toolbar there. Sorted or filtered catalogs (any query string) are not
+//! cached either.
+//!
+//! # Invalidation
+//!
+//! Any successful non-GET request on a board-scoped route (new post, settings
+//! change, volunteer change) drops the board's pages from `PageCache` and
+//! deletes its generation token in the `CacheStore`. Shared page keys embed
+//! that token, so every cached page of the board is orphaned at once and the
+//! orphans expire on their own. The same request drops the board's shared
+//! settings entry (see `board_config::CachedBoardConfigSource`). Moderator
+//! deletions call `invalidate_board` from their handlers. Other moderation
+//! actions show up once the TTL runs out, as do writes made on another
+//! instance for its own `PageCache`.
+//!
+//! Must run inside `board_config_middleware`, which supplies the board ID.
+//! Store failures are logged and only the in-process cache is used.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::{
+    body::Body,
+    extract::{MatchedPath, Request, State},
+    http::{header, Method, StatusCode},
+    middleware::Next,
+    response::{Html, IntoResponse, Response},
+};
+use bytes::Bytes;
+use domains::models::BoardId;
+use domains::ports::CacheStore;
+use storage_adapters::cache::{PageCache, PageKey};
+
+use super::board_config::{board_config_cache_key, ExtractedBoardConfig};
+use crate::axum::i18n::request_locale;
+
+/// Lifetime of a generation token. Only bounds how long an idle board's token
+/// lingers; pages themselves expire after `RenderCacheState::ttl`.
+const GENERATION_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Shared state injected into the middleware via `from_fn_with_state`.
+#[derive(Clone)]
+pub struct RenderCacheState {
+    /// In-process pages; checked before `store`. Its TTL should match `ttl`.
+    pub pages: Arc<PageCache>,
+    pub store: Arc<dyn CacheStore>,
+    /// How long a rendered page is served from the store. Zero disables
+    /// page caching; writes still invalidate.
+    pub ttl:   Duration,
+}
+
+fn generation_key(board: BoardId) -> String {
+    format!("render-gen:{}", board.0)
+}
+
+/// Axum middleware that serves and fills the rendered page cache.
+pub async fn render_cache_middleware(
+    State(state): State<RenderCacheState>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let Some(board_ctx) = req.extensions().get::<ExtractedBoardConfig>().cloned() else {
+        return next.run(req).await;
+    };
+
+    if req.method() != Method::GET && req.method() != Method::HEAD {
+        let resp = next.run(req).await;
+        if resp.status().is_success() || resp.status().is_redirection() {
+            invalidate(&state, &board_ctx).await;
+        }
+        return resp;
+    }
+
+    let page = if req.method() == Method::GET && !state.ttl.is_zero() {
+        page_key(&req)
+    } else {
+        None
+    };
+    let Some(page) = page else {
+        return next.run(req).await;
+    };
+    let board_id = board_ctx.board_id;
+
+    if let Some(html) = state.pages.get(board_id, page) {
+        return cached_page(html);
+    }
+
+    // Shared store key; `None` while the store is unreachable, in which case
+    // only the in-process cache is used.
+    let key = current_generation(&state, board_id)
+        .await
+        .map(|generation| format!("render:{}:{generation}:{page}", board_id.0));
+    if let Some(key) = &key {
+        match state.store.get(key).await {
+            Ok(Some(html)) => {
+                state.pages.set(board_id, page, html.clone());
+                return cached_page(html);
+            }
+            Ok(None) => {}
+            Err(e) => tracing::warn!(%key, error = %e, "render cache read failed"),
+        }
+    }
+
+    let resp = next.run(req).await;
+    let is_html = resp
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("text/html"));
+    if resp.status() != StatusCode::OK || !is_html {
+        return resp;
+    }
+
+    let (parts, body) = resp.into_parts();
+    let html = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(html) => html,
+        Err(e) => {
+            tracing::error!(board_id = %board_id.0, error = %e, "failed to buffer rendered page");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    state.pages.set(board_id, page, html.clone());
+    if let Some(key) = key {
+        if let Err(e) = state.store.set(&key, html.clone(), state.ttl).await {
+            tracing::warn!(%key, error = %e, "render cache write failed");
+        }
+    }
+    Response::from_parts(parts, Body::from(html))
+}
+
+/// A page served from the cache, with the headers the handler would have set.
+fn cached_page(html: Bytes) -> Response {
+    ([(header::VARY, "Accept-Language")], Html(html)).into_response()
+}
+
+/// The cache key of a board-scoped request, or `None` if it is not cached.
+///
+/// An index request whose `page` does not parse is left to the handler,
+/// which rejects it. Only the plain catalog is cached: a `sort` or `q`
+/// parameter (a random order above all) must reach the handler.
+fn page_key(req: &Request) -> Option<PageKey> {
+    let locale = request_locale(req.headers(), req.extensions());
+    match req.extensions().get::<MatchedPath>()?.as_str() {
+        "/board/{slug}/catalog" => req.uri().query().is_none_or(str::is_empty).then_some(PageKey::Catalog(locale)),
+        "/board/{slug}" => {
+            let page = req
+                .uri()
+                .query()
+                .unwrap_or("")
+                .split('&')
+                .find_map(|pair| pair.strip_prefix("page="));
+            match page {
+                Some(page) => page.parse().ok().map(|page| PageKey::Index(page, locale)),
+                None => Some(PageKey::Index(1, locale)),
+            }
+        }
+        _ => None,
+    }
+}
+
+/// The board's generation token, minting one if none is stored.
+///
+/// Returns `None` when the store is unreachable.
+async fn current_generation(state: &RenderCacheState, board: BoardId) -> Option<String> {
+    let key = generation_key(board);
+    match state.store.get(&key).await {
+        Ok(Some(raw)) => return Some(String::from_utf8_lossy(&raw).into_owned()),
+        Ok(None) => {}
+        Err(e) => {
+            tracing::warn!(%key, error = %e, "render cache read failed");
+            return None;
+        }
+    }
+    let generation = uuid::Uuid::new_v4().simple().to_string();
+    match state.store.set(&key, Bytes::from(generation.clone()), GENERATION_TTL).await {
+        Ok(()) => Some(generation),
+        Err(e) => {
+            tracing::warn!(%key, error = %e, "render cache write failed");
+            None
+        }
+    }
+}
+
+/// Drop every cached page of `board_id`, in this process and in the store.
+pub async fn invalidate_board(state: &RenderCacheState, board_id: BoardId) {
+    state.pages.invalidate_board(board_id);
+    invalidate_key(state, generation_key(board_id)).await;
+}
+
+async fn invalidate(state: &RenderCacheState, board_ctx: &ExtractedBoardConfig) {
+    invalidate_board(state, board_ctx.board_id).await;
+    invalidate_key(state, board_config_cache_key(board_ctx.board.site_id, &board_ctx.slug)).await;
+}
+
+async fn invalidate_key(state: &RenderCacheState, key: String) {
+    if let Err(e) = state.store.invalidate(&key).await {
+        tracing::warn!(%key, error = %e, "render cache invalidation failed");
+    }
+}
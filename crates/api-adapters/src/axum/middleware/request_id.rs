@@ -1,13 +1,60 @@
 //! Request-ID middleware.
 //!
-//! Assigns a unique `X-Request-Id` header to every inbound request so that
-//! log lines can be correlated across the application and reverse-proxy layers.
+//! Gives every request an ID so a user's bug report can be matched to the
+//! server's log lines:
 //!
-//! **Status — v1.1.1 planned.**  The `tracing-subscriber` structured logger
-//! already emits per-request spans; a dedicated `X-Request-Id` header is the
-//! next step. Implementation will use `tower-http::request_id::SetRequestId`
-//! with a `uuid::Uuid::new_v4()` generator and echo the value back in the
-//! response so callers can include it in bug reports.
+//! 1. An `X-Request-Id` set by the reverse proxy (e.g. nginx `$request_id`) is
+//!    kept, so proxy and application logs share one ID. Values longer than
+//!    64 characters, or with characters outside `A-Z a-z 0-9 - _ .`, are
+//!    replaced rather than copied into the logs.
+//! 2. Otherwise a UUID v4 is generated.
 //!
-//! The module is declared now so the middleware stack wiring does not need a
-//! module-tree restructure when the feature lands.
+//! The request is handled inside a `request` span carrying `request_id`,
+//! `method`, `uri` and, once known, `status`, so every JSON log line written
+//! while handling it includes the ID. The ID is echoed in the `X-Request-Id`
+//! response header.
+
+use axum::{
+    body::Body,
+    http::{HeaderName, HeaderValue, Request},
+    middleware::Next,
+    response::Response,
+};
+use tracing::Instrument;
+
+/// `X-Request-Id`, on both the request and the response.
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Longest incoming request ID that is kept.
+const MAX_LEN: usize = 64;
+
+/// Assign the request ID, handle the request inside its span, and echo the ID.
+pub async fn request_id_middleware(mut req: Request<Body>, next: Next) -> Response {
+    let id = match req.headers().get(&REQUEST_ID_HEADER) {
+        Some(value) if is_valid(value) => value.clone(),
+        _ => {
+            let value = HeaderValue::from_str(&uuid::Uuid::new_v4().to_string())
+                .expect("a UUID is a valid header value");
+            req.headers_mut().insert(REQUEST_ID_HEADER, value.clone());
+            value
+        }
+    };
+    let span = tracing::info_span!(
+        "request",
+        request_id = id.to_str().unwrap_or_default(),
+        method = %req.method(),
+        uri = %req.uri(),
+        status = tracing::field::Empty,
+    );
+    let mut response = next.run(req).instrument(span.clone()).await;
+    span.record("status", response.status().as_u16());
+    response.headers_mut().insert(REQUEST_ID_HEADER, id);
+    response
+}
+
+fn is_valid(value: &HeaderValue) -> bool {
+    let bytes = value.as_bytes();
+    !bytes.is_empty()
+        && bytes.len() <= MAX_LEN
+        && bytes.iter().all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.'))
+}
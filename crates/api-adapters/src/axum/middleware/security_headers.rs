@@ -10,23 +10,84 @@
 //! | `Permissions-Policy` | `interest-cohort=()` |
 //! | `Content-Security-Policy` | see below |
 //!
-//! CSP: `default-src 'self'; img-src 'self' data:; script-src 'self'; style-src 'self'`
+//! CSP: `default-src 'self'; img-src 'self' data: blob:; media-src 'self';
+//! script-src 'self' 'nonce-…'; style-src 'self' 'unsafe-inline';
+//! object-src 'none'; base-uri 'self'; frame-ancestors 'none'`
+//!
+//! When media is served from another origin (`MEDIA_CDN_URL`), that origin is
+//! added to `img-src` and `media-src`, so images, thumbnails and videos load.
+//!
+//! Inline scripts run only with the request's nonce, so markup that slips
+//! past escaping cannot run script. Templates mark their `<script>` blocks
+//! with `nonce="__CSP_NONCE__"` and this middleware swaps in a fresh nonce
+//! per response; pages from the render cache are shared between viewers, so
+//! the nonce cannot be rendered into them. Askama and `rb-format` escape
+//! quotes, so the marked attribute can only come from a template. Inline event
+//! handlers (`onclick=`) are blocked; templates attach listeners instead.
+//! `304 Not Modified` responses go without the CSP header, so the browser
+//! keeps the policy whose nonce its stored page holds.
 //!
 //! These are conservative defaults appropriate for an imageboard that serves its
 //! own static assets. Operators running behind a reverse proxy should also enable HSTS
 //! at the proxy layer.
 
-use axum::{body::Body, http::Request, middleware::Next, response::Response};
+use axum::{
+    body::Body,
+    extract::State,
+    http::{header, HeaderValue, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use storage_adapters::media::cdn::MediaUrls;
+
+use crate::common::errors::ApiError;
+
+/// Attribute in templates' `<script>` tags replaced with the response's nonce.
+pub const NONCE_PLACEHOLDER: &str = r#"nonce="__CSP_NONCE__""#;
+
+/// State of `security_headers_middleware`.
+#[derive(Clone, Default)]
+pub struct SecurityHeaders {
+    /// Origin of media links when it is not this site, e.g. a CDN.
+    media_origin: Option<String>,
+}
+
+impl SecurityHeaders {
+    /// Headers for a site whose attachment links `media` builds.
+    pub fn new(media: &MediaUrls) -> Self {
+        Self { media_origin: media.origin().map(str::to_owned) }
+    }
+}
 
-// 'unsafe-inline' is required because board/thread/login templates embed
-// JavaScript in <script> blocks (quick-reply, flag modal, login handler).
-// Extracting them to separate .js files is tracked as a v1.2 hardening task.
-static CSP: &str =
-    "default-src 'self'; img-src 'self' data: blob:; script-src 'self' 'unsafe-inline'; style-src 'self' 'unsafe-inline'";
+// 'unsafe-inline' styles remain: templates use style="" attributes
+// throughout, and nonces do not cover attributes.
+fn csp(nonce: &str, media_origin: Option<&str>) -> String {
+    let media = media_origin.map(|origin| format!(" {origin}")).unwrap_or_default();
+    format!(
+        "default-src 'self'; img-src 'self' data: blob:{media}; media-src 'self'{media}; \
+         script-src 'self' 'nonce-{nonce}'; style-src 'self' 'unsafe-inline'; object-src 'none'; \
+         base-uri 'self'; frame-ancestors 'none'"
+    )
+}
 
 /// Axum middleware that adds security-related HTTP headers to every response.
-pub async fn security_headers_middleware(req: Request<Body>, next: Next) -> Response {
+pub async fn security_headers_middleware(
+    State(config): State<SecurityHeaders>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    let nonce = hex::encode(rand::random::<[u8; 16]>());
     let mut response = next.run(req).await;
+    let is_html = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|t| t.starts_with("text/html"));
+    if is_html {
+        response = add_nonce(response, &nonce).await;
+    }
+
+    let status = response.status();
     let headers = response.headers_mut();
     headers.insert(
         axum::http::header::HeaderName::from_static("x-content-type-options"),
@@ -44,9 +105,31 @@ pub async fn security_headers_middleware(req: Request<Body>, next: Next) -> Resp
         axum::http::header::HeaderName::from_static("permissions-policy"),
         axum::http::HeaderValue::from_static("interest-cohort=()"),
     );
-    headers.insert(
-        axum::http::header::HeaderName::from_static("content-security-policy"),
-        axum::http::HeaderValue::from_static(CSP),
-    );
+    // A 304's headers replace the stored ones, and the stored page keeps the
+    // nonce it was sent with; a new one would block its scripts.
+    if status != StatusCode::NOT_MODIFIED {
+        if let Ok(value) = HeaderValue::from_str(&csp(&nonce, config.media_origin.as_deref())) {
+            headers.insert(axum::http::header::HeaderName::from_static("content-security-policy"), value);
+        }
+    }
     response
 }
+
+/// `response` with each nonce marker in its body replaced by `nonce`.
+async fn add_nonce(response: Response, nonce: &str) -> Response {
+    let (mut parts, body) = response.into_parts();
+    let bytes = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::error!(error = %e, "failed to buffer response for CSP nonce");
+            return ApiError::Internal(e.to_string()).into_response();
+        }
+    };
+    match std::str::from_utf8(&bytes) {
+        Ok(html) if html.contains(NONCE_PLACEHOLDER) => {
+            parts.headers.remove(header::CONTENT_LENGTH);
+            Response::from_parts(parts, Body::from(html.replace(NONCE_PLACEHOLDER, &format!(r#"nonce="{nonce}""#))))
+        }
+        _ => Response::from_parts(parts, Body::from(bytes)),
+    }
+}
@@ -0,0 +1,81 @@
+//! Site resolution middleware.
+//!
+//! One process can serve several independent imageboards. This middleware
+//! maps the request's host (the `Host` header, or the URI authority under
+//! HTTP/2) to a `Site` and inserts it into request extensions; requests for a
+//! host no site claims get the default site. Board lookups, the overboard and
+//! upload keys are then scoped to that site.
+//!
+//! Behind a reverse proxy, the proxy must pass the client's `Host` through
+//! (nginx: `proxy_set_header Host $host;`).
+//!
+//! Handlers read the site with the `CurrentSite` extractor:
+//!
+//! ```rust,ignore
+//! CurrentSite(site): CurrentSite
+//! ```
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Request, State},
+    http::{header::HOST, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use domains::models::Site;
+use domains::ports::SiteRepository;
+use storage_adapters::cache::SiteCache;
+
+/// Shared state injected into the middleware via `from_fn_with_state`.
+#[derive(Clone)]
+pub struct SiteState {
+    /// Source of the site list.
+    pub repo:  Arc<dyn SiteRepository>,
+    /// In-process cache of the list — avoids a DB round-trip on every request.
+    pub cache: Arc<SiteCache>,
+}
+
+/// Axum middleware that resolves the request's host to a `Site` and inserts it
+/// into request extensions.
+///
+/// Returns `503 Service Unavailable` if the site list cannot be loaded, rather
+/// than serving one site's boards on another's host.
+pub async fn site_middleware(State(state): State<SiteState>, mut req: Request, next: Next) -> Response {
+    let sites = match state.cache.get() {
+        Some(sites) => sites,
+        None => match state.repo.find_all().await {
+            Ok(sites) => state.cache.set(sites),
+            Err(e) => {
+                tracing::error!(error = %e, "site_middleware: repository error");
+                return (StatusCode::SERVICE_UNAVAILABLE, "failed to load site configuration").into_response();
+            }
+        },
+    };
+
+    let host = req
+        .uri()
+        .authority()
+        .map(|a| a.as_str())
+        .or_else(|| req.headers().get(HOST).and_then(|v| v.to_str().ok()));
+    let site = services::site::resolve(&sites, host).into_owned();
+    req.extensions_mut().insert(site);
+    next.run(req).await
+}
+
+/// Axum extractor — the `Site` resolved by `site_middleware`.
+///
+/// Never rejects: routers mounted without the middleware (unit tests, a
+/// single-site embed) see `Site::fallback()`, the default site.
+pub struct CurrentSite(pub Site);
+
+// axum 0.8: FromRequestParts uses RPITIT — plain async fn in impl, no #[async_trait] needed.
+impl<S: Send + Sync> axum::extract::FromRequestParts<S> for CurrentSite {
+    type Rejection = std::convert::Infallible;
+    async fn from_request_parts(
+        parts: &mut axum::http::request::Parts,
+        _state: &S,
+    ) -> Result<Self, Self::Rejection> {
+        Ok(CurrentSite(parts.extensions.get::<Site>().cloned().unwrap_or_else(Site::fallback)))
+    }
+}
@@ -0,0 +1,108 @@
+//! The site notice (MOTD) on every page and JSON response.
+//!
+//! While the site's notice is switched on (`Site::active_notice`), this
+//! middleware adds it to each response:
+//!
+//! - HTML pages get a `<div class="site-notice">` at the `site-notice` marker
+//!   that `base.html` leaves below the site header.
+//! - JSON objects get a top-level `"meta": { "notice": "…" }` member, unless
+//!   they already have a `meta` member. Arrays and other bodies are untouched.
+//!
+//! The middleware runs inside `site_middleware` and outside the render cache,
+//! so cached pages stay notice-free and a change applies on the next request.
+
+use std::collections::HashMap;
+
+use axum::{
+    body::Body,
+    extract::Request,
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use domains::models::Site;
+use serde::de::IgnoredAny;
+
+/// Where `base.html` wants the notice.
+const NOTICE_MARKER: &str = "<!-- site-notice -->";
+
+/// Axum middleware that adds the site notice to HTML and JSON responses.
+pub async fn site_notice_middleware(req: Request, next: Next) -> Response {
+    let notice = req.extensions().get::<Site>().and_then(Site::active_notice).map(str::to_owned);
+    let response = next.run(req).await;
+    let Some(notice) = notice else {
+        return response;
+    };
+
+    let content_type = response.headers().get(header::CONTENT_TYPE).and_then(|v| v.to_str().ok());
+    let kind = match content_type {
+        Some(t) if t.starts_with("text/html") => Kind::Html,
+        Some(t) if t.starts_with("application/json") => Kind::Json,
+        _ => return response,
+    };
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::error!(error = %e, "failed to buffer response for the site notice");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let with_notice = std::str::from_utf8(&bytes).ok().and_then(|text| match kind {
+        Kind::Html => add_to_html(text, &notice),
+        Kind::Json => add_to_json(text, &notice),
+    });
+    let Some(with_notice) = with_notice else {
+        return Response::from_parts(parts, Body::from(bytes));
+    };
+    parts.headers.remove(header::CONTENT_LENGTH);
+    Response::from_parts(parts, Body::from(with_notice))
+}
+
+#[derive(Clone, Copy)]
+enum Kind {
+    Html,
+    Json,
+}
+
+/// `html` with the notice at the marker; `None` when the page has no marker.
+fn add_to_html(html: &str, notice: &str) -> Option<String> {
+    let at = html.find(NOTICE_MARKER)?;
+    let mut out = String::with_capacity(html.len() + notice.len() + 64);
+    out.push_str(&html[..at]);
+    out.push_str(r#"<div class="site-notice" role="status">"#);
+    push_escaped(&mut out, notice);
+    out.push_str("</div>");
+    out.push_str(&html[at + NOTICE_MARKER.len()..]);
+    Some(out)
+}
+
+/// Append `text` to `out`, escaped for HTML text and attribute values.
+pub(crate) fn push_escaped(out: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+}
+
+/// `json` with a `meta` member holding the notice; `None` unless `json` is
+/// an object without one.
+fn add_to_json(json: &str, notice: &str) -> Option<String> {
+    let members: HashMap<String, IgnoredAny> = serde_json::from_str(json).ok()?;
+    if members.contains_key("meta") {
+        return None;
+    }
+    let meta = serde_json::json!({ "notice": notice }).to_string();
+    // The opening brace is the first non-whitespace character of an object.
+    let open = json.find('{')?;
+    let separator = if members.is_empty() { "" } else { "," };
+    Some(format!("{}{{\"meta\":{meta}{separator}{}", &json[..open], &json[open + 1..]))
+}
+
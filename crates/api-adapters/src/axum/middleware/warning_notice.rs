@@ -0,0 +1,127 @@
+//! A moderator's pending warning on every page the warned poster loads.
+//!
+//! After the handler runs, an HTML response for a visitor whose daily IP hash
+//! (as `create_post` computes it) has an unacknowledged `Warning` gets a
+//! `<div class="warning-notice">` at the `warning-notice` marker that
+//! `base.html` leaves below the site header. The notice carries the warning
+//! text and a form posting to `/warning/{id}/ack`; until that is sent,
+//! `create_post` refuses the poster's posts.
+//!
+//! Other responses are passed through without a lookup, except that a
+//! conditional request is looked up before it runs: a warned visitor's loses
+//! its `If-None-Match` and `If-Modified-Since`, so they get the page with the
+//! notice rather than a `304`. A failed lookup is logged and the page is
+//! served without a notice.
+
+use std::net::IpAddr;
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use domains::models::Warning;
+use domains::ports::WarningRepository;
+
+use crate::axum::i18n::{request_locale, I18n};
+use crate::axum::middleware::client_ip::client_ip;
+use crate::axum::middleware::site_notice::push_escaped;
+
+/// Where `base.html` wants the notice.
+const NOTICE_MARKER: &str = "<!-- warning-notice -->";
+
+/// Axum middleware that shows a visitor their pending warning.
+pub async fn warning_notice_middleware(
+    State(warnings): State<Arc<dyn WarningRepository>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let mut req = req;
+    let peer = client_ip(req.extensions(), req.headers());
+    let locale = request_locale(req.headers(), req.extensions());
+    // A 304 would leave the visitor with their stored copy, which lacks the
+    // notice, so conditional requests are looked up first and a warned
+    // visitor's are made unconditional.
+    let conditional = [header::IF_NONE_MATCH, header::IF_MODIFIED_SINCE]
+        .iter()
+        .any(|name| req.headers().contains_key(name));
+    let looked_up = match peer {
+        Some(peer) if conditional => {
+            let warning = pending_warning(warnings.as_ref(), peer).await;
+            if warning.is_some() {
+                req.headers_mut().remove(header::IF_NONE_MATCH);
+                req.headers_mut().remove(header::IF_MODIFIED_SINCE);
+            }
+            Some(warning)
+        }
+        _ => None,
+    };
+    let response = next.run(req).await;
+    let Some(peer) = peer else {
+        return response;
+    };
+    let is_html = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|t| t.starts_with("text/html"));
+    if !is_html {
+        return response;
+    }
+
+    let warning = match looked_up {
+        Some(warning) => warning,
+        None => pending_warning(warnings.as_ref(), peer).await,
+    };
+    let Some(warning) = warning else {
+        return response;
+    };
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::error!(error = %e, "failed to buffer response for a warning");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let with_notice = std::str::from_utf8(&bytes)
+        .ok()
+        .and_then(|html| add_to_html(html, &warning, &I18n::new(locale)));
+    let Some(with_notice) = with_notice else {
+        return Response::from_parts(parts, Body::from(bytes));
+    };
+    parts.headers.remove(header::CONTENT_LENGTH);
+    Response::from_parts(parts, Body::from(with_notice))
+}
+
+/// The visitor's pending warning; `None` also when the lookup fails.
+async fn pending_warning(warnings: &dyn WarningRepository, peer: IpAddr) -> Option<Warning> {
+    let daily_salt = chrono::Utc::now().format("%Y-%m-%d").to_string();
+    let ip_hash = services::common::utils::hash_ip(&peer.to_string(), &daily_salt);
+    warnings.find_pending_by_ip(&ip_hash).await.unwrap_or_else(|e| {
+        tracing::warn!(error = %e, "pending warning lookup failed");
+        None
+    })
+}
+
+/// `html` with the warning at the marker; `None` when the page has no marker.
+fn add_to_html(html: &str, warning: &Warning, i18n: &I18n) -> Option<String> {
+    let at = html.find(NOTICE_MARKER)?;
+    let mut out = String::with_capacity(html.len() + warning.reason.len() + 512);
+    out.push_str(&html[..at]);
+    out.push_str(r#"<div class="warning-notice" role="alert"><strong>"#);
+    push_escaped(&mut out, &i18n.t("warning-heading"));
+    out.push_str("</strong><p>");
+    push_escaped(&mut out, &i18n.t("warning-intro"));
+    out.push_str(r#"</p><blockquote class="ban-reason">"#);
+    push_escaped(&mut out, &warning.reason);
+    out.push_str(&format!(r#"</blockquote><form method="post" action="/warning/{}/ack"><button type="submit">"#, warning.id));
+    push_escaped(&mut out, &i18n.t("warning-acknowledge"));
+    out.push_str("</button></form></div>");
+    out.push_str(&html[at + NOTICE_MARKER.len()..]);
+    Some(out)
+}
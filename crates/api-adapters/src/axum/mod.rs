@@ -4,12 +4,16 @@
 //! `axum::Router` with all routes, middleware layers, and injected services.
 //! Called once in `composition.rs`.
 
+pub mod compression;
 pub mod error;
 pub mod handlers;
 pub mod health;
+pub mod i18n;
 pub mod metrics;
 pub mod middleware;
+pub mod multipart;
 pub mod routes;
+pub mod static_archive;
 pub mod templates;
 
 use std::sync::Arc;
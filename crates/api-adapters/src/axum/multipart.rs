@@ -0,0 +1,184 @@
+//! Multipart form posts within the configured limits.
+//!
+//! `MultipartLimits` (`MAX_BODY_KB`, `MAX_UPLOAD_KB`, `MAX_TEXT_FIELD_KB`,
+//! `MAX_FORM_FIELDS`) is installed as an `Extension`, and the body limit as
+//! `DefaultBodyLimit`. Handlers take `LimitedMultipart` instead of axum's
+//! `Multipart`; it enforces the limits while the body streams in, so a post
+//! over any of them is refused at the first field or chunk past the limit and
+//! the rest of the body is never read:
+//!
+//! - the whole body, and the field count: `413 PAYLOAD_TOO_LARGE`;
+//! - a text field read with `LimitedField::text`: `413 PAYLOAD_TOO_LARGE`;
+//! - a file part read with `LimitedField::bytes`: `422` "file too large",
+//!   as for a board's `max_file_size`. Handlers that stream file parts
+//!   themselves check `LimitedField::max_file_bytes`.
+
+use axum::{
+    extract::{
+        multipart::{Field, MultipartError},
+        DefaultBodyLimit, FromRequest, Multipart, Request,
+    },
+    http::StatusCode,
+};
+use bytes::{Bytes, BytesMut};
+use domains::errors::{DomainError, ValidationError};
+
+use crate::common::errors::ApiError;
+
+/// Limits on request bodies and multipart form posts.
+#[derive(Debug, Clone, Copy)]
+pub struct MultipartLimits {
+    /// Largest request body, in bytes.
+    pub max_body_bytes: usize,
+    /// Largest file part, in bytes.
+    pub max_file_bytes: u64,
+    /// Longest text field, in bytes.
+    pub max_text_bytes: usize,
+    /// Most fields in one post.
+    pub max_fields:     usize,
+}
+
+impl Default for MultipartLimits {
+    /// The `Settings` defaults: 12 MiB bodies, 10 MiB files, 64 KiB text
+    /// fields, 32 fields.
+    fn default() -> Self {
+        Self {
+            max_body_bytes: 12 * 1024 * 1024,
+            max_file_bytes: 10 * 1024 * 1024,
+            max_text_bytes: 64 * 1024,
+            max_fields:     32,
+        }
+    }
+}
+
+impl MultipartLimits {
+    /// Limits from `Settings` values in KiB.
+    pub fn from_kb(max_body_kb: u64, max_upload_kb: u64, max_text_field_kb: u64, max_fields: usize) -> Self {
+        let bytes = |kb: u64| usize::try_from(kb.saturating_mul(1024)).unwrap_or(usize::MAX);
+        Self {
+            max_body_bytes: bytes(max_body_kb),
+            max_file_bytes: max_upload_kb.saturating_mul(1024),
+            max_text_bytes: bytes(max_text_field_kb),
+            max_fields,
+        }
+    }
+
+    /// The body limit for every extractor, multipart or not.
+    pub fn body_limit(&self) -> DefaultBodyLimit {
+        DefaultBodyLimit::max(self.max_body_bytes)
+    }
+
+    fn error(&self, e: MultipartError) -> ApiError {
+        if e.status() == StatusCode::PAYLOAD_TOO_LARGE {
+            ApiError::PayloadTooLarge(format!("request body is larger than {} KiB", self.max_body_bytes / 1024))
+        } else {
+            ApiError::BadRequest(format!("multipart error: {e}"))
+        }
+    }
+}
+
+/// A `multipart/form-data` body read within `MultipartLimits`.
+pub struct LimitedMultipart {
+    inner:  Multipart,
+    limits: MultipartLimits,
+    fields: usize,
+}
+
+impl<S: Send + Sync> FromRequest<S> for LimitedMultipart {
+    type Rejection = ApiError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let limits = req.extensions().get::<MultipartLimits>().copied().unwrap_or_default();
+        let inner = Multipart::from_request(req, state)
+            .await
+            .map_err(|e| ApiError::BadRequest(e.body_text()))?;
+        Ok(Self { inner, limits, fields: 0 })
+    }
+}
+
+impl LimitedMultipart {
+    /// The next field, or `None` at the end of the body.
+    pub async fn next_field(&mut self) -> Result<Option<LimitedField<'_>>, ApiError> {
+        let limits = self.limits;
+        let Some(field) = self.inner.next_field().await.map_err(|e| limits.error(e))? else {
+            return Ok(None);
+        };
+        self.fields += 1;
+        if self.fields > limits.max_fields {
+            return Err(ApiError::PayloadTooLarge(format!("too many form fields (at most {})", limits.max_fields)));
+        }
+        Ok(Some(LimitedField { inner: field, limits }))
+    }
+}
+
+/// One field of a `LimitedMultipart`.
+pub struct LimitedField<'a> {
+    inner:  Field<'a>,
+    limits: MultipartLimits,
+}
+
+impl LimitedField<'_> {
+    /// The field's name.
+    pub fn name(&self) -> Option<&str> {
+        self.inner.name()
+    }
+
+    /// The file name of a file part.
+    pub fn file_name(&self) -> Option<&str> {
+        self.inner.file_name()
+    }
+
+    /// The part's `Content-Type`.
+    pub fn content_type(&self) -> Option<&str> {
+        self.inner.content_type()
+    }
+
+    /// The largest file part accepted, in bytes.
+    pub fn max_file_bytes(&self) -> u64 {
+        self.limits.max_file_bytes
+    }
+
+    /// The next chunk of the field's data; only the body limit applies.
+    pub async fn chunk(&mut self) -> Result<Option<Bytes>, ApiError> {
+        let limits = self.limits;
+        self.inner.chunk().await.map_err(|e| limits.error(e))
+    }
+
+    /// The field as text, refused past the text field limit.
+    pub async fn text(mut self) -> Result<String, ApiError> {
+        let max = self.limits.max_text_bytes;
+        let mut buf = BytesMut::new();
+        while let Some(chunk) = self.chunk().await? {
+            if buf.len() + chunk.len() > max {
+                let name = self.name().unwrap_or_default();
+                return Err(ApiError::PayloadTooLarge(format!("form field `{name}` is longer than {} KiB", max / 1024)));
+            }
+            buf.extend_from_slice(&chunk);
+        }
+        Ok(String::from_utf8_lossy(&buf).into_owned())
+    }
+
+    /// The field's data, refused past `max_bytes` or the file limit,
+    /// whichever is lower.
+    pub async fn bytes(mut self, max_bytes: u64) -> Result<Bytes, ApiError> {
+        let max = max_bytes.min(self.limits.max_file_bytes);
+        let mut buf = BytesMut::new();
+        while let Some(chunk) = self.chunk().await? {
+            let size = (buf.len() + chunk.len()) as u64;
+            if size > max {
+                return Err(file_too_large(size, max));
+            }
+            buf.extend_from_slice(&chunk);
+        }
+        Ok(buf.freeze())
+    }
+}
+
+/// The error for a file part of at least `size` bytes over `max_bytes`.
+pub fn file_too_large(size: u64, max_bytes: u64) -> ApiError {
+    let kb = |bytes: u64| bytes.div_ceil(1024).min(u32::MAX as u64) as u32;
+    ApiError::from(DomainError::Validation(ValidationError::FileTooLarge {
+        size_kb: kb(size),
+        max_kb:  (max_bytes / 1024).min(u32::MAX as u64) as u32,
+    }))
+}
@@ -1,4 +1,5 @@
-//! Admin routes: user CRUD, board owner assignment, dashboard, board creation, announcements.
+//! Admin routes: user CRUD, board owner assignment, dashboard, board creation,
+//! announcements, config reload.
 
 use axum::{
     routing::{delete, get, post},
@@ -10,6 +11,7 @@ use domains::ports::{AuthProvider, StaffRequestRepository};
 use services::board::BoardRepo;
 use services::staff_request::StaffRequestService;
 use services::user::UserService;
+use storage_adapters::cache::{BoardConfigCache, SiteCache};
 
 use crate::axum::handlers::admin_handlers::{self, AdminDashboardState};
 
@@ -20,6 +22,7 @@ use crate::axum::handlers::admin_handlers::{self, AdminDashboardState};
 /// - **Dashboard state** (`AdminDashboardState`) — dashboard view, board creation, announcements
 /// - **Request service state** — approve / deny staff requests
 /// - **User service state** — user CRUD, board-owner assignment, audit log
+/// - **Board config and site caches** — config reload
 ///
 /// The `msg_service` is injected as an `axum::Extension` so the broadcast handler
 /// can reach it without adding a generic parameter to the primary state type.
@@ -28,6 +31,8 @@ pub fn admin_routes<UR, AP, BR, RR, MR>(
     board_service:   Arc<BR>,
     request_service: Arc<StaffRequestService<RR, UR>>,
     msg_service:     Arc<services::staff_message::StaffMessageService<MR>>,
+    board_configs:   Arc<BoardConfigCache>,
+    sites:           Arc<SiteCache>,
 ) -> Router
 where
     UR: domains::ports::UserRepository + 'static,
@@ -68,8 +73,15 @@ where
         .route("/admin/audit",                          get(admin_handlers::list_audit_log::<UR, AP>))
         .with_state(user_service);
 
+    // ── Routes that use BoardConfigCache and SiteCache ────────────────────────
+    let reload_router: Router = Router::new()
+        .route("/admin/reload", post(admin_handlers::reload_config))
+        .layer(axum::Extension(sites))
+        .with_state(board_configs);
+
     Router::new()
         .merge(dashboard_router)
         .merge(request_router)
         .merge(user_router)
+        .merge(reload_router)
 }
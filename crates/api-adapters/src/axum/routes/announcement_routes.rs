@@ -0,0 +1,35 @@
+//! Blotter routes.
+//!
+//! Routes (admin only):
+//! - `GET    /admin/blotter`      — list the site's entries
+//! - `POST   /admin/blotter`      — post an entry
+//! - `DELETE /admin/blotter/{id}` — delete an entry
+
+use axum::{routing::{delete, get}, Router};
+use std::sync::Arc;
+
+use crate::axum::handlers::announcement_handlers::{self, BlotterState};
+use domains::ports::AnnouncementRepository;
+use services::announcement::AnnouncementService;
+use services::board::BoardRepo;
+
+/// Mount the blotter routes.
+///
+/// Layer an `Extension<RenderCacheState>` on the router to have changes to a
+/// board's entries drop its cached pages.
+pub fn announcement_routes<AR, BR>(svc: Arc<AnnouncementService<AR>>, boards: Arc<BR>) -> Router
+where
+    AR: AnnouncementRepository + 'static,
+    BR: BoardRepo + 'static,
+{
+    let state = BlotterState { svc, boards };
+
+    Router::new()
+        .route(
+            "/admin/blotter",
+            get(announcement_handlers::list_announcements::<AR, BR>)
+                .post(announcement_handlers::post_announcement::<AR, BR>),
+        )
+        .route("/admin/blotter/{id}", delete(announcement_handlers::delete_announcement::<AR, BR>))
+        .with_state(state)
+}
@@ -0,0 +1,24 @@
+//! API key management routes.
+//!
+//! Routes:
+//! - `GET  /admin/api-keys`             — list keys (admin)
+//! - `POST /admin/api-keys`             — issue a key (admin)
+//! - `POST /admin/api-keys/{id}/revoke` — revoke a key (admin)
+
+use axum::{routing::{get, post}, Router};
+use std::sync::Arc;
+
+use crate::axum::handlers::api_key_handlers;
+use domains::ports::ApiKeyRepository;
+use services::api_key::ApiKeyService;
+
+/// Mount the API key management routes.
+pub fn api_key_routes<AK: ApiKeyRepository>(svc: Arc<ApiKeyService<AK>>) -> Router {
+    Router::new()
+        .route(
+            "/admin/api-keys",
+            get(api_key_handlers::list_api_keys::<AK>).post(api_key_handlers::create_api_key::<AK>),
+        )
+        .route("/admin/api-keys/{id}/revoke", post(api_key_handlers::revoke_api_key::<AK>))
+        .with_state(svc)
+}
@@ -1,4 +1,4 @@
-//! Authentication routes: `/auth/login`, `/auth/refresh`, `/auth/logout`.
+//! Authentication routes: `/auth/login`, `/auth/refresh`, `/auth/logout`, `/auth/totp`.
 
 use axum::{routing::{get, post}, Router};
 use std::sync::Arc;
@@ -16,6 +16,10 @@ use crate::axum::handlers::auth_handlers;
 /// `GET  /auth/logout`   — clear `token` cookie and redirect to overboard
 /// `GET  /auth/register` — render registration page (only when `open_registration` is true)
 /// `POST /auth/register` — create a new `Role::User` account
+/// `GET  /auth/totp`     — two-factor settings page (HTML)
+/// `POST /auth/totp/setup`   — start TOTP enrollment; returns the secret
+/// `POST /auth/totp/confirm` — enable TOTP with a first code; returns recovery codes
+/// `POST /auth/totp/disable` — disable TOTP with a code
 pub fn auth_routes<UR, AP>(
     user_service: Arc<UserService<UR, AP>>,
     open_registration: bool,
@@ -32,7 +36,11 @@ where
         )
         .route("/auth/refresh", post(auth_handlers::refresh_token::<UR, AP>))
         .route("/auth/logout",  get(auth_handlers::logout))
-        .route("/auth/me",      get(auth_handlers::me));
+        .route("/auth/me",      get(auth_handlers::me))
+        .route("/auth/totp",    get(auth_handlers::totp_page::<UR, AP>))
+        .route("/auth/totp/setup",   post(auth_handlers::totp_setup::<UR, AP>))
+        .route("/auth/totp/confirm", post(auth_handlers::totp_confirm::<UR, AP>))
+        .route("/auth/totp/disable", post(auth_handlers::totp_disable::<UR, AP>));
 
     if open_registration {
         router = router.route(
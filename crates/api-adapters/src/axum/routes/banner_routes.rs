@@ -0,0 +1,35 @@
+//! Board banner routes.
+//!
+//! Routes (all board-scoped):
+//! - `GET    /board/{slug}/banner`      — a random banner of the board
+//! - `GET    /board/{slug}/banners`     — list banners (board owners)
+//! - `POST   /board/{slug}/banners`     — upload a banner (board owners)
+//! - `DELETE /board/{slug}/banners/{id}` — remove a banner (board owners)
+
+use axum::{routing::{delete, get}, Router};
+use std::sync::Arc;
+
+use crate::axum::handlers::banner_handlers::{self, BannerState};
+use domains::ports::{BannerRepository, MediaStorage};
+use services::banner::BannerService;
+
+/// Mount the banner routes.
+///
+/// The board-config middleware must run before the handlers: every route
+/// acts on the board it resolves.
+pub fn banner_routes<BR, MS>(svc: Arc<BannerService<BR, MS>>) -> Router
+where
+    BR: BannerRepository + 'static,
+    MS: MediaStorage + 'static,
+{
+    let state = BannerState { svc };
+
+    Router::new()
+        .route("/board/{slug}/banner", get(banner_handlers::random_banner::<BR, MS>))
+        .route(
+            "/board/{slug}/banners",
+            get(banner_handlers::list_banners::<BR, MS>).post(banner_handlers::upload_banner::<BR, MS>),
+        )
+        .route("/board/{slug}/banners/{id}", delete(banner_handlers::remove_banner::<BR, MS>))
+        .with_state(state)
+}
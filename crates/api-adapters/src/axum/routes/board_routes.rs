@@ -48,6 +48,14 @@ where
         )
 }
 
+/// Static pages of archived threads — board-scoped, so they must be merged
+/// under `board_config_middleware`.
+pub fn archived_thread_routes(media: Arc<dyn domains::ports::MediaStorage>) -> Router {
+    Router::new()
+        .route("/board/{slug}/archive/{thread_id}", get(board_handlers::show_archived_thread))
+        .with_state(media)
+}
+
 /// Admin board management routes — require `Admin` role.
 pub fn board_admin_routes<BR: BoardRepo>(board_service: Arc<BR>) -> Router {
     Router::new()
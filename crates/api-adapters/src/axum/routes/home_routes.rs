@@ -0,0 +1,19 @@
+//! Homepage route: board directory and site activity.
+
+use axum::{routing::get, Router};
+use std::sync::Arc;
+
+use crate::axum::handlers::home_handlers::{show_home, HomeState, SiteActivitySource};
+use services::board::BoardRepo;
+
+/// `GET /` — board directory, recent posts and site stats.
+pub fn home_routes<BR, PR>(board_service: Arc<BR>, post_service: Arc<PR>) -> Router
+where
+    BR: BoardRepo + 'static,
+    PR: SiteActivitySource + 'static,
+{
+    let state = HomeState { board_service, post_service };
+    Router::new()
+        .route("/", get(show_home::<BR, PR>))
+        .with_state(state)
+}
@@ -0,0 +1,18 @@
+//! Media route: locally stored uploads.
+
+use axum::{routing::get, Router};
+use domains::ports::PostRepository;
+use std::path::PathBuf;
+use std::sync::Arc;
+use storage_adapters::media::cdn::MediaUrls;
+
+use crate::axum::handlers::media_handlers::{serve_media, MediaFiles};
+
+/// `GET /media/{*key}` — files under `root`, with caching and range support.
+/// Requests must carry a valid signature when `urls` signs them; `posts`
+/// supplies each original's stored content type.
+pub fn media_routes(root: PathBuf, urls: MediaUrls, posts: Arc<dyn PostRepository>) -> Router {
+    Router::new()
+        .route("/media/{*key}", get(serve_media))
+        .with_state(MediaFiles { root, urls, posts })
+}
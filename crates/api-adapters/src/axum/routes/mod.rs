@@ -1,11 +1,22 @@
 //! Axum route definitions.
 pub mod admin_routes;
+pub mod announcement_routes;
+pub mod api_key_routes;
 pub mod auth_routes;
+pub mod banner_routes;
 pub mod board_owner_routes;
 pub mod board_routes;
+pub mod home_routes;
+pub mod media_routes;
 pub mod moderation_routes;
+pub mod notice_routes;
 pub mod overboard_routes;
+pub mod page_routes;
+pub mod poll_routes;
 pub mod post_routes;
+pub mod reaction_routes;
 pub mod staff_message_routes;
+pub mod stats_routes;
 pub mod thread_routes;
 pub mod user_routes;
+pub mod watch_routes;
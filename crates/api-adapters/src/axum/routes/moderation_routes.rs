@@ -1,4 +1,5 @@
-//! Moderation routes: flags, bans, delete, sticky, close, cycle, pin.
+//! Moderation routes: flags, bans (IP, ASN, and upload hash), warnings, delete, edit, move, sticky,
+//! close, cycle, pin, the IP history, and the mod logs.
 
 use axum::{
     routing::{get, post},
@@ -25,7 +26,7 @@ where
     UR: domains::ports::UserRepository + 'static,
     BS: BoardRepo + 'static,
 {
-    // Dashboard routes need both mod_svc and board_svc.
+    // Dashboard routes, post moves and post histories need both mod_svc and board_svc.
     let dashboard_state = ModerationDashboardState {
         mod_svc:   mod_service.clone(),
         board_svc: board_service,
@@ -42,7 +43,7 @@ where
         .route(
             "/volunteer/dashboard",
             get(moderation_handlers::volunteer_dashboard::<BR, PR, TR, FR, AR, UR, BS>)
-                .with_state(dashboard_state),
+                .with_state(dashboard_state.clone()),
         )
         // ── Public board-scoped routes (no auth required) ──────────────────────
         .route(
@@ -54,12 +55,31 @@ where
         .route("/mod/flags/{id}/resolve", post(moderation_handlers::resolve_flag::<BR, PR, TR, FR, AR, UR>))
         // ── Post & thread management ───────────────────────────────────────────
         .route("/mod/posts/{id}/delete", post(moderation_handlers::delete_post::<BR, PR, TR, FR, AR, UR>))
+        .route("/mod/posts/{id}/edit", post(moderation_handlers::edit_post::<BR, PR, TR, FR, AR, UR>))
+        .route(
+            "/mod/posts/{id}/revisions",
+            get(moderation_handlers::post_revisions::<BR, PR, TR, FR, AR, UR, BS>).with_state(dashboard_state.clone()),
+        )
         .route("/mod/threads/{id}/delete", post(moderation_handlers::delete_thread::<BR, PR, TR, FR, AR, UR>))
         .route("/mod/threads/{id}/delete-by-ip", post(moderation_handlers::delete_posts_by_ip::<BR, PR, TR, FR, AR, UR>))
+        .route(
+            "/mod/boards/{id}/delete-by-ip",
+            post(moderation_handlers::delete_board_posts_by_ip::<BR, PR, TR, FR, AR, UR>),
+        )
+        .route(
+            "/mod/threads/{id}/move",
+            post(moderation_handlers::move_posts::<BR, PR, TR, FR, AR, UR, BS>).with_state(dashboard_state),
+        )
         .route("/mod/threads/{id}/sticky", post(moderation_handlers::toggle_sticky::<BR, PR, TR, FR, AR, UR>))
         .route("/mod/threads/{id}/close",  post(moderation_handlers::toggle_closed::<BR, PR, TR, FR, AR, UR>))
         .route("/mod/threads/{id}/cycle",  post(moderation_handlers::toggle_cycle::<BR, PR, TR, FR, AR, UR>))
         .route("/mod/posts/{id}/pin",      post(moderation_handlers::set_post_pinned::<BR, PR, TR, FR, AR, UR>))
+        // ── IP history ─────────────────────────────────────────────────────────
+        .route("/mod/ip-history", get(moderation_handlers::ip_history::<BR, PR, TR, FR, AR, UR>))
+        .route(
+            "/mod/ip-history/delete-all",
+            post(moderation_handlers::delete_ip_history::<BR, PR, TR, FR, AR, UR>),
+        )
         // ── Bans ───────────────────────────────────────────────────────────────
         .route(
             "/mod/bans",
@@ -67,6 +87,19 @@ where
                 .post(moderation_handlers::create_ban::<BR, PR, TR, FR, AR, UR>),
         )
         .route("/mod/bans/{id}/expire", post(moderation_handlers::expire_ban::<BR, PR, TR, FR, AR, UR>))
+        .route("/mod/warnings", post(moderation_handlers::create_warning::<BR, PR, TR, FR, AR, UR>))
+        .route(
+            "/mod/asn-bans",
+            get(moderation_handlers::list_asn_bans::<BR, PR, TR, FR, AR, UR>)
+                .post(moderation_handlers::create_asn_ban::<BR, PR, TR, FR, AR, UR>),
+        )
+        .route("/mod/asn-bans/{id}/expire", post(moderation_handlers::expire_asn_ban::<BR, PR, TR, FR, AR, UR>))
+        .route(
+            "/mod/hash-bans",
+            get(moderation_handlers::list_hash_bans::<BR, PR, TR, FR, AR, UR>)
+                .post(moderation_handlers::create_hash_ban::<BR, PR, TR, FR, AR, UR>),
+        )
+        .route("/mod/hash-bans/{id}/expire", post(moderation_handlers::expire_hash_ban::<BR, PR, TR, FR, AR, UR>))
         // ── Audit log pages ────────────────────────────────────────────────────
         .route(
             "/janitor/logs",
@@ -82,3 +115,23 @@ where
         )
         .with_state(mod_service)
 }
+
+/// Mount a board's public mod log (`GET /board/{slug}/log`).
+///
+/// The board-config middleware must run before the handler: the page is only
+/// served when the board's config has `public_log` set.
+pub fn board_log_routes<BR, PR, TR, FR, AR, UR>(
+    mod_service: Arc<ModerationService<BR, PR, TR, FR, AR, UR>>,
+) -> Router
+where
+    BR: domains::ports::BanRepository + 'static,
+    PR: domains::ports::PostRepository + 'static,
+    TR: domains::ports::ThreadRepository + 'static,
+    FR: domains::ports::FlagRepository + 'static,
+    AR: domains::ports::AuditRepository + 'static,
+    UR: domains::ports::UserRepository + 'static,
+{
+    Router::new()
+        .route("/board/{slug}/log", get(moderation_handlers::public_board_log::<BR, PR, TR, FR, AR, UR>))
+        .with_state(mod_service)
+}
@@ -0,0 +1,23 @@
+//! Site notice routes.
+//!
+//! Routes:
+//! - `GET /admin/notice` — the site's notice (admin)
+//! - `PUT /admin/notice` — set the notice and switch it on or off (admin)
+
+use axum::{routing::get, Router};
+use std::sync::Arc;
+
+use crate::axum::handlers::notice_handlers::{self, NoticeState};
+use domains::ports::SiteRepository;
+use services::site::SiteService;
+use storage_adapters::cache::SiteCache;
+
+/// Mount the notice management routes.
+pub fn notice_routes<SR>(svc: Arc<SiteService<SR>>, cache: Arc<SiteCache>) -> Router
+where
+    SR: SiteRepository + 'static,
+{
+    Router::new()
+        .route("/admin/notice", get(notice_handlers::get_notice).put(notice_handlers::set_notice::<SR>))
+        .with_state(NoticeState { svc, cache })
+}
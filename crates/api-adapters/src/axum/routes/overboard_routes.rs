@@ -1,12 +1,13 @@
-//! Overboard route: recent posts across all boards.
+//! Overboard routes: recent posts and recently bumped threads across all boards.
 
 use axum::{routing::get, Router};
 use std::sync::Arc;
 
 use crate::axum::handlers::overboard_handlers::{
-    OverboardPostSource, OverboardState, show_overboard,
+    list_all_json, show_all_html, AllThreadsState, OverboardPostSource, OverboardState, show_overboard,
 };
 use services::board::BoardRepo;
+use services::thread::ThreadRepo;
 
 /// `GET /overboard` — recent posts across all boards.
 pub fn overboard_routes<BR, PR>(board_service: Arc<BR>, post_service: Arc<PR>) -> Router
@@ -19,3 +20,18 @@ where
         .route("/overboard", get(show_overboard::<BR, PR>))
         .with_state(state)
 }
+
+/// `GET /all` (and `/all/`) — catalog of recently bumped threads across all
+/// boards; `GET /all.json` — the same as JSON.
+pub fn all_threads_routes<BR, TR>(board_service: Arc<BR>, thread_service: Arc<TR>) -> Router
+where
+    BR: BoardRepo + 'static,
+    TR: ThreadRepo,
+{
+    let state = AllThreadsState { board_service, thread_service };
+    Router::new()
+        .route("/all",      get(show_all_html::<BR, TR>))
+        .route("/all/",     get(show_all_html::<BR, TR>))
+        .route("/all.json", get(list_all_json::<BR, TR>))
+        .with_state(state)
+}
@@ -0,0 +1,54 @@
+//! Static page routes.
+//!
+//! Routes:
+//! - `GET    /{page}`              — a site page
+//! - `GET    /board/{slug}/{page}` — a board page (board-scoped)
+//! - `GET    /admin/pages`         — list the site's pages (admin)
+//! - `POST   /admin/pages`         — create a page (admin)
+//! - `PUT    /admin/pages/{id}`    — update a page (admin)
+//! - `DELETE /admin/pages/{id}`    — delete a page (admin)
+
+use axum::{routing::{get, put}, Router};
+use std::sync::Arc;
+
+use crate::axum::handlers::page_handlers::{self, PageAdminState};
+use domains::ports::StaticPageRepository;
+use services::board::BoardRepo;
+use services::page::PageService;
+
+/// Mount the site page and page management routes.
+pub fn page_routes<PR, BR>(svc: Arc<PageService<PR>>, boards: Arc<BR>) -> Router
+where
+    PR: StaticPageRepository + 'static,
+    BR: BoardRepo + 'static,
+{
+    let site_pages: Router = Router::new()
+        .route("/{page}", get(page_handlers::show_site_page::<PR>))
+        .with_state(svc.clone());
+
+    let admin: Router = Router::new()
+        .route(
+            "/admin/pages",
+            get(page_handlers::list_pages::<PR, BR>).post(page_handlers::create_page::<PR, BR>),
+        )
+        .route(
+            "/admin/pages/{id}",
+            put(page_handlers::update_page::<PR, BR>).delete(page_handlers::delete_page::<PR, BR>),
+        )
+        .with_state(PageAdminState { svc, boards });
+
+    site_pages.merge(admin)
+}
+
+/// Mount the board page route.
+///
+/// The board-config middleware must run before the handler: pages are looked
+/// up on the board it resolves.
+pub fn board_page_routes<PR>(svc: Arc<PageService<PR>>) -> Router
+where
+    PR: StaticPageRepository + 'static,
+{
+    Router::new()
+        .route("/board/{slug}/{page}", get(page_handlers::show_board_page::<PR>))
+        .with_state(svc)
+}
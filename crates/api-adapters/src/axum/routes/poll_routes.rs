@@ -0,0 +1,30 @@
+//! Thread poll routes — nested under `/board/{slug}`.
+//!
+//! Routes:
+//! - `GET  /board/{slug}/thread/{id}/poll`      — current results (JSON)
+//! - `POST /board/{slug}/thread/{id}/poll/vote` — cast a vote
+
+use axum::{
+    routing::{get, post},
+    Router,
+};
+use std::sync::Arc;
+
+use crate::axum::handlers::poll_handlers::{self, PollState};
+use services::poll::PollService;
+
+/// Mount the poll routes under a shared `PollState`.
+///
+/// The board-config middleware must run before these handlers: voting checks
+/// the board's `polls_enabled`.
+pub fn poll_routes<PR>(svc: Arc<PollService<PR>>) -> Router
+where
+    PR: domains::ports::PollRepository + 'static,
+{
+    let state = PollState { svc };
+
+    Router::new()
+        .route("/board/{slug}/thread/{id}/poll",      get(poll_handlers::poll_results::<PR>))
+        .route("/board/{slug}/thread/{id}/poll/vote", post(poll_handlers::vote::<PR>))
+        .with_state(state)
+}
@@ -1,15 +1,18 @@
-//! Post routes: create post/thread.
+//! Post routes: create post/thread, delete own post, ban status, warning acknowledgement.
 
-use axum::{routing::post, Router};
+use axum::{routing::{get, post}, Router};
 use std::sync::Arc;
 
 use services::post::PostService;
 
 use crate::axum::handlers::post_handlers;
 
-/// Post creation route.
+/// Post creation, deletion, ban status and warning routes.
 ///
-/// `POST /board/{slug}/post` — create a post or new thread (multipart)
+/// `POST /board/{slug}/post`  — create a post or new thread (multipart)
+/// `POST /board/{slug}/post/{id}/delete` — delete the visitor's own post
+/// `GET  /banned`             — the visitor's active ban and pending warning, if any
+/// `POST /warning/{id}/ack`   — acknowledge the visitor's pending warning
 ///
 /// The board-config middleware must inject `ExtractedBoardConfig` before these handlers run.
 pub fn post_routes<PR, TR, BR, MS, RL, MP>(
@@ -28,5 +31,11 @@ where
             "/board/{slug}/post",
             post(post_handlers::create_post::<PR, TR, BR, MS, RL, MP>),
         )
+        .route(
+            "/board/{slug}/post/{id}/delete",
+            post(post_handlers::delete_own_post::<PR, TR, BR, MS, RL, MP>),
+        )
+        .route("/banned", get(post_handlers::ban_status::<PR, TR, BR, MS, RL, MP>))
+        .route("/warning/{id}/ack", post(post_handlers::acknowledge_warning::<PR, TR, BR, MS, RL, MP>))
         .with_state(post_service)
 }
@@ -0,0 +1,29 @@
+//! Post reaction route — nested under `/board/{slug}`.
+//!
+//! Routes:
+//! - `POST /board/{slug}/post/{post_id}/react` — react to a post
+
+use axum::{routing::post, Router};
+use std::sync::Arc;
+
+use crate::axum::handlers::reaction_handlers::{self, ReactionState};
+use domains::ports::{PostRepository, RateLimiter, ReactionRepository, ThreadRepository};
+use services::reaction::ReactionService;
+
+/// Mount the reaction route under a shared `ReactionState`.
+///
+/// The board-config middleware must run before the handler: reacting checks
+/// the board's `reactions_enabled` and that the post is on that board.
+pub fn reaction_routes<RR, PR, TR, RL>(svc: Arc<ReactionService<RR, PR, TR, RL>>) -> Router
+where
+    RR: ReactionRepository + 'static,
+    PR: PostRepository + 'static,
+    TR: ThreadRepository + 'static,
+    RL: RateLimiter + 'static,
+{
+    let state = ReactionState { svc };
+
+    Router::new()
+        .route("/board/{slug}/post/{post_id}/react", post(reaction_handlers::react::<RR, PR, TR, RL>))
+        .with_state(state)
+}
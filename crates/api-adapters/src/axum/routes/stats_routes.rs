@@ -0,0 +1,37 @@
+//! Board statistics routes.
+//!
+//! Routes:
+//! - `GET /board/{slug}/stats` — a board's stats page (board-scoped)
+//! - `GET /admin/stats`        — daily activity of the site's boards (admin)
+
+use axum::{routing::get, Router};
+use std::sync::Arc;
+
+use crate::axum::handlers::stats_handlers::{self, StatsAdminState};
+use domains::ports::StatsRepository;
+use services::board::BoardRepo;
+use services::stats::StatsService;
+
+/// Mount the admin overview route.
+pub fn stats_routes<SR, BR>(svc: Arc<StatsService<SR>>, boards: Arc<BR>) -> Router
+where
+    SR: StatsRepository + 'static,
+    BR: BoardRepo + 'static,
+{
+    Router::new()
+        .route("/admin/stats", get(stats_handlers::site_stats_overview::<SR, BR>))
+        .with_state(StatsAdminState { svc, boards })
+}
+
+/// Mount the board stats page route.
+///
+/// The board-config middleware must run before the handler: the page shows
+/// the board it resolves.
+pub fn board_stats_routes<SR>(svc: Arc<StatsService<SR>>) -> Router
+where
+    SR: StatsRepository + 'static,
+{
+    Router::new()
+        .route("/board/{slug}/stats", get(stats_handlers::show_board_stats::<SR>))
+        .with_state(svc)
+}
@@ -0,0 +1,43 @@
+//! Thread watcher routes.
+//!
+//! Routes:
+//! - `POST /board/{slug}/thread/{id}/watch`   — watch a thread (board-scoped)
+//! - `POST /board/{slug}/thread/{id}/unwatch` — stop watching it (board-scoped)
+//! - `GET  /watched`                          — the viewer's watch list
+
+use axum::{routing::{get, post}, Router};
+use std::sync::Arc;
+
+use crate::axum::handlers::watch_handlers::{self, WatchState};
+use domains::ports::{ThreadRepository, WatchRepository};
+use services::watch::WatchService;
+
+/// Mount the watch and unwatch routes.
+///
+/// The board-config middleware must run before the handlers: watching checks
+/// that the thread is on the board.
+pub fn watch_routes<WR, TR>(svc: Arc<WatchService<WR, TR>>) -> Router
+where
+    WR: WatchRepository + 'static,
+    TR: ThreadRepository + 'static,
+{
+    let state = WatchState { svc };
+
+    Router::new()
+        .route("/board/{slug}/thread/{id}/watch",   post(watch_handlers::watch::<WR, TR>))
+        .route("/board/{slug}/thread/{id}/unwatch", post(watch_handlers::unwatch::<WR, TR>))
+        .with_state(state)
+}
+
+/// Mount `GET /watched`, which is not board-scoped.
+pub fn watch_list_routes<WR, TR>(svc: Arc<WatchService<WR, TR>>) -> Router
+where
+    WR: WatchRepository + 'static,
+    TR: ThreadRepository + 'static,
+{
+    let state = WatchState { svc };
+
+    Router::new()
+        .route("/watched", get(watch_handlers::show_watched::<WR, TR>))
+        .with_state(state)
+}
@@ -0,0 +1,199 @@
+//! Static pages for archived threads.
+//!
+//! `StaticArchiveRepository` wraps the real `ArchiveRepository`. After a
+//! thread is archived it renders the thread once — `thread.html` as an
+//! anonymous viewer sees it, deleted posts as tombstones, and a JSON
+//! document — and writes both into media storage under
+//! `MediaKey::archived_thread`. The thread's posts are deleted when it is
+//! pruned, so this is the only copy of its content.
+//!
+//! `GET /board/:slug/archive/:thread_id` (`board_handlers::show_archived_thread`)
+//! serves the stored file without touching the database.
+//!
+//! Rendering is best-effort: a failure is logged and the thread stays
+//! archived without a static page. The handler then redirects to the live
+//! thread URL, which is also the path for threads archived before this
+//! existed or brought in by `rb-import`.
+//!
+//! As an `EventSubscriber` it renders the page again on `PostEdited` and
+//! `PostsMoved`, so staff changes to a thread archived but not yet pruned
+//! reach the copy kept for good.
+//!
+//! Attachment files of an archived thread are not copied; once the thread is
+//! pruned they are collected by the orphaned-media sweep like any other.
+
+use std::sync::Arc;
+
+use askama::Template;
+use async_trait::async_trait;
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use domains::errors::DomainError;
+use domains::models::{Attachment, BoardId, DeletedBy, IpHash, MediaKey, Page, Paginated, PostId, Thread, Viewer};
+use domains::ports::{
+    ArchiveRepository, BoardRepository, DomainEvent, EventSubscriber, MediaStorage, PostRepository, ThreadRepository,
+};
+use serde::Serialize;
+use storage_adapters::media::cdn::MediaUrls;
+
+use crate::axum::handlers::thread_handlers::post_displays;
+use crate::axum::i18n::I18n;
+use crate::axum::templates::{PostDisplay, ThreadTemplate};
+
+/// `ArchiveRepository` decorator that also writes the static page of every
+/// archived thread.
+pub struct StaticArchiveRepository {
+    inner:   Arc<dyn ArchiveRepository>,
+    boards:  Arc<dyn BoardRepository>,
+    threads: Arc<dyn ThreadRepository>,
+    posts:   Arc<dyn PostRepository>,
+    media:   Arc<dyn MediaStorage>,
+}
+
+impl StaticArchiveRepository {
+    pub fn new(
+        inner: Arc<dyn ArchiveRepository>,
+        boards: Arc<dyn BoardRepository>,
+        threads: Arc<dyn ThreadRepository>,
+        posts: Arc<dyn PostRepository>,
+        media: Arc<dyn MediaStorage>,
+    ) -> Self {
+        Self { inner, boards, threads, posts, media }
+    }
+
+    /// Render `thread` and store its HTML and JSON pages.
+    async fn render(&self, thread: &Thread) -> Result<(), DomainError> {
+        let board = self.boards.find_by_id(thread.board_id).await?;
+        let config = self.boards.find_config(thread.board_id).await?;
+        let posts = self.posts.find_visible_by_thread(thread.id, &Viewer::Public).await?;
+        let post_ids: Vec<PostId> = posts.iter().map(|p| p.id).collect();
+        let attachments = self.posts.find_attachments_by_post_ids(&post_ids).await?;
+        // Poster IDs are derived from the IP hash above; the stored page is
+        // public for good, so the hash itself is left out.
+        let mut posts = post_displays(thread.id, posts, attachments, &rb_format::Options::for_board(&config));
+        for pd in &mut posts {
+            pd.post.ip_hash = IpHash::new("");
+            pd.ip_hash_short.clear();
+        }
+
+        let json = serde_json::to_vec(&ArchivedThreadJson {
+            thread: thread.clone(),
+            posts:  posts.iter().map(ArchivedPostJson::from).collect(),
+        })
+        .map_err(|e| DomainError::internal(format!("archive json error: {e}")))?;
+
+        // Archived threads are read-only: no reply form, no mod toolbar.
+        let i18n = I18n::new(config.default_locale);
+        let html = ThreadTemplate {
+            board,
+            thread: thread.clone(),
+            posts,
+            is_closed: true,
+            is_cycle: thread.cycle,
+            viewer_role: None,
+            config,
+            // Signed links would expire in a page kept for good; the files
+            // themselves go once the thread is pruned (see above).
+            media: MediaUrls::default(),
+            poll: None,
+            quote: None,
+            watched: false,
+            blotter: Vec::new(),
+            own_ip_hash: None,
+            i18n,
+        }
+        .render()
+        .map_err(|e| DomainError::internal(format!("archive render error: {e}")))?;
+
+        self.media
+            .store(&MediaKey::archived_thread(thread.board_id, thread.id, "json"), Bytes::from(json), "application/json")
+            .await?;
+        self.media
+            .store(&MediaKey::archived_thread(thread.board_id, thread.id, "html"), Bytes::from(html), "text/html; charset=utf-8")
+            .await
+    }
+}
+
+#[async_trait]
+impl ArchiveRepository for StaticArchiveRepository {
+    async fn archive_thread(&self, thread: &Thread) -> Result<(), DomainError> {
+        self.inner.archive_thread(thread).await?;
+        if let Err(e) = self.render(thread).await {
+            tracing::warn!(thread_id = %thread.id.0, error = %e, "failed to write static archive page");
+        }
+        Ok(())
+    }
+
+    async fn find_archived(&self, board_id: BoardId, page: Page) -> Result<Paginated<Thread>, DomainError> {
+        self.inner.find_archived(board_id, page).await
+    }
+}
+
+#[async_trait]
+impl EventSubscriber for StaticArchiveRepository {
+    fn name(&self) -> &str {
+        "static-archive"
+    }
+
+    /// Re-render the page of an archived thread when one of its posts is
+    /// edited or posts move in or out. Live threads have no page and are
+    /// left alone.
+    async fn handle(&self, event: &DomainEvent) -> Result<(), DomainError> {
+        let (board_id, threads) = match event {
+            DomainEvent::PostEdited { board_id, post } => (*board_id, vec![post.thread_id]),
+            DomainEvent::PostsMoved { board_id, from, to, .. } => (*board_id, vec![*from, *to]),
+            _ => return Ok(()),
+        };
+        for thread_id in threads {
+            if self.media.exists(&MediaKey::archived_thread(board_id, thread_id, "html")).await? {
+                self.render(&self.threads.find_by_id(thread_id).await?).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Body of `archive/{board}/{thread}.json`.
+#[derive(Serialize)]
+struct ArchivedThreadJson {
+    thread: Thread,
+    posts:  Vec<ArchivedPostJson>,
+}
+
+/// A post as published in the archive: the public fields only, with the
+/// per-thread poster ID in place of the IP hash.
+#[derive(Serialize)]
+struct ArchivedPostJson {
+    id:          PostId,
+    post_number: u64,
+    name:        Option<String>,
+    tripcode:    Option<String>,
+    poster_id:   String,
+    capcode:     Option<String>,
+    body:        String,
+    created_at:  DateTime<Utc>,
+    attachments: Vec<Attachment>,
+    /// Who deleted the post, for a tombstone.
+    deleted:     Option<DeletedBy>,
+    /// When staff last edited the post.
+    edited_at:   Option<DateTime<Utc>>,
+}
+
+impl From<&PostDisplay> for ArchivedPostJson {
+    fn from(pd: &PostDisplay) -> Self {
+        Self {
+            id:          pd.post.id,
+            post_number: pd.post.post_number,
+            name:        pd.post.name.clone(),
+            tripcode:    pd.post.tripcode.clone(),
+            // A tombstone does not tie the post to its poster's others.
+            poster_id:   if pd.post.is_deleted() { String::new() } else { pd.poster_id.clone() },
+            capcode:     pd.capcode_role.clone(),
+            body:        pd.post.body.clone(),
+            created_at:  pd.post.created_at,
+            attachments: pd.attachments.clone(),
+            deleted:     pd.post.deleted.map(|t| t.by),
+            edited_at:   pd.post.edited_at,
+        }
+    }
+}
@@ -9,10 +9,13 @@
 
 use askama::Template;
 use axum::{
-    http::StatusCode,
+    http::{header, StatusCode},
     response::{Html, IntoResponse, Response},
 };
-use domains::models::{Board, BoardConfig, OverboardPost, Post, Thread, ThreadSummary, User};
+use domains::models::{Board, BoardConfig, OverboardPost, OverboardThread, Post, Theme, Thread, ThreadSummary, User};
+use storage_adapters::media::cdn::MediaUrls;
+
+use crate::axum::i18n::I18n;
 
 /// A post bundled with its per-thread poster ID badge for template rendering.
 ///
@@ -38,12 +41,40 @@ pub struct PostDisplay {
     pub tripcode_level: Option<&'static str>,
     /// First 10 characters of the IP hash for truncated mod display.
     pub ip_hash_short: String,
+    /// The body rendered by `rb_format` under the board's formatting toggles.
+    pub body_html: String,
+    /// Reaction counts, in `Reaction::ALL` order, without zero counts.
+    pub reactions: Vec<domains::models::ReactionCount>,
+}
+
+impl PostDisplay {
+    /// Every `Reaction` in display order with its count on this post, zero
+    /// counts included — the reaction buttons under the post.
+    pub fn reaction_row(&self) -> Vec<domains::models::ReactionCount> {
+        domains::models::Reaction::ALL
+            .into_iter()
+            .map(|reaction| domains::models::ReactionCount {
+                reaction,
+                count: self.reactions.iter().find(|c| c.reaction == reaction).map_or(0, |c| c.count),
+            })
+            .collect()
+    }
+
+    /// The UI string shown in place of a tombstone, or above it for staff.
+    pub fn tombstone_message(&self) -> Option<&'static str> {
+        self.post.deleted.map(|tombstone| match tombstone.by {
+            domains::models::DeletedBy::User  => "post-deleted-user",
+            domains::models::DeletedBy::Staff => "post-deleted-staff",
+        })
+    }
 }
 
 /// Render a template to an HTML response, returning 500 on render failure.
+///
+/// Pages are translated for the request's `Accept-Language` (see `i18n`).
 fn render_template(tmpl: impl Template) -> Response {
     match tmpl.render() {
-        Ok(html) => Html(html).into_response(),
+        Ok(html) => ([(header::VARY, "Accept-Language")], Html(html)).into_response(),
         Err(e) => {
             tracing::error!(error = %e, "template render error");
             StatusCode::INTERNAL_SERVER_ERROR.into_response()
@@ -51,6 +82,45 @@ fn render_template(tmpl: impl Template) -> Response {
     }
 }
 
+/// URL of the stylesheet for `theme`. Board-scoped templates fill `base.html`'s
+/// `stylesheet` block with it; the viewer's `theme` cookie still wins in the browser.
+pub fn theme_stylesheet(theme: &Theme) -> String {
+    match theme {
+        Theme::Futaba => "/static/css/style.css".to_owned(),
+        other => format!("/static/css/{other}.css"),
+    }
+}
+
+/// Width of a stats bar in percent: `value` relative to the series' `peak`.
+pub fn bar_percent(value: &u64, peak: &u64) -> u64 {
+    if *peak == 0 { 0 } else { value * 100 / peak }
+}
+
+/// Human-readable byte count: bytes below 1 KB, whole KB below 1 MB, else MB
+/// or GB with one decimal.
+pub fn display_bytes(bytes: &u64) -> String {
+    const KB: u64 = 1024;
+    const MB: u64 = 1024 * KB;
+    const GB: u64 = 1024 * MB;
+    match *bytes {
+        n if n < KB => format!("{n} B"),
+        n if n < MB => format!("{} KB", n.div_ceil(KB)),
+        n if n < GB => format!("{:.1} MB", n as f64 / MB as f64),
+        n => format!("{:.1} GB", n as f64 / GB as f64),
+    }
+}
+
+/// Message ID of the catalog's label for `sort`.
+pub fn catalog_sort_label(sort: &domains::models::CatalogSort) -> &'static str {
+    use domains::models::CatalogSort;
+    match sort {
+        CatalogSort::Bump => "catalog-sort-bump",
+        CatalogSort::ReplyCount => "catalog-sort-reply-count",
+        CatalogSort::Creation => "catalog-sort-creation",
+        CatalogSort::Random => "catalog-sort-random",
+    }
+}
+
 /// Base layout values available to all templates.
 #[derive(Debug)]
 pub struct BaseContext {
@@ -65,28 +135,36 @@ pub struct BoardThreadDisplay {
     pub thread:         domains::models::ThreadSummary,
     pub poster_id:      String,
     pub tripcode_level: Option<&'static str>,
+    /// The OP body rendered by `rb_format`.
+    pub op_body_html:   String,
 }
 
 #[derive(Template)]
 #[template(path = "board.html")]
 pub struct BoardTemplate {
+    /// UI strings in the request's locale.
+    pub i18n: I18n,
     pub board:        Board,
     pub config:       BoardConfig,
     pub threads:      Vec<BoardThreadDisplay>,
     pub total_pages:  u32,
     pub current_page: u32,
+    /// Builds thumbnail URLs (CDN base, signatures).
+    pub media:        MediaUrls,
+    /// Blotter entries shown under the header, newest first.
+    pub blotter:      Vec<domains::models::Announcement>,
 }
 
 impl IntoResponse for BoardTemplate {
     fn into_response(self) -> Response { render_template(self) }
 }
 
-/// Template for the catalog view (`catalog.html`) — grid of thread thumbnails.
-
 /// Template for the board archive view (`archive.html`).
 #[derive(askama::Template)]
 #[template(path = "archive.html")]
 pub struct ArchiveTemplate {
+    /// UI strings in the request's locale.
+    pub i18n: I18n,
     /// The board whose archived threads are being displayed.
     pub board:        domains::models::Board,
     /// Archived threads, newest-archived first.
@@ -99,13 +177,22 @@ impl axum::response::IntoResponse for ArchiveTemplate {
     fn into_response(self) -> axum::response::Response { render_template(self) }
 }
 
+/// A search hit with its body rendered by `rb_format`.
+#[derive(Debug, Clone)]
+pub struct SearchResultDisplay {
+    pub post:      Post,
+    pub body_html: String,
+}
+
 /// Template for the search results page (`search_results.html`).
 #[derive(askama::Template)]
 #[template(path = "search_results.html")]
 pub struct SearchResultsTemplate {
+    /// UI strings in the request's locale.
+    pub i18n: I18n,
     pub board:        domains::models::Board,
     pub query:        String,
-    pub results:      Vec<domains::models::Post>,
+    pub results:      Vec<SearchResultDisplay>,
     pub total:        u64,
     pub current_page: u32,
     pub total_pages:  u32,
@@ -115,15 +202,33 @@ impl axum::response::IntoResponse for SearchResultsTemplate {
     fn into_response(self) -> axum::response::Response { render_template(self) }
 }
 
+/// A catalog entry with its OP excerpt rendered by `rb_format::snippet`.
+#[derive(Debug, Clone)]
+pub struct CatalogThreadDisplay {
+    pub thread:       ThreadSummary,
+    pub excerpt_html: String,
+}
+
+/// Template for the catalog view (`catalog.html`) — grid of thread thumbnails.
 #[derive(Template)]
 #[template(path = "catalog.html")]
 pub struct CatalogTemplate {
+    /// UI strings in the request's locale.
+    pub i18n: I18n,
     /// The board whose threads are being displayed in catalog format.
     pub board:   Board,
     /// Thread summaries (OP excerpt + thumbnail + reply count) for the grid.
-    pub threads: Vec<ThreadSummary>,
+    pub threads: Vec<CatalogThreadDisplay>,
     /// Board config needed to render the new-thread form correctly.
     pub config:  domains::models::BoardConfig,
+    /// Builds thumbnail URLs (CDN base, signatures).
+    pub media:   MediaUrls,
+    /// Order the threads are listed in.
+    pub sort:    domains::models::CatalogSort,
+    /// Text the threads were filtered by; empty when unfiltered.
+    pub filter:  String,
+    /// Blotter entries shown under the header, newest first.
+    pub blotter: Vec<domains::models::Announcement>,
 }
 
 impl IntoResponse for CatalogTemplate {
@@ -134,18 +239,133 @@ impl IntoResponse for CatalogTemplate {
 #[derive(Template)]
 #[template(path = "thread.html")]
 pub struct ThreadTemplate {
+    /// UI strings in the request's locale.
+    pub i18n: I18n,
     pub board:       Board,
     pub thread:      Thread,
     pub posts:       Vec<PostDisplay>,
     pub is_closed:   bool,
-    /// Whether the thread is in cycle mode (oldest unpinned reply pruned when full).
+    /// Whether the thread is in cycle mode (oldest unpinned replies pruned when full).
     pub is_cycle:    bool,
     pub viewer_role: Option<String>,
+    /// Board config — the reply form's file picker is limited to `allowed_mimes`.
+    pub config:      BoardConfig,
+    /// Builds attachment URLs (CDN base, signatures).
+    pub media:       MediaUrls,
+    /// The thread's poll with current results, if it has one.
+    pub poll:        Option<domains::models::Poll>,
+    /// Post number the reply box is pre-filled to quote (`?quote=N`).
+    pub quote:       Option<u64>,
+    /// Whether the thread is on the viewer's watch list.
+    pub watched:     bool,
+    /// Blotter entries shown under the header, newest first.
+    pub blotter:     Vec<domains::models::Announcement>,
+    /// The viewer's daily IP hash; their own posts get a delete button.
+    /// `None` for staff and the static archive.
+    pub own_ip_hash: Option<domains::models::IpHash>,
+}
+impl ThreadTemplate {
+    /// The OP's subject, used as the page title.
+    pub fn subject(&self) -> Option<&str> {
+        self.posts.first().and_then(|pd| pd.post.subject.as_deref())
+    }
+
+    /// Whether the viewer made `pd` today from their current address, so
+    /// they can delete it.
+    pub fn is_own(&self, pd: &PostDisplay) -> bool {
+        !pd.post.is_deleted() && self.own_ip_hash.as_ref() == Some(&pd.post.ip_hash)
+    }
 }
 impl IntoResponse for ThreadTemplate {
     fn into_response(self) -> Response { render_template(self) }
 }
 
+/// Template for the watch list (`watched.html`) — the viewer's watched
+/// threads with their unread reply counts.
+#[derive(Template)]
+#[template(path = "watched.html")]
+pub struct WatchedTemplate {
+    /// UI strings in the request's locale.
+    pub i18n:    I18n,
+    /// Watched threads, those with unread replies first.
+    pub threads: Vec<domains::models::WatchedThread>,
+}
+
+impl IntoResponse for WatchedTemplate {
+    fn into_response(self) -> Response { render_template(self) }
+}
+
+/// Template for an admin-edited static page (`page.html`).
+#[derive(Template)]
+#[template(path = "page.html")]
+pub struct PageTemplate {
+    /// UI strings in the request's locale.
+    pub i18n:      I18n,
+    pub page:      domains::models::StaticPage,
+    /// The board of a board page; `None` for a site page.
+    pub board:     Option<Board>,
+    /// The body rendered by `rb_format::page::render`.
+    pub body_html: String,
+}
+
+impl IntoResponse for PageTemplate {
+    fn into_response(self) -> Response { render_template(self) }
+}
+
+/// Template for a board's stats page (`board_stats.html`).
+#[derive(Template)]
+#[template(path = "board_stats.html")]
+pub struct BoardStatsTemplate {
+    /// UI strings in the request's locale.
+    pub i18n:   I18n,
+    pub board:  Board,
+    pub config: BoardConfig,
+    pub report: services::stats::BoardStatsReport,
+}
+
+impl BoardStatsTemplate {
+    /// Most posts in any of the last 24 hours; the hourly bars scale to it.
+    pub fn hourly_peak(&self) -> u64 {
+        self.report.hourly.iter().map(|p| p.posts).max().unwrap_or(0)
+    }
+
+    /// Most posts in any of the last 30 days; the daily bars scale to it.
+    pub fn daily_peak(&self) -> u64 {
+        self.report.daily.iter().map(|p| p.posts).max().unwrap_or(0)
+    }
+}
+
+impl IntoResponse for BoardStatsTemplate {
+    fn into_response(self) -> Response { render_template(self) }
+}
+
+/// Template for a board's public mod log (`board_log.html`).
+#[derive(Template)]
+#[template(path = "board_log.html")]
+pub struct BoardLogTemplate {
+    /// UI strings in the request's locale.
+    pub i18n:        I18n,
+    pub board:       Board,
+    pub config:      BoardConfig,
+    /// Redacted entries on this page, newest first.
+    pub entries:     Vec<domains::models::AuditEntry>,
+    /// Current page number (1-indexed).
+    pub page:        u32,
+    /// Total number of pages.
+    pub total_pages: u32,
+}
+
+impl BoardLogTemplate {
+    /// Localized label of a public log action (`log-action-*`).
+    pub fn action_label(&self, action: &domains::models::AuditAction) -> String {
+        self.i18n.t(&format!("log-action-{}", action.to_string().replace('_', "-")))
+    }
+}
+
+impl IntoResponse for BoardLogTemplate {
+    fn into_response(self) -> Response { render_template(self) }
+}
+
 /// An overboard post bundled with its media attachments for template rendering.
 #[derive(Debug, Clone)]
 pub struct OverboardPostDisplay {
@@ -159,12 +379,16 @@ pub struct OverboardPostDisplay {
     pub tripcode_level: Option<&'static str>,
     /// First 10 chars of ip_hash for truncated mod display.
     pub ip_hash_short: String,
+    /// The body rendered by `rb_format` under its board's formatting toggles.
+    pub body_html: String,
 }
 
 /// Template for the overboard view (`overboard.html`) — recent posts across all boards.
 #[derive(Template)]
 #[template(path = "overboard.html")]
 pub struct OverboardTemplate {
+    /// UI strings in the request's locale.
+    pub i18n: I18n,
     /// All boards, used to render the board list sidebar.
     pub boards:       Vec<Board>,
     /// Recent posts across all boards on the current page, with attachments.
@@ -173,24 +397,210 @@ pub struct OverboardTemplate {
     pub current_page: u32,
     /// Total number of pages in the recent-posts feed.
     pub total_pages:  u32,
+    /// Builds attachment URLs (CDN base, signatures).
+    pub media:        MediaUrls,
 }
 
 impl IntoResponse for OverboardTemplate {
     fn into_response(self) -> Response { render_template(self) }
 }
 
+/// A thread of the `/all` catalog with its OP excerpt rendered by
+/// `rb_format::snippet` under its board's formatting toggles.
+#[derive(Debug, Clone)]
+pub struct OverboardThreadDisplay {
+    pub thread:       OverboardThread,
+    pub excerpt_html: String,
+}
+
+/// Template for the overboard catalog (`all.html`) — recently bumped threads
+/// across all boards.
+#[derive(Template)]
+#[template(path = "all.html")]
+pub struct AllThreadsTemplate {
+    /// UI strings in the request's locale.
+    pub i18n: I18n,
+    /// All boards, for the navigation header.
+    pub boards:       Vec<Board>,
+    /// Threads on the current page, most recently bumped first.
+    pub threads:      Vec<OverboardThreadDisplay>,
+    /// The page number currently being rendered (1-indexed).
+    pub current_page: u32,
+    /// Total number of pages.
+    pub total_pages:  u32,
+    /// Builds thumbnail URLs (CDN base, signatures).
+    pub media:        MediaUrls,
+}
+
+impl IntoResponse for AllThreadsTemplate {
+    fn into_response(self) -> Response { render_template(self) }
+}
+
+/// A board in the homepage directory.
+#[derive(Debug, Clone)]
+pub struct HomeBoardDisplay {
+    /// The board.
+    pub board: Board,
+    /// Whether the board is marked NSFW.
+    pub nsfw:  bool,
+}
+
+/// One heading of the homepage directory.
+#[derive(Debug, Clone)]
+pub struct HomeCategory {
+    /// The boards' `category`; empty for boards without one.
+    pub name:   String,
+    /// Boards in this category, by slug.
+    pub boards: Vec<HomeBoardDisplay>,
+}
+
+/// A recent post on the homepage.
+#[derive(Debug, Clone)]
+pub struct HomePostDisplay {
+    /// The post with its board's slug.
+    pub post:         OverboardPost,
+    /// Start of the body, rendered under its board's formatting toggles.
+    pub excerpt_html: String,
+}
+
+/// Template for the homepage (`home.html`) — board directory, recent posts and site stats.
+#[derive(Template)]
+#[template(path = "home.html")]
+pub struct HomeTemplate {
+    /// UI strings in the request's locale.
+    pub i18n: I18n,
+    /// The site's display name.
+    pub site_name:    String,
+    /// The site's boards grouped by category, named categories first.
+    pub categories:   Vec<HomeCategory>,
+    /// Number of boards on the site.
+    pub board_count:  usize,
+    /// The latest posts across the site's boards.
+    pub recent_posts: Vec<HomePostDisplay>,
+    /// Threads on the site.
+    pub total_threads:  u64,
+    /// Posts on the site.
+    pub total_posts:    u64,
+    /// Posts made in the last 24 hours.
+    pub posts_last_day: u64,
+}
+
+impl IntoResponse for HomeTemplate {
+    fn into_response(self) -> Response { render_template(self) }
+}
+
 /// Template for the login page (`login.html`).
 #[derive(Template)]
 #[template(path = "login.html")]
 pub struct LoginTemplate {
+    /// UI strings in the request's locale.
+    pub i18n: I18n,
     /// An optional error message to display above the login form (e.g. "Invalid credentials").
     pub error: Option<String>,
+    /// Single sign-on button, when an SSO plugin is configured.
+    pub sso: Option<crate::axum::handlers::auth_handlers::SsoLogin>,
 }
 
 impl IntoResponse for LoginTemplate {
     fn into_response(self) -> Response { render_template(self) }
 }
 
+/// Template for the two-factor settings page (`totp.html`).
+#[derive(Template)]
+#[template(path = "totp.html")]
+pub struct TotpTemplate {
+    /// UI strings in the request's locale.
+    pub i18n: I18n,
+    /// Whether the account has two-factor auth enabled.
+    pub enabled: bool,
+}
+
+impl IntoResponse for TotpTemplate {
+    fn into_response(self) -> Response { render_template(self) }
+}
+
+/// Template for the ban notice (`banned.html`).
+///
+/// Rendered with `403 Forbidden` when a browser post is rejected by a ban, and
+/// with `200 OK` from `GET /banned` so visitors can check their status.
+#[derive(Template)]
+#[template(path = "banned.html")]
+pub struct BannedTemplate {
+    /// UI strings in the request's locale.
+    pub i18n: I18n,
+    /// `false` renders the "you are not banned" variant.
+    pub banned:     bool,
+    /// The ban reason entered by the moderator.
+    pub reason:     String,
+    /// When the ban was issued. `None` for automatic blocks (e.g. DNSBL).
+    pub issued_at:  Option<chrono::DateTime<chrono::Utc>>,
+    /// When the ban expires. `None` = permanent.
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Human-readable scope of the ban, e.g. "all boards", in the page's locale.
+    pub boards:     String,
+}
+
+impl BannedTemplate {
+    /// Build the ban notice from an `ApiError::Banned`. Returns `None` for any other error.
+    pub fn from_error(err: &crate::common::errors::ApiError, i18n: I18n) -> Option<Self> {
+        match err {
+            crate::common::errors::ApiError::Banned { reason, issued_at, expires_at } => Some(Self {
+                banned:     true,
+                reason:     reason.clone(),
+                issued_at:  *issued_at,
+                expires_at: *expires_at,
+                boards:     i18n.t("ban-scope-all"),
+                i18n,
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl IntoResponse for BannedTemplate {
+    fn into_response(self) -> Response {
+        let status = if self.banned { StatusCode::FORBIDDEN } else { StatusCode::OK };
+        (status, render_template(self)).into_response()
+    }
+}
+
+/// Template for the 403/404/429/500 pages (`error.html`).
+///
+/// Rendered by `error_page_middleware` in place of the JSON or empty body of
+/// an error response to a browser.
+#[derive(Template)]
+#[template(path = "error.html")]
+pub struct ErrorTemplate {
+    /// UI strings in the request's locale.
+    pub i18n: I18n,
+    /// The response status code.
+    pub status:  u16,
+    /// Short name of the error, e.g. "Not Found".
+    pub heading: String,
+    /// One sentence on what happened and what to do.
+    pub message: String,
+}
+
+impl ErrorTemplate {
+    /// The page for `status`, or `None` for statuses without one. `retry_after_secs`
+    /// fills in the 429 message.
+    pub fn for_status(status: StatusCode, retry_after_secs: u32, i18n: I18n) -> Option<Self> {
+        let message = match status {
+            StatusCode::FORBIDDEN => i18n.t("error-403"),
+            StatusCode::NOT_FOUND => i18n.t("error-404"),
+            StatusCode::TOO_MANY_REQUESTS => i18n.count("error-429", retry_after_secs),
+            StatusCode::INTERNAL_SERVER_ERROR => i18n.t("error-500"),
+            _ => return None,
+        };
+        Some(Self {
+            status: status.as_u16(),
+            heading: i18n.t(&format!("error-{}-heading", status.as_u16())),
+            message,
+            i18n,
+        })
+    }
+}
+
 // ─── Unified Dashboard ────────────────────────────────────────────────────────
 //
 // All roles share one template (`dashboard.html`) and one context struct.
@@ -206,6 +616,9 @@ pub struct DashboardBoard {
     pub can_manage: bool,
     /// Show volunteer management controls on the per-board dashboard.
     pub can_manage_volunteers: bool,
+    /// Stored media against the board's quota, e.g. `"1.2 GB of 5.0 GB"`.
+    /// Only filled in on the admin dashboard.
+    pub media_usage: Option<String>,
 }
 
 /// A user row in the dashboard Staff table.
@@ -226,6 +639,8 @@ pub struct DashboardUser {
 #[derive(Template)]
 #[template(path = "dashboard.html")]
 pub struct DashboardTemplate {
+    /// UI strings in the request's locale.
+    pub i18n: I18n,
     // ── Header ────────────────────────────────────────────────────────────────
     /// Display name for the current user's role: "Admin", "Janitor", etc.
     pub role_display: &'static str,
@@ -271,6 +686,8 @@ impl IntoResponse for DashboardTemplate {
 #[derive(Template)]
 #[template(path = "mod_flags.html")]
 pub struct FlagsPageTemplate {
+    /// UI strings in the request's locale.
+    pub i18n: I18n,
     /// Pending flags to display on this page.
     pub flags:       Vec<domains::models::Flag>,
     /// Current page number (1-indexed).
@@ -287,6 +704,8 @@ impl IntoResponse for FlagsPageTemplate {
 #[derive(Template)]
 #[template(path = "mod_bans.html")]
 pub struct BansPageTemplate {
+    /// UI strings in the request's locale.
+    pub i18n: I18n,
     /// Active bans to display on this page.
     pub bans:        Vec<domains::models::Ban>,
     /// Current page number (1-indexed).
@@ -299,6 +718,55 @@ impl IntoResponse for BansPageTemplate {
     fn into_response(self) -> Response { render_template(self) }
 }
 
+/// Template for the mod IP history page (`mod_ip_history.html`).
+#[derive(Template)]
+#[template(path = "mod_ip_history.html")]
+pub struct IpHistoryPageTemplate {
+    /// UI strings in the request's locale.
+    pub i18n: I18n,
+    /// The IP address or IP hash looked up; empty before a lookup.
+    pub ip:          String,
+    /// Days a raw IP address is matched over.
+    pub days:        u32,
+    /// The posts on this page, newest first.
+    pub posts:       Vec<domains::models::OverboardPost>,
+    /// Posts from the IP across all pages.
+    pub total:       u64,
+    /// Current page number (1-indexed).
+    pub page:        u32,
+    /// Total number of pages.
+    pub total_pages: u32,
+}
+
+impl IpHistoryPageTemplate {
+    /// The leading characters of an IP hash, as in the thread view.
+    pub fn short_hash<'a>(&self, hash: &'a domains::models::IpHash) -> &'a str {
+        hash.as_str().get(..10).unwrap_or(hash.as_str())
+    }
+}
+
+impl IntoResponse for IpHistoryPageTemplate {
+    fn into_response(self) -> Response { render_template(self) }
+}
+
+/// Template for a post's edit history (`mod_post_revisions.html`).
+#[derive(Template)]
+#[template(path = "mod_post_revisions.html")]
+pub struct PostRevisionsPageTemplate {
+    /// UI strings in the request's locale.
+    pub i18n: I18n,
+    /// The post as it reads now.
+    pub post:       domains::models::Post,
+    /// The slug of the post's board, for the link back to the thread.
+    pub board_slug: String,
+    /// The text each edit replaced, newest first.
+    pub revisions:  Vec<domains::models::PostRevision>,
+}
+
+impl IntoResponse for PostRevisionsPageTemplate {
+    fn into_response(self) -> Response { render_template(self) }
+}
+
 /// Template for the board owner per-board dashboard (`board_owner_dashboard.html`).
 ///
 /// This is NOT the unified dashboard — it is the deep-dive config surface for a
@@ -306,6 +774,8 @@ impl IntoResponse for BansPageTemplate {
 #[derive(Template)]
 #[template(path = "board_owner_dashboard.html")]
 pub struct BoardOwnerDashboardTemplate {
+    /// UI strings in the request's locale.
+    pub i18n: I18n,
     /// The board this owner is managing.
     pub board:  Board,
     /// Current runtime configuration for the board.
@@ -320,6 +790,8 @@ impl IntoResponse for BoardOwnerDashboardTemplate {
 #[derive(Template)]
 #[template(path = "register.html")]
 pub struct RegisterTemplate {
+    /// UI strings in the request's locale.
+    pub i18n: I18n,
     /// An optional error message to display above the form.
     pub error: Option<String>,
 }
@@ -332,6 +804,8 @@ impl IntoResponse for RegisterTemplate {
 #[derive(Template)]
 #[template(path = "user_dashboard.html")]
 pub struct UserDashboardTemplate {
+    /// UI strings in the request's locale.
+    pub i18n: I18n,
     /// Display name of the authenticated user.
     pub username:        String,
     /// When the account was created (formatted for display).
@@ -353,6 +827,8 @@ impl IntoResponse for UserDashboardTemplate {
 #[derive(askama::Template)]
 #[template(path = "audit_log.html")]
 pub struct AuditLogTemplate {
+    /// UI strings in the request's locale.
+    pub i18n: I18n,
     /// Heading label, e.g. "Janitor" or "Board Owner".
     pub role_label:  String,
     /// Paginated audit entries for this page.
@@ -371,6 +847,8 @@ impl axum::response::IntoResponse for AuditLogTemplate {
 #[derive(askama::Template)]
 #[template(path = "staff_inbox.html")]
 pub struct StaffInboxTemplate {
+    /// UI strings in the request's locale.
+    pub i18n: I18n,
     pub messages:     Vec<domains::models::StaffMessage>,
     pub current_page: u32,
     pub total_pages:  u32,
@@ -387,6 +865,8 @@ impl axum::response::IntoResponse for StaffInboxTemplate {
 #[derive(askama::Template)]
 #[template(path = "staff_compose.html")]
 pub struct StaffComposeTemplate {
+    /// UI strings in the request's locale.
+    pub i18n: I18n,
     /// Pre-filled recipient UUID (from `?to=` query param), or empty string.
     pub to_user_id: String,
 }
@@ -7,7 +7,7 @@
 //! DTOs validate shape (types, required fields) but not business rules.
 //! Business rule validation happens in services.
 
-use domains::models::{FlagResolution, Role};
+use domains::models::{CatalogSort, FlagResolution, Role};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -72,12 +72,60 @@ pub struct BoardConfigUpdate {
     pub captcha_required:       Option<bool>,
     /// Mark the board as NSFW. `None` leaves unchanged.
     pub nsfw:                   Option<bool>,
+    /// Run `!roll` / `!fortune` lines in new posts. `None` leaves unchanged.
+    pub commands_enabled:       Option<bool>,
+    /// Let the OP of a new thread attach a poll. `None` leaves unchanged.
+    pub polls_enabled:          Option<bool>,
+    /// Let posters react to posts. `None` leaves unchanged.
+    pub reactions_enabled:      Option<bool>,
+    /// Publish the board as an ActivityPub actor. `None` leaves unchanged.
+    pub federation_enabled:     Option<bool>,
     /// Enable full-text search on this board. `None` leaves unchanged.
     pub search_enabled:         Option<bool>,
     /// Enable thread archiving (pruned threads are moved to archive). `None` leaves unchanged.
     pub archive_enabled:        Option<bool>,
     /// Minimum seconds between posts from the same name/tripcode. `0` disables. `None` leaves unchanged.
     pub name_rate_limit_window_secs: Option<u32>,
+    /// Treatment of DNSBL-listed / Tor exit posters (`off`, `score`, `block`). `None` leaves unchanged.
+    pub dnsbl_action:           Option<domains::models::DnsblAction>,
+    /// Strip EXIF/XMP/GPS metadata from uploaded images. `None` leaves unchanged.
+    pub strip_metadata:         Option<bool>,
+    /// Render bold, italic, underline, strike and red/blue text. `None` leaves unchanged.
+    pub markup_enabled:         Option<bool>,
+    /// Render `[spoiler]` text. `None` leaves unchanged.
+    pub spoilers_enabled:       Option<bool>,
+    /// Render inline code and code blocks. `None` leaves unchanged.
+    pub code_enabled:           Option<bool>,
+    /// Render greentext and pinktext lines. `None` leaves unchanged.
+    pub greentext_enabled:      Option<bool>,
+    /// Syntax-highlight fenced code blocks that name their language. `None` leaves unchanged.
+    pub code_highlighting:      Option<bool>,
+    /// Board thumbnail size, format and quality. `None` leaves unchanged;
+    /// `max_width: 0` clears the override so the global settings apply.
+    /// Existing thumbnails keep their old parameters until `rusty-board regen-thumbs` runs.
+    pub thumbnail:              Option<domains::models::ThumbnailSpec>,
+    /// What a classifier label at or above the threshold does (`off`, `label`,
+    /// `spoiler`, `flag`). `None` leaves unchanged.
+    pub classifier_action:      Option<domains::models::ClassifierAction>,
+    /// Classifier score (0.0–1.0) that triggers `classifier_action`. `None` leaves unchanged.
+    pub classifier_threshold:   Option<f32>,
+    /// Media quota in megabytes; `0` = unlimited. `None` leaves unchanged.
+    /// Ignored on the board-owner route — only admins change quotas.
+    pub media_quota_mb:         Option<u32>,
+    /// What an upload past the quota does (`reject`, `prune`). `None` leaves unchanged.
+    pub quota_action:           Option<domains::models::QuotaAction>,
+    /// Algorithm for `Name#password` trips (`sha256`, `crypt`). `None` leaves unchanged.
+    pub tripcode_algorithm:     Option<domains::models::TripcodeAlgorithm>,
+    /// Stylesheet for viewers without a theme cookie. `None` leaves unchanged.
+    pub default_theme:          Option<domains::models::Theme>,
+    /// Language when `Accept-Language` names no supported locale. `None` leaves unchanged.
+    pub default_locale:         Option<domains::models::Locale>,
+    /// Homepage directory heading; trimmed and cut to 32 characters. `None` leaves unchanged.
+    pub category:               Option<String>,
+    /// Leave the board off the `/all` catalog. `None` leaves unchanged.
+    pub overboard_hidden:       Option<bool>,
+    /// Publish the board's moderation log at `/board/{slug}/log`. `None` leaves unchanged.
+    pub public_log:             Option<bool>,
 }
 
 impl BoardConfigUpdate {
@@ -100,9 +148,33 @@ impl BoardConfigUpdate {
         if let Some(v) = self.allow_tripcodes        { config.allow_tripcodes = v; }
         if let Some(v) = self.captcha_required       { config.captcha_required = v; }
         if let Some(v) = self.nsfw                   { config.nsfw = v; }
+        if let Some(v) = self.commands_enabled       { config.commands_enabled = v; }
+        if let Some(v) = self.polls_enabled          { config.polls_enabled = v; }
+        if let Some(v) = self.reactions_enabled      { config.reactions_enabled = v; }
+        if let Some(v) = self.federation_enabled     { config.federation_enabled = v; }
         if let Some(v) = self.search_enabled         { config.search_enabled = v; }
         if let Some(v) = self.archive_enabled        { config.archive_enabled = v; }
         if let Some(v) = self.name_rate_limit_window_secs { config.name_rate_limit_window_secs = v; }
+        if let Some(v) = self.dnsbl_action           { config.dnsbl_action = v; }
+        if let Some(v) = self.strip_metadata         { config.strip_metadata = v; }
+        if let Some(v) = self.markup_enabled         { config.markup_enabled = v; }
+        if let Some(v) = self.spoilers_enabled       { config.spoilers_enabled = v; }
+        if let Some(v) = self.code_enabled           { config.code_enabled = v; }
+        if let Some(v) = self.greentext_enabled      { config.greentext_enabled = v; }
+        if let Some(v) = self.code_highlighting      { config.code_highlighting = v; }
+        if let Some(v) = self.thumbnail              { config.thumbnail = (v.max_width > 0).then_some(v); }
+        if let Some(v) = self.classifier_action      { config.classifier_action = v; }
+        if let Some(v) = self.classifier_threshold   { config.classifier_threshold = v.clamp(0.0, 1.0); }
+        if let Some(v) = self.media_quota_mb         { config.media_quota_mb = v; }
+        if let Some(v) = self.quota_action           { config.quota_action = v; }
+        if let Some(v) = self.tripcode_algorithm     { config.tripcode_algorithm = v; }
+        if let Some(v) = self.default_theme          { config.default_theme = v; }
+        if let Some(v) = self.default_locale         { config.default_locale = v; }
+        if let Some(v) = self.category {
+            config.category = v.trim().chars().take(domains::models::BoardConfig::CATEGORY_MAX_CHARS).collect();
+        }
+        if let Some(v) = self.overboard_hidden       { config.overboard_hidden = v; }
+        if let Some(v) = self.public_log             { config.public_log = v; }
         config
     }
 }
@@ -127,6 +199,9 @@ pub struct LoginRequest {
     pub username: String,
     /// The plaintext password (transmitted over TLS; never stored raw).
     pub password: String,
+    /// TOTP or recovery code, for accounts with two-factor auth enabled.
+    #[serde(default)]
+    pub otp:      Option<String>,
 }
 
 /// Response body for a successful login.
@@ -138,6 +213,30 @@ pub struct LoginResponse {
     pub expires_at: i64,
 }
 
+/// Request body carrying a two-factor code, for `POST /auth/totp/confirm`
+/// and `POST /auth/totp/disable`.
+#[derive(Debug, Deserialize)]
+pub struct TotpCodeRequest {
+    /// A six-digit TOTP code, or (to disable) a recovery code.
+    pub code: String,
+}
+
+/// Response body for `POST /auth/totp/setup`.
+#[derive(Debug, Serialize)]
+pub struct TotpSetupResponse {
+    /// Base32 shared secret, for manual entry into an authenticator app.
+    pub secret: String,
+    /// `otpauth://` URI with the same secret.
+    pub uri:    String,
+}
+
+/// Response body for `POST /auth/totp/confirm`.
+#[derive(Debug, Serialize)]
+pub struct TotpConfirmResponse {
+    /// One-time recovery codes, shown only this once.
+    pub recovery_codes: Vec<String>,
+}
+
 // ─── Post / thread DTOs ──────────────────────────────────────────────────────
 
 /// Pagination query parameters used across list endpoints.
@@ -152,6 +251,291 @@ fn default_page() -> u32 {
     1
 }
 
+/// Query parameters of `GET /board/:slug/catalog`.
+#[derive(Debug, Default, Deserialize)]
+pub struct CatalogQuery {
+    /// Thread order. Defaults to bump order.
+    #[serde(default)]
+    pub sort: CatalogSort,
+    /// Only threads whose OP subject or body contains this text.
+    #[serde(default)]
+    pub q: Option<String>,
+}
+
+/// Query parameters of `GET /board/:slug/thread/:id`.
+#[derive(Debug, Default, Deserialize)]
+pub struct ThreadQuery {
+    /// Post number to quote: the reply box opens pre-filled with `>>N`. Set
+    /// by the post number links, so quoting works without JavaScript.
+    pub quote: Option<u64>,
+}
+
+/// Query parameters of `POST /board/:slug/thread/:id/unwatch`.
+#[derive(Debug, Default, Deserialize)]
+pub struct UnwatchQuery {
+    /// Return to the watch list (`/watched`) rather than the thread.
+    #[serde(default)]
+    pub list: bool,
+}
+
+/// Query parameters of `GET /board/:slug/thread/:id/posts`.
+#[derive(Debug, Default, Deserialize)]
+pub struct PostsAfterQuery {
+    /// Only posts numbered above this. Defaults to `0` (every post).
+    #[serde(default)]
+    pub after: u64,
+}
+
+/// Query parameters of `GET /board/:slug/threads`.
+#[derive(Debug, Deserialize)]
+pub struct ThreadIdsQuery {
+    /// Comma-separated thread UUIDs.
+    pub ids: String,
+}
+
+/// A thread as listed by `GET /board/:slug/threads`: its OP and reply count,
+/// without the IP hash.
+#[derive(Debug, Clone, Serialize)]
+pub struct ThreadSummaryResponse {
+    pub thread_id:      domains::models::ThreadId,
+    /// Link to the thread page.
+    pub url:            String,
+    /// Board-scoped post number of the OP.
+    pub op_post_number: u64,
+    /// The OP subject.
+    pub subject:        Option<String>,
+    /// The start of the OP body, rendered under the board's formatting toggles.
+    pub excerpt_html:   String,
+    /// Link to the OP's first thumbnail; `None` when it has none or it is spoilered.
+    pub thumbnail_url:  Option<String>,
+    /// Whether the OP's thumbnail is hidden behind a spoiler image.
+    pub spoiler:        bool,
+    pub reply_count:    u32,
+    pub sticky:         bool,
+    pub closed:         bool,
+    /// When the thread was started.
+    pub created_at:     chrono::DateTime<chrono::Utc>,
+    /// When the thread was last bumped.
+    pub bumped_at:      chrono::DateTime<chrono::Utc>,
+}
+
+/// A post as returned by `GET /board/:slug/thread/:id/posts`: what a page
+/// needs to show it (quote previews, new replies) without the IP hash.
+#[derive(Debug, Serialize)]
+pub struct ThreadPostResponse {
+    /// Board-scoped post number (`No.N`).
+    pub post_number: u64,
+    /// Poster name; `None` shows as Anonymous.
+    pub name:        Option<String>,
+    /// Tripcode, without the leading `!`.
+    pub tripcode:    Option<String>,
+    /// Staff role shown in place of the tripcode, e.g. `"Admin"`.
+    pub capcode:     Option<String>,
+    /// Per-thread poster ID; empty for a tombstone unless staff are asking.
+    pub poster_id:   String,
+    /// Thread subject; only ever set on the opening post.
+    pub subject:     Option<String>,
+    /// When the post was made.
+    pub created_at:  chrono::DateTime<chrono::Utc>,
+    /// The body rendered under the board's formatting toggles.
+    pub body_html:   String,
+    /// The post's files.
+    pub attachments: Vec<AttachmentResponse>,
+    /// Who deleted the post, for a tombstone. Only staff get its text.
+    pub deleted:     Option<domains::models::DeletedBy>,
+    /// When staff last edited the post.
+    pub edited_at:   Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// A thread as listed by `GET /all.json`.
+#[derive(Debug, Clone, Serialize)]
+pub struct OverboardThreadResponse {
+    /// Slug of the thread's board.
+    pub board_slug:    String,
+    /// Title of the thread's board.
+    pub board_title:   String,
+    pub thread_id:     domains::models::ThreadId,
+    /// Link to the thread page.
+    pub url:           String,
+    /// The OP subject.
+    pub subject:       Option<String>,
+    /// The start of the OP body, rendered under the board's formatting toggles.
+    pub excerpt_html:  String,
+    /// Link to the OP's first thumbnail; `None` when it has none or it is spoilered.
+    pub thumbnail_url: Option<String>,
+    /// Whether the OP's thumbnail is hidden behind a spoiler image.
+    pub spoiler:       bool,
+    pub reply_count:   u32,
+    pub sticky:        bool,
+    pub closed:        bool,
+    /// When the thread was started.
+    pub created_at:    chrono::DateTime<chrono::Utc>,
+    /// When the thread was last bumped.
+    pub bumped_at:     chrono::DateTime<chrono::Utc>,
+}
+
+/// The site notice, as returned by `GET` and `PUT /admin/notice`.
+#[derive(Debug, Serialize)]
+pub struct SiteNoticeResponse {
+    pub message: String,
+    /// Whether the notice is shown.
+    pub enabled: bool,
+}
+
+impl From<&domains::models::Site> for SiteNoticeResponse {
+    fn from(site: &domains::models::Site) -> Self {
+        Self { message: site.notice.clone(), enabled: site.notice_enabled }
+    }
+}
+
+/// Response of `GET /admin/stats`.
+#[derive(Debug, Serialize)]
+pub struct SiteStatsResponse {
+    /// Days covered, today included.
+    pub days:   u32,
+    /// Each day summed over the site's boards, oldest first.
+    pub daily:  Vec<services::stats::StatsPoint>,
+    /// Boards with posts in the window, busiest first.
+    pub boards: Vec<BoardStatsResponse>,
+}
+
+/// A board's totals in `SiteStatsResponse`.
+#[derive(Debug, Serialize)]
+pub struct BoardStatsResponse {
+    pub board_id: domains::models::BoardId,
+    pub slug:     String,
+    pub title:    String,
+    /// Posts, files and file bytes over the window.
+    #[serde(flatten)]
+    pub totals:   services::stats::StatsTotals,
+}
+
+/// A file of a `ThreadPostResponse`, with ready-to-use links.
+#[derive(Debug, Serialize)]
+pub struct AttachmentResponse {
+    /// Link to the full file.
+    pub url:           String,
+    /// Link to the thumbnail; `None` when the file has none.
+    pub thumbnail_url: Option<String>,
+    /// MIME type of the file.
+    pub mime:          String,
+    /// Stored file name.
+    pub filename:      String,
+    /// Whether the thumbnail is hidden behind a spoiler image.
+    pub spoiler:       bool,
+}
+
+// ─── Board banner DTOs ───────────────────────────────────────────────────────
+
+/// A banner as listed by `GET /board/:slug/banners`.
+#[derive(Debug, Serialize)]
+pub struct BannerResponse {
+    /// The banner's id, used to remove it.
+    pub id:           domains::models::BannerId,
+    /// Link to the image.
+    pub url:          String,
+    /// MIME type of the image.
+    pub content_type: String,
+    /// When it was uploaded.
+    pub created_at:   chrono::DateTime<chrono::Utc>,
+}
+
+/// A blotter entry as listed by `GET /admin/blotter`.
+#[derive(Debug, Serialize)]
+pub struct AnnouncementResponse {
+    /// The entry's id, used to delete it.
+    pub id:         domains::models::AnnouncementId,
+    /// The board it is shown on; `null` for every board of the site.
+    pub board_id:   Option<domains::models::BoardId>,
+    pub message:    String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<domains::models::Announcement> for AnnouncementResponse {
+    fn from(a: domains::models::Announcement) -> Self {
+        Self { id: a.id, board_id: a.board_id, message: a.message, created_at: a.created_at }
+    }
+}
+
+/// A static page as listed by `GET /admin/pages`.
+#[derive(Debug, Serialize)]
+pub struct PageResponse {
+    /// The page's id, used to update or delete it.
+    pub id:         domains::models::PageId,
+    /// The board of a board page; `null` for a site page.
+    pub board_id:   Option<domains::models::BoardId>,
+    pub slug:       String,
+    pub title:      String,
+    /// Markdown source.
+    pub body:       String,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<domains::models::StaticPage> for PageResponse {
+    fn from(p: domains::models::StaticPage) -> Self {
+        Self {
+            id:         p.id,
+            board_id:   p.board_id,
+            slug:       p.slug,
+            title:      p.title,
+            body:       p.body,
+            updated_at: p.updated_at,
+        }
+    }
+}
+
+// ─── API key DTOs ────────────────────────────────────────────────────────────
+
+/// Request body for `POST /admin/api-keys`.
+#[derive(Debug, Deserialize)]
+pub struct CreateApiKeyRequest {
+    /// Who or what the key is for, e.g. `archive-bot`.
+    pub name:               String,
+    /// `read`, `post` or both.
+    pub scopes:             Vec<domains::models::ApiScope>,
+    /// Requests the key may make per minute.
+    pub rate_limit_per_min: u32,
+}
+
+/// An API key as listed by `GET /admin/api-keys`. Never carries the key.
+#[derive(Debug, Serialize)]
+pub struct ApiKeyResponse {
+    pub id:                 domains::models::ApiKeyId,
+    pub name:               String,
+    /// The first characters of the key.
+    pub prefix:             String,
+    pub scopes:             Vec<domains::models::ApiScope>,
+    pub rate_limit_per_min: u32,
+    pub created_at:         chrono::DateTime<chrono::Utc>,
+    pub last_used_at:       Option<chrono::DateTime<chrono::Utc>>,
+    /// `null` while the key works.
+    pub revoked_at:         Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl From<domains::models::ApiKey> for ApiKeyResponse {
+    fn from(k: domains::models::ApiKey) -> Self {
+        Self {
+            id:                 k.id,
+            name:               k.name,
+            prefix:             k.prefix,
+            scopes:             k.scopes,
+            rate_limit_per_min: k.rate_limit_per_min,
+            created_at:         k.created_at,
+            last_used_at:       k.last_used_at,
+            revoked_at:         k.revoked_at,
+        }
+    }
+}
+
+/// Response of `POST /admin/api-keys`: the new key, shown this once.
+#[derive(Debug, Serialize)]
+pub struct IssuedApiKeyResponse {
+    /// The key to send as `Authorization: Bearer`.
+    pub key:     String,
+    #[serde(flatten)]
+    pub api_key: ApiKeyResponse,
+}
+
 // ─── Moderation DTOs ─────────────────────────────────────────────────────────
 
 /// Request body for `POST /mod/bans`.
@@ -163,6 +547,62 @@ pub struct CreateBanRequest {
     pub reason:     String,
     /// Optional expiry. `None` = permanent ban.
     pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Issue a shadowban: the poster's posts are accepted but hidden from
+    /// everyone except them and staff.
+    #[serde(default)]
+    pub shadow:     bool,
+}
+
+/// Request body for `POST /mod/warnings`.
+#[derive(Debug, Deserialize)]
+pub struct CreateWarningRequest {
+    /// IP hash to warn, as shown on the post.
+    pub ip_hash: String,
+    /// The warning text shown to the poster.
+    pub reason:  String,
+    /// The post that prompted the warning, if any.
+    #[serde(default)]
+    pub post_id: Option<Uuid>,
+}
+
+/// Request body for `POST /mod/posts/:id/edit`.
+#[derive(Debug, Deserialize)]
+pub struct EditPostRequest {
+    /// The new body.
+    pub body:    String,
+    /// The new subject; only an opening post takes one. Absent or blank
+    /// clears it.
+    #[serde(default)]
+    pub subject: Option<String>,
+}
+
+/// Request body for `POST /mod/asn-bans`.
+#[derive(Debug, Deserialize)]
+pub struct CreateAsnBanRequest {
+    /// Autonomous system number to ban (e.g. `9009`).
+    pub asn:        u32,
+    /// Human-readable reason displayed to posters from this network.
+    pub reason:     String,
+    /// Optional expiry. `None` = permanent ban.
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Request body for `POST /mod/hash-bans`.
+///
+/// Exactly one of `post_id` (ban every attachment on that post, MD5 and
+/// perceptual hash) or `md5` (ban a known file by its hex digest) is given.
+#[derive(Debug, Deserialize)]
+pub struct CreateHashBanRequest {
+    /// Post whose attachments should be banned.
+    #[serde(default)]
+    pub post_id:    Option<uuid::Uuid>,
+    /// Hex MD5 of a file to ban.
+    #[serde(default)]
+    pub md5:        Option<String>,
+    /// Human-readable reason displayed to posters whose upload matches.
+    pub reason:     String,
+    /// Optional expiry. `None` = permanent ban.
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 /// Request body for `POST /mod/flags/:id/resolve`.
@@ -8,16 +8,42 @@ use domains::errors::DomainError;
 use serde::Serialize;
 use thiserror::Error;
 
-/// A structured error body returned by all API endpoints.
+/// The error envelope returned by all API endpoints:
+/// `{"error": {"code": "RATE_LIMITED", "message": "…", "retry_after": 30}}`.
 #[derive(Debug, Serialize)]
 pub struct ErrorBody {
-    /// Short machine-readable error code, e.g. `"NOT_FOUND"` or `"RATE_LIMITED"`.
-    pub error:   String,
+    /// The error itself.
+    pub error: ErrorDetail,
+}
+
+/// The contents of an `ErrorBody`.
+#[derive(Debug, Serialize)]
+pub struct ErrorDetail {
+    /// Stable machine-readable error code, e.g. `"NOT_FOUND"` or `"RATE_LIMITED"`.
+    /// Clients branch on this; the message may change.
+    pub code:        String,
     /// Human-readable description of the error, safe to display to end users.
-    pub message: String,
-    /// Optional structured details (e.g. validation field errors). Omitted when `None`.
+    pub message:     String,
+    /// Seconds to wait before retrying, as in the `Retry-After` header. Omitted when `None`.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub details: Option<serde_json::Value>,
+    pub retry_after: Option<u64>,
+    /// Optional structured details (e.g. a ban's dates). Omitted when `None`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details:     Option<serde_json::Value>,
+}
+
+impl ErrorBody {
+    /// An envelope with just a code and a message.
+    pub fn new(code: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            error: ErrorDetail {
+                code:        code.into(),
+                message:     message.into(),
+                retry_after: None,
+                details:     None,
+            },
+        }
+    }
 }
 
 /// The unified API error type.
@@ -34,10 +60,21 @@ pub enum ApiError {
     #[error("unauthorized")]
     Unauthorized,
 
+    /// 401 Unauthorized — the password was accepted but the account's
+    /// two-factor code is missing or wrong. Rendered as `TOTP_REQUIRED` so the
+    /// login form knows to ask for one.
+    #[error("two-factor: {0}")]
+    TwoFactor(String),
+
     /// 403 Forbidden — the authenticated user lacks permission.
     #[error("forbidden")]
     Forbidden,
 
+    /// 403 Forbidden — a form post or other cross-site-capable request came
+    /// without the visitor's CSRF token. Rendered as `CSRF_FAILED`.
+    #[error("missing or invalid CSRF token")]
+    Csrf,
+
     /// 404 Not Found — the requested resource does not exist.
     #[error("not found: {0}")]
     NotFound(String),
@@ -46,6 +83,11 @@ pub enum ApiError {
     #[error("conflict: {0}")]
     Conflict(String),
 
+    /// 413 Payload Too Large — the body, a form field, or the number of
+    /// fields is over the configured multipart limits.
+    #[error("payload too large: {0}")]
+    PayloadTooLarge(String),
+
     /// 422 Unprocessable Entity — semantic validation failed.
     #[error("validation error: {0}")]
     UnprocessableEntity(String),
@@ -62,6 +104,8 @@ pub enum ApiError {
     Banned {
         /// The ban reason shown to the poster.
         reason:     String,
+        /// When the ban was issued. `None` for automatic blocks (e.g. DNSBL).
+        issued_at:  Option<chrono::DateTime<chrono::Utc>>,
         /// When the ban expires, or `None` for a permanent ban.
         expires_at: Option<chrono::DateTime<chrono::Utc>>,
     },
@@ -89,7 +133,9 @@ impl From<DomainError> for ApiError {
             DomainError::Validation(v)          => ApiError::UnprocessableEntity(v.to_string()),
             DomainError::Auth                   => ApiError::Unauthorized,
             DomainError::MediaProcessing { reason } => ApiError::UnprocessableEntity(reason),
-            DomainError::Banned { reason, expires_at } => ApiError::Banned { reason, expires_at },
+            DomainError::Banned { reason, expires_at } => {
+                ApiError::Banned { reason, issued_at: None, expires_at }
+            }
             DomainError::RateLimit { retry_after_secs } => ApiError::RateLimited { retry_after_secs },
             DomainError::Internal { reason }    => ApiError::Internal(reason),
         }
@@ -114,9 +160,10 @@ impl From<services::board::BoardError> for ApiError {
 impl From<services::post::PostError> for ApiError {
     fn from(e: services::post::PostError) -> Self {
         match e {
-            services::post::PostError::Banned { reason, expires_at } => {
-                ApiError::Banned { reason, expires_at }
+            services::post::PostError::Banned { reason, issued_at, expires_at } => {
+                ApiError::Banned { reason, issued_at, expires_at }
             }
+            e @ services::post::PostError::Warned { .. } => ApiError::UnprocessableEntity(e.to_string()),
             services::post::PostError::RateLimited { retry_after_secs } => {
                 ApiError::RateLimited { retry_after_secs }
             }
@@ -129,13 +176,21 @@ impl From<services::post::PostError> for ApiError {
             services::post::PostError::Validation { reason } => {
                 ApiError::UnprocessableEntity(reason)
             }
+            services::post::PostError::Rejected { reason } => ApiError::UnprocessableEntity(reason),
             services::post::PostError::ThreadNotFound { id } => ApiError::NotFound(id),
             services::post::PostError::ThreadClosed => {
                 ApiError::UnprocessableEntity("thread is closed".to_owned())
             }
+            services::post::PostError::BannedMedia { reason } => {
+                ApiError::UnprocessableEntity(format!("file is banned: {reason}"))
+            }
+            services::post::PostError::QuotaExceeded { .. } => {
+                ApiError::UnprocessableEntity("this board's file storage is full".to_owned())
+            }
             services::post::PostError::MediaError { reason } => {
                 ApiError::UnprocessableEntity(reason)
             }
+            services::post::PostError::NotOwnPost => ApiError::Forbidden,
             services::post::PostError::Internal(d) => ApiError::from(d),
         }
     }
@@ -148,6 +203,11 @@ impl From<services::user::UserError> for ApiError {
             services::user::UserError::Validation { reason } => ApiError::BadRequest(reason),
             services::user::UserError::InvalidCredentials   => ApiError::Unauthorized,
             services::user::UserError::Deactivated          => ApiError::Forbidden,
+            e @ services::user::UserError::UsernameTaken { .. } => ApiError::Conflict(e.to_string()),
+            e @ (services::user::UserError::TotpRequired
+                | services::user::UserError::InvalidTotp)   => ApiError::TwoFactor(e.to_string()),
+            services::user::UserError::TotpLocked { retry_after_secs } => ApiError::RateLimited { retry_after_secs },
+            services::user::UserError::NotConfigured { .. } => ApiError::NotImplemented,
             services::user::UserError::Internal(d)          => ApiError::from(d),
         }
     }
@@ -160,6 +220,13 @@ impl From<services::moderation::ModerationError> for ApiError {
                 ApiError::NotFound(resource)
             }
             services::moderation::ModerationError::PermissionDenied => ApiError::Forbidden,
+            services::moderation::ModerationError::NotConfigured { .. } => ApiError::NotImplemented,
+            e @ (services::moderation::ModerationError::InvalidIp { .. }
+            | services::moderation::ModerationError::InvalidHours { .. }
+            | services::moderation::ModerationError::InvalidMove { .. }
+            | services::moderation::ModerationError::InvalidEdit { .. }) => {
+                ApiError::UnprocessableEntity(e.to_string())
+            }
             services::moderation::ModerationError::Internal(d) => ApiError::from(d),
         }
     }
@@ -211,3 +278,126 @@ impl From<services::staff_message::StaffMessageError> for ApiError {
         }
     }
 }
+
+impl From<services::poll::PollError> for ApiError {
+    fn from(e: services::poll::PollError) -> Self {
+        match e {
+            services::poll::PollError::NotFound { thread_id } => ApiError::NotFound(thread_id),
+            services::poll::PollError::Disabled => ApiError::Forbidden,
+            services::poll::PollError::AlreadyVoted => {
+                ApiError::Conflict("already voted in this poll".to_owned())
+            }
+            services::poll::PollError::Validation { reason } => ApiError::BadRequest(reason),
+            services::poll::PollError::Internal(d) => ApiError::from(d),
+        }
+    }
+}
+
+impl From<services::watch::WatchError> for ApiError {
+    fn from(e: services::watch::WatchError) -> Self {
+        match e {
+            services::watch::WatchError::ThreadNotFound { id } => ApiError::NotFound(id),
+            services::watch::WatchError::ListFull { max } => {
+                ApiError::Conflict(format!("watch list is full ({max} threads)"))
+            }
+            services::watch::WatchError::Internal(d) => ApiError::from(d),
+        }
+    }
+}
+
+impl From<services::banner::BannerError> for ApiError {
+    fn from(e: services::banner::BannerError) -> Self {
+        match e {
+            services::banner::BannerError::NotFound { id } => ApiError::NotFound(id),
+            services::banner::BannerError::TooMany { max } => {
+                ApiError::Conflict(format!("board already has {max} banners"))
+            }
+            e @ (services::banner::BannerError::InvalidType | services::banner::BannerError::TooLarge { .. }) => {
+                ApiError::UnprocessableEntity(e.to_string())
+            }
+            services::banner::BannerError::Internal(d) => ApiError::from(d),
+        }
+    }
+}
+
+impl From<services::announcement::AnnouncementError> for ApiError {
+    fn from(e: services::announcement::AnnouncementError) -> Self {
+        match e {
+            services::announcement::AnnouncementError::NotFound { id } => ApiError::NotFound(id),
+            e @ (services::announcement::AnnouncementError::Empty
+            | services::announcement::AnnouncementError::TooLong { .. }) => {
+                ApiError::UnprocessableEntity(e.to_string())
+            }
+            services::announcement::AnnouncementError::Internal(d) => ApiError::from(d),
+        }
+    }
+}
+
+impl From<services::page::PageError> for ApiError {
+    fn from(e: services::page::PageError) -> Self {
+        match e {
+            services::page::PageError::NotFound { page } => ApiError::NotFound(page),
+            e @ services::page::PageError::SlugTaken { .. } => ApiError::Conflict(e.to_string()),
+            e @ (services::page::PageError::InvalidSlug { .. }
+            | services::page::PageError::ReservedSlug { .. }
+            | services::page::PageError::EmptyTitle
+            | services::page::PageError::TooLong { .. }) => ApiError::UnprocessableEntity(e.to_string()),
+            services::page::PageError::Internal(d) => ApiError::from(d),
+        }
+    }
+}
+
+impl From<services::site::SiteError> for ApiError {
+    fn from(e: services::site::SiteError) -> Self {
+        match e {
+            services::site::SiteError::NotFound { host } => ApiError::NotFound(host),
+            e @ (services::site::SiteError::InvalidHost { .. }
+            | services::site::SiteError::InvalidPrefix { .. }
+            | services::site::SiteError::InvalidName
+            | services::site::SiteError::NoticeTooLong { .. }
+            | services::site::SiteError::EmptyNotice) => ApiError::UnprocessableEntity(e.to_string()),
+            services::site::SiteError::Internal(d) => ApiError::from(d),
+        }
+    }
+}
+
+impl From<services::stats::StatsError> for ApiError {
+    fn from(e: services::stats::StatsError) -> Self {
+        match e {
+            services::stats::StatsError::Internal(d) => ApiError::from(d),
+        }
+    }
+}
+
+impl From<services::reaction::ReactionError> for ApiError {
+    fn from(e: services::reaction::ReactionError) -> Self {
+        match e {
+            services::reaction::ReactionError::PostNotFound { id } => ApiError::NotFound(id),
+            services::reaction::ReactionError::Disabled => ApiError::Forbidden,
+            services::reaction::ReactionError::AlreadyReacted => {
+                ApiError::Conflict("already reacted to this post".to_owned())
+            }
+            services::reaction::ReactionError::RateLimited { retry_after_secs } => {
+                ApiError::RateLimited { retry_after_secs }
+            }
+            services::reaction::ReactionError::Internal(d) => ApiError::from(d),
+        }
+    }
+}
+
+impl From<services::api_key::ApiKeyError> for ApiError {
+    fn from(e: services::api_key::ApiKeyError) -> Self {
+        match e {
+            services::api_key::ApiKeyError::NotFound { id } => ApiError::NotFound(id),
+            services::api_key::ApiKeyError::InvalidKey => ApiError::Unauthorized,
+            services::api_key::ApiKeyError::MissingScope { .. } => ApiError::Forbidden,
+            services::api_key::ApiKeyError::RateLimited { retry_after_secs } => {
+                ApiError::RateLimited { retry_after_secs }
+            }
+            e @ (services::api_key::ApiKeyError::InvalidName { .. }
+            | services::api_key::ApiKeyError::NoScopes
+            | services::api_key::ApiKeyError::InvalidRateLimit { .. }) => ApiError::UnprocessableEntity(e.to_string()),
+            services::api_key::ApiKeyError::Internal(d) => ApiError::from(d),
+        }
+    }
+}
@@ -34,6 +34,7 @@ impl StubBoardRepoOk {
         Self {
             board: Board {
                 id:         BoardId(Uuid::new_v4()),
+                site_id:    domains::models::SiteId::DEFAULT,
                 slug:       Slug::new(slug).unwrap(),
                 title:      "Test Board".to_owned(),
                 rules:      "Be nice.".to_owned(),
@@ -45,9 +46,10 @@ impl StubBoardRepoOk {
 
 #[async_trait::async_trait]
 impl BoardRepo for StubBoardRepoOk {
-    async fn create_board(&self, slug: &str, title: &str, _rules: &str) -> Result<Board, BoardError> {
+    async fn create_board(&self, _site: domains::models::SiteId, slug: &str, title: &str, _rules: &str) -> Result<Board, BoardError> {
         Ok(Board {
             id:         BoardId(Uuid::new_v4()),
+            site_id:    domains::models::SiteId::DEFAULT,
             slug:       Slug::new(slug).unwrap(),
             title:      title.to_owned(),
             rules:      "".to_owned(),
@@ -55,7 +57,7 @@ impl BoardRepo for StubBoardRepoOk {
         })
     }
 
-    async fn get_by_slug(&self, _slug: &str) -> Result<Board, BoardError> {
+    async fn get_by_slug(&self, _site: domains::models::SiteId, _slug: &str) -> Result<Board, BoardError> {
         Ok(self.board.clone())
     }
 
@@ -80,7 +82,7 @@ impl BoardRepo for StubBoardRepoOk {
         Ok(())
     }
 
-    async fn list_boards(&self, page: Page) -> Result<Paginated<Board>, BoardError> {
+    async fn list_boards(&self, _site: domains::models::SiteId, page: Page) -> Result<Paginated<Board>, BoardError> {
         Ok(Paginated::new(vec![self.board.clone()], 1, page, 15))
     }
 
@@ -88,9 +90,10 @@ impl BoardRepo for StubBoardRepoOk {
         Ok(BoardConfig::default())
     }
 
-    async fn update_config(&self, _board_id: BoardId, config: BoardConfig) -> Result<BoardConfig, BoardError> {
+    async fn update_config(&self, _board_id: BoardId, config: BoardConfig, _: domains::models::UserId) -> Result<BoardConfig, BoardError> {
         Ok(config)
     }
+    async fn media_usage(&self, _: BoardId) -> Result<u64, BoardError> { Ok(0) }
     async fn list_volunteers(&self, _: BoardId) -> Result<Vec<(domains::models::UserId, String, chrono::DateTime<chrono::Utc>)>, BoardError> { Ok(vec![]) }
     async fn add_volunteer_by_username(&self, _: BoardId, _: &str, _: domains::models::UserId) -> Result<(), BoardError> { Ok(()) }
     async fn remove_volunteer(&self, _: BoardId, _: domains::models::UserId) -> Result<(), BoardError> { Ok(()) }
@@ -101,10 +104,10 @@ struct StubBoardRepoNotFound;
 
 #[async_trait::async_trait]
 impl BoardRepo for StubBoardRepoNotFound {
-    async fn create_board(&self, _: &str, _: &str, _: &str) -> Result<Board, BoardError> {
+    async fn create_board(&self, _site: domains::models::SiteId, _: &str, _: &str, _: &str) -> Result<Board, BoardError> {
         Err(BoardError::NotFound { slug: "test-board".into() })
     }
-    async fn get_by_slug(&self, slug: &str) -> Result<Board, BoardError> {
+    async fn get_by_slug(&self, _site: domains::models::SiteId, slug: &str) -> Result<Board, BoardError> {
         Err(BoardError::NotFound { slug: slug.to_owned() })
     }
     async fn get_by_id(&self, id: BoardId) -> Result<Board, BoardError> {
@@ -116,15 +119,16 @@ impl BoardRepo for StubBoardRepoNotFound {
     async fn delete_board(&self, id: BoardId) -> Result<(), BoardError> {
         Err(BoardError::NotFound { slug: id.0.to_string() })
     }
-    async fn list_boards(&self, page: Page) -> Result<Paginated<Board>, BoardError> {
+    async fn list_boards(&self, _site: domains::models::SiteId, page: Page) -> Result<Paginated<Board>, BoardError> {
         Ok(Paginated::new(vec![], 0, page, 15))
     }
     async fn get_config(&self, id: BoardId) -> Result<BoardConfig, BoardError> {
         Err(BoardError::NotFound { slug: id.0.to_string() })
     }
-    async fn update_config(&self, id: BoardId, _: BoardConfig) -> Result<BoardConfig, BoardError> {
+    async fn update_config(&self, id: BoardId, _: BoardConfig, _: domains::models::UserId) -> Result<BoardConfig, BoardError> {
         Err(BoardError::NotFound { slug: id.0.to_string() })
     }
+    async fn media_usage(&self, _: BoardId) -> Result<u64, BoardError> { Ok(0) }
     async fn list_volunteers(&self, _: BoardId) -> Result<Vec<(domains::models::UserId, String, chrono::DateTime<chrono::Utc>)>, BoardError> { Ok(vec![]) }
     async fn add_volunteer_by_username(&self, _: BoardId, _: &str, _: domains::models::UserId) -> Result<(), BoardError> { Ok(()) }
     async fn remove_volunteer(&self, _: BoardId, _: domains::models::UserId) -> Result<(), BoardError> { Ok(()) }
@@ -142,25 +146,45 @@ impl domains::ports::PostRepository for NopPostRepo {
     async fn find_by_thread(&self, _: ThreadId, _: Page) -> Result<Paginated<Post>, domains::errors::DomainError> { unimplemented!() }
     async fn find_recent_hashes(&self, _: BoardId, _: u32) -> Result<Vec<domains::models::ContentHash>, domains::errors::DomainError> { unimplemented!() }
     async fn find_by_ip_hash(&self, _: &domains::models::IpHash) -> Result<Vec<Post>, domains::errors::DomainError> { unimplemented!() }
+    async fn find_by_ip_hashes(&self, _: domains::models::SiteId, _: &[domains::models::IpHash], _: Page) -> Result<Paginated<domains::models::OverboardPost>, domains::errors::DomainError> { unimplemented!() }
     async fn save(&self, _: &Post) -> Result<(PostId, u64), domains::errors::DomainError> { unimplemented!() }
-    async fn delete(&self, _: PostId) -> Result<(), domains::errors::DomainError> { unimplemented!() }
+    async fn delete(&self, _: PostId, _: domains::models::DeletedBy) -> Result<(), domains::errors::DomainError> { unimplemented!() }
     async fn delete_by_ip_in_thread(&self, _: &domains::models::IpHash, _: domains::models::ThreadId) -> Result<u64, domains::errors::DomainError> { Ok(0) }
+    async fn delete_by_ip_on_board(&self, _: &domains::models::IpHash, _: domains::models::BoardId, _: chrono::DateTime<chrono::Utc>) -> Result<domains::models::PurgedPosts, domains::errors::DomainError> { Ok(Default::default()) }
+    async fn purge_deleted(&self, _: u32) -> Result<u32, domains::errors::DomainError> { unimplemented!() }
+    async fn edit(&self, _: PostId, _: &str, _: Option<String>, _: UserId) -> Result<(), domains::errors::DomainError> { unimplemented!() }
+    async fn find_revisions(&self, _: PostId) -> Result<Vec<PostRevision>, domains::errors::DomainError> { unimplemented!() }
+    async fn move_posts(&self, _: &domains::models::PostMove) -> Result<(), domains::errors::DomainError> { Ok(()) }
     async fn save_attachments(&self, _: &[domains::models::Attachment]) -> Result<(), domains::errors::DomainError> { Ok(()) }
     async fn find_attachments_by_post_ids(&self, _: &[PostId]) -> Result<std::collections::HashMap<PostId, Vec<domains::models::Attachment>>, domains::errors::DomainError> { Ok(std::collections::HashMap::new()) }
-    async fn find_overboard(&self, p: Page) -> Result<Paginated<domains::models::OverboardPost>, domains::errors::DomainError> { Ok(Paginated::new(vec![], 0, p, 15)) }
+    async fn find_overboard(&self, _site: domains::models::SiteId, p: Page) -> Result<Paginated<domains::models::OverboardPost>, domains::errors::DomainError> { Ok(Paginated::new(vec![], 0, p, 15)) }
+    async fn find_site_activity(&self, _: domains::models::SiteId, _: u32) -> Result<domains::models::SiteActivity, domains::errors::DomainError> { Ok(domains::models::SiteActivity::default()) }
     async fn search_fulltext(&self, _: BoardId, _: &str, p: Page) -> Result<Paginated<Post>, domains::errors::DomainError> { Ok(Paginated::new(vec![], 0, p, 15)) }
     async fn find_all_by_thread(&self, _: ThreadId) -> Result<Vec<Post>, domains::errors::DomainError> { Ok(vec![]) }
+    async fn find_visible_by_thread(&self, _: ThreadId, _: &domains::models::Viewer) -> Result<Vec<Post>, domains::errors::DomainError> { Ok(vec![]) }
     async fn find_thread_id_by_post_number(&self, _: BoardId, _: u64) -> Result<Option<domains::models::ThreadId>, domains::errors::DomainError> { Ok(None) }
     async fn set_pinned(&self, _: domains::models::PostId, _: bool) -> Result<(), domains::errors::DomainError> { Ok(()) }
-    async fn find_oldest_unpinned_reply(&self, _: domains::models::ThreadId) -> Result<Option<domains::models::PostId>, domains::errors::DomainError> { Ok(None) }
+    async fn find_cycle_excess(&self, _: domains::models::ThreadId, _: u32) -> Result<Vec<domains::models::PostId>, domains::errors::DomainError> { Ok(vec![]) }
     async fn find_attachment_by_hash(&self, _: &domains::models::ContentHash) -> Result<Option<domains::models::Attachment>, domains::errors::DomainError> { Ok(None) }
     async fn delete_by_id(&self, _: domains::models::PostId) -> Result<(), domains::errors::DomainError> { Ok(()) }
+    async fn find_referenced_media_keys(&self) -> Result<std::collections::HashSet<domains::models::MediaKey>, domains::errors::DomainError> { Ok(Default::default()) }
+    async fn find_thumbnailed_attachments(&self) -> Result<Vec<(BoardId, domains::models::Attachment)>, domains::errors::DomainError> { Ok(vec![]) }
+    async fn find_all_attachments(&self) -> Result<Vec<(BoardId, domains::models::Attachment)>, domains::errors::DomainError> { Ok(vec![]) }
+    async fn find_visible_by_id(&self, _: domains::models::PostId) -> Result<Option<domains::models::Post>, domains::errors::DomainError> { Ok(None) }
+    async fn find_by_ids(&self, _: domains::models::ThreadId, _: &[domains::models::PostId]) -> Result<Vec<domains::models::Post>, domains::errors::DomainError> { Ok(vec![]) }
+    async fn find_by_numbers(&self, _: domains::models::ThreadId, _: &[u64]) -> Result<Vec<domains::models::Post>, domains::errors::DomainError> { Ok(vec![]) }
+    async fn find_quoting(&self, _: domains::models::ThreadId, _: &[u64]) -> Result<Vec<domains::models::Post>, domains::errors::DomainError> { Ok(vec![]) }
+    async fn find_media_mime(&self, _: &domains::models::MediaKey) -> Result<Option<String>, domains::errors::DomainError> { Ok(None) }
+    async fn set_thumbnail_key(&self, _: &domains::models::MediaKey, _: &domains::models::MediaKey) -> Result<u64, domains::errors::DomainError> { Ok(0) }
+    async fn restore(&self, _: &domains::models::Post) -> Result<(), domains::errors::DomainError> { Ok(()) }
+    async fn find_number_range(&self, _: domains::models::BoardId) -> Result<Option<domains::models::PostNumberRange>, domains::errors::DomainError> { Ok(None) }
+    async fn find_by_number_range(&self, _: domains::models::BoardId, _: u64, _: u64, _: u32) -> Result<Vec<domains::models::Post>, domains::errors::DomainError> { Ok(vec![]) }
 }
 
 // ─── Router factory helpers ───────────────────────────────────────────────────
 
 fn board_public_router(repo: impl BoardRepo) -> Router {
-    api_adapters::axum::routes::board_routes::board_public_routes(Arc::new(repo), NopPostRepo, std::sync::Arc::new(NopArchiveRepo::default()))
+    api_adapters::axum::routes::board_routes::board_public_routes(Arc::new(repo), NopPostRepo, std::sync::Arc::new(NopArchiveRepo))
 }
 
 fn json_get(uri: &str) -> Request<Body> {
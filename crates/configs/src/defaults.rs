@@ -19,6 +19,33 @@ pub fn port() -> u16 {
     8080
 }
 
+/// Smallest response compressed, in bytes; below it the encoding costs
+/// more than it saves.
+pub fn compression_min_bytes() -> u16 {
+    1024
+}
+
+/// Largest request body, in KiB: a file at the default upload cap plus room
+/// for the rest of the form and the multipart framing.
+pub fn max_body_kb() -> u64 {
+    12 * 1024
+}
+
+/// Largest file part, in KiB.
+pub fn max_upload_kb() -> u64 {
+    10 * 1024
+}
+
+/// Longest text field, in KiB; a 4000-character post is at most 16 KiB.
+pub fn max_text_field_kb() -> u64 {
+    64
+}
+
+/// Most fields in a form post; the post form has ten plus its files.
+pub fn max_form_fields() -> usize {
+    32
+}
+
 /// Graceful shutdown drain timeout in seconds.
 /// In-flight requests are given this long to complete after a SIGTERM.
 pub fn shutdown_timeout_secs() -> u64 {
@@ -70,28 +97,239 @@ pub fn media_url_ttl_secs() -> u64 {
     86_400
 }
 
-/// Maximum width (and height) of generated thumbnails in pixels.
+/// Maximum width of generated thumbnails in pixels.
 pub fn thumbnail_width_px() -> u32 {
     320
 }
 
-/// JPEG thumbnail quality (0–100). Higher = better quality, larger file.
+/// JPEG thumbnail quality (1–100). Higher = better quality, larger file.
 pub fn thumbnail_quality() -> u8 {
     85
 }
 
+/// Worker tasks draining the background thumbnail queue.
+pub fn thumbnail_workers() -> usize {
+    2
+}
+
+/// AVIF quality for re-encoded originals.
+pub fn reencode_quality() -> u8 {
+    75
+}
+
+/// Smallest original (KB) worth re-encoding.
+pub fn reencode_min_kb() -> u64 {
+    512
+}
+
+/// Thumbnail jobs that may wait in the queue before posting generates inline.
+pub fn thumbnail_queue_capacity() -> usize {
+    64
+}
+
+/// Kubo RPC API of the local IPFS node (`media-ipfs`).
+pub fn ipfs_api_url() -> String {
+    "http://127.0.0.1:5001".to_owned()
+}
+
+/// Gateway serving pinned media (`media-ipfs`). The node's own gateway by default.
+pub fn ipfs_gateway_url() -> String {
+    "http://127.0.0.1:8080".to_owned()
+}
+
+/// MFS directory holding uploads (`media-ipfs`).
+pub fn ipfs_mfs_root() -> String {
+    "/rusty-board".to_owned()
+}
+
+/// `ffmpeg` executable for the `video` feature, resolved via `PATH`.
+pub fn ffmpeg_path() -> PathBuf {
+    PathBuf::from("ffmpeg")
+}
+
+/// `ffprobe` executable for the `video` feature, resolved via `PATH`.
+pub fn ffprobe_path() -> PathBuf {
+    PathBuf::from("ffprobe")
+}
+
+/// `vips` executable for the `thumbs-vips` feature, resolved via `PATH`.
+pub fn vips_path() -> PathBuf {
+    PathBuf::from("vips")
+}
+
+/// `service.name` attached to exported spans.
+pub fn otel_service_name() -> String {
+    "rusty-board".to_owned()
+}
+
+/// Fraction of traces exported: all of them.
+pub fn otel_sample_ratio() -> f64 {
+    1.0
+}
+
+/// ONNX runner for the `nsfw-onnx` feature, relative to the working directory.
+pub fn nsfw_classifier_cmd() -> PathBuf {
+    PathBuf::from("scripts/nsfw_classify.py")
+}
+
+/// Fuel for one WASM plugin call.
+pub fn wasm_plugin_fuel() -> u64 {
+    10_000_000
+}
+
+/// Memory cap of one WASM plugin instance, in MB.
+pub fn wasm_plugin_memory_mb() -> usize {
+    16
+}
+
+/// Wall time of one Lua script call, in milliseconds.
+pub fn lua_script_timeout_ms() -> u64 {
+    50
+}
+
+/// Memory cap of one Lua script call, in MB.
+pub fn lua_script_memory_mb() -> usize {
+    16
+}
+
+/// Interval between orphaned-media sweeps in seconds. Default: once per day.
+pub fn media_gc_interval_secs() -> u64 {
+    86_400
+}
+
+/// Perceptual-hash Hamming distance treated as a hash-ban match.
+pub fn hash_ban_max_distance() -> u32 {
+    6
+}
+
+/// Minimum age in seconds before unreferenced media may be swept.
+pub fn media_gc_grace_secs() -> u64 {
+    3_600
+}
+
+/// Interval between scheduled thread prunes in seconds. Default: hourly.
+pub fn prune_interval_secs() -> u64 {
+    3_600
+}
+
+/// Interval between expired ban/session/message/tombstone cleanups in seconds. Default: hourly.
+pub fn expire_interval_secs() -> u64 {
+    3_600
+}
+
+/// Days an expired ban is kept before deletion.
+pub fn ban_retention_days() -> u32 {
+    90
+}
+
+/// Days a deleted post's tombstone is kept before removal.
+pub fn deleted_post_retention_days() -> u32 {
+    30
+}
+
+/// Interval between per-board stats refreshes in seconds. Default: 5 minutes.
+pub fn stats_interval_secs() -> u64 {
+    300
+}
+
+/// Interval between board statistics rollups in seconds. Default: 10 minutes.
+pub fn stats_rollup_interval_secs() -> u64 {
+    600
+}
+
 /// How often the in-memory IP hashing salt rotates in seconds.
 /// Default: once per day (86400 seconds). Set to 0 to never rotate (not recommended).
 pub fn ip_salt_rotation_secs() -> u64 {
     86_400
 }
 
+/// DNSBL zones queried when the `spam-dnsbl` feature is compiled in.
+pub fn dnsbl_zones() -> String {
+    "zen.spamhaus.org".to_owned()
+}
+
+/// IP reputation verdict cache TTL in seconds. Default: 1 hour.
+pub fn dnsbl_cache_ttl_secs() -> u64 {
+    3_600
+}
+
 /// `BoardConfig` cache TTL in seconds.
 /// Dashboard updates take effect within this window on all instances.
 pub fn config_cache_ttl_secs() -> u64 {
     60
 }
 
+/// Rendered board index / catalog cache TTL in seconds.
+/// Posts on the board invalidate immediately; moderation actions wait this long.
+pub fn render_cache_ttl_secs() -> u64 {
+    10
+}
+
+/// Events buffered per event bus subscriber before it starts missing them.
+pub fn event_bus_capacity() -> usize {
+    1024
+}
+
+/// Attempts per webhook delivery, the first included.
+pub fn webhook_max_attempts() -> u32 {
+    5
+}
+
+/// Wait before the first webhook retry, in seconds.
+pub fn webhook_backoff_secs() -> u64 {
+    5
+}
+
+/// Time limit of one webhook request, in seconds.
+pub fn webhook_timeout_secs() -> u64 {
+    10
+}
+
+/// Long-poll wait of one Matrix `/sync`, in seconds.
+pub fn matrix_sync_timeout_secs() -> u64 {
+    30
+}
+
+/// RSA key file of ActivityPub request signatures.
+pub fn federation_key_path() -> PathBuf {
+    PathBuf::from("federation.pem")
+}
+
+/// NNTP gateway listen address; loopback only.
+pub fn nntp_listen() -> String {
+    "127.0.0.1:1119".to_owned()
+}
+
+/// Newsgroup name prefix of the NNTP gateway.
+pub fn nntp_group_prefix() -> String {
+    "rusty-board".to_owned()
+}
+
+/// Concurrent NNTP readers.
+pub fn nntp_max_connections() -> usize {
+    64
+}
+
+/// SMTP submission port.
+pub fn email_smtp_port() -> u16 {
+    587
+}
+
+/// Pending reports on a post that trigger an email alert.
+pub fn email_report_threshold() -> u64 {
+    3
+}
+
+/// Interval of report digest emails: hourly.
+pub fn email_digest_interval_secs() -> u64 {
+    3_600
+}
+
+/// Time limit of one email delivery.
+pub fn email_timeout_secs() -> u64 {
+    30
+}
+
 /// Whether public self-registration is open by default.
 /// Operators can set `OPEN_REGISTRATION=false` to disable it.
 pub fn open_registration() -> bool {
@@ -1,8 +1,10 @@
 //! `configs` — infrastructure configuration for rusty-board.
 //!
-//! `Settings` is loaded at startup from environment variables and an optional
-//! `.env` file. It contains **only** infrastructure configuration — connection
-//! URLs, secrets, resource limits, and processing parameters.
+//! `Settings` is loaded at startup from layered sources, lowest precedence
+//! first: an optional TOML config file, an optional `.env` file, environment
+//! variables, then `--set key=value` command-line overrides. It contains
+//! **only** infrastructure configuration — connection URLs, secrets, resource
+//! limits, and processing parameters.
 //!
 //! Per-board behavioural configuration lives in `BoardConfig` in the database.
 //! Never add per-board toggles to `Settings`.
@@ -11,14 +13,33 @@
 //! Flat top-level fields use `SNAKE_UPPER_CASE` (e.g. `DB_URL`, `JWT_SECRET`).
 //! Nested struct fields (e.g. `S3Config`) use `DOUBLE__UNDERSCORE` to separate levels
 //! (e.g. `S3__BUCKET`, `S3__REGION`). Single underscores within a name are preserved.
+//!
+//! # Config file
+//! The file is `--config <path>`, else `$RUSTY_BOARD_CONFIG`, else
+//! `rusty-board.toml` in the working directory if it exists. Keys are the
+//! field names in lower case, with nested structs as tables (`[s3]`,
+//! `[ipfs]`); see `rusty-board.example.toml`. Keep secrets in the environment.
+//!
+//! # Plugin sections
+//! Tables under `[plugins.<name>]` (or `PLUGINS__<NAME>__<KEY>`) are kept
+//! untyped in `Settings::plugins`; a plugin reads its own section with
+//! `Settings::plugin_config`.
 
 use secrecy::SecretString;
+use serde::de::DeserializeOwned;
 use serde::Deserialize;
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Environment variable naming the config file when `--config` is not given.
+pub const CONFIG_FILE_ENV: &str = "RUSTY_BOARD_CONFIG";
+
+/// Config file read from the working directory when no path is given.
+pub const DEFAULT_CONFIG_FILE: &str = "rusty-board.toml";
 
 /// Infrastructure configuration for a rusty-board deployment.
 ///
-/// Loaded once at startup via `Settings::load()`. Immutable after loading.
+/// Loaded once at startup via `Settings::load_layered()`. Immutable after loading.
 /// Accessed in `composition.rs` to construct concrete adapter instances.
 ///
 /// # INVARIANT
@@ -35,11 +56,72 @@ pub struct Settings {
     #[serde(default = "defaults::port")]
     pub port: u16,
 
-    /// How long to wait for in-flight requests during graceful shutdown (seconds).
-    /// Default: 30.
+    /// How long to wait for in-flight requests and queued background work
+    /// during graceful shutdown (seconds). Default: 30.
     #[serde(default = "defaults::shutdown_timeout_secs")]
     pub shutdown_timeout_secs: u64,
 
+    /// PEM certificate chain for serving HTTPS directly. Set together with
+    /// `tls_key_path`; when both are unset the server speaks plain HTTP and
+    /// expects a reverse proxy to terminate TLS.
+    #[serde(default)]
+    pub tls_cert_path: Option<PathBuf>,
+
+    /// PEM private key (PKCS#8, PKCS#1 or SEC1) for `tls_cert_path`.
+    #[serde(default)]
+    pub tls_key_path: Option<PathBuf>,
+
+    /// Listen on this Unix socket instead of `host:port`. The reverse proxy
+    /// connecting to it must send the client address in `X-Real-IP` or
+    /// `X-Forwarded-For`. Cannot be combined with `tls_cert_path`.
+    #[serde(default)]
+    pub unix_socket_path: Option<PathBuf>,
+
+    /// Encodings offered for text responses (pages, JSON, CSS, JavaScript):
+    /// `all` (brotli and gzip), `br`, `gzip` or `off`, e.g. when the reverse
+    /// proxy compresses. Media is never compressed. Default: `all`.
+    #[serde(default)]
+    pub compression: Compression,
+
+    /// Text responses smaller than this (bytes) are sent uncompressed.
+    /// Default: 1024.
+    #[serde(default = "defaults::compression_min_bytes")]
+    pub compression_min_bytes: u16,
+
+    /// Reverse proxies whose `X-Forwarded-For` names the client: addresses
+    /// and CIDR ranges, comma-separated (e.g. `127.0.0.1, 10.0.0.0/8`).
+    /// Requests from anywhere else are attributed to their peer address.
+    /// Default: empty, trusting no proxy.
+    #[serde(default)]
+    pub trusted_proxies: String,
+
+    /// Take the client address from `CF-Connecting-IP` on requests from
+    /// `trusted_proxies`. Enable only when Cloudflare is in front and the
+    /// trusted list holds its ranges. Default: false.
+    #[serde(default)]
+    pub trust_cf_connecting_ip: bool,
+
+    // ── Request limits ────────────────────────────────────────────────────
+    /// Largest request body accepted (KiB), form posts with attachments
+    /// included. Larger bodies are refused with `413` as soon as they pass
+    /// the limit. Default: 12288 (12 MiB).
+    #[serde(default = "defaults::max_body_kb")]
+    pub max_body_kb: u64,
+
+    /// Largest file in a multipart post (KiB), whatever a board's
+    /// `max_file_size` allows. Default: 10240 (10 MiB).
+    #[serde(default = "defaults::max_upload_kb")]
+    pub max_upload_kb: u64,
+
+    /// Longest text field in a multipart post (KiB). Must hold the longest
+    /// `max_post_length` of any board. Default: 64.
+    #[serde(default = "defaults::max_text_field_kb")]
+    pub max_text_field_kb: u64,
+
+    /// Most fields in a multipart post, file parts included. Default: 32.
+    #[serde(default = "defaults::max_form_fields")]
+    pub max_form_fields: usize,
+
     // ── Database ──────────────────────────────────────────────────────────
     /// PostgreSQL database URL. Required.
     pub db_url: SecretString,
@@ -52,10 +134,16 @@ pub struct Settings {
     #[serde(default = "defaults::db_min_connections")]
     pub db_min_connections: u32,
 
-    // ── Redis ─────────────────────────────────────────────────────────────
-    /// Redis URL. Required when compiled with `redis` feature.
+    // ── Cache and rate limiting ───────────────────────────────────────────
+    /// Rate limiter and shared cache backend: `redis` (feature `redis`) or
+    /// `memory`. Default: the first compiled in, `redis` before `memory`.
+    #[serde(default)]
+    pub cache_backend: Option<String>,
+
+    /// Redis URL. Required when the cache backend is `redis`.
     #[cfg(feature = "redis")]
-    pub redis_url: SecretString,
+    #[serde(default)]
+    pub redis_url: Option<SecretString>,
 
     // ── Auth ──────────────────────────────────────────────────────────────
     /// JWT signing secret. Required when compiled with `auth-jwt` feature.
@@ -75,7 +163,9 @@ pub struct Settings {
     ///
     /// When set, `##password` trips are computed as `SHA-256(pepper || "::" || password)`.
     /// This makes tripcodes server-specific — the same password produces different trips
-    /// on different servers. If unset or empty, `##` degrades to `SHA-256("::" || password)`.
+    /// on different servers. If unset or empty, a random pepper is generated on first
+    /// start and kept in the database (`server_secrets`), so trips stay stable across
+    /// restarts but not across databases.
     ///
     /// **Changing this value invalidates all existing `##` tripcodes on the site.**
     /// Set once and do not rotate.
@@ -95,11 +185,23 @@ pub struct Settings {
     pub argon2_p_cost: u32,
 
     // ── Media storage ─────────────────────────────────────────────────────
-    /// S3 configuration. Required when compiled with `media-s3` feature.
+    /// Media storage backend: `local`, `s3` or `ipfs`, each needing its
+    /// feature. Default: the first compiled in, in that order.
+    #[serde(default)]
+    pub media_backend: Option<String>,
+
+    /// S3 configuration. Required when the media backend is `s3`.
     #[cfg(feature = "media-s3")]
-    pub s3: S3Config,
+    #[serde(default)]
+    pub s3: Option<S3Config>,
 
-    /// Local media storage path. Required when compiled with `media-local` feature.
+    /// IPFS node configuration. Used when compiled with `media-ipfs` feature;
+    /// every field has a local-node default.
+    #[cfg(feature = "media-ipfs")]
+    #[serde(default)]
+    pub ipfs: IpfsConfig,
+
+    /// Local media storage path, used by the `local` media backend. Default: `./media`.
     #[cfg(feature = "media-local")]
     #[serde(default = "defaults::media_path")]
     pub media_path: PathBuf,
@@ -108,29 +210,280 @@ pub struct Settings {
     #[serde(default = "defaults::media_url_base")]
     pub media_url_base: String,
 
-    /// Presigned URL TTL for S3 media (seconds). Default: 86400 (24h).
+    /// Presigned URL TTL for S3 media, and the shortest lifetime of a signed
+    /// media URL (seconds). Default: 86400 (24h).
     #[serde(default = "defaults::media_url_ttl_secs")]
     pub media_url_ttl_secs: u64,
 
+    /// Base URL of a CDN in front of media, e.g. `https://cdn.example.com/media`.
+    /// Pages link attachments there instead of to `/media`. Default: unset.
+    #[serde(default)]
+    pub media_cdn_url: Option<String>,
+
+    /// Secret for signing media URLs with expiring HMAC tokens. When set,
+    /// `/media` refuses requests without a current signature. Default: unset.
+    #[serde(default)]
+    pub media_url_signing_key: Option<SecretString>,
+
     // ── Media processing ──────────────────────────────────────────────────
-    /// Generated thumbnail width in pixels. Default: 320.
+    /// Maximum thumbnail width in pixels. Default: 320.
+    /// Boards may override all four thumbnail settings (`BoardConfig::thumbnail`).
     #[serde(default = "defaults::thumbnail_width_px")]
     pub thumbnail_width_px: u32,
 
-    /// Thumbnail quality / compression level. Default: 85.
+    /// Maximum thumbnail height in pixels; 0 = no limit. Default: 0.
+    #[serde(default)]
+    pub thumbnail_height_px: u32,
+
+    /// Thumbnail format: `png`, `jpeg`, or `webp` (lossless). Default: `png`.
+    #[serde(default)]
+    pub thumbnail_format: ThumbnailFormat,
+
+    /// JPEG thumbnail quality (1–100). Default: 85.
     #[serde(default = "defaults::thumbnail_quality")]
     pub thumbnail_quality: u8,
 
+    /// Format large JPEG/PNG originals are transcoded to before storage:
+    /// `off`, `webp` (lossless), or `avif`. Default: `off`.
+    #[serde(default)]
+    pub reencode_format: ReencodeFormat,
+
+    /// AVIF quality (1–100) for `reencode_format = avif`. Default: 75.
+    #[serde(default = "defaults::reencode_quality")]
+    pub reencode_quality: u8,
+
+    /// Originals below this size (KB) keep their format. Default: 512.
+    #[serde(default = "defaults::reencode_min_kb")]
+    pub reencode_min_kb: u64,
+
+    /// Background workers generating image thumbnails. Default: 2.
+    #[serde(default = "defaults::thumbnail_workers")]
+    pub thumbnail_workers: usize,
+
+    /// Pending thumbnail jobs held before uploads fall back to generating
+    /// their thumbnail inline. Default: 64.
+    #[serde(default = "defaults::thumbnail_queue_capacity")]
+    pub thumbnail_queue_capacity: usize,
+
+    /// `ffmpeg` executable used for video poster frames and remuxing
+    /// (feature: `video`). Default: `ffmpeg` (looked up on `PATH`).
+    #[serde(default = "defaults::ffmpeg_path")]
+    pub ffmpeg_path: PathBuf,
+
+    /// `ffprobe` executable used to validate videos and read their duration
+    /// (feature: `video`). Default: `ffprobe` (looked up on `PATH`).
+    #[serde(default = "defaults::ffprobe_path")]
+    pub ffprobe_path: PathBuf,
+
+    /// `vips` executable used to render image thumbnails
+    /// (feature: `thumbs-vips`). Default: `vips` (looked up on `PATH`).
+    #[serde(default = "defaults::vips_path")]
+    pub vips_path: PathBuf,
+
+    /// PDFium shared library (file, or the directory containing it) used to
+    /// render PDF previews (feature: `documents`). `None` searches the system
+    /// library path.
+    #[serde(default)]
+    pub pdfium_library_path: Option<PathBuf>,
+
+    /// How often the orphaned-media sweep runs (seconds). `0` disables the
+    /// scheduled sweep; `rusty-board gc-media` still works. Default: 86400 (24h).
+    #[serde(default = "defaults::media_gc_interval_secs")]
+    pub media_gc_interval_secs: u64,
+
+    /// Stored objects younger than this (seconds) are never swept, protecting
+    /// uploads whose post is still being saved. Default: 3600.
+    #[serde(default = "defaults::media_gc_grace_secs")]
+    pub media_gc_grace_secs: u64,
+
+    // ── Scheduled jobs ────────────────────────────────────────────────────
+    /// How often boards over `max_threads` are pruned, archiving where the
+    /// board has `archive_enabled` (seconds). Posting prunes too; this catches
+    /// boards whose limit was lowered. `0` disables. Default: 3600.
+    #[serde(default = "defaults::prune_interval_secs")]
+    pub prune_interval_secs: u64,
+
+    /// How often expired bans, sessions, staff messages and post tombstones
+    /// are deleted (seconds). `0` disables. Default: 3600.
+    #[serde(default = "defaults::expire_interval_secs")]
+    pub expire_interval_secs: u64,
+
+    /// Days an expired ban is kept before the expiry job deletes it. `0`
+    /// keeps expired bans forever. Default: 90.
+    #[serde(default = "defaults::ban_retention_days")]
+    pub ban_retention_days: u32,
+
+    /// Days a deleted post's tombstone is kept, with its text visible to
+    /// staff, before the expiry job removes it. `0` keeps tombstones
+    /// forever. Default: 30.
+    #[serde(default = "defaults::deleted_post_retention_days")]
+    pub deleted_post_retention_days: u32,
+
+    /// How often the per-board thread and media gauges on `/metrics` are
+    /// recomputed (seconds). `0` disables. Default: 300.
+    #[serde(default = "defaults::stats_interval_secs")]
+    pub stats_interval_secs: u64,
+
+    /// How often recent posts are counted into the hourly and daily buckets
+    /// behind `/board/{slug}/stats` (seconds). `0` disables. Default: 600.
+    #[serde(default = "defaults::stats_rollup_interval_secs")]
+    pub stats_rollup_interval_secs: u64,
+
     // ── IP privacy ────────────────────────────────────────────────────────
     /// How often the IP hash salt rotates (seconds). Default: 86400 (24h).
     #[serde(default = "defaults::ip_salt_rotation_secs")]
     pub ip_salt_rotation_secs: u64,
 
+    // ── IP reputation (feature: spam-dnsbl) ──────────────────────────────
+    /// Comma-separated DNSBL zones queried for each poster IP.
+    /// Default: `zen.spamhaus.org`. Empty disables DNS blocklist lookups.
+    #[serde(default = "defaults::dnsbl_zones")]
+    pub dnsbl_zones: String,
+
+    /// Path to a local mirror of the Tor bulk exit list. `None` disables the
+    /// Tor exit check. The file is re-read at most once per `dnsbl_cache_ttl_secs`.
+    #[serde(default)]
+    pub tor_exit_list_path: Option<PathBuf>,
+
+    /// How long an IP reputation verdict is cached in-process (seconds). Default: 3600.
+    #[serde(default = "defaults::dnsbl_cache_ttl_secs")]
+    pub dnsbl_cache_ttl_secs: u64,
+
+    /// Path to a MaxMind GeoLite2-ASN (or compatible) `.mmdb` database used to
+    /// resolve posters to autonomous systems for ASN bans (feature: `geoip-asn`).
+    /// `None` disables ASN ban enforcement.
+    #[serde(default)]
+    pub asn_db_path: Option<PathBuf>,
+
+    // ── Media classification (feature: nsfw-onnx) ─────────────────────────
+    /// ONNX NSFW model passed to `nsfw_classifier_cmd`. `None` disables
+    /// classification; boards with a `classifier_action` then get no labels.
+    #[serde(default)]
+    pub nsfw_model_path: Option<PathBuf>,
+
+    /// Runner invoked as `<cmd> <model>` with an image on stdin, printing JSON
+    /// labels on stdout. Default: `scripts/nsfw_classify.py`.
+    #[serde(default = "defaults::nsfw_classifier_cmd")]
+    pub nsfw_classifier_cmd: PathBuf,
+
+    // ── Plugins (feature: plugins-wasm) ───────────────────────────────────
+    /// Directory of `.wasm` plugins loaded at startup. `None` loads none.
+    #[serde(default)]
+    pub wasm_plugin_dir: Option<PathBuf>,
+
+    /// Fuel (roughly, instructions) one plugin call may use. Default: 10 000 000.
+    #[serde(default = "defaults::wasm_plugin_fuel")]
+    pub wasm_plugin_fuel: u64,
+
+    /// Memory one plugin instance may grow to, in MB. Default: 16.
+    #[serde(default = "defaults::wasm_plugin_memory_mb")]
+    pub wasm_plugin_memory_mb: usize,
+
+    // ── Lua scripts (feature: plugins-lua) ────────────────────────────────
+    /// Directory of `.lua` post filter scripts loaded at startup. `None` loads none.
+    #[serde(default)]
+    pub lua_script_dir: Option<PathBuf>,
+
+    /// Wall time one script call may run, in milliseconds. Default: 50.
+    #[serde(default = "defaults::lua_script_timeout_ms")]
+    pub lua_script_timeout_ms: u64,
+
+    /// Memory one script call may allocate, in MB. Default: 16.
+    #[serde(default = "defaults::lua_script_memory_mb")]
+    pub lua_script_memory_mb: usize,
+
+    // ── Tracing export (feature: otel) ────────────────────────────────────
+    /// OTLP/HTTP collector base URL (e.g. `http://localhost:4318`); spans are
+    /// sent to `<endpoint>/v1/traces`. `None` disables export.
+    #[serde(default)]
+    pub otlp_endpoint: Option<String>,
+
+    /// `service.name` reported with exported spans. Default: `rusty-board`.
+    #[serde(default = "defaults::otel_service_name")]
+    pub otel_service_name: String,
+
+    /// Fraction of requests whose trace is exported, 0.0–1.0. Default: 1.0.
+    #[serde(default = "defaults::otel_sample_ratio")]
+    pub otel_sample_ratio: f64,
+
+    // ── Error reporting (feature: sentry) ─────────────────────────────────
+    /// Sentry project DSN. Panics, 500 responses and failed scheduled jobs
+    /// are reported there. `None` disables reporting.
+    #[serde(default)]
+    pub sentry_dsn: Option<String>,
+
+    /// Environment tag on reported events (e.g. `production`). Default: none.
+    #[serde(default)]
+    pub sentry_environment: Option<String>,
+
+    // ── Upload hash bans ──────────────────────────────────────────────────
+    /// Largest Hamming distance (bits, of 64) between an image's perceptual
+    /// hash and a banned one that still counts as a match. 0 = exact perceptual
+    /// match only. Default: 6.
+    #[serde(default = "defaults::hash_ban_max_distance")]
+    pub hash_ban_max_distance: u32,
+
     // ── BoardConfig cache ─────────────────────────────────────────────────
     /// In-process BoardConfig cache TTL in seconds. Default: 60.
     #[serde(default = "defaults::config_cache_ttl_secs")]
     pub config_cache_ttl_secs: u64,
 
+    /// Seconds a rendered board index or catalog is served from the
+    /// in-process page cache and the shared cache store (Redis with the
+    /// `redis` feature). 0 disables. Default: 10.
+    #[serde(default = "defaults::render_cache_ttl_secs")]
+    pub render_cache_ttl_secs: u64,
+
+    // ── Event bus ─────────────────────────────────────────────────────────
+    /// Events buffered for each event bus subscriber; one further behind
+    /// misses the oldest. Default: 1024.
+    #[serde(default = "defaults::event_bus_capacity")]
+    pub event_bus_capacity: usize,
+
+    // ── Webhooks (feature: webhooks) ──────────────────────────────────────
+    /// Outgoing webhooks, from `[[webhooks]]` tables in the config file.
+    /// Default: none.
+    #[serde(default)]
+    pub webhooks: Vec<WebhookConfig>,
+
+    /// Attempts per webhook delivery, the first included. Default: 5.
+    #[serde(default = "defaults::webhook_max_attempts")]
+    pub webhook_max_attempts: u32,
+
+    /// Seconds before the first retry of a failed delivery; doubles for each
+    /// later one. Default: 5.
+    #[serde(default = "defaults::webhook_backoff_secs")]
+    pub webhook_backoff_secs: u64,
+
+    /// Time limit of one webhook request, in seconds. Default: 10.
+    #[serde(default = "defaults::webhook_timeout_secs")]
+    pub webhook_timeout_secs: u64,
+
+    // ── Matrix bridge (feature: matrix-bridge) ────────────────────────────
+    /// Threads mirrored into Matrix rooms, from the `[matrix]` table or
+    /// `MATRIX__HOMESERVER` / `MATRIX__ACCESS_TOKEN`. Default: off.
+    #[serde(default)]
+    pub matrix: Option<MatrixConfig>,
+
+    // ── ActivityPub (feature: federation-activitypub) ─────────────────────
+    /// ActivityPub federation of boards with `federation_enabled`, from the
+    /// `[federation]` table or `FEDERATION__BASE_URL` / `FEDERATION__KEY_PATH`.
+    /// Default: off.
+    #[serde(default)]
+    pub federation: Option<FederationConfig>,
+
+    // ── NNTP gateway (feature: nntp) ──────────────────────────────────────
+    /// Read-only newsgroup access to the boards, from the `[nntp]` table or
+    /// `NNTP__DOMAIN` / `NNTP__LISTEN`. Default: off.
+    #[serde(default)]
+    pub nntp: Option<NntpConfig>,
+
+    // ── Email (feature: notify-email) ─────────────────────────────────────
+    /// Report notifications by email, from the `[email]` table or
+    /// `EMAIL__SMTP_HOST` / `EMAIL__FROM` / `EMAIL__RECIPIENTS`. Default: off.
+    #[serde(default)]
+    pub email: Option<EmailConfig>,
+
     // ── Registration ──────────────────────────────────────────────────────
     /// Allow public self-registration at `POST /auth/register`.
     ///
@@ -139,6 +492,12 @@ pub struct Settings {
     /// still be created by an admin via `POST /admin/users`.
     #[serde(default = "defaults::open_registration")]
     pub open_registration: bool,
+
+    // ── Plugins ───────────────────────────────────────────────────────────
+    /// Per-plugin configuration sections, keyed by plugin name. Read with
+    /// `Settings::plugin_config`.
+    #[serde(default)]
+    pub plugins: HashMap<String, config::Value>,
 }
 
 /// S3 / S3-compatible storage credentials and configuration.
@@ -156,24 +515,289 @@ pub struct S3Config {
     pub secret_key: SecretString,
 }
 
+/// One outgoing webhook (`[[webhooks]]`).
+#[derive(Debug, Deserialize)]
+pub struct WebhookConfig {
+    /// Endpoint receiving the POSTs.
+    pub url: String,
+    /// Key signing each request body (`X-Rusty-Board-Signature`).
+    pub secret: SecretString,
+    /// Events to send: `thread_created`, `flag_filed`, `ban_issued`.
+    /// Empty sends all of them.
+    #[serde(default)]
+    pub events: Vec<String>,
+}
+
+/// Matrix bridge account and bridged threads (`[matrix]`).
+#[derive(Debug, Deserialize)]
+pub struct MatrixConfig {
+    /// Homeserver base URL, e.g. `https://matrix.example.org`.
+    pub homeserver: String,
+    /// Access token of the bridge's Matrix account.
+    pub access_token: SecretString,
+    /// Seconds one `/sync` long poll waits for room messages. Default: 30.
+    #[serde(default = "defaults::matrix_sync_timeout_secs")]
+    pub sync_timeout_secs: u64,
+    /// Thread ↔ room pairs (`[[matrix.rooms]]`).
+    #[serde(default)]
+    pub rooms: Vec<MatrixRoomConfig>,
+}
+
+/// One bridged thread (`[[matrix.rooms]]`).
+#[derive(Debug, Deserialize)]
+pub struct MatrixRoomConfig {
+    /// Room ID (`!abc:example.org`) or alias (`#chat:example.org`).
+    pub room: String,
+    /// UUID of the thread mirrored into the room.
+    pub thread_id: String,
+}
+
+/// ActivityPub federation (`[federation]`).
+#[derive(Debug, Deserialize)]
+pub struct FederationConfig {
+    /// Public origin of the site, e.g. `https://board.example.com`. Actor and
+    /// post IDs are built on it, so it must not change once boards have
+    /// followers. Boards of the site serving this host are federated.
+    pub base_url: String,
+    /// PEM file of the RSA key signing outgoing requests; created on first
+    /// start. Default: `federation.pem`.
+    #[serde(default = "defaults::federation_key_path")]
+    pub key_path: PathBuf,
+}
+
+/// NNTP gateway (`[nntp]`).
+#[derive(Debug, Deserialize)]
+pub struct NntpConfig {
+    /// Host name of the site whose boards are served, e.g. `board.example.com`.
+    /// Also the right-hand side of Message-IDs, so it must not change once
+    /// articles have been archived elsewhere.
+    pub domain: String,
+    /// Address the NNTP server listens on. Default: `127.0.0.1:1119`.
+    #[serde(default = "defaults::nntp_listen")]
+    pub listen: String,
+    /// Newsgroup of board `g` is `<group_prefix>.g`. Default: `rusty-board`.
+    #[serde(default = "defaults::nntp_group_prefix")]
+    pub group_prefix: String,
+    /// Readers connected at once; more are refused. Default: 64.
+    #[serde(default = "defaults::nntp_max_connections")]
+    pub max_connections: usize,
+}
+
+/// Report emails to moderators (`[email]`).
+#[derive(Debug, Deserialize)]
+pub struct EmailConfig {
+    /// SMTP server, e.g. `smtp.example.com`.
+    pub smtp_host: String,
+    /// SMTP port. Default: 587.
+    #[serde(default = "defaults::email_smtp_port")]
+    pub smtp_port: u16,
+    /// Encryption of the SMTP connection. Default: `starttls`.
+    #[serde(default)]
+    pub smtp_tls: SmtpTls,
+    /// SMTP login; `None` sends without authenticating.
+    #[serde(default)]
+    pub smtp_username: Option<String>,
+    /// SMTP password of `smtp_username`.
+    #[serde(default)]
+    pub smtp_password: Option<SecretString>,
+    /// Sender, e.g. `rusty-board <board@example.com>`.
+    pub from: String,
+    /// Comma-separated addresses every notification is sent to.
+    pub recipients: String,
+    /// Public origin of the site; emails link to its report queue. Default: none.
+    #[serde(default)]
+    pub site_url: Option<String>,
+    /// When reports are sent. Default: `each`.
+    #[serde(default)]
+    pub reports: ReportEmails,
+    /// Pending reports on one post that send an alert at once, whatever
+    /// `reports` says. 0 disables. Default: 3.
+    #[serde(default = "defaults::email_report_threshold")]
+    pub report_threshold: u64,
+    /// Seconds between two report digests (`reports = "digest"`). Default: 3600.
+    #[serde(default = "defaults::email_digest_interval_secs")]
+    pub digest_interval_secs: u64,
+    /// Time limit of one delivery, in seconds. Default: 30.
+    #[serde(default = "defaults::email_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+impl EmailConfig {
+    /// The recipients, split on commas with blanks removed.
+    pub fn recipient_list(&self) -> Vec<String> {
+        self.recipients.split(',').map(str::trim).filter(|r| !r.is_empty()).map(str::to_owned).collect()
+    }
+}
+
+/// Encryption of the SMTP connection (`[email] smtp_tls`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpTls {
+    /// Plain connection upgraded with `STARTTLS`, usually port 587.
+    #[default]
+    Starttls,
+    /// TLS from the first byte, usually port 465.
+    Tls,
+    /// No encryption; for a relay on the same host only.
+    None,
+}
+
+/// When report emails are sent (`[email] reports`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportEmails {
+    /// One email per report.
+    #[default]
+    Each,
+    /// One email every `digest_interval_secs` listing the new reports.
+    Digest,
+    /// Threshold alerts only.
+    None,
+}
+
+/// Response compression encodings (`COMPRESSION`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Compression {
+    /// Brotli or gzip, whichever the client prefers.
+    #[default]
+    All,
+    /// Brotli only.
+    Br,
+    /// Gzip only.
+    Gzip,
+    /// No compression.
+    Off,
+}
+
+impl Compression {
+    /// Whether gzip is offered.
+    pub fn gzip(self) -> bool {
+        matches!(self, Self::All | Self::Gzip)
+    }
+
+    /// Whether brotli is offered.
+    pub fn br(self) -> bool {
+        matches!(self, Self::All | Self::Br)
+    }
+}
+
+/// Encoding of generated thumbnails (`THUMBNAIL_FORMAT`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThumbnailFormat {
+    /// oxipng-compressed PNG.
+    #[default]
+    Png,
+    /// JPEG at `thumbnail_quality`.
+    Jpeg,
+    /// Lossless WebP.
+    Webp,
+}
+
+/// Target format for re-encoded image originals (`REENCODE_FORMAT`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReencodeFormat {
+    /// Store originals in the uploaded format.
+    #[default]
+    Off,
+    /// Lossless WebP.
+    Webp,
+    /// Lossy AVIF at `reencode_quality`.
+    Avif,
+}
+
+/// IPFS (Kubo) node used for media storage.
+#[derive(Debug, Deserialize)]
+pub struct IpfsConfig {
+    /// Kubo RPC API endpoint. Keep it on localhost; the API is unauthenticated.
+    #[serde(default = "defaults::ipfs_api_url")]
+    pub api_url: String,
+    /// Gateway prefix for media links (`<gateway_url>/ipfs/<cid>`).
+    #[serde(default = "defaults::ipfs_gateway_url")]
+    pub gateway_url: String,
+    /// MFS directory uploads are written under.
+    #[serde(default = "defaults::ipfs_mfs_root")]
+    pub mfs_root: String,
+}
+
+impl Default for IpfsConfig {
+    fn default() -> Self {
+        Self {
+            api_url:     defaults::ipfs_api_url(),
+            gateway_url: defaults::ipfs_gateway_url(),
+            mfs_root:    defaults::ipfs_mfs_root(),
+        }
+    }
+}
+
 impl Settings {
-    /// Load settings from environment variables and an optional `.env` file.
+    /// Certificate and key paths when HTTPS is configured, `None` for plain HTTP.
     ///
-    /// Reads `.env` if present (does not fail if absent). Environment variables
-    /// override `.env` file values.
+    /// Returns an error naming the missing setting when only one is set.
+    pub fn tls_paths(&self) -> Result<Option<(&Path, &Path)>, config::ConfigError> {
+        match (&self.tls_cert_path, &self.tls_key_path) {
+            (Some(cert), Some(key)) => Ok(Some((cert, key))),
+            (None, None) => Ok(None),
+            (Some(_), None) => Err(config::ConfigError::Message("TLS_CERT_PATH is set but TLS_KEY_PATH is not".into())),
+            (None, Some(_)) => Err(config::ConfigError::Message("TLS_KEY_PATH is set but TLS_CERT_PATH is not".into())),
+        }
+    }
+
+    /// The configured DNSBL zones, split on commas with blanks removed.
+    pub fn dnsbl_zone_list(&self) -> Vec<String> {
+        self.dnsbl_zones
+            .split(',')
+            .map(str::trim)
+            .filter(|z| !z.is_empty())
+            .map(str::to_owned)
+            .collect()
+    }
+
+    /// Load settings from environment variables and an optional `.env` file,
+    /// over the config file if one is found.
     ///
-    /// Returns an error if required variables are missing or cannot be parsed.
+    /// Equivalent to `load_layered(None, &[])`.
     pub fn load() -> Result<Self, config::ConfigError> {
+        Self::load_layered(None, &[])
+    }
+
+    /// Load settings from every layer, lowest precedence first:
+    ///
+    /// 1. The config file: `file`, else `$RUSTY_BOARD_CONFIG`, else
+    ///    `rusty-board.toml` if present. An explicitly named file must exist.
+    /// 2. `.env`, if present (does not override variables already set).
+    /// 3. Environment variables.
+    /// 4. `overrides` — `(key, value)` pairs from `--set key=value`, where
+    ///    `key` is a lower-case field name with `.` between levels
+    ///    (e.g. `port`, `s3.bucket`).
+    ///
+    /// Returns an error if required values are missing or cannot be parsed.
+    pub fn load_layered(file: Option<&Path>, overrides: &[(String, String)]) -> Result<Self, config::ConfigError> {
         // Load .env file if present (silently skip if absent)
         let _ = dotenvy::dotenv();
 
-        config::Config::builder()
+        let file_source = match file.map(Path::to_path_buf).or_else(|| std::env::var_os(CONFIG_FILE_ENV).map(PathBuf::from)) {
+            Some(path) => config::File::from(path).format(config::FileFormat::Toml).required(true),
+            None => config::File::new(DEFAULT_CONFIG_FILE, config::FileFormat::Toml).required(false),
+        };
+
+        let mut builder = config::Config::builder()
+            .add_source(file_source)
             .add_source(
                 config::Environment::default()
                     .separator("__"),  // double underscore separates nested segments
-            )
-            .build()?
-            .try_deserialize()
+            );
+        for (key, value) in overrides {
+            builder = builder.set_override(key.as_str(), value.as_str())?;
+        }
+        builder.build()?.try_deserialize()
+    }
+
+    /// Deserialize the `[plugins.<name>]` section, or `None` if there is none.
+    pub fn plugin_config<T: DeserializeOwned>(&self, name: &str) -> Result<Option<T>, config::ConfigError> {
+        self.plugins.get(name).cloned().map(config::Value::try_deserialize).transpose()
     }
 }
 
@@ -131,9 +131,10 @@ pub enum ValidationError {
     },
 
     /// An uploaded file exceeds the board's maximum allowed size.
-    #[error("file size {size_kb}KB exceeds board maximum {max_kb}KB")]
+    #[error("file is too large ({size_kb}KB) — this board accepts files up to {max_kb}KB")]
     FileTooLarge {
-        /// The actual file size in kilobytes.
+        /// The file size in kilobytes. When an upload is aborted mid-stream this
+        /// is the amount received so far, i.e. a lower bound.
         size_kb: u32,
         /// The board's configured maximum file size in kilobytes.
         max_kb: u32,
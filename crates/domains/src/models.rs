@@ -197,10 +197,53 @@ impl std::fmt::Display for IpHash {
 pub struct MediaKey(pub String);
 
 impl MediaKey {
+    /// Prefix of the pre-rendered pages of archived threads. Nothing under it
+    /// is an upload, so the orphaned-media sweep leaves it alone.
+    pub const ARCHIVE_PREFIX: &'static str = "archive/";
+
+    /// Prefix of board banner images. Banners are referenced by the
+    /// `board_banners` table rather than by a post, so the orphaned-media
+    /// sweep leaves them alone too.
+    pub const BANNER_PREFIX: &'static str = "banners/";
+
     /// Create a `MediaKey` from a path string.
     pub fn new(key: impl Into<String>) -> Self {
         Self(key.into())
     }
+
+    /// Key of an archived thread's static page: `archive/{board}/{thread}.{ext}`.
+    pub fn archived_thread(board_id: BoardId, thread_id: ThreadId, ext: &str) -> Self {
+        Self(format!("{}{}/{}.{ext}", Self::ARCHIVE_PREFIX, board_id.0, thread_id.0))
+    }
+
+    /// Whether this key holds an archived thread's static page.
+    pub fn is_archive(&self) -> bool {
+        self.0.starts_with(Self::ARCHIVE_PREFIX)
+    }
+
+    /// Key of a board banner image: `banners/{board}/{banner}.{ext}`.
+    pub fn banner(board_id: BoardId, banner_id: BannerId, ext: &str) -> Self {
+        Self(format!("{}{}/{}.{ext}", Self::BANNER_PREFIX, board_id.0, banner_id.0))
+    }
+
+    /// Whether this key holds a board banner image.
+    pub fn is_banner(&self) -> bool {
+        self.0.starts_with(Self::BANNER_PREFIX)
+    }
+
+    /// This key stored under `prefix/`, e.g. a site's `Site::media_prefix`.
+    /// An empty prefix leaves the key unchanged.
+    pub fn with_prefix(&self, prefix: &str) -> Self {
+        match prefix.trim_matches('/') {
+            "" => self.clone(),
+            prefix => Self(format!("{prefix}/{}", self.0)),
+        }
+    }
+
+    /// The directory part of the key: everything before the last `/`, or `""`.
+    pub fn prefix(&self) -> &str {
+        self.0.rsplit_once('/').map_or("", |(dir, _)| dir)
+    }
 }
 
 impl std::fmt::Display for MediaKey {
@@ -463,6 +506,10 @@ impl FromStr for Role {
 pub struct Board {
     /// Unique identifier.
     pub id: BoardId,
+    /// The site this board is served on. Slugs are unique per site.
+    /// Records written before sites existed belong to the default site.
+    #[serde(default)]
+    pub site_id: SiteId,
     /// URL-safe identifier matching `^[a-z0-9_-]{1,16}$`.
     pub slug: Slug,
     /// Human-readable display name (1–64 characters).
@@ -513,6 +560,9 @@ pub struct ThreadSummary {
     pub op_body: String,
     /// The first attachment thumbnail key, if any.
     pub thumbnail_key: Option<MediaKey>,
+    /// Whether that first attachment is spoilered — render a placeholder instead.
+    #[serde(default)]
+    pub thumbnail_spoiler: bool,
     /// Number of replies.
     pub reply_count: u32,
     /// Whether the thread is sticky.
@@ -525,6 +575,12 @@ pub struct ThreadSummary {
     pub op_name: Option<String>,
     /// OP tripcode string, or `None`.
     pub op_tripcode: Option<String>,
+    /// OP subject, or `None`.
+    #[serde(default)]
+    pub op_subject: Option<String>,
+    /// OP capcode role display string, or `None`.
+    #[serde(default)]
+    pub op_capcode: Option<String>,
     /// When the OP post was created.
     pub op_created_at: DateTime<Utc>,
     /// Board-scoped sequential post number of the OP.
@@ -533,6 +589,52 @@ pub struct ThreadSummary {
     pub op_ip_hash: IpHash,
 }
 
+/// Order of the threads in a board's catalog. Sticky threads come first in
+/// every order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CatalogSort {
+    /// Most recently bumped first.
+    #[default]
+    Bump,
+    /// Most replies first.
+    ReplyCount,
+    /// Newest thread first.
+    Creation,
+    /// A fresh shuffle on every request.
+    Random,
+}
+
+impl CatalogSort {
+    /// Every catalog order, in the order the catalog offers them.
+    pub const ALL: [CatalogSort; 4] =
+        [CatalogSort::Bump, CatalogSort::ReplyCount, CatalogSort::Creation, CatalogSort::Random];
+}
+
+impl std::fmt::Display for CatalogSort {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CatalogSort::Bump => f.write_str("bump"),
+            CatalogSort::ReplyCount => f.write_str("reply_count"),
+            CatalogSort::Creation => f.write_str("creation"),
+            CatalogSort::Random => f.write_str("random"),
+        }
+    }
+}
+
+impl FromStr for CatalogSort {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "bump" => Ok(CatalogSort::Bump),
+            "reply_count" => Ok(CatalogSort::ReplyCount),
+            "creation" => Ok(CatalogSort::Creation),
+            "random" => Ok(CatalogSort::Random),
+            other => Err(format!("unknown CatalogSort: {other}")),
+        }
+    }
+}
+
 /// A post — the atomic unit of content in rusty-board.
 ///
 /// Posts belong to a thread and are ordered by creation time within the thread.
@@ -552,7 +654,8 @@ pub struct Post {
     pub name: Option<String>,
     /// Tripcode derived from a password hash. `None` unless tripcodes are enabled.
     pub tripcode: Option<String>,
-    /// Email field. `Some("sage")` disables thread bump; otherwise unused.
+    /// Email/options field: space-separated options such as `sage` (do not
+    /// bump) and `nonoko` (return to the board after posting).
     pub email: Option<String>,
     /// When this post was created.
     pub created_at: DateTime<Utc>,
@@ -560,6 +663,173 @@ pub struct Post {
     pub post_number: u64,
     /// Pinned posts are excluded from cycle-mode pruning.
     pub pinned: bool,
+    /// Labels attached by operator post filters at submission, e.g. `link`
+    /// or `needs-review`. Shown to staff only.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Results of `!roll` / `!fortune` lines, computed once at submission on
+    /// boards with `commands_enabled`. Empty for most posts.
+    #[serde(default)]
+    pub commands: Vec<CommandResult>,
+    /// Thread subject. Only an OP has one; `None` on replies.
+    #[serde(default)]
+    pub subject: Option<String>,
+    /// Staff capcode role display string, e.g. `"Admin"`. Set only when the
+    /// poster's session role allowed it; kept apart from `tripcode` so no
+    /// tripcode can pass for one.
+    #[serde(default)]
+    pub capcode: Option<String>,
+    /// Set once the post is deleted: it stays in its thread as a tombstone,
+    /// keeping its number, until the purge job removes it.
+    #[serde(default)]
+    pub deleted: Option<Tombstone>,
+    /// When staff last edited the post's body or subject. The earlier
+    /// versions are kept as `PostRevision`s.
+    #[serde(default)]
+    pub edited_at: Option<DateTime<Utc>>,
+}
+
+impl Post {
+    /// Whether the post is a tombstone.
+    pub fn is_deleted(&self) -> bool {
+        self.deleted.is_some()
+    }
+
+    /// Clear what a tombstone must not show — body, name, tripcode, email,
+    /// subject, capcode, tags and command results — keeping its number and
+    /// place in the thread. A live post is left alone.
+    pub fn redact(&mut self) {
+        if !self.is_deleted() {
+            return;
+        }
+        self.body.clear();
+        self.name = None;
+        self.tripcode = None;
+        self.email = None;
+        self.subject = None;
+        self.capcode = None;
+        self.tags.clear();
+        self.commands.clear();
+    }
+}
+
+/// Who deleted a post.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DeletedBy {
+    /// The poster, from the IP hash the post was made from.
+    User,
+    /// A moderator.
+    Staff,
+}
+
+impl std::fmt::Display for DeletedBy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeletedBy::User  => f.write_str("user"),
+            DeletedBy::Staff => f.write_str("staff"),
+        }
+    }
+}
+
+impl FromStr for DeletedBy {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "user"  => Ok(DeletedBy::User),
+            "staff" => Ok(DeletedBy::Staff),
+            other   => Err(format!("unknown DeletedBy: {other}")),
+        }
+    }
+}
+
+/// The deletion of a `Post`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Tombstone {
+    /// Who deleted the post.
+    pub by: DeletedBy,
+    /// When it was deleted; the purge job counts its retention from here.
+    pub at: DateTime<Utc>,
+}
+
+/// A post's body and subject as they were before a staff edit.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PostRevision {
+    /// Unique identifier.
+    pub id: Uuid,
+    /// The edited post.
+    pub post_id: PostId,
+    /// The body before the edit.
+    pub body: String,
+    /// The subject before the edit; `None` on replies.
+    pub subject: Option<String>,
+    /// The staff account that made the edit.
+    pub edited_by: UserId,
+    /// When the edit was made.
+    pub edited_at: DateTime<Utc>,
+}
+
+/// The outcome of a post command, stored with the post so every reader sees
+/// the same roll.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CommandResult {
+    /// `!roll NdS+M` — `rolls` holds each die in order; `total` includes the modifier.
+    Roll { dice: u32, sides: u32, modifier: i32, rolls: Vec<u32>, total: i64 },
+    /// `!fortune` — one line from the built-in list.
+    Fortune { fortune: String },
+}
+
+impl CommandResult {
+    /// `"roll"` or `"fortune"`, for CSS classes.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Roll { .. } => "roll",
+            Self::Fortune { .. } => "fortune",
+        }
+    }
+}
+
+impl std::fmt::Display for CommandResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Roll { dice, sides, modifier, rolls, total } => {
+                write!(f, "Rolled {dice}d{sides}")?;
+                if *modifier != 0 {
+                    write!(f, "{modifier:+}")?;
+                }
+                let rolls: Vec<String> = rolls.iter().map(u32::to_string).collect();
+                write!(f, ": {} = {total}", rolls.join(", "))
+            }
+            Self::Fortune { fortune } => write!(f, "Your fortune: {fortune}"),
+        }
+    }
+}
+
+/// The post numbers in use on a board: its lowest and highest, and how many
+/// posts it has. Numbers of deleted posts leave gaps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PostNumberRange {
+    pub low:   u64,
+    pub high:  u64,
+    pub count: u64,
+}
+
+/// A thread on the `/all` overboard catalog: its catalog summary with the
+/// board it is on.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OverboardThread {
+    /// The thread as shown in a board catalog.
+    pub summary: ThreadSummary,
+    /// Slug of the board the thread is on.
+    pub board_slug: String,
+    /// Title of that board.
+    pub board_title: String,
+}
+
+impl OverboardThread {
+    /// Threads per page of the `/all` catalog.
+    pub const PAGE_SIZE: u32 = 50;
 }
 
 /// A lightweight post entry for the overboard view, enriched with board context.
@@ -586,6 +856,54 @@ pub struct OverboardPost {
     pub created_at: DateTime<Utc>,
     /// Board-scoped sequential post number, same as `Post::post_number`.
     pub post_number: u64,
+    /// Thread subject when the post is an OP, same as `Post::subject`.
+    #[serde(default)]
+    pub subject: Option<String>,
+    /// Staff capcode, same as `Post::capcode`.
+    #[serde(default)]
+    pub capcode: Option<String>,
+}
+
+/// Site-wide activity for the homepage: the latest posts across the site's
+/// boards and a few totals.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SiteActivity {
+    /// The most recent posts, newest first.
+    pub recent_posts: Vec<OverboardPost>,
+    /// Threads on the site's boards.
+    pub total_threads: u64,
+    /// Posts on the site's boards, opening posts included.
+    pub total_posts: u64,
+    /// Posts made in the last 24 hours.
+    pub posts_last_day: u64,
+}
+
+/// What `PostRepository::delete_by_ip_on_board` removed.
+#[derive(Debug, Clone, Default)]
+pub struct PurgedPosts {
+    /// Every removed post with its thread, replies of removed threads included.
+    pub posts: Vec<(ThreadId, PostId)>,
+    /// Threads removed because their opening post was among the matches.
+    pub threads: Vec<ThreadId>,
+    /// Attachments of the removed posts. Their rows are gone; their files are not.
+    pub attachments: Vec<Attachment>,
+}
+
+/// Posts to reparent from one thread to another, as planned by the
+/// moderation service and applied by `PostRepository::move_posts`.
+#[derive(Debug, Clone)]
+pub struct PostMove {
+    /// The thread the posts are in now.
+    pub from: ThreadId,
+    /// The thread they go to.
+    pub to: ThreadId,
+    /// When splitting, the thread to create as `to` before moving; its
+    /// opening post is one of `post_ids`.
+    pub new_thread: Option<Thread>,
+    /// The posts to move. Their numbers and timestamps are kept.
+    pub post_ids: Vec<PostId>,
+    /// New bodies for the posts, on either side, whose quote links the move rewrote.
+    pub bodies: Vec<(PostId, String)>,
 }
 
 /// A media attachment associated with a post.
@@ -612,6 +930,194 @@ pub struct Attachment {
     pub thumbnail_key: Option<MediaKey>,
     /// Whether this attachment is marked as a spoiler (blurred until clicked).
     pub spoiler: bool,
+    /// Playback length in milliseconds for audio/video. `None` for still images.
+    #[serde(default)]
+    pub duration_ms: Option<u32>,
+    /// Number of pages for documents (PDF). `None` for other media.
+    #[serde(default)]
+    pub page_count: Option<u32>,
+    /// Hex MD5 of the original file, matched against hash bans. `None` for
+    /// attachments uploaded before MD5s were recorded.
+    #[serde(default)]
+    pub md5: Option<String>,
+    /// 64-bit perceptual (difference) hash of images, matched against hash bans
+    /// by Hamming distance. `None` for non-images.
+    #[serde(default)]
+    pub phash: Option<u64>,
+    /// Upload-time facts for the file info line. `None` for attachments
+    /// uploaded before they were recorded.
+    #[serde(default)]
+    pub meta: Option<MediaMeta>,
+}
+
+/// Encoding of generated thumbnails.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThumbnailFormat {
+    /// oxipng-compressed PNG. Quality is ignored.
+    #[default]
+    Png,
+    /// JPEG at `ThumbnailSpec::quality`. Transparency is flattened.
+    Jpeg,
+    /// Lossless WebP. Quality is ignored.
+    Webp,
+}
+
+impl ThumbnailFormat {
+    /// MIME type of thumbnails in this format.
+    pub fn mime(self) -> &'static str {
+        match self {
+            Self::Png  => "image/png",
+            Self::Jpeg => "image/jpeg",
+            Self::Webp => "image/webp",
+        }
+    }
+
+    /// File extension used in thumbnail keys.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Png  => "png",
+            Self::Jpeg => "jpg",
+            Self::Webp => "webp",
+        }
+    }
+
+    /// The format of a stored thumbnail, read from its key's extension.
+    /// Unknown extensions are treated as PNG, the historical format.
+    pub fn of_key(key: &MediaKey) -> Self {
+        match key.0.rsplit_once('.').map(|(_, ext)| ext) {
+            Some("jpg") => Self::Jpeg,
+            Some("webp") => Self::Webp,
+            _ => Self::Png,
+        }
+    }
+}
+
+impl std::fmt::Display for ThumbnailFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.extension())
+    }
+}
+
+impl std::str::FromStr for ThumbnailFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "png"          => Ok(Self::Png),
+            "jpg" | "jpeg" => Ok(Self::Jpeg),
+            "webp"         => Ok(Self::Webp),
+            other => Err(format!("unknown thumbnail format `{other}` (expected png, jpeg or webp)")),
+        }
+    }
+}
+
+/// Size and encoding of generated thumbnails.
+///
+/// The operator sets the global spec (`THUMBNAIL_*` settings); a board may
+/// override it with `BoardConfig::thumbnail`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThumbnailSpec {
+    /// Thumbnails are scaled down to at most this many pixels wide.
+    pub max_width:  u32,
+    /// Thumbnails are scaled down to at most this many pixels tall. `0` = no limit.
+    #[serde(default)]
+    pub max_height: u32,
+    /// Output encoding.
+    #[serde(default)]
+    pub format:     ThumbnailFormat,
+    /// JPEG quality, 1–100.
+    #[serde(default = "default_thumbnail_quality")]
+    pub quality:    u8,
+}
+
+fn default_thumbnail_quality() -> u8 {
+    85
+}
+
+impl ThumbnailSpec {
+    /// Bounding box for scaling: `max_height = 0` becomes unbounded.
+    pub fn bounds(&self) -> (u32, u32) {
+        let height = if self.max_height == 0 { u32::MAX } else { self.max_height };
+        (self.max_width.max(1), height)
+    }
+}
+
+impl Default for ThumbnailSpec {
+    /// 320px wide, any height, PNG.
+    fn default() -> Self {
+        Self { max_width: 320, max_height: 0, format: ThumbnailFormat::Png, quality: default_thumbnail_quality() }
+    }
+}
+
+/// What the poster uploaded, as shown in the imageboard file info line:
+/// `filename.jpg (123 KB, 1920x1080)`.
+///
+/// The stored MIME type lives on `Attachment::mime`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MediaMeta {
+    /// The filename as uploaded, without any directory components.
+    pub original_filename: String,
+    /// Size of the stored original in bytes.
+    pub size_bytes: u64,
+    /// Pixel width of images and video. `None` for other media.
+    pub width: Option<u32>,
+    /// Pixel height of images and video. `None` for other media.
+    pub height: Option<u32>,
+    /// MIME type of the upload when the original was re-encoded before
+    /// storage (so `Attachment::mime` differs). `None` when stored as uploaded.
+    #[serde(default)]
+    pub original_mime: Option<String>,
+    /// Labels assigned by the `MediaClassifier` at upload time. Empty when no
+    /// classifier ran or it found nothing.
+    #[serde(default)]
+    pub labels: Vec<MediaLabel>,
+}
+
+/// One label a `MediaClassifier` assigned to an upload, e.g. `nsfw` at 0.97.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MediaLabel {
+    /// Classifier-defined label name.
+    pub label: String,
+    /// Confidence from 0.0 to 1.0.
+    pub score: f32,
+}
+
+impl MediaMeta {
+    /// Longest `original_filename` kept, in characters.
+    pub const MAX_FILENAME_CHARS: usize = 255;
+
+    /// Reduce a client-supplied filename to its final path component, capped
+    /// at `MAX_FILENAME_CHARS`. Falls back to `"file"` when nothing is left.
+    pub fn sanitize_filename(raw: &str) -> String {
+        let name: String = raw
+            .rsplit(['/', '\\'])
+            .next()
+            .unwrap_or_default()
+            .chars()
+            .filter(|c| !c.is_control())
+            .take(Self::MAX_FILENAME_CHARS)
+            .collect();
+        let name = name.trim();
+        if name.is_empty() { "file".to_owned() } else { name.to_owned() }
+    }
+
+    /// Human-readable size: bytes below 1 KB, whole KB below 1 MB, else MB
+    /// with one decimal.
+    pub fn display_size(&self) -> String {
+        const KB: u64 = 1024;
+        const MB: u64 = 1024 * 1024;
+        match self.size_bytes {
+            n if n < KB => format!("{n} B"),
+            n if n < MB => format!("{} KB", n.div_ceil(KB)),
+            n => format!("{:.1} MB", n as f64 / MB as f64),
+        }
+    }
+
+    /// `"WIDTHxHEIGHT"` when both dimensions are known.
+    pub fn dimensions(&self) -> Option<String> {
+        Some(format!("{}x{}", self.width?, self.height?))
+    }
 }
 
 /// An IP ban record.
@@ -632,6 +1138,88 @@ pub struct Ban {
     pub expires_at: Option<DateTime<Utc>>,
     /// When this ban was issued.
     pub created_at: DateTime<Utc>,
+    /// A shadowban: posts are accepted as usual but shown only to their
+    /// poster and to staff, so bots have no rejection to adapt to.
+    #[serde(default)]
+    pub shadow: bool,
+}
+
+/// A moderator's warning to the poster behind an IP hash.
+///
+/// Shown on the poster's next page load; they cannot post again until they
+/// acknowledge it. Warnings share `BanId`s and their storage neighbourhood
+/// with bans.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Warning {
+    /// Unique identifier.
+    pub id: BanId,
+    /// The warned IP hash.
+    pub ip_hash: IpHash,
+    /// The post the warning is about, if any.
+    pub post_id: Option<PostId>,
+    /// The user account that issued this warning.
+    pub issued_by: UserId,
+    /// Warning text shown to the poster.
+    pub reason: String,
+    /// When this warning was issued.
+    pub created_at: DateTime<Utc>,
+    /// When the poster acknowledged it. `None` = still pending.
+    pub acknowledged_at: Option<DateTime<Utc>>,
+}
+
+/// Who a read of posts is for, which decides whether posts under a shadowban
+/// (see `Ban::shadow`) are included.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Viewer {
+    /// Staff see every post.
+    Staff,
+    /// A visitor sees shadowbanned posts only when they were made from this IP hash.
+    Poster(IpHash),
+    /// A page shared by every visitor, such as a cached board index.
+    Public,
+}
+
+/// A ban on an uploaded file's content.
+///
+/// `md5` rejects byte-identical re-uploads; `phash` rejects images whose
+/// perceptual hash lies within the configured Hamming distance, catching
+/// re-encoded or resized copies. At least one of the two is set.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HashBan {
+    /// Unique identifier.
+    pub id: BanId,
+    /// Hex MD5 of the banned file.
+    pub md5: Option<String>,
+    /// Perceptual hash of the banned image.
+    pub phash: Option<u64>,
+    /// The user account that issued this ban.
+    pub banned_by: UserId,
+    /// Reason displayed to posters whose upload matches.
+    pub reason: String,
+    /// When this ban expires. `None` = permanent ban.
+    pub expires_at: Option<DateTime<Utc>>,
+    /// When this ban was issued.
+    pub created_at: DateTime<Utc>,
+}
+
+/// A ban on an entire autonomous system (e.g. a VPN or hosting provider).
+///
+/// Matched by resolving the poster's raw IP to its ASN at post time; the ASN
+/// itself is not personal data, so it is stored as-is.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AsnBan {
+    /// Unique identifier.
+    pub id: BanId,
+    /// The banned autonomous system number (e.g. `9009`).
+    pub asn: u32,
+    /// The user account that issued this ban.
+    pub banned_by: UserId,
+    /// Reason displayed to posters from this network.
+    pub reason: String,
+    /// When this ban expires. `None` = permanent ban.
+    pub expires_at: Option<DateTime<Utc>>,
+    /// When this ban was issued.
+    pub created_at: DateTime<Utc>,
 }
 
 /// A flag (report) submitted by a visitor against a post.
@@ -709,6 +1297,27 @@ pub struct User {
     pub created_at: DateTime<Utc>,
 }
 
+/// An account's TOTP second factor (RFC 6238).
+///
+/// Created pending by enrollment; login asks for a code only once the user
+/// has confirmed one (`enabled_at` set).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StaffTotp {
+    /// The account this factor belongs to.
+    pub user_id: UserId,
+    /// Base32 shared secret, as given to the authenticator app.
+    pub secret: String,
+    /// When enrollment was confirmed. `None` = pending, not yet enforced.
+    pub enabled_at: Option<DateTime<Utc>>,
+    /// SHA-256 hex of each unused recovery code.
+    pub recovery_codes: Vec<String>,
+    /// The last step a code was accepted for; codes for it or earlier are refused.
+    pub last_step: Option<i64>,
+    /// Set after too many wrong codes; no code is checked until it passes.
+    /// Maintained by `TotpRepository::claim_attempt`; `save` leaves it alone.
+    pub locked_until: Option<DateTime<Utc>>,
+}
+
 /// An audit log entry recording a moderation action.
 ///
 /// Every privileged action (delete post, ban IP, resolve flag, etc.) writes
@@ -727,18 +1336,33 @@ pub struct AuditEntry {
     pub target_id: Option<Uuid>,
     /// The kind of entity targeted.
     pub target_type: Option<String>,
+    /// The board the action was taken on. `None` for site-wide actions (bans).
+    #[serde(default)]
+    pub board_id: Option<BoardId>,
+    /// The reason the actor gave, if the action takes one.
+    #[serde(default)]
+    pub reason: Option<String>,
     /// Action-specific detail payload (JSON).
     pub details: Option<serde_json::Value>,
     /// When this action occurred.
     pub created_at: DateTime<Utc>,
 }
 
+impl AuditEntry {
+    /// The entry as a board's public log shows it: no actor, IP hash or details.
+    pub fn redacted(self) -> Self {
+        Self { actor_id: None, actor_ip_hash: None, details: None, ..self }
+    }
+}
+
 /// The kind of moderation action recorded in an audit log entry.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum AuditAction {
     /// A post was deleted by a moderator or admin.
     DeletePost,
+    /// A post's body or subject was edited by a moderator or admin.
+    EditPost,
     /// A thread (and all its posts) was deleted by a moderator or admin.
     DeleteThread,
     /// A thread was stickied or un-stickied.
@@ -749,8 +1373,18 @@ pub enum AuditAction {
     CycleThread,
     /// A post's pinned status was set (only meaningful in cycle threads).
     PinPost,
+    /// Posts were moved to another thread, or split off into a new one.
+    MovePosts,
     /// An IP hash was banned.
     BanIp,
+    /// An IP hash was shadowbanned. Never shown in a public log.
+    ShadowbanIp,
+    /// The poster behind an IP hash was warned.
+    WarnIp,
+    /// An autonomous system (ASN) was banned.
+    BanAsn,
+    /// An upload hash (MD5 and/or perceptual) was banned.
+    BanHash,
     /// An active ban was manually expired before its scheduled expiry.
     ExpireBan,
     /// A content flag was resolved (approved or rejected).
@@ -771,12 +1405,18 @@ impl std::fmt::Display for AuditAction {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let s = match self {
             AuditAction::DeletePost        => "delete_post",
+            AuditAction::EditPost          => "edit_post",
             AuditAction::DeleteThread      => "delete_thread",
             AuditAction::StickyThread      => "sticky_thread",
             AuditAction::CloseThread       => "close_thread",
             AuditAction::CycleThread       => "cycle_thread",
             AuditAction::PinPost           => "pin_post",
+            AuditAction::MovePosts         => "move_posts",
             AuditAction::BanIp             => "ban_ip",
+            AuditAction::ShadowbanIp       => "shadowban_ip",
+            AuditAction::WarnIp            => "warn_ip",
+            AuditAction::BanAsn            => "ban_asn",
+            AuditAction::BanHash           => "ban_hash",
             AuditAction::ExpireBan         => "expire_ban",
             AuditAction::ResolveFlag       => "resolve_flag",
             AuditAction::UpdateBoardConfig => "update_board_config",
@@ -789,17 +1429,41 @@ impl std::fmt::Display for AuditAction {
     }
 }
 
+impl AuditAction {
+    /// Actions a board's public log lists. Warnings, shadowbans and the rest
+    /// stay in the staff log.
+    pub const PUBLIC: &'static [AuditAction] = &[
+        AuditAction::DeletePost,
+        AuditAction::EditPost,
+        AuditAction::DeleteThread,
+        AuditAction::StickyThread,
+        AuditAction::CloseThread,
+        AuditAction::CycleThread,
+        AuditAction::PinPost,
+        AuditAction::MovePosts,
+        AuditAction::BanIp,
+        AuditAction::BanHash,
+        AuditAction::UpdateBoardConfig,
+    ];
+}
+
 impl std::str::FromStr for AuditAction {
     type Err = String;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
             "delete_post"        => Ok(AuditAction::DeletePost),
+            "edit_post"          => Ok(AuditAction::EditPost),
             "delete_thread"      => Ok(AuditAction::DeleteThread),
             "sticky_thread"      => Ok(AuditAction::StickyThread),
             "close_thread"       => Ok(AuditAction::CloseThread),
             "cycle_thread"       => Ok(AuditAction::CycleThread),
             "pin_post"           => Ok(AuditAction::PinPost),
+            "move_posts"         => Ok(AuditAction::MovePosts),
             "ban_ip"             => Ok(AuditAction::BanIp),
+            "shadowban_ip"       => Ok(AuditAction::ShadowbanIp),
+            "warn_ip"            => Ok(AuditAction::WarnIp),
+            "ban_asn"            => Ok(AuditAction::BanAsn),
+            "ban_hash"           => Ok(AuditAction::BanHash),
             "expire_ban"         => Ok(AuditAction::ExpireBan),
             "resolve_flag"       => Ok(AuditAction::ResolveFlag),
             "update_board_config" => Ok(AuditAction::UpdateBoardConfig),
@@ -812,83 +1476,408 @@ impl std::str::FromStr for AuditAction {
     }
 }
 
-// ─── BoardConfig ─────────────────────────────────────────────────────────────
+// ─── DnsblAction ─────────────────────────────────────────────────────────────
 
-/// The runtime behaviour surface for a board.
-///
-/// One row exists in `board_configs` per board. Operators change these fields
-/// through the admin or board-owner dashboard; no redeployment is required.
-///
-/// # INVARIANT
-/// `BoardConfig` is the **only** path from a dashboard control to service
-/// behaviour. Services branch on its fields. They never read environment
-/// variables, feature flags, or global state.
-///
-/// # Caching
-/// `BoardConfig` is cached in-process with a 60-second TTL in a
-/// `DashMap<BoardId, (BoardConfig, Instant)>`. The cache is invalidated
-/// immediately on `PUT /board/:slug/config`.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct BoardConfig {
-    // ── Content rules ──────────────────────────────────────────────────────
-    /// Replies past this count no longer bump the thread. Default: 500.
-    pub bump_limit: u32,
-    /// Maximum number of live threads on this board. When the board exceeds this
-    /// limit after a new OP is posted, the oldest non-sticky thread is pruned
-    /// (or archived if `archive_enabled` is true). Default: 200.
-    pub max_threads: u32,
-    /// Maximum number of file attachments per post. Default: 4.
-    pub max_files: u8,
-    /// Maximum file size per attachment in kilobytes. Default: 10240 (10 MB).
-    pub max_file_size: FileSizeKb,
-    /// MIME types allowed as attachments. Default: jpeg, png, gif, webp.
-    pub allowed_mimes: Vec<String>,
-    /// Maximum post body length in characters. Default: 4000.
-    pub max_post_length: u32,
+/// What a board does with a post whose IP is listed by the reputation checker
+/// (DNS blocklists or the Tor exit list).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DnsblAction {
+    /// Ignore the listing entirely.
+    Off,
+    /// Add a fixed penalty to the post's spam score; the post is rejected only
+    /// if the combined score reaches `spam_score_threshold`.
+    Score,
+    /// Reject the post outright, as if the IP were banned.
+    #[default]
+    Block,
+}
+
+impl std::fmt::Display for DnsblAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DnsblAction::Off   => f.write_str("off"),
+            DnsblAction::Score => f.write_str("score"),
+            DnsblAction::Block => f.write_str("block"),
+        }
+    }
+}
 
-    // ── Rate limiting ──────────────────────────────────────────────────────
-    /// Whether IP-based rate limiting is enforced on this board. Default: true.
-    pub rate_limit_enabled: bool,
-    /// Rolling window length for rate limiting in seconds. Default: 60.
-    pub rate_limit_window_secs: u32,
-    /// Maximum posts per IP within the rate-limit window. Default: 3.
-    pub rate_limit_posts: u32,
+impl FromStr for DnsblAction {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "off"   => Ok(DnsblAction::Off),
+            "score" => Ok(DnsblAction::Score),
+            "block" => Ok(DnsblAction::Block),
+            other   => Err(format!("unknown DnsblAction: {other}")),
+        }
+    }
+}
 
-    // ── Spam filtering ─────────────────────────────────────────────────────
-    /// Whether spam heuristics are run on new posts. Default: true.
-    pub spam_filter_enabled: bool,
-    /// A post with a spam score ≥ this threshold is rejected. Default: 0.75.
-    pub spam_score_threshold: f32,
-    /// Reject posts whose content hash matches a recent post on this board. Default: true.
-    pub duplicate_check: bool,
-    /// Domains whose URLs are blocked in post bodies. Empty list = no blacklist. Default: [].
-    ///
-    /// Any post body containing a URL whose hostname matches an entry in this list
-    /// is rejected with `PostError::SpamDetected`. Matching is case-insensitive and
-    /// checks the full hostname (e.g. `"spam.example.com"` matches that host only,
-    /// not `"example.com"`).
-    pub link_blacklist: Vec<String>,
-    /// Minimum seconds that must pass between posts from the same name+tripcode
-    /// combination on this board. `0` disables the check. Default: 0.
-    ///
-    /// Prevents a single identity from flooding a board without triggering the
-    /// IP-based rate limiter (e.g. via proxies). Only applied when `forced_anon`
-    /// is false and the poster provides a name.
-    pub name_rate_limit_window_secs: u32,
+// ─── ClassifierAction ────────────────────────────────────────────────────────
 
-    // ── Posting behaviour ──────────────────────────────────────────────────
-    /// When true, the name field is ignored and all posts display as "Anonymous". Default: false.
-    pub forced_anon: bool,
-    /// Allow posters to sage (prevent bump by setting email to "sage"). Default: true.
-    pub allow_sage: bool,
-    /// Allow tripcode identifiers. Adapter not compiled in v1.0. Default: false.
+/// What a board does with an upload the `MediaClassifier` labelled at or above
+/// `BoardConfig::classifier_threshold`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClassifierAction {
+    /// Do not classify uploads.
+    #[default]
+    Off,
+    /// Record the labels only.
+    Label,
+    /// Record the labels and mark the attachment as a spoiler.
+    Spoiler,
+    /// Record the labels and file a moderation flag for review.
+    Flag,
+}
+
+impl std::fmt::Display for ClassifierAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClassifierAction::Off     => f.write_str("off"),
+            ClassifierAction::Label   => f.write_str("label"),
+            ClassifierAction::Spoiler => f.write_str("spoiler"),
+            ClassifierAction::Flag    => f.write_str("flag"),
+        }
+    }
+}
+
+impl FromStr for ClassifierAction {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "off"     => Ok(ClassifierAction::Off),
+            "label"   => Ok(ClassifierAction::Label),
+            "spoiler" => Ok(ClassifierAction::Spoiler),
+            "flag"    => Ok(ClassifierAction::Flag),
+            other     => Err(format!("unknown ClassifierAction: {other}")),
+        }
+    }
+}
+
+// ─── QuotaAction ─────────────────────────────────────────────────────────────
+
+/// What happens to an upload that would take a board past
+/// `BoardConfig::media_quota_mb`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QuotaAction {
+    /// Reject the post.
+    #[default]
+    Reject,
+    /// Delete the board's oldest non-sticky threads until the upload fits,
+    /// rejecting it only if that is not enough.
+    Prune,
+}
+
+impl std::fmt::Display for QuotaAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QuotaAction::Reject => f.write_str("reject"),
+            QuotaAction::Prune  => f.write_str("prune"),
+        }
+    }
+}
+
+impl FromStr for QuotaAction {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "reject" => Ok(QuotaAction::Reject),
+            "prune"  => Ok(QuotaAction::Prune),
+            other    => Err(format!("unknown QuotaAction: {other}")),
+        }
+    }
+}
+
+// ─── TripcodeAlgorithm ───────────────────────────────────────────────────────
+
+/// How a board turns a `Name#password` tripcode into its displayed `!trip`.
+///
+/// Only the single-`#` level is affected; `##` and `###` trips are always
+/// derived from the server pepper.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TripcodeAlgorithm {
+    /// SHA-256 of the password, shown as 10 hex characters.
+    #[default]
+    Sha256,
+    /// The classic imageboard algorithm: the password in Shift-JIS run
+    /// through DES `crypt(3)`, so trips made elsewhere carry over.
+    Crypt,
+}
+
+impl std::fmt::Display for TripcodeAlgorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TripcodeAlgorithm::Sha256 => f.write_str("sha256"),
+            TripcodeAlgorithm::Crypt  => f.write_str("crypt"),
+        }
+    }
+}
+
+impl FromStr for TripcodeAlgorithm {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sha256" => Ok(TripcodeAlgorithm::Sha256),
+            "crypt"  => Ok(TripcodeAlgorithm::Crypt),
+            other    => Err(format!("unknown TripcodeAlgorithm: {other}")),
+        }
+    }
+}
+
+// ─── Theme ───────────────────────────────────────────────────────────────────
+
+/// A site stylesheet. Boards pick a default; viewers may override it from the
+/// settings panel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    /// Red-on-cream Futaba palette (`style.css`).
+    #[default]
+    Futaba,
+    /// Blue-grey Yotsuba B palette.
+    Yotsuba,
+    /// Muted dark Tomorrow palette.
+    Tomorrow,
+    /// High-contrast dark palette.
+    Dark,
+}
+
+impl Theme {
+    /// Every theme, in the order the settings panel lists them.
+    pub const ALL: [Theme; 4] = [Theme::Futaba, Theme::Yotsuba, Theme::Tomorrow, Theme::Dark];
+}
+
+impl std::fmt::Display for Theme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Theme::Futaba   => f.write_str("futaba"),
+            Theme::Yotsuba  => f.write_str("yotsuba"),
+            Theme::Tomorrow => f.write_str("tomorrow"),
+            Theme::Dark     => f.write_str("dark"),
+        }
+    }
+}
+
+impl FromStr for Theme {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "futaba"   => Ok(Theme::Futaba),
+            "yotsuba"  => Ok(Theme::Yotsuba),
+            "tomorrow" => Ok(Theme::Tomorrow),
+            "dark"     => Ok(Theme::Dark),
+            other      => Err(format!("unknown Theme: {other}")),
+        }
+    }
+}
+
+// ─── Locale ──────────────────────────────────────────────────────────────────
+
+/// A language the UI is translated into. Pages are shown in the first locale
+/// of the viewer's `Accept-Language` that is supported here, else in the
+/// board's default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    /// English. The source strings; every other locale falls back to them.
+    #[default]
+    En,
+    /// Spanish.
+    Es,
+}
+
+impl Locale {
+    /// Every supported locale.
+    pub const ALL: [Locale; 2] = [Locale::En, Locale::Es];
+}
+
+impl std::fmt::Display for Locale {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Locale::En => f.write_str("en"),
+            Locale::Es => f.write_str("es"),
+        }
+    }
+}
+
+impl FromStr for Locale {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "en" => Ok(Locale::En),
+            "es" => Ok(Locale::Es),
+            other => Err(format!("unknown Locale: {other}")),
+        }
+    }
+}
+
+// ─── BoardConfig ─────────────────────────────────────────────────────────────
+
+/// The runtime behaviour surface for a board.
+///
+/// One row exists in `board_configs` per board. Operators change these fields
+/// through the admin or board-owner dashboard; no redeployment is required.
+///
+/// # INVARIANT
+/// `BoardConfig` is the **only** path from a dashboard control to service
+/// behaviour. Services branch on its fields. They never read environment
+/// variables, feature flags, or global state.
+///
+/// # Caching
+/// `BoardConfig` is cached in-process with a 60-second TTL in a
+/// `DashMap<BoardId, (BoardConfig, Instant)>`. The cache is invalidated
+/// immediately on `PUT /board/:slug/config`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BoardConfig {
+    // ── Content rules ──────────────────────────────────────────────────────
+    /// Replies past this count no longer bump the thread. Default: 500.
+    pub bump_limit: u32,
+    /// Maximum number of live threads on this board. When the board exceeds this
+    /// limit after a new OP is posted, the oldest non-sticky thread is pruned
+    /// (or archived if `archive_enabled` is true). Default: 200.
+    pub max_threads: u32,
+    /// Maximum number of file attachments per post. Default: 4.
+    pub max_files: u8,
+    /// Maximum file size per attachment in kilobytes. Default: 10240 (10 MB).
+    pub max_file_size: FileSizeKb,
+    /// MIME types allowed as attachments. Default: jpeg, png, gif, webp.
+    pub allowed_mimes: Vec<String>,
+    /// Strip EXIF/XMP/GPS metadata from uploaded images before the original is
+    /// stored. Disabling keeps originals byte-for-byte (e.g. for photography
+    /// boards); posters' location data is then stored as uploaded. Default: true.
+    #[serde(default = "default_strip_metadata")]
+    pub strip_metadata: bool,
+    /// Thumbnail size and format for this board. `None` uses the global
+    /// `THUMBNAIL_*` settings. Default: `None`.
+    #[serde(default)]
+    pub thumbnail: Option<ThumbnailSpec>,
+    /// Cap on the bytes of stored originals across the board's posts, in
+    /// megabytes. `0` = unlimited. Set by admins only. Default: 0.
+    #[serde(default)]
+    pub media_quota_mb: u32,
+    /// What an upload past `media_quota_mb` does. Default: `Reject`.
+    #[serde(default)]
+    pub quota_action: QuotaAction,
+    /// Maximum post body length in characters. Default: 4000.
+    pub max_post_length: u32,
+
+    // ── Rate limiting ──────────────────────────────────────────────────────
+    /// Whether IP-based rate limiting is enforced on this board. Default: true.
+    pub rate_limit_enabled: bool,
+    /// Rolling window length for rate limiting in seconds. Default: 60.
+    pub rate_limit_window_secs: u32,
+    /// Maximum posts per IP within the rate-limit window. Default: 3.
+    pub rate_limit_posts: u32,
+
+    // ── Spam filtering ─────────────────────────────────────────────────────
+    /// Whether spam heuristics are run on new posts. Default: true.
+    pub spam_filter_enabled: bool,
+    /// A post with a spam score ≥ this threshold is rejected. Default: 0.75.
+    pub spam_score_threshold: f32,
+    /// Reject posts whose content hash matches a recent post on this board. Default: true.
+    pub duplicate_check: bool,
+    /// Domains whose URLs are blocked in post bodies. Empty list = no blacklist. Default: [].
+    ///
+    /// Any post body containing a URL whose hostname matches an entry in this list
+    /// is rejected with `PostError::SpamDetected`. Matching is case-insensitive and
+    /// checks the full hostname (e.g. `"spam.example.com"` matches that host only,
+    /// not `"example.com"`).
+    pub link_blacklist: Vec<String>,
+    /// Minimum seconds that must pass between posts from the same name+tripcode
+    /// combination on this board. `0` disables the check. Default: 0.
+    ///
+    /// Prevents a single identity from flooding a board without triggering the
+    /// IP-based rate limiter (e.g. via proxies). Only applied when `forced_anon`
+    /// is false and the poster provides a name.
+    pub name_rate_limit_window_secs: u32,
+    /// How to treat posters whose IP is listed in a DNS blocklist or is a known
+    /// Tor exit node. Only consulted when `spam_filter_enabled` is true and an
+    /// IP reputation checker is wired. Default: `Block`.
+    #[serde(default)]
+    pub dnsbl_action: DnsblAction,
+    /// How to treat uploads the media classifier labels (e.g. NSFW detection on
+    /// SFW boards). Only consulted when a `MediaClassifier` is wired. Default: `Off`.
+    #[serde(default)]
+    pub classifier_action: ClassifierAction,
+    /// Lowest label score that triggers `classifier_action`, 0.0–1.0. Default: 0.8.
+    #[serde(default = "default_classifier_threshold")]
+    pub classifier_threshold: f32,
+
+    // ── Posting behaviour ──────────────────────────────────────────────────
+    /// When true, the name field is ignored and all posts display as "Anonymous". Default: false.
+    pub forced_anon: bool,
+    /// Allow posters to sage (prevent bump by setting email to "sage"). Default: true.
+    pub allow_sage: bool,
+    /// Allow tripcode identifiers. Adapter not compiled in v1.0. Default: false.
     // TODO(v1.1): wire CaptchaVerifier port when captcha_required = true
     pub allow_tripcodes: bool,
+    /// Algorithm for single-`#` tripcodes. Default: `Sha256`.
+    #[serde(default)]
+    pub tripcode_algorithm: TripcodeAlgorithm,
     /// Require CAPTCHA verification on post creation. Adapter not compiled in v1.0. Default: false.
     // TODO(v1.1): wire CaptchaVerifier port when captcha_required = true
     pub captcha_required: bool,
     /// This board contains adult/NSFW content. Default: false.
     pub nsfw: bool,
+    /// Run `!roll 2d6` and `!fortune` lines when a post is made and show the
+    /// results under the body. Default: false.
+    #[serde(default)]
+    pub commands_enabled: bool,
+    /// Let the OP attach a poll to a new thread. Each poster ID gets one
+    /// vote per poll. Default: false.
+    #[serde(default)]
+    pub polls_enabled: bool,
+    /// Let posters react to posts with one of the fixed `Reaction`s, once per
+    /// post. Default: false.
+    #[serde(default)]
+    pub reactions_enabled: bool,
+
+    // ── Post formatting (rendered by `rb-format`) ──────────────────────────
+    /// Bold, italic, underline, strike and red/blue text. Default: true.
+    #[serde(default = "default_formatting")]
+    pub markup_enabled: bool,
+    /// `[spoiler]` text. Default: true.
+    #[serde(default = "default_formatting")]
+    pub spoilers_enabled: bool,
+    /// Inline code and code blocks. Default: true.
+    #[serde(default = "default_formatting")]
+    pub code_enabled: bool,
+    /// Greentext (`>`) and pinktext (`<`) lines. Quote links are always
+    /// rendered. Default: true.
+    #[serde(default = "default_formatting")]
+    pub greentext_enabled: bool,
+    /// Syntax-highlight fenced code blocks that name their language
+    /// (```` ```rust ````). For programming boards. Default: false.
+    #[serde(default)]
+    pub code_highlighting: bool,
+
+    // ── Appearance ─────────────────────────────────────────────────────────
+    /// Stylesheet the board's pages load for viewers who have not picked a
+    /// theme of their own. Default: `Futaba`.
+    #[serde(default)]
+    pub default_theme: Theme,
+    /// Language for viewers whose `Accept-Language` names no supported
+    /// locale. Default: `En`.
+    #[serde(default)]
+    pub default_locale: Locale,
+    /// Heading the board is listed under in the homepage directory, at most
+    /// [`BoardConfig::CATEGORY_MAX_CHARS`] characters. Empty lists it under
+    /// "Other". Default: empty.
+    #[serde(default)]
+    pub category: String,
+    /// Leave the board's threads out of the `/all` overboard catalog, e.g.
+    /// for staff or off-topic boards. Default: false.
+    #[serde(default)]
+    pub overboard_hidden: bool,
+    /// Publish the board's moderation log at `/board/{slug}/log`, without
+    /// actors, IP hashes or details. Default: false.
+    #[serde(default)]
+    pub public_log: bool,
 
     // ── Future capabilities ─────────────────────────────────────────────────
     // Fields are present now so that the schema is stable; the adapters that
@@ -897,11 +1886,24 @@ pub struct BoardConfig {
     pub search_enabled: bool,
     /// Posts are archived before pruning. Adapter ships in v1.2. Default: false.
     pub archive_enabled: bool,
-    /// ActivityPub federation enabled. Adapter ships in v2.0. Default: false.
-    // TODO(v2.0): wire FederationSync port when federation_enabled = true
+    /// The board is an ActivityPub actor: its posts are sent to remote
+    /// followers and their replies are posted back. Needs the
+    /// `federation-activitypub` build. Default: false.
     pub federation_enabled: bool,
 }
 
+fn default_strip_metadata() -> bool {
+    true
+}
+
+fn default_formatting() -> bool {
+    true
+}
+
+fn default_classifier_threshold() -> f32 {
+    0.8
+}
+
 impl Default for BoardConfig {
     /// Conservative defaults — safe for a new board with no custom configuration.
     fn default() -> Self {
@@ -916,6 +1918,10 @@ impl Default for BoardConfig {
                 "image/gif".to_owned(),
                 "image/webp".to_owned(),
             ],
+            strip_metadata:         true,
+            thumbnail:              None,
+            media_quota_mb:         0,
+            quota_action:           QuotaAction::Reject,
             max_post_length:        4000,
             rate_limit_enabled:     true,
             rate_limit_window_secs: 60,
@@ -925,11 +1931,28 @@ impl Default for BoardConfig {
             duplicate_check:             true,
             link_blacklist:              vec![],
             name_rate_limit_window_secs: 0,
+            dnsbl_action:                DnsblAction::Block,
+            classifier_action:           ClassifierAction::Off,
+            classifier_threshold:        default_classifier_threshold(),
             forced_anon:                 false,
             allow_sage:             true,
             allow_tripcodes:        false,
+            tripcode_algorithm:     TripcodeAlgorithm::Sha256,
             captcha_required:       false,
             nsfw:                   false,
+            commands_enabled:       false,
+            polls_enabled:          false,
+            reactions_enabled:      false,
+            markup_enabled:         true,
+            spoilers_enabled:       true,
+            code_enabled:           true,
+            greentext_enabled:      true,
+            code_highlighting:      false,
+            default_theme:          Theme::Futaba,
+            default_locale:         Locale::En,
+            category:               String::new(),
+            overboard_hidden:       false,
+            public_log:             false,
             search_enabled:         false,
             archive_enabled:        false,
             federation_enabled:     false,
@@ -938,6 +1961,9 @@ impl Default for BoardConfig {
 }
 
 impl BoardConfig {
+    /// Longest `category`, in characters.
+    pub const CATEGORY_MAX_CHARS: usize = 32;
+
     /// Check whether a given MIME type string is allowed by this config.
     pub fn allows_mime(&self, mime: &str) -> bool {
         self.allowed_mimes.iter().any(|m| m == mime)
@@ -952,6 +1978,11 @@ impl BoardConfig {
     pub fn allows_post_length(&self, len: usize) -> bool {
         len <= self.max_post_length as usize
     }
+
+    /// The media quota in bytes, or `None` when the board is unlimited.
+    pub fn media_quota_bytes(&self) -> Option<u64> {
+        (self.media_quota_mb > 0).then(|| u64::from(self.media_quota_mb) * 1024 * 1024)
+    }
 }
 
 // ─── CurrentUser ─────────────────────────────────────────────────────────────
@@ -1181,10 +2212,95 @@ impl std::fmt::Display for StaffRequestId {
     }
 }
 
+// ─── Site ────────────────────────────────────────────────────────────────────
+
+/// A site's unique identifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SiteId(pub Uuid);
+
+impl SiteId {
+    /// The site every installation starts with, created by migration 028.
+    /// Requests whose `Host` matches no other site are served by it.
+    pub const DEFAULT: Self = Self(Uuid::nil());
+
+    /// Create a new random `SiteId`.
+    pub fn new() -> Self {
+        Self(Uuid::new_v4())
+    }
+}
+
+impl Default for SiteId {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl std::fmt::Display for SiteId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// An independent imageboard served by this process, selected by the request's
+/// `Host` header.
+///
+/// Each site has its own board namespace, overboard and media prefix. Staff
+/// accounts, bans, the audit log and the HTML templates are shared.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Site {
+    /// Unique identifier.
+    pub id: SiteId,
+    /// Lowercase host name without port, e.g. `"boards.example.org"`. Empty
+    /// for the default site until one is assigned.
+    pub host: String,
+    /// Human-readable name, e.g. for `rusty-board site list`.
+    pub name: String,
+    /// Directory uploads are stored under, e.g. `"example"`. Empty stores them
+    /// at the root of the media backend, as single-site installations do.
+    pub media_prefix: String,
+    /// When this site was created.
+    pub created_at: DateTime<Utc>,
+    /// Admin-set message shown on every page while `notice_enabled`, e.g. a
+    /// maintenance notice. Kept when the notice is switched off.
+    pub notice: String,
+    /// Whether `notice` is shown.
+    pub notice_enabled: bool,
+}
+
+impl Site {
+    /// The default site as it is before an operator changes it. Used when no
+    /// site was resolved for a request (handlers mounted without the middleware).
+    pub fn fallback() -> Self {
+        Self {
+            id:             SiteId::DEFAULT,
+            host:           String::new(),
+            name:           "rusty-board".to_owned(),
+            media_prefix:   String::new(),
+            created_at:     DateTime::<Utc>::UNIX_EPOCH,
+            notice:         String::new(),
+            notice_enabled: false,
+        }
+    }
+
+    /// The notice to show, if it is switched on and not blank.
+    pub fn active_notice(&self) -> Option<&str> {
+        (self.notice_enabled && !self.notice.trim().is_empty()).then_some(self.notice.as_str())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn media_key_prefixes() {
+        let key = MediaKey::new("a1.png");
+        assert_eq!(key.with_prefix(""), key);
+        assert_eq!(key.with_prefix("/example/"), MediaKey::new("example/a1.png"));
+        assert_eq!(key.with_prefix("example").prefix(), "example");
+        assert_eq!(key.prefix(), "");
+    }
+
     #[test]
     fn slug_valid_values() {
         assert!(Slug::new("tech").is_ok());
@@ -1203,6 +2319,31 @@ mod tests {
         assert!(Slug::new("spe©ial").is_err()); // non-ascii
     }
 
+    #[test]
+    fn media_meta_sanitizes_filenames() {
+        assert_eq!(MediaMeta::sanitize_filename("C:\\Users\\anon\\cat.jpg"), "cat.jpg");
+        assert_eq!(MediaMeta::sanitize_filename("../../etc/passwd"), "passwd");
+        assert_eq!(MediaMeta::sanitize_filename("dir/"), "file");
+        assert_eq!(MediaMeta::sanitize_filename(&"a".repeat(300)).len(), MediaMeta::MAX_FILENAME_CHARS);
+    }
+
+    #[test]
+    fn media_meta_formats_size_and_dimensions() {
+        let meta = |size_bytes, width, height| MediaMeta {
+            original_filename: "a.png".to_owned(),
+            size_bytes,
+            width,
+            height,
+            original_mime: None,
+            labels: vec![],
+        };
+        assert_eq!(meta(512, None, None).display_size(), "512 B");
+        assert_eq!(meta(125_000, None, None).display_size(), "123 KB");
+        assert_eq!(meta(3 * 1024 * 1024 / 2, None, None).display_size(), "1.5 MB");
+        assert_eq!(meta(1, Some(1920), Some(1080)).dimensions().as_deref(), Some("1920x1080"));
+        assert_eq!(meta(1, Some(1920), None).dimensions(), None);
+    }
+
     #[test]
     fn board_config_default_allows_standard_images() {
         let cfg = BoardConfig::default();
@@ -1227,6 +2368,57 @@ mod tests {
         assert!(!cfg.search_enabled);
         assert!(!cfg.archive_enabled);
         assert!(!cfg.federation_enabled);
+        assert_eq!(cfg.dnsbl_action, DnsblAction::Block);
+    }
+
+    #[test]
+    fn dnsbl_action_round_trips_through_str() {
+        for action in [DnsblAction::Off, DnsblAction::Score, DnsblAction::Block] {
+            assert_eq!(action.to_string().parse::<DnsblAction>(), Ok(action));
+        }
+        assert!("captcha".parse::<DnsblAction>().is_err());
+    }
+
+    #[test]
+    fn media_quota_zero_is_unlimited() {
+        let mut cfg = BoardConfig::default();
+        assert_eq!(cfg.media_quota_bytes(), None);
+        cfg.media_quota_mb = 5;
+        assert_eq!(cfg.media_quota_bytes(), Some(5 * 1024 * 1024));
+        assert_eq!("prune".parse::<QuotaAction>(), Ok(QuotaAction::Prune));
+    }
+
+    #[test]
+    fn tripcode_algorithm_round_trips() {
+        for algorithm in [TripcodeAlgorithm::Sha256, TripcodeAlgorithm::Crypt] {
+            assert_eq!(algorithm.to_string().parse::<TripcodeAlgorithm>(), Ok(algorithm));
+        }
+        assert!("md5".parse::<TripcodeAlgorithm>().is_err());
+    }
+
+    #[test]
+    fn theme_round_trips() {
+        for theme in Theme::ALL {
+            assert_eq!(theme.to_string().parse::<Theme>(), Ok(theme));
+        }
+        assert!("default".parse::<Theme>().is_err());
+    }
+
+    #[test]
+    fn locale_round_trips() {
+        for locale in Locale::ALL {
+            assert_eq!(locale.to_string().parse::<Locale>(), Ok(locale));
+        }
+        assert!("en-US".parse::<Locale>().is_err());
+    }
+
+    #[test]
+    fn catalog_sort_round_trips() {
+        for sort in CatalogSort::ALL {
+            assert_eq!(sort.to_string().parse::<CatalogSort>(), Ok(sort));
+            assert_eq!(serde_json::to_value(sort).unwrap(), sort.to_string());
+        }
+        assert!("replies".parse::<CatalogSort>().is_err());
     }
 
     #[test]
@@ -1242,6 +2434,425 @@ mod tests {
     }
 }
 
+// ─── RemoteFollower ──────────────────────────────────────────────────────────
+
+/// A remote ActivityPub actor following a board with `federation_enabled`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RemoteFollower {
+    /// The board being followed.
+    pub board_id: BoardId,
+    /// The follower's actor ID, e.g. `"https://social.example/users/alice"`.
+    pub actor_id: String,
+    /// Where new posts are delivered: the instance's shared inbox when it
+    /// has one, so an instance with many followers gets each post once.
+    pub inbox: String,
+    /// When the follow was accepted.
+    pub created_at: DateTime<Utc>,
+}
+
+// ─── Poll ────────────────────────────────────────────────────────────────────
+
+/// A poll the OP attached to a new thread on a board with `polls_enabled`.
+/// A thread has at most one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Poll {
+    /// The thread the poll belongs to.
+    pub thread_id: ThreadId,
+    /// The question, as typed by the OP (trimmed).
+    pub question: String,
+    /// The answers in the order the OP gave them, with their vote counts.
+    pub options: Vec<PollOption>,
+    /// When the thread was created.
+    pub created_at: DateTime<Utc>,
+}
+
+/// One answer of a `Poll` and how many posters chose it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PollOption {
+    pub text:  String,
+    pub votes: u64,
+}
+
+impl Poll {
+    /// Votes cast across all options.
+    pub fn total_votes(&self) -> u64 {
+        self.options.iter().map(|o| o.votes).sum()
+    }
+
+    /// `option`'s share of the votes as a whole percentage; 0 before anyone votes.
+    pub fn percent(&self, option: &PollOption) -> u64 {
+        match self.total_votes() {
+            0 => 0,
+            total => (option.votes * 100 + total / 2) / total,
+        }
+    }
+}
+
+// ─── Reaction ────────────────────────────────────────────────────────────────
+
+/// A reaction a poster can leave on a post on a board with `reactions_enabled`.
+/// The set is fixed; each poster gets one reaction per post.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Reaction {
+    Like,
+    Love,
+    Laugh,
+    Wow,
+    Sad,
+    Angry,
+}
+
+impl Reaction {
+    /// Every reaction, in display order.
+    pub const ALL: [Reaction; 6] =
+        [Reaction::Like, Reaction::Love, Reaction::Laugh, Reaction::Wow, Reaction::Sad, Reaction::Angry];
+
+    /// The stored and submitted name, e.g. `"laugh"`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Reaction::Like  => "like",
+            Reaction::Love  => "love",
+            Reaction::Laugh => "laugh",
+            Reaction::Wow   => "wow",
+            Reaction::Sad   => "sad",
+            Reaction::Angry => "angry",
+        }
+    }
+
+    /// The emoji shown for this reaction.
+    pub fn emoji(self) -> &'static str {
+        match self {
+            Reaction::Like  => "👍",
+            Reaction::Love  => "❤️",
+            Reaction::Laugh => "😂",
+            Reaction::Wow   => "😮",
+            Reaction::Sad   => "😢",
+            Reaction::Angry => "😠",
+        }
+    }
+}
+
+impl std::fmt::Display for Reaction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Reaction {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Reaction::ALL
+            .into_iter()
+            .find(|r| r.as_str() == s)
+            .ok_or_else(|| format!("unknown reaction: {s}"))
+    }
+}
+
+/// How many posters left one `Reaction` on a post.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReactionCount {
+    pub reaction: Reaction,
+    pub count:    u64,
+}
+
+// ─── Thread watcher ──────────────────────────────────────────────────────────
+
+/// Identifies an anonymous visitor's thread watcher.
+///
+/// Random and handed out in the `watcher` cookie on the first watch; whoever
+/// holds it sees that watch list. Not tied to an account or IP.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct WatcherToken(pub Uuid);
+
+impl WatcherToken {
+    /// Create a new random `WatcherToken`.
+    pub fn new() -> Self {
+        Self(Uuid::new_v4())
+    }
+}
+
+impl Default for WatcherToken {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Display for WatcherToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// A thread on a watch list, with the replies made since the watcher last
+/// opened it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WatchedThread {
+    pub thread_id:  ThreadId,
+    /// Slug of the thread's board.
+    pub board_slug: String,
+    /// The OP's subject, or the start of its body when it has none.
+    pub title:      String,
+    /// Posts numbered after the last one the watcher saw.
+    pub unread:     u64,
+    /// When the thread was last bumped.
+    pub bumped_at:  DateTime<Utc>,
+}
+
+// ─── Board banners ───────────────────────────────────────────────────────────
+
+/// A board banner's unique identifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct BannerId(pub Uuid);
+
+impl BannerId {
+    /// Create a new random `BannerId`.
+    pub fn new() -> Self {
+        Self(Uuid::new_v4())
+    }
+}
+
+impl Default for BannerId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Display for BannerId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// One of a board's banner images. Board pages show a random one per render.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BoardBanner {
+    pub id:           BannerId,
+    pub board_id:     BoardId,
+    /// Where the image is stored, under `MediaKey::BANNER_PREFIX`.
+    pub media_key:    MediaKey,
+    /// Sniffed MIME type of the image.
+    pub content_type: String,
+    pub created_at:   DateTime<Utc>,
+}
+
+// ─── Blotter ─────────────────────────────────────────────────────────────────
+
+/// A blotter entry's unique identifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct AnnouncementId(pub Uuid);
+
+impl AnnouncementId {
+    /// Create a new random `AnnouncementId`.
+    pub fn new() -> Self {
+        Self(Uuid::new_v4())
+    }
+}
+
+impl Default for AnnouncementId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Display for AnnouncementId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// A short dated announcement on the blotter at the top of board pages.
+///
+/// Posted by admins, either for every board of a site or for one board.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Announcement {
+    pub id:         AnnouncementId,
+    pub site_id:    SiteId,
+    /// The board it is shown on; `None` shows it on every board of the site.
+    pub board_id:   Option<BoardId>,
+    /// Plain text, escaped when rendered.
+    pub message:    String,
+    pub created_at: DateTime<Utc>,
+}
+
+// ─── Static pages ────────────────────────────────────────────────────────────
+
+/// A static page's unique identifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PageId(pub Uuid);
+
+impl PageId {
+    /// Create a new random `PageId`.
+    pub fn new() -> Self {
+        Self(Uuid::new_v4())
+    }
+}
+
+impl Default for PageId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Display for PageId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// An admin-edited page such as the rules or a FAQ.
+///
+/// Site pages are served at `/{slug}`, board pages at `/board/{board}/{slug}`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StaticPage {
+    pub id:         PageId,
+    pub site_id:    SiteId,
+    /// The board the page belongs to; `None` for a site page.
+    pub board_id:   Option<BoardId>,
+    /// The last path segment of the page's URL, unique per site or board.
+    pub slug:       String,
+    pub title:      String,
+    /// Markdown, rendered by `rb_format::page::render`.
+    pub body:       String,
+    pub updated_at: DateTime<Utc>,
+}
+
+// ─── API keys ────────────────────────────────────────────────────────────────
+
+/// An API key's unique identifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ApiKeyId(pub Uuid);
+
+impl ApiKeyId {
+    /// Create a new random `ApiKeyId`.
+    pub fn new() -> Self {
+        Self(Uuid::new_v4())
+    }
+}
+
+impl Default for ApiKeyId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Display for ApiKeyId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// What an API key may do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiScope {
+    /// `GET` and `HEAD` requests: boards, catalogs, threads, archives.
+    Read,
+    /// Every other method: creating threads and replies, reporting posts.
+    Post,
+}
+
+impl ApiScope {
+    /// The name stored in the database and used in the admin API.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Read => "read",
+            Self::Post => "post",
+        }
+    }
+}
+
+impl std::fmt::Display for ApiScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ApiScope {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "read" => Ok(Self::Read),
+            "post" => Ok(Self::Post),
+            other  => Err(format!("unknown API scope: {other}")),
+        }
+    }
+}
+
+/// A key a bot or bridge sends as `Authorization: Bearer` instead of logging in.
+///
+/// Only a hash of the key is stored; the key itself is shown once, when an
+/// admin issues it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiKey {
+    pub id:                 ApiKeyId,
+    /// Who or what the key is for, e.g. `archive-bot`. Shown to staff on the
+    /// posts made with it.
+    pub name:               String,
+    /// The first characters of the key, so admins can tell keys apart.
+    pub prefix:             String,
+    /// SHA-256 hex of the key.
+    pub key_hash:           String,
+    pub scopes:             Vec<ApiScope>,
+    /// Requests allowed per minute.
+    pub rate_limit_per_min: u32,
+    /// The admin who issued the key.
+    pub created_by:         UserId,
+    pub created_at:         DateTime<Utc>,
+    /// Recorded at most once a minute.
+    pub last_used_at:       Option<DateTime<Utc>>,
+    /// Revoked keys are kept for the record but authenticate nothing.
+    pub revoked_at:         Option<DateTime<Utc>>,
+}
+
+/// The API key a request authenticated with, inserted into the request
+/// extensions by the API key middleware.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiClient {
+    pub key_id: ApiKeyId,
+    pub name:   String,
+    pub scopes: Vec<ApiScope>,
+}
+
+// ─── Board statistics ────────────────────────────────────────────────────────
+
+/// Length of a `StatsBucket`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatsPeriod {
+    Hour,
+    Day,
+}
+
+impl StatsPeriod {
+    /// The name stored in the database and used by `date_trunc`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Hour => "hour",
+            Self::Day => "day",
+        }
+    }
+}
+
+/// A board's activity over one hour or day, rolled up from its posts by the
+/// stats job. Buckets are kept after the posts they count are deleted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatsBucket {
+    pub board_id:       BoardId,
+    pub period:         StatsPeriod,
+    /// Start of the hour or day (UTC).
+    pub start:          DateTime<Utc>,
+    /// Posts made, opening posts included.
+    pub posts:          u64,
+    /// Distinct posters, by IP hash. IP hashes change when the salt rotates,
+    /// so a bucket longer than the rotation may count a poster twice.
+    pub unique_posters: u64,
+    /// Files attached to those posts.
+    pub media_count:    u64,
+    /// Total size of those files, in bytes.
+    pub media_bytes:    u64,
+}
+
 // ─── StaffMessage ─────────────────────────────────────────────────────────────
 
 /// A text-only message between staff accounts.
@@ -15,20 +15,25 @@
 //! See `PORTS.md` for the required steps. The trait definition here must be
 //! accompanied by an entry in `PORTS.md` before any adapter is written.
 
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::time::Duration;
 
 use bytes::Bytes;
-use crate::models::OverboardPost;
+use crate::models::{OverboardPost, OverboardThread};
 use chrono::{DateTime, Utc};
 use mime::Mime;
+use serde::{Deserialize, Serialize};
 
 use async_trait::async_trait;
 use crate::errors::DomainError;
 use crate::models::{
-    AuditEntry, Ban, BanId, Board, BoardConfig, BoardId, Claims, ContentHash, Flag, FlagId,
-    FlagResolution, IpHash, MediaKey, Page, Paginated, PasswordHash, Post, PostId,
-    StaffRequest, StaffRequestId, StaffRequestStatus,
-    Thread, ThreadId, ThreadSummary, Token, User, UserId,
+    Announcement, AnnouncementId, ApiKey, ApiKeyId, AsnBan, Attachment, AuditEntry, Ban, BanId, BannerId, Board, BoardBanner, BoardConfig, BoardId, CatalogSort, Claims, ContentHash, Flag,
+    FlagId, FlagResolution, HashBan, IpHash, MediaKey, Page, PageId, Paginated, PasswordHash, Poll, Post, PostId, PostRevision,
+    PostNumberRange, Reaction, ReactionCount, RemoteFollower,
+    Site, SiteActivity, SiteId, StaffRequest, StaffRequestId, StaffRequestStatus, StaffTotp, StaticPage, StatsBucket,
+    StatsPeriod,
+    Thread, ThreadId, ThreadSummary, Token, User, UserId, Warning, WatchedThread, WatcherToken,
 };
 
 // ─── Repository Ports ────────────────────────────────────────────────────────
@@ -45,12 +50,16 @@ pub trait BoardRepository: Send + Sync + 'static {
     /// Returns `DomainError::NotFound` if no board with the given id exists.
     async fn find_by_id(&self, id: BoardId) -> Result<Board, DomainError>;
 
-    /// Fetch a board by its slug.
+    /// Fetch a board by its slug on the given site.
     ///
-    /// Returns `DomainError::NotFound` if no board with the given slug exists.
-    async fn find_by_slug(&self, slug: &crate::models::Slug) -> Result<Board, DomainError>;
+    /// Returns `DomainError::NotFound` if the site has no board with that slug.
+    async fn find_by_slug(&self, site: SiteId, slug: &crate::models::Slug) -> Result<Board, DomainError>;
 
-    /// Paginated list of all boards ordered by creation date ascending.
+    /// Paginated list of the site's boards ordered by creation date ascending.
+    async fn find_by_site(&self, site: SiteId, page: Page) -> Result<Paginated<Board>, DomainError>;
+
+    /// Paginated list of all boards on every site, ordered by creation date
+    /// ascending. For maintenance jobs and backups rather than page rendering.
     async fn find_all(&self, page: Page) -> Result<Paginated<Board>, DomainError>;
 
     /// Insert (if new) or update (if existing) a board record.
@@ -80,6 +89,10 @@ pub trait BoardRepository: Send + Sync + 'static {
     /// Overwrites all fields. The caller is responsible for merging partial updates.
     async fn save_config(&self, board_id: BoardId, config: &BoardConfig) -> Result<(), DomainError>;
 
+    /// Bytes of stored originals referenced by the board's posts, for
+    /// `BoardConfig::media_quota_mb`. A file shared by several posts counts once;
+    /// thumbnails are not counted.
+    async fn media_usage_bytes(&self, board_id: BoardId) -> Result<u64, DomainError>;
 }
 
 /// Volunteer management — kept separate from `BoardRepository` so that
@@ -126,8 +139,20 @@ pub trait ThreadRepository: Send + Sync + 'static {
     /// All threads on a board for the catalog view — no pagination.
     ///
     /// Returns only OP post summary (body preview + thumbnail). Used to render the
-    /// catalog grid. Ordered by `bumped_at DESC`, sticky first.
-    async fn find_catalog(&self, board_id: BoardId) -> Result<Vec<ThreadSummary>, DomainError>;
+    /// catalog grid. Sticky threads first, then in `sort` order.
+    async fn find_catalog(&self, board_id: BoardId, sort: CatalogSort) -> Result<Vec<ThreadSummary>, DomainError>;
+
+    /// Catalog summaries of the given threads, in no particular order.
+    ///
+    /// Ids of threads that do not exist, are on another board, or would be
+    /// left out of the catalog are skipped rather than reported.
+    async fn find_summaries(&self, board_id: BoardId, ids: &[ThreadId]) -> Result<Vec<ThreadSummary>, DomainError>;
+
+    /// Threads across the site's boards for the `/all` overboard catalog,
+    /// ordered by `bumped_at DESC` — stickies get no priority — in pages of
+    /// `OverboardThread::PAGE_SIZE`. Boards with `overboard_hidden` set are
+    /// left out.
+    async fn find_overboard_threads(&self, site: SiteId, page: Page) -> Result<Paginated<OverboardThread>, DomainError>;
 
     /// Insert a new thread row and return the assigned `ThreadId`.
     async fn save(&self, thread: &Thread) -> Result<ThreadId, DomainError>;
@@ -177,6 +202,27 @@ pub trait ThreadRepository: Send + Sync + 'static {
     async fn delete(&self, id: ThreadId) -> Result<(), DomainError>;
 }
 
+/// When boards and threads last changed, for conditional GET.
+///
+/// The composition root wires `PgLastModifiedRepository`; the timestamps are
+/// kept by database triggers, so every write counts without the writer's help.
+#[cfg_attr(any(test, feature = "testing"), mockall::automock)]
+#[async_trait]
+pub trait LastModifiedRepository: Send + Sync + 'static {
+    /// The last change to anything on the board's index or catalog: its
+    /// threads, settings, blotter, title or rules. `None` for no such board.
+    async fn board_modified_at(&self, board_id: BoardId) -> Result<Option<DateTime<Utc>>, DomainError>;
+
+    /// The last change to anything on the thread's page: its posts and their
+    /// attachments, reactions and poll, or the board's settings, blotter,
+    /// title or rules. `None` unless the thread is on `board_id`.
+    async fn thread_modified_at(
+        &self,
+        board_id: BoardId,
+        thread_id: ThreadId,
+    ) -> Result<Option<DateTime<Utc>>, DomainError>;
+}
+
 /// Persistence boundary for `Post` entities.
 ///
 /// The composition root wires this to `PgPostRepository` (feature: `db-postgres`).
@@ -196,6 +242,18 @@ pub trait PostRepository: Send + Sync + 'static {
     /// Used in moderation to find all posts by a poster before issuing a ban.
     async fn find_by_ip_hash(&self, ip_hash: &IpHash) -> Result<Vec<Post>, DomainError>;
 
+    /// Posts made from any of `ip_hashes` on the site's boards, newest first,
+    /// with their board's slug.
+    ///
+    /// Backs the moderator's IP history page. A raw IP has one hash per daily
+    /// salt, so callers pass every hash it had in the period they look at.
+    async fn find_by_ip_hashes(
+        &self,
+        site:      SiteId,
+        ip_hashes: &[IpHash],
+        page:      Page,
+    ) -> Result<Paginated<OverboardPost>, DomainError>;
+
     /// The `ContentHash` values of the most recent `limit` posts on a board.
     ///
     /// Used for duplicate content detection in spam heuristics. Ordered by
@@ -210,13 +268,16 @@ pub trait PostRepository: Send + Sync + 'static {
     /// the repository generates UUIDs for new records.
     async fn save(&self, post: &Post) -> Result<(PostId, u64), DomainError>;
 
-    /// Delete a single post.
+    /// Delete a single post, leaving a tombstone deleted by `by`.
     ///
-    /// Does not cascade to the thread (thread deletion is via `ThreadRepository::delete`).
-    /// Returns `DomainError::NotFound` if the post does not exist.
-    async fn delete(&self, id: PostId) -> Result<(), DomainError>;
+    /// The row keeps its number and place in the thread until `purge_deleted`;
+    /// its attachment rows go at once. Does not cascade to the thread (thread
+    /// deletion is via `ThreadRepository::delete`). Returns
+    /// `DomainError::NotFound` if the post does not exist or is already deleted.
+    async fn delete(&self, id: PostId, by: crate::models::DeletedBy) -> Result<(), DomainError>;
 
-    /// Delete all posts by a given IP hash within a specific thread.
+    /// Delete all posts by a given IP hash within a specific thread, leaving
+    /// tombstones deleted by staff, as `delete` does.
     ///
     /// Used for the [D*] moderation action. Returns the number of deleted posts.
     /// Returns `Ok(0)` if no posts matched (not an error).
@@ -226,6 +287,49 @@ pub trait PostRepository: Send + Sync + 'static {
         thread_id: ThreadId,
     ) -> Result<u64, DomainError>;
 
+    /// Delete, in one transaction, every post made from `ip_hash` on a board
+    /// since `since`.
+    ///
+    /// Replies become tombstones deleted by staff; a matched opening post
+    /// takes its whole thread with it. Returns what was removed, with the
+    /// attachments loaded before the rows went so the caller can purge their
+    /// files. Nothing matching is not an error.
+    async fn delete_by_ip_on_board(
+        &self,
+        ip_hash:  &IpHash,
+        board_id: BoardId,
+        since:    DateTime<Utc>,
+    ) -> Result<crate::models::PurgedPosts, DomainError>;
+
+    /// Permanently remove tombstones deleted more than `older_than_days` days
+    /// ago. An opening post's tombstone stays as long as its thread.
+    ///
+    /// Returns the number of posts removed.
+    async fn purge_deleted(&self, older_than_days: u32) -> Result<u32, DomainError>;
+
+    /// Replace a post's body and subject in one transaction, keeping the old
+    /// ones as a `PostRevision` by `edited_by` and setting `Post::edited_at`.
+    ///
+    /// Returns `DomainError::NotFound` if the post does not exist or is deleted.
+    async fn edit(
+        &self,
+        id:        PostId,
+        body:      &str,
+        subject:   Option<String>,
+        edited_by: UserId,
+    ) -> Result<(), DomainError>;
+
+    /// The revisions of a post, newest first. Empty for a post never edited.
+    async fn find_revisions(&self, id: PostId) -> Result<Vec<PostRevision>, DomainError>;
+
+    /// Apply a `PostMove` in one transaction: create `new_thread` if set,
+    /// reparent the posts, store the rewritten bodies, and recount the replies
+    /// of both threads.
+    ///
+    /// Returns `DomainError::NotFound` when any of the posts is no longer in
+    /// `from`; nothing is changed then.
+    async fn move_posts(&self, post_move: &crate::models::PostMove) -> Result<(), DomainError>;
+
     /// Persist attachment metadata records for a post.
     ///
     /// Called immediately after `save()`. The files themselves are already in media
@@ -242,10 +346,15 @@ pub trait PostRepository: Send + Sync + 'static {
     ) -> Result<std::collections::HashMap<PostId, Vec<crate::models::Attachment>>, DomainError>;
 
     /// Recent posts across all boards for the overboard view, ordered by `created_at DESC`.
+    /// Tombstones are left out.
     ///
     /// Returns `OverboardPost` entries enriched with `board_slug` so the template
     /// can build links to the parent thread without additional lookups.
-    async fn find_overboard(&self, page: Page) -> Result<Paginated<OverboardPost>, DomainError>;
+    async fn find_overboard(&self, site: SiteId, page: Page) -> Result<Paginated<OverboardPost>, DomainError>;
+
+    /// The `recent` newest posts across the site's boards (as in `find_overboard`)
+    /// with the site's thread, post and last-24-hours post counts. Backs the homepage.
+    async fn find_site_activity(&self, site: SiteId, recent: u32) -> Result<SiteActivity, DomainError>;
 
     /// Full-text search for posts on a single board.
     ///
@@ -254,7 +363,8 @@ pub trait PostRepository: Send + Sync + 'static {
     /// using the existing GIN index on `posts.body`. Results are ordered by relevance
     /// (`ts_rank`) descending.
     ///
-    /// Returns an empty `Paginated` when no results match — never `NotFound`.
+    /// Tombstones and shadowbanned posts are left out. Returns an empty
+    /// `Paginated` when no results match — never `NotFound`.
     async fn search_fulltext(
         &self,
         board_id: crate::models::BoardId,
@@ -268,6 +378,15 @@ pub trait PostRepository: Send + Sync + 'static {
     /// bump limit). Callers should not assume the list is exhaustive beyond 500 posts.
     async fn find_all_by_thread(&self, thread_id: ThreadId) -> Result<Vec<Post>, DomainError>;
 
+    /// `find_all_by_thread` as seen by `viewer`: unless it is `Viewer::Staff`,
+    /// posts made from a shadowbanned IP hash are left out, except the
+    /// viewer's own, and tombstones come back redacted (`Post::redact`).
+    async fn find_visible_by_thread(
+        &self,
+        thread_id: ThreadId,
+        viewer: &crate::models::Viewer,
+    ) -> Result<Vec<Post>, DomainError>;
+
     /// Resolve a board-scoped post number to its containing `ThreadId`.
     ///
     /// Used by the cross-board `>>>/{slug}/{N}` redirect handler. Returns `None`
@@ -283,14 +402,17 @@ pub trait PostRepository: Send + Sync + 'static {
     /// Pinned posts are never pruned during cycle rotation.
     async fn set_pinned(&self, id: PostId, pinned: bool) -> Result<(), DomainError>;
 
-    /// Return the oldest non-OP, non-pinned post in a thread by `post_number ASC`.
+    /// Return the replies to delete so a cycle thread keeps at most `keep` replies.
     ///
-    /// Returns `None` when every reply is pinned or the thread has no replies.
+    /// Candidates are non-OP, non-pinned posts, oldest first by `post_number`.
+    /// Pinned replies count toward `keep` but are never returned, so the result
+    /// may be shorter than the excess. Empty when the thread is within `keep`.
     /// Used by cycle-mode pruning in `PostService::create_post`.
-    async fn find_oldest_unpinned_reply(
+    async fn find_cycle_excess(
         &self,
         thread_id: ThreadId,
-    ) -> Result<Option<PostId>, DomainError>;
+        keep:      u32,
+    ) -> Result<Vec<PostId>, DomainError>;
 
     /// Look up an existing attachment by its SHA-256 content hash (v1.2 — deduplication).
     ///
@@ -301,8 +423,93 @@ pub trait PostRepository: Send + Sync + 'static {
         hash: &crate::models::ContentHash,
     ) -> Result<Option<crate::models::Attachment>, DomainError>;
 
-    /// Delete a single post by ID. Caller is responsible for cascade logic.
+    /// Remove a single post by ID for good, without a tombstone. Used by
+    /// cycle-mode pruning. Caller is responsible for cascade logic.
     async fn delete_by_id(&self, id: PostId) -> Result<(), DomainError>;
+
+    /// Every `media_key` and `thumbnail_key` still referenced by an attachment.
+    ///
+    /// Used by the orphaned-media sweep: stored objects outside this set belong
+    /// to no post and may be deleted.
+    async fn find_referenced_media_keys(
+        &self,
+    ) -> Result<std::collections::HashSet<MediaKey>, DomainError>;
+
+    /// Every attachment that has a thumbnail, with the board its post is on.
+    ///
+    /// Used by `rusty-board regen-thumbs`; not called on the request path.
+    async fn find_thumbnailed_attachments(
+        &self,
+    ) -> Result<Vec<(BoardId, crate::models::Attachment)>, DomainError>;
+
+    /// Every attachment, with the board its post is on.
+    ///
+    /// Used by `rusty-board storage verify`; not called on the request path.
+    async fn find_all_attachments(
+        &self,
+    ) -> Result<Vec<(BoardId, crate::models::Attachment)>, DomainError>;
+
+    /// The MIME type the original stored at `media_key` was saved with.
+    ///
+    /// `None` for thumbnails and keys no attachment references. Used by the
+    /// local media route to set `Content-Type`.
+    async fn find_media_mime(&self, media_key: &MediaKey) -> Result<Option<String>, DomainError>;
+
+    /// Point every attachment whose original is `media_key` at a new thumbnail.
+    ///
+    /// Returns the number of attachments updated.
+    async fn set_thumbnail_key(
+        &self,
+        media_key: &MediaKey,
+        thumbnail_key: &MediaKey,
+    ) -> Result<u64, DomainError>;
+
+    /// Insert a post exactly as given, keeping its `id` and `post_number`.
+    ///
+    /// Used by `rusty-board import`; `save` would assign a new number and
+    /// break `>>N` quotes. Raises the board's post counter to at least
+    /// `post_number` so new posts continue after the restored ones. A
+    /// tombstone is restored as one.
+    async fn restore(&self, post: &Post) -> Result<(), DomainError>;
+
+    /// The post numbers in use on a board. `None` when it has no posts.
+    ///
+    /// Used by the NNTP gateway to describe a board's newsgroup.
+    async fn find_number_range(&self, board_id: BoardId) -> Result<Option<PostNumberRange>, DomainError>;
+
+    /// Posts on a board numbered `first..=last`, ordered by `post_number ASC`,
+    /// at most `limit` of them. Tombstones and shadowbanned posts are left out.
+    async fn find_by_number_range(
+        &self,
+        board_id: BoardId,
+        first: u64,
+        last: u64,
+        limit: u32,
+    ) -> Result<Vec<Post>, DomainError>;
+
+    /// The post with `id`, unless it is a tombstone or shadowbanned.
+    ///
+    /// `None` for those and for unknown ids, so a reader cannot tell them
+    /// apart. Used by the NNTP gateway to look posts up by Message-ID.
+    async fn find_visible_by_id(&self, id: PostId) -> Result<Option<Post>, DomainError>;
+
+    /// The posts among `ids` that are in thread `thread_id`, ordered by
+    /// `post_number ASC`, however long the thread.
+    ///
+    /// Ids of posts elsewhere, or of no post, are left out. Used by
+    /// `ModerationService::move_posts` to load the selection.
+    async fn find_by_ids(&self, thread_id: ThreadId, ids: &[PostId]) -> Result<Vec<Post>, DomainError>;
+
+    /// The posts of thread `thread_id` numbered one of `post_numbers`,
+    /// ordered by `post_number ASC`.
+    async fn find_by_numbers(&self, thread_id: ThreadId, post_numbers: &[u64]) -> Result<Vec<Post>, DomainError>;
+
+    /// The posts of thread `thread_id` whose body has `>>N` for some `N` in
+    /// `post_numbers`, ordered by `post_number ASC`.
+    ///
+    /// A superset: `>>>/slug/N` matches too. Used by
+    /// `ModerationService::move_posts` to find the quotes a move separates.
+    async fn find_quoting(&self, thread_id: ThreadId, post_numbers: &[u64]) -> Result<Vec<Post>, DomainError>;
 }
 
 /// Persistence boundary for `Ban` records.
@@ -326,6 +533,147 @@ pub trait BanRepository: Send + Sync + 'static {
 
     /// Paginated list of all bans (active and expired) for moderator review.
     async fn find_all(&self, page: Page) -> Result<Paginated<Ban>, DomainError>;
+
+    /// Delete bans that expired more than `older_than_days` days ago.
+    ///
+    /// Permanent bans are never deleted. Returns the number of bans removed.
+    /// Called by the scheduled `expire-bans` job.
+    async fn delete_expired(&self, older_than_days: u32) -> Result<u32, DomainError>;
+}
+
+/// Persistence boundary for `AsnBan` records.
+///
+/// Kept separate from `BanRepository` so deployments without an ASN database
+/// need not implement it. The composition root wires `PgAsnBanRepository`.
+#[cfg_attr(any(test, feature = "testing"), mockall::automock)]
+#[async_trait]
+pub trait AsnBanRepository: Send + Sync + 'static {
+    /// Returns the active (non-expired) ban for the given ASN, if any.
+    async fn find_active_by_asn(&self, asn: u32) -> Result<Option<AsnBan>, DomainError>;
+
+    /// Insert a new ASN ban and return the assigned `BanId`.
+    async fn save(&self, ban: &AsnBan) -> Result<BanId, DomainError>;
+
+    /// Mark an ASN ban as expired immediately.
+    ///
+    /// Returns `DomainError::NotFound` if the ban does not exist.
+    async fn expire(&self, id: BanId) -> Result<(), DomainError>;
+
+    /// Paginated list of all ASN bans (active and expired) for moderator review.
+    async fn find_all(&self, page: Page) -> Result<Paginated<AsnBan>, DomainError>;
+}
+
+/// Persistence boundary for `HashBan` records.
+///
+/// The composition root wires `PgHashBanRepository`.
+#[cfg_attr(any(test, feature = "testing"), mockall::automock)]
+#[async_trait]
+pub trait HashBanRepository: Send + Sync + 'static {
+    /// Returns the active (non-expired) ban on the given hex MD5, if any.
+    async fn find_active_by_md5(&self, md5: &str) -> Result<Option<HashBan>, DomainError>;
+
+    /// All active bans that carry a perceptual hash.
+    ///
+    /// Near-duplicate matching is a Hamming-distance scan done by the caller;
+    /// ban lists are small enough that this beats an index.
+    async fn find_active_perceptual(&self) -> Result<Vec<HashBan>, DomainError>;
+
+    /// Insert a new hash ban and return the assigned `BanId`.
+    async fn save(&self, ban: &HashBan) -> Result<BanId, DomainError>;
+
+    /// Mark a hash ban as expired immediately.
+    ///
+    /// Returns `DomainError::NotFound` if the ban does not exist.
+    async fn expire(&self, id: BanId) -> Result<(), DomainError>;
+
+    /// Paginated list of all hash bans (active and expired) for moderator review.
+    async fn find_all(&self, page: Page) -> Result<Paginated<HashBan>, DomainError>;
+}
+
+/// Persistence boundary for `Warning` records.
+///
+/// The composition root wires `PgWarningRepository`.
+#[cfg_attr(any(test, feature = "testing"), mockall::automock)]
+#[async_trait]
+pub trait WarningRepository: Send + Sync + 'static {
+    /// The oldest unacknowledged warning for the given IP hash, if any.
+    async fn find_pending_by_ip(&self, ip_hash: &IpHash) -> Result<Option<Warning>, DomainError>;
+
+    /// Insert a new warning and return the assigned `BanId`.
+    async fn save(&self, warning: &Warning) -> Result<BanId, DomainError>;
+
+    /// Mark a pending warning acknowledged, now.
+    ///
+    /// Returns `DomainError::NotFound` unless `id` is a pending warning for
+    /// `ip_hash`, so nobody can acknowledge another poster's warning.
+    async fn acknowledge(&self, id: BanId, ip_hash: &IpHash) -> Result<(), DomainError>;
+}
+
+/// Persistence boundary for accounts' TOTP second factors.
+///
+/// The composition root wires `PgTotpRepository`.
+#[cfg_attr(any(test, feature = "testing"), mockall::automock)]
+#[async_trait]
+pub trait TotpRepository: Send + Sync + 'static {
+    /// The factor for `user_id`, pending or enabled, if any.
+    async fn find(&self, user_id: UserId) -> Result<Option<StaffTotp>, DomainError>;
+
+    /// Insert or replace the factor for `totp.user_id`.
+    async fn save(&self, totp: &StaffTotp) -> Result<(), DomainError>;
+
+    /// Count an attempt at a code against `user_id`, unless its factor is
+    /// locked at `now`. The `limit`-th attempt since the last accepted code
+    /// locks it until `locked_until`; a lock that has run out starts the
+    /// count over.
+    ///
+    /// Returns `false` if the factor is locked or missing. Atomic, so
+    /// concurrent guesses cannot get past the limit.
+    async fn claim_attempt(
+        &self,
+        user_id: UserId,
+        limit: u32,
+        now: DateTime<Utc>,
+        locked_until: DateTime<Utc>,
+    ) -> Result<bool, DomainError>;
+
+    /// Accept a TOTP code for `step`: record it as the last step and clear
+    /// the attempt count. Returns `false` if `step` is not later than the last
+    /// accepted one — the code was already used, perhaps concurrently.
+    async fn consume_step(&self, user_id: UserId, step: i64) -> Result<bool, DomainError>;
+
+    /// Spend the recovery code hashed as `hash` and clear the attempt count.
+    /// Returns `false` if it is not (or no longer) one of the unused codes.
+    async fn consume_recovery_code(&self, user_id: UserId, hash: &str) -> Result<bool, DomainError>;
+
+    /// Remove the factor for `user_id`. Succeeds if there is none.
+    async fn delete(&self, user_id: UserId) -> Result<(), DomainError>;
+}
+
+/// Persistence boundary for accounts created by an external identity
+/// provider (SSO), keyed by the provider's issuer and subject (`iss`, `sub`).
+///
+/// The composition root wires `PgExternalIdentityRepository`.
+#[cfg_attr(any(test, feature = "testing"), mockall::automock)]
+#[async_trait]
+pub trait ExternalIdentityRepository: Send + Sync + 'static {
+    /// The account linked to `subject` at `issuer`, if any.
+    async fn find_user(&self, issuer: &str, subject: &str) -> Result<Option<UserId>, DomainError>;
+
+    /// Link `subject` at `issuer` to `user_id`. Fails if the identity is
+    /// already linked.
+    async fn link(&self, issuer: &str, subject: &str, user_id: UserId) -> Result<(), DomainError>;
+}
+
+/// IP → autonomous system number lookup boundary.
+///
+/// Implemented over a MaxMind GeoLite2-ASN (or compatible) database by the
+/// `geoip-asn` feature. **Fail-open**: unknown or unparsable addresses return
+/// `Ok(None)`.
+#[cfg_attr(any(test, feature = "testing"), mockall::automock)]
+#[async_trait]
+pub trait AsnResolver: Send + Sync + 'static {
+    /// Resolve `ip` to the ASN announcing it, or `None` if unknown.
+    async fn lookup_asn(&self, ip: &str) -> Result<Option<u32>, DomainError>;
 }
 
 /// Persistence boundary for `Flag` (report) records.
@@ -355,6 +703,10 @@ pub trait FlagRepository: Send + Sync + 'static {
         resolution: FlagResolution,
         resolved_by: UserId,
     ) -> Result<(), DomainError>;
+
+    /// Number of pending flags on a post. Used to alert moderators once a
+    /// post is reported often enough.
+    async fn count_pending_for_post(&self, post_id: PostId) -> Result<u64, DomainError>;
 }
 
 /// Write and read boundary for audit log entries.
@@ -382,12 +734,13 @@ pub trait AuditRepository: Send + Sync + 'static {
     /// All audit entries site-wide, newest first. Used by the Janitor audit log page.
     async fn find_all(&self, page: Page) -> Result<Paginated<AuditEntry>, DomainError>;
 
-    /// Audit entries for a specific board (all actions whose `target_type` links to
-    /// the given board). Used by Board Owner and Volunteer audit log pages.
-    ///
-    /// Scoped by board via a JSON detail field lookup — slower than an indexed column
-    /// but avoids a schema change. For v1.2 a dedicated `board_id` column is planned.
+    /// Audit entries for a specific board (those whose `board_id` is the given
+    /// board), newest first. Used by Board Owner and Volunteer audit log pages.
     async fn find_by_board(&self, board_id: crate::models::BoardId, page: Page) -> Result<Paginated<AuditEntry>, DomainError>;
+
+    /// The board's entries whose action is in `AuditAction::PUBLIC`, newest
+    /// first. Used by the public `/board/{slug}/log`; callers redact them.
+    async fn find_public_by_board(&self, board_id: crate::models::BoardId, page: Page) -> Result<Paginated<AuditEntry>, DomainError>;
 }
 
 /// Persistence boundary for moderator/admin `User` accounts.
@@ -438,6 +791,68 @@ pub trait UserRepository: Send + Sync + 'static {
 
 // ─── Media Ports ─────────────────────────────────────────────────────────────
 
+/// Where the bytes of an upload, or of a processed original, live.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MediaData {
+    /// Held in memory. Used for re-encoded originals and in tests.
+    Memory(Bytes),
+    /// Spooled to a file by the HTTP adapter, which also owns (and removes)
+    /// the file once the request completes.
+    Spooled(SpooledFile),
+}
+
+impl MediaData {
+    /// Size in bytes.
+    pub fn len(&self) -> u64 {
+        match self {
+            Self::Memory(bytes) => bytes.len() as u64,
+            Self::Spooled(file) => file.size,
+        }
+    }
+
+    /// `true` if there are no bytes at all.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The leading bytes, enough for magic-byte MIME sniffing.
+    pub fn head(&self) -> &[u8] {
+        match self {
+            Self::Memory(bytes) => bytes,
+            Self::Spooled(file) => &file.head,
+        }
+    }
+}
+
+impl From<Bytes> for MediaData {
+    fn from(bytes: Bytes) -> Self {
+        Self::Memory(bytes)
+    }
+}
+
+/// An upload written to a temporary file as it streamed in.
+///
+/// The size and SHA-256 are computed incrementally while spooling, so
+/// neither requires reading the file back.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpooledFile {
+    /// Location of the temporary file.
+    pub path: PathBuf,
+    /// File size in bytes.
+    pub size: u64,
+    /// SHA-256 of the file contents.
+    pub hash: ContentHash,
+    /// Hex MD5 of the file contents, for hash-ban matching.
+    pub md5: String,
+    /// The first `SpooledFile::HEAD_LEN` bytes (or the whole file if shorter).
+    pub head: Bytes,
+}
+
+impl SpooledFile {
+    /// How many leading bytes are kept in `head`.
+    pub const HEAD_LEN: usize = 8 * 1024;
+}
+
 /// Raw (unprocessed) media uploaded by a poster.
 ///
 /// Passed into `MediaProcessor::process`. The `mime` field is validated
@@ -448,8 +863,14 @@ pub struct RawMedia {
     pub filename: String,
     /// The MIME type as determined by sniffing (not from the Content-Type header alone).
     pub mime: Mime,
-    /// The raw file bytes.
-    pub data: Bytes,
+    /// The raw file contents, usually spooled to disk by the HTTP adapter.
+    pub data: MediaData,
+    /// Strip EXIF/XMP/GPS metadata from the stored original.
+    /// `PostService` sets this from `BoardConfig::strip_metadata`.
+    pub strip_metadata: bool,
+    /// Thumbnail spec overriding the processor's default.
+    /// `PostService` sets this from `BoardConfig::thumbnail`.
+    pub thumbnail: Option<crate::models::ThumbnailSpec>,
 }
 
 /// Processed media ready for storage, returned by `MediaProcessor::process`.
@@ -457,22 +878,56 @@ pub struct RawMedia {
 pub struct ProcessedMedia {
     /// Deterministic storage key: `{board_slug}/{thread_id}/{post_uuid}.{ext}`.
     pub original_key: MediaKey,
-    /// MIME-validated and normalised original bytes.
-    pub original_data: Bytes,
+    /// MIME-validated and normalised original. Processors that store the upload
+    /// unmodified pass the spooled file through instead of loading it.
+    pub original_data: MediaData,
     /// Thumbnail storage key. `None` if no thumbnail was generated (e.g. unsupported MIME).
     pub thumbnail_key: Option<MediaKey>,
-    /// Compressed thumbnail bytes. `None` if no thumbnail was generated.
+    /// Compressed thumbnail bytes. `None` if no thumbnail was generated, or if
+    /// it is left for the background (`thumbnail_task` is then set).
     pub thumbnail_data: Option<Bytes>,
+    /// Thumbnail work left for the background. Nothing runs until the caller
+    /// submits it with the key the attachment keeps, so an upload that turns
+    /// out to be a duplicate, or a rejected post, leaves no thumbnail behind.
+    pub thumbnail_task: Option<Arc<dyn ThumbnailTask>>,
     /// SHA-256 of `original_data` bytes, used for duplicate detection.
     pub hash: ContentHash,
     /// Size of the original file in kilobytes.
     pub size_kb: u32,
+    /// Playback length in milliseconds for audio/video. `None` for still images.
+    pub duration_ms: Option<u32>,
+    /// Number of pages for documents (PDF). `None` for other media.
+    pub page_count: Option<u32>,
+    /// Hex MD5 of the original upload, for hash-ban matching.
+    pub md5: String,
+    /// 64-bit difference hash of still images, for near-duplicate hash bans.
+    /// `None` for other media.
+    pub phash: Option<u64>,
+    /// Pixel width of images and video. `None` for other media.
+    pub width: Option<u32>,
+    /// Pixel height of images and video. `None` for other media.
+    pub height: Option<u32>,
+    /// MIME type of `original_data` when the processor changed the format
+    /// (e.g. a JPEG re-encoded to AVIF). `None` when stored as uploaded.
+    pub stored_mime: Option<String>,
+}
+
+/// A thumbnail a `MediaProcessor` left to render in the background.
+#[cfg_attr(any(test, feature = "testing"), mockall::automock)]
+#[async_trait]
+pub trait ThumbnailTask: std::fmt::Debug + Send + Sync + 'static {
+    /// Render the thumbnail and store it under `key` in the background.
+    ///
+    /// When the background is busy the thumbnail is rendered here instead and
+    /// returned, for the caller to store; `None` means it was queued.
+    async fn submit(&self, key: MediaKey) -> Result<Option<Bytes>, DomainError>;
 }
 
 /// Object storage boundary for media files.
 ///
-/// The composition root wires this to `S3MediaStorage` (`media-s3`) or
-/// `LocalFsMediaStorage` (`media-local`) based on the active feature flag.
+/// The composition root wires this to `LocalFsMediaStorage`, `S3MediaStorage`
+/// or `IpfsMediaStorage`, whichever of the compiled-in backends
+/// `MEDIA_BACKEND` names.
 #[cfg_attr(any(test, feature = "testing"), mockall::automock)]
 #[async_trait]
 pub trait MediaStorage: Send + Sync + 'static {
@@ -486,35 +941,106 @@ pub trait MediaStorage: Send + Sync + 'static {
         content_type: &str,
     ) -> Result<(), DomainError>;
 
+    /// Store the file at `path` under `key` without loading it into memory.
+    ///
+    /// The file itself is left in place; the caller owns its cleanup.
+    /// Returns `DomainError::Internal` if the file cannot be read or the
+    /// storage backend returns an error.
+    async fn store_file(
+        &self,
+        key: &MediaKey,
+        path: &Path,
+        content_type: &str,
+    ) -> Result<(), DomainError>;
+
     /// Generate a URL for the object at `key` valid for at least `ttl`.
     ///
     /// For S3, this is a pre-signed URL. For local filesystem, this is a
     /// static public path (the `ttl` argument is ignored).
     async fn get_url(&self, key: &MediaKey, ttl: Duration) -> Result<String, DomainError>;
 
+    /// Read the object at `key` into memory.
+    ///
+    /// Returns `DomainError::NotFound` if nothing is stored there. Used by
+    /// maintenance commands; the request path serves media via `get_url`.
+    async fn fetch(&self, key: &MediaKey) -> Result<Bytes, DomainError>;
+
     /// Delete the object at `key`.
     ///
     /// Returns `Ok(())` even if the object does not exist (idempotent delete).
     async fn delete(&self, key: &MediaKey) -> Result<(), DomainError>;
+
+    /// Whether an object is stored at `key`.
+    ///
+    /// Used to confirm that a purge actually removed the file.
+    async fn exists(&self, key: &MediaKey) -> Result<bool, DomainError>;
+
+    /// List every stored object with its last-modified time.
+    ///
+    /// Used by the orphaned-media sweep; not called on the request path.
+    async fn list(&self) -> Result<Vec<StoredMedia>, DomainError>;
+}
+
+/// A shared backend chosen at runtime (`Arc<dyn MediaStorage>`) is itself a
+/// `MediaStorage`, so services can be generic over it.
+#[async_trait]
+impl<T: MediaStorage + ?Sized> MediaStorage for Arc<T> {
+    async fn store(&self, key: &MediaKey, data: Bytes, content_type: &str) -> Result<(), DomainError> {
+        (**self).store(key, data, content_type).await
+    }
+
+    async fn store_file(&self, key: &MediaKey, path: &Path, content_type: &str) -> Result<(), DomainError> {
+        (**self).store_file(key, path, content_type).await
+    }
+
+    async fn get_url(&self, key: &MediaKey, ttl: Duration) -> Result<String, DomainError> {
+        (**self).get_url(key, ttl).await
+    }
+
+    async fn fetch(&self, key: &MediaKey) -> Result<Bytes, DomainError> {
+        (**self).fetch(key).await
+    }
+
+    async fn delete(&self, key: &MediaKey) -> Result<(), DomainError> {
+        (**self).delete(key).await
+    }
+
+    async fn exists(&self, key: &MediaKey) -> Result<bool, DomainError> {
+        (**self).exists(key).await
+    }
+
+    async fn list(&self) -> Result<Vec<StoredMedia>, DomainError> {
+        (**self).list().await
+    }
+}
+
+/// An object in media storage, as returned by `MediaStorage::list`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredMedia {
+    /// Storage key of the object.
+    pub key: MediaKey,
+    /// When the object was last written.
+    pub modified: DateTime<Utc>,
 }
 
 /// Media processing boundary: validate, EXIF-strip, thumbnail-generate.
 ///
-/// The composition root selects the correct processor based on active features:
-/// - `ImageMediaProcessor` (always compiled) — images only
-/// - `VideoMediaProcessor` (`video` feature) — images + video keyframe extraction
-/// - `FullMediaProcessor` (`video` + `documents` features) — images + video + PDFs
+/// The composition root chains the processors of the active features into a
+/// `CompositeMediaProcessor` (images always; video, audio, and documents behind
+/// their feature flags).
 ///
 /// # Invariant
-/// EXIF stripping is always performed — it is not a `BoardConfig` toggle.
+/// Metadata stripping is on unless the board owner explicitly disables it via
+/// `BoardConfig::strip_metadata` (carried on `RawMedia::strip_metadata`).
+/// Thumbnails never carry metadata.
 #[cfg_attr(any(test, feature = "testing"), mockall::automock)]
 #[async_trait]
 pub trait MediaProcessor: Send + Sync + 'static {
     /// Process raw uploaded media.
     ///
-    /// Steps (unconditional):
+    /// Steps:
     /// 1. Validate MIME type (returns `DomainError::Validation` if unsupported)
-    /// 2. Strip EXIF metadata
+    /// 2. Strip EXIF/XMP/GPS metadata (when `input.strip_metadata`)
     /// 3. Generate a thumbnail PNG at 320px width
     /// 4. Compute SHA-256 content hash of the original bytes
     ///
@@ -652,8 +1178,8 @@ pub enum RateLimitStatus {
 
 /// Rate limiting boundary for per-IP, per-board post creation.
 ///
-/// The composition root wires this to `RedisRateLimiter` (`redis`) or
-/// `NoopRateLimiter` in dev/test environments.
+/// The composition root wires this to `RedisRateLimiter` or
+/// `InMemoryRateLimiter`, as `CACHE_BACKEND` selects.
 #[cfg_attr(any(test, feature = "testing"), mockall::automock)]
 #[async_trait]
 pub trait RateLimiter: Send + Sync + 'static {
@@ -673,6 +1199,44 @@ pub trait RateLimiter: Send + Sync + 'static {
     async fn reset(&self, key: &RateLimitKey) -> Result<(), DomainError>;
 }
 
+/// A shared limiter chosen at runtime (`Arc<dyn RateLimiter>`) is itself a
+/// `RateLimiter`.
+#[async_trait]
+impl<T: RateLimiter + ?Sized> RateLimiter for Arc<T> {
+    async fn check(&self, key: &RateLimitKey) -> Result<RateLimitStatus, DomainError> {
+        (**self).check(key).await
+    }
+
+    async fn increment(&self, key: &RateLimitKey, window_secs: u32) -> Result<(), DomainError> {
+        (**self).increment(key, window_secs).await
+    }
+
+    async fn reset(&self, key: &RateLimitKey) -> Result<(), DomainError> {
+        (**self).reset(key).await
+    }
+}
+
+// ─── Cache Store Port ────────────────────────────────────────────────────────
+
+/// Shared key/value cache for derived data (rendered pages, board settings).
+///
+/// Values are opaque bytes; callers own the encoding. Every entry carries a
+/// TTL, so a lost invalidation only ever serves stale data for that long.
+/// The composition root wires this to `RedisCacheStore` (`CACHE_BACKEND=redis`)
+/// so that every instance sees the same entries, or to `InMemoryCacheStore`.
+#[cfg_attr(any(test, feature = "testing"), mockall::automock)]
+#[async_trait]
+pub trait CacheStore: Send + Sync + 'static {
+    /// Return the value stored under `key`, or `None` if absent or expired.
+    async fn get(&self, key: &str) -> Result<Option<Bytes>, DomainError>;
+
+    /// Store `value` under `key`, replacing any previous value. Expires after `ttl`.
+    async fn set(&self, key: &str, value: Bytes, ttl: Duration) -> Result<(), DomainError>;
+
+    /// Remove `key`. Removing an absent key is not an error.
+    async fn invalidate(&self, key: &str) -> Result<(), DomainError>;
+}
+
 // ─── StaffRequest Repository Port ────────────────────────────────────────────
 
 /// Persistence boundary for staff escalation requests.
@@ -766,11 +1330,14 @@ pub trait StaffMessageRepository: Send + Sync + 'static {
     async fn delete_expired(&self, older_than_days: u32) -> Result<u32, DomainError>;
 }
 
-/// DNSBL (DNS Block List) checking boundary.
+/// DNSBL (DNS Block List) / IP reputation checking boundary.
 ///
-/// Checks whether a given IPv4 address appears in a configured block list.
-/// The implementation performs a DNS A-record lookup of the reversed IP address
-/// against the DNSBL zone (e.g. `1.2.3.4` → `4.3.2.1.zen.spamhaus.org`).
+/// Checks whether a given IP address appears in a configured block list.
+/// The DNS implementation performs an A-record lookup of the reversed IP address
+/// against the DNSBL zone (e.g. `1.2.3.4` → `4.3.2.1.zen.spamhaus.org`); other
+/// implementations consult the Tor exit list, combine several sources, or cache
+/// results. How a listed IP is treated is decided per board by
+/// `BoardConfig::dnsbl_action`.
 ///
 /// **Fail-open**: all implementations must return `Ok(false)` on DNS timeout
 /// or lookup failure rather than blocking the post. A degraded DNSBL service
@@ -787,6 +1354,32 @@ pub trait DnsblChecker: Send + Sync + 'static {
     async fn is_blocked(&self, ip: &str) -> Result<bool, DomainError>;
 }
 
+/// Content classification boundary for uploads (e.g. NSFW detection).
+///
+/// `PostService` calls the classifier after an upload has been processed and
+/// before it is stored, for boards whose `BoardConfig::classifier_action` is
+/// not `Off`. The returned labels are recorded in `MediaMeta::labels`; a label
+/// scoring at least `BoardConfig::classifier_threshold` triggers the board's
+/// action (spoiler the attachment, or flag the post for review).
+///
+/// **Fail-open**: callers treat `Err` as "no labels" — a broken or slow
+/// classifier must never block posting.
+///
+/// The composition root wires `NoopMediaClassifier` (no labels) unless the
+/// `nsfw-onnx` feature supplies a real one.
+#[cfg_attr(any(test, feature = "testing"), mockall::automock)]
+#[async_trait]
+pub trait MediaClassifier: Send + Sync + 'static {
+    /// Label the stored original `data` of MIME type `mime`.
+    ///
+    /// Returns an empty list for media the classifier does not handle.
+    async fn classify(
+        &self,
+        mime: &Mime,
+        data: &MediaData,
+    ) -> Result<Vec<crate::models::MediaLabel>, DomainError>;
+}
+
 /// Archive store for pruned threads.
 ///
 /// When `board_config.archive_enabled` is true, `ThreadService::prune_if_needed`
@@ -816,3 +1409,497 @@ pub trait ArchiveRepository: Send + Sync + 'static {
         page: crate::models::Page,
     ) -> Result<crate::models::Paginated<crate::models::Thread>, DomainError>;
 }
+
+/// Persistence boundary for `Site` records — the imageboards served by one
+/// process, keyed by host name.
+///
+/// The default site (`SiteId::DEFAULT`) always exists; it cannot be deleted.
+/// The composition root wires `PgSiteRepository`.
+#[cfg_attr(any(test, feature = "testing"), mockall::automock)]
+#[async_trait]
+pub trait SiteRepository: Send + Sync + 'static {
+    /// Fetch a site by its UUID.
+    ///
+    /// Returns `DomainError::NotFound` if no site with the given id exists.
+    async fn find_by_id(&self, id: SiteId) -> Result<Site, DomainError>;
+
+    /// Fetch the site whose `host` is exactly `host` (lowercase, no port).
+    ///
+    /// Returns `Ok(None)` if no site claims the host.
+    async fn find_by_host(&self, host: &str) -> Result<Option<Site>, DomainError>;
+
+    /// Every site, default first, then by creation date ascending.
+    async fn find_all(&self) -> Result<Vec<Site>, DomainError>;
+
+    /// Insert (if new) or update (if existing) a site record.
+    ///
+    /// Returns `DomainError::Validation` if another site already has the host.
+    async fn save(&self, site: &Site) -> Result<(), DomainError>;
+
+    /// Delete a site that has no boards.
+    ///
+    /// Returns `DomainError::NotFound` if the site does not exist, and
+    /// `DomainError::Validation` if it still has boards or is the default site.
+    async fn delete(&self, id: SiteId) -> Result<(), DomainError>;
+}
+
+/// The shared repository the site middleware holds (`Arc<dyn SiteRepository>`)
+/// is itself a `SiteRepository`, so services can be generic over it.
+#[async_trait]
+impl<T: SiteRepository + ?Sized> SiteRepository for Arc<T> {
+    async fn find_by_id(&self, id: SiteId) -> Result<Site, DomainError> {
+        (**self).find_by_id(id).await
+    }
+
+    async fn find_by_host(&self, host: &str) -> Result<Option<Site>, DomainError> {
+        (**self).find_by_host(host).await
+    }
+
+    async fn find_all(&self) -> Result<Vec<Site>, DomainError> {
+        (**self).find_all().await
+    }
+
+    async fn save(&self, site: &Site) -> Result<(), DomainError> {
+        (**self).save(site).await
+    }
+
+    async fn delete(&self, id: SiteId) -> Result<(), DomainError> {
+        (**self).delete(id).await
+    }
+}
+
+/// Persistence boundary for `RemoteFollower` records — the ActivityPub actors
+/// following each federated board.
+///
+/// The composition root wires `PgFollowerRepository`.
+#[cfg_attr(any(test, feature = "testing"), mockall::automock)]
+#[async_trait]
+pub trait FollowerRepository: Send + Sync + 'static {
+    /// Insert a follower, or update the inbox of one already following.
+    async fn save(&self, follower: &RemoteFollower) -> Result<(), DomainError>;
+
+    /// Remove `actor_id` from the board's followers. Succeeds if it was not
+    /// following.
+    async fn delete(&self, board_id: BoardId, actor_id: &str) -> Result<(), DomainError>;
+
+    /// Every follower of the board, oldest first.
+    async fn find_by_board(&self, board_id: BoardId) -> Result<Vec<RemoteFollower>, DomainError>;
+}
+
+/// Persistence boundary for thread polls and their votes.
+///
+/// A poll is keyed by its thread; deleting the thread removes the poll and its
+/// votes. The composition root wires `PgPollRepository`.
+#[cfg_attr(any(test, feature = "testing"), mockall::automock)]
+#[async_trait]
+pub trait PollRepository: Send + Sync + 'static {
+    /// Store a new poll. The vote counts in `poll.options` are ignored.
+    ///
+    /// Returns `DomainError::Validation` if the thread already has a poll.
+    async fn save(&self, poll: &Poll) -> Result<(), DomainError>;
+
+    /// The thread's poll with its current vote counts, or `None`.
+    async fn find_by_thread(&self, thread_id: ThreadId) -> Result<Option<Poll>, DomainError>;
+
+    /// Record `voter`'s vote for the option at index `option`.
+    ///
+    /// Returns `false`, leaving the earlier vote in place, if `voter` has
+    /// already voted in this poll. Returns `DomainError::NotFound` if the
+    /// thread has no poll.
+    async fn vote(&self, thread_id: ThreadId, option: usize, voter: &IpHash) -> Result<bool, DomainError>;
+}
+
+/// Persistence boundary for post reactions.
+///
+/// A poster (IP hash) has at most one reaction per post; deleting the post
+/// removes its reactions. The composition root wires `PgReactionRepository`.
+#[cfg_attr(any(test, feature = "testing"), mockall::automock)]
+#[async_trait]
+pub trait ReactionRepository: Send + Sync + 'static {
+    /// Record `voter`'s reaction to the post.
+    ///
+    /// Returns `false`, leaving the earlier reaction in place, if `voter` has
+    /// already reacted to this post.
+    async fn react(&self, post_id: PostId, reaction: Reaction, voter: &IpHash) -> Result<bool, DomainError>;
+
+    /// Reaction counts for each of `post_ids` that has any, in `Reaction::ALL`
+    /// order and without zero counts.
+    async fn counts_by_posts(
+        &self,
+        post_ids: &[PostId],
+    ) -> Result<std::collections::HashMap<PostId, Vec<ReactionCount>>, DomainError>;
+}
+
+/// Persistence boundary for anonymous thread watch lists.
+///
+/// Each watch remembers the last post number its watcher saw; deleting the
+/// thread removes its watches. The composition root wires `PgWatchRepository`.
+#[cfg_attr(any(test, feature = "testing"), mockall::automock)]
+#[async_trait]
+pub trait WatchRepository: Send + Sync + 'static {
+    /// Add the thread to `watcher`'s list with every current post seen.
+    /// Watching a thread already on the list leaves it as it was.
+    ///
+    /// Returns `DomainError::NotFound` if the thread does not exist.
+    async fn watch(&self, watcher: WatcherToken, thread_id: ThreadId) -> Result<(), DomainError>;
+
+    /// Remove the thread from `watcher`'s list; a no-op if it is not on it.
+    async fn unwatch(&self, watcher: WatcherToken, thread_id: ThreadId) -> Result<(), DomainError>;
+
+    /// Number of threads on `watcher`'s list.
+    async fn count(&self, watcher: WatcherToken) -> Result<u64, DomainError>;
+
+    /// Record that `watcher` has seen the thread up to `post_number`.
+    ///
+    /// Returns whether the thread is on `watcher`'s list; nothing is stored
+    /// when it is not.
+    async fn mark_seen(&self, watcher: WatcherToken, thread_id: ThreadId, post_number: u64) -> Result<bool, DomainError>;
+
+    /// `watcher`'s threads, those with unread posts first, then by last bump.
+    async fn list(&self, watcher: WatcherToken) -> Result<Vec<WatchedThread>, DomainError>;
+}
+
+/// Persistence boundary for board banner images.
+///
+/// Only the records live here; the images themselves are in `MediaStorage`.
+/// Deleting a board removes its banners. The composition root wires
+/// `PgBannerRepository`.
+#[cfg_attr(any(test, feature = "testing"), mockall::automock)]
+#[async_trait]
+pub trait BannerRepository: Send + Sync + 'static {
+    /// Record a newly stored banner.
+    async fn add(&self, banner: &BoardBanner) -> Result<(), DomainError>;
+
+    /// The board's banners, oldest first.
+    async fn list(&self, board_id: BoardId) -> Result<Vec<BoardBanner>, DomainError>;
+
+    /// A randomly chosen banner of the board, or `None` when it has none.
+    async fn random(&self, board_id: BoardId) -> Result<Option<BoardBanner>, DomainError>;
+
+    /// Remove the banner record and return it.
+    ///
+    /// Returns `DomainError::NotFound` if the board has no such banner.
+    async fn remove(&self, board_id: BoardId, id: BannerId) -> Result<BoardBanner, DomainError>;
+}
+
+/// Persistence boundary for blotter announcements.
+///
+/// Entries belong to a site and optionally to one of its boards; deleting
+/// the board removes its entries. The composition root wires
+/// `PgAnnouncementRepository`.
+#[cfg_attr(any(test, feature = "testing"), mockall::automock)]
+#[async_trait]
+pub trait AnnouncementRepository: Send + Sync + 'static {
+    /// Store a new entry.
+    async fn create(&self, announcement: &Announcement) -> Result<(), DomainError>;
+
+    /// Delete an entry of the site and return it.
+    ///
+    /// Returns `DomainError::NotFound` if the site has no such entry.
+    async fn delete(&self, site_id: SiteId, id: AnnouncementId) -> Result<Announcement, DomainError>;
+
+    /// Every entry of the site, site-wide and per-board, newest first.
+    async fn list(&self, site_id: SiteId) -> Result<Vec<Announcement>, DomainError>;
+
+    /// The newest `limit` entries shown on `board_id`: the site-wide ones and
+    /// the board's own, newest first.
+    async fn list_for_board(
+        &self,
+        site_id: SiteId,
+        board_id: BoardId,
+        limit: u32,
+    ) -> Result<Vec<Announcement>, DomainError>;
+}
+
+/// Persistence boundary for admin-edited static pages.
+///
+/// The composition root wires this to `PgStaticPageRepository`.
+#[cfg_attr(any(test, feature = "testing"), mockall::automock)]
+#[async_trait]
+pub trait StaticPageRepository: Send + Sync + 'static {
+    /// The page of the site (`board_id: None`) or of one of its boards with
+    /// this slug, if there is one.
+    async fn find(
+        &self,
+        site_id: SiteId,
+        board_id: Option<BoardId>,
+        slug: &str,
+    ) -> Result<Option<StaticPage>, DomainError>;
+
+    /// Fetch a page of the site by id.
+    ///
+    /// Returns `DomainError::NotFound` if the site has no such page.
+    async fn find_by_id(&self, site_id: SiteId, id: PageId) -> Result<StaticPage, DomainError>;
+
+    /// Every page of the site, site pages first, then by board and slug.
+    async fn list(&self, site_id: SiteId) -> Result<Vec<StaticPage>, DomainError>;
+
+    /// Insert (if new) or update (if existing) a page.
+    async fn save(&self, page: &StaticPage) -> Result<(), DomainError>;
+
+    /// Delete a page of the site and return it.
+    ///
+    /// Returns `DomainError::NotFound` if the site has no such page.
+    async fn delete(&self, site_id: SiteId, id: PageId) -> Result<StaticPage, DomainError>;
+}
+
+/// Persistence boundary for API keys.
+///
+/// The composition root wires this to `PgApiKeyRepository`.
+#[cfg_attr(any(test, feature = "testing"), mockall::automock)]
+#[async_trait]
+pub trait ApiKeyRepository: Send + Sync + 'static {
+    /// Insert (if new) or update (if existing) a key.
+    async fn save(&self, key: &ApiKey) -> Result<(), DomainError>;
+
+    /// The key whose SHA-256 hex is `key_hash`, revoked or not.
+    async fn find_by_hash(&self, key_hash: &str) -> Result<Option<ApiKey>, DomainError>;
+
+    /// Every key, newest first.
+    async fn list(&self) -> Result<Vec<ApiKey>, DomainError>;
+
+    /// Mark a key revoked at `at` and return it.
+    ///
+    /// Returns `DomainError::NotFound` if there is no such key.
+    async fn revoke(&self, id: ApiKeyId, at: DateTime<Utc>) -> Result<ApiKey, DomainError>;
+
+    /// Record that the key was used at `at`.
+    async fn touch(&self, id: ApiKeyId, at: DateTime<Utc>) -> Result<(), DomainError>;
+}
+
+/// Hourly and daily activity buckets of each board (`StatsBucket`), rolled up
+/// from the posts and attachments tables by the stats job.
+///
+/// The composition root wires this to `PgStatsRepository` (feature: `db-postgres`).
+#[cfg_attr(any(test, feature = "testing"), mockall::automock)]
+#[async_trait]
+pub trait StatsRepository: Send + Sync + 'static {
+    /// Recompute, from the posts still stored, every hour and day bucket of
+    /// every board from the one containing `since` onwards. Returns the
+    /// number of buckets written.
+    async fn rollup(&self, since: DateTime<Utc>) -> Result<u64, DomainError>;
+
+    /// A board's buckets of `period` starting at or after `since`, oldest first.
+    /// Periods without posts have no bucket.
+    async fn board_series(
+        &self,
+        board_id: BoardId,
+        period: StatsPeriod,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<StatsBucket>, DomainError>;
+
+    /// The day buckets of every board of the site starting at or after
+    /// `since`, oldest first.
+    async fn site_daily(&self, site_id: SiteId, since: DateTime<Utc>) -> Result<Vec<StatsBucket>, DomainError>;
+}
+
+/// An unexpected failure, with the context needed to find its cause.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorReport {
+    /// What went wrong: the internal error message, the panic payload, or a
+    /// failed job's error chain.
+    pub message: String,
+    /// True when the failure was a panic rather than a returned error.
+    pub panic: bool,
+    /// Where it happened, as tags: `request_id`, `method`, `route` and
+    /// `status` for requests; `job` for scheduled jobs.
+    pub context: Vec<(&'static str, String)>,
+}
+
+/// Sink for failures an operator should hear about: panics and 5xx causes in
+/// request handling, and failed scheduled jobs.
+///
+/// Called from `error_report_middleware` and the job scheduler, after the
+/// failure has been logged. `report` must not block the caller; send in the
+/// background.
+///
+/// The composition root wires `SentryErrorReporter` when the `sentry` feature
+/// is enabled and `SENTRY_DSN` is set, otherwise `NoopErrorReporter`.
+#[cfg_attr(any(test, feature = "testing"), mockall::automock)]
+pub trait ErrorReporter: Send + Sync + 'static {
+    /// Record one failure.
+    fn report(&self, report: &ErrorReport);
+
+    /// Send reports still queued, waiting at most `timeout`. Called on shutdown.
+    fn flush(&self, _timeout: Duration) {}
+}
+
+// ─── Post Filter Port ────────────────────────────────────────────────────────
+
+/// A post as submitted, before validation, handed to each `PostFilter`.
+#[derive(Debug, Clone, Serialize)]
+pub struct PostSubmission {
+    pub board_id:   BoardId,
+    /// `None` when the post starts a new thread.
+    pub thread_id:  Option<ThreadId>,
+    pub body:       String,
+    pub name:       Option<String>,
+    pub email:      Option<String>,
+    pub ip_hash:    IpHash,
+    /// Number of attached files.
+    pub file_count: usize,
+    /// True when a signed-in staff member is posting.
+    pub is_staff:   bool,
+}
+
+/// What a `PostFilter` decides about a submission.
+///
+/// Serialized as `{"action": "accept"}`, `{"action": "rewrite", "body": ...}`
+/// or `{"action": "reject", "reason": ...}`. Accept and rewrite may add
+/// `"tags": [...]`, stored on the post as `Post::tags`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum FilterVerdict {
+    /// Let the post through unchanged.
+    Accept {
+        #[serde(default)]
+        tags: Vec<String>,
+    },
+    /// Let the post through with `body` in place of the submitted body.
+    Rewrite {
+        body: String,
+        #[serde(default)]
+        tags: Vec<String>,
+    },
+    /// Refuse the post; `reason` is shown to the poster.
+    Reject { reason: String },
+}
+
+impl FilterVerdict {
+    /// Accept without tags.
+    pub fn accept() -> Self {
+        Self::Accept { tags: Vec::new() }
+    }
+}
+
+/// Operator-supplied check run on every submission before `PostService`
+/// validates it — custom word filters, board-specific rules.
+///
+/// Filters run in the order they were attached; each sees the body as left by
+/// the previous one, and the first `Reject` ends the chain. The rewritten body
+/// still goes through length validation, spam scoring and duplicate detection.
+///
+/// **Fail-open**: callers treat `Err` as `Accept` — a broken plugin must not
+/// stop posting.
+///
+/// The composition root wires `WasmPostFilter`s (feature: `plugins-wasm`)
+/// when `WASM_PLUGIN_DIR` is set.
+#[cfg_attr(any(test, feature = "testing"), mockall::automock)]
+#[async_trait]
+pub trait PostFilter: Send + Sync + 'static {
+    /// Short name identifying the filter in logs.
+    fn name(&self) -> &str;
+
+    /// Decide what happens to `post`.
+    async fn check(&self, post: &PostSubmission) -> Result<FilterVerdict, DomainError>;
+}
+
+// ─── Event Bus Port ──────────────────────────────────────────────────────────
+
+/// Something that happened on the board, published on the `EventBus` after
+/// the change is committed.
+///
+/// Serialized as `{"type": "post_created", ...}`. Events carry whole records,
+/// IP hashes included: subscribers that forward them off-site (webhooks,
+/// federation) pick the fields they publish.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DomainEvent {
+    /// A new thread was started. `thread.op_post_id` is set; its OP follows
+    /// as a `PostCreated`.
+    ThreadCreated { thread: Thread },
+    /// A post was saved, the OP of a new thread included.
+    PostCreated { board_id: BoardId, post: Post },
+    /// A post was removed by a moderator (alone, in a bulk delete, or with its
+    /// thread) or pruned from a cycle thread.
+    PostDeleted { board_id: BoardId, thread_id: ThreadId, post_id: PostId },
+    /// Staff replaced a post's body or subject; `post` carries the new text.
+    PostEdited { board_id: BoardId, post: Post },
+    /// Staff moved the `moved` posts from thread `from` to thread `to`, which
+    /// may be new. `rewritten` are posts whose quotes the move separated, on
+    /// either side, and whose bodies were changed to `>>>/slug/N`.
+    PostsMoved { board_id: BoardId, from: ThreadId, to: ThreadId, moved: Vec<PostId>, rewritten: Vec<PostId> },
+    /// An IP ban was issued.
+    BanIssued { ban: Ban },
+    /// A visitor reported a post; the flag is pending review.
+    FlagFiled { flag: Flag },
+    /// An upload was stored and attached to `attachment.post_id`. Published
+    /// after the post's `PostCreated`.
+    MediaUploaded { board_id: BoardId, attachment: Attachment },
+}
+
+impl DomainEvent {
+    /// The serialized `type` tag, e.g. `"post_created"`.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::ThreadCreated { .. } => "thread_created",
+            Self::PostCreated { .. } => "post_created",
+            Self::PostDeleted { .. } => "post_deleted",
+            Self::PostEdited { .. } => "post_edited",
+            Self::PostsMoved { .. } => "posts_moved",
+            Self::BanIssued { .. } => "ban_issued",
+            Self::FlagFiled { .. } => "flag_filed",
+            Self::MediaUploaded { .. } => "media_uploaded",
+        }
+    }
+}
+
+/// In-process publish/subscribe hub for `DomainEvent`s.
+///
+/// Services publish through it (`PostService::with_events`,
+/// `ModerationService::with_events`); plugins register an `EventSubscriber`
+/// at startup instead of being called from handlers. Delivery is
+/// asynchronous and at-most-once: `publish` never waits for subscribers, and
+/// a subscriber that falls too far behind misses events.
+///
+/// The composition root wires `InMemoryEventBus`.
+#[cfg_attr(any(test, feature = "testing"), mockall::automock)]
+pub trait EventBus: Send + Sync + 'static {
+    /// Hand `event` to every subscriber. Must not block or fail; an event
+    /// published with no subscribers is dropped.
+    fn publish(&self, event: DomainEvent);
+
+    /// Deliver every event published from now on to `subscriber`.
+    fn subscribe(&self, subscriber: Arc<dyn EventSubscriber>);
+}
+
+/// A consumer of `DomainEvent`s — webhooks, live updates, search indexing,
+/// statistics.
+///
+/// Each subscriber receives events one at a time, in publish order.
+#[cfg_attr(any(test, feature = "testing"), mockall::automock)]
+#[async_trait]
+pub trait EventSubscriber: Send + Sync + 'static {
+    /// Short name identifying the subscriber in logs.
+    fn name(&self) -> &str;
+
+    /// React to one event.
+    ///
+    /// An `Err` is logged by the bus; it never reaches the publisher and does
+    /// not stop later deliveries.
+    async fn handle(&self, event: &DomainEvent) -> Result<(), DomainError>;
+}
+
+// ─── Notifier Port ───────────────────────────────────────────────────────────
+
+/// Something moderators are told about outside the site.
+#[derive(Debug, Clone)]
+pub enum NotifyEvent {
+    /// A post was reported.
+    ReportFiled { flag: Flag },
+    /// A post has reached `reports` pending reports.
+    ReportThreshold { post_id: PostId, reports: u64 },
+    /// Reports filed since the last digest, oldest first. Never empty.
+    ReportDigest { flags: Vec<Flag> },
+}
+
+/// Delivery of `NotifyEvent`s to a person, e.g. by email.
+///
+/// The composition root wires `SmtpNotifier` (feature: `notify-email`) when
+/// `[email]` is configured.
+#[cfg_attr(any(test, feature = "testing"), mockall::automock)]
+#[async_trait]
+pub trait Notifier: Send + Sync + 'static {
+    /// Send `event` to `recipient`, an address in the notifier's scheme.
+    async fn notify(&self, event: &NotifyEvent, recipient: &str) -> Result<(), DomainError>;
+}
@@ -1,8 +1,8 @@
 //! Integration tests for admin HTTP endpoints.
 //!
 //! Covers: user management CRUD, board owner assignment/removal, audit log,
-//! and the admin dashboard. All tests use hand-rolled stubs for `UserRepository`
-//! and `AuthProvider`; no real database or JWT stack is needed.
+//! the admin dashboard, and config reload. All tests use hand-rolled stubs for
+//! `UserRepository` and `AuthProvider`; no real database or JWT stack is needed.
 
 use api_adapters::axum::routes::admin_routes::admin_routes;
 use axum::{
@@ -16,6 +16,7 @@ use services::staff_request::StaffRequestService;
 use services::staff_message::StaffMessageService;
 use services::user::UserService;
 use std::sync::Arc;
+use storage_adapters::cache::{BoardConfigCache, SiteCache};
 use tower::ServiceExt;
 use uuid::Uuid;
 
@@ -86,10 +87,10 @@ struct NoBoardRepo;
 
 #[async_trait::async_trait]
 impl BoardRepo for NoBoardRepo {
-    async fn create_board(&self, _slug: &str, _title: &str, _rules: &str) -> Result<domains::models::Board, services::board::BoardError> {
+    async fn create_board(&self, _site: domains::models::SiteId, _slug: &str, _title: &str, _rules: &str) -> Result<domains::models::Board, services::board::BoardError> {
         unimplemented!("not needed in admin tests")
     }
-    async fn get_by_slug(&self, _slug: &str) -> Result<domains::models::Board, services::board::BoardError> {
+    async fn get_by_slug(&self, _site: domains::models::SiteId, _slug: &str) -> Result<domains::models::Board, services::board::BoardError> {
         unimplemented!()
     }
     async fn get_by_id(&self, _id: domains::models::BoardId) -> Result<domains::models::Board, services::board::BoardError> {
@@ -101,15 +102,16 @@ impl BoardRepo for NoBoardRepo {
     async fn delete_board(&self, _id: domains::models::BoardId) -> Result<(), services::board::BoardError> {
         unimplemented!()
     }
-    async fn list_boards(&self, _page: domains::models::Page) -> Result<domains::models::Paginated<domains::models::Board>, services::board::BoardError> {
+    async fn list_boards(&self, _site: domains::models::SiteId, _page: domains::models::Page) -> Result<domains::models::Paginated<domains::models::Board>, services::board::BoardError> {
         Ok(domains::models::Paginated::new(vec![], 0, _page, 15))
     }
     async fn get_config(&self, _id: domains::models::BoardId) -> Result<domains::models::BoardConfig, services::board::BoardError> {
         unimplemented!()
     }
-    async fn update_config(&self, _id: domains::models::BoardId, _config: domains::models::BoardConfig) -> Result<domains::models::BoardConfig, services::board::BoardError> {
+    async fn update_config(&self, _id: domains::models::BoardId, _config: domains::models::BoardConfig, _: domains::models::UserId) -> Result<domains::models::BoardConfig, services::board::BoardError> {
         unimplemented!()
     }
+    async fn media_usage(&self, _: BoardId) -> Result<u64, BoardError> { Ok(0) }
     async fn list_volunteers(&self, _: BoardId) -> Result<Vec<(domains::models::UserId, String, chrono::DateTime<chrono::Utc>)>, BoardError> { Ok(vec![]) }
     async fn add_volunteer_by_username(&self, _: BoardId, _: &str, _: domains::models::UserId) -> Result<(), BoardError> { Ok(()) }
     async fn remove_volunteer(&self, _: BoardId, _: domains::models::UserId) -> Result<(), BoardError> { Ok(()) }
@@ -149,11 +151,16 @@ impl StaffMessageRepository for NopMsgRepo {
 }
 
 fn app() -> axum::Router {
+    app_with_cache(Arc::new(BoardConfigCache::new(std::time::Duration::from_secs(60))))
+}
+
+fn app_with_cache(board_configs: Arc<BoardConfigCache>) -> axum::Router {
     let svc        = Arc::new(UserService::new(OkUserRepo, OkAuth, 3600));
     let board_svc  = Arc::new(NoBoardRepo);
     let request_svc = Arc::new(StaffRequestService::new(NopRequestRepo, OkUserRepo));
     let msg_svc = Arc::new(StaffMessageService::new(NopMsgRepo));
-    admin_routes(svc, board_svc, request_svc, msg_svc)
+    let sites = Arc::new(SiteCache::new(std::time::Duration::from_secs(60)));
+    admin_routes(svc, board_svc, request_svc, msg_svc, board_configs, sites)
 }
 
 fn with_admin(mut req: Request<Body>) -> Request<Body> {
@@ -334,3 +341,27 @@ async fn admin_dashboard_returns_401_without_auth() {
         .unwrap();
     assert!(resp.status() == StatusCode::UNAUTHORIZED || resp.status() == StatusCode::FORBIDDEN);
 }
+
+// ─── Config reload ────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn reload_clears_cached_board_configs() {
+    let cache = Arc::new(BoardConfigCache::new(std::time::Duration::from_secs(60)));
+    let board_id = BoardId(Uuid::new_v4());
+    cache.set(board_id, BoardConfig::default());
+
+    let resp = app_with_cache(cache.clone()).oneshot(with_admin(post_empty("/admin/reload"))).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let bytes = axum::body::to_bytes(resp.into_body(), 1 << 20).await.unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(json["boards_cleared"], 1);
+    assert!(cache.get(board_id).is_none());
+}
+
+#[tokio::test]
+async fn reload_requires_admin() {
+    let cache = Arc::new(BoardConfigCache::new(std::time::Duration::from_secs(60)));
+    cache.set(BoardId(Uuid::new_v4()), BoardConfig::default());
+    let resp = app_with_cache(cache.clone()).oneshot(post_empty("/admin/reload")).await.unwrap();
+    assert!(resp.status() == StatusCode::UNAUTHORIZED || resp.status() == StatusCode::FORBIDDEN);
+}
@@ -0,0 +1,159 @@
+//! Integration tests for the `/all` overboard catalog.
+//!
+//! Tests verify:
+//! - `/all` shows the threads the repository lists, in order, each tagged with
+//!   its board and linking to the thread
+//! - OP excerpts follow their own board's formatting toggles
+//! - `/all.json` returns the same page as JSON, with thread links
+//! - The requested page reaches the repository
+
+use std::sync::Arc;
+
+use api_adapters::axum::routes::overboard_routes::all_threads_routes;
+use async_trait::async_trait;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    Router,
+};
+use chrono::{Duration, Utc};
+use domains::models::*;
+use domains::ports::{MockPostRepository, MockThreadRepository};
+use services::board::{BoardError, BoardRepo};
+use services::thread::ThreadService;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+/// A fixed set of boards with their configs; only listing and configs are used.
+struct Boards(Vec<(Board, BoardConfig)>);
+
+#[async_trait]
+impl BoardRepo for Boards {
+    async fn create_board(&self, _: SiteId, _: &str, _: &str, _: &str) -> Result<Board, BoardError> { unimplemented!() }
+    async fn get_by_slug(&self, _: SiteId, _: &str) -> Result<Board, BoardError> { unimplemented!() }
+    async fn get_by_id(&self, _: BoardId) -> Result<Board, BoardError> { unimplemented!() }
+    async fn update_board(&self, _: BoardId, _: Option<&str>, _: Option<&str>) -> Result<Board, BoardError> { unimplemented!() }
+    async fn delete_board(&self, _: BoardId) -> Result<(), BoardError> { unimplemented!() }
+    async fn list_boards(&self, _: SiteId, page: Page) -> Result<Paginated<Board>, BoardError> {
+        let boards: Vec<_> = self.0.iter().map(|(b, _)| b.clone()).collect();
+        let total = boards.len() as u64;
+        Ok(Paginated::new(boards, total, page, Page::DEFAULT_PAGE_SIZE))
+    }
+    async fn get_config(&self, id: BoardId) -> Result<BoardConfig, BoardError> {
+        self.0
+            .iter()
+            .find(|(b, _)| b.id == id)
+            .map(|(_, c)| c.clone())
+            .ok_or_else(|| BoardError::NotFound { slug: id.to_string() })
+    }
+    async fn update_config(&self, _: BoardId, _: BoardConfig, _: domains::models::UserId) -> Result<BoardConfig, BoardError> { unimplemented!() }
+    async fn media_usage(&self, _: BoardId) -> Result<u64, BoardError> { unimplemented!() }
+    async fn list_volunteers(&self, _: BoardId) -> Result<Vec<(UserId, String, chrono::DateTime<Utc>)>, BoardError> { unimplemented!() }
+    async fn add_volunteer_by_username(&self, _: BoardId, _: &str, _: UserId) -> Result<(), BoardError> { unimplemented!() }
+    async fn remove_volunteer(&self, _: BoardId, _: UserId) -> Result<(), BoardError> { unimplemented!() }
+}
+
+fn make_board(slug: &str, title: &str) -> Board {
+    Board {
+        id:         BoardId(Uuid::new_v4()),
+        site_id:    SiteId::DEFAULT,
+        slug:       Slug::new(slug).unwrap(),
+        title:      title.to_owned(),
+        rules:      String::new(),
+        created_at: Utc::now(),
+    }
+}
+
+fn thread_on(board: &Board, subject: &str, body: &str, bumped_minutes_ago: i64) -> OverboardThread {
+    let bumped_at = Utc::now() - Duration::minutes(bumped_minutes_ago);
+    OverboardThread {
+        summary: ThreadSummary {
+            thread_id:         ThreadId::new(),
+            board_id:          board.id,
+            op_body:           body.to_owned(),
+            thumbnail_key:     None,
+            thumbnail_spoiler: false,
+            reply_count:       3,
+            sticky:            false,
+            closed:            false,
+            bumped_at,
+            op_name:           None,
+            op_tripcode:       None,
+            op_subject:        Some(subject.to_owned()),
+            op_capcode:        None,
+            op_created_at:     bumped_at - Duration::hours(1),
+            op_post_number:    1,
+            op_ip_hash:        IpHash::new("hash"),
+        },
+        board_slug:  board.slug.to_string(),
+        board_title: board.title.clone(),
+    }
+}
+
+/// `/b/` with markup on and `/tech/` with markup off, one thread each, `/tech/`
+/// bumped last. The repository expects to be asked for `page`.
+fn app(page: u32) -> (Router, Vec<OverboardThread>) {
+    let b = make_board("b", "Random");
+    let tech = make_board("tech", "Technology");
+    let threads = vec![
+        thread_on(&tech, "Rust 2.0", "**not bold** here", 1),
+        thread_on(&b, "Cats", "**bold** cats", 5),
+    ];
+    let tech_config = BoardConfig { markup_enabled: false, ..BoardConfig::default() };
+
+    let listed = threads.clone();
+    let mut repo = MockThreadRepository::new();
+    repo.expect_find_overboard_threads()
+        .withf(move |site, p| *site == SiteId::DEFAULT && p.0 == page)
+        .returning(move |_, p| Ok(Paginated::new(listed.clone(), 120, p, OverboardThread::PAGE_SIZE)));
+    let thread_svc = Arc::new(ThreadService::new(repo, MockPostRepository::new()));
+    let boards = Arc::new(Boards(vec![(b, BoardConfig::default()), (tech, tech_config)]));
+    (all_threads_routes(boards, thread_svc), threads)
+}
+
+async fn get(app: Router, uri: &str) -> (StatusCode, String) {
+    let resp = app.oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap()).await.unwrap();
+    let status = resp.status();
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[tokio::test]
+async fn all_lists_threads_across_boards_in_bump_order() {
+    let (app, threads) = app(1);
+    let (status, html) = get(app, "/all").await;
+    assert_eq!(status, StatusCode::OK);
+
+    let rust = html.find("Rust 2.0").expect("tech thread listed");
+    let cats = html.find("Cats").expect("b thread listed");
+    assert!(rust < cats, "most recently bumped first");
+    assert!(html.contains(&format!(r#"href="/board/tech/thread/{}""#, threads[0].summary.thread_id)));
+    assert!(html.contains(r#"<a class="board-tag" href="/board/b" title="Random">/b/</a>"#));
+    assert!(html.contains(r#"href="/all?page=2""#));
+}
+
+#[tokio::test]
+async fn excerpts_follow_their_boards_formatting() {
+    let (app, _) = app(1);
+    let (_, html) = get(app, "/all/").await;
+    assert!(html.contains("<strong>bold</strong> cats"));
+    assert!(html.contains("**not bold** here"));
+}
+
+#[tokio::test]
+async fn json_feed_lists_the_same_page() {
+    let (app, threads) = app(2);
+    let (status, body) = get(app, "/all.json?page=2").await;
+    assert_eq!(status, StatusCode::OK);
+
+    let feed: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(feed["page"], 2);
+    assert_eq!(feed["total_pages"], 3);
+    assert_eq!(feed["items"][0]["board_slug"], "tech");
+    assert_eq!(feed["items"][0]["subject"], "Rust 2.0");
+    assert_eq!(
+        feed["items"][0]["url"],
+        format!("/board/tech/thread/{}", threads[0].summary.thread_id),
+    );
+    assert_eq!(feed["items"][1]["excerpt_html"], "<strong>bold</strong> cats");
+}
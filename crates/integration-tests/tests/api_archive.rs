@@ -0,0 +1,339 @@
+//! Integration tests for the static pages of archived threads.
+//!
+//! Tests verify:
+//! - Archiving a thread writes its HTML and JSON pages to media storage
+//! - The pages are read-only and leave out IP hashes and email fields
+//! - The pages use the board's default theme and locale
+//! - The pages load the small-screen stylesheet after the theme
+//! - A failed render does not fail the archiving
+//! - Staff edits and post moves in an archived thread render its page again
+//! - `GET /board/:slug/archive/:id` serves the stored page, JSON on request
+//! - Threads without a static page redirect to the thread view
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use api_adapters::axum::middleware::board_config::{board_config_middleware, BoardConfigSource, BoardConfigState};
+use api_adapters::axum::routes::board_routes::archived_thread_routes;
+use api_adapters::axum::static_archive::StaticArchiveRepository;
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+    middleware, Router,
+};
+use bytes::Bytes;
+use chrono::Utc;
+use domains::errors::DomainError;
+use domains::models::*;
+use domains::ports::{
+    ArchiveRepository, DomainEvent, EventSubscriber, MediaStorage, MockArchiveRepository, MockBoardRepository,
+    MockPostRepository, MockThreadRepository,
+};
+use storage_adapters::cache::BoardConfigCache;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+/// `MediaStorage` holding objects in a map.
+#[derive(Clone, Default)]
+struct MapStorage {
+    objects: Arc<Mutex<HashMap<String, (Bytes, String)>>>,
+}
+
+impl MapStorage {
+    fn object(&self, key: &MediaKey) -> Option<(String, String)> {
+        let objects = self.objects.lock().unwrap();
+        let (data, content_type) = objects.get(&key.0)?;
+        Some((String::from_utf8(data.to_vec()).unwrap(), content_type.clone()))
+    }
+}
+
+#[async_trait::async_trait]
+impl MediaStorage for MapStorage {
+    async fn store(&self, key: &MediaKey, data: Bytes, content_type: &str) -> Result<(), DomainError> {
+        self.objects.lock().unwrap().insert(key.0.clone(), (data, content_type.to_owned()));
+        Ok(())
+    }
+    async fn store_file(&self, _: &MediaKey, _: &std::path::Path, _: &str) -> Result<(), DomainError> {
+        unimplemented!()
+    }
+    async fn get_url(&self, key: &MediaKey, _: Duration) -> Result<String, DomainError> {
+        Ok(format!("/media/{key}"))
+    }
+    async fn fetch(&self, key: &MediaKey) -> Result<Bytes, DomainError> {
+        let objects = self.objects.lock().unwrap();
+        objects.get(&key.0).map(|(data, _)| data.clone()).ok_or_else(|| DomainError::not_found(key.to_string()))
+    }
+    async fn delete(&self, key: &MediaKey) -> Result<(), DomainError> {
+        self.objects.lock().unwrap().remove(&key.0);
+        Ok(())
+    }
+    async fn exists(&self, key: &MediaKey) -> Result<bool, DomainError> {
+        Ok(self.objects.lock().unwrap().contains_key(&key.0))
+    }
+    async fn list(&self) -> Result<Vec<domains::ports::StoredMedia>, DomainError> { Ok(vec![]) }
+}
+
+fn board() -> Board {
+    Board {
+        id:         BoardId(Uuid::new_v4()),
+        site_id:    SiteId::DEFAULT,
+        slug:       Slug::new("b").unwrap(),
+        title:      "Random".to_owned(),
+        rules:      String::new(),
+        created_at: Utc::now(),
+    }
+}
+
+fn thread(board_id: BoardId) -> Thread {
+    Thread {
+        id: ThreadId(Uuid::new_v4()), board_id, op_post_id: None, reply_count: 0, bumped_at: Utc::now(),
+        sticky: false, closed: false, cycle: false, created_at: Utc::now(),
+    }
+}
+
+fn post(thread_id: ThreadId) -> Post {
+    Post {
+        id: PostId(Uuid::new_v4()), thread_id, body: "an archived post".to_owned(),
+        ip_hash: IpHash::new("feedface".repeat(8)), name: None, email: Some("poster@example.com".to_owned()),
+        tripcode: None, created_at: Utc::now(), post_number: 7, pinned: false, tags: vec![], commands: vec![],
+        subject: None,
+        capcode: None,
+        deleted: None,
+        edited_at: None,
+    }
+}
+
+fn inner_archive() -> MockArchiveRepository {
+    let mut inner = MockArchiveRepository::new();
+    inner.expect_archive_thread().times(1).returning(|_| Ok(()));
+    inner
+}
+
+fn archive(board: &Board, inner: MockArchiveRepository, media: MapStorage) -> StaticArchiveRepository {
+    archive_with_config(board, inner, media, BoardConfig::default())
+}
+
+fn archive_with_config(
+    board: &Board,
+    inner: MockArchiveRepository,
+    media: MapStorage,
+    config: BoardConfig,
+) -> StaticArchiveRepository {
+    let mut boards = MockBoardRepository::new();
+    let found = board.clone();
+    boards.expect_find_by_id().returning(move |_| Ok(found.clone()));
+    boards.expect_find_config().returning(move |_| Ok(config.clone()));
+    let mut posts = MockPostRepository::new();
+    posts.expect_find_visible_by_thread().returning(|thread_id, _| Ok(vec![post(thread_id)]));
+    posts.expect_find_attachments_by_post_ids().returning(|_| Ok(HashMap::new()));
+    StaticArchiveRepository::new(
+        Arc::new(inner),
+        Arc::new(boards),
+        Arc::new(MockThreadRepository::new()),
+        Arc::new(posts),
+        Arc::new(media),
+    )
+}
+
+#[tokio::test]
+async fn archiving_writes_static_pages() {
+    let board = board();
+    let thread = thread(board.id);
+    let media = MapStorage::default();
+    archive(&board, inner_archive(), media.clone()).archive_thread(&thread).await.unwrap();
+
+    let (html, content_type) = media.object(&MediaKey::archived_thread(board.id, thread.id, "html")).unwrap();
+    assert!(content_type.starts_with("text/html"));
+    assert!(html.contains("an archived post"));
+    assert!(!html.contains("class=\"reply-form\""), "archived page must not offer a reply form");
+    assert!(!html.contains("feedface"));
+
+    let (json, content_type) = media.object(&MediaKey::archived_thread(board.id, thread.id, "json")).unwrap();
+    assert_eq!(content_type, "application/json");
+    let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(json["thread"]["id"], thread.id.0.to_string());
+    let first = &json["posts"][0];
+    assert_eq!(first["post_number"], 7);
+    assert_eq!(first["body"], "an archived post");
+    assert!(first.get("ip_hash").is_none());
+    assert!(first.get("email").is_none());
+}
+
+#[tokio::test]
+async fn edits_and_moves_in_an_archived_thread_render_its_page_again() {
+    let board = board();
+    let (archived, live) = (thread(board.id), thread(board.id));
+    let media = MapStorage::default();
+    let html = MediaKey::archived_thread(board.id, archived.id, "html");
+    media.store(&html, Bytes::from_static(b"my address is 1 Main St"), "text/html").await.unwrap();
+    let mut boards = MockBoardRepository::new();
+    let found = board.clone();
+    boards.expect_find_by_id().returning(move |_| Ok(found.clone()));
+    boards.expect_find_config().returning(|_| Ok(BoardConfig::default()));
+    let mut threads = MockThreadRepository::new();
+    let found = archived.clone();
+    threads.expect_find_by_id().times(2).returning(move |_| Ok(found.clone()));
+    let mut posts = MockPostRepository::new();
+    posts.expect_find_visible_by_thread().returning(|thread_id, _| Ok(vec![post(thread_id)]));
+    posts.expect_find_attachments_by_post_ids().returning(|_| Ok(HashMap::new()));
+    let repo = StaticArchiveRepository::new(
+        Arc::new(MockArchiveRepository::new()),
+        Arc::new(boards),
+        Arc::new(threads),
+        Arc::new(posts),
+        Arc::new(media.clone()),
+    );
+
+    repo.handle(&DomainEvent::PostEdited { board_id: board.id, post: post(archived.id) }).await.unwrap();
+    repo.handle(&DomainEvent::PostEdited { board_id: board.id, post: post(live.id) }).await.unwrap();
+
+    let (page, _) = media.object(&html).unwrap();
+    assert!(page.contains("an archived post") && !page.contains("1 Main St"));
+    assert!(media.object(&MediaKey::archived_thread(board.id, live.id, "html")).is_none());
+
+    // Posts moving out of the archived thread render it again too.
+    media.store(&html, Bytes::from_static(b"moved away"), "text/html").await.unwrap();
+    let moved = DomainEvent::PostsMoved {
+        board_id:  board.id,
+        from:      archived.id,
+        to:        live.id,
+        moved:     vec![PostId::new()],
+        rewritten: vec![],
+    };
+    repo.handle(&moved).await.unwrap();
+    assert!(media.object(&html).unwrap().0.contains("an archived post"));
+    assert!(media.object(&MediaKey::archived_thread(board.id, live.id, "html")).is_none());
+}
+
+#[tokio::test]
+async fn archived_page_loads_the_board_default_theme() {
+    let board = board();
+    let thread = thread(board.id);
+    let media = MapStorage::default();
+    let config = BoardConfig { default_theme: domains::models::Theme::Tomorrow, ..BoardConfig::default() };
+    archive_with_config(&board, inner_archive(), media.clone(), config).archive_thread(&thread).await.unwrap();
+
+    let (html, _) = media.object(&MediaKey::archived_thread(board.id, thread.id, "html")).unwrap();
+    assert!(html.contains(r#"href="/static/css/tomorrow.css" id="theme-css""#));
+}
+
+#[tokio::test]
+async fn archived_page_loads_the_mobile_stylesheet_after_the_theme() {
+    let board = board();
+    let thread = thread(board.id);
+    let media = MapStorage::default();
+    archive(&board, inner_archive(), media.clone()).archive_thread(&thread).await.unwrap();
+
+    let (html, _) = media.object(&MediaKey::archived_thread(board.id, thread.id, "html")).unwrap();
+    let theme = html.find(r#"id="theme-css""#).unwrap();
+    let mobile = html.find(r#"href="/static/css/mobile.css""#).expect("mobile stylesheet is linked");
+    assert!(mobile > theme, "mobile rules must override the theme");
+}
+
+#[tokio::test]
+async fn archived_page_uses_the_board_default_locale() {
+    let board = board();
+    let thread = thread(board.id);
+    let media = MapStorage::default();
+    let config = BoardConfig { default_locale: domains::models::Locale::Es, ..BoardConfig::default() };
+    archive_with_config(&board, inner_archive(), media.clone(), config).archive_thread(&thread).await.unwrap();
+
+    let (html, _) = media.object(&MediaKey::archived_thread(board.id, thread.id, "html")).unwrap();
+    assert!(html.contains(r#"<html lang="es">"#));
+    assert!(html.contains("[Catálogo]"));
+}
+
+#[tokio::test]
+async fn render_failure_still_archives() {
+    let board = board();
+    let thread = thread(board.id);
+    let media = MapStorage::default();
+    let mut boards = MockBoardRepository::new();
+    boards.expect_find_by_id().returning(|_| Err(DomainError::internal("db down")));
+    let repo = StaticArchiveRepository::new(
+        Arc::new(inner_archive()),
+        Arc::new(boards),
+        Arc::new(MockThreadRepository::new()),
+        Arc::new(MockPostRepository::new()),
+        Arc::new(media.clone()),
+    );
+
+    repo.archive_thread(&thread).await.unwrap();
+    assert!(media.objects.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn archive_failure_skips_rendering() {
+    let board = board();
+    let mut inner = MockArchiveRepository::new();
+    inner.expect_archive_thread().returning(|_| Err(DomainError::internal("db down")));
+    let media = MapStorage::default();
+
+    assert!(archive(&board, inner, media.clone()).archive_thread(&thread(board.id)).await.is_err());
+    assert!(media.objects.lock().unwrap().is_empty());
+}
+
+struct FixedSource(Board);
+
+#[async_trait::async_trait]
+impl BoardConfigSource for FixedSource {
+    async fn config_by_slug(
+        &self,
+        _site: SiteId,
+        slug: &Slug,
+    ) -> Result<Option<(Board, BoardId, BoardConfig)>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok((slug == &self.0.slug).then(|| (self.0.clone(), self.0.id, BoardConfig::default())))
+    }
+}
+
+fn app(board: &Board, media: MapStorage) -> Router {
+    let config_state = BoardConfigState {
+        source: Arc::new(FixedSource(board.clone())),
+        cache:  Arc::new(BoardConfigCache::new(Duration::from_secs(60))),
+    };
+    archived_thread_routes(Arc::new(media))
+        .route_layer(middleware::from_fn_with_state(config_state, board_config_middleware))
+}
+
+async fn get(app: &Router, uri: &str, accept: &str) -> axum::response::Response {
+    let req = Request::builder().uri(uri).header(header::ACCEPT, accept).body(Body::empty()).unwrap();
+    app.clone().oneshot(req).await.unwrap()
+}
+
+async fn body(resp: axum::response::Response) -> String {
+    let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    String::from_utf8(bytes.to_vec()).unwrap()
+}
+
+#[tokio::test]
+async fn stored_pages_are_served() {
+    let board = board();
+    let thread = thread(board.id);
+    let media = MapStorage::default();
+    archive(&board, inner_archive(), media.clone()).archive_thread(&thread).await.unwrap();
+    let app = app(&board, media);
+    let uri = format!("/board/b/archive/{}", thread.id.0);
+
+    let resp = get(&app, &uri, "text/html").await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert!(resp.headers()[header::CONTENT_TYPE].to_str().unwrap().starts_with("text/html"));
+    assert!(body(resp).await.contains("an archived post"));
+
+    let resp = get(&app, &uri, "application/json").await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()[header::CONTENT_TYPE], "application/json");
+    let json: serde_json::Value = serde_json::from_str(&body(resp).await).unwrap();
+    assert_eq!(json["posts"][0]["post_number"], 7);
+}
+
+#[tokio::test]
+async fn missing_page_redirects_to_thread() {
+    let board = board();
+    let app = app(&board, MapStorage::default());
+    let id = Uuid::new_v4();
+
+    let resp = get(&app, &format!("/board/b/archive/{id}"), "text/html").await;
+    assert_eq!(resp.status(), StatusCode::SEE_OTHER);
+    assert_eq!(resp.headers()[header::LOCATION], format!("/board/b/thread/{id}"));
+}
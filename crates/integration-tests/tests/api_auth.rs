@@ -1,4 +1,5 @@
-//! Integration tests for `POST /auth/login` and `POST /auth/refresh`.
+//! Integration tests for `POST /auth/login`, `POST /auth/refresh`, and TOTP
+//! two-factor enrollment.
 //!
 //! These tests build a minimal Axum router with hand-rolled stub implementations
 //! of `UserRepository` and `AuthProvider`. No real database or JWT signing is
@@ -10,11 +11,11 @@ use axum::{
     body::Body,
     http::{header, Method, Request, StatusCode},
 };
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use domains::{
     errors::DomainError,
     models::*,
-    ports::{AuthProvider, UserRepository},
+    ports::{AuthProvider, TotpRepository, UserRepository},
 };
 use services::user::UserService;
 use std::sync::Arc;
@@ -326,3 +327,111 @@ async fn register_returns_422_on_missing_fields() {
 
     assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
 }
+
+// ─── Two-factor auth ──────────────────────────────────────────────────────────
+
+/// In-memory `TotpRepository` holding one account's factor. Attempts are not
+/// limited.
+#[derive(Default)]
+struct MemTotp(std::sync::Mutex<Option<StaffTotp>>);
+
+#[async_trait::async_trait]
+impl TotpRepository for MemTotp {
+    async fn find(&self, _: UserId) -> Result<Option<StaffTotp>, DomainError> {
+        Ok(self.0.lock().unwrap().clone())
+    }
+    async fn save(&self, totp: &StaffTotp) -> Result<(), DomainError> {
+        *self.0.lock().unwrap() = Some(totp.clone());
+        Ok(())
+    }
+    async fn claim_attempt(&self, _: UserId, _: u32, _: DateTime<Utc>, _: DateTime<Utc>) -> Result<bool, DomainError> {
+        Ok(self.0.lock().unwrap().is_some())
+    }
+    async fn consume_step(&self, _: UserId, step: i64) -> Result<bool, DomainError> {
+        let mut factor = self.0.lock().unwrap();
+        let Some(factor) = factor.as_mut().filter(|f| f.last_step.is_none_or(|last| last < step)) else { return Ok(false) };
+        factor.last_step = Some(step);
+        Ok(true)
+    }
+    async fn consume_recovery_code(&self, _: UserId, hash: &str) -> Result<bool, DomainError> {
+        let mut factor = self.0.lock().unwrap();
+        let Some(codes) = factor.as_mut().map(|f| &mut f.recovery_codes) else { return Ok(false) };
+        let before = codes.len();
+        codes.retain(|h| h != hash);
+        Ok(codes.len() < before)
+    }
+    async fn delete(&self, _: UserId) -> Result<(), DomainError> {
+        *self.0.lock().unwrap() = None;
+        Ok(())
+    }
+}
+
+async fn send_json(app: &axum::Router, uri: &str, body: &str, user: Option<&User>) -> (StatusCode, serde_json::Value) {
+    let mut req = Request::builder()
+        .method(Method::POST)
+        .uri(uri)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_owned()))
+        .unwrap();
+    if let Some(user) = user {
+        req.extensions_mut().insert(CurrentUser::from_claims(Claims {
+            user_id:          user.id,
+            username:         user.username.clone(),
+            role:             user.role,
+            owned_boards:     vec![],
+            volunteer_boards: vec![],
+            exp:              (Utc::now() + chrono::Duration::hours(24)).timestamp(),
+        }));
+    }
+    let resp = app.clone().oneshot(req).await.unwrap();
+    let status = resp.status();
+    let bytes = axum::body::to_bytes(resp.into_body(), 1024 * 1024).await.unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null))
+}
+
+#[tokio::test]
+async fn enrolled_staff_need_a_second_factor_to_log_in() {
+    use services::common::totp;
+    let repo = AlwaysOkUserRepo::admin();
+    let user = repo.user.clone();
+    let svc = Arc::new(UserService::new(repo, AlwaysOkAuth, 3600).with_totp(Arc::new(MemTotp::default())));
+    let app = auth_routes(svc, true)
+        .layer(axum::Extension(api_adapters::axum::middleware::login_guard::LoginGuard::new()));
+
+    let (status, setup) = send_json(&app, "/auth/totp/setup", "", Some(&user)).await;
+    assert_eq!(status, StatusCode::OK);
+    let secret = setup["secret"].as_str().unwrap();
+    assert!(setup["uri"].as_str().unwrap().starts_with("otpauth://totp/"));
+
+    // Until enrollment is confirmed the password alone still works.
+    let (status, _) = send_json(&app, "/auth/login", r#"{"username":"admin","password":"pw"}"#, None).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let code = totp::code_at(secret, totp::step_at(Utc::now().timestamp())).unwrap();
+    let (status, confirmed) =
+        send_json(&app, "/auth/totp/confirm", &format!(r#"{{"code":"{code}"}}"#), Some(&user)).await;
+    assert_eq!(status, StatusCode::OK);
+    let recovery = confirmed["recovery_codes"][0].as_str().unwrap().to_owned();
+
+    let (status, body) = send_json(&app, "/auth/login", r#"{"username":"admin","password":"pw"}"#, None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["error"]["code"], "TOTP_REQUIRED");
+
+    // The enrollment code was spent; a recovery code works once.
+    let login = |otp: &str| format!(r#"{{"username":"admin","password":"pw","otp":"{otp}"}}"#);
+    let (status, _) = send_json(&app, "/auth/login", &login(&code), None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _) = send_json(&app, "/auth/login", &login(&recovery), None).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = send_json(&app, "/auth/login", &login(&recovery), None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn totp_setup_without_a_store_returns_501() {
+    let repo = AlwaysOkUserRepo::admin();
+    let user = repo.user.clone();
+    let svc = Arc::new(UserService::new(repo, AlwaysOkAuth, 3600));
+    let (status, _) = send_json(&auth_routes(svc, true), "/auth/totp/setup", "", Some(&user)).await;
+    assert_eq!(status, StatusCode::NOT_IMPLEMENTED);
+}
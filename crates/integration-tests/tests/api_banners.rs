@@ -0,0 +1,207 @@
+//! Integration tests for the board banner routes.
+//!
+//! Tests verify:
+//! - `GET /board/:slug/banner` redirects to one of the board's banners and is
+//!   never cached, and is `404` for a board without banners
+//! - Board owners can upload, list and remove banners
+//! - Other users cannot upload banners
+
+use std::sync::{Arc, Mutex};
+
+use api_adapters::axum::middleware::board_config::ExtractedBoardConfig;
+use api_adapters::axum::routes::banner_routes::banner_routes;
+use async_trait::async_trait;
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+    Router,
+};
+use chrono::Utc;
+use domains::errors::DomainError;
+use domains::models::*;
+use domains::ports::{BannerRepository, MockMediaStorage};
+use services::banner::BannerService;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+/// The 8-byte PNG signature followed by an IHDR chunk header.
+const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+
+/// Banners kept in memory; `random` returns the first. Clones share the rows.
+#[derive(Clone, Default)]
+struct MemBanners {
+    rows: Arc<Mutex<Vec<BoardBanner>>>,
+}
+
+#[async_trait]
+impl BannerRepository for MemBanners {
+    async fn add(&self, banner: &BoardBanner) -> Result<(), DomainError> {
+        self.rows.lock().unwrap().push(banner.clone());
+        Ok(())
+    }
+    async fn list(&self, board_id: BoardId) -> Result<Vec<BoardBanner>, DomainError> {
+        Ok(self.rows.lock().unwrap().iter().filter(|b| b.board_id == board_id).cloned().collect())
+    }
+    async fn random(&self, board_id: BoardId) -> Result<Option<BoardBanner>, DomainError> {
+        Ok(self.list(board_id).await?.into_iter().next())
+    }
+    async fn remove(&self, board_id: BoardId, id: BannerId) -> Result<BoardBanner, DomainError> {
+        let mut rows = self.rows.lock().unwrap();
+        let at = rows
+            .iter()
+            .position(|b| b.board_id == board_id && b.id == id)
+            .ok_or_else(|| DomainError::not_found(format!("banner {id}")))?;
+        Ok(rows.remove(at))
+    }
+}
+
+fn make_board() -> Board {
+    Board {
+        id:         BoardId(Uuid::new_v4()),
+        site_id:    SiteId::DEFAULT,
+        slug:       Slug::new("b").unwrap(),
+        title:      "Random".to_owned(),
+        rules:      String::new(),
+        created_at: Utc::now(),
+    }
+}
+
+fn banner_on(board_id: BoardId) -> BoardBanner {
+    let id = BannerId::new();
+    BoardBanner {
+        id,
+        board_id,
+        media_key: MediaKey::banner(board_id, id, "png"),
+        content_type: "image/png".to_owned(),
+        created_at: Utc::now(),
+    }
+}
+
+fn app(board: Board, banners: MemBanners) -> Router {
+    let mut media = MockMediaStorage::new();
+    media.expect_store().returning(|_, _, _| Ok(()));
+    media.expect_delete().returning(|_| Ok(()));
+    let ctx = ExtractedBoardConfig {
+        board_id: board.id,
+        config:   BoardConfig::default(),
+        slug:     board.slug.clone(),
+        board,
+    };
+    banner_routes(Arc::new(BannerService::new(banners, media))).layer(axum::Extension(ctx))
+}
+
+/// A board owner of `owns`.
+fn owner_of(owns: BoardId) -> CurrentUser {
+    CurrentUser::from_claims(Claims {
+        user_id:          UserId(Uuid::new_v4()),
+        username:         "owner".into(),
+        role:             Role::BoardOwner,
+        owned_boards:     vec![owns],
+        volunteer_boards: vec![],
+        exp:              (Utc::now() + chrono::Duration::hours(24)).timestamp(),
+    })
+}
+
+/// `POST /board/b/banners` with `data` as the multipart `file` field.
+fn upload(data: &[u8], user: CurrentUser) -> Request<Body> {
+    let mut body = b"--XBOUNDARY\r\nContent-Disposition: form-data; name=\"file\"; filename=\"banner.png\"\r\nContent-Type: image/png\r\n\r\n".to_vec();
+    body.extend_from_slice(data);
+    body.extend_from_slice(b"\r\n--XBOUNDARY--\r\n");
+    let mut req = Request::builder()
+        .method("POST")
+        .uri("/board/b/banners")
+        .header(header::CONTENT_TYPE, "multipart/form-data; boundary=XBOUNDARY")
+        .header(header::ACCEPT, "text/html")
+        .body(Body::from(body))
+        .unwrap();
+    req.extensions_mut().insert(user);
+    req
+}
+
+fn get(uri: &str) -> Request<Body> {
+    Request::builder().uri(uri).body(Body::empty()).unwrap()
+}
+
+#[tokio::test]
+async fn banner_redirects_to_a_stored_image_without_caching() {
+    let board = make_board();
+    let banners = MemBanners::default();
+    let banner = banner_on(board.id);
+    banners.rows.lock().unwrap().push(banner.clone());
+
+    let resp = app(board, banners).oneshot(get("/board/b/banner")).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::TEMPORARY_REDIRECT);
+    assert_eq!(resp.headers()[header::LOCATION], format!("/media/{}", banner.media_key));
+    assert_eq!(resp.headers()[header::CACHE_CONTROL], "no-store");
+}
+
+#[tokio::test]
+async fn board_without_banners_has_none_to_serve() {
+    let board = make_board();
+    let resp = app(board, MemBanners::default()).oneshot(get("/board/b/banner")).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn owner_uploads_a_banner_and_returns_to_the_dashboard() {
+    let board = make_board();
+    let banners = MemBanners::default();
+    let app = app(board.clone(), banners.clone());
+
+    let resp = app.oneshot(upload(PNG, owner_of(board.id))).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::SEE_OTHER);
+    assert_eq!(resp.headers()[header::LOCATION], "/board/b/dashboard");
+    let rows = banners.rows.lock().unwrap();
+    assert_eq!(rows.len(), 1);
+    assert!(rows[0].media_key.is_banner());
+}
+
+#[tokio::test]
+async fn non_image_uploads_are_refused() {
+    let board = make_board();
+    let banners = MemBanners::default();
+    let app = app(board.clone(), banners.clone());
+
+    let resp = app.oneshot(upload(b"just text", owner_of(board.id))).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(banners.rows.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn owners_of_other_boards_cannot_upload() {
+    let board = make_board();
+    let banners = MemBanners::default();
+    let app = app(board, banners.clone());
+
+    let resp = app.oneshot(upload(PNG, owner_of(BoardId::new()))).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    assert!(banners.rows.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn owner_lists_and_removes_banners() {
+    let board = make_board();
+    let banners = MemBanners::default();
+    let banner = banner_on(board.id);
+    banners.rows.lock().unwrap().push(banner.clone());
+    let app = app(board.clone(), banners.clone());
+
+    let mut req = get("/board/b/banners");
+    req.extensions_mut().insert(owner_of(board.id));
+    let resp = app.clone().oneshot(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    let listed: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(listed[0]["id"], banner.id.to_string());
+    assert_eq!(listed[0]["url"], format!("/media/{}", banner.media_key));
+
+    let mut req = Request::builder()
+        .method("DELETE")
+        .uri(format!("/board/b/banners/{}", banner.id))
+        .body(Body::empty())
+        .unwrap();
+    req.extensions_mut().insert(owner_of(board.id));
+    let resp = app.oneshot(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+    assert!(banners.rows.lock().unwrap().is_empty());
+}
@@ -0,0 +1,241 @@
+//! Integration tests for the blotter.
+//!
+//! Tests verify:
+//! - Admins can post, list and delete entries; other users cannot
+//! - Blank entries are rejected, as are boards of another site
+//! - Board pages list their blotter entries, escaped
+
+use std::sync::{Arc, Mutex};
+
+use api_adapters::axum::i18n::I18n;
+use api_adapters::axum::routes::announcement_routes::announcement_routes;
+use api_adapters::axum::templates::BoardTemplate;
+use async_trait::async_trait;
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+    response::IntoResponse,
+    Router,
+};
+use chrono::Utc;
+use domains::errors::DomainError;
+use domains::models::*;
+use domains::ports::AnnouncementRepository;
+use services::announcement::AnnouncementService;
+use services::board::{BoardError, BoardRepo};
+use storage_adapters::media::cdn::MediaUrls;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+/// Entries kept in memory, oldest first. Clones share the rows.
+#[derive(Clone, Default)]
+struct MemAnnouncements {
+    rows: Arc<Mutex<Vec<Announcement>>>,
+}
+
+#[async_trait]
+impl AnnouncementRepository for MemAnnouncements {
+    async fn create(&self, announcement: &Announcement) -> Result<(), DomainError> {
+        self.rows.lock().unwrap().push(announcement.clone());
+        Ok(())
+    }
+    async fn delete(&self, site_id: SiteId, id: AnnouncementId) -> Result<Announcement, DomainError> {
+        let mut rows = self.rows.lock().unwrap();
+        let at = rows
+            .iter()
+            .position(|a| a.site_id == site_id && a.id == id)
+            .ok_or_else(|| DomainError::not_found(format!("announcement {id}")))?;
+        Ok(rows.remove(at))
+    }
+    async fn list(&self, site_id: SiteId) -> Result<Vec<Announcement>, DomainError> {
+        Ok(self.rows.lock().unwrap().iter().rev().filter(|a| a.site_id == site_id).cloned().collect())
+    }
+    async fn list_for_board(&self, site_id: SiteId, board_id: BoardId, limit: u32) -> Result<Vec<Announcement>, DomainError> {
+        Ok(self
+            .list(site_id)
+            .await?
+            .into_iter()
+            .filter(|a| a.board_id.is_none_or(|b| b == board_id))
+            .take(limit as usize)
+            .collect())
+    }
+}
+
+/// The only board there is; everything but `get_by_id` is unused.
+struct OneBoard(Board);
+
+#[async_trait]
+impl BoardRepo for OneBoard {
+    async fn create_board(&self, _: SiteId, _: &str, _: &str, _: &str) -> Result<Board, BoardError> { unimplemented!() }
+    async fn get_by_slug(&self, _: SiteId, _: &str) -> Result<Board, BoardError> { unimplemented!() }
+    async fn get_by_id(&self, id: BoardId) -> Result<Board, BoardError> {
+        if id == self.0.id { Ok(self.0.clone()) } else { Err(BoardError::NotFound { slug: id.to_string() }) }
+    }
+    async fn update_board(&self, _: BoardId, _: Option<&str>, _: Option<&str>) -> Result<Board, BoardError> { unimplemented!() }
+    async fn delete_board(&self, _: BoardId) -> Result<(), BoardError> { unimplemented!() }
+    async fn list_boards(&self, _: SiteId, _: Page) -> Result<Paginated<Board>, BoardError> { unimplemented!() }
+    async fn get_config(&self, _: BoardId) -> Result<BoardConfig, BoardError> { unimplemented!() }
+    async fn update_config(&self, _: BoardId, _: BoardConfig, _: domains::models::UserId) -> Result<BoardConfig, BoardError> { unimplemented!() }
+    async fn media_usage(&self, _: BoardId) -> Result<u64, BoardError> { unimplemented!() }
+    async fn list_volunteers(&self, _: BoardId) -> Result<Vec<(UserId, String, chrono::DateTime<Utc>)>, BoardError> { unimplemented!() }
+    async fn add_volunteer_by_username(&self, _: BoardId, _: &str, _: UserId) -> Result<(), BoardError> { unimplemented!() }
+    async fn remove_volunteer(&self, _: BoardId, _: UserId) -> Result<(), BoardError> { unimplemented!() }
+}
+
+fn make_board(site_id: SiteId) -> Board {
+    Board {
+        id:         BoardId(Uuid::new_v4()),
+        site_id,
+        slug:       Slug::new("b").unwrap(),
+        title:      "Random".to_owned(),
+        rules:      String::new(),
+        created_at: Utc::now(),
+    }
+}
+
+fn app(board: Board, entries: MemAnnouncements) -> Router {
+    announcement_routes(Arc::new(AnnouncementService::new(entries)), Arc::new(OneBoard(board)))
+}
+
+fn user(role: Role) -> CurrentUser {
+    CurrentUser::from_claims(Claims {
+        user_id:          UserId(Uuid::new_v4()),
+        username:         "staff".into(),
+        role,
+        owned_boards:     vec![],
+        volunteer_boards: vec![],
+        exp:              (Utc::now() + chrono::Duration::hours(24)).timestamp(),
+    })
+}
+
+fn post(body: serde_json::Value, as_user: CurrentUser) -> Request<Body> {
+    let mut req = Request::builder()
+        .method("POST")
+        .uri("/admin/blotter")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    req.extensions_mut().insert(as_user);
+    req
+}
+
+fn admin_request(method: &str, uri: &str) -> Request<Body> {
+    let mut req = Request::builder().method(method).uri(uri).body(Body::empty()).unwrap();
+    req.extensions_mut().insert(user(Role::Admin));
+    req
+}
+
+async fn json(resp: axum::response::Response) -> serde_json::Value {
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    serde_json::from_slice(&body).unwrap()
+}
+
+#[tokio::test]
+async fn admins_post_and_list_entries() {
+    let board = make_board(SiteId::DEFAULT);
+    let entries = MemAnnouncements::default();
+    let app = app(board.clone(), entries.clone());
+
+    let resp = app
+        .clone()
+        .oneshot(post(serde_json::json!({ "message": "  Maintenance tonight  " }), user(Role::Admin)))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let created = json(resp).await;
+    assert_eq!(created["message"], "Maintenance tonight");
+    assert!(created["board_id"].is_null());
+
+    let resp = app
+        .clone()
+        .oneshot(post(serde_json::json!({ "message": "New rules", "board_id": board.id }), user(Role::Admin)))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::CREATED);
+
+    let listed = json(app.oneshot(admin_request("GET", "/admin/blotter")).await.unwrap()).await;
+    let messages: Vec<_> = listed.as_array().unwrap().iter().map(|e| e["message"].clone()).collect();
+    assert_eq!(messages, ["New rules", "Maintenance tonight"]);
+    assert_eq!(listed[0]["board_id"], board.id.to_string());
+}
+
+#[tokio::test]
+async fn only_admins_post_entries() {
+    let app = app(make_board(SiteId::DEFAULT), MemAnnouncements::default());
+    let resp = app
+        .oneshot(post(serde_json::json!({ "message": "hello" }), user(Role::Janitor)))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn blank_entries_are_rejected() {
+    let app = app(make_board(SiteId::DEFAULT), MemAnnouncements::default());
+    let resp = app
+        .oneshot(post(serde_json::json!({ "message": "   " }), user(Role::Admin)))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn boards_of_another_site_are_not_found() {
+    let board = make_board(SiteId(Uuid::new_v4()));
+    let entries = MemAnnouncements::default();
+    let resp = app(board.clone(), entries.clone())
+        .oneshot(post(serde_json::json!({ "message": "hello", "board_id": board.id }), user(Role::Admin)))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    assert!(entries.rows.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn deleted_entries_are_gone() {
+    let entries = MemAnnouncements::default();
+    let entry = Announcement {
+        id:         AnnouncementId::new(),
+        site_id:    SiteId::DEFAULT,
+        board_id:   None,
+        message:    "hello".to_owned(),
+        created_at: Utc::now(),
+    };
+    entries.rows.lock().unwrap().push(entry.clone());
+    let app = app(make_board(SiteId::DEFAULT), entries.clone());
+
+    let uri = format!("/admin/blotter/{}", entry.id);
+    let resp = app.clone().oneshot(admin_request("DELETE", &uri)).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+    assert!(entries.rows.lock().unwrap().is_empty());
+
+    let resp = app.oneshot(admin_request("DELETE", &uri)).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn board_pages_show_the_blotter() {
+    let board = make_board(SiteId::DEFAULT);
+    let entry = Announcement {
+        id:         AnnouncementId::new(),
+        site_id:    board.site_id,
+        board_id:   Some(board.id),
+        message:    "<b>Welcome</b> back".to_owned(),
+        created_at: "2026-03-04T12:00:00Z".parse().unwrap(),
+    };
+    let page = BoardTemplate {
+        i18n:         I18n::default(),
+        board,
+        config:       BoardConfig::default(),
+        threads:      vec![],
+        total_pages:  1,
+        current_page: 1,
+        media:        MediaUrls::default(),
+        blotter:      vec![entry],
+    }
+    .into_response();
+    let body = axum::body::to_bytes(page.into_body(), usize::MAX).await.unwrap();
+    let html = String::from_utf8(body.to_vec()).unwrap();
+    assert!(html.contains(r#"<ul class="blotter""#));
+    assert!(html.contains(">2026-03-04</time> &#60;b&#62;Welcome&#60;/b&#62; back"));
+}
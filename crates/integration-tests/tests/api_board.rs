@@ -23,6 +23,7 @@ use uuid::Uuid;
 fn make_board(slug: &str) -> Board {
     Board {
         id:         BoardId(Uuid::new_v4()),
+        site_id:    domains::models::SiteId::DEFAULT,
         slug:       Slug::new(slug).unwrap(),
         title:      format!("/{slug}/ — Test"),
         rules:      "".to_owned(),
@@ -40,13 +41,13 @@ impl OkBoardRepo {
 
 #[async_trait::async_trait]
 impl BoardRepo for OkBoardRepo {
-    async fn create_board(&self, slug: &str, title: &str, _: &str) -> Result<Board, BoardError> {
+    async fn create_board(&self, _site: domains::models::SiteId, slug: &str, title: &str, _: &str) -> Result<Board, BoardError> {
         Ok(make_board(slug).tap(|_b| {
             // We can't mutate; just return a fresh board with the right title.
             let _ = title;
         }))
     }
-    async fn get_by_slug(&self, _: &str) -> Result<Board, BoardError> {
+    async fn get_by_slug(&self, _site: domains::models::SiteId, _: &str) -> Result<Board, BoardError> {
         Ok(self.board.clone())
     }
     async fn get_by_id(&self, _: BoardId) -> Result<Board, BoardError> {
@@ -60,15 +61,16 @@ impl BoardRepo for OkBoardRepo {
         Ok(b)
     }
     async fn delete_board(&self, _: BoardId) -> Result<(), BoardError> { Ok(()) }
-    async fn list_boards(&self, page: Page) -> Result<Paginated<Board>, BoardError> {
+    async fn list_boards(&self, _site: domains::models::SiteId, page: Page) -> Result<Paginated<Board>, BoardError> {
         Ok(Paginated::new(vec![self.board.clone()], 1, page, 15))
     }
     async fn get_config(&self, _: BoardId) -> Result<BoardConfig, BoardError> {
         Ok(BoardConfig::default())
     }
-    async fn update_config(&self, _: BoardId, c: BoardConfig) -> Result<BoardConfig, BoardError> {
+    async fn update_config(&self, _: BoardId, c: BoardConfig, _: domains::models::UserId) -> Result<BoardConfig, BoardError> {
         Ok(c)
     }
+    async fn media_usage(&self, _: BoardId) -> Result<u64, BoardError> { Ok(0) }
     async fn list_volunteers(&self, _: BoardId) -> Result<Vec<(domains::models::UserId, String, chrono::DateTime<chrono::Utc>)>, BoardError> { Ok(vec![]) }
     async fn add_volunteer_by_username(&self, _: BoardId, _: &str, _: domains::models::UserId) -> Result<(), BoardError> { Ok(()) }
     async fn remove_volunteer(&self, _: BoardId, _: domains::models::UserId) -> Result<(), BoardError> { Ok(()) }
@@ -78,10 +80,10 @@ struct NotFoundBoardRepo;
 
 #[async_trait::async_trait]
 impl BoardRepo for NotFoundBoardRepo {
-    async fn create_board(&self, _: &str, _: &str, _: &str) -> Result<Board, BoardError> {
+    async fn create_board(&self, _site: domains::models::SiteId, _: &str, _: &str, _: &str) -> Result<Board, BoardError> {
         Err(BoardError::NotFound { slug: String::new() })
     }
-    async fn get_by_slug(&self, s: &str) -> Result<Board, BoardError> {
+    async fn get_by_slug(&self, _site: domains::models::SiteId, s: &str) -> Result<Board, BoardError> {
         Err(BoardError::NotFound { slug: s.to_owned() })
     }
     async fn get_by_id(&self, id: BoardId) -> Result<Board, BoardError> {
@@ -95,15 +97,16 @@ impl BoardRepo for NotFoundBoardRepo {
     async fn delete_board(&self, id: BoardId) -> Result<(), BoardError> {
         Err(BoardError::NotFound { slug: id.0.to_string() })
     }
-    async fn list_boards(&self, page: Page) -> Result<Paginated<Board>, BoardError> {
+    async fn list_boards(&self, _site: domains::models::SiteId, page: Page) -> Result<Paginated<Board>, BoardError> {
         Ok(Paginated::new(vec![], 0, page, 15))
     }
     async fn get_config(&self, id: BoardId) -> Result<BoardConfig, BoardError> {
         Err(BoardError::NotFound { slug: id.0.to_string() })
     }
-    async fn update_config(&self, id: BoardId, _: BoardConfig) -> Result<BoardConfig, BoardError> {
+    async fn update_config(&self, id: BoardId, _: BoardConfig, _: domains::models::UserId) -> Result<BoardConfig, BoardError> {
         Err(BoardError::NotFound { slug: id.0.to_string() })
     }
+    async fn media_usage(&self, _: BoardId) -> Result<u64, BoardError> { Ok(0) }
     async fn list_volunteers(&self, _: BoardId) -> Result<Vec<(domains::models::UserId, String, chrono::DateTime<chrono::Utc>)>, BoardError> { Ok(vec![]) }
     async fn add_volunteer_by_username(&self, _: BoardId, _: &str, _: domains::models::UserId) -> Result<(), BoardError> { Ok(()) }
     async fn remove_volunteer(&self, _: BoardId, _: domains::models::UserId) -> Result<(), BoardError> { Ok(()) }
@@ -121,19 +124,39 @@ impl domains::ports::PostRepository for NopPostRepo {
     async fn find_by_thread(&self, _: ThreadId, _: Page) -> Result<Paginated<Post>, domains::errors::DomainError> { unimplemented!() }
     async fn find_recent_hashes(&self, _: BoardId, _: u32) -> Result<Vec<domains::models::ContentHash>, domains::errors::DomainError> { unimplemented!() }
     async fn find_by_ip_hash(&self, _: &domains::models::IpHash) -> Result<Vec<Post>, domains::errors::DomainError> { unimplemented!() }
+    async fn find_by_ip_hashes(&self, _: domains::models::SiteId, _: &[domains::models::IpHash], _: Page) -> Result<Paginated<domains::models::OverboardPost>, domains::errors::DomainError> { unimplemented!() }
     async fn save(&self, _: &Post) -> Result<(PostId, u64), domains::errors::DomainError> { unimplemented!() }
-    async fn delete(&self, _: PostId) -> Result<(), domains::errors::DomainError> { unimplemented!() }
+    async fn delete(&self, _: PostId, _: domains::models::DeletedBy) -> Result<(), domains::errors::DomainError> { unimplemented!() }
     async fn delete_by_ip_in_thread(&self, _: &domains::models::IpHash, _: domains::models::ThreadId) -> Result<u64, domains::errors::DomainError> { Ok(0) }
+    async fn delete_by_ip_on_board(&self, _: &domains::models::IpHash, _: domains::models::BoardId, _: chrono::DateTime<chrono::Utc>) -> Result<domains::models::PurgedPosts, domains::errors::DomainError> { Ok(Default::default()) }
+    async fn purge_deleted(&self, _: u32) -> Result<u32, domains::errors::DomainError> { unimplemented!() }
+    async fn edit(&self, _: PostId, _: &str, _: Option<String>, _: UserId) -> Result<(), domains::errors::DomainError> { unimplemented!() }
+    async fn find_revisions(&self, _: PostId) -> Result<Vec<PostRevision>, domains::errors::DomainError> { unimplemented!() }
+    async fn move_posts(&self, _: &domains::models::PostMove) -> Result<(), domains::errors::DomainError> { Ok(()) }
     async fn save_attachments(&self, _: &[domains::models::Attachment]) -> Result<(), domains::errors::DomainError> { Ok(()) }
     async fn find_attachments_by_post_ids(&self, _: &[PostId]) -> Result<std::collections::HashMap<PostId, Vec<domains::models::Attachment>>, domains::errors::DomainError> { Ok(std::collections::HashMap::new()) }
-    async fn find_overboard(&self, p: Page) -> Result<Paginated<domains::models::OverboardPost>, domains::errors::DomainError> { Ok(Paginated::new(vec![], 0, p, 15)) }
+    async fn find_overboard(&self, _site: domains::models::SiteId, p: Page) -> Result<Paginated<domains::models::OverboardPost>, domains::errors::DomainError> { Ok(Paginated::new(vec![], 0, p, 15)) }
+    async fn find_site_activity(&self, _: domains::models::SiteId, _: u32) -> Result<domains::models::SiteActivity, domains::errors::DomainError> { Ok(domains::models::SiteActivity::default()) }
     async fn search_fulltext(&self, _: BoardId, _: &str, p: Page) -> Result<Paginated<Post>, domains::errors::DomainError> { Ok(Paginated::new(vec![], 0, p, 15)) }
     async fn find_all_by_thread(&self, _: ThreadId) -> Result<Vec<Post>, domains::errors::DomainError> { Ok(vec![]) }
+    async fn find_visible_by_thread(&self, _: ThreadId, _: &domains::models::Viewer) -> Result<Vec<Post>, domains::errors::DomainError> { Ok(vec![]) }
     async fn find_thread_id_by_post_number(&self, _: BoardId, _: u64) -> Result<Option<domains::models::ThreadId>, domains::errors::DomainError> { Ok(None) }
     async fn set_pinned(&self, _: domains::models::PostId, _: bool) -> Result<(), domains::errors::DomainError> { Ok(()) }
-    async fn find_oldest_unpinned_reply(&self, _: domains::models::ThreadId) -> Result<Option<domains::models::PostId>, domains::errors::DomainError> { Ok(None) }
+    async fn find_cycle_excess(&self, _: domains::models::ThreadId, _: u32) -> Result<Vec<domains::models::PostId>, domains::errors::DomainError> { Ok(vec![]) }
     async fn find_attachment_by_hash(&self, _: &domains::models::ContentHash) -> Result<Option<domains::models::Attachment>, domains::errors::DomainError> { Ok(None) }
     async fn delete_by_id(&self, _: domains::models::PostId) -> Result<(), domains::errors::DomainError> { Ok(()) }
+    async fn find_referenced_media_keys(&self) -> Result<std::collections::HashSet<domains::models::MediaKey>, domains::errors::DomainError> { Ok(Default::default()) }
+    async fn find_thumbnailed_attachments(&self) -> Result<Vec<(BoardId, domains::models::Attachment)>, domains::errors::DomainError> { Ok(vec![]) }
+    async fn find_all_attachments(&self) -> Result<Vec<(BoardId, domains::models::Attachment)>, domains::errors::DomainError> { Ok(vec![]) }
+    async fn find_visible_by_id(&self, _: domains::models::PostId) -> Result<Option<domains::models::Post>, domains::errors::DomainError> { Ok(None) }
+    async fn find_by_ids(&self, _: domains::models::ThreadId, _: &[domains::models::PostId]) -> Result<Vec<domains::models::Post>, domains::errors::DomainError> { Ok(vec![]) }
+    async fn find_by_numbers(&self, _: domains::models::ThreadId, _: &[u64]) -> Result<Vec<domains::models::Post>, domains::errors::DomainError> { Ok(vec![]) }
+    async fn find_quoting(&self, _: domains::models::ThreadId, _: &[u64]) -> Result<Vec<domains::models::Post>, domains::errors::DomainError> { Ok(vec![]) }
+    async fn find_media_mime(&self, _: &domains::models::MediaKey) -> Result<Option<String>, domains::errors::DomainError> { Ok(None) }
+    async fn set_thumbnail_key(&self, _: &domains::models::MediaKey, _: &domains::models::MediaKey) -> Result<u64, domains::errors::DomainError> { Ok(0) }
+    async fn restore(&self, _: &domains::models::Post) -> Result<(), domains::errors::DomainError> { Ok(()) }
+    async fn find_number_range(&self, _: domains::models::BoardId) -> Result<Option<domains::models::PostNumberRange>, domains::errors::DomainError> { Ok(None) }
+    async fn find_by_number_range(&self, _: domains::models::BoardId, _: u64, _: u64, _: u32) -> Result<Vec<domains::models::Post>, domains::errors::DomainError> { Ok(vec![]) }
 }
 
 // ─── Helper: inject admin CurrentUser into a request ─────────────────────────
@@ -200,7 +223,7 @@ impl domains::ports::ArchiveRepository for NopArchiveRepo {
 
 #[tokio::test]
 async fn list_boards_returns_200_with_page() {
-    let app = board_public_routes(Arc::new(OkBoardRepo::for_slug("tech")), NopPostRepo, std::sync::Arc::new(NopArchiveRepo::default()));
+    let app = board_public_routes(Arc::new(OkBoardRepo::for_slug("tech")), NopPostRepo, std::sync::Arc::new(NopArchiveRepo));
     let resp = app.oneshot(get("/boards")).await.unwrap();
 
     assert_eq!(resp.status(), StatusCode::OK);
@@ -212,7 +235,7 @@ async fn list_boards_returns_200_with_page() {
 
 #[tokio::test]
 async fn list_boards_returns_empty_page_when_no_boards() {
-    let app = board_public_routes(Arc::new(NotFoundBoardRepo), NopPostRepo, std::sync::Arc::new(NopArchiveRepo::default()));
+    let app = board_public_routes(Arc::new(NotFoundBoardRepo), NopPostRepo, std::sync::Arc::new(NopArchiveRepo));
     let resp = app.oneshot(get("/boards")).await.unwrap();
 
     assert_eq!(resp.status(), StatusCode::OK);
@@ -224,7 +247,7 @@ async fn list_boards_returns_empty_page_when_no_boards() {
 
 #[tokio::test]
 async fn get_board_by_slug_returns_200_for_existing() {
-    let app = board_public_routes(Arc::new(OkBoardRepo::for_slug("b")), NopPostRepo, std::sync::Arc::new(NopArchiveRepo::default()));
+    let app = board_public_routes(Arc::new(OkBoardRepo::for_slug("b")), NopPostRepo, std::sync::Arc::new(NopArchiveRepo));
     let resp = app.oneshot(get("/boards/b")).await.unwrap();
 
     assert_eq!(resp.status(), StatusCode::OK);
@@ -235,7 +258,7 @@ async fn get_board_by_slug_returns_200_for_existing() {
 
 #[tokio::test]
 async fn get_board_by_slug_returns_404_for_missing() {
-    let app = board_public_routes(Arc::new(NotFoundBoardRepo), NopPostRepo, std::sync::Arc::new(NopArchiveRepo::default()));
+    let app = board_public_routes(Arc::new(NotFoundBoardRepo), NopPostRepo, std::sync::Arc::new(NopArchiveRepo));
     let resp = app.oneshot(get("/boards/nobody")).await.unwrap();
     assert_eq!(resp.status(), StatusCode::NOT_FOUND);
 }
@@ -327,7 +350,7 @@ impl Tap for Board {}
 #[tokio::test]
 async fn search_returns_403_when_disabled() {
     // BoardConfig::default() has search_enabled = false
-    let app = board_public_routes(Arc::new(OkBoardRepo::for_slug("tech")), NopPostRepo, std::sync::Arc::new(NopArchiveRepo::default()));
+    let app = board_public_routes(Arc::new(OkBoardRepo::for_slug("tech")), NopPostRepo, std::sync::Arc::new(NopArchiveRepo));
     let resp = app
         .oneshot(
             axum::http::Request::builder()
@@ -344,7 +367,7 @@ async fn search_returns_403_when_disabled() {
 
 #[tokio::test]
 async fn search_returns_400_when_query_empty() {
-    let app = board_public_routes(Arc::new(OkBoardRepo::for_slug("tech")), NopPostRepo, std::sync::Arc::new(NopArchiveRepo::default()));
+    let app = board_public_routes(Arc::new(OkBoardRepo::for_slug("tech")), NopPostRepo, std::sync::Arc::new(NopArchiveRepo));
     let resp = app
         .oneshot(
             axum::http::Request::builder()
@@ -31,6 +31,7 @@ impl OkConfigRepo {
         Self {
             board: Board {
                 id:         BoardId(Uuid::new_v4()),
+                site_id:    domains::models::SiteId::DEFAULT,
                 slug:       Slug::new(slug).unwrap(),
                 title:      format!("/{slug}/ — Test"),
                 rules:      "".to_owned(),
@@ -42,10 +43,10 @@ impl OkConfigRepo {
 
 #[async_trait::async_trait]
 impl BoardRepo for OkConfigRepo {
-    async fn create_board(&self, _s: &str, _t: &str, _: &str) -> Result<Board, BoardError> {
+    async fn create_board(&self, _site: domains::models::SiteId, _s: &str, _t: &str, _: &str) -> Result<Board, BoardError> {
         Ok(self.board.clone())
     }
-    async fn get_by_slug(&self, _: &str) -> Result<Board, BoardError> { Ok(self.board.clone()) }
+    async fn get_by_slug(&self, _site: domains::models::SiteId, _: &str) -> Result<Board, BoardError> { Ok(self.board.clone()) }
     async fn get_by_id(&self, _: BoardId) -> Result<Board, BoardError> { Ok(self.board.clone()) }
     async fn update_board(&self, _: BoardId, _: Option<&str>, _: Option<&str>)
         -> Result<Board, BoardError>
@@ -53,15 +54,16 @@ impl BoardRepo for OkConfigRepo {
         Ok(self.board.clone())
     }
     async fn delete_board(&self, _: BoardId) -> Result<(), BoardError> { Ok(()) }
-    async fn list_boards(&self, p: Page) -> Result<Paginated<Board>, BoardError> {
+    async fn list_boards(&self, _site: domains::models::SiteId, p: Page) -> Result<Paginated<Board>, BoardError> {
         Ok(Paginated::new(vec![self.board.clone()], 1, p, 15))
     }
     async fn get_config(&self, _: BoardId) -> Result<BoardConfig, BoardError> {
         Ok(BoardConfig::default())
     }
-    async fn update_config(&self, _: BoardId, c: BoardConfig) -> Result<BoardConfig, BoardError> {
+    async fn update_config(&self, _: BoardId, c: BoardConfig, _: domains::models::UserId) -> Result<BoardConfig, BoardError> {
         Ok(c)
     }
+    async fn media_usage(&self, _: BoardId) -> Result<u64, BoardError> { Ok(0) }
     async fn list_volunteers(&self, _: BoardId) -> Result<Vec<(domains::models::UserId, String, chrono::DateTime<chrono::Utc>)>, BoardError> { Ok(vec![]) }
     async fn add_volunteer_by_username(&self, _: BoardId, _: &str, _: domains::models::UserId) -> Result<(), BoardError> { Ok(()) }
     async fn remove_volunteer(&self, _: BoardId, _: domains::models::UserId) -> Result<(), BoardError> { Ok(()) }
@@ -232,6 +234,25 @@ async fn update_board_config_partial_update_leaves_other_fields_unchanged() {
     assert_eq!(json["bump_limit"], 500);
 }
 
+#[tokio::test]
+async fn update_board_config_ignores_media_quota_fields() {
+    let repo = OkConfigRepo::for_slug("b");
+    let board = repo.board.clone();
+    let app = board_owner_routes(Arc::new(repo), make_request_svc());
+
+    // Quotas are admin-only; the owner route drops them.
+    let body = r#"{"media_quota_mb":5,"quota_action":"prune","bump_limit":100}"#;
+    let req = with_board_owner_context(put_config("b", body), &board);
+    let resp = app.oneshot(req).await.unwrap();
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    let bytes = axum::body::to_bytes(resp.into_body(), 1 << 20).await.unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(json["bump_limit"], 100);
+    assert_eq!(json["media_quota_mb"], 0);
+    assert_eq!(json["quota_action"], "reject");
+}
+
 // ─── Volunteer endpoints ──────────────────────────────────────────────────────
 
 fn with_board_owner_user(mut req: Request<Body>, board: &Board) -> Request<Body> {
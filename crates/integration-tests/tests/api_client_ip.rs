@@ -0,0 +1,91 @@
+//! Integration tests for the `ClientIp` extractor.
+//!
+//! Tests verify:
+//! - Forwarding headers are ignored unless the peer is a trusted proxy
+//! - `X-Forwarded-For` is read from the right, past trusted hops, so entries a
+//!   client prepends cannot pick its address
+//! - `CF-Connecting-IP` is used only when enabled
+//! - `TRUSTED_PROXIES` parsing accepts addresses and CIDR ranges
+
+use std::net::SocketAddr;
+
+use api_adapters::axum::middleware::client_ip::{ClientIp, TrustedProxies};
+use axum::{
+    body::Body,
+    extract::ConnectInfo,
+    http::{Request, StatusCode},
+    routing::get,
+    Extension, Router,
+};
+use tower::ServiceExt;
+
+fn app(proxies: TrustedProxies) -> Router {
+    Router::new()
+        .route("/", get(|ClientIp(ip): ClientIp| async move { ip.to_string() }))
+        .layer(Extension(proxies))
+}
+
+fn proxies(cf_connecting_ip: bool) -> TrustedProxies {
+    TrustedProxies::parse("10.0.0.0/8, 127.0.0.1", cf_connecting_ip).unwrap()
+}
+
+async fn client_ip(app: Router, peer: &str, headers: &[(&str, &str)]) -> String {
+    let mut req = Request::get("/");
+    for (name, value) in headers {
+        req = req.header(*name, *value);
+    }
+    let mut req = req.body(Body::empty()).unwrap();
+    req.extensions_mut().insert(ConnectInfo(SocketAddr::new(peer.parse().unwrap(), 4000)));
+    let resp = app.oneshot(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+#[tokio::test]
+async fn untrusted_peers_are_the_client() {
+    let spoofed = [("x-forwarded-for", "198.51.100.9"), ("cf-connecting-ip", "198.51.100.9")];
+    assert_eq!(client_ip(app(proxies(true)), "203.0.113.5", &spoofed).await, "203.0.113.5");
+    // No proxies configured: the peer, whatever the headers say.
+    assert_eq!(client_ip(app(TrustedProxies::default()), "127.0.0.1", &spoofed).await, "127.0.0.1");
+}
+
+#[tokio::test]
+async fn forwarded_for_is_read_past_trusted_hops() {
+    let ip = client_ip(app(proxies(false)), "127.0.0.1", &[("x-forwarded-for", "198.51.100.9, 203.0.113.7, 10.1.2.3")]).await;
+    assert_eq!(ip, "203.0.113.7", "the client's own prefix is not believed");
+
+    let ip = client_ip(app(proxies(false)), "10.0.0.1", &[("x-forwarded-for", "203.0.113.7"), ("x-forwarded-for", "10.0.0.2")]).await;
+    assert_eq!(ip, "203.0.113.7", "repeated headers are one list");
+
+    let ip = client_ip(app(proxies(false)), "::ffff:127.0.0.1", &[("x-forwarded-for", "2001:db8::7")]).await;
+    assert_eq!(ip, "2001:db8::7", "IPv4-mapped peers match IPv4 ranges");
+
+    // Nothing usable beyond the proxy: the last trusted address reached.
+    assert_eq!(client_ip(app(proxies(false)), "127.0.0.1", &[]).await, "127.0.0.1");
+    let ip = client_ip(app(proxies(false)), "127.0.0.1", &[("x-forwarded-for", "203.0.113.7, junk, 10.0.0.2")]).await;
+    assert_eq!(ip, "10.0.0.2");
+}
+
+#[tokio::test]
+async fn cf_connecting_ip_only_when_enabled() {
+    let headers = [("cf-connecting-ip", "2001:db8::1"), ("x-forwarded-for", "203.0.113.7")];
+    assert_eq!(client_ip(app(proxies(true)), "10.0.0.1", &headers).await, "2001:db8::1");
+    assert_eq!(client_ip(app(proxies(false)), "10.0.0.1", &headers).await, "203.0.113.7");
+}
+
+#[tokio::test]
+async fn requests_without_a_peer_are_refused() {
+    let resp = app(proxies(false)).oneshot(Request::get("/").body(Body::empty()).unwrap()).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+}
+
+#[test]
+fn trusted_proxies_parse_addresses_and_ranges() {
+    let parsed = TrustedProxies::parse(" 10.0.0.0/8,,::1 , 2001:db8::/32", false).unwrap();
+    assert!(parsed.contains("10.200.0.1".parse().unwrap()));
+    assert!(parsed.contains("::1".parse().unwrap()));
+    assert!(parsed.contains("2001:db8::42".parse().unwrap()));
+    assert!(!parsed.contains("11.0.0.1".parse().unwrap()));
+    assert_eq!(TrustedProxies::parse("10.0.0.0/8, proxy.local", false).unwrap_err(), "proxy.local");
+}
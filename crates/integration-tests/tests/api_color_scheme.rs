@@ -0,0 +1,81 @@
+//! Integration tests for the color-scheme middleware.
+//!
+//! Tests verify:
+//! - A dark `Sec-CH-Prefers-Color-Scheme` hint turns a light board default dark
+//! - The `theme` cookie beats the hint
+//! - Dark board defaults are kept
+//! - HTML responses request the hint and vary on it; other responses are untouched
+
+use api_adapters::axum::middleware::color_scheme::color_scheme_middleware;
+use axum::{
+    body::Body,
+    http::{header, Request},
+    middleware,
+    response::Html,
+    routing::get,
+    Router,
+};
+use tower::ServiceExt;
+
+fn page(stylesheet: &str) -> String {
+    format!(r#"<head><link rel="stylesheet" href="{stylesheet}" id="theme-css"></head>"#)
+}
+
+fn app() -> Router {
+    Router::new()
+        .route("/light", get(|| async { Html(page("/static/css/style.css")) }))
+        .route("/tomorrow", get(|| async { Html(page("/static/css/tomorrow.css")) }))
+        .route("/json", get(|| async { axum::Json(serde_json::json!({ "ok": true })) }))
+        .layer(middleware::from_fn(color_scheme_middleware))
+}
+
+async fn get_page(uri: &str, headers: &[(&str, &str)]) -> (axum::http::HeaderMap, String) {
+    let mut req = Request::builder().uri(uri);
+    for (name, value) in headers {
+        req = req.header(*name, *value);
+    }
+    let resp = app().oneshot(req.body(Body::empty()).unwrap()).await.unwrap();
+    let headers = resp.headers().clone();
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    (headers, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[tokio::test]
+async fn dark_hint_swaps_a_light_default() {
+    let (_, html) = get_page("/light", &[("sec-ch-prefers-color-scheme", "\"dark\"")]).await;
+    assert!(html.contains(r#"href="/static/css/dark.css" data-default="/static/css/style.css" id="theme-css""#));
+}
+
+#[tokio::test]
+async fn light_hint_keeps_the_default() {
+    let (_, html) = get_page("/light", &[("sec-ch-prefers-color-scheme", "\"light\"")]).await;
+    assert!(html.contains(r#"href="/static/css/style.css" data-default="/static/css/style.css""#));
+}
+
+#[tokio::test]
+async fn theme_cookie_beats_the_hint() {
+    let headers = [("sec-ch-prefers-color-scheme", "\"dark\""), ("cookie", "token=abc; theme=yotsuba")];
+    let (_, html) = get_page("/light", &headers).await;
+    assert!(html.contains(r#"href="/static/css/yotsuba.css" data-default="/static/css/style.css""#));
+}
+
+#[tokio::test]
+async fn dark_board_default_is_kept() {
+    let (_, html) = get_page("/tomorrow", &[("sec-ch-prefers-color-scheme", "\"dark\"")]).await;
+    assert!(html.contains(r#"href="/static/css/tomorrow.css""#));
+}
+
+#[tokio::test]
+async fn html_responses_ask_for_the_hint() {
+    let (headers, _) = get_page("/light", &[]).await;
+    assert_eq!(headers["accept-ch"], "sec-ch-prefers-color-scheme");
+    assert_eq!(headers["critical-ch"], "sec-ch-prefers-color-scheme");
+    assert!(headers[header::VARY].to_str().unwrap().contains("Sec-CH-Prefers-Color-Scheme"));
+}
+
+#[tokio::test]
+async fn other_responses_are_untouched() {
+    let (headers, body) = get_page("/json", &[("cookie", "theme=dark")]).await;
+    assert!(headers.get("accept-ch").is_none());
+    assert_eq!(body, r#"{"ok":true}"#);
+}
@@ -0,0 +1,80 @@
+//! Integration tests for response compression.
+//!
+//! Tests verify:
+//! - Pages and JSON are compressed with the client's preferred enabled encoding
+//! - Media and other binary types pass through untouched
+//! - Small responses, and every response with compression off, are not compressed
+
+use api_adapters::axum::compression::{compression_layer, is_compressible};
+use axum::{
+    body::Body,
+    http::{header, HeaderMap, HeaderValue, Request},
+    response::{Html, IntoResponse},
+    routing::get,
+    Router,
+};
+use tower::ServiceExt;
+
+fn big_page() -> String {
+    "<div class=\"post\">No. 12345 Anonymous</div>\n".repeat(400)
+}
+
+fn app(gzip: bool, br: bool) -> Router {
+    Router::new()
+        .route("/page", get(|| async { Html(big_page()) }))
+        .route("/small", get(|| async { Html("<p>hi</p>") }))
+        .route("/json", get(|| async { ([(header::CONTENT_TYPE, "application/activity+json")], big_page()) }))
+        .route("/media", get(|| async { ([(header::CONTENT_TYPE, "video/webm")], big_page()).into_response() }))
+        .layer(compression_layer(gzip, br, 1024))
+}
+
+/// The `Content-Encoding` and body length of `path` fetched with `accept`.
+async fn fetch(app: Router, path: &str, accept: &str) -> (Option<String>, usize) {
+    let req = Request::get(path).header(header::ACCEPT_ENCODING, accept).body(Body::empty()).unwrap();
+    let resp = app.oneshot(req).await.unwrap();
+    let encoding = resp.headers().get(header::CONTENT_ENCODING).map(|v| v.to_str().unwrap().to_owned());
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    (encoding, body.len())
+}
+
+#[tokio::test]
+async fn pages_and_json_are_compressed() {
+    let (encoding, len) = fetch(app(true, true), "/page", "gzip, br").await;
+    assert_eq!(encoding.as_deref(), Some("br"));
+    assert!(len < big_page().len() / 10);
+
+    let (encoding, _) = fetch(app(true, true), "/json", "gzip").await;
+    assert_eq!(encoding.as_deref(), Some("gzip"));
+
+    // Only the enabled encodings are offered.
+    let (encoding, _) = fetch(app(true, false), "/page", "br").await;
+    assert_eq!(encoding, None);
+}
+
+#[tokio::test]
+async fn media_and_small_responses_are_sent_as_is() {
+    let (encoding, len) = fetch(app(true, true), "/media", "gzip, br").await;
+    assert_eq!((encoding, len), (None, big_page().len()));
+
+    let (encoding, _) = fetch(app(true, true), "/small", "gzip, br").await;
+    assert_eq!(encoding, None);
+
+    let (encoding, _) = fetch(app(false, false), "/page", "gzip, br").await;
+    assert_eq!(encoding, None);
+}
+
+#[test]
+fn only_text_types_are_compressible() {
+    let with = |content_type: &'static str| {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+        is_compressible(&headers)
+    };
+    for yes in ["text/html; charset=utf-8", "text/css", "application/json", "application/rss+xml", "image/svg+xml"] {
+        assert!(with(yes), "{yes}");
+    }
+    for no in ["image/png", "video/mp4", "application/zip", "application/pdf", "text/event-stream"] {
+        assert!(!with(no), "{no}");
+    }
+    assert!(!is_compressible(&HeaderMap::new()));
+}
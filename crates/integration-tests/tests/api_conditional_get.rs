@@ -0,0 +1,219 @@
+//! Integration tests for conditional GET on board pages.
+//!
+//! Tests verify:
+//! - Board pages and threads carry an `ETag`, `Last-Modified` and
+//!   `Cache-Control: private, no-cache`
+//! - A matching `If-None-Match` or a current `If-Modified-Since` is answered
+//!   `304` without running the handler, until the timestamp moves
+//! - `If-None-Match` takes precedence over `If-Modified-Since`
+//! - The `ETag` differs between viewers of the same URL
+//! - Threads of another board, unknown threads and failed lookups are served
+//!   in full without validators
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use api_adapters::axum::middleware::board_config::{board_config_middleware, BoardConfigSource, BoardConfigState};
+use api_adapters::axum::middleware::conditional_get::conditional_get_middleware;
+use async_trait::async_trait;
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+    middleware,
+    response::{Html, Response},
+    routing::get,
+    Router,
+};
+use chrono::{DateTime, TimeZone, Utc};
+use domains::errors::DomainError;
+use domains::models::{Board, BoardConfig, BoardId, SiteId, Slug, ThreadId};
+use domains::ports::LastModifiedRepository;
+use storage_adapters::cache::BoardConfigCache;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+const THREAD: Uuid = Uuid::from_u128(0x7);
+const OTHER_THREAD: Uuid = Uuid::from_u128(0x8);
+
+/// Board `/b/` only.
+struct OneBoard(Board);
+
+#[async_trait]
+impl BoardConfigSource for OneBoard {
+    async fn config_by_slug(
+        &self,
+        _site: SiteId,
+        slug: &Slug,
+    ) -> Result<Option<(Board, BoardId, BoardConfig)>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok((slug == &self.0.slug).then(|| (self.0.clone(), self.0.id, BoardConfig::default())))
+    }
+}
+
+/// Timestamps kept in memory; `THREAD` is on the board, `OTHER_THREAD` is not.
+struct MemModified {
+    board:   BoardId,
+    at:      Mutex<DateTime<Utc>>,
+    threads: HashMap<ThreadId, BoardId>,
+    fail:    bool,
+}
+
+#[async_trait]
+impl LastModifiedRepository for MemModified {
+    async fn board_modified_at(&self, board_id: BoardId) -> Result<Option<DateTime<Utc>>, DomainError> {
+        if self.fail {
+            return Err(DomainError::internal("database down"));
+        }
+        Ok((board_id == self.board).then(|| *self.at.lock().unwrap()))
+    }
+
+    async fn thread_modified_at(
+        &self,
+        board_id: BoardId,
+        thread_id: ThreadId,
+    ) -> Result<Option<DateTime<Utc>>, DomainError> {
+        if self.fail {
+            return Err(DomainError::internal("database down"));
+        }
+        let on_board = self.threads.get(&thread_id) == Some(&board_id);
+        Ok(on_board.then(|| *self.at.lock().unwrap()))
+    }
+}
+
+struct Fixture {
+    app:      Router,
+    modified: Arc<MemModified>,
+    renders:  Arc<AtomicUsize>,
+}
+
+fn fixture(fail: bool) -> Fixture {
+    let board = Board {
+        id:         BoardId(Uuid::new_v4()),
+        site_id:    SiteId::DEFAULT,
+        slug:       Slug::new("b").unwrap(),
+        title:      "Random".to_owned(),
+        rules:      String::new(),
+        created_at: Utc::now(),
+    };
+    let modified = Arc::new(MemModified {
+        board:   board.id,
+        at:      Mutex::new(Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap()),
+        threads: HashMap::from([(ThreadId(THREAD), board.id), (ThreadId(OTHER_THREAD), BoardId(Uuid::new_v4()))]),
+        fail,
+    });
+    let renders = Arc::new(AtomicUsize::new(0));
+    let render = {
+        let renders = renders.clone();
+        move || {
+            let renders = renders.clone();
+            async move { Html(format!("render #{}", renders.fetch_add(1, Ordering::SeqCst) + 1)) }
+        }
+    };
+    let config_state = BoardConfigState {
+        source: Arc::new(OneBoard(board)),
+        cache:  Arc::new(BoardConfigCache::new(Duration::from_secs(60))),
+    };
+    let repo: Arc<dyn LastModifiedRepository> = modified.clone();
+    let app = Router::new()
+        .route("/board/{slug}", get(render.clone()))
+        .route("/board/{slug}/catalog", get(render.clone()))
+        .route("/board/{slug}/thread/{id}", get(render.clone()))
+        .route("/board/{slug}/stats", get(render))
+        .route_layer(middleware::from_fn_with_state(repo, conditional_get_middleware))
+        .route_layer(middleware::from_fn_with_state(config_state, board_config_middleware));
+    Fixture { app, modified, renders }
+}
+
+async fn send(app: &Router, uri: &str, headers: &[(header::HeaderName, &str)]) -> Response {
+    let mut req = Request::get(uri);
+    for (name, value) in headers {
+        req = req.header(name, *value);
+    }
+    app.clone().oneshot(req.body(Body::empty()).unwrap()).await.unwrap()
+}
+
+fn etag(resp: &Response) -> String {
+    resp.headers()[header::ETAG].to_str().unwrap().to_owned()
+}
+
+#[tokio::test]
+async fn a_matching_etag_is_not_modified_until_the_page_changes() {
+    let f = fixture(false);
+    let thread_uri = format!("/board/b/thread/{THREAD}");
+    for uri in ["/board/b", "/board/b/catalog", thread_uri.as_str()] {
+        let first = send(&f.app, uri, &[]).await;
+        assert_eq!(first.status(), StatusCode::OK);
+        assert_eq!(first.headers()[header::LAST_MODIFIED], "Sun, 01 Mar 2026 12:00:00 GMT");
+        assert_eq!(first.headers()[header::CACHE_CONTROL], "private, no-cache");
+        let tag = etag(&first);
+        assert!(tag.starts_with("W/\""), "{tag}");
+
+        let renders = f.renders.load(Ordering::SeqCst);
+        let again = send(&f.app, uri, &[(header::IF_NONE_MATCH, &tag)]).await;
+        assert_eq!(again.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(etag(&again), tag);
+        assert!(axum::body::to_bytes(again.into_body(), usize::MAX).await.unwrap().is_empty());
+        let listed = format!("\"other\", {}", tag.trim_start_matches("W/"));
+        assert_eq!(send(&f.app, uri, &[(header::IF_NONE_MATCH, &listed)]).await.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(f.renders.load(Ordering::SeqCst), renders, "a 304 does not render");
+    }
+
+    // A write moves the timestamp, even within the second: the old ETag no longer matches.
+    let before = etag(&send(&f.app, "/board/b", &[]).await);
+    *f.modified.at.lock().unwrap() += chrono::Duration::milliseconds(10);
+    let resp = send(&f.app, "/board/b", &[(header::IF_NONE_MATCH, &before)]).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_ne!(etag(&resp), before);
+}
+
+#[tokio::test]
+async fn if_modified_since_is_used_without_if_none_match() {
+    let f = fixture(false);
+    let at = "Sun, 01 Mar 2026 12:00:00 GMT";
+    let before = "Sun, 01 Mar 2026 11:59:59 GMT";
+
+    assert_eq!(send(&f.app, "/board/b", &[(header::IF_MODIFIED_SINCE, at)]).await.status(), StatusCode::NOT_MODIFIED);
+    assert_eq!(send(&f.app, "/board/b", &[(header::IF_MODIFIED_SINCE, before)]).await.status(), StatusCode::OK);
+    assert_eq!(send(&f.app, "/board/b", &[(header::IF_MODIFIED_SINCE, "yesterday")]).await.status(), StatusCode::OK);
+    let both = [(header::IF_NONE_MATCH, "W/\"stale\""), (header::IF_MODIFIED_SINCE, at)];
+    assert_eq!(send(&f.app, "/board/b", &both).await.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn the_etag_differs_between_viewers() {
+    let f = fixture(false);
+    let anonymous = etag(&send(&f.app, "/board/b", &[]).await);
+    let themed = etag(&send(&f.app, "/board/b", &[(header::COOKIE, "theme=tomorrow")]).await);
+    let staff = etag(&send(&f.app, "/board/b", &[(header::AUTHORIZATION, "Bearer abc")]).await);
+    let spanish = etag(&send(&f.app, "/board/b", &[(header::ACCEPT_LANGUAGE, "es")]).await);
+    let page_two = etag(&send(&f.app, "/board/b?page=2", &[]).await);
+    let all = [&anonymous, &themed, &staff, &spanish, &page_two];
+    for (i, a) in all.iter().enumerate() {
+        for b in &all[i + 1..] {
+            assert_ne!(a, b);
+        }
+    }
+
+    let resp = send(&f.app, "/board/b", &[(header::IF_NONE_MATCH, &themed)]).await;
+    assert_eq!(resp.status(), StatusCode::OK, "another viewer's ETag does not match");
+}
+
+#[tokio::test]
+async fn unknown_threads_and_failed_lookups_are_served_in_full() {
+    let f = fixture(false);
+    for uri in [
+        format!("/board/b/thread/{OTHER_THREAD}"),
+        format!("/board/b/thread/{}", Uuid::new_v4()),
+        "/board/b/stats".to_owned(),
+    ] {
+        let resp = send(&f.app, &uri, &[(header::IF_NONE_MATCH, "*")]).await;
+        assert_eq!(resp.status(), StatusCode::OK, "{uri}");
+        assert!(!resp.headers().contains_key(header::ETAG), "{uri}");
+    }
+
+    let failing = fixture(true);
+    let resp = send(&failing.app, "/board/b", &[(header::IF_NONE_MATCH, "*")]).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert!(!resp.headers().contains_key(header::ETAG));
+}
@@ -0,0 +1,129 @@
+//! Integration tests for CSRF protection.
+//!
+//! Tests verify:
+//! - Pages set the `csrf` cookie and get the token in each POST form, and
+//!   only in POST forms
+//! - Form posts need the cookie's token in the header, a urlencoded field or
+//!   the first multipart field; uploads after it arrive intact
+//! - Safe methods, `Authorization` headers and JSON bodies are not checked
+
+use api_adapters::axum::middleware::csrf::csrf_middleware;
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+    middleware,
+    response::Html,
+    routing::get,
+    Router,
+};
+use tower::ServiceExt;
+
+const TOKEN: &str = "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+
+const PAGE: &str = r#"<form method="GET" action="/search"></form><form method="post" action="/submit"><textarea name="body"></textarea></form><FORM METHOD=POST action="/other"></FORM>"#;
+
+/// A page with forms, and `/submit` echoing the body it received.
+fn app() -> Router {
+    Router::new()
+        .route("/page", get(|| async { Html(PAGE) }))
+        .route("/submit", axum::routing::post(|body: String| async move { body }))
+        .layer(middleware::from_fn(csrf_middleware))
+}
+
+fn post(content_type: &str) -> axum::http::request::Builder {
+    Request::post("/submit").header(header::CONTENT_TYPE, content_type)
+}
+
+fn with_cookie(builder: axum::http::request::Builder) -> axum::http::request::Builder {
+    builder.header(header::COOKIE, format!("theme=dark; csrf={TOKEN}"))
+}
+
+async fn body_text(resp: axum::response::Response) -> String {
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+fn multipart(first: &str, first_value: &str) -> String {
+    format!(
+        "--XyZ\r\nContent-Disposition: form-data; name=\"{first}\"\r\n\r\n{first_value}\r\n\
+         --XyZ\r\nContent-Disposition: form-data; name=\"files\"; filename=\"a.txt\"\r\n\
+         Content-Type: text/plain\r\n\r\nfile contents\r\n--XyZ--\r\n"
+    )
+}
+
+#[tokio::test]
+async fn pages_get_a_cookie_and_tokens_in_post_forms() {
+    let resp = app().oneshot(Request::get("/page").body(Body::empty()).unwrap()).await.unwrap();
+    let cookie = resp.headers()[header::SET_COOKIE].to_str().unwrap().to_owned();
+    assert!(cookie.starts_with("csrf=") && cookie.contains("SameSite=Lax"));
+    let token = &cookie["csrf=".len()..cookie.find(';').unwrap()];
+    assert_eq!(resp.headers()[header::CACHE_CONTROL], "private");
+
+    let html = body_text(resp).await;
+    let field = format!(r#"<input type="hidden" name="_csrf" value="{token}">"#);
+    assert_eq!(html.matches(&field).count(), 2);
+    assert!(html.starts_with(r#"<form method="GET" action="/search"></form><form method="post" action="/submit"><input"#));
+
+    // A visitor who has a token keeps it.
+    let req = with_cookie(Request::get("/page")).body(Body::empty()).unwrap();
+    let resp = app().oneshot(req).await.unwrap();
+    assert!(resp.headers().get(header::SET_COOKIE).is_none());
+    assert!(body_text(resp).await.contains(TOKEN));
+}
+
+#[tokio::test]
+async fn form_posts_need_the_token() {
+    let form = "body=hi".to_owned();
+    let resp = app()
+        .oneshot(post("application/x-www-form-urlencoded").body(Body::from(form.clone())).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    assert!(body_text(resp).await.contains("CSRF_FAILED"));
+
+    // A cookie alone is not enough, nor a token that differs from it.
+    let req = with_cookie(post("application/x-www-form-urlencoded")).body(Body::from(form.clone()));
+    assert_eq!(app().oneshot(req.unwrap()).await.unwrap().status(), StatusCode::FORBIDDEN);
+    let req = with_cookie(post("text/plain"))
+        .header("X-CSRF-Token", TOKEN.replace('0', "1"))
+        .body(Body::from(form.clone()));
+    assert_eq!(app().oneshot(req.unwrap()).await.unwrap().status(), StatusCode::FORBIDDEN);
+
+    // Without a body type: e.g. a no-cors fetch of a Blob.
+    let req = with_cookie(Request::post("/submit")).body(Body::from("x"));
+    assert_eq!(app().oneshot(req.unwrap()).await.unwrap().status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn tokens_are_accepted_from_header_and_fields() {
+    let req = with_cookie(post("text/plain")).header("X-CSRF-Token", TOKEN).body(Body::from("hi"));
+    let resp = app().oneshot(req.unwrap()).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let form = format!("body=hi&_csrf={TOKEN}");
+    let req = with_cookie(post("application/x-www-form-urlencoded")).body(Body::from(form.clone()));
+    let resp = app().oneshot(req.unwrap()).await.unwrap();
+    assert_eq!(body_text(resp).await, form, "the handler still gets the whole form");
+
+    let upload = multipart("_csrf", TOKEN);
+    let req = with_cookie(post("multipart/form-data; boundary=XyZ")).body(Body::from(upload.clone()));
+    let resp = app().oneshot(req.unwrap()).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(body_text(resp).await, upload);
+
+    // Only the first multipart field is looked at.
+    let upload = multipart("body", TOKEN);
+    let req = with_cookie(post("multipart/form-data; boundary=XyZ")).body(Body::from(upload));
+    assert_eq!(app().oneshot(req.unwrap()).await.unwrap().status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn requests_browsers_do_not_send_cross_site_are_not_checked() {
+    let req = post("application/json").body(Body::from("{}"));
+    assert_eq!(app().oneshot(req.unwrap()).await.unwrap().status(), StatusCode::OK);
+
+    let req = post("multipart/form-data; boundary=XyZ")
+        .header(header::AUTHORIZATION, "Bearer rbk_0000")
+        .body(Body::from(multipart("body", "hi")));
+    assert_eq!(app().oneshot(req.unwrap()).await.unwrap().status(), StatusCode::OK);
+}
@@ -0,0 +1,135 @@
+//! Integration tests for the JSON error envelope.
+//!
+//! Tests verify:
+//! - `ApiError` renders `{"error": {"code", "message"}}`, with `retry_after`
+//!   for rate limits and `details` for bans
+//! - Extractor rejections, missing roles, unknown methods and empty 500s get
+//!   the same envelope from `error_envelope_middleware`
+//! - A 5xx body's text is not sent; `Retry-After` becomes `retry_after`
+//! - JSON and HTML responses and successes are untouched
+
+use api_adapters::axum::middleware::auth::AdminUser;
+use api_adapters::axum::middleware::error_envelope::error_envelope_middleware;
+use api_adapters::common::errors::ApiError;
+use axum::{
+    body::Body,
+    http::{header, Method, Request, StatusCode},
+    middleware,
+    response::Html,
+    routing::{get, post},
+    Json, Router,
+};
+use serde_json::Value;
+use tower::ServiceExt;
+
+fn app() -> Router {
+    Router::new()
+        .route("/limited", get(|| async { Err::<(), _>(ApiError::RateLimited { retry_after_secs: 30 }) }))
+        .route(
+            "/banned",
+            get(|| async {
+                Err::<(), _>(ApiError::Banned { reason: "spam".to_owned(), issued_at: None, expires_at: None })
+            }),
+        )
+        .route("/echo", post(|Json(v): Json<Value>| async move { Json(v) }))
+        .route("/admin", get(|_: AdminUser| async { "ok" }))
+        .route("/leaky", get(|| async { (StatusCode::INTERNAL_SERVER_ERROR, "connection refused at db:5432") }))
+        .route(
+            "/busy",
+            get(|| async { (StatusCode::SERVICE_UNAVAILABLE, [(header::RETRY_AFTER, "120")], "") }),
+        )
+        .route("/page", get(|| async { (StatusCode::FORBIDDEN, Html("<p>ban notice</p>")) }))
+        .route("/text", get(|| async { "plain" }))
+        .layer(middleware::from_fn(error_envelope_middleware))
+}
+
+async fn send(method: Method, uri: &str, json: Option<&str>) -> (StatusCode, axum::http::HeaderMap, String) {
+    let mut req = Request::builder().method(method).uri(uri);
+    let body = match json {
+        Some(json) => {
+            req = req.header(header::CONTENT_TYPE, "application/json");
+            Body::from(json.to_owned())
+        }
+        None => Body::empty(),
+    };
+    let resp = app().oneshot(req.body(body).unwrap()).await.unwrap();
+    let status = resp.status();
+    let headers = resp.headers().clone();
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    (status, headers, String::from_utf8(body.to_vec()).unwrap())
+}
+
+fn error(body: &str) -> Value {
+    let json: Value = serde_json::from_str(body).unwrap_or_else(|e| panic!("{e}: {body}"));
+    json["error"].clone()
+}
+
+#[tokio::test]
+async fn api_errors_render_the_envelope() {
+    let (status, headers, body) = send(Method::GET, "/limited", None).await;
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(headers[header::RETRY_AFTER], "30");
+    let err = error(&body);
+    assert_eq!(err["code"], "RATE_LIMITED");
+    assert_eq!(err["retry_after"], 30);
+    assert!(err["message"].as_str().unwrap().contains("retry after 30s"));
+    assert!(err.get("details").is_none());
+
+    let (status, _, body) = send(Method::GET, "/banned", None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let err = error(&body);
+    assert_eq!(err["code"], "BANNED");
+    assert_eq!(err["message"], "you are banned: spam");
+    assert!(err["details"].as_object().unwrap().contains_key("expires_at"));
+    assert!(err.get("retry_after").is_none());
+}
+
+#[tokio::test]
+async fn rejections_get_the_envelope() {
+    let (status, headers, body) = send(Method::POST, "/echo", Some("{not json")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(headers[header::CONTENT_TYPE], "application/json");
+    let err = error(&body);
+    assert_eq!(err["code"], "BAD_REQUEST");
+    assert!(err["message"].as_str().unwrap().contains("JSON"), "{body}");
+
+    let (status, _, body) = send(Method::GET, "/admin", None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(error(&body)["code"], "UNAUTHORIZED");
+    assert_eq!(error(&body)["message"], "authentication required");
+
+    let (status, headers, body) = send(Method::DELETE, "/text", None).await;
+    assert_eq!(status, StatusCode::METHOD_NOT_ALLOWED);
+    assert!(headers.contains_key(header::ALLOW));
+    assert_eq!(error(&body)["code"], "METHOD_NOT_ALLOWED");
+    assert_eq!(error(&body)["message"], "method not allowed");
+}
+
+#[tokio::test]
+async fn server_errors_do_not_leak_their_body() {
+    let (status, _, body) = send(Method::GET, "/leaky", None).await;
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert!(!body.contains("db:5432"), "{body}");
+    let err = error(&body);
+    assert_eq!(err["code"], "INTERNAL_ERROR");
+    assert_eq!(err["message"], "an unexpected error occurred");
+
+    let (status, headers, body) = send(Method::GET, "/busy", None).await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(headers[header::RETRY_AFTER], "120");
+    assert_eq!(error(&body)["code"], "SERVICE_UNAVAILABLE");
+    assert_eq!(error(&body)["retry_after"], 120);
+}
+
+#[tokio::test]
+async fn html_pages_and_successes_are_untouched() {
+    let (status, _, body) = send(Method::GET, "/page", None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body, "<p>ban notice</p>");
+    let (status, _, body) = send(Method::GET, "/text", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, "plain");
+    let (status, _, body) = send(Method::POST, "/echo", Some(r#"{"error":"mine"}"#)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, r#"{"error":"mine"}"#);
+}
@@ -0,0 +1,90 @@
+//! Integration tests for the error-page middleware.
+//!
+//! Tests verify:
+//! - Browser requests get `error.html` for 403/404/429/500, keeping the status
+//!   and `Retry-After`
+//! - The page is in the `Accept-Language` locale
+//! - API clients keep the JSON body; HTML error responses and other statuses
+//!   are untouched
+
+use api_adapters::axum::middleware::error_page::error_page_middleware;
+use api_adapters::common::errors::ApiError;
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+    middleware,
+    response::Html,
+    routing::get,
+    Router,
+};
+use tower::ServiceExt;
+
+const BROWSER_ACCEPT: &str = "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8";
+
+fn app() -> Router {
+    Router::new()
+        .route("/forbidden", get(|| async { Err::<(), _>(ApiError::Forbidden) }))
+        .route("/missing", get(|| async { Err::<(), _>(ApiError::NotFound("thread".to_owned())) }))
+        .route("/limited", get(|| async { Err::<(), _>(ApiError::RateLimited { retry_after_secs: 30 }) }))
+        .route("/broken", get(|| async { StatusCode::INTERNAL_SERVER_ERROR }))
+        .route("/conflict", get(|| async { Err::<(), _>(ApiError::Conflict("taken".to_owned())) }))
+        .route("/banned", get(|| async { (StatusCode::FORBIDDEN, Html("<p>ban notice</p>")) }))
+        .layer(middleware::from_fn(error_page_middleware))
+}
+
+async fn get_page(uri: &str, headers: &[(&str, &str)]) -> (StatusCode, axum::http::HeaderMap, String) {
+    let mut req = Request::builder().uri(uri);
+    for (name, value) in headers {
+        req = req.header(*name, *value);
+    }
+    let resp = app().oneshot(req.body(Body::empty()).unwrap()).await.unwrap();
+    let status = resp.status();
+    let headers = resp.headers().clone();
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    (status, headers, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[tokio::test]
+async fn browsers_get_an_error_page() {
+    for (uri, status, heading) in [
+        ("/forbidden", StatusCode::FORBIDDEN, "Forbidden"),
+        ("/missing", StatusCode::NOT_FOUND, "Not Found"),
+        ("/broken", StatusCode::INTERNAL_SERVER_ERROR, "Server Error"),
+    ] {
+        let (got, headers, html) = get_page(uri, &[("accept", BROWSER_ACCEPT)]).await;
+        assert_eq!(got, status, "{uri}");
+        assert!(headers[header::CONTENT_TYPE].to_str().unwrap().starts_with("text/html"), "{uri}");
+        assert!(html.contains(&format!(r#"<span class="error-status">{}</span> {heading}"#, status.as_u16())), "{uri}");
+    }
+}
+
+#[tokio::test]
+async fn rate_limit_page_keeps_retry_after() {
+    let (status, headers, html) = get_page("/limited", &[("accept", BROWSER_ACCEPT)]).await;
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(headers[header::RETRY_AFTER], "30");
+    assert!(html.contains("Try again in 30 seconds."));
+}
+
+#[tokio::test]
+async fn page_follows_accept_language() {
+    let headers = [("accept", BROWSER_ACCEPT), ("accept-language", "es-ES,es;q=0.9")];
+    let (_, _, html) = get_page("/missing", &headers).await;
+    assert!(html.contains("No encontrado"));
+}
+
+#[tokio::test]
+async fn api_clients_keep_the_json_body() {
+    let (status, _, body) = get_page("/missing", &[("accept", "application/json")]).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert!(body.contains(r#""code":"NOT_FOUND""#));
+}
+
+#[tokio::test]
+async fn html_errors_and_other_statuses_are_untouched() {
+    let (_, _, body) = get_page("/banned", &[("accept", BROWSER_ACCEPT)]).await;
+    assert_eq!(body, "<p>ban notice</p>");
+    let (status, _, body) = get_page("/conflict", &[("accept", BROWSER_ACCEPT)]).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert!(body.contains(r#""code":"CONFLICT""#));
+}
@@ -0,0 +1,101 @@
+//! Integration tests for the error-reporting middleware.
+//!
+//! Tests verify:
+//! - A handler panic becomes a 500 and is reported as a panic, tagged with
+//!   the request ID, method, matched route and status
+//! - An `ApiError::Internal` is reported with its internal message, which
+//!   stays out of the response body
+//! - 4xx responses and 503s are not reported
+
+use std::sync::{Arc, Mutex};
+
+use api_adapters::axum::middleware::{
+    error_report::error_report_middleware,
+    request_id::request_id_middleware,
+};
+use api_adapters::common::errors::ApiError;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    middleware,
+    routing::get,
+    Router,
+};
+use domains::ports::{ErrorReport, ErrorReporter};
+use tower::ServiceExt;
+
+#[derive(Default)]
+struct Recording(Mutex<Vec<ErrorReport>>);
+
+impl ErrorReporter for Recording {
+    fn report(&self, report: &ErrorReport) {
+        self.0.lock().unwrap().push(report.clone());
+    }
+}
+
+fn app(reporter: Arc<Recording>) -> Router {
+    Router::new()
+        .route("/panic/{id}", get(|| async {
+            panic!("handler blew up");
+            #[allow(unreachable_code)]
+            StatusCode::OK
+        }))
+        .route(
+            "/internal",
+            get(|| async { Err::<(), _>(ApiError::Internal("db connection reset".into())) }),
+        )
+        .route("/missing", get(|| async { Err::<(), _>(ApiError::NotFound("thread".into())) }))
+        .route("/unavailable", get(|| async { StatusCode::SERVICE_UNAVAILABLE }))
+        .layer(middleware::from_fn_with_state(reporter as Arc<dyn ErrorReporter>, error_report_middleware))
+        .layer(middleware::from_fn(request_id_middleware))
+}
+
+async fn get_path(reporter: &Arc<Recording>, uri: &str) -> (StatusCode, String) {
+    let req = Request::builder().uri(uri).header("x-request-id", "req-42").body(Body::empty()).unwrap();
+    let resp = app(reporter.clone()).oneshot(req).await.unwrap();
+    let status = resp.status();
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[tokio::test]
+async fn panic_is_a_reported_500() {
+    let reporter = Arc::new(Recording::default());
+    let (status, _) = get_path(&reporter, "/panic/7").await;
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+
+    let reports = reporter.0.lock().unwrap();
+    assert_eq!(reports.len(), 1);
+    assert!(reports[0].panic);
+    assert_eq!(reports[0].message, "handler blew up");
+    assert_eq!(
+        reports[0].context,
+        vec![
+            ("request_id", "req-42".to_owned()),
+            ("method", "GET".to_owned()),
+            ("route", "/panic/{id}".to_owned()),
+            ("status", "500".to_owned()),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn internal_error_cause_is_reported_but_not_sent() {
+    let reporter = Arc::new(Recording::default());
+    let (status, body) = get_path(&reporter, "/internal").await;
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert!(!body.contains("db connection reset"), "{body}");
+
+    let reports = reporter.0.lock().unwrap();
+    assert_eq!(reports.len(), 1);
+    assert!(!reports[0].panic);
+    assert_eq!(reports[0].message, "db connection reset");
+}
+
+#[tokio::test]
+async fn client_errors_and_503_are_not_reported() {
+    let reporter = Arc::new(Recording::default());
+    assert_eq!(get_path(&reporter, "/missing").await.0, StatusCode::NOT_FOUND);
+    assert_eq!(get_path(&reporter, "/unavailable").await.0, StatusCode::SERVICE_UNAVAILABLE);
+    assert!(reporter.0.lock().unwrap().is_empty());
+}
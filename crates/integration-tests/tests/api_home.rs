@@ -0,0 +1,134 @@
+//! Integration tests for the homepage.
+//!
+//! Tests verify:
+//! - Boards are listed under their category, named categories first and
+//!   uncategorised boards under "Other"
+//! - Boards past the first page of `list_boards` are listed
+//! - Recent posts link to their thread and are formatted under their board's toggles
+//! - Site totals are shown
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use api_adapters::axum::handlers::home_handlers::SiteActivitySource;
+use api_adapters::axum::routes::home_routes::home_routes;
+use axum::{body::Body, http::Request};
+use chrono::Utc;
+use domains::models::*;
+use services::board::{BoardError, BoardRepo};
+use services::post::PostError;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+fn make_board(slug: &str) -> Board {
+    Board {
+        id:         BoardId(Uuid::new_v4()),
+        site_id:    SiteId::DEFAULT,
+        slug:       Slug::new(slug).unwrap(),
+        title:      format!("{slug} title"),
+        rules:      String::new(),
+        created_at: Utc::now(),
+    }
+}
+
+/// Boards with their configs, served one board per `list_boards` page.
+struct Directory {
+    boards:  Vec<Board>,
+    configs: HashMap<BoardId, BoardConfig>,
+}
+
+impl Directory {
+    fn new(boards: &[(&str, &str, bool)]) -> Self {
+        let mut directory = Directory { boards: vec![], configs: HashMap::new() };
+        for (slug, category, nsfw) in boards {
+            let board = make_board(slug);
+            let config = BoardConfig { category: (*category).to_owned(), nsfw: *nsfw, ..BoardConfig::default() };
+            directory.configs.insert(board.id, config);
+            directory.boards.push(board);
+        }
+        directory
+    }
+}
+
+#[async_trait::async_trait]
+impl BoardRepo for Directory {
+    async fn create_board(&self, _: SiteId, _: &str, _: &str, _: &str) -> Result<Board, BoardError> { unimplemented!() }
+    async fn get_by_slug(&self, _: SiteId, _: &str) -> Result<Board, BoardError> { unimplemented!() }
+    async fn get_by_id(&self, _: BoardId) -> Result<Board, BoardError> { unimplemented!() }
+    async fn update_board(&self, _: BoardId, _: Option<&str>, _: Option<&str>) -> Result<Board, BoardError> {
+        unimplemented!()
+    }
+    async fn delete_board(&self, _: BoardId) -> Result<(), BoardError> { unimplemented!() }
+    async fn list_boards(&self, _: SiteId, page: Page) -> Result<Paginated<Board>, BoardError> {
+        let items = self.boards.iter().skip(page.0 as usize - 1).take(1).cloned().collect();
+        Ok(Paginated::new(items, self.boards.len() as u64, page, 1))
+    }
+    async fn get_config(&self, id: BoardId) -> Result<BoardConfig, BoardError> { Ok(self.configs[&id].clone()) }
+    async fn update_config(&self, _: BoardId, _: BoardConfig, _: domains::models::UserId) -> Result<BoardConfig, BoardError> { unimplemented!() }
+    async fn media_usage(&self, _: BoardId) -> Result<u64, BoardError> { Ok(0) }
+    async fn list_volunteers(&self, _: BoardId)
+        -> Result<Vec<(UserId, String, chrono::DateTime<Utc>)>, BoardError> { Ok(vec![]) }
+    async fn add_volunteer_by_username(&self, _: BoardId, _: &str, _: UserId) -> Result<(), BoardError> { Ok(()) }
+    async fn remove_volunteer(&self, _: BoardId, _: UserId) -> Result<(), BoardError> { Ok(()) }
+}
+
+struct Activity(SiteActivity);
+
+#[async_trait::async_trait]
+impl SiteActivitySource for Activity {
+    async fn site_activity(&self, _: SiteId, _: u32) -> Result<SiteActivity, PostError> { Ok(self.0.clone()) }
+}
+
+fn recent_post(board_slug: &str, body: &str) -> OverboardPost {
+    OverboardPost {
+        id: PostId(Uuid::new_v4()), thread_id: ThreadId(Uuid::new_v4()), board_slug: board_slug.to_owned(),
+        body: body.to_owned(), name: None, tripcode: None, ip_hash: IpHash::new("ab".repeat(32)),
+        created_at: Utc::now(), post_number: 42, subject: None, capcode: None,
+    }
+}
+
+async fn homepage(directory: Directory, activity: SiteActivity) -> String {
+    let app = home_routes(Arc::new(directory), Arc::new(Activity(activity)));
+    let resp = app.oneshot(Request::builder().uri("/").body(Body::empty()).unwrap()).await.unwrap();
+    assert!(resp.status().is_success());
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+#[tokio::test]
+async fn boards_are_grouped_by_category() {
+    let directory = Directory::new(&[("b", "", false), ("g", "Technology", false), ("a", "Creative", true)]);
+    let html = homepage(directory, SiteActivity::default()).await;
+
+    let creative = html.find("<h3>Creative</h3>").expect("named category");
+    let technology = html.find("<h3>Technology</h3>").expect("named category");
+    let other = html.find("<h3>Other</h3>").expect("uncategorised boards");
+    assert!(creative < technology && technology < other);
+    assert!(html.contains(r#"<a href="/board/g">/g/ — g title</a>"#));
+    assert!(html[creative..technology].contains("/board/a") && html[creative..technology].contains("NSFW"));
+    assert!(html[other..].contains("/board/b"));
+    assert!(html.contains("3 boards"));
+}
+
+#[tokio::test]
+async fn recent_posts_and_totals_are_shown() {
+    let directory = Directory::new(&[("b", "", false)]);
+    let post = recent_post("b", "**hello** world");
+    let link = format!(r#"href="/board/b/thread/{}#post-42""#, post.thread_id.0);
+    let activity = SiteActivity { recent_posts: vec![post], total_threads: 1, total_posts: 12, posts_last_day: 3 };
+    let html = homepage(directory, activity).await;
+
+    assert!(html.contains(&link));
+    assert!(html.contains("<strong>hello</strong> world"));
+    assert!(html.contains("1 thread"));
+    assert!(html.contains("12 posts"));
+    assert!(html.contains("3 in the last 24 hours"));
+    assert!(html.contains(r#"href="/overboard""#));
+}
+
+#[tokio::test]
+async fn empty_site_says_so() {
+    let html = homepage(Directory::new(&[]), SiteActivity::default()).await;
+    assert!(html.contains("No boards yet."));
+    assert!(html.contains("Nothing has been posted yet."));
+}
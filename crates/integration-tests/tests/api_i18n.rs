@@ -0,0 +1,89 @@
+//! Integration tests for UI translations.
+//!
+//! Tests verify:
+//! - `Accept-Language` negotiation honours q-values and primary subtags
+//! - Unsupported or absent languages fall back to the board default
+//! - Pages render in the negotiated locale and vary on `Accept-Language`
+//! - Every English message has a Spanish translation
+
+use std::collections::BTreeSet;
+
+use api_adapters::axum::handlers::auth_handlers::login_page;
+use api_adapters::axum::i18n::{negotiate, I18n};
+use axum::{
+    body::Body,
+    http::{header, Request},
+    routing::get,
+    Router,
+};
+use domains::models::Locale;
+use tower::ServiceExt;
+
+#[test]
+fn negotiation_prefers_the_highest_weight() {
+    assert_eq!(negotiate(Some("es"), Locale::En), Locale::Es);
+    assert_eq!(negotiate(Some("es-AR,es;q=0.9"), Locale::En), Locale::Es);
+    assert_eq!(negotiate(Some("en;q=0.5, es;q=0.8"), Locale::En), Locale::Es);
+    assert_eq!(negotiate(Some("fr-FR, en;q=0.3, es;q=0.2"), Locale::Es), Locale::En);
+    assert_eq!(negotiate(Some("EN-us"), Locale::Es), Locale::En);
+}
+
+#[test]
+fn negotiation_falls_back_to_the_default() {
+    assert_eq!(negotiate(None, Locale::Es), Locale::Es);
+    assert_eq!(negotiate(Some("fr, de;q=0.5"), Locale::Es), Locale::Es);
+    assert_eq!(negotiate(Some("*"), Locale::Es), Locale::Es);
+    assert_eq!(negotiate(Some("es;q=0"), Locale::En), Locale::En);
+    assert_eq!(negotiate(Some("es;q=abc"), Locale::En), Locale::En);
+}
+
+#[test]
+fn messages_pluralize_and_fall_back() {
+    let es = I18n::new(Locale::Es);
+    assert_eq!(es.count("thread-replies", 1u32), "1 respuesta");
+    assert_eq!(es.count("thread-replies", 3u32), "3 respuestas");
+    assert_eq!(I18n::new(Locale::En).count("thread-replies", 1u32), "1 reply");
+    assert_eq!(es.args("page-of", &[("page", 2), ("total", 5)]), "Página 2 de 5");
+    assert_eq!(es.t("no-such-message"), "no-such-message");
+}
+
+async fn login(accept_language: Option<&str>) -> (axum::http::HeaderMap, String) {
+    let app = Router::new().route("/auth/login", get(login_page));
+    let mut req = Request::builder().uri("/auth/login");
+    if let Some(value) = accept_language {
+        req = req.header(header::ACCEPT_LANGUAGE, value);
+    }
+    let resp = app.oneshot(req.body(Body::empty()).unwrap()).await.unwrap();
+    let headers = resp.headers().clone();
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    (headers, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[tokio::test]
+async fn pages_render_in_the_negotiated_locale() {
+    let (headers, html) = login(Some("es-ES,es;q=0.9,en;q=0.8")).await;
+    assert!(html.contains(r#"<html lang="es">"#));
+    assert!(html.contains("Acceso del staff"));
+    assert!(html.contains("[ajustes]"));
+    assert_eq!(headers[header::VARY], "Accept-Language");
+
+    let (_, html) = login(None).await;
+    assert!(html.contains(r#"<html lang="en">"#));
+    assert!(html.contains("Staff Login"));
+}
+
+fn message_ids(source: &str) -> BTreeSet<&str> {
+    source
+        .lines()
+        .filter(|line| line.starts_with(|c: char| c.is_ascii_lowercase()))
+        .filter_map(|line| line.split_once(" =").map(|(id, _)| id))
+        .collect()
+}
+
+#[test]
+fn every_message_is_translated() {
+    let en = include_str!("../../api-adapters/locales/en/main.ftl");
+    let es = include_str!("../../api-adapters/locales/es/main.ftl");
+    let missing: Vec<_> = message_ids(en).difference(&message_ids(es)).copied().collect();
+    assert!(missing.is_empty(), "missing from es/main.ftl: {missing:?}");
+}
@@ -0,0 +1,206 @@
+//! Integration tests for API keys.
+//!
+//! Tests verify:
+//! - Admins issue, list and revoke keys; the key is returned only once
+//! - Requests with a key carry its `ApiClient`; requests without one pass through
+//! - Unknown or revoked keys get `401`, a missing scope `403`, a used-up rate
+//!   limit `429`
+
+use std::sync::{Arc, Mutex};
+
+use api_adapters::axum::middleware::api_key::api_key_middleware;
+use api_adapters::axum::routes::api_key_routes::api_key_routes;
+use async_trait::async_trait;
+use axum::{
+    body::Body,
+    extract::Extension,
+    http::{header, Request, StatusCode},
+    middleware,
+    routing::get,
+    Router,
+};
+use chrono::{DateTime, Utc};
+use domains::errors::DomainError;
+use domains::models::*;
+use domains::ports::ApiKeyRepository;
+use services::api_key::ApiKeyService;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+/// Keys kept in memory. Clones share the rows.
+#[derive(Clone, Default)]
+struct MemKeys {
+    rows: Arc<Mutex<Vec<ApiKey>>>,
+}
+
+#[async_trait]
+impl ApiKeyRepository for MemKeys {
+    async fn save(&self, key: &ApiKey) -> Result<(), DomainError> {
+        let mut rows = self.rows.lock().unwrap();
+        rows.retain(|k| k.id != key.id);
+        rows.push(key.clone());
+        Ok(())
+    }
+    async fn find_by_hash(&self, key_hash: &str) -> Result<Option<ApiKey>, DomainError> {
+        Ok(self.rows.lock().unwrap().iter().find(|k| k.key_hash == key_hash).cloned())
+    }
+    async fn list(&self) -> Result<Vec<ApiKey>, DomainError> {
+        Ok(self.rows.lock().unwrap().clone())
+    }
+    async fn revoke(&self, id: ApiKeyId, at: DateTime<Utc>) -> Result<ApiKey, DomainError> {
+        let mut rows = self.rows.lock().unwrap();
+        let key = rows
+            .iter_mut()
+            .find(|k| k.id == id)
+            .ok_or_else(|| DomainError::not_found(id.to_string()))?;
+        key.revoked_at.get_or_insert(at);
+        Ok(key.clone())
+    }
+    async fn touch(&self, id: ApiKeyId, at: DateTime<Utc>) -> Result<(), DomainError> {
+        if let Some(key) = self.rows.lock().unwrap().iter_mut().find(|k| k.id == id) {
+            key.last_used_at = Some(at);
+        }
+        Ok(())
+    }
+}
+
+/// The key routes, and `/probe` answering with the caller's key name, behind
+/// the key middleware as in the composition root.
+fn app(keys: MemKeys) -> Router {
+    let svc = Arc::new(ApiKeyService::new(keys));
+    let probe = |client: Option<Extension<ApiClient>>| async move {
+        client.map_or_else(|| "anonymous".to_owned(), |c| c.0.name.clone())
+    };
+    api_key_routes(svc.clone())
+        .route("/probe", get(probe).post(probe))
+        .layer(middleware::from_fn_with_state(svc, api_key_middleware::<MemKeys>))
+}
+
+fn user(role: Role) -> CurrentUser {
+    CurrentUser::from_claims(Claims {
+        user_id:          UserId(Uuid::new_v4()),
+        username:         "staff".into(),
+        role,
+        owned_boards:     vec![],
+        volunteer_boards: vec![],
+        exp:              (Utc::now() + chrono::Duration::hours(24)).timestamp(),
+    })
+}
+
+fn admin_json(method: &str, uri: &str, body: serde_json::Value) -> Request<Body> {
+    let mut req = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    req.extensions_mut().insert(user(Role::Admin));
+    req
+}
+
+fn with_key(method: &str, key: &str) -> Request<Body> {
+    Request::builder()
+        .method(method)
+        .uri("/probe")
+        .header(header::AUTHORIZATION, format!("Bearer {key}"))
+        .body(Body::empty())
+        .unwrap()
+}
+
+async fn body_text(resp: axum::response::Response) -> String {
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+/// Issue a key through the admin API; returns its id and the key.
+async fn issue(app: &Router, scopes: &[&str], rate_limit_per_min: u32) -> (String, String) {
+    let resp = app
+        .clone()
+        .oneshot(admin_json("POST", "/admin/api-keys", serde_json::json!({
+            "name": "archive-bot", "scopes": scopes, "rate_limit_per_min": rate_limit_per_min,
+        })))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let issued: serde_json::Value = serde_json::from_str(&body_text(resp).await).unwrap();
+    (issued["id"].as_str().unwrap().to_owned(), issued["key"].as_str().unwrap().to_owned())
+}
+
+#[tokio::test]
+async fn admins_issue_list_and_revoke_keys() {
+    let keys = MemKeys::default();
+    let app = app(keys.clone());
+    let (id, key) = issue(&app, &["read"], 60).await;
+    assert!(key.starts_with("rbk_"));
+    assert!(!keys.rows.lock().unwrap()[0].key_hash.contains(&key));
+
+    let resp = app.clone().oneshot(admin_json("GET", "/admin/api-keys", serde_json::Value::Null)).await.unwrap();
+    let listed = body_text(resp).await;
+    assert!(listed.contains("archive-bot"));
+    assert!(!listed.contains(&key), "the key is shown only when issued");
+
+    let resp = app
+        .clone()
+        .oneshot(admin_json("POST", &format!("/admin/api-keys/{id}/revoke"), serde_json::Value::Null))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let resp = app.oneshot(with_key("GET", &key)).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn only_admins_issue_keys() {
+    let app = app(MemKeys::default());
+    let mut req = admin_json("POST", "/admin/api-keys", serde_json::json!({
+        "name": "bot", "scopes": ["post"], "rate_limit_per_min": 60,
+    }));
+    req.extensions_mut().insert(user(Role::Janitor));
+    let resp = app.oneshot(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn keys_identify_their_caller_within_their_scopes() {
+    let app = app(MemKeys::default());
+    let (_, key) = issue(&app, &["read"], 60).await;
+
+    let resp = app.clone().oneshot(with_key("GET", &key)).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(body_text(resp).await, "archive-bot");
+
+    let resp = app.clone().oneshot(with_key("POST", &key)).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    let resp = app.clone().oneshot(with_key("GET", "rbk_not-a-key")).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    // No key, or a staff JWT in the same header: not the middleware's business.
+    let resp = app.clone().oneshot(Request::get("/probe").body(Body::empty()).unwrap()).await.unwrap();
+    assert_eq!(body_text(resp).await, "anonymous");
+    let resp = app.oneshot(with_key("GET", "eyJhbGciOiJIUzI1NiJ9.e30.sig")).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn keys_are_held_to_their_rate_limit() {
+    let app = app(MemKeys::default());
+    let (_, key) = issue(&app, &["read", "post"], 2).await;
+    let (_, other) = issue(&app, &["read"], 2).await;
+
+    let minute = || Utc::now().timestamp() / 60;
+    let started = minute();
+    for _ in 0..2 {
+        let resp = app.clone().oneshot(with_key("POST", &key)).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+    let resp = app.clone().oneshot(with_key("GET", &key)).await.unwrap();
+    // Crossing into the next minute would have reset the count.
+    if minute() == started {
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(resp.headers().contains_key(header::RETRY_AFTER));
+    }
+    // Each key has a limit of its own.
+    let resp = app.oneshot(with_key("GET", &other)).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+}
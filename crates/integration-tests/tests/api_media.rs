@@ -0,0 +1,155 @@
+//! Integration tests for `GET /media/{*key}`.
+//!
+//! Tests verify:
+//! - Stored files carry immutable caching headers, an ETag, and a MIME type
+//! - A matching `If-None-Match` returns 304
+//! - Single byte ranges return 206; unsatisfiable ones 416
+//! - Directories, dot segments, and missing keys are 404
+//! - Missing thumbnails get the pending placeholder
+//! - With signed URLs, only a current signature for the key is served
+
+use api_adapters::axum::routes::media_routes::media_routes;
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+    response::Response,
+    Router,
+};
+use domains::models::MediaKey;
+use domains::ports::MockPostRepository;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use storage_adapters::media::cdn::MediaUrls;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+/// A fresh media root holding `abc.png` (bytes `0..=15`) and an `inner/` directory.
+fn media_root() -> PathBuf {
+    let root = std::env::temp_dir().join(format!("rusty-board-media-{}", Uuid::new_v4()));
+    std::fs::create_dir_all(root.join("inner")).unwrap();
+    std::fs::write(root.join("abc.png"), (0u8..16).collect::<Vec<_>>()).unwrap();
+    root
+}
+
+/// Attachment records that know no stored content types.
+fn no_attachments() -> Arc<MockPostRepository> {
+    let mut posts = MockPostRepository::new();
+    posts.expect_find_media_mime().returning(|_| Ok(None));
+    Arc::new(posts)
+}
+
+fn app(root: PathBuf) -> Router {
+    media_routes(root, MediaUrls::default(), no_attachments())
+}
+
+async fn get(app: Router, uri: &str, headers: &[(header::HeaderName, &str)]) -> Response {
+    let mut req = Request::get(uri);
+    for (name, value) in headers {
+        req = req.header(name, *value);
+    }
+    app.oneshot(req.body(Body::empty()).unwrap()).await.unwrap()
+}
+
+async fn body(resp: Response) -> Vec<u8> {
+    axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap().to_vec()
+}
+
+#[tokio::test]
+async fn serves_file_with_caching_headers() {
+    let resp = get(app(media_root()), "/media/abc.png", &[]).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let h = resp.headers();
+    assert_eq!(h[header::CONTENT_TYPE], "image/png");
+    assert_eq!(h[header::CACHE_CONTROL], "public, max-age=31536000, immutable");
+    assert_eq!(h[header::ACCEPT_RANGES], "bytes");
+    assert_eq!(h[header::CONTENT_LENGTH], "16");
+    assert!(h.contains_key(header::ETAG));
+    assert_eq!(body(resp).await, (0u8..16).collect::<Vec<_>>());
+}
+
+#[tokio::test]
+async fn content_type_is_the_stored_one_not_the_extension_guess() {
+    let root = media_root();
+    std::fs::write(root.join("clip.png"), b"not really a png").unwrap();
+    let mut posts = MockPostRepository::new();
+    posts.expect_find_media_mime()
+        .withf(|key| key.0 == "clip.png")
+        .returning(|_| Ok(Some("video/webm".into())));
+
+    let resp = get(media_routes(root, MediaUrls::default(), Arc::new(posts)), "/media/clip.png", &[]).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()[header::CONTENT_TYPE], "video/webm");
+}
+
+#[tokio::test]
+async fn matching_etag_is_not_modified() {
+    let root = media_root();
+    let first = get(app(root.clone()), "/media/abc.png", &[]).await;
+    let etag = first.headers()[header::ETAG].to_str().unwrap().to_owned();
+
+    let resp = get(app(root), "/media/abc.png", &[(header::IF_NONE_MATCH, &etag)]).await;
+    assert_eq!(resp.status(), StatusCode::NOT_MODIFIED);
+    assert!(body(resp).await.is_empty());
+}
+
+#[tokio::test]
+async fn byte_range_is_partial_content() {
+    let root = media_root();
+    let resp = get(app(root.clone()), "/media/abc.png", &[(header::RANGE, "bytes=2-5")]).await;
+    assert_eq!(resp.status(), StatusCode::PARTIAL_CONTENT);
+    assert_eq!(resp.headers()[header::CONTENT_RANGE], "bytes 2-5/16");
+    assert_eq!(body(resp).await, vec![2, 3, 4, 5]);
+
+    let resp = get(app(root), "/media/abc.png", &[(header::RANGE, "bytes=-3")]).await;
+    assert_eq!(resp.status(), StatusCode::PARTIAL_CONTENT);
+    assert_eq!(body(resp).await, vec![13, 14, 15]);
+}
+
+#[tokio::test]
+async fn range_past_the_end_is_not_satisfiable() {
+    let resp = get(app(media_root()), "/media/abc.png", &[(header::RANGE, "bytes=100-")]).await;
+    assert_eq!(resp.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+    assert_eq!(resp.headers()[header::CONTENT_RANGE], "bytes */16");
+}
+
+#[tokio::test]
+async fn directories_and_dot_segments_are_not_found() {
+    let root = media_root();
+    for uri in ["/media/inner", "/media/inner/", "/media/..%2Fetc%2Fpasswd", "/media/.hidden"] {
+        let resp = get(app(root.clone()), uri, &[]).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND, "{uri}");
+    }
+}
+
+#[tokio::test]
+async fn missing_thumbnail_is_a_placeholder() {
+    let root = media_root();
+    let resp = get(app(root.clone()), "/media/gone_thumb.png", &[]).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()[header::CONTENT_TYPE], "image/svg+xml");
+    assert_eq!(resp.headers()[header::CACHE_CONTROL], "no-store");
+
+    let resp = get(app(root), "/media/gone.png", &[]).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn signed_urls_require_a_valid_signature() {
+    let root = media_root();
+    let urls = MediaUrls::default().with_signing(b"secret", Duration::from_secs(60));
+    let app = || media_routes(root.clone(), urls.clone(), no_attachments());
+
+    let signed = urls.url(&MediaKey::new("abc.png"));
+    assert_eq!(get(app(), &signed, &[]).await.status(), StatusCode::OK);
+
+    let (_, query) = signed.split_once('?').unwrap();
+    for uri in [
+        "/media/abc.png".to_owned(),
+        format!("/media/gone_thumb.png?{query}"),
+        signed.replace("sig=", "sig=00"),
+        "/media/abc.png?expires=1&sig=00".to_owned(),
+    ] {
+        assert_eq!(get(app(), &uri, &[]).await.status(), StatusCode::FORBIDDEN, "{uri}");
+    }
+}
@@ -1,7 +1,10 @@
 //! Integration tests for moderation HTTP endpoints.
 //!
-//! Covers: flag queue, flag resolution, post/thread delete,
-//!         sticky/close toggles, ban creation, ban expiry, and `POST .../flag`.
+//! Covers: flag queue, flag resolution, post/thread delete (and the cached
+//!         board pages it invalidates), post edits and their history,
+//!         sticky/close toggles, ban and shadowban creation, warnings, ban expiry, ASN
+//!         bans, upload hash bans, the IP history and its delete-all, the board-wide
+//!         delete by IP, post moves, the public mod log, and `POST .../flag`.
 //!
 //! All tests use stub implementations of the six port traits required by
 //! `ModerationService`. No real database or Redis is used.
@@ -30,6 +33,38 @@ impl BanRepository for NopBan {
     async fn find_all(&self, page: Page) -> Result<Paginated<Ban>, DomainError> {
         Ok(Paginated::new(vec![], 0, page, 15))
     }
+    async fn delete_expired(&self, _: u32) -> Result<u32, DomainError> { Ok(0) }
+}
+
+struct NopAsnBan;
+#[async_trait::async_trait]
+impl AsnBanRepository for NopAsnBan {
+    async fn find_active_by_asn(&self, _: u32) -> Result<Option<AsnBan>, DomainError> { Ok(None) }
+    async fn save(&self, ban: &AsnBan) -> Result<BanId, DomainError> { Ok(ban.id) }
+    async fn expire(&self, _: BanId) -> Result<(), DomainError> { Ok(()) }
+    async fn find_all(&self, page: Page) -> Result<Paginated<AsnBan>, DomainError> {
+        Ok(Paginated::new(vec![], 0, page, 15))
+    }
+}
+
+struct NopWarning;
+#[async_trait::async_trait]
+impl WarningRepository for NopWarning {
+    async fn find_pending_by_ip(&self, _: &IpHash) -> Result<Option<Warning>, DomainError> { Ok(None) }
+    async fn save(&self, warning: &Warning) -> Result<BanId, DomainError> { Ok(warning.id) }
+    async fn acknowledge(&self, _: BanId, _: &IpHash) -> Result<(), DomainError> { Ok(()) }
+}
+
+struct NopHashBan;
+#[async_trait::async_trait]
+impl HashBanRepository for NopHashBan {
+    async fn find_active_by_md5(&self, _: &str) -> Result<Option<HashBan>, DomainError> { Ok(None) }
+    async fn find_active_perceptual(&self) -> Result<Vec<HashBan>, DomainError> { Ok(vec![]) }
+    async fn save(&self, ban: &HashBan) -> Result<BanId, DomainError> { Ok(ban.id) }
+    async fn expire(&self, _: BanId) -> Result<(), DomainError> { Ok(()) }
+    async fn find_all(&self, page: Page) -> Result<Paginated<HashBan>, DomainError> {
+        Ok(Paginated::new(vec![], 0, page, 15))
+    }
 }
 
 struct NopPost;
@@ -44,7 +79,7 @@ impl PostRepository for NopPost {
             name:        None,
             email:       None,
             tripcode:    None,
-            post_number: 1, pinned: false,
+            post_number: 1, pinned: false, tags: vec![], commands: vec![], subject: None, capcode: None, deleted: None, edited_at: None,
             created_at:  Utc::now(),
         })
     }
@@ -52,17 +87,50 @@ impl PostRepository for NopPost {
         Ok(Paginated::new(vec![], 0, p, 15))
     }
     async fn find_by_ip_hash(&self, _: &IpHash) -> Result<Vec<Post>, DomainError> { Ok(vec![]) }
+    /// One post on /b/, from the first of the hashes looked up.
+    async fn find_by_ip_hashes(&self, _: SiteId, hashes: &[IpHash], p: Page) -> Result<Paginated<OverboardPost>, DomainError> {
+        let post = OverboardPost {
+            id:          PostId(Uuid::new_v4()),
+            thread_id:   ThreadId(Uuid::new_v4()),
+            board_slug:  "b".to_owned(),
+            body:        "stub".to_owned(),
+            name:        None,
+            tripcode:    None,
+            ip_hash:     hashes[0].clone(),
+            created_at:  Utc::now(),
+            post_number: 1, subject: None, capcode: None,
+        };
+        Ok(Paginated::new(vec![post], 1, p, 15))
+    }
     async fn find_recent_hashes(&self, _: BoardId, _: u32) -> Result<Vec<ContentHash>, DomainError> {
         Ok(vec![])
     }
     async fn save(&self, post: &Post) -> Result<(PostId, u64), DomainError> { Ok((post.id, 1)) }
-    async fn delete(&self, _: PostId) -> Result<(), DomainError> { Ok(()) }
+    async fn delete(&self, _: PostId, _: domains::models::DeletedBy) -> Result<(), DomainError> { Ok(()) }
     async fn delete_by_ip_in_thread(&self, _: &IpHash, _: ThreadId) -> Result<u64, DomainError> { Ok(0) }
+    async fn delete_by_ip_on_board(&self, _: &IpHash, _: BoardId, _: chrono::DateTime<chrono::Utc>) -> Result<domains::models::PurgedPosts, DomainError> {
+        Ok(domains::models::PurgedPosts { posts: vec![(ThreadId::new(), PostId::new())], ..Default::default() })
+    }
+    async fn purge_deleted(&self, _: u32) -> Result<u32, DomainError> { Ok(0) }
+    async fn edit(&self, _: PostId, _: &str, _: Option<String>, _: UserId) -> Result<(), DomainError> { Ok(()) }
+    /// One earlier version of every post.
+    async fn find_revisions(&self, id: PostId) -> Result<Vec<PostRevision>, DomainError> {
+        Ok(vec![PostRevision {
+            id:        Uuid::new_v4(),
+            post_id:   id,
+            body:      "call me at 555-0100".to_owned(),
+            subject:   None,
+            edited_by: UserId(Uuid::new_v4()),
+            edited_at: Utc::now(),
+        }])
+    }
+    async fn move_posts(&self, _: &domains::models::PostMove) -> Result<(), DomainError> { Ok(()) }
     async fn save_attachments(&self, _: &[domains::models::Attachment]) -> Result<(), DomainError> { Ok(()) }
     async fn find_attachments_by_post_ids(&self, _: &[PostId]) -> Result<std::collections::HashMap<PostId, Vec<domains::models::Attachment>>, DomainError> { Ok(std::collections::HashMap::new()) }
-    async fn find_overboard(&self, p: Page) -> Result<Paginated<OverboardPost>, DomainError> {
+    async fn find_overboard(&self, _site: domains::models::SiteId, p: Page) -> Result<Paginated<OverboardPost>, DomainError> {
         Ok(Paginated::new(vec![], 0, p, 15))
     }
+    async fn find_site_activity(&self, _: domains::models::SiteId, _: u32) -> Result<domains::models::SiteActivity, domains::errors::DomainError> { Ok(domains::models::SiteActivity::default()) }
     async fn search_fulltext(
         &self,
         _board_id: BoardId,
@@ -72,11 +140,24 @@ impl PostRepository for NopPost {
         Ok(Paginated::new(vec![], 0, p, 15))
     }
     async fn find_all_by_thread(&self, _: ThreadId) -> Result<Vec<Post>, DomainError> { Ok(vec![]) }
+    async fn find_visible_by_thread(&self, _: ThreadId, _: &domains::models::Viewer) -> Result<Vec<Post>, DomainError> { Ok(vec![]) }
     async fn find_thread_id_by_post_number(&self, _: BoardId, _: u64) -> Result<Option<ThreadId>, DomainError> { Ok(None) }
     async fn set_pinned(&self, _: domains::models::PostId, _: bool) -> Result<(), domains::errors::DomainError> { Ok(()) }
-    async fn find_oldest_unpinned_reply(&self, _: domains::models::ThreadId) -> Result<Option<domains::models::PostId>, domains::errors::DomainError> { Ok(None) }
+    async fn find_cycle_excess(&self, _: domains::models::ThreadId, _: u32) -> Result<Vec<domains::models::PostId>, domains::errors::DomainError> { Ok(vec![]) }
     async fn find_attachment_by_hash(&self, _: &domains::models::ContentHash) -> Result<Option<domains::models::Attachment>, domains::errors::DomainError> { Ok(None) }
     async fn delete_by_id(&self, _: domains::models::PostId) -> Result<(), domains::errors::DomainError> { Ok(()) }
+    async fn find_referenced_media_keys(&self) -> Result<std::collections::HashSet<domains::models::MediaKey>, domains::errors::DomainError> { Ok(Default::default()) }
+    async fn find_thumbnailed_attachments(&self) -> Result<Vec<(BoardId, domains::models::Attachment)>, domains::errors::DomainError> { Ok(vec![]) }
+    async fn find_all_attachments(&self) -> Result<Vec<(BoardId, domains::models::Attachment)>, domains::errors::DomainError> { Ok(vec![]) }
+    async fn find_visible_by_id(&self, _: domains::models::PostId) -> Result<Option<domains::models::Post>, domains::errors::DomainError> { Ok(None) }
+    async fn find_by_ids(&self, _: domains::models::ThreadId, _: &[domains::models::PostId]) -> Result<Vec<domains::models::Post>, domains::errors::DomainError> { Ok(vec![]) }
+    async fn find_by_numbers(&self, _: domains::models::ThreadId, _: &[u64]) -> Result<Vec<domains::models::Post>, domains::errors::DomainError> { Ok(vec![]) }
+    async fn find_quoting(&self, _: domains::models::ThreadId, _: &[u64]) -> Result<Vec<domains::models::Post>, domains::errors::DomainError> { Ok(vec![]) }
+    async fn find_media_mime(&self, _: &domains::models::MediaKey) -> Result<Option<String>, domains::errors::DomainError> { Ok(None) }
+    async fn set_thumbnail_key(&self, _: &domains::models::MediaKey, _: &domains::models::MediaKey) -> Result<u64, domains::errors::DomainError> { Ok(0) }
+    async fn restore(&self, _: &domains::models::Post) -> Result<(), domains::errors::DomainError> { Ok(()) }
+    async fn find_number_range(&self, _: domains::models::BoardId) -> Result<Option<domains::models::PostNumberRange>, domains::errors::DomainError> { Ok(None) }
+    async fn find_by_number_range(&self, _: domains::models::BoardId, _: u64, _: u64, _: u32) -> Result<Vec<domains::models::Post>, domains::errors::DomainError> { Ok(vec![]) }
 }
 
 struct NopThread;
@@ -97,7 +178,9 @@ impl ThreadRepository for NopThread {
     async fn find_by_board(&self, _: BoardId, p: Page) -> Result<Paginated<Thread>, DomainError> {
         Ok(Paginated::new(vec![], 0, p, 15))
     }
-    async fn find_catalog(&self, _: BoardId) -> Result<Vec<ThreadSummary>, DomainError> { Ok(vec![]) }
+    async fn find_catalog(&self, _: BoardId, _: CatalogSort) -> Result<Vec<ThreadSummary>, DomainError> { Ok(vec![]) }
+    async fn find_summaries(&self, _: BoardId, _: &[ThreadId]) -> Result<Vec<ThreadSummary>, DomainError> { Ok(vec![]) }
+    async fn find_overboard_threads(&self, _: domains::models::SiteId, p: Page) -> Result<Paginated<domains::models::OverboardThread>, DomainError> { Ok(Paginated::new(vec![], 0, p, 50)) }
     async fn save(&self, t: &Thread) -> Result<ThreadId, DomainError> { Ok(t.id) }
     async fn bump(&self, _: ThreadId, _: chrono::DateTime<Utc>) -> Result<(), DomainError> { Ok(()) }
     async fn set_op_post(&self, _: ThreadId, _: PostId) -> Result<(), DomainError> { Ok(()) }
@@ -144,6 +227,7 @@ impl FlagRepository for NopFlag {
     {
         Ok(())
     }
+    async fn count_pending_for_post(&self, _: PostId) -> Result<u64, DomainError> { Ok(1) }
 }
 
 struct NopAudit;
@@ -163,6 +247,9 @@ impl AuditRepository for NopAudit {
     async fn find_by_board(&self, _: BoardId, p: Page) -> Result<Paginated<AuditEntry>, DomainError> {
         Ok(Paginated::new(vec![], 0, p, 15))
     }
+    async fn find_public_by_board(&self, _: BoardId, p: Page) -> Result<Paginated<AuditEntry>, DomainError> {
+        Ok(Paginated::new(vec![], 0, p, 15))
+    }
 }
 
 struct NopUser;
@@ -200,18 +287,28 @@ struct NopBoardRepo;
 
 #[async_trait::async_trait]
 impl BoardRepo for NopBoardRepo {
-    async fn create_board(&self, _: &str, _: &str, _: &str) -> Result<domains::models::Board, BoardError> {
+    async fn create_board(&self, _site: domains::models::SiteId, _: &str, _: &str, _: &str) -> Result<domains::models::Board, BoardError> {
         unimplemented!()
     }
-    async fn get_by_slug(&self, _: &str) -> Result<domains::models::Board, BoardError> { unimplemented!() }
-    async fn get_by_id(&self, _: BoardId) -> Result<domains::models::Board, BoardError> { unimplemented!() }
+    async fn get_by_slug(&self, _site: domains::models::SiteId, _: &str) -> Result<domains::models::Board, BoardError> { unimplemented!() }
+    async fn get_by_id(&self, id: BoardId) -> Result<domains::models::Board, BoardError> {
+        Ok(domains::models::Board {
+            id,
+            site_id:    domains::models::SiteId::DEFAULT,
+            slug:       domains::models::Slug::new("b").unwrap(),
+            title:      "Random".to_owned(),
+            rules:      String::new(),
+            created_at: Utc::now(),
+        })
+    }
     async fn update_board(&self, _: BoardId, _: Option<&str>, _: Option<&str>) -> Result<domains::models::Board, BoardError> { unimplemented!() }
     async fn delete_board(&self, _: BoardId) -> Result<(), BoardError> { unimplemented!() }
-    async fn list_boards(&self, p: Page) -> Result<Paginated<domains::models::Board>, BoardError> {
+    async fn list_boards(&self, _site: domains::models::SiteId, p: Page) -> Result<Paginated<domains::models::Board>, BoardError> {
         Ok(Paginated::new(vec![], 0, p, 15))
     }
     async fn get_config(&self, _: BoardId) -> Result<BoardConfig, BoardError> { unimplemented!() }
-    async fn update_config(&self, _: BoardId, c: BoardConfig) -> Result<BoardConfig, BoardError> { Ok(c) }
+    async fn update_config(&self, _: BoardId, c: BoardConfig, _: domains::models::UserId) -> Result<BoardConfig, BoardError> { Ok(c) }
+    async fn media_usage(&self, _: BoardId) -> Result<u64, BoardError> { Ok(0) }
     async fn list_volunteers(&self, _: BoardId) -> Result<Vec<(UserId, String, chrono::DateTime<chrono::Utc>)>, BoardError> { Ok(vec![]) }
     async fn add_volunteer_by_username(&self, _: BoardId, _: &str, _: UserId) -> Result<(), BoardError> { Ok(()) }
     async fn remove_volunteer(&self, _: BoardId, _: UserId) -> Result<(), BoardError> { Ok(()) }
@@ -315,6 +412,46 @@ async fn delete_post_returns_204() {
     assert_eq!(resp.status(), StatusCode::NO_CONTENT);
 }
 
+#[tokio::test]
+async fn edit_post_returns_204_and_refuses_reply_subjects() {
+    let uri = format!("/mod/posts/{}/edit", Uuid::new_v4());
+    let resp = mod_app()
+        .oneshot(with_mod_user(json_post(&uri, r#"{"body":"call me at [redacted]"}"#)))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+    // The stub post is a reply: its thread opens with another post.
+    let resp = mod_app()
+        .oneshot(with_mod_user(json_post(&uri, r#"{"body":"stub","subject":"Hi"}"#)))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn post_revisions_list_the_replaced_text() {
+    let post_id = Uuid::new_v4();
+    let uri = format!("/mod/posts/{post_id}/revisions");
+    let resp = mod_app().oneshot(with_mod_user(get(&uri))).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let bytes = axum::body::to_bytes(resp.into_body(), 1 << 20).await.unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(json[0]["body"], "call me at 555-0100");
+    assert_eq!(json[0]["post_id"], post_id.to_string());
+
+    let resp = mod_app().oneshot(with_role_user(html_get(&uri), Role::Janitor)).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let bytes = axum::body::to_bytes(resp.into_body(), 1 << 20).await.unwrap();
+    let html = String::from_utf8(bytes.to_vec()).unwrap();
+    assert!(html.contains(r#"<pre class="revision-body">call me at 555-0100</pre>"#));
+    assert!(html.contains(r#"<pre class="revision-body">stub</pre>"#));
+    assert!(html.contains("/board/b/thread/"));
+
+    let resp = mod_app().oneshot(get(&uri)).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+}
+
 #[tokio::test]
 async fn delete_thread_returns_204() {
     let thread_id = Uuid::new_v4();
@@ -325,6 +462,29 @@ async fn delete_thread_returns_204() {
     assert_eq!(resp.status(), StatusCode::NO_CONTENT);
 }
 
+#[tokio::test]
+async fn deletions_invalidate_cached_board_pages() {
+    use api_adapters::axum::middleware::render_cache::RenderCacheState;
+    use storage_adapters::cache::PageCache;
+
+    let mut store = MockCacheStore::new();
+    store
+        .expect_invalidate()
+        .withf(|key| key.starts_with("render-gen:"))
+        .times(2)
+        .returning(|_| Ok(()));
+    let app = mod_app().layer(axum::Extension(RenderCacheState {
+        pages: Arc::new(PageCache::new(std::time::Duration::from_secs(60))),
+        store: Arc::new(store),
+        ttl:   std::time::Duration::from_secs(60),
+    }));
+
+    for uri in [format!("/mod/posts/{}/delete", Uuid::new_v4()), format!("/mod/threads/{}/delete", Uuid::new_v4())] {
+        let resp = app.clone().oneshot(with_mod_user(plain_post(&uri))).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+    }
+}
+
 #[tokio::test]
 async fn toggle_sticky_returns_204() {
     let thread_id = Uuid::new_v4();
@@ -351,6 +511,113 @@ async fn toggle_close_returns_204() {
     assert_eq!(resp.status(), StatusCode::NO_CONTENT);
 }
 
+// ─── IP history ───────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn ip_history_looks_up_raw_ips_by_todays_hash() {
+    let resp = mod_app()
+        .oneshot(with_mod_user(get("/mod/ip-history?ip=203.0.113.7")))
+        .await
+        .unwrap();
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    let bytes = axum::body::to_bytes(resp.into_body(), 1 << 20).await.unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    let today = Utc::now().format("%Y-%m-%d").to_string();
+    assert_eq!(json["items"][0]["ip_hash"], services::common::utils::hash_ip("203.0.113.7", &today).0);
+    assert_eq!(json["items"][0]["board_slug"], "b");
+}
+
+#[tokio::test]
+async fn ip_history_page_lists_posts_with_actions() {
+    let hash = "b".repeat(64);
+    let resp = mod_app()
+        .oneshot(with_role_user(html_get(&format!("/mod/ip-history?ip={hash}")), Role::Janitor))
+        .await
+        .unwrap();
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    let bytes = axum::body::to_bytes(resp.into_body(), 1 << 20).await.unwrap();
+    let html = String::from_utf8(bytes.to_vec()).unwrap();
+    assert!(html.contains(&format!(r#"<code title="{hash}">bbbbbbbbbb</code>"#)));
+    assert!(html.contains(r#"data-action="ban""#));
+    assert!(html.contains(r#"id="ip-delete-all""#));
+}
+
+#[tokio::test]
+async fn ip_history_rejects_malformed_lookups() {
+    let resp = mod_app()
+        .oneshot(with_mod_user(get("/mod/ip-history?ip=not-an-ip")))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let resp = mod_app()
+        .oneshot(with_mod_user(json_post("/mod/ip-history/delete-all", r#"{"ip":"abc"}"#)))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn delete_ip_history_returns_count() {
+    let resp = mod_app()
+        .oneshot(with_mod_user(json_post("/mod/ip-history/delete-all", r#"{"ip":"203.0.113.7"}"#)))
+        .await
+        .unwrap();
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    let bytes = axum::body::to_bytes(resp.into_body(), 1 << 20).await.unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(json["deleted"], 0, "stub deletes nothing");
+}
+
+#[tokio::test]
+async fn delete_board_posts_by_ip_returns_count() {
+    let uri = format!("/mod/boards/{}/delete-by-ip", Uuid::new_v4());
+    let resp = mod_app()
+        .oneshot(with_mod_user(json_post(&uri, r#"{"ip_hash":"abc","hours":24}"#)))
+        .await
+        .unwrap();
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    let bytes = axum::body::to_bytes(resp.into_body(), 1 << 20).await.unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(json["deleted"], 1);
+}
+
+#[tokio::test]
+async fn delete_board_posts_by_ip_rejects_bad_windows() {
+    let uri = format!("/mod/boards/{}/delete-by-ip", Uuid::new_v4());
+    let resp = mod_app()
+        .oneshot(with_mod_user(json_post(&uri, r#"{"ip_hash":"abc","hours":0}"#)))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn move_posts_rejects_posts_from_elsewhere() {
+    // NopPost holds no posts in any thread, so nothing selected can move.
+    let uri = format!("/mod/threads/{}/move", Uuid::new_v4());
+    let body = format!(r#"{{"post_ids":["{}"],"thread_id":null}}"#, Uuid::new_v4());
+    let resp = mod_app()
+        .oneshot(with_mod_user(json_post(&uri, &body)))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn move_posts_requires_a_moderator() {
+    let uri = format!("/mod/threads/{}/move", Uuid::new_v4());
+    let resp = mod_app()
+        .oneshot(json_post(&uri, r#"{"post_ids":[]}"#))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+}
+
 // ─── Bans ─────────────────────────────────────────────────────────────────────
 
 #[tokio::test]
@@ -379,6 +646,46 @@ async fn create_ban_returns_201() {
     assert_eq!(resp.status(), StatusCode::CREATED);
 }
 
+#[tokio::test]
+async fn create_shadowban_returns_201() {
+    let resp = mod_app()
+        .oneshot(with_mod_user(json_post(
+            "/mod/bans",
+            r#"{"ip_hash":"aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa","reason":"bot","expires_at":null,"shadow":true}"#,
+        )))
+        .await
+        .unwrap();
+
+    assert_eq!(resp.status(), StatusCode::CREATED);
+}
+
+#[tokio::test]
+async fn create_warning_returns_201() {
+    let svc = Arc::new(
+        ModerationService::new(NopBan, NopPost, NopThread, NopFlag::new(), NopAudit, NopUser)
+            .with_warnings(Arc::new(NopWarning)),
+    );
+    let resp = moderation_routes(svc, Arc::new(NopBoardRepo))
+        .oneshot(with_mod_user(json_post(
+            "/mod/warnings",
+            &format!(r#"{{"ip_hash":"{}","reason":"off topic","post_id":"{}"}}"#, "a".repeat(64), Uuid::new_v4()),
+        )))
+        .await
+        .unwrap();
+
+    assert_eq!(resp.status(), StatusCode::CREATED);
+}
+
+#[tokio::test]
+async fn create_warning_without_repo_returns_501() {
+    let resp = mod_app()
+        .oneshot(with_mod_user(json_post("/mod/warnings", r#"{"ip_hash":"abc","reason":"off topic"}"#)))
+        .await
+        .unwrap();
+
+    assert_eq!(resp.status(), StatusCode::NOT_IMPLEMENTED);
+}
+
 #[tokio::test]
 async fn expire_ban_returns_204() {
     let ban_id = Uuid::new_v4();
@@ -389,6 +696,83 @@ async fn expire_ban_returns_204() {
     assert_eq!(resp.status(), StatusCode::NO_CONTENT);
 }
 
+#[tokio::test]
+async fn create_asn_ban_returns_201() {
+    let svc = Arc::new(
+        ModerationService::new(NopBan, NopPost, NopThread, NopFlag::new(), NopAudit, NopUser)
+            .with_asn_bans(Arc::new(NopAsnBan)),
+    );
+    let resp = moderation_routes(svc, Arc::new(NopBoardRepo))
+        .oneshot(with_mod_user(json_post(
+            "/mod/asn-bans",
+            r#"{"asn":9009,"reason":"vpn abuse","expires_at":null}"#,
+        )))
+        .await
+        .unwrap();
+
+    assert_eq!(resp.status(), StatusCode::CREATED);
+}
+
+#[tokio::test]
+async fn create_asn_ban_without_repo_returns_501() {
+    let resp = mod_app()
+        .oneshot(with_mod_user(json_post(
+            "/mod/asn-bans",
+            r#"{"asn":9009,"reason":"vpn abuse","expires_at":null}"#,
+        )))
+        .await
+        .unwrap();
+
+    assert_eq!(resp.status(), StatusCode::NOT_IMPLEMENTED);
+}
+
+fn hash_ban_app() -> axum::Router {
+    let svc = Arc::new(
+        ModerationService::new(NopBan, NopPost, NopThread, NopFlag::new(), NopAudit, NopUser)
+            .with_hash_bans(Arc::new(NopHashBan)),
+    );
+    moderation_routes(svc, Arc::new(NopBoardRepo))
+}
+
+#[tokio::test]
+async fn create_md5_hash_ban_returns_201() {
+    let resp = hash_ban_app()
+        .oneshot(with_mod_user(json_post(
+            "/mod/hash-bans",
+            r#"{"md5":"D41D8CD98F00B204E9800998ECF8427E","reason":"spam image","expires_at":null}"#,
+        )))
+        .await
+        .unwrap();
+
+    assert_eq!(resp.status(), StatusCode::CREATED);
+}
+
+#[tokio::test]
+async fn create_hash_ban_rejects_malformed_md5() {
+    let resp = hash_ban_app()
+        .oneshot(with_mod_user(json_post(
+            "/mod/hash-bans",
+            r#"{"md5":"not-a-digest","reason":"spam image","expires_at":null}"#,
+        )))
+        .await
+        .unwrap();
+
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn create_hash_ban_without_repo_returns_501() {
+    let resp = mod_app()
+        .oneshot(with_mod_user(json_post(
+            "/mod/hash-bans",
+            r#"{"md5":"d41d8cd98f00b204e9800998ecf8427e","reason":"spam image","expires_at":null}"#,
+        )))
+        .await
+        .unwrap();
+
+    assert_eq!(resp.status(), StatusCode::NOT_IMPLEMENTED);
+}
+
 // ─── Public flag creation ─────────────────────────────────────────────────────
 
 #[tokio::test]
@@ -497,3 +881,111 @@ async fn volunteer_dashboard_returns_401_without_auth() {
         resp.status()
     );
 }
+
+// ─── Public mod log ───────────────────────────────────────────────────────────
+
+/// Audit entries for one board; `find_public_by_board` filters as the
+/// Postgres adapter does.
+struct LogAudit(Vec<AuditEntry>);
+#[async_trait::async_trait]
+impl AuditRepository for LogAudit {
+    async fn record(&self, _: &AuditEntry) -> Result<(), DomainError> { Ok(()) }
+    async fn find_recent(&self, _: u32) -> Result<Vec<AuditEntry>, DomainError> { unimplemented!() }
+    async fn find_by_actor(&self, _: UserId, _: Page) -> Result<Paginated<AuditEntry>, DomainError> { unimplemented!() }
+    async fn find_by_target(&self, _: uuid::Uuid, _: Page) -> Result<Paginated<AuditEntry>, DomainError> { unimplemented!() }
+    async fn find_all(&self, _: Page) -> Result<Paginated<AuditEntry>, DomainError> { unimplemented!() }
+    async fn find_by_board(&self, _: BoardId, _: Page) -> Result<Paginated<AuditEntry>, DomainError> { unimplemented!() }
+    async fn find_public_by_board(&self, board_id: BoardId, p: Page) -> Result<Paginated<AuditEntry>, DomainError> {
+        let items: Vec<AuditEntry> = self
+            .0
+            .iter()
+            .filter(|e| e.board_id == Some(board_id) && AuditAction::PUBLIC.contains(&e.action))
+            .cloned()
+            .collect();
+        let total = items.len() as u64;
+        Ok(Paginated::new(items, total, p, 15))
+    }
+}
+
+fn log_entry(board_id: BoardId, action: AuditAction, reason: Option<&str>) -> AuditEntry {
+    AuditEntry {
+        id:            Uuid::new_v4(),
+        actor_id:      Some(UserId(Uuid::new_v4())),
+        actor_ip_hash: Some(IpHash::new("staffhash")),
+        action,
+        target_id:     Some(Uuid::new_v4()),
+        target_type:   Some("thread".to_owned()),
+        board_id:      Some(board_id),
+        reason:        reason.map(str::to_owned),
+        details:       Some(serde_json::json!({ "ip_hash": "posterhash" })),
+        created_at:    Utc::now(),
+    }
+}
+
+/// `/board/b/log` with the board-config extension the middleware would set.
+fn log_app(public_log: bool, entries: Vec<AuditEntry>) -> (axum::Router, BoardId) {
+    use api_adapters::axum::middleware::board_config::ExtractedBoardConfig;
+    use api_adapters::axum::routes::moderation_routes::board_log_routes;
+    let board = Board {
+        id:         BoardId(Uuid::new_v4()),
+        site_id:    SiteId::DEFAULT,
+        slug:       Slug::new("b").unwrap(),
+        title:      "Random".to_owned(),
+        rules:      String::new(),
+        created_at: Utc::now(),
+    };
+    let board_id = board.id;
+    let entries = entries.into_iter().map(|e| AuditEntry { board_id: Some(board_id), ..e }).collect();
+    let svc = Arc::new(ModerationService::new(
+        NopBan, NopPost, NopThread, NopFlag::new(), LogAudit(entries), NopUser,
+    ));
+    let ctx = ExtractedBoardConfig {
+        board_id,
+        config: BoardConfig { public_log, ..BoardConfig::default() },
+        slug:   board.slug.clone(),
+        board,
+    };
+    (board_log_routes(svc).layer(axum::Extension(ctx)), board_id)
+}
+
+#[tokio::test]
+async fn public_log_is_404_unless_enabled() {
+    let (app, _) = log_app(false, vec![]);
+    let resp = app.oneshot(html_get("/board/b/log")).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn public_log_lists_public_actions_without_actors() {
+    let other = BoardId(Uuid::new_v4());
+    let entries = vec![
+        log_entry(other, AuditAction::DeleteThread, None),
+        log_entry(other, AuditAction::BanIp, Some("spam")),
+        log_entry(other, AuditAction::ShadowbanIp, Some("hidden")),
+        log_entry(other, AuditAction::WarnIp, Some("rule 2")),
+    ];
+    let (app, _) = log_app(true, entries.clone());
+
+    let resp = app.clone().oneshot(get("/board/b/log")).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let bytes = axum::body::to_bytes(resp.into_body(), 1 << 20).await.unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    let items = json["items"].as_array().unwrap();
+    let actions: Vec<&str> = items.iter().map(|e| e["action"].as_str().unwrap()).collect();
+    assert_eq!(actions, ["delete_thread", "ban_ip"]);
+    assert_eq!(items[1]["reason"], "spam");
+    for item in items {
+        assert!(item["actor_id"].is_null());
+        assert!(item["actor_ip_hash"].is_null());
+        assert!(item["details"].is_null());
+    }
+
+    let resp = app.oneshot(html_get("/board/b/log")).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let bytes = axum::body::to_bytes(resp.into_body(), 1 << 20).await.unwrap();
+    let html = String::from_utf8(bytes.to_vec()).unwrap();
+    assert!(html.contains("Thread deleted"));
+    assert!(html.contains("Poster banned"));
+    assert!(!html.contains("posterhash"));
+    assert!(!html.contains(&entries[0].actor_id.unwrap().to_string()));
+}
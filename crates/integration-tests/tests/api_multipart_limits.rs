@@ -0,0 +1,155 @@
+//! Integration tests for the multipart limits.
+//!
+//! Tests verify:
+//! - Posts within `MultipartLimits` are read in full
+//! - Too many fields, a long text field or an oversized body get `413
+//!   PAYLOAD_TOO_LARGE`; an oversized file part gets `422`
+//! - A refused post is not read past the limit it broke
+//! - Without the extension the `Settings` defaults apply
+
+use std::convert::Infallible;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use api_adapters::axum::multipart::{LimitedMultipart, MultipartLimits};
+use api_adapters::common::errors::ApiError;
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+    routing::post,
+    Extension, Json, Router,
+};
+use bytes::Bytes;
+use futures_util::StreamExt;
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+const BOUNDARY: &str = "limitsboundary";
+
+const LIMITS: MultipartLimits = MultipartLimits {
+    max_body_bytes: 64 * 1024,
+    max_file_bytes: 8 * 1024,
+    max_text_bytes: 1024,
+    max_fields:     4,
+};
+
+/// Reads text fields as text and `file` fields as bytes; answers with their sizes.
+async fn read_form(mut form: LimitedMultipart) -> Result<Json<Value>, ApiError> {
+    let mut sizes = serde_json::Map::new();
+    while let Some(field) = form.next_field().await? {
+        let name = field.name().unwrap_or_default().to_owned();
+        let size = if name == "file" { field.bytes(u64::MAX).await?.len() } else { field.text().await?.len() };
+        sizes.insert(name, json!(size));
+    }
+    Ok(Json(Value::Object(sizes)))
+}
+
+fn app(limits: Option<MultipartLimits>) -> Router {
+    let router = Router::new().route("/form", post(read_form));
+    match limits {
+        Some(limits) => router.layer(limits.body_limit()).layer(Extension(limits)),
+        None => router,
+    }
+}
+
+fn part(name: &str, value: &[u8]) -> Vec<u8> {
+    let mut part = format!("--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"{name}\"\r\n\r\n").into_bytes();
+    part.extend_from_slice(value);
+    part.extend_from_slice(b"\r\n");
+    part
+}
+
+fn form(parts: &[(&str, Vec<u8>)]) -> Vec<u8> {
+    let mut body: Vec<u8> = parts.iter().flat_map(|(name, value)| part(name, value)).collect();
+    body.extend_from_slice(format!("--{BOUNDARY}--\r\n").as_bytes());
+    body
+}
+
+/// Sends `body` in 1 KiB chunks and counts the chunks the server pulled.
+///
+/// Chunks after the first arrive after a yield, as from a socket; a stream
+/// that is always ready would be buffered by multer in one go.
+async fn send(app: Router, body: Vec<u8>) -> (StatusCode, Value, usize) {
+    let pulled = Arc::new(AtomicUsize::new(0));
+    let chunks: Vec<Bytes> = body.chunks(1024).map(Bytes::copy_from_slice).collect();
+    let stream = {
+        let pulled = pulled.clone();
+        futures_util::stream::iter(chunks.into_iter().enumerate()).then(move |(i, chunk)| {
+            let pulled = pulled.clone();
+            async move {
+                if i > 0 {
+                    tokio::task::yield_now().await;
+                }
+                pulled.fetch_add(1, Ordering::SeqCst);
+                Ok::<_, Infallible>(chunk)
+            }
+        })
+    };
+    let req = Request::post("/form")
+        .header(header::CONTENT_TYPE, format!("multipart/form-data; boundary={BOUNDARY}"))
+        .body(Body::from_stream(stream))
+        .unwrap();
+    let resp = app.oneshot(req).await.unwrap();
+    let status = resp.status();
+    let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    let json = serde_json::from_slice(&bytes).unwrap_or(Value::Null);
+    (status, json, pulled.load(Ordering::SeqCst))
+}
+
+#[tokio::test]
+async fn posts_within_the_limits_are_read() {
+    let body = form(&[("name", b"anon".to_vec()), ("body", vec![b'x'; 1024]), ("file", vec![0; 8 * 1024])]);
+    let (status, json, _) = send(app(Some(LIMITS)), body).await;
+    assert_eq!(status, StatusCode::OK, "{json}");
+    assert_eq!(json, json!({ "name": 4, "body": 1024, "file": 8192 }));
+}
+
+#[tokio::test]
+async fn too_many_fields_are_refused() {
+    let parts: Vec<_> = (0..5).map(|_| ("name", b"anon".to_vec())).collect();
+    let (status, json, _) = send(app(Some(LIMITS)), form(&parts)).await;
+    assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+    assert_eq!(json["error"]["code"], "PAYLOAD_TOO_LARGE");
+    assert_eq!(json["error"]["message"], "too many form fields (at most 4)");
+}
+
+#[tokio::test]
+async fn long_text_fields_are_refused_early() {
+    let body = form(&[("body", vec![b'x'; 48 * 1024])]);
+    let total = body.len().div_ceil(1024);
+    let (status, json, pulled) = send(app(Some(LIMITS)), body).await;
+    assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+    assert_eq!(json["error"]["message"], "form field `body` is longer than 1 KiB");
+    assert!(pulled < total / 2, "read {pulled} of {total} chunks");
+}
+
+#[tokio::test]
+async fn large_files_are_refused_early() {
+    let body = form(&[("file", vec![0; 48 * 1024])]);
+    let total = body.len().div_ceil(1024);
+    let (status, json, pulled) = send(app(Some(LIMITS)), body).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(json["error"]["message"].as_str().unwrap().contains("up to 8KB"), "{json}");
+    assert!(pulled < total / 2, "read {pulled} of {total} chunks");
+}
+
+#[tokio::test]
+async fn large_bodies_are_refused_early() {
+    let limits = MultipartLimits { max_body_bytes: 12 * 1024, ..LIMITS };
+    let parts: Vec<_> = (0..4).map(|_| ("file", vec![0; 8 * 1024])).collect();
+    let body = form(&parts);
+    let total = body.len().div_ceil(1024);
+    let (status, json, pulled) = send(app(Some(limits)), body).await;
+    assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+    assert_eq!(json["error"]["message"], "request body is larger than 12 KiB");
+    assert!(pulled < total / 2, "read {pulled} of {total} chunks");
+}
+
+#[tokio::test]
+async fn defaults_apply_without_the_extension() {
+    let (status, _, _) = send(app(None), form(&[("body", vec![b'x'; 32 * 1024])])).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _, _) = send(app(None), form(&[("body", vec![b'x'; 65 * 1024])])).await;
+    assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+}
+
@@ -0,0 +1,153 @@
+//! Integration tests for the site notice.
+//!
+//! Tests verify:
+//! - Admins set the notice and switch it on and off; other users cannot
+//! - A shown notice appears, escaped, on HTML pages and in the `meta` member
+//!   of JSON objects, from the next request on
+//! - A switched-off notice keeps its message and appears nowhere
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use api_adapters::axum::middleware::site::{site_middleware, SiteState};
+use api_adapters::axum::middleware::site_notice::site_notice_middleware;
+use api_adapters::axum::routes::notice_routes::notice_routes;
+use async_trait::async_trait;
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+    middleware,
+    response::Html,
+    routing::get,
+    Json, Router,
+};
+use chrono::Utc;
+use domains::errors::DomainError;
+use domains::models::*;
+use domains::ports::SiteRepository;
+use services::site::SiteService;
+use storage_adapters::cache::SiteCache;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+/// The default site only. Clones share it.
+#[derive(Clone)]
+struct OneSite(Arc<Mutex<Site>>);
+
+#[async_trait]
+impl SiteRepository for OneSite {
+    async fn find_by_id(&self, id: SiteId) -> Result<Site, DomainError> {
+        let site = self.0.lock().unwrap().clone();
+        if site.id == id { Ok(site) } else { Err(DomainError::not_found(format!("site/{id}"))) }
+    }
+    async fn find_by_host(&self, _: &str) -> Result<Option<Site>, DomainError> { Ok(None) }
+    async fn find_all(&self) -> Result<Vec<Site>, DomainError> { Ok(vec![self.0.lock().unwrap().clone()]) }
+    async fn save(&self, site: &Site) -> Result<(), DomainError> {
+        *self.0.lock().unwrap() = site.clone();
+        Ok(())
+    }
+    async fn delete(&self, _: SiteId) -> Result<(), DomainError> { unimplemented!() }
+}
+
+/// The notice routes next to a page and a JSON endpoint, under the site and
+/// notice middleware as in the composition root.
+fn app(sites: OneSite) -> Router {
+    let state = SiteState { repo: Arc::new(sites.clone()), cache: Arc::new(SiteCache::new(Duration::from_secs(60))) };
+    notice_routes(Arc::new(SiteService::new(sites)), state.cache.clone())
+        .route("/page", get(|| async { Html("<header></header><!-- site-notice --><main>hi</main>") }))
+        .route("/list.json", get(|| async { Json(serde_json::json!({ "items": [1, 2] })) }))
+        .layer(middleware::from_fn(site_notice_middleware))
+        .layer(middleware::from_fn_with_state(state, site_middleware))
+}
+
+fn user(role: Role) -> CurrentUser {
+    CurrentUser::from_claims(Claims {
+        user_id:          UserId(Uuid::new_v4()),
+        username:         "staff".into(),
+        role,
+        owned_boards:     vec![],
+        volunteer_boards: vec![],
+        exp:              (Utc::now() + chrono::Duration::hours(24)).timestamp(),
+    })
+}
+
+fn put_notice(body: serde_json::Value, role: Role) -> Request<Body> {
+    let mut req = Request::builder()
+        .method("PUT")
+        .uri("/admin/notice")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    req.extensions_mut().insert(user(role));
+    req
+}
+
+fn get_req(uri: &str) -> Request<Body> {
+    Request::builder().uri(uri).body(Body::empty()).unwrap()
+}
+
+async fn body_text(resp: axum::response::Response) -> String {
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+#[tokio::test]
+async fn a_shown_notice_is_on_pages_and_json() {
+    let app = app(OneSite(Arc::new(Mutex::new(Site::fallback()))));
+    let resp = app
+        .clone()
+        .oneshot(put_notice(serde_json::json!({ "message": " Down at <2am> ", "enabled": true }), Role::Admin))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let saved: serde_json::Value = serde_json::from_str(&body_text(resp).await).unwrap();
+    assert_eq!(saved["message"], "Down at <2am>");
+
+    let html = body_text(app.clone().oneshot(get_req("/page")).await.unwrap()).await;
+    assert_eq!(
+        html,
+        "<header></header><div class=\"site-notice\" role=\"status\">Down at &lt;2am&gt;</div><main>hi</main>",
+    );
+    let json = body_text(app.oneshot(get_req("/list.json")).await.unwrap()).await;
+    assert_eq!(json, r#"{"meta":{"notice":"Down at <2am>"},"items":[1,2]}"#);
+}
+
+#[tokio::test]
+async fn a_switched_off_notice_is_kept_but_not_shown() {
+    let site = Site { notice: "Maintenance".to_owned(), notice_enabled: true, ..Site::fallback() };
+    let app = app(OneSite(Arc::new(Mutex::new(site))));
+    let resp = app
+        .clone()
+        .oneshot(put_notice(serde_json::json!({ "message": "Maintenance", "enabled": false }), Role::Admin))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let html = body_text(app.clone().oneshot(get_req("/page")).await.unwrap()).await;
+    assert!(!html.contains("site-notice\""));
+    let json = body_text(app.clone().oneshot(get_req("/list.json")).await.unwrap()).await;
+    assert_eq!(json, r#"{"items":[1,2]}"#);
+
+    let mut req = get_req("/admin/notice");
+    req.extensions_mut().insert(user(Role::Admin));
+    let current: serde_json::Value = serde_json::from_str(&body_text(app.oneshot(req).await.unwrap()).await).unwrap();
+    assert_eq!(current["message"], "Maintenance");
+    assert_eq!(current["enabled"], false);
+}
+
+#[tokio::test]
+async fn only_admins_set_the_notice() {
+    let app = app(OneSite(Arc::new(Mutex::new(Site::fallback()))));
+    let resp = app
+        .clone()
+        .oneshot(put_notice(serde_json::json!({ "message": "hi", "enabled": true }), Role::Janitor))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    let resp = app
+        .oneshot(put_notice(serde_json::json!({ "message": "  ", "enabled": true }), Role::Admin))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
@@ -0,0 +1,275 @@
+//! Integration tests for static pages.
+//!
+//! Tests verify:
+//! - Site pages render at `/:page` and board pages at `/board/:slug/:page`,
+//!   from Markdown, escaped
+//! - Fixed routes take priority over pages
+//! - Admins create, list, update and delete pages; other users cannot
+//! - Reserved and duplicate slugs are rejected
+
+use std::sync::{Arc, Mutex};
+
+use api_adapters::axum::middleware::board_config::ExtractedBoardConfig;
+use api_adapters::axum::routes::page_routes::{board_page_routes, page_routes};
+use async_trait::async_trait;
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+    routing::get,
+    Router,
+};
+use chrono::Utc;
+use domains::errors::DomainError;
+use domains::models::*;
+use domains::ports::StaticPageRepository;
+use services::board::{BoardError, BoardRepo};
+use services::page::PageService;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+/// Pages kept in memory. Clones share the rows.
+#[derive(Clone, Default)]
+struct MemPages {
+    rows: Arc<Mutex<Vec<StaticPage>>>,
+}
+
+#[async_trait]
+impl StaticPageRepository for MemPages {
+    async fn find(&self, site_id: SiteId, board_id: Option<BoardId>, slug: &str) -> Result<Option<StaticPage>, DomainError> {
+        Ok(self
+            .rows
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|p| p.site_id == site_id && p.board_id == board_id && p.slug == slug)
+            .cloned())
+    }
+    async fn find_by_id(&self, site_id: SiteId, id: PageId) -> Result<StaticPage, DomainError> {
+        self.rows
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|p| p.site_id == site_id && p.id == id)
+            .cloned()
+            .ok_or_else(|| DomainError::not_found(format!("page {id}")))
+    }
+    async fn list(&self, site_id: SiteId) -> Result<Vec<StaticPage>, DomainError> {
+        Ok(self.rows.lock().unwrap().iter().filter(|p| p.site_id == site_id).cloned().collect())
+    }
+    async fn save(&self, page: &StaticPage) -> Result<(), DomainError> {
+        let mut rows = self.rows.lock().unwrap();
+        rows.retain(|p| p.id != page.id);
+        rows.push(page.clone());
+        Ok(())
+    }
+    async fn delete(&self, site_id: SiteId, id: PageId) -> Result<StaticPage, DomainError> {
+        let mut rows = self.rows.lock().unwrap();
+        let at = rows
+            .iter()
+            .position(|p| p.site_id == site_id && p.id == id)
+            .ok_or_else(|| DomainError::not_found(format!("page {id}")))?;
+        Ok(rows.remove(at))
+    }
+}
+
+/// The only board there is; everything but `get_by_id` is unused.
+struct OneBoard(Board);
+
+#[async_trait]
+impl BoardRepo for OneBoard {
+    async fn create_board(&self, _: SiteId, _: &str, _: &str, _: &str) -> Result<Board, BoardError> { unimplemented!() }
+    async fn get_by_slug(&self, _: SiteId, _: &str) -> Result<Board, BoardError> { unimplemented!() }
+    async fn get_by_id(&self, id: BoardId) -> Result<Board, BoardError> {
+        if id == self.0.id { Ok(self.0.clone()) } else { Err(BoardError::NotFound { slug: id.to_string() }) }
+    }
+    async fn update_board(&self, _: BoardId, _: Option<&str>, _: Option<&str>) -> Result<Board, BoardError> { unimplemented!() }
+    async fn delete_board(&self, _: BoardId) -> Result<(), BoardError> { unimplemented!() }
+    async fn list_boards(&self, _: SiteId, _: Page) -> Result<Paginated<Board>, BoardError> { unimplemented!() }
+    async fn get_config(&self, _: BoardId) -> Result<BoardConfig, BoardError> { unimplemented!() }
+    async fn update_config(&self, _: BoardId, _: BoardConfig, _: domains::models::UserId) -> Result<BoardConfig, BoardError> { unimplemented!() }
+    async fn media_usage(&self, _: BoardId) -> Result<u64, BoardError> { unimplemented!() }
+    async fn list_volunteers(&self, _: BoardId) -> Result<Vec<(UserId, String, chrono::DateTime<Utc>)>, BoardError> { unimplemented!() }
+    async fn add_volunteer_by_username(&self, _: BoardId, _: &str, _: UserId) -> Result<(), BoardError> { unimplemented!() }
+    async fn remove_volunteer(&self, _: BoardId, _: UserId) -> Result<(), BoardError> { unimplemented!() }
+}
+
+fn make_board() -> Board {
+    Board {
+        id:         BoardId(Uuid::new_v4()),
+        site_id:    SiteId::DEFAULT,
+        slug:       Slug::new("b").unwrap(),
+        title:      "Random".to_owned(),
+        rules:      String::new(),
+        created_at: Utc::now(),
+    }
+}
+
+fn page_on(board_id: Option<BoardId>, slug: &str, body: &str) -> StaticPage {
+    StaticPage {
+        id:         PageId::new(),
+        site_id:    SiteId::DEFAULT,
+        board_id,
+        slug:       slug.to_owned(),
+        title:      "Rules & FAQ".to_owned(),
+        body:       body.to_owned(),
+        updated_at: Utc::now(),
+    }
+}
+
+/// The page routes next to fixed routes, as in the composition root.
+fn app(board: Board, pages: MemPages) -> Router {
+    let svc = Arc::new(PageService::new(pages));
+    let ctx = ExtractedBoardConfig {
+        board_id: board.id,
+        config:   BoardConfig::default(),
+        slug:     board.slug.clone(),
+        board:    board.clone(),
+    };
+    let board_scoped = board_page_routes(svc.clone())
+        .route("/board/{slug}/catalog", get(|| async { "catalog" }))
+        .layer(axum::Extension(ctx));
+    page_routes(svc, Arc::new(OneBoard(board)))
+        .route("/overboard", get(|| async { "overboard" }))
+        .merge(board_scoped)
+}
+
+fn user(role: Role) -> CurrentUser {
+    CurrentUser::from_claims(Claims {
+        user_id:          UserId(Uuid::new_v4()),
+        username:         "staff".into(),
+        role,
+        owned_boards:     vec![],
+        volunteer_boards: vec![],
+        exp:              (Utc::now() + chrono::Duration::hours(24)).timestamp(),
+    })
+}
+
+fn admin_json(method: &str, uri: &str, body: serde_json::Value) -> Request<Body> {
+    let mut req = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    req.extensions_mut().insert(user(Role::Admin));
+    req
+}
+
+fn get_req(uri: &str) -> Request<Body> {
+    Request::builder().uri(uri).body(Body::empty()).unwrap()
+}
+
+async fn body_text(resp: axum::response::Response) -> String {
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+#[tokio::test]
+async fn site_pages_render_their_markdown() {
+    let pages = MemPages::default();
+    pages.rows.lock().unwrap().push(page_on(None, "faq", "# Posting\n- be <nice>\n\nsee [rules](/board/b/rules)"));
+
+    let resp = app(make_board(), pages).oneshot(get_req("/faq")).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let html = body_text(resp).await;
+    assert!(html.contains("<h1>Rules &#38; FAQ</h1>"));
+    assert!(html.contains("<h2>Posting</h2>"));
+    assert!(html.contains("<li>be &lt;nice&gt;</li>"));
+    assert!(html.contains("<a href=\"/board/b/rules\">rules</a>"));
+}
+
+#[tokio::test]
+async fn board_pages_render_on_their_board() {
+    let board = make_board();
+    let pages = MemPages::default();
+    pages.rows.lock().unwrap().push(page_on(Some(board.id), "rules", "1. no spam"));
+    let app = app(board, pages);
+
+    let resp = app.clone().oneshot(get_req("/board/b/rules")).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert!(body_text(resp).await.contains("<ol>\n<li>no spam</li>\n</ol>"));
+    // A board page is not a site page.
+    let resp = app.oneshot(get_req("/rules")).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn fixed_routes_win_over_pages() {
+    let app = app(make_board(), MemPages::default());
+    let resp = app.clone().oneshot(get_req("/overboard")).await.unwrap();
+    assert_eq!(body_text(resp).await, "overboard");
+    let resp = app.clone().oneshot(get_req("/board/b/catalog")).await.unwrap();
+    assert_eq!(body_text(resp).await, "catalog");
+    let resp = app.oneshot(get_req("/missing")).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn admins_manage_pages() {
+    let board = make_board();
+    let pages = MemPages::default();
+    let app = app(board.clone(), pages.clone());
+
+    let resp = app
+        .clone()
+        .oneshot(admin_json("POST", "/admin/pages", serde_json::json!({
+            "slug": "rules", "title": "Rules", "body": "1. be nice", "board_id": board.id,
+        })))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let created: serde_json::Value = serde_json::from_str(&body_text(resp).await).unwrap();
+    assert_eq!(created["board_id"], board.id.to_string());
+    let id = created["id"].as_str().unwrap().to_owned();
+
+    let resp = app
+        .clone()
+        .oneshot(admin_json("PUT", &format!("/admin/pages/{id}"), serde_json::json!({
+            "slug": "rules", "title": "Board rules", "body": "1. be very nice",
+        })))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let resp = app.clone().oneshot(get_req("/board/b/rules")).await.unwrap();
+    assert!(body_text(resp).await.contains("be very nice"));
+
+    let resp = app.clone().oneshot(admin_json("GET", "/admin/pages", serde_json::Value::Null)).await.unwrap();
+    let listed: serde_json::Value = serde_json::from_str(&body_text(resp).await).unwrap();
+    assert_eq!(listed[0]["title"], "Board rules");
+
+    let resp = app
+        .oneshot(admin_json("DELETE", &format!("/admin/pages/{id}"), serde_json::Value::Null))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+    assert!(pages.rows.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn only_admins_manage_pages() {
+    let app = app(make_board(), MemPages::default());
+    let mut req = admin_json("POST", "/admin/pages", serde_json::json!({ "slug": "faq", "title": "FAQ" }));
+    req.extensions_mut().insert(user(Role::Janitor));
+    let resp = app.oneshot(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn reserved_and_taken_slugs_are_rejected() {
+    let pages = MemPages::default();
+    pages.rows.lock().unwrap().push(page_on(None, "faq", ""));
+    let app = app(make_board(), pages);
+
+    let resp = app
+        .clone()
+        .oneshot(admin_json("POST", "/admin/pages", serde_json::json!({ "slug": "overboard", "title": "x" })))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let resp = app
+        .oneshot(admin_json("POST", "/admin/pages", serde_json::json!({ "slug": "faq", "title": "x" })))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::CONFLICT);
+}
@@ -6,7 +6,10 @@
 //!
 //! Tests verify:
 //! - Text-only posts create a thread successfully
-//! - Banned IP receives 403
+//! - Banned IP receives 403 (HTML ban page for browsers)
+//! - `GET /banned` reports the visitor's active ban
+//! - A warned poster is refused until they acknowledge the warning at
+//!   `POST /warning/:id/ack`
 //! - Rate-limited IP receives 429
 //! - Invalid/missing multipart body receives 422
 //! - Sage posts work (same 201, but the service marks no-bump)
@@ -43,15 +46,22 @@ impl PostRepository for OkPostRepo {
         Ok(Paginated::new(vec![], 0, p, 15))
     }
     async fn find_by_ip_hash(&self, _: &IpHash) -> Result<Vec<Post>, DomainError> { Ok(vec![]) }
+    async fn find_by_ip_hashes(&self, _: domains::models::SiteId, _: &[IpHash], p: Page) -> Result<Paginated<OverboardPost>, DomainError> { Ok(Paginated::new(vec![], 0, p, 15)) }
     async fn find_recent_hashes(&self, _: BoardId, _: u32) -> Result<Vec<ContentHash>, DomainError> {
         Ok(vec![])
     }
     async fn save(&self, post: &Post) -> Result<(PostId, u64), DomainError> { Ok((post.id, 1)) }
-    async fn delete(&self, _: PostId) -> Result<(), DomainError> { Ok(()) }
+    async fn delete(&self, _: PostId, _: domains::models::DeletedBy) -> Result<(), DomainError> { Ok(()) }
     async fn delete_by_ip_in_thread(&self, _: &IpHash, _: ThreadId) -> Result<u64, DomainError> { Ok(0) }
-    async fn find_overboard(&self, p: Page) -> Result<Paginated<OverboardPost>, DomainError> {
+    async fn delete_by_ip_on_board(&self, _: &IpHash, _: BoardId, _: chrono::DateTime<chrono::Utc>) -> Result<domains::models::PurgedPosts, DomainError> { Ok(Default::default()) }
+    async fn purge_deleted(&self, _: u32) -> Result<u32, DomainError> { Ok(0) }
+    async fn edit(&self, _: PostId, _: &str, _: Option<String>, _: UserId) -> Result<(), DomainError> { Ok(()) }
+    async fn find_revisions(&self, _: PostId) -> Result<Vec<PostRevision>, DomainError> { Ok(vec![]) }
+    async fn move_posts(&self, _: &domains::models::PostMove) -> Result<(), DomainError> { Ok(()) }
+    async fn find_overboard(&self, _site: domains::models::SiteId, p: Page) -> Result<Paginated<OverboardPost>, DomainError> {
         Ok(Paginated::new(vec![], 0, p, 15))
     }
+    async fn find_site_activity(&self, _: domains::models::SiteId, _: u32) -> Result<domains::models::SiteActivity, domains::errors::DomainError> { Ok(domains::models::SiteActivity::default()) }
     async fn save_attachments(&self, _: &[domains::models::Attachment]) -> Result<(), DomainError> { Ok(()) }
     async fn find_attachments_by_post_ids(&self, _: &[PostId]) -> Result<std::collections::HashMap<PostId, Vec<domains::models::Attachment>>, DomainError> {
         Ok(std::collections::HashMap::new())
@@ -65,11 +75,24 @@ impl PostRepository for OkPostRepo {
         Ok(Paginated::new(vec![], 0, p, 15))
     }
     async fn find_all_by_thread(&self, _: ThreadId) -> Result<Vec<Post>, DomainError> { Ok(vec![]) }
+    async fn find_visible_by_thread(&self, _: ThreadId, _: &domains::models::Viewer) -> Result<Vec<Post>, DomainError> { Ok(vec![]) }
     async fn find_thread_id_by_post_number(&self, _: BoardId, _: u64) -> Result<Option<ThreadId>, DomainError> { Ok(None) }
     async fn set_pinned(&self, _: domains::models::PostId, _: bool) -> Result<(), domains::errors::DomainError> { Ok(()) }
-    async fn find_oldest_unpinned_reply(&self, _: domains::models::ThreadId) -> Result<Option<domains::models::PostId>, domains::errors::DomainError> { Ok(None) }
+    async fn find_cycle_excess(&self, _: domains::models::ThreadId, _: u32) -> Result<Vec<domains::models::PostId>, domains::errors::DomainError> { Ok(vec![]) }
     async fn find_attachment_by_hash(&self, _: &domains::models::ContentHash) -> Result<Option<domains::models::Attachment>, domains::errors::DomainError> { Ok(None) }
     async fn delete_by_id(&self, _: domains::models::PostId) -> Result<(), domains::errors::DomainError> { Ok(()) }
+    async fn find_referenced_media_keys(&self) -> Result<std::collections::HashSet<domains::models::MediaKey>, domains::errors::DomainError> { Ok(Default::default()) }
+    async fn find_thumbnailed_attachments(&self) -> Result<Vec<(BoardId, domains::models::Attachment)>, domains::errors::DomainError> { Ok(vec![]) }
+    async fn find_all_attachments(&self) -> Result<Vec<(BoardId, domains::models::Attachment)>, domains::errors::DomainError> { Ok(vec![]) }
+    async fn find_visible_by_id(&self, _: domains::models::PostId) -> Result<Option<domains::models::Post>, domains::errors::DomainError> { Ok(None) }
+    async fn find_by_ids(&self, _: domains::models::ThreadId, _: &[domains::models::PostId]) -> Result<Vec<domains::models::Post>, domains::errors::DomainError> { Ok(vec![]) }
+    async fn find_by_numbers(&self, _: domains::models::ThreadId, _: &[u64]) -> Result<Vec<domains::models::Post>, domains::errors::DomainError> { Ok(vec![]) }
+    async fn find_quoting(&self, _: domains::models::ThreadId, _: &[u64]) -> Result<Vec<domains::models::Post>, domains::errors::DomainError> { Ok(vec![]) }
+    async fn find_media_mime(&self, _: &domains::models::MediaKey) -> Result<Option<String>, domains::errors::DomainError> { Ok(None) }
+    async fn set_thumbnail_key(&self, _: &domains::models::MediaKey, _: &domains::models::MediaKey) -> Result<u64, domains::errors::DomainError> { Ok(0) }
+    async fn restore(&self, _: &domains::models::Post) -> Result<(), domains::errors::DomainError> { Ok(()) }
+    async fn find_number_range(&self, _: domains::models::BoardId) -> Result<Option<domains::models::PostNumberRange>, domains::errors::DomainError> { Ok(None) }
+    async fn find_by_number_range(&self, _: domains::models::BoardId, _: u64, _: u64, _: u32) -> Result<Vec<domains::models::Post>, domains::errors::DomainError> { Ok(vec![]) }
 }
 
 struct OkThreadRepo;
@@ -90,7 +113,9 @@ impl ThreadRepository for OkThreadRepo {
     async fn find_by_board(&self, _: BoardId, p: Page) -> Result<Paginated<Thread>, DomainError> {
         Ok(Paginated::new(vec![], 0, p, 15))
     }
-    async fn find_catalog(&self, _: BoardId) -> Result<Vec<ThreadSummary>, DomainError> { Ok(vec![]) }
+    async fn find_catalog(&self, _: BoardId, _: CatalogSort) -> Result<Vec<ThreadSummary>, DomainError> { Ok(vec![]) }
+    async fn find_summaries(&self, _: BoardId, _: &[ThreadId]) -> Result<Vec<ThreadSummary>, DomainError> { Ok(vec![]) }
+    async fn find_overboard_threads(&self, _: domains::models::SiteId, p: Page) -> Result<Paginated<domains::models::OverboardThread>, DomainError> { Ok(Paginated::new(vec![], 0, p, 50)) }
     async fn save(&self, t: &Thread) -> Result<ThreadId, DomainError> { Ok(t.id) }
     async fn bump(&self, _: ThreadId, _: chrono::DateTime<Utc>) -> Result<(), DomainError> { Ok(()) }
     async fn set_op_post(&self, _: ThreadId, _: PostId) -> Result<(), DomainError> { Ok(()) }
@@ -115,6 +140,7 @@ impl BanRepository for BannedIpRepo {
             reason:     "spam test ban".to_owned(),
             expires_at: None,
             created_at: Utc::now(),
+            shadow:     false,
         }))
     }
     async fn save(&self, b: &Ban) -> Result<BanId, DomainError> { Ok(b.id) }
@@ -122,6 +148,7 @@ impl BanRepository for BannedIpRepo {
     async fn find_all(&self, p: Page) -> Result<Paginated<Ban>, DomainError> {
         Ok(Paginated::new(vec![], 0, p, 15))
     }
+    async fn delete_expired(&self, _: u32) -> Result<u32, DomainError> { Ok(0) }
 }
 
 /// Ban repo that reports no active bans.
@@ -134,16 +161,23 @@ impl BanRepository for NoBanRepo {
     async fn find_all(&self, p: Page) -> Result<Paginated<Ban>, DomainError> {
         Ok(Paginated::new(vec![], 0, p, 15))
     }
+    async fn delete_expired(&self, _: u32) -> Result<u32, DomainError> { Ok(0) }
 }
 
 struct NopMedia;
 #[async_trait::async_trait]
 impl MediaStorage for NopMedia {
     async fn store(&self, _: &MediaKey, _: Bytes, _: &str) -> Result<(), DomainError> { Ok(()) }
+    async fn store_file(&self, _: &MediaKey, _: &std::path::Path, _: &str) -> Result<(), DomainError> {
+        Ok(())
+    }
     async fn get_url(&self, _: &MediaKey, _: std::time::Duration) -> Result<String, DomainError> {
         Ok("http://localhost/stub".to_owned())
     }
     async fn delete(&self, _: &MediaKey) -> Result<(), DomainError> { Ok(()) }
+    async fn fetch(&self, k: &MediaKey) -> Result<Bytes, DomainError> { Err(DomainError::not_found(k.to_string())) }
+    async fn exists(&self, _: &MediaKey) -> Result<bool, DomainError> { Ok(false) }
+    async fn list(&self) -> Result<Vec<domains::ports::StoredMedia>, DomainError> { Ok(vec![]) }
 }
 
 /// Rate limiter that always allows.
@@ -177,11 +211,37 @@ impl MediaProcessor for NopProcessor {
     fn accepts(&self, _: &mime::Mime) -> bool { false }
 }
 
+/// Warnings kept in memory. Clones share them.
+#[derive(Clone, Default)]
+struct MemWarnings(Arc<std::sync::Mutex<Vec<Warning>>>);
+#[async_trait::async_trait]
+impl WarningRepository for MemWarnings {
+    async fn find_pending_by_ip(&self, ip_hash: &IpHash) -> Result<Option<Warning>, DomainError> {
+        let warnings = self.0.lock().unwrap();
+        Ok(warnings.iter().find(|w| &w.ip_hash == ip_hash && w.acknowledged_at.is_none()).cloned())
+    }
+    async fn save(&self, warning: &Warning) -> Result<BanId, DomainError> {
+        self.0.lock().unwrap().push(warning.clone());
+        Ok(warning.id)
+    }
+    async fn acknowledge(&self, id: BanId, ip_hash: &IpHash) -> Result<(), DomainError> {
+        let mut warnings = self.0.lock().unwrap();
+        match warnings.iter_mut().find(|w| w.id == id && &w.ip_hash == ip_hash && w.acknowledged_at.is_none()) {
+            Some(w) => {
+                w.acknowledged_at = Some(Utc::now());
+                Ok(())
+            }
+            None => Err(DomainError::not_found(format!("warning/{id}"))),
+        }
+    }
+}
+
 // ─── Helpers ─────────────────────────────────────────────────────────────────
 
 fn inject_board_ctx(mut req: Request<Body>, board_id: BoardId) -> Request<Body> {
     let board = Board {
         id:         board_id,
+        site_id:    domains::models::SiteId::DEFAULT,
         slug:       Slug::new("tech").unwrap(),
         title:      "/tech/ — Technology".to_owned(),
         rules:      "".to_owned(),
@@ -207,6 +267,7 @@ fn inject_board_ctx_with_config(
 ) -> Request<Body> {
     let board = Board {
         id:         board_id,
+        site_id:    domains::models::SiteId::DEFAULT,
         slug:       Slug::new("tech").unwrap(),
         title:      "/tech/ — Technology".to_owned(),
         rules:      "".to_owned(),
@@ -278,6 +339,50 @@ async fn banned_ip_receives_403() {
     assert_eq!(resp.status(), StatusCode::FORBIDDEN);
 }
 
+#[tokio::test]
+async fn banned_browser_post_renders_ban_page() {
+    let board_id = BoardId(Uuid::new_v4());
+    let svc = Arc::new(PostService::new(
+        OkPostRepo, OkThreadRepo, BannedIpRepo, NopMedia, AllowAllRateLimiter, NopProcessor, String::new(),
+    ));
+    let app = post_routes(svc);
+
+    let req = inject_board_ctx(multipart_req("tech", "This should be rejected"), board_id);
+    let resp = app.oneshot(req).await.unwrap();
+
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    let ct = resp.headers().get(header::CONTENT_TYPE).unwrap().to_str().unwrap().to_owned();
+    assert!(ct.starts_with("text/html"), "expected HTML ban page, got {ct}");
+    let body = axum::body::to_bytes(resp.into_body(), 1 << 20).await.unwrap();
+    let html = String::from_utf8_lossy(&body);
+    assert!(html.contains("spam test ban"));
+    assert!(html.contains("permanent"));
+}
+
+#[tokio::test]
+async fn ban_status_page_reports_active_ban() {
+    let svc = Arc::new(PostService::new(
+        OkPostRepo, OkThreadRepo, BannedIpRepo, NopMedia, AllowAllRateLimiter, NopProcessor, String::new(),
+    ));
+    let app = post_routes(svc);
+
+    let mut req = Request::builder()
+        .uri("/banned")
+        .header(header::ACCEPT, "application/json")
+        .body(Body::empty())
+        .unwrap();
+    req.extensions_mut().insert(axum::extract::ConnectInfo(
+        std::net::SocketAddr::from(([127, 0, 0, 1], 1234)),
+    ));
+    let resp = app.oneshot(req).await.unwrap();
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(resp.into_body(), 1 << 20).await.unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["banned"], true);
+    assert_eq!(json["reason"], "spam test ban");
+}
+
 #[tokio::test]
 async fn rate_limited_ip_receives_429() {
     let board_id = BoardId(Uuid::new_v4());
@@ -352,3 +457,51 @@ async fn post_without_multipart_content_type_returns_400_or_415() {
         resp.status()
     );
 }
+
+#[tokio::test]
+async fn warned_poster_must_acknowledge_before_posting() {
+    let warnings = MemWarnings::default();
+    let daily_salt = Utc::now().format("%Y-%m-%d").to_string();
+    let warning = Warning {
+        id:              BanId(Uuid::new_v4()),
+        ip_hash:         services::common::utils::hash_ip("127.0.0.1", &daily_salt),
+        post_id:         None,
+        issued_by:       UserId(Uuid::new_v4()),
+        reason:          "read the rules".to_owned(),
+        created_at:      Utc::now(),
+        acknowledged_at: None,
+    };
+    warnings.save(&warning).await.unwrap();
+    let svc = Arc::new(
+        PostService::new(
+            OkPostRepo, OkThreadRepo, NoBanRepo, NopMedia, AllowAllRateLimiter, NopProcessor, String::new(),
+        )
+        .with_warnings(Arc::new(warnings)),
+    );
+    let app = post_routes(svc);
+    let board_id = BoardId(Uuid::new_v4());
+    let ack = || {
+        let mut req = Request::builder()
+            .method(Method::POST)
+            .uri(format!("/warning/{}/ack", warning.id))
+            .header(header::REFERER, "https://example.org/board/tech/?page=2")
+            .body(Body::empty())
+            .unwrap();
+        req.extensions_mut().insert(axum::extract::ConnectInfo(
+            std::net::SocketAddr::from(([127, 0, 0, 1], 1234)),
+        ));
+        req
+    };
+
+    let resp = app.clone().oneshot(inject_board_ctx(multipart_req("tech", "hi"), board_id)).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let resp = app.clone().oneshot(ack()).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::SEE_OTHER);
+    assert_eq!(resp.headers()[header::LOCATION], "/board/tech/?page=2");
+
+    let resp = app.clone().oneshot(inject_board_ctx(multipart_req("tech", "hi"), board_id)).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::SEE_OTHER);
+    // Already acknowledged.
+    assert_eq!(app.oneshot(ack()).await.unwrap().status(), StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,54 @@
+//! Integration tests for the Unix-socket peer middleware.
+//!
+//! Tests verify:
+//! - `X-Real-IP` becomes the handler's `ConnectInfo` address
+//! - Without it, the last `X-Forwarded-For` entry is used
+//! - Requests with neither header still reach the handler as `0.0.0.0`
+
+use std::net::SocketAddr;
+
+use api_adapters::axum::middleware::proxy_peer::proxy_peer_middleware;
+use axum::{
+    body::Body,
+    extract::ConnectInfo,
+    http::{Request, StatusCode},
+    middleware,
+    routing::get,
+    Router,
+};
+use tower::ServiceExt;
+
+fn app() -> Router {
+    Router::new()
+        .route("/", get(|ConnectInfo(peer): ConnectInfo<SocketAddr>| async move { peer.ip().to_string() }))
+        .layer(middleware::from_fn(proxy_peer_middleware))
+}
+
+async fn peer_ip(headers: &[(&str, &str)]) -> String {
+    let mut req = Request::builder().uri("/");
+    for (name, value) in headers {
+        req = req.header(*name, *value);
+    }
+    let resp = app().oneshot(req.body(Body::empty()).unwrap()).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+#[tokio::test]
+async fn real_ip_header_wins() {
+    let ip = peer_ip(&[("x-real-ip", "203.0.113.7"), ("x-forwarded-for", "198.51.100.1")]).await;
+    assert_eq!(ip, "203.0.113.7");
+}
+
+#[tokio::test]
+async fn last_forwarded_for_entry_is_used() {
+    let ip = peer_ip(&[("x-forwarded-for", "10.0.0.1, 2001:db8::5")]).await;
+    assert_eq!(ip, "2001:db8::5");
+}
+
+#[tokio::test]
+async fn unannotated_requests_get_the_unspecified_address() {
+    assert_eq!(peer_ip(&[]).await, "0.0.0.0");
+    assert_eq!(peer_ip(&[("x-real-ip", "not an ip")]).await, "0.0.0.0");
+}
@@ -0,0 +1,227 @@
+//! Integration tests for the shared cache layers.
+//!
+//! Tests verify:
+//! - A board index is rendered once, then served from cache
+//! - Index pages are keyed by board, page number and UI locale; other query parameters are ignored
+//! - Another instance sharing the store is served the page without rendering it
+//! - A successful write on the board invalidates its cached pages; a failed one does not
+//! - Thread pages and sorted or filtered catalogs are never cached
+//! - A cache store failure falls back to the in-process cache
+//! - Board settings lookups are answered from the store once loaded
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use api_adapters::axum::middleware::board_config::{
+    board_config_middleware, BoardConfigSource, BoardConfigState, CachedBoardConfigSource,
+};
+use api_adapters::axum::middleware::render_cache::{render_cache_middleware, RenderCacheState};
+use axum::{
+    body::Body,
+    http::{header, Method, Request, StatusCode},
+    middleware,
+    response::Html,
+    routing::get,
+    Router,
+};
+use chrono::Utc;
+use domains::errors::DomainError;
+use domains::models::{Board, BoardConfig, BoardId, SiteId, Slug};
+use domains::ports::{CacheStore, MockCacheStore};
+use storage_adapters::cache::{BoardConfigCache, PageCache};
+use storage_adapters::in_memory::InMemoryCacheStore;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+/// Board source for `/board/b`, counting how often it is asked.
+struct CountingSource {
+    board: Board,
+    calls: AtomicUsize,
+}
+
+impl CountingSource {
+    fn new() -> Self {
+        let board = Board {
+            id:         BoardId(Uuid::new_v4()),
+            site_id:    SiteId::DEFAULT,
+            slug:       Slug::new("b").unwrap(),
+            title:      "Random".to_owned(),
+            rules:      String::new(),
+            created_at: Utc::now(),
+        };
+        Self { board, calls: AtomicUsize::new(0) }
+    }
+}
+
+#[async_trait::async_trait]
+impl BoardConfigSource for CountingSource {
+    async fn config_by_slug(
+        &self,
+        _site: SiteId,
+        slug: &Slug,
+    ) -> Result<Option<(Board, BoardId, BoardConfig)>, Box<dyn std::error::Error + Send + Sync>> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        Ok((slug == &self.board.slug).then(|| (self.board.clone(), self.board.id, BoardConfig::default())))
+    }
+}
+
+/// A board-scoped router whose handlers count their renders and succeed
+/// unless the `fail` query parameter is present on a POST.
+fn app(store: Arc<dyn CacheStore>, renders: Arc<AtomicUsize>) -> Router {
+    app_for(Arc::new(CountingSource::new()), store, renders)
+}
+
+fn app_for(source: Arc<CountingSource>, store: Arc<dyn CacheStore>, renders: Arc<AtomicUsize>) -> Router {
+    let config_state = BoardConfigState {
+        source,
+        cache:  Arc::new(BoardConfigCache::new(Duration::from_secs(60))),
+    };
+    let render = move || {
+        let renders = renders.clone();
+        async move { Html(format!("render #{}", renders.fetch_add(1, Ordering::SeqCst) + 1)) }
+    };
+    Router::new()
+        .route("/board/{slug}", get(render.clone()).post(|req: Request<Body>| async move {
+            if req.uri().query() == Some("fail") { StatusCode::BAD_REQUEST } else { StatusCode::SEE_OTHER }
+        }))
+        .route("/board/{slug}/catalog", get(render.clone()))
+        .route("/board/{slug}/thread/{id}", get(render))
+        .route_layer(middleware::from_fn_with_state(
+            RenderCacheState {
+                pages: Arc::new(PageCache::new(Duration::from_secs(60))),
+                store,
+                ttl:   Duration::from_secs(60),
+            },
+            render_cache_middleware,
+        ))
+        .route_layer(middleware::from_fn_with_state(config_state, board_config_middleware))
+}
+
+async fn send(app: &Router, method: Method, uri: &str) -> (StatusCode, String) {
+    let req = Request::builder().method(method).uri(uri).body(Body::empty()).unwrap();
+    let resp = app.clone().oneshot(req).await.unwrap();
+    let status = resp.status();
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+async fn get_body(app: &Router, uri: &str) -> String {
+    send(app, Method::GET, uri).await.1
+}
+
+#[tokio::test]
+async fn index_and_catalog_are_served_from_cache() {
+    let renders = Arc::new(AtomicUsize::new(0));
+    let app = app(Arc::new(InMemoryCacheStore::new()), renders.clone());
+
+    assert_eq!(get_body(&app, "/board/b").await, "render #1");
+    assert_eq!(get_body(&app, "/board/b").await, "render #1");
+    assert_eq!(get_body(&app, "/board/b?page=1").await, "render #1");
+    assert_eq!(get_body(&app, "/board/b?page=2").await, "render #2");
+    assert_eq!(get_body(&app, "/board/b?page=2&utm=x").await, "render #2");
+    assert_eq!(get_body(&app, "/board/b/catalog").await, "render #3");
+    assert_eq!(get_body(&app, "/board/b/catalog").await, "render #3");
+    assert_eq!(renders.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn pages_are_cached_per_locale() {
+    let renders = Arc::new(AtomicUsize::new(0));
+    let app = app(Arc::new(InMemoryCacheStore::new()), renders.clone());
+    let get_in = |language: &'static str| {
+        let req = Request::builder().uri("/board/b").header(header::ACCEPT_LANGUAGE, language);
+        let app = app.clone();
+        async move {
+            let resp = app.oneshot(req.body(Body::empty()).unwrap()).await.unwrap();
+            String::from_utf8(axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap().to_vec()).unwrap()
+        }
+    };
+
+    assert_eq!(get_in("es").await, "render #1");
+    assert_eq!(get_in("en").await, "render #2");
+    assert_eq!(get_in("es-MX,es;q=0.9").await, "render #1");
+    assert_eq!(get_body(&app, "/board/b").await, "render #2");
+}
+
+#[tokio::test]
+async fn instances_share_pages_through_the_store() {
+    let store: Arc<dyn CacheStore> = Arc::new(InMemoryCacheStore::new());
+    let renders = Arc::new(AtomicUsize::new(0));
+    let source = Arc::new(CountingSource::new());
+    let first = app_for(source.clone(), store.clone(), renders.clone());
+    let second = app_for(source, store, renders.clone());
+
+    assert_eq!(get_body(&first, "/board/b").await, "render #1");
+    assert_eq!(get_body(&second, "/board/b").await, "render #1");
+    assert_eq!(renders.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn successful_write_invalidates_the_board() {
+    let renders = Arc::new(AtomicUsize::new(0));
+    let app = app(Arc::new(InMemoryCacheStore::new()), renders.clone());
+
+    assert_eq!(get_body(&app, "/board/b").await, "render #1");
+    assert_eq!(send(&app, Method::POST, "/board/b?fail").await.0, StatusCode::BAD_REQUEST);
+    assert_eq!(get_body(&app, "/board/b").await, "render #1");
+
+    assert_eq!(send(&app, Method::POST, "/board/b").await.0, StatusCode::SEE_OTHER);
+    assert_eq!(get_body(&app, "/board/b").await, "render #2");
+}
+
+#[tokio::test]
+async fn thread_pages_are_not_cached() {
+    let renders = Arc::new(AtomicUsize::new(0));
+    let app = app(Arc::new(InMemoryCacheStore::new()), renders.clone());
+    let uri = format!("/board/b/thread/{}", Uuid::new_v4());
+
+    assert_eq!(get_body(&app, &uri).await, "render #1");
+    assert_eq!(get_body(&app, &uri).await, "render #2");
+}
+
+#[tokio::test]
+async fn sorted_and_filtered_catalogs_are_not_cached() {
+    let renders = Arc::new(AtomicUsize::new(0));
+    let app = app(Arc::new(InMemoryCacheStore::new()), renders.clone());
+
+    assert_eq!(get_body(&app, "/board/b/catalog?sort=random").await, "render #1");
+    assert_eq!(get_body(&app, "/board/b/catalog?sort=random").await, "render #2");
+    assert_eq!(get_body(&app, "/board/b/catalog?q=rust").await, "render #3");
+    assert_eq!(get_body(&app, "/board/b/catalog").await, "render #4");
+    assert_eq!(get_body(&app, "/board/b/catalog").await, "render #4");
+}
+
+#[tokio::test]
+async fn store_failure_falls_back_to_process_cache() {
+    let mut store = MockCacheStore::new();
+    store.expect_get().returning(|_| Err(DomainError::internal("redis down")));
+    store.expect_set().never();
+    let renders = Arc::new(AtomicUsize::new(0));
+    let app = app(Arc::new(store), renders.clone());
+
+    assert_eq!(send(&app, Method::GET, "/board/b").await, (StatusCode::OK, "render #1".to_owned()));
+    assert_eq!(get_body(&app, "/board/b").await, "render #1");
+    assert_eq!(get_body(&app, "/board/b/catalog").await, "render #2");
+}
+
+#[tokio::test]
+async fn board_settings_are_shared_through_the_store() {
+    let store: Arc<dyn CacheStore> = Arc::new(InMemoryCacheStore::new());
+    let source = Arc::new(CountingSource::new());
+    // Two sources over one store stand in for two instances.
+    let first = CachedBoardConfigSource { inner: source.clone(), store: store.clone(), ttl: Duration::from_secs(60) };
+    let second = CachedBoardConfigSource { inner: source.clone(), store, ttl: Duration::from_secs(60) };
+    let slug = Slug::new("b").unwrap();
+
+    let (board, _, _) = first.config_by_slug(SiteId::DEFAULT, &slug).await.unwrap().unwrap();
+    let (cached, _, _) = second.config_by_slug(SiteId::DEFAULT, &slug).await.unwrap().unwrap();
+    assert_eq!(cached.id, board.id);
+    assert_eq!(source.calls.load(Ordering::SeqCst), 1);
+
+    // Unknown boards are not cached.
+    let missing = Slug::new("nope").unwrap();
+    assert!(second.config_by_slug(SiteId::DEFAULT, &missing).await.unwrap().is_none());
+    assert!(second.config_by_slug(SiteId::DEFAULT, &missing).await.unwrap().is_none());
+    assert_eq!(source.calls.load(Ordering::SeqCst), 3);
+}
@@ -0,0 +1,65 @@
+//! Integration tests for the request-ID middleware.
+//!
+//! Tests verify:
+//! - A request without `X-Request-Id` gets a UUID, seen by the handler and
+//!   echoed in the response
+//! - A well-formed incoming ID is kept
+//! - A malformed or oversized incoming ID is replaced
+
+use api_adapters::axum::middleware::request_id::{request_id_middleware, REQUEST_ID_HEADER};
+use axum::{
+    body::Body,
+    http::{HeaderMap, Request, StatusCode},
+    middleware,
+    routing::get,
+    Router,
+};
+use tower::ServiceExt;
+
+fn app() -> Router {
+    Router::new()
+        .route(
+            "/",
+            get(|headers: HeaderMap| async move {
+                headers.get(REQUEST_ID_HEADER).map(|v| v.to_str().unwrap().to_owned()).unwrap_or_default()
+            }),
+        )
+        .layer(middleware::from_fn(request_id_middleware))
+}
+
+/// The ID the handler saw and the ID echoed in the response.
+async fn request_ids(incoming: Option<&str>) -> (String, String) {
+    let mut req = Request::builder().uri("/");
+    if let Some(id) = incoming {
+        req = req.header("x-request-id", id);
+    }
+    let resp = app().oneshot(req.body(Body::empty()).unwrap()).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let echoed = resp.headers()["x-request-id"].to_str().unwrap().to_owned();
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    (String::from_utf8(body.to_vec()).unwrap(), echoed)
+}
+
+#[tokio::test]
+async fn missing_id_is_generated_and_echoed() {
+    let (seen, echoed) = request_ids(None).await;
+    assert!(uuid::Uuid::parse_str(&seen).is_ok(), "{seen}");
+    assert_eq!(seen, echoed);
+}
+
+#[tokio::test]
+async fn proxy_id_is_kept() {
+    let (seen, echoed) = request_ids(Some("a1b2c3d4e5f6.proxy-1")).await;
+    assert_eq!(seen, "a1b2c3d4e5f6.proxy-1");
+    assert_eq!(echoed, "a1b2c3d4e5f6.proxy-1");
+}
+
+#[tokio::test]
+async fn malformed_id_is_replaced() {
+    let long = "a".repeat(65);
+    for bad in ["has space", "quote\"d", long.as_str()] {
+        let (seen, echoed) = request_ids(Some(bad)).await;
+        assert!(uuid::Uuid::parse_str(&seen).is_ok(), "{bad} kept as {seen}");
+        assert_eq!(seen, echoed);
+    }
+}
@@ -0,0 +1,121 @@
+//! Integration tests for the security headers middleware.
+//!
+//! Tests verify:
+//! - Every response gets the security headers, with `frame-ancestors 'none'`
+//!   and no `'unsafe-inline'` scripts in the CSP
+//! - Marked inline scripts get the CSP's nonce, fresh per response; escaped
+//!   markers in page text are left alone
+//! - `304 Not Modified` responses keep the stored page's CSP by sending none
+//! - A media CDN origin is allowed for images and video, and only then
+//! - Every template's inline script is marked, and none uses an inline event
+//!   handler, which the CSP would block
+
+use api_adapters::axum::middleware::security_headers::{
+    security_headers_middleware, SecurityHeaders, NONCE_PLACEHOLDER,
+};
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+    middleware,
+    response::{Html, IntoResponse},
+    routing::get,
+    Json, Router,
+};
+use storage_adapters::media::cdn::MediaUrls;
+use tower::ServiceExt;
+
+const PAGE: &str = r#"<script nonce="__CSP_NONCE__">go()</script><p>nonce=&quot;__CSP_NONCE__&quot;</p><script src="/static/js/app.js"></script>"#;
+
+fn app() -> Router {
+    app_with(SecurityHeaders::default())
+}
+
+fn app_with(headers: SecurityHeaders) -> Router {
+    Router::new()
+        .route("/page", get(|| async { Html(PAGE) }))
+        .route("/json", get(|| async { Json(serde_json::json!({ "ok": true })).into_response() }))
+        .route("/unchanged", get(|| async { StatusCode::NOT_MODIFIED }))
+        .layer(middleware::from_fn_with_state(headers, security_headers_middleware))
+}
+
+/// The page's CSP nonce and body.
+async fn fetch_page() -> (String, String) {
+    let resp = app().oneshot(Request::get("/page").body(Body::empty()).unwrap()).await.unwrap();
+    let csp = resp.headers()["content-security-policy"].to_str().unwrap().to_owned();
+    let nonce = csp.split("'nonce-").nth(1).unwrap().split('\'').next().unwrap().to_owned();
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    (nonce, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[tokio::test]
+async fn every_response_gets_the_headers() {
+    let resp = app().oneshot(Request::get("/json").body(Body::empty()).unwrap()).await.unwrap();
+    let headers = resp.headers();
+    assert_eq!(headers["x-content-type-options"], "nosniff");
+    assert_eq!(headers["x-frame-options"], "DENY");
+    assert_eq!(headers["referrer-policy"], "strict-origin-when-cross-origin");
+    let csp = headers["content-security-policy"].to_str().unwrap();
+    assert!(csp.contains("frame-ancestors 'none'"));
+    let script_src = csp.split(';').find(|d| d.trim().starts_with("script-src")).unwrap();
+    assert!(!script_src.contains("unsafe-inline"));
+    assert_eq!(headers[header::CONTENT_TYPE], "application/json");
+}
+
+/// The sources `directive` of the CSP `app` sends.
+async fn csp_sources(app: Router, directive: &str) -> String {
+    let resp = app.oneshot(Request::get("/json").body(Body::empty()).unwrap()).await.unwrap();
+    let csp = resp.headers()["content-security-policy"].to_str().unwrap().to_owned();
+    csp.split(';').map(str::trim).find_map(|d| d.strip_prefix(directive)).unwrap().to_owned()
+}
+
+#[tokio::test]
+async fn media_cdn_origin_is_allowed_for_images_and_video() {
+    let cdn = app_with(SecurityHeaders::new(&MediaUrls::new("https://cdn.example.com/media")));
+    assert_eq!(csp_sources(cdn.clone(), "img-src ").await, "'self' data: blob: https://cdn.example.com");
+    assert_eq!(csp_sources(cdn, "media-src ").await, "'self' https://cdn.example.com");
+
+    let local = app_with(SecurityHeaders::new(&MediaUrls::default()));
+    assert_eq!(csp_sources(local.clone(), "img-src ").await, "'self' data: blob:");
+    assert_eq!(csp_sources(local, "media-src ").await, "'self'");
+}
+
+#[tokio::test]
+async fn marked_scripts_get_a_fresh_nonce() {
+    let (nonce, body) = fetch_page().await;
+    assert_eq!(nonce.len(), 32);
+    assert!(body.starts_with(&format!(r#"<script nonce="{nonce}">go()</script>"#)));
+    assert!(body.contains("<p>nonce=&quot;__CSP_NONCE__&quot;</p>"), "escaped text is not a marker");
+
+    let (other, _) = fetch_page().await;
+    assert_ne!(nonce, other);
+}
+
+#[tokio::test]
+async fn not_modified_responses_keep_the_stored_policy() {
+    let resp = app().oneshot(Request::get("/unchanged").body(Body::empty()).unwrap()).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::NOT_MODIFIED);
+    assert!(!resp.headers().contains_key("content-security-policy"));
+    assert_eq!(resp.headers()["x-frame-options"], "DENY");
+}
+
+#[test]
+fn templates_mark_inline_scripts_and_use_no_inline_handlers() {
+    let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/../api-adapters/templates");
+    let mut checked = 0;
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_none_or(|e| e != "html") {
+            continue;
+        }
+        let html = std::fs::read_to_string(&path).unwrap();
+        for tag in html.match_indices("<script").map(|(at, _)| &html[at..at + html[at..].find('>').unwrap()]) {
+            assert!(tag.contains(" src=") || tag.contains(NONCE_PLACEHOLDER), "{}: {tag}", path.display());
+        }
+        let lower = html.to_ascii_lowercase();
+        for handler in ["onclick=", "onsubmit=", "onchange=", "oninput=", "onerror=", "onload="] {
+            assert!(!lower.contains(&format!(" {handler}")), "{}: {handler}", path.display());
+        }
+        checked += 1;
+    }
+    assert!(checked > 10);
+}
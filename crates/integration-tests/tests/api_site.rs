@@ -0,0 +1,74 @@
+//! Integration tests for the site resolution middleware.
+//!
+//! Tests verify:
+//! - A request is served by the site claiming its `Host`, port and case aside
+//! - Unknown and missing hosts fall back to the default site
+//! - The site list is cached rather than re-read on every request
+//! - A repository failure is a 503, not another site's content
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use api_adapters::axum::middleware::site::{site_middleware, CurrentSite, SiteState};
+use axum::{body::Body, http::{Request, StatusCode}, middleware, routing::get, Router};
+use domains::errors::DomainError;
+use domains::models::{Site, SiteId};
+use domains::ports::MockSiteRepository;
+use storage_adapters::cache::SiteCache;
+use tower::ServiceExt;
+
+fn sites() -> Vec<Site> {
+    vec![
+        Site { name: "main".to_owned(), ..Site::fallback() },
+        Site { id: SiteId::new(), host: "other.example".to_owned(), name: "other".to_owned(), ..Site::fallback() },
+    ]
+}
+
+fn app(repo: MockSiteRepository) -> Router {
+    let state = SiteState {
+        repo:  Arc::new(repo),
+        cache: Arc::new(SiteCache::new(Duration::from_secs(60))),
+    };
+    Router::new()
+        .route("/", get(|CurrentSite(site): CurrentSite| async move { site.name }))
+        .layer(middleware::from_fn_with_state(state, site_middleware))
+}
+
+/// The status and the name of the site that served a request for `host`.
+async fn served_by(app: &Router, host: Option<&str>) -> (StatusCode, String) {
+    let mut req = Request::builder().uri("/");
+    if let Some(host) = host {
+        req = req.header("host", host);
+    }
+    let resp = app.clone().oneshot(req.body(Body::empty()).unwrap()).await.unwrap();
+    let status = resp.status();
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[tokio::test]
+async fn host_selects_its_site() {
+    let mut repo = MockSiteRepository::new();
+    repo.expect_find_all().times(1).returning(|| Ok(sites()));
+    let app = app(repo);
+
+    assert_eq!(served_by(&app, Some("Other.Example:8080")).await, (StatusCode::OK, "other".to_owned()));
+    assert_eq!(served_by(&app, Some("unknown.example")).await, (StatusCode::OK, "main".to_owned()));
+    assert_eq!(served_by(&app, None).await, (StatusCode::OK, "main".to_owned()));
+}
+
+#[tokio::test]
+async fn repository_failure_is_unavailable() {
+    let mut repo = MockSiteRepository::new();
+    repo.expect_find_all().returning(|| Err(DomainError::internal("db down")));
+
+    let (status, _) = served_by(&app(repo), Some("other.example")).await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+}
+
+#[tokio::test]
+async fn handlers_without_the_middleware_see_the_default_site() {
+    let app = Router::new().route("/", get(|CurrentSite(site): CurrentSite| async move { site.id.to_string() }));
+    let (_, id) = served_by(&app, Some("other.example")).await;
+    assert_eq!(id, SiteId::DEFAULT.to_string());
+}
@@ -0,0 +1,174 @@
+//! Integration tests for board statistics.
+//!
+//! Tests verify:
+//! - The board stats page lists the last 24 hours and 30 days, with totals
+//! - Admins get the site's daily activity and board totals as JSON; other
+//!   users cannot
+
+use std::sync::Arc;
+
+use api_adapters::axum::middleware::board_config::ExtractedBoardConfig;
+use api_adapters::axum::routes::stats_routes::{board_stats_routes, stats_routes};
+use async_trait::async_trait;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    Router,
+};
+use chrono::{DateTime, DurationRound, Utc};
+use domains::errors::DomainError;
+use domains::models::*;
+use domains::ports::StatsRepository;
+use services::board::{BoardError, BoardRepo};
+use services::stats::StatsService;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+/// Fixed buckets; only reads are used.
+#[derive(Clone, Default)]
+struct Buckets(Vec<StatsBucket>);
+
+#[async_trait]
+impl StatsRepository for Buckets {
+    async fn rollup(&self, _: DateTime<Utc>) -> Result<u64, DomainError> { unimplemented!() }
+    async fn board_series(
+        &self,
+        board_id: BoardId,
+        period: StatsPeriod,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<StatsBucket>, DomainError> {
+        Ok(self
+            .0
+            .iter()
+            .filter(|b| b.board_id == board_id && b.period == period && b.start >= since)
+            .cloned()
+            .collect())
+    }
+    async fn site_daily(&self, _: SiteId, since: DateTime<Utc>) -> Result<Vec<StatsBucket>, DomainError> {
+        Ok(self.0.iter().filter(|b| b.period == StatsPeriod::Day && b.start >= since).cloned().collect())
+    }
+}
+
+/// The only board there is; everything but `get_by_id` is unused.
+struct OneBoard(Board);
+
+#[async_trait]
+impl BoardRepo for OneBoard {
+    async fn create_board(&self, _: SiteId, _: &str, _: &str, _: &str) -> Result<Board, BoardError> { unimplemented!() }
+    async fn get_by_slug(&self, _: SiteId, _: &str) -> Result<Board, BoardError> { unimplemented!() }
+    async fn get_by_id(&self, id: BoardId) -> Result<Board, BoardError> {
+        if id == self.0.id { Ok(self.0.clone()) } else { Err(BoardError::NotFound { slug: id.to_string() }) }
+    }
+    async fn update_board(&self, _: BoardId, _: Option<&str>, _: Option<&str>) -> Result<Board, BoardError> { unimplemented!() }
+    async fn delete_board(&self, _: BoardId) -> Result<(), BoardError> { unimplemented!() }
+    async fn list_boards(&self, _: SiteId, _: Page) -> Result<Paginated<Board>, BoardError> { unimplemented!() }
+    async fn get_config(&self, _: BoardId) -> Result<BoardConfig, BoardError> { unimplemented!() }
+    async fn update_config(&self, _: BoardId, _: BoardConfig, _: domains::models::UserId) -> Result<BoardConfig, BoardError> { unimplemented!() }
+    async fn media_usage(&self, _: BoardId) -> Result<u64, BoardError> { unimplemented!() }
+    async fn list_volunteers(&self, _: BoardId) -> Result<Vec<(UserId, String, chrono::DateTime<Utc>)>, BoardError> { unimplemented!() }
+    async fn add_volunteer_by_username(&self, _: BoardId, _: &str, _: UserId) -> Result<(), BoardError> { unimplemented!() }
+    async fn remove_volunteer(&self, _: BoardId, _: UserId) -> Result<(), BoardError> { unimplemented!() }
+}
+
+fn make_board() -> Board {
+    Board {
+        id:         BoardId(Uuid::new_v4()),
+        site_id:    SiteId::DEFAULT,
+        slug:       Slug::new("b").unwrap(),
+        title:      "Random".to_owned(),
+        rules:      String::new(),
+        created_at: Utc::now(),
+    }
+}
+
+fn bucket(board_id: BoardId, period: StatsPeriod, start: DateTime<Utc>, posts: u64) -> StatsBucket {
+    StatsBucket { board_id, period, start, posts, unique_posters: 2, media_count: 1, media_bytes: 3 * 1024 * 1024 }
+}
+
+/// This hour and today with posts, plus a board deleted since.
+fn buckets(board_id: BoardId) -> Buckets {
+    let hour = Utc::now().duration_trunc(chrono::Duration::hours(1)).unwrap();
+    let day = Utc::now().duration_trunc(chrono::Duration::days(1)).unwrap();
+    Buckets(vec![
+        bucket(board_id, StatsPeriod::Hour, hour, 7),
+        bucket(board_id, StatsPeriod::Day, day, 12),
+        bucket(BoardId(Uuid::new_v4()), StatsPeriod::Day, day, 40),
+    ])
+}
+
+/// The stats routes, as in the composition root.
+fn app(board: Board, buckets: Buckets) -> Router {
+    let svc = Arc::new(StatsService::new(buckets));
+    let ctx = ExtractedBoardConfig {
+        board_id: board.id,
+        config:   BoardConfig::default(),
+        slug:     board.slug.clone(),
+        board:    board.clone(),
+    };
+    let board_scoped = board_stats_routes(svc.clone()).layer(axum::Extension(ctx));
+    stats_routes(svc, Arc::new(OneBoard(board))).merge(board_scoped)
+}
+
+fn request(uri: &str, role: Option<Role>) -> Request<Body> {
+    let mut req = Request::builder().uri(uri).body(Body::empty()).unwrap();
+    if let Some(role) = role {
+        req.extensions_mut().insert(CurrentUser::from_claims(Claims {
+            user_id: UserId(Uuid::new_v4()),
+            username: "staff".into(),
+            role,
+            owned_boards: vec![],
+            volunteer_boards: vec![],
+            exp: (Utc::now() + chrono::Duration::hours(24)).timestamp(),
+        }));
+    }
+    req
+}
+
+async fn body_text(resp: axum::response::Response) -> String {
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+#[tokio::test]
+async fn board_stats_page_lists_hours_and_days() {
+    let board = make_board();
+    let resp = app(board.clone(), buckets(board.id)).oneshot(request("/board/b/stats", None)).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let html = body_text(resp).await;
+    assert!(html.contains("Last 30 days — posts: 12, files: 1 (3.0 MB)"));
+    // 24 hours and 30 days, the busiest of each with a full bar.
+    assert_eq!(html.matches("class=\"stats-bar\"").count(), 24 + 30);
+    assert_eq!(html.matches("width:100%").count(), 2);
+}
+
+#[tokio::test]
+async fn admins_get_the_site_overview() {
+    let board = make_board();
+    let resp = app(board.clone(), buckets(board.id))
+        .oneshot(request("/admin/stats?days=7", Some(Role::Admin)))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let overview: serde_json::Value = serde_json::from_str(&body_text(resp).await).unwrap();
+    assert_eq!(overview["days"], 7);
+    let daily = overview["daily"].as_array().unwrap();
+    assert_eq!(daily.len(), 7);
+    assert_eq!(daily[6]["posts"], 52);
+    assert_eq!(daily[0]["posts"], 0);
+    // The deleted board is left out.
+    let boards = overview["boards"].as_array().unwrap();
+    assert_eq!(boards.len(), 1);
+    assert_eq!(boards[0]["slug"], "b");
+    assert_eq!(boards[0]["posts"], 12);
+    assert_eq!(boards[0]["media_bytes"], 3 * 1024 * 1024);
+}
+
+#[tokio::test]
+async fn only_admins_get_the_site_overview() {
+    let board = make_board();
+    let app = app(board.clone(), buckets(board.id));
+    let resp = app.clone().oneshot(request("/admin/stats", Some(Role::Janitor))).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    let resp = app.oneshot(request("/admin/stats", None)).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+}
@@ -0,0 +1,441 @@
+//! Integration tests for the thread page and its posts JSON.
+//!
+//! Tests verify:
+//! - `?quote=N` pre-fills the reply box with `>>N`
+//! - Post numbers link to the pre-filled reply form, so quoting works without
+//!   JavaScript; the `No.` part stays a permalink
+//! - Closed threads have no quote links
+//! - Auto-update appends replies from the posts JSON and has a "new posts"
+//!   indicator
+//! - `/posts?after=N` returns the later posts with rendered bodies and file
+//!   links, and no IP hash
+//! - Threads of another board are not found there
+//! - `/threads?ids=` lists the board's threads among the ids, without IP
+//!   hashes, and refuses malformed ids or too many of them
+//! - Shadowbanned posts are left out for everyone but their poster
+//! - Tombstones say who deleted them and keep their text for staff only;
+//!   the posts JSON marks them and drops their poster ID
+//! - A poster's own posts get a delete button
+//! - Posts edited by staff say so; staff get an edit button and a link to the
+//!   edit history, and the posts JSON carries `edited_at`
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use api_adapters::axum::i18n::I18n;
+use api_adapters::axum::middleware::board_config::ExtractedBoardConfig;
+use api_adapters::axum::routes::thread_routes::thread_routes;
+use api_adapters::axum::templates::{PostDisplay, ThreadTemplate};
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    response::IntoResponse,
+};
+use chrono::Utc;
+use domains::models::*;
+use services::thread::{ThreadError, ThreadRepo};
+use storage_adapters::media::cdn::MediaUrls;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+fn make_board() -> Board {
+    Board {
+        id:         BoardId(Uuid::new_v4()),
+        site_id:    SiteId::DEFAULT,
+        slug:       Slug::new("b").unwrap(),
+        title:      "Random".to_owned(),
+        rules:      String::new(),
+        created_at: Utc::now(),
+    }
+}
+
+fn make_thread(board_id: BoardId, closed: bool) -> Thread {
+    Thread {
+        id: ThreadId(Uuid::new_v4()), board_id, op_post_id: None, reply_count: 0,
+        bumped_at: Utc::now(), sticky: false, closed, cycle: false, created_at: Utc::now(),
+    }
+}
+
+fn make_post(thread_id: ThreadId, post_number: u64, body: &str) -> Post {
+    Post {
+        id: PostId(Uuid::new_v4()), thread_id, body: body.to_owned(),
+        ip_hash: IpHash::new("deadbeef".repeat(8)), name: None, email: None, tripcode: None,
+        created_at: Utc::now(), post_number, pinned: false, tags: vec![], commands: vec![],
+        subject: None, capcode: None, deleted: None, edited_at: None,
+    }
+}
+
+async fn render(closed: bool, quote: Option<u64>) -> String {
+    render_with(closed, quote, |_| {}).await
+}
+
+/// `render` with the template adjusted by `edit` first.
+async fn render_with(closed: bool, quote: Option<u64>, edit: impl FnOnce(&mut ThreadTemplate)) -> String {
+    let board = make_board();
+    let thread = make_thread(board.id, closed);
+    let post = make_post(thread.id, 7, "hello");
+    let pd = PostDisplay {
+        post,
+        poster_id:      "abcdef12".to_owned(),
+        attachments:    vec![],
+        capcode_role:   None,
+        capcode_css:    None,
+        tripcode_level: None,
+        ip_hash_short:  "deadbeefde".to_owned(),
+        body_html:      "hello".to_owned(),
+        reactions:      vec![],
+    };
+    let mut page = ThreadTemplate {
+        i18n: I18n::default(),
+        board,
+        thread,
+        posts: vec![pd],
+        is_closed: closed,
+        is_cycle: false,
+        viewer_role: None,
+        config: BoardConfig::default(),
+        media: MediaUrls::default(),
+        poll: None,
+        quote,
+        watched: false,
+        blotter: vec![],
+        own_ip_hash: None,
+    };
+    edit(&mut page);
+    let page = page.into_response();
+    let body = axum::body::to_bytes(page.into_body(), usize::MAX).await.unwrap();
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+#[tokio::test]
+async fn quote_prefills_the_reply_box() {
+    let html = render(false, Some(7)).await;
+    assert!(html.contains("\">>>7\n</textarea>"));
+}
+
+#[tokio::test]
+async fn reply_box_is_empty_without_quote() {
+    let html = render(false, None).await;
+    assert!(html.contains("\"></textarea>"));
+}
+
+#[tokio::test]
+async fn post_numbers_link_to_the_prefilled_form() {
+    let html = render(false, None).await;
+    assert!(html.contains(r##"<a href="#post-7" title="Link to this post">No.</a>"##));
+    assert!(html.contains(r##"<a href="?quote=7#top-reply-form" data-post-number="7""##));
+}
+
+#[tokio::test]
+async fn closed_threads_have_no_quote_links() {
+    let html = render(true, None).await;
+    assert!(!html.contains(r#"href="?quote="#));
+    assert!(html.contains(r##"<a href="#post-7" data-post-number="7""##));
+}
+
+#[tokio::test]
+async fn auto_update_appends_from_the_posts_json() {
+    let html = render(false, None).await;
+    assert!(html.contains(r#"id="rb-new-posts""#));
+    assert!(html.contains("'/posts?after=' + lastPostNumber"));
+}
+
+/// One thread with its posts and attachments.
+struct OneThread {
+    thread:      Thread,
+    posts:       Vec<Post>,
+    attachments: HashMap<PostId, Vec<Attachment>>,
+    /// An IP hash under a shadowban, whose posts only its own poster sees.
+    shadowbanned: Option<IpHash>,
+}
+
+#[async_trait::async_trait]
+impl ThreadRepo for OneThread {
+    async fn create_thread(&self, _: BoardId) -> Result<Thread, ThreadError> { unimplemented!() }
+    async fn list_threads(&self, _: BoardId, _: Page) -> Result<Paginated<Thread>, ThreadError> { unimplemented!() }
+    async fn get_catalog(&self, _: BoardId, _: CatalogSort, _: Option<&str>) -> Result<Vec<ThreadSummary>, ThreadError> {
+        unimplemented!()
+    }
+    async fn get_summaries(&self, board_id: BoardId, ids: &[ThreadId]) -> Result<Vec<ThreadSummary>, ThreadError> {
+        let listed = board_id == self.thread.board_id && ids.contains(&self.thread.id);
+        let op = &self.posts[0];
+        Ok(listed.then(|| ThreadSummary {
+            thread_id: self.thread.id, board_id, op_body: op.body.clone(), thumbnail_key: None,
+            thumbnail_spoiler: false, reply_count: self.posts.len() as u32 - 1, sticky: false,
+            closed: self.thread.closed, bumped_at: self.thread.bumped_at, op_name: None, op_tripcode: None,
+            op_subject: Some("hello".to_owned()), op_capcode: None, op_created_at: op.created_at,
+            op_post_number: op.post_number, op_ip_hash: op.ip_hash.clone(),
+        }).into_iter().collect())
+    }
+    async fn list_overboard_threads(&self, _: SiteId, _: Page) -> Result<Paginated<OverboardThread>, ThreadError> {
+        unimplemented!()
+    }
+    async fn get_thread(&self, id: ThreadId) -> Result<Thread, ThreadError> {
+        if id == self.thread.id { Ok(self.thread.clone()) } else { Err(ThreadError::NotFound { id: id.to_string() }) }
+    }
+    async fn list_posts(&self, _: ThreadId, _: Page) -> Result<Paginated<Post>, ThreadError> { unimplemented!() }
+    async fn list_all_posts(&self, _: ThreadId, viewer: &Viewer) -> Result<Vec<Post>, ThreadError> {
+        let visible = |p: &&Post| match viewer {
+            Viewer::Staff => true,
+            Viewer::Poster(own) if *own == p.ip_hash => true,
+            _ => self.shadowbanned.as_ref() != Some(&p.ip_hash),
+        };
+        Ok(self.posts.iter().filter(visible).cloned().collect())
+    }
+    async fn find_thread_id_by_post_number(&self, _: BoardId, _: u64) -> Result<Option<ThreadId>, ThreadError> {
+        unimplemented!()
+    }
+    async fn find_post_attachments(&self, ids: &[PostId]) -> Result<HashMap<PostId, Vec<Attachment>>, ThreadError> {
+        Ok(self.attachments.iter().filter(|(id, _)| ids.contains(id)).map(|(id, a)| (*id, a.clone())).collect())
+    }
+    async fn find_poll(&self, _: ThreadId) -> Result<Option<Poll>, ThreadError> { Ok(None) }
+    async fn find_reactions(&self, _: &[PostId]) -> Result<HashMap<PostId, Vec<ReactionCount>>, ThreadError> {
+        Ok(HashMap::new())
+    }
+    async fn mark_watched_seen(&self, _: WatcherToken, _: ThreadId, _: u64) -> Result<bool, ThreadError> { Ok(false) }
+    async fn find_blotter(&self, _: SiteId, _: BoardId) -> Result<Vec<Announcement>, ThreadError> { Ok(vec![]) }
+    async fn set_sticky(&self, _: ThreadId, _: bool) -> Result<(), ThreadError> { unimplemented!() }
+    async fn set_closed(&self, _: ThreadId, _: bool) -> Result<(), ThreadError> { unimplemented!() }
+    async fn prune_if_needed(&self, _: BoardId, _: u32) -> Result<u32, ThreadError> { unimplemented!() }
+}
+
+async fn get_posts(board: Board, repo: OneThread, uri: &str) -> (StatusCode, serde_json::Value) {
+    get_posts_from(board, repo, uri, None).await
+}
+
+/// `get_posts` as a visitor connecting from `peer`.
+async fn get_posts_from(
+    board: Board,
+    repo: OneThread,
+    uri: &str,
+    peer: Option<std::net::SocketAddr>,
+) -> (StatusCode, serde_json::Value) {
+    let ctx = ExtractedBoardConfig {
+        board_id: board.id,
+        config:   BoardConfig::default(),
+        slug:     board.slug.clone(),
+        board,
+    };
+    let app = thread_routes(Arc::new(repo)).layer(axum::Extension(ctx));
+    let mut req = Request::builder().uri(uri).body(Body::empty()).unwrap();
+    if let Some(addr) = peer {
+        req.extensions_mut().insert(axum::extract::ConnectInfo(addr));
+    }
+    let resp = app.oneshot(req).await.unwrap();
+    let status = resp.status();
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or_default())
+}
+
+fn thread_with_image(board_id: BoardId) -> OneThread {
+    let thread = make_thread(board_id, false);
+    let posts = vec![
+        make_post(thread.id, 1, "op"),
+        make_post(thread.id, 2, ">>1 **hi**"),
+        make_post(thread.id, 3, "third"),
+    ];
+    let image = Attachment {
+        id:            Uuid::new_v4(),
+        post_id:       posts[1].id,
+        filename:      "cat.png".to_owned(),
+        mime:          "image/png".to_owned(),
+        hash:          ContentHash::new("abc"),
+        size_kb:       12,
+        media_key:     MediaKey::new("b/cat.png"),
+        thumbnail_key: Some(MediaKey::new("b/cat_thumb.jpg")),
+        spoiler:       false,
+        duration_ms:   None,
+        page_count:    None,
+        md5:           None,
+        phash:         None,
+        meta:          None,
+    };
+    let attachments = HashMap::from([(posts[1].id, vec![image])]);
+    OneThread { thread, posts, attachments, shadowbanned: None }
+}
+
+#[tokio::test]
+async fn posts_after_returns_later_posts() {
+    let board = make_board();
+    let repo = thread_with_image(board.id);
+    let uri = format!("/board/b/thread/{}/posts?after=1", repo.thread.id);
+    let (status, json) = get_posts(board, repo, &uri).await;
+    assert_eq!(status, StatusCode::OK);
+    let posts = json.as_array().unwrap();
+    assert_eq!(posts.len(), 2);
+    assert_eq!(posts[0]["post_number"], 2);
+    assert!(posts[0]["body_html"].as_str().unwrap().contains(r#"class="post-quote-link""#));
+    assert_eq!(posts[0]["attachments"][0]["mime"], "image/png");
+    assert!(posts[0]["attachments"][0]["url"].as_str().unwrap().ends_with("b/cat.png"));
+    assert!(posts[0]["attachments"][0]["thumbnail_url"].as_str().unwrap().ends_with("b/cat_thumb.jpg"));
+    assert!(posts[0].get("ip_hash").is_none());
+    assert_eq!(posts[1]["post_number"], 3);
+}
+
+#[tokio::test]
+async fn posts_after_defaults_to_every_post() {
+    let board = make_board();
+    let repo = thread_with_image(board.id);
+    let uri = format!("/board/b/thread/{}/posts", repo.thread.id);
+    let (_, json) = get_posts(board, repo, &uri).await;
+    assert_eq!(json.as_array().unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn threads_of_another_board_are_not_found() {
+    let board = make_board();
+    let repo = thread_with_image(BoardId(Uuid::new_v4()));
+    let uri = format!("/board/b/thread/{}/posts", repo.thread.id);
+    let (status, _) = get_posts(board, repo, &uri).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+/// `thread_with_image` whose last post was made from `hash`, under a shadowban.
+fn thread_with_shadowbanned_reply(board_id: BoardId, hash: &IpHash) -> OneThread {
+    let mut repo = thread_with_image(board_id);
+    repo.posts[2].ip_hash = hash.clone();
+    repo.shadowbanned = Some(hash.clone());
+    repo
+}
+
+#[tokio::test]
+async fn shadowbanned_posts_are_shown_only_to_their_poster() {
+    let peer: std::net::SocketAddr = "203.0.113.9:0".parse().unwrap();
+    let daily_salt = Utc::now().format("%Y-%m-%d").to_string();
+    let hash = services::common::utils::hash_ip(&peer.ip().to_string(), &daily_salt);
+    let board = make_board();
+
+    let repo = thread_with_shadowbanned_reply(board.id, &hash);
+    let uri = format!("/board/b/thread/{}/posts", repo.thread.id);
+    let (_, json) = get_posts(board.clone(), repo, &uri).await;
+    assert_eq!(json.as_array().unwrap().len(), 2);
+
+    let repo = thread_with_shadowbanned_reply(board.id, &hash);
+    let uri = format!("/board/b/thread/{}/posts", repo.thread.id);
+    let (_, json) = get_posts_from(board, repo, &uri, Some(peer)).await;
+    assert_eq!(json.as_array().unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn bulk_threads_lists_the_boards_threads_among_the_ids() {
+    let board = make_board();
+    let repo = thread_with_image(board.id);
+    let id = repo.thread.id;
+    let uri = format!("/board/b/threads?ids={},{id}", Uuid::new_v4());
+    let (status, json) = get_posts(board, repo, &uri).await;
+    assert_eq!(status, StatusCode::OK);
+    let threads = json.as_array().unwrap();
+    assert_eq!(threads.len(), 1);
+    assert_eq!(threads[0]["thread_id"], id.to_string());
+    assert_eq!(threads[0]["url"], format!("/board/b/thread/{id}"));
+    assert_eq!(threads[0]["subject"], "hello");
+    assert_eq!(threads[0]["excerpt_html"], "op");
+    assert_eq!(threads[0]["reply_count"], 2);
+    assert!(threads[0].get("op_ip_hash").is_none());
+
+    let board = make_board();
+    let repo = thread_with_image(BoardId(Uuid::new_v4()));
+    let uri = format!("/board/b/threads?ids={}", repo.thread.id);
+    let (status, json) = get_posts(board, repo, &uri).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(json.as_array().unwrap().is_empty(), "threads of another board are left out");
+}
+
+#[tokio::test]
+async fn bulk_threads_refuses_bad_or_too_many_ids() {
+    let board = make_board();
+    let (status, _) = get_posts(board.clone(), thread_with_image(board.id), "/board/b/threads?ids=1,2").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let ids: Vec<_> = (0..=api_adapters::axum::handlers::thread_handlers::MAX_BULK_THREADS)
+        .map(|_| Uuid::new_v4().to_string())
+        .collect();
+    let uri = format!("/board/b/threads?ids={}", ids.join(","));
+    let (status, _) = get_posts(board.clone(), thread_with_image(board.id), &uri).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn tombstones_show_who_deleted_them() {
+    let tombstone = |by| Some(Tombstone { by, at: Utc::now() });
+    let html = render_with(false, None, |page| {
+        page.posts[0].post.deleted = tombstone(DeletedBy::User);
+        page.posts[0].post.redact();
+        page.posts[0].body_html.clear();
+    })
+    .await;
+    assert!(html.contains("[Deleted by the poster]"));
+    assert!(!html.contains("ID: abcdef12"));
+    assert!(!html.contains("/react"));
+
+    let html = render_with(false, None, |page| {
+        page.posts[0].post.deleted = tombstone(DeletedBy::Staff);
+        page.viewer_role = Some("admin".to_owned());
+    })
+    .await;
+    assert!(html.contains("[Deleted by staff]"));
+    assert!(html.contains("ID: abcdef12"));
+    assert!(html.contains(">hello</div>"));
+}
+
+#[tokio::test]
+async fn posts_json_marks_tombstones() {
+    let board = make_board();
+    let mut repo = thread_with_image(board.id);
+    repo.posts[2].deleted = Some(Tombstone { by: DeletedBy::Staff, at: Utc::now() });
+    repo.posts[2].redact();
+    let uri = format!("/board/b/thread/{}/posts?after=1", repo.thread.id);
+    let (_, json) = get_posts(board, repo, &uri).await;
+    assert_eq!(json[0]["deleted"], serde_json::Value::Null);
+    assert_eq!(json[1]["deleted"], "staff");
+    assert_eq!(json[1]["poster_id"], "");
+    assert_eq!(json[1]["body_html"], "");
+}
+
+#[tokio::test]
+async fn own_posts_get_a_delete_button() {
+    let html = render(false, None).await;
+    assert!(!html.contains("post-delete-form"));
+
+    let html = render_with(false, None, |page| page.own_ip_hash = Some(page.posts[0].post.ip_hash.clone())).await;
+    let action = format!("/board/b/post/{}/delete", html_post_id(&html));
+    assert!(html.contains(&action), "{action}");
+
+    let html = render_with(false, None, |page| page.own_ip_hash = Some(IpHash::new("someone else"))).await;
+    assert!(!html.contains("post-delete-form"));
+}
+
+#[tokio::test]
+async fn staff_edits_are_marked() {
+    let html = render(false, None).await;
+    assert!(!html.contains("Edited by staff"));
+
+    let html = render_with(false, None, |page| page.posts[0].post.edited_at = Some(Utc::now())).await;
+    assert!(html.contains("Edited by staff"));
+    assert!(!html.contains("/revisions"));
+    assert!(!html.contains(r#"data-action="E""#));
+
+    let html = render_with(false, None, |page| {
+        page.posts[0].post.edited_at = Some(Utc::now());
+        page.viewer_role = Some("moderator".to_owned());
+    })
+    .await;
+    let history = format!(r#"<a href="/mod/posts/{}/revisions""#, html_post_id(&html));
+    assert!(html.contains(&history), "{history}");
+    assert!(html.contains(r#"data-action="E""#));
+    assert!(html.contains(r#"data-body="hello""#));
+
+    let board = make_board();
+    let mut repo = thread_with_image(board.id);
+    repo.posts[1].edited_at = Some(Utc::now());
+    let uri = format!("/board/b/thread/{}/posts", repo.thread.id);
+    let (_, json) = get_posts(board, repo, &uri).await;
+    assert_eq!(json[0]["edited_at"], serde_json::Value::Null);
+    assert!(json[1]["edited_at"].is_string());
+}
+
+/// The `data-post-id` of the first post on a rendered page.
+fn html_post_id(html: &str) -> &str {
+    let start = html.find("data-post-id=\"").unwrap() + "data-post-id=\"".len();
+    &html[start..start + 36]
+}
@@ -0,0 +1,127 @@
+//! Integration tests for the moderator warning notice.
+//!
+//! Tests verify:
+//! - A visitor with a pending warning sees it, escaped, with its
+//!   acknowledgement form, on HTML pages
+//! - Other visitors, JSON responses, and acknowledged warnings get no notice
+//! - A warned visitor's conditional requests reach the page unconditionally
+
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+use api_adapters::axum::middleware::warning_notice::warning_notice_middleware;
+use async_trait::async_trait;
+use axum::{
+    body::Body,
+    extract::ConnectInfo,
+    http::{header, HeaderMap, Request, StatusCode},
+    middleware,
+    response::{Html, IntoResponse},
+    routing::get,
+    Json, Router,
+};
+use chrono::Utc;
+use domains::errors::DomainError;
+use domains::models::*;
+use domains::ports::WarningRepository;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+/// Warnings kept in memory.
+struct MemWarnings(Mutex<Vec<Warning>>);
+
+#[async_trait]
+impl WarningRepository for MemWarnings {
+    async fn find_pending_by_ip(&self, ip_hash: &IpHash) -> Result<Option<Warning>, DomainError> {
+        let warnings = self.0.lock().unwrap();
+        Ok(warnings.iter().find(|w| &w.ip_hash == ip_hash && w.acknowledged_at.is_none()).cloned())
+    }
+    async fn save(&self, _: &Warning) -> Result<BanId, DomainError> { unimplemented!() }
+    async fn acknowledge(&self, _: BanId, _: &IpHash) -> Result<(), DomainError> { unimplemented!() }
+}
+
+/// A warning for `ip` as the middleware hashes it today.
+fn warning(ip: &str, reason: &str) -> Warning {
+    let daily_salt = Utc::now().format("%Y-%m-%d").to_string();
+    Warning {
+        id:              BanId(Uuid::new_v4()),
+        ip_hash:         services::common::utils::hash_ip(ip, &daily_salt),
+        post_id:         None,
+        issued_by:       UserId(Uuid::new_v4()),
+        reason:          reason.to_owned(),
+        created_at:      Utc::now(),
+        acknowledged_at: None,
+    }
+}
+
+/// A page and a JSON endpoint under the warning middleware.
+fn app(warnings: Vec<Warning>) -> Router {
+    let repo: Arc<dyn WarningRepository> = Arc::new(MemWarnings(Mutex::new(warnings)));
+    Router::new()
+        .route("/page", get(|| async { Html("<header></header><!-- warning-notice --><main>hi</main>") }))
+        .route("/list.json", get(|| async { Json(serde_json::json!({ "items": [1, 2] })) }))
+        // Stands in for conditional_get_middleware: not modified for any validator.
+        .route("/cached", get(|headers: HeaderMap| async move {
+            if headers.contains_key(header::IF_NONE_MATCH) {
+                StatusCode::NOT_MODIFIED.into_response()
+            } else {
+                Html("<!-- warning-notice --><main>hi</main>").into_response()
+            }
+        }))
+        .layer(middleware::from_fn_with_state(repo, warning_notice_middleware))
+}
+
+fn get_from(uri: &str, ip: [u8; 4]) -> Request<Body> {
+    let mut req = Request::builder().uri(uri).body(Body::empty()).unwrap();
+    req.extensions_mut().insert(ConnectInfo(SocketAddr::from((ip, 1234))));
+    req
+}
+
+async fn body_text(resp: axum::response::Response) -> String {
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+#[tokio::test]
+async fn a_warned_visitor_sees_the_warning_on_pages() {
+    let warning = warning("203.0.113.7", "No <spam>");
+    let id = warning.id;
+    let app = app(vec![warning]);
+
+    let html = body_text(app.clone().oneshot(get_from("/page", [203, 0, 113, 7])).await.unwrap()).await;
+    assert!(html.starts_with("<header></header><div class=\"warning-notice\" role=\"alert\">"), "{html}");
+    assert!(html.contains("<blockquote class=\"ban-reason\">No &lt;spam&gt;</blockquote>"), "{html}");
+    assert!(html.contains(&format!("<form method=\"post\" action=\"/warning/{id}/ack\">")), "{html}");
+    assert!(html.ends_with("</div><main>hi</main>"), "{html}");
+
+    let json = body_text(app.oneshot(get_from("/list.json", [203, 0, 113, 7])).await.unwrap()).await;
+    assert_eq!(json, r#"{"items":[1,2]}"#);
+}
+
+#[tokio::test]
+async fn other_visitors_and_acknowledged_warnings_get_no_notice() {
+    let acknowledged = Warning { acknowledged_at: Some(Utc::now()), ..warning("198.51.100.1", "old") };
+    let app = app(vec![warning("203.0.113.7", "No spam"), acknowledged]);
+
+    for ip in [[198, 51, 100, 2], [198, 51, 100, 1]] {
+        let html = body_text(app.clone().oneshot(get_from("/page", ip)).await.unwrap()).await;
+        assert_eq!(html, "<header></header><!-- warning-notice --><main>hi</main>");
+    }
+}
+
+#[tokio::test]
+async fn a_warned_visitor_is_not_sent_not_modified() {
+    let app = app(vec![warning("203.0.113.7", "No spam")]);
+    let conditional = |ip: [u8; 4]| {
+        let mut req = get_from("/cached", ip);
+        req.headers_mut().insert(header::IF_NONE_MATCH, "W/\"abc\"".parse().unwrap());
+        req
+    };
+
+    let resp = app.clone().oneshot(conditional([203, 0, 113, 7])).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert!(body_text(resp).await.starts_with("<div class=\"warning-notice\""));
+
+    let resp = app.oneshot(conditional([198, 51, 100, 2])).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::NOT_MODIFIED);
+}
@@ -0,0 +1,189 @@
+//! Integration tests for the thread watcher routes.
+//!
+//! Tests verify:
+//! - The first watch hands out a `watcher` cookie and the thread shows up on
+//!   the watch list
+//! - The watch list shows unread reply counts, and is empty without a cookie
+//! - Unwatching from the list returns to it
+//! - Threads of another board cannot be watched there
+
+use std::sync::{Arc, Mutex};
+
+use api_adapters::axum::middleware::board_config::ExtractedBoardConfig;
+use api_adapters::axum::routes::watch_routes::{watch_list_routes, watch_routes};
+use async_trait::async_trait;
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+    Router,
+};
+use chrono::Utc;
+use domains::errors::DomainError;
+use domains::models::*;
+use domains::ports::{MockThreadRepository, WatchRepository};
+use services::watch::WatchService;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+/// Watches kept in memory; every watched thread has `latest` posts. Clones
+/// share the rows.
+#[derive(Clone, Default)]
+struct MemWatches {
+    rows:   Arc<Mutex<Vec<(WatcherToken, ThreadId, u64)>>>,
+    latest: u64,
+}
+
+#[async_trait]
+impl WatchRepository for MemWatches {
+    async fn watch(&self, watcher: WatcherToken, thread_id: ThreadId) -> Result<(), DomainError> {
+        let mut rows = self.rows.lock().unwrap();
+        if !rows.iter().any(|(w, t, _)| *w == watcher && *t == thread_id) {
+            rows.push((watcher, thread_id, self.latest));
+        }
+        Ok(())
+    }
+    async fn unwatch(&self, watcher: WatcherToken, thread_id: ThreadId) -> Result<(), DomainError> {
+        self.rows.lock().unwrap().retain(|(w, t, _)| !(*w == watcher && *t == thread_id));
+        Ok(())
+    }
+    async fn count(&self, watcher: WatcherToken) -> Result<u64, DomainError> {
+        Ok(self.rows.lock().unwrap().iter().filter(|(w, _, _)| *w == watcher).count() as u64)
+    }
+    async fn mark_seen(&self, _: WatcherToken, _: ThreadId, _: u64) -> Result<bool, DomainError> {
+        unimplemented!()
+    }
+    async fn list(&self, watcher: WatcherToken) -> Result<Vec<WatchedThread>, DomainError> {
+        Ok(self
+            .rows
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(w, _, _)| *w == watcher)
+            .map(|(_, thread_id, seen)| WatchedThread {
+                thread_id:  *thread_id,
+                board_slug: "b".to_owned(),
+                title:      "cats".to_owned(),
+                unread:     self.latest - seen,
+                bumped_at:  Utc::now(),
+            })
+            .collect())
+    }
+}
+
+fn make_board() -> Board {
+    Board {
+        id:         BoardId(Uuid::new_v4()),
+        site_id:    SiteId::DEFAULT,
+        slug:       Slug::new("b").unwrap(),
+        title:      "Random".to_owned(),
+        rules:      String::new(),
+        created_at: Utc::now(),
+    }
+}
+
+/// Threads that all live on `thread_board`.
+fn threads_on(thread_board: BoardId) -> MockThreadRepository {
+    let mut threads = MockThreadRepository::new();
+    threads.expect_find_by_id().returning(move |id| {
+        Ok(Thread {
+            id,
+            board_id: thread_board,
+            op_post_id: None,
+            reply_count: 0,
+            bumped_at: Utc::now(),
+            sticky: false,
+            closed: false,
+            cycle: false,
+            created_at: Utc::now(),
+        })
+    });
+    threads
+}
+
+fn app(board: Board, thread_board: BoardId, watches: MemWatches) -> Router {
+    let ctx = ExtractedBoardConfig {
+        board_id: board.id,
+        config:   BoardConfig::default(),
+        slug:     board.slug.clone(),
+        board,
+    };
+    let svc = Arc::new(WatchService::new(watches, threads_on(thread_board)));
+    watch_routes(svc.clone()).layer(axum::Extension(ctx)).merge(watch_list_routes(svc))
+}
+
+async fn send(app: &Router, method: &str, uri: &str, cookie: Option<&str>) -> axum::response::Response {
+    let mut req = Request::builder().method(method).uri(uri).header(header::ACCEPT, "text/html");
+    if let Some(cookie) = cookie {
+        req = req.header(header::COOKIE, cookie);
+    }
+    app.clone().oneshot(req.body(Body::empty()).unwrap()).await.unwrap()
+}
+
+async fn body_text(resp: axum::response::Response) -> String {
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+#[tokio::test]
+async fn first_watch_sets_the_cookie_and_lists_the_thread() {
+    let board = make_board();
+    let app = app(board.clone(), board.id, MemWatches::default());
+    let thread_id = ThreadId::new();
+
+    let resp = send(&app, "POST", &format!("/board/b/thread/{thread_id}/watch"), None).await;
+    assert_eq!(resp.status(), StatusCode::SEE_OTHER);
+    assert_eq!(resp.headers()[header::LOCATION], format!("/board/b/thread/{thread_id}"));
+    let set_cookie = resp.headers()[header::SET_COOKIE].to_str().unwrap().to_owned();
+    assert!(set_cookie.starts_with("watcher="));
+    assert!(set_cookie.contains("HttpOnly"));
+    let cookie = set_cookie.split(';').next().unwrap();
+
+    let html = body_text(send(&app, "GET", "/watched", Some(cookie)).await).await;
+    assert!(html.contains(&format!(r#"href="/board/b/thread/{thread_id}">cats</a>"#)));
+    assert!(html.contains("No new replies"));
+}
+
+#[tokio::test]
+async fn watch_list_shows_unread_replies() {
+    let board = make_board();
+    let watches = MemWatches { latest: 3, ..MemWatches::default() };
+    let watcher = WatcherToken::new();
+    watches.rows.lock().unwrap().push((watcher, ThreadId::new(), 0));
+    let app = app(board.clone(), board.id, watches);
+
+    let html = body_text(send(&app, "GET", "/watched", Some(&format!("watcher={watcher}"))).await).await;
+    assert!(html.contains("3 new replies"));
+    assert!(html.contains("watched-thread--unread"));
+}
+
+#[tokio::test]
+async fn watch_list_is_empty_without_a_cookie() {
+    let board = make_board();
+    let app = app(board.clone(), board.id, MemWatches::default());
+    let html = body_text(send(&app, "GET", "/watched", None).await).await;
+    assert!(html.contains("You are not watching any threads."));
+}
+
+#[tokio::test]
+async fn unwatching_from_the_list_returns_to_it() {
+    let board = make_board();
+    let watches = MemWatches::default();
+    let watcher = WatcherToken::new();
+    let thread_id = ThreadId::new();
+    watches.rows.lock().unwrap().push((watcher, thread_id, 0));
+    let app = app(board.clone(), board.id, watches.clone());
+
+    let cookie = format!("theme=dark; watcher={watcher}");
+    let resp = send(&app, "POST", &format!("/board/b/thread/{thread_id}/unwatch?list=true"), Some(&cookie)).await;
+    assert_eq!(resp.headers()[header::LOCATION], "/watched");
+    assert!(watches.rows.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn threads_of_another_board_cannot_be_watched() {
+    let board = make_board();
+    let app = app(board, BoardId::new(), MemWatches::default());
+    let resp = send(&app, "POST", &format!("/board/b/thread/{}/watch", ThreadId::new()), None).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    assert!(resp.headers().get(header::SET_COOKIE).is_none());
+}
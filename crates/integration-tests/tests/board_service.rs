@@ -17,6 +17,7 @@ use services::board::{BoardError, BoardService};
 fn sample_board(slug: &str) -> Board {
     Board {
         id:         BoardId::new(),
+        site_id:    domains::models::SiteId::DEFAULT,
         slug:       Slug::new(slug).unwrap(),
         title:      "Sample Board".to_owned(),
         rules:      "Be civil.".to_owned(),
@@ -33,7 +34,7 @@ async fn create_board_returns_board_with_correct_slug_and_title() {
     // Also need find_config for get_config call? No — create_board only calls save.
 
     let svc = BoardService::new(mock);
-    let board = svc.create_board("tech", "Technology", "No trolling").await.unwrap();
+    let board = svc.create_board(SiteId::DEFAULT, "tech", "Technology", "No trolling").await.unwrap();
     assert_eq!(board.slug.as_str(), "tech");
     assert_eq!(board.title, "Technology");
     assert_eq!(board.rules, "No trolling");
@@ -42,7 +43,7 @@ async fn create_board_returns_board_with_correct_slug_and_title() {
 #[tokio::test]
 async fn create_board_rejects_invalid_slug() {
     let svc = BoardService::new(MockBoardRepository::new());
-    let err = svc.create_board("My Board!", "Title", "").await.unwrap_err();
+    let err = svc.create_board(SiteId::DEFAULT, "My Board!", "Title", "").await.unwrap_err();
     assert!(matches!(err, BoardError::InvalidSlug { .. }));
 }
 
@@ -54,7 +55,7 @@ async fn create_board_propagates_repo_error() {
         .returning(|_| Err(DomainError::internal("db down")));
 
     let svc = BoardService::new(mock);
-    let err = svc.create_board("tech", "Technology", "").await.unwrap_err();
+    let err = svc.create_board(SiteId::DEFAULT, "tech", "Technology", "").await.unwrap_err();
     assert!(matches!(err, BoardError::Internal(_)));
 }
 
@@ -68,10 +69,10 @@ async fn get_by_slug_returns_existing_board() {
     let mut mock = MockBoardRepository::new();
     mock.expect_find_by_slug()
         .times(1)
-        .returning(move |_| Ok(returned.clone()));
+        .returning(move |_, _| Ok(returned.clone()));
 
     let svc = BoardService::new(mock);
-    let board = svc.get_by_slug("tech").await.unwrap();
+    let board = svc.get_by_slug(SiteId::DEFAULT, "tech").await.unwrap();
     assert_eq!(board.slug.as_str(), "tech");
 }
 
@@ -80,10 +81,10 @@ async fn get_by_slug_maps_not_found() {
     let mut mock = MockBoardRepository::new();
     mock.expect_find_by_slug()
         .times(1)
-        .returning(|_| Err(DomainError::not_found("board")));
+        .returning(|_, _| Err(DomainError::not_found("board")));
 
     let svc = BoardService::new(mock);
-    let err = svc.get_by_slug("nope").await.unwrap_err();
+    let err = svc.get_by_slug(SiteId::DEFAULT, "nope").await.unwrap_err();
     assert!(matches!(err, BoardError::NotFound { .. }));
 }
 
@@ -110,12 +111,12 @@ async fn get_by_id_returns_existing_board() {
 #[tokio::test]
 async fn list_boards_returns_empty_paginated_when_no_boards() {
     let mut mock = MockBoardRepository::new();
-    mock.expect_find_all()
+    mock.expect_find_by_site()
         .times(1)
-        .returning(|p| Ok(Paginated::new(vec![], 0, p, 15)));
+        .returning(|_, p| Ok(Paginated::new(vec![], 0, p, 15)));
 
     let svc = BoardService::new(mock);
-    let result = svc.list_boards(Page::new(1)).await.unwrap();
+    let result = svc.list_boards(SiteId::DEFAULT, Page::new(1)).await.unwrap();
     assert_eq!(result.total, 0);
     assert!(result.items.is_empty());
 }
@@ -126,12 +127,12 @@ async fn list_boards_returns_paginated_with_items() {
     let items = vec![board];
 
     let mut mock = MockBoardRepository::new();
-    mock.expect_find_all()
+    mock.expect_find_by_site()
         .times(1)
-        .returning(move |p| Ok(Paginated::new(items.clone(), 1, p, 15)));
+        .returning(move |_, p| Ok(Paginated::new(items.clone(), 1, p, 15)));
 
     let svc = BoardService::new(mock);
-    let result = svc.list_boards(Page::new(1)).await.unwrap();
+    let result = svc.list_boards(SiteId::DEFAULT, Page::new(1)).await.unwrap();
     assert_eq!(result.total, 1);
     assert_eq!(result.items.len(), 1);
 }
@@ -196,6 +196,71 @@ fn board_config_default_future_flags_off() {
     assert!(!cfg.federation_enabled);
 }
 
+#[test]
+fn board_config_default_formatting_on() {
+    let cfg = BoardConfig::default();
+    assert!(cfg.markup_enabled && cfg.spoilers_enabled && cfg.code_enabled && cfg.greentext_enabled);
+    assert!(!cfg.code_highlighting);
+    // Configs serialized before the toggles existed (e.g. cached) keep formatting on.
+    let mut json = serde_json::to_value(&cfg).unwrap();
+    for key in ["markup_enabled", "spoilers_enabled", "code_enabled", "greentext_enabled"] {
+        json.as_object_mut().unwrap().remove(key);
+    }
+    let old: BoardConfig = serde_json::from_value(json).unwrap();
+    assert!(old.markup_enabled && old.spoilers_enabled && old.code_enabled && old.greentext_enabled);
+}
+
+#[test]
+fn board_config_default_commands_off() {
+    assert!(!BoardConfig::default().commands_enabled);
+}
+
+#[test]
+fn board_config_default_polls_off() {
+    assert!(!BoardConfig::default().polls_enabled);
+}
+
+#[test]
+fn board_config_default_reactions_off() {
+    assert!(!BoardConfig::default().reactions_enabled);
+}
+
+#[test]
+fn reactions_round_trip_through_their_names() {
+    for reaction in Reaction::ALL {
+        assert_eq!(reaction.as_str().parse::<Reaction>(), Ok(reaction));
+        assert_eq!(serde_json::to_value(reaction).unwrap(), reaction.as_str());
+    }
+    assert!("thumbs_up".parse::<Reaction>().is_err());
+}
+
+#[test]
+fn poll_percentages_round_and_handle_no_votes() {
+    let option = |text: &str, votes| PollOption { text: text.to_owned(), votes };
+    let mut poll = Poll {
+        thread_id:  ThreadId::new(),
+        question:   "Tabs or spaces?".to_owned(),
+        options:    vec![option("tabs", 0), option("spaces", 0), option("both", 0)],
+        created_at: chrono::Utc::now(),
+    };
+    assert_eq!(poll.percent(&poll.options[0]), 0);
+    poll.options[0].votes = 1;
+    poll.options[1].votes = 2;
+    assert_eq!(poll.total_votes(), 3);
+    assert_eq!((poll.percent(&poll.options[0]), poll.percent(&poll.options[1])), (33, 67));
+}
+
+#[test]
+fn command_results_serialize_with_a_kind_tag() {
+    let roll = CommandResult::Roll { dice: 2, sides: 6, modifier: 1, rolls: vec![3, 5], total: 9 };
+    assert_eq!(roll.to_string(), "Rolled 2d6+1: 3, 5 = 9");
+    let json = serde_json::to_value(&roll).unwrap();
+    assert_eq!(json["kind"], "roll");
+    assert_eq!(serde_json::from_value::<CommandResult>(json).unwrap(), roll);
+    let fortune = CommandResult::Fortune { fortune: "Outlook good".to_owned() };
+    assert_eq!((fortune.kind(), fortune.to_string().as_str()), ("fortune", "Your fortune: Outlook good"));
+}
+
 #[test]
 fn board_config_default_max_threads() {
     let cfg = BoardConfig::default();
@@ -63,7 +63,7 @@ pub mod boards {
 
     /// Construct a `Board` with the given slug and a generated UUID.
     pub fn board(slug: &str) -> Board {
-        Board { id: BoardId(Uuid::new_v4()), slug: Slug::new(slug).unwrap(), title: format!("/{slug}/ — Test Board"), rules: "".to_owned(), created_at: Utc::now() }
+        Board { id: BoardId(Uuid::new_v4()), site_id: SiteId::DEFAULT, slug: Slug::new(slug).unwrap(), title: format!("/{slug}/ — Test Board"), rules: "".to_owned(), created_at: Utc::now() }
     }
 
     /// A pre-built board with slug `tech`.
@@ -125,7 +125,7 @@ pub mod users {
 
 /// Build a minimal `Board` with the given slug for use in handler tests.
 pub fn board_fixture(slug: &str) -> Board {
-    Board { id: BoardId(Uuid::new_v4()), slug: Slug::new(slug).unwrap(), title: format!("/{slug}/ — Test Board"), rules: "Be excellent.".to_owned(), created_at: Utc::now() }
+    Board { id: BoardId(Uuid::new_v4()), site_id: SiteId::DEFAULT, slug: Slug::new(slug).unwrap(), title: format!("/{slug}/ — Test Board"), rules: "Be excellent.".to_owned(), created_at: Utc::now() }
 }
 
 /// A default `BoardConfig` (all fields at their zero-restriction defaults).
@@ -144,7 +144,7 @@ pub fn thread_fixture(board_id: BoardId) -> Thread {
 
 /// Build a minimal text `Post` belonging to `thread_id`.
 pub fn post_fixture(thread_id: ThreadId) -> Post {
-    Post { id: PostId(Uuid::new_v4()), thread_id, body: "Test post body.".to_owned(), ip_hash: IpHash::new("deadbeef".repeat(8)), name: None, email: None, tripcode: None, created_at: Utc::now(), post_number: 1, pinned: false }
+    Post { id: PostId(Uuid::new_v4()), thread_id, body: "Test post body.".to_owned(), ip_hash: IpHash::new("deadbeef".repeat(8)), name: None, email: None, tripcode: None, created_at: Utc::now(), post_number: 1, pinned: false, tags: vec![], commands: vec![], subject: None, capcode: None, deleted: None, edited_at: None }
 }
 
 /// A bearer token with an invalid signature — triggers `401` on protected routes.
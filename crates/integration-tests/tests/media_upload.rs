@@ -9,6 +9,7 @@
 
 use api_adapters::axum::{
     middleware::board_config::ExtractedBoardConfig,
+    multipart::MultipartLimits,
     routes::post_routes::post_routes,
 };
 use axum::{
@@ -35,17 +36,24 @@ impl PostRepository for OkPostRepo {
         Ok(Paginated::new(vec![], 0, p, 15))
     }
     async fn find_by_ip_hash(&self, _: &IpHash) -> Result<Vec<Post>, DomainError> { Ok(vec![]) }
+    async fn find_by_ip_hashes(&self, _: domains::models::SiteId, _: &[IpHash], p: Page) -> Result<Paginated<OverboardPost>, DomainError> { Ok(Paginated::new(vec![], 0, p, 15)) }
     async fn find_recent_hashes(&self, _: BoardId, _: u32) -> Result<Vec<ContentHash>, DomainError> {
         Ok(vec![])
     }
     async fn save(&self, p: &Post) -> Result<(PostId, u64), DomainError> { Ok((p.id, 1)) }
-    async fn delete(&self, _: PostId) -> Result<(), DomainError> { Ok(()) }
+    async fn delete(&self, _: PostId, _: domains::models::DeletedBy) -> Result<(), DomainError> { Ok(()) }
     async fn delete_by_ip_in_thread(&self, _: &IpHash, _: ThreadId) -> Result<u64, DomainError> { Ok(0) }
+    async fn delete_by_ip_on_board(&self, _: &IpHash, _: BoardId, _: chrono::DateTime<chrono::Utc>) -> Result<domains::models::PurgedPosts, DomainError> { Ok(Default::default()) }
+    async fn purge_deleted(&self, _: u32) -> Result<u32, DomainError> { Ok(0) }
+    async fn edit(&self, _: PostId, _: &str, _: Option<String>, _: UserId) -> Result<(), DomainError> { Ok(()) }
+    async fn find_revisions(&self, _: PostId) -> Result<Vec<PostRevision>, DomainError> { Ok(vec![]) }
+    async fn move_posts(&self, _: &domains::models::PostMove) -> Result<(), DomainError> { Ok(()) }
     async fn save_attachments(&self, _: &[domains::models::Attachment]) -> Result<(), DomainError> { Ok(()) }
     async fn find_attachments_by_post_ids(&self, _: &[PostId]) -> Result<std::collections::HashMap<PostId, Vec<domains::models::Attachment>>, DomainError> { Ok(std::collections::HashMap::new()) }
-    async fn find_overboard(&self, p: Page) -> Result<Paginated<OverboardPost>, DomainError> {
+    async fn find_overboard(&self, _site: domains::models::SiteId, p: Page) -> Result<Paginated<OverboardPost>, DomainError> {
         Ok(Paginated::new(vec![], 0, p, 15))
     }
+    async fn find_site_activity(&self, _: domains::models::SiteId, _: u32) -> Result<domains::models::SiteActivity, domains::errors::DomainError> { Ok(domains::models::SiteActivity::default()) }
     async fn search_fulltext(
         &self,
         _board_id: BoardId,
@@ -55,11 +63,24 @@ impl PostRepository for OkPostRepo {
         Ok(Paginated::new(vec![], 0, p, 15))
     }
     async fn find_all_by_thread(&self, _: ThreadId) -> Result<Vec<Post>, DomainError> { Ok(vec![]) }
+    async fn find_visible_by_thread(&self, _: ThreadId, _: &domains::models::Viewer) -> Result<Vec<Post>, DomainError> { Ok(vec![]) }
     async fn find_thread_id_by_post_number(&self, _: BoardId, _: u64) -> Result<Option<ThreadId>, DomainError> { Ok(None) }
     async fn set_pinned(&self, _: domains::models::PostId, _: bool) -> Result<(), domains::errors::DomainError> { Ok(()) }
-    async fn find_oldest_unpinned_reply(&self, _: domains::models::ThreadId) -> Result<Option<domains::models::PostId>, domains::errors::DomainError> { Ok(None) }
+    async fn find_cycle_excess(&self, _: domains::models::ThreadId, _: u32) -> Result<Vec<domains::models::PostId>, domains::errors::DomainError> { Ok(vec![]) }
     async fn find_attachment_by_hash(&self, _: &domains::models::ContentHash) -> Result<Option<domains::models::Attachment>, domains::errors::DomainError> { Ok(None) }
     async fn delete_by_id(&self, _: domains::models::PostId) -> Result<(), domains::errors::DomainError> { Ok(()) }
+    async fn find_referenced_media_keys(&self) -> Result<std::collections::HashSet<domains::models::MediaKey>, domains::errors::DomainError> { Ok(Default::default()) }
+    async fn find_thumbnailed_attachments(&self) -> Result<Vec<(BoardId, domains::models::Attachment)>, domains::errors::DomainError> { Ok(vec![]) }
+    async fn find_all_attachments(&self) -> Result<Vec<(BoardId, domains::models::Attachment)>, domains::errors::DomainError> { Ok(vec![]) }
+    async fn find_visible_by_id(&self, _: domains::models::PostId) -> Result<Option<domains::models::Post>, domains::errors::DomainError> { Ok(None) }
+    async fn find_by_ids(&self, _: domains::models::ThreadId, _: &[domains::models::PostId]) -> Result<Vec<domains::models::Post>, domains::errors::DomainError> { Ok(vec![]) }
+    async fn find_by_numbers(&self, _: domains::models::ThreadId, _: &[u64]) -> Result<Vec<domains::models::Post>, domains::errors::DomainError> { Ok(vec![]) }
+    async fn find_quoting(&self, _: domains::models::ThreadId, _: &[u64]) -> Result<Vec<domains::models::Post>, domains::errors::DomainError> { Ok(vec![]) }
+    async fn find_media_mime(&self, _: &domains::models::MediaKey) -> Result<Option<String>, domains::errors::DomainError> { Ok(None) }
+    async fn set_thumbnail_key(&self, _: &domains::models::MediaKey, _: &domains::models::MediaKey) -> Result<u64, domains::errors::DomainError> { Ok(0) }
+    async fn restore(&self, _: &domains::models::Post) -> Result<(), domains::errors::DomainError> { Ok(()) }
+    async fn find_number_range(&self, _: domains::models::BoardId) -> Result<Option<domains::models::PostNumberRange>, domains::errors::DomainError> { Ok(None) }
+    async fn find_by_number_range(&self, _: domains::models::BoardId, _: u64, _: u64, _: u32) -> Result<Vec<domains::models::Post>, domains::errors::DomainError> { Ok(vec![]) }
 }
 
 struct OkThreadRepo;
@@ -80,7 +101,9 @@ impl ThreadRepository for OkThreadRepo {
     async fn find_by_board(&self, _: BoardId, p: Page) -> Result<Paginated<Thread>, DomainError> {
         Ok(Paginated::new(vec![], 0, p, 15))
     }
-    async fn find_catalog(&self, _: BoardId) -> Result<Vec<ThreadSummary>, DomainError> { Ok(vec![]) }
+    async fn find_catalog(&self, _: BoardId, _: CatalogSort) -> Result<Vec<ThreadSummary>, DomainError> { Ok(vec![]) }
+    async fn find_summaries(&self, _: BoardId, _: &[ThreadId]) -> Result<Vec<ThreadSummary>, DomainError> { Ok(vec![]) }
+    async fn find_overboard_threads(&self, _: domains::models::SiteId, p: Page) -> Result<Paginated<domains::models::OverboardThread>, DomainError> { Ok(Paginated::new(vec![], 0, p, 50)) }
     async fn save(&self, t: &Thread) -> Result<ThreadId, DomainError> { Ok(t.id) }
     async fn bump(&self, _: ThreadId, _: chrono::DateTime<Utc>) -> Result<(), DomainError> { Ok(()) }
     async fn set_op_post(&self, _: ThreadId, _: PostId) -> Result<(), DomainError> { Ok(()) }
@@ -102,18 +125,25 @@ impl BanRepository for NoBanRepo {
     async fn find_all(&self, p: Page) -> Result<Paginated<Ban>, DomainError> {
         Ok(Paginated::new(vec![], 0, p, 15))
     }
+    async fn delete_expired(&self, _: u32) -> Result<u32, DomainError> { Ok(0) }
 }
 
 /// Tracking `MediaStorage` stub — records the keys of every file stored.
 #[derive(Clone, Default)]
 struct TrackingStorage {
     stored: Arc<Mutex<Vec<String>>>,
+    /// Source paths handed to `store_file`.
+    files:  Arc<Mutex<Vec<std::path::PathBuf>>>,
 }
 
 impl TrackingStorage {
     fn stored_keys(&self) -> Vec<String> {
         self.stored.lock().unwrap().clone()
     }
+
+    fn stored_files(&self) -> Vec<std::path::PathBuf> {
+        self.files.lock().unwrap().clone()
+    }
 }
 
 #[async_trait::async_trait]
@@ -122,10 +152,19 @@ impl MediaStorage for TrackingStorage {
         self.stored.lock().unwrap().push(key.0.clone());
         Ok(())
     }
+    async fn store_file(&self, key: &MediaKey, path: &std::path::Path, _: &str) -> Result<(), DomainError> {
+        assert!(path.exists(), "spooled upload removed before it was stored");
+        self.stored.lock().unwrap().push(key.0.clone());
+        self.files.lock().unwrap().push(path.to_owned());
+        Ok(())
+    }
     async fn get_url(&self, _: &MediaKey, _: std::time::Duration) -> Result<String, DomainError> {
         Ok("http://localhost/stub".to_owned())
     }
     async fn delete(&self, _: &MediaKey) -> Result<(), DomainError> { Ok(()) }
+    async fn fetch(&self, k: &MediaKey) -> Result<Bytes, DomainError> { Err(DomainError::not_found(k.to_string())) }
+    async fn exists(&self, _: &MediaKey) -> Result<bool, DomainError> { Ok(false) }
+    async fn list(&self) -> Result<Vec<domains::ports::StoredMedia>, DomainError> { Ok(vec![]) }
 }
 
 struct AllowAllRateLimiter;
@@ -145,22 +184,30 @@ impl MediaProcessor for StubProcessor {
     async fn process(&self, input: RawMedia) -> Result<ProcessedMedia, DomainError> {
         let key = MediaKey(format!("uploads/{}.bin", Uuid::new_v4()));
         let thumb_key = MediaKey(format!("thumbs/{}.bin", Uuid::new_v4()));
-        let hash = ContentHash(crate::content_hash_from(&input.data));
+        let hash = ContentHash(crate::content_hash_from(input.data.len()));
         Ok(ProcessedMedia {
             original_key:   key,
             original_data:  input.data.clone(),
             thumbnail_key:  Some(thumb_key),
-            thumbnail_data: Some(input.data),
+            thumbnail_data: Some(Bytes::copy_from_slice(input.data.head())),
+            thumbnail_task: None,
             hash,
             size_kb:        0,
+            duration_ms:    None,
+            page_count:     None,
+            md5:            String::new(),
+            phash:          None,
+            width:          None,
+            height:         None,
+            stored_mime:    None,
         })
     }
     fn accepts(&self, _: &mime::Mime) -> bool { true }
 }
 
-fn content_hash_from(data: &Bytes) -> String {
+fn content_hash_from(len: u64) -> String {
     // Simple deterministic pseudo-hash for tests; not SHA-256.
-    format!("{:064x}", data.len() as u64 * 0xDEADBEEF)
+    format!("{:064x}", len * 0xDEADBEEF)
 }
 
 // ─── Helpers ─────────────────────────────────────────────────────────────────
@@ -168,6 +215,7 @@ fn content_hash_from(data: &Bytes) -> String {
 fn inject_ctx(mut req: Request<Body>, board_id: BoardId) -> Request<Body> {
     let board = Board {
         id:         board_id,
+        site_id:    domains::models::SiteId::DEFAULT,
         slug:       Slug::new("tech").unwrap(),
         title:      "/tech/ — Technology".to_owned(),
         rules:      "".to_owned(),
@@ -260,6 +308,32 @@ async fn post_with_jpeg_attachment_redirects_after_create() {
     assert!(!keys.is_empty(), "expected storage to record at least one file, got none");
 }
 
+#[tokio::test]
+async fn upload_is_stored_from_spooled_file_then_removed() {
+    let board_id = BoardId(Uuid::new_v4());
+    let storage = TrackingStorage::default();
+
+    let svc = Arc::new(PostService::new(
+        OkPostRepo, OkThreadRepo, NoBanRepo,
+        storage.clone(), AllowAllRateLimiter, StubProcessor, String::new(),
+    ));
+    let app = post_routes(svc);
+
+    let fake_jpeg = &[0xFF, 0xD8, 0xFF, 0xD9];
+    let req = inject_ctx(
+        file_post_req("tech", "spooled", "photo.jpg", "image/jpeg", fake_jpeg),
+        board_id,
+    );
+    let resp = app.oneshot(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::SEE_OTHER);
+
+    // The stub processor passes the upload through untouched, so the original
+    // is stored straight from the temp file — which is gone once the request ends.
+    let files = storage.stored_files();
+    assert_eq!(files.len(), 1, "expected the original to be stored from disk");
+    assert!(!files[0].exists(), "temp file {} was not cleaned up", files[0].display());
+}
+
 #[tokio::test]
 async fn post_with_disallowed_mime_is_rejected() {
     let board_id = BoardId(Uuid::new_v4());
@@ -289,6 +363,64 @@ async fn post_with_disallowed_mime_is_rejected() {
     );
 }
 
+#[tokio::test]
+async fn post_exceeding_board_max_file_size_is_rejected() {
+    let board_id = BoardId(Uuid::new_v4());
+    let storage = TrackingStorage::default();
+    let svc = Arc::new(PostService::new(
+        OkPostRepo, OkThreadRepo, NoBanRepo,
+        storage.clone(), AllowAllRateLimiter, StubProcessor, String::new(),
+    ));
+    let app = post_routes(svc);
+
+    let mut big_jpeg = vec![0xFF, 0xD8, 0xFF, 0xE0];
+    big_jpeg.resize(4 * 1024, 0);
+    let mut req = inject_ctx(
+        file_post_req("tech", "too big", "big.jpg", "image/jpeg", &big_jpeg),
+        board_id,
+    );
+    if let Some(ctx) = req.extensions_mut().get_mut::<ExtractedBoardConfig>() {
+        ctx.config.max_file_size = FileSizeKb(1);
+    }
+
+    let resp = app.oneshot(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let bytes = axum::body::to_bytes(resp.into_body(), 1 << 20).await.unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    assert!(
+        json["error"]["message"].as_str().unwrap_or("").contains("up to 1KB"),
+        "expected a friendly size message, got {json}"
+    );
+    assert!(storage.stored_keys().is_empty(), "oversized file must not be stored");
+}
+
+#[tokio::test]
+async fn upload_cap_applies_below_the_board_limit() {
+    let board_id = BoardId(Uuid::new_v4());
+    let storage = TrackingStorage::default();
+    let svc = Arc::new(PostService::new(
+        OkPostRepo, OkThreadRepo, NoBanRepo,
+        storage.clone(), AllowAllRateLimiter, StubProcessor, String::new(),
+    ));
+    let app = post_routes(svc);
+
+    let mut big_jpeg = vec![0xFF, 0xD8, 0xFF, 0xE0];
+    big_jpeg.resize(4 * 1024, 0);
+    let mut req = inject_ctx(
+        file_post_req("tech", "too big", "big.jpg", "image/jpeg", &big_jpeg),
+        board_id,
+    );
+    // The board allows 10 MB (the default); MAX_UPLOAD_KB=2 does not.
+    req.extensions_mut().insert(MultipartLimits { max_file_bytes: 2 * 1024, ..MultipartLimits::default() });
+
+    let resp = app.oneshot(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let bytes = axum::body::to_bytes(resp.into_body(), 1 << 20).await.unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    assert!(json["error"]["message"].as_str().unwrap_or("").contains("up to 2KB"), "{json}");
+    assert!(storage.stored_keys().is_empty());
+}
+
 #[tokio::test]
 async fn post_exceeding_max_files_is_rejected() {
     let board_id = BoardId(Uuid::new_v4());
@@ -332,3 +464,16 @@ async fn post_exceeding_max_files_is_rejected() {
         resp.status()
     );
 }
+
+#[tokio::test]
+async fn pending_thumbnail_gets_uncached_placeholder() {
+    use api_adapters::axum::handlers::media_handlers::pending_thumbnail;
+
+    let resp = pending_thumbnail("/abc_thumb.png".parse().unwrap()).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()[header::CONTENT_TYPE], "image/svg+xml");
+    assert_eq!(resp.headers()[header::CACHE_CONTROL], "no-store");
+
+    let resp = pending_thumbnail("/abc.png".parse().unwrap()).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+}
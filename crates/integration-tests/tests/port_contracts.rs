@@ -20,11 +20,13 @@ struct NoOpBoardRepo;
 impl BoardRepository for NoOpBoardRepo {
     async fn save(&self, _: &Board) -> Result<(), DomainError> { unimplemented!() }
     async fn find_by_id(&self, _: BoardId) -> Result<Board, DomainError> { unimplemented!() }
-    async fn find_by_slug(&self, _: &Slug) -> Result<Board, DomainError> { unimplemented!() }
+    async fn find_by_slug(&self, _site: domains::models::SiteId, _: &Slug) -> Result<Board, DomainError> { unimplemented!() }
+    async fn find_by_site(&self, _: domains::models::SiteId, _: Page) -> Result<Paginated<Board>, DomainError> { unimplemented!() }
     async fn find_all(&self, _: Page) -> Result<Paginated<Board>, DomainError> { unimplemented!() }
     async fn delete(&self, _: BoardId) -> Result<(), DomainError> { unimplemented!() }
     async fn find_config(&self, _: BoardId) -> Result<BoardConfig, DomainError> { unimplemented!() }
     async fn save_config(&self, _: BoardId, _: &BoardConfig) -> Result<(), DomainError> { unimplemented!() }
+    async fn media_usage_bytes(&self, _: BoardId) -> Result<u64, DomainError> { unimplemented!() }
 }
 
 #[async_trait::async_trait]
@@ -39,7 +41,9 @@ struct NoOpThreadRepo;
 impl ThreadRepository for NoOpThreadRepo {
     async fn find_by_id(&self, _: ThreadId) -> Result<Thread, DomainError> { unimplemented!() }
     async fn find_by_board(&self, _: BoardId, _: Page) -> Result<Paginated<Thread>, DomainError> { unimplemented!() }
-    async fn find_catalog(&self, _: BoardId) -> Result<Vec<ThreadSummary>, DomainError> { unimplemented!() }
+    async fn find_catalog(&self, _: BoardId, _: CatalogSort) -> Result<Vec<ThreadSummary>, DomainError> { unimplemented!() }
+    async fn find_summaries(&self, _: BoardId, _: &[ThreadId]) -> Result<Vec<ThreadSummary>, DomainError> { unimplemented!() }
+    async fn find_overboard_threads(&self, _: domains::models::SiteId, _: Page) -> Result<Paginated<domains::models::OverboardThread>, DomainError> { unimplemented!() }
     async fn save(&self, _: &Thread) -> Result<ThreadId, DomainError> { unimplemented!() }
     async fn bump(&self, _: ThreadId, _: DateTime<Utc>) -> Result<(), DomainError> { unimplemented!() }
     async fn set_op_post(&self, _: ThreadId, _: PostId) -> Result<(), DomainError> { unimplemented!() }
@@ -58,13 +62,20 @@ impl PostRepository for NoOpPostRepo {
     async fn find_by_id(&self, _: PostId) -> Result<Post, DomainError> { unimplemented!() }
     async fn find_by_thread(&self, _: ThreadId, _: Page) -> Result<Paginated<Post>, DomainError> { unimplemented!() }
     async fn find_by_ip_hash(&self, _: &IpHash) -> Result<Vec<Post>, DomainError> { unimplemented!() }
+    async fn find_by_ip_hashes(&self, _: domains::models::SiteId, _: &[IpHash], _: Page) -> Result<Paginated<OverboardPost>, DomainError> { unimplemented!() }
     async fn find_recent_hashes(&self, _: BoardId, _: u32) -> Result<Vec<ContentHash>, DomainError> { unimplemented!() }
     async fn save(&self, _: &Post) -> Result<(PostId, u64), DomainError> { unimplemented!() }
-    async fn delete(&self, _: PostId) -> Result<(), DomainError> { unimplemented!() }
+    async fn delete(&self, _: PostId, _: domains::models::DeletedBy) -> Result<(), DomainError> { unimplemented!() }
     async fn delete_by_ip_in_thread(&self, _: &IpHash, _: ThreadId) -> Result<u64, DomainError> { Ok(0) }
+    async fn delete_by_ip_on_board(&self, _: &IpHash, _: BoardId, _: chrono::DateTime<chrono::Utc>) -> Result<domains::models::PurgedPosts, DomainError> { Ok(Default::default()) }
+    async fn purge_deleted(&self, _: u32) -> Result<u32, DomainError> { unimplemented!() }
+    async fn edit(&self, _: PostId, _: &str, _: Option<String>, _: UserId) -> Result<(), DomainError> { unimplemented!() }
+    async fn find_revisions(&self, _: PostId) -> Result<Vec<PostRevision>, DomainError> { unimplemented!() }
+    async fn move_posts(&self, _: &domains::models::PostMove) -> Result<(), DomainError> { Ok(()) }
     async fn save_attachments(&self, _: &[domains::models::Attachment]) -> Result<(), DomainError> { Ok(()) }
     async fn find_attachments_by_post_ids(&self, _: &[PostId]) -> Result<std::collections::HashMap<PostId, Vec<domains::models::Attachment>>, DomainError> { Ok(std::collections::HashMap::new()) }
-    async fn find_overboard(&self, _: Page) -> Result<Paginated<OverboardPost>, DomainError> { unimplemented!() }
+    async fn find_overboard(&self, _site: domains::models::SiteId, _: Page) -> Result<Paginated<OverboardPost>, DomainError> { unimplemented!() }
+    async fn find_site_activity(&self, _: domains::models::SiteId, _: u32) -> Result<domains::models::SiteActivity, domains::errors::DomainError> { unimplemented!() }
     async fn search_fulltext(
         &self,
         _: BoardId,
@@ -72,11 +83,24 @@ impl PostRepository for NoOpPostRepo {
         _: Page,
     ) -> Result<Paginated<Post>, DomainError> { unimplemented!() }
     async fn find_all_by_thread(&self, _: ThreadId) -> Result<Vec<Post>, DomainError> { Ok(vec![]) }
+    async fn find_visible_by_thread(&self, _: ThreadId, _: &domains::models::Viewer) -> Result<Vec<Post>, DomainError> { Ok(vec![]) }
     async fn find_thread_id_by_post_number(&self, _: BoardId, _: u64) -> Result<Option<ThreadId>, DomainError> { Ok(None) }
     async fn set_pinned(&self, _: domains::models::PostId, _: bool) -> Result<(), domains::errors::DomainError> { Ok(()) }
-    async fn find_oldest_unpinned_reply(&self, _: domains::models::ThreadId) -> Result<Option<domains::models::PostId>, domains::errors::DomainError> { Ok(None) }
+    async fn find_cycle_excess(&self, _: domains::models::ThreadId, _: u32) -> Result<Vec<domains::models::PostId>, domains::errors::DomainError> { Ok(vec![]) }
     async fn find_attachment_by_hash(&self, _: &domains::models::ContentHash) -> Result<Option<domains::models::Attachment>, domains::errors::DomainError> { Ok(None) }
     async fn delete_by_id(&self, _: domains::models::PostId) -> Result<(), domains::errors::DomainError> { Ok(()) }
+    async fn find_referenced_media_keys(&self) -> Result<std::collections::HashSet<domains::models::MediaKey>, domains::errors::DomainError> { Ok(Default::default()) }
+    async fn find_thumbnailed_attachments(&self) -> Result<Vec<(BoardId, domains::models::Attachment)>, domains::errors::DomainError> { Ok(vec![]) }
+    async fn find_all_attachments(&self) -> Result<Vec<(BoardId, domains::models::Attachment)>, domains::errors::DomainError> { Ok(vec![]) }
+    async fn find_visible_by_id(&self, _: domains::models::PostId) -> Result<Option<domains::models::Post>, domains::errors::DomainError> { Ok(None) }
+    async fn find_by_ids(&self, _: domains::models::ThreadId, _: &[domains::models::PostId]) -> Result<Vec<domains::models::Post>, domains::errors::DomainError> { Ok(vec![]) }
+    async fn find_by_numbers(&self, _: domains::models::ThreadId, _: &[u64]) -> Result<Vec<domains::models::Post>, domains::errors::DomainError> { Ok(vec![]) }
+    async fn find_quoting(&self, _: domains::models::ThreadId, _: &[u64]) -> Result<Vec<domains::models::Post>, domains::errors::DomainError> { Ok(vec![]) }
+    async fn find_media_mime(&self, _: &domains::models::MediaKey) -> Result<Option<String>, domains::errors::DomainError> { Ok(None) }
+    async fn set_thumbnail_key(&self, _: &domains::models::MediaKey, _: &domains::models::MediaKey) -> Result<u64, domains::errors::DomainError> { Ok(0) }
+    async fn restore(&self, _: &domains::models::Post) -> Result<(), domains::errors::DomainError> { unimplemented!() }
+    async fn find_number_range(&self, _: domains::models::BoardId) -> Result<Option<domains::models::PostNumberRange>, domains::errors::DomainError> { unimplemented!() }
+    async fn find_by_number_range(&self, _: domains::models::BoardId, _: u64, _: u64, _: u32) -> Result<Vec<domains::models::Post>, domains::errors::DomainError> { unimplemented!() }
 }
 
 struct NoOpBanRepo;
@@ -86,6 +110,7 @@ impl BanRepository for NoOpBanRepo {
     async fn save(&self, _: &Ban) -> Result<BanId, DomainError> { unimplemented!() }
     async fn expire(&self, _: BanId) -> Result<(), DomainError> { unimplemented!() }
     async fn find_all(&self, _: Page) -> Result<Paginated<Ban>, DomainError> { unimplemented!() }
+    async fn delete_expired(&self, _: u32) -> Result<u32, DomainError> { unimplemented!() }
 }
 
 struct NoOpFlagRepo;
@@ -95,6 +120,7 @@ impl FlagRepository for NoOpFlagRepo {
     async fn find_pending(&self, _: Page) -> Result<Paginated<Flag>, DomainError> { unimplemented!() }
     async fn save(&self, _: &Flag) -> Result<FlagId, DomainError> { unimplemented!() }
     async fn resolve(&self, _: FlagId, _: FlagResolution, _: UserId) -> Result<(), DomainError> { unimplemented!() }
+    async fn count_pending_for_post(&self, _: PostId) -> Result<u64, DomainError> { unimplemented!() }
 }
 
 struct NoOpAuditRepo;
@@ -106,6 +132,7 @@ impl AuditRepository for NoOpAuditRepo {
     async fn find_by_target(&self, _: uuid::Uuid, _: Page) -> Result<Paginated<AuditEntry>, DomainError> { unimplemented!() }
     async fn find_all(&self, _: Page) -> Result<Paginated<AuditEntry>, DomainError> { unimplemented!() }
     async fn find_by_board(&self, _: BoardId, _: Page) -> Result<Paginated<AuditEntry>, DomainError> { unimplemented!() }
+    async fn find_public_by_board(&self, _: BoardId, _: Page) -> Result<Paginated<AuditEntry>, DomainError> { unimplemented!() }
 }
 
 struct NoOpUserRepo;
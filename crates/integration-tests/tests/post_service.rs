@@ -11,6 +11,9 @@
 //!  - Bump limit enforcement
 //!  - forced_anon strips the name field
 //!  - Spam filter rejection
+//!  - Hash-banned uploads (exact MD5 and near-identical perceptual hash)
+//!  - Media classifier labels, auto-spoiler and review flags
+//!  - Board media quotas (reject, prune oldest threads)
 
 use chrono::Utc;
 use domains::{
@@ -26,6 +29,9 @@ use services::post::{PostDraft, PostService, PostError};
 
 // ── Helpers ──────────────────────────────────────────────────────────────────
 
+/// Leading bytes of a JPEG file — enough for magic-byte sniffing.
+const JPEG_MAGIC: &[u8] = &[0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x10, b'J', b'F', b'I', b'F'];
+
 fn permissive_config() -> BoardConfig {
     BoardConfig {
         rate_limit_enabled:  false,
@@ -62,9 +68,15 @@ fn text_draft(board_id: BoardId, thread_id: Option<ThreadId>) -> PostDraft {
         raw_ip:  None,
         name:        None,
         email:       None,
+        subject:     None,
         files:       vec![],
+        spoiler:     false,
         is_staff:    false,
         poster_role: None,
+        media_prefix: String::new(),
+        tags:        vec![],
+        poll:        None,
+        via_api_key: false,
     }
 }
 
@@ -161,6 +173,7 @@ async fn banned_ip_is_rejected() {
             reason:     "spam".to_owned(),
             expires_at: None,
             created_at: Utc::now(),
+            shadow:     false,
         }))
     });
 
@@ -270,9 +283,11 @@ async fn too_many_files_is_rejected() {
     // Default max_files = 4; submit 5
     for _ in 0..5 {
         draft.files.push(RawMedia {
-            data:     Bytes::from_static(b"fake-png-data"),
+            data:     Bytes::from_static(b"fake-png-data").into(),
             mime:     IMAGE_PNG,
             filename: "img.png".to_owned(),
+            strip_metadata: true,
+            thumbnail:      None,
         });
     }
 
@@ -549,9 +564,9 @@ async fn post_repo_error_propagates() {
 // ── v1.2 feature tests ───────────────────────────────────────────────────────
 
 #[tokio::test]
-async fn cycle_thread_prunes_oldest_unpinned_reply_when_past_bump_limit() {
-    // When thread.cycle = true AND reply_count >= bump_limit, create_post must call
-    // find_oldest_unpinned_reply and then delete_by_id on the result.
+async fn cycle_thread_prunes_replies_past_the_window_and_keeps_bumping() {
+    // When thread.cycle = true AND reply_count >= bump_limit, create_post must ask
+    // for the excess beyond bump_limit replies, delete each one, and still bump.
     let board_id  = BoardId::new();
     let thread_id = ThreadId::new();
 
@@ -564,16 +579,18 @@ async fn cycle_thread_prunes_oldest_unpinned_reply_when_past_bump_limit() {
     };
     thread_mock.expect_find_by_id()
         .returning(move |_| Ok(cycle_thread.clone()));
-    let oldest_id = PostId::new();
+    thread_mock.expect_bump().times(1).returning(|_, _| Ok(()));
+    let excess = vec![PostId::new(), PostId::new()];
     let mut post_mock = MockPostRepository::new();
     post_mock.expect_find_recent_hashes().returning(|_, _| Ok(vec![]));
     post_mock.expect_save().returning(|_| Ok((PostId::new(), 1u64)));
     post_mock.expect_save_attachments().returning(|_| Ok(()));
-    post_mock.expect_find_oldest_unpinned_reply()
+    post_mock.expect_find_cycle_excess()
+        .withf(|_, keep| *keep == 500)
         .times(1)
-        .returning(move |_| Ok(Some(oldest_id)));
+        .returning(move |_, _| Ok(excess.clone()));
     post_mock.expect_delete_by_id()
-        .times(1)
+        .times(2)
         .returning(|_| Ok(()));
 
     let mut ban_mock = MockBanRepository::new();
@@ -617,6 +634,11 @@ async fn file_deduplication_reuses_existing_attachment_on_hash_match() {
             media_key:     domains::models::MediaKey("existing-key".into()),
             thumbnail_key: Some(domains::models::MediaKey("existing-thumb".into())),
             spoiler:       false,
+            duration_ms:   None,
+            page_count:    None,
+            md5:           None,
+            phash:         None,
+            meta:          None,
         })));
 
     let mut ban_mock = MockBanRepository::new();
@@ -630,11 +652,24 @@ async fn file_deduplication_reuses_existing_attachment_on_hash_match() {
     processor_mock.expect_process()
         .returning(|_| Ok(domains::ports::ProcessedMedia {
             original_key:   domains::models::MediaKey("processed-key".into()),
-            original_data:  bytes::Bytes::from(vec![0u8; 10]),
-            thumbnail_key:  None,
+            original_data:  bytes::Bytes::from(vec![0u8; 10]).into(),
+            thumbnail_key:  Some(domains::models::MediaKey("processed-thumb.png".into())),
             thumbnail_data: None,
+            // A duplicate must not queue a thumbnail for keys it never keeps.
+            thumbnail_task: Some(std::sync::Arc::new({
+                let mut task = domains::ports::MockThumbnailTask::new();
+                task.expect_submit().times(0);
+                task
+            })),
             hash:           domains::models::ContentHash("abc".into()),
             size_kb:        100,
+            duration_ms:    None,
+            page_count:     None,
+            md5:            String::new(),
+            phash:          None,
+            width:          None,
+            height:         None,
+            stored_mime:    None,
         }));
 
     let svc = make_service(post_mock, thread_mock, ban_mock,
@@ -644,9 +679,685 @@ async fn file_deduplication_reuses_existing_attachment_on_hash_match() {
     draft.files = vec![domains::ports::RawMedia {
         filename: "test.jpg".into(),
         mime:     mime::IMAGE_JPEG,
-        data:     bytes::Bytes::from(vec![0u8; 10]),
+        data:     bytes::Bytes::from_static(JPEG_MAGIC).into(),
+        strip_metadata: true,
+        thumbnail:      None,
     }];
 
     let result = svc.create_post(draft, &permissive_config()).await;
     assert!(result.is_ok(), "dedup post should succeed without re-uploading");
 }
+
+#[tokio::test]
+async fn new_upload_submits_the_deferred_thumbnail_under_the_site_prefix() {
+    let board_id  = BoardId::new();
+    let thread_id = ThreadId::new();
+
+    let mut thread_mock = MockThreadRepository::new();
+    thread_mock.expect_find_by_id()
+        .returning(move |_| Ok(open_thread(board_id, thread_id)));
+    thread_mock.expect_bump().returning(|_, _| Ok(()));
+
+    let mut post_mock = MockPostRepository::new();
+    post_mock.expect_find_recent_hashes().returning(|_, _| Ok(vec![]));
+    post_mock.expect_save().returning(|_| Ok((PostId::new(), 1u64)));
+    post_mock.expect_save_attachments().returning(|_| Ok(()));
+    post_mock.expect_find_attachment_by_hash().returning(|_| Ok(None));
+
+    let mut ban_mock = MockBanRepository::new();
+    ban_mock.expect_find_active_by_ip().returning(|_| Ok(None));
+
+    // Only the original is stored here; the queued task stores the thumbnail.
+    let mut media_mock = MockMediaStorage::new();
+    media_mock.expect_store()
+        .withf(|key, _, _| key.0 == "site/processed-key.jpg")
+        .times(1)
+        .returning(|_, _, _| Ok(()));
+
+    let mut task = domains::ports::MockThumbnailTask::new();
+    task.expect_submit()
+        .withf(|key| key.0 == "site/processed-key_thumb.png")
+        .times(1)
+        .returning(|_| Ok(None));
+    let task = std::sync::Arc::new(task);
+
+    let mut processor_mock = MockMediaProcessor::new();
+    processor_mock.expect_process()
+        .returning(move |_| Ok(domains::ports::ProcessedMedia {
+            original_key:   domains::models::MediaKey("processed-key.jpg".into()),
+            original_data:  bytes::Bytes::from(vec![0u8; 10]).into(),
+            thumbnail_key:  Some(domains::models::MediaKey("processed-key_thumb.png".into())),
+            thumbnail_data: None,
+            thumbnail_task: Some(task.clone()),
+            hash:           domains::models::ContentHash("abc".into()),
+            size_kb:        1,
+            duration_ms:    None,
+            page_count:     None,
+            md5:            String::new(),
+            phash:          None,
+            width:          None,
+            height:         None,
+            stored_mime:    None,
+        }));
+
+    let svc = make_service(post_mock, thread_mock, ban_mock,
+        media_mock, MockRateLimiter::new(), processor_mock);
+
+    let mut draft = text_draft(board_id, Some(thread_id));
+    draft.media_prefix = "site".into();
+    draft.files = vec![domains::ports::RawMedia {
+        filename: "test.jpg".into(),
+        mime:     mime::IMAGE_JPEG,
+        data:     bytes::Bytes::from_static(JPEG_MAGIC).into(),
+        strip_metadata: true,
+        thumbnail:      None,
+    }];
+
+    let result = svc.create_post(draft, &permissive_config()).await;
+    assert!(result.is_ok(), "{result:?}");
+}
+
+#[tokio::test]
+async fn board_metadata_opt_out_reaches_media_processor() {
+    // The board's strip_metadata setting overrides whatever the caller put on
+    // RawMedia before the file reaches the processor.
+    let board_id  = BoardId::new();
+    let thread_id = ThreadId::new();
+
+    let mut thread_mock = MockThreadRepository::new();
+    thread_mock.expect_find_by_id()
+        .returning(move |_| Ok(open_thread(board_id, thread_id)));
+
+    let mut post_mock = MockPostRepository::new();
+    post_mock.expect_find_recent_hashes().returning(|_, _| Ok(vec![]));
+
+    let mut ban_mock = MockBanRepository::new();
+    ban_mock.expect_find_active_by_ip().returning(|_| Ok(None));
+
+    let mut processor_mock = MockMediaProcessor::new();
+    processor_mock.expect_process()
+        .withf(|raw| !raw.strip_metadata)
+        .times(1)
+        .returning(|_| Err(domains::errors::DomainError::media_processing("stop after processor")));
+
+    let svc = make_service(post_mock, thread_mock, ban_mock,
+        MockMediaStorage::new(), MockRateLimiter::new(), processor_mock);
+
+    let mut draft = text_draft(board_id, Some(thread_id));
+    draft.files = vec![domains::ports::RawMedia {
+        filename: "photo.jpg".into(),
+        mime:     mime::IMAGE_JPEG,
+        data:     bytes::Bytes::from_static(JPEG_MAGIC).into(),
+        strip_metadata: true,
+        thumbnail:      None,
+    }];
+
+    let mut cfg = permissive_config();
+    cfg.strip_metadata = false;
+    let result = svc.create_post(draft, &cfg).await;
+    assert!(matches!(result, Err(PostError::MediaError { .. })));
+}
+
+#[tokio::test]
+async fn upload_with_spoofed_content_type_is_rejected() {
+    // An executable declared as image/jpeg must be rejected on its magic bytes
+    // before it ever reaches the media processor.
+    let board_id  = BoardId::new();
+    let thread_id = ThreadId::new();
+
+    let mut thread_mock = MockThreadRepository::new();
+    thread_mock.expect_find_by_id()
+        .returning(move |_| Ok(open_thread(board_id, thread_id)));
+
+    let mut post_mock = MockPostRepository::new();
+    post_mock.expect_find_recent_hashes().returning(|_, _| Ok(vec![]));
+
+    let mut ban_mock = MockBanRepository::new();
+    ban_mock.expect_find_active_by_ip().returning(|_| Ok(None));
+
+    let mut processor_mock = MockMediaProcessor::new();
+    processor_mock.expect_process().times(0);
+
+    let svc = make_service(post_mock, thread_mock, ban_mock,
+        MockMediaStorage::new(), MockRateLimiter::new(), processor_mock);
+
+    let mut draft = text_draft(board_id, Some(thread_id));
+    draft.files = vec![domains::ports::RawMedia {
+        filename: "totally-a-photo.jpg".into(),
+        mime:     mime::IMAGE_JPEG,
+        data:     bytes::Bytes::from_static(b"MZ\x90\x00\x03\x00\x00\x00\x04\x00").into(),
+        strip_metadata: true,
+        thumbnail:      None,
+    }];
+
+    let result = svc.create_post(draft, &permissive_config()).await;
+    assert!(matches!(result, Err(PostError::Validation { .. })));
+}
+
+#[tokio::test]
+async fn upload_type_is_taken_from_magic_bytes() {
+    // A PNG sent as application/octet-stream is identified by its content and
+    // checked against the board allowlist as image/png.
+    let board_id  = BoardId::new();
+    let thread_id = ThreadId::new();
+
+    let mut thread_mock = MockThreadRepository::new();
+    thread_mock.expect_find_by_id()
+        .returning(move |_| Ok(open_thread(board_id, thread_id)));
+
+    let mut post_mock = MockPostRepository::new();
+    post_mock.expect_find_recent_hashes().returning(|_, _| Ok(vec![]));
+
+    let mut ban_mock = MockBanRepository::new();
+    ban_mock.expect_find_active_by_ip().returning(|_| Ok(None));
+
+    let mut processor_mock = MockMediaProcessor::new();
+    processor_mock.expect_process()
+        .withf(|raw| raw.mime == mime::IMAGE_PNG)
+        .times(1)
+        .returning(|_| Err(domains::errors::DomainError::media_processing("stop after processor")));
+
+    let svc = make_service(post_mock, thread_mock, ban_mock,
+        MockMediaStorage::new(), MockRateLimiter::new(), processor_mock);
+
+    let mut draft = text_draft(board_id, Some(thread_id));
+    draft.files = vec![domains::ports::RawMedia {
+        filename: "upload".into(),
+        mime:     mime::APPLICATION_OCTET_STREAM,
+        data:     bytes::Bytes::from_static(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR").into(),
+        strip_metadata: true,
+        thumbnail:      None,
+    }];
+
+    let mut cfg = permissive_config();
+    cfg.allowed_mimes = vec!["image/png".to_owned()];
+    let result = svc.create_post(draft, &cfg).await;
+    assert!(matches!(result, Err(PostError::MediaError { .. })));
+}
+
+#[tokio::test]
+async fn spoiler_flag_is_stored_on_attachments() {
+    let board_id  = BoardId::new();
+    let thread_id = ThreadId::new();
+
+    let mut thread_mock = MockThreadRepository::new();
+    thread_mock.expect_find_by_id()
+        .returning(move |_| Ok(open_thread(board_id, thread_id)));
+    thread_mock.expect_bump().returning(|_, _| Ok(()));
+
+    let mut post_mock = MockPostRepository::new();
+    post_mock.expect_find_recent_hashes().returning(|_, _| Ok(vec![]));
+    post_mock.expect_save().returning(|_| Ok((PostId::new(), 1u64)));
+    post_mock.expect_find_attachment_by_hash().returning(|_| Ok(None));
+    post_mock.expect_save_attachments()
+        .withf(|atts| atts.len() == 1 && atts[0].spoiler)
+        .times(1)
+        .returning(|_| Ok(()));
+
+    let mut ban_mock = MockBanRepository::new();
+    ban_mock.expect_find_active_by_ip().returning(|_| Ok(None));
+
+    let mut media_mock = MockMediaStorage::new();
+    media_mock.expect_store().returning(|_, _, _| Ok(()));
+
+    let mut processor_mock = MockMediaProcessor::new();
+    processor_mock.expect_process()
+        .returning(|_| Ok(domains::ports::ProcessedMedia {
+            original_key:   domains::models::MediaKey("processed-key".into()),
+            original_data:  bytes::Bytes::from(vec![0u8; 10]).into(),
+            thumbnail_key:  None,
+            thumbnail_data: None,
+            thumbnail_task: None,
+            hash:           domains::models::ContentHash("abc".into()),
+            size_kb:        1,
+            duration_ms:    None,
+            page_count:     None,
+            md5:            String::new(),
+            phash:          None,
+            width:          None,
+            height:         None,
+            stored_mime:    None,
+        }));
+
+    let svc = make_service(post_mock, thread_mock, ban_mock,
+        media_mock, MockRateLimiter::new(), processor_mock);
+
+    let mut draft = text_draft(board_id, Some(thread_id));
+    draft.spoiler = true;
+    draft.files = vec![domains::ports::RawMedia {
+        filename: "reveal.jpg".into(),
+        mime:     mime::IMAGE_JPEG,
+        data:     bytes::Bytes::from_static(JPEG_MAGIC).into(),
+        strip_metadata: true,
+        thumbnail:      None,
+    }];
+
+    let result = svc.create_post(draft, &permissive_config()).await.unwrap();
+    assert!(result.attachments[0].spoiler);
+}
+
+#[tokio::test]
+async fn reencoded_original_is_stored_in_its_new_format() {
+    let board_id  = BoardId::new();
+    let thread_id = ThreadId::new();
+
+    let mut thread_mock = MockThreadRepository::new();
+    thread_mock.expect_find_by_id()
+        .returning(move |_| Ok(open_thread(board_id, thread_id)));
+    thread_mock.expect_bump().returning(|_, _| Ok(()));
+
+    let mut post_mock = MockPostRepository::new();
+    post_mock.expect_find_recent_hashes().returning(|_, _| Ok(vec![]));
+    post_mock.expect_save().returning(|_| Ok((PostId::new(), 1u64)));
+    post_mock.expect_find_attachment_by_hash().returning(|_| Ok(None));
+    post_mock.expect_save_attachments().returning(|_| Ok(()));
+
+    let mut ban_mock = MockBanRepository::new();
+    ban_mock.expect_find_active_by_ip().returning(|_| Ok(None));
+
+    let mut media_mock = MockMediaStorage::new();
+    media_mock.expect_store()
+        .withf(|_, _, content_type| content_type == "image/avif")
+        .times(1)
+        .returning(|_, _, _| Ok(()));
+
+    let mut processor_mock = MockMediaProcessor::new();
+    processor_mock.expect_process()
+        .returning(|_| Ok(domains::ports::ProcessedMedia {
+            original_key:   domains::models::MediaKey("processed-key.avif".into()),
+            original_data:  bytes::Bytes::from(vec![0u8; 10]).into(),
+            thumbnail_key:  None,
+            thumbnail_data: None,
+            thumbnail_task: None,
+            hash:           domains::models::ContentHash("abc".into()),
+            size_kb:        1,
+            duration_ms:    None,
+            page_count:     None,
+            md5:            String::new(),
+            phash:          None,
+            width:          Some(4),
+            height:         Some(4),
+            stored_mime:    Some("image/avif".into()),
+        }));
+
+    let svc = make_service(post_mock, thread_mock, ban_mock,
+        media_mock, MockRateLimiter::new(), processor_mock);
+
+    let mut draft = text_draft(board_id, Some(thread_id));
+    draft.files = vec![domains::ports::RawMedia {
+        filename: "photo.jpg".into(),
+        mime:     mime::IMAGE_JPEG,
+        data:     bytes::Bytes::from_static(JPEG_MAGIC).into(),
+        strip_metadata: true,
+        thumbnail:      None,
+    }];
+
+    let result = svc.create_post(draft, &permissive_config()).await.unwrap();
+    let attachment = &result.attachments[0];
+    assert_eq!(attachment.mime, "image/avif");
+    let meta = attachment.meta.as_ref().unwrap();
+    assert_eq!(meta.original_mime.as_deref(), Some("image/jpeg"));
+    assert_eq!(meta.original_filename, "photo.jpg");
+}
+
+/// A post service whose processor reports `md5`/`phash` for every upload.
+fn hash_ban_service(
+    board_id: BoardId,
+    thread_id: ThreadId,
+    md5: &'static str,
+    phash: u64,
+    bans: domains::ports::MockHashBanRepository,
+) -> PostService<
+    MockPostRepository, MockThreadRepository, MockBanRepository,
+    MockMediaStorage, MockRateLimiter, MockMediaProcessor,
+> {
+    let mut thread_mock = MockThreadRepository::new();
+    thread_mock.expect_find_by_id()
+        .returning(move |_| Ok(open_thread(board_id, thread_id)));
+    thread_mock.expect_bump().returning(|_, _| Ok(()));
+
+    let mut post_mock = MockPostRepository::new();
+    post_mock.expect_find_recent_hashes().returning(|_, _| Ok(vec![]));
+    post_mock.expect_find_attachment_by_hash().returning(|_| Ok(None));
+    post_mock.expect_save().returning(|_| Ok((PostId::new(), 1u64)));
+    post_mock.expect_save_attachments().returning(|_| Ok(()));
+
+    let mut ban_mock = MockBanRepository::new();
+    ban_mock.expect_find_active_by_ip().returning(|_| Ok(None));
+
+    let mut media_mock = MockMediaStorage::new();
+    media_mock.expect_store().returning(|_, _, _| Ok(()));
+
+    let mut processor_mock = MockMediaProcessor::new();
+    processor_mock.expect_process()
+        .returning(move |_| Ok(domains::ports::ProcessedMedia {
+            original_key:   domains::models::MediaKey("processed-key".into()),
+            original_data:  bytes::Bytes::from(vec![0u8; 10]).into(),
+            thumbnail_key:  None,
+            thumbnail_data: None,
+            thumbnail_task: None,
+            hash:           domains::models::ContentHash("abc".into()),
+            size_kb:        1,
+            duration_ms:    None,
+            page_count:     None,
+            md5:            md5.to_owned(),
+            phash:          Some(phash),
+            width:          None,
+            height:         None,
+            stored_mime:    None,
+        }));
+
+    make_service(post_mock, thread_mock, ban_mock,
+        media_mock, MockRateLimiter::new(), processor_mock)
+        .with_hash_bans(std::sync::Arc::new(bans), 6)
+}
+
+fn hash_ban(md5: Option<&str>, phash: Option<u64>) -> domains::models::HashBan {
+    domains::models::HashBan {
+        id:         BanId::new(),
+        md5:        md5.map(str::to_owned),
+        phash,
+        banned_by:  UserId::new(),
+        reason:     "spam image".to_owned(),
+        expires_at: None,
+        created_at: Utc::now(),
+    }
+}
+
+fn file_draft(board_id: BoardId, thread_id: ThreadId) -> PostDraft {
+    let mut draft = text_draft(board_id, Some(thread_id));
+    draft.files = vec![domains::ports::RawMedia {
+        filename: "spam.jpg".into(),
+        mime:     mime::IMAGE_JPEG,
+        data:     bytes::Bytes::from_static(JPEG_MAGIC).into(),
+        strip_metadata: true,
+        thumbnail:      None,
+    }];
+    draft
+}
+
+#[tokio::test]
+async fn md5_hash_ban_rejects_upload() {
+    let (board_id, thread_id) = (BoardId::new(), ThreadId::new());
+    let mut bans = domains::ports::MockHashBanRepository::new();
+    bans.expect_find_active_by_md5()
+        .withf(|md5| md5 == "d41d8cd98f00b204e9800998ecf8427e")
+        .returning(|md5| Ok(Some(hash_ban(Some(md5), None))));
+    let svc = hash_ban_service(board_id, thread_id, "d41d8cd98f00b204e9800998ecf8427e", 0, bans);
+
+    let result = svc.create_post(file_draft(board_id, thread_id), &permissive_config()).await;
+    assert!(matches!(result, Err(PostError::BannedMedia { reason }) if reason == "spam image"));
+}
+
+#[tokio::test]
+async fn near_identical_image_matches_perceptual_ban() {
+    let (board_id, thread_id) = (BoardId::new(), ThreadId::new());
+    let mut bans = domains::ports::MockHashBanRepository::new();
+    bans.expect_find_active_by_md5().returning(|_| Ok(None));
+    // Three bits away from the upload's hash — within the distance of 6.
+    bans.expect_find_active_perceptual()
+        .returning(|| Ok(vec![hash_ban(None, Some(0xFFFF_0000_FFFF_0007))]));
+    let svc = hash_ban_service(board_id, thread_id, "0123", 0xFFFF_0000_FFFF_0000, bans);
+
+    let result = svc.create_post(file_draft(board_id, thread_id), &permissive_config()).await;
+    assert!(matches!(result, Err(PostError::BannedMedia { .. })));
+}
+
+#[tokio::test]
+async fn distant_perceptual_hash_is_not_banned() {
+    let (board_id, thread_id) = (BoardId::new(), ThreadId::new());
+    let mut bans = domains::ports::MockHashBanRepository::new();
+    bans.expect_find_active_by_md5().returning(|_| Ok(None));
+    bans.expect_find_active_perceptual()
+        .returning(|| Ok(vec![hash_ban(None, Some(0x0000_FFFF_0000_FFFF))]));
+    let svc = hash_ban_service(board_id, thread_id, "0123", 0xFFFF_0000_FFFF_0000, bans);
+
+    let result = svc.create_post(file_draft(board_id, thread_id), &permissive_config()).await.unwrap();
+    assert_eq!(result.attachments[0].md5.as_deref(), Some("0123"));
+    assert_eq!(result.attachments[0].phash, Some(0xFFFF_0000_FFFF_0000));
+}
+
+/// A post service that classifies uploads with `classifier` and files review
+/// flags into `flags`.
+fn classifier_service(
+    board_id: BoardId,
+    thread_id: ThreadId,
+    classifier: domains::ports::MockMediaClassifier,
+    flags: domains::ports::MockFlagRepository,
+) -> PostService<
+    MockPostRepository, MockThreadRepository, MockBanRepository,
+    MockMediaStorage, MockRateLimiter, MockMediaProcessor,
+> {
+    let mut thread_mock = MockThreadRepository::new();
+    thread_mock.expect_find_by_id()
+        .returning(move |_| Ok(open_thread(board_id, thread_id)));
+    thread_mock.expect_bump().returning(|_, _| Ok(()));
+
+    let mut post_mock = MockPostRepository::new();
+    post_mock.expect_find_recent_hashes().returning(|_, _| Ok(vec![]));
+    post_mock.expect_find_attachment_by_hash().returning(|_| Ok(None));
+    post_mock.expect_save().returning(|_| Ok((PostId::new(), 1u64)));
+    post_mock.expect_save_attachments().returning(|_| Ok(()));
+
+    let mut ban_mock = MockBanRepository::new();
+    ban_mock.expect_find_active_by_ip().returning(|_| Ok(None));
+
+    let mut media_mock = MockMediaStorage::new();
+    media_mock.expect_store().returning(|_, _, _| Ok(()));
+
+    let mut processor_mock = MockMediaProcessor::new();
+    processor_mock.expect_process()
+        .returning(|_| Ok(domains::ports::ProcessedMedia {
+            original_key:   domains::models::MediaKey("processed-key".into()),
+            original_data:  bytes::Bytes::from(vec![0u8; 10]).into(),
+            thumbnail_key:  None,
+            thumbnail_data: None,
+            thumbnail_task: None,
+            hash:           domains::models::ContentHash("abc".into()),
+            size_kb:        1,
+            duration_ms:    None,
+            page_count:     None,
+            md5:            String::new(),
+            phash:          None,
+            width:          None,
+            height:         None,
+            stored_mime:    None,
+        }));
+
+    make_service(post_mock, thread_mock, ban_mock,
+        media_mock, MockRateLimiter::new(), processor_mock)
+        .with_classifier(std::sync::Arc::new(classifier), std::sync::Arc::new(flags))
+}
+
+fn nsfw_classifier(score: f32) -> domains::ports::MockMediaClassifier {
+    let mut classifier = domains::ports::MockMediaClassifier::new();
+    classifier.expect_classify().returning(move |_, _| {
+        Ok(vec![domains::models::MediaLabel { label: "porn".into(), score }])
+    });
+    classifier
+}
+
+fn classifier_config(action: domains::models::ClassifierAction) -> BoardConfig {
+    BoardConfig { classifier_action: action, classifier_threshold: 0.8, ..permissive_config() }
+}
+
+#[tokio::test]
+async fn classifier_spoiler_action_spoilers_flagged_upload() {
+    let (board_id, thread_id) = (BoardId::new(), ThreadId::new());
+    let svc = classifier_service(board_id, thread_id, nsfw_classifier(0.95), domains::ports::MockFlagRepository::new());
+
+    let config = classifier_config(domains::models::ClassifierAction::Spoiler);
+    let result = svc.create_post(file_draft(board_id, thread_id), &config).await.unwrap();
+    let attachment = &result.attachments[0];
+    assert!(attachment.spoiler);
+    assert_eq!(attachment.meta.as_ref().unwrap().labels[0].label, "porn");
+}
+
+#[tokio::test]
+async fn classifier_score_below_threshold_only_records_labels() {
+    let (board_id, thread_id) = (BoardId::new(), ThreadId::new());
+    // No flag expectations: filing one would panic.
+    let svc = classifier_service(board_id, thread_id, nsfw_classifier(0.3), domains::ports::MockFlagRepository::new());
+
+    let config = classifier_config(domains::models::ClassifierAction::Flag);
+    let result = svc.create_post(file_draft(board_id, thread_id), &config).await.unwrap();
+    let attachment = &result.attachments[0];
+    assert!(!attachment.spoiler);
+    assert_eq!(attachment.meta.as_ref().unwrap().labels.len(), 1);
+}
+
+#[tokio::test]
+async fn classifier_flag_action_files_review_flag() {
+    let (board_id, thread_id) = (BoardId::new(), ThreadId::new());
+    let mut flags = domains::ports::MockFlagRepository::new();
+    flags.expect_save()
+        .withf(|flag| flag.reason == "media classifier: porn (0.95)"
+            && flag.reporter_ip_hash.0 == services::post::CLASSIFIER_REPORTER)
+        .times(1)
+        .returning(|flag| Ok(flag.id));
+    let svc = classifier_service(board_id, thread_id, nsfw_classifier(0.95), flags);
+
+    let config = classifier_config(domains::models::ClassifierAction::Flag);
+    let result = svc.create_post(file_draft(board_id, thread_id), &config).await.unwrap();
+    assert!(!result.attachments[0].spoiler);
+}
+
+#[tokio::test]
+async fn classifier_failure_does_not_block_posting() {
+    let (board_id, thread_id) = (BoardId::new(), ThreadId::new());
+    let mut classifier = domains::ports::MockMediaClassifier::new();
+    classifier.expect_classify()
+        .returning(|_, _| Err(domains::errors::DomainError::internal("runner crashed")));
+    let svc = classifier_service(board_id, thread_id, classifier, domains::ports::MockFlagRepository::new());
+
+    let config = classifier_config(domains::models::ClassifierAction::Spoiler);
+    let result = svc.create_post(file_draft(board_id, thread_id), &config).await.unwrap();
+    assert!(result.attachments[0].meta.as_ref().unwrap().labels.is_empty());
+}
+
+/// A reply service on a board whose media usage is read from `boards`.
+fn quota_service(
+    board_id: BoardId,
+    thread_id: ThreadId,
+    thread_mock: MockThreadRepository,
+    boards: domains::ports::MockBoardRepository,
+) -> PostService<
+    MockPostRepository, MockThreadRepository, MockBanRepository,
+    MockMediaStorage, MockRateLimiter, MockMediaProcessor,
+> {
+    let mut thread_mock = thread_mock;
+    thread_mock.expect_find_by_id()
+        .returning(move |_| Ok(open_thread(board_id, thread_id)));
+    thread_mock.expect_bump().returning(|_, _| Ok(()));
+
+    let mut post_mock = MockPostRepository::new();
+    post_mock.expect_find_recent_hashes().returning(|_, _| Ok(vec![]));
+    post_mock.expect_find_attachment_by_hash().returning(|_| Ok(None));
+    post_mock.expect_save().returning(|_| Ok((PostId::new(), 1u64)));
+    post_mock.expect_save_attachments().returning(|_| Ok(()));
+
+    let mut ban_mock = MockBanRepository::new();
+    ban_mock.expect_find_active_by_ip().returning(|_| Ok(None));
+
+    let mut media_mock = MockMediaStorage::new();
+    media_mock.expect_store().returning(|_, _, _| Ok(()));
+
+    let mut processor_mock = MockMediaProcessor::new();
+    processor_mock.expect_process()
+        .returning(|_| Ok(domains::ports::ProcessedMedia {
+            original_key:   domains::models::MediaKey("processed-key".into()),
+            original_data:  bytes::Bytes::from(vec![0u8; 10]).into(),
+            thumbnail_key:  None,
+            thumbnail_data: None,
+            thumbnail_task: None,
+            hash:           domains::models::ContentHash("abc".into()),
+            size_kb:        1,
+            duration_ms:    None,
+            page_count:     None,
+            md5:            String::new(),
+            phash:          None,
+            width:          None,
+            height:         None,
+            stored_mime:    None,
+        }));
+
+    make_service(post_mock, thread_mock, ban_mock,
+        media_mock, MockRateLimiter::new(), processor_mock)
+        .with_media_quotas(std::sync::Arc::new(boards))
+}
+
+fn quota_config(action: domains::models::QuotaAction) -> BoardConfig {
+    BoardConfig { media_quota_mb: 1, quota_action: action, ..permissive_config() }
+}
+
+const ONE_MB: u64 = 1024 * 1024;
+
+#[tokio::test]
+async fn upload_within_media_quota_is_accepted() {
+    let (board_id, thread_id) = (BoardId::new(), ThreadId::new());
+    let mut boards = domains::ports::MockBoardRepository::new();
+    boards.expect_media_usage_bytes().returning(|_| Ok(1024));
+    let svc = quota_service(board_id, thread_id, MockThreadRepository::new(), boards);
+
+    let config = quota_config(domains::models::QuotaAction::Reject);
+    assert!(svc.create_post(file_draft(board_id, thread_id), &config).await.is_ok());
+}
+
+#[tokio::test]
+async fn upload_past_media_quota_is_rejected() {
+    let (board_id, thread_id) = (BoardId::new(), ThreadId::new());
+    let mut boards = domains::ports::MockBoardRepository::new();
+    boards.expect_media_usage_bytes().returning(|_| Ok(ONE_MB - 4));
+    // Reject never prunes.
+    let mut thread_mock = MockThreadRepository::new();
+    thread_mock.expect_delete().times(0);
+    let svc = quota_service(board_id, thread_id, thread_mock, boards);
+
+    let config = quota_config(domains::models::QuotaAction::Reject);
+    let result = svc.create_post(file_draft(board_id, thread_id), &config).await;
+    assert!(matches!(result, Err(PostError::QuotaExceeded { quota_bytes, .. }) if quota_bytes == ONE_MB));
+}
+
+#[tokio::test]
+async fn prune_quota_action_deletes_oldest_threads_until_upload_fits() {
+    let (board_id, thread_id) = (BoardId::new(), ThreadId::new());
+    let (oldest, next, newest) = (ThreadId::new(), ThreadId::new(), ThreadId::new());
+
+    let mut boards = domains::ports::MockBoardRepository::new();
+    // Full before pruning, still full after one thread, room after two.
+    let mut usage = vec![0, ONE_MB, ONE_MB];
+    boards.expect_media_usage_bytes().times(3).returning(move |_| Ok(usage.pop().unwrap()));
+
+    let mut thread_mock = MockThreadRepository::new();
+    thread_mock.expect_find_oldest_for_archive()
+        .returning(move |_, _| Ok(vec![
+            open_thread(board_id, oldest),
+            // The thread being replied to is never pruned.
+            open_thread(board_id, thread_id),
+            open_thread(board_id, next),
+            open_thread(board_id, newest),
+        ]));
+    thread_mock.expect_delete()
+        .withf(move |id| *id == oldest || *id == next)
+        .times(2)
+        .returning(|_| Ok(()));
+    let svc = quota_service(board_id, thread_id, thread_mock, boards);
+
+    let config = quota_config(domains::models::QuotaAction::Prune);
+    assert!(svc.create_post(file_draft(board_id, thread_id), &config).await.is_ok());
+}
+
+#[tokio::test]
+async fn prune_quota_action_rejects_when_pruning_is_not_enough() {
+    let (board_id, thread_id) = (BoardId::new(), ThreadId::new());
+    let mut boards = domains::ports::MockBoardRepository::new();
+    boards.expect_media_usage_bytes().returning(|_| Ok(ONE_MB));
+
+    let mut thread_mock = MockThreadRepository::new();
+    thread_mock.expect_find_oldest_for_archive().returning(|_, _| Ok(vec![]));
+    let svc = quota_service(board_id, thread_id, thread_mock, boards);
+
+    let config = quota_config(domains::models::QuotaAction::Prune);
+    let result = svc.create_post(file_draft(board_id, thread_id), &config).await;
+    assert!(matches!(result, Err(PostError::QuotaExceeded { .. })));
+}
@@ -0,0 +1,489 @@
+//! `rb-auth-oidc` — staff login through an OpenID Connect provider.
+//!
+//! For teams that already run single sign-on (Keycloak, Authentik, ...):
+//! staff log in at the provider, and the provider's groups decide their
+//! rusty-board role.
+//!
+//! - `GET /auth/oidc/login` redirects to the provider's authorization
+//!   endpoint (authorization code flow with PKCE, a `state` and a `nonce`,
+//!   kept in a short-lived cookie);
+//! - `GET /auth/oidc/callback` exchanges the code for an ID token, checks
+//!   its signature against the provider's published keys, its issuer,
+//!   audience, expiry and nonce, then maps its groups to a role and logs the
+//!   account in like a password login would, with the same `token` cookie.
+//!
+//! The account is the one linked to the token's issuer and subject (`iss`,
+//! `sub`), created on first login and named by the username claim. Accounts
+//! are never matched by name: a first login whose name is taken by another
+//! account, local or not, is refused with `409`. Its role follows the
+//! provider on every login: the highest role any of its groups maps to.
+//! Someone in no mapped group is refused. The password and TOTP checks are
+//! the provider's business.
+//!
+//! Configured under `[plugins.rb-auth-oidc]`:
+//!
+//! ```toml
+//! [plugins.rb-auth-oidc]
+//! issuer = "https://sso.example.com/realms/board"
+//! client_id = "rusty-board"
+//! client_secret = "..."
+//! redirect_url = "https://board.example.com/auth/oidc/callback"
+//!
+//! [plugins.rb-auth-oidc.roles]
+//! board-admins = "admin"
+//! board-janitors = "janitor"
+//! ```
+//!
+//! The provider is discovered from `{issuer}/.well-known/openid-configuration`
+//! on the first login, so an unreachable provider does not stop the server.
+
+pub mod provider;
+pub mod routes;
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use domains::models::{Claims, Role, Token};
+use domains::ports::{AuthProvider, UserRepository};
+use secrecy::SecretString;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use services::user::{UserError, UserService};
+use sha2::{Digest, Sha256};
+use url::Url;
+
+use crate::provider::Provider;
+
+/// Plugin name: its `[plugins.<name>]` section and its name in logs.
+pub const NAME: &str = "rb-auth-oidc";
+
+/// `[plugins.rb-auth-oidc]`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct OidcConfig {
+    /// Issuer URL; the provider's discovery document is under it.
+    pub issuer:         String,
+    pub client_id:      String,
+    pub client_secret:  SecretString,
+    /// This server's `/auth/oidc/callback`, as registered with the provider.
+    pub redirect_url:   String,
+    /// Scopes asked for. Default: `openid profile`. Some providers release
+    /// groups only for an extra scope, e.g. `groups`.
+    #[serde(default = "default_scopes")]
+    pub scopes:         Vec<String>,
+    /// ID token claim holding the username. Default: `preferred_username`.
+    #[serde(default = "default_username_claim")]
+    pub username_claim: String,
+    /// ID token claim holding the groups. Default: `groups`.
+    #[serde(default = "default_groups_claim")]
+    pub groups_claim:   String,
+    /// Provider group → rusty-board role (`admin`, `janitor`, `board_owner`,
+    /// `board_volunteer` or `user`).
+    #[serde(default)]
+    pub roles:          BTreeMap<String, String>,
+    /// Provider name on the login page button. Default: `SSO`.
+    #[serde(default = "default_label")]
+    pub label:          String,
+}
+
+fn default_scopes() -> Vec<String> {
+    vec!["openid".to_owned(), "profile".to_owned()]
+}
+
+fn default_username_claim() -> String {
+    "preferred_username".to_owned()
+}
+
+fn default_groups_claim() -> String {
+    "groups".to_owned()
+}
+
+fn default_label() -> String {
+    "SSO".to_owned()
+}
+
+/// Why a login through the provider failed.
+#[derive(Debug, thiserror::Error)]
+pub enum OidcError {
+    /// The provider could not be reached or answered nonsense.
+    #[error("identity provider: {0}")]
+    Provider(String),
+    /// The callback or the ID token did not check out.
+    #[error("login refused: {0}")]
+    Rejected(String),
+    /// The account is in none of the groups mapped to a role.
+    #[error("not a member of any group with a rusty-board role")]
+    NoRole,
+    #[error(transparent)]
+    Login(#[from] UserError),
+}
+
+/// Where logins through the provider end up. Implemented by `UserService`.
+#[async_trait]
+pub trait StaffSessions: Send + Sync + 'static {
+    /// Log in the account linked to `subject` at `issuer` with `role`,
+    /// creating it as `username` if there is none.
+    async fn sign_in(&self, issuer: &str, subject: &str, username: &str, role: Role)
+        -> Result<(Token, Claims), UserError>;
+}
+
+#[async_trait]
+impl<UR: UserRepository, AP: AuthProvider> StaffSessions for UserService<UR, AP> {
+    async fn sign_in(&self, issuer: &str, subject: &str, username: &str, role: Role)
+        -> Result<(Token, Claims), UserError>
+    {
+        self.login_external(issuer, subject, username, role).await
+    }
+}
+
+/// A login in progress: what the callback must match. Kept in a cookie
+/// between the redirect to the provider and the callback.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Flow {
+    pub state:    String,
+    pub nonce:    String,
+    /// PKCE code verifier; the provider saw only its hash.
+    pub verifier: String,
+}
+
+impl Flow {
+    fn new() -> Self {
+        Self { state: random_token(), nonce: random_token(), verifier: random_token() }
+    }
+
+    /// The cookie value: the three parts, dot-separated.
+    pub fn encode(&self) -> String {
+        format!("{}.{}.{}", self.state, self.nonce, self.verifier)
+    }
+
+    pub fn decode(value: &str) -> Option<Self> {
+        let mut parts = value.split('.');
+        let flow = Self {
+            state:    parts.next()?.to_owned(),
+            nonce:    parts.next()?.to_owned(),
+            verifier: parts.next()?.to_owned(),
+        };
+        (parts.next().is_none() && !flow.state.is_empty()).then_some(flow)
+    }
+}
+
+/// Staff login through one OpenID provider.
+pub struct OidcLogin {
+    config:   OidcConfig,
+    roles:    Vec<(String, Role)>,
+    provider: Provider,
+    sessions: Arc<dyn StaffSessions>,
+}
+
+impl OidcLogin {
+    /// Check `config` and set up the provider. Nothing is fetched yet.
+    pub fn new(config: OidcConfig, sessions: Arc<dyn StaffSessions>) -> Result<Self, OidcError> {
+        Url::parse(&config.issuer).map_err(|e| OidcError::Provider(format!("issuer: {e}")))?;
+        Url::parse(&config.redirect_url).map_err(|e| OidcError::Provider(format!("redirect_url: {e}")))?;
+        let roles = config
+            .roles
+            .iter()
+            .map(|(group, role)| Ok((group.clone(), role.parse::<Role>().map_err(OidcError::Provider)?)))
+            .collect::<Result<Vec<_>, OidcError>>()?;
+        let provider = Provider::new(&config.issuer, &config.client_id, config.client_secret.clone())?;
+        Ok(Self { config, roles, provider, sessions })
+    }
+
+    /// Provider name for the login page.
+    pub fn label(&self) -> &str {
+        &self.config.label
+    }
+
+    /// Whether the callback is served over HTTPS, so its cookies can be `Secure`.
+    pub fn is_https(&self) -> bool {
+        self.config.redirect_url.starts_with("https://")
+    }
+
+    /// Start a login: the provider URL to send the browser to, and the flow
+    /// to remember until the callback.
+    pub async fn begin(&self) -> Result<(String, Flow), OidcError> {
+        let discovery = self.provider.discovery().await?;
+        let flow = Flow::new();
+        let mut url = Url::parse(&discovery.authorization_endpoint)
+            .map_err(|e| OidcError::Provider(format!("authorization_endpoint: {e}")))?;
+        url.query_pairs_mut()
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &self.config.client_id)
+            .append_pair("redirect_uri", &self.config.redirect_url)
+            .append_pair("scope", &self.config.scopes.join(" "))
+            .append_pair("state", &flow.state)
+            .append_pair("nonce", &flow.nonce)
+            .append_pair("code_challenge", &pkce_challenge(&flow.verifier))
+            .append_pair("code_challenge_method", "S256");
+        Ok((url.into(), flow))
+    }
+
+    /// Finish a login from the callback's `code` and `state`.
+    pub async fn complete(&self, code: &str, state: &str, flow: &Flow) -> Result<(Token, Claims), OidcError> {
+        if state != flow.state {
+            return Err(OidcError::Rejected("state does not match".to_owned()));
+        }
+        let id_token = self.provider.exchange(code, &flow.verifier, &self.config.redirect_url).await?;
+        let claims = self.provider.verify(&id_token).await?;
+        if claims.get("nonce").and_then(Value::as_str) != Some(flow.nonce.as_str()) {
+            return Err(OidcError::Rejected("nonce does not match".to_owned()));
+        }
+        let username = claims
+            .get(&self.config.username_claim)
+            .and_then(Value::as_str)
+            .map(local_username)
+            .ok_or_else(|| OidcError::Rejected(format!("no {} claim", self.config.username_claim)))?;
+        let (Some(issuer), Some(subject)) = (claims.get("iss").and_then(Value::as_str), claims.get("sub").and_then(Value::as_str))
+        else {
+            return Err(OidcError::Rejected("no iss or sub claim".to_owned()));
+        };
+        let role = self.role_of(&claims).ok_or(OidcError::NoRole)?;
+        let session = self.sessions.sign_in(issuer, subject, &username, role).await?;
+        tracing::info!(plugin = NAME, username = %username, role = %role, "staff logged in through the identity provider");
+        Ok(session)
+    }
+
+    /// The highest role any of the token's groups maps to.
+    fn role_of(&self, claims: &Map<String, Value>) -> Option<Role> {
+        let groups: Vec<&str> = match claims.get(&self.config.groups_claim) {
+            Some(Value::Array(groups)) => groups.iter().filter_map(Value::as_str).collect(),
+            Some(Value::String(group)) => vec![group.as_str()],
+            _ => vec![],
+        };
+        self.roles
+            .iter()
+            .filter(|(group, _)| groups.contains(&group.as_str()))
+            .map(|(_, role)| *role)
+            .max_by_key(|role| rank(*role))
+    }
+}
+
+/// Roles from least to most privileged.
+fn rank(role: Role) -> u8 {
+    match role {
+        Role::User           => 0,
+        Role::BoardVolunteer => 1,
+        Role::BoardOwner     => 2,
+        Role::Janitor        => 3,
+        Role::Admin          => 4,
+    }
+}
+
+/// A provider username as a local one: characters other than letters, digits
+/// and `_` become `_`, and it is cut to 32 characters.
+fn local_username(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' { c } else { '_' })
+        .take(32)
+        .collect()
+}
+
+/// The PKCE `S256` challenge for `verifier` (RFC 7636 §4.2).
+fn pkce_challenge(verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+}
+
+fn random_token() -> String {
+    URL_SAFE_NO_PAD.encode(rand::random::<[u8; 32]>())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use axum::body::Body;
+    use axum::extract::{Form, State};
+    use axum::http::{header, Request, StatusCode};
+    use axum::routing::{get, post};
+    use axum::Json;
+    use domains::models::UserId;
+    use jsonwebtoken::{EncodingKey, Header};
+    use serde_json::json;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    /// The Ed25519 key of RFC 8037 appendix A, as PKCS#8 DER and as a JWK.
+    const TEST_SEED: &str = "nWGxne_9WmC6hEr0kuwsxERJxWl7MmkZcDusAxyuf2A";
+    const TEST_X: &str = "11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo";
+
+    fn signing_key() -> EncodingKey {
+        let mut der = vec![0x30, 0x2e, 0x02, 0x01, 0x00, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x04, 0x22, 0x04, 0x20];
+        der.extend(URL_SAFE_NO_PAD.decode(TEST_SEED).unwrap());
+        EncodingKey::from_ed_der(&der)
+    }
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<(String, String, Role)>>);
+
+    #[async_trait]
+    impl StaffSessions for Recorder {
+        async fn sign_in(&self, _issuer: &str, subject: &str, username: &str, role: Role)
+            -> Result<(Token, Claims), UserError>
+        {
+            self.0.lock().unwrap().push((subject.to_owned(), username.to_owned(), role));
+            let claims = Claims {
+                user_id: UserId::new(), username: username.to_owned(), role, owned_boards: vec![],
+                volunteer_boards: vec![], exp: chrono::Utc::now().timestamp() + 3600,
+            };
+            Ok((Token::new("session-token"), claims))
+        }
+    }
+
+    /// What the provider puts in the ID token it issues next.
+    #[derive(Clone)]
+    struct Issued {
+        issuer: String,
+        claims: Arc<Mutex<Value>>,
+        /// The PKCE challenge the login started with.
+        challenge: Arc<Mutex<String>>,
+    }
+
+    /// A provider issuing ID tokens signed with the RFC 8037 key.
+    async fn provider() -> Issued {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let issuer = format!("http://{}", listener.local_addr().unwrap());
+        let issued = Issued {
+            issuer: issuer.clone(),
+            claims: Arc::new(Mutex::new(Value::Null)),
+            challenge: Arc::new(Mutex::new(String::new())),
+        };
+        let discovery = json!({
+            "issuer": issuer,
+            "authorization_endpoint": format!("{issuer}/authorize"),
+            "token_endpoint": format!("{issuer}/token"),
+            "jwks_uri": format!("{issuer}/jwks"),
+        });
+        let jwks = json!({ "keys": [{ "kty": "OKP", "crv": "Ed25519", "x": TEST_X, "kid": "k1", "alg": "EdDSA" }] });
+        let app = axum::Router::new()
+            .route("/.well-known/openid-configuration", get(move || async move { Json(discovery) }))
+            .route("/jwks", get(move || async move { Json(jwks) }))
+            .route("/token", post(token))
+            .with_state(issued.clone());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        issued
+    }
+
+    async fn token(State(issued): State<Issued>, Form(form): Form<BTreeMap<String, String>>) -> axum::response::Response {
+        use axum::response::IntoResponse;
+        if form.get("code").map(String::as_str) != Some("the-code")
+            || pkce_challenge(&form["code_verifier"]) != *issued.challenge.lock().unwrap()
+        {
+            return (StatusCode::BAD_REQUEST, Json(json!({ "error": "invalid_grant" }))).into_response();
+        }
+        let claims = issued.claims.lock().unwrap().clone();
+        let header = Header { kid: Some("k1".to_owned()), ..Header::new(jsonwebtoken::Algorithm::EdDSA) };
+        let id_token = jsonwebtoken::encode(&header, &claims, &signing_key()).unwrap();
+        Json(json!({ "access_token": "at", "token_type": "Bearer", "id_token": id_token })).into_response()
+    }
+
+    fn config(issuer: &str) -> OidcConfig {
+        OidcConfig {
+            issuer: issuer.to_owned(),
+            client_id: "rusty-board".to_owned(),
+            client_secret: SecretString::from("s3cret"),
+            redirect_url: "https://board.example.com/auth/oidc/callback".to_owned(),
+            scopes: default_scopes(),
+            username_claim: default_username_claim(),
+            groups_claim: default_groups_claim(),
+            roles: BTreeMap::from([
+                ("mods".to_owned(), "janitor".to_owned()),
+                ("admins".to_owned(), "admin".to_owned()),
+            ]),
+            label: default_label(),
+        }
+    }
+
+    /// Start a login through `app`; returns the query of the provider URL and the flow cookie.
+    async fn start(app: &axum::Router, issued: &Issued) -> (BTreeMap<String, String>, String) {
+        let response = app
+            .clone()
+            .oneshot(Request::get("/auth/oidc/login").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::SEE_OTHER);
+        let location = Url::parse(response.headers()[header::LOCATION].to_str().unwrap()).unwrap();
+        assert!(location.as_str().starts_with(&format!("{}/authorize?", issued.issuer)));
+        let query: BTreeMap<String, String> = location.query_pairs().into_owned().collect();
+        let cookie = response.headers()[header::SET_COOKIE].to_str().unwrap();
+        let cookie = cookie.split(';').next().unwrap().to_owned();
+        *issued.challenge.lock().unwrap() = query["code_challenge"].clone();
+        (query, cookie)
+    }
+
+    async fn callback(app: &axum::Router, state: &str, cookie: &str) -> axum::response::Response {
+        let request = Request::get(format!("/auth/oidc/callback?code=the-code&state={state}"))
+            .header(header::COOKIE, cookie)
+            .body(Body::empty())
+            .unwrap();
+        app.clone().oneshot(request).await.unwrap()
+    }
+
+    fn id_claims(issued: &Issued, nonce: &str, groups: &[&str]) -> Value {
+        json!({
+            "iss": issued.issuer, "aud": "rusty-board", "sub": "f00", "exp": chrono::Utc::now().timestamp() + 300,
+            "nonce": nonce, "preferred_username": "alice.smith", "groups": groups,
+        })
+    }
+
+    #[tokio::test]
+    async fn a_provider_login_signs_in_with_the_highest_mapped_role() {
+        let issued = provider().await;
+        let sessions = Arc::new(Recorder::default());
+        let app = routes::routes(Arc::new(OidcLogin::new(config(&issued.issuer), sessions.clone()).unwrap()));
+
+        let (query, cookie) = start(&app, &issued).await;
+        assert_eq!(query["code_challenge_method"], "S256");
+        assert_eq!(query["scope"], "openid profile");
+        *issued.claims.lock().unwrap() = id_claims(&issued, &query["nonce"], &["mods", "admins", "other"]);
+
+        let response = callback(&app, &query["state"], &cookie).await;
+        assert_eq!(response.status(), StatusCode::SEE_OTHER);
+        assert_eq!(response.headers()[header::LOCATION], "/mod/dashboard");
+        let cookies: Vec<_> = response.headers().get_all(header::SET_COOKIE).iter().map(|v| v.to_str().unwrap()).collect();
+        assert!(cookies.iter().any(|c| c.starts_with("token=session-token;")));
+        assert_eq!(*sessions.0.lock().unwrap(), [("f00".to_owned(), "alice_smith".to_owned(), Role::Admin)]);
+    }
+
+    #[tokio::test]
+    async fn the_callback_refuses_a_forged_state_a_replayed_nonce_and_unmapped_groups() {
+        let issued = provider().await;
+        let sessions = Arc::new(Recorder::default());
+        let app = routes::routes(Arc::new(OidcLogin::new(config(&issued.issuer), sessions.clone()).unwrap()));
+
+        let (query, cookie) = start(&app, &issued).await;
+        *issued.claims.lock().unwrap() = id_claims(&issued, &query["nonce"], &["mods"]);
+        assert_eq!(callback(&app, "forged", &cookie).await.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(callback(&app, &query["state"], "").await.status(), StatusCode::BAD_REQUEST);
+
+        *issued.claims.lock().unwrap() = id_claims(&issued, "some-other-login", &["mods"]);
+        assert_eq!(callback(&app, &query["state"], &cookie).await.status(), StatusCode::BAD_REQUEST);
+
+        *issued.claims.lock().unwrap() = id_claims(&issued, &query["nonce"], &["other"]);
+        assert_eq!(callback(&app, &query["state"], &cookie).await.status(), StatusCode::FORBIDDEN);
+        assert!(sessions.0.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn unknown_roles_fail_at_startup() {
+        let mut config = config("https://sso.example.com");
+        config.roles.insert("x".to_owned(), "superuser".to_owned());
+        assert!(OidcLogin::new(config, Arc::new(Recorder::default())).is_err());
+    }
+
+    #[test]
+    fn pkce_challenge_matches_rfc_7636() {
+        assert_eq!(
+            pkce_challenge("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk"),
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+    }
+
+    #[test]
+    fn usernames_are_made_local() {
+        assert_eq!(local_username("alice.smith@example.com"), "alice_smith_example_com");
+        assert_eq!(local_username(&"x".repeat(40)).len(), 32);
+    }
+}
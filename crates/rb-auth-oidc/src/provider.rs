@@ -0,0 +1,176 @@
+//! The OpenID provider: discovery, the token endpoint and ID token checks.
+
+use std::time::Duration;
+
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use secrecy::{ExposeSecret, SecretString};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use tokio::sync::{OnceCell, RwLock};
+
+use crate::OidcError;
+
+/// Largest provider document read.
+const MAX_DOCUMENT_BYTES: usize = 1 << 20;
+
+/// Time limit of one request to the provider.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Signature algorithms accepted on ID tokens. Symmetric ones are not: the
+/// client secret is never used as a verification key.
+const ALGORITHMS: &[Algorithm] = &[
+    Algorithm::RS256,
+    Algorithm::RS384,
+    Algorithm::RS512,
+    Algorithm::PS256,
+    Algorithm::PS384,
+    Algorithm::PS512,
+    Algorithm::ES256,
+    Algorithm::ES384,
+    Algorithm::EdDSA,
+];
+
+/// The parts of `/.well-known/openid-configuration` the login flow uses.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Discovery {
+    pub issuer:                 String,
+    pub authorization_endpoint: String,
+    pub token_endpoint:         String,
+    pub jwks_uri:               String,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    id_token: String,
+}
+
+/// One OpenID provider, discovered on first use.
+pub struct Provider {
+    issuer:        String,
+    client_id:     String,
+    client_secret: SecretString,
+    http:          reqwest::Client,
+    discovery:     OnceCell<Discovery>,
+    /// The provider's signing keys, fetched again when a token names a key
+    /// that is not in the cached set (key rotation).
+    keys:          RwLock<Option<JwkSet>>,
+}
+
+impl Provider {
+    pub fn new(issuer: &str, client_id: &str, client_secret: SecretString) -> Result<Self, OidcError> {
+        let http = reqwest::Client::builder()
+            .timeout(TIMEOUT)
+            .user_agent(concat!("rusty-board/", env!("CARGO_PKG_VERSION")))
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .map_err(|e| OidcError::Provider(format!("client: {e}")))?;
+        Ok(Self {
+            issuer: issuer.trim_end_matches('/').to_owned(),
+            client_id: client_id.to_owned(),
+            client_secret,
+            http,
+            discovery: OnceCell::new(),
+            keys: RwLock::new(None),
+        })
+    }
+
+    /// The provider's endpoints. Its advertised issuer must be the configured one.
+    pub async fn discovery(&self) -> Result<&Discovery, OidcError> {
+        self.discovery
+            .get_or_try_init(|| async {
+                let url = format!("{}/.well-known/openid-configuration", self.issuer);
+                let discovery: Discovery = serde_json::from_value(self.fetch(self.http.get(&url)).await?)
+                    .map_err(|e| OidcError::Provider(format!("discovery: {e}")))?;
+                if discovery.issuer.trim_end_matches('/') != self.issuer {
+                    return Err(OidcError::Provider(format!("discovery names issuer {}", discovery.issuer)));
+                }
+                Ok(discovery)
+            })
+            .await
+    }
+
+    /// Exchange an authorization `code` for the ID token, proving the flow
+    /// with its PKCE `verifier`.
+    pub async fn exchange(&self, code: &str, verifier: &str, redirect_url: &str) -> Result<String, OidcError> {
+        let discovery = self.discovery().await?;
+        let form = [
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", redirect_url),
+            ("code_verifier", verifier),
+        ];
+        let request = self
+            .http
+            .post(&discovery.token_endpoint)
+            .basic_auth(&self.client_id, Some(self.client_secret.expose_secret()))
+            .form(&form);
+        let response: TokenResponse = serde_json::from_value(self.fetch(request).await?)
+            .map_err(|e| OidcError::Provider(format!("token response: {e}")))?;
+        Ok(response.id_token)
+    }
+
+    /// The claims of `id_token` once its signature, issuer, audience and
+    /// expiry check out.
+    pub async fn verify(&self, id_token: &str) -> Result<Map<String, Value>, OidcError> {
+        let header = jsonwebtoken::decode_header(id_token).map_err(|e| OidcError::Rejected(format!("ID token: {e}")))?;
+        if !ALGORITHMS.contains(&header.alg) {
+            return Err(OidcError::Rejected(format!("ID token signed with {:?}", header.alg)));
+        }
+        let key = self.key(header.kid.as_deref()).await?;
+        let discovery = self.discovery().await?;
+        let mut validation = Validation::new(header.alg);
+        validation.set_issuer(&[&discovery.issuer]);
+        validation.set_audience(&[&self.client_id]);
+        validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+        jsonwebtoken::decode::<Map<String, Value>>(id_token, &key, &validation)
+            .map(|data| data.claims)
+            .map_err(|e| OidcError::Rejected(format!("ID token: {e}")))
+    }
+
+    /// The signing key `kid` names, or the only key when the token names none.
+    async fn key(&self, kid: Option<&str>) -> Result<DecodingKey, OidcError> {
+        if let Some(key) = self.cached_key(kid).await? {
+            return Ok(key);
+        }
+        let discovery = self.discovery().await?;
+        let keys: JwkSet = serde_json::from_value(self.fetch(self.http.get(&discovery.jwks_uri)).await?)
+            .map_err(|e| OidcError::Provider(format!("JWKS: {e}")))?;
+        *self.keys.write().await = Some(keys);
+        self.cached_key(kid)
+            .await?
+            .ok_or_else(|| OidcError::Rejected(format!("ID token signed with unknown key {kid:?}")))
+    }
+
+    async fn cached_key(&self, kid: Option<&str>) -> Result<Option<DecodingKey>, OidcError> {
+        let keys = self.keys.read().await;
+        let Some(keys) = keys.as_ref() else {
+            return Ok(None);
+        };
+        let jwk = match kid {
+            Some(kid) => keys.find(kid),
+            None if keys.keys.len() == 1 => keys.keys.first(),
+            None => None,
+        };
+        jwk.map(DecodingKey::from_jwk)
+            .transpose()
+            .map_err(|e| OidcError::Provider(format!("JWKS: {e}")))
+    }
+
+    async fn fetch(&self, request: reqwest::RequestBuilder) -> Result<Value, OidcError> {
+        let response = request.send().await.map_err(|e| OidcError::Provider(e.to_string()))?;
+        let status = response.status();
+        if response.content_length().is_some_and(|len| len > MAX_DOCUMENT_BYTES as u64) {
+            return Err(OidcError::Provider("response too large".to_owned()));
+        }
+        let body = response.bytes().await.map_err(|e| OidcError::Provider(e.to_string()))?;
+        if !status.is_success() {
+            let detail = String::from_utf8_lossy(&body[..body.len().min(200)]).into_owned();
+            return Err(OidcError::Provider(format!("{status}: {detail}")));
+        }
+        if body.len() > MAX_DOCUMENT_BYTES {
+            return Err(OidcError::Provider("response too large".to_owned()));
+        }
+        serde_json::from_slice(&body).map_err(|e| OidcError::Provider(e.to_string()))
+    }
+}
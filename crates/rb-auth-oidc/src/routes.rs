@@ -0,0 +1,106 @@
+//! HTTP endpoints of the login flow.
+//!
+//! - `GET /auth/oidc/login` — redirect to the provider
+//! - `GET /auth/oidc/callback?code=...&state=...` — finish the login, set the
+//!   `token` cookie and redirect to the account's dashboard
+//!
+//! Failed logins answer with a plain-text reason: `400` for a callback that
+//! does not check out, `403` for an account with no role, `409` for a first
+//! login whose username another account has, `502` when the provider cannot
+//! be reached.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::extract::{Query, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use services::user::UserError;
+
+use crate::{Flow, OidcError, OidcLogin, NAME};
+
+/// Cookie holding the `Flow` between the redirect and the callback.
+const FLOW_COOKIE: &str = "rb_oidc";
+
+/// How long a login may take at the provider, in seconds.
+const FLOW_TTL_SECS: u32 = 600;
+
+type Login = State<Arc<OidcLogin>>;
+
+pub fn routes(oidc: Arc<OidcLogin>) -> Router {
+    Router::new()
+        .route("/auth/oidc/login", get(login))
+        .route("/auth/oidc/callback", get(callback))
+        .with_state(oidc)
+}
+
+async fn login(State(oidc): Login) -> Response {
+    match oidc.begin().await {
+        Ok((url, flow)) => (
+            StatusCode::SEE_OTHER,
+            [
+                (header::LOCATION, url),
+                (header::SET_COOKIE, flow_cookie(&oidc, &flow.encode(), FLOW_TTL_SECS)),
+            ],
+        )
+            .into_response(),
+        Err(e) => failure(e),
+    }
+}
+
+async fn callback(State(oidc): Login, headers: HeaderMap, Query(query): Query<HashMap<String, String>>) -> Response {
+    if let Some(error) = query.get("error") {
+        return failure(OidcError::Rejected(format!("the provider answered {error}")));
+    }
+    let (Some(code), Some(state)) = (query.get("code"), query.get("state")) else {
+        return failure(OidcError::Rejected("missing code or state".to_owned()));
+    };
+    let Some(flow) = cookie(&headers, FLOW_COOKIE).and_then(Flow::decode) else {
+        return failure(OidcError::Rejected("no login in progress".to_owned()));
+    };
+    match oidc.complete(code, state, &flow).await {
+        Ok((token, claims)) => {
+            let ttl_secs = claims.exp - chrono::Utc::now().timestamp();
+            let mut response = (StatusCode::SEE_OTHER, [(header::LOCATION, "/mod/dashboard")]).into_response();
+            let session = format!("token={}; HttpOnly; SameSite=Lax; Path=/; Max-Age={}", token.0, ttl_secs.max(0));
+            for value in [session, flow_cookie(&oidc, "", 0)] {
+                if let Ok(value) = value.parse() {
+                    response.headers_mut().append(header::SET_COOKIE, value);
+                }
+            }
+            response
+        }
+        Err(e) => failure(e),
+    }
+}
+
+fn failure(error: OidcError) -> Response {
+    let status = match &error {
+        OidcError::Provider(_) => StatusCode::BAD_GATEWAY,
+        OidcError::Rejected(_) => StatusCode::BAD_REQUEST,
+        OidcError::NoRole | OidcError::Login(UserError::Deactivated) => StatusCode::FORBIDDEN,
+        OidcError::Login(UserError::Validation { .. }) => StatusCode::BAD_REQUEST,
+        OidcError::Login(UserError::UsernameTaken { .. }) => StatusCode::CONFLICT,
+        OidcError::Login(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    tracing::warn!(plugin = NAME, error = %error, "SSO login failed");
+    (status, error.to_string()).into_response()
+}
+
+fn flow_cookie(oidc: &OidcLogin, value: &str, max_age: u32) -> String {
+    let secure = if oidc.is_https() { "; Secure" } else { "" };
+    format!("{FLOW_COOKIE}={value}; HttpOnly; SameSite=Lax; Path=/auth/oidc; Max-Age={max_age}{secure}")
+}
+
+fn cookie<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find_map(|(key, value)| (key == name).then_some(value))
+}
+
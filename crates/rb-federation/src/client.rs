@@ -0,0 +1,121 @@
+//! Signed requests to other servers: fetching actors, delivering activities.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::http::{HeaderMap, HeaderValue, Method};
+use domains::errors::DomainError;
+use reqwest::Url;
+use serde_json::Value;
+
+use crate::keys::InstanceKey;
+use crate::signatures;
+
+/// Media type of ActivityPub requests and responses.
+pub const ACTIVITY_JSON: &str = "application/activity+json";
+
+/// Largest remote document read.
+const MAX_DOCUMENT_BYTES: usize = 1 << 20;
+
+/// Time limit of one request.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// HTTP client signing each request with the instance key.
+#[derive(Clone)]
+pub struct ApClient {
+    http:       reqwest::Client,
+    key:        Arc<InstanceKey>,
+    /// Plain-HTTP remotes are allowed only when the site itself is served
+    /// over HTTP, i.e. in local testing.
+    allow_http: bool,
+}
+
+impl ApClient {
+    pub fn new(key: Arc<InstanceKey>, allow_http: bool) -> Result<Self, DomainError> {
+        let http = reqwest::Client::builder()
+            .timeout(TIMEOUT)
+            .user_agent(concat!("rusty-board/", env!("CARGO_PKG_VERSION")))
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .map_err(|e| DomainError::internal(format!("federation client: {e}")))?;
+        Ok(Self { http, key, allow_http })
+    }
+
+    /// GET the ActivityPub document at `url`, signed as `key_id`.
+    pub async fn fetch(&self, url: &str, key_id: &str) -> Result<Value, DomainError> {
+        let url = self.remote_url(url)?;
+        let mut headers = HeaderMap::new();
+        headers.insert("accept", HeaderValue::from_static(ACTIVITY_JSON));
+        signatures::sign(&self.key, key_id, &Method::GET, &url, None, &mut headers)?;
+        let response = self.send(self.http.get(url.clone()).headers(headers), &url).await?;
+        if response.content_length().is_some_and(|len| len > MAX_DOCUMENT_BYTES as u64) {
+            return Err(DomainError::internal(format!("{}: document too large", host(&url))));
+        }
+        let body = response.bytes().await.map_err(|e| DomainError::internal(format!("{}: {e}", host(&url))))?;
+        if body.len() > MAX_DOCUMENT_BYTES {
+            return Err(DomainError::internal(format!("{}: document too large", host(&url))));
+        }
+        serde_json::from_slice(&body).map_err(|e| DomainError::internal(format!("{}: {e}", host(&url))))
+    }
+
+    /// POST `activity` to `inbox`, signed as `key_id`.
+    pub async fn deliver(&self, inbox: &str, key_id: &str, activity: &Value) -> Result<(), DomainError> {
+        let url = self.remote_url(inbox)?;
+        let body = activity.to_string().into_bytes();
+        let mut headers = HeaderMap::new();
+        headers.insert("content-type", HeaderValue::from_static(ACTIVITY_JSON));
+        signatures::sign(&self.key, key_id, &Method::POST, &url, Some(&body), &mut headers)?;
+        self.send(self.http.post(url.clone()).headers(headers).body(body), &url).await?;
+        Ok(())
+    }
+
+    async fn send(&self, request: reqwest::RequestBuilder, url: &Url) -> Result<reqwest::Response, DomainError> {
+        let response = request
+            .send()
+            .await
+            .map_err(|e| DomainError::internal(format!("{}: {}", host(url), e.without_url())))?;
+        if !response.status().is_success() {
+            return Err(DomainError::internal(format!("{}: {}", host(url), response.status())));
+        }
+        Ok(response)
+    }
+
+    /// `url` parsed, if it is one this client may request.
+    pub fn remote_url(&self, url: &str) -> Result<Url, DomainError> {
+        let parsed = Url::parse(url).map_err(|_| DomainError::internal(format!("invalid remote URL {url:?}")))?;
+        if self.allow_http {
+            return match parsed.scheme() {
+                "http" | "https" => Ok(parsed),
+                _ => Err(DomainError::internal(format!("remote URL must be http(s): {url:?}"))),
+            };
+        }
+        if parsed.scheme() != "https" {
+            return Err(DomainError::internal(format!("remote URL must be https: {url:?}")));
+        }
+        // Remote documents name the URLs fetched next; keep them off this
+        // machine and the local network.
+        let local = match parsed.host() {
+            Some(url::Host::Domain(domain)) => {
+                domain.eq_ignore_ascii_case("localhost") || domain.ends_with(".localhost")
+            }
+            Some(url::Host::Ipv4(ip)) => {
+                ip.is_loopback() || ip.is_private() || ip.is_link_local() || ip.is_unspecified()
+            }
+            Some(url::Host::Ipv6(ip)) => {
+                // fc00::/7 is unique-local, fe80::/10 link-local.
+                let first = ip.segments()[0];
+                ip.is_loopback() || ip.is_unspecified() || first & 0xfe00 == 0xfc00 || first & 0xffc0 == 0xfe80
+            }
+            None => true,
+        };
+        if local {
+            return Err(DomainError::internal(format!("remote URL is local: {}", host(&parsed))));
+        }
+        Ok(parsed)
+    }
+}
+
+/// Host of `url`, for logs and errors: paths may hold tokens.
+pub fn host(url: &Url) -> &str {
+    url.host_str().unwrap_or("?")
+}
@@ -0,0 +1,118 @@
+//! The instance's RSA key, signing every outgoing request.
+//!
+//! One key serves every board actor: each actor document publishes it as
+//! `<actor>#main-key`. Signatures are RSASSA-PKCS1-v1_5 with SHA-256
+//! (`rsa-sha256`), the only algorithm Mastodon and most other servers accept.
+
+use std::io::Write;
+use std::path::Path;
+
+use domains::errors::DomainError;
+use rsa::pkcs1::DecodeRsaPublicKey;
+use rsa::pkcs1v15::{Signature, SigningKey, VerifyingKey};
+use rsa::pkcs8::{DecodePrivateKey, DecodePublicKey, EncodePrivateKey, EncodePublicKey, LineEnding};
+use rsa::signature::{SignatureEncoding, Signer, Verifier};
+use rsa::{RsaPrivateKey, RsaPublicKey};
+use sha2::Sha256;
+
+/// Size of a generated key.
+const KEY_BITS: usize = 2048;
+
+/// The private key and its public half as PEM.
+pub struct InstanceKey {
+    signing:    SigningKey<Sha256>,
+    public_pem: String,
+}
+
+impl InstanceKey {
+    /// Read a PKCS#8 PEM private key.
+    pub fn from_pem(pem: &str) -> Result<Self, DomainError> {
+        let private =
+            RsaPrivateKey::from_pkcs8_pem(pem).map_err(|e| DomainError::internal(format!("federation key: {e}")))?;
+        Self::from_private(private)
+    }
+
+    /// Read the key at `path`, or generate one and write it there (mode 0600
+    /// on Unix) when the file does not exist.
+    pub fn load_or_create(path: &Path) -> Result<Self, DomainError> {
+        let io_error = |e: std::io::Error| DomainError::internal(format!("federation key {}: {e}", path.display()));
+        match std::fs::read_to_string(path) {
+            Ok(pem) => return Self::from_pem(&pem),
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(io_error(e)),
+            Err(_) => {}
+        }
+        let private = RsaPrivateKey::new(&mut rsa::rand_core::OsRng, KEY_BITS)
+            .map_err(|e| DomainError::internal(format!("generating federation key: {e}")))?;
+        let pem = private
+            .to_pkcs8_pem(LineEnding::LF)
+            .map_err(|e| DomainError::internal(format!("encoding federation key: {e}")))?;
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        options.open(path).and_then(|mut file| file.write_all(pem.as_bytes())).map_err(io_error)?;
+        tracing::info!(path = %path.display(), "federation key generated");
+        Self::from_private(private)
+    }
+
+    fn from_private(private: RsaPrivateKey) -> Result<Self, DomainError> {
+        let public_pem = private
+            .to_public_key()
+            .to_public_key_pem(LineEnding::LF)
+            .map_err(|e| DomainError::internal(format!("encoding federation key: {e}")))?;
+        Ok(Self { signing: SigningKey::new(private), public_pem })
+    }
+
+    /// The public key as SPKI PEM (`-----BEGIN PUBLIC KEY-----`).
+    pub fn public_pem(&self) -> &str {
+        &self.public_pem
+    }
+
+    /// `rsa-sha256` signature of `data`.
+    pub fn sign(&self, data: &[u8]) -> Vec<u8> {
+        self.signing.sign(data).to_vec()
+    }
+}
+
+/// Whether `signature` is the `rsa-sha256` signature of `data` by the key in
+/// `public_pem` (SPKI or PKCS#1 PEM).
+pub fn verify(public_pem: &str, data: &[u8], signature: &[u8]) -> bool {
+    let Ok(public) = RsaPublicKey::from_public_key_pem(public_pem.trim())
+        .or_else(|_| RsaPublicKey::from_pkcs1_pem(public_pem.trim()))
+    else {
+        return false;
+    };
+    let Ok(signature) = Signature::try_from(signature) else {
+        return false;
+    };
+    VerifyingKey::<Sha256>::new(public).verify(data, &signature).is_ok()
+}
+
+#[cfg(test)]
+pub(crate) fn test_key() -> InstanceKey {
+    InstanceKey::from_pem(include_str!("../tests/fixtures/test-key.pem")).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signatures_verify_with_the_public_key() {
+        let key = test_key();
+        let signature = key.sign(b"hello");
+        assert!(key.public_pem().starts_with("-----BEGIN PUBLIC KEY-----"));
+        assert!(verify(key.public_pem(), b"hello", &signature));
+        assert!(!verify(key.public_pem(), b"hello!", &signature));
+        assert!(!verify("not a key", b"hello", &signature));
+    }
+
+    #[test]
+    fn existing_key_files_are_loaded() {
+        let path = std::env::temp_dir().join(format!("rb-federation-{}.pem", uuid::Uuid::new_v4()));
+        std::fs::write(&path, include_str!("../tests/fixtures/test-key.pem")).unwrap();
+        let loaded = InstanceKey::load_or_create(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.public_pem(), test_key().public_pem());
+    }
+}
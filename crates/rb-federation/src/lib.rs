@@ -0,0 +1,623 @@
+//! `rb-federation` — ActivityPub federation of boards.
+//!
+//! Each board with `BoardConfig::federation_enabled` is a `Group` actor that
+//! remote users (Mastodon, Misskey, Lemmy, ...) can follow as
+//! `@slug@host`:
+//!
+//! - new posts are sent to the board's followers as `Create`s of `Note`s, a
+//!   thread's replies `inReplyTo` its OP; deleted posts as `Delete`s;
+//! - a follower's instance may answer: a `Note` `inReplyTo` one of the
+//!   board's posts, sent by an actor of an instance following the board, is
+//!   posted to that thread through `PostService` like any other reply;
+//! - `Follow` is accepted at once, `Undo` of a follow removes the follower.
+//!
+//! Every request is signed with the instance key (`keys.rs`), and every
+//! inbox request must be signed by the actor it comes from
+//! (`signatures.rs`). IP hashes are never published.
+//!
+//! The crate plugs into the server through ports: `ActivityPubFederationSync`
+//! is an `EventSubscriber` for publishing and serves `routes::routes` for
+//! the inbound side; followers live in a `FollowerRepository`.
+
+pub mod client;
+pub mod keys;
+pub mod objects;
+pub mod routes;
+pub mod signatures;
+
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use axum::http::{HeaderMap, Method};
+use chrono::Utc;
+use domains::errors::DomainError;
+use domains::models::{Board, BoardConfig, Post, PostId, RemoteFollower, SiteId, Slug};
+use domains::ports::{
+    BanRepository, BoardRepository, DomainEvent, EventSubscriber, FollowerRepository, MediaProcessor, MediaStorage,
+    PostRepository, RateLimiter, ThreadRepository,
+};
+use serde_json::Value;
+use services::common::utils::hash_ip;
+use services::post::{PostDraft, PostError, PostService};
+
+pub use client::ApClient;
+pub use keys::InstanceKey;
+pub use objects::Urls;
+
+/// Tag on posts that came from another server.
+pub const FEDERATED_TAG: &str = "federated";
+
+/// Where federated replies are posted. Implemented by `PostService`.
+#[async_trait]
+pub trait ReplySink: Send + Sync + 'static {
+    /// Create `draft` like a reply submitted on the board.
+    async fn post_reply(&self, draft: PostDraft, board_config: &BoardConfig) -> Result<Post, PostError>;
+}
+
+#[async_trait]
+impl<PR, TR, BR, MS, RL, MP> ReplySink for PostService<PR, TR, BR, MS, RL, MP>
+where
+    PR: PostRepository,
+    TR: ThreadRepository,
+    BR: BanRepository,
+    MS: MediaStorage,
+    RL: RateLimiter,
+    MP: MediaProcessor,
+{
+    async fn post_reply(&self, draft: PostDraft, board_config: &BoardConfig) -> Result<Post, PostError> {
+        Ok(self.create_post(draft, board_config).await?.post)
+    }
+}
+
+/// The repositories federation reads and writes.
+pub struct Repositories {
+    pub boards:    Arc<dyn BoardRepository>,
+    pub threads:   Arc<dyn ThreadRepository>,
+    pub posts:     Arc<dyn PostRepository>,
+    pub followers: Arc<dyn FollowerRepository>,
+}
+
+/// A federated board: the board and its config.
+type Federated = (Board, BoardConfig);
+
+/// The boards of one site as ActivityPub actors.
+pub struct ActivityPubFederationSync {
+    urls:    Urls,
+    site_id: SiteId,
+    key:     Arc<InstanceKey>,
+    client:  ApClient,
+    repos:   Repositories,
+    replies: Arc<dyn ReplySink>,
+}
+
+impl ActivityPubFederationSync {
+    /// Federate the boards of `site_id`, published under `urls`.
+    pub fn new(
+        urls: Urls,
+        site_id: SiteId,
+        key: InstanceKey,
+        repos: Repositories,
+        replies: Arc<dyn ReplySink>,
+    ) -> Result<Self, DomainError> {
+        let key = Arc::new(key);
+        let client = ApClient::new(key.clone(), urls.is_http())?;
+        Ok(Self { urls, site_id, key, client, repos, replies })
+    }
+
+    pub fn urls(&self) -> &Urls {
+        &self.urls
+    }
+
+    /// The board `slug` names, if it is federated.
+    pub async fn board(&self, slug: &str) -> Result<Option<Federated>, DomainError> {
+        let Ok(slug) = Slug::new(slug) else {
+            return Ok(None);
+        };
+        match self.repos.boards.find_by_slug(self.site_id, &slug).await {
+            Ok(board) => self.federated(board).await,
+            Err(DomainError::NotFound { .. }) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn federated(&self, board: Board) -> Result<Option<Federated>, DomainError> {
+        if board.site_id != self.site_id {
+            return Ok(None);
+        }
+        let config = self.repos.boards.find_config(board.id).await?;
+        Ok(config.federation_enabled.then_some((board, config)))
+    }
+
+    /// The board's actor document.
+    pub async fn actor(&self, slug: &str) -> Result<Option<Value>, DomainError> {
+        Ok(self.board(slug).await?.map(|(board, _)| objects::actor(&self.urls, &board, self.key.public_pem())))
+    }
+
+    /// WebFinger answer for `resource`.
+    pub async fn webfinger(&self, resource: &str) -> Result<Option<Value>, DomainError> {
+        let Some(slug) = objects::webfinger_slug(&self.urls, resource) else {
+            return Ok(None);
+        };
+        Ok(self.board(slug).await?.map(|(board, _)| objects::webfinger(&self.urls, &board)))
+    }
+
+    /// The board's followers collection, with its count.
+    pub async fn followers(&self, slug: &str) -> Result<Option<Value>, DomainError> {
+        let Some((board, _)) = self.board(slug).await? else {
+            return Ok(None);
+        };
+        let count = self.repos.followers.find_by_board(board.id).await?.len();
+        Ok(Some(objects::collection(self.urls.followers(&board), count)))
+    }
+
+    /// The board's outbox. Posts are pushed to followers, not listed here.
+    pub async fn outbox(&self, slug: &str) -> Result<Option<Value>, DomainError> {
+        let Some((board, _)) = self.board(slug).await? else {
+            return Ok(None);
+        };
+        Ok(Some(objects::collection(format!("{}/outbox", self.urls.actor(&board)), 0)))
+    }
+
+    /// The note of `post_id`, if it was posted on a federated board.
+    pub async fn note(&self, post_id: PostId) -> Result<Option<Value>, DomainError> {
+        let Some((board, config, post, op)) = self.post(post_id).await? else {
+            return Ok(None);
+        };
+        if post.tags.iter().any(|t| t == FEDERATED_TAG) {
+            return Ok(None);
+        }
+        Ok(Some(objects::note(&self.urls, &board, &post, op, config.nsfw)))
+    }
+
+    /// A post of a federated board, with the board and its thread's OP.
+    /// `None` for a tombstone, whose `Delete` went out with `PostDeleted`,
+    /// and for a shadowbanned post, which never went out at all.
+    async fn post(&self, post_id: PostId) -> Result<Option<(Board, BoardConfig, Post, Option<PostId>)>, DomainError> {
+        let Some(post) = self.repos.posts.find_visible_by_id(post_id).await? else {
+            return Ok(None);
+        };
+        let thread = self.repos.threads.find_by_id(post.thread_id).await?;
+        let board = self.repos.boards.find_by_id(thread.board_id).await?;
+        Ok(self.federated(board).await?.map(|(board, config)| (board, config, post, thread.op_post_id)))
+    }
+
+    /// Handle an activity POSTed to the inbox of `slug`. `target` is the
+    /// request's path and query. Unknown or irrelevant activities are
+    /// accepted and dropped.
+    ///
+    /// Fails with `DomainError::NotFound` for a board that is not federated
+    /// and `DomainError::Auth` for a request not signed by the activity's
+    /// actor.
+    pub async fn receive(
+        &self,
+        slug: &str,
+        method: &Method,
+        target: &str,
+        headers: &HeaderMap,
+        body: &[u8],
+    ) -> Result<(), DomainError> {
+        let (board, config) = self.board(slug).await?.ok_or_else(|| DomainError::not_found(format!("board/{slug}")))?;
+        let request = signatures::SignedRequest::from_request(method, target, headers, body, Utc::now())?;
+        let activity: Value = serde_json::from_slice(body).map_err(|_| DomainError::Auth)?;
+        let actor_id = objects::id_of(&activity["actor"]).ok_or(DomainError::Auth)?;
+
+        // The key must be the actor's own, as its server publishes it.
+        let actor = self.client.fetch(actor_id, &self.urls.key_id(&board)).await.map_err(|e| {
+            tracing::info!(error = %e, "federation: actor of an inbox request could not be fetched");
+            DomainError::Auth
+        })?;
+        let keys = match &actor["publicKey"] {
+            Value::Array(keys) => keys.iter().collect(),
+            key => vec![key],
+        };
+        let key = keys.into_iter().find(|k| k["id"] == request.key_id.as_str());
+        let pem = key.and_then(|k| k["publicKeyPem"].as_str()).ok_or(DomainError::Auth)?;
+        if actor["id"] != actor_id || !request.verify(pem) {
+            return Err(DomainError::Auth);
+        }
+
+        match activity["type"].as_str().unwrap_or_default() {
+            "Follow" if objects::id_of(&activity["object"]) == Some(&self.urls.actor(&board)) => {
+                self.follow(&board, &activity, &actor).await
+            }
+            "Undo" if activity["object"]["type"] == "Follow" => {
+                self.repos.followers.delete(board.id, actor_id).await?;
+                tracing::info!(board = %board.slug.as_str(), actor = actor_id, "federation: follower removed");
+                Ok(())
+            }
+            "Create" if activity["object"]["type"] == "Note" => {
+                self.reply(&board, &config, &activity["object"], &actor).await
+            }
+            kind => {
+                tracing::debug!(kind, "federation: activity ignored");
+                Ok(())
+            }
+        }
+    }
+
+    async fn follow(&self, board: &Board, follow: &Value, actor: &Value) -> Result<(), DomainError> {
+        let actor_id = actor["id"].as_str().unwrap_or_default();
+        let inbox = actor["endpoints"]["sharedInbox"].as_str().or(actor["inbox"].as_str()).ok_or(DomainError::Auth)?;
+        self.client.remote_url(inbox).map_err(|_| DomainError::Auth)?;
+        self.repos
+            .followers
+            .save(&RemoteFollower {
+                board_id:   board.id,
+                actor_id:   actor_id.to_owned(),
+                inbox:      inbox.to_owned(),
+                created_at: Utc::now(),
+            })
+            .await?;
+        tracing::info!(board = %board.slug.as_str(), actor = actor_id, "federation: follower added");
+
+        let accept = objects::accept(&self.urls, board, follow);
+        let inbox = actor["inbox"].as_str().unwrap_or(inbox).to_owned();
+        self.spawn_delivery(board, inbox, accept);
+        Ok(())
+    }
+
+    async fn reply(&self, board: &Board, config: &BoardConfig, note: &Value, actor: &Value) -> Result<(), DomainError> {
+        let actor_id = actor["id"].as_str().unwrap_or_default();
+        if objects::id_of(&note["attributedTo"]) != Some(actor_id) {
+            return Ok(());
+        }
+        let Some(parent) = note["inReplyTo"].as_str().and_then(|id| self.urls.post_id_of(id)) else {
+            return Ok(());
+        };
+        let Some((parent_board, _, parent, _)) = self.post(parent).await? else {
+            return Ok(());
+        };
+        if parent_board.id != board.id {
+            return Ok(());
+        }
+
+        // Replies are taken from instances following the board only.
+        let actor_host = self.client.remote_url(actor_id).ok().and_then(|u| u.host_str().map(str::to_owned));
+        let followed_from = self.repos.followers.find_by_board(board.id).await?.into_iter().any(|f| {
+            self.client.remote_url(&f.actor_id).ok().and_then(|u| u.host_str().map(str::to_owned)) == actor_host
+        });
+        if !followed_from {
+            tracing::info!(
+                board = %board.slug.as_str(),
+                actor = actor_id,
+                "federation: reply from an instance not following the board dropped"
+            );
+            return Ok(());
+        }
+
+        let handle = match (actor["preferredUsername"].as_str(), actor_host) {
+            (Some(user), Some(host)) => format!("@{user}@{host}"),
+            _ => actor_id.to_owned(),
+        };
+        let daily_salt = Utc::now().format("%Y-%m-%d").to_string();
+        let draft = PostDraft {
+            board_id:     board.id,
+            thread_id:    Some(parent.thread_id),
+            body:         objects::html_to_text(note["content"].as_str().unwrap_or_default()),
+            ip_hash:      hash_ip(&format!("activitypub:{actor_id}"), &daily_salt),
+            raw_ip:       None,
+            name:         Some(handle.replace('#', "")),
+            email:        None,
+            subject:      None,
+            files:        Vec::new(),
+            spoiler:      false,
+            is_staff:     false,
+            poster_role:  None,
+            media_prefix: String::new(),
+            tags:         vec![FEDERATED_TAG.to_owned()],
+            poll:         None,
+            via_api_key:  false,
+        };
+        match self.replies.post_reply(draft, config).await {
+            Ok(post) => {
+                tracing::info!(board = %board.slug.as_str(), post_id = %post.id, actor = actor_id, "federation: reply");
+                Ok(())
+            }
+            Err(PostError::Internal(e)) => Err(e),
+            Err(e) => {
+                let board = board.slug.as_str();
+                tracing::info!(board, actor = actor_id, reason = %e, "federation: reply refused");
+                Ok(())
+            }
+        }
+    }
+
+    /// Send `activity` to every follower of `board`, each shared inbox once.
+    async fn publish(&self, board: &Board, activity: Value) -> Result<(), DomainError> {
+        let inboxes: BTreeSet<String> =
+            self.repos.followers.find_by_board(board.id).await?.into_iter().map(|f| f.inbox).collect();
+        for inbox in inboxes {
+            self.spawn_delivery(board, inbox, activity.clone());
+        }
+        Ok(())
+    }
+
+    /// Deliver in the background, so one slow server delays no one else.
+    fn spawn_delivery(&self, board: &Board, inbox: String, activity: Value) {
+        let client = self.client.clone();
+        let key_id = self.urls.key_id(board);
+        tokio::spawn(async move {
+            if let Err(e) = client.deliver(&inbox, &key_id, &activity).await {
+                tracing::warn!(error = %e, kind = activity["type"].as_str(), "federation: delivery failed");
+            }
+        });
+    }
+}
+
+#[async_trait]
+impl EventSubscriber for ActivityPubFederationSync {
+    fn name(&self) -> &str {
+        "activitypub"
+    }
+
+    async fn handle(&self, event: &DomainEvent) -> Result<(), DomainError> {
+        match event {
+            DomainEvent::PostCreated { post, .. } if !post.tags.iter().any(|t| t == FEDERATED_TAG) => {
+                let Some((board, config, post, op)) = self.post(post.id).await? else {
+                    return Ok(());
+                };
+                let note = objects::note(&self.urls, &board, &post, op, config.nsfw);
+                self.publish(&board, objects::create(&self.urls, &board, note)).await
+            }
+            DomainEvent::PostEdited { post, .. } if !post.tags.iter().any(|t| t == FEDERATED_TAG) => {
+                let Some((board, config, post, op)) = self.post(post.id).await? else {
+                    return Ok(());
+                };
+                let note = objects::note(&self.urls, &board, &post, op, config.nsfw);
+                self.publish(&board, objects::update(&self.urls, &board, note)).await
+            }
+            // Moved notes reply to another OP; rewritten ones quote differently.
+            DomainEvent::PostsMoved { moved, rewritten, .. } => {
+                for &post_id in moved.iter().chain(rewritten.iter().filter(|id| !moved.contains(id))) {
+                    let Some((board, config, post, op)) = self.post(post_id).await? else {
+                        continue;
+                    };
+                    if post.tags.iter().any(|t| t == FEDERATED_TAG) {
+                        continue;
+                    }
+                    let note = objects::note(&self.urls, &board, &post, op, config.nsfw);
+                    self.publish(&board, objects::update(&self.urls, &board, note)).await?;
+                }
+                Ok(())
+            }
+            DomainEvent::PostDeleted { board_id, post_id, .. } => {
+                let board = self.repos.boards.find_by_id(*board_id).await?;
+                let Some((board, _)) = self.federated(board).await? else {
+                    return Ok(());
+                };
+                self.publish(&board, objects::delete(&self.urls, &board, *post_id)).await
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use axum::routing::{get, post};
+    use domains::models::{BoardId, IpHash, Thread, ThreadId};
+    use domains::ports::{MockBoardRepository, MockFollowerRepository, MockPostRepository, MockThreadRepository};
+    use serde_json::json;
+
+    use super::*;
+    use crate::keys::test_key;
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<PostDraft>>);
+
+    #[async_trait]
+    impl ReplySink for Recorder {
+        async fn post_reply(&self, draft: PostDraft, _: &BoardConfig) -> Result<Post, PostError> {
+            let post = Post {
+                id: PostId::new(), thread_id: draft.thread_id.unwrap(), body: draft.body.clone(),
+                ip_hash: draft.ip_hash.clone(), name: draft.name.clone(), tripcode: None, email: None,
+                created_at: Utc::now(), post_number: 2, pinned: false, tags: draft.tags.clone(), commands: vec![],
+                subject: None,
+                capcode: None,
+                deleted: None,
+                edited_at: None,
+            };
+            self.0.lock().unwrap().push(draft);
+            Ok(post)
+        }
+    }
+
+    struct Fixture {
+        board:    Board,
+        thread:   Thread,
+        op:       Post,
+        /// `http://127.0.0.1:<port>/users/alice`, served by `remote_server`.
+        actor_id: String,
+    }
+
+    /// A remote server publishing alice's actor with the test key.
+    async fn remote_server() -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let actor_id = format!("http://{}/users/alice", listener.local_addr().unwrap());
+        let actor = json!({
+            "id": actor_id, "type": "Person", "preferredUsername": "alice", "inbox": format!("{actor_id}/inbox"),
+            "publicKey": { "id": format!("{actor_id}#main-key"), "publicKeyPem": test_key().public_pem() },
+        });
+        let app = axum::Router::new()
+            .route("/users/alice", get(move || async move { actor.to_string() }))
+            .route("/users/alice/inbox", post(|| async { axum::http::StatusCode::ACCEPTED }));
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        actor_id
+    }
+
+    async fn fixture() -> Fixture {
+        let board = Board {
+            id: BoardId::new(), site_id: SiteId::DEFAULT, slug: Slug::new("g").unwrap(), title: "Technology".to_owned(),
+            rules: String::new(), created_at: Utc::now(),
+        };
+        let op = Post {
+            id: PostId::new(), thread_id: ThreadId::new(), body: "first".to_owned(), ip_hash: IpHash::new("h"),
+            name: None, tripcode: None, email: None, created_at: Utc::now(), post_number: 1, pinned: false,
+            tags: vec![], commands: vec![], subject: None, capcode: None, deleted: None, edited_at: None,
+        };
+        let thread = Thread {
+            id: op.thread_id, board_id: board.id, op_post_id: Some(op.id), reply_count: 0, bumped_at: Utc::now(),
+            sticky: false, closed: false, cycle: false, created_at: Utc::now(),
+        };
+        Fixture { board, thread, op, actor_id: remote_server().await }
+    }
+
+    fn federation(
+        fixture: &Fixture,
+        followers: MockFollowerRepository,
+        replies: Arc<Recorder>,
+    ) -> ActivityPubFederationSync {
+        let mut boards = MockBoardRepository::new();
+        let board = fixture.board.clone();
+        boards.expect_find_by_slug().returning(move |_, _| Ok(board.clone()));
+        let board = fixture.board.clone();
+        boards.expect_find_by_id().returning(move |_| Ok(board.clone()));
+        boards
+            .expect_find_config()
+            .returning(|_| Ok(BoardConfig { federation_enabled: true, ..BoardConfig::default() }));
+        let mut threads = MockThreadRepository::new();
+        let thread = fixture.thread.clone();
+        threads.expect_find_by_id().returning(move |_| Ok(thread.clone()));
+        let mut posts = MockPostRepository::new();
+        let op = fixture.op.clone();
+        posts.expect_find_visible_by_id().returning(move |_| Ok(Some(op.clone())));
+        let repos = Repositories {
+            boards:    Arc::new(boards),
+            threads:   Arc::new(threads),
+            posts:     Arc::new(posts),
+            followers: Arc::new(followers),
+        };
+        let urls = Urls::new("http://board.example").unwrap();
+        ActivityPubFederationSync::new(urls, SiteId::DEFAULT, test_key(), repos, replies).unwrap()
+    }
+
+    /// `activity` POSTed to the board's inbox, signed by alice's key as `key_id`.
+    async fn deliver(federation: &ActivityPubFederationSync, key_id: &str, activity: Value) -> Result<(), DomainError> {
+        let body = activity.to_string().into_bytes();
+        let url = reqwest::Url::parse("http://board.example/ap/boards/g/inbox").unwrap();
+        let mut headers = HeaderMap::new();
+        signatures::sign(&test_key(), key_id, &Method::POST, &url, Some(&body), &mut headers).unwrap();
+        federation.receive("g", &Method::POST, "/ap/boards/g/inbox", &headers, &body).await
+    }
+
+    #[tokio::test]
+    async fn follows_are_recorded() {
+        let fixture = fixture().await;
+        let mut followers = MockFollowerRepository::new();
+        let actor_id = fixture.actor_id.clone();
+        followers
+            .expect_save()
+            .withf(move |f| f.actor_id == actor_id && f.inbox == format!("{actor_id}/inbox"))
+            .times(1)
+            .returning(|_| Ok(()));
+        let federation = federation(&fixture, followers, Arc::default());
+
+        let follow = json!({
+            "id": format!("{}/follows/1", fixture.actor_id), "type": "Follow", "actor": fixture.actor_id,
+            "object": "http://board.example/ap/boards/g",
+        });
+        deliver(&federation, &format!("{}#main-key", fixture.actor_id), follow).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn replies_from_followers_are_posted_to_the_thread() {
+        let fixture = fixture().await;
+        // Another user of alice's instance follows the board.
+        let follower = RemoteFollower {
+            board_id:   fixture.board.id,
+            actor_id:   fixture.actor_id.replace("alice", "bob"),
+            inbox:      String::new(),
+            created_at: Utc::now(),
+        };
+        let mut followers = MockFollowerRepository::new();
+        followers.expect_find_by_board().returning(move |_| Ok(vec![follower.clone()]));
+        let replies = Arc::new(Recorder::default());
+        let federation = federation(&fixture, followers, replies.clone());
+
+        let create = json!({
+            "type": "Create", "actor": fixture.actor_id,
+            "object": {
+                "type": "Note", "attributedTo": fixture.actor_id,
+                "inReplyTo": format!("http://board.example/ap/posts/{}", fixture.op.id.0),
+                "content": "<p><span class=\"h-card\">@g</span> nice</p>",
+            },
+        });
+        deliver(&federation, &format!("{}#main-key", fixture.actor_id), create).await.unwrap();
+
+        let drafts = replies.0.lock().unwrap();
+        assert_eq!(drafts.len(), 1);
+        assert_eq!(drafts[0].thread_id, Some(fixture.thread.id));
+        assert_eq!(drafts[0].body, "nice");
+        assert_eq!(drafts[0].name.as_deref(), Some("@alice@127.0.0.1"));
+        assert_eq!(drafts[0].tags, [FEDERATED_TAG]);
+    }
+
+    /// Followers whose one inbox hands each delivery to the test.
+    async fn follower_inbox(fixture: &Fixture) -> (MockFollowerRepository, tokio::sync::mpsc::UnboundedReceiver<Value>) {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel::<Value>();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let inbox = format!("http://{}/inbox", listener.local_addr().unwrap());
+        let app = axum::Router::new().route(
+            "/inbox",
+            post(move |body: String| async move {
+                let _ = tx.send(serde_json::from_str(&body).unwrap());
+                axum::http::StatusCode::ACCEPTED
+            }),
+        );
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        let follower = RemoteFollower {
+            board_id: fixture.board.id, actor_id: fixture.actor_id.clone(), inbox, created_at: Utc::now(),
+        };
+        let mut followers = MockFollowerRepository::new();
+        followers.expect_find_by_board().returning(move |_| Ok(vec![follower.clone()]));
+        (followers, rx)
+    }
+
+    #[tokio::test]
+    async fn staff_edits_go_out_as_updates() {
+        let fixture = fixture().await;
+        let (followers, mut rx) = follower_inbox(&fixture).await;
+        let federation = federation(&fixture, followers, Arc::default());
+
+        let event = DomainEvent::PostEdited { board_id: fixture.board.id, post: fixture.op.clone() };
+        federation.handle(&event).await.unwrap();
+
+        let activity = tokio::time::timeout(std::time::Duration::from_secs(5), rx.recv()).await.unwrap().unwrap();
+        assert_eq!(activity["type"], "Update");
+        assert_eq!(activity["object"]["id"], format!("http://board.example/ap/posts/{}", fixture.op.id.0));
+    }
+
+    #[tokio::test]
+    async fn moved_posts_go_out_as_updates_once_each() {
+        let fixture = fixture().await;
+        let (followers, mut rx) = follower_inbox(&fixture).await;
+        let federation = federation(&fixture, followers, Arc::default());
+
+        let event = DomainEvent::PostsMoved {
+            board_id:  fixture.board.id,
+            from:      ThreadId::new(),
+            to:        fixture.thread.id,
+            moved:     vec![fixture.op.id],
+            rewritten: vec![fixture.op.id],
+        };
+        federation.handle(&event).await.unwrap();
+
+        let activity = tokio::time::timeout(std::time::Duration::from_secs(5), rx.recv()).await.unwrap().unwrap();
+        assert_eq!(activity["type"], "Update");
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        assert!(rx.try_recv().is_err(), "one update per post");
+    }
+
+    #[tokio::test]
+    async fn requests_signed_with_another_key_are_refused() {
+        let fixture = fixture().await;
+        let federation = federation(&fixture, MockFollowerRepository::new(), Arc::default());
+        let follow =
+            json!({ "type": "Follow", "actor": fixture.actor_id, "object": "http://board.example/ap/boards/g" });
+        let result = deliver(&federation, "http://127.0.0.1:1/users/mallory#main-key", follow).await;
+        assert!(matches!(result, Err(DomainError::Auth)));
+    }
+}
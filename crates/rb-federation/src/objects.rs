@@ -0,0 +1,368 @@
+//! ActivityStreams documents: board actors, notes and the activities
+//! carrying them, plus the URLs they are published under.
+//!
+//! | URL | Document |
+//! |-----|----------|
+//! | `/ap/boards/{slug}` | The board's `Group` actor |
+//! | `/ap/boards/{slug}/inbox` | Where followers send `Follow`, `Undo` and replies |
+//! | `/ap/boards/{slug}/outbox`, `/followers` | Collections with a count only |
+//! | `/ap/posts/{post_id}` | A post as a `Note`; replies are `inReplyTo` the OP |
+
+use domains::errors::DomainError;
+use domains::models::{Board, Post, PostId};
+use reqwest::Url;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+/// Audience of public posts.
+pub const PUBLIC: &str = "https://www.w3.org/ns/activitystreams#Public";
+
+const CONTEXT: [&str; 2] = ["https://www.w3.org/ns/activitystreams", "https://w3id.org/security/v1"];
+
+/// The URLs of the federated site, built on its public origin.
+#[derive(Debug, Clone)]
+pub struct Urls {
+    /// Origin without a trailing slash, e.g. `https://board.example`.
+    base: String,
+    host: String,
+}
+
+impl Urls {
+    /// `base_url` must be an `http(s)` origin; a path is not allowed.
+    pub fn new(base_url: &str) -> Result<Self, DomainError> {
+        let url = Url::parse(base_url).map_err(|e| DomainError::internal(format!("federation base_url: {e}")))?;
+        let host = url.host_str().unwrap_or_default().to_ascii_lowercase();
+        if !matches!(url.scheme(), "http" | "https") || host.is_empty() || url.path() != "/" || url.query().is_some() {
+            return Err(DomainError::internal("federation base_url must be an origin like https://board.example"));
+        }
+        let base = url.as_str().trim_end_matches('/').to_owned();
+        Ok(Self { base, host })
+    }
+
+    /// Host name of the site, lowercase, as used in `acct:slug@host`.
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
+    /// Whether the site is served over plain HTTP (local testing).
+    pub fn is_http(&self) -> bool {
+        self.base.starts_with("http:")
+    }
+
+    pub fn actor(&self, board: &Board) -> String {
+        format!("{}/ap/boards/{}", self.base, board.slug.as_str())
+    }
+
+    pub fn key_id(&self, board: &Board) -> String {
+        format!("{}#main-key", self.actor(board))
+    }
+
+    pub fn followers(&self, board: &Board) -> String {
+        format!("{}/followers", self.actor(board))
+    }
+
+    pub fn note(&self, post_id: PostId) -> String {
+        format!("{}/ap/posts/{}", self.base, post_id.0)
+    }
+
+    /// The post whose note `note_id` is, if it is one of ours.
+    pub fn post_id_of(&self, note_id: &str) -> Option<PostId> {
+        let id = note_id.strip_prefix(&self.base)?.strip_prefix("/ap/posts/")?;
+        Uuid::parse_str(id).ok().map(PostId)
+    }
+}
+
+/// The board's actor document.
+pub fn actor(urls: &Urls, board: &Board, public_pem: &str) -> Value {
+    let id = urls.actor(board);
+    json!({
+        "@context": CONTEXT,
+        "id": id,
+        "type": "Group",
+        "preferredUsername": board.slug.as_str(),
+        "name": board.title,
+        "summary": paragraphs(&board.rules),
+        "url": format!("{}/board/{}", urls.base, board.slug.as_str()),
+        "inbox": format!("{id}/inbox"),
+        "outbox": format!("{id}/outbox"),
+        "followers": urls.followers(board),
+        "manuallyApprovesFollowers": false,
+        "publicKey": {
+            "id": urls.key_id(board),
+            "owner": id,
+            "publicKeyPem": public_pem,
+        },
+    })
+}
+
+/// WebFinger answer for `acct:{slug}@{host}`.
+pub fn webfinger(urls: &Urls, board: &Board) -> Value {
+    json!({
+        "subject": format!("acct:{}@{}", board.slug.as_str(), urls.host),
+        "aliases": [urls.actor(board)],
+        "links": [{ "rel": "self", "type": "application/activity+json", "href": urls.actor(board) }],
+    })
+}
+
+/// The board slug a WebFinger `resource` (`acct:g@host` or the actor URL) names.
+pub fn webfinger_slug<'a>(urls: &Urls, resource: &'a str) -> Option<&'a str> {
+    if let Some(account) = resource.strip_prefix("acct:") {
+        let (slug, host) = account.trim_start_matches('@').rsplit_once('@')?;
+        return host.eq_ignore_ascii_case(&urls.host).then_some(slug);
+    }
+    resource.strip_prefix(&urls.base)?.strip_prefix("/ap/boards/")
+}
+
+/// An `OrderedCollection` with a count but no items.
+pub fn collection(id: String, total_items: usize) -> Value {
+    json!({ "@context": CONTEXT[0], "id": id, "type": "OrderedCollection", "totalItems": total_items })
+}
+
+/// `post` as a note from the board; replies are `inReplyTo` the thread's OP.
+pub fn note(urls: &Urls, board: &Board, post: &Post, op: Option<PostId>, sensitive: bool) -> Value {
+    let name = post.name.as_deref().filter(|n| !n.is_empty()).unwrap_or("Anonymous");
+    let trip = post.tripcode.as_deref().map(|t| format!(" !{}", escape(t))).unwrap_or_default();
+    let header = format!("<p><strong>{}</strong>{trip} No.{}</p>", escape(name), post.post_number);
+    json!({
+        "id": urls.note(post.id),
+        "type": "Note",
+        "attributedTo": urls.actor(board),
+        "content": header + &paragraphs(&post.body),
+        "published": post.created_at.to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        "updated": post.edited_at.map(|at| at.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)),
+        "url": format!("{}/board/{}/thread/{}#post-{}", urls.base, board.slug.as_str(), post.thread_id.0, post.id.0),
+        "inReplyTo": op.filter(|op| *op != post.id).map(|op| urls.note(op)),
+        "sensitive": sensitive,
+        "to": [PUBLIC],
+        "cc": [urls.followers(board)],
+    })
+}
+
+/// `Create` of `note`, addressed like it.
+pub fn create(urls: &Urls, board: &Board, note: Value) -> Value {
+    json!({
+        "@context": CONTEXT[0],
+        "id": format!("{}/activity", note["id"].as_str().unwrap_or_default()),
+        "type": "Create",
+        "actor": urls.actor(board),
+        "published": note["published"],
+        "to": note["to"],
+        "cc": note["cc"],
+        "object": note,
+    })
+}
+
+/// `Update` of `note` after a staff edit. Each edit is its own activity.
+pub fn update(urls: &Urls, board: &Board, note: Value) -> Value {
+    json!({
+        "@context": CONTEXT[0],
+        "id": format!("{}#updates/{}", note["id"].as_str().unwrap_or_default(), Uuid::new_v4()),
+        "type": "Update",
+        "actor": urls.actor(board),
+        "published": note["updated"],
+        "to": note["to"],
+        "cc": note["cc"],
+        "object": note,
+    })
+}
+
+/// `Delete` of the note of `post_id`.
+pub fn delete(urls: &Urls, board: &Board, post_id: PostId) -> Value {
+    json!({
+        "@context": CONTEXT[0],
+        "id": format!("{}#delete", urls.note(post_id)),
+        "type": "Delete",
+        "actor": urls.actor(board),
+        "to": [PUBLIC],
+        "cc": [urls.followers(board)],
+        "object": { "id": urls.note(post_id), "type": "Tombstone" },
+    })
+}
+
+/// `Accept` of a `Follow` of the board.
+pub fn accept(urls: &Urls, board: &Board, follow: &Value) -> Value {
+    json!({
+        "@context": CONTEXT[0],
+        "id": format!("{}#accepts/{}", urls.actor(board), Uuid::new_v4()),
+        "type": "Accept",
+        "actor": urls.actor(board),
+        "object": follow,
+    })
+}
+
+/// The ID of an object given inline or as a bare ID.
+pub fn id_of(value: &Value) -> Option<&str> {
+    value.as_str().or_else(|| value["id"].as_str())
+}
+
+/// Plain text as HTML paragraphs; lines within one are joined by `<br>`.
+fn paragraphs(text: &str) -> String {
+    text.trim()
+        .split("\n\n")
+        .filter(|p| !p.trim().is_empty())
+        .map(|p| format!("<p>{}</p>", escape(p.trim()).replace('\n', "<br>")))
+        .collect()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// The text of a remote note's HTML `content`: paragraphs and line breaks
+/// kept, other markup dropped, and the `@board` mentions a reply starts
+/// with removed.
+pub fn html_to_text(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        text.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('>') else {
+            rest = "";
+            break;
+        };
+        let tag = rest[start + 1..start + end].trim().to_ascii_lowercase();
+        if tag.starts_with("br") {
+            text.push('\n');
+        } else if tag == "/p" {
+            text.push_str("\n\n");
+        }
+        rest = &rest[start + end + 1..];
+    }
+    text.push_str(rest);
+    let text = decode_entities(&text);
+
+    let mut body = text.trim_start();
+    while body.starts_with('@') {
+        body = body.split_once(char::is_whitespace).map_or("", |(_, after)| after).trim_start();
+    }
+    body.trim_end().to_owned()
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest[1..].find(';').filter(|&end| end <= 8).map(|end| &rest[1..=end]);
+        let character = entity.and_then(|e| match e {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            _ => e
+                .strip_prefix("#x")
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .or_else(|| e.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                .and_then(char::from_u32),
+        });
+        match (entity, character) {
+            (Some(entity), Some(character)) => {
+                decoded.push(character);
+                rest = &rest[entity.len() + 2..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use domains::models::{BoardId, IpHash, SiteId, Slug, ThreadId};
+
+    fn urls() -> Urls {
+        Urls::new("https://Board.example/").unwrap()
+    }
+
+    fn board() -> Board {
+        Board {
+            id: BoardId::new(), site_id: SiteId::DEFAULT, slug: Slug::new("g").unwrap(), title: "Technology".to_owned(),
+            rules: "Be nice.\n\nNo spam.".to_owned(), created_at: Utc::now(),
+        }
+    }
+
+    fn post(body: &str) -> Post {
+        Post {
+            id: PostId::new(), thread_id: ThreadId::new(), body: body.to_owned(), ip_hash: IpHash::new("secret"),
+            name: None, tripcode: None, email: None, created_at: Utc::now(), post_number: 7, pinned: false,
+            tags: vec![], commands: vec![], subject: None, capcode: None, deleted: None, edited_at: None,
+        }
+    }
+
+    #[test]
+    fn base_url_must_be_an_origin() {
+        assert_eq!(urls().host(), "board.example");
+        assert_eq!(urls().actor(&board()), "https://board.example/ap/boards/g");
+        assert!(Urls::new("https://board.example/sub").is_err());
+        assert!(Urls::new("ftp://board.example").is_err());
+    }
+
+    #[test]
+    fn replies_point_at_the_op() {
+        let (urls, board) = (urls(), board());
+        let op = post("first\n<b>line</b>");
+        let note = note(&urls, &board, &op, Some(op.id), false);
+        assert_eq!(note["inReplyTo"], Value::Null);
+        assert_eq!(note["content"], "<p><strong>Anonymous</strong> No.7</p><p>first<br>&lt;b&gt;line&lt;/b&gt;</p>");
+        assert!(!note.to_string().contains("secret"), "no IP hash in {note}");
+
+        let reply = self::note(&urls, &board, &post("hi"), Some(op.id), true);
+        assert_eq!(reply["inReplyTo"], urls.note(op.id));
+        assert_eq!(reply["sensitive"], true);
+        assert_eq!(urls.post_id_of(reply["inReplyTo"].as_str().unwrap()), Some(op.id));
+        assert_eq!(urls.post_id_of("https://elsewhere.example/ap/posts/1"), None);
+
+        let create = create(&urls, &board, reply.clone());
+        assert_eq!(create["object"], reply);
+        assert_eq!(create["id"], format!("{}/activity", urls.note(reply_id(&reply))));
+    }
+
+    #[test]
+    fn edits_update_the_note() {
+        let (urls, board) = (urls(), board());
+        let unedited = note(&urls, &board, &post("hi"), None, false);
+        assert_eq!(unedited["updated"], Value::Null);
+
+        let edited = Post { edited_at: Some(Utc::now()), ..post("[redacted]") };
+        let note = note(&urls, &board, &edited, None, false);
+        let first = update(&urls, &board, note.clone());
+        assert_eq!(first["type"], "Update");
+        assert_eq!(first["object"], note);
+        assert_eq!(first["published"], note["updated"]);
+        assert!(first["id"].as_str().unwrap().starts_with(&format!("{}#updates/", urls.note(edited.id))));
+        assert_ne!(first["id"], update(&urls, &board, note)["id"]);
+    }
+
+    fn reply_id(note: &Value) -> PostId {
+        urls().post_id_of(note["id"].as_str().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn webfinger_resources_name_a_board() {
+        let urls = urls();
+        assert_eq!(webfinger_slug(&urls, "acct:g@board.example"), Some("g"));
+        assert_eq!(webfinger_slug(&urls, "acct:@g@BOARD.example"), Some("g"));
+        assert_eq!(webfinger_slug(&urls, "https://board.example/ap/boards/g"), Some("g"));
+        assert_eq!(webfinger_slug(&urls, "acct:g@elsewhere.example"), None);
+        assert_eq!(webfinger(&urls, &board())["subject"], "acct:g@board.example");
+    }
+
+    #[test]
+    fn remote_html_becomes_text() {
+        let html = concat!(
+            r#"<p><span class="h-card"><a href="https://board.example/ap/boards/g" class="u-url mention">"#,
+            r#"@<span>g</span></a></span> same &amp; <i>more</i><br>next&#39;s&#x21;</p><p>two &lt;3</p>"#,
+        );
+        assert_eq!(html_to_text(html), "same & more\nnext's!\n\ntwo <3");
+        assert_eq!(html_to_text("AT&T &bogus; &"), "AT&T &bogus; &");
+    }
+}
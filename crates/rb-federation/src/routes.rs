@@ -0,0 +1,97 @@
+//! HTTP endpoints of the federated boards.
+//!
+//! - `GET  /.well-known/webfinger?resource=acct:slug@host`
+//! - `GET  /ap/boards/{slug}` — the board's `Group` actor
+//! - `POST /ap/boards/{slug}/inbox`
+//! - `GET  /ap/boards/{slug}/outbox`, `/followers`
+//! - `GET  /ap/posts/{id}` — a post as a `Note`
+//!
+//! Boards that are not federated answer 404 everywhere.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::body::Bytes;
+use axum::extract::{DefaultBodyLimit, Path, Query, State};
+use axum::http::{header, HeaderMap, Method, StatusCode, Uri};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::Router;
+use domains::errors::DomainError;
+use domains::models::PostId;
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::client::ACTIVITY_JSON;
+use crate::ActivityPubFederationSync;
+
+/// Largest activity accepted by an inbox.
+const MAX_ACTIVITY_BYTES: usize = 256 * 1024;
+
+type Federation = State<Arc<ActivityPubFederationSync>>;
+
+pub fn routes(federation: Arc<ActivityPubFederationSync>) -> Router {
+    Router::new()
+        .route("/.well-known/webfinger", get(webfinger))
+        .route("/ap/boards/{slug}", get(actor))
+        .route("/ap/boards/{slug}/inbox", post(inbox).layer(DefaultBodyLimit::max(MAX_ACTIVITY_BYTES)))
+        .route("/ap/boards/{slug}/outbox", get(outbox))
+        .route("/ap/boards/{slug}/followers", get(followers))
+        .route("/ap/posts/{id}", get(note))
+        .with_state(federation)
+}
+
+async fn webfinger(State(federation): Federation, Query(query): Query<HashMap<String, String>>) -> Response {
+    let resource = query.get("resource").map(String::as_str).unwrap_or_default();
+    document(federation.webfinger(resource).await, "application/jrd+json")
+}
+
+async fn actor(State(federation): Federation, Path(slug): Path<String>) -> Response {
+    document(federation.actor(&slug).await, ACTIVITY_JSON)
+}
+
+async fn outbox(State(federation): Federation, Path(slug): Path<String>) -> Response {
+    document(federation.outbox(&slug).await, ACTIVITY_JSON)
+}
+
+async fn followers(State(federation): Federation, Path(slug): Path<String>) -> Response {
+    document(federation.followers(&slug).await, ACTIVITY_JSON)
+}
+
+async fn note(State(federation): Federation, Path(id): Path<String>) -> Response {
+    let Ok(id) = Uuid::parse_str(&id) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    document(federation.note(PostId(id)).await, ACTIVITY_JSON)
+}
+
+async fn inbox(
+    State(federation): Federation,
+    Path(slug): Path<String>,
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let target = uri.path_and_query().map_or(uri.path(), |pq| pq.as_str());
+    match federation.receive(&slug, &method, target, &headers, &body).await {
+        Ok(()) => StatusCode::ACCEPTED.into_response(),
+        Err(DomainError::NotFound { .. }) => StatusCode::NOT_FOUND.into_response(),
+        Err(DomainError::Auth) => StatusCode::UNAUTHORIZED.into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "federation: inbox request failed");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+fn document(result: Result<Option<Value>, DomainError>, content_type: &'static str) -> Response {
+    match result {
+        Ok(Some(document)) => ([(header::CONTENT_TYPE, content_type)], document.to_string()).into_response(),
+        Ok(None) => StatusCode::NOT_FOUND.into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "federation: document lookup failed");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
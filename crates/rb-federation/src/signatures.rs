@@ -0,0 +1,216 @@
+//! HTTP Signatures (draft-cavage-http-signatures-12), as servers in the
+//! fediverse use them.
+//!
+//! Outgoing requests sign `(request-target) host date`, plus `digest` when
+//! they have a body. Incoming inbox requests must sign at least
+//! `(request-target) host date digest`, carry a `Digest` matching the body
+//! and a `Date` within `MAX_CLOCK_SKEW` of ours.
+
+use axum::http::{HeaderMap, HeaderName, HeaderValue, Method};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use domains::errors::DomainError;
+use sha2::{Digest, Sha256};
+
+use crate::keys::{self, InstanceKey};
+
+/// Largest accepted difference between a request's `Date` and the clock.
+pub const MAX_CLOCK_SKEW: chrono::Duration = chrono::Duration::hours(1);
+
+/// Headers an inbox request must sign.
+const REQUIRED: [&str; 4] = ["(request-target)", "host", "date", "digest"];
+
+/// `Digest` header value of `body`.
+pub fn digest(body: &[u8]) -> String {
+    format!("SHA-256={}", BASE64.encode(Sha256::digest(body)))
+}
+
+/// `Date` header value of `at` (RFC 7231 IMF-fixdate).
+pub fn http_date(at: DateTime<Utc>) -> String {
+    at.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+/// Set `Host`, `Date` (and `Digest` when `body` is given) on `headers`, and
+/// a `Signature` over them made with `key` under `key_id`.
+pub fn sign(
+    key: &InstanceKey,
+    key_id: &str,
+    method: &Method,
+    url: &reqwest::Url,
+    body: Option<&[u8]>,
+    headers: &mut HeaderMap,
+) -> Result<(), DomainError> {
+    let host = match url.port() {
+        Some(port) => format!("{}:{port}", url.host_str().unwrap_or_default()),
+        None => url.host_str().unwrap_or_default().to_owned(),
+    };
+    let mut names = vec!["(request-target)", "host", "date"];
+    insert(headers, "host", &host)?;
+    insert(headers, "date", &http_date(Utc::now()))?;
+    if let Some(body) = body {
+        insert(headers, "digest", &digest(body))?;
+        names.push("digest");
+    }
+    let target = match url.query() {
+        Some(query) => format!("{}?{query}", url.path()),
+        None => url.path().to_owned(),
+    };
+    let names: Vec<String> = names.into_iter().map(str::to_owned).collect();
+    let signing_string = signing_string(method, &target, headers, &names)
+        .ok_or_else(|| DomainError::internal("signing a request without its headers"))?;
+    let signature = format!(
+        r#"keyId="{key_id}",algorithm="rsa-sha256",headers="{}",signature="{}""#,
+        names.join(" "),
+        BASE64.encode(key.sign(signing_string.as_bytes())),
+    );
+    insert(headers, "signature", &signature)
+}
+
+fn insert(headers: &mut HeaderMap, name: &'static str, value: &str) -> Result<(), DomainError> {
+    let value = HeaderValue::from_str(value).map_err(|e| DomainError::internal(format!("{name} header: {e}")))?;
+    headers.insert(HeaderName::from_static(name), value);
+    Ok(())
+}
+
+/// The lines a signature covers: `name: value` for each of `names`, in order.
+fn signing_string(method: &Method, target: &str, headers: &HeaderMap, names: &[String]) -> Option<String> {
+    let mut lines = Vec::with_capacity(names.len());
+    for name in names {
+        if name == "(request-target)" {
+            lines.push(format!("(request-target): {} {target}", method.as_str().to_ascii_lowercase()));
+            continue;
+        }
+        let values: Vec<&str> = headers.get_all(name.as_str()).iter().map(|v| v.to_str().ok()).collect::<Option<_>>()?;
+        if values.is_empty() {
+            return None;
+        }
+        lines.push(format!("{name}: {}", values.join(", ")));
+    }
+    Some(lines.join("\n"))
+}
+
+/// An incoming request whose digest, date and signed headers were checked;
+/// `verify` checks the signature itself once the sender's key is fetched.
+#[derive(Debug)]
+pub struct SignedRequest {
+    /// The key the sender says it signed with, e.g. `https://social.example/users/alice#main-key`.
+    pub key_id:     String,
+    signing_string: String,
+    signature:      Vec<u8>,
+}
+
+impl SignedRequest {
+    /// Check everything about the request's signature but the key. Fails
+    /// with `DomainError::Auth` when the request is unsigned, signs too
+    /// little, or its `Digest` or `Date` are wrong.
+    pub fn from_request(
+        method: &Method,
+        target: &str,
+        headers: &HeaderMap,
+        body: &[u8],
+        now: DateTime<Utc>,
+    ) -> Result<Self, DomainError> {
+        let header = headers.get("signature").and_then(|v| v.to_str().ok()).ok_or(DomainError::Auth)?;
+        let params = parse_params(header);
+        let param = |name: &str| params.iter().find(|(n, _)| *n == name).map(|(_, v)| *v);
+        let key_id = param("keyId").ok_or(DomainError::Auth)?;
+        if param("algorithm").is_some_and(|a| !matches!(a, "rsa-sha256" | "hs2019")) {
+            return Err(DomainError::Auth);
+        }
+        let names: Vec<String> =
+            param("headers").unwrap_or("date").split_ascii_whitespace().map(str::to_ascii_lowercase).collect();
+        if REQUIRED.iter().any(|required| !names.iter().any(|n| n == required)) {
+            return Err(DomainError::Auth);
+        }
+        let signature = BASE64.decode(param("signature").ok_or(DomainError::Auth)?).map_err(|_| DomainError::Auth)?;
+
+        let sent_digest = headers.get("digest").and_then(|v| v.to_str().ok()).ok_or(DomainError::Auth)?;
+        let expected = digest(body);
+        if !sent_digest.split(',').any(|d| d.trim() == expected) {
+            return Err(DomainError::Auth);
+        }
+        let date = headers
+            .get("date")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|d| DateTime::parse_from_rfc2822(d).ok())
+            .ok_or(DomainError::Auth)?;
+        if (now - date.with_timezone(&Utc)).abs() > MAX_CLOCK_SKEW {
+            return Err(DomainError::Auth);
+        }
+
+        let signing_string = signing_string(method, target, headers, &names).ok_or(DomainError::Auth)?;
+        Ok(Self { key_id: key_id.to_owned(), signing_string, signature })
+    }
+
+    /// Whether the request was signed by the key in `public_pem`.
+    pub fn verify(&self, public_pem: &str) -> bool {
+        keys::verify(public_pem, self.signing_string.as_bytes(), &self.signature)
+    }
+}
+
+/// `name="value"` pairs of a `Signature` header.
+fn parse_params(header: &str) -> Vec<(&str, &str)> {
+    let mut params = Vec::new();
+    let mut rest = header.trim();
+    while let Some((name, after)) = rest.split_once('=') {
+        let Some(after) = after.strip_prefix('"') else {
+            break;
+        };
+        let Some((value, after)) = after.split_once('"') else {
+            break;
+        };
+        params.push((name.trim(), value));
+        rest = after.trim_start_matches([',', ' ']);
+    }
+    params
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keys::test_key;
+
+    const KEY_ID: &str = "https://board.example/ap/boards/g#main-key";
+
+    fn signed(body: &[u8]) -> HeaderMap {
+        let url = reqwest::Url::parse("https://remote.example:8443/inbox?x=1").unwrap();
+        let mut headers = HeaderMap::new();
+        sign(&test_key(), KEY_ID, &Method::POST, &url, Some(body), &mut headers).unwrap();
+        headers
+    }
+
+    #[test]
+    fn signed_requests_verify() {
+        let headers = signed(b"{}");
+        assert_eq!(headers["host"], "remote.example:8443");
+        let request = SignedRequest::from_request(&Method::POST, "/inbox?x=1", &headers, b"{}", Utc::now()).unwrap();
+        assert_eq!(request.key_id, KEY_ID);
+        assert!(request.verify(test_key().public_pem()));
+
+        let elsewhere = SignedRequest::from_request(&Method::POST, "/other", &headers, b"{}", Utc::now()).unwrap();
+        assert!(!elsewhere.verify(test_key().public_pem()));
+    }
+
+    #[test]
+    fn tampered_or_stale_requests_are_refused() {
+        let headers = signed(b"{}");
+        let check = |headers: &HeaderMap, body: &[u8], now| {
+            SignedRequest::from_request(&Method::POST, "/inbox?x=1", headers, body, now).map(|_| ())
+        };
+        assert!(matches!(check(&headers, b"{ }", Utc::now()), Err(DomainError::Auth)));
+        assert!(matches!(check(&headers, b"{}", Utc::now() + chrono::Duration::hours(2)), Err(DomainError::Auth)));
+        assert!(matches!(check(&HeaderMap::new(), b"{}", Utc::now()), Err(DomainError::Auth)));
+
+        let mut unsigned_digest = headers.clone();
+        let signature = headers["signature"].to_str().unwrap().replace(" digest", "");
+        unsigned_digest.insert("signature", HeaderValue::from_str(&signature).unwrap());
+        assert!(matches!(check(&unsigned_digest, b"{}", Utc::now()), Err(DomainError::Auth)));
+    }
+
+    #[test]
+    fn signature_params_are_parsed() {
+        let params = parse_params(r#"keyId="a#b",algorithm="hs2019", headers="date host",signature="c=d""#);
+        assert_eq!(params, [("keyId", "a#b"), ("algorithm", "hs2019"), ("headers", "date host"), ("signature", "c=d")]);
+    }
+}
@@ -0,0 +1,350 @@
+//! Syntax highlighting for fenced code blocks.
+//!
+//! A small tokenizer per language family: comments, strings, numbers,
+//! keywords, literals and (for languages that capitalise them) type names,
+//! each wrapped in a `<span class="hl-*">`. It does not parse, so unusual
+//! code may be coloured wrongly, but every byte still goes through
+//! `escape_into` and the text is unchanged. Whitespace is left as typed;
+//! the surrounding `<pre>` keeps it.
+
+use crate::escape_into;
+
+/// How one language is tokenized.
+pub(crate) struct Language {
+    /// Canonical name, emitted as `data-lang`.
+    pub name:       &'static str,
+    aliases:        &'static [&'static str],
+    keywords:       &'static [&'static str],
+    literals:       &'static [&'static str],
+    line_comments:  &'static [&'static str],
+    block_comment:  Option<(&'static str, &'static str)>,
+    quotes:         &'static [char],
+    /// `"""` and `'''` strings may span lines.
+    triple_quotes:  bool,
+    /// `'a` is a lifetime, not the start of a character literal.
+    lifetimes:      bool,
+    /// Capitalised identifiers are types.
+    types:          bool,
+    /// Keywords match in any case.
+    ignore_case:    bool,
+}
+
+const C_COMMENTS: Option<(&str, &str)> = Some(("/*", "*/"));
+
+const BASE: Language = Language {
+    name:          "",
+    aliases:       &[],
+    keywords:      &[],
+    literals:      &[],
+    line_comments: &[],
+    block_comment: None,
+    quotes:        &['"'],
+    triple_quotes: false,
+    lifetimes:     false,
+    types:         false,
+    ignore_case:   false,
+};
+
+const LANGUAGES: &[Language] = &[
+    Language {
+        name:          "rust",
+        aliases:       &["rs"],
+        keywords:      &[
+            "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern", "fn",
+            "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return", "self",
+            "static", "struct", "super", "trait", "type", "unsafe", "use", "where", "while",
+        ],
+        literals:      &["true", "false"],
+        line_comments: &["//"],
+        block_comment: C_COMMENTS,
+        quotes:        &['"', '\''],
+        lifetimes:     true,
+        types:         true,
+        ..BASE
+    },
+    Language {
+        name:          "c",
+        aliases:       &["h", "cpp", "c++", "cc", "hpp", "cxx"],
+        keywords:      &[
+            "auto", "break", "case", "char", "class", "const", "constexpr", "continue", "default", "delete", "do",
+            "double", "else", "enum", "extern", "float", "for", "goto", "if", "include", "define", "inline", "int",
+            "long", "namespace", "new", "private", "protected", "public", "return", "short", "signed", "sizeof",
+            "static", "struct", "switch", "template", "this", "typedef", "union", "unsigned", "using", "virtual",
+            "void", "volatile", "while",
+        ],
+        literals:      &["true", "false", "NULL", "nullptr"],
+        line_comments: &["//"],
+        block_comment: C_COMMENTS,
+        quotes:        &['"', '\''],
+        types:         true,
+        ..BASE
+    },
+    Language {
+        name:          "python",
+        aliases:       &["py", "python3"],
+        keywords:      &[
+            "and", "as", "assert", "async", "await", "break", "class", "continue", "def", "del", "elif", "else",
+            "except", "finally", "for", "from", "global", "if", "import", "in", "is", "lambda", "nonlocal", "not",
+            "or", "pass", "raise", "return", "try", "while", "with", "yield",
+        ],
+        literals:      &["True", "False", "None"],
+        line_comments: &["#"],
+        quotes:        &['"', '\''],
+        triple_quotes: true,
+        ..BASE
+    },
+    Language {
+        name:          "javascript",
+        aliases:       &["js", "jsx", "ts", "tsx", "typescript"],
+        keywords:      &[
+            "async", "await", "break", "case", "catch", "class", "const", "continue", "debugger", "default",
+            "delete", "do", "else", "enum", "export", "extends", "finally", "for", "function", "if", "implements",
+            "import", "in", "instanceof", "interface", "let", "new", "of", "return", "static", "super", "switch",
+            "this", "throw", "try", "type", "typeof", "var", "void", "while", "yield",
+        ],
+        literals:      &["true", "false", "null", "undefined", "NaN"],
+        line_comments: &["//"],
+        block_comment: C_COMMENTS,
+        quotes:        &['"', '\'', '`'],
+        types:         true,
+        ..BASE
+    },
+    Language {
+        name:          "go",
+        aliases:       &["golang"],
+        keywords:      &[
+            "break", "case", "chan", "const", "continue", "default", "defer", "else", "fallthrough", "for", "func",
+            "go", "goto", "if", "import", "interface", "map", "package", "range", "return", "select", "struct",
+            "switch", "type", "var",
+        ],
+        literals:      &["true", "false", "nil", "iota"],
+        line_comments: &["//"],
+        block_comment: C_COMMENTS,
+        quotes:        &['"', '\'', '`'],
+        types:         true,
+        ..BASE
+    },
+    Language {
+        name:          "java",
+        aliases:       &["kotlin", "kt", "cs", "csharp"],
+        keywords:      &[
+            "abstract", "break", "case", "catch", "class", "continue", "default", "do", "else", "enum", "extends",
+            "final", "finally", "for", "fun", "if", "implements", "import", "interface", "new", "package",
+            "private", "protected", "public", "return", "static", "super", "switch", "this", "throw", "throws",
+            "try", "val", "var", "void", "while",
+        ],
+        literals:      &["true", "false", "null"],
+        line_comments: &["//"],
+        block_comment: C_COMMENTS,
+        quotes:        &['"', '\''],
+        types:         true,
+        ..BASE
+    },
+    Language {
+        name:          "shell",
+        aliases:       &["sh", "bash", "zsh", "console"],
+        keywords:      &[
+            "case", "do", "done", "elif", "else", "esac", "export", "fi", "for", "function", "if", "in", "local",
+            "return", "then", "until", "while",
+        ],
+        line_comments: &["#"],
+        quotes:        &['"', '\''],
+        ..BASE
+    },
+    Language {
+        name:          "sql",
+        aliases:       &["postgres", "sqlite", "mysql"],
+        keywords:      &[
+            "add", "alter", "and", "as", "asc", "begin", "by", "commit", "create", "default", "delete", "desc",
+            "distinct", "drop", "from", "group", "having", "index", "inner", "insert", "into", "join", "key",
+            "left", "limit", "not", "offset", "on", "or", "order", "outer", "primary", "references", "right",
+            "select", "set", "table", "union", "update", "values", "where", "with",
+        ],
+        literals:      &["null", "true", "false"],
+        line_comments: &["--"],
+        block_comment: C_COMMENTS,
+        quotes:        &['\''],
+        ignore_case:   true,
+        ..BASE
+    },
+    Language {
+        name:     "json",
+        literals: &["true", "false", "null"],
+        ..BASE
+    },
+    Language {
+        name:          "toml",
+        aliases:       &["ini"],
+        literals:      &["true", "false"],
+        line_comments: &["#"],
+        quotes:        &['"', '\''],
+        ..BASE
+    },
+];
+
+/// The language a fence's info string names, if it is one we highlight.
+pub(crate) fn language(info: &str) -> Option<&'static Language> {
+    LANGUAGES
+        .iter()
+        .find(|lang| lang.name.eq_ignore_ascii_case(info) || lang.aliases.iter().any(|a| a.eq_ignore_ascii_case(info)))
+}
+
+/// Append `code`, highlighted as `lang`, to `out`.
+pub(crate) fn render(code: &str, lang: &Language, out: &mut String) {
+    let mut i = 0;
+    while i < code.len() {
+        let rest = &code[i..];
+        let (len, class) = token(rest, lang, i == 0 || !is_ident(code[..i].chars().next_back().unwrap_or(' ')));
+        match class {
+            Some(class) => {
+                out.push_str("<span class=\"hl-");
+                out.push_str(class);
+                out.push_str("\">");
+                escape_into(&rest[..len], out);
+                out.push_str("</span>");
+            }
+            None => escape_into(&rest[..len], out),
+        }
+        i += len;
+    }
+}
+
+/// The token `text` starts with: its length and class. `word_start` is
+/// whether the previous character ends a word, so `x1` is not a number.
+fn token(text: &str, lang: &Language, word_start: bool) -> (usize, Option<&'static str>) {
+    if let Some((open, close)) = lang.block_comment {
+        if let Some(after) = text.strip_prefix(open) {
+            let len = after.find(close).map_or(text.len(), |end| open.len() + end + close.len());
+            return (len, Some("comment"));
+        }
+    }
+    if lang.line_comments.iter().any(|prefix| text.starts_with(prefix)) {
+        return (text.find('\n').unwrap_or(text.len()), Some("comment"));
+    }
+    let c = text.chars().next().unwrap_or_default();
+    if lang.quotes.contains(&c) {
+        if let Some(len) = string(text, c, lang) {
+            return (len, Some("string"));
+        }
+    }
+    if word_start && c.is_ascii_digit() {
+        let len = text.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '.')).unwrap_or(text.len());
+        return (len, Some("number"));
+    }
+    if is_ident(c) && !c.is_ascii_digit() {
+        let len = text.find(|c: char| !is_ident(c)).unwrap_or(text.len());
+        let word = &text[..len];
+        let matches = |list: &[&str]| {
+            list.iter().any(|k| if lang.ignore_case { k.eq_ignore_ascii_case(word) } else { *k == word })
+        };
+        let class = if matches(lang.keywords) {
+            Some("keyword")
+        } else if matches(lang.literals) {
+            Some("literal")
+        } else if lang.types && c.is_ascii_uppercase() {
+            Some("type")
+        } else {
+            None
+        };
+        return (len, class);
+    }
+    (c.len_utf8(), None)
+}
+
+/// Length of the string literal opened by `quote` at the start of `text`,
+/// or `None` if this quote does not open one. Strings end at their closing
+/// quote or the end of the line; triple-quoted ones may span lines.
+fn string(text: &str, quote: char, lang: &Language) -> Option<usize> {
+    if lang.triple_quotes {
+        let triple: String = [quote; 3].iter().collect();
+        if let Some(after) = text.strip_prefix(triple.as_str()) {
+            return Some(after.find(triple.as_str()).map_or(text.len(), |end| 6 + end));
+        }
+    }
+    if lang.lifetimes && quote == '\'' {
+        // `'a'` and `'\n'` are characters; `'a` is a lifetime.
+        let mut chars = text[1..].chars();
+        let first = chars.next()?;
+        let closed = match first {
+            '\\' => text[1..].find('\'').is_some_and(|end| end <= 10),
+            _ => chars.next() == Some('\''),
+        };
+        if !closed {
+            return None;
+        }
+    }
+    let mut escaped = false;
+    for (i, c) in text.char_indices().skip(1) {
+        match c {
+            '\n' if quote != '`' => return Some(i),
+            '\\' if !escaped => escaped = true,
+            c if c == quote && !escaped => return Some(i + c.len_utf8()),
+            _ => escaped = false,
+        }
+    }
+    Some(text.len())
+}
+
+fn is_ident(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn highlight(lang: &str, code: &str) -> String {
+        let mut out = String::new();
+        render(code, language(lang).unwrap(), &mut out);
+        out
+    }
+
+    #[test]
+    fn languages_are_found_by_alias() {
+        assert_eq!(language("RS").map(|l| l.name), Some("rust"));
+        assert_eq!(language("c++").map(|l| l.name), Some("c"));
+        assert_eq!(language("ts").map(|l| l.name), Some("javascript"));
+        assert!(language("brainfuck").is_none());
+    }
+
+    #[test]
+    fn rust_tokens() {
+        assert_eq!(
+            highlight("rust", "fn main() -> Option<&'a str> { let x1 = 42; \"hi\\\"\" } // done"),
+            concat!(
+                "<span class=\"hl-keyword\">fn</span> main() -&gt; ",
+                "<span class=\"hl-type\">Option</span>&lt;&amp;&#39;a ",
+                "str&gt; { <span class=\"hl-keyword\">let</span> x1 = <span class=\"hl-number\">42</span>; ",
+                "<span class=\"hl-string\">&quot;hi\\&quot;&quot;</span> } <span class=\"hl-comment\">// done</span>",
+            ),
+        );
+        assert_eq!(highlight("rust", "'x'"), "<span class=\"hl-string\">&#39;x&#39;</span>");
+    }
+
+    #[test]
+    fn comments_and_strings_span_lines_where_the_language_allows() {
+        assert_eq!(highlight("c", "/* a\nb */x"), "<span class=\"hl-comment\">/* a\nb */</span>x");
+        assert_eq!(
+            highlight("python", "\"\"\"a\nb\"\"\""),
+            "<span class=\"hl-string\">&quot;&quot;&quot;a\nb&quot;&quot;&quot;</span>",
+        );
+        assert_eq!(highlight("shell", "echo \"a\nb"), "echo <span class=\"hl-string\">&quot;a</span>\nb");
+    }
+
+    #[test]
+    fn sql_keywords_ignore_case() {
+        assert_eq!(
+            highlight("sql", "SELECT * FROM t -- all"),
+            concat!(
+                "<span class=\"hl-keyword\">SELECT</span> * <span class=\"hl-keyword\">FROM</span> t ",
+                "<span class=\"hl-comment\">-- all</span>",
+            ),
+        );
+    }
+
+    #[test]
+    fn unterminated_tokens_run_to_the_end() {
+        assert_eq!(highlight("javascript", "/* open"), "<span class=\"hl-comment\">/* open</span>");
+        assert_eq!(highlight("go", "`raw\nstring"), "<span class=\"hl-string\">`raw\nstring</span>");
+    }
+}
@@ -0,0 +1,142 @@
+//! Formatting within one line: paired delimiters and quote links.
+
+use crate::{escape_into, Options};
+
+/// Which toggle a span belongs to.
+enum Kind {
+    Markup,
+    Spoiler,
+    Code,
+}
+
+/// A pair of delimiters and the element they become.
+struct Span {
+    open:   &'static str,
+    close:  &'static str,
+    before: &'static str,
+    after:  &'static str,
+    kind:   Kind,
+    /// The text inside may not start or end with whitespace, so `2 * 3 * 4`
+    /// stays as typed.
+    tight:  bool,
+}
+
+const fn pair(open: &'static str, close: &'static str, before: &'static str, after: &'static str, kind: Kind) -> Span {
+    Span { open, close, before, after, kind, tight: false }
+}
+
+/// Tried in order at each position; `**` must come before `*`.
+const SPANS: &[Span] = &[
+    pair("`", "`", "<code>", "</code>", Kind::Code),
+    pair("[code]", "[/code]", "<code>", "</code>", Kind::Code),
+    pair("[spoiler]", "[/spoiler]", "<span class=\"spoiler\">", "</span>", Kind::Spoiler),
+    Span { tight: true, ..pair("||", "||", "<span class=\"spoiler\">", "</span>", Kind::Spoiler) },
+    pair("**", "**", "<strong>", "</strong>", Kind::Markup),
+    pair("[b]", "[/b]", "<strong>", "</strong>", Kind::Markup),
+    Span { tight: true, ..pair("*", "*", "<em>", "</em>", Kind::Markup) },
+    pair("[i]", "[/i]", "<em>", "</em>", Kind::Markup),
+    pair("__", "__", "<u>", "</u>", Kind::Markup),
+    pair("[u]", "[/u]", "<u>", "</u>", Kind::Markup),
+    pair("~~", "~~", "<s>", "</s>", Kind::Markup),
+    pair("[s]", "[/s]", "<s>", "</s>", Kind::Markup),
+    Span { tight: true, ..pair("==", "==", "<span class=\"redtext\">", "</span>", Kind::Markup) },
+    pair("(((", ")))", "<span class=\"bluetext\">", "</span>", Kind::Markup),
+];
+
+/// Render one line (or the inside of a span) into `out`.
+pub(crate) fn render(text: &str, options: &Options, out: &mut String) {
+    let mut i = 0;
+    while i < text.len() {
+        let rest = &text[i..];
+        if let Some(len) = span_at(rest, options, out).or_else(|| quote_link_at(rest, out)) {
+            i += len;
+            continue;
+        }
+        let len = rest.chars().next().map_or(1, char::len_utf8);
+        escape_into(&rest[..len], out);
+        i += len;
+    }
+}
+
+/// Render the span `text` starts with, if any, returning its length.
+fn span_at(text: &str, options: &Options, out: &mut String) -> Option<usize> {
+    for span in SPANS {
+        let enabled = match span.kind {
+            Kind::Markup => options.markup,
+            Kind::Spoiler => options.spoilers,
+            Kind::Code => options.code,
+        };
+        if !enabled || !starts_with_ci(text, span.open) {
+            continue;
+        }
+        let after = span.open.len();
+        let Some(len) = find_ci(&text[after..], span.close) else { continue };
+        let inner = &text[after..after + len];
+        let loose = inner.starts_with(char::is_whitespace) || inner.ends_with(char::is_whitespace);
+        if inner.is_empty() || (span.tight && loose) {
+            continue;
+        }
+        out.push_str(span.before);
+        match span.kind {
+            Kind::Code => escape_into(inner, out),
+            Kind::Markup | Kind::Spoiler => render(inner, options, out),
+        }
+        out.push_str(span.after);
+        return Some(after + len + span.close.len());
+    }
+    None
+}
+
+/// Render the quote link `text` starts with, if any, returning its length.
+/// Only digits and `[a-z0-9_-]` slugs reach the attributes.
+fn quote_link_at(text: &str, out: &mut String) -> Option<usize> {
+    if let Some(rest) = text.strip_prefix(">>>/") {
+        let slug_len = rest
+            .bytes()
+            .take_while(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || matches!(b, b'_' | b'-'))
+            .count();
+        if slug_len > 0 && rest[slug_len..].starts_with('/') {
+            let slug = &rest[..slug_len];
+            let number = digits(&rest[slug_len + 1..]);
+            if number.is_empty() {
+                out.push_str(&format!(
+                    "<a class=\"post-quote-link cross-board\" href=\"/board/{slug}\">&gt;&gt;&gt;/{slug}/</a>"
+                ));
+            } else {
+                out.push_str(&format!(
+                    "<a class=\"post-quote-link cross-board\" href=\"/board/{slug}/post/{number}\" \
+                     data-xboard=\"{slug}\" data-target=\"{number}\">&gt;&gt;&gt;/{slug}/{number}</a>"
+                ));
+            }
+            return Some(">>>/".len() + slug_len + 1 + number.len());
+        }
+    }
+    let number = digits(text.strip_prefix(">>")?);
+    if number.is_empty() {
+        return None;
+    }
+    out.push_str(&format!(
+        "<a class=\"post-quote-link\" href=\"#post-{number}\" data-target=\"{number}\">&gt;&gt;{number}</a>"
+    ));
+    Some(">>".len() + number.len())
+}
+
+/// The ASCII digits `text` starts with.
+fn digits(text: &str) -> &str {
+    &text[..text.bytes().take_while(u8::is_ascii_digit).count()]
+}
+
+/// Whether `text` starts with the ASCII `pattern`, ignoring ASCII case.
+pub(crate) fn starts_with_ci(text: &str, pattern: &str) -> bool {
+    text.len() >= pattern.len() && text.as_bytes()[..pattern.len()].eq_ignore_ascii_case(pattern.as_bytes())
+}
+
+/// Byte offset of the first match of the ASCII `pattern` in `text`, ignoring
+/// ASCII case. A match of an ASCII pattern always starts on a char boundary.
+pub(crate) fn find_ci(text: &str, pattern: &str) -> Option<usize> {
+    let (text, pattern) = (text.as_bytes(), pattern.as_bytes());
+    if text.len() < pattern.len() {
+        return None;
+    }
+    (0..=text.len() - pattern.len()).find(|&i| text[i..i + pattern.len()].eq_ignore_ascii_case(pattern))
+}
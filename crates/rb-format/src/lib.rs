@@ -0,0 +1,429 @@
+//! `rb-format` — post bodies rendered to HTML on the server.
+//!
+//! `render` is the only way a post body reaches a page as markup. The body is
+//! parsed as plain text and every byte of it is HTML-escaped on output; the
+//! only tags in the result are the fixed ones below, with fixed attributes.
+//! Quote-link attributes carry nothing but digits and board-slug characters.
+//!
+//! | Syntax                                   | Output                          | Toggle      |
+//! |------------------------------------------|---------------------------------|-------------|
+//! | `**b**` `[b]b[/b]`                       | `<strong>`                      | `markup`    |
+//! | `*i*` `[i]i[/i]`                         | `<em>`                          | `markup`    |
+//! | `__u__` `[u]u[/u]`                       | `<u>`                           | `markup`    |
+//! | `~~s~~` `[s]s[/s]`                       | `<s>`                           | `markup`    |
+//! | `==red==` `(((blue)))`                   | `<span class="redtext">` / `bluetext` | `markup` |
+//! | `[spoiler]…[/spoiler]` `\|\|…\|\|`         | `<span class="spoiler">`        | `spoilers`  |
+//! | `` `code` `` `[code]…[/code]` (one line) | `<code>`                        | `code`      |
+//! | ```` ```…``` ```` `[code]…[/code]` (lines) | `<pre class="code-block"><code>` | `code`    |
+//! | ```` ```rust ```` info string            | `<span class="hl-*">` tokens    | `highlight` |
+//! | line starting `>` / `<`                  | `<span class="greentext">` / `pinktext` | `greentext` |
+//! | `>>N` `>>>/slug/N` `>>>/slug/`           | `<a class="post-quote-link">`   | always      |
+//!
+//! Markup never spans lines, except spoilers and code blocks. A delimiter
+//! without its closing half is shown as typed. Code is shown verbatim: no
+//! formatting applies inside it. A fence's info string (```` ```py ````) is
+//! dropped; with `highlight` on and a language `highlight.rs` knows, the
+//! block is also coloured.
+//!
+//! Static pages (rules, FAQ) go through `page::render`, a Markdown subset
+//! built on the same inline pass.
+
+mod highlight;
+mod inline;
+pub mod page;
+
+use domains::models::BoardConfig;
+
+/// Which formatting a board allows. Quote links are always rendered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    /// Bold, italic, underline, strike, red and blue text.
+    pub markup:    bool,
+    /// `[spoiler]` text.
+    pub spoilers:  bool,
+    /// Inline code and code blocks.
+    pub code:      bool,
+    /// Greentext and pinktext lines.
+    pub greentext: bool,
+    /// Syntax highlighting of fenced code blocks that name their language.
+    pub highlight: bool,
+}
+
+impl Default for Options {
+    /// Everything on.
+    fn default() -> Self {
+        Self { markup: true, spoilers: true, code: true, greentext: true, highlight: true }
+    }
+}
+
+impl Options {
+    /// The toggles of one board.
+    pub fn for_board(config: &BoardConfig) -> Self {
+        Self {
+            markup:    config.markup_enabled,
+            spoilers:  config.spoilers_enabled,
+            code:      config.code_enabled,
+            greentext: config.greentext_enabled,
+            highlight: config.code_highlighting,
+        }
+    }
+}
+
+/// Render a post body to HTML safe to embed unescaped.
+pub fn render(body: &str, options: &Options) -> String {
+    let body = body.replace("\r\n", "\n");
+    let mut out = String::with_capacity(body.len() + body.len() / 4);
+    blocks(&body, true, options, &mut out);
+    out
+}
+
+/// `body` rendered and cut to `max_chars` visible characters, for previews
+/// such as catalog excerpts. Elements still open at the cut are closed, so
+/// a spoiler cut short stays hidden.
+pub fn snippet(body: &str, options: &Options, max_chars: usize) -> String {
+    let html = render(body, options);
+    let mut out = String::with_capacity(html.len().min(max_chars * 4));
+    let mut open: Vec<String> = Vec::new();
+    let mut visible = 0;
+    let mut rest = html.as_str();
+    while let Some(c) = rest.chars().next() {
+        let closing = rest.starts_with("</");
+        if visible == max_chars && !closing {
+            // Only closing tags may follow the last character kept.
+            out.push('…');
+            break;
+        }
+        let len = match c {
+            '<' => rest.find('>').map_or(rest.len(), |i| i + 1),
+            '&' => rest.find(';').map_or(1, |i| i + 1),
+            _ => c.len_utf8(),
+        };
+        let (piece, tail) = rest.split_at(len);
+        if closing {
+            open.pop();
+        } else if c == '<' {
+            let name = piece[1..].split([' ', '>']).next().unwrap_or_default();
+            open.push(format!("</{name}>"));
+        } else {
+            visible += 1;
+        }
+        out.push_str(piece);
+        rest = tail;
+    }
+    for tag in open.iter().rev() {
+        out.push_str(tag);
+    }
+    out
+}
+
+/// Append `text` to `out`, HTML-escaped.
+pub(crate) fn escape_into(text: &str, out: &mut String) {
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+}
+
+/// A construct that may span lines.
+enum Block {
+    Fenced,
+    Code,
+    Spoiler,
+}
+
+impl Block {
+    fn delimiters(&self) -> (&'static str, &'static str) {
+        match self {
+            Block::Fenced => ("```", "```"),
+            Block::Code => ("[code]", "[/code]"),
+            Block::Spoiler => ("[spoiler]", "[/spoiler]"),
+        }
+    }
+}
+
+/// Render `text`, taking out code blocks and multi-line spoilers first.
+/// `line_start` is whether `text` begins at the start of a line, which
+/// decides if its first line may be greentext.
+fn blocks(text: &str, line_start: bool, options: &Options, out: &mut String) {
+    let mut rest = text;
+    let mut rest_line_start = line_start;
+    while let Some((start, block, inner, end)) = next_block(rest, options) {
+        lines(&rest[..start], rest_line_start, options, out);
+        match block {
+            Block::Fenced | Block::Code => {
+                let (info, inner) = match block {
+                    Block::Fenced => fence_info(inner),
+                    _ => (None, inner),
+                };
+                let inner = inner.strip_prefix('\n').unwrap_or(inner);
+                let inner = inner.strip_suffix('\n').unwrap_or(inner);
+                match info.and_then(highlight::language).filter(|_| options.highlight) {
+                    Some(lang) => {
+                        out.push_str("<pre class=\"code-block\" data-lang=\"");
+                        out.push_str(lang.name);
+                        out.push_str("\"><code>");
+                        highlight::render(inner, lang, out);
+                    }
+                    None => {
+                        out.push_str("<pre class=\"code-block\"><code>");
+                        escape_into(inner, out);
+                    }
+                }
+                out.push_str("</code></pre>");
+            }
+            Block::Spoiler => {
+                out.push_str("<span class=\"spoiler\">");
+                blocks(inner, false, options, out);
+                out.push_str("</span>");
+            }
+        }
+        rest = &rest[end..];
+        rest_line_start = false;
+    }
+    lines(rest, rest_line_start, options, out);
+}
+
+/// The first block in `text`: its start, kind, contents and end. `[code]`
+/// and `[spoiler]` within one line are left to the inline pass; a fence is a
+/// block even on one line.
+fn next_block<'a>(text: &'a str, options: &Options) -> Option<(usize, Block, &'a str, usize)> {
+    let bytes = text.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        for block in [Block::Fenced, Block::Code, Block::Spoiler] {
+            let enabled = match block {
+                Block::Fenced | Block::Code => options.code,
+                Block::Spoiler => options.spoilers,
+            };
+            let (open, close) = block.delimiters();
+            if !enabled || !inline::starts_with_ci(&text[i..], open) {
+                continue;
+            }
+            let after = i + open.len();
+            let Some(len) = inline::find_ci(&text[after..], close) else { continue };
+            let inner = &text[after..after + len];
+            if inner.is_empty() || (!matches!(block, Block::Fenced) && !inner.contains('\n')) {
+                continue;
+            }
+            return Some((i, block, inner, after + len + close.len()));
+        }
+        // Delimiters are ASCII, so only ASCII positions need checking.
+        i += 1;
+        while i < bytes.len() && !text.is_char_boundary(i) {
+            i += 1;
+        }
+    }
+    None
+}
+
+/// Split a fence's info string (```` ```rust ````) from its code. Only a
+/// single word directly after the fence, followed by a line break, counts.
+fn fence_info(inner: &str) -> (Option<&str>, &str) {
+    match inner.split_once('\n') {
+        Some((first, code))
+            if !first.is_empty()
+                && first.len() <= 20
+                && first.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '#' | '-' | '_')) =>
+        {
+            (Some(first), code)
+        }
+        _ => (None, inner),
+    }
+}
+
+/// Render `text` line by line.
+fn lines(text: &str, line_start: bool, options: &Options, out: &mut String) {
+    for (n, line) in text.split('\n').enumerate() {
+        if n > 0 {
+            out.push('\n');
+        }
+        self::line(line, line_start || n > 0, options, out);
+    }
+}
+
+fn line(line: &str, line_start: bool, options: &Options, out: &mut String) {
+    let trimmed = line.trim_start();
+    let class = if !line_start || !options.greentext {
+        None
+    } else if trimmed.starts_with('>') && !trimmed.starts_with(">>") {
+        Some("greentext")
+    } else if trimmed.starts_with('<') {
+        Some("pinktext")
+    } else {
+        None
+    };
+    match class {
+        Some(class) => {
+            out.push_str("<span class=\"");
+            out.push_str(class);
+            out.push_str("\">");
+            inline::render(line, options, out);
+            out.push_str("</span>");
+        }
+        None => inline::render(line, options, out),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn html(body: &str) -> String {
+        render(body, &Options::default())
+    }
+
+    #[test]
+    fn plain_text_is_escaped() {
+        assert_eq!(html("a < b && c > \"d\" 'e'"), "a &lt; b &amp;&amp; c &gt; &quot;d&quot; &#39;e&#39;");
+    }
+
+    #[test]
+    fn emphasis() {
+        assert_eq!(html("**bold** and *italic*"), "<strong>bold</strong> and <em>italic</em>");
+        assert_eq!(html("[b]bold[/B] and [i]italic[/i]"), "<strong>bold</strong> and <em>italic</em>");
+        assert_eq!(html("__u__ ~~s~~ [u]u[/u] [s]s[/s]"), "<u>u</u> <s>s</s> <u>u</u> <s>s</s>");
+        assert_eq!(html("**bold *italic***"), "<strong>bold *italic</strong>*");
+        assert_eq!(html("**a *b* c**"), "<strong>a <em>b</em> c</strong>");
+    }
+
+    #[test]
+    fn lone_delimiters_stay_as_typed() {
+        assert_eq!(html("2 * 3 * 4"), "2 * 3 * 4");
+        assert_eq!(html("**open"), "**open");
+        assert_eq!(html("[b]open"), "[b]open");
+        assert_eq!(html("****"), "****");
+        assert_eq!(html("**a\nb**"), "**a\nb**");
+    }
+
+    #[test]
+    fn colored_text() {
+        assert_eq!(
+            html("==red== (((blue)))"),
+            "<span class=\"redtext\">red</span> <span class=\"bluetext\">blue</span>",
+        );
+    }
+
+    #[test]
+    fn spoilers() {
+        assert_eq!(html("a [spoiler]b[/spoiler] c"), "a <span class=\"spoiler\">b</span> c");
+        assert_eq!(
+            html("[spoiler]\n>green\n**b**\n[/spoiler]"),
+            "<span class=\"spoiler\">\n<span class=\"greentext\">&gt;green</span>\n<strong>b</strong>\n</span>",
+        );
+    }
+
+    #[test]
+    fn pipe_spoilers() {
+        assert_eq!(html("the ||butler|| did it"), "the <span class=\"spoiler\">butler</span> did it");
+        assert_eq!(html("a || b || c"), "a || b || c");
+        let off = Options { spoilers: false, ..Options::default() };
+        assert_eq!(render("||x||", &off), "||x||");
+    }
+
+    #[test]
+    fn snippets_close_what_they_cut() {
+        let options = Options::default();
+        assert_eq!(snippet("short", &options, 10), "short");
+        assert_eq!(snippet("a **bold** word", &options, 4), "a <strong>bo…</strong>");
+        assert_eq!(
+            snippet("ending [spoiler]who did it[/spoiler] here", &options, 10),
+            "ending <span class=\"spoiler\">who…</span>",
+        );
+        // Entities count as one character and are never split.
+        assert_eq!(snippet("a<b>c", &options, 2), "a&lt;…");
+        assert_eq!(snippet("**ab**", &options, 2), "<strong>ab</strong>");
+    }
+
+    #[test]
+    fn code_is_verbatim() {
+        assert_eq!(html("`**not bold**`"), "<code>**not bold**</code>");
+        assert_eq!(html("[code]x < y[/code]"), "<code>x &lt; y</code>");
+        assert_eq!(
+            html("```\nfn main() { a && b }\n>>1\n```"),
+            "<pre class=\"code-block\"><code>fn main() { a &amp;&amp; b }\n&gt;&gt;1</code></pre>",
+        );
+        assert_eq!(
+            html("[code]\n[spoiler]x[/spoiler]\n[/code]"),
+            "<pre class=\"code-block\"><code>[spoiler]x[/spoiler]</code></pre>",
+        );
+    }
+
+    #[test]
+    fn fences_name_their_language() {
+        assert_eq!(
+            html("```rust\nlet x = 1;\n```"),
+            concat!(
+                "<pre class=\"code-block\" data-lang=\"rust\"><code><span class=\"hl-keyword\">let</span> x = ",
+                "<span class=\"hl-number\">1</span>;</code></pre>",
+            ),
+        );
+        let plain = Options { highlight: false, ..Options::default() };
+        assert_eq!(render("```rust\nlet x;\n```", &plain), "<pre class=\"code-block\"><code>let x;</code></pre>");
+        assert_eq!(html("```cobol\nMOVE\n```"), "<pre class=\"code-block\"><code>MOVE</code></pre>");
+        assert_eq!(html("```a b\n```"), "<pre class=\"code-block\"><code>a b</code></pre>");
+        // Indentation is kept as typed.
+        assert_eq!(html("```\n\tx\n    y\n```"), "<pre class=\"code-block\"><code>\tx\n    y</code></pre>");
+    }
+
+    #[test]
+    fn greentext_and_pinktext() {
+        assert_eq!(html(">implying\n<pink\nplain"), concat!(
+            "<span class=\"greentext\">&gt;implying</span>\n",
+            "<span class=\"pinktext\">&lt;pink</span>\nplain",
+        ));
+        assert_eq!(html("not > green"), "not &gt; green");
+        assert_eq!(html("`x`>not green"), "<code>x</code>&gt;not green");
+    }
+
+    #[test]
+    fn quote_links() {
+        assert_eq!(html(">>12"), "<a class=\"post-quote-link\" href=\"#post-12\" data-target=\"12\">&gt;&gt;12</a>");
+        assert_eq!(
+            html(">>>/tech/7"),
+            concat!(
+                "<a class=\"post-quote-link cross-board\" href=\"/board/tech/post/7\" ",
+                "data-xboard=\"tech\" data-target=\"7\">&gt;&gt;&gt;/tech/7</a>",
+            ),
+        );
+        assert_eq!(
+            html(">>>/tech/"),
+            "<a class=\"post-quote-link cross-board\" href=\"/board/tech\">&gt;&gt;&gt;/tech/</a>",
+        );
+        assert_eq!(
+            html(">agreed >>3"),
+            concat!(
+                "<span class=\"greentext\">&gt;agreed ",
+                "<a class=\"post-quote-link\" href=\"#post-3\" data-target=\"3\">&gt;&gt;3</a></span>",
+            ),
+        );
+        assert_eq!(html(">>x >>>/Tech/1"), "&gt;&gt;x &gt;&gt;&gt;/Tech/1");
+    }
+
+    #[test]
+    fn toggles_leave_syntax_as_typed() {
+        let off = Options { markup: false, spoilers: false, code: false, greentext: false, highlight: false };
+        let body = "**b** [spoiler]s[/spoiler] `c`\n>green";
+        assert_eq!(render(body, &off), "**b** [spoiler]s[/spoiler] `c`\n&gt;green");
+        let only_code = Options { code: true, ..off };
+        assert_eq!(render("**b** `c`", &only_code), "**b** <code>c</code>");
+        // Quote links do not depend on any toggle.
+        assert!(render(">>1", &off).contains("post-quote-link"));
+    }
+
+    #[test]
+    fn board_toggles_map_to_options() {
+        let config = BoardConfig { spoilers_enabled: false, ..BoardConfig::default() };
+        assert_eq!(Options::for_board(&config), Options { spoilers: false, highlight: false, ..Options::default() });
+    }
+
+    #[test]
+    fn crlf_is_normalised() {
+        assert_eq!(html(">a\r\nb"), "<span class=\"greentext\">&gt;a</span>\nb");
+    }
+}
@@ -0,0 +1,179 @@
+//! Static page bodies: a small Markdown subset rendered to HTML.
+//!
+//! Pages are written by admins, but rendered as carefully as post bodies:
+//! every byte of the source is escaped and only the fixed tags below appear.
+//!
+//! | Syntax                        | Output                      |
+//! |-------------------------------|-----------------------------|
+//! | `# ` `## ` `### ` lines        | `<h2>` `<h3>` `<h4>` (the page title is the `<h1>`) |
+//! | lines starting `- ` or `* `   | `<ul><li>`                  |
+//! | lines starting `1. `          | `<ol><li>`                  |
+//! | ```` ``` ```` fenced lines     | `<pre class="code-block"><code>` |
+//! | other lines                   | `<p>`, split on blank lines |
+//! | `[text](url)`                 | `<a href>` for `http(s)://`, `mailto:` and `/` links |
+//!
+//! Within a line the post markup applies (bold, italic, code, spoilers…).
+
+use crate::{escape_into, inline, Options};
+
+/// Render a page body to HTML safe to embed unescaped.
+pub fn render(body: &str) -> String {
+    let body = body.replace("\r\n", "\n");
+    let options = Options { greentext: false, highlight: false, ..Options::default() };
+    let mut out = String::with_capacity(body.len() + body.len() / 4);
+    let mut open: Option<&'static str> = None;
+    let mut lines = body.split('\n');
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") {
+            close(&mut open, &mut out);
+            out.push_str("<pre class=\"code-block\"><code>");
+            let mut first = true;
+            for code in lines.by_ref() {
+                if code.trim_start().starts_with("```") {
+                    break;
+                }
+                if !first {
+                    out.push('\n');
+                }
+                escape_into(code, &mut out);
+                first = false;
+            }
+            out.push_str("</code></pre>\n");
+        } else if trimmed.is_empty() {
+            close(&mut open, &mut out);
+        } else if let Some((level, text)) = heading(trimmed) {
+            close(&mut open, &mut out);
+            out.push_str(&format!("<h{level}>"));
+            inline_links(text, &options, &mut out);
+            out.push_str(&format!("</h{level}>\n"));
+        } else if let Some((list, text)) = list_item(trimmed) {
+            if open != Some(list) {
+                close(&mut open, &mut out);
+                out.push_str(&format!("<{list}>\n"));
+                open = Some(list);
+            }
+            out.push_str("<li>");
+            inline_links(text, &options, &mut out);
+            out.push_str("</li>\n");
+        } else {
+            match open {
+                Some("p") => out.push('\n'),
+                _ => {
+                    close(&mut open, &mut out);
+                    out.push_str("<p>");
+                    open = Some("p");
+                }
+            }
+            inline_links(trimmed, &options, &mut out);
+        }
+    }
+    close(&mut open, &mut out);
+    out
+}
+
+/// Close the paragraph or list being written, if any.
+fn close(open: &mut Option<&'static str>, out: &mut String) {
+    if let Some(tag) = open.take() {
+        out.push_str(&format!("</{tag}>\n"));
+    }
+}
+
+/// `# Title` as (heading level, text). One `#` is an `<h2>`.
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let hashes = line.chars().take_while(|&c| c == '#').count();
+    let text = line[hashes..].strip_prefix(' ')?;
+    (1..=3).contains(&hashes).then_some((hashes + 1, text.trim()))
+}
+
+/// `- item` or `1. item` as (list tag, text).
+fn list_item(line: &str) -> Option<(&'static str, &str)> {
+    if let Some(text) = line.strip_prefix("- ").or_else(|| line.strip_prefix("* ")) {
+        return Some(("ul", text));
+    }
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    let text = line[digits..].strip_prefix(". ").filter(|_| digits > 0)?;
+    Some(("ol", text))
+}
+
+/// Render one line, turning `[text](url)` into links and the rest into
+/// inline markup.
+fn inline_links(text: &str, options: &Options, out: &mut String) {
+    let mut rest = text;
+    // Brackets that start no link are left to the inline pass, which knows
+    // `[b]` and friends.
+    while let Some((start, (label, url, len))) =
+        rest.match_indices('[').find_map(|(i, _)| link_at(&rest[i..]).map(|link| (i, link)))
+    {
+        inline::render(&rest[..start], options, out);
+        out.push_str("<a href=\"");
+        escape_into(url, out);
+        out.push_str("\">");
+        inline::render(label, options, out);
+        out.push_str("</a>");
+        rest = &rest[start + len..];
+    }
+    inline::render(rest, options, out);
+}
+
+/// A `[label](url)` link at the start of `text`: its label, URL and length.
+/// Only web, mail and site-relative URLs are links.
+fn link_at(text: &str) -> Option<(&str, &str, usize)> {
+    let label_end = text.find("](")?;
+    let label = &text[1..label_end];
+    if label.is_empty() || label.contains('[') {
+        return None;
+    }
+    let url_start = label_end + 2;
+    let url_len = text[url_start..].find(')')?;
+    let url = &text[url_start..url_start + url_len];
+    let allowed = ["https://", "http://", "mailto:", "/"].iter().any(|p| url.starts_with(p))
+        && !url.starts_with("//")
+        && !url.chars().any(|c| c.is_whitespace() || c.is_control());
+    allowed.then_some((label, url, url_start + url_len + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::render;
+
+    #[test]
+    fn paragraphs_split_on_blank_lines() {
+        assert_eq!(render("one\ntwo\n\nthree"), "<p>one\ntwo</p>\n<p>three</p>\n");
+    }
+
+    #[test]
+    fn headings_start_below_the_page_title() {
+        assert_eq!(render("# Rules\n### Fine print"), "<h2>Rules</h2>\n<h4>Fine print</h4>\n");
+        assert_eq!(render("#hashtag"), "<p>#hashtag</p>\n");
+        assert_eq!(render("#### deep"), "<p>#### deep</p>\n");
+    }
+
+    #[test]
+    fn lists() {
+        assert_eq!(
+            render("- no spam\n* be **nice**\n1. first\n2. second"),
+            "<ul>\n<li>no spam</li>\n<li>be <strong>nice</strong></li>\n</ul>\n<ol>\n<li>first</li>\n<li>second</li>\n</ol>\n",
+        );
+    }
+
+    #[test]
+    fn everything_is_escaped() {
+        assert_eq!(
+            render("<script>alert(1)</script>\n```\n<b>\n```"),
+            "<p>&lt;script&gt;alert(1)&lt;/script&gt;</p>\n<pre class=\"code-block\"><code>&lt;b&gt;</code></pre>\n",
+        );
+    }
+
+    #[test]
+    fn links_keep_to_safe_schemes() {
+        assert_eq!(
+            render("see [the FAQ](/faq) or [mail](mailto:a@b.c)"),
+            "<p>see <a href=\"/faq\">the FAQ</a> or <a href=\"mailto:a@b.c\">mail</a></p>\n",
+        );
+        assert_eq!(render("[x](javascript:alert(1))"), "<p>[x](javascript:alert(1))</p>\n");
+        assert_eq!(render("[x](//evil.example)"), "<p>[x](//evil.example)</p>\n");
+        assert_eq!(render("[x](\"/a\" onclick)"), "<p>[x](&quot;/a&quot; onclick)</p>\n");
+        assert_eq!(render("[b]bold[/b]"), "<p><strong>bold</strong></p>\n");
+    }
+}
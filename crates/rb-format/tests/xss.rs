@@ -0,0 +1,275 @@
+//! Nothing a poster types may leave `render` as markup of its own.
+//!
+//! Every output is checked the same way: each tag must be one `render`
+//! emits, tags must nest, and the text between them must contain no
+//! unescaped `<`, `>`, `"` or `'`.
+
+use rb_format::{render, snippet, Options};
+
+const FIXED_TAGS: &[&str] = &[
+    "<strong>",
+    "</strong>",
+    "<em>",
+    "</em>",
+    "<u>",
+    "</u>",
+    "<s>",
+    "</s>",
+    "<code>",
+    "</code>",
+    "<pre class=\"code-block\">",
+    "</pre>",
+    "<span class=\"spoiler\">",
+    "<span class=\"redtext\">",
+    "<span class=\"bluetext\">",
+    "<span class=\"greentext\">",
+    "<span class=\"pinktext\">",
+    "<span class=\"hl-comment\">",
+    "<span class=\"hl-string\">",
+    "<span class=\"hl-number\">",
+    "<span class=\"hl-keyword\">",
+    "<span class=\"hl-literal\">",
+    "<span class=\"hl-type\">",
+    "</span>",
+    "</a>",
+];
+
+/// A quote link: fixed attribute names, values of digits, slug characters
+/// and the `/` and `#` of its own paths.
+fn is_quote_link(tag: &str) -> bool {
+    let Some(attrs) = tag.strip_prefix("<a ").and_then(|t| t.strip_suffix('>')) else { return false };
+    let parts: Vec<&str> = attrs.split('"').collect();
+    if parts.len().is_multiple_of(2) || !parts.last().is_some_and(|p| p.is_empty()) {
+        return false;
+    }
+    parts.chunks(2).filter(|pair| pair.len() == 2).all(|pair| {
+        let name_ok = matches!(pair[0].trim(), "class=" | "href=" | "data-xboard=" | "data-target=");
+        let value_ok = pair[1] == "post-quote-link"
+            || pair[1] == "post-quote-link cross-board"
+            || pair[1].bytes().all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b"_-/#".contains(&b));
+        let no_scheme = !pair[1].contains(':') && !pair[1].starts_with("//");
+        name_ok && value_ok && no_scheme
+    })
+}
+
+/// A highlighted code block: the language is a lowercase word.
+fn is_highlighted_block(tag: &str) -> bool {
+    tag.strip_prefix("<pre class=\"code-block\" data-lang=\"")
+        .and_then(|t| t.strip_suffix("\">"))
+        .is_some_and(|lang| !lang.is_empty() && lang.bytes().all(|b| b.is_ascii_lowercase()))
+}
+
+fn tag_name(tag: &str) -> &str {
+    let inner = tag.trim_start_matches("</").trim_start_matches('<').trim_end_matches('>');
+    inner.split(' ').next().unwrap_or_default()
+}
+
+fn assert_safe(body: &str, options: &Options) {
+    assert_safe_html(&render(body, options), body);
+}
+
+fn assert_safe_html(html: &str, body: &str) {
+    let mut stack = Vec::new();
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        assert_clean_text(&rest[..start], body, html);
+        let end = start + rest[start..].find('>').unwrap_or_else(|| panic!("unclosed tag in {html:?} from {body:?}"));
+        let tag = &rest[start..=end];
+        assert!(FIXED_TAGS.contains(&tag) || is_quote_link(tag) || is_highlighted_block(tag), "unexpected tag {tag:?} in {html:?} from {body:?}");
+        if tag.starts_with("</") {
+            assert_eq!(stack.pop(), Some(tag_name(tag)), "misnested {tag:?} in {html:?} from {body:?}");
+        } else {
+            stack.push(tag_name(tag));
+        }
+        rest = &rest[end + 1..];
+    }
+    assert_clean_text(rest, body, html);
+    assert!(stack.is_empty(), "unclosed {stack:?} in {html:?} from {body:?}");
+}
+
+fn assert_clean_text(text: &str, body: &str, html: &str) {
+    assert!(!text.contains(['<', '>', '"', '\'']), "unescaped text {text:?} in {html:?} from {body:?}");
+    for (i, _) in text.match_indices('&') {
+        let entity = &text[i..];
+        let known = ["&amp;", "&lt;", "&gt;", "&quot;", "&#39;"].iter().any(|e| entity.starts_with(e));
+        assert!(known, "bare ampersand in {html:?} from {body:?}");
+    }
+}
+
+fn all_options() -> Vec<Options> {
+    (0..32)
+        .map(|bits| Options {
+            markup:    bits & 1 != 0,
+            spoilers:  bits & 2 != 0,
+            code:      bits & 4 != 0,
+            greentext: bits & 8 != 0,
+            highlight: bits & 16 != 0,
+        })
+        .collect()
+}
+
+const PAYLOADS: &[&str] = &[
+    "<script>alert(1)</script>",
+    "<ScRiPt>alert(1)</sCrIpT>",
+    "<img src=x onerror=alert(1)>",
+    "<svg/onload=alert(1)>",
+    "\"><script>alert(1)</script>",
+    "'><img src=x onerror=alert(1)>",
+    "<a href=\"javascript:alert(1)\">x</a>",
+    "javascript:alert(1)",
+    "<iframe src=//evil.example></iframe>",
+    "<style>*{display:none}</style>",
+    "<!-- comment -->",
+    "<![CDATA[<script>]]>",
+    "&lt;script&gt;alert(1)&lt;/script&gt;",
+    "&#60;script&#62;",
+    "**<script>alert(1)</script>**",
+    "[b]<img src=x onerror=alert(1)>[/b]",
+    "*\"onmouseover=\"alert(1)*",
+    "[i]</em><script>[/i]",
+    "__</u>__ ~~</s>~~",
+    "==</span><script>alert(1)</script>==",
+    "(((</span><img src=x>)))",
+    "[spoiler]</span><script>alert(1)</script>[/spoiler]",
+    "[spoiler]\n</span>\n<script>\n[/spoiler]",
+    "[spoiler]\n[spoiler]\n<b>\n[/spoiler]\n[/spoiler]",
+    "`</code><script>alert(1)</script>`",
+    "[code]</code><script>[/code]",
+    "```\n</pre><script>alert(1)</script>\n```",
+    "```<img src=x onerror=alert(1)>```",
+    "[code]\n</pre>\n[/code]",
+    "```rust\n\"</span><script>alert(1)</script>\n```",
+    "```js\n// </pre><img src=x onerror=alert(1)>\n`${'<b>'}`\n```",
+    "```python\n'''</code>\n```",
+    "```sql\n/* <script> */ SELECT '<b>'\n```",
+    "```rust\n'\\'<b>\n```",
+    "```\"><script>\n```",
+    "```rust\" onclick=\"x\nfn\n```",
+    ">>1\"><script>alert(1)</script>",
+    ">>1 onmouseover=alert(1)",
+    ">>>/a\"onmouseover=\"alert(1)/1",
+    ">>>/b/1\" onclick=\"alert(1)",
+    ">>>/javascript:alert(1)/",
+    ">>>//evil.example/1",
+    ">>>/b/<script>",
+    "><script>alert(1)</script>",
+    "<<script>alert(1)//<</script>",
+    ">**<img src=x>**\n<[spoiler]<b>[/spoiler]",
+    "[b][i]<x>[/b][/i]",
+    "**[spoiler]**[/spoiler]",
+    "[spoiler]**[/spoiler]**",
+    "`**`**`",
+    "[url=javascript:alert(1)]x[/url]",
+    "[img]javascript:alert(1)[/img]",
+    "[color=red\" onclick=\"alert(1)]x[/color]",
+    "\u{0}<script>\u{0}",
+    "\r<script>\r\n>\r",
+    "\t<b>\t",
+    "＜script＞alert(1)＜/script＞",
+    "\u{202e}<script>",
+    "[SPOILER]<b>[/SPOILER] [CODE]<b>[/CODE]",
+    "***",
+    "```",
+    "``````",
+    "[spoiler][/spoiler]",
+    "||</span><script>alert(1)</script>||",
+    "||[spoiler]||[/spoiler]",
+    "[code][/code]",
+    "",
+];
+
+#[test]
+fn known_payloads_are_escaped_under_every_toggle() {
+    for options in all_options() {
+        for payload in PAYLOADS {
+            assert_safe(payload, &options);
+        }
+    }
+}
+
+#[test]
+fn markup_inside_payloads_is_still_rendered() {
+    let html = render("**<b>**", &Options::default());
+    assert_eq!(html, "<strong>&lt;b&gt;</strong>");
+    let html = render("[spoiler]<script>[/spoiler]", &Options::default());
+    assert_eq!(html, "<span class=\"spoiler\">&lt;script&gt;</span>");
+}
+
+#[test]
+fn entities_are_escaped_again() {
+    assert_eq!(render("&lt;script&gt;", &Options::default()), "&amp;lt;script&amp;gt;");
+}
+
+#[test]
+fn without_toggles_only_quote_links_are_markup() {
+    let off = Options { markup: false, spoilers: false, code: false, greentext: false, highlight: false };
+    for payload in PAYLOADS.iter().filter(|p| !p.contains(">>")) {
+        let expected = payload
+            .replace("\r\n", "\n")
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+            .replace('\'', "&#39;");
+        assert_eq!(render(payload, &off), expected);
+    }
+}
+
+/// Every string of up to three of these pieces, under every toggle.
+#[test]
+fn all_short_combinations_are_safe() {
+    const PIECES: &[&str] = &[
+        "*", "**", "__", "~~", "==", "(((", ")))", "`", "```", "[b]", "[/b]", "[i]", "[/i]", "[spoiler]",
+        "[/spoiler]", "[code]", "[/code]", ">", ">>", ">>1", ">>>/b/", ">>>/b/2", "<", "<script>", "\"", "'",
+        "&", "\n", " ", "x", "||",
+    ];
+    let options = all_options();
+    for a in PIECES {
+        for b in PIECES {
+            for c in PIECES {
+                let body = format!("{a}{b}{c}");
+                for options in &options {
+                    assert_safe(&body, options);
+                }
+            }
+        }
+    }
+}
+
+/// Every string of up to three lexer pieces, as a fenced block of every
+/// language the highlighter knows.
+#[test]
+fn all_short_code_combinations_are_safe() {
+    const LANGUAGES: &[&str] = &["rust", "c", "python", "javascript", "go", "java", "shell", "sql", "json", "toml"];
+    const PIECES: &[&str] = &[
+        "\"", "'", "`", "\"\"\"", "'''", "\\", "//", "/*", "*/", "#", "--", "\n", "fn", "Foo", "1", "<b>", "&",
+    ];
+    for lang in LANGUAGES {
+        for a in PIECES {
+            for b in PIECES {
+                for c in PIECES {
+                    assert_safe(&format!("```{lang}\n{a}{b}{c}\n```"), &Options::default());
+                }
+            }
+        }
+    }
+}
+
+/// Snippets cut every payload at every length and must stay well formed.
+#[test]
+fn snippets_are_safe_at_every_cut() {
+    for payload in PAYLOADS {
+        for max_chars in 0..=payload.chars().count() + 1 {
+            assert_safe_html(&snippet(payload, &Options::default(), max_chars), payload);
+        }
+    }
+}
+
+#[test]
+fn long_and_deep_bodies_are_safe() {
+    let nested = "[spoiler]\n**[b][i]__~~==(((`x`)))==~~__[/i][/b]**\n".repeat(50) + &"[/spoiler]".repeat(50);
+    assert_safe(&nested, &Options::default());
+    assert_safe(&"*".repeat(4000), &Options::default());
+    assert_safe(&"[spoiler]\n".repeat(500), &Options::default());
+    assert_safe(&">>>/".repeat(1000), &Options::default());
+}
@@ -0,0 +1,222 @@
+//! Posts as news articles (RFC 5536): headers, body and overview lines.
+//!
+//! Each post is one article, numbered by its board post number. Message-IDs
+//! are `<post-uuid@domain>`; a reply's `References` is its thread's OP, so
+//! newsreaders show every thread as one tree. IP hashes and the email field
+//! are never published.
+
+use domains::models::{Post, PostId};
+use uuid::Uuid;
+
+/// Longest subject header, in characters.
+const MAX_SUBJECT_CHARS: usize = 72;
+
+/// What the articles of one thread share.
+#[derive(Debug, Clone)]
+pub struct ThreadInfo {
+    /// The OP, which every reply references. `None` once it is deleted.
+    pub op_id:   Option<PostId>,
+    /// Subject of the OP; replies are `Re: <subject>`.
+    pub subject: String,
+}
+
+/// A post as an article of `group`.
+pub struct Article<'a> {
+    pub group:  &'a str,
+    pub domain: &'a str,
+    pub post:   &'a Post,
+    pub thread: &'a ThreadInfo,
+}
+
+pub fn message_id(post_id: PostId, domain: &str) -> String {
+    format!("<{}@{domain}>", post_id.0)
+}
+
+/// The post a Message-ID of ours names.
+pub fn parse_message_id(message_id: &str, domain: &str) -> Option<PostId> {
+    let (id, host) = message_id.strip_prefix('<')?.strip_suffix('>')?.split_once('@')?;
+    if !host.eq_ignore_ascii_case(domain) {
+        return None;
+    }
+    Uuid::parse_str(id).ok().map(PostId)
+}
+
+/// Subject of a thread: the OP's subject, else its first line of text, else `No.<n>`.
+pub fn subject(op: &Post) -> String {
+    let line = match op.subject.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+        Some(subject) => subject,
+        None => op.body.lines().map(str::trim).find(|l| !l.is_empty()).unwrap_or_default(),
+    };
+    if line.is_empty() {
+        return format!("No.{}", op.post_number);
+    }
+    let mut subject: String = header_value(line).chars().take(MAX_SUBJECT_CHARS).collect();
+    if line.chars().count() > MAX_SUBJECT_CHARS {
+        subject.push('…');
+    }
+    subject
+}
+
+impl Article<'_> {
+    pub fn message_id(&self) -> String {
+        message_id(self.post.id, self.domain)
+    }
+
+    fn is_op(&self) -> bool {
+        self.thread.op_id == Some(self.post.id)
+    }
+
+    fn subject(&self) -> String {
+        if self.is_op() {
+            self.thread.subject.clone()
+        } else {
+            format!("Re: {}", self.thread.subject)
+        }
+    }
+
+    fn from(&self) -> String {
+        let name = self.post.name.as_deref().map(header_value).unwrap_or_else(|| "Anonymous".to_owned());
+        let name: String = name.chars().filter(|c| !matches!(c, '"' | '\\' | '<' | '>')).collect();
+        match &self.post.tripcode {
+            Some(trip) => format!("\"{name} {}\" <nobody@invalid>", header_value(trip).replace(['"', '\\'], "")),
+            None => format!("\"{name}\" <nobody@invalid>"),
+        }
+    }
+
+    fn date(&self) -> String {
+        self.post.created_at.format("%a, %d %b %Y %H:%M:%S +0000").to_string()
+    }
+
+    fn references(&self) -> String {
+        match self.thread.op_id {
+            Some(op) if !self.is_op() => message_id(op, self.domain),
+            _ => String::new(),
+        }
+    }
+
+    /// Header lines, without CRLF.
+    pub fn head(&self) -> Vec<String> {
+        let mut head = vec![
+            format!("Path: {}!not-for-mail", self.domain),
+            format!("From: {}", self.from()),
+            format!("Newsgroups: {}", self.group),
+            format!("Subject: {}", self.subject()),
+            format!("Date: {}", self.date()),
+            format!("Message-ID: {}", self.message_id()),
+        ];
+        let references = self.references();
+        if !references.is_empty() {
+            head.push(format!("References: {references}"));
+        }
+        head.extend([
+            format!("Xref: {} {}:{}", self.domain, self.group, self.post.post_number),
+            "MIME-Version: 1.0".to_owned(),
+            "Content-Type: text/plain; charset=UTF-8".to_owned(),
+            "Content-Transfer-Encoding: 8bit".to_owned(),
+        ]);
+        head
+    }
+
+    /// Body lines, without CRLF and before dot-stuffing.
+    pub fn body(&self) -> Vec<String> {
+        let body = self.post.body.trim_end();
+        if body.is_empty() {
+            return vec!["(no text)".to_owned()];
+        }
+        body.lines().map(|line| line.trim_end_matches('\r').to_owned()).collect()
+    }
+
+    /// Tab-separated overview line in `LIST OVERVIEW.FMT` order.
+    pub fn overview(&self) -> String {
+        let (head, body) = (self.head(), self.body());
+        // Lines plus CRLF, and the blank line between head and body.
+        let bytes: usize = head.iter().chain(&body).map(|l| l.len() + 2).sum::<usize>() + 2;
+        [
+            self.post.post_number.to_string(),
+            self.subject(),
+            self.from(),
+            self.date(),
+            self.message_id(),
+            self.references(),
+            bytes.to_string(),
+            body.len().to_string(),
+        ]
+        .join("\t")
+    }
+}
+
+/// `text` on one line: control characters (tabs, line breaks) become spaces.
+fn header_value(text: &str) -> String {
+    text.chars().map(|c| if c.is_control() { ' ' } else { c }).collect::<String>().trim().to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use domains::models::{IpHash, ThreadId};
+
+    fn post(number: u64, body: &str) -> Post {
+        Post {
+            id: PostId::new(), thread_id: ThreadId::new(), body: body.to_owned(), ip_hash: IpHash::new("secret"),
+            name: None, tripcode: None, email: Some("me@mail.example".to_owned()),
+            created_at: Utc.with_ymd_and_hms(2026, 3, 1, 12, 30, 5).unwrap(), post_number: number, pinned: false,
+            tags: vec![], commands: vec![], subject: None, capcode: None, deleted: None, edited_at: None,
+        }
+    }
+
+    #[test]
+    fn replies_reference_the_op() {
+        let op = post(1, "\n  Rust\tthreads  \nsecond line");
+        let thread = ThreadInfo { op_id: Some(op.id), subject: subject(&op) };
+        let reply = Post { name: Some("bob\r\nX-Evil: 1".to_owned()), ..post(2, ">>1\n.dot") };
+        let article = Article { group: "rusty-board.g", domain: "board.example", post: &reply, thread: &thread };
+
+        let head = article.head();
+        assert!(head.contains(&"Subject: Re: Rust threads".to_owned()));
+        assert!(head.contains(&"From: \"bob  X-Evil: 1\" <nobody@invalid>".to_owned()));
+        assert!(head.contains(&"Date: Sun, 01 Mar 2026 12:30:05 +0000".to_owned()));
+        assert!(head.contains(&format!("References: <{}@board.example>", op.id.0)));
+        assert!(head.contains(&"Xref: board.example rusty-board.g:2".to_owned()));
+        assert_eq!(article.body(), [">>1", ".dot"]);
+        assert!(!head.join("\n").contains("secret") && !head.join("\n").contains("mail.example"));
+
+        let op_article = Article { post: &op, ..article };
+        assert!(op_article.head().contains(&"Subject: Rust threads".to_owned()));
+        assert!(!op_article.head().iter().any(|h| h.starts_with("References:")));
+    }
+
+    #[test]
+    fn overview_fields_are_tab_separated() {
+        let op = post(7, "");
+        let thread = ThreadInfo { op_id: Some(op.id), subject: subject(&op) };
+        let article = Article { group: "g", domain: "d", post: &op, thread: &thread };
+        let fields: Vec<String> = article.overview().split('\t').map(str::to_owned).collect();
+        assert_eq!(fields[..3], ["7", "No.7", "\"Anonymous\" <nobody@invalid>"]);
+        assert_eq!(fields[5], "");
+        assert_eq!(fields[7], "1");
+        let bytes: usize = article.head().iter().chain(&article.body()).map(|l| l.len() + 2).sum::<usize>() + 2;
+        assert_eq!(fields[6], bytes.to_string());
+    }
+
+    #[test]
+    fn message_ids_round_trip() {
+        let id = PostId::new();
+        assert_eq!(parse_message_id(&message_id(id, "board.example"), "Board.Example"), Some(id));
+        assert_eq!(parse_message_id(&message_id(id, "elsewhere.example"), "board.example"), None);
+        assert_eq!(parse_message_id("<nope@board.example>", "board.example"), None);
+    }
+
+    #[test]
+    fn op_subject_wins_over_first_line() {
+        let op = Post { subject: Some("General".to_owned()), ..post(1, "first line") };
+        assert_eq!(subject(&op), "General");
+    }
+
+    #[test]
+    fn long_subjects_are_cut() {
+        let subject = subject(&post(1, &"x".repeat(100)));
+        assert_eq!(subject.chars().count(), MAX_SUBJECT_CHARS + 1);
+        assert!(subject.ends_with('…'));
+    }
+}
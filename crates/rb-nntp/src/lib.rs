@@ -0,0 +1,102 @@
+//! `rb-nntp` — the boards of a site as NNTP newsgroups (RFC 3977).
+//!
+//! Board `g` is the newsgroup `<prefix>.g`; each post is an article numbered
+//! by its post number, and replies reference their thread's OP, so any
+//! newsreader shows the board thread by thread. Archivers can mirror a board
+//! with `OVER` and `ARTICLE` instead of scraping pages.
+//!
+//! Access is read-only: `POST` is refused and no authentication is offered.
+//! Posting will go through `PostService` like the Matrix bridge does.
+//!
+//! The server reads boards and posts through the repository ports only.
+
+mod article;
+mod session;
+mod wildmat;
+
+use std::sync::Arc;
+
+use domains::models::SiteId;
+use domains::ports::{BoardRepository, PostRepository, ThreadRepository};
+use tokio::io::{AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+use tokio::sync::Semaphore;
+use tokio_util::sync::CancellationToken;
+
+/// The repositories the server reads.
+pub struct Repositories {
+    pub boards:  Arc<dyn BoardRepository>,
+    pub threads: Arc<dyn ThreadRepository>,
+    pub posts:   Arc<dyn PostRepository>,
+}
+
+/// How the boards are presented.
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// Host of the site; the right-hand side of Message-IDs.
+    pub domain:          String,
+    /// Group of board `g` is `<group_prefix>.g`, or `g` when empty.
+    pub group_prefix:    String,
+    /// Connections served at once; more are answered `400` and closed.
+    pub max_connections: usize,
+}
+
+/// NNTP server for the boards of one site.
+pub struct NntpServer {
+    site_id: SiteId,
+    repos:   Repositories,
+    options: Options,
+}
+
+impl NntpServer {
+    pub fn new(site_id: SiteId, repos: Repositories, options: Options) -> Self {
+        Self { site_id, repos, options }
+    }
+
+    /// Accept readers on `listener` until `stop` is cancelled. Open sessions
+    /// are told the server is going away and closed.
+    pub async fn run(self: Arc<Self>, listener: TcpListener, stop: CancellationToken) {
+        let slots = Arc::new(Semaphore::new(self.options.max_connections));
+        loop {
+            let (mut stream, peer) = tokio::select! {
+                _ = stop.cancelled() => return,
+                accepted = listener.accept() => match accepted {
+                    Ok(accepted) => accepted,
+                    Err(e) => {
+                        tracing::warn!(error = %e, "nntp: accept failed");
+                        continue;
+                    }
+                },
+            };
+            let Ok(slot) = slots.clone().try_acquire_owned() else {
+                tracing::warn!(%peer, "nntp: connection limit reached; reader refused");
+                let _ = stream.write_all(b"400 Too many connections, try again later\r\n").await;
+                continue;
+            };
+            let (server, stop) = (self.clone(), stop.clone());
+            tokio::spawn(async move {
+                let (reader, writer) = stream.into_split();
+                if let Err(e) = session::run(&server, BufReader::new(reader), writer, stop).await {
+                    tracing::debug!(%peer, error = %e, "nntp: session ended");
+                }
+                drop(slot);
+            });
+        }
+    }
+
+    /// Newsgroup name of board `slug`.
+    fn group_name(&self, slug: &str) -> String {
+        match self.options.group_prefix.as_str() {
+            "" => slug.to_owned(),
+            prefix => format!("{prefix}.{slug}"),
+        }
+    }
+
+    /// Board slug of newsgroup `name`, if it is one of ours.
+    fn slug_of<'a>(&self, name: &'a str) -> Option<&'a str> {
+        match self.options.group_prefix.as_str() {
+            "" => Some(name),
+            prefix => name.strip_prefix(prefix)?.strip_prefix('.'),
+        }
+    }
+}
@@ -0,0 +1,867 @@
+//! One reader's connection: the command loop and the commands of the READER
+//! capability (RFC 3977).
+
+use std::collections::HashMap;
+use std::io;
+use std::time::Duration;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use domains::errors::DomainError;
+use domains::models::{Board, Page, Post, PostNumberRange, Slug, ThreadId};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio_util::sync::CancellationToken;
+
+use crate::article::{self, Article, ThreadInfo};
+use crate::wildmat;
+use crate::NntpServer;
+
+/// Longest command line, CRLF included (RFC 3977 §3.1).
+const MAX_LINE: u64 = 512;
+
+/// A reader silent for this long is disconnected.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Posts read per query when listing a range.
+const CHUNK: u32 = 500;
+
+/// Buffered output is sent once it grows past this.
+const SPILL_BYTES: usize = 64 * 1024;
+
+/// Threads whose OP and subject a session remembers.
+const MAX_CACHED_THREADS: usize = 1024;
+
+/// Fields of `OVER` lines after the article number.
+const OVERVIEW_FMT: [&str; 7] = ["Subject:", "From:", "Date:", "Message-ID:", "References:", ":bytes", ":lines"];
+
+/// Commands that exist but were given the wrong arguments.
+const COMMANDS: [&str; 16] = [
+    "ARTICLE", "BODY", "CAPABILITIES", "DATE", "GROUP", "HEAD", "HELP", "LAST", "LIST", "LISTGROUP", "MODE",
+    "NEWGROUPS", "NEXT", "OVER", "QUIT", "STAT",
+];
+
+enum Flow {
+    Continue,
+    Quit,
+}
+
+enum Failure {
+    Io(io::Error),
+    Domain(DomainError),
+}
+
+impl From<io::Error> for Failure {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<DomainError> for Failure {
+    fn from(e: DomainError) -> Self {
+        Self::Domain(e)
+    }
+}
+
+/// Response lines, buffered and sent at the end of each command or when
+/// the buffer is full.
+struct Output<W> {
+    writer: W,
+    buf:    Vec<u8>,
+    /// Part of the current response was already sent.
+    sent:   bool,
+}
+
+impl<W: AsyncWrite + Unpin> Output<W> {
+    fn line(&mut self, line: &str) {
+        self.buf.extend_from_slice(line.as_bytes());
+        self.buf.extend_from_slice(b"\r\n");
+    }
+
+    /// A line of a multi-line block, dot-stuffed.
+    fn text(&mut self, line: &str) {
+        if line.starts_with('.') {
+            self.buf.push(b'.');
+        }
+        self.line(line);
+    }
+
+    /// End of a multi-line block.
+    fn end(&mut self) {
+        self.line(".");
+    }
+
+    async fn spill(&mut self) -> io::Result<()> {
+        if self.buf.len() >= SPILL_BYTES {
+            self.writer.write_all(&self.buf).await?;
+            self.buf.clear();
+            self.sent = true;
+        }
+        Ok(())
+    }
+
+    async fn finish(&mut self) -> io::Result<()> {
+        self.writer.write_all(&self.buf).await?;
+        self.writer.flush().await?;
+        self.buf.clear();
+        self.sent = false;
+        Ok(())
+    }
+}
+
+/// The selected newsgroup.
+struct Group {
+    board: Board,
+    name:  String,
+    range: Option<PostNumberRange>,
+}
+
+impl Group {
+    /// `(count, low, high)`; an empty group is `0 1 0`.
+    fn counts(&self) -> (u64, u64, u64) {
+        self.range.map_or((0, 1, 0), |r| (r.count, r.low, r.high))
+    }
+}
+
+struct Session<'a, W> {
+    server:  &'a NntpServer,
+    out:     Output<W>,
+    group:   Option<Group>,
+    /// Current article number in the selected group.
+    current: Option<u64>,
+    threads: HashMap<ThreadId, ThreadInfo>,
+}
+
+/// Serve one reader until it quits, goes idle or `stop` is cancelled.
+pub(crate) async fn run<R, W>(server: &NntpServer, mut reader: R, writer: W, stop: CancellationToken) -> io::Result<()>
+where
+    R: AsyncBufRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let out = Output { writer, buf: Vec::new(), sent: false };
+    let mut session = Session { server, out, group: None, current: None, threads: HashMap::new() };
+    session.out.line("201 rusty-board NNTP service ready, posting prohibited");
+    session.out.finish().await?;
+
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        let mut limited = (&mut reader).take(MAX_LINE);
+        let read = tokio::select! {
+            _ = stop.cancelled() => {
+                session.out.line("400 Service shutting down");
+                return session.out.finish().await;
+            }
+            read = tokio::time::timeout(IDLE_TIMEOUT, limited.read_until(b'\n', &mut line)) => read,
+        };
+        let Ok(read) = read else {
+            session.out.line("400 Idle timeout");
+            return session.out.finish().await;
+        };
+        if read? == 0 {
+            return Ok(());
+        }
+        if !line.ends_with(b"\n") {
+            session.out.line("501 Command line too long");
+            return session.out.finish().await;
+        }
+        let command = String::from_utf8_lossy(&line);
+        match session.command(command.trim_end_matches(['\r', '\n'])).await {
+            Ok(Flow::Continue) => session.out.finish().await?,
+            Ok(Flow::Quit) => return session.out.finish().await,
+            Err(Failure::Io(e)) => return Err(e),
+            Err(Failure::Domain(e)) => {
+                tracing::error!(error = %e, "nntp: command failed");
+                if session.out.sent {
+                    // Half a response was sent; the reader cannot recover.
+                    return Ok(());
+                }
+                session.out.buf.clear();
+                session.out.line("403 Internal fault");
+                session.out.finish().await?;
+            }
+        }
+    }
+}
+
+impl<W: AsyncWrite + Unpin> Session<'_, W> {
+    async fn command(&mut self, line: &str) -> Result<Flow, Failure> {
+        let mut words = line.split_ascii_whitespace();
+        let verb = words.next().unwrap_or_default().to_ascii_uppercase();
+        let args: Vec<&str> = words.collect();
+        match (verb.as_str(), args.as_slice()) {
+            ("CAPABILITIES", _) => self.capabilities(),
+            ("MODE", [mode]) if mode.eq_ignore_ascii_case("READER") => {
+                self.out.line("201 Reader mode, posting prohibited");
+            }
+            ("QUIT", []) => {
+                self.out.line("205 Bye");
+                return Ok(Flow::Quit);
+            }
+            ("DATE", []) => self.out.line(&format!("111 {}", Utc::now().format("%Y%m%d%H%M%S"))),
+            ("HELP", []) => self.help(),
+            ("LIST", args) if args.len() <= 2 => self.list(args).await?,
+            ("NEWGROUPS", [date, time, rest @ ..]) if rest.len() <= 1 => self.newgroups(date, time).await?,
+            ("GROUP", [name]) => self.select_group(name).await?,
+            ("LISTGROUP", args) if args.len() <= 2 => self.listgroup(args).await?,
+            ("ARTICLE" | "HEAD" | "BODY" | "STAT", args) if args.len() <= 1 => {
+                self.article(&verb, args.first().copied()).await?
+            }
+            ("NEXT", []) => self.step(true).await?,
+            ("LAST", []) => self.step(false).await?,
+            ("OVER" | "XOVER", args) if args.len() <= 1 => self.over(args.first().copied()).await?,
+            ("POST", []) => self.out.line("440 Posting not permitted"),
+            (verb, _) if COMMANDS.contains(&verb) => self.out.line("501 Syntax error"),
+            _ => self.out.line("500 Unknown command"),
+        }
+        Ok(Flow::Continue)
+    }
+
+    fn capabilities(&mut self) {
+        self.out.line("101 Capability list:");
+        for capability in [
+            "VERSION 2",
+            "READER",
+            "LIST ACTIVE NEWSGROUPS OVERVIEW.FMT",
+            "OVER MSGID",
+            concat!("IMPLEMENTATION rusty-board ", env!("CARGO_PKG_VERSION")),
+        ] {
+            self.out.text(capability);
+        }
+        self.out.end();
+    }
+
+    fn help(&mut self) {
+        self.out.line("100 Help text follows");
+        for command in [
+            "ARTICLE|HEAD|BODY|STAT [number|<message-id>]",
+            "CAPABILITIES",
+            "DATE",
+            "GROUP newsgroup",
+            "LAST",
+            "LIST [ACTIVE|NEWSGROUPS [wildmat]|OVERVIEW.FMT]",
+            "LISTGROUP [newsgroup [range]]",
+            "MODE READER",
+            "NEWGROUPS yymmdd hhmmss [GMT]",
+            "NEXT",
+            "OVER [range|<message-id>]",
+            "QUIT",
+        ] {
+            self.out.text(command);
+        }
+        self.out.end();
+    }
+
+    // ── Groups ────────────────────────────────────────────────────────────
+
+    /// Every board of the site, oldest first.
+    async fn boards(&self) -> Result<Vec<Board>, DomainError> {
+        let mut boards = Vec::new();
+        for page in 1.. {
+            let found = self.server.repos.boards.find_by_site(self.server.site_id, Page::new(page)).await?;
+            let done = found.items.is_empty() || boards.len() + found.items.len() >= found.total as usize;
+            boards.extend(found.items);
+            if done {
+                break;
+            }
+        }
+        Ok(boards)
+    }
+
+    async fn find_group(&self, name: &str) -> Result<Option<Group>, DomainError> {
+        let Some(slug) = self.server.slug_of(name).and_then(|slug| Slug::new(slug).ok()) else {
+            return Ok(None);
+        };
+        let board = match self.server.repos.boards.find_by_slug(self.server.site_id, &slug).await {
+            Ok(board) => board,
+            Err(DomainError::NotFound { .. }) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        let group_name = self.server.group_name(board.slug.as_str());
+        if group_name != name {
+            return Ok(None);
+        }
+        let range = self.server.repos.posts.find_number_range(board.id).await?;
+        Ok(Some(Group { board, name: group_name, range }))
+    }
+
+    /// `name high low n` lines of `boards` matching `pattern`; posting is
+    /// never allowed.
+    async fn active(&mut self, boards: Vec<Board>, pattern: Option<&str>) -> Result<(), Failure> {
+        for board in boards {
+            let name = self.server.group_name(board.slug.as_str());
+            if pattern.is_some_and(|p| !wildmat::matches(p, &name)) {
+                continue;
+            }
+            let range = self.server.repos.posts.find_number_range(board.id).await?;
+            let group = Group { board, name, range };
+            let (_, low, high) = group.counts();
+            self.out.text(&format!("{} {high} {low} n", group.name));
+            self.out.spill().await?;
+        }
+        Ok(())
+    }
+
+    async fn list(&mut self, args: &[&str]) -> Result<(), Failure> {
+        let keyword = args.first().map(|k| k.to_ascii_uppercase());
+        match keyword.as_deref() {
+            None | Some("ACTIVE") => {
+                let boards = self.boards().await?;
+                self.out.line("215 Newsgroups in form \"group high low status\"");
+                self.active(boards, args.get(1).copied()).await?;
+            }
+            Some("NEWSGROUPS") => {
+                let boards = self.boards().await?;
+                self.out.line("215 Descriptions in form \"group description\"");
+                for board in boards {
+                    let name = self.server.group_name(board.slug.as_str());
+                    if args.get(1).is_some_and(|p| !wildmat::matches(p, &name)) {
+                        continue;
+                    }
+                    let title: String = board.title.chars().map(|c| if c.is_control() { ' ' } else { c }).collect();
+                    self.out.text(&format!("{name}\t{title}"));
+                }
+            }
+            Some("OVERVIEW.FMT") if args.len() == 1 => {
+                self.out.line("215 Order of fields in overview database");
+                for field in OVERVIEW_FMT {
+                    self.out.text(field);
+                }
+            }
+            _ => {
+                self.out.line("503 LIST keyword not supported");
+                return Ok(());
+            }
+        }
+        self.out.end();
+        Ok(())
+    }
+
+    async fn newgroups(&mut self, date: &str, time: &str) -> Result<(), Failure> {
+        let Some(since) = parse_date_time(date, time) else {
+            self.out.line("501 Syntax error in date or time");
+            return Ok(());
+        };
+        let boards = self.boards().await?.into_iter().filter(|b| b.created_at > since).collect();
+        self.out.line("231 List of new newsgroups follows");
+        self.active(boards, None).await?;
+        self.out.end();
+        Ok(())
+    }
+
+    async fn select_group(&mut self, name: &str) -> Result<(), Failure> {
+        let Some(group) = self.find_group(name).await? else {
+            self.out.line("411 No such newsgroup");
+            return Ok(());
+        };
+        let (count, low, high) = group.counts();
+        self.out.line(&format!("211 {count} {low} {high} {}", group.name));
+        self.current = group.range.map(|r| r.low);
+        self.group = Some(group);
+        Ok(())
+    }
+
+    async fn listgroup(&mut self, args: &[&str]) -> Result<(), Failure> {
+        if let Some(name) = args.first() {
+            let Some(group) = self.find_group(name).await? else {
+                self.out.line("411 No such newsgroup");
+                return Ok(());
+            };
+            self.group = Some(group);
+        }
+        let Some(group) = &self.group else {
+            self.out.line("412 No newsgroup selected");
+            return Ok(());
+        };
+        let (count, low, high) = group.counts();
+        let Some((first, last)) = args.get(1).map_or(Some((low, high)), |range| parse_range(range, high)) else {
+            self.out.line("501 Syntax error in range");
+            return Ok(());
+        };
+        let board_id = group.board.id;
+        self.out.line(&format!("211 {count} {low} {high} {} list follows", group.name));
+        self.current = group.range.map(|r| r.low);
+        let mut from = first;
+        while from <= last {
+            let posts = self.server.repos.posts.find_by_number_range(board_id, from, last, CHUNK).await?;
+            for post in &posts {
+                self.out.text(&post.post_number.to_string());
+            }
+            self.out.spill().await?;
+            match posts.last() {
+                Some(post) if posts.len() == CHUNK as usize => from = post.post_number + 1,
+                _ => break,
+            }
+        }
+        self.out.end();
+        Ok(())
+    }
+
+    // ── Articles ──────────────────────────────────────────────────────────
+
+    /// The post numbered `number` in the selected group.
+    async fn numbered(&self, number: u64) -> Result<Option<Post>, DomainError> {
+        let Some(group) = &self.group else {
+            return Ok(None);
+        };
+        let found = self.server.repos.posts.find_by_number_range(group.board.id, number, number, 1).await?;
+        Ok(found.into_iter().next())
+    }
+
+    /// The post a Message-ID names, with the name of its group, if it is on
+    /// one of the site's boards. Tombstones and shadowbanned posts are not
+    /// articles, as in `numbered`.
+    async fn by_message_id(&self, message_id: &str) -> Result<Option<(Post, String)>, DomainError> {
+        let Some(post_id) = article::parse_message_id(message_id, &self.server.options.domain) else {
+            return Ok(None);
+        };
+        let repos = &self.server.repos;
+        let Some(post) = repos.posts.find_visible_by_id(post_id).await? else {
+            return Ok(None);
+        };
+        let thread = repos.threads.find_by_id(post.thread_id).await?;
+        let board = repos.boards.find_by_id(thread.board_id).await?;
+        if board.site_id != self.server.site_id {
+            return Ok(None);
+        }
+        Ok(Some((post, self.server.group_name(board.slug.as_str()))))
+    }
+
+    /// The OP and subject of `post`'s thread.
+    async fn thread_info(&mut self, post: &Post) -> Result<ThreadInfo, DomainError> {
+        if let Some(info) = self.threads.get(&post.thread_id) {
+            return Ok(info.clone());
+        }
+        let repos = &self.server.repos;
+        let op_id = match repos.threads.find_by_id(post.thread_id).await {
+            Ok(thread) => thread.op_post_id,
+            Err(DomainError::NotFound { .. }) => None,
+            Err(e) => return Err(e),
+        };
+        let op = match op_id {
+            Some(id) if id == post.id => Some(post.clone()),
+            Some(id) => repos.posts.find_visible_by_id(id).await?,
+            None => None,
+        };
+        let info = ThreadInfo {
+            op_id:   op.as_ref().map(|op| op.id),
+            subject: op.as_ref().map_or_else(|| "(deleted thread)".to_owned(), article::subject),
+        };
+        if self.threads.len() >= MAX_CACHED_THREADS {
+            self.threads.clear();
+        }
+        self.threads.insert(post.thread_id, info.clone());
+        Ok(info)
+    }
+
+    /// `ARTICLE`, `HEAD`, `BODY` or `STAT` of the current article, a number
+    /// in the selected group, or a Message-ID.
+    async fn article(&mut self, verb: &str, arg: Option<&str>) -> Result<(), Failure> {
+        let (number, post, group) = match arg {
+            Some(message_id) if message_id.starts_with('<') => match self.by_message_id(message_id).await? {
+                Some((post, group)) => (0, post, group),
+                None => {
+                    self.out.line("430 No article with that message-id");
+                    return Ok(());
+                }
+            },
+            arg => {
+                let Some(group) = &self.group else {
+                    self.out.line("412 No newsgroup selected");
+                    return Ok(());
+                };
+                let group = group.name.clone();
+                let number = match arg {
+                    Some(number) => match number.parse::<u64>() {
+                        Ok(number) => number,
+                        Err(_) => {
+                            self.out.line("501 Syntax error in article number");
+                            return Ok(());
+                        }
+                    },
+                    None => match self.current {
+                        Some(current) => current,
+                        None => {
+                            self.out.line("420 Current article number is invalid");
+                            return Ok(());
+                        }
+                    },
+                };
+                match self.numbered(number).await? {
+                    Some(post) => {
+                        self.current = Some(number);
+                        (number, post, group)
+                    }
+                    None if arg.is_none() => {
+                        self.out.line("420 Current article number is invalid");
+                        return Ok(());
+                    }
+                    None => {
+                        self.out.line("423 No article with that number");
+                        return Ok(());
+                    }
+                }
+            }
+        };
+
+        let thread = self.thread_info(&post).await?;
+        let domain = self.server.options.domain.as_str();
+        let article = Article { group: &group, domain, post: &post, thread: &thread };
+        let (code, head, body) = match verb {
+            "ARTICLE" => (220, true, true),
+            "HEAD" => (221, true, false),
+            "BODY" => (222, false, true),
+            _ => (223, false, false),
+        };
+        self.out.line(&format!("{code} {number} {}", article.message_id()));
+        if head {
+            for line in article.head() {
+                self.out.text(&line);
+            }
+        }
+        if head && body {
+            self.out.line("");
+        }
+        if body {
+            for line in article.body() {
+                self.out.text(&line);
+            }
+        }
+        if head || body {
+            self.out.end();
+        }
+        Ok(())
+    }
+
+    /// `NEXT` (`forward`) or `LAST`.
+    async fn step(&mut self, forward: bool) -> Result<(), Failure> {
+        let Some(group) = &self.group else {
+            self.out.line("412 No newsgroup selected");
+            return Ok(());
+        };
+        let Some(current) = self.current else {
+            self.out.line("420 Current article number is invalid");
+            return Ok(());
+        };
+        let (board_id, (_, low, high)) = (group.board.id, group.counts());
+        let posts = &self.server.repos.posts;
+        let found = if forward {
+            posts.find_by_number_range(board_id, current.saturating_add(1), high, 1).await?.into_iter().next()
+        } else {
+            // Search backwards a window at a time; numbers may have gaps.
+            let mut last = current.saturating_sub(1);
+            let mut found = None;
+            while found.is_none() && last >= low && last > 0 {
+                let first = last.saturating_sub(u64::from(CHUNK) - 1).max(low);
+                found = posts.find_by_number_range(board_id, first, last, CHUNK).await?.pop();
+                last = first.saturating_sub(1);
+            }
+            found
+        };
+        let Some(post) = found else {
+            self.out.line(if forward {
+                "421 No next article in this group"
+            } else {
+                "422 No previous article in this group"
+            });
+            return Ok(());
+        };
+        self.current = Some(post.post_number);
+        let message_id = article::message_id(post.id, &self.server.options.domain);
+        self.out.line(&format!("223 {} {message_id} retrieved", post.post_number));
+        Ok(())
+    }
+
+    async fn overview_line(&mut self, post: &Post, group: &str) -> Result<String, DomainError> {
+        let thread = self.thread_info(post).await?;
+        let article = Article { group, domain: &self.server.options.domain, post, thread: &thread };
+        Ok(article.overview())
+    }
+
+    /// `OVER` of the current article, a range in the selected group, or a
+    /// Message-ID.
+    async fn over(&mut self, arg: Option<&str>) -> Result<(), Failure> {
+        if let Some(message_id) = arg.filter(|a| a.starts_with('<')) {
+            let Some((post, group)) = self.by_message_id(message_id).await? else {
+                self.out.line("430 No article with that message-id");
+                return Ok(());
+            };
+            let line = self.overview_line(&post, &group).await?;
+            // The number field of a lookup by Message-ID is 0.
+            let fields = line.split_once('\t').map_or("", |(_, fields)| fields);
+            self.out.line("224 Overview information follows");
+            self.out.text(&format!("0\t{fields}"));
+            self.out.end();
+            return Ok(());
+        }
+        let Some(group) = &self.group else {
+            self.out.line("412 No newsgroup selected");
+            return Ok(());
+        };
+        let (board_id, name, (_, _, high)) = (group.board.id, group.name.clone(), group.counts());
+        let (first, last) = match arg {
+            Some(range) => match parse_range(range, high) {
+                Some(range) => range,
+                None => {
+                    self.out.line("501 Syntax error in range");
+                    return Ok(());
+                }
+            },
+            None => match self.current {
+                Some(current) => (current, current),
+                None => {
+                    self.out.line("420 Current article number is invalid");
+                    return Ok(());
+                }
+            },
+        };
+
+        let mut from = first;
+        let mut any = false;
+        while from <= last {
+            let posts = self.server.repos.posts.find_by_number_range(board_id, from, last, CHUNK).await?;
+            if posts.is_empty() {
+                break;
+            }
+            if !any {
+                self.out.line("224 Overview information follows");
+                any = true;
+            }
+            for post in &posts {
+                let line = self.overview_line(post, &name).await?;
+                self.out.text(&line);
+            }
+            self.out.spill().await?;
+            match posts.last() {
+                Some(post) if posts.len() == CHUNK as usize => from = post.post_number + 1,
+                _ => break,
+            }
+        }
+        if !any {
+            self.out.line(if arg.is_some() {
+                "423 No articles in that range"
+            } else {
+                "420 Current article number is invalid"
+            });
+            return Ok(());
+        }
+        self.out.end();
+        Ok(())
+    }
+}
+
+/// `n`, `n-` or `n-m`; `n-` runs to `high`.
+fn parse_range(range: &str, high: u64) -> Option<(u64, u64)> {
+    match range.split_once('-') {
+        None => range.parse().ok().map(|n| (n, n)),
+        Some((first, "")) => Some((first.parse().ok()?, high)),
+        Some((first, last)) => Some((first.parse().ok()?, last.parse().ok()?)),
+    }
+}
+
+/// `NEWGROUPS` date (`yymmdd` or `yyyymmdd`) and time (`hhmmss`), in UTC.
+fn parse_date_time(date: &str, time: &str) -> Option<DateTime<Utc>> {
+    if !date.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let date = match date.len() {
+        8 => NaiveDate::parse_from_str(date, "%Y%m%d").ok()?,
+        // Two-digit years are the closest one not in the future.
+        6 => {
+            let this_year = Utc::now().format("%Y").to_string().parse::<i32>().ok()?;
+            let yy: i32 = date[..2].parse().ok()?;
+            let year = if 2000 + yy <= this_year { 2000 + yy } else { 1900 + yy };
+            NaiveDate::parse_from_str(&format!("{year}{}", &date[2..]), "%Y%m%d").ok()?
+        }
+        _ => return None,
+    };
+    let time = NaiveTime::parse_from_str(time, "%H%M%S").ok()?;
+    Some(NaiveDateTime::new(date, time).and_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use chrono::TimeZone;
+    use domains::models::{BoardId, DeletedBy, IpHash, Paginated, PostId, SiteId, Thread, Tombstone};
+    use domains::ports::{MockBoardRepository, MockPostRepository, MockThreadRepository};
+
+    use super::*;
+    use crate::{Options, Repositories};
+
+    fn board() -> Board {
+        Board {
+            id: BoardId::new(), site_id: SiteId::DEFAULT, slug: Slug::new("g").unwrap(), title: "Technology".to_owned(),
+            rules: String::new(), created_at: Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap(),
+        }
+    }
+
+    fn post(thread_id: ThreadId, number: u64, body: &str) -> Post {
+        Post {
+            id: PostId::new(), thread_id, body: body.to_owned(), ip_hash: IpHash::new("h"), name: None,
+            tripcode: None, email: None, created_at: Utc::now(), post_number: number, pinned: false, tags: vec![],
+            commands: vec![],
+            subject: None,
+            capcode: None,
+            deleted: None,
+            edited_at: None,
+        }
+    }
+
+    /// Board `g` with one thread: OP No.1 and reply No.3 (No.2 was deleted).
+    fn server(board: &Board, posts: Vec<Post>) -> NntpServer {
+        let mut boards = MockBoardRepository::new();
+        let found = board.clone();
+        boards.expect_find_by_slug().returning(move |_, slug| {
+            if slug.as_str() == "g" {
+                Ok(found.clone())
+            } else {
+                Err(DomainError::not_found("board"))
+            }
+        });
+        let found = board.clone();
+        boards.expect_find_by_site().returning(move |_, page| Ok(Paginated::new(vec![found.clone()], 1, page, 15)));
+        let found = board.clone();
+        boards.expect_find_by_id().returning(move |_| Ok(found.clone()));
+
+        let mut threads = MockThreadRepository::new();
+        let (board_id, thread_id, op_id) = (board.id, posts[0].thread_id, posts[0].id);
+        threads.expect_find_by_id().returning(move |_| {
+            Ok(Thread {
+                id: thread_id, board_id, op_post_id: Some(op_id), reply_count: 1, bumped_at: Utc::now(),
+                sticky: false, closed: false, cycle: false, created_at: Utc::now(),
+            })
+        });
+
+        let mut repo = MockPostRepository::new();
+        let range = PostNumberRange { low: 1, high: 3, count: posts.len() as u64 };
+        repo.expect_find_number_range().returning(move |_| Ok(Some(range)));
+        let all = posts.clone();
+        repo.expect_find_by_number_range().returning(move |_, first, last, limit| {
+            let found = all.iter().filter(|p| (first..=last).contains(&p.post_number));
+            Ok(found.take(limit as usize).cloned().collect())
+        });
+        // Posts with the IP hash "shadow" stand in for shadowbanned ones.
+        repo.expect_find_visible_by_id().returning(move |id| {
+            Ok(posts.iter().find(|p| p.id == id && !p.is_deleted() && p.ip_hash.0 != "shadow").cloned())
+        });
+
+        let repos = Repositories { boards: Arc::new(boards), threads: Arc::new(threads), posts: Arc::new(repo) };
+        let options =
+            Options { domain: "board.example".to_owned(), group_prefix: "rusty-board".to_owned(), max_connections: 1 };
+        NntpServer::new(SiteId::DEFAULT, repos, options)
+    }
+
+    /// The server's answers to `commands`, one per CRLF-terminated line.
+    async fn converse(server: &NntpServer, commands: &str) -> Vec<String> {
+        let mut output = Vec::new();
+        run(server, commands.as_bytes(), &mut output, CancellationToken::new()).await.unwrap();
+        String::from_utf8(output).unwrap().split("\r\n").map(str::to_owned).collect()
+    }
+
+    #[tokio::test]
+    async fn groups_are_read_thread_by_thread() {
+        let board = board();
+        let thread_id = ThreadId::new();
+        let (op, reply) = (post(thread_id, 1, "Rust thread"), post(thread_id, 3, ">>1\n.hidden dot"));
+        let server = server(&board, vec![op.clone(), reply.clone()]);
+        let lines = converse(
+            &server,
+            concat!(
+                "MODE READER\r\nLIST\r\nGROUP rusty-board.g\r\nOVER 1-\r\nARTICLE 3\r\n",
+                "LAST\r\nNEXT\r\nNEXT\r\nPOST\r\nQUIT\r\n",
+            ),
+        )
+        .await;
+        let op_id = article::message_id(op.id, "board.example");
+        let reply_id = article::message_id(reply.id, "board.example");
+
+        let expected = [
+            "201 Reader mode, posting prohibited",
+            "rusty-board.g 3 1 n",
+            "211 2 1 3 rusty-board.g",
+            "224 Overview information follows",
+            &format!("220 3 {reply_id}"),
+            &format!("References: {op_id}"),
+            "..hidden dot",
+            &format!("223 1 {op_id} retrieved"),
+            &format!("223 3 {reply_id} retrieved"),
+            "421 No next article in this group",
+            "440 Posting not permitted",
+            "205 Bye",
+        ];
+        let mut rest = lines.iter();
+        for line in expected {
+            assert!(rest.any(|l| l == line), "{line:?} missing from, or out of order in, {lines:#?}");
+        }
+        let overviews: Vec<&String> = lines.iter().filter(|l| l.contains('\t')).collect();
+        assert_eq!(overviews.len(), 2);
+        assert!(overviews[0].starts_with("1\tRust thread\t"));
+        assert!(overviews[1].starts_with("3\tRe: Rust thread\t"));
+    }
+
+    #[tokio::test]
+    async fn unknown_groups_and_articles_are_refused() {
+        let board = board();
+        let server = server(&board, vec![post(ThreadId::new(), 1, "op")]);
+        let lines = converse(
+            &server,
+            "ARTICLE 1\r\nGROUP rusty-board.x\r\nGROUP other.g\r\nARTICLE <nope@elsewhere.example>\r\n\
+             GROUP\r\nFROB\r\nGROUP rusty-board.g\r\nSTAT 2\r\nOVER 5-9\r\n",
+        )
+        .await;
+        assert_eq!(lines[1..10], [
+            "412 No newsgroup selected",
+            "411 No such newsgroup",
+            "411 No such newsgroup",
+            "430 No article with that message-id",
+            "501 Syntax error",
+            "500 Unknown command",
+            "211 1 1 3 rusty-board.g",
+            "423 No article with that number",
+            "423 No articles in that range",
+        ]);
+    }
+
+    #[tokio::test]
+    async fn tombstones_are_not_served_by_message_id() {
+        let board = board();
+        let thread_id = ThreadId::new();
+        let deleted = Post {
+            deleted: Some(Tombstone { by: DeletedBy::Staff, at: Utc::now() }),
+            ..post(thread_id, 2, "deleted secret")
+        };
+        let server = server(&board, vec![post(thread_id, 1, "op"), deleted.clone()]);
+        let id = article::message_id(deleted.id, "board.example");
+        let lines = converse(&server, &format!("ARTICLE {id}\r\nHEAD {id}\r\nBODY {id}\r\nSTAT {id}\r\n")).await;
+        assert_eq!(lines[1..5], ["430 No article with that message-id"; 4]);
+        assert!(!lines.iter().any(|l| l.contains("deleted secret")));
+    }
+
+    #[tokio::test]
+    async fn shadowbanned_posts_are_not_served_by_message_id_or_as_the_op() {
+        let board = board();
+        let thread_id = ThreadId::new();
+        let shadowed = |number, body| Post { ip_hash: IpHash::new("shadow"), ..post(thread_id, number, body) };
+        let (op, reply) = (shadowed(1, "shadowed op"), post(thread_id, 2, "a reply"));
+        let hidden = shadowed(3, "shadowed reply");
+        let server = server(&board, vec![op.clone(), reply.clone(), hidden.clone()]);
+        let (op_id, hidden_id) = (article::message_id(op.id, "board.example"), article::message_id(hidden.id, "board.example"));
+        let reply_id = article::message_id(reply.id, "board.example");
+        let lines = converse(&server, &format!("STAT {op_id}\r\nARTICLE {hidden_id}\r\nARTICLE {reply_id}\r\n")).await;
+        assert_eq!(lines[1..3], ["430 No article with that message-id"; 2]);
+        assert_eq!(lines[3], format!("220 0 {reply_id}"));
+        assert!(!lines.iter().any(|l| l.contains("shadowed")), "{lines:#?}");
+    }
+
+    #[test]
+    fn ranges_and_dates_are_parsed() {
+        assert_eq!(parse_range("5", 9), Some((5, 5)));
+        assert_eq!(parse_range("5-", 9), Some((5, 9)));
+        assert_eq!(parse_range("5-7", 9), Some((5, 7)));
+        assert_eq!(parse_range("x-7", 9), None);
+        let expected = Utc.with_ymd_and_hms(2026, 2, 3, 4, 5, 6).unwrap();
+        assert_eq!(parse_date_time("20260203", "040506"), Some(expected));
+        assert_eq!(parse_date_time("260203", "040506"), Some(expected));
+        assert_eq!(parse_date_time("260203", "250000"), None);
+    }
+}
@@ -0,0 +1,62 @@
+//! Wildmat group patterns (RFC 3977 §4): `*` and `?` wildcards, several
+//! patterns separated by commas, and `!` to exclude. The last pattern that
+//! matches decides.
+
+/// Whether `name` matches the wildmat `pattern`.
+pub fn matches(pattern: &str, name: &str) -> bool {
+    let mut matched = false;
+    for part in pattern.split(',') {
+        let (negated, glob) = match part.strip_prefix('!') {
+            Some(glob) => (true, glob),
+            None => (false, part),
+        };
+        if glob_matches(glob.as_bytes(), name.as_bytes()) {
+            matched = !negated;
+        }
+    }
+    matched
+}
+
+fn glob_matches(glob: &[u8], name: &[u8]) -> bool {
+    // Backtrack to just after the last `*`, one more name byte consumed.
+    let (mut g, mut n) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while n < name.len() {
+        match glob.get(g) {
+            Some(b'*') => {
+                star = Some((g + 1, n));
+                g += 1;
+            }
+            Some(&c) if c == b'?' || c == name[n] => {
+                g += 1;
+                n += 1;
+            }
+            _ => match star {
+                Some((after_star, start)) => {
+                    g = after_star;
+                    n = start + 1;
+                    star = Some((after_star, start + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    glob[g..].iter().all(|&c| c == b'*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wildmats_match_and_exclude() {
+        assert!(matches("*", "rusty-board.g"));
+        assert!(matches("rusty-board.*", "rusty-board.g"));
+        assert!(matches("rusty-board.?", "rusty-board.g"));
+        assert!(!matches("rusty-board.?", "rusty-board.tech"));
+        assert!(matches("*.t*h", "rusty-board.tech"));
+        assert!(!matches("rusty-board.*,!*.b", "rusty-board.b"));
+        assert!(matches("rusty-board.*,!*.b", "rusty-board.g"));
+        assert!(!matches("other.*", "rusty-board.g"));
+    }
+}
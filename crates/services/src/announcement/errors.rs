@@ -0,0 +1,30 @@
+//! Error type for `AnnouncementService` operations.
+
+use domains::errors::DomainError;
+use thiserror::Error;
+
+/// Errors that can occur in `AnnouncementService` methods.
+#[derive(Debug, Error)]
+pub enum AnnouncementError {
+    /// The message is blank.
+    #[error("announcement is empty")]
+    Empty,
+
+    /// The message is longer than `MAX_ANNOUNCEMENT_CHARS`.
+    #[error("announcement is too long (max {max} characters)")]
+    TooLong {
+        /// The most characters an announcement may have.
+        max: usize,
+    },
+
+    /// The site has no announcement with this id.
+    #[error("announcement not found: {id}")]
+    NotFound {
+        /// The announcement that was asked for.
+        id: String,
+    },
+
+    /// A domain-level error that could not be handled at this level.
+    #[error("internal error: {0}")]
+    Internal(#[from] DomainError),
+}
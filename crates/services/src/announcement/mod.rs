@@ -0,0 +1,131 @@
+//! `AnnouncementService` — the blotter: short dated announcements from admins.
+//!
+//! An announcement is site-wide or belongs to one board. Board pages show the
+//! newest `BLOTTER_ENTRIES` of those that apply to them (see
+//! `ThreadService::with_announcements`); this service posts, lists and
+//! deletes them.
+
+pub mod errors;
+pub use errors::AnnouncementError;
+
+use chrono::Utc;
+use domains::errors::DomainError;
+use domains::models::{Announcement, AnnouncementId, BoardId, SiteId};
+use domains::ports::AnnouncementRepository;
+use tracing::{info, instrument};
+
+/// Most characters one announcement may have.
+pub const MAX_ANNOUNCEMENT_CHARS: usize = 300;
+
+/// Announcements shown on a board page.
+pub const BLOTTER_ENTRIES: u32 = 5;
+
+/// Service for blotter announcements.
+///
+/// Generic over `AR: AnnouncementRepository`.
+pub struct AnnouncementService<AR: AnnouncementRepository> {
+    repo: AR,
+}
+
+impl<AR: AnnouncementRepository> AnnouncementService<AR> {
+    /// Construct an `AnnouncementService`.
+    pub fn new(repo: AR) -> Self {
+        Self { repo }
+    }
+
+    /// Post `message` on the blotter of `board_id`, or of every board of the
+    /// site when `None`.
+    ///
+    /// # Errors
+    /// - `AnnouncementError::Empty` — the message is blank
+    /// - `AnnouncementError::TooLong` — more than `MAX_ANNOUNCEMENT_CHARS`
+    #[instrument(skip(self, message), fields(site_id = %site_id))]
+    pub async fn post(
+        &self,
+        site_id:  SiteId,
+        board_id: Option<BoardId>,
+        message:  &str,
+    ) -> Result<Announcement, AnnouncementError> {
+        let message = message.trim();
+        if message.is_empty() {
+            return Err(AnnouncementError::Empty);
+        }
+        if message.chars().count() > MAX_ANNOUNCEMENT_CHARS {
+            return Err(AnnouncementError::TooLong { max: MAX_ANNOUNCEMENT_CHARS });
+        }
+
+        let announcement = Announcement {
+            id: AnnouncementId::new(),
+            site_id,
+            board_id,
+            message: message.to_owned(),
+            created_at: Utc::now(),
+        };
+        self.repo.create(&announcement).await?;
+        info!(announcement_id = %announcement.id, "announcement posted");
+        Ok(announcement)
+    }
+
+    /// Every announcement of the site, newest first.
+    #[instrument(skip(self), fields(site_id = %site_id))]
+    pub async fn list(&self, site_id: SiteId) -> Result<Vec<Announcement>, AnnouncementError> {
+        Ok(self.repo.list(site_id).await?)
+    }
+
+    /// Delete an announcement of the site and return it.
+    ///
+    /// # Errors
+    /// - `AnnouncementError::NotFound` — the site has no such announcement
+    #[instrument(skip(self), fields(site_id = %site_id, announcement_id = %id))]
+    pub async fn delete(&self, site_id: SiteId, id: AnnouncementId) -> Result<Announcement, AnnouncementError> {
+        let announcement = self.repo.delete(site_id, id).await.map_err(|e| match e {
+            DomainError::NotFound { .. } => AnnouncementError::NotFound { id: id.to_string() },
+            other => other.into(),
+        })?;
+        info!("announcement deleted");
+        Ok(announcement)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use domains::ports::MockAnnouncementRepository;
+
+    #[tokio::test]
+    async fn post_stores_the_trimmed_message() {
+        let mut repo = MockAnnouncementRepository::new();
+        repo.expect_create()
+            .withf(|a| a.message == "Server move tonight" && a.board_id.is_none())
+            .times(1)
+            .returning(|_| Ok(()));
+        let svc = AnnouncementService::new(repo);
+
+        let a = svc.post(SiteId::DEFAULT, None, "  Server move tonight \n").await.unwrap();
+        assert_eq!(a.site_id, SiteId::DEFAULT);
+    }
+
+    #[tokio::test]
+    async fn blank_and_overlong_messages_are_refused() {
+        let mut repo = MockAnnouncementRepository::new();
+        repo.expect_create().never();
+        let svc = AnnouncementService::new(repo);
+
+        assert!(matches!(svc.post(SiteId::DEFAULT, None, "   ").await, Err(AnnouncementError::Empty)));
+        let long = "x".repeat(MAX_ANNOUNCEMENT_CHARS + 1);
+        assert!(matches!(
+            svc.post(SiteId::DEFAULT, Some(BoardId::new()), &long).await,
+            Err(AnnouncementError::TooLong { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn deleting_an_unknown_announcement_is_not_found() {
+        let mut repo = MockAnnouncementRepository::new();
+        repo.expect_delete().returning(|_, id| Err(DomainError::not_found(format!("announcement {id}"))));
+        let svc = AnnouncementService::new(repo);
+
+        let err = svc.delete(SiteId::DEFAULT, AnnouncementId::new()).await;
+        assert!(matches!(err, Err(AnnouncementError::NotFound { .. })));
+    }
+}
@@ -0,0 +1,56 @@
+//! Error type for `ApiKeyService` operations.
+
+use domains::errors::DomainError;
+use domains::models::ApiScope;
+use thiserror::Error;
+
+/// Errors that can occur in `ApiKeyService` methods.
+#[derive(Debug, Error)]
+pub enum ApiKeyError {
+    /// The name is blank or longer than `MAX_NAME_CHARS`.
+    #[error("API key name must be 1–{max} characters")]
+    InvalidName {
+        /// The most characters a name may have.
+        max: usize,
+    },
+
+    /// No scope was given.
+    #[error("an API key needs at least one scope")]
+    NoScopes,
+
+    /// The rate limit is outside `1..=MAX_RATE_LIMIT_PER_MIN`.
+    #[error("API key rate limit must be 1–{max} requests per minute")]
+    InvalidRateLimit {
+        /// The highest rate limit a key may have.
+        max: u32,
+    },
+
+    /// The key is unknown or revoked.
+    #[error("invalid API key")]
+    InvalidKey,
+
+    /// The key is valid but lacks the scope the request needs.
+    #[error("API key lacks the '{scope}' scope")]
+    MissingScope {
+        /// The scope the request needs.
+        scope: ApiScope,
+    },
+
+    /// The key used up its requests for this minute.
+    #[error("API key rate limit exceeded")]
+    RateLimited {
+        /// Seconds until the key may be used again.
+        retry_after_secs: u32,
+    },
+
+    /// There is no API key with this id.
+    #[error("API key not found: {id}")]
+    NotFound {
+        /// The key that was asked for.
+        id: String,
+    },
+
+    /// A domain-level error that could not be handled at this level.
+    #[error("internal error: {0}")]
+    Internal(#[from] DomainError),
+}
@@ -0,0 +1,272 @@
+//! `ApiKeyService` — keys for bots and bridges using the JSON API.
+//!
+//! Admins issue a key with a name, scopes and a per-minute rate limit. The
+//! key is shown once; only its SHA-256 hex is stored. Requests present it as
+//! `Authorization: Bearer rbk_...`, and `authorize` checks it, its scope and
+//! its rate limit.
+//!
+//! Rate limits are counted per process, in one-minute windows, like the login
+//! lockout. Behind several instances a key gets its limit on each.
+
+pub mod errors;
+pub use errors::ApiKeyError;
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use domains::errors::DomainError;
+use domains::models::{ApiClient, ApiKey, ApiKeyId, ApiScope, UserId};
+use domains::ports::ApiKeyRepository;
+use sha2::{Digest, Sha256};
+use tracing::{info, instrument, warn};
+
+/// Every key starts with this, so the auth middleware can tell keys from JWTs.
+pub const KEY_PREFIX: &str = "rbk_";
+
+/// Most characters a key's name may have.
+pub const MAX_NAME_CHARS: usize = 64;
+
+/// Highest per-minute rate limit a key may have.
+pub const MAX_RATE_LIMIT_PER_MIN: u32 = 6000;
+
+/// Characters of the key kept in `ApiKey::prefix`.
+const SHOWN_CHARS: usize = 12;
+
+/// How stale `last_used_at` may get before a request records it again.
+const TOUCH_INTERVAL_SECS: i64 = 60;
+
+/// A request count in one minute.
+#[derive(Debug, Clone, Copy)]
+struct Window {
+    minute: i64,
+    count:  u32,
+}
+
+/// Service for issuing, revoking and checking API keys.
+///
+/// Generic over `AK: ApiKeyRepository`.
+pub struct ApiKeyService<AK: ApiKeyRepository> {
+    repo:    AK,
+    windows: Mutex<HashMap<ApiKeyId, Window>>,
+}
+
+impl<AK: ApiKeyRepository> ApiKeyService<AK> {
+    /// Construct an `ApiKeyService`.
+    pub fn new(repo: AK) -> Self {
+        Self { repo, windows: Mutex::new(HashMap::new()) }
+    }
+
+    /// Issue a key. Returns the stored record and the key itself, which is
+    /// not kept and cannot be shown again.
+    ///
+    /// # Errors
+    /// - `ApiKeyError::InvalidName` — blank or longer than `MAX_NAME_CHARS`
+    /// - `ApiKeyError::NoScopes` — `scopes` is empty
+    /// - `ApiKeyError::InvalidRateLimit` — outside `1..=MAX_RATE_LIMIT_PER_MIN`
+    #[instrument(skip(self), fields(created_by = %created_by))]
+    pub async fn issue(
+        &self,
+        name: &str,
+        scopes: &[ApiScope],
+        rate_limit_per_min: u32,
+        created_by: UserId,
+    ) -> Result<(ApiKey, String), ApiKeyError> {
+        let name = name.trim();
+        if name.is_empty() || name.chars().count() > MAX_NAME_CHARS {
+            return Err(ApiKeyError::InvalidName { max: MAX_NAME_CHARS });
+        }
+        if scopes.is_empty() {
+            return Err(ApiKeyError::NoScopes);
+        }
+        if !(1..=MAX_RATE_LIMIT_PER_MIN).contains(&rate_limit_per_min) {
+            return Err(ApiKeyError::InvalidRateLimit { max: MAX_RATE_LIMIT_PER_MIN });
+        }
+        let mut scopes = scopes.to_vec();
+        scopes.sort_by_key(|s| s.as_str());
+        scopes.dedup();
+
+        let secret = format!("{KEY_PREFIX}{}", hex::encode(rand::random::<[u8; 24]>()));
+        let key = ApiKey {
+            id: ApiKeyId::new(),
+            name: name.to_owned(),
+            prefix: secret[..SHOWN_CHARS].to_owned(),
+            key_hash: hash_key(&secret),
+            scopes,
+            rate_limit_per_min,
+            created_by,
+            created_at: Utc::now(),
+            last_used_at: None,
+            revoked_at: None,
+        };
+        self.repo.save(&key).await?;
+        info!(key_id = %key.id, name = %key.name, "API key issued");
+        Ok((key, secret))
+    }
+
+    /// Every key, newest first, revoked ones included.
+    pub async fn list(&self) -> Result<Vec<ApiKey>, ApiKeyError> {
+        Ok(self.repo.list().await?)
+    }
+
+    /// Revoke a key; it stops working at once.
+    ///
+    /// # Errors
+    /// - `ApiKeyError::NotFound` — there is no such key
+    #[instrument(skip(self), fields(key_id = %id))]
+    pub async fn revoke(&self, id: ApiKeyId) -> Result<ApiKey, ApiKeyError> {
+        let key = self.repo.revoke(id, Utc::now()).await.map_err(|e| match e {
+            DomainError::NotFound { .. } => ApiKeyError::NotFound { id: id.to_string() },
+            other => other.into(),
+        })?;
+        info!("API key revoked");
+        Ok(key)
+    }
+
+    /// Check a presented key for a request needing `scope`, and count the
+    /// request against the key's rate limit. Returns who is calling.
+    ///
+    /// # Errors
+    /// - `ApiKeyError::InvalidKey` — unknown or revoked
+    /// - `ApiKeyError::MissingScope` — the key lacks `scope`
+    /// - `ApiKeyError::RateLimited` — the key used up this minute's requests
+    pub async fn authorize(&self, secret: &str, scope: ApiScope) -> Result<ApiClient, ApiKeyError> {
+        self.authorize_at(secret, scope, Utc::now()).await
+    }
+
+    async fn authorize_at(&self, secret: &str, scope: ApiScope, now: DateTime<Utc>) -> Result<ApiClient, ApiKeyError> {
+        let key = match self.repo.find_by_hash(&hash_key(secret)).await? {
+            Some(key) if key.revoked_at.is_none() => key,
+            _ => return Err(ApiKeyError::InvalidKey),
+        };
+        if !key.scopes.contains(&scope) {
+            return Err(ApiKeyError::MissingScope { scope });
+        }
+        self.count_request(&key, now)?;
+
+        let stale = key.last_used_at.is_none_or(|at| (now - at).num_seconds() >= TOUCH_INTERVAL_SECS);
+        if stale {
+            // Fail open: a missed timestamp is not worth refusing the request.
+            if let Err(e) = self.repo.touch(key.id, now).await {
+                warn!(key_id = %key.id, error = %e, "failed to record API key use");
+            }
+        }
+        Ok(ApiClient { key_id: key.id, name: key.name, scopes: key.scopes })
+    }
+
+    fn count_request(&self, key: &ApiKey, now: DateTime<Utc>) -> Result<(), ApiKeyError> {
+        let minute = now.timestamp().div_euclid(60);
+        let mut windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+        windows.retain(|_, w| w.minute == minute);
+        let window = windows.entry(key.id).or_insert(Window { minute, count: 0 });
+        if window.count >= key.rate_limit_per_min {
+            let retry_after_secs = (60 - now.timestamp().rem_euclid(60)) as u32;
+            return Err(ApiKeyError::RateLimited { retry_after_secs });
+        }
+        window.count += 1;
+        Ok(())
+    }
+}
+
+/// The stored form of a key.
+fn hash_key(secret: &str) -> String {
+    hex::encode(Sha256::digest(secret.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use domains::ports::MockApiKeyRepository;
+
+    fn stored(scopes: Vec<ApiScope>, rate_limit_per_min: u32) -> (ApiKey, String) {
+        let secret = format!("{KEY_PREFIX}0123456789abcdef");
+        let key = ApiKey {
+            id: ApiKeyId::new(),
+            name: "archive-bot".to_owned(),
+            prefix: secret[..SHOWN_CHARS].to_owned(),
+            key_hash: hash_key(&secret),
+            scopes,
+            rate_limit_per_min,
+            created_by: UserId::new(),
+            created_at: Utc::now(),
+            last_used_at: None,
+            revoked_at: None,
+        };
+        (key, secret)
+    }
+
+    fn service_with(key: ApiKey) -> ApiKeyService<MockApiKeyRepository> {
+        let mut repo = MockApiKeyRepository::new();
+        repo.expect_find_by_hash()
+            .returning(move |hash| Ok((hash == key.key_hash).then(|| key.clone())));
+        repo.expect_touch().returning(|_, _| Ok(()));
+        ApiKeyService::new(repo)
+    }
+
+    #[tokio::test]
+    async fn issue_stores_only_the_hash() {
+        let mut repo = MockApiKeyRepository::new();
+        repo.expect_save()
+            .withf(|k| k.name == "bridge" && k.scopes == [ApiScope::Post, ApiScope::Read] && k.prefix.starts_with(KEY_PREFIX))
+            .times(1)
+            .returning(|_| Ok(()));
+        let svc = ApiKeyService::new(repo);
+
+        let (key, secret) = svc
+            .issue(" bridge ", &[ApiScope::Read, ApiScope::Post, ApiScope::Read], 60, UserId::new())
+            .await
+            .unwrap();
+        assert!(secret.starts_with(&key.prefix));
+        assert_eq!(key.key_hash, hash_key(&secret));
+        assert_ne!(key.key_hash, secret);
+    }
+
+    #[tokio::test]
+    async fn issue_validates_its_input() {
+        let mut repo = MockApiKeyRepository::new();
+        repo.expect_save().never();
+        let svc = ApiKeyService::new(repo);
+        let admin = UserId::new();
+
+        assert!(matches!(svc.issue(" ", &[ApiScope::Read], 60, admin).await, Err(ApiKeyError::InvalidName { .. })));
+        assert!(matches!(svc.issue("bot", &[], 60, admin).await, Err(ApiKeyError::NoScopes)));
+        assert!(matches!(
+            svc.issue("bot", &[ApiScope::Read], 0, admin).await,
+            Err(ApiKeyError::InvalidRateLimit { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn authorize_checks_the_key_and_its_scope() {
+        let (key, secret) = stored(vec![ApiScope::Read], 10);
+        let svc = service_with(key);
+
+        assert!(svc.authorize(&secret, ApiScope::Read).await.is_ok());
+        assert!(matches!(
+            svc.authorize(&secret, ApiScope::Post).await,
+            Err(ApiKeyError::MissingScope { scope: ApiScope::Post })
+        ));
+        assert!(matches!(svc.authorize("rbk_wrong", ApiScope::Read).await, Err(ApiKeyError::InvalidKey)));
+    }
+
+    #[tokio::test]
+    async fn revoked_keys_are_invalid() {
+        let (key, secret) = stored(vec![ApiScope::Read], 10);
+        let svc = service_with(ApiKey { revoked_at: Some(Utc::now()), ..key });
+        assert!(matches!(svc.authorize(&secret, ApiScope::Read).await, Err(ApiKeyError::InvalidKey)));
+    }
+
+    #[tokio::test]
+    async fn each_key_gets_its_rate_limit_per_minute() {
+        let (key, secret) = stored(vec![ApiScope::Read], 2);
+        let svc = service_with(key);
+        let at = |secs: i64| Utc.timestamp_opt(1_700_000_000 + secs, 0).unwrap();
+
+        assert!(svc.authorize_at(&secret, ApiScope::Read, at(0)).await.is_ok());
+        assert!(svc.authorize_at(&secret, ApiScope::Read, at(1)).await.is_ok());
+        let err = svc.authorize_at(&secret, ApiScope::Read, at(2)).await;
+        assert!(matches!(err, Err(ApiKeyError::RateLimited { retry_after_secs }) if retry_after_secs > 0));
+        assert!(svc.authorize_at(&secret, ApiScope::Read, at(60)).await.is_ok());
+    }
+}
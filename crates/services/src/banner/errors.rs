@@ -0,0 +1,37 @@
+//! Error type for `BannerService` operations.
+
+use domains::errors::DomainError;
+use thiserror::Error;
+
+/// Errors that can occur in `BannerService` methods.
+#[derive(Debug, Error)]
+pub enum BannerError {
+    /// The upload is not a PNG, JPEG, GIF or WebP image.
+    #[error("banner must be a PNG, JPEG, GIF or WebP image")]
+    InvalidType,
+
+    /// The upload is larger than `MAX_BANNER_BYTES`.
+    #[error("banner is too large (max {max} bytes)")]
+    TooLarge {
+        /// The largest banner accepted, in bytes.
+        max: usize,
+    },
+
+    /// The board already has `MAX_BANNERS_PER_BOARD` banners.
+    #[error("board already has {max} banners")]
+    TooMany {
+        /// The most banners a board may have.
+        max: usize,
+    },
+
+    /// The board has no banner with this id.
+    #[error("banner not found: {id}")]
+    NotFound {
+        /// The banner that was asked for.
+        id: String,
+    },
+
+    /// A domain-level error that could not be handled at this level.
+    #[error("internal error: {0}")]
+    Internal(#[from] DomainError),
+}
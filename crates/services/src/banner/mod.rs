@@ -0,0 +1,224 @@
+//! `BannerService` — rotating board banners.
+//!
+//! # Uploads
+//! Board owners upload banner images from the board dashboard. An upload is
+//! identified by its magic bytes (`sniff_mime`) and must be a PNG, JPEG, GIF
+//! or WebP image no larger than `MAX_BANNER_BYTES`. It is stored in
+//! `MediaStorage` under `MediaKey::BANNER_PREFIX`, which the orphaned-media
+//! sweep skips, and recorded by the `BannerRepository`.
+//!
+//! # Rotation
+//! Board pages load `GET /board/{slug}/banner`, which asks `random` for one of
+//! the board's banners on every request.
+
+pub mod errors;
+pub use errors::BannerError;
+
+use bytes::Bytes;
+use chrono::Utc;
+use domains::errors::DomainError;
+use domains::models::{BannerId, BoardBanner, BoardId, MediaKey};
+use domains::ports::{BannerRepository, MediaStorage};
+use tracing::{info, instrument, warn};
+
+use crate::common::utils::sniff_mime;
+
+/// Largest banner image accepted, in bytes.
+pub const MAX_BANNER_BYTES: usize = 512 * 1024;
+
+/// Most banners one board may have.
+pub const MAX_BANNERS_PER_BOARD: usize = 20;
+
+/// Service for board banners.
+///
+/// Generic over `BR: BannerRepository` and `MS: MediaStorage` (where the
+/// images are kept).
+pub struct BannerService<BR, MS>
+where
+    BR: BannerRepository,
+    MS: MediaStorage,
+{
+    repo:  BR,
+    media: MS,
+}
+
+impl<BR, MS> BannerService<BR, MS>
+where
+    BR: BannerRepository,
+    MS: MediaStorage,
+{
+    /// Construct a `BannerService`.
+    pub fn new(repo: BR, media: MS) -> Self {
+        Self { repo, media }
+    }
+
+    /// Store `data` as a new banner of `board_id`.
+    ///
+    /// # Errors
+    /// - `BannerError::TooLarge` — more than `MAX_BANNER_BYTES`
+    /// - `BannerError::InvalidType` — not a PNG, JPEG, GIF or WebP image
+    /// - `BannerError::TooMany` — the board has `MAX_BANNERS_PER_BOARD` banners
+    #[instrument(skip(self, data), fields(board_id = %board_id, bytes = data.len()))]
+    pub async fn upload(&self, board_id: BoardId, data: Bytes) -> Result<BoardBanner, BannerError> {
+        if data.len() > MAX_BANNER_BYTES {
+            return Err(BannerError::TooLarge { max: MAX_BANNER_BYTES });
+        }
+        let (content_type, ext) = match sniff_mime(&data) {
+            Some("image/png")  => ("image/png", "png"),
+            Some("image/jpeg") => ("image/jpeg", "jpg"),
+            Some("image/gif")  => ("image/gif", "gif"),
+            Some("image/webp") => ("image/webp", "webp"),
+            _ => return Err(BannerError::InvalidType),
+        };
+        if self.repo.list(board_id).await?.len() >= MAX_BANNERS_PER_BOARD {
+            return Err(BannerError::TooMany { max: MAX_BANNERS_PER_BOARD });
+        }
+
+        let id = BannerId::new();
+        let banner = BoardBanner {
+            id,
+            board_id,
+            media_key: MediaKey::banner(board_id, id, ext),
+            content_type: content_type.to_owned(),
+            created_at: Utc::now(),
+        };
+        self.media.store(&banner.media_key, data, content_type).await?;
+        if let Err(e) = self.repo.add(&banner).await {
+            // Nothing refers to the image now, and the sweep skips banners.
+            if let Err(cleanup) = self.media.delete(&banner.media_key).await {
+                warn!(key = %banner.media_key, error = %cleanup, "failed to delete unrecorded banner");
+            }
+            return Err(e.into());
+        }
+        info!(banner_id = %id, "banner uploaded");
+        Ok(banner)
+    }
+
+    /// The board's banners, oldest first.
+    #[instrument(skip(self), fields(board_id = %board_id))]
+    pub async fn list(&self, board_id: BoardId) -> Result<Vec<BoardBanner>, BannerError> {
+        Ok(self.repo.list(board_id).await?)
+    }
+
+    /// A random banner of the board, or `None` when it has none.
+    #[instrument(skip(self), fields(board_id = %board_id))]
+    pub async fn random(&self, board_id: BoardId) -> Result<Option<BoardBanner>, BannerError> {
+        Ok(self.repo.random(board_id).await?)
+    }
+
+    /// Remove a banner of the board and delete its image.
+    ///
+    /// A failed image delete is logged; the banner is gone from the board
+    /// either way.
+    ///
+    /// # Errors
+    /// - `BannerError::NotFound` — the board has no such banner
+    #[instrument(skip(self), fields(board_id = %board_id, banner_id = %id))]
+    pub async fn remove(&self, board_id: BoardId, id: BannerId) -> Result<(), BannerError> {
+        let banner = self.repo.remove(board_id, id).await.map_err(|e| match e {
+            DomainError::NotFound { .. } => BannerError::NotFound { id: id.to_string() },
+            other => other.into(),
+        })?;
+        if let Err(e) = self.media.delete(&banner.media_key).await {
+            warn!(key = %banner.media_key, error = %e, "failed to delete banner image");
+        }
+        info!("banner removed");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use domains::ports::{MockBannerRepository, MockMediaStorage};
+
+    /// The 8-byte PNG signature followed by an IHDR chunk header.
+    const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+
+    fn banner(board_id: BoardId) -> BoardBanner {
+        let id = BannerId::new();
+        BoardBanner {
+            id,
+            board_id,
+            media_key: MediaKey::banner(board_id, id, "png"),
+            content_type: "image/png".to_owned(),
+            created_at: Utc::now(),
+        }
+    }
+
+    /// A repository whose boards have `existing` banners.
+    fn repo_with(existing: usize) -> MockBannerRepository {
+        let mut repo = MockBannerRepository::new();
+        repo.expect_list().returning(move |board_id| Ok((0..existing).map(|_| banner(board_id)).collect()));
+        repo
+    }
+
+    #[tokio::test]
+    async fn upload_stores_the_image_under_the_banner_prefix() {
+        let mut repo = repo_with(0);
+        repo.expect_add().times(1).returning(|_| Ok(()));
+        let mut media = MockMediaStorage::new();
+        media
+            .expect_store()
+            .withf(|key, _, content_type| key.is_banner() && key.0.ends_with(".png") && content_type == "image/png")
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+        let svc = BannerService::new(repo, media);
+
+        let board_id = BoardId::new();
+        let banner = svc.upload(board_id, Bytes::from_static(PNG)).await.unwrap();
+        assert_eq!(banner.board_id, board_id);
+        assert_eq!(banner.content_type, "image/png");
+    }
+
+    #[tokio::test]
+    async fn non_images_are_refused() {
+        let mut media = MockMediaStorage::new();
+        media.expect_store().never();
+        let svc = BannerService::new(repo_with(0), media);
+
+        let err = svc.upload(BoardId::new(), Bytes::from_static(b"%PDF-1.7 hello")).await;
+        assert!(matches!(err, Err(BannerError::InvalidType)));
+    }
+
+    #[tokio::test]
+    async fn oversized_uploads_are_refused() {
+        let svc = BannerService::new(repo_with(0), MockMediaStorage::new());
+        let mut data = PNG.to_vec();
+        data.resize(MAX_BANNER_BYTES + 1, 0);
+
+        let err = svc.upload(BoardId::new(), Bytes::from(data)).await;
+        assert!(matches!(err, Err(BannerError::TooLarge { .. })));
+    }
+
+    #[tokio::test]
+    async fn a_full_board_refuses_more_banners() {
+        let svc = BannerService::new(repo_with(MAX_BANNERS_PER_BOARD), MockMediaStorage::new());
+        let err = svc.upload(BoardId::new(), Bytes::from_static(PNG)).await;
+        assert!(matches!(err, Err(BannerError::TooMany { max: MAX_BANNERS_PER_BOARD })));
+    }
+
+    #[tokio::test]
+    async fn remove_deletes_the_image() {
+        let board_id = BoardId::new();
+        let stored = banner(board_id);
+        let key = stored.media_key.clone();
+        let mut repo = MockBannerRepository::new();
+        repo.expect_remove().returning(move |_, _| Ok(stored.clone()));
+        let mut media = MockMediaStorage::new();
+        media.expect_delete().withf(move |k| *k == key).times(1).returning(|_| Ok(()));
+        let svc = BannerService::new(repo, media);
+
+        svc.remove(board_id, BannerId::new()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn removing_an_unknown_banner_is_not_found() {
+        let mut repo = MockBannerRepository::new();
+        repo.expect_remove().returning(|_, id| Err(DomainError::not_found(format!("banner {id}"))));
+        let svc = BannerService::new(repo, MockMediaStorage::new());
+
+        let err = svc.remove(BoardId::new(), BannerId::new()).await;
+        assert!(matches!(err, Err(BannerError::NotFound { .. })));
+    }
+}
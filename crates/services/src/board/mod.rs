@@ -7,7 +7,11 @@
 //! - Retrieve and update `BoardConfig` (with cache invalidation notification)
 //! - List boards (paginated)
 //!
-//! This service is generic over `BoardRepository` and depends on no other port.
+//! Slugs are unique per site, so creation, slug lookup and listing take the
+//! `SiteId` the request was resolved to.
+//!
+//! This service is generic over `BoardRepository`. An `AuditRepository` can be
+//! attached with [`BoardService::with_audit`] to record config changes.
 //! All tests use `MockBoardRepository` from `mockall`.
 
 pub mod errors;
@@ -15,9 +19,13 @@ pub use errors::BoardError;
 
 use async_trait::async_trait;
 use chrono::Utc;
-use domains::models::{Board, BoardConfig, BoardId, Page, Paginated};
-use domains::ports::{BoardRepository, BoardVolunteerRepository};
-use tracing::{info, instrument};
+use std::sync::Arc;
+
+use domains::models::{
+    AuditAction, AuditEntry, Board, BoardConfig, BoardId, Page, Paginated, SiteId, UserId,
+};
+use domains::ports::{AuditRepository, BoardRepository, BoardVolunteerRepository};
+use tracing::{info, instrument, warn};
 use uuid::Uuid;
 
 use crate::common::utils::slug_validate;
@@ -29,16 +37,16 @@ use crate::common::utils::slug_validate;
 /// without handlers needing to know the generic parameter.
 #[async_trait]
 pub trait BoardRepo: Send + Sync + 'static {
-    /// Create a new board with the given slug, title, and rules.
+    /// Create a new board on `site` with the given slug, title, and rules.
     ///
     /// Returns `BoardError::InvalidSlug` if the slug is malformed,
     /// `BoardError::SlugConflict` if a board with that slug already exists.
-    async fn create_board(&self, slug: &str, title: &str, rules: &str) -> Result<Board, BoardError>;
+    async fn create_board(&self, site: SiteId, slug: &str, title: &str, rules: &str) -> Result<Board, BoardError>;
 
-    /// Fetch a board by its URL slug.
+    /// Fetch a board by its URL slug on `site`.
     ///
-    /// Returns `BoardError::NotFound` if no board with that slug exists.
-    async fn get_by_slug(&self, slug: &str) -> Result<Board, BoardError>;
+    /// Returns `BoardError::NotFound` if the site has no board with that slug.
+    async fn get_by_slug(&self, site: SiteId, slug: &str) -> Result<Board, BoardError>;
 
     /// Fetch a board by its UUID.
     ///
@@ -55,8 +63,8 @@ pub trait BoardRepo: Send + Sync + 'static {
     /// Returns `BoardError::NotFound` if the board does not exist.
     async fn delete_board(&self, id: BoardId) -> Result<(), BoardError>;
 
-    /// Return a paginated list of all boards, ordered by creation date ascending.
-    async fn list_boards(&self, page: Page) -> Result<Paginated<Board>, BoardError>;
+    /// Return a paginated list of the site's boards, ordered by creation date ascending.
+    async fn list_boards(&self, site: SiteId, page: Page) -> Result<Paginated<Board>, BoardError>;
 
     /// Return the `BoardConfig` for the given board.
     ///
@@ -65,8 +73,12 @@ pub trait BoardRepo: Send + Sync + 'static {
 
     /// Persist an updated `BoardConfig`, replacing all fields atomically.
     ///
+    /// `actor` is the staff member making the change, recorded in the mod log.
     /// Returns the saved config. Returns `BoardError::NotFound` if the board does not exist.
-    async fn update_config(&self, board_id: BoardId, config: BoardConfig) -> Result<BoardConfig, BoardError>;
+    async fn update_config(&self, board_id: BoardId, config: BoardConfig, actor: UserId) -> Result<BoardConfig, BoardError>;
+
+    /// Bytes of stored media counted against the board's `media_quota_mb`.
+    async fn media_usage(&self, board_id: BoardId) -> Result<u64, BoardError>;
 
 
     /// List volunteers for a board. Returns `(UserId, username, assigned_at)`.
@@ -92,15 +104,22 @@ pub trait BoardRepo: Send + Sync + 'static {
 /// without any concrete storage dependency.
 pub struct BoardService<BR: BoardRepository> {
     repo: BR,
+    audit: Option<Arc<dyn AuditRepository>>,
 }
 
 impl<BR: BoardRepository> BoardService<BR> {
     /// Construct a new `BoardService` with the given repository.
     pub fn new(repo: BR) -> Self {
-        Self { repo }
+        Self { repo, audit: None }
     }
 
-    /// Create a new board with the given slug and title.
+    /// Record config changes in the mod log.
+    pub fn with_audit(mut self, audit: Arc<dyn AuditRepository>) -> Self {
+        self.audit = Some(audit);
+        self
+    }
+
+    /// Create a new board on `site` with the given slug and title.
     ///
     /// - Validates the slug format (`^[a-z0-9_-]{1,16}$`)
     /// - Persists the board via the repository
@@ -108,9 +127,10 @@ impl<BR: BoardRepository> BoardService<BR> {
     ///
     /// Returns `BoardError::InvalidSlug` for an invalid slug.
     /// Returns `BoardError::Internal` if persistence fails.
-    #[instrument(skip(self), fields(slug = %slug, title = %title))]
+    #[instrument(skip(self), fields(site_id = %site, slug = %slug, title = %title))]
     pub async fn create_board(
         &self,
+        site: SiteId,
         slug: &str,
         title: &str,
         rules: &str,
@@ -121,6 +141,7 @@ impl<BR: BoardRepository> BoardService<BR> {
 
         let board = Board {
             id: BoardId(Uuid::new_v4()),
+            site_id: site,
             slug,
             title: title.to_owned(),
             rules: rules.to_owned(),
@@ -132,16 +153,16 @@ impl<BR: BoardRepository> BoardService<BR> {
         Ok(board)
     }
 
-    /// Retrieve a board by its URL slug.
+    /// Retrieve a board by its URL slug on `site`.
     ///
-    /// Returns `BoardError::NotFound` if no board with the given slug exists.
-    #[instrument(skip(self), fields(slug = %slug))]
-    pub async fn get_by_slug(&self, slug: &str) -> Result<Board, BoardError> {
+    /// Returns `BoardError::NotFound` if the site has no board with the given slug.
+    #[instrument(skip(self), fields(site_id = %site, slug = %slug))]
+    pub async fn get_by_slug(&self, site: SiteId, slug: &str) -> Result<Board, BoardError> {
         let slug = slug_validate(slug).map_err(|_| BoardError::InvalidSlug {
             slug: slug.to_owned(),
         })?;
         self.repo
-            .find_by_slug(&slug)
+            .find_by_slug(site, &slug)
             .await
             .map_err(|e| match e {
                 domains::errors::DomainError::NotFound { .. } => BoardError::NotFound {
@@ -201,9 +222,15 @@ impl<BR: BoardRepository> BoardService<BR> {
         Ok(())
     }
 
-    /// Paginated list of all boards.
+    /// Paginated list of the site's boards.
+    #[instrument(skip(self), fields(site_id = %site, page = page.0))]
+    pub async fn list_boards(&self, site: SiteId, page: Page) -> Result<Paginated<Board>, BoardError> {
+        Ok(self.repo.find_by_site(site, page).await?)
+    }
+
+    /// Paginated list of every board on every site, for maintenance jobs.
     #[instrument(skip(self), fields(page = page.0))]
-    pub async fn list_boards(&self, page: Page) -> Result<Paginated<Board>, BoardError> {
+    pub async fn list_all_boards(&self, page: Page) -> Result<Paginated<Board>, BoardError> {
         Ok(self.repo.find_all(page).await?)
     }
 
@@ -228,18 +255,46 @@ impl<BR: BoardRepository> BoardService<BR> {
     /// The API layer must invalidate the in-process `BoardConfigCache` entry for this
     /// board after calling this method.
     ///
+    /// When an audit repository is attached, the change is recorded as
+    /// `update_board_config` against the board. Audit failures are logged and
+    /// swallowed, as in `ModerationService`.
+    ///
     /// Returns `BoardError::NotFound` if the board does not exist.
-    #[instrument(skip(self, config), fields(board_id = %board_id))]
+    #[instrument(skip(self, config), fields(board_id = %board_id, actor = %actor))]
     pub async fn update_config(
         &self,
         board_id: BoardId,
         config: BoardConfig,
+        actor: UserId,
     ) -> Result<BoardConfig, BoardError> {
         self.repo.save_config(board_id, &config).await?;
         info!(board_id = %board_id, "board config updated");
+        if let Some(audit) = &self.audit {
+            let entry = AuditEntry {
+                id:            Uuid::new_v4(),
+                actor_id:      Some(actor),
+                actor_ip_hash: None,
+                action:        AuditAction::UpdateBoardConfig,
+                target_id:     Some(board_id.0),
+                target_type:   Some("board".to_owned()),
+                board_id:      Some(board_id),
+                reason:        None,
+                details:       None,
+                created_at:    Utc::now(),
+            };
+            if let Err(e) = audit.record(&entry).await {
+                warn!(error = %e, board_id = %board_id, "failed to write audit log entry");
+            }
+        }
         Ok(config)
     }
 
+    /// Bytes of stored media counted against the board's `media_quota_mb`.
+    #[instrument(skip(self), fields(board_id = %board_id))]
+    pub async fn media_usage(&self, board_id: BoardId) -> Result<u64, BoardError> {
+        Ok(self.repo.media_usage_bytes(board_id).await?)
+    }
+
 }
 
 impl<BR: BoardRepository + BoardVolunteerRepository> BoardService<BR> {
@@ -279,11 +334,11 @@ impl<BR: BoardRepository + BoardVolunteerRepository> BoardService<BR> {
 /// Handlers depend on the trait; the composition root injects the concrete service.
 #[async_trait]
 impl<BR: BoardRepository + BoardVolunteerRepository> BoardRepo for BoardService<BR> {
-    async fn create_board(&self, slug: &str, title: &str, rules: &str) -> Result<Board, BoardError> {
-        self.create_board(slug, title, rules).await
+    async fn create_board(&self, site: SiteId, slug: &str, title: &str, rules: &str) -> Result<Board, BoardError> {
+        self.create_board(site, slug, title, rules).await
     }
-    async fn get_by_slug(&self, slug: &str) -> Result<Board, BoardError> {
-        self.get_by_slug(slug).await
+    async fn get_by_slug(&self, site: SiteId, slug: &str) -> Result<Board, BoardError> {
+        self.get_by_slug(site, slug).await
     }
     async fn get_by_id(&self, id: BoardId) -> Result<Board, BoardError> {
         self.get_by_id(id).await
@@ -294,14 +349,17 @@ impl<BR: BoardRepository + BoardVolunteerRepository> BoardRepo for BoardService<
     async fn delete_board(&self, id: BoardId) -> Result<(), BoardError> {
         self.delete_board(id).await
     }
-    async fn list_boards(&self, page: Page) -> Result<Paginated<Board>, BoardError> {
-        self.list_boards(page).await
+    async fn list_boards(&self, site: SiteId, page: Page) -> Result<Paginated<Board>, BoardError> {
+        self.list_boards(site, page).await
     }
     async fn get_config(&self, board_id: BoardId) -> Result<BoardConfig, BoardError> {
         self.get_config(board_id).await
     }
-    async fn update_config(&self, board_id: BoardId, config: BoardConfig) -> Result<BoardConfig, BoardError> {
-        self.update_config(board_id, config).await
+    async fn update_config(&self, board_id: BoardId, config: BoardConfig, actor: UserId) -> Result<BoardConfig, BoardError> {
+        self.update_config(board_id, config, actor).await
+    }
+    async fn media_usage(&self, board_id: BoardId) -> Result<u64, BoardError> {
+        self.media_usage(board_id).await
     }
     async fn list_volunteers(&self, board_id: BoardId)
         -> Result<Vec<(domains::models::UserId, String, chrono::DateTime<chrono::Utc>)>, BoardError> {
@@ -328,6 +386,7 @@ mod tests {
     fn sample_board(slug: &str) -> Board {
         Board {
             id: BoardId(Uuid::new_v4()),
+            site_id: SiteId::DEFAULT,
             slug: Slug::new(slug).unwrap(),
             title: "Test Board".to_owned(),
             rules: "".to_owned(),
@@ -343,9 +402,11 @@ mod tests {
             .returning(|_| Ok(()));
 
         let svc = BoardService::new(mock);
-        let result = svc.create_board("tech", "Technology", "Be nice").await;
+        let site = SiteId::new();
+        let result = svc.create_board(site, "tech", "Technology", "Be nice").await;
         assert!(result.is_ok());
         let board = result.unwrap();
+        assert_eq!(board.site_id, site);
         assert_eq!(board.slug.as_str(), "tech");
         assert_eq!(board.title, "Technology");
     }
@@ -354,7 +415,7 @@ mod tests {
     async fn create_board_invalid_slug() {
         let mock = MockBoardRepository::new();
         let svc = BoardService::new(mock);
-        let result = svc.create_board("INVALID SLUG!", "Title", "").await;
+        let result = svc.create_board(SiteId::DEFAULT, "INVALID SLUG!", "Title", "").await;
         assert!(matches!(result, Err(BoardError::InvalidSlug { .. })));
     }
 
@@ -363,22 +424,24 @@ mod tests {
         let mut mock = MockBoardRepository::new();
         mock.expect_find_by_slug()
             .times(1)
-            .returning(|_| Err(domains::errors::DomainError::not_found("board")));
+            .returning(|_, _| Err(domains::errors::DomainError::not_found("board")));
 
         let svc = BoardService::new(mock);
-        let result = svc.get_by_slug("tech").await;
+        let result = svc.get_by_slug(SiteId::DEFAULT, "tech").await;
         assert!(matches!(result, Err(BoardError::NotFound { .. })));
     }
 
     #[tokio::test]
     async fn list_boards_returns_paginated() {
         let mut mock = MockBoardRepository::new();
-        mock.expect_find_all()
+        let site = SiteId::new();
+        mock.expect_find_by_site()
+            .withf(move |s, _| *s == site)
             .times(1)
-            .returning(|_| Ok(Paginated::new(vec![], 0, Page::new(1), 15)));
+            .returning(|_, _| Ok(Paginated::new(vec![], 0, Page::new(1), 15)));
 
         let svc = BoardService::new(mock);
-        let result = svc.list_boards(Page::new(1)).await;
+        let result = svc.list_boards(site, Page::new(1)).await;
         assert!(result.is_ok());
         assert_eq!(result.unwrap().total, 0);
     }
@@ -394,4 +457,25 @@ mod tests {
         let result = svc.delete_board(BoardId::new()).await;
         assert!(matches!(result, Err(BoardError::NotFound { .. })));
     }
+
+    #[tokio::test]
+    async fn update_config_records_the_change_against_the_board() {
+        let mut mock = MockBoardRepository::new();
+        mock.expect_save_config().times(1).returning(|_, _| Ok(()));
+        let board_id = BoardId(Uuid::new_v4());
+        let actor = UserId(Uuid::new_v4());
+        let mut audit = domains::ports::MockAuditRepository::new();
+        audit.expect_record()
+            .withf(move |e| {
+                e.action == AuditAction::UpdateBoardConfig
+                    && e.board_id == Some(board_id)
+                    && e.actor_id == Some(actor)
+            })
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let svc = BoardService::new(mock).with_audit(Arc::new(audit));
+        let result = svc.update_config(board_id, BoardConfig::default(), actor).await;
+        assert!(result.is_ok());
+    }
 }
@@ -1,5 +1,7 @@
 //! Shared utilities and error helpers used across service modules.
 
+pub mod crypt;
 pub mod errors;
+pub mod totp;
 pub mod tripcode;
 pub mod utils;
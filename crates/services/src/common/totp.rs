@@ -0,0 +1,194 @@
+//! Time-based one-time passwords (RFC 6238) for staff two-factor auth.
+//!
+//! Codes are six digits from HMAC-SHA1 over 30-second steps, the parameters
+//! every authenticator app assumes when an `otpauth://` URI names no others.
+//! A code is accepted for the current step and one step either side, to allow
+//! for clock drift.
+//!
+//! Recovery codes stand in for a lost authenticator. They are shown once at
+//! enrollment and stored as SHA-256 hex, like IP hashes, so a database leak
+//! does not reveal them.
+
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+
+/// Seconds per TOTP step.
+pub const STEP_SECS: i64 = 30;
+
+/// Steps either side of the current one whose codes are still accepted.
+const WINDOW: i64 = 1;
+
+/// Number of recovery codes issued at enrollment.
+pub const RECOVERY_CODES: usize = 10;
+
+/// RFC 4648 base32 alphabet, as used in `otpauth://` secrets.
+const BASE32: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// A new random 160-bit shared secret, base32-encoded without padding.
+pub fn generate_secret() -> String {
+    base32_encode(&rand::random::<[u8; 20]>())
+}
+
+/// The step containing Unix time `unix_secs`.
+pub fn step_at(unix_secs: i64) -> i64 {
+    unix_secs.div_euclid(STEP_SECS)
+}
+
+/// The six-digit code for `step`, or `None` if `secret` is not valid base32.
+pub fn code_at(secret: &str, step: i64) -> Option<String> {
+    let key = base32_decode(secret)?;
+    let mut mac = Hmac::<Sha1>::new_from_slice(&key).ok()?;
+    mac.update(&step.to_be_bytes());
+    let digest = mac.finalize().into_bytes();
+    // Dynamic truncation (RFC 4226 §5.3).
+    let offset = (digest[19] & 0x0f) as usize;
+    let bin = u32::from_be_bytes([digest[offset] & 0x7f, digest[offset + 1], digest[offset + 2], digest[offset + 3]]);
+    Some(format!("{:06}", bin % 1_000_000))
+}
+
+/// The step `code` is valid for at Unix time `unix_secs`, if any.
+///
+/// Steps at or before `last_step` are refused, so an accepted code cannot be
+/// replayed. Spaces in `code` are ignored, as authenticator apps group digits.
+pub fn verify(secret: &str, code: &str, unix_secs: i64, last_step: Option<i64>) -> Option<i64> {
+    let code: String = code.chars().filter(|c| !c.is_whitespace()).collect();
+    if code.len() != 6 || !code.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let now = step_at(unix_secs);
+    (now - WINDOW..=now + WINDOW)
+        .filter(|step| last_step.is_none_or(|last| *step > last))
+        .find(|step| code_at(secret, *step).is_some_and(|expected| constant_time_eq(&expected, &code)))
+}
+
+/// The `otpauth://` URI an authenticator app enrolls from (usually as a QR code).
+pub fn otpauth_uri(issuer: &str, account: &str, secret: &str) -> String {
+    let issuer = percent_encode(issuer);
+    format!(
+        "otpauth://totp/{issuer}:{}?secret={secret}&issuer={issuer}&algorithm=SHA1&digits=6&period={STEP_SECS}",
+        percent_encode(account),
+    )
+}
+
+/// Fresh recovery codes, `xxxxx-xxxxx` in lowercase base32.
+pub fn generate_recovery_codes() -> Vec<String> {
+    (0..RECOVERY_CODES)
+        .map(|_| {
+            let code = base32_encode(&rand::random::<[u8; 7]>()).to_ascii_lowercase();
+            format!("{}-{}", &code[..5], &code[5..10])
+        })
+        .collect()
+}
+
+/// The stored form of a recovery code. Case, spaces and dashes are ignored.
+pub fn hash_recovery_code(code: &str) -> String {
+    let normalized: String = code
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    hex::encode(Sha256::digest(normalized.as_bytes()))
+}
+
+fn base32_encode(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(5) * 8);
+    let (mut buffer, mut bits) = (0u32, 0u32);
+    for &byte in bytes {
+        buffer = (buffer << 8) | byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            out.push(BASE32[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        out.push(BASE32[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+    out
+}
+
+fn base32_decode(text: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(text.len() * 5 / 8);
+    let (mut buffer, mut bits) = (0u32, 0u32);
+    for c in text.chars().filter(|c| *c != '=' && !c.is_whitespace()) {
+        let value = BASE32.iter().position(|b| *b as char == c.to_ascii_uppercase())? as u32;
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+        }
+    }
+    (!out.is_empty()).then_some(out)
+}
+
+fn percent_encode(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            out.push(byte as char);
+        } else {
+            out.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    out
+}
+
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len() && a.bytes().zip(b.bytes()).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The RFC 6238 appendix B SHA-1 key, "12345678901234567890".
+    const RFC_SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+
+    #[test]
+    fn codes_match_the_rfc_test_vectors() {
+        // The RFC lists eight digits; the last six are the six-digit code.
+        assert_eq!(code_at(RFC_SECRET, step_at(59)).unwrap(), "287082");
+        assert_eq!(code_at(RFC_SECRET, step_at(1_111_111_109)).unwrap(), "081804");
+        assert_eq!(code_at(RFC_SECRET, step_at(2_000_000_000)).unwrap(), "279037");
+    }
+
+    #[test]
+    fn base32_round_trips() {
+        let bytes = b"12345678901234567890";
+        assert_eq!(base32_encode(bytes), RFC_SECRET);
+        assert_eq!(base32_decode(RFC_SECRET).unwrap(), bytes);
+        assert_eq!(base32_decode(&RFC_SECRET.to_lowercase()).unwrap(), bytes);
+        assert!(base32_decode("not base32!").is_none());
+    }
+
+    #[test]
+    fn verify_allows_one_step_of_drift_and_refuses_replays() {
+        let now = 1_111_111_109;
+        let previous = code_at(RFC_SECRET, step_at(now) - 1).unwrap();
+        assert_eq!(verify(RFC_SECRET, &previous, now, None), Some(step_at(now) - 1));
+        assert_eq!(verify(RFC_SECRET, "081 804", now, None), Some(step_at(now)));
+        assert_eq!(verify(RFC_SECRET, "081804", now, Some(step_at(now))), None);
+        let stale = code_at(RFC_SECRET, step_at(now) - 2).unwrap();
+        assert_eq!(verify(RFC_SECRET, &stale, now, None), None);
+        assert_eq!(verify(RFC_SECRET, "12345", now, None), None);
+    }
+
+    #[test]
+    fn recovery_codes_hash_loosely() {
+        let codes = generate_recovery_codes();
+        assert_eq!(codes.len(), RECOVERY_CODES);
+        assert!(codes.iter().all(|c| c.len() == 11 && c.as_bytes()[5] == b'-'));
+        assert_eq!(hash_recovery_code(&codes[0]), hash_recovery_code(&codes[0].to_uppercase().replace('-', " ")));
+    }
+
+    #[test]
+    fn otpauth_uri_encodes_the_account() {
+        let uri = otpauth_uri("rusty-board", "alice mod", "ABC");
+        assert_eq!(
+            uri,
+            "otpauth://totp/rusty-board:alice%20mod?secret=ABC&issuer=rusty-board&algorithm=SHA1&digits=6&period=30"
+        );
+    }
+}
@@ -8,6 +8,7 @@
 //! | Syntax         | Level       | Algorithm                          | Display      |
 //! |----------------|-------------|-------------------------------------|--------------|
 //! | `Name#pass`    | Insecure    | SHA-256(password)[0..5] as hex      | `!{10hex}`   |
+//! | `Name#pass`    | Legacy      | Shift-JIS + DES `crypt(3)`          | `!{10chars}` |
 //! | `Name##pass`   | Secure      | SHA-256(pepper ‖ password)[0..5]    | `!!{10hex}`  |
 //! | `Name###pass`  | Super       | (stub — see below)                  | `!!!{10hex}` |
 //!
@@ -15,6 +16,11 @@
 //! Anyone who knows the password can verify the identity; rainbow tables exist,
 //! so these are for vanity use only.
 //!
+//! **Legacy tripcodes** replace the insecure level on boards whose
+//! `tripcode_algorithm` is `Crypt`. They use the classic imageboard algorithm, so a
+//! password produces the same trip here as on other sites and old identities carry
+//! over. Only the single-`#` level is affected.
+//!
 //! **Secure tripcodes** use a server-side pepper. The same password + the same
 //! pepper always produces the same trip. Without the pepper an attacker cannot
 //! precompute the hash, so the identity is cryptographically bound to this server.
@@ -35,20 +41,22 @@
 //!
 //! Capcodes allow authenticated staff to display their role next to their post.
 //!
-//! **Input format (name field):** `{optional name} ### {ROLE}`
-//! (three `#`, a space, then a role keyword — the space distinguishes it from `###password`)
+//! **Input format (name field):** `{optional name} ## {ROLE}` or `{optional name} ### {ROLE}`
+//! (two or three `#`, a space, then a role keyword — the space distinguishes it
+//! from `##password` and `###password`)
 //!
 //! **Role keywords** (case-insensitive):
-//! `Admin`, `Janitor`, `Owner`, `Volunteer`, `Developer`
+//! `Admin`, `Mod`, `Janitor`, `Owner`, `Volunteer`, `Developer`
 //!
-//! **Display format:** the `tripcode` field is set to `!!!! {RoleDisplay}` and the
-//! template renders it with the `capcode` CSS class for emphasis. The name field is
-//! set to the text before `###`.
+//! **Display format:** `ParsedName::capcode` is set to the role display string and
+//! stored in the post's own `capcode` field, never in `tripcode`, so no tripcode can
+//! imitate it. The template renders it with the `capcode` CSS class for emphasis.
+//! The name field is set to the text before the `#`s.
 //!
 //! Capcodes are only granted when the poster is authenticated with a matching role.
 //! A `User`-role account attempting a capcode receives `CapcodePermissionDenied`.
 
-use domains::models::Role;
+use domains::models::{Role, TripcodeAlgorithm};
 use sha2::{Digest, Sha256};
 
 // ── Public types ─────────────────────────────────────────────────────────────
@@ -67,8 +75,20 @@ pub struct ParsedName {
     /// - `"!a1b2c3d4e5"` — insecure
     /// - `"!!a1b2c3d4e5"` — secure
     /// - `"!!!{10hex}"` — super tripcode: HMAC-SHA256(key=pepper, msg="###"||password)[0..5]
-    /// - `"!!!! Admin"` — capcode
     pub tripcode: Option<String>,
+
+    /// Verified capcode role display string, e.g. `"Admin"` or `"Board Owner"`.
+    /// `None` unless the poster claimed a capcode their role allows.
+    pub capcode: Option<String>,
+}
+
+/// Settings that decide how tripcodes are computed.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TripcodeOptions<'a> {
+    /// Server-side secret for `##` and `###` trips; may be empty.
+    pub pepper: &'a str,
+    /// Algorithm for single-`#` trips, usually the board's `tripcode_algorithm`.
+    pub algorithm: TripcodeAlgorithm,
 }
 
 /// Errors that can occur during name field parsing.
@@ -84,7 +104,7 @@ pub enum NameParseError {
     },
 
     /// The capcode role keyword is not recognised.
-    #[error("unknown capcode role '{role}'; valid roles: Admin, Janitor, Owner, Volunteer, Developer")]
+    #[error("unknown capcode role '{role}'; valid roles: Admin, Mod, Janitor, Owner, Volunteer, Developer")]
     UnknownCapcodeRole {
         /// The unrecognised keyword supplied by the poster.
         role: String,
@@ -109,24 +129,34 @@ pub fn parse_name_field(
     poster_role: Option<&Role>,
     pepper:      &str,
 ) -> Result<ParsedName, NameParseError> {
+    parse_name_field_with(raw, poster_role, &TripcodeOptions { pepper, ..Default::default() })
+}
+
+/// Like [`parse_name_field`], with the single-`#` algorithm taken from `options`.
+pub fn parse_name_field_with(
+    raw:         &str,
+    poster_role: Option<&Role>,
+    options:     &TripcodeOptions<'_>,
+) -> Result<ParsedName, NameParseError> {
+    let pepper = options.pepper;
     // Fast path: empty name
     let raw = raw.trim();
     if raw.is_empty() {
-        return Ok(ParsedName { name: None, tripcode: None });
+        return Ok(ParsedName { name: None, tripcode: None, capcode: None });
     }
 
     // Find the first `#` — splits name from specifier
     let Some(hash_pos) = raw.find('#') else {
         // No tripcode specifier
         let name = clean_name(raw);
-        return Ok(ParsedName { name, tripcode: None });
+        return Ok(ParsedName { name, tripcode: None, capcode: None });
     };
 
     let display_name = raw[..hash_pos].trim();
     let specifier    = &raw[hash_pos..]; // starts with one or more `#`
 
-    // ── Capcode detection (`### ` followed by role keyword) ──────────────────
-    if let Some(role_part) = specifier.strip_prefix("### ") {
+    // ── Capcode detection (`## ` or `### ` followed by role keyword) ─────────
+    if let Some(role_part) = specifier.strip_prefix("### ").or_else(|| specifier.strip_prefix("## ")) {
         let role_kw = role_part.trim();
         let (capcode_requires, capcode_display) = parse_capcode_role(role_kw)?;
 
@@ -143,9 +173,8 @@ pub fn parse_name_field(
             });
         }
 
-        let name     = clean_name(display_name);
-        let tripcode = Some(format!("!!!! {capcode_display}"));
-        return Ok(ParsedName { name, tripcode });
+        let name = clean_name(display_name);
+        return Ok(ParsedName { name, tripcode: None, capcode: Some(capcode_display) });
     }
 
     // ── Super tripcode (`###password`, no space) ─────────────────────────────
@@ -156,7 +185,7 @@ pub fn parse_name_field(
         } else {
             Some(super_trip(password, pepper))
         };
-        return Ok(ParsedName { name, tripcode });
+        return Ok(ParsedName { name, tripcode, capcode: None });
     }
 
     // ── Secure tripcode (`##password`) ───────────────────────────────────────
@@ -167,7 +196,7 @@ pub fn parse_name_field(
         } else {
             Some(secure_trip(password, pepper))
         };
-        return Ok(ParsedName { name, tripcode });
+        return Ok(ParsedName { name, tripcode, capcode: None });
     }
 
     // ── Insecure tripcode (`#password`) ──────────────────────────────────────
@@ -176,18 +205,26 @@ pub fn parse_name_field(
         let tripcode = if password.is_empty() {
             None
         } else {
-            Some(insecure_trip(password))
+            Some(generate_tripcode(password, options))
         };
-        return Ok(ParsedName { name, tripcode });
+        return Ok(ParsedName { name, tripcode, capcode: None });
     }
 
     // Unreachable — we checked `#` at hash_pos
     let name = clean_name(raw);
-    Ok(ParsedName { name, tripcode: None })
+    Ok(ParsedName { name, tripcode: None, capcode: None })
 }
 
 // ── Tripcode algorithms ───────────────────────────────────────────────────────
 
+/// Compute the single-`#` tripcode for `password`, including its `!` prefix.
+pub fn generate_tripcode(password: &str, options: &TripcodeOptions<'_>) -> String {
+    match options.algorithm {
+        TripcodeAlgorithm::Sha256 => insecure_trip(password),
+        TripcodeAlgorithm::Crypt => legacy_trip(password),
+    }
+}
+
 /// Insecure (vanity) tripcode.
 ///
 /// `SHA-256(password)` → first 5 bytes → 10 hex chars.
@@ -201,6 +238,35 @@ fn insecure_trip(password: &str) -> String {
     format!("!{}", &hex::encode(&digest[..5]))
 }
 
+/// Legacy (classic imageboard) tripcode.
+///
+/// The password is HTML-escaped and encoded as Shift-JIS; the salt is its second
+/// and third bytes (padded with `H.`), folded into the `crypt(3)` alphabet. The
+/// trip is the last 10 characters of `crypt(password, salt)`.
+fn legacy_trip(password: &str) -> String {
+    let escaped = password
+        .replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;");
+    let (key, _, _) = encoding_rs::SHIFT_JIS.encode(&escaped);
+
+    let mut salt = [b'H', b'.'];
+    for (i, slot) in salt.iter_mut().enumerate() {
+        let c = key.get(i + 1).copied().unwrap_or(b"H."[i]);
+        *slot = match c {
+            b':'..=b'@' => c - b':' + b'A',
+            b'['..=b'`' => c - b'[' + b'a',
+            b'.'..=b'z' => c,
+            _ => b'.',
+        };
+    }
+
+    let hash = crate::common::crypt::des_crypt(&key, salt);
+    format!("!{}", &hash[hash.len() - 10..])
+}
+
 /// Secure tripcode.
 ///
 /// `SHA-256(pepper || "::" || password)` → first 5 bytes → 10 hex chars.
@@ -252,7 +318,8 @@ fn super_trip(password: &str, pepper: &str) -> String {
 fn parse_capcode_role(keyword: &str) -> Result<(Role, String), NameParseError> {
     match keyword.to_ascii_lowercase().as_str() {
         "admin" | "administrator" => Ok((Role::Admin, "Admin".to_owned())),
-        "janitor" | "mod" | "moderator" => Ok((Role::Janitor, "Janitor".to_owned())),
+        "janitor" => Ok((Role::Janitor, "Janitor".to_owned())),
+        "mod" | "moderator" => Ok((Role::Janitor, "Mod".to_owned())),
         "owner" | "boardowner" | "board owner" => Ok((Role::BoardOwner, "Board Owner".to_owned())),
         "volunteer" | "boardvolunteer" | "board volunteer" => {
             Ok((Role::BoardVolunteer, "Volunteer".to_owned()))
@@ -273,21 +340,6 @@ fn clean_name(s: &str) -> Option<String> {
 
 // ── Display helpers (used by template layer) ──────────────────────────────────
 
-/// Return `true` if `tripcode` is a capcode (starts with `"!!!! "`).
-///
-/// Used by template logic to decide whether to apply capcode CSS.
-pub fn is_capcode(tripcode: &str) -> bool {
-    tripcode.starts_with("!!!! ")
-}
-
-/// Extract the role string from a capcode tripcode value.
-///
-/// Returns `None` if the value is not a capcode. The returned string is
-/// the role display name, e.g. `"Admin"` or `"Board Owner"`.
-pub fn capcode_role_str(tripcode: &str) -> Option<&str> {
-    tripcode.strip_prefix("!!!! ")
-}
-
 // ── CSS class helper ─────────────────────────────────────────────────────────
 
 /// Return a CSS-safe class suffix for a capcode role string.
@@ -328,6 +380,36 @@ mod tests {
         assert_ne!(a, b);
     }
 
+    // ── Legacy tripcodes ──────────────────────────────────────────────────────
+
+    fn crypt_trip(password: &str) -> String {
+        let options = TripcodeOptions { pepper: "", algorithm: TripcodeAlgorithm::Crypt };
+        generate_tripcode(password, &options)
+    }
+
+    #[test]
+    fn legacy_trip_matches_known_trips() {
+        assert_eq!(crypt_trip("faggot"), "!Ep8pui8Vw2");
+        assert_eq!(crypt_trip("a"), "!ZnBI2EKkq.");
+        assert_eq!(crypt_trip("password"), "!ozOtJW9BFA");
+        assert_eq!(crypt_trip("12345678901"), "!WBRXcNtpf.");
+    }
+
+    #[test]
+    fn legacy_trip_escapes_html_and_encodes_shift_jis() {
+        assert_eq!(crypt_trip("<>&\"'"), "!Gw/f5wZwNg");
+        assert_eq!(crypt_trip("東方"), "!boyUn.XHfE");
+    }
+
+    #[test]
+    fn crypt_option_only_changes_single_hash_trips() {
+        let options = TripcodeOptions { pepper: "pepper", algorithm: TripcodeAlgorithm::Crypt };
+        let r = parse_name_field_with("Name#faggot", None, &options).unwrap();
+        assert_eq!(r.tripcode.as_deref(), Some("!Ep8pui8Vw2"));
+        let secure = parse_name_field_with("N##pass", None, &options).unwrap().tripcode;
+        assert_eq!(secure, parse_name_field("N##pass", None, "pepper").unwrap().tripcode);
+    }
+
     // ── Secure tripcodes ──────────────────────────────────────────────────────
 
     #[test]
@@ -374,7 +456,29 @@ mod tests {
     #[test]
     fn capcode_admin_granted_to_admin() {
         let r = parse_name_field("Admin### Admin", Some(&Role::Admin), "").unwrap();
-        assert_eq!(r.tripcode.as_deref(), Some("!!!! Admin"));
+        assert_eq!(r.capcode.as_deref(), Some("Admin"));
+        assert!(r.tripcode.is_none());
+    }
+
+    #[test]
+    fn two_hash_capcode_granted_to_janitor() {
+        let r = parse_name_field("Staff ## Mod", Some(&Role::Janitor), "").unwrap();
+        assert_eq!(r.name.as_deref(), Some("Staff"));
+        assert_eq!(r.capcode.as_deref(), Some("Mod"));
+        assert!(r.tripcode.is_none());
+    }
+
+    #[test]
+    fn two_hash_capcode_denied_to_user() {
+        let err = parse_name_field("## Admin", Some(&Role::User), "").unwrap_err();
+        assert!(matches!(err, NameParseError::CapcodePermissionDenied { .. }));
+    }
+
+    #[test]
+    fn secure_trip_without_space_is_not_a_capcode() {
+        let r = parse_name_field("Name##Admin", None, "pepper").unwrap();
+        assert!(r.capcode.is_none());
+        assert!(r.tripcode.as_deref().is_some_and(|t| t.starts_with("!!")));
     }
 
     #[test]
@@ -393,7 +497,7 @@ mod tests {
     fn capcode_volunteer_granted_to_boardowner() {
         // Admin can claim any capcode
         let r = parse_name_field("Mod### Volunteer", Some(&Role::Admin), "").unwrap();
-        assert_eq!(r.tripcode.as_deref(), Some("!!!! Volunteer"));
+        assert_eq!(r.capcode.as_deref(), Some("Volunteer"));
     }
 
     #[test]
@@ -426,14 +530,7 @@ mod tests {
         assert!(r.tripcode.is_some());
     }
 
-    // ── is_capcode / capcode_role_str helpers ─────────────────────────────────
-
-    #[test]
-    fn is_capcode_helper() {
-        assert!(is_capcode("!!!! Admin"));
-        assert!(!is_capcode("!!a1b2c3d4e5"));
-        assert!(!is_capcode("!a1b2c3d4e5"));
-    }
+    // ── capcode_css_class helper ──────────────────────────────────────────────
 
     #[test]
     fn capcode_css_class_helper() {
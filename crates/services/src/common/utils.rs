@@ -5,7 +5,7 @@
 //! hashing, regex matching, and score computation live — keeping individual
 //! service modules focused on business logic.
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Days, NaiveDate, Utc};
 use domains::models::{ContentHash, IpHash, Page, Paginated, Slug};
 use domains::errors::ValidationError;
 use sha2::{Digest, Sha256};
@@ -54,6 +54,18 @@ pub fn hash_ip(raw_ip: &str, daily_salt: &str) -> IpHash {
     IpHash::new(hex::encode(result))
 }
 
+/// Every hash `raw_ip` had under the daily salts of the `days` days up to
+/// and including `today`, newest first.
+///
+/// A post stores the hash made with the salt of its own day (the UTC date as
+/// `YYYY-MM-DD`), so finding the posts of a raw IP takes one hash per day.
+pub fn ip_hashes_for_days(raw_ip: &str, today: NaiveDate, days: u32) -> Vec<IpHash> {
+    (0..days)
+        .filter_map(|back| today.checked_sub_days(Days::new(back.into())))
+        .map(|day| hash_ip(raw_ip, &day.format("%Y-%m-%d").to_string()))
+        .collect()
+}
+
 /// Compute the SHA-256 content hash of raw bytes.
 ///
 /// Used for duplicate post detection and deduplication.
@@ -63,6 +75,33 @@ pub fn hash_content(data: &[u8]) -> ContentHash {
     ContentHash::new(hex::encode(hasher.finalize()))
 }
 
+/// Identify an upload's MIME type from its leading magic bytes.
+///
+/// Returns `None` when the content matches no known signature. The result is
+/// authoritative: the multipart `Content-Type` header is client-controlled and
+/// is only ever compared against it, never trusted on its own.
+pub fn sniff_mime(data: &[u8]) -> Option<&'static str> {
+    infer::get(data).map(|kind| match kind.mime_type() {
+        "audio/x-flac" => "audio/flac",
+        other          => other,
+    })
+}
+
+/// Canonicalise a client-declared MIME type for comparison with `sniff_mime`.
+///
+/// Lowercases, drops parameters, and folds common legacy aliases
+/// (`image/jpg`, `image/pjpeg`, `audio/mp3`, `audio/x-flac`) onto their registered type.
+pub fn canonical_mime(declared: &str) -> String {
+    let essence = declared.split(';').next().unwrap_or("").trim().to_ascii_lowercase();
+    match essence.as_str() {
+        "image/jpg" | "image/pjpeg" => "image/jpeg".to_owned(),
+        "image/x-png"               => "image/png".to_owned(),
+        "audio/mp3"                 => "audio/mpeg".to_owned(),
+        "audio/x-flac"              => "audio/flac".to_owned(),
+        _                           => essence,
+    }
+}
+
 /// Parse `>>postid` quote references from a post body.
 ///
 /// Returns the quoted post ID strings found in the body.
@@ -85,6 +124,34 @@ pub fn parse_quotes(body: &str) -> Vec<String> {
     quotes
 }
 
+/// Turn each `>>N` in `body` whose `N` satisfies `moved` into the
+/// cross-thread form `>>>/board_slug/N`, which resolves wherever post N is.
+///
+/// Used when posts change threads, since a bare `>>N` only links within the
+/// thread it is in. Returns `None` when nothing was rewritten.
+pub fn rewrite_quotes(body: &str, board_slug: &str, mut moved: impl FnMut(u64) -> bool) -> Option<String> {
+    let mut out = String::with_capacity(body.len());
+    let mut rest = body;
+    let mut changed = false;
+    while let Some(at) = rest.find(">>") {
+        let (before, quote) = rest.split_at(at);
+        out.push_str(before);
+        let digits = quote[2..].bytes().take_while(u8::is_ascii_digit).count();
+        // `>>>/slug/N` is already cross-thread; `>>N` after another `>` is not a quote.
+        let bare = !out.ends_with('>');
+        match quote[2..2 + digits].parse::<u64>() {
+            Ok(number) if bare && moved(number) => {
+                out.push_str(&format!(">>>/{board_slug}/{number}"));
+                changed = true;
+            }
+            _ => out.push_str(&quote[..2 + digits]),
+        }
+        rest = &quote[2 + digits..];
+    }
+    out.push_str(rest);
+    changed.then_some(out)
+}
+
 /// Compute a spam probability score for a post body.
 ///
 /// Returns a value in `[0.0, 1.0]` where 1.0 is maximum spam likelihood.
@@ -199,6 +266,17 @@ mod tests {
         assert_ne!(h1, h2);
     }
 
+    #[test]
+    fn ip_hashes_for_days_cover_each_daily_salt() {
+        let today = NaiveDate::from_ymd_opt(2026, 3, 1).unwrap();
+        let hashes = ip_hashes_for_days("192.168.1.1", today, 3);
+        assert_eq!(hashes, [
+            hash_ip("192.168.1.1", "2026-03-01"),
+            hash_ip("192.168.1.1", "2026-02-28"),
+            hash_ip("192.168.1.1", "2026-02-27"),
+        ]);
+    }
+
     #[test]
     fn parse_quotes_finds_references() {
         let body = "Hello\n>>abc123\nsome text\n>>def456";
@@ -211,6 +289,16 @@ mod tests {
         assert!(parse_quotes("").is_empty());
     }
 
+    #[test]
+    fn rewrite_quotes_makes_moved_quotes_cross_thread() {
+        let body = ">>12 and >>13\n>>>/b/12 >>>12 >>120";
+        assert_eq!(
+            rewrite_quotes(body, "b", |n| n == 12).as_deref(),
+            Some(">>>/b/12 and >>13\n>>>/b/12 >>>12 >>120"),
+        );
+        assert_eq!(rewrite_quotes(">>13", "b", |n| n == 12), None);
+    }
+
     #[test]
     fn score_spam_low_for_normal_post() {
         let score = score_spam("This is a normal post about things I like.", &[]);
@@ -245,6 +333,24 @@ mod tests {
         assert_eq!(without, also_without);
     }
 
+    #[test]
+    fn sniff_mime_uses_magic_bytes() {
+        assert_eq!(sniff_mime(&[0xFF, 0xD8, 0xFF, 0xE0]), Some("image/jpeg"));
+        assert_eq!(sniff_mime(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"), Some("image/png"));
+        assert_eq!(sniff_mime(b"GIF89a"), Some("image/gif"));
+        assert_eq!(sniff_mime(b"fLaC\0\0\0\x22"), Some("audio/flac"));
+        assert_eq!(sniff_mime(b"plain text"), None);
+    }
+
+    #[test]
+    fn canonical_mime_folds_aliases_and_parameters() {
+        assert_eq!(canonical_mime("image/JPG"), "image/jpeg");
+        assert_eq!(canonical_mime("image/png; charset=binary"), "image/png");
+        assert_eq!(canonical_mime("video/webm"), "video/webm");
+        assert_eq!(canonical_mime("audio/x-flac"), "audio/flac");
+        assert_eq!(canonical_mime("audio/mp3"), "audio/mpeg");
+    }
+
     #[test]
     fn slug_validate_accepts_valid() {
         assert!(slug_validate("tech").is_ok());
@@ -9,15 +9,41 @@
 //! - `thread/` — thread creation, sticky/close, prune trigger
 //! - `post/` — ban check, rate limit, spam heuristics, media dispatch, insert
 //! - `moderation/` — ban, flag, delete, audit log
+//! - `poll/` — thread polls: draft validation, one vote per poster ID, results
+//! - `reaction/` — fixed-set post reactions, one per poster ID, rate limited
+//! - `watch/` — anonymous thread watch lists with unread counts
+//! - `banner/` — rotating board banners: upload checks, storage, random pick
+//! - `announcement/` — the blotter: short dated site-wide and per-board announcements
+//! - `page/` — admin-edited static pages (rules, FAQ) for the site or one board
+//! - `stats/` — hourly and daily board activity: rollups, board reports, admin overview
+//! - `media_gc/` — sweep stored media no attachment references
+//! - `thumbnails/` — regenerate stored thumbnails with current settings
+//! - `storage_audit/` — re-hash stored media and find missing files
+//! - `report_alerts/` — send new reports, digests and threshold alerts to moderators
+//! - `site/` — resolve hosts to sites; add, list and remove sites
 //! - `user/` — create user, login, deactivate, register
+//! - `api_key/` — API keys for bots: issue, revoke, authorize with scopes and rate limits
 //! - `staff_request/` — submit, list, approve, deny escalation requests
 //! - `common/` — shared utilities (slug, pagination, ip_hash, spam scoring)
 
+pub mod announcement;
+pub mod api_key;
+pub mod banner;
 pub mod board;
 pub mod common;
+pub mod media_gc;
 pub mod moderation;
+pub mod page;
+pub mod poll;
 pub mod post;
+pub mod reaction;
+pub mod report_alerts;
+pub mod site;
 pub mod staff_message;
 pub mod staff_request;
+pub mod stats;
+pub mod storage_audit;
 pub mod thread;
+pub mod thumbnails;
 pub mod user;
+pub mod watch;
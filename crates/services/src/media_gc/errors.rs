@@ -0,0 +1,12 @@
+//! Error type for `MediaGcService` operations.
+
+use domains::errors::DomainError;
+use thiserror::Error;
+
+/// Errors that can occur while sweeping orphaned media.
+#[derive(Debug, Error)]
+pub enum MediaGcError {
+    /// Listing storage or loading the referenced keys failed; nothing was deleted.
+    #[error("internal error: {0}")]
+    Internal(#[from] DomainError),
+}
@@ -0,0 +1,209 @@
+//! `MediaGcService` — removes stored media that no post references.
+//!
+//! Uploads are content-addressed and deduplicated, so deleting a post only
+//! removes its attachment rows; the files stay in storage because another
+//! post may share them. The sweep lists media storage, subtracts every
+//! `media_key`/`thumbnail_key` still referenced by an attachment, and deletes
+//! the rest — originals and thumbnails alike. Static pages of archived
+//! threads (`MediaKey::ARCHIVE_PREFIX`) are not uploads and are never swept;
+//! neither are board banners (`MediaKey::BANNER_PREFIX`), which only the
+//! `board_banners` table references.
+//!
+//! # Grace period
+//! A post stores its files before its attachment rows are written, and
+//! background thumbnails land after. Objects modified within the grace period
+//! are never deleted, so an in-flight post cannot lose its media.
+//!
+//! Run periodically by the composition root (`MEDIA_GC_INTERVAL_SECS`) and on
+//! demand with `rusty-board gc-media`.
+
+pub mod errors;
+pub use errors::MediaGcError;
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use domains::ports::{MediaStorage, PostRepository};
+use tracing::{info, instrument, warn};
+
+/// Outcome of one sweep.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GcReport {
+    /// Objects found in storage.
+    pub scanned:  usize,
+    /// Unreferenced objects older than the grace period.
+    pub orphaned: usize,
+    /// Orphans actually removed (0 on a dry run).
+    pub deleted:  usize,
+}
+
+/// Service for sweeping orphaned media.
+///
+/// Generic over `PR: PostRepository` (attachment references) and
+/// `MS: MediaStorage` (the objects themselves).
+pub struct MediaGcService<PR, MS>
+where
+    PR: PostRepository,
+    MS: MediaStorage,
+{
+    post_repo:     PR,
+    media_storage: MS,
+}
+
+impl<PR, MS> MediaGcService<PR, MS>
+where
+    PR: PostRepository,
+    MS: MediaStorage,
+{
+    /// Construct a `MediaGcService`.
+    pub fn new(post_repo: PR, media_storage: MS) -> Self {
+        Self { post_repo, media_storage }
+    }
+
+    /// Delete every stored object that no attachment references and that is
+    /// older than `grace`. With `dry_run`, only counts them.
+    ///
+    /// Storage is listed *before* references are loaded, so an object that
+    /// gains a reference mid-sweep is seen as referenced. Each delete is
+    /// confirmed with `exists`; a failed or unconfirmed delete is logged and
+    /// not counted, and the next sweep retries it.
+    #[instrument(skip(self))]
+    pub async fn sweep(&self, grace: Duration, dry_run: bool) -> Result<GcReport, MediaGcError> {
+        let objects = self.media_storage.list().await?;
+        let referenced = self.post_repo.find_referenced_media_keys().await?;
+        let cutoff = chrono::Duration::from_std(grace)
+            .ok()
+            .and_then(|grace| Utc::now().checked_sub_signed(grace))
+            .unwrap_or(DateTime::<Utc>::MIN_UTC);
+
+        let mut report = GcReport { scanned: objects.len(), ..GcReport::default() };
+        for object in objects {
+            if object.key.is_archive() || object.key.is_banner() || referenced.contains(&object.key) || object.modified > cutoff {
+                continue;
+            }
+            report.orphaned += 1;
+            if dry_run {
+                info!(key = %object.key, "orphaned media (dry run)");
+                continue;
+            }
+            if let Err(e) = self.media_storage.delete(&object.key).await {
+                warn!(key = %object.key, error = %e, "failed to delete orphaned media");
+                continue;
+            }
+            match self.media_storage.exists(&object.key).await {
+                Ok(false) => report.deleted += 1,
+                Ok(true) => warn!(key = %object.key, "orphaned media still present after delete"),
+                Err(e) => warn!(key = %object.key, error = %e, "could not confirm orphaned media delete"),
+            }
+        }
+
+        info!(
+            scanned = report.scanned,
+            orphaned = report.orphaned,
+            deleted = report.deleted,
+            dry_run,
+            "media sweep complete"
+        );
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use domains::models::MediaKey;
+    use domains::ports::{MockMediaStorage, MockPostRepository, StoredMedia};
+
+    const HOUR: Duration = Duration::from_secs(3_600);
+
+    fn stored(key: &str, age_hours: i64) -> StoredMedia {
+        StoredMedia { key: MediaKey::new(key), modified: Utc::now() - chrono::Duration::hours(age_hours) }
+    }
+
+    fn storage_with(objects: Vec<StoredMedia>) -> MockMediaStorage {
+        let mut storage = MockMediaStorage::new();
+        storage.expect_list().returning(move || Ok(objects.clone()));
+        storage.expect_exists().returning(|_| Ok(false));
+        storage
+    }
+
+    fn repo_referencing(keys: &'static [&'static str]) -> MockPostRepository {
+        let mut repo = MockPostRepository::new();
+        repo.expect_find_referenced_media_keys()
+            .returning(move || Ok(keys.iter().map(|k| MediaKey::new(*k)).collect()));
+        repo
+    }
+
+    #[tokio::test]
+    async fn deletes_only_old_unreferenced_objects() {
+        let mut storage = storage_with(vec![
+            stored("kept.jpg", 48),
+            stored("kept_thumb.png", 48),
+            stored("orphan.jpg", 48),
+            stored("fresh.jpg", 0),
+        ]);
+        storage
+            .expect_delete()
+            .withf(|key| key.0 == "orphan.jpg")
+            .times(1)
+            .returning(|_| Ok(()));
+        let svc = MediaGcService::new(repo_referencing(&["kept.jpg", "kept_thumb.png"]), storage);
+
+        let report = svc.sweep(HOUR, false).await.unwrap();
+        assert_eq!(report, GcReport { scanned: 4, orphaned: 1, deleted: 1 });
+    }
+
+    #[tokio::test]
+    async fn archived_thread_pages_are_kept() {
+        let mut storage = storage_with(vec![stored("archive/b/t.html", 48), stored("archive/b/t.json", 48)]);
+        storage.expect_delete().never();
+        let svc = MediaGcService::new(repo_referencing(&[]), storage);
+
+        let report = svc.sweep(HOUR, false).await.unwrap();
+        assert_eq!(report, GcReport { scanned: 2, orphaned: 0, deleted: 0 });
+    }
+
+    #[tokio::test]
+    async fn board_banners_are_kept() {
+        let mut storage = storage_with(vec![stored("banners/b/1.png", 48)]);
+        storage.expect_delete().never();
+        let svc = MediaGcService::new(repo_referencing(&[]), storage);
+
+        let report = svc.sweep(HOUR, false).await.unwrap();
+        assert_eq!(report, GcReport { scanned: 1, orphaned: 0, deleted: 0 });
+    }
+
+    #[tokio::test]
+    async fn dry_run_deletes_nothing() {
+        let mut storage = storage_with(vec![stored("orphan.jpg", 48)]);
+        storage.expect_delete().never();
+        let svc = MediaGcService::new(repo_referencing(&[]), storage);
+
+        let report = svc.sweep(HOUR, true).await.unwrap();
+        assert_eq!(report, GcReport { scanned: 1, orphaned: 1, deleted: 0 });
+    }
+
+    #[tokio::test]
+    async fn failed_delete_is_skipped() {
+        let mut storage = storage_with(vec![stored("a.jpg", 48), stored("b.jpg", 48)]);
+        storage.expect_delete().withf(|key| key.0 == "a.jpg")
+            .returning(|_| Err(domains::errors::DomainError::internal("boom")));
+        storage.expect_delete().withf(|key| key.0 == "b.jpg").returning(|_| Ok(()));
+        let svc = MediaGcService::new(repo_referencing(&[]), storage);
+
+        let report = svc.sweep(HOUR, false).await.unwrap();
+        assert_eq!(report, GcReport { scanned: 2, orphaned: 2, deleted: 1 });
+    }
+
+    #[tokio::test]
+    async fn unconfirmed_delete_is_not_counted() {
+        let mut storage = MockMediaStorage::new();
+        storage.expect_list().returning(|| Ok(vec![stored("stuck.jpg", 48)]));
+        storage.expect_delete().returning(|_| Ok(()));
+        storage.expect_exists().returning(|_| Ok(true));
+        let svc = MediaGcService::new(repo_referencing(&[]), storage);
+
+        let report = svc.sweep(HOUR, false).await.unwrap();
+        assert_eq!(report, GcReport { scanned: 1, orphaned: 1, deleted: 0 });
+    }
+}
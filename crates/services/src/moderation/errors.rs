@@ -17,6 +17,45 @@ pub enum ModerationError {
     #[error("permission denied")]
     PermissionDenied,
 
+    /// The operation depends on an optional port that was not wired at startup
+    /// (e.g. ASN bans without an `AsnBanRepository`).
+    #[error("not configured: {feature}")]
+    NotConfigured {
+        /// The optional feature that is missing.
+        feature: String,
+    },
+
+    /// An IP lookup was neither an IP address nor an IP hash.
+    #[error("not an IP address or IP hash: {value}")]
+    InvalidIp {
+        /// The lookup as given.
+        value: String,
+    },
+
+    /// A bulk delete window was outside `1..=max` hours.
+    #[error("hours must be between 1 and {max}, got {hours}")]
+    InvalidHours {
+        /// The window as given.
+        hours: u32,
+        /// The longest window allowed.
+        max:   u32,
+    },
+
+    /// A post move was refused: no posts, a post not in the thread, the
+    /// thread's opening post, or a target on another board.
+    #[error("cannot move posts: {reason}")]
+    InvalidMove {
+        /// Why the move was refused.
+        reason: String,
+    },
+
+    /// A post edit was refused: a subject on a reply, or no change.
+    #[error("cannot edit post: {reason}")]
+    InvalidEdit {
+        /// Why the edit was refused.
+        reason: String,
+    },
+
     /// A domain-level error that could not be handled at this level.
     #[error("internal error: {0}")]
     Internal(#[from] DomainError),
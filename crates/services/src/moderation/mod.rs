@@ -2,9 +2,16 @@
 //!
 //! Responsibilities:
 //! - Delete posts and threads
+//! - List and delete a poster's posts across boards by IP (the IP history)
 //! - Toggle sticky/closed on threads
-//! - Issue and expire bans
+//! - Move posts to another thread, or split them off into a new one
+//! - Edit a post's body and subject, keeping the earlier text as revisions
+//! - Issue and expire bans (per-IP or shadow, and per-ASN when an `AsnBanRepository` is attached)
+//! - Ban upload hashes (MD5 + perceptual) when a `HashBanRepository` is attached
+//! - Warn posters when a `WarningRepository` is attached
 //! - Resolve flags (approve or reject)
+//! - Purge the stored files of deleted posts when a `MediaStorage` is attached
+//! - Publish `PostDeleted` and `BanIssued` when an `EventBus` is attached
 //! - Write an audit log entry for every action
 //!
 //! Generic over 6 port traits. Every mutating operation writes an `AuditEntry`.
@@ -16,18 +23,36 @@ pub use errors::ModerationError;
 
 use domains::errors::DomainError;
 use domains::models::{
-    AuditAction, AuditEntry, Ban, BanId, FlagId, FlagResolution, IpHash, Page,
-    Paginated, PostId, ThreadId, UserId,
+    AsnBan, Attachment, AuditAction, AuditEntry, Ban, BanId, BoardId, DeletedBy, Flag, FlagId, FlagResolution,
+    HashBan, IpHash, OverboardPost, Page, Paginated, PostId, PostRevision, SiteId, ThreadId, UserId,
+    Warning,
 };
 use domains::ports::{
-    AuditRepository, BanRepository, FlagRepository, PostRepository, ThreadRepository,
-    UserRepository,
+    AsnBanRepository, AuditRepository, BanRepository, DomainEvent, EventBus, FlagRepository,
+    HashBanRepository, MediaStorage, PostRepository, ThreadRepository, UserRepository, WarningRepository,
 };
 use chrono::Utc;
-use tracing::{error, info, instrument};
+use std::sync::Arc;
+use tracing::{error, info, instrument, warn};
 use uuid::Uuid;
 
-use crate::common::utils::now_utc;
+use crate::common::utils::{ip_hashes_for_days, now_utc, rewrite_quotes};
+
+/// Days of daily salts a raw-IP lookup covers. Posts made from the IP before
+/// then carry hashes the lookup cannot rebuild.
+pub const IP_HISTORY_DAYS: u32 = 30;
+
+/// The longest window, in hours, of `delete_posts_by_ip_on_board`.
+pub const MAX_BULK_DELETE_HOURS: u32 = 24 * 30;
+
+/// The outcome of deleting many posts at once.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BulkDeletion {
+    /// Posts deleted.
+    pub deleted: u64,
+    /// Boards the posts were on, each once.
+    pub boards:  Vec<BoardId>,
+}
 
 /// Service handling all moderation and administrative actions.
 ///
@@ -48,6 +73,21 @@ where
     audit_repo: AR,
     #[allow(dead_code)]
     user_repo:  UR,
+    /// Optional ASN ban store. When `None`, ASN ban operations return
+    /// `ModerationError::NotConfigured`.
+    asn_ban_repo: Option<Arc<dyn AsnBanRepository>>,
+    /// Optional upload hash ban store. When `None`, hash ban operations return
+    /// `ModerationError::NotConfigured`.
+    hash_ban_repo: Option<Arc<dyn HashBanRepository>>,
+    /// Optional warning store. When `None`, `warn_ip` returns
+    /// `ModerationError::NotConfigured`.
+    warning_repo: Option<Arc<dyn WarningRepository>>,
+    /// Optional media store. When `None`, deleting a post removes only its
+    /// rows and leaves the files to the orphaned-media sweep.
+    media_storage: Option<Arc<dyn MediaStorage>>,
+    /// Optional event bus for `PostDeleted` and `BanIssued`. `None` = nothing
+    /// is published.
+    events: Option<Arc<dyn EventBus>>,
 }
 
 impl<BR, PR, TR, FR, AR, UR> ModerationService<BR, PR, TR, FR, AR, UR>
@@ -75,27 +115,84 @@ where
             flag_repo,
             audit_repo,
             user_repo,
+            asn_ban_repo: None,
+            hash_ban_repo: None,
+            warning_repo: None,
+            media_storage: None,
+            events: None,
         }
     }
 
-    /// Delete a single post and record an audit entry.
+    /// Attach an `AsnBanRepository`, enabling `ban_asn` / `expire_asn_ban`.
+    pub fn with_asn_bans(mut self, repo: Arc<dyn AsnBanRepository>) -> Self {
+        self.asn_ban_repo = Some(repo);
+        self
+    }
+
+    /// Attach a `HashBanRepository`, enabling `ban_post_media` / `ban_md5` /
+    /// `expire_hash_ban`.
+    pub fn with_hash_bans(mut self, repo: Arc<dyn HashBanRepository>) -> Self {
+        self.hash_ban_repo = Some(repo);
+        self
+    }
+
+    /// Attach a `WarningRepository`, enabling `warn_ip`.
+    pub fn with_warnings(mut self, repo: Arc<dyn WarningRepository>) -> Self {
+        self.warning_repo = Some(repo);
+        self
+    }
+
+    /// Attach a `MediaStorage`, so post and thread deletions purge files that
+    /// no remaining attachment references.
+    pub fn with_media_storage(mut self, storage: Arc<dyn MediaStorage>) -> Self {
+        self.media_storage = Some(storage);
+        self
+    }
+
+    /// Attach an `EventBus`, so deletions publish one `PostDeleted` per
+    /// removed post, `ban_ip` publishes `BanIssued` and `file_flag` publishes
+    /// `FlagFiled`.
+    pub fn with_events(mut self, events: Arc<dyn EventBus>) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    fn asn_ban_repo(&self) -> Result<&Arc<dyn AsnBanRepository>, ModerationError> {
+        self.asn_ban_repo.as_ref().ok_or_else(|| ModerationError::NotConfigured {
+            feature: "asn bans".to_owned(),
+        })
+    }
+
+    /// Delete a single post, leaving a tombstone, and record an audit entry.
     ///
-    /// Returns `ModerationError::NotFound` if the post does not exist.
+    /// Its files are purged at once; staff can still read its text until
+    /// `purge_deleted_posts` removes it. Returns `ModerationError::NotFound`
+    /// if the post does not exist or is already deleted.
     #[instrument(skip(self), fields(post_id = %post_id, actor_id = %actor_id))]
     pub async fn delete_post(
         &self,
         post_id: PostId,
         actor_id: UserId,
     ) -> Result<(), ModerationError> {
-        self.post_repo.delete(post_id).await.map_err(|e| match e {
+        let attachments = self.attachments_of(&[post_id]).await?;
+        let (board_id, events) = match self.post_repo.find_by_id(post_id).await {
+            Ok(post) => (
+                self.board_of(post.thread_id).await,
+                self.deletion_events(post.thread_id, &[post_id]).await,
+            ),
+            Err(_) => (None, Vec::new()),
+        };
+        self.post_repo.delete(post_id, DeletedBy::Staff).await.map_err(|e| match e {
             DomainError::NotFound { .. } => ModerationError::NotFound {
                 resource: post_id.to_string(),
             },
             other => ModerationError::Internal(other),
         })?;
+        self.purge_media(attachments).await;
+        self.publish(events);
         self.write_audit(
             Some(actor_id),
-            None,
+            board_id,
             AuditAction::DeletePost,
             Some(post_id.0),
             Some("post".to_owned()),
@@ -116,13 +213,27 @@ where
         thread_id: ThreadId,
         actor_id: UserId,
     ) -> Result<u64, ModerationError> {
+        let (attachments, events) = if self.media_storage.is_some() || self.events.is_some() {
+            let post_ids: Vec<PostId> = self.post_repo
+                .find_all_by_thread(thread_id)
+                .await?
+                .into_iter()
+                .filter(|p| p.ip_hash == ip_hash && !p.is_deleted())
+                .map(|p| p.id)
+                .collect();
+            (self.attachments_of(&post_ids).await?, self.deletion_events(thread_id, &post_ids).await)
+        } else {
+            (Vec::new(), Vec::new())
+        };
         let count = self.post_repo
             .delete_by_ip_in_thread(&ip_hash, thread_id)
             .await
             .map_err(ModerationError::Internal)?;
+        self.purge_media(attachments).await;
+        self.publish(events);
         self.write_audit(
             Some(actor_id),
-            None,
+            self.board_of(thread_id).await,
             AuditAction::DeletePost,
             Some(thread_id.0),
             Some("thread".to_owned()),
@@ -133,6 +244,112 @@ where
         Ok(count)
     }
 
+    /// Delete every post made from `ip_hash` on a board in the last `hours`
+    /// hours, for cleaning up a spam wave.
+    ///
+    /// The rows go in one transaction: replies are left as tombstones and a
+    /// matched opening post takes its whole thread. Files no surviving post shares are purged afterwards and the
+    /// action gets a single audit entry. Returns
+    /// `ModerationError::InvalidHours` unless `hours` is in
+    /// `1..=MAX_BULK_DELETE_HOURS`.
+    #[instrument(skip(self), fields(board_id = %board_id, hours, actor_id = %actor_id))]
+    pub async fn delete_posts_by_ip_on_board(
+        &self,
+        ip_hash:  IpHash,
+        board_id: BoardId,
+        hours:    u32,
+        actor_id: UserId,
+    ) -> Result<BulkDeletion, ModerationError> {
+        if !(1..=MAX_BULK_DELETE_HOURS).contains(&hours) {
+            return Err(ModerationError::InvalidHours { hours, max: MAX_BULK_DELETE_HOURS });
+        }
+        let since = now_utc() - chrono::Duration::hours(i64::from(hours));
+        let purged = self.post_repo.delete_by_ip_on_board(&ip_hash, board_id, since).await?;
+        let count = purged.posts.len() as u64;
+
+        self.purge_media(purged.attachments).await;
+        self.publish(
+            purged.posts
+                .iter()
+                .map(|&(thread_id, post_id)| DomainEvent::PostDeleted { board_id, thread_id, post_id })
+                .collect(),
+        );
+        self.write_audit(
+            Some(actor_id),
+            Some(board_id),
+            AuditAction::DeletePost,
+            Some(board_id.0),
+            Some("board".to_owned()),
+            Some(serde_json::json!({
+                "board_id": board_id.0,
+                "ip_hash":  ip_hash.0,
+                "hours":    hours,
+                "count":    count,
+                "threads":  purged.threads.len(),
+                "bulk":     true,
+            })),
+        )
+        .await;
+        info!(board_id = %board_id, count, threads = purged.threads.len(), "bulk-deleted board posts by IP");
+        Ok(BulkDeletion { deleted: count, boards: vec![board_id] })
+    }
+
+    /// Posts made from an IP on the site's boards, newest first.
+    ///
+    /// `lookup` is a raw IP address, matched over the last `IP_HISTORY_DAYS`
+    /// daily salts, or the 64-character hex hash shown to staff.
+    /// Returns `ModerationError::InvalidIp` for anything else.
+    pub async fn ip_history(
+        &self,
+        site:   SiteId,
+        lookup: &str,
+        page:   Page,
+    ) -> Result<Paginated<OverboardPost>, ModerationError> {
+        let hashes = resolve_ip_lookup(lookup)?;
+        Ok(self.post_repo.find_by_ip_hashes(site, &hashes, page).await?)
+    }
+
+    /// Delete every post in the IP history of `lookup` (see `ip_history`).
+    ///
+    /// Posts go thread by thread through `delete_posts_by_ip_in_thread`, so
+    /// each thread gets its own audit entry and its files are purged.
+    #[instrument(skip(self), fields(site_id = %site, actor_id = %actor_id))]
+    pub async fn delete_ip_history(
+        &self,
+        site:     SiteId,
+        lookup:   &str,
+        actor_id: UserId,
+    ) -> Result<BulkDeletion, ModerationError> {
+        let hashes = resolve_ip_lookup(lookup)?;
+        let mut targets: Vec<(IpHash, ThreadId)> = Vec::new();
+        let mut page = Page::default();
+        loop {
+            let history = self.post_repo.find_by_ip_hashes(site, &hashes, page).await?;
+            let last_page = u64::from(page.0) >= history.total_pages();
+            for post in history.items {
+                let target = (post.ip_hash, post.thread_id);
+                if !targets.contains(&target) {
+                    targets.push(target);
+                }
+            }
+            if last_page {
+                break;
+            }
+            page = Page(page.0 + 1);
+        }
+
+        let mut outcome = BulkDeletion::default();
+        for (ip_hash, thread_id) in targets {
+            let board_id = self.get_thread(thread_id).await?.board_id;
+            outcome.deleted += self.delete_posts_by_ip_in_thread(ip_hash, thread_id, actor_id).await?;
+            if !outcome.boards.contains(&board_id) {
+                outcome.boards.push(board_id);
+            }
+        }
+        info!(deleted = outcome.deleted, boards = outcome.boards.len(), "deleted IP history");
+        Ok(outcome)
+    }
+
     /// Delete a thread and all its posts; record an audit entry.
     ///
     /// Returns `ModerationError::NotFound` if the thread does not exist.
@@ -142,15 +359,29 @@ where
         thread_id: ThreadId,
         actor_id: UserId,
     ) -> Result<(), ModerationError> {
+        let (attachments, events) = if self.media_storage.is_some() || self.events.is_some() {
+            let post_ids: Vec<PostId> = self.post_repo
+                .find_all_by_thread(thread_id)
+                .await?
+                .into_iter()
+                .map(|p| p.id)
+                .collect();
+            (self.attachments_of(&post_ids).await?, self.deletion_events(thread_id, &post_ids).await)
+        } else {
+            (Vec::new(), Vec::new())
+        };
+        let board_id = self.board_of(thread_id).await;
         self.thread_repo.delete(thread_id).await.map_err(|e| match e {
             DomainError::NotFound { .. } => ModerationError::NotFound {
                 resource: thread_id.to_string(),
             },
             other => ModerationError::Internal(other),
         })?;
+        self.purge_media(attachments).await;
+        self.publish(events);
         self.write_audit(
             Some(actor_id),
-            None,
+            board_id,
             AuditAction::DeleteThread,
             Some(thread_id.0),
             Some("thread".to_owned()),
@@ -161,6 +392,129 @@ where
         Ok(())
     }
 
+    /// Move `post_ids` out of thread `from` into thread `to`, or into a new
+    /// thread opened by the oldest of them when `to` is `None`.
+    ///
+    /// Posts keep their numbers and timestamps, so they fall into place by
+    /// date. A bare `>>N` only links within its thread, so quotes the move
+    /// separates — moved posts quoting ones left behind, and the reverse — are
+    /// rewritten to `>>>/board_slug/N`. Publishes `PostsMoved`. Returns the
+    /// destination thread, or `ModerationError::InvalidMove` for an empty
+    /// selection, posts not in `from`, `from`'s opening post, or a `to` on
+    /// another board.
+    #[instrument(skip(self, post_ids), fields(from = %from, count = post_ids.len(), actor_id = %actor_id))]
+    pub async fn move_posts(
+        &self,
+        from:       ThreadId,
+        post_ids:   &[PostId],
+        to:         Option<ThreadId>,
+        board_slug: &str,
+        actor_id:   UserId,
+    ) -> Result<ThreadId, ModerationError> {
+        let invalid = |reason: &str| ModerationError::InvalidMove { reason: reason.to_owned() };
+        if post_ids.is_empty() {
+            return Err(invalid("no posts selected"));
+        }
+        let source = self.get_thread(from).await?;
+        let Some(op_post_id) = source.op_post_id else {
+            return Err(invalid("the thread has no opening post yet"));
+        };
+        if post_ids.contains(&op_post_id) {
+            return Err(invalid("the opening post cannot leave its thread"));
+        }
+        // Loaded by id: the selection may lie anywhere in a long thread.
+        let moved = self.post_repo.find_by_ids(from, post_ids).await?;
+        if moved.len() != post_ids.len() {
+            return Err(invalid("a selected post is not in this thread"));
+        }
+
+        let (to, new_thread) = match to {
+            Some(to) if to == from => return Err(invalid("the target is the same thread")),
+            Some(to) => {
+                if self.get_thread(to).await?.board_id != source.board_id {
+                    return Err(invalid("the target thread is on another board"));
+                }
+                (to, None)
+            }
+            None => {
+                // `find_by_ids` returns posts in number order.
+                let (first, last) = (&moved[0], &moved[moved.len() - 1]);
+                let thread = domains::models::Thread {
+                    id:          ThreadId::new(),
+                    board_id:    source.board_id,
+                    op_post_id:  Some(first.id),
+                    reply_count: 0,
+                    bumped_at:   last.created_at,
+                    sticky:      false,
+                    closed:      false,
+                    cycle:       false,
+                    created_at:  first.created_at,
+                };
+                (thread.id, Some(thread))
+            }
+        };
+
+        // The posts left behind that a quote crosses the move to or from.
+        let moved_numbers: Vec<u64> = moved.iter().map(|p| p.post_number).collect();
+        let is_moved = |n: u64| moved_numbers.contains(&n);
+        let mut quoted = Vec::new();
+        for post in &moved {
+            rewrite_quotes(&post.body, board_slug, |n| {
+                quoted.push(n);
+                false
+            });
+        }
+        quoted.retain(|&n| !is_moved(n));
+        quoted.sort_unstable();
+        quoted.dedup();
+        let quoted: Vec<u64> = self.post_repo.find_by_numbers(from, &quoted).await?.iter().map(|p| p.post_number).collect();
+        let quoting = self.post_repo.find_quoting(from, &moved_numbers).await?;
+        let is_quoted = |n: u64| quoted.contains(&n);
+        let bodies: Vec<(PostId, String)> = moved
+            .iter()
+            .filter_map(|p| rewrite_quotes(&p.body, board_slug, is_quoted).map(|body| (p.id, body)))
+            .chain(
+                quoting
+                    .iter()
+                    .filter(|p| !is_moved(p.post_number))
+                    .filter_map(|p| rewrite_quotes(&p.body, board_slug, is_moved).map(|body| (p.id, body))),
+            )
+            .collect();
+
+        let post_move = domains::models::PostMove {
+            from,
+            to,
+            new_thread,
+            post_ids: moved.iter().map(|p| p.id).collect(),
+            bodies,
+        };
+        self.post_repo.move_posts(&post_move).await?;
+        self.publish(vec![DomainEvent::PostsMoved {
+            board_id:  source.board_id,
+            from,
+            to,
+            moved:     post_move.post_ids.clone(),
+            rewritten: post_move.bodies.iter().map(|(id, _)| *id).collect(),
+        }]);
+        self.write_audit(
+            Some(actor_id),
+            Some(source.board_id),
+            AuditAction::MovePosts,
+            Some(to.0),
+            Some("thread".to_owned()),
+            Some(serde_json::json!({
+                "board_id": source.board_id.0,
+                "from":     from.0,
+                "split":    post_move.new_thread.is_some(),
+                "posts":    moved_numbers,
+                "rewritten": post_move.bodies.len(),
+            })),
+        )
+        .await;
+        info!(from = %from, to = %to, count = moved.len(), "posts moved");
+        Ok(to)
+    }
+
     /// Set the sticky flag on a thread and record an audit entry.
     #[instrument(skip(self), fields(thread_id = %thread_id, actor_id = %actor_id, sticky))]
     pub async fn set_sticky(
@@ -177,7 +531,7 @@ where
         })?;
         self.write_audit(
             Some(actor_id),
-            None,
+            self.board_of(thread_id).await,
             AuditAction::StickyThread,
             Some(thread_id.0),
             Some("thread".to_owned()),
@@ -204,7 +558,7 @@ where
         })?;
         self.write_audit(
             Some(actor_id),
-            None,
+            self.board_of(thread_id).await,
             AuditAction::CloseThread,
             Some(thread_id.0),
             Some("thread".to_owned()),
@@ -232,7 +586,7 @@ where
         })?;
         self.write_audit(
             Some(actor_id),
-            None,
+            self.board_of(thread_id).await,
             AuditAction::CycleThread,
             Some(thread_id.0),
             Some("thread".to_owned()),
@@ -259,7 +613,7 @@ where
         })?;
         self.write_audit(
             Some(actor_id),
-            None,
+            self.board_of_post(post_id).await,
             AuditAction::PinPost,
             Some(post_id.0),
             Some("post".to_owned()),
@@ -270,6 +624,72 @@ where
         Ok(())
     }
 
+    /// Replace a post's body and subject, e.g. to redact personal details,
+    /// and record an audit entry.
+    ///
+    /// The text it replaces is kept as a `PostRevision`, listed by
+    /// `post_revisions`, and the post is shown as edited by staff. Only an
+    /// opening post has a subject: a blank one is stored as none. Publishes
+    /// `PostEdited`. Returns `ModerationError::NotFound` for a missing or
+    /// deleted post and `ModerationError::InvalidEdit` for a subject on a
+    /// reply, a blank body on a post without attachments, or an edit that
+    /// changes nothing.
+    #[instrument(skip(self, body, subject), fields(post_id = %post_id, actor_id = %actor_id))]
+    pub async fn edit_post(
+        &self,
+        post_id:  PostId,
+        body:     String,
+        subject:  Option<String>,
+        actor_id: UserId,
+    ) -> Result<(), ModerationError> {
+        let invalid = |reason: &str| ModerationError::InvalidEdit { reason: reason.to_owned() };
+        let post = self.get_post(post_id).await?;
+        if post.is_deleted() {
+            return Err(ModerationError::NotFound { resource: format!("post {post_id}") });
+        }
+        let thread = self.get_thread(post.thread_id).await?;
+        let subject = subject.map(|s| s.trim().to_owned()).filter(|s| !s.is_empty());
+        if subject.is_some() && thread.op_post_id != Some(post_id) {
+            return Err(invalid("only the opening post has a subject"));
+        }
+        if body == post.body && subject == post.subject {
+            return Err(invalid("nothing changed"));
+        }
+        // Same rule as posting: a body or at least one attachment.
+        if body.trim().is_empty() && self.post_repo.find_attachments_by_post_ids(&[post_id]).await?.is_empty() {
+            return Err(invalid("post must contain a body or at least one attachment"));
+        }
+        self.post_repo.edit(post_id, &body, subject.clone(), actor_id).await.map_err(|e| match e {
+            DomainError::NotFound { .. } => ModerationError::NotFound {
+                resource: format!("post {post_id}"),
+            },
+            other => ModerationError::Internal(other),
+        })?;
+        self.write_audit(
+            Some(actor_id),
+            Some(thread.board_id),
+            AuditAction::EditPost,
+            Some(post_id.0),
+            Some("post".to_owned()),
+            None,
+        )
+        .await;
+        self.publish(vec![DomainEvent::PostEdited {
+            board_id: thread.board_id,
+            post:     domains::models::Post { body, subject, edited_at: Some(now_utc()), ..post },
+        }]);
+        info!(post_id = %post_id, "post edited");
+        Ok(())
+    }
+
+    /// The earlier versions of a post, newest first, for the mod UI.
+    ///
+    /// Returns `ModerationError::NotFound` if the post does not exist.
+    pub async fn post_revisions(&self, post_id: PostId) -> Result<Vec<PostRevision>, ModerationError> {
+        self.get_post(post_id).await?;
+        Ok(self.post_repo.find_revisions(post_id).await?)
+    }
+
     /// Issue an IP ban and record an audit entry.
     ///
     /// Returns the assigned `BanId`.
@@ -280,6 +700,32 @@ where
         reason: String,
         expires_at: Option<chrono::DateTime<chrono::Utc>>,
         actor_id: UserId,
+    ) -> Result<BanId, ModerationError> {
+        self.issue_ip_ban(ip_hash, reason, expires_at, false, actor_id).await
+    }
+
+    /// Issue a shadowban and record an audit entry.
+    ///
+    /// The poster is not told: their posts are accepted but shown only to
+    /// them and to staff. Returns the assigned `BanId`.
+    #[instrument(skip(self), fields(actor_id = %actor_id))]
+    pub async fn shadowban_ip(
+        &self,
+        ip_hash: IpHash,
+        reason: String,
+        expires_at: Option<chrono::DateTime<chrono::Utc>>,
+        actor_id: UserId,
+    ) -> Result<BanId, ModerationError> {
+        self.issue_ip_ban(ip_hash, reason, expires_at, true, actor_id).await
+    }
+
+    async fn issue_ip_ban(
+        &self,
+        ip_hash: IpHash,
+        reason: String,
+        expires_at: Option<chrono::DateTime<chrono::Utc>>,
+        shadow: bool,
+        actor_id: UserId,
     ) -> Result<BanId, ModerationError> {
         let ban = Ban {
             id:         BanId::new(),
@@ -288,21 +734,66 @@ where
             reason:     reason.clone(),
             expires_at,
             created_at: now_utc(),
+            shadow,
         };
         let ban_id = self.ban_repo.save(&ban).await?;
+        self.publish(vec![DomainEvent::BanIssued { ban: Ban { id: ban_id, ..ban } }]);
         self.write_audit(
             Some(actor_id),
             None,
-            AuditAction::BanIp,
+            if shadow { AuditAction::ShadowbanIp } else { AuditAction::BanIp },
             Some(ban_id.0),
             Some("ban".to_owned()),
-            Some(serde_json::json!({ "reason": reason, "expires_at": expires_at })),
+            Some(serde_json::json!({ "reason": reason, "expires_at": expires_at, "shadow": shadow })),
         )
         .await;
-        info!(ban_id = %ban_id, "ip banned");
+        info!(ban_id = %ban_id, shadow, "ip banned");
         Ok(ban_id)
     }
 
+    /// Warn the poster behind `ip_hash`, optionally about one post, and
+    /// record an audit entry.
+    ///
+    /// They see `reason` on their next page load and cannot post until they
+    /// acknowledge it. Returns the assigned `BanId`, or
+    /// `ModerationError::NotConfigured` if no `WarningRepository` is attached.
+    #[instrument(skip(self), fields(actor_id = %actor_id))]
+    pub async fn warn_ip(
+        &self,
+        ip_hash: IpHash,
+        post_id: Option<PostId>,
+        reason: String,
+        actor_id: UserId,
+    ) -> Result<BanId, ModerationError> {
+        let repo = self.warning_repo.as_ref().ok_or_else(|| ModerationError::NotConfigured {
+            feature: "warnings".to_owned(),
+        })?;
+        let warning = Warning {
+            id:              BanId::new(),
+            ip_hash,
+            post_id,
+            issued_by:       actor_id,
+            reason:          reason.clone(),
+            created_at:      now_utc(),
+            acknowledged_at: None,
+        };
+        let warning_id = repo.save(&warning).await?;
+        self.write_audit(
+            Some(actor_id),
+            match post_id {
+                Some(post_id) => self.board_of_post(post_id).await,
+                None => None,
+            },
+            AuditAction::WarnIp,
+            Some(warning_id.0),
+            Some("warning".to_owned()),
+            Some(serde_json::json!({ "reason": reason, "post_id": post_id })),
+        )
+        .await;
+        info!(warning_id = %warning_id, "ip warned");
+        Ok(warning_id)
+    }
+
     /// Expire a ban immediately and record an audit entry.
     ///
     /// Returns `ModerationError::NotFound` if the ban does not exist.
@@ -331,6 +822,227 @@ where
         Ok(())
     }
 
+    /// Delete IP bans that expired more than `days` days ago.
+    ///
+    /// Keeps the ban list short without losing recent history. Permanent bans
+    /// are kept. Not audited: it removes no active ban.
+    pub async fn purge_expired_bans(&self, days: u32) -> Result<u32, ModerationError> {
+        let deleted = self.ban_repo.delete_expired(days).await?;
+        if deleted > 0 {
+            info!(deleted, "purged expired bans");
+        }
+        Ok(deleted)
+    }
+
+    /// Remove post tombstones deleted more than `days` days ago for good.
+    ///
+    /// Not audited: the deletions themselves were.
+    pub async fn purge_deleted_posts(&self, days: u32) -> Result<u32, ModerationError> {
+        let purged = self.post_repo.purge_deleted(days).await?;
+        if purged > 0 {
+            info!(purged, "purged deleted posts");
+        }
+        Ok(purged)
+    }
+
+    /// Ban an entire autonomous system and record an audit entry.
+    ///
+    /// Returns the assigned `BanId`, or `ModerationError::NotConfigured` if no
+    /// `AsnBanRepository` is attached.
+    #[instrument(skip(self), fields(asn, actor_id = %actor_id))]
+    pub async fn ban_asn(
+        &self,
+        asn: u32,
+        reason: String,
+        expires_at: Option<chrono::DateTime<chrono::Utc>>,
+        actor_id: UserId,
+    ) -> Result<BanId, ModerationError> {
+        let repo = self.asn_ban_repo()?;
+        let ban = AsnBan {
+            id:         BanId::new(),
+            asn,
+            banned_by:  actor_id,
+            reason:     reason.clone(),
+            expires_at,
+            created_at: now_utc(),
+        };
+        let ban_id = repo.save(&ban).await?;
+        self.write_audit(
+            Some(actor_id),
+            None,
+            AuditAction::BanAsn,
+            Some(ban_id.0),
+            Some("asn_ban".to_owned()),
+            Some(serde_json::json!({ "asn": asn, "reason": reason, "expires_at": expires_at })),
+        )
+        .await;
+        info!(ban_id = %ban_id, asn, "asn banned");
+        Ok(ban_id)
+    }
+
+    /// Expire an ASN ban immediately and record an audit entry.
+    ///
+    /// Returns `ModerationError::NotFound` if the ban does not exist.
+    #[instrument(skip(self), fields(ban_id = %ban_id, actor_id = %actor_id))]
+    pub async fn expire_asn_ban(
+        &self,
+        ban_id: BanId,
+        actor_id: UserId,
+    ) -> Result<(), ModerationError> {
+        self.asn_ban_repo()?.expire(ban_id).await.map_err(|e| match e {
+            DomainError::NotFound { .. } => ModerationError::NotFound {
+                resource: ban_id.to_string(),
+            },
+            other => ModerationError::Internal(other),
+        })?;
+        self.write_audit(
+            Some(actor_id),
+            None,
+            AuditAction::ExpireBan,
+            Some(ban_id.0),
+            Some("asn_ban".to_owned()),
+            None,
+        )
+        .await;
+        info!(ban_id = %ban_id, "asn ban expired");
+        Ok(())
+    }
+
+    /// List all ASN bans (active and expired) for review.
+    pub async fn list_asn_bans(
+        &self,
+        page: Page,
+    ) -> Result<Paginated<AsnBan>, ModerationError> {
+        Ok(self.asn_ban_repo()?.find_all(page).await?)
+    }
+
+    /// Ban the MD5 and perceptual hash of every attachment on a post, so the
+    /// same or near-identical files are rejected when uploaded again.
+    ///
+    /// Returns one `BanId` per banned attachment. Returns
+    /// `ModerationError::NotFound` if the post has no attachment with recorded
+    /// hashes, or `ModerationError::NotConfigured` if no `HashBanRepository`
+    /// is attached.
+    #[instrument(skip(self), fields(post_id = %post_id, actor_id = %actor_id))]
+    pub async fn ban_post_media(
+        &self,
+        post_id: PostId,
+        reason: String,
+        expires_at: Option<chrono::DateTime<chrono::Utc>>,
+        actor_id: UserId,
+    ) -> Result<Vec<BanId>, ModerationError> {
+        let repo = self.hash_ban_repo()?;
+        let attachments = self.post_repo
+            .find_attachments_by_post_ids(&[post_id])
+            .await?
+            .remove(&post_id)
+            .unwrap_or_default();
+        let mut ban_ids = Vec::new();
+        for att in attachments.into_iter().filter(|a| a.md5.is_some() || a.phash.is_some()) {
+            let ban = HashBan {
+                id:         BanId::new(),
+                md5:        att.md5,
+                phash:      att.phash,
+                banned_by:  actor_id,
+                reason:     reason.clone(),
+                expires_at,
+                created_at: now_utc(),
+            };
+            ban_ids.push(self.save_hash_ban(repo, &ban).await?);
+        }
+        if ban_ids.is_empty() {
+            return Err(ModerationError::NotFound {
+                resource: format!("hashed attachments of post {post_id}"),
+            });
+        }
+        Ok(ban_ids)
+    }
+
+    /// Ban a file by its hex MD5 (e.g. a known spam image not yet posted here).
+    ///
+    /// Returns the assigned `BanId`, or `ModerationError::NotConfigured` if no
+    /// `HashBanRepository` is attached.
+    #[instrument(skip(self), fields(actor_id = %actor_id))]
+    pub async fn ban_md5(
+        &self,
+        md5: String,
+        reason: String,
+        expires_at: Option<chrono::DateTime<chrono::Utc>>,
+        actor_id: UserId,
+    ) -> Result<BanId, ModerationError> {
+        let repo = self.hash_ban_repo()?;
+        let ban = HashBan {
+            id:         BanId::new(),
+            md5:        Some(md5.to_ascii_lowercase()),
+            phash:      None,
+            banned_by:  actor_id,
+            reason,
+            expires_at,
+            created_at: now_utc(),
+        };
+        self.save_hash_ban(repo, &ban).await
+    }
+
+    async fn save_hash_ban(
+        &self,
+        repo: &Arc<dyn HashBanRepository>,
+        ban: &HashBan,
+    ) -> Result<BanId, ModerationError> {
+        let ban_id = repo.save(ban).await?;
+        self.write_audit(
+            Some(ban.banned_by),
+            None,
+            AuditAction::BanHash,
+            Some(ban_id.0),
+            Some("hash_ban".to_owned()),
+            Some(serde_json::json!({
+                "md5": ban.md5,
+                "phash": ban.phash.map(|h| format!("{h:016x}")),
+                "reason": ban.reason,
+                "expires_at": ban.expires_at,
+            })),
+        )
+        .await;
+        info!(ban_id = %ban_id, "upload hash banned");
+        Ok(ban_id)
+    }
+
+    /// Expire a hash ban immediately and record an audit entry.
+    ///
+    /// Returns `ModerationError::NotFound` if the ban does not exist.
+    #[instrument(skip(self), fields(ban_id = %ban_id, actor_id = %actor_id))]
+    pub async fn expire_hash_ban(
+        &self,
+        ban_id: BanId,
+        actor_id: UserId,
+    ) -> Result<(), ModerationError> {
+        self.hash_ban_repo()?.expire(ban_id).await.map_err(|e| match e {
+            DomainError::NotFound { .. } => ModerationError::NotFound {
+                resource: ban_id.to_string(),
+            },
+            other => ModerationError::Internal(other),
+        })?;
+        self.write_audit(
+            Some(actor_id),
+            None,
+            AuditAction::ExpireBan,
+            Some(ban_id.0),
+            Some("hash_ban".to_owned()),
+            None,
+        )
+        .await;
+        info!(ban_id = %ban_id, "hash ban expired");
+        Ok(())
+    }
+
+    /// List all hash bans (active and expired) for review.
+    pub async fn list_hash_bans(
+        &self,
+        page: Page,
+    ) -> Result<Paginated<HashBan>, ModerationError> {
+        Ok(self.hash_ban_repo()?.find_all(page).await?)
+    }
+
     /// Resolve (approve or reject) a flag and record an audit entry.
     ///
     /// Returns `ModerationError::NotFound` if the flag does not exist.
@@ -387,11 +1099,25 @@ where
             })
     }
 
-    /// Submit a user flag on a post, creating a new moderation queue entry.
-    ///
-    /// `reason` is the reporter's description of the rule violation.
-    /// `reporter_ip_hash` is the hashed IP of the reporter.
-    /// Returns the assigned `FlagId`.
+    /// Fetch a post by ID — used by the delete handler to find the board whose
+    /// cached pages the deletion invalidates.
+    pub async fn get_post(&self, post_id: PostId) -> Result<domains::models::Post, ModerationError> {
+        self.post_repo
+            .find_by_id(post_id)
+            .await
+            .map_err(|e| match e {
+                DomainError::NotFound { .. } => ModerationError::NotFound {
+                    resource: format!("post {post_id}"),
+                },
+                other => ModerationError::Internal(other),
+            })
+    }
+
+    /// Submit a user flag on a post, creating a new moderation queue entry.
+    ///
+    /// `reason` is the reporter's description of the rule violation.
+    /// `reporter_ip_hash` is the hashed IP of the reporter.
+    /// Returns the assigned `FlagId`.
     #[instrument(skip(self), fields(post_id = %post_id))]
     pub async fn file_flag(
         &self,
@@ -399,7 +1125,7 @@ where
         reason:             String,
         reporter_ip_hash:   IpHash,
     ) -> Result<FlagId, ModerationError> {
-        let flag = domains::models::Flag {
+        let flag = Flag {
             id:               FlagId(Uuid::new_v4()),
             post_id,
             reason,
@@ -410,6 +1136,7 @@ where
         };
         let flag_id = self.flag_repo.save(&flag).await?;
         info!(flag_id = %flag_id, post_id = %post_id, "flag filed");
+        self.publish(vec![DomainEvent::FlagFiled { flag: Flag { id: flag_id, ..flag } }]);
         Ok(flag_id)
     }
 
@@ -442,7 +1169,7 @@ where
     /// Paginated audit log scoped to a single board.
     ///
     /// Used by Board Owner (`/board-owner/logs`) and Volunteer (`/volunteer/logs`) pages.
-    /// Entries are matched by the `board_id` field in their JSON `details` column.
+    /// Entries are matched by their `board_id` column.
     pub async fn audit_log_for_board(
         &self,
         board_id: domains::models::BoardId,
@@ -451,6 +1178,105 @@ where
         Ok(self.audit_repo.find_by_board(board_id, page).await?)
     }
 
+    /// Public mod log for a board (`/board/{slug}/log`): only the actions in
+    /// [`AuditAction::PUBLIC`], with actor and details redacted.
+    pub async fn public_log_for_board(
+        &self,
+        board_id: domains::models::BoardId,
+        page: Page,
+    ) -> Result<domains::models::Paginated<AuditEntry>, ModerationError> {
+        let mut log = self.audit_repo.find_public_by_board(board_id, page).await?;
+        log.items = log.items.into_iter().map(AuditEntry::redacted).collect();
+        Ok(log)
+    }
+
+    /// The hash ban store, or `ModerationError::NotConfigured` if none is attached.
+    fn hash_ban_repo(&self) -> Result<&Arc<dyn HashBanRepository>, ModerationError> {
+        self.hash_ban_repo.as_ref().ok_or_else(|| ModerationError::NotConfigured {
+            feature: "hash bans".to_owned(),
+        })
+    }
+
+    /// Attachments of `post_ids`, loaded before deletion so their files can be
+    /// purged afterwards. Empty when no media store is attached.
+    async fn attachments_of(&self, post_ids: &[PostId]) -> Result<Vec<Attachment>, ModerationError> {
+        if self.media_storage.is_none() || post_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let by_post = self.post_repo.find_attachments_by_post_ids(post_ids).await?;
+        Ok(by_post.into_values().flatten().collect())
+    }
+
+    /// Delete the original and thumbnail of every attachment whose content no
+    /// surviving post shares, then confirm each file is gone.
+    ///
+    /// Uploads are deduplicated by hash, so a file still referenced elsewhere
+    /// is kept. Failures are logged, not propagated: the rows are already
+    /// deleted and the orphaned-media sweep retries anything left behind.
+    async fn purge_media(&self, attachments: Vec<Attachment>) {
+        let Some(storage) = &self.media_storage else { return };
+        for att in attachments {
+            match self.post_repo.find_attachment_by_hash(&att.hash).await {
+                Ok(None) => {}
+                Ok(Some(_)) => continue,
+                Err(e) => {
+                    warn!(hash = %att.hash.0, error = %e, "media reference check failed; leaving files for the sweep");
+                    continue;
+                }
+            }
+            for key in std::iter::once(&att.media_key).chain(att.thumbnail_key.as_ref()) {
+                if let Err(e) = storage.delete(key).await {
+                    error!(key = %key, error = %e, "failed to purge media");
+                    continue;
+                }
+                match storage.exists(key).await {
+                    Ok(false) => info!(key = %key, "media purged"),
+                    Ok(true) => error!(key = %key, "media still present after purge"),
+                    Err(e) => warn!(key = %key, error = %e, "could not confirm media purge"),
+                }
+            }
+        }
+    }
+
+    /// `PostDeleted` events for `post_ids` of `thread_id`, built before the
+    /// rows are deleted. Empty when no bus is attached; a failed thread lookup
+    /// is logged and publishes nothing.
+    async fn deletion_events(&self, thread_id: ThreadId, post_ids: &[PostId]) -> Vec<DomainEvent> {
+        if self.events.is_none() || post_ids.is_empty() {
+            return Vec::new();
+        }
+        match self.thread_repo.find_by_id(thread_id).await {
+            Ok(thread) => post_ids
+                .iter()
+                .map(|&post_id| DomainEvent::PostDeleted { board_id: thread.board_id, thread_id, post_id })
+                .collect(),
+            Err(e) => {
+                warn!(thread_id = %thread_id, error = %e, "thread lookup failed; deletion events not published");
+                Vec::new()
+            }
+        }
+    }
+
+    /// The board of `thread_id`, to scope an audit entry; `None` if the
+    /// lookup fails.
+    async fn board_of(&self, thread_id: ThreadId) -> Option<BoardId> {
+        self.thread_repo.find_by_id(thread_id).await.ok().map(|t| t.board_id)
+    }
+
+    /// The board of `post_id`, to scope an audit entry; `None` if a lookup fails.
+    async fn board_of_post(&self, post_id: PostId) -> Option<BoardId> {
+        let post = self.post_repo.find_by_id(post_id).await.ok()?;
+        self.board_of(post.thread_id).await
+    }
+
+    /// Publish `events` on the bus, if one is attached.
+    fn publish(&self, events: Vec<DomainEvent>) {
+        let Some(bus) = &self.events else { return };
+        for event in events {
+            bus.publish(event);
+        }
+    }
+
     /// Write an audit log entry. Failures are logged and swallowed.
     ///
     /// # INVARIANT
@@ -460,19 +1286,26 @@ where
     async fn write_audit(
         &self,
         actor_id: Option<UserId>,
-        actor_ip_hash: Option<IpHash>,
+        board_id: Option<BoardId>,
         action: AuditAction,
         target_id: Option<Uuid>,
         target_type: Option<String>,
         details: Option<serde_json::Value>,
     ) {
+        let reason = details
+            .as_ref()
+            .and_then(|d| d.get("reason"))
+            .and_then(|r| r.as_str())
+            .map(str::to_owned);
         let entry = AuditEntry {
             id:            Uuid::new_v4(),
             actor_id,
-            actor_ip_hash,
+            actor_ip_hash: None,
             action,
             target_id,
             target_type,
+            board_id,
+            reason,
             details,
             created_at:    now_utc(),
         };
@@ -482,12 +1315,27 @@ where
     }
 }
 
+/// The hashes an IP lookup stands for: those of a raw IP over the last
+/// `IP_HISTORY_DAYS` days, or the IP hash given.
+fn resolve_ip_lookup(lookup: &str) -> Result<Vec<IpHash>, ModerationError> {
+    let lookup = lookup.trim();
+    if let Ok(ip) = lookup.parse::<std::net::IpAddr>() {
+        return Ok(ip_hashes_for_days(&ip.to_string(), now_utc().date_naive(), IP_HISTORY_DAYS));
+    }
+    if lookup.len() == 64 && lookup.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Ok(vec![IpHash::new(lookup.to_ascii_lowercase())]);
+    }
+    Err(ModerationError::InvalidIp { value: lookup.to_owned() })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use domains::models::{ContentHash, MediaKey};
     use domains::ports::{
-        MockAuditRepository, MockBanRepository, MockFlagRepository, MockPostRepository,
-        MockThreadRepository, MockUserRepository,
+        MockAsnBanRepository, MockAuditRepository, MockBanRepository, MockFlagRepository,
+        MockHashBanRepository, MockMediaStorage, MockPostRepository, MockThreadRepository,
+        MockUserRepository, MockWarningRepository,
     };
 
     fn make_service() -> ModerationService<
@@ -513,10 +1361,12 @@ mod tests {
     #[tokio::test]
     async fn delete_post_happy_path() {
         let mut svc = make_service();
+        svc.post_repo.expect_find_by_id().returning(|_| Err(DomainError::not_found("post")));
         svc.post_repo
             .expect_delete()
+            .withf(|_, by| *by == DeletedBy::Staff)
             .times(1)
-            .returning(|_| Ok(()));
+            .returning(|_, _| Ok(()));
 
         let result = svc.delete_post(PostId::new(), UserId::new()).await;
         assert!(result.is_ok());
@@ -525,10 +1375,274 @@ mod tests {
     #[tokio::test]
     async fn delete_post_not_found() {
         let mut svc = make_service();
+        svc.post_repo.expect_find_by_id().returning(|_| Err(DomainError::not_found("post")));
         svc.post_repo
             .expect_delete()
             .times(1)
-            .returning(|_| Err(DomainError::not_found("post")));
+            .returning(|_, _| Err(DomainError::not_found("post")));
+
+        let result = svc.delete_post(PostId::new(), UserId::new()).await;
+        assert!(matches!(result, Err(ModerationError::NotFound { .. })));
+    }
+
+    #[tokio::test]
+    async fn ip_history_looks_up_raw_ips_by_daily_hash() {
+        let mut svc = make_service();
+        svc.post_repo
+            .expect_find_by_ip_hashes()
+            .withf(|_, hashes, _| {
+                hashes.len() == IP_HISTORY_DAYS as usize
+                    && hashes[0] == crate::common::utils::hash_ip("2001:db8::1", &Utc::now().format("%Y-%m-%d").to_string())
+            })
+            .times(1)
+            .returning(|_, _, page| Ok(Paginated::empty(page, Page::DEFAULT_PAGE_SIZE)));
+
+        svc.ip_history(SiteId::DEFAULT, " 2001:db8::1 ", Page::default()).await.unwrap();
+        let result = svc.ip_history(SiteId::DEFAULT, "not-an-ip", Page::default()).await;
+        assert!(matches!(result, Err(ModerationError::InvalidIp { .. })));
+    }
+
+    #[tokio::test]
+    async fn delete_ip_history_deletes_thread_by_thread() {
+        let ip_hash = IpHash::new("a".repeat(64));
+        let (first, second) = (ThreadId::new(), ThreadId::new());
+        let board_id = BoardId::new();
+        let mut svc = make_service();
+        svc.post_repo.expect_find_by_ip_hashes().times(1).returning(move |_, hashes, page| {
+            let post = |thread_id| OverboardPost {
+                id: PostId::new(), thread_id, board_slug: "b".to_owned(), body: String::new(), name: None,
+                tripcode: None, ip_hash: hashes[0].clone(), created_at: Utc::now(), post_number: 1, subject: None,
+                capcode: None,
+            };
+            Ok(Paginated::new(vec![post(first), post(second), post(first)], 3, page, Page::DEFAULT_PAGE_SIZE))
+        });
+        svc.thread_repo.expect_find_by_id().returning(move |id| {
+            Ok(domains::models::Thread {
+                id, board_id, op_post_id: None, reply_count: 2, bumped_at: Utc::now(), sticky: false,
+                closed: false, cycle: false, created_at: Utc::now(),
+            })
+        });
+        svc.post_repo
+            .expect_delete_by_ip_in_thread()
+            .withf(move |hash, thread_id| *hash == ip_hash && (*thread_id == first || *thread_id == second))
+            .times(2)
+            .returning(move |_, thread_id| Ok(if thread_id == first { 2 } else { 1 }));
+
+        let outcome = svc.delete_ip_history(SiteId::DEFAULT, &"a".repeat(64), UserId::new()).await.unwrap();
+        assert_eq!(outcome, BulkDeletion { deleted: 3, boards: vec![board_id] });
+    }
+
+    #[tokio::test]
+    async fn delete_posts_by_ip_on_board_purges_and_logs_once() {
+        let board_id = BoardId::new();
+        let (thread_id, post_id) = (ThreadId::new(), PostId::new());
+        let mut svc = make_service();
+        svc.post_repo
+            .expect_delete_by_ip_on_board()
+            .withf(move |hash, board, since| {
+                hash.0 == "abc" && *board == board_id && Utc::now() - *since >= chrono::Duration::hours(6)
+            })
+            .times(1)
+            .returning(move |_, _, _| {
+                Ok(domains::models::PurgedPosts {
+                    posts:       vec![(thread_id, post_id), (thread_id, PostId::new())],
+                    threads:     vec![thread_id],
+                    attachments: vec![attachment(post_id, "spam")],
+                })
+            });
+        svc.post_repo.expect_find_attachment_by_hash().returning(|_| Ok(None));
+        let mut audit = MockAuditRepository::new();
+        audit
+            .expect_record()
+            .withf(move |e| {
+                e.target_id == Some(board_id.0)
+                    && e.details.as_ref().is_some_and(|d| d["count"] == 2 && d["threads"] == 1 && d["hours"] == 6)
+            })
+            .times(1)
+            .returning(|_| Ok(()));
+        svc.audit_repo = audit;
+        let mut storage = MockMediaStorage::new();
+        storage.expect_delete().times(2).returning(|_| Ok(()));
+        storage.expect_exists().times(2).returning(|_| Ok(false));
+        let mut bus = domains::ports::MockEventBus::new();
+        bus.expect_publish()
+            .withf(move |e| matches!(e, DomainEvent::PostDeleted { board_id: b, .. } if *b == board_id))
+            .times(2)
+            .return_const(());
+        let svc = svc.with_media_storage(Arc::new(storage)).with_events(Arc::new(bus));
+
+        let outcome = svc.delete_posts_by_ip_on_board(IpHash::new("abc"), board_id, 6, UserId::new()).await.unwrap();
+        assert_eq!(outcome, BulkDeletion { deleted: 2, boards: vec![board_id] });
+    }
+
+    #[tokio::test]
+    async fn delete_posts_by_ip_on_board_rejects_bad_windows() {
+        let svc = make_service();
+        for hours in [0, MAX_BULK_DELETE_HOURS + 1] {
+            let result = svc.delete_posts_by_ip_on_board(IpHash::new("abc"), BoardId::new(), hours, UserId::new()).await;
+            assert!(matches!(result, Err(ModerationError::InvalidHours { .. })));
+        }
+    }
+
+    fn thread_posts(thread_id: ThreadId, bodies: &[&str]) -> Vec<domains::models::Post> {
+        bodies
+            .iter()
+            .enumerate()
+            .map(|(i, body)| domains::models::Post {
+                id: PostId::new(), thread_id, body: (*body).to_owned(), ip_hash: IpHash::new("abc"), name: None,
+                email: None, tripcode: None, created_at: Utc::now() + chrono::Duration::seconds(i as i64),
+                post_number: i as u64 + 1, pinned: false, tags: vec![], commands: vec![], subject: None, capcode: None,
+                deleted: None, edited_at: None,
+            })
+            .collect()
+    }
+
+    /// Answer `find_by_ids`, `find_by_numbers` and `find_quoting` from `posts`.
+    fn expect_thread_lookups(
+        svc: &mut ModerationService<
+            MockBanRepository,
+            MockPostRepository,
+            MockThreadRepository,
+            MockFlagRepository,
+            MockAuditRepository,
+            MockUserRepository,
+        >,
+        posts: Vec<domains::models::Post>,
+    ) {
+        let all = posts.clone();
+        svc.post_repo.expect_find_by_ids().returning(move |thread_id, ids| {
+            Ok(all.iter().filter(|p| p.thread_id == thread_id && ids.contains(&p.id)).cloned().collect())
+        });
+        let all = posts.clone();
+        svc.post_repo.expect_find_by_numbers().returning(move |thread_id, numbers| {
+            Ok(all.iter().filter(|p| p.thread_id == thread_id && numbers.contains(&p.post_number)).cloned().collect())
+        });
+        svc.post_repo.expect_find_quoting().returning(move |thread_id, numbers| {
+            Ok(posts
+                .iter()
+                .filter(|p| p.thread_id == thread_id && numbers.iter().any(|n| p.body.contains(&format!(">>{n}"))))
+                .cloned()
+                .collect())
+        });
+    }
+
+    #[tokio::test]
+    async fn move_posts_splits_and_rewrites_separated_quotes() {
+        let from = ThreadId::new();
+        let posts = thread_posts(from, &["op", ">>1 reply", ">>2 follow-up", ">>3 back in the thread"]);
+        let (op, moved, posts_3) = (posts[0].id, vec![posts[1].id, posts[2].id], posts[3].id);
+        let expected = moved.clone();
+        let mut svc = make_service();
+        svc.thread_repo.expect_find_by_id().returning(move |id| {
+            Ok(domains::models::Thread {
+                id, board_id: BoardId::new(), op_post_id: Some(op), reply_count: 3, bumped_at: Utc::now(),
+                sticky: false, closed: false, cycle: false, created_at: Utc::now(),
+            })
+        });
+        expect_thread_lookups(&mut svc, posts);
+        svc.post_repo
+            .expect_move_posts()
+            .withf(move |m| {
+                let thread = m.new_thread.as_ref().unwrap();
+                m.from == from
+                    && m.to == thread.id
+                    && thread.op_post_id == Some(expected[0])
+                    && m.post_ids == expected
+                    && m.bodies.iter().map(|(_, b)| b.as_str()).eq([">>>/b/1 reply", ">>>/b/3 back in the thread"])
+            })
+            .times(1)
+            .returning(|_| Ok(()));
+        let mut bus = domains::ports::MockEventBus::new();
+        let (published_moved, published_rewritten) = (moved.clone(), vec![moved[0], posts_3]);
+        bus.expect_publish()
+            .withf(move |e| {
+                matches!(e, DomainEvent::PostsMoved { from: f, to, moved, rewritten, .. }
+                    if *f == from && to != f && *moved == published_moved && *rewritten == published_rewritten)
+            })
+            .times(1)
+            .return_const(());
+        let svc = svc.with_events(Arc::new(bus));
+
+        let to = svc.move_posts(from, &moved, None, "b", UserId::new()).await.unwrap();
+        assert_ne!(to, from);
+    }
+
+    #[tokio::test]
+    async fn move_posts_keeps_the_opening_post() {
+        let from = ThreadId::new();
+        let posts = thread_posts(from, &["op", "reply"]);
+        let op = posts[0].id;
+        let mut svc = make_service();
+        svc.thread_repo.expect_find_by_id().returning(move |id| {
+            Ok(domains::models::Thread {
+                id, board_id: BoardId::new(), op_post_id: Some(op), reply_count: 1, bumped_at: Utc::now(),
+                sticky: false, closed: false, cycle: false, created_at: Utc::now(),
+            })
+        });
+        expect_thread_lookups(&mut svc, posts);
+        svc.post_repo.expect_move_posts().never();
+
+        let result = svc.move_posts(from, &[op], None, "b", UserId::new()).await;
+        assert!(matches!(result, Err(ModerationError::InvalidMove { .. })));
+        let result = svc.move_posts(from, &[PostId::new()], Some(ThreadId::new()), "b", UserId::new()).await;
+        assert!(matches!(result, Err(ModerationError::InvalidMove { .. })));
+    }
+
+    fn attachment(post_id: PostId, hash: &str) -> Attachment {
+        Attachment {
+            id: Uuid::new_v4(),
+            post_id,
+            filename: "a.png".to_owned(),
+            mime: "image/png".to_owned(),
+            hash: ContentHash::new(hash),
+            size_kb: 1,
+            media_key: MediaKey::new(format!("{hash}.png")),
+            thumbnail_key: Some(MediaKey::new(format!("{hash}_thumb.png"))),
+            spoiler: false,
+            duration_ms: None,
+            page_count: None,
+            md5: None,
+            phash: None,
+            meta: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn delete_post_purges_unshared_media() {
+        let post_id = PostId::new();
+        let mut svc = make_service();
+        svc.post_repo.expect_find_by_id().returning(|_| Err(DomainError::not_found("post")));
+        svc.post_repo.expect_find_attachments_by_post_ids().returning(move |_| {
+            Ok([(post_id, vec![attachment(post_id, "unique"), attachment(post_id, "shared")])].into())
+        });
+        svc.post_repo.expect_delete().times(1).returning(|_, _| Ok(()));
+        svc.post_repo
+            .expect_find_attachment_by_hash()
+            .returning(move |hash| Ok((hash.0 == "shared").then(|| attachment(PostId::new(), "shared"))));
+
+        let mut storage = MockMediaStorage::new();
+        storage
+            .expect_delete()
+            .withf(|key| key.0.starts_with("unique"))
+            .times(2)
+            .returning(|_| Ok(()));
+        storage.expect_exists().times(2).returning(|_| Ok(false));
+        let svc = svc.with_media_storage(Arc::new(storage));
+
+        svc.delete_post(post_id, UserId::new()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn delete_post_keeps_media_when_post_missing() {
+        let mut svc = make_service();
+        svc.post_repo.expect_find_by_id().returning(|_| Err(DomainError::not_found("post")));
+        svc.post_repo.expect_find_attachments_by_post_ids().returning(|_| Ok(Default::default()));
+        svc.post_repo
+            .expect_delete()
+            .returning(|_, _| Err(DomainError::not_found("post")));
+        let mut storage = MockMediaStorage::new();
+        storage.expect_delete().never();
+        let svc = svc.with_media_storage(Arc::new(storage));
 
         let result = svc.delete_post(PostId::new(), UserId::new()).await;
         assert!(matches!(result, Err(ModerationError::NotFound { .. })));
@@ -548,6 +1662,187 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn shadowban_ip_saves_a_shadow_ban() {
+        let mut svc = make_service();
+        svc.ban_repo
+            .expect_save()
+            .withf(|b| b.shadow && b.ip_hash.0 == "abc")
+            .times(1)
+            .returning(|b| Ok(b.id));
+
+        svc.shadowban_ip(IpHash::new("abc"), "bot".to_owned(), None, UserId::new()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn warn_ip_saves_a_pending_warning() {
+        let post_id = PostId::new();
+        let mut warnings = MockWarningRepository::new();
+        warnings
+            .expect_save()
+            .withf(move |w| w.ip_hash.0 == "abc" && w.post_id == Some(post_id) && w.acknowledged_at.is_none())
+            .times(1)
+            .returning(|w| Ok(w.id));
+        let mut svc = make_service();
+        svc.post_repo.expect_find_by_id().returning(|_| Err(DomainError::not_found("post")));
+        let svc = svc.with_warnings(Arc::new(warnings));
+
+        svc.warn_ip(IpHash::new("abc"), Some(post_id), "off topic".to_owned(), UserId::new()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn warn_ip_without_repo_is_not_configured() {
+        let result = make_service()
+            .warn_ip(IpHash::new("abc"), None, "off topic".to_owned(), UserId::new())
+            .await;
+        assert!(matches!(result, Err(ModerationError::NotConfigured { .. })));
+    }
+
+    #[tokio::test]
+    async fn ban_ip_publishes_ban_issued() {
+        let mut svc = make_service();
+        svc.ban_repo.expect_save().returning(|b| Ok(b.id));
+        let mut bus = domains::ports::MockEventBus::new();
+        bus.expect_publish()
+            .withf(|e| matches!(e, DomainEvent::BanIssued { ban } if ban.ip_hash.0 == "abc"))
+            .times(1)
+            .return_const(());
+        let svc = svc.with_events(Arc::new(bus));
+
+        svc.ban_ip(IpHash::new("abc"), "spam".to_owned(), None, UserId::new()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn delete_thread_publishes_post_deleted_per_post() {
+        let board_id = domains::models::BoardId::new();
+        let thread_id = ThreadId::new();
+        let mut svc = make_service();
+        svc.post_repo.expect_find_all_by_thread().returning(move |thread_id| {
+            Ok((1..=3)
+                .map(|n| domains::models::Post {
+                    id: PostId::new(), thread_id, body: String::new(), ip_hash: IpHash::new("abc"), name: None,
+                    email: None, tripcode: None, created_at: Utc::now(), post_number: n, pinned: false, tags: vec![],
+                    commands: vec![],
+                    subject: None,
+                    capcode: None,
+                    deleted: None,
+                    edited_at: None,
+                })
+                .collect())
+        });
+        svc.thread_repo.expect_find_by_id().returning(move |id| {
+            Ok(domains::models::Thread {
+                id, board_id, op_post_id: None, reply_count: 2, bumped_at: Utc::now(), sticky: false,
+                closed: false, cycle: false, created_at: Utc::now(),
+            })
+        });
+        svc.thread_repo.expect_delete().times(1).returning(|_| Ok(()));
+        let mut bus = domains::ports::MockEventBus::new();
+        bus.expect_publish()
+            .withf(move |e| matches!(e, DomainEvent::PostDeleted { board_id: b, thread_id: t, .. } if *b == board_id && *t == thread_id))
+            .times(3)
+            .return_const(());
+        let svc = svc.with_events(Arc::new(bus));
+
+        svc.delete_thread(thread_id, UserId::new()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn failed_delete_publishes_nothing() {
+        let mut svc = make_service();
+        svc.thread_repo.expect_find_by_id().returning(|_| Err(DomainError::not_found("thread")));
+        svc.post_repo.expect_find_all_by_thread().returning(|_| Ok(vec![]));
+        svc.thread_repo.expect_delete().returning(|_| Err(DomainError::not_found("thread")));
+        let mut bus = domains::ports::MockEventBus::new();
+        bus.expect_publish().never();
+        let svc = svc.with_events(Arc::new(bus));
+
+        assert!(svc.delete_thread(ThreadId::new(), UserId::new()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn ban_asn_creates_ban() {
+        let mut asn_repo = MockAsnBanRepository::new();
+        asn_repo
+            .expect_save()
+            .withf(|b| b.asn == 9009)
+            .times(1)
+            .returning(|b| Ok(b.id));
+        let svc = make_service().with_asn_bans(Arc::new(asn_repo));
+
+        let result = svc
+            .ban_asn(9009, "vpn abuse".to_owned(), None, UserId::new())
+            .await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn ban_asn_without_repo_is_not_configured() {
+        let svc = make_service();
+        let result = svc
+            .ban_asn(9009, "vpn abuse".to_owned(), None, UserId::new())
+            .await;
+        assert!(matches!(result, Err(ModerationError::NotConfigured { .. })));
+    }
+
+    #[tokio::test]
+    async fn ban_post_media_bans_each_hashed_attachment() {
+        let post_id = PostId::new();
+        let mut svc = make_service();
+        svc.post_repo.expect_find_attachments_by_post_ids().returning(move |_| {
+            let hashed = Attachment { md5: Some("d41d8cd9".to_owned()), phash: Some(0xF0F0), ..attachment(post_id, "a") };
+            // Uploaded before hashes were recorded — nothing to ban.
+            let legacy = attachment(post_id, "b");
+            Ok([(post_id, vec![hashed, legacy])].into())
+        });
+        let mut hash_repo = MockHashBanRepository::new();
+        hash_repo
+            .expect_save()
+            .withf(|b| b.md5.as_deref() == Some("d41d8cd9") && b.phash == Some(0xF0F0))
+            .times(1)
+            .returning(|b| Ok(b.id));
+        let svc = svc.with_hash_bans(Arc::new(hash_repo));
+
+        let bans = svc.ban_post_media(post_id, "spam".to_owned(), None, UserId::new()).await.unwrap();
+        assert_eq!(bans.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn ban_post_media_without_hashes_is_not_found() {
+        let post_id = PostId::new();
+        let mut svc = make_service();
+        svc.post_repo
+            .expect_find_attachments_by_post_ids()
+            .returning(move |_| Ok([(post_id, vec![attachment(post_id, "legacy")])].into()));
+        let mut hash_repo = MockHashBanRepository::new();
+        hash_repo.expect_save().never();
+        let svc = svc.with_hash_bans(Arc::new(hash_repo));
+
+        let result = svc.ban_post_media(post_id, "spam".to_owned(), None, UserId::new()).await;
+        assert!(matches!(result, Err(ModerationError::NotFound { .. })));
+    }
+
+    #[tokio::test]
+    async fn ban_md5_without_repo_is_not_configured() {
+        let result = make_service()
+            .ban_md5("d41d8cd98f00b204e9800998ecf8427e".to_owned(), "spam".to_owned(), None, UserId::new())
+            .await;
+        assert!(matches!(result, Err(ModerationError::NotConfigured { .. })));
+    }
+
+    #[tokio::test]
+    async fn expire_asn_ban_not_found() {
+        let mut asn_repo = MockAsnBanRepository::new();
+        asn_repo
+            .expect_expire()
+            .times(1)
+            .returning(|_| Err(DomainError::not_found("asn ban")));
+        let svc = make_service().with_asn_bans(Arc::new(asn_repo));
+
+        let result = svc.expire_asn_ban(BanId::new(), UserId::new()).await;
+        assert!(matches!(result, Err(ModerationError::NotFound { .. })));
+    }
+
     #[tokio::test]
     async fn resolve_flag_happy_path() {
         let mut svc = make_service();
@@ -579,6 +1874,7 @@ mod tests {
     #[tokio::test]
     async fn delete_thread_happy_path() {
         let mut svc = make_service();
+        svc.thread_repo.expect_find_by_id().returning(|_| Err(DomainError::not_found("thread")));
         svc.thread_repo
             .expect_delete()
             .times(1)
@@ -591,6 +1887,7 @@ mod tests {
     #[tokio::test]
     async fn delete_thread_not_found() {
         let mut svc = make_service();
+        svc.thread_repo.expect_find_by_id().returning(|_| Err(DomainError::not_found("thread")));
         svc.thread_repo
             .expect_delete()
             .times(1)
@@ -603,6 +1900,7 @@ mod tests {
     #[tokio::test]
     async fn set_sticky_true() {
         let mut svc = make_service();
+        svc.thread_repo.expect_find_by_id().returning(|_| Err(DomainError::not_found("thread")));
         svc.thread_repo
             .expect_set_sticky()
             .withf(|_, sticky| *sticky)
@@ -616,6 +1914,7 @@ mod tests {
     #[tokio::test]
     async fn set_sticky_false() {
         let mut svc = make_service();
+        svc.thread_repo.expect_find_by_id().returning(|_| Err(DomainError::not_found("thread")));
         svc.thread_repo
             .expect_set_sticky()
             .withf(|_, sticky| !sticky)
@@ -629,6 +1928,7 @@ mod tests {
     #[tokio::test]
     async fn set_closed_true() {
         let mut svc = make_service();
+        svc.thread_repo.expect_find_by_id().returning(|_| Err(DomainError::not_found("thread")));
         svc.thread_repo
             .expect_set_closed()
             .withf(|_, closed| *closed)
@@ -642,6 +1942,7 @@ mod tests {
     #[tokio::test]
     async fn set_closed_false() {
         let mut svc = make_service();
+        svc.thread_repo.expect_find_by_id().returning(|_| Err(DomainError::not_found("thread")));
         svc.thread_repo
             .expect_set_closed()
             .withf(|_, closed| !closed)
@@ -652,6 +1953,93 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn set_sticky_records_the_thread_board() {
+        let board_id = domains::models::BoardId::new();
+        let mut thread_repo = MockThreadRepository::new();
+        thread_repo.expect_set_sticky().returning(|_, _| Ok(()));
+        thread_repo.expect_find_by_id().returning(move |id| {
+            Ok(domains::models::Thread {
+                id, board_id, op_post_id: None, reply_count: 0, bumped_at: Utc::now(), sticky: true,
+                closed: false, cycle: false, created_at: Utc::now(),
+            })
+        });
+        let mut audit = MockAuditRepository::new();
+        audit.expect_record()
+            .withf(move |e| e.action == AuditAction::StickyThread && e.board_id == Some(board_id))
+            .times(1)
+            .returning(|_| Ok(()));
+        let svc = ModerationService::new(
+            MockBanRepository::new(),
+            MockPostRepository::new(),
+            thread_repo,
+            MockFlagRepository::new(),
+            audit,
+            MockUserRepository::new(),
+        );
+
+        svc.set_sticky(ThreadId::new(), true, UserId::new()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn shadowbans_are_logged_apart_from_bans() {
+        let mut ban_repo = MockBanRepository::new();
+        ban_repo.expect_save().returning(|b| Ok(b.id));
+        let mut audit = MockAuditRepository::new();
+        audit.expect_record()
+            .withf(|e| e.action == AuditAction::ShadowbanIp && e.reason.as_deref() == Some("bot"))
+            .times(1)
+            .returning(|_| Ok(()));
+        let svc = ModerationService::new(
+            ban_repo,
+            MockPostRepository::new(),
+            MockThreadRepository::new(),
+            MockFlagRepository::new(),
+            audit,
+            MockUserRepository::new(),
+        );
+
+        svc.shadowban_ip(IpHash::new("abc"), "bot".to_owned(), None, UserId::new()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn public_log_is_redacted() {
+        let board_id = domains::models::BoardId::new();
+        let mut audit = MockAuditRepository::new();
+        audit.expect_find_public_by_board().returning(move |_, page| {
+            Ok(Paginated::new(
+                vec![AuditEntry {
+                    id:            Uuid::new_v4(),
+                    actor_id:      Some(UserId::new()),
+                    actor_ip_hash: Some(IpHash::new("staff")),
+                    action:        AuditAction::DeletePost,
+                    target_id:     Some(Uuid::new_v4()),
+                    target_type:   Some("post".to_owned()),
+                    board_id:      Some(board_id),
+                    reason:        None,
+                    details:       Some(serde_json::json!({ "ip_hash": "abc" })),
+                    created_at:    Utc::now(),
+                }],
+                1,
+                page,
+                15,
+            ))
+        });
+        let svc = ModerationService::new(
+            MockBanRepository::new(),
+            MockPostRepository::new(),
+            MockThreadRepository::new(),
+            MockFlagRepository::new(),
+            audit,
+            MockUserRepository::new(),
+        );
+
+        let log = svc.public_log_for_board(board_id, Page::new(1)).await.unwrap();
+        let entry = &log.items[0];
+        assert!(entry.actor_id.is_none() && entry.actor_ip_hash.is_none() && entry.details.is_none());
+        assert_eq!(entry.board_id, Some(board_id));
+    }
+
     #[tokio::test]
     async fn ban_ip_with_expiry() {
         use chrono::Duration;
@@ -692,6 +2080,125 @@ mod tests {
         assert!(matches!(result, Err(ModerationError::NotFound { .. })));
     }
 
+    #[tokio::test]
+    async fn purge_expired_bans_passes_the_retention_window() {
+        let mut svc = make_service();
+        svc.ban_repo
+            .expect_delete_expired()
+            .withf(|days| *days == 90)
+            .times(1)
+            .returning(|_| Ok(3));
+
+        assert_eq!(svc.purge_expired_bans(90).await.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn purge_deleted_posts_passes_the_retention_window() {
+        let mut svc = make_service();
+        svc.post_repo
+            .expect_purge_deleted()
+            .withf(|days| *days == 30)
+            .times(1)
+            .returning(|_| Ok(4));
+
+        assert_eq!(svc.purge_deleted_posts(30).await.unwrap(), 4);
+    }
+
+    /// A moderation service over `posts` of one thread, opened by the first.
+    fn edit_service(posts: Vec<domains::models::Post>) -> ModerationService<
+        MockBanRepository,
+        MockPostRepository,
+        MockThreadRepository,
+        MockFlagRepository,
+        MockAuditRepository,
+        MockUserRepository,
+    > {
+        let op = posts[0].id;
+        let mut svc = make_service();
+        svc.post_repo
+            .expect_find_by_id()
+            .returning(move |id| posts.iter().find(|p| p.id == id).cloned().ok_or(DomainError::not_found("post")));
+        svc.thread_repo.expect_find_by_id().returning(move |id| {
+            Ok(domains::models::Thread {
+                id, board_id: BoardId::new(), op_post_id: Some(op), reply_count: 1, bumped_at: Utc::now(),
+                sticky: false, closed: false, cycle: false, created_at: Utc::now(),
+            })
+        });
+        svc
+    }
+
+    #[tokio::test]
+    async fn edit_post_keeps_the_old_text_as_a_revision() {
+        let posts = thread_posts(ThreadId::new(), &["op", "my address is 1 Main St"]);
+        let (op, reply) = (posts[0].id, posts[1].id);
+        let actor = UserId::new();
+        let mut svc = edit_service(posts);
+        svc.post_repo
+            .expect_edit()
+            .withf(move |id, body, subject, by| {
+                *id == reply && body == "my address is [redacted]" && subject.is_none() && *by == actor
+            })
+            .times(1)
+            .returning(|_, _, _, _| Ok(()));
+        svc.post_repo
+            .expect_edit()
+            .withf(move |id, body, subject, _| *id == op && body == "op" && subject.as_deref() == Some("Rules"))
+            .times(1)
+            .returning(|_, _, _, _| Ok(()));
+
+        let mut bus = domains::ports::MockEventBus::new();
+        bus.expect_publish()
+            .withf(move |e| {
+                matches!(e, DomainEvent::PostEdited { post, .. }
+                    if post.id == reply && post.body == "my address is [redacted]" && post.edited_at.is_some())
+            })
+            .times(1)
+            .return_const(());
+        bus.expect_publish()
+            .withf(move |e| matches!(e, DomainEvent::PostEdited { post, .. } if post.subject.as_deref() == Some("Rules")))
+            .times(1)
+            .return_const(());
+        let svc = svc.with_events(Arc::new(bus));
+
+        svc.edit_post(reply, "my address is [redacted]".to_owned(), Some("  ".to_owned()), actor).await.unwrap();
+        svc.edit_post(op, "op".to_owned(), Some(" Rules ".to_owned()), actor).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn edit_post_refuses_a_blank_body_unless_the_post_has_files() {
+        let posts = thread_posts(ThreadId::new(), &["op", "text", "picture"]);
+        let (text, picture) = (posts[1].id, posts[2].id);
+        let mut svc = edit_service(posts);
+        svc.post_repo.expect_find_attachments_by_post_ids().returning(move |ids| {
+            let mut found = std::collections::HashMap::new();
+            if ids == [picture] {
+                found.insert(picture, vec![attachment(picture, "a")]);
+            }
+            Ok(found)
+        });
+        svc.post_repo.expect_edit().withf(move |id, _, _, _| *id == picture).times(1).returning(|_, _, _, _| Ok(()));
+
+        let result = svc.edit_post(text, " \n ".to_owned(), None, UserId::new()).await;
+        assert!(matches!(result, Err(ModerationError::InvalidEdit { .. })));
+        svc.edit_post(picture, String::new(), None, UserId::new()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn edit_post_refuses_reply_subjects_no_ops_and_tombstones() {
+        let mut posts = thread_posts(ThreadId::new(), &["op", "reply", "gone"]);
+        posts[2].deleted = Some(domains::models::Tombstone { by: DeletedBy::User, at: Utc::now() });
+        let (reply, gone) = (posts[1].id, posts[2].id);
+        let mut svc = edit_service(posts);
+        svc.post_repo.expect_edit().never();
+
+        let result = svc.edit_post(reply, "reply".to_owned(), Some("Hi".to_owned()), UserId::new()).await;
+        assert!(matches!(result, Err(ModerationError::InvalidEdit { .. })));
+        let result = svc.edit_post(reply, "reply".to_owned(), None, UserId::new()).await;
+        assert!(matches!(result, Err(ModerationError::InvalidEdit { .. })));
+        let result = svc.edit_post(gone, "back".to_owned(), None, UserId::new()).await;
+        assert!(matches!(result, Err(ModerationError::NotFound { .. })));
+    }
+
     #[tokio::test]
     async fn file_flag_happy_path() {
         let mut svc = make_service();
@@ -706,6 +2213,20 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn file_flag_publishes_flag_filed() {
+        let mut svc = make_service();
+        svc.flag_repo.expect_save().returning(|f| Ok(f.id));
+        let mut bus = domains::ports::MockEventBus::new();
+        bus.expect_publish()
+            .withf(|e| matches!(e, DomainEvent::FlagFiled { flag } if flag.reason == "off-topic"))
+            .times(1)
+            .return_const(());
+        let svc = svc.with_events(Arc::new(bus));
+
+        svc.file_flag(PostId::new(), "off-topic".to_owned(), IpHash::new("xyz")).await.unwrap();
+    }
+
     #[tokio::test]
     async fn audit_log_failure_does_not_propagate() {
         // delete_post succeeds even when the audit log write fails
@@ -716,7 +2237,8 @@ mod tests {
             MockBanRepository::new(),
             {
                 let mut m = MockPostRepository::new();
-                m.expect_delete().returning(|_| Ok(()));
+                m.expect_find_by_id().returning(|_| Err(DomainError::not_found("post")));
+                m.expect_delete().returning(|_, _| Ok(()));
                 m
             },
             MockThreadRepository::new(),
@@ -0,0 +1,53 @@
+//! Error type for `PageService` operations.
+
+use domains::errors::DomainError;
+use thiserror::Error;
+
+/// Errors that can occur in `PageService` methods.
+#[derive(Debug, Error)]
+pub enum PageError {
+    /// The slug is not 1–32 lowercase letters, digits and hyphens.
+    #[error("invalid page slug: {slug}")]
+    InvalidSlug {
+        /// The slug that was rejected.
+        slug: String,
+    },
+
+    /// The slug is the path of a built-in page, which would hide this one.
+    #[error("page slug is reserved: {slug}")]
+    ReservedSlug {
+        /// The slug that was rejected.
+        slug: String,
+    },
+
+    /// Another page of the site or board already has this slug.
+    #[error("a page with slug '{slug}' already exists")]
+    SlugTaken {
+        /// The slug that was rejected.
+        slug: String,
+    },
+
+    /// The title is blank.
+    #[error("page title is empty")]
+    EmptyTitle,
+
+    /// The title or body is longer than allowed.
+    #[error("page {field} is too long (max {max} characters)")]
+    TooLong {
+        /// `title` or `body`.
+        field: &'static str,
+        /// The most characters the field may have.
+        max:   usize,
+    },
+
+    /// The page was not found.
+    #[error("page not found: {page}")]
+    NotFound {
+        /// The slug or id that was asked for.
+        page: String,
+    },
+
+    /// A domain-level error that could not be handled at this level.
+    #[error("internal error: {0}")]
+    Internal(#[from] DomainError),
+}
@@ -0,0 +1,264 @@
+//! `PageService` — admin-edited static pages such as rules, FAQ and contact.
+//!
+//! A page belongs to the site (served at `/{slug}`) or to one of its boards
+//! (served at `/board/{board}/{slug}`). Bodies are Markdown, rendered by
+//! `rb_format::page::render` when the page is shown.
+
+pub mod errors;
+pub use errors::PageError;
+
+use chrono::Utc;
+use domains::errors::DomainError;
+use domains::models::{BoardId, PageId, SiteId, StaticPage};
+use domains::ports::StaticPageRepository;
+use tracing::{info, instrument};
+
+/// Most characters a page title may have.
+pub const MAX_PAGE_TITLE_CHARS: usize = 100;
+
+/// Most characters a page body may have.
+pub const MAX_PAGE_BODY_CHARS: usize = 50_000;
+
+/// Site page slugs that built-in routes already answer.
+const RESERVED_SITE_SLUGS: &[&str] = &["all", "banned", "boards", "healthz", "media", "metrics", "overboard", "static", "watched"];
+
+/// Board page slugs that built-in board routes already answer.
+const RESERVED_BOARD_SLUGS: &[&str] =
+    &["archive", "banner", "banners", "catalog", "config", "dashboard", "post", "stats", "volunteers"];
+
+/// Service for static pages.
+///
+/// Generic over `PR: StaticPageRepository`.
+pub struct PageService<PR: StaticPageRepository> {
+    repo: PR,
+}
+
+impl<PR: StaticPageRepository> PageService<PR> {
+    /// Construct a `PageService`.
+    pub fn new(repo: PR) -> Self {
+        Self { repo }
+    }
+
+    /// The page `slug` of the site, or of `board_id` when given.
+    ///
+    /// # Errors
+    /// - `PageError::NotFound` — there is no such page
+    #[instrument(skip(self), fields(site_id = %site_id))]
+    pub async fn get(&self, site_id: SiteId, board_id: Option<BoardId>, slug: &str) -> Result<StaticPage, PageError> {
+        // Stray requests (`/favicon.ico`) never reach the store.
+        if validate_slug(slug, board_id.is_some()).is_err() {
+            return Err(PageError::NotFound { page: slug.to_owned() });
+        }
+        self.repo
+            .find(site_id, board_id, slug)
+            .await?
+            .ok_or_else(|| PageError::NotFound { page: slug.to_owned() })
+    }
+
+    /// Every page of the site, site pages first.
+    #[instrument(skip(self), fields(site_id = %site_id))]
+    pub async fn list(&self, site_id: SiteId) -> Result<Vec<StaticPage>, PageError> {
+        Ok(self.repo.list(site_id).await?)
+    }
+
+    /// Create a page of the site, or of `board_id` when given.
+    ///
+    /// # Errors
+    /// - `PageError::InvalidSlug` / `ReservedSlug` — the slug cannot be used
+    /// - `PageError::SlugTaken` — the site or board already has such a page
+    /// - `PageError::EmptyTitle` / `TooLong` — the title or body is unusable
+    #[instrument(skip(self, title, body), fields(site_id = %site_id))]
+    pub async fn create(
+        &self,
+        site_id:  SiteId,
+        board_id: Option<BoardId>,
+        slug:     &str,
+        title:    &str,
+        body:     &str,
+    ) -> Result<StaticPage, PageError> {
+        let page = StaticPage {
+            id: PageId::new(),
+            site_id,
+            board_id,
+            slug: slug.to_owned(),
+            title: title.to_owned(),
+            body: body.to_owned(),
+            updated_at: Utc::now(),
+        };
+        let page = self.save(page).await?;
+        info!(page_id = %page.id, slug = %page.slug, "page created");
+        Ok(page)
+    }
+
+    /// Replace the slug, title and body of a page of the site. The page stays
+    /// on its site or board.
+    ///
+    /// # Errors
+    /// - `PageError::NotFound` — the site has no such page
+    /// - as `create`
+    #[instrument(skip(self, title, body), fields(site_id = %site_id, page_id = %id))]
+    pub async fn update(
+        &self,
+        site_id: SiteId,
+        id:      PageId,
+        slug:    &str,
+        title:   &str,
+        body:    &str,
+    ) -> Result<StaticPage, PageError> {
+        let page = self.repo.find_by_id(site_id, id).await.map_err(|e| not_found(e, id))?;
+        let page = StaticPage {
+            slug: slug.to_owned(),
+            title: title.to_owned(),
+            body: body.to_owned(),
+            updated_at: Utc::now(),
+            ..page
+        };
+        let page = self.save(page).await?;
+        info!(slug = %page.slug, "page updated");
+        Ok(page)
+    }
+
+    /// Delete a page of the site and return it.
+    ///
+    /// # Errors
+    /// - `PageError::NotFound` — the site has no such page
+    #[instrument(skip(self), fields(site_id = %site_id, page_id = %id))]
+    pub async fn delete(&self, site_id: SiteId, id: PageId) -> Result<StaticPage, PageError> {
+        let page = self.repo.delete(site_id, id).await.map_err(|e| not_found(e, id))?;
+        info!(slug = %page.slug, "page deleted");
+        Ok(page)
+    }
+
+    /// Check and store `page`, with its slug and title trimmed.
+    async fn save(&self, mut page: StaticPage) -> Result<StaticPage, PageError> {
+        page.slug = page.slug.trim().to_owned();
+        page.title = page.title.trim().to_owned();
+        validate_slug(&page.slug, page.board_id.is_some())?;
+        if page.title.is_empty() {
+            return Err(PageError::EmptyTitle);
+        }
+        if page.title.chars().count() > MAX_PAGE_TITLE_CHARS {
+            return Err(PageError::TooLong { field: "title", max: MAX_PAGE_TITLE_CHARS });
+        }
+        if page.body.chars().count() > MAX_PAGE_BODY_CHARS {
+            return Err(PageError::TooLong { field: "body", max: MAX_PAGE_BODY_CHARS });
+        }
+        if let Some(other) = self.repo.find(page.site_id, page.board_id, &page.slug).await? {
+            if other.id != page.id {
+                return Err(PageError::SlugTaken { slug: page.slug });
+            }
+        }
+        self.repo.save(&page).await?;
+        Ok(page)
+    }
+}
+
+/// Slugs are 1–32 lowercase ASCII letters, digits and hyphens, and may not
+/// shadow a built-in route.
+fn validate_slug(slug: &str, on_board: bool) -> Result<(), PageError> {
+    let well_formed = (1..=32).contains(&slug.len())
+        && slug.bytes().all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-');
+    if !well_formed {
+        return Err(PageError::InvalidSlug { slug: slug.to_owned() });
+    }
+    let reserved = if on_board { RESERVED_BOARD_SLUGS } else { RESERVED_SITE_SLUGS };
+    if reserved.contains(&slug) {
+        return Err(PageError::ReservedSlug { slug: slug.to_owned() });
+    }
+    Ok(())
+}
+
+fn not_found(e: DomainError, id: PageId) -> PageError {
+    match e {
+        DomainError::NotFound { .. } => PageError::NotFound { page: id.to_string() },
+        other => other.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use domains::ports::MockStaticPageRepository;
+
+    fn page(slug: &str) -> StaticPage {
+        StaticPage {
+            id:         PageId::new(),
+            site_id:    SiteId::DEFAULT,
+            board_id:   None,
+            slug:       slug.to_owned(),
+            title:      "FAQ".to_owned(),
+            body:       String::new(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn create_stores_the_trimmed_page() {
+        let mut repo = MockStaticPageRepository::new();
+        repo.expect_find().returning(|_, _, _| Ok(None));
+        repo.expect_save()
+            .withf(|p| p.slug == "faq" && p.title == "Frequently asked" && p.board_id.is_none())
+            .times(1)
+            .returning(|_| Ok(()));
+        let svc = PageService::new(repo);
+
+        svc.create(SiteId::DEFAULT, None, " faq ", " Frequently asked ", "# Hi").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn bad_and_reserved_slugs_are_refused() {
+        let mut repo = MockStaticPageRepository::new();
+        repo.expect_save().never();
+        let svc = PageService::new(repo);
+
+        for slug in ["", "FAQ", "a/b", "faq.html"] {
+            let err = svc.create(SiteId::DEFAULT, None, slug, "t", "").await;
+            assert!(matches!(err, Err(PageError::InvalidSlug { .. })), "{slug:?}");
+        }
+        let err = svc.create(SiteId::DEFAULT, None, "overboard", "t", "").await;
+        assert!(matches!(err, Err(PageError::ReservedSlug { .. })));
+        let err = svc.create(SiteId::DEFAULT, Some(BoardId::new()), "catalog", "t", "").await;
+        assert!(matches!(err, Err(PageError::ReservedSlug { .. })));
+    }
+
+    #[tokio::test]
+    async fn slugs_are_unique_per_site_or_board() {
+        let mut repo = MockStaticPageRepository::new();
+        repo.expect_find().returning(|_, _, slug| Ok(Some(page(slug))));
+        repo.expect_save().never();
+        let svc = PageService::new(repo);
+
+        let err = svc.create(SiteId::DEFAULT, None, "rules", "Rules", "").await;
+        assert!(matches!(err, Err(PageError::SlugTaken { .. })));
+    }
+
+    #[tokio::test]
+    async fn update_keeps_the_page_on_its_board() {
+        let board = BoardId::new();
+        let existing = StaticPage { board_id: Some(board), ..page("rules") };
+        let id = existing.id;
+        let mut repo = MockStaticPageRepository::new();
+        repo.expect_find_by_id().returning(move |_, _| Ok(existing.clone()));
+        // Finding itself under the new slug is no conflict.
+        repo.expect_find().returning(move |_, _, slug| Ok(Some(StaticPage { id, ..page(slug) })));
+        repo.expect_save()
+            .withf(move |p| p.id == id && p.board_id == Some(board) && p.slug == "rules-2")
+            .times(1)
+            .returning(|_| Ok(()));
+        let svc = PageService::new(repo);
+
+        svc.update(SiteId::DEFAULT, id, "rules-2", "Rules", "1. be nice").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn unknown_pages_are_not_found() {
+        let mut repo = MockStaticPageRepository::new();
+        repo.expect_find().times(1).returning(|_, _, _| Ok(None));
+        repo.expect_delete().returning(|_, id| Err(DomainError::not_found(format!("page {id}"))));
+        let svc = PageService::new(repo);
+
+        assert!(matches!(svc.get(SiteId::DEFAULT, None, "faq").await, Err(PageError::NotFound { .. })));
+        assert!(matches!(svc.get(SiteId::DEFAULT, None, "favicon.ico").await, Err(PageError::NotFound { .. })));
+        assert!(matches!(svc.delete(SiteId::DEFAULT, PageId::new()).await, Err(PageError::NotFound { .. })));
+    }
+}
@@ -0,0 +1,34 @@
+//! Error type for `PollService` operations.
+
+use domains::errors::DomainError;
+use thiserror::Error;
+
+/// Errors that can occur in `PollService` methods and poll validation.
+#[derive(Debug, Error)]
+pub enum PollError {
+    /// The thread has no poll.
+    #[error("poll not found in thread {thread_id}")]
+    NotFound {
+        /// The thread that was asked for.
+        thread_id: String,
+    },
+
+    /// The board does not have `polls_enabled`.
+    #[error("polls are disabled on this board")]
+    Disabled,
+
+    /// This poster has already voted in the poll; the first vote stands.
+    #[error("already voted in this poll")]
+    AlreadyVoted,
+
+    /// The poll or vote failed validation (question too long, unknown option, ...).
+    #[error("validation failed: {reason}")]
+    Validation {
+        /// Human-readable description of the validation failure.
+        reason: String,
+    },
+
+    /// A domain-level error that could not be handled at this level.
+    #[error("internal error: {0}")]
+    Internal(#[from] DomainError),
+}
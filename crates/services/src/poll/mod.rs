@@ -0,0 +1,214 @@
+//! `PollService` — voting in thread polls and reading their results.
+//!
+//! A poll is created together with its thread: the OP's `PostDraft::poll` is
+//! checked with `PollDraft::validate` and saved by `PostService` once the
+//! thread exists. Everything after that goes through this service.
+//!
+//! # One vote per poster ID
+//! Votes are keyed on the poster's IP hash — the value the thread's ID badges
+//! are derived from — so each ID shown in the thread gets one vote. A second
+//! vote is refused rather than replacing the first.
+
+pub mod errors;
+pub use errors::PollError;
+
+use chrono::{DateTime, Utc};
+use domains::errors::DomainError;
+use domains::models::{BoardConfig, IpHash, Poll, PollOption, ThreadId};
+use domains::ports::PollRepository;
+use tracing::{info, instrument};
+
+/// Longest question, in characters.
+pub const MAX_QUESTION_LEN: usize = 200;
+/// Longest option, in characters.
+pub const MAX_OPTION_LEN: usize = 100;
+/// Fewest options a poll may have.
+pub const MIN_OPTIONS: usize = 2;
+/// Most options a poll may have.
+pub const MAX_OPTIONS: usize = 10;
+
+/// A poll as submitted with a new thread — part of `PostDraft`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PollDraft {
+    /// The question.
+    pub question: String,
+    /// The answers, in display order. Blank entries are dropped.
+    pub options:  Vec<String>,
+}
+
+impl PollDraft {
+    /// Trim the question and options, drop blank options, and check the
+    /// length and count limits.
+    pub fn validate(self) -> Result<Self, PollError> {
+        let invalid = |reason: String| Err(PollError::Validation { reason });
+        let question = self.question.trim().to_owned();
+        let options: Vec<String> =
+            self.options.iter().map(|o| o.trim()).filter(|o| !o.is_empty()).map(str::to_owned).collect();
+        if question.is_empty() {
+            return invalid("poll question must not be empty".to_owned());
+        }
+        if question.chars().count() > MAX_QUESTION_LEN {
+            return invalid(format!("poll question exceeds {MAX_QUESTION_LEN} characters"));
+        }
+        if !(MIN_OPTIONS..=MAX_OPTIONS).contains(&options.len()) {
+            return invalid(format!("a poll needs {MIN_OPTIONS} to {MAX_OPTIONS} options, got {}", options.len()));
+        }
+        if options.iter().any(|o| o.chars().count() > MAX_OPTION_LEN) {
+            return invalid(format!("poll options must be at most {MAX_OPTION_LEN} characters"));
+        }
+        Ok(Self { question, options })
+    }
+
+    /// The poll for `thread_id`, with no votes yet.
+    pub fn into_poll(self, thread_id: ThreadId, created_at: DateTime<Utc>) -> Poll {
+        Poll {
+            thread_id,
+            question: self.question,
+            options: self.options.into_iter().map(|text| PollOption { text, votes: 0 }).collect(),
+            created_at,
+        }
+    }
+}
+
+/// Service for voting in and reading thread polls.
+///
+/// Generic over `PR: PollRepository`.
+pub struct PollService<PR: PollRepository> {
+    repo: PR,
+}
+
+impl<PR: PollRepository> PollService<PR> {
+    /// Construct a `PollService`.
+    pub fn new(repo: PR) -> Self {
+        Self { repo }
+    }
+
+    /// The thread's poll with current results, or `None` if it has none.
+    #[instrument(skip(self), fields(thread_id = %thread_id))]
+    pub async fn find(&self, thread_id: ThreadId) -> Result<Option<Poll>, PollError> {
+        Ok(self.repo.find_by_thread(thread_id).await?)
+    }
+
+    /// Vote for the option at index `option` and return the updated results.
+    ///
+    /// # Errors
+    /// - `PollError::Disabled` — the board does not have `polls_enabled`
+    /// - `PollError::NotFound` — the thread has no poll
+    /// - `PollError::Validation` — `option` is out of range
+    /// - `PollError::AlreadyVoted` — `voter` has voted in this poll before
+    #[instrument(skip(self, voter, config), fields(thread_id = %thread_id, option = option))]
+    pub async fn vote(
+        &self,
+        thread_id: ThreadId,
+        option:    usize,
+        voter:     &IpHash,
+        config:    &BoardConfig,
+    ) -> Result<Poll, PollError> {
+        if !config.polls_enabled {
+            return Err(PollError::Disabled);
+        }
+        match self.repo.vote(thread_id, option, voter).await {
+            Ok(true) => info!("poll vote recorded"),
+            Ok(false) => return Err(PollError::AlreadyVoted),
+            Err(DomainError::NotFound { .. }) => return Err(PollError::NotFound { thread_id: thread_id.to_string() }),
+            Err(DomainError::Validation(e)) => return Err(PollError::Validation { reason: e.to_string() }),
+            Err(e) => return Err(e.into()),
+        }
+        self.repo
+            .find_by_thread(thread_id)
+            .await?
+            .ok_or_else(|| PollError::NotFound { thread_id: thread_id.to_string() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use domains::errors::ValidationError;
+    use domains::ports::MockPollRepository;
+
+    fn draft(question: &str, options: &[&str]) -> PollDraft {
+        PollDraft { question: question.to_owned(), options: options.iter().map(|o| (*o).to_owned()).collect() }
+    }
+
+    fn enabled() -> BoardConfig {
+        BoardConfig { polls_enabled: true, ..BoardConfig::default() }
+    }
+
+    fn poll(thread_id: ThreadId, votes: &[u64]) -> Poll {
+        Poll {
+            thread_id,
+            question: "Best editor?".to_owned(),
+            options: votes.iter().enumerate().map(|(i, &votes)| PollOption { text: format!("#{i}"), votes }).collect(),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn draft_is_trimmed_and_blank_options_dropped() {
+        let validated = draft("  Best editor? ", &[" vim", "", "  ", "emacs "]).validate().unwrap();
+        assert_eq!(validated, draft("Best editor?", &["vim", "emacs"]));
+    }
+
+    #[test]
+    fn draft_limits_are_enforced() {
+        let long_option = "x".repeat(MAX_OPTION_LEN + 1);
+        let too_many: Vec<&str> = vec!["o"; MAX_OPTIONS + 1];
+        for bad in [
+            draft(" ", &["a", "b"]),
+            draft(&"q".repeat(MAX_QUESTION_LEN + 1), &["a", "b"]),
+            draft("q", &["only one", " "]),
+            draft("q", &too_many),
+            draft("q", &["a", &long_option]),
+        ] {
+            assert!(matches!(bad.validate(), Err(PollError::Validation { .. })));
+        }
+        assert!(draft(&"é".repeat(MAX_QUESTION_LEN), &["a", "b"]).validate().is_ok());
+    }
+
+    #[tokio::test]
+    async fn vote_returns_updated_results() {
+        let thread_id = ThreadId::new();
+        let mut repo = MockPollRepository::new();
+        repo.expect_vote().withf(|_, option, _| *option == 1).returning(|_, _, _| Ok(true));
+        repo.expect_find_by_thread().returning(|id| Ok(Some(poll(id, &[0, 1]))));
+        let svc = PollService::new(repo);
+
+        let result = svc.vote(thread_id, 1, &IpHash::new("voter"), &enabled()).await.unwrap();
+        assert_eq!(result.total_votes(), 1);
+        assert_eq!(result.percent(&result.options[1]), 100);
+    }
+
+    #[tokio::test]
+    async fn second_vote_is_refused() {
+        let mut repo = MockPollRepository::new();
+        repo.expect_vote().returning(|_, _, _| Ok(false));
+        let svc = PollService::new(repo);
+
+        let err = svc.vote(ThreadId::new(), 0, &IpHash::new("voter"), &enabled()).await.unwrap_err();
+        assert!(matches!(err, PollError::AlreadyVoted));
+    }
+
+    #[tokio::test]
+    async fn vote_errors_are_mapped() {
+        let mut repo = MockPollRepository::new();
+        repo.expect_vote().returning(|_, option, _| match option {
+            0 => Err(DomainError::not_found("poll")),
+            _ => Err(DomainError::Validation(ValidationError::ValueOutOfRange {
+                field: "option".to_owned(), actual: option.to_string(), min: "0".to_owned(), max: "1".to_owned(),
+            })),
+        });
+        let svc = PollService::new(repo);
+        let voter = IpHash::new("voter");
+
+        assert!(matches!(svc.vote(ThreadId::new(), 0, &voter, &enabled()).await, Err(PollError::NotFound { .. })));
+        assert!(matches!(svc.vote(ThreadId::new(), 5, &voter, &enabled()).await, Err(PollError::Validation { .. })));
+    }
+
+    #[tokio::test]
+    async fn voting_needs_polls_enabled() {
+        let svc = PollService::new(MockPollRepository::new());
+        let err = svc.vote(ThreadId::new(), 0, &IpHash::new("voter"), &BoardConfig::default()).await.unwrap_err();
+        assert!(matches!(err, PollError::Disabled));
+    }
+}
@@ -0,0 +1,138 @@
+//! Post commands: `!roll` and `!fortune` lines, run once when a post is made.
+//!
+//! A command is a line holding nothing but the command. Lines that look like
+//! commands but do not parse (`!roll 0d6`, `!roll 500d6`, `!fortune please`)
+//! stay ordinary text and produce no result.
+
+use domains::models::CommandResult;
+
+/// Most commands run for one post; later command lines are left as text.
+pub const MAX_COMMANDS: usize = 5;
+/// Most dice in one `!roll`.
+pub const MAX_DICE: u32 = 20;
+/// Most sides on one die.
+pub const MAX_SIDES: u32 = 1000;
+/// Largest `!roll` modifier, either sign.
+pub const MAX_MODIFIER: i32 = 1000;
+
+/// What `!fortune` draws from.
+pub const FORTUNES: &[&str] = &[
+    "Godly Luck",
+    "Excellent Luck",
+    "Good Luck",
+    "Average Luck",
+    "Bad Luck",
+    "Very Bad Luck",
+    "Good news will come to you by mail",
+    "You will meet a dark handsome stranger",
+    "Better not tell you now",
+    "Outlook good",
+    "Reply hazy, try again",
+    "Don't count on it",
+    "It is certain",
+    "Ask again later",
+];
+
+enum Command {
+    Roll { dice: u32, sides: u32, modifier: i32 },
+    Fortune,
+}
+
+/// Run every command line in `body`, in order. `roll(n)` must return a
+/// number in `1..=n`; the service passes a random one, tests a fixed one.
+pub fn run(body: &str, mut roll: impl FnMut(u32) -> u32) -> Vec<CommandResult> {
+    body.lines()
+        .filter_map(|line| parse(line.trim()))
+        .take(MAX_COMMANDS)
+        .map(|command| match command {
+            Command::Roll { dice, sides, modifier } => {
+                let rolls: Vec<u32> = (0..dice).map(|_| roll(sides)).collect();
+                let total = rolls.iter().map(|&r| i64::from(r)).sum::<i64>() + i64::from(modifier);
+                CommandResult::Roll { dice, sides, modifier, rolls, total }
+            }
+            Command::Fortune => {
+                let index = roll(FORTUNES.len() as u32) as usize - 1;
+                CommandResult::Fortune { fortune: FORTUNES[index].to_owned() }
+            }
+        })
+        .collect()
+}
+
+fn parse(line: &str) -> Option<Command> {
+    let (name, arg) = line.split_once(char::is_whitespace).map_or((line, ""), |(n, a)| (n, a.trim()));
+    if name.eq_ignore_ascii_case("!roll") {
+        parse_dice(arg)
+    } else if name.eq_ignore_ascii_case("!fortune") && arg.is_empty() {
+        Some(Command::Fortune)
+    } else {
+        None
+    }
+}
+
+/// `NdS`, `NdS+M` or `NdS-M`; `N` defaults to 1 and a bare `!roll` is `1d6`.
+fn parse_dice(arg: &str) -> Option<Command> {
+    if arg.is_empty() {
+        return Some(Command::Roll { dice: 1, sides: 6, modifier: 0 });
+    }
+    let (dice, rest) = arg.split_once(['d', 'D'])?;
+    let dice = if dice.is_empty() { 1 } else { digits(dice)? };
+    let (sides, modifier) = match rest.find(['+', '-']) {
+        Some(i) => {
+            let m = i32::try_from(digits(&rest[i + 1..])?).ok().filter(|m| *m <= MAX_MODIFIER)?;
+            (digits(&rest[..i])?, if rest.as_bytes()[i] == b'-' { -m } else { m })
+        }
+        None => (digits(rest)?, 0),
+    };
+    let valid = (1..=MAX_DICE).contains(&dice) && (2..=MAX_SIDES).contains(&sides);
+    valid.then_some(Command::Roll { dice, sides, modifier })
+}
+
+/// `text` as a number if it is only ASCII digits.
+fn digits(text: &str) -> Option<u32> {
+    if text.is_empty() || !text.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    text.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn highest(n: u32) -> u32 {
+        n
+    }
+
+    #[test]
+    fn roll_lines_are_parsed() {
+        let results = run("!roll 2d6\n!ROLL d20+3\n!roll 3d4-2\n!roll", highest);
+        let totals: Vec<i64> = results
+            .iter()
+            .map(|r| match r {
+                CommandResult::Roll { total, .. } => *total,
+                CommandResult::Fortune { .. } => panic!("not a roll"),
+            })
+            .collect();
+        assert_eq!(totals, [12, 23, 10, 6]);
+        assert_eq!(results[2].to_string(), "Rolled 3d4-2: 4, 4, 4 = 10");
+    }
+
+    #[test]
+    fn invalid_commands_are_ignored() {
+        let body = "!roll 0d6\n!roll 21d6\n!roll 1d1\n!roll 2d6+1001\n!roll 2d\n!roll +2d6\n!roll 2d6 please\n\
+                    !fortune please\nsay !roll 2d6\n!rolls 2d6";
+        assert!(run(body, highest).is_empty());
+    }
+
+    #[test]
+    fn fortune_picks_from_the_list() {
+        let results = run("  !fortune  ", |_| 1);
+        assert_eq!(results, [CommandResult::Fortune { fortune: FORTUNES[0].to_owned() }]);
+        assert_eq!(results[0].to_string(), "Your fortune: Godly Luck");
+    }
+
+    #[test]
+    fn at_most_max_commands_run() {
+        assert_eq!(run(&"!roll\n".repeat(MAX_COMMANDS + 3), highest).len(), MAX_COMMANDS);
+    }
+}
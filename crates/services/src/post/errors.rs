@@ -11,10 +11,21 @@ pub enum PostError {
     Banned {
         /// The ban reason shown to the poster.
         reason: String,
+        /// When the ban was issued. `None` for automatic blocks (e.g. DNSBL).
+        issued_at: Option<chrono::DateTime<chrono::Utc>>,
         /// When the ban expires, or `None` for a permanent ban.
         expires_at: Option<chrono::DateTime<chrono::Utc>>,
     },
 
+    /// The poster has a moderator's warning they have not acknowledged yet.
+    #[error("acknowledge the moderator's warning before posting: {reason}")]
+    Warned {
+        /// The warning to acknowledge.
+        id: domains::models::BanId,
+        /// The warning text.
+        reason: String,
+    },
+
     /// The poster has exceeded the board's rate limit.
     #[error("rate limit exceeded; retry after {retry_after_secs}s")]
     RateLimited {
@@ -40,6 +51,13 @@ pub enum PostError {
         reason: String,
     },
 
+    /// An operator post filter refused the post.
+    #[error("post rejected: {reason}")]
+    Rejected {
+        /// The filter's reason, shown to the poster.
+        reason: String,
+    },
+
     /// The thread does not exist.
     #[error("thread not found: {id}")]
     ThreadNotFound {
@@ -51,6 +69,27 @@ pub enum PostError {
     #[error("thread is closed")]
     ThreadClosed,
 
+    /// An attachment matches a hash ban (identical or near-identical file).
+    #[error("file is banned: {reason}")]
+    BannedMedia {
+        /// The ban reason shown to the poster.
+        reason: String,
+    },
+
+    /// The upload would take the board past its `media_quota_mb`.
+    #[error("board media quota exceeded ({used_bytes} of {quota_bytes} bytes used)")]
+    QuotaExceeded {
+        /// Bytes of media the board already stores.
+        used_bytes: u64,
+        /// The board's quota in bytes.
+        quota_bytes: u64,
+    },
+
+    /// A poster tried to delete a post made from another IP hash, or on
+    /// another day.
+    #[error("not your post")]
+    NotOwnPost,
+
     /// Media processing failed.
     #[error("media processing failed: {reason}")]
     MediaError {
@@ -3,34 +3,60 @@
 //! This is the most complex service. It orchestrates:
 //! 1. Active ban check (always runs, regardless of BoardConfig)
 //! 2. Rate limit check (if `board_config.rate_limit_enabled`)
+//!    then operator post filters (if any are attached)
 //! 3. Spam heuristics (if `board_config.spam_filter_enabled`)
 //! 4. Duplicate detection (if `board_config.duplicate_check`)
-//! 5. Post body validation (length, content)
+//! 5. Post body validation (length, content), then post commands
+//!    (`!roll`, `!fortune`; if `board_config.commands_enabled`)
 //! 6. Media processing (if attachments present)
 //! 7. Media storage
 //! 8. Post persistence
-//! 9. Thread bump (unless email == "sage" and `board_config.allow_sage`)
+//! 9. Thread bump (unless the options include `sage` and `board_config.allow_sage`),
+//!    then cycle pruning of replies past `bump_limit` in cycle threads
 //! 10. Thread prune check
+//! 11. Event publication (if an `EventBus` is attached)
 //!
 //! Generic over 6 port traits. All conditional logic is driven by `BoardConfig`
 //! fields — never by feature flags or environment variables.
 
+pub mod commands;
 pub mod errors;
 pub use errors::PostError;
 
 use domains::errors::DomainError;
 use domains::models::{
-    Attachment, BoardConfig, BoardId, IpHash, OverboardPost, Page, Post, PostId,
-    Thread, ThreadId,
+    Attachment, BoardConfig, BoardId, ClassifierAction, DnsblAction, IpHash, MediaMeta, OverboardPost, Page, Post,
+    PostId, QuotaAction, Thread, ThreadId, ThumbnailFormat,
 };
 use domains::ports::{
-    BanRepository, MediaProcessor, MediaStorage, PostRepository, RateLimitKey, RateLimitStatus,
-    RateLimiter, RawMedia, ThreadRepository,
+    BanRepository, DomainEvent, FilterVerdict, MediaData, MediaProcessor, MediaStorage, PostRepository,
+    PostSubmission, RateLimitKey, RateLimitStatus, RateLimiter, RawMedia, ThreadRepository,
 };
 use tracing::{info, instrument, warn};
 use uuid::Uuid;
 
-use crate::common::utils::{hash_content, now_utc, score_spam};
+use crate::common::utils::{canonical_mime, hash_content, now_utc, score_spam, sniff_mime};
+
+/// Spam-score penalty added for a DNSBL-listed or Tor-exit poster when the
+/// board's `dnsbl_action` is `Score`. With the default threshold of 0.75 a
+/// listed poster is rejected only if the body itself also looks spammy.
+pub const DNSBL_SPAM_PENALTY: f32 = 0.5;
+
+/// `reporter_ip_hash` recorded on flags filed by the media classifier.
+pub const CLASSIFIER_REPORTER: &str = "media-classifier";
+
+/// Most threads `QuotaAction::Prune` deletes for a single upload. Bounds the
+/// work one post can trigger when a quota is lowered far below current usage.
+pub const QUOTA_PRUNE_LIMIT: u32 = 25;
+
+/// Longest accepted thread subject, in characters.
+pub const MAX_SUBJECT_CHARS: usize = 100;
+
+/// Whether the email/options field holds `option` as one of its
+/// space-separated words, e.g. `sage` or `nonoko`.
+pub fn has_option(email: Option<&str>, option: &str) -> bool {
+    email.is_some_and(|e| e.split_whitespace().any(|o| o.eq_ignore_ascii_case(option)))
+}
 
 /// A post draft submitted by a poster — the input to `PostService::create_post`.
 ///
@@ -52,16 +78,34 @@ pub struct PostDraft {
     pub raw_ip: Option<String>,
     /// The poster's display name. `None` for anonymous.
     pub name: Option<String>,
-    /// The email field. `Some("sage")` prevents thread bump.
+    /// The email/options field. The `sage` option prevents thread bump; see
+    /// [`has_option`].
     pub email: Option<String>,
+    /// The thread subject. Kept only when starting a new thread.
+    pub subject: Option<String>,
     /// Raw media attachments.
     pub files: Vec<RawMedia>,
+    /// When `true` every attachment on the post is stored as a spoiler and
+    /// rendered behind a placeholder thumbnail until clicked.
+    pub spoiler: bool,
     /// When `true` the poster is an authenticated staff member (janitor/mod/admin).
     /// Staff bypass rate-limiting, spam scoring, and duplicate detection.
     pub is_staff: bool,
     /// The authenticated role of the poster, if any. `None` for anonymous posts.
-    /// Required to verify capcode claims (`### Admin`, etc.).
+    /// Required to verify capcode claims (`## Admin`, etc.).
     pub poster_role: Option<domains::models::Role>,
+    /// The site's `Site::media_prefix`; new uploads are stored under it.
+    pub media_prefix: String,
+    /// Staff-only tags set by whoever submits the post, e.g. `matrix` on posts
+    /// relayed by the Matrix bridge. Post filters may add more.
+    pub tags: Vec<String>,
+    /// A poll to attach to the new thread. Only accepted without `thread_id`
+    /// on boards with `polls_enabled`.
+    pub poll: Option<crate::poll::PollDraft>,
+    /// When `true` the post was sent with an API key, which has a rate limit
+    /// of its own: the per-IP board rate limit is skipped. Spam scoring and
+    /// duplicate detection still apply.
+    pub via_api_key: bool,
 }
 
 /// The result of a successful post creation.
@@ -100,6 +144,32 @@ where
     dnsbl:            Option<std::sync::Arc<dyn domains::ports::DnsblChecker>>,
     /// Optional archive store for board-capacity pruning when `archive_enabled = true`.
     archive_repo:     Option<std::sync::Arc<dyn domains::ports::ArchiveRepository>>,
+    /// Optional IP→ASN resolver and ASN ban store. `None` = ASN bans disabled.
+    asn_bans:         Option<(
+        std::sync::Arc<dyn domains::ports::AsnResolver>,
+        std::sync::Arc<dyn domains::ports::AsnBanRepository>,
+    )>,
+    /// Optional hash ban store and the largest perceptual-hash Hamming distance
+    /// that counts as a match. `None` = hash bans disabled.
+    hash_bans:        Option<(std::sync::Arc<dyn domains::ports::HashBanRepository>, u32)>,
+    /// Optional upload classifier and the flag store used by `ClassifierAction::Flag`.
+    /// `None` = uploads are never classified.
+    classifier:       Option<(
+        std::sync::Arc<dyn domains::ports::MediaClassifier>,
+        std::sync::Arc<dyn domains::ports::FlagRepository>,
+    )>,
+    /// Optional board store used to read media usage for `media_quota_mb`.
+    /// `None` = quotas are not enforced.
+    media_quota:      Option<std::sync::Arc<dyn domains::ports::BoardRepository>>,
+    /// Optional event bus for `ThreadCreated`, `PostCreated` and `MediaUploaded`.
+    /// `None` = nothing is published.
+    events:           Option<std::sync::Arc<dyn domains::ports::EventBus>>,
+    /// Operator post filters, run in order before validation. Empty = none.
+    filters:          Vec<std::sync::Arc<dyn domains::ports::PostFilter>>,
+    /// Optional poll store for `PostDraft::poll`. `None` = polls are refused.
+    polls:            Option<std::sync::Arc<dyn domains::ports::PollRepository>>,
+    /// Optional warning store. `None` = warnings never block posting.
+    warnings:         Option<std::sync::Arc<dyn domains::ports::WarningRepository>>,
 }
 
 impl<PR, TR, BR, MS, RL, MP> PostService<PR, TR, BR, MS, RL, MP>
@@ -135,14 +205,23 @@ where
             tripcode_pepper,
             dnsbl: None,
             archive_repo: None,
+            asn_bans: None,
+            hash_bans: None,
+            classifier: None,
+            media_quota: None,
+            events: None,
+            filters: Vec::new(),
+            polls: None,
+            warnings: None,
         }
     }
 
     /// Attach a `DnsblChecker` to this service.
     ///
     /// When set, `create_post` checks the poster's raw IP (if available)
-    /// against the DNSBL before accepting the post. The check is fail-open:
-    /// a DNS error or timeout never blocks posting.
+    /// against the DNSBL before accepting the post and applies the board's
+    /// `dnsbl_action`. The check is fail-open: a DNS error or timeout never
+    /// blocks posting.
     pub fn with_dnsbl(
         mut self,
         checker: std::sync::Arc<dyn domains::ports::DnsblChecker>,
@@ -161,6 +240,103 @@ where
         self
     }
 
+    /// Attach an `AsnResolver` and `AsnBanRepository` to this service.
+    ///
+    /// When set, `create_post` resolves the poster's raw IP (if available) to
+    /// its autonomous system and rejects the post if that ASN is banned.
+    /// Resolution is fail-open: a lookup error never blocks posting.
+    pub fn with_asn_bans(
+        mut self,
+        resolver: std::sync::Arc<dyn domains::ports::AsnResolver>,
+        repo: std::sync::Arc<dyn domains::ports::AsnBanRepository>,
+    ) -> Self {
+        self.asn_bans = Some((resolver, repo));
+        self
+    }
+
+    /// Attach a `HashBanRepository` to this service.
+    ///
+    /// When set, `create_post` rejects any attachment whose MD5 is banned, or
+    /// whose perceptual hash lies within `max_distance` bits of a banned one.
+    pub fn with_hash_bans(
+        mut self,
+        repo: std::sync::Arc<dyn domains::ports::HashBanRepository>,
+        max_distance: u32,
+    ) -> Self {
+        self.hash_bans = Some((repo, max_distance));
+        self
+    }
+
+    /// Attach a `MediaClassifier` and the `FlagRepository` its flags go to.
+    ///
+    /// When set, `create_post` classifies each upload on boards whose
+    /// `classifier_action` is not `Off`, records the labels, and applies the
+    /// action to labels at or above `classifier_threshold`. Classification is
+    /// fail-open: a classifier error never blocks posting.
+    pub fn with_classifier(
+        mut self,
+        classifier: std::sync::Arc<dyn domains::ports::MediaClassifier>,
+        flags: std::sync::Arc<dyn domains::ports::FlagRepository>,
+    ) -> Self {
+        self.classifier = Some((classifier, flags));
+        self
+    }
+
+    /// Attach the `BoardRepository` that reports per-board media usage.
+    ///
+    /// When set, `create_post` enforces `media_quota_mb` on posts with files:
+    /// an upload that would take the board past its quota is rejected, or with
+    /// `QuotaAction::Prune` the board's oldest non-sticky threads are deleted
+    /// until it fits.
+    pub fn with_media_quotas(
+        mut self,
+        boards: std::sync::Arc<dyn domains::ports::BoardRepository>,
+    ) -> Self {
+        self.media_quota = Some(boards);
+        self
+    }
+
+    /// Attach an `EventBus`.
+    ///
+    /// When set, a successful `create_post` publishes `ThreadCreated` (new
+    /// threads only), then `PostCreated`, then one `MediaUploaded` per
+    /// attachment.
+    pub fn with_events(
+        mut self,
+        events: std::sync::Arc<dyn domains::ports::EventBus>,
+    ) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Append `PostFilter`s to run, in order, on every submission.
+    ///
+    /// Filters run after the ban and rate limit checks and before body
+    /// validation; a `Reject` fails `create_post` with `PostError::Rejected`
+    /// and a `Rewrite` replaces the body. Tags from every filter are saved
+    /// on the post. A filter error is logged and treated as `Accept`.
+    pub fn with_filters(
+        mut self,
+        filters: Vec<std::sync::Arc<dyn domains::ports::PostFilter>>,
+    ) -> Self {
+        self.filters.extend(filters);
+        self
+    }
+
+    /// Attach a `PollRepository` so the OP of a new thread can add a poll on
+    /// boards with `polls_enabled`. The poll is saved once the thread exists.
+    pub fn with_polls(mut self, polls: std::sync::Arc<dyn domains::ports::PollRepository>) -> Self {
+        self.polls = Some(polls);
+        self
+    }
+
+    /// Attach a `WarningRepository`, so a poster with an unacknowledged
+    /// warning is refused with `PostError::Warned` until they acknowledge it.
+    pub fn with_warnings(mut self, warnings: std::sync::Arc<dyn domains::ports::WarningRepository>) -> Self {
+        self.warnings = Some(warnings);
+        self
+    }
+
     /// Create a new post (and optionally a new thread if no `thread_id` is provided).
     ///
     /// # Behaviour controlled by `BoardConfig`
@@ -168,10 +344,16 @@ where
     /// - `spam_filter_enabled` / `spam_score_threshold`
     /// - `duplicate_check`
     /// - `max_post_length`
-    /// - `max_files` / `max_file_size` / `allowed_mimes`
+    /// - `max_files` / `max_file_size` / `allowed_mimes` (checked against the
+    ///   MIME type sniffed from magic bytes, not the declared Content-Type)
+    /// - `strip_metadata` (EXIF/XMP/GPS removal from stored originals)
+    /// - `classifier_action` / `classifier_threshold` (upload labelling, auto-spoiler, review flags)
+    /// - `media_quota_mb` / `quota_action` (reject or prune when the board's media is full)
     /// - `allow_sage` (controls whether sage email prevents bump)
     /// - `forced_anon` (ignores the name field when true)
     /// - `bump_limit` (posts past this count no longer bump the thread)
+    /// - `commands_enabled` (`!roll` / `!fortune` results stored on the post)
+    /// - `polls_enabled` (whether `PostDraft::poll` is accepted on a new thread)
     ///
     /// # Staff bypass (`PostDraft::is_staff`)
     /// When `draft.is_staff` is `true` (set by the handler when a valid staff JWT is
@@ -181,16 +363,22 @@ where
     /// - Spam scoring (`spam_filter_enabled`)
     /// - Duplicate content detection (`duplicate_check`)
     ///
-    /// The ban check (step 1) is **never** bypassed — it applies to all posters.
+    /// The ban check (step 1) and the upload hash-ban check are **never**
+    /// bypassed — they apply to all posters.
     ///
     /// # Error conditions
-    /// - `PostError::Banned` — the poster's IP has an active ban (always checked)
+    /// - `PostError::Banned` — the poster's IP or ASN has an active ban (always checked;
+    ///   a shadowban instead accepts the post without announcing it)
+    /// - `PostError::Warned` — the poster has a warning to acknowledge (always checked)
     /// - `PostError::RateLimited` — rate limit exceeded (anonymous posters only)
     /// - `PostError::SpamDetected` — spam score above threshold (anonymous posters only)
     /// - `PostError::DuplicatePost` — duplicate content hash (anonymous posters only)
-    /// - `PostError::Validation` — body/file validation failed
+    /// - `PostError::Rejected` — an operator post filter refused the post
+    /// - `PostError::Validation` — body/file/poll validation failed
     /// - `PostError::ThreadNotFound` — specified thread does not exist
     /// - `PostError::ThreadClosed` — thread is closed
+    /// - `PostError::BannedMedia` — an attachment matches a hash ban (always checked)
+    /// - `PostError::QuotaExceeded` — the board's media quota is full (always checked)
     /// - `PostError::MediaError` — media processing failed
     #[instrument(skip(self, draft, board_config), fields(
         board_id = %draft.board_id,
@@ -199,55 +387,85 @@ where
     ))]
     pub async fn create_post(
         &self,
-        draft: PostDraft,
+        mut draft: PostDraft,
         board_config: &BoardConfig,
     ) -> Result<PostResult, PostError> {
         // ── Step 1: Active ban check ─────────────────────────────────────────
         // INVARIANT: ban check ALWAYS runs — it is not a BoardConfig toggle.
+        // A shadowban lets the post through; it is saved but never announced,
+        // and does not bump or prune, so only its poster and staff see it.
+        let mut shadowbanned = false;
         if let Some(ban) = self
             .ban_repo
             .find_active_by_ip(&draft.ip_hash)
             .await?
         {
-            return Err(PostError::Banned {
-                reason:     ban.reason.clone(),
-                expires_at: ban.expires_at,
-            });
+            if ban.shadow {
+                shadowbanned = true;
+            } else {
+                return Err(PostError::Banned {
+                    reason:     ban.reason.clone(),
+                    issued_at:  Some(ban.created_at),
+                    expires_at: ban.expires_at,
+                });
+            }
+        }
+
+        // ── Step 1a: ASN ban check ───────────────────────────────────────────
+        // Like step 1, never bypassed — but only active when an ASN resolver is
+        // wired and the raw IP is available.
+        if let Some(raw_ip) = draft.raw_ip.as_deref() {
+            if let Some(ban) = self.find_active_asn_ban(raw_ip).await? {
+                info!(asn = ban.asn, "post rejected by ASN ban");
+                return Err(PostError::Banned {
+                    reason:     ban.reason,
+                    issued_at:  Some(ban.created_at),
+                    expires_at: ban.expires_at,
+                });
+            }
         }
 
-        // ── Step 1b: DNSBL check ──────────────────────────────────────────────
+        // ── Step 1b: IP reputation check (DNSBL / Tor exit list) ─────────────
         // Gated by BoardConfig + presence of a DnsblChecker. Fail-open: a DNS
-        // error or timeout is logged and treated as "not listed".
-        if board_config.spam_filter_enabled {
-            if let Some(ref checker) = self.dnsbl {
-                // draft.ip_hash is the hashed IP; the raw IP for DNSBL is in draft.raw_ip.
-                // Only check if the raw IP is available (not proxied/unknown).
-                if let Some(ref raw_ip) = draft.raw_ip {
-                    match checker.is_blocked(raw_ip).await {
-                        Ok(true) => {
-                            tracing::warn!(
-                                ip_hash = %draft.ip_hash.0,
-                                "DNSBL blocked post attempt"
-                            );
-                            return Err(PostError::Banned {
-                                reason: "Your IP address is listed in a spam blocklist. \
-                                         Contact your ISP or use a clean connection.".to_owned(),
-                                expires_at: None,
-                            });
-                        }
-                        Ok(false) => {}
-                        Err(e) => {
-                            // Fail open — log but do not block.
-                            tracing::warn!(error = %e, "DNSBL lookup failed (fail-open)");
-                        }
+        // error or timeout is logged and treated as "not listed". A listed IP
+        // is either rejected here (`DnsblAction::Block`) or carries a penalty
+        // into spam scoring at step 5b (`DnsblAction::Score`).
+        let mut reputation_penalty: f32 = 0.0;
+        if board_config.spam_filter_enabled && board_config.dnsbl_action != DnsblAction::Off {
+            if let (Some(checker), Some(raw_ip)) = (self.dnsbl.as_ref(), draft.raw_ip.as_ref()) {
+                match checker.is_blocked(raw_ip).await {
+                    Ok(true) if board_config.dnsbl_action == DnsblAction::Block => {
+                        warn!(ip_hash = %draft.ip_hash.0, "DNSBL blocked post attempt");
+                        return Err(PostError::Banned {
+                            reason: "Your IP address is listed in a spam blocklist or is a \
+                                     known proxy. Contact your ISP or use a clean connection.".to_owned(),
+                            issued_at:  None,
+                            expires_at: None,
+                        });
+                    }
+                    Ok(true) => {
+                        info!(ip_hash = %draft.ip_hash.0, "DNSBL-listed poster; applying spam penalty");
+                        reputation_penalty = DNSBL_SPAM_PENALTY;
+                    }
+                    Ok(false) => {}
+                    Err(e) => {
+                        // Fail open — log but do not block.
+                        warn!(error = %e, "DNSBL lookup failed (fail-open)");
                     }
                 }
             }
         }
 
+        // ── Step 1c: Pending warning ─────────────────────────────────────────
+        // A warned poster must acknowledge the warning before posting again.
+        if let Some(warning) = self.find_pending_warning(&draft.ip_hash).await? {
+            return Err(PostError::Warned { id: warning.id, reason: warning.reason });
+        }
+
         // ── Step 2: Rate limit check ─────────────────────────────────────────
-        // Staff (authenticated janitor/mod/admin) bypass rate limiting entirely.
-        if board_config.rate_limit_enabled && !draft.is_staff {
+        // Staff (authenticated janitor/mod/admin) bypass rate limiting entirely;
+        // API keys were already held to their own limit.
+        if board_config.rate_limit_enabled && !draft.is_staff && !draft.via_api_key {
             let key = RateLimitKey {
                 ip_hash:  draft.ip_hash.clone(),
                 board_id: draft.board_id,
@@ -260,6 +478,40 @@ where
             }
         }
 
+        // ── Step 2b: Operator post filters ───────────────────────────────────
+        // Fail-open: a filter error is logged and the post continues as is.
+        let mut tags = std::mem::take(&mut draft.tags);
+        if !self.filters.is_empty() {
+            let mut submission = PostSubmission {
+                board_id:   draft.board_id,
+                thread_id:  draft.thread_id,
+                body:       std::mem::take(&mut draft.body),
+                name:       draft.name.clone(),
+                email:      draft.email.clone(),
+                ip_hash:    draft.ip_hash.clone(),
+                file_count: draft.files.len(),
+                is_staff:   draft.is_staff,
+            };
+            for filter in &self.filters {
+                match filter.check(&submission).await {
+                    Ok(FilterVerdict::Accept { tags: added }) => tags.extend(added),
+                    Ok(FilterVerdict::Rewrite { body, tags: added }) => {
+                        submission.body = body;
+                        tags.extend(added);
+                    }
+                    Ok(FilterVerdict::Reject { reason }) => {
+                        info!(filter = filter.name(), reason = %reason, "post rejected by filter");
+                        return Err(PostError::Rejected { reason });
+                    }
+                    Err(e) => warn!(filter = filter.name(), error = %e, "post filter failed (fail-open)"),
+                }
+            }
+            draft.body = submission.body;
+        }
+        tags.retain(|t| !t.trim().is_empty());
+        tags.sort();
+        tags.dedup();
+
         // ── Step 3: Post body validation ─────────────────────────────────────
         // A post must have either a non-empty body or at least one attachment.
         if draft.body.trim().is_empty() && draft.files.is_empty() {
@@ -277,6 +529,41 @@ where
             });
         }
 
+        // Only an OP carries a subject; a blank one is no subject.
+        let subject = match draft.subject.take() {
+            Some(s) if draft.thread_id.is_none() && !s.trim().is_empty() => Some(s.trim().to_owned()),
+            _ => None,
+        };
+        if subject.as_ref().is_some_and(|s| s.chars().count() > MAX_SUBJECT_CHARS) {
+            return Err(PostError::Validation {
+                reason: format!("subject exceeds {MAX_SUBJECT_CHARS} characters"),
+            });
+        }
+
+        // ── Step 3a: Post commands ───────────────────────────────────────────
+        // Rolled once here and stored with the post, so every reader sees the
+        // same result.
+        let commands = if board_config.commands_enabled {
+            commands::run(&draft.body, |sides| rand::random_range(1..=sides))
+        } else {
+            Vec::new()
+        };
+
+        // ── Step 3b: Poll validation ─────────────────────────────────────────
+        let poll = match draft.poll.take() {
+            None => None,
+            Some(_) if draft.thread_id.is_some() => {
+                return Err(PostError::Validation { reason: "only a new thread can have a poll".to_owned() });
+            }
+            Some(_) if !board_config.polls_enabled || self.polls.is_none() => {
+                return Err(PostError::Validation { reason: "polls are disabled on this board".to_owned() });
+            }
+            Some(poll) => Some(poll.validate().map_err(|e| match e {
+                crate::poll::PollError::Validation { reason } => PostError::Validation { reason },
+                other => PostError::Validation { reason: other.to_string() },
+            })?),
+        };
+
         // ── Step 4: Attachment count validation ──────────────────────────────
         if draft.files.len() > board_config.max_files as usize {
             return Err(PostError::Validation {
@@ -325,8 +612,13 @@ where
 
         // ── Step 5b: Spam heuristics ─────────────────────────────────────────
         // Staff bypass spam and duplicate checks (they can be trusted).
-        if board_config.spam_filter_enabled && !draft.is_staff && !draft.body.is_empty() {
-            let spam_score = score_spam(&draft.body, &board_config.link_blacklist);
+        if board_config.spam_filter_enabled
+            && !draft.is_staff
+            && (!draft.body.is_empty() || reputation_penalty > 0.0)
+        {
+            let spam_score = (score_spam(&draft.body, &board_config.link_blacklist)
+                + reputation_penalty)
+                .min(1.0);
             if spam_score >= board_config.spam_score_threshold {
                 warn!(
                     score = spam_score,
@@ -349,6 +641,14 @@ where
             }
         }
 
+        // ── Step 6b: Board media quota ───────────────────────────────────────
+        // Checked before a new thread is created so a rejected upload leaves
+        // nothing behind. Applies to staff too: it protects the host's disk.
+        if !draft.files.is_empty() {
+            let incoming: u64 = draft.files.iter().map(|f| f.data.len()).sum();
+            self.enforce_media_quota(board_config, draft.board_id, draft.thread_id, incoming).await?;
+        }
+
         // ── Step 7: Resolve or create thread ─────────────────────────────────
         let (thread, is_new_thread) = match draft.thread_id {
             Some(thread_id) => {
@@ -389,28 +689,92 @@ where
 
         // ── Step 8: Process and store media attachments ───────────────────────
         let mut attachments: Vec<Attachment> = Vec::new();
+        // Classifier labels that reached the board threshold, for review flags.
+        let mut flagged_labels: Vec<domains::models::MediaLabel> = Vec::new();
         for raw_file in draft.files {
-            // Validate MIME type against board config
-            let mime_str = raw_file.mime.to_string();
+            // Identify the file by its magic bytes; the declared Content-Type is
+            // only cross-checked. `application/octet-stream` means "undeclared".
+            let Some(sniffed) = sniff_mime(raw_file.data.head()) else {
+                return Err(PostError::Validation {
+                    reason: format!("could not determine the file type of '{}'", raw_file.filename),
+                });
+            };
+            let declared = canonical_mime(raw_file.mime.as_ref());
+            if declared != mime::APPLICATION_OCTET_STREAM.as_ref() && declared != sniffed {
+                return Err(PostError::Validation {
+                    reason: format!(
+                        "'{}' was uploaded as {} but its content is {}",
+                        raw_file.filename, declared, sniffed,
+                    ),
+                });
+            }
+            // Validate the sniffed MIME type against board config
+            let mime_str = sniffed.to_owned();
             if !board_config.allows_mime(&mime_str) {
                 return Err(PostError::Validation {
                     reason: format!("mime type '{}' is not allowed on this board", mime_str),
                 });
             }
             // Validate file size
-            let size_kb = (raw_file.data.len() as u32).div_ceil(1024);
+            let size_kb = raw_file.data.len().div_ceil(1024).min(u32::MAX as u64) as u32;
             if !board_config.allows_file_size_kb(size_kb) {
                 return Err(PostError::Validation {
-                    reason: format!(
-                        "file size {}KB exceeds board maximum {}KB",
-                        size_kb, board_config.max_file_size.0,
-                    ),
+                    reason: domains::errors::ValidationError::FileTooLarge {
+                        size_kb,
+                        max_kb: board_config.max_file_size.0,
+                    }
+                    .to_string(),
                 });
             }
-            // Process
-            let processed = self.media_processor.process(raw_file).await.map_err(|e| {
+            let original_filename = MediaMeta::sanitize_filename(&raw_file.filename);
+            // Process — metadata stripping and thumbnail parameters follow the
+            // board settings, never the caller.
+            let raw_file = RawMedia {
+                mime:           mime_str.parse().unwrap_or(mime::APPLICATION_OCTET_STREAM),
+                strip_metadata: board_config.strip_metadata,
+                thumbnail:      board_config.thumbnail,
+                ..raw_file
+            };
+            let mut processed = self.media_processor.process(raw_file).await.map_err(|e| {
                 PostError::MediaError { reason: e.to_string() }
             })?;
+            // Each site stores its uploads under its own prefix.
+            processed.original_key = processed.original_key.with_prefix(&draft.media_prefix);
+            processed.thumbnail_key = processed.thumbnail_key.map(|k| k.with_prefix(&draft.media_prefix));
+
+            // ── Hash bans: identical (MD5) or near-identical (perceptual) files ──
+            if let Some(ban) = self.find_hash_ban(&processed.md5, processed.phash).await? {
+                info!(ban_id = %ban.id, "upload rejected by hash ban");
+                return Err(PostError::BannedMedia { reason: ban.reason });
+            }
+            // A re-encoded original is stored (and served) in its new format;
+            // the uploaded one is kept in the metadata.
+            let (mime_str, original_mime) = match processed.stored_mime.take() {
+                Some(stored) => (stored, Some(mime_str)),
+                None => (mime_str, None),
+            };
+
+            // ── Classification: label the stored original (fail-open) ──
+            let labels = self.classify(board_config, &mime_str, &processed.original_data).await;
+            let triggered: Vec<_> = labels
+                .iter()
+                .filter(|l| l.score >= board_config.classifier_threshold)
+                .cloned()
+                .collect();
+            let spoiler = draft.spoiler
+                || (!triggered.is_empty() && board_config.classifier_action == ClassifierAction::Spoiler);
+            if board_config.classifier_action == ClassifierAction::Flag {
+                flagged_labels.extend(triggered);
+            }
+
+            let meta = MediaMeta {
+                original_filename,
+                size_bytes: processed.original_data.len(),
+                width:      processed.width,
+                height:     processed.height,
+                original_mime,
+                labels,
+            };
 
             // ── Deduplication: reuse existing storage keys for identical files ──
             // If we already have an attachment with this SHA-256 hash, point the new
@@ -423,17 +787,28 @@ where
             let (media_key, thumbnail_key) = if let Some(ref dup) = existing {
                 (dup.media_key.clone(), dup.thumbnail_key.clone())
             } else {
-                // Store original
-                self.media_storage
-                    .store(&processed.original_key, processed.original_data.clone(), &mime_str)
-                    .await
-                    .map_err(|e| PostError::MediaError { reason: e.to_string() })?;
-                // Store thumbnail if present
-                if let (Some(thumb_key), Some(thumb_data)) =
-                    (&processed.thumbnail_key, &processed.thumbnail_data)
-                {
+                // Store original — spooled uploads go straight from disk
+                match processed.original_data {
+                    MediaData::Memory(bytes) => {
+                        self.media_storage.store(&processed.original_key, bytes, &mime_str).await
+                    }
+                    MediaData::Spooled(ref file) => {
+                        self.media_storage.store_file(&processed.original_key, &file.path, &mime_str).await
+                    }
+                }
+                .map_err(|e| PostError::MediaError { reason: e.to_string() })?;
+                // Store thumbnail if present. Deferred work is submitted only
+                // now, under the prefixed key, so duplicates never queue one.
+                let thumbnail_data = match (&processed.thumbnail_key, processed.thumbnail_task.take()) {
+                    (Some(thumb_key), Some(task)) if processed.thumbnail_data.is_none() => task
+                        .submit(thumb_key.clone())
+                        .await
+                        .map_err(|e| PostError::MediaError { reason: e.to_string() })?,
+                    _ => processed.thumbnail_data.take(),
+                };
+                if let (Some(thumb_key), Some(thumb_data)) = (&processed.thumbnail_key, thumbnail_data) {
                     self.media_storage
-                        .store(thumb_key, thumb_data.clone(), "image/png")
+                        .store(thumb_key, thumb_data, ThumbnailFormat::of_key(thumb_key).mime())
                         .await
                         .map_err(|e| PostError::MediaError { reason: e.to_string() })?;
                 }
@@ -449,26 +824,35 @@ where
                 size_kb:       processed.size_kb,
                 media_key,
                 thumbnail_key,
-                spoiler:       false,
+                spoiler,
+                duration_ms:   processed.duration_ms,
+                page_count:    processed.page_count,
+                md5:           Some(processed.md5),
+                phash:         processed.phash,
+                meta:          Some(meta),
             });
         }
 
         // ── Step 9: Apply forced_anon + tripcode/capcode parsing ─────────────
-        // Parse the name field for `#` tripcode specifiers and `### Role` capcodes.
-        // forced_anon strips both name and tripcode.
+        // Parse the name field for `#` tripcode specifiers and `## Role` capcodes.
+        // forced_anon strips name, tripcode and capcode.
         // When the `auth-tripcode` feature is disabled, the name is stored as-is
         // and no tripcode or capcode processing occurs.
-        let (name, tripcode) = if board_config.forced_anon {
-            (None, None)
+        let (name, tripcode, capcode) = if board_config.forced_anon {
+            (None, None, None)
         } else {
             #[cfg(feature = "auth-tripcode")]
             let result = {
-                use crate::common::tripcode::parse_name_field;
+                use crate::common::tripcode::{parse_name_field_with, TripcodeOptions};
+                let options = TripcodeOptions {
+                    pepper:    &self.tripcode_pepper,
+                    algorithm: board_config.tripcode_algorithm,
+                };
                 match draft.name.as_deref() {
-                    None | Some("") => (None, None),
+                    None | Some("") => (None, None, None),
                     Some(raw_name) => {
-                        match parse_name_field(raw_name, draft.poster_role.as_ref(), &self.tripcode_pepper) {
-                            Ok(parsed) => (parsed.name, parsed.tripcode),
+                        match parse_name_field_with(raw_name, draft.poster_role.as_ref(), &options) {
+                            Ok(parsed) => (parsed.name, parsed.tripcode, parsed.capcode),
                             Err(crate::common::tripcode::NameParseError::CapcodePermissionDenied { .. }) => {
                                 return Err(PostError::Validation {
                                     reason: "capcode permission denied: you do not have that staff role".to_owned(),
@@ -485,8 +869,8 @@ where
             };
             #[cfg(not(feature = "auth-tripcode"))]
             let result = match draft.name.as_deref() {
-                None | Some("") => (None, None),
-                Some(raw) => (Some(raw.to_owned()), None),
+                None | Some("") => (None, None, None),
+                Some(raw) => (Some(raw.to_owned()), None, None),
             };
             result
         };
@@ -502,6 +886,12 @@ where
             created_at:  now_utc(),
             post_number: 0, // assigned atomically by the repository via board counter
             pinned:      false,
+            tags,        // from step 2b
+            commands,    // from step 3a
+            subject,     // from step 3
+            capcode,     // computed in step 9
+            deleted:     None,
+            edited_at:   None,
         };
         let (post_id, post_number) = self.post_repo.save(&post).await?;
         let post = Post { id: post_id, post_number, ..post };
@@ -512,28 +902,38 @@ where
                 .set_op_post(thread.id, post.id)
                 .await?;
         }
+        if let (Some(poll), Some(polls)) = (poll, self.polls.as_ref()) {
+            polls.save(&poll.into_poll(thread.id, thread.created_at)).await?;
+        }
 
         // ── Step 11: Bump thread ──────────────────────────────────────────────
-        // Sage: if allow_sage is true and email == "sage", skip the bump.
+        // Sage: if allow_sage is true and the options include "sage", skip the bump.
         let is_sage = board_config.allow_sage
-            && draft.email.as_deref() == Some("sage");
-        // Bump limit: past bump_limit replies, thread no longer bumps.
+            && has_option(draft.email.as_deref(), "sage");
+        // Bump limit: past bump_limit replies, thread no longer bumps — unless
+        // it is a cycle thread, which keeps bumping as it rolls over.
         let past_bump_limit = thread.reply_count >= board_config.bump_limit;
 
-        if !is_sage && !past_bump_limit && !is_new_thread {
+        if !is_sage && !shadowbanned && (!past_bump_limit || thread.cycle) && !is_new_thread {
             self.thread_repo.bump(thread.id, now_utc()).await?;
         }
 
         // ── Step 11b: Cycle mode pruning ──────────────────────────────────────
-        // When the thread is in cycle mode and past the bump limit, delete the
-        // oldest non-OP unpinned reply so the thread stays perpetually live.
-        if past_bump_limit && thread.cycle {
-            if let Ok(Some(oldest_id)) = self.post_repo
-                .find_oldest_unpinned_reply(thread.id)
+        // A cycle thread keeps a rolling window of bump_limit replies: once past
+        // the limit, every reply beyond the newest bump_limit is deleted, oldest
+        // first. Pinned replies are never pruned.
+        let mut cycled: Vec<PostId> = Vec::new();
+        if past_bump_limit && thread.cycle && !is_new_thread {
+            if let Ok(excess) = self.post_repo
+                .find_cycle_excess(thread.id, board_config.bump_limit)
                 .await
             {
                 // Best-effort: pruning failure never blocks the new post.
-                let _ = self.post_repo.delete_by_id(oldest_id).await;
+                for id in excess {
+                    if self.post_repo.delete_by_id(id).await.is_ok() {
+                        cycled.push(id);
+                    }
+                }
             }
         }
 
@@ -541,7 +941,7 @@ where
         // After a new OP is posted, prune the oldest non-sticky thread when the
         // board exceeds max_threads. When archive_enabled is true, candidates
         // are copied to the archive store before deletion (best-effort).
-        if is_new_thread && board_config.max_threads > 0 {
+        if is_new_thread && !shadowbanned && board_config.max_threads > 0 {
             let count = self.thread_repo.count_by_board(draft.board_id).await
                 .unwrap_or(0);
             if count > board_config.max_threads {
@@ -565,7 +965,7 @@ where
         }
 
         // ── Step 12: Increment rate limit counter ─────────────────────────────
-        if board_config.rate_limit_enabled && !draft.is_staff {
+        if board_config.rate_limit_enabled && !draft.is_staff && !draft.via_api_key {
             let key = RateLimitKey {
                 ip_hash:  draft.ip_hash.clone(),
                 board_id: draft.board_id,
@@ -596,9 +996,118 @@ where
             self.post_repo.save_attachments(&attachments).await?;
         }
 
+        // ── Step 13: Queue classifier hits for moderator review ───────────────
+        if !flagged_labels.is_empty() {
+            self.flag_for_review(post.id, &flagged_labels).await;
+        }
+
+        // ── Step 14: Publish events ───────────────────────────────────────────
+        if let Some(events) = &self.events {
+            if is_new_thread && !shadowbanned {
+                let thread = domains::models::Thread { op_post_id: Some(post.id), ..thread.clone() };
+                events.publish(DomainEvent::ThreadCreated { thread });
+            }
+            if !shadowbanned {
+                events.publish(DomainEvent::PostCreated { board_id: draft.board_id, post: post.clone() });
+            }
+            for post_id in cycled {
+                events.publish(DomainEvent::PostDeleted { board_id: draft.board_id, thread_id: thread.id, post_id });
+            }
+            for attachment in &attachments {
+                events.publish(DomainEvent::MediaUploaded {
+                    board_id:   draft.board_id,
+                    attachment: attachment.clone(),
+                });
+            }
+        }
+
         Ok(PostResult { post, thread, attachments })
     }
 
+    /// Run the attached classifier on one upload if the board asks for it.
+    /// Errors are logged and yield no labels.
+    async fn classify(
+        &self,
+        board_config: &BoardConfig,
+        mime_str: &str,
+        data: &MediaData,
+    ) -> Vec<domains::models::MediaLabel> {
+        let Some((classifier, _)) = self.classifier.as_ref() else { return Vec::new() };
+        if board_config.classifier_action == ClassifierAction::Off {
+            return Vec::new();
+        }
+        let mime = mime_str.parse().unwrap_or(mime::APPLICATION_OCTET_STREAM);
+        match classifier.classify(&mime, data).await {
+            Ok(labels) => labels,
+            Err(e) => {
+                warn!(error = %e, "media classifier failed; upload left unlabelled");
+                Vec::new()
+            }
+        }
+    }
+
+    /// Make room for `incoming` bytes under the board's media quota, pruning
+    /// the oldest threads (never `reply_to`) when `quota_action` is `Prune`.
+    ///
+    /// Pruned threads' files are left to the orphaned-media sweep, as with
+    /// capacity pruning; they stop counting against the quota immediately.
+    async fn enforce_media_quota(
+        &self,
+        board_config: &BoardConfig,
+        board_id: BoardId,
+        reply_to: Option<ThreadId>,
+        incoming: u64,
+    ) -> Result<(), PostError> {
+        let (Some(boards), Some(quota)) = (self.media_quota.as_ref(), board_config.media_quota_bytes()) else {
+            return Ok(());
+        };
+        let mut used = boards.media_usage_bytes(board_id).await?;
+        if used + incoming <= quota {
+            return Ok(());
+        }
+        if board_config.quota_action == QuotaAction::Prune {
+            let candidates = self.thread_repo.find_oldest_for_archive(board_id, QUOTA_PRUNE_LIMIT).await?;
+            let mut pruned = 0u32;
+            for thread in candidates.iter().filter(|t| Some(t.id) != reply_to) {
+                if board_config.archive_enabled {
+                    if let Some(ref archive) = self.archive_repo {
+                        let _ = archive.archive_thread(thread).await;
+                    }
+                }
+                self.thread_repo.delete(thread.id).await?;
+                pruned += 1;
+                used = boards.media_usage_bytes(board_id).await?;
+                if used + incoming <= quota {
+                    info!(board_id = %board_id, pruned, "threads pruned to fit the board media quota");
+                    return Ok(());
+                }
+            }
+            warn!(board_id = %board_id, pruned, "pruning could not make room under the board media quota");
+        }
+        info!(board_id = %board_id, used, incoming, quota, "upload rejected by board media quota");
+        Err(PostError::QuotaExceeded { used_bytes: used, quota_bytes: quota })
+    }
+
+    /// File one moderation flag listing the classifier labels that reached the
+    /// board threshold. Best-effort: the post is already saved.
+    async fn flag_for_review(&self, post_id: PostId, labels: &[domains::models::MediaLabel]) {
+        let Some((_, flags)) = self.classifier.as_ref() else { return };
+        let summary: Vec<String> = labels.iter().map(|l| format!("{} ({:.2})", l.label, l.score)).collect();
+        let flag = domains::models::Flag {
+            id:               domains::models::FlagId(Uuid::new_v4()),
+            post_id,
+            reason:           format!("media classifier: {}", summary.join(", ")),
+            reporter_ip_hash: IpHash::new(CLASSIFIER_REPORTER),
+            status:           domains::models::FlagStatus::Pending,
+            resolved_by:      None,
+            created_at:       now_utc(),
+        };
+        match flags.save(&flag).await {
+            Ok(flag_id) => info!(flag_id = %flag_id, post_id = %post_id, "upload flagged by media classifier"),
+            Err(e) => warn!(post_id = %post_id, error = %e, "failed to file media classifier flag"),
+        }
+    }
+
     /// `GET /board/:slug/thread/:id` — list posts in a thread, paginated.
     pub async fn list_posts(
         &self,
@@ -609,15 +1118,26 @@ where
         Ok(paginated)
     }
 
-    /// `GET /overboard` — recent posts across all boards, paginated.
+    /// `GET /overboard` — recent posts across the site's boards, paginated.
     pub async fn list_overboard(
         &self,
+        site: domains::models::SiteId,
         page: Page,
     ) -> Result<domains::models::Paginated<OverboardPost>, PostError> {
-        let paginated = self.post_repo.find_overboard(page).await?;
+        let paginated = self.post_repo.find_overboard(site, page).await?;
         Ok(paginated)
     }
 
+    /// `GET /` — the `recent` newest posts across the site's boards and the
+    /// site's post and thread totals.
+    pub async fn site_activity(
+        &self,
+        site: domains::models::SiteId,
+        recent: u32,
+    ) -> Result<domains::models::SiteActivity, PostError> {
+        Ok(self.post_repo.find_site_activity(site, recent).await?)
+    }
+
     /// Bulk-fetch attachments for a slice of post IDs, grouped by `PostId`.
     ///
     /// Delegates to `PostRepository::find_attachments_by_post_ids`. Used by the
@@ -630,6 +1150,130 @@ where
         self.post_repo.find_attachments_by_post_ids(post_ids).await
             .map_err(PostError::Internal)
     }
+
+    /// `GET /banned` — the active ban for `ip_hash`, if any.
+    ///
+    /// Lets a visitor see why they cannot post before they try to. A shadowban
+    /// is never shown: the poster is not meant to know about it.
+    pub async fn find_active_ban(
+        &self,
+        ip_hash: &IpHash,
+    ) -> Result<Option<domains::models::Ban>, PostError> {
+        Ok(self.ban_repo.find_active_by_ip(ip_hash).await?.filter(|ban| !ban.shadow))
+    }
+
+    /// The oldest warning `ip_hash` has yet to acknowledge, if any.
+    ///
+    /// Returns `Ok(None)` when warnings are not wired.
+    pub async fn find_pending_warning(
+        &self,
+        ip_hash: &IpHash,
+    ) -> Result<Option<domains::models::Warning>, PostError> {
+        match &self.warnings {
+            Some(warnings) => Ok(warnings.find_pending_by_ip(ip_hash).await?),
+            None => Ok(None),
+        }
+    }
+
+    /// `POST /warning/:id/ack` — acknowledge a warning, letting `ip_hash` post
+    /// again (unless another warning is pending).
+    ///
+    /// Returns `PostError::Internal(DomainError::NotFound)` unless `id` is a
+    /// pending warning for `ip_hash`.
+    pub async fn acknowledge_warning(
+        &self,
+        id: domains::models::BanId,
+        ip_hash: &IpHash,
+    ) -> Result<(), PostError> {
+        let Some(warnings) = &self.warnings else {
+            return Err(PostError::Internal(DomainError::not_found(id.to_string())));
+        };
+        warnings.acknowledge(id, ip_hash).await?;
+        info!(warning_id = %id, "warning acknowledged");
+        Ok(())
+    }
+
+    /// `POST /board/:slug/post/:id/delete` — a poster deletes their own post,
+    /// leaving a tombstone deleted by the user.
+    ///
+    /// The post must have been made from `ip_hash`, the daily hash the poster
+    /// has now, so it works from the same address on the same day. Its
+    /// attachment rows go at once; the media GC sweeps the files. Returns
+    /// `PostError::Internal(DomainError::NotFound)` if the post is not on
+    /// `board_id` or is already deleted, and `PostError::NotOwnPost` if it
+    /// was made from another hash.
+    #[instrument(skip(self, ip_hash), fields(post_id = %post_id, board_id = %board_id))]
+    pub async fn delete_own_post(
+        &self,
+        post_id: domains::models::PostId,
+        board_id: domains::models::BoardId,
+        ip_hash: &IpHash,
+    ) -> Result<(), PostError> {
+        let post = self.post_repo.find_by_id(post_id).await?;
+        let thread = self.thread_repo.find_by_id(post.thread_id).await?;
+        if thread.board_id != board_id || post.is_deleted() {
+            return Err(PostError::Internal(DomainError::not_found(post_id.to_string())));
+        }
+        if post.ip_hash != *ip_hash {
+            return Err(PostError::NotOwnPost);
+        }
+        self.post_repo.delete(post_id, domains::models::DeletedBy::User).await?;
+        if let Some(events) = &self.events {
+            events.publish(DomainEvent::PostDeleted { board_id, thread_id: thread.id, post_id });
+        }
+        info!(post_id = %post_id, "post deleted by its poster");
+        Ok(())
+    }
+
+    /// The active ban on the autonomous system announcing `raw_ip`, if any.
+    ///
+    /// Returns `Ok(None)` when ASN bans are not wired or the address cannot be
+    /// resolved (fail-open); ban store errors propagate.
+    pub async fn find_active_asn_ban(
+        &self,
+        raw_ip: &str,
+    ) -> Result<Option<domains::models::AsnBan>, PostError> {
+        let Some((resolver, repo)) = self.asn_bans.as_ref() else {
+            return Ok(None);
+        };
+        let asn = match resolver.lookup_asn(raw_ip).await {
+            Ok(Some(asn)) => asn,
+            Ok(None) => return Ok(None),
+            Err(e) => {
+                warn!(error = %e, "ASN lookup failed (fail-open)");
+                return Ok(None);
+            }
+        };
+        Ok(repo.find_active_by_asn(asn).await?)
+    }
+
+    /// The active hash ban matching an upload, if any.
+    ///
+    /// An exact MD5 match wins; otherwise the closest perceptual ban within the
+    /// configured distance. Returns `Ok(None)` when hash bans are not wired.
+    async fn find_hash_ban(
+        &self,
+        md5: &str,
+        phash: Option<u64>,
+    ) -> Result<Option<domains::models::HashBan>, PostError> {
+        let Some((repo, max_distance)) = self.hash_bans.as_ref() else {
+            return Ok(None);
+        };
+        if let Some(ban) = repo.find_active_by_md5(md5).await? {
+            return Ok(Some(ban));
+        }
+        let Some(phash) = phash else {
+            return Ok(None);
+        };
+        Ok(repo
+            .find_active_perceptual()
+            .await?
+            .into_iter()
+            .filter_map(|ban| Some(((ban.phash? ^ phash).count_ones(), ban)))
+            .filter(|(distance, _)| distance <= max_distance)
+            .min_by_key(|(distance, _)| *distance)
+            .map(|(_, ban)| ban))
+    }
 }
 
 #[cfg(test)]
@@ -686,9 +1330,15 @@ mod tests {
             raw_ip: None,
             name: None,
             email: None,
+            subject: None,
             files: vec![],
+            spoiler: false,
             is_staff: false,
             poster_role: None,
+            media_prefix: String::new(),
+            tags: vec![],
+            poll: None,
+            via_api_key: false,
         }
     }
 
@@ -738,69 +1388,679 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn create_post_banned_ip_rejected() {
+    async fn new_thread_keeps_trimmed_subject() {
         let mut ban_mock = MockBanRepository::new();
-        ban_mock.expect_find_active_by_ip().returning(|_| {
-            Ok(Some(domains::models::Ban {
-                id:         domains::models::BanId::new(),
-                ip_hash:    IpHash::new("abc123"),
-                banned_by:  domains::models::UserId::new(),
-                reason:     "spam".to_owned(),
-                expires_at: None,
-                created_at: Utc::now(),
-            }))
-        });
+        ban_mock.expect_find_active_by_ip().returning(|_| Ok(None));
+        let mut thread_mock = MockThreadRepository::new();
+        thread_mock.expect_save().returning(|t| Ok(t.id));
+        thread_mock.expect_set_op_post().returning(|_, _| Ok(()));
+        thread_mock.expect_count_by_board().returning(|_| Ok(0));
+        let mut post_mock = MockPostRepository::new();
+        post_mock
+            .expect_save()
+            .withf(|p| p.subject.as_deref() == Some("General"))
+            .returning(|p| Ok((p.id, 1)));
 
         let svc = make_post_service(
-            MockPostRepository::new(),
-            MockThreadRepository::new(),
+            post_mock,
+            thread_mock,
             ban_mock,
             MockMediaStorage::new(),
             MockRateLimiter::new(),
             MockMediaProcessor::new(),
         );
 
-        let result = svc
-            .create_post(text_draft(BoardId::new(), None), &permissive_config())
-            .await;
-        assert!(matches!(result, Err(PostError::Banned { .. })));
+        let mut draft = text_draft(BoardId::new(), None);
+        draft.subject = Some("  General ".to_owned());
+        assert!(svc.create_post(draft, &permissive_config()).await.is_ok());
     }
 
+    #[cfg(feature = "auth-tripcode")]
     #[tokio::test]
-    async fn create_post_rate_limited() {
+    async fn staff_capcode_is_stored_apart_from_tripcode() {
         let mut ban_mock = MockBanRepository::new();
         ban_mock.expect_find_active_by_ip().returning(|_| Ok(None));
-
-        let mut rl_mock = MockRateLimiter::new();
-        rl_mock.expect_check().returning(|_| {
-            Ok(RateLimitStatus::Exceeded { retry_after_secs: 30 })
-        });
+        let mut thread_mock = MockThreadRepository::new();
+        thread_mock.expect_save().returning(|t| Ok(t.id));
+        thread_mock.expect_set_op_post().returning(|_, _| Ok(()));
+        thread_mock.expect_count_by_board().returning(|_| Ok(0));
+        let mut post_mock = MockPostRepository::new();
+        post_mock
+            .expect_save()
+            .withf(|p| p.capcode.as_deref() == Some("Admin") && p.tripcode.is_none())
+            .returning(|p| Ok((p.id, 1)));
 
         let svc = make_post_service(
-            MockPostRepository::new(),
-            MockThreadRepository::new(),
+            post_mock,
+            thread_mock,
             ban_mock,
             MockMediaStorage::new(),
-            rl_mock,
+            MockRateLimiter::new(),
             MockMediaProcessor::new(),
         );
 
-        let config = BoardConfig { rate_limit_enabled: true, ..BoardConfig::default() };
-
-        let result = svc
-            .create_post(text_draft(BoardId::new(), None), &config)
-            .await;
-        assert!(matches!(result, Err(PostError::RateLimited { .. })));
+        let mut draft = text_draft(BoardId::new(), None);
+        draft.name = Some("## Admin".to_owned());
+        draft.poster_role = Some(domains::models::Role::Admin);
+        assert!(svc.create_post(draft, &permissive_config()).await.is_ok());
     }
 
+    #[cfg(feature = "auth-tripcode")]
     #[tokio::test]
-    async fn create_post_body_too_long() {
+    async fn crypt_board_uses_legacy_tripcodes() {
         let mut ban_mock = MockBanRepository::new();
         ban_mock.expect_find_active_by_ip().returning(|_| Ok(None));
+        let mut thread_mock = MockThreadRepository::new();
+        thread_mock.expect_save().returning(|t| Ok(t.id));
+        thread_mock.expect_set_op_post().returning(|_, _| Ok(()));
+        thread_mock.expect_count_by_board().returning(|_| Ok(0));
+        let mut post_mock = MockPostRepository::new();
+        post_mock
+            .expect_save()
+            .withf(|p| p.tripcode.as_deref() == Some("!Ep8pui8Vw2"))
+            .returning(|p| Ok((p.id, 1)));
 
         let svc = make_post_service(
-            MockPostRepository::new(),
-            MockThreadRepository::new(),
+            post_mock,
+            thread_mock,
+            ban_mock,
+            MockMediaStorage::new(),
+            MockRateLimiter::new(),
+            MockMediaProcessor::new(),
+        );
+
+        let mut config = permissive_config();
+        config.tripcode_algorithm = domains::models::TripcodeAlgorithm::Crypt;
+        let mut draft = text_draft(BoardId::new(), None);
+        draft.name = Some("#faggot".to_owned());
+        assert!(svc.create_post(draft, &config).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn overlong_subject_is_rejected() {
+        let mut ban_mock = MockBanRepository::new();
+        ban_mock.expect_find_active_by_ip().returning(|_| Ok(None));
+        let svc = make_post_service(
+            MockPostRepository::new(),
+            MockThreadRepository::new(),
+            ban_mock,
+            MockMediaStorage::new(),
+            MockRateLimiter::new(),
+            MockMediaProcessor::new(),
+        );
+
+        let mut draft = text_draft(BoardId::new(), None);
+        draft.subject = Some("x".repeat(MAX_SUBJECT_CHARS + 1));
+        let result = svc.create_post(draft, &permissive_config()).await;
+        assert!(matches!(result, Err(PostError::Validation { .. })));
+    }
+
+    #[test]
+    fn options_are_space_separated_words() {
+        assert!(has_option(Some("nonoko sage"), "sage"));
+        assert!(has_option(Some("SAGE"), "sage"));
+        assert!(!has_option(Some("sagebrush"), "sage"));
+        assert!(!has_option(None, "nonoko"));
+    }
+
+    #[tokio::test]
+    async fn create_post_saves_poll_with_new_thread() {
+        let mut ban_mock = MockBanRepository::new();
+        ban_mock.expect_find_active_by_ip().returning(|_| Ok(None));
+        let mut thread_mock = MockThreadRepository::new();
+        thread_mock.expect_save().returning(|t| Ok(t.id));
+        thread_mock.expect_set_op_post().returning(|_, _| Ok(()));
+        thread_mock.expect_count_by_board().returning(|_| Ok(0));
+        let mut post_mock = MockPostRepository::new();
+        post_mock.expect_save().returning(|p| Ok((p.id, 1)));
+        let mut poll_mock = domains::ports::MockPollRepository::new();
+        poll_mock
+            .expect_save()
+            .withf(|poll| poll.question == "Tabs?" && poll.options.len() == 2)
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let svc = make_post_service(
+            post_mock,
+            thread_mock,
+            ban_mock,
+            MockMediaStorage::new(),
+            MockRateLimiter::new(),
+            MockMediaProcessor::new(),
+        )
+        .with_polls(std::sync::Arc::new(poll_mock));
+        let mut draft = text_draft(BoardId::new(), None);
+        draft.poll = Some(crate::poll::PollDraft {
+            question: "Tabs?".to_owned(),
+            options:  vec!["yes".to_owned(), " ".to_owned(), "no".to_owned()],
+        });
+        let config = BoardConfig { polls_enabled: true, ..permissive_config() };
+
+        assert!(svc.create_post(draft, &config).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn create_post_poll_refused_on_reply_or_disabled_board() {
+        let mut ban_mock = MockBanRepository::new();
+        ban_mock.expect_find_active_by_ip().returning(|_| Ok(None));
+        let svc = make_post_service(
+            MockPostRepository::new(),
+            MockThreadRepository::new(),
+            ban_mock,
+            MockMediaStorage::new(),
+            MockRateLimiter::new(),
+            MockMediaProcessor::new(),
+        )
+        .with_polls(std::sync::Arc::new(domains::ports::MockPollRepository::new()));
+        let poll = crate::poll::PollDraft { question: "Tabs?".to_owned(), options: vec!["yes".into(), "no".into()] };
+        let enabled = BoardConfig { polls_enabled: true, ..permissive_config() };
+
+        let mut reply = text_draft(BoardId::new(), Some(ThreadId::new()));
+        reply.poll = Some(poll.clone());
+        assert!(matches!(svc.create_post(reply, &enabled).await, Err(PostError::Validation { .. })));
+
+        let mut op = text_draft(BoardId::new(), None);
+        op.poll = Some(poll);
+        assert!(matches!(svc.create_post(op, &permissive_config()).await, Err(PostError::Validation { .. })));
+    }
+
+    #[tokio::test]
+    async fn create_post_banned_ip_rejected() {
+        let mut ban_mock = MockBanRepository::new();
+        ban_mock.expect_find_active_by_ip().returning(|_| {
+            Ok(Some(domains::models::Ban {
+                id:         domains::models::BanId::new(),
+                ip_hash:    IpHash::new("abc123"),
+                banned_by:  domains::models::UserId::new(),
+                reason:     "spam".to_owned(),
+                expires_at: None,
+                created_at: Utc::now(),
+                shadow:     false,
+            }))
+        });
+
+        let svc = make_post_service(
+            MockPostRepository::new(),
+            MockThreadRepository::new(),
+            ban_mock,
+            MockMediaStorage::new(),
+            MockRateLimiter::new(),
+            MockMediaProcessor::new(),
+        );
+
+        let result = svc
+            .create_post(text_draft(BoardId::new(), None), &permissive_config())
+            .await;
+        assert!(matches!(result, Err(PostError::Banned { .. })));
+    }
+
+    /// An event bus mock recording the type tag of every published event.
+    fn recording_bus() -> (std::sync::Arc<dyn domains::ports::EventBus>, std::sync::Arc<std::sync::Mutex<Vec<String>>>) {
+        let seen = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut bus = domains::ports::MockEventBus::new();
+        let sink = seen.clone();
+        bus.expect_publish().returning(move |event| {
+            let tag = serde_json::to_value(&event).unwrap()["type"].as_str().unwrap().to_owned();
+            sink.lock().unwrap().push(tag);
+        });
+        (std::sync::Arc::new(bus), seen)
+    }
+
+    #[tokio::test]
+    async fn create_post_new_thread_publishes_events() {
+        let mut ban_mock = MockBanRepository::new();
+        ban_mock.expect_find_active_by_ip().returning(|_| Ok(None));
+        let mut thread_mock = MockThreadRepository::new();
+        thread_mock.expect_save().returning(|t| Ok(t.id));
+        thread_mock.expect_set_op_post().returning(|_, _| Ok(()));
+        thread_mock.expect_count_by_board().returning(|_| Ok(0));
+        let mut post_mock = MockPostRepository::new();
+        post_mock.expect_save().returning(|p| Ok((p.id, 1)));
+        let (bus, seen) = recording_bus();
+
+        let svc = make_post_service(
+            post_mock,
+            thread_mock,
+            ban_mock,
+            MockMediaStorage::new(),
+            MockRateLimiter::new(),
+            MockMediaProcessor::new(),
+        )
+        .with_events(bus);
+
+        svc.create_post(text_draft(BoardId::new(), None), &permissive_config()).await.unwrap();
+        assert_eq!(*seen.lock().unwrap(), ["thread_created", "post_created"]);
+    }
+
+    #[tokio::test]
+    async fn cycle_pruning_publishes_post_deleted() {
+        let board_id = BoardId::new();
+        let thread_id = ThreadId::new();
+        let mut ban_mock = MockBanRepository::new();
+        ban_mock.expect_find_active_by_ip().returning(|_| Ok(None));
+        let mut thread_mock = MockThreadRepository::new();
+        thread_mock.expect_find_by_id().returning(move |_| {
+            Ok(Thread {
+                id:          thread_id,
+                board_id,
+                op_post_id:  None,
+                reply_count: 500,
+                bumped_at:   Utc::now(),
+                sticky:      false,
+                closed:      false,
+                cycle:       true,
+                created_at:  Utc::now(),
+            })
+        });
+        thread_mock.expect_bump().returning(|_, _| Ok(()));
+        let mut post_mock = MockPostRepository::new();
+        post_mock.expect_save().returning(|p| Ok((p.id, 1)));
+        post_mock.expect_find_cycle_excess().returning(|_, _| Ok(vec![PostId::new(), PostId::new()]));
+        // The second delete fails and must not be reported as deleted.
+        let mut deletes = 0;
+        post_mock.expect_delete_by_id().returning(move |_| {
+            deletes += 1;
+            if deletes == 1 { Ok(()) } else { Err(DomainError::internal("gone")) }
+        });
+        let (bus, seen) = recording_bus();
+
+        let svc = make_post_service(
+            post_mock,
+            thread_mock,
+            ban_mock,
+            MockMediaStorage::new(),
+            MockRateLimiter::new(),
+            MockMediaProcessor::new(),
+        )
+        .with_events(bus);
+
+        let mut config = permissive_config();
+        config.bump_limit = 500;
+        svc.create_post(text_draft(board_id, Some(thread_id)), &config).await.unwrap();
+        assert_eq!(*seen.lock().unwrap(), ["post_created", "post_deleted"]);
+    }
+
+    #[tokio::test]
+    async fn rejected_post_publishes_nothing() {
+        let mut ban_mock = MockBanRepository::new();
+        ban_mock.expect_find_active_by_ip().returning(|_| Ok(None));
+        let (bus, seen) = recording_bus();
+
+        let svc = make_post_service(
+            MockPostRepository::new(),
+            MockThreadRepository::new(),
+            ban_mock,
+            MockMediaStorage::new(),
+            MockRateLimiter::new(),
+            MockMediaProcessor::new(),
+        )
+        .with_events(bus);
+
+        let mut draft = text_draft(BoardId::new(), None);
+        draft.body = "x".repeat(permissive_config().max_post_length as usize + 1);
+        assert!(svc.create_post(draft, &permissive_config()).await.is_err());
+        assert!(seen.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn shadowbanned_reply_is_saved_without_bump_or_events() {
+        let board_id = BoardId::new();
+        let thread_id = ThreadId::new();
+        let mut ban_mock = MockBanRepository::new();
+        ban_mock.expect_find_active_by_ip().returning(|ip_hash| {
+            Ok(Some(domains::models::Ban {
+                id:         domains::models::BanId::new(),
+                ip_hash:    ip_hash.clone(),
+                banned_by:  domains::models::UserId::new(),
+                reason:     "bot".to_owned(),
+                expires_at: None,
+                created_at: Utc::now(),
+                shadow:     true,
+            }))
+        });
+        // No `expect_bump`: a shadowbanned reply must not bump the thread.
+        let mut thread_mock = MockThreadRepository::new();
+        thread_mock.expect_find_by_id().returning(move |_| {
+            Ok(Thread {
+                id:          thread_id,
+                board_id,
+                op_post_id:  None,
+                reply_count: 1,
+                bumped_at:   Utc::now(),
+                sticky:      false,
+                closed:      false,
+                cycle:       false,
+                created_at:  Utc::now(),
+            })
+        });
+        let mut post_mock = MockPostRepository::new();
+        post_mock.expect_save().times(1).returning(|p| Ok((p.id, 2)));
+        let (bus, seen) = recording_bus();
+
+        let svc = make_post_service(
+            post_mock,
+            thread_mock,
+            ban_mock,
+            MockMediaStorage::new(),
+            MockRateLimiter::new(),
+            MockMediaProcessor::new(),
+        )
+        .with_events(bus);
+
+        svc.create_post(text_draft(board_id, Some(thread_id)), &permissive_config()).await.unwrap();
+        assert!(seen.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn pending_warning_blocks_posting_until_acknowledged() {
+        let mut ban_mock = MockBanRepository::new();
+        ban_mock.expect_find_active_by_ip().returning(|_| Ok(None));
+        let mut warnings = domains::ports::MockWarningRepository::new();
+        warnings.expect_find_pending_by_ip().returning(|ip_hash| {
+            Ok(Some(domains::models::Warning {
+                id:              domains::models::BanId::new(),
+                ip_hash:         ip_hash.clone(),
+                post_id:         None,
+                issued_by:       domains::models::UserId::new(),
+                reason:          "read the rules".to_owned(),
+                created_at:      Utc::now(),
+                acknowledged_at: None,
+            }))
+        });
+        // No `expect_save`: the post must be refused before it is stored.
+        let svc = make_post_service(
+            MockPostRepository::new(),
+            MockThreadRepository::new(),
+            ban_mock,
+            MockMediaStorage::new(),
+            MockRateLimiter::new(),
+            MockMediaProcessor::new(),
+        )
+        .with_warnings(std::sync::Arc::new(warnings));
+
+        let result = svc.create_post(text_draft(BoardId::new(), None), &permissive_config()).await;
+        assert!(matches!(result, Err(PostError::Warned { ref reason, .. }) if reason == "read the rules"));
+    }
+
+    /// A filter answering `verdict`, or failing when it is `None`.
+    fn filter(verdict: Option<FilterVerdict>) -> std::sync::Arc<dyn domains::ports::PostFilter> {
+        let mut filter = domains::ports::MockPostFilter::new();
+        filter.expect_name().return_const("test".to_owned());
+        filter
+            .expect_check()
+            .returning(move |_| verdict.clone().ok_or_else(|| DomainError::internal("plugin trapped")));
+        std::sync::Arc::new(filter)
+    }
+
+    /// A service that saves new threads, checking the saved body.
+    fn filtered_service(
+        expected_body: &'static str,
+        filters: Vec<std::sync::Arc<dyn domains::ports::PostFilter>>,
+    ) -> PostService<
+        MockPostRepository,
+        MockThreadRepository,
+        MockBanRepository,
+        MockMediaStorage,
+        MockRateLimiter,
+        MockMediaProcessor,
+    > {
+        let mut ban_mock = MockBanRepository::new();
+        ban_mock.expect_find_active_by_ip().returning(|_| Ok(None));
+        let mut thread_mock = MockThreadRepository::new();
+        thread_mock.expect_save().returning(|t| Ok(t.id));
+        thread_mock.expect_set_op_post().returning(|_, _| Ok(()));
+        thread_mock.expect_count_by_board().returning(|_| Ok(0));
+        let mut post_mock = MockPostRepository::new();
+        post_mock.expect_save().withf(move |p| p.body == expected_body).returning(|p| Ok((p.id, 1)));
+        make_post_service(
+            post_mock,
+            thread_mock,
+            ban_mock,
+            MockMediaStorage::new(),
+            MockRateLimiter::new(),
+            MockMediaProcessor::new(),
+        )
+        .with_filters(filters)
+    }
+
+    #[tokio::test]
+    async fn post_filters_rewrite_in_order() {
+        let mut second = domains::ports::MockPostFilter::new();
+        second.expect_name().return_const("second".to_owned());
+        second
+            .expect_check()
+            .withf(|p| p.body == "rewritten")
+            .returning(|p| Ok(FilterVerdict::Rewrite { body: format!("{} twice", p.body), tags: vec![] }));
+        let svc = filtered_service("rewritten twice", vec![
+            filter(Some(FilterVerdict::Rewrite { body: "rewritten".to_owned(), tags: vec![] })),
+            std::sync::Arc::new(second),
+        ]);
+
+        svc.create_post(text_draft(BoardId::new(), None), &permissive_config()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn draft_and_filter_tags_are_saved_on_the_post() {
+        let mut ban_mock = MockBanRepository::new();
+        ban_mock.expect_find_active_by_ip().returning(|_| Ok(None));
+        let mut thread_mock = MockThreadRepository::new();
+        thread_mock.expect_save().returning(|t| Ok(t.id));
+        thread_mock.expect_set_op_post().returning(|_, _| Ok(()));
+        thread_mock.expect_count_by_board().returning(|_| Ok(0));
+        let mut post_mock = MockPostRepository::new();
+        post_mock.expect_save().withf(|p| p.tags == ["link", "needs-review"]).returning(|p| Ok((p.id, 1)));
+        let tag = |tags: &[&str]| FilterVerdict::Accept { tags: tags.iter().map(|t| (*t).to_owned()).collect() };
+
+        let svc = make_post_service(
+            post_mock,
+            thread_mock,
+            ban_mock,
+            MockMediaStorage::new(),
+            MockRateLimiter::new(),
+            MockMediaProcessor::new(),
+        )
+        .with_filters(vec![
+            filter(Some(tag(&["needs-review", "link"]))),
+            filter(Some(tag(&[" "]))),
+        ]);
+
+        let mut draft = text_draft(BoardId::new(), None);
+        draft.tags = vec!["link".to_owned()];
+        svc.create_post(draft, &permissive_config()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn commands_run_only_when_the_board_enables_them() {
+        use domains::models::CommandResult;
+
+        for enabled in [true, false] {
+            let mut ban_mock = MockBanRepository::new();
+            ban_mock.expect_find_active_by_ip().returning(|_| Ok(None));
+            let mut thread_mock = MockThreadRepository::new();
+            thread_mock.expect_save().returning(|t| Ok(t.id));
+            thread_mock.expect_set_op_post().returning(|_, _| Ok(()));
+            thread_mock.expect_count_by_board().returning(|_| Ok(0));
+            let mut post_mock = MockPostRepository::new();
+            post_mock
+                .expect_save()
+                .withf(move |p| match p.commands.as_slice() {
+                    [CommandResult::Roll { rolls, .. }, CommandResult::Fortune { .. }] => {
+                        enabled && rolls.len() == 2 && rolls.iter().all(|r| (1..=6).contains(r))
+                    }
+                    [] => !enabled,
+                    _ => false,
+                })
+                .returning(|p| Ok((p.id, 1)));
+
+            let svc = make_post_service(
+                post_mock,
+                thread_mock,
+                ban_mock,
+                MockMediaStorage::new(),
+                MockRateLimiter::new(),
+                MockMediaProcessor::new(),
+            );
+            let mut draft = text_draft(BoardId::new(), None);
+            draft.body = "rolling for it\n!roll 2d6\n!fortune".to_owned();
+            let config = BoardConfig { commands_enabled: enabled, ..permissive_config() };
+            let result = svc.create_post(draft, &config).await.unwrap();
+            assert_eq!(result.post.commands.len(), if enabled { 2 } else { 0 });
+        }
+    }
+
+    #[tokio::test]
+    async fn post_filter_reject_stops_the_post() {
+        let mut ban_mock = MockBanRepository::new();
+        ban_mock.expect_find_active_by_ip().returning(|_| Ok(None));
+        let svc = make_post_service(
+            MockPostRepository::new(),
+            MockThreadRepository::new(),
+            ban_mock,
+            MockMediaStorage::new(),
+            MockRateLimiter::new(),
+            MockMediaProcessor::new(),
+        )
+        .with_filters(vec![filter(Some(FilterVerdict::Reject { reason: "no links".to_owned() }))]);
+
+        let result = svc.create_post(text_draft(BoardId::new(), None), &permissive_config()).await;
+        assert!(matches!(result, Err(PostError::Rejected { reason }) if reason == "no links"));
+    }
+
+    #[tokio::test]
+    async fn post_filter_error_is_fail_open() {
+        let svc = filtered_service("Hello world", vec![filter(None)]);
+
+        svc.create_post(text_draft(BoardId::new(), None), &permissive_config()).await.unwrap();
+    }
+
+    fn listed_dnsbl() -> std::sync::Arc<dyn domains::ports::DnsblChecker> {
+        let mut dnsbl = domains::ports::MockDnsblChecker::new();
+        dnsbl.expect_is_blocked().returning(|_| Ok(true));
+        std::sync::Arc::new(dnsbl)
+    }
+
+    #[tokio::test]
+    async fn create_post_dnsbl_listed_blocked() {
+        let mut ban_mock = MockBanRepository::new();
+        ban_mock.expect_find_active_by_ip().returning(|_| Ok(None));
+
+        let svc = make_post_service(
+            MockPostRepository::new(),
+            MockThreadRepository::new(),
+            ban_mock,
+            MockMediaStorage::new(),
+            MockRateLimiter::new(),
+            MockMediaProcessor::new(),
+        )
+        .with_dnsbl(listed_dnsbl());
+
+        let config = BoardConfig { spam_filter_enabled: true, ..permissive_config() };
+        let mut draft = text_draft(BoardId::new(), None);
+        draft.raw_ip = Some("192.0.2.1".to_owned());
+
+        let result = svc.create_post(draft, &config).await;
+        assert!(matches!(result, Err(PostError::Banned { .. })));
+    }
+
+    #[tokio::test]
+    async fn create_post_dnsbl_listed_scored_as_spam() {
+        let mut ban_mock = MockBanRepository::new();
+        ban_mock.expect_find_active_by_ip().returning(|_| Ok(None));
+
+        let svc = make_post_service(
+            MockPostRepository::new(),
+            MockThreadRepository::new(),
+            ban_mock,
+            MockMediaStorage::new(),
+            MockRateLimiter::new(),
+            MockMediaProcessor::new(),
+        )
+        .with_dnsbl(listed_dnsbl());
+
+        let config = BoardConfig {
+            spam_filter_enabled:  true,
+            spam_score_threshold: DNSBL_SPAM_PENALTY,
+            dnsbl_action:         DnsblAction::Score,
+            ..permissive_config()
+        };
+        let mut draft = text_draft(BoardId::new(), None);
+        draft.raw_ip = Some("192.0.2.1".to_owned());
+
+        let result = svc.create_post(draft, &config).await;
+        assert!(matches!(result, Err(PostError::SpamDetected { .. })));
+    }
+
+    #[tokio::test]
+    async fn create_post_banned_asn_rejected() {
+        let mut ban_mock = MockBanRepository::new();
+        ban_mock.expect_find_active_by_ip().returning(|_| Ok(None));
+
+        let mut resolver = domains::ports::MockAsnResolver::new();
+        resolver.expect_lookup_asn().returning(|_| Ok(Some(9009)));
+        let mut asn_repo = domains::ports::MockAsnBanRepository::new();
+        asn_repo.expect_find_active_by_asn().withf(|asn| *asn == 9009).returning(|asn| {
+            Ok(Some(domains::models::AsnBan {
+                id:         domains::models::BanId::new(),
+                asn,
+                banned_by:  domains::models::UserId::new(),
+                reason:     "datacenter abuse".to_owned(),
+                expires_at: None,
+                created_at: Utc::now(),
+            }))
+        });
+
+        let svc = make_post_service(
+            MockPostRepository::new(),
+            MockThreadRepository::new(),
+            ban_mock,
+            MockMediaStorage::new(),
+            MockRateLimiter::new(),
+            MockMediaProcessor::new(),
+        )
+        .with_asn_bans(std::sync::Arc::new(resolver), std::sync::Arc::new(asn_repo));
+
+        let mut draft = text_draft(BoardId::new(), None);
+        draft.raw_ip = Some("192.0.2.1".to_owned());
+
+        let result = svc.create_post(draft, &permissive_config()).await;
+        assert!(matches!(result, Err(PostError::Banned { reason, .. }) if reason == "datacenter abuse"));
+    }
+
+    #[tokio::test]
+    async fn create_post_rate_limited() {
+        let mut ban_mock = MockBanRepository::new();
+        ban_mock.expect_find_active_by_ip().returning(|_| Ok(None));
+
+        let mut rl_mock = MockRateLimiter::new();
+        rl_mock.expect_check().returning(|_| {
+            Ok(RateLimitStatus::Exceeded { retry_after_secs: 30 })
+        });
+
+        let svc = make_post_service(
+            MockPostRepository::new(),
+            MockThreadRepository::new(),
+            ban_mock,
+            MockMediaStorage::new(),
+            rl_mock,
+            MockMediaProcessor::new(),
+        );
+
+        let config = BoardConfig { rate_limit_enabled: true, ..BoardConfig::default() };
+
+        let result = svc
+            .create_post(text_draft(BoardId::new(), None), &config)
+            .await;
+        assert!(matches!(result, Err(PostError::RateLimited { .. })));
+    }
+
+    #[tokio::test]
+    async fn create_post_body_too_long() {
+        let mut ban_mock = MockBanRepository::new();
+        ban_mock.expect_find_active_by_ip().returning(|_| Ok(None));
+
+        let svc = make_post_service(
+            MockPostRepository::new(),
+            MockThreadRepository::new(),
             ban_mock,
             MockMediaStorage::new(),
             MockRateLimiter::new(),
@@ -1166,4 +2426,90 @@ mod tests {
             .await;
         assert!(result.is_ok());
     }
+
+    // ── delete_own_post ───────────────────────────────────────────────────────
+    fn own_post(thread_id: ThreadId, ip_hash: &str) -> Post {
+        Post {
+            id:          PostId::new(),
+            thread_id,
+            body:        "oops".to_owned(),
+            ip_hash:     IpHash::new(ip_hash),
+            name:        None,
+            tripcode:    None,
+            email:       None,
+            created_at:  Utc::now(),
+            post_number: 2,
+            pinned:      false,
+            tags:        vec![],
+            commands:    vec![],
+            subject:     None,
+            capcode:     None,
+            deleted:     None,
+            edited_at:   None,
+        }
+    }
+
+    fn delete_own_service(post: Post, board_id: BoardId, post_mock: MockPostRepository) -> PostService<
+        MockPostRepository,
+        MockThreadRepository,
+        MockBanRepository,
+        MockMediaStorage,
+        MockRateLimiter,
+        MockMediaProcessor,
+    > {
+        let mut post_mock = post_mock;
+        let thread_id = post.thread_id;
+        post_mock.expect_find_by_id().returning(move |_| Ok(post.clone()));
+        let mut thread_mock = MockThreadRepository::new();
+        thread_mock.expect_find_by_id().returning(move |_| Ok(sample_thread(board_id, thread_id)));
+        make_post_service(
+            post_mock,
+            thread_mock,
+            MockBanRepository::new(),
+            MockMediaStorage::new(),
+            MockRateLimiter::new(),
+            MockMediaProcessor::new(),
+        )
+    }
+
+    #[tokio::test]
+    async fn delete_own_post_leaves_a_user_tombstone() {
+        let board_id = BoardId::new();
+        let post = own_post(ThreadId::new(), "mine");
+        let post_id = post.id;
+        let mut post_mock = MockPostRepository::new();
+        post_mock
+            .expect_delete()
+            .withf(move |id, by| *id == post_id && *by == domains::models::DeletedBy::User)
+            .times(1)
+            .returning(|_, _| Ok(()));
+        let svc = delete_own_service(post, board_id, post_mock);
+
+        svc.delete_own_post(post_id, board_id, &IpHash::new("mine")).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn delete_own_post_refuses_other_posters_and_boards() {
+        let board_id = BoardId::new();
+        let post = own_post(ThreadId::new(), "mine");
+        let post_id = post.id;
+        let mut post_mock = MockPostRepository::new();
+        post_mock.expect_delete().never();
+        let svc = delete_own_service(post.clone(), board_id, post_mock);
+
+        let result = svc.delete_own_post(post_id, board_id, &IpHash::new("theirs")).await;
+        assert!(matches!(result, Err(PostError::NotOwnPost)));
+        let result = svc.delete_own_post(post_id, BoardId::new(), &IpHash::new("mine")).await;
+        assert!(matches!(result, Err(PostError::Internal(DomainError::NotFound { .. }))));
+
+        let deleted = Post {
+            deleted: Some(domains::models::Tombstone { by: domains::models::DeletedBy::Staff, at: Utc::now() }),
+            ..post
+        };
+        let mut post_mock = MockPostRepository::new();
+        post_mock.expect_delete().never();
+        let svc = delete_own_service(deleted, board_id, post_mock);
+        let result = svc.delete_own_post(post_id, board_id, &IpHash::new("mine")).await;
+        assert!(matches!(result, Err(PostError::Internal(DomainError::NotFound { .. }))));
+    }
 }
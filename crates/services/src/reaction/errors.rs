@@ -0,0 +1,34 @@
+//! Error type for `ReactionService` operations.
+
+use domains::errors::DomainError;
+use thiserror::Error;
+
+/// Errors that can occur in `ReactionService` methods.
+#[derive(Debug, Error)]
+pub enum ReactionError {
+    /// The post does not exist on this board.
+    #[error("post not found: {id}")]
+    PostNotFound {
+        /// The post that was asked for.
+        id: String,
+    },
+
+    /// The board does not have `reactions_enabled`.
+    #[error("reactions are disabled on this board")]
+    Disabled,
+
+    /// This poster has already reacted to the post; the first reaction stands.
+    #[error("already reacted to this post")]
+    AlreadyReacted,
+
+    /// Too many reactions from this poster; retry after the given delay.
+    #[error("rate limited; retry after {retry_after_secs}s")]
+    RateLimited {
+        /// Seconds until the poster may react again.
+        retry_after_secs: u32,
+    },
+
+    /// A domain-level error that could not be handled at this level.
+    #[error("internal error: {0}")]
+    Internal(#[from] DomainError),
+}
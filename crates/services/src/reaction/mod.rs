@@ -0,0 +1,221 @@
+//! `ReactionService` — reacting to posts with one of the fixed `Reaction`s.
+//!
+//! # One reaction per poster ID
+//! Reactions are keyed on the poster's IP hash, so each poster gets one
+//! reaction per post. A second reaction is refused rather than replacing the
+//! first.
+//!
+//! # Rate limiting
+//! Every attempt, refused or not, counts against the shared `RateLimiter`
+//! under a key of its own, so reacting never uses up the poster's post
+//! allowance. The limiter's per-window allowance applies to each
+//! `RATE_WINDOW_SECS` window.
+
+pub mod errors;
+pub use errors::ReactionError;
+
+use domains::errors::DomainError;
+use domains::models::{BoardConfig, BoardId, IpHash, PostId, Reaction, ReactionCount};
+use domains::ports::{
+    PostRepository, RateLimitKey, RateLimitStatus, RateLimiter, ReactionRepository, ThreadRepository,
+};
+use tracing::{info, instrument};
+
+/// Window, in seconds, of the reaction rate limit.
+pub const RATE_WINDOW_SECS: u32 = 20;
+
+/// Service for reacting to posts.
+///
+/// Generic over `RR: ReactionRepository`, `PR: PostRepository` and
+/// `TR: ThreadRepository` (to find a post's board) and `RL: RateLimiter`.
+pub struct ReactionService<RR, PR, TR, RL>
+where
+    RR: ReactionRepository,
+    PR: PostRepository,
+    TR: ThreadRepository,
+    RL: RateLimiter,
+{
+    repo:         RR,
+    post_repo:    PR,
+    thread_repo:  TR,
+    rate_limiter: RL,
+}
+
+impl<RR, PR, TR, RL> ReactionService<RR, PR, TR, RL>
+where
+    RR: ReactionRepository,
+    PR: PostRepository,
+    TR: ThreadRepository,
+    RL: RateLimiter,
+{
+    /// Construct a `ReactionService`.
+    pub fn new(repo: RR, post_repo: PR, thread_repo: TR, rate_limiter: RL) -> Self {
+        Self { repo, post_repo, thread_repo, rate_limiter }
+    }
+
+    /// React to a post on `board_id` and return its updated counts.
+    ///
+    /// # Errors
+    /// - `ReactionError::Disabled` — the board does not have `reactions_enabled`
+    /// - `ReactionError::RateLimited` — too many reactions from `voter`
+    /// - `ReactionError::PostNotFound` — no such post on this board, or it is deleted
+    /// - `ReactionError::AlreadyReacted` — `voter` has reacted to this post before
+    #[instrument(skip(self, voter, config), fields(post_id = %post_id, reaction = %reaction))]
+    pub async fn react(
+        &self,
+        post_id:  PostId,
+        reaction: Reaction,
+        voter:    &IpHash,
+        board_id: BoardId,
+        config:   &BoardConfig,
+    ) -> Result<Vec<ReactionCount>, ReactionError> {
+        if !config.reactions_enabled {
+            return Err(ReactionError::Disabled);
+        }
+
+        let key = RateLimitKey { ip_hash: IpHash::new(format!("reaction:{}", voter.0)), board_id };
+        if let RateLimitStatus::Exceeded { retry_after_secs } = self.rate_limiter.check(&key).await? {
+            return Err(ReactionError::RateLimited { retry_after_secs });
+        }
+        self.rate_limiter.increment(&key, RATE_WINDOW_SECS).await?;
+
+        let not_found = || ReactionError::PostNotFound { id: post_id.to_string() };
+        let post = self.post_repo.find_by_id(post_id).await.map_err(|e| match e {
+            DomainError::NotFound { .. } => not_found(),
+            other => other.into(),
+        })?;
+        let thread = self.thread_repo.find_by_id(post.thread_id).await?;
+        if thread.board_id != board_id || post.is_deleted() {
+            return Err(not_found());
+        }
+
+        match self.repo.react(post_id, reaction, voter).await {
+            Ok(true) => info!("reaction recorded"),
+            Ok(false) => return Err(ReactionError::AlreadyReacted),
+            Err(DomainError::NotFound { .. }) => return Err(not_found()),
+            Err(e) => return Err(e.into()),
+        }
+        Ok(self.repo.counts_by_posts(&[post_id]).await?.remove(&post_id).unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use domains::models::{Post, Thread, ThreadId};
+    use domains::ports::{MockPostRepository, MockRateLimiter, MockReactionRepository, MockThreadRepository};
+    use std::collections::HashMap;
+
+    fn enabled() -> BoardConfig {
+        BoardConfig { reactions_enabled: true, ..BoardConfig::default() }
+    }
+
+    fn post(id: PostId, thread_id: ThreadId) -> Post {
+        Post {
+            id,
+            thread_id,
+            body: String::new(),
+            ip_hash: IpHash::new("poster"),
+            name: None,
+            tripcode: None,
+            email: None,
+            created_at: Utc::now(),
+            post_number: 1,
+            pinned: false,
+            tags: vec![],
+            commands: vec![],
+            subject: None,
+            capcode: None,
+            deleted: None,
+            edited_at: None,
+        }
+    }
+
+    fn thread(id: ThreadId, board_id: BoardId) -> Thread {
+        Thread {
+            id,
+            board_id,
+            op_post_id: None,
+            reply_count: 0,
+            bumped_at: Utc::now(),
+            sticky: false,
+            closed: false,
+            cycle: false,
+            created_at: Utc::now(),
+        }
+    }
+
+    fn allowed() -> MockRateLimiter {
+        let mut rl = MockRateLimiter::new();
+        rl.expect_check().returning(|_| Ok(RateLimitStatus::Allowed { remaining: 2 }));
+        rl.expect_increment().returning(|_, _| Ok(()));
+        rl
+    }
+
+    /// A service whose post lives on `post_board`, with `react` returning `recorded`.
+    fn service(
+        post_board: BoardId,
+        recorded: bool,
+        rl: MockRateLimiter,
+    ) -> ReactionService<MockReactionRepository, MockPostRepository, MockThreadRepository, MockRateLimiter> {
+        let mut posts = MockPostRepository::new();
+        posts.expect_find_by_id().returning(|id| Ok(post(id, ThreadId::new())));
+        let mut threads = MockThreadRepository::new();
+        threads.expect_find_by_id().returning(move |id| Ok(thread(id, post_board)));
+        let mut repo = MockReactionRepository::new();
+        repo.expect_react().returning(move |_, _, _| Ok(recorded));
+        repo.expect_counts_by_posts().returning(|ids| {
+            Ok(HashMap::from([(ids[0], vec![ReactionCount { reaction: Reaction::Laugh, count: 1 }])]))
+        });
+        ReactionService::new(repo, posts, threads, rl)
+    }
+
+    #[tokio::test]
+    async fn react_returns_updated_counts() {
+        let board_id = BoardId::new();
+        let svc = service(board_id, true, allowed());
+        let counts = svc
+            .react(PostId::new(), Reaction::Laugh, &IpHash::new("voter"), board_id, &enabled())
+            .await
+            .unwrap();
+        assert_eq!(counts, [ReactionCount { reaction: Reaction::Laugh, count: 1 }]);
+    }
+
+    #[tokio::test]
+    async fn second_reaction_is_refused() {
+        let board_id = BoardId::new();
+        let svc = service(board_id, false, allowed());
+        let err = svc.react(PostId::new(), Reaction::Like, &IpHash::new("voter"), board_id, &enabled()).await;
+        assert!(matches!(err, Err(ReactionError::AlreadyReacted)));
+    }
+
+    #[tokio::test]
+    async fn post_on_another_board_is_not_found() {
+        let svc = service(BoardId::new(), true, allowed());
+        let err = svc.react(PostId::new(), Reaction::Like, &IpHash::new("voter"), BoardId::new(), &enabled()).await;
+        assert!(matches!(err, Err(ReactionError::PostNotFound { .. })));
+    }
+
+    #[tokio::test]
+    async fn rate_limited_reaction_is_refused() {
+        let mut rl = MockRateLimiter::new();
+        rl.expect_check()
+            .withf(|key| key.ip_hash.0 == "reaction:voter")
+            .returning(|_| Ok(RateLimitStatus::Exceeded { retry_after_secs: 7 }));
+        let board_id = BoardId::new();
+        let svc = service(board_id, true, rl);
+        let err = svc.react(PostId::new(), Reaction::Like, &IpHash::new("voter"), board_id, &enabled()).await;
+        assert!(matches!(err, Err(ReactionError::RateLimited { retry_after_secs: 7 })));
+    }
+
+    #[tokio::test]
+    async fn reacting_needs_reactions_enabled() {
+        let board_id = BoardId::new();
+        let svc = service(board_id, true, MockRateLimiter::new());
+        let err = svc
+            .react(PostId::new(), Reaction::Like, &IpHash::new("voter"), board_id, &BoardConfig::default())
+            .await;
+        assert!(matches!(err, Err(ReactionError::Disabled)));
+    }
+}
@@ -0,0 +1,211 @@
+//! `ReportAlerts` — tells moderators about new reports outside the site.
+//!
+//! An `EventSubscriber` for `FlagFiled`. Depending on `ReportMode`, each
+//! report is sent to every recipient as it arrives, or collected and sent as
+//! one digest by `send_digest`, which the composition root runs on an
+//! interval. Independently, a post whose pending reports reach
+//! `ReportAlertPolicy::threshold` raises a `ReportThreshold` at once.
+//!
+//! The digest is kept in memory: reports not yet sent when the server stops,
+//! or in a digest no recipient could receive, are only in the `/mod/flags`
+//! queue.
+
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use domains::errors::DomainError;
+use domains::models::Flag;
+use domains::ports::{DomainEvent, EventSubscriber, FlagRepository, Notifier, NotifyEvent};
+use tracing::{info, warn};
+
+/// When each report is sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportMode {
+    /// One notification per report.
+    Each,
+    /// Reports are collected for `send_digest`.
+    Digest,
+    /// Reports alone send nothing; only the threshold alerts.
+    Off,
+}
+
+/// What moderators are told about.
+#[derive(Debug, Clone, Copy)]
+pub struct ReportAlertPolicy {
+    pub mode:      ReportMode,
+    /// Pending reports on one post that raise a `ReportThreshold`. 0 turns
+    /// threshold alerts off.
+    pub threshold: u64,
+}
+
+/// Sends report notifications to a fixed list of recipients.
+///
+/// Generic over `FR: FlagRepository` (pending reports per post).
+pub struct ReportAlerts<FR: FlagRepository> {
+    notifier:   Arc<dyn Notifier>,
+    flag_repo:  FR,
+    recipients: Vec<String>,
+    policy:     ReportAlertPolicy,
+    digest:     Mutex<Vec<Flag>>,
+}
+
+impl<FR: FlagRepository> ReportAlerts<FR> {
+    /// Construct a `ReportAlerts` sending through `notifier` to `recipients`.
+    pub fn new(notifier: Arc<dyn Notifier>, flag_repo: FR, recipients: Vec<String>, policy: ReportAlertPolicy) -> Self {
+        Self { notifier, flag_repo, recipients, policy, digest: Mutex::new(Vec::new()) }
+    }
+
+    /// React to a new report: send or collect it, and check the threshold.
+    pub async fn report_filed(&self, flag: &Flag) -> Result<(), DomainError> {
+        match self.policy.mode {
+            ReportMode::Each => self.send(&NotifyEvent::ReportFiled { flag: flag.clone() }).await?,
+            ReportMode::Digest => self.digest.lock().expect("digest lock poisoned").push(flag.clone()),
+            ReportMode::Off => {}
+        }
+        if self.policy.threshold > 0 {
+            let reports = self.flag_repo.count_pending_for_post(flag.post_id).await?;
+            // Equal, not at least: one alert per post, not one per later report.
+            if reports == self.policy.threshold {
+                self.send(&NotifyEvent::ReportThreshold { post_id: flag.post_id, reports }).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Send the reports collected since the last digest, if any. Returns how
+    /// many were sent.
+    pub async fn send_digest(&self) -> Result<usize, DomainError> {
+        let flags = std::mem::take(&mut *self.digest.lock().expect("digest lock poisoned"));
+        if flags.is_empty() {
+            return Ok(0);
+        }
+        let count = flags.len();
+        self.send(&NotifyEvent::ReportDigest { flags }).await?;
+        info!(reports = count, "report digest sent");
+        Ok(count)
+    }
+
+    /// Send `event` to every recipient. One failed recipient does not stop
+    /// the others; the failures are returned together.
+    async fn send(&self, event: &NotifyEvent) -> Result<(), DomainError> {
+        let mut failures = Vec::new();
+        for recipient in &self.recipients {
+            if let Err(e) = self.notifier.notify(event, recipient).await {
+                warn!(recipient = %recipient, error = %e, "report notification failed");
+                failures.push(format!("{recipient}: {e}"));
+            }
+        }
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(DomainError::internal(format!("report notification failed for {}", failures.join("; "))))
+        }
+    }
+}
+
+#[async_trait]
+impl<FR: FlagRepository> EventSubscriber for ReportAlerts<FR> {
+    fn name(&self) -> &str {
+        "report-alerts"
+    }
+
+    async fn handle(&self, event: &DomainEvent) -> Result<(), DomainError> {
+        match event {
+            DomainEvent::FlagFiled { flag } => self.report_filed(flag).await,
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use domains::models::{FlagId, FlagStatus, IpHash, PostId};
+    use domains::ports::{MockFlagRepository, MockNotifier};
+
+    fn flag() -> Flag {
+        Flag {
+            id:               FlagId::new(),
+            post_id:          PostId::new(),
+            reason:           "spam".to_owned(),
+            reporter_ip_hash: IpHash::new("reporter"),
+            status:           FlagStatus::Pending,
+            resolved_by:      None,
+            created_at:       Utc::now(),
+        }
+    }
+
+    fn recipients() -> Vec<String> {
+        vec!["mods@example.com".to_owned(), "admin@example.com".to_owned()]
+    }
+
+    fn repo_counting(reports: u64) -> MockFlagRepository {
+        let mut repo = MockFlagRepository::new();
+        repo.expect_count_pending_for_post().returning(move |_| Ok(reports));
+        repo
+    }
+
+    #[tokio::test]
+    async fn each_report_goes_to_every_recipient() {
+        let mut notifier = MockNotifier::new();
+        notifier
+            .expect_notify()
+            .withf(|event, _| matches!(event, NotifyEvent::ReportFiled { flag } if flag.reason == "spam"))
+            .times(2)
+            .returning(|_, _| Ok(()));
+        let policy = ReportAlertPolicy { mode: ReportMode::Each, threshold: 0 };
+        let alerts = ReportAlerts::new(Arc::new(notifier), MockFlagRepository::new(), recipients(), policy);
+
+        alerts.handle(&DomainEvent::FlagFiled { flag: flag() }).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn threshold_alerts_once_when_reached() {
+        let mut notifier = MockNotifier::new();
+        notifier
+            .expect_notify()
+            .withf(|event, _| matches!(event, NotifyEvent::ReportThreshold { reports: 3, .. }))
+            .times(2)
+            .returning(|_, _| Ok(()));
+        let policy = ReportAlertPolicy { mode: ReportMode::Off, threshold: 3 };
+        let alerts = ReportAlerts::new(Arc::new(notifier), repo_counting(3), recipients(), policy);
+        alerts.report_filed(&flag()).await.unwrap();
+
+        let policy = ReportAlertPolicy { mode: ReportMode::Off, threshold: 3 };
+        let quiet = ReportAlerts::new(Arc::new(MockNotifier::new()), repo_counting(4), recipients(), policy);
+        quiet.report_filed(&flag()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn digest_sends_collected_reports_once() {
+        let mut notifier = MockNotifier::new();
+        notifier
+            .expect_notify()
+            .withf(|event, _| matches!(event, NotifyEvent::ReportDigest { flags } if flags.len() == 2))
+            .times(1)
+            .returning(|_, _| Ok(()));
+        let policy = ReportAlertPolicy { mode: ReportMode::Digest, threshold: 0 };
+        let recipients = vec!["mods@example.com".to_owned()];
+        let alerts = ReportAlerts::new(Arc::new(notifier), MockFlagRepository::new(), recipients, policy);
+        alerts.report_filed(&flag()).await.unwrap();
+        alerts.report_filed(&flag()).await.unwrap();
+
+        assert_eq!(alerts.send_digest().await.unwrap(), 2);
+        assert_eq!(alerts.send_digest().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn failed_recipients_do_not_stop_the_others() {
+        let mut notifier = MockNotifier::new();
+        notifier
+            .expect_notify()
+            .returning(|_, to| if to == "mods@example.com" { Err(DomainError::internal("refused")) } else { Ok(()) })
+            .times(2);
+        let policy = ReportAlertPolicy { mode: ReportMode::Each, threshold: 0 };
+        let alerts = ReportAlerts::new(Arc::new(notifier), MockFlagRepository::new(), recipients(), policy);
+
+        let err = alerts.report_filed(&flag()).await.unwrap_err();
+        assert!(err.to_string().contains("mods@example.com"));
+    }
+}
@@ -0,0 +1,48 @@
+//! Error type for `SiteService` operations.
+
+use domains::errors::DomainError;
+use thiserror::Error;
+
+/// Errors that can occur in `SiteService` methods.
+#[derive(Debug, Error)]
+pub enum SiteError {
+    /// No site claims the host.
+    #[error("no site for host '{host}'")]
+    NotFound {
+        /// The host that was looked up.
+        host: String,
+    },
+
+    /// The host is not a valid DNS name.
+    #[error("invalid host '{host}': expected a name like boards.example.org")]
+    InvalidHost {
+        /// The rejected host.
+        host: String,
+    },
+
+    /// The media prefix is not a valid directory name.
+    #[error("invalid media prefix '{prefix}': must match ^[a-z0-9_-]{{0,32}}$")]
+    InvalidPrefix {
+        /// The rejected prefix.
+        prefix: String,
+    },
+
+    /// The site name is empty or too long.
+    #[error("site name must be 1–64 characters")]
+    InvalidName,
+
+    /// The notice is longer than `MAX_NOTICE_CHARS`.
+    #[error("notice must be at most {max} characters")]
+    NoticeTooLong {
+        /// The limit.
+        max: usize,
+    },
+
+    /// A blank notice cannot be switched on.
+    #[error("notice is empty")]
+    EmptyNotice,
+
+    /// A domain-level error that could not be handled at the site service level.
+    #[error("{0}")]
+    Internal(#[from] DomainError),
+}
@@ -0,0 +1,234 @@
+//! `SiteService` — the imageboards one process serves, keyed by host name.
+//!
+//! Responsibilities:
+//! - Normalize `Host` header values and resolve them to a `Site`
+//! - Add, list and remove sites (with host and media prefix validation)
+//! - Set the site notice shown on every page (`Site::notice`)
+//!
+//! Requests for a host no site claims are served by the default site
+//! (`SiteId::DEFAULT`), so a single-site installation needs no configuration.
+
+pub mod errors;
+pub use errors::SiteError;
+
+use chrono::Utc;
+use domains::models::{Site, SiteId};
+use domains::ports::SiteRepository;
+use tracing::{info, instrument};
+
+/// Longest host name DNS allows.
+const MAX_HOST_LEN: usize = 253;
+
+/// Longest `Site::media_prefix`, matching the `sites` table CHECK.
+const MAX_PREFIX_LEN: usize = 32;
+
+/// Longest `Site::notice` in characters, matching the `sites` table CHECK.
+pub const MAX_NOTICE_CHARS: usize = 500;
+
+/// Lowercase `host` and strip any port and trailing dot, as a `Host` header
+/// or a `--site` argument may carry them. Returns `None` unless the result is
+/// a plausible DNS name or IP address.
+pub fn normalize_host(host: &str) -> Option<String> {
+    let host = host.trim();
+    let host = match host.strip_prefix('[') {
+        // Bracketed IPv6 literal, with or without a port.
+        Some(rest) => rest.split_once(']')?.0,
+        None => host.split(':').next().unwrap_or_default(),
+    };
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    let valid = !host.is_empty()
+        && host.len() <= MAX_HOST_LEN
+        && host.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'.' || b == b':');
+    valid.then_some(host)
+}
+
+/// The site serving `host`: the one claiming it, otherwise the default site.
+///
+/// `sites` is the full list from `SiteRepository::find_all`. Falls back to
+/// `Site::fallback()` should the default site's row be missing.
+pub fn resolve<'a>(sites: &'a [Site], host: Option<&str>) -> std::borrow::Cow<'a, Site> {
+    let host = host.and_then(normalize_host);
+    host.and_then(|host| sites.iter().find(|s| s.host == host))
+        .or_else(|| sites.iter().find(|s| s.id == SiteId::DEFAULT))
+        .map_or_else(|| std::borrow::Cow::Owned(Site::fallback()), std::borrow::Cow::Borrowed)
+}
+
+/// Service handling site management.
+///
+/// Generic over `SR: SiteRepository` so tests can inject a mock repository.
+pub struct SiteService<SR: SiteRepository> {
+    repo: SR,
+}
+
+impl<SR: SiteRepository> SiteService<SR> {
+    /// Construct a new `SiteService` with the given repository.
+    pub fn new(repo: SR) -> Self {
+        Self { repo }
+    }
+
+    /// Add a site served on `host`. Its uploads are stored under
+    /// `media_prefix`, which defaults to the host with dots replaced by dashes.
+    ///
+    /// Returns `SiteError::InvalidHost`, `InvalidPrefix` or `InvalidName` for
+    /// malformed input, and `SiteError::Internal` if another site has the host.
+    #[instrument(skip(self))]
+    pub async fn create_site(&self, host: &str, name: &str, media_prefix: Option<&str>) -> Result<Site, SiteError> {
+        let host = normalize_host(host).ok_or_else(|| SiteError::InvalidHost { host: host.to_owned() })?;
+        let media_prefix = match media_prefix {
+            Some(prefix) => prefix.to_owned(),
+            None => host.replace(['.', ':'], "-").chars().take(MAX_PREFIX_LEN).collect(),
+        };
+        let prefix_ok = media_prefix.len() <= MAX_PREFIX_LEN
+            && media_prefix.bytes().all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'_' || b == b'-');
+        if !prefix_ok {
+            return Err(SiteError::InvalidPrefix { prefix: media_prefix });
+        }
+        if name.is_empty() || name.chars().count() > 64 {
+            return Err(SiteError::InvalidName);
+        }
+
+        let site = Site {
+            id: SiteId::new(),
+            host,
+            name: name.to_owned(),
+            media_prefix,
+            created_at: Utc::now(),
+            notice: String::new(),
+            notice_enabled: false,
+        };
+        self.repo.save(&site).await?;
+        info!(site_id = %site.id, host = %site.host, "site created");
+        Ok(site)
+    }
+
+    /// The site claiming `host` exactly; unlike `resolve`, there is no fallback.
+    ///
+    /// Returns `SiteError::NotFound` if no site claims it.
+    #[instrument(skip(self))]
+    pub async fn get_by_host(&self, host: &str) -> Result<Site, SiteError> {
+        let normalized = normalize_host(host).ok_or_else(|| SiteError::InvalidHost { host: host.to_owned() })?;
+        self.repo
+            .find_by_host(&normalized)
+            .await?
+            .ok_or(SiteError::NotFound { host: normalized })
+    }
+
+    /// Every site, default first.
+    #[instrument(skip(self))]
+    pub async fn list_sites(&self) -> Result<Vec<Site>, SiteError> {
+        Ok(self.repo.find_all().await?)
+    }
+
+    /// Replace the site's notice and switch it on or off. The message is
+    /// trimmed; switching on a blank notice is refused.
+    ///
+    /// Returns `SiteError::NoticeTooLong` or `SiteError::EmptyNotice` for bad
+    /// input, and `SiteError::Internal` if the site does not exist.
+    #[instrument(skip(self, message), fields(site_id = %site_id))]
+    pub async fn set_notice(&self, site_id: SiteId, message: &str, enabled: bool) -> Result<Site, SiteError> {
+        let message = message.trim();
+        if message.chars().count() > MAX_NOTICE_CHARS {
+            return Err(SiteError::NoticeTooLong { max: MAX_NOTICE_CHARS });
+        }
+        if enabled && message.is_empty() {
+            return Err(SiteError::EmptyNotice);
+        }
+        let mut site = self.repo.find_by_id(site_id).await?;
+        site.notice = message.to_owned();
+        site.notice_enabled = enabled;
+        self.repo.save(&site).await?;
+        info!(site_id = %site.id, enabled, "site notice set");
+        Ok(site)
+    }
+
+    /// Delete the site claiming `host`. Its boards must be deleted first.
+    #[instrument(skip(self))]
+    pub async fn delete_site(&self, host: &str) -> Result<Site, SiteError> {
+        let site = self.get_by_host(host).await?;
+        self.repo.delete(site.id).await?;
+        info!(site_id = %site.id, host = %site.host, "site deleted");
+        Ok(site)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use domains::ports::MockSiteRepository;
+
+    fn site(host: &str) -> Site {
+        Site { id: SiteId::new(), host: host.to_owned(), ..Site::fallback() }
+    }
+
+    #[test]
+    fn hosts_are_normalized() {
+        assert_eq!(normalize_host("Boards.Example.org:8080").as_deref(), Some("boards.example.org"));
+        assert_eq!(normalize_host("example.org.").as_deref(), Some("example.org"));
+        assert_eq!(normalize_host("[::1]:3000").as_deref(), Some("::1"));
+        assert_eq!(normalize_host("127.0.0.1").as_deref(), Some("127.0.0.1"));
+        assert_eq!(normalize_host(""), None);
+        assert_eq!(normalize_host("bad host"), None);
+        assert_eq!(normalize_host("evil/../x"), None);
+    }
+
+    #[test]
+    fn unknown_hosts_resolve_to_the_default_site() {
+        let default = Site { name: "main".to_owned(), ..Site::fallback() };
+        let other = site("other.example");
+        let sites = vec![default, other.clone()];
+
+        assert_eq!(resolve(&sites, Some("OTHER.example:443")).id, other.id);
+        assert_eq!(resolve(&sites, Some("unknown.example")).name, "main");
+        assert_eq!(resolve(&sites, None).id, SiteId::DEFAULT);
+        assert_eq!(resolve(&[], Some("other.example")).id, SiteId::DEFAULT);
+    }
+
+    #[tokio::test]
+    async fn create_site_derives_the_media_prefix() {
+        let mut repo = MockSiteRepository::new();
+        repo.expect_save().times(1).returning(|_| Ok(()));
+        let svc = SiteService::new(repo);
+
+        let site = svc.create_site("Boards.Example.org", "Example", None).await.unwrap();
+        assert_eq!(site.host, "boards.example.org");
+        assert_eq!(site.media_prefix, "boards-example-org");
+    }
+
+    #[tokio::test]
+    async fn create_site_rejects_bad_input() {
+        let svc = SiteService::new(MockSiteRepository::new());
+        assert!(matches!(svc.create_site("no spaces", "x", None).await, Err(SiteError::InvalidHost { .. })));
+        assert!(matches!(svc.create_site("a.org", "x", Some("../up")).await, Err(SiteError::InvalidPrefix { .. })));
+        assert!(matches!(svc.create_site("a.org", "", None).await, Err(SiteError::InvalidName)));
+    }
+
+    #[tokio::test]
+    async fn set_notice_keeps_the_message_when_switched_off() {
+        let mut repo = MockSiteRepository::new();
+        repo.expect_find_by_id().returning(|_| Ok(Site::fallback()));
+        repo.expect_save()
+            .withf(|s| s.notice == "Maintenance at 02:00 UTC" && !s.notice_enabled)
+            .times(1)
+            .returning(|_| Ok(()));
+        let svc = SiteService::new(repo);
+
+        let site = svc.set_notice(SiteId::DEFAULT, "  Maintenance at 02:00 UTC ", false).await.unwrap();
+        assert_eq!(site.active_notice(), None);
+    }
+
+    #[tokio::test]
+    async fn set_notice_rejects_bad_input() {
+        let svc = SiteService::new(MockSiteRepository::new());
+        assert!(matches!(svc.set_notice(SiteId::DEFAULT, "  ", true).await, Err(SiteError::EmptyNotice)));
+        let long = "x".repeat(MAX_NOTICE_CHARS + 1);
+        assert!(matches!(svc.set_notice(SiteId::DEFAULT, &long, false).await, Err(SiteError::NoticeTooLong { .. })));
+    }
+
+    #[tokio::test]
+    async fn delete_site_needs_an_exact_host() {
+        let mut repo = MockSiteRepository::new();
+        repo.expect_find_by_host().returning(|_| Ok(None));
+        let svc = SiteService::new(repo);
+        assert!(matches!(svc.delete_site("gone.example").await, Err(SiteError::NotFound { .. })));
+    }
+}
@@ -0,0 +1,12 @@
+//! Error type for `StatsService` operations.
+
+use domains::errors::DomainError;
+use thiserror::Error;
+
+/// Errors that can occur in `StatsService` methods.
+#[derive(Debug, Error)]
+pub enum StatsError {
+    /// A domain-level error that could not be handled at this level.
+    #[error("internal error: {0}")]
+    Internal(#[from] DomainError),
+}
@@ -0,0 +1,284 @@
+//! `StatsService` — board activity statistics.
+//!
+//! The stats job calls `rollup`, which counts recent posts, posters and files
+//! into hourly and daily buckets per board (`StatsRepository`). The board
+//! stats page and the admin overview read those buckets back as gap-free
+//! series: an hour or day without a bucket had no posts.
+
+pub mod errors;
+pub use errors::StatsError;
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, DurationRound, Utc};
+use domains::models::{BoardId, SiteId, StatsBucket, StatsPeriod};
+use domains::ports::StatsRepository;
+use serde::Serialize;
+use tracing::{info, instrument};
+
+/// Hours shown on a board's stats page.
+pub const BOARD_STATS_HOURS: u32 = 24;
+
+/// Days shown on a board's stats page.
+pub const BOARD_STATS_DAYS: u32 = 30;
+
+/// Most days the admin overview covers.
+pub const MAX_OVERVIEW_DAYS: u32 = 90;
+
+/// Activity of one hour or day; zero when nothing was posted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct StatsPoint {
+    /// Start of the hour or day (UTC).
+    pub start:          DateTime<Utc>,
+    pub posts:          u64,
+    /// Distinct posters by IP hash. Summed over boards in the admin overview,
+    /// where someone posting on two boards counts twice.
+    pub unique_posters: u64,
+    pub media_count:    u64,
+    pub media_bytes:    u64,
+}
+
+impl StatsPoint {
+    fn add(&mut self, bucket: &StatsBucket) {
+        self.posts += bucket.posts;
+        self.unique_posters += bucket.unique_posters;
+        self.media_count += bucket.media_count;
+        self.media_bytes += bucket.media_bytes;
+    }
+}
+
+impl From<&StatsBucket> for StatsPoint {
+    fn from(b: &StatsBucket) -> Self {
+        Self {
+            start:          b.start,
+            posts:          b.posts,
+            unique_posters: b.unique_posters,
+            media_count:    b.media_count,
+            media_bytes:    b.media_bytes,
+        }
+    }
+}
+
+/// Posts and files over a whole series. Unique posters do not add up across
+/// hours or days, so they are left out.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct StatsTotals {
+    pub posts:       u64,
+    pub media_count: u64,
+    pub media_bytes: u64,
+}
+
+impl StatsTotals {
+    fn of<'a>(points: impl IntoIterator<Item = &'a StatsBucket>) -> Self {
+        points.into_iter().fold(Self::default(), |t, b| Self {
+            posts:       t.posts + b.posts,
+            media_count: t.media_count + b.media_count,
+            media_bytes: t.media_bytes + b.media_bytes,
+        })
+    }
+}
+
+/// What a board's stats page shows.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BoardStatsReport {
+    /// The last `BOARD_STATS_HOURS` hours, oldest first, the current one last.
+    pub hourly: Vec<StatsPoint>,
+    /// The last `BOARD_STATS_DAYS` days, oldest first, today last.
+    pub daily:  Vec<StatsPoint>,
+    /// Sums over `daily`.
+    pub totals: StatsTotals,
+}
+
+/// The admin overview of a site's boards.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SiteStatsOverview {
+    /// Each day of the window, summed over the site's boards, oldest first.
+    pub daily:  Vec<StatsPoint>,
+    /// Totals of each board with activity in the window, busiest first.
+    pub boards: Vec<(BoardId, StatsTotals)>,
+}
+
+/// Service for board statistics.
+///
+/// Generic over `SR: StatsRepository`.
+pub struct StatsService<SR: StatsRepository> {
+    repo: SR,
+}
+
+impl<SR: StatsRepository> StatsService<SR> {
+    /// Construct a `StatsService`.
+    pub fn new(repo: SR) -> Self {
+        Self { repo }
+    }
+
+    /// Recount the buckets of every board from the hour and day containing
+    /// `since` onwards. Returns the number of buckets written.
+    #[instrument(skip(self))]
+    pub async fn rollup(&self, since: DateTime<Utc>) -> Result<u64, StatsError> {
+        let written = self.repo.rollup(since).await?;
+        info!(written, "stats rolled up");
+        Ok(written)
+    }
+
+    /// A board's last `BOARD_STATS_HOURS` hours and `BOARD_STATS_DAYS` days
+    /// as of `now`.
+    #[instrument(skip(self), fields(board_id = %board_id))]
+    pub async fn board_report(&self, board_id: BoardId, now: DateTime<Utc>) -> Result<BoardStatsReport, StatsError> {
+        let first_hour = truncate(now, StatsPeriod::Hour) - Duration::hours(i64::from(BOARD_STATS_HOURS - 1));
+        let first_day = truncate(now, StatsPeriod::Day) - Duration::days(i64::from(BOARD_STATS_DAYS - 1));
+        let hours = self.repo.board_series(board_id, StatsPeriod::Hour, first_hour).await?;
+        let days = self.repo.board_series(board_id, StatsPeriod::Day, first_day).await?;
+        Ok(BoardStatsReport {
+            hourly: fill(&by_start(&hours), StatsPeriod::Hour, first_hour, BOARD_STATS_HOURS),
+            daily:  fill(&by_start(&days), StatsPeriod::Day, first_day, BOARD_STATS_DAYS),
+            totals: StatsTotals::of(&days),
+        })
+    }
+
+    /// The site's last `days` days as of `now`, clamped to
+    /// 1..=`MAX_OVERVIEW_DAYS`.
+    #[instrument(skip(self), fields(site_id = %site_id))]
+    pub async fn site_overview(
+        &self,
+        site_id: SiteId,
+        days: u32,
+        now: DateTime<Utc>,
+    ) -> Result<SiteStatsOverview, StatsError> {
+        let days = days.clamp(1, MAX_OVERVIEW_DAYS);
+        let first_day = truncate(now, StatsPeriod::Day) - Duration::days(i64::from(days - 1));
+        let buckets = self.repo.site_daily(site_id, first_day).await?;
+
+        let mut by_day: HashMap<DateTime<Utc>, StatsPoint> = HashMap::new();
+        let mut by_board: HashMap<BoardId, Vec<StatsBucket>> = HashMap::new();
+        for bucket in buckets {
+            by_day
+                .entry(bucket.start)
+                .and_modify(|day| day.add(&bucket))
+                .or_insert_with(|| StatsPoint::from(&bucket));
+            by_board.entry(bucket.board_id).or_default().push(bucket);
+        }
+
+        let mut boards: Vec<_> = by_board.into_iter().map(|(id, b)| (id, StatsTotals::of(&b))).collect();
+        boards.sort_by(|a, b| b.1.posts.cmp(&a.1.posts).then(a.0 .0.cmp(&b.0 .0)));
+        Ok(SiteStatsOverview { daily: fill(&by_day, StatsPeriod::Day, first_day, days), boards })
+    }
+}
+/// Start of the hour or day containing `at`.
+fn truncate(at: DateTime<Utc>, period: StatsPeriod) -> DateTime<Utc> {
+    at.duration_trunc(step(period)).unwrap_or(at)
+}
+
+fn step(period: StatsPeriod) -> Duration {
+    match period {
+        StatsPeriod::Hour => Duration::hours(1),
+        StatsPeriod::Day => Duration::days(1),
+    }
+}
+
+fn by_start(buckets: &[StatsBucket]) -> HashMap<DateTime<Utc>, StatsPoint> {
+    buckets.iter().map(|b| (b.start, StatsPoint::from(b))).collect()
+}
+
+/// `count` consecutive points of `period` from `first`, zero where `points`
+/// has none.
+fn fill(
+    points: &HashMap<DateTime<Utc>, StatsPoint>,
+    period: StatsPeriod,
+    first: DateTime<Utc>,
+    count: u32,
+) -> Vec<StatsPoint> {
+    (0..count)
+        .map(|i| {
+            let start = first + step(period) * i as i32;
+            points.get(&start).copied().unwrap_or(StatsPoint {
+                start,
+                posts: 0,
+                unique_posters: 0,
+                media_count: 0,
+                media_bytes: 0,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use domains::ports::MockStatsRepository;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    fn bucket(board_id: BoardId, period: StatsPeriod, start: &str, posts: u64) -> StatsBucket {
+        StatsBucket {
+            board_id,
+            period,
+            start: at(start),
+            posts,
+            unique_posters: 1,
+            media_count: posts / 2,
+            media_bytes: posts * 1000,
+        }
+    }
+
+    #[tokio::test]
+    async fn board_report_fills_quiet_hours_and_days() {
+        let board = BoardId::new();
+        let mut repo = MockStatsRepository::new();
+        repo.expect_board_series()
+            .withf(|_, period, since| *period == StatsPeriod::Hour && *since == at("2026-03-03T13:00:00Z"))
+            .returning(move |_, _, _| Ok(vec![bucket(board, StatsPeriod::Hour, "2026-03-04T12:00:00Z", 4)]));
+        repo.expect_board_series()
+            .withf(|_, period, since| *period == StatsPeriod::Day && *since == at("2026-02-03T00:00:00Z"))
+            .returning(move |_, _, _| Ok(vec![
+                bucket(board, StatsPeriod::Day, "2026-03-01T00:00:00Z", 6),
+                bucket(board, StatsPeriod::Day, "2026-03-04T00:00:00Z", 4),
+            ]));
+
+        let report = StatsService::new(repo).board_report(board, at("2026-03-04T12:34:00Z")).await.unwrap();
+        assert_eq!(report.hourly.len(), 24);
+        assert_eq!(report.hourly[0].start, at("2026-03-03T13:00:00Z"));
+        assert_eq!(report.hourly[23].posts, 4);
+        assert!(report.hourly[..23].iter().all(|p| p.posts == 0));
+        assert_eq!(report.daily.len(), 30);
+        assert_eq!(report.daily[29].start, at("2026-03-04T00:00:00Z"));
+        assert_eq!(report.daily[26].posts, 6);
+        assert_eq!(report.totals, StatsTotals { posts: 10, media_count: 5, media_bytes: 10_000 });
+    }
+
+    #[tokio::test]
+    async fn site_overview_sums_days_and_ranks_boards() {
+        let (quiet, busy) = (BoardId::new(), BoardId::new());
+        let mut repo = MockStatsRepository::new();
+        repo.expect_site_daily()
+            .withf(|_, since| *since == at("2026-03-03T00:00:00Z"))
+            .returning(move |_, _| Ok(vec![
+                bucket(quiet, StatsPeriod::Day, "2026-03-03T00:00:00Z", 1),
+                bucket(busy, StatsPeriod::Day, "2026-03-03T00:00:00Z", 5),
+                bucket(busy, StatsPeriod::Day, "2026-03-04T00:00:00Z", 2),
+            ]));
+
+        let overview = StatsService::new(repo)
+            .site_overview(SiteId::DEFAULT, 2, at("2026-03-04T08:00:00Z"))
+            .await
+            .unwrap();
+        let posts: Vec<_> = overview.daily.iter().map(|p| (p.posts, p.unique_posters)).collect();
+        assert_eq!(posts, [(6, 2), (2, 1)]);
+        let ranked: Vec<_> = overview.boards.iter().map(|(id, t)| (*id, t.posts)).collect();
+        assert_eq!(ranked, [(busy, 7), (quiet, 1)]);
+    }
+
+    #[tokio::test]
+    async fn site_overview_clamps_the_window() {
+        let mut repo = MockStatsRepository::new();
+        repo.expect_site_daily()
+            .withf(|_, since| *since == at("2025-12-05T00:00:00Z"))
+            .returning(|_, _| Ok(vec![]));
+        let overview = StatsService::new(repo)
+            .site_overview(SiteId::DEFAULT, 1000, at("2026-03-04T08:00:00Z"))
+            .await
+            .unwrap();
+        assert_eq!(overview.daily.len(), MAX_OVERVIEW_DAYS as usize);
+    }
+}
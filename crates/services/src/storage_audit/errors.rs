@@ -0,0 +1,12 @@
+//! Error type for `StorageAuditService` operations.
+
+use domains::errors::DomainError;
+use thiserror::Error;
+
+/// Errors that can occur while auditing stored media.
+#[derive(Debug, Error)]
+pub enum StorageAuditError {
+    /// Listing storage or loading the attachments failed; nothing was checked.
+    #[error("internal error: {0}")]
+    Internal(#[from] DomainError),
+}
@@ -0,0 +1,209 @@
+//! `StorageAuditService` — checks stored media against the attachment records.
+//!
+//! Every distinct original referenced by an attachment is looked up in the
+//! storage listing, fetched, and re-hashed; a SHA-256 that no longer matches
+//! `Attachment::hash` means the file was corrupted after upload. Thumbnails
+//! missing from storage are reported by their original's key so they can be
+//! handed to `ThumbnailRegenService::regenerate_only`.
+//!
+//! The audit only reads. Repairs and orphan removal are left to the existing
+//! services; `rusty-board storage verify [--fix]` runs all three.
+
+pub mod errors;
+pub use errors::StorageAuditError;
+
+use std::collections::HashSet;
+
+use domains::models::MediaKey;
+use domains::ports::{MediaStorage, PostRepository};
+use sha2::{Digest, Sha256};
+use tracing::{info, instrument, warn};
+
+/// Outcome of one audit.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AuditReport {
+    /// Objects found in storage.
+    pub stored:             usize,
+    /// Distinct originals referenced by attachments.
+    pub originals:          usize,
+    /// Originals present whose hash matches their attachment.
+    pub verified:           usize,
+    /// Originals whose contents no longer match their recorded hash.
+    pub corrupt:            Vec<MediaKey>,
+    /// Originals referenced by an attachment but absent from storage.
+    pub missing_originals:  Vec<MediaKey>,
+    /// Originals (present and intact) whose thumbnail is absent from storage.
+    pub missing_thumbnails: Vec<MediaKey>,
+    /// Originals listed in storage that could not be fetched.
+    pub unreadable:         usize,
+}
+
+/// Service for auditing stored media.
+///
+/// Generic over `PR: PostRepository` (attachment records) and
+/// `MS: MediaStorage` (the objects themselves).
+pub struct StorageAuditService<PR, MS>
+where
+    PR: PostRepository,
+    MS: MediaStorage,
+{
+    post_repo:     PR,
+    media_storage: MS,
+}
+
+impl<PR, MS> StorageAuditService<PR, MS>
+where
+    PR: PostRepository,
+    MS: MediaStorage,
+{
+    /// Construct a `StorageAuditService`.
+    pub fn new(post_repo: PR, media_storage: MS) -> Self {
+        Self { post_repo, media_storage }
+    }
+
+    /// Check every referenced original and thumbnail.
+    ///
+    /// Deduplicated uploads share one original, so each `media_key` is fetched
+    /// once. A thumbnail still being rendered in the background is reported as
+    /// missing; regenerating it is harmless.
+    #[instrument(skip(self))]
+    pub async fn verify(&self) -> Result<AuditReport, StorageAuditError> {
+        let stored: HashSet<MediaKey> = self.media_storage.list().await?.into_iter().map(|o| o.key).collect();
+        let attachments = self.post_repo.find_all_attachments().await?;
+
+        let mut report = AuditReport { stored: stored.len(), ..AuditReport::default() };
+        let mut seen = HashSet::new();
+        for (_, attachment) in attachments {
+            if !seen.insert(attachment.media_key.clone()) {
+                continue;
+            }
+            report.originals += 1;
+            let key = attachment.media_key;
+            if !stored.contains(&key) {
+                warn!(media_key = %key, "original missing from storage");
+                report.missing_originals.push(key);
+                continue;
+            }
+            let data = match self.media_storage.fetch(&key).await {
+                Ok(data) => data,
+                Err(e) => {
+                    warn!(media_key = %key, error = %e, "failed to read original");
+                    report.unreadable += 1;
+                    continue;
+                }
+            };
+            if hex::encode(Sha256::digest(&data)) != attachment.hash.as_str() {
+                warn!(media_key = %key, "original does not match its recorded hash");
+                report.corrupt.push(key);
+                continue;
+            }
+            report.verified += 1;
+            if attachment.thumbnail_key.is_some_and(|thumb| !stored.contains(&thumb)) {
+                report.missing_thumbnails.push(key);
+            }
+        }
+
+        info!(
+            stored = report.stored,
+            originals = report.originals,
+            verified = report.verified,
+            corrupt = report.corrupt.len(),
+            missing_originals = report.missing_originals.len(),
+            missing_thumbnails = report.missing_thumbnails.len(),
+            unreadable = report.unreadable,
+            "storage audit complete"
+        );
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+    use chrono::Utc;
+    use domains::models::{Attachment, BoardId, ContentHash, PostId};
+    use domains::ports::{MockMediaStorage, MockPostRepository, StoredMedia};
+
+    fn sha256(data: &[u8]) -> ContentHash {
+        ContentHash::new(hex::encode(Sha256::digest(data)))
+    }
+
+    fn attachment(media_key: &str, contents: &[u8]) -> (BoardId, Attachment) {
+        (BoardId::new(), Attachment {
+            id:            uuid::Uuid::new_v4(),
+            post_id:       PostId(uuid::Uuid::new_v4()),
+            filename:      media_key.to_owned(),
+            mime:          "image/png".to_owned(),
+            hash:          sha256(contents),
+            size_kb:       1,
+            media_key:     MediaKey::new(media_key),
+            thumbnail_key: Some(MediaKey::new(format!("{media_key}_thumb.png"))),
+            spoiler:       false,
+            duration_ms:   None,
+            page_count:    None,
+            md5:           None,
+            phash:         None,
+            meta:          None,
+        })
+    }
+
+    fn storage_with(keys: &'static [&'static str]) -> MockMediaStorage {
+        let mut storage = MockMediaStorage::new();
+        storage.expect_list().returning(move || {
+            Ok(keys.iter().map(|k| StoredMedia { key: MediaKey::new(*k), modified: Utc::now() }).collect())
+        });
+        storage.expect_fetch().returning(|key| match key.0.as_str() {
+            "bad.png" => Ok(Bytes::from_static(b"bit rot")),
+            "locked.png" => Err(domains::errors::DomainError::internal("permission denied")),
+            _ => Ok(Bytes::from_static(b"original")),
+        });
+        storage
+    }
+
+    #[tokio::test]
+    async fn reports_corrupt_missing_and_thumbless_originals() {
+        let mut posts = MockPostRepository::new();
+        posts.expect_find_all_attachments().returning(|| {
+            Ok(vec![
+                attachment("ok.png", b"original"),
+                attachment("ok.png", b"original"),
+                attachment("bad.png", b"original"),
+                attachment("gone.png", b"original"),
+                attachment("nothumb.png", b"original"),
+                attachment("locked.png", b"original"),
+            ])
+        });
+        let storage = storage_with(&[
+            "ok.png", "ok.png_thumb.png",
+            "bad.png", "bad.png_thumb.png",
+            "nothumb.png",
+            "locked.png", "locked.png_thumb.png",
+            "orphan.png",
+        ]);
+
+        let report = StorageAuditService::new(posts, storage).verify().await.unwrap();
+        assert_eq!(report, AuditReport {
+            stored:             8,
+            originals:          5,
+            verified:           2,
+            corrupt:            vec![MediaKey::new("bad.png")],
+            missing_originals:  vec![MediaKey::new("gone.png")],
+            missing_thumbnails: vec![MediaKey::new("nothumb.png")],
+            unreadable:         1,
+        });
+    }
+
+    #[tokio::test]
+    async fn missing_originals_are_not_fetched() {
+        let mut posts = MockPostRepository::new();
+        posts.expect_find_all_attachments().returning(|| Ok(vec![attachment("gone.png", b"x")]));
+        let mut storage = MockMediaStorage::new();
+        storage.expect_list().returning(|| Ok(vec![]));
+        storage.expect_fetch().never();
+
+        let report = StorageAuditService::new(posts, storage).verify().await.unwrap();
+        assert_eq!(report.missing_originals, vec![MediaKey::new("gone.png")]);
+        assert!(report.missing_thumbnails.is_empty());
+    }
+}
@@ -12,7 +12,9 @@ pub use errors::ThreadError;
 
 use async_trait::async_trait;
 use chrono::Utc;
-use domains::models::{BoardId, Page, Paginated, Post, Thread, ThreadId, ThreadSummary};
+use domains::models::{
+    BoardId, CatalogSort, OverboardThread, Page, Paginated, Post, SiteId, Thread, ThreadId, ThreadSummary, Viewer,
+};
 use domains::ports::{PostRepository, ThreadRepository};
 use tracing::{info, instrument, warn};
 use uuid::Uuid;
@@ -33,8 +35,24 @@ pub trait ThreadRepo: Send + Sync + 'static {
 
     /// Return all thread summaries for the board (OP body, thumbnail, reply count).
     ///
-    /// Used by catalog views and the board index. Results are ordered by `bumped_at` descending.
-    async fn get_catalog(&self, board_id: BoardId) -> Result<Vec<ThreadSummary>, ThreadError>;
+    /// Used by catalog views and the board index. Sticky threads come first,
+    /// then the rest in `sort` order. With a non-blank `filter`, only threads
+    /// whose OP subject or body contains it (ignoring case) are returned.
+    async fn get_catalog(
+        &self,
+        board_id: BoardId,
+        sort: CatalogSort,
+        filter: Option<&str>,
+    ) -> Result<Vec<ThreadSummary>, ThreadError>;
+
+    /// Catalog summaries (OP and reply count) of several threads of the board
+    /// at once, in the order of `ids`. Repeated ids are answered once; threads
+    /// that are gone, on another board or hidden from the catalog are left out.
+    async fn get_summaries(&self, board_id: BoardId, ids: &[ThreadId]) -> Result<Vec<ThreadSummary>, ThreadError>;
+
+    /// One page of the `/all` overboard catalog: threads across the site's
+    /// boards, most recently bumped first, without hidden boards.
+    async fn list_overboard_threads(&self, site: SiteId, page: Page) -> Result<Paginated<OverboardThread>, ThreadError>;
 
     /// Fetch a single thread by ID.
     ///
@@ -49,7 +67,8 @@ pub trait ThreadRepo: Send + Sync + 'static {
     /// All posts in a thread, ordered by `post_number ASC`, up to the bump limit (500).
     ///
     /// Used by the thread HTML view, which shows all posts without pagination.
-    async fn list_all_posts(&self, thread_id: ThreadId) -> Result<Vec<Post>, ThreadError>;
+    /// Shadowbanned posts are included only for staff and their own poster.
+    async fn list_all_posts(&self, thread_id: ThreadId, viewer: &Viewer) -> Result<Vec<Post>, ThreadError>;
 
     /// Resolve a board-scoped post number to the `ThreadId` that contains it.
     ///
@@ -69,6 +88,40 @@ pub trait ThreadRepo: Send + Sync + 'static {
         post_ids: &[domains::models::PostId],
     ) -> Result<std::collections::HashMap<domains::models::PostId, Vec<domains::models::Attachment>>, ThreadError>;
 
+    /// The thread's poll with current results, or `None` if it has none.
+    ///
+    /// Always `None` when no `PollRepository` is wired.
+    async fn find_poll(&self, thread_id: ThreadId) -> Result<Option<domains::models::Poll>, ThreadError>;
+
+    /// Reaction counts for each of `post_ids` that has any.
+    ///
+    /// Always empty when no `ReactionRepository` is wired.
+    async fn find_reactions(
+        &self,
+        post_ids: &[domains::models::PostId],
+    ) -> Result<std::collections::HashMap<domains::models::PostId, Vec<domains::models::ReactionCount>>, ThreadError>;
+
+    /// Record that `watcher` has read the thread up to `post_number` and
+    /// return whether the thread is on their watch list.
+    ///
+    /// Always `false` when no `WatchRepository` is wired.
+    async fn mark_watched_seen(
+        &self,
+        watcher: domains::models::WatcherToken,
+        thread_id: ThreadId,
+        post_number: u64,
+    ) -> Result<bool, ThreadError>;
+
+    /// The blotter of a board page: the newest `BLOTTER_ENTRIES`
+    /// announcements of the site and the board, newest first.
+    ///
+    /// Always empty when no `AnnouncementRepository` is wired.
+    async fn find_blotter(
+        &self,
+        site_id: domains::models::SiteId,
+        board_id: BoardId,
+    ) -> Result<Vec<domains::models::Announcement>, ThreadError>;
+
     /// Set or clear the sticky flag on a thread.
     ///
     /// Returns `ThreadError::NotFound` if the thread does not exist.
@@ -93,12 +146,28 @@ pub struct ThreadService<TR: ThreadRepository, PR: PostRepository> {
     post_repo: PR,
     /// Optional archive store. When set, pruned threads are archived before deletion.
     archive:   Option<std::sync::Arc<dyn domains::ports::ArchiveRepository>>,
+    /// Optional poll store, read by `find_poll` for the thread view.
+    polls:     Option<std::sync::Arc<dyn domains::ports::PollRepository>>,
+    /// Optional reaction store, read by `find_reactions` for the thread view.
+    reactions: Option<std::sync::Arc<dyn domains::ports::ReactionRepository>>,
+    /// Optional watch list store, updated by `mark_watched_seen` from the thread view.
+    watches:   Option<std::sync::Arc<dyn domains::ports::WatchRepository>>,
+    /// Optional announcement store, read by `find_blotter` for board pages.
+    announcements: Option<std::sync::Arc<dyn domains::ports::AnnouncementRepository>>,
 }
 
 impl<TR: ThreadRepository, PR: PostRepository> ThreadService<TR, PR> {
     /// Construct a new `ThreadService`.
     pub fn new(repo: TR, post_repo: PR) -> Self {
-        Self { repo, post_repo, archive: None }
+        Self {
+            repo,
+            post_repo,
+            archive: None,
+            polls: None,
+            reactions: None,
+            watches: None,
+            announcements: None,
+        }
     }
 
     /// Attach an `ArchiveRepository` so pruned threads are archived rather than deleted.
@@ -110,6 +179,35 @@ impl<TR: ThreadRepository, PR: PostRepository> ThreadService<TR, PR> {
         self
     }
 
+    /// Attach a `PollRepository` so `find_poll` can return thread polls.
+    pub fn with_polls(mut self, polls: std::sync::Arc<dyn domains::ports::PollRepository>) -> Self {
+        self.polls = Some(polls);
+        self
+    }
+
+    /// Attach a `ReactionRepository` so `find_reactions` can return post reactions.
+    pub fn with_reactions(mut self, reactions: std::sync::Arc<dyn domains::ports::ReactionRepository>) -> Self {
+        self.reactions = Some(reactions);
+        self
+    }
+
+    /// Attach a `WatchRepository` so viewing a thread marks it read on the
+    /// viewer's watch list.
+    pub fn with_watches(mut self, watches: std::sync::Arc<dyn domains::ports::WatchRepository>) -> Self {
+        self.watches = Some(watches);
+        self
+    }
+
+    /// Attach an `AnnouncementRepository` so `find_blotter` can return the
+    /// blotter shown on board pages.
+    pub fn with_announcements(
+        mut self,
+        announcements: std::sync::Arc<dyn domains::ports::AnnouncementRepository>,
+    ) -> Self {
+        self.announcements = Some(announcements);
+        self
+    }
+
     /// Allocate a new thread row for the given board.
     ///
     /// Returns the new `Thread`. The OP post is inserted separately by `PostService`,
@@ -145,13 +243,54 @@ impl<TR: ThreadRepository, PR: PostRepository> ThreadService<TR, PR> {
         Ok(self.repo.find_by_board(board_id, page).await?)
     }
 
-    /// Get all threads for catalog view (no pagination).
-    #[instrument(skip(self), fields(board_id = %board_id))]
+    /// Get all threads for catalog view (no pagination), in `sort` order and
+    /// narrowed to those whose OP subject or body contains `filter`.
+    #[instrument(skip(self), fields(board_id = %board_id, %sort))]
     pub async fn get_catalog(
         &self,
         board_id: BoardId,
+        sort: CatalogSort,
+        filter: Option<&str>,
     ) -> Result<Vec<ThreadSummary>, ThreadError> {
-        Ok(self.repo.find_catalog(board_id).await?)
+        let mut threads = self.repo.find_catalog(board_id, sort).await?;
+        if let Some(needle) = filter.map(str::trim).filter(|f| !f.is_empty()) {
+            let needle = needle.to_lowercase();
+            threads.retain(|t| {
+                t.op_subject.as_deref().is_some_and(|s| s.to_lowercase().contains(&needle))
+                    || t.op_body.to_lowercase().contains(&needle)
+            });
+        }
+        Ok(threads)
+    }
+
+    /// Summaries of the given threads of the board, in the order of `ids`,
+    /// without repeats or threads the board's catalog would not list.
+    #[instrument(skip(self, ids), fields(board_id = %board_id, count = ids.len()))]
+    pub async fn get_summaries(
+        &self,
+        board_id: BoardId,
+        ids: &[ThreadId],
+    ) -> Result<Vec<ThreadSummary>, ThreadError> {
+        let mut unique = Vec::with_capacity(ids.len());
+        for id in ids {
+            if !unique.contains(id) {
+                unique.push(*id);
+            }
+        }
+        let mut found = self.repo.find_summaries(board_id, &unique).await?;
+        found.sort_by_key(|s| unique.iter().position(|id| *id == s.thread_id));
+        Ok(found)
+    }
+
+    /// Threads across the site's boards for the `/all` overboard catalog,
+    /// most recently bumped first. Boards hidden from the overboard are left out.
+    #[instrument(skip(self), fields(site_id = %site, page = page.0))]
+    pub async fn list_overboard_threads(
+        &self,
+        site: SiteId,
+        page: Page,
+    ) -> Result<Paginated<OverboardThread>, ThreadError> {
+        Ok(self.repo.find_overboard_threads(site, page).await?)
     }
 
     /// Get a single thread by ID.
@@ -259,8 +398,19 @@ impl<TR: ThreadRepository, PR: PostRepository> ThreadRepo for ThreadService<TR,
     async fn list_threads(&self, board_id: BoardId, page: Page) -> Result<Paginated<Thread>, ThreadError> {
         self.list_threads(board_id, page).await
     }
-    async fn get_catalog(&self, board_id: BoardId) -> Result<Vec<ThreadSummary>, ThreadError> {
-        self.get_catalog(board_id).await
+    async fn get_catalog(
+        &self,
+        board_id: BoardId,
+        sort: CatalogSort,
+        filter: Option<&str>,
+    ) -> Result<Vec<ThreadSummary>, ThreadError> {
+        self.get_catalog(board_id, sort, filter).await
+    }
+    async fn get_summaries(&self, board_id: BoardId, ids: &[ThreadId]) -> Result<Vec<ThreadSummary>, ThreadError> {
+        self.get_summaries(board_id, ids).await
+    }
+    async fn list_overboard_threads(&self, site: SiteId, page: Page) -> Result<Paginated<OverboardThread>, ThreadError> {
+        self.list_overboard_threads(site, page).await
     }
     async fn get_thread(&self, id: ThreadId) -> Result<Thread, ThreadError> {
         self.get_thread(id).await
@@ -269,8 +419,8 @@ impl<TR: ThreadRepository, PR: PostRepository> ThreadRepo for ThreadService<TR,
         self.post_repo.find_by_thread(thread_id, page).await
             .map_err(ThreadError::Internal)
     }
-    async fn list_all_posts(&self, thread_id: ThreadId) -> Result<Vec<Post>, ThreadError> {
-        self.post_repo.find_all_by_thread(thread_id).await
+    async fn list_all_posts(&self, thread_id: ThreadId, viewer: &Viewer) -> Result<Vec<Post>, ThreadError> {
+        self.post_repo.find_visible_by_thread(thread_id, viewer).await
             .map_err(ThreadError::Internal)
     }
     async fn find_thread_id_by_post_number(
@@ -288,6 +438,45 @@ impl<TR: ThreadRepository, PR: PostRepository> ThreadRepo for ThreadService<TR,
         self.post_repo.find_attachments_by_post_ids(post_ids).await
             .map_err(ThreadError::Internal)
     }
+    async fn find_poll(&self, thread_id: ThreadId) -> Result<Option<domains::models::Poll>, ThreadError> {
+        match &self.polls {
+            Some(polls) => polls.find_by_thread(thread_id).await.map_err(ThreadError::Internal),
+            None => Ok(None),
+        }
+    }
+    async fn find_reactions(
+        &self,
+        post_ids: &[domains::models::PostId],
+    ) -> Result<std::collections::HashMap<domains::models::PostId, Vec<domains::models::ReactionCount>>, ThreadError> {
+        match &self.reactions {
+            Some(reactions) => reactions.counts_by_posts(post_ids).await.map_err(ThreadError::Internal),
+            None => Ok(std::collections::HashMap::new()),
+        }
+    }
+    async fn mark_watched_seen(
+        &self,
+        watcher: domains::models::WatcherToken,
+        thread_id: ThreadId,
+        post_number: u64,
+    ) -> Result<bool, ThreadError> {
+        match &self.watches {
+            Some(watches) => watches.mark_seen(watcher, thread_id, post_number).await.map_err(ThreadError::Internal),
+            None => Ok(false),
+        }
+    }
+    async fn find_blotter(
+        &self,
+        site_id: domains::models::SiteId,
+        board_id: BoardId,
+    ) -> Result<Vec<domains::models::Announcement>, ThreadError> {
+        match &self.announcements {
+            Some(announcements) => announcements
+                .list_for_board(site_id, board_id, crate::announcement::BLOTTER_ENTRIES)
+                .await
+                .map_err(ThreadError::Internal),
+            None => Ok(Vec::new()),
+        }
+    }
     async fn set_sticky(&self, id: ThreadId, sticky: bool) -> Result<(), ThreadError> {
         self.set_sticky(id, sticky).await
     }
@@ -328,6 +517,52 @@ mod tests {
         assert!(matches!(result, Err(ThreadError::NotFound { .. })));
     }
 
+    fn summary(subject: Option<&str>, body: &str) -> ThreadSummary {
+        ThreadSummary {
+            thread_id: ThreadId::new(), board_id: BoardId::new(), op_body: body.to_owned(), thumbnail_key: None,
+            thumbnail_spoiler: false, reply_count: 0, sticky: false, closed: false, bumped_at: Utc::now(),
+            op_name: None, op_tripcode: None, op_subject: subject.map(str::to_owned), op_capcode: None,
+            op_created_at: Utc::now(), op_post_number: 1, op_ip_hash: domains::models::IpHash::new("00".repeat(32)),
+        }
+    }
+
+    #[tokio::test]
+    async fn get_catalog_passes_sort_and_filters_by_subject_or_body() {
+        let mut mock = MockThreadRepository::new();
+        mock.expect_find_catalog()
+            .withf(|_, sort| *sort == CatalogSort::ReplyCount)
+            .times(2)
+            .returning(|_, _| Ok(vec![
+                summary(Some("Rust thread"), "general"),
+                summary(None, "all about RUST"),
+                summary(Some("cooking"), "pasta"),
+            ]));
+
+        let svc = ThreadService::new(mock, MockPostRepository::new());
+        let found = svc.get_catalog(BoardId::new(), CatalogSort::ReplyCount, Some(" rust ")).await.unwrap();
+        assert_eq!(found.len(), 2);
+        let blank = svc.get_catalog(BoardId::new(), CatalogSort::ReplyCount, Some("  ")).await.unwrap();
+        assert_eq!(blank.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn get_summaries_asks_once_per_thread_and_keeps_the_order() {
+        let (a, b, gone) = (ThreadId::new(), ThreadId::new(), ThreadId::new());
+        let mut mock = MockThreadRepository::new();
+        mock.expect_find_summaries()
+            .withf(move |_, ids| ids == [b, gone, a])
+            .times(1)
+            .returning(move |_, _| {
+                let with_id = |id| ThreadSummary { thread_id: id, ..summary(None, "") };
+                Ok(vec![with_id(a), with_id(b)])
+            });
+
+        let svc = ThreadService::new(mock, MockPostRepository::new());
+        let found = svc.get_summaries(BoardId::new(), &[b, gone, b, a]).await.unwrap();
+        let order: Vec<_> = found.iter().map(|s| s.thread_id).collect();
+        assert_eq!(order, [b, a]);
+    }
+
     #[tokio::test]
     async fn prune_if_needed_no_prune() {
         let mut mock = MockThreadRepository::new();
@@ -341,6 +576,30 @@ mod tests {
         assert_eq!(deleted, 0);
     }
 
+    #[tokio::test]
+    async fn find_poll_and_reactions_without_stores_are_empty() {
+        let svc = ThreadService::new(MockThreadRepository::new(), MockPostRepository::new());
+        assert!(ThreadRepo::find_poll(&svc, ThreadId::new()).await.unwrap().is_none());
+        assert!(ThreadRepo::find_reactions(&svc, &[domains::models::PostId::new()]).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn find_blotter_asks_for_the_newest_entries() {
+        let mut announcements = domains::ports::MockAnnouncementRepository::new();
+        announcements
+            .expect_list_for_board()
+            .withf(|_, _, limit| *limit == crate::announcement::BLOTTER_ENTRIES)
+            .returning(|_, _, _| Ok(Vec::new()));
+        let svc = ThreadService::new(MockThreadRepository::new(), MockPostRepository::new())
+            .with_announcements(std::sync::Arc::new(announcements));
+        let blotter = ThreadRepo::find_blotter(&svc, domains::models::SiteId::DEFAULT, BoardId::new()).await;
+        assert!(blotter.unwrap().is_empty());
+
+        let bare = ThreadService::new(MockThreadRepository::new(), MockPostRepository::new());
+        let blotter = ThreadRepo::find_blotter(&bare, domains::models::SiteId::DEFAULT, BoardId::new()).await;
+        assert!(blotter.unwrap().is_empty());
+    }
+
     #[tokio::test]
     async fn prune_if_needed_triggers_prune() {
         let mut mock = MockThreadRepository::new();
@@ -0,0 +1,12 @@
+//! Error type for `ThumbnailRegenService` operations.
+
+use domains::errors::DomainError;
+use thiserror::Error;
+
+/// Errors that can occur while regenerating thumbnails.
+#[derive(Debug, Error)]
+pub enum ThumbnailRegenError {
+    /// Loading the attachment list failed; nothing was regenerated.
+    #[error("internal error: {0}")]
+    Internal(#[from] DomainError),
+}
@@ -0,0 +1,346 @@
+//! `ThumbnailRegenService` — re-renders stored thumbnails with current settings.
+//!
+//! Thumbnail size, format and quality come from `Settings` and may be
+//! overridden per board (`BoardConfig::thumbnail`). Changing either only affects
+//! new uploads; this service brings existing attachments in line. Each stored
+//! original is fetched, run back through the `MediaProcessor` with its board's
+//! spec, and the new thumbnail is stored under a fresh key before the
+//! attachment rows are repointed. Keys are never rewritten in place, since media
+//! responses are cached as immutable.
+//!
+//! Old thumbnails are left in storage for `MediaGcService` to collect.
+//!
+//! Run on demand with `rusty-board regen-thumbs`; `rusty-board storage verify
+//! --fix` uses it to replace thumbnails missing from storage.
+
+pub mod errors;
+pub use errors::ThumbnailRegenError;
+
+use std::collections::{HashMap, HashSet};
+
+use domains::models::{Attachment, BoardId, MediaKey, ThumbnailFormat, ThumbnailSpec};
+use domains::ports::{BoardRepository, MediaData, MediaProcessor, MediaStorage, PostRepository, RawMedia};
+use tracing::{info, instrument, warn};
+
+/// Outcome of one regeneration run.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RegenReport {
+    /// Distinct stored originals that have a thumbnail.
+    pub scanned:     usize,
+    /// Thumbnails rendered, stored, and recorded on their attachments.
+    pub regenerated: usize,
+    /// Originals whose type the configured processor does not handle.
+    pub skipped:     usize,
+    /// Originals that could not be fetched, processed, or stored.
+    pub failed:      usize,
+}
+
+/// Service for regenerating thumbnails.
+///
+/// Generic over `PR: PostRepository` (attachments), `BR: BoardRepository`
+/// (per-board thumbnail overrides), `MS: MediaStorage`, and
+/// `MP: MediaProcessor`. The processor must render thumbnails inline — one
+/// built with a background queue would return no thumbnail data.
+pub struct ThumbnailRegenService<PR, BR, MS, MP>
+where
+    PR: PostRepository,
+    BR: BoardRepository,
+    MS: MediaStorage,
+    MP: MediaProcessor,
+{
+    post_repo:       PR,
+    board_repo:      BR,
+    media_storage:   MS,
+    media_processor: MP,
+}
+
+impl<PR, BR, MS, MP> ThumbnailRegenService<PR, BR, MS, MP>
+where
+    PR: PostRepository,
+    BR: BoardRepository,
+    MS: MediaStorage,
+    MP: MediaProcessor,
+{
+    /// Construct a `ThumbnailRegenService`.
+    pub fn new(post_repo: PR, board_repo: BR, media_storage: MS, media_processor: MP) -> Self {
+        Self { post_repo, board_repo, media_storage, media_processor }
+    }
+
+    /// Regenerate the thumbnail of every attachment that has one.
+    ///
+    /// Deduplicated uploads share one original, so each `media_key` is
+    /// rendered once, with the spec of the first board it was found on. A
+    /// failure on one original is logged and counted; the run continues.
+    #[instrument(skip(self))]
+    pub async fn regenerate(&self) -> Result<RegenReport, ThumbnailRegenError> {
+        self.run(None).await
+    }
+
+    /// Regenerate only the thumbnails of the originals in `media_keys`.
+    ///
+    /// Used by `rusty-board storage verify --fix` for thumbnails that have gone
+    /// missing from storage. Keys without a thumbnailed attachment are ignored.
+    #[instrument(skip(self, media_keys), fields(count = media_keys.len()))]
+    pub async fn regenerate_only(&self, media_keys: &HashSet<MediaKey>) -> Result<RegenReport, ThumbnailRegenError> {
+        self.run(Some(media_keys)).await
+    }
+
+    async fn run(&self, only: Option<&HashSet<MediaKey>>) -> Result<RegenReport, ThumbnailRegenError> {
+        let attachments = self.post_repo.find_thumbnailed_attachments().await?;
+
+        let mut originals: Vec<(BoardId, Attachment)> = Vec::new();
+        let mut seen = HashSet::new();
+        for (board_id, attachment) in attachments {
+            if only.is_some_and(|keys| !keys.contains(&attachment.media_key)) {
+                continue;
+            }
+            if seen.insert(attachment.media_key.clone()) {
+                originals.push((board_id, attachment));
+            }
+        }
+
+        let mut specs: HashMap<BoardId, Option<ThumbnailSpec>> = HashMap::new();
+        let mut report = RegenReport { scanned: originals.len(), ..RegenReport::default() };
+        for (board_id, attachment) in originals {
+            let mime = attachment.mime.parse().unwrap_or(mime::APPLICATION_OCTET_STREAM);
+            if !self.media_processor.accepts(&mime) {
+                report.skipped += 1;
+                continue;
+            }
+            let spec = match specs.get(&board_id) {
+                Some(spec) => *spec,
+                None => {
+                    // A board that cannot be loaded falls back to the global settings.
+                    let spec = self.board_repo.find_config(board_id).await.ok().and_then(|c| c.thumbnail);
+                    specs.insert(board_id, spec);
+                    spec
+                }
+            };
+            match self.regenerate_one(&attachment.media_key, mime, spec).await {
+                Ok(key) => {
+                    info!(media_key = %attachment.media_key, thumbnail_key = %key, "thumbnail regenerated");
+                    report.regenerated += 1;
+                }
+                Err(e) => {
+                    warn!(media_key = %attachment.media_key, error = %e, "failed to regenerate thumbnail");
+                    report.failed += 1;
+                }
+            }
+        }
+
+        info!(
+            scanned = report.scanned,
+            regenerated = report.regenerated,
+            skipped = report.skipped,
+            failed = report.failed,
+            "thumbnail regeneration complete"
+        );
+        Ok(report)
+    }
+
+    /// Render, store and record one thumbnail; returns its new key.
+    async fn regenerate_one(
+        &self,
+        media_key: &MediaKey,
+        mime: mime::Mime,
+        spec: Option<ThumbnailSpec>,
+    ) -> Result<MediaKey, domains::errors::DomainError> {
+        let data = self.media_storage.fetch(media_key).await?;
+        // The stored original was already stripped (or deliberately kept) at
+        // upload time; processing it again must not change it.
+        let processed = self
+            .media_processor
+            .process(RawMedia {
+                filename:       media_key.to_string(),
+                mime,
+                data:           MediaData::Memory(data),
+                strip_metadata: false,
+                thumbnail:      spec,
+            })
+            .await?;
+        let (Some(key), Some(data)) = (processed.thumbnail_key, processed.thumbnail_data) else {
+            return Err(domains::errors::DomainError::media_processing("no thumbnail was produced"));
+        };
+        // Keep the thumbnail under the same site prefix as its original.
+        let key = key.with_prefix(media_key.prefix());
+        self.media_storage.store(&key, data, ThumbnailFormat::of_key(&key).mime()).await?;
+        self.post_repo.set_thumbnail_key(media_key, &key).await?;
+        Ok(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+    use domains::models::{BoardConfig, ContentHash, PostId};
+    use domains::ports::{MockBoardRepository, MockMediaProcessor, MockMediaStorage, MockPostRepository, ProcessedMedia};
+
+    fn attachment(media_key: &str, mime: &str) -> Attachment {
+        Attachment {
+            id:            uuid::Uuid::new_v4(),
+            post_id:       PostId(uuid::Uuid::new_v4()),
+            filename:      media_key.to_owned(),
+            mime:          mime.to_owned(),
+            hash:          ContentHash::new("h"),
+            size_kb:       1,
+            media_key:     MediaKey::new(media_key),
+            thumbnail_key: Some(MediaKey::new(format!("{media_key}_thumb.png"))),
+            spoiler:       false,
+            duration_ms:   None,
+            page_count:    None,
+            md5:           None,
+            phash:         None,
+            meta:          None,
+        }
+    }
+
+    fn processed(thumbnail_key: &str) -> ProcessedMedia {
+        ProcessedMedia {
+            original_key:   MediaKey::new("ignored.jpg"),
+            original_data:  MediaData::Memory(Bytes::new()),
+            thumbnail_key:  Some(MediaKey::new(thumbnail_key)),
+            thumbnail_data: Some(Bytes::from_static(b"thumb")),
+            thumbnail_task: None,
+            hash:           ContentHash::new("h"),
+            size_kb:        1,
+            duration_ms:    None,
+            page_count:     None,
+            md5:            String::new(),
+            phash:          None,
+            width:          None,
+            height:         None,
+            stored_mime:    None,
+        }
+    }
+
+    fn processor_accepting_images() -> MockMediaProcessor {
+        let mut processor = MockMediaProcessor::new();
+        processor.expect_accepts().returning(|m| m.type_() == mime::IMAGE);
+        processor
+    }
+
+    #[tokio::test]
+    async fn shared_originals_are_rendered_once_with_the_board_spec() {
+        let board = BoardId::new();
+        let spec = ThumbnailSpec { max_width: 150, max_height: 150, format: ThumbnailFormat::Jpeg, quality: 70 };
+
+        let mut posts = MockPostRepository::new();
+        posts.expect_find_thumbnailed_attachments().returning(move || {
+            Ok(vec![(board, attachment("a.jpg", "image/jpeg")), (board, attachment("a.jpg", "image/jpeg"))])
+        });
+        posts.expect_set_thumbnail_key()
+            .withf(|media, thumb| media.0 == "a.jpg" && thumb.0 == "new_thumb.jpg")
+            .times(1)
+            .returning(|_, _| Ok(2));
+
+        let mut boards = MockBoardRepository::new();
+        boards.expect_find_config()
+            .times(1)
+            .returning(move |_| Ok(BoardConfig { thumbnail: Some(spec), ..BoardConfig::default() }));
+
+        let mut storage = MockMediaStorage::new();
+        storage.expect_fetch().returning(|_| Ok(Bytes::from_static(b"original")));
+        storage.expect_store()
+            .withf(|key, _, content_type| key.0 == "new_thumb.jpg" && content_type == "image/jpeg")
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+
+        let mut processor = processor_accepting_images();
+        processor.expect_process()
+            .withf(move |raw| raw.thumbnail == Some(spec) && !raw.strip_metadata)
+            .times(1)
+            .returning(|_| Ok(processed("new_thumb.jpg")));
+
+        let svc = ThumbnailRegenService::new(posts, boards, storage, processor);
+        let report = svc.regenerate().await.unwrap();
+        assert_eq!(report, RegenReport { scanned: 1, regenerated: 1, skipped: 0, failed: 0 });
+    }
+
+    #[tokio::test]
+    async fn thumbnails_stay_under_the_original_site_prefix() {
+        let board = BoardId::new();
+        let mut posts = MockPostRepository::new();
+        posts.expect_find_thumbnailed_attachments()
+            .returning(move || Ok(vec![(board, attachment("example/a.jpg", "image/jpeg"))]));
+        posts.expect_set_thumbnail_key()
+            .withf(|media, thumb| media.0 == "example/a.jpg" && thumb.0 == "example/new_thumb.jpg")
+            .times(1)
+            .returning(|_, _| Ok(1));
+
+        let mut boards = MockBoardRepository::new();
+        boards.expect_find_config().returning(|_| Ok(BoardConfig::default()));
+
+        let mut storage = MockMediaStorage::new();
+        storage.expect_fetch().returning(|_| Ok(Bytes::from_static(b"original")));
+        storage.expect_store()
+            .withf(|key, _, _| key.0 == "example/new_thumb.jpg")
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+
+        let mut processor = processor_accepting_images();
+        processor.expect_process().returning(|_| Ok(processed("new_thumb.jpg")));
+
+        let svc = ThumbnailRegenService::new(posts, boards, storage, processor);
+        assert_eq!(svc.regenerate().await.unwrap().regenerated, 1);
+    }
+
+    #[tokio::test]
+    async fn missing_originals_and_unhandled_types_do_not_stop_the_run() {
+        let board = BoardId::new();
+        let mut posts = MockPostRepository::new();
+        posts.expect_find_thumbnailed_attachments().returning(move || {
+            Ok(vec![
+                (board, attachment("gone.png", "image/png")),
+                (board, attachment("clip.webm", "video/webm")),
+                (board, attachment("ok.png", "image/png")),
+            ])
+        });
+        posts.expect_set_thumbnail_key().times(1).returning(|_, _| Ok(1));
+
+        let mut boards = MockBoardRepository::new();
+        boards.expect_find_config().returning(|_| Ok(BoardConfig::default()));
+
+        let mut storage = MockMediaStorage::new();
+        storage.expect_fetch().returning(|key| match key.0.as_str() {
+            "ok.png" => Ok(Bytes::from_static(b"original")),
+            other => Err(domains::errors::DomainError::not_found(other)),
+        });
+        storage.expect_store().returning(|_, _, _| Ok(()));
+
+        let mut processor = processor_accepting_images();
+        processor.expect_process().times(1).returning(|_| Ok(processed("ok2_thumb.png")));
+
+        let svc = ThumbnailRegenService::new(posts, boards, storage, processor);
+        let report = svc.regenerate().await.unwrap();
+        assert_eq!(report, RegenReport { scanned: 3, regenerated: 1, skipped: 1, failed: 1 });
+    }
+
+    #[tokio::test]
+    async fn regenerate_only_renders_the_requested_originals() {
+        let board = BoardId::new();
+        let mut posts = MockPostRepository::new();
+        posts.expect_find_thumbnailed_attachments().returning(move || {
+            Ok(vec![(board, attachment("a.png", "image/png")), (board, attachment("b.png", "image/png"))])
+        });
+        posts.expect_set_thumbnail_key()
+            .withf(|media, _| media.0 == "b.png")
+            .times(1)
+            .returning(|_, _| Ok(1));
+
+        let mut boards = MockBoardRepository::new();
+        boards.expect_find_config().returning(|_| Ok(BoardConfig::default()));
+
+        let mut storage = MockMediaStorage::new();
+        storage.expect_fetch().withf(|key| key.0 == "b.png").returning(|_| Ok(Bytes::from_static(b"original")));
+        storage.expect_store().returning(|_, _, _| Ok(()));
+
+        let mut processor = processor_accepting_images();
+        processor.expect_process().times(1).returning(|_| Ok(processed("b2_thumb.png")));
+
+        let svc = ThumbnailRegenService::new(posts, boards, storage, processor);
+        let only = HashSet::from([MediaKey::new("b.png")]);
+        let report = svc.regenerate_only(&only).await.unwrap();
+        assert_eq!(report, RegenReport { scanned: 1, regenerated: 1, skipped: 0, failed: 0 });
+    }
+}
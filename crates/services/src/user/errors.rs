@@ -26,10 +26,40 @@ pub enum UserError {
     #[error("invalid username or password")]
     InvalidCredentials,
 
+    /// An SSO login's username belongs to an account it is not linked to.
+    #[error("username {username} is taken by another account")]
+    UsernameTaken {
+        /// The username asked for.
+        username: String,
+    },
+
     /// The account has been deactivated.
     #[error("account is deactivated")]
     Deactivated,
 
+    /// The password was right but the account has two-factor auth enabled and
+    /// no code was given.
+    #[error("two-factor code required")]
+    TotpRequired,
+
+    /// The two-factor code (or recovery code) given was wrong or already used.
+    #[error("invalid two-factor code")]
+    InvalidTotp,
+
+    /// Too many wrong two-factor codes; none are checked until the lock passes.
+    #[error("too many two-factor attempts, retry in {retry_after_secs}s")]
+    TotpLocked {
+        /// Seconds until codes are checked again.
+        retry_after_secs: u32,
+    },
+
+    /// The operation requires an optional port that is not attached.
+    #[error("{feature} not configured")]
+    NotConfigured {
+        /// The feature whose port is missing.
+        feature: String,
+    },
+
     /// A domain-level error that could not be handled at this level.
     #[error("internal error: {0}")]
     Internal(#[from] DomainError),
@@ -2,22 +2,48 @@
 //!
 //! Responsibilities:
 //! - Create new moderator/admin accounts (admin only — enforced by handler)
-//! - Log in (verify password, issue token)
+//! - Log in (verify password and, once enrolled, a TOTP code; issue token)
+//! - Log in accounts vouched for by an external identity provider (SSO)
+//! - Enroll in and remove TOTP two-factor auth
 //! - Deactivate accounts (soft delete)
 //!
-//! Generic over `UserRepository` and `AuthProvider`.
+//! Generic over `UserRepository` and `AuthProvider`. Two-factor auth needs a
+//! `TotpRepository`, attached with [`UserService::with_totp`]; SSO logins an
+//! `ExternalIdentityRepository`, attached with
+//! [`UserService::with_external_identities`].
 
 pub mod errors;
 pub use errors::UserError;
 
 use domains::errors::DomainError;
-use domains::models::{Claims, Page, Paginated, Role, Token, User, UserId};
-use domains::ports::{AuthProvider, UserRepository};
-use tracing::{info, instrument};
+use std::sync::Arc;
+
+use domains::models::{Claims, Page, Paginated, Role, StaffTotp, Token, User, UserId};
+use domains::ports::{AuthProvider, ExternalIdentityRepository, TotpRepository, UserRepository};
+use tracing::{info, instrument, warn};
 use uuid::Uuid;
 
+use crate::common::totp;
 use crate::common::utils::now_utc;
 
+/// Issuer shown by authenticator apps next to enrolled accounts.
+const TOTP_ISSUER: &str = "rusty-board";
+
+/// Codes an account may try since its last accepted one before it is locked.
+const TOTP_MAX_ATTEMPTS: u32 = 5;
+
+/// How long an account stays locked after `TOTP_MAX_ATTEMPTS` wrong codes.
+const TOTP_LOCKOUT_MINUTES: i64 = 15;
+
+/// A started TOTP enrollment, for the user to add to their authenticator app.
+#[derive(Debug, Clone)]
+pub struct TotpEnrollment {
+    /// Base32 shared secret, for manual entry.
+    pub secret: String,
+    /// `otpauth://` URI carrying the secret, for QR codes.
+    pub uri:    String,
+}
+
 /// Minimum password length enforced by `UserService`.
 const MIN_PASSWORD_LEN: usize = 12;
 
@@ -28,6 +54,11 @@ pub struct UserService<UR: UserRepository, AP: AuthProvider> {
     user_repo: UR,
     auth:      AP,
     jwt_ttl_secs: u64,
+    /// Optional. When absent, logins are password-only and enrollment returns
+    /// `UserError::NotConfigured`.
+    totp:      Option<Arc<dyn TotpRepository>>,
+    /// Optional. When absent, `login_external` returns `UserError::NotConfigured`.
+    identities: Option<Arc<dyn ExternalIdentityRepository>>,
 }
 
 impl<UR: UserRepository, AP: AuthProvider> UserService<UR, AP> {
@@ -35,7 +66,19 @@ impl<UR: UserRepository, AP: AuthProvider> UserService<UR, AP> {
     ///
     /// `jwt_ttl_secs` controls how long issued tokens are valid.
     pub fn new(user_repo: UR, auth: AP, jwt_ttl_secs: u64) -> Self {
-        Self { user_repo, auth, jwt_ttl_secs }
+        Self { user_repo, auth, jwt_ttl_secs, totp: None, identities: None }
+    }
+
+    /// Attach the TOTP store, enabling two-factor enrollment and enforcement.
+    pub fn with_totp(mut self, totp: Arc<dyn TotpRepository>) -> Self {
+        self.totp = Some(totp);
+        self
+    }
+
+    /// Attach the store of SSO accounts, enabling `login_external`.
+    pub fn with_external_identities(mut self, identities: Arc<dyn ExternalIdentityRepository>) -> Self {
+        self.identities = Some(identities);
+        self
     }
 
     /// Create a new moderator or admin account.
@@ -52,21 +95,7 @@ impl<UR: UserRepository, AP: AuthProvider> UserService<UR, AP> {
         password: &str,
         role: Role,
     ) -> Result<User, UserError> {
-        // Validate username
-        if username.len() < 3 || username.len() > 32 {
-            return Err(UserError::Validation {
-                reason: format!(
-                    "username length {} is outside allowed range 3..=32",
-                    username.len()
-                ),
-            });
-        }
-        if !username.chars().all(|c| c.is_alphanumeric() || c == '_') {
-            return Err(UserError::Validation {
-                reason: "username must contain only alphanumeric characters and underscores"
-                    .to_owned(),
-            });
-        }
+        check_username(username)?;
 
         // Validate password
         if password.len() < MIN_PASSWORD_LEN {
@@ -98,16 +127,24 @@ impl<UR: UserRepository, AP: AuthProvider> UserService<UR, AP> {
         Ok(user)
     }
 
-    /// Log in with a username and password.
+    /// Log in with a username, password and, for accounts with two-factor auth
+    /// enabled, a TOTP or recovery code in `otp`.
     ///
     /// Returns `UserError::InvalidCredentials` if the username does not exist or
     /// the password does not match (deliberate vagueness to prevent enumeration).
     /// Returns `UserError::Deactivated` if the account is inactive.
+    /// Returns `UserError::TotpRequired` if the password matched but `otp` is
+    /// missing, and `UserError::InvalidTotp` if `otp` is wrong.
     ///
     /// On success returns `(Token, Claims)` so callers can inspect expiry without
     /// re-verifying the token.
-    #[instrument(skip(self, password), fields(username = %username))]
-    pub async fn login(&self, username: &str, password: &str) -> Result<(Token, Claims), UserError> {
+    #[instrument(skip(self, password, otp), fields(username = %username))]
+    pub async fn login(
+        &self,
+        username: &str,
+        password: &str,
+        otp: Option<&str>,
+    ) -> Result<(Token, Claims), UserError> {
         let user = self
             .user_repo
             .find_by_username(username)
@@ -126,11 +163,84 @@ impl<UR: UserRepository, AP: AuthProvider> UserService<UR, AP> {
             .await
             .map_err(|_| UserError::InvalidCredentials)?;
 
+        if let Some(factor) = self.enabled_totp(user.id).await? {
+            let otp = otp.filter(|c| !c.trim().is_empty()).ok_or(UserError::TotpRequired)?;
+            self.check_second_factor(factor, otp).await?;
+        }
+
         let (token, claims) = self.mint_token_for(&user).await?;
         info!(user_id = %user.id, "user logged in");
         Ok((token, claims))
     }
 
+    /// Log in an account vouched for by an external identity provider (SSO).
+    ///
+    /// The account is the one linked to `subject` at `issuer`. On the first
+    /// login it is created as `username`, with a random password nobody
+    /// knows, and linked; accounts are never matched by name, so a provider
+    /// account cannot take over a local one. A linked account gets `role` on
+    /// every login: the provider, not rusty-board, decides who is staff. The
+    /// password and TOTP checks are skipped; the provider is trusted with both.
+    ///
+    /// Returns `UserError::UsernameTaken` if a first login's `username` belongs
+    /// to another account, `UserError::Validation` for a username
+    /// `create_user` would refuse and `UserError::Deactivated` if the account
+    /// is inactive.
+    #[instrument(skip(self), fields(issuer = %issuer, username = %username, role = %role))]
+    pub async fn login_external(
+        &self,
+        issuer: &str,
+        subject: &str,
+        username: &str,
+        role: Role,
+    ) -> Result<(Token, Claims), UserError> {
+        let identities = self.identities.as_ref().ok_or_else(|| UserError::NotConfigured {
+            feature: "SSO login".to_owned(),
+        })?;
+        let user = match identities.find_user(issuer, subject).await? {
+            Some(user_id) => {
+                let user = self.user_repo.find_by_id(user_id).await?;
+                if !user.is_active {
+                    return Err(UserError::Deactivated);
+                }
+                if user.role != role {
+                    let user = User { role, ..user };
+                    self.user_repo.save(&user).await?;
+                    info!(user_id = %user.id, "role updated by identity provider");
+                    user
+                } else {
+                    user
+                }
+            }
+            None => {
+                check_username(username)?;
+                match self.user_repo.find_by_username(username).await {
+                    Ok(_) => return Err(UserError::UsernameTaken { username: username.to_owned() }),
+                    Err(DomainError::NotFound { .. }) => {}
+                    Err(e) => return Err(UserError::Internal(e)),
+                }
+                let unusable = hex::encode(rand::random::<[u8; 32]>());
+                let password_hash = self.auth.hash_password(&unusable).await.map_err(UserError::Internal)?;
+                let user = User {
+                    id: UserId(Uuid::new_v4()),
+                    username: username.to_owned(),
+                    password_hash,
+                    role,
+                    is_active: true,
+                    created_at: now_utc(),
+                };
+                self.user_repo.save(&user).await?;
+                identities.link(issuer, subject, user.id).await?;
+                info!(user_id = %user.id, "user created by identity provider");
+                user
+            }
+        };
+
+        let (token, claims) = self.mint_token_for(&user).await?;
+        info!(user_id = %user.id, "user logged in through identity provider");
+        Ok((token, claims))
+    }
+
     /// Issue a refreshed token for an already-authenticated user.
     ///
     /// The caller must have already verified the existing token via the auth
@@ -216,6 +326,18 @@ impl<UR: UserRepository, AP: AuthProvider> UserService<UR, AP> {
         })
     }
 
+    /// Fetch a single user by username.
+    ///
+    /// Returns `UserError::NotFound` if no such user exists.
+    pub async fn get_by_username(&self, username: &str) -> Result<User, UserError> {
+        self.user_repo.find_by_username(username).await.map_err(|e| match e {
+            DomainError::NotFound { .. } => UserError::NotFound {
+                id: username.to_owned(),
+            },
+            other => UserError::Internal(other),
+        })
+    }
+
     /// Paginated list of all user accounts.
     pub async fn list_users(
         &self,
@@ -240,6 +362,119 @@ impl<UR: UserRepository, AP: AuthProvider> UserService<UR, AP> {
             .map_err(UserError::Internal)
     }
 
+    /// Whether `user_id` has confirmed two-factor auth. `false` without a TOTP store.
+    pub async fn totp_enabled(&self, user_id: UserId) -> Result<bool, UserError> {
+        Ok(self.enabled_totp(user_id).await?.is_some())
+    }
+
+    /// Start TOTP enrollment: store a new pending secret for `user_id`,
+    /// replacing any earlier pending one.
+    ///
+    /// Returns `UserError::Validation` if two-factor auth is already enabled;
+    /// it must be disabled first.
+    #[instrument(skip(self), fields(user_id = %user_id))]
+    pub async fn begin_totp_enrollment(&self, user_id: UserId) -> Result<TotpEnrollment, UserError> {
+        let store = self.totp_store()?;
+        if self.enabled_totp(user_id).await?.is_some() {
+            return Err(UserError::Validation { reason: "two-factor auth is already enabled".to_owned() });
+        }
+        let user = self.get_user(user_id).await?;
+        let secret = totp::generate_secret();
+        store
+            .save(&StaffTotp {
+                user_id,
+                secret: secret.clone(),
+                enabled_at: None,
+                recovery_codes: Vec::new(),
+                last_step: None,
+                locked_until: None,
+            })
+            .await?;
+        let uri = totp::otpauth_uri(TOTP_ISSUER, &user.username, &secret);
+        Ok(TotpEnrollment { secret, uri })
+    }
+
+    /// Finish enrollment with a code from the authenticator app, enabling
+    /// two-factor auth for `user_id`.
+    ///
+    /// Returns the recovery codes, in plain text; only their hashes are kept,
+    /// so this is the one time they can be shown. Returns
+    /// `UserError::Validation` if no enrollment is pending and
+    /// `UserError::InvalidTotp` if `code` is wrong.
+    #[instrument(skip(self, code), fields(user_id = %user_id))]
+    pub async fn confirm_totp_enrollment(&self, user_id: UserId, code: &str) -> Result<Vec<String>, UserError> {
+        let store = self.totp_store()?;
+        let mut factor = match store.find(user_id).await? {
+            Some(factor) if factor.enabled_at.is_none() => factor,
+            _ => return Err(UserError::Validation { reason: "no two-factor enrollment is pending".to_owned() }),
+        };
+        let step = totp::verify(&factor.secret, code, now_utc().timestamp(), None).ok_or(UserError::InvalidTotp)?;
+        let codes = totp::generate_recovery_codes();
+        factor.recovery_codes = codes.iter().map(|c| totp::hash_recovery_code(c)).collect();
+        factor.enabled_at = Some(now_utc());
+        factor.last_step = Some(step);
+        store.save(&factor).await?;
+        info!(user_id = %user_id, "two-factor auth enabled");
+        Ok(codes)
+    }
+
+    /// Turn two-factor auth off for `user_id`, given a current TOTP or
+    /// recovery code. A pending enrollment is discarded without one.
+    ///
+    /// Returns `UserError::InvalidTotp` if `code` is wrong.
+    #[instrument(skip(self, code), fields(user_id = %user_id))]
+    pub async fn disable_totp(&self, user_id: UserId, code: &str) -> Result<(), UserError> {
+        let store = self.totp_store()?;
+        if let Some(factor) = self.enabled_totp(user_id).await? {
+            self.check_second_factor(factor, code).await?;
+        }
+        store.delete(user_id).await?;
+        info!(user_id = %user_id, "two-factor auth disabled");
+        Ok(())
+    }
+
+    fn totp_store(&self) -> Result<&Arc<dyn TotpRepository>, UserError> {
+        self.totp.as_ref().ok_or_else(|| UserError::NotConfigured {
+            feature: "two-factor auth".to_owned(),
+        })
+    }
+
+    /// The confirmed factor for `user_id`; `None` if none, pending, or no store.
+    async fn enabled_totp(&self, user_id: UserId) -> Result<Option<StaffTotp>, UserError> {
+        let Some(store) = &self.totp else { return Ok(None) };
+        Ok(store.find(user_id).await?.filter(|f| f.enabled_at.is_some()))
+    }
+
+    /// Accept `code` as the next TOTP code or an unused recovery code, and
+    /// record that it is spent.
+    ///
+    /// Every try counts against `TOTP_MAX_ATTEMPTS` before the code is
+    /// looked at, and spending it is a conditional update, so neither
+    /// parallel guesses nor a code replayed in parallel get through.
+    async fn check_second_factor(&self, factor: StaffTotp, code: &str) -> Result<(), UserError> {
+        let store = self.totp_store()?;
+        let now = now_utc();
+        let locked_until = now + chrono::Duration::minutes(TOTP_LOCKOUT_MINUTES);
+        if !store.claim_attempt(factor.user_id, TOTP_MAX_ATTEMPTS, now, locked_until).await? {
+            // Locked by a parallel request since `factor` was read: a full lockout.
+            let retry = (factor.locked_until.unwrap_or(locked_until) - now).num_seconds();
+            warn!(user_id = %factor.user_id, "two-factor attempts locked");
+            return Err(UserError::TotpLocked { retry_after_secs: u32::try_from(retry).unwrap_or(0).max(1) });
+        }
+        if let Some(step) = totp::verify(&factor.secret, code, now.timestamp(), factor.last_step) {
+            if !store.consume_step(factor.user_id, step).await? {
+                return Err(UserError::InvalidTotp);
+            }
+        } else {
+            let hash = totp::hash_recovery_code(code);
+            if !store.consume_recovery_code(factor.user_id, &hash).await? {
+                return Err(UserError::InvalidTotp);
+            }
+            info!(user_id = %factor.user_id, remaining = factor.recovery_codes.len().saturating_sub(1), "recovery code used");
+        }
+        Ok(())
+    }
+
     /// Public self-registration — creates a `Role::User` account.
     ///
     /// Identical validation rules to `create_user`. The caller is responsible
@@ -253,11 +488,30 @@ impl<UR: UserRepository, AP: AuthProvider> UserService<UR, AP> {
     }
 }
 
+/// Usernames are 3–32 characters, alphanumeric + underscore.
+fn check_username(username: &str) -> Result<(), UserError> {
+    if username.len() < 3 || username.len() > 32 {
+        return Err(UserError::Validation {
+            reason: format!(
+                "username length {} is outside allowed range 3..=32",
+                username.len()
+            ),
+        });
+    }
+    if !username.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        return Err(UserError::Validation {
+            reason: "username must contain only alphanumeric characters and underscores"
+                .to_owned(),
+        });
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use domains::models::{PasswordHash, Token};
-    use domains::ports::{MockAuthProvider, MockUserRepository};
+    use domains::ports::{MockAuthProvider, MockExternalIdentityRepository, MockTotpRepository, MockUserRepository};
 
     fn make_service(
         user_repo: MockUserRepository,
@@ -309,7 +563,7 @@ mod tests {
             .returning(|_| Err(DomainError::not_found("user")));
 
         let svc = make_service(repo, MockAuthProvider::new());
-        let result = svc.login("nobody", "password123456").await;
+        let result = svc.login("nobody", "password123456", None).await;
         assert!(matches!(result, Err(UserError::InvalidCredentials)));
     }
 
@@ -330,7 +584,7 @@ mod tests {
             .returning(move |_| Ok(user.clone()));
 
         let svc = make_service(repo, MockAuthProvider::new());
-        let result = svc.login("alice", "correct-horse-battery").await;
+        let result = svc.login("alice", "correct-horse-battery", None).await;
         assert!(matches!(result, Err(UserError::Deactivated)));
     }
 
@@ -366,7 +620,7 @@ mod tests {
             .returning(|_| Ok(Token::new("eyJhbGci...")));
 
         let svc = make_service(repo, auth);
-        let result = svc.login("alice", "correct-horse-battery").await;
+        let result = svc.login("alice", "correct-horse-battery", None).await;
         assert!(result.is_ok());
         let (token, claims) = result.unwrap();
         assert!(!token.0.is_empty());
@@ -397,4 +651,206 @@ mod tests {
         assert_eq!(user.role, Role::User);
         assert_eq!(user.username, "newuser");
     }
+
+    fn active_user() -> User {
+        User {
+            id:            UserId::new(),
+            username:      "alice".to_owned(),
+            password_hash: PasswordHash::new("$argon2id$..."),
+            role:          Role::Janitor,
+            is_active:     true,
+            created_at:    now_utc(),
+        }
+    }
+
+    fn enabled_factor(user_id: UserId, recovery_codes: &[&str]) -> StaffTotp {
+        StaffTotp {
+            user_id,
+            secret:         totp::generate_secret(),
+            enabled_at:     Some(now_utc()),
+            recovery_codes: recovery_codes.iter().map(|c| totp::hash_recovery_code(c)).collect(),
+            last_step:      None,
+            locked_until:   None,
+        }
+    }
+
+    /// A service whose one user has the password "correct-horse-battery" and `factor`.
+    fn totp_service(user: User, factor: StaffTotp, store: MockTotpRepository)
+        -> UserService<MockUserRepository, MockAuthProvider>
+    {
+        let mut repo = MockUserRepository::new();
+        repo.expect_find_by_username().returning(move |_| Ok(user.clone()));
+        repo.expect_find_owned_boards().returning(|_| Ok(vec![]));
+        repo.expect_find_volunteer_boards().returning(|_| Ok(vec![]));
+        let mut auth = MockAuthProvider::new();
+        auth.expect_verify_password().returning(|_, _| Ok(()));
+        auth.expect_create_token().returning(|_| Ok(Token::new("eyJhbGci...")));
+        let mut store = store;
+        store.expect_find().returning(move |_| Ok(Some(factor.clone())));
+        make_service(repo, auth).with_totp(Arc::new(store))
+    }
+
+    #[tokio::test]
+    async fn login_asks_for_a_code_once_totp_is_enabled() {
+        let user = active_user();
+        let factor = enabled_factor(user.id, &[]);
+        let secret = factor.secret.clone();
+        let mut store = MockTotpRepository::new();
+        store.expect_claim_attempt()
+            .withf(|_, limit, now, until| *limit == TOTP_MAX_ATTEMPTS && until > now)
+            .times(2)
+            .returning(|_, _, _, _| Ok(true));
+        store.expect_consume_recovery_code().times(1).returning(|_, _| Ok(false));
+        store.expect_consume_step().times(1).returning(|_, _| Ok(true));
+        let svc = totp_service(user, factor, store);
+
+        let result = svc.login("alice", "correct-horse-battery", None).await;
+        assert!(matches!(result, Err(UserError::TotpRequired)));
+        let result = svc.login("alice", "correct-horse-battery", Some("000000x")).await;
+        assert!(matches!(result, Err(UserError::InvalidTotp)));
+
+        let code = totp::code_at(&secret, totp::step_at(now_utc().timestamp())).unwrap();
+        assert!(svc.login("alice", "correct-horse-battery", Some(&code)).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn recovery_codes_work_once() {
+        let user = active_user();
+        let factor = enabled_factor(user.id, &["abcde-fghij", "klmno-pqrst"]);
+        let mut store = MockTotpRepository::new();
+        store.expect_claim_attempt().returning(|_, _, _, _| Ok(true));
+        store.expect_consume_recovery_code()
+            .withf(|_, hash| hash == totp::hash_recovery_code("abcde-fghij"))
+            .times(1)
+            .returning(|_, _| Ok(true));
+        let svc = totp_service(user, factor, store);
+
+        assert!(svc.login("alice", "correct-horse-battery", Some("ABCDE FGHIJ")).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn a_locked_factor_checks_no_code() {
+        let user = active_user();
+        let factor = StaffTotp {
+            locked_until: Some(now_utc() + chrono::Duration::minutes(10)),
+            ..enabled_factor(user.id, &["abcde-fghij"])
+        };
+        let code = totp::code_at(&factor.secret, totp::step_at(now_utc().timestamp())).unwrap();
+        let mut store = MockTotpRepository::new();
+        store.expect_claim_attempt().times(1).returning(|_, _, _, _| Ok(false));
+        store.expect_consume_step().never();
+        store.expect_consume_recovery_code().never();
+        let svc = totp_service(user, factor, store);
+
+        let result = svc.login("alice", "correct-horse-battery", Some(&code)).await;
+        assert!(matches!(result, Err(UserError::TotpLocked { retry_after_secs }) if (540..=600).contains(&retry_after_secs)));
+    }
+
+    #[tokio::test]
+    async fn a_code_spent_by_a_parallel_login_is_refused() {
+        let user = active_user();
+        let factor = enabled_factor(user.id, &[]);
+        let code = totp::code_at(&factor.secret, totp::step_at(now_utc().timestamp())).unwrap();
+        let mut store = MockTotpRepository::new();
+        store.expect_claim_attempt().returning(|_, _, _, _| Ok(true));
+        // Another login recorded this step between our read and our update.
+        store.expect_consume_step().times(1).returning(|_, _| Ok(false));
+        let svc = totp_service(user, factor, store);
+
+        let result = svc.login("alice", "correct-horse-battery", Some(&code)).await;
+        assert!(matches!(result, Err(UserError::InvalidTotp)));
+    }
+
+    #[tokio::test]
+    async fn confirming_enrollment_enables_totp_and_returns_recovery_codes() {
+        let user_id = UserId::new();
+        let factor = StaffTotp { enabled_at: None, ..enabled_factor(user_id, &[]) };
+        let code = totp::code_at(&factor.secret, totp::step_at(now_utc().timestamp())).unwrap();
+        let mut store = MockTotpRepository::new();
+        store.expect_find().returning(move |_| Ok(Some(factor.clone())));
+        store.expect_save()
+            .withf(|f| f.enabled_at.is_some() && f.recovery_codes.len() == totp::RECOVERY_CODES)
+            .times(1)
+            .returning(|_| Ok(()));
+        let svc = make_service(MockUserRepository::new(), MockAuthProvider::new()).with_totp(Arc::new(store));
+
+        let codes = svc.confirm_totp_enrollment(user_id, &code).await.unwrap();
+        assert_eq!(codes.len(), totp::RECOVERY_CODES);
+    }
+
+    const ISSUER: &str = "https://sso.example.com";
+
+    #[tokio::test]
+    async fn external_login_creates_and_links_the_account_then_follows_the_provider_role() {
+        let user = active_user();
+        let user_id = user.id;
+        let mut identities = MockExternalIdentityRepository::new();
+        identities.expect_find_user().times(1).returning(|_, _| Ok(None));
+        identities.expect_link()
+            .withf(|issuer, subject, _| issuer == ISSUER && subject == "f00")
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+        identities.expect_find_user().returning(move |_, _| Ok(Some(user_id)));
+        let mut repo = MockUserRepository::new();
+        repo.expect_find_by_username()
+            .times(1)
+            .returning(|name| Err(DomainError::not_found(name)));
+        repo.expect_save()
+            .withf(|u| u.username == "alice" && u.role == Role::Janitor)
+            .times(1)
+            .returning(|_| Ok(()));
+        repo.expect_find_by_id().returning(move |_| Ok(user.clone()));
+        repo.expect_save()
+            .withf(move |u| u.id == user_id && u.role == Role::Admin)
+            .times(1)
+            .returning(|_| Ok(()));
+        repo.expect_find_owned_boards().returning(|_| Ok(vec![]));
+        repo.expect_find_volunteer_boards().returning(|_| Ok(vec![]));
+        let mut auth = MockAuthProvider::new();
+        auth.expect_hash_password().times(1).returning(|_| Ok(PasswordHash::new("$argon2id$...")));
+        auth.expect_create_token().returning(|_| Ok(Token::new("eyJhbGci...")));
+        let svc = make_service(repo, auth).with_external_identities(Arc::new(identities));
+
+        let (_, claims) = svc.login_external(ISSUER, "f00", "alice", Role::Janitor).await.unwrap();
+        assert_eq!(claims.role, Role::Janitor);
+        let (_, claims) = svc.login_external(ISSUER, "f00", "alice", Role::Admin).await.unwrap();
+        assert_eq!(claims.user_id, user_id);
+        assert_eq!(claims.role, Role::Admin);
+    }
+
+    #[tokio::test]
+    async fn external_login_does_not_log_in_as_a_local_account_of_the_same_name() {
+        let mut identities = MockExternalIdentityRepository::new();
+        identities.expect_find_user().returning(|_, _| Ok(None));
+        identities.expect_link().never();
+        let mut repo = MockUserRepository::new();
+        repo.expect_find_by_username()
+            .returning(|_| Ok(User { username: "admin".to_owned(), role: Role::Admin, ..active_user() }));
+        repo.expect_save().never();
+        let mut auth = MockAuthProvider::new();
+        auth.expect_create_token().never();
+        let svc = make_service(repo, auth).with_external_identities(Arc::new(identities));
+
+        let result = svc.login_external(ISSUER, "someone-else", "admin", Role::User).await;
+        assert!(matches!(result, Err(UserError::UsernameTaken { .. })));
+    }
+
+    #[tokio::test]
+    async fn external_login_refuses_deactivated_accounts() {
+        let mut identities = MockExternalIdentityRepository::new();
+        identities.expect_find_user().returning(|_, _| Ok(Some(UserId::new())));
+        let mut repo = MockUserRepository::new();
+        repo.expect_find_by_id()
+            .returning(|_| Ok(User { is_active: false, ..active_user() }));
+        let svc = make_service(repo, MockAuthProvider::new()).with_external_identities(Arc::new(identities));
+        let result = svc.login_external(ISSUER, "f00", "alice", Role::Admin).await;
+        assert!(matches!(result, Err(UserError::Deactivated)));
+    }
+
+    #[tokio::test]
+    async fn enrollment_without_a_store_is_not_configured() {
+        let svc = make_service(MockUserRepository::new(), MockAuthProvider::new());
+        let result = svc.begin_totp_enrollment(UserId::new()).await;
+        assert!(matches!(result, Err(UserError::NotConfigured { .. })));
+    }
 }
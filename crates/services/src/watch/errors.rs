@@ -0,0 +1,26 @@
+//! Error type for `WatchService` operations.
+
+use domains::errors::DomainError;
+use thiserror::Error;
+
+/// Errors that can occur in `WatchService` methods.
+#[derive(Debug, Error)]
+pub enum WatchError {
+    /// The thread does not exist on this board.
+    #[error("thread not found: {id}")]
+    ThreadNotFound {
+        /// The thread that was asked for.
+        id: String,
+    },
+
+    /// The watch list already holds `MAX_WATCHED_THREADS` threads.
+    #[error("watch list is full ({max} threads)")]
+    ListFull {
+        /// The most threads a watch list may hold.
+        max: u64,
+    },
+
+    /// A domain-level error that could not be handled at this level.
+    #[error("internal error: {0}")]
+    Internal(#[from] DomainError),
+}
@@ -0,0 +1,147 @@
+//! `WatchService` — anonymous thread watch lists.
+//!
+//! # Watchers
+//! A watcher is a random `WatcherToken` kept in a cookie, so lurkers can watch
+//! threads without an account. Each watch remembers the last post number the
+//! watcher saw; the thread view moves it forward (see
+//! `ThreadService::with_watches`) and the watch list counts the posts after it
+//! as unread.
+//!
+//! # Limits
+//! A watch list holds at most `MAX_WATCHED_THREADS` threads. Threads leave it
+//! when unwatched or deleted.
+
+pub mod errors;
+pub use errors::WatchError;
+
+use domains::errors::DomainError;
+use domains::models::{BoardId, ThreadId, WatchedThread, WatcherToken};
+use domains::ports::{ThreadRepository, WatchRepository};
+use tracing::{info, instrument};
+
+/// Most threads one watch list may hold.
+pub const MAX_WATCHED_THREADS: u64 = 100;
+
+/// Service for watching threads.
+///
+/// Generic over `WR: WatchRepository` and `TR: ThreadRepository` (to check a
+/// thread's board).
+pub struct WatchService<WR, TR>
+where
+    WR: WatchRepository,
+    TR: ThreadRepository,
+{
+    repo:        WR,
+    thread_repo: TR,
+}
+
+impl<WR, TR> WatchService<WR, TR>
+where
+    WR: WatchRepository,
+    TR: ThreadRepository,
+{
+    /// Construct a `WatchService`.
+    pub fn new(repo: WR, thread_repo: TR) -> Self {
+        Self { repo, thread_repo }
+    }
+
+    /// Add a thread on `board_id` to `watcher`'s list, with its current posts
+    /// counted as seen.
+    ///
+    /// # Errors
+    /// - `WatchError::ThreadNotFound` — no such thread on this board
+    /// - `WatchError::ListFull` — the list already holds `MAX_WATCHED_THREADS`
+    #[instrument(skip(self, watcher), fields(thread_id = %thread_id))]
+    pub async fn watch(
+        &self,
+        watcher:   WatcherToken,
+        thread_id: ThreadId,
+        board_id:  BoardId,
+    ) -> Result<(), WatchError> {
+        let not_found = || WatchError::ThreadNotFound { id: thread_id.to_string() };
+        let thread = self.thread_repo.find_by_id(thread_id).await.map_err(|e| match e {
+            DomainError::NotFound { .. } => not_found(),
+            other => other.into(),
+        })?;
+        if thread.board_id != board_id {
+            return Err(not_found());
+        }
+
+        if self.repo.count(watcher).await? >= MAX_WATCHED_THREADS {
+            return Err(WatchError::ListFull { max: MAX_WATCHED_THREADS });
+        }
+        match self.repo.watch(watcher, thread_id).await {
+            Ok(()) => info!("thread watched"),
+            Err(DomainError::NotFound { .. }) => return Err(not_found()),
+            Err(e) => return Err(e.into()),
+        }
+        Ok(())
+    }
+
+    /// Remove a thread from `watcher`'s list.
+    #[instrument(skip(self, watcher), fields(thread_id = %thread_id))]
+    pub async fn unwatch(&self, watcher: WatcherToken, thread_id: ThreadId) -> Result<(), WatchError> {
+        self.repo.unwatch(watcher, thread_id).await?;
+        Ok(())
+    }
+
+    /// `watcher`'s threads with their unread post counts, unread first.
+    #[instrument(skip(self, watcher))]
+    pub async fn list(&self, watcher: WatcherToken) -> Result<Vec<WatchedThread>, WatchError> {
+        Ok(self.repo.list(watcher).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use domains::models::Thread;
+    use domains::ports::{MockThreadRepository, MockWatchRepository};
+
+    fn thread(id: ThreadId, board_id: BoardId) -> Thread {
+        Thread {
+            id,
+            board_id,
+            op_post_id: None,
+            reply_count: 0,
+            bumped_at: Utc::now(),
+            sticky: false,
+            closed: false,
+            cycle: false,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// A service whose threads live on `thread_board` and whose lists hold `listed` threads.
+    fn service(thread_board: BoardId, listed: u64) -> WatchService<MockWatchRepository, MockThreadRepository> {
+        let mut threads = MockThreadRepository::new();
+        threads.expect_find_by_id().returning(move |id| Ok(thread(id, thread_board)));
+        let mut repo = MockWatchRepository::new();
+        repo.expect_count().returning(move |_| Ok(listed));
+        repo.expect_watch().returning(|_, _| Ok(()));
+        WatchService::new(repo, threads)
+    }
+
+    #[tokio::test]
+    async fn watch_adds_the_thread() {
+        let board_id = BoardId::new();
+        let svc = service(board_id, 0);
+        svc.watch(WatcherToken::new(), ThreadId::new(), board_id).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn thread_on_another_board_is_not_found() {
+        let svc = service(BoardId::new(), 0);
+        let err = svc.watch(WatcherToken::new(), ThreadId::new(), BoardId::new()).await;
+        assert!(matches!(err, Err(WatchError::ThreadNotFound { .. })));
+    }
+
+    #[tokio::test]
+    async fn full_list_is_refused() {
+        let board_id = BoardId::new();
+        let svc = service(board_id, MAX_WATCHED_THREADS);
+        let err = svc.watch(WatcherToken::new(), ThreadId::new(), board_id).await;
+        assert!(matches!(err, Err(WatchError::ListFull { max: MAX_WATCHED_THREADS })));
+    }
+}
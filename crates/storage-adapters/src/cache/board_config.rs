@@ -2,7 +2,8 @@
 //!
 //! Wraps a `DashMap<BoardId, (BoardConfig, Instant)>` with a configurable TTL.
 //! The cache is populated on first access and invalidated immediately on any
-//! `PUT /board/:slug/config` request.
+//! `PUT /board/:slug/config` request. A reload (SIGHUP or `POST /admin/reload`)
+//! clears it entirely.
 //!
 //! This is a single-instance cache. In multi-instance deployments, each instance
 //! has its own cache and may serve stale config for up to `ttl` seconds after an
@@ -13,17 +14,17 @@
 use std::time::{Duration, Instant};
 
 use dashmap::DashMap;
-use domains::models::{Board, BoardConfig, BoardId, Slug};
+use domains::models::{Board, BoardConfig, BoardId, SiteId, Slug};
 
 /// In-process cache for `BoardConfig` objects.
 ///
 /// Entries expire after `ttl`. The cache is write-through on config updates.
 /// Two key spaces are maintained:
 /// - `by_id`: `BoardId → (BoardConfig, Instant)` — used by handlers that already know the ID
-/// - `by_slug`: `Slug → (Board, BoardId, BoardConfig, Instant)` — used by middleware resolving the path
+/// - `by_slug`: `(SiteId, Slug) → (Board, BoardId, BoardConfig, Instant)` — used by middleware resolving the path
 pub struct BoardConfigCache {
     by_id:   DashMap<BoardId, (BoardConfig, Instant)>,
-    by_slug: DashMap<Slug, (Board, BoardId, BoardConfig, Instant)>,
+    by_slug: DashMap<(SiteId, Slug), (Board, BoardId, BoardConfig, Instant)>,
     ttl:     Duration,
 }
 
@@ -55,12 +56,12 @@ impl BoardConfigCache {
         self.by_id.insert(board_id, (config, Instant::now()));
     }
 
-    /// Retrieve cached `(Board, BoardId, BoardConfig)` by board slug.
+    /// Retrieve cached `(Board, BoardId, BoardConfig)` by the site's board slug.
     ///
     /// Used by `board_config_middleware` to skip a DB round-trip when the slug
     /// was already resolved in a prior request.
-    pub fn get_by_slug(&self, slug: &Slug) -> Option<(Board, BoardId, BoardConfig)> {
-        let entry = self.by_slug.get(slug)?;
+    pub fn get_by_slug(&self, site: SiteId, slug: &Slug) -> Option<(Board, BoardId, BoardConfig)> {
+        let entry = self.by_slug.get(&(site, slug.clone()))?;
         let (board, board_id, config, cached_at) = entry.value();
         if cached_at.elapsed() < self.ttl {
             Some((board.clone(), *board_id, config.clone()))
@@ -69,12 +70,12 @@ impl BoardConfigCache {
         }
     }
 
-    /// Insert or update a cache entry keyed by `Slug`.
+    /// Insert or update a cache entry keyed by the board's site and `Slug`.
     ///
     /// Also populates the `by_id` entry so both key spaces stay consistent.
     pub fn set_by_slug(&self, slug: Slug, board: Board, board_id: BoardId, config: BoardConfig) {
         let now = Instant::now();
-        self.by_slug.insert(slug, (board, board_id, config.clone(), now));
+        self.by_slug.insert((board.site_id, slug), (board, board_id, config.clone(), now));
         self.by_id.insert(board_id, (config, now));
     }
 
@@ -86,6 +87,18 @@ impl BoardConfigCache {
         self.by_id.remove(&board_id);
         self.by_slug.retain(|_, (_, id, _, _)| *id != board_id);
     }
+
+    /// Drop every entry, so each board's config is re-read from the database
+    /// on its next request. Returns how many boards were cached.
+    ///
+    /// Triggered by SIGHUP or `POST /admin/reload`, e.g. after editing
+    /// `board_configs` directly or updating a board on another instance.
+    pub fn clear(&self) -> usize {
+        let boards = self.by_id.len();
+        self.by_id.clear();
+        self.by_slug.clear();
+        boards
+    }
 }
 
 #[cfg(test)]
@@ -99,6 +112,7 @@ mod tests {
         use domains::models::Slug;
         Board {
             id,
+            site_id:    SiteId::DEFAULT,
             slug:       Slug::new("b".to_owned()).unwrap(),
             title:      "Random".to_owned(),
             rules:      String::new(),
@@ -129,7 +143,7 @@ mod tests {
         cache.set_by_slug(slug.clone(), sample_board(id), id, BoardConfig::default());
         cache.invalidate(id);
         assert!(cache.get(id).is_none());
-        assert!(cache.get_by_slug(&slug).is_none());
+        assert!(cache.get_by_slug(SiteId::DEFAULT, &slug).is_none());
     }
 
     #[test]
@@ -148,12 +162,22 @@ mod tests {
         let slug = Slug::new("b".to_owned()).unwrap();
         let cfg = BoardConfig::default();
         cache.set_by_slug(slug.clone(), sample_board(id), id, cfg.clone());
-        let result = cache.get_by_slug(&slug);
+        let result = cache.get_by_slug(SiteId::DEFAULT, &slug);
         assert!(result.is_some());
         let (_, returned_id, _) = result.unwrap();
         assert_eq!(returned_id, id);
     }
 
+    #[test]
+    fn slugs_are_cached_per_site() {
+        let cache = BoardConfigCache::new(Duration::from_secs(60));
+        let id = board_id();
+        let slug = Slug::new("b".to_owned()).unwrap();
+        cache.set_by_slug(slug.clone(), sample_board(id), id, BoardConfig::default());
+        assert!(cache.get_by_slug(SiteId::DEFAULT, &slug).is_some());
+        assert!(cache.get_by_slug(SiteId::new(), &slug).is_none());
+    }
+
     #[test]
     fn set_by_slug_also_populates_by_id() {
         let cache = BoardConfigCache::new(Duration::from_secs(60));
@@ -162,4 +186,17 @@ mod tests {
         cache.set_by_slug(slug, sample_board(id), id, BoardConfig::default());
         assert!(cache.get(id).is_some());
     }
+
+    #[test]
+    fn clear_drops_both_key_spaces() {
+        let cache = BoardConfigCache::new(Duration::from_secs(60));
+        let (a, b) = (board_id(), board_id());
+        let slug = Slug::new("g".to_owned()).unwrap();
+        cache.set_by_slug(slug.clone(), sample_board(a), a, BoardConfig::default());
+        cache.set(b, BoardConfig::default());
+        assert_eq!(cache.clear(), 2);
+        assert!(cache.get(a).is_none());
+        assert!(cache.get(b).is_none());
+        assert!(cache.get_by_slug(SiteId::DEFAULT, &slug).is_none());
+    }
 }
@@ -1,4 +1,8 @@
 //! In-process caching layer.
 
 pub mod board_config;
+pub mod page;
+pub mod site;
 pub use board_config::BoardConfigCache;
+pub use page::{PageCache, PageKey};
+pub use site::SiteCache;
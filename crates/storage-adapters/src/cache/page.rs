@@ -0,0 +1,139 @@
+//! In-process rendered page cache.
+//!
+//! Holds the HTML of board indexes and catalogs, keyed by board, page and UI
+//! locale, so repeat views skip the full thread scan and template render. Entries expire
+//! after a TTL and are dropped per board as soon as a post on that board is
+//! created or deleted (see `render_cache_middleware`).
+//!
+//! This is a single-instance cache: a write on one instance does not reach the
+//! others, which serve their copy until it expires. The shared `CacheStore`
+//! behind it is invalidated for every instance.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use bytes::Bytes;
+use dashmap::DashMap;
+use domains::models::{BoardId, Locale};
+
+/// Most pages kept per board. Index pages past the last thread still render,
+/// so without a cap a crawler walking `?page=` could grow the map unbounded.
+const MAX_PAGES_PER_BOARD: usize = 64;
+
+/// Which page of a board a cached entry holds, and in which UI locale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PageKey {
+    /// `GET /board/:slug?page=N` (1-indexed).
+    Index(u32, Locale),
+    /// `GET /board/:slug/catalog`.
+    Catalog(Locale),
+}
+
+impl fmt::Display for PageKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Index(page, locale) => write!(f, "index:{page}:{locale}"),
+            Self::Catalog(locale) => write!(f, "catalog:{locale}"),
+        }
+    }
+}
+
+/// In-process cache of rendered board pages.
+pub struct PageCache {
+    boards: DashMap<BoardId, HashMap<PageKey, (Bytes, Instant)>>,
+    ttl:    Duration,
+}
+
+impl PageCache {
+    /// Create an empty cache with the given time-to-live.
+    pub fn new(ttl: Duration) -> Self {
+        Self { boards: DashMap::new(), ttl }
+    }
+
+    /// The cached page, or `None` if absent or expired.
+    pub fn get(&self, board_id: BoardId, page: PageKey) -> Option<Bytes> {
+        let pages = self.boards.get(&board_id)?;
+        let (html, cached_at) = pages.get(&page)?;
+        (cached_at.elapsed() < self.ttl).then(|| html.clone())
+    }
+
+    /// Insert or replace a page. Skipped once the board holds
+    /// `MAX_PAGES_PER_BOARD` live pages.
+    pub fn set(&self, board_id: BoardId, page: PageKey, html: Bytes) {
+        let mut pages = self.boards.entry(board_id).or_default();
+        if pages.len() >= MAX_PAGES_PER_BOARD && !pages.contains_key(&page) {
+            pages.retain(|_, (_, cached_at)| cached_at.elapsed() < self.ttl);
+            if pages.len() >= MAX_PAGES_PER_BOARD {
+                return;
+            }
+        }
+        pages.insert(page, (html, Instant::now()));
+    }
+
+    /// Drop every page of `board_id`.
+    pub fn invalidate_board(&self, board_id: BoardId) {
+        self.boards.remove(&board_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn board_id() -> BoardId { BoardId(Uuid::new_v4()) }
+
+    #[test]
+    fn pages_are_keyed_by_board_and_page() {
+        let cache = PageCache::new(Duration::from_secs(60));
+        let (a, b) = (board_id(), board_id());
+        cache.set(a, PageKey::Index(1, Locale::En), Bytes::from_static(b"a1"));
+        cache.set(a, PageKey::Catalog(Locale::En), Bytes::from_static(b"ac"));
+        assert_eq!(cache.get(a, PageKey::Index(1, Locale::En)).unwrap(), "a1");
+        assert_eq!(cache.get(a, PageKey::Catalog(Locale::En)).unwrap(), "ac");
+        assert!(cache.get(a, PageKey::Index(2, Locale::En)).is_none());
+        assert!(cache.get(b, PageKey::Index(1, Locale::En)).is_none());
+    }
+
+    #[test]
+    fn pages_are_keyed_by_locale() {
+        let cache = PageCache::new(Duration::from_secs(60));
+        let id = board_id();
+        cache.set(id, PageKey::Index(1, Locale::Es), Bytes::from_static(b"es"));
+        assert_eq!(cache.get(id, PageKey::Index(1, Locale::Es)).unwrap(), "es");
+        assert!(cache.get(id, PageKey::Index(1, Locale::En)).is_none());
+    }
+
+    #[test]
+    fn invalidate_board_leaves_other_boards() {
+        let cache = PageCache::new(Duration::from_secs(60));
+        let (a, b) = (board_id(), board_id());
+        cache.set(a, PageKey::Index(1, Locale::En), Bytes::from_static(b"a"));
+        cache.set(b, PageKey::Index(1, Locale::En), Bytes::from_static(b"b"));
+        cache.invalidate_board(a);
+        assert!(cache.get(a, PageKey::Index(1, Locale::En)).is_none());
+        assert!(cache.get(b, PageKey::Index(1, Locale::En)).is_some());
+    }
+
+    #[test]
+    fn expired_page_returns_none() {
+        let cache = PageCache::new(Duration::from_millis(1));
+        let id = board_id();
+        cache.set(id, PageKey::Catalog(Locale::En), Bytes::from_static(b"c"));
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(cache.get(id, PageKey::Catalog(Locale::En)).is_none());
+    }
+
+    #[test]
+    fn pages_per_board_are_capped() {
+        let cache = PageCache::new(Duration::from_secs(60));
+        let id = board_id();
+        for page in 1..=MAX_PAGES_PER_BOARD as u32 + 10 {
+            cache.set(id, PageKey::Index(page, Locale::En), Bytes::from_static(b"p"));
+        }
+        assert!(cache.get(id, PageKey::Index(1, Locale::En)).is_some());
+        assert!(cache.get(id, PageKey::Index(MAX_PAGES_PER_BOARD as u32 + 1, Locale::En)).is_none());
+        assert_eq!(cache.boards.get(&id).unwrap().len(), MAX_PAGES_PER_BOARD);
+    }
+}
@@ -0,0 +1,79 @@
+//! In-process `Site` cache.
+//!
+//! Holds the full list of sites, which is short and read on every request by
+//! `site_middleware`. Caching the list rather than one entry per `Host` keeps
+//! the cache bounded however many host names clients send. Like
+//! `BoardConfigCache` it expires after a TTL and is cleared by a reload
+//! (SIGHUP or `POST /admin/reload`), e.g. after `rusty-board site add`.
+
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use domains::models::Site;
+
+/// In-process cache of every configured `Site`.
+pub struct SiteCache {
+    sites: RwLock<Option<(Arc<Vec<Site>>, Instant)>>,
+    ttl:   Duration,
+}
+
+impl SiteCache {
+    /// Create an empty cache with the given time-to-live.
+    pub fn new(ttl: Duration) -> Self {
+        Self { sites: RwLock::new(None), ttl }
+    }
+
+    /// The cached sites, or `None` if the list was never loaded or has expired.
+    pub fn get(&self) -> Option<Arc<Vec<Site>>> {
+        let guard = self.sites.read().unwrap_or_else(|e| e.into_inner());
+        let (sites, cached_at) = guard.as_ref()?;
+        (cached_at.elapsed() < self.ttl).then(|| sites.clone())
+    }
+
+    /// Replace the cached list, returning it shared.
+    pub fn set(&self, sites: Vec<Site>) -> Arc<Vec<Site>> {
+        let sites = Arc::new(sites);
+        *self.sites.write().unwrap_or_else(|e| e.into_inner()) = Some((sites.clone(), Instant::now()));
+        sites
+    }
+
+    /// Drop the cached list so the next request re-reads it. Returns how many
+    /// sites were cached.
+    pub fn clear(&self) -> usize {
+        self.sites
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .take()
+            .map_or(0, |(sites, _)| sites.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_then_get_returns_the_list() {
+        let cache = SiteCache::new(Duration::from_secs(60));
+        assert!(cache.get().is_none());
+        cache.set(vec![Site::fallback()]);
+        assert_eq!(cache.get().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn expired_list_returns_none() {
+        let cache = SiteCache::new(Duration::from_millis(1));
+        cache.set(vec![Site::fallback()]);
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(cache.get().is_none());
+    }
+
+    #[test]
+    fn clear_empties_the_cache() {
+        let cache = SiteCache::new(Duration::from_secs(60));
+        cache.set(vec![Site::fallback(), Site::fallback()]);
+        assert_eq!(cache.clear(), 2);
+        assert!(cache.get().is_none());
+        assert_eq!(cache.clear(), 0);
+    }
+}
@@ -0,0 +1,31 @@
+//! Media classifier adapters.
+//!
+//! - `NoopMediaClassifier` — never labels anything; always compiled
+//! - `OnnxNsfwClassifier` (`onnx.rs`) — NSFW detection through an external ONNX
+//!   runner (`nsfw-onnx` feature)
+//!
+//! The composition root wires `NoopMediaClassifier` when the feature is
+//! disabled, so boards with a classifier action configured simply get no labels.
+
+#[cfg(feature = "nsfw-onnx")]
+pub mod onnx;
+
+#[cfg(feature = "nsfw-onnx")]
+pub use onnx::OnnxNsfwClassifier;
+
+use async_trait::async_trait;
+use domains::errors::DomainError;
+use domains::models::MediaLabel;
+use domains::ports::{MediaClassifier, MediaData};
+use mime::Mime;
+
+/// No-op classifier — returns no labels for any upload.
+#[derive(Clone, Default)]
+pub struct NoopMediaClassifier;
+
+#[async_trait]
+impl MediaClassifier for NoopMediaClassifier {
+    async fn classify(&self, _mime: &Mime, _data: &MediaData) -> Result<Vec<MediaLabel>, DomainError> {
+        Ok(Vec::new())
+    }
+}
@@ -0,0 +1,129 @@
+//! ONNX NSFW classifier (`nsfw-onnx` feature).
+//!
+//! The model runs in a helper process rather than in-process: `command` is
+//! invoked as `<command> <model_path>`, receives the image on stdin and prints
+//! a JSON array of `{"label": …, "score": …}` objects on stdout. The bundled
+//! `scripts/nsfw_classify.py` does this with onnxruntime for the common
+//! open-source NSFW models (e.g. the GantMan/nsfw_model export), and any
+//! runner speaking the same protocol can replace it.
+//!
+//! Like the `video` and `thumbs-vips` features, nothing is linked into the
+//! binary — the runtime image needs the helper and its dependencies installed.
+//! Only still images are classified; other uploads get no labels.
+
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use domains::errors::DomainError;
+use domains::models::MediaLabel;
+use domains::ports::{MediaClassifier, MediaData};
+use mime::Mime;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tracing::{debug, instrument};
+
+/// Upper bound on a single helper invocation; the child is killed when this elapses.
+const TOOL_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Image types handed to the model.
+const CLASSIFIED: &[&str] = &["image/jpeg", "image/png", "image/gif", "image/webp"];
+
+/// Classifies images by piping them through an external ONNX runner.
+/// Cheap to clone.
+#[derive(Clone, Debug)]
+pub struct OnnxNsfwClassifier {
+    command: Arc<PathBuf>,
+    model:   Arc<PathBuf>,
+}
+
+impl OnnxNsfwClassifier {
+    /// Create a classifier that runs `command <model>` for each image.
+    pub fn new(command: impl Into<PathBuf>, model: impl Into<PathBuf>) -> Self {
+        Self { command: Arc::new(command.into()), model: Arc::new(model.into()) }
+    }
+}
+
+#[async_trait]
+impl MediaClassifier for OnnxNsfwClassifier {
+    #[instrument(skip(self, data), fields(mime = %mime))]
+    async fn classify(&self, mime: &Mime, data: &MediaData) -> Result<Vec<MediaLabel>, DomainError> {
+        if !CLASSIFIED.contains(&mime.essence_str()) {
+            return Ok(Vec::new());
+        }
+        let source = match data {
+            MediaData::Memory(bytes) => bytes.clone(),
+            MediaData::Spooled(file) => Bytes::from(
+                tokio::fs::read(&file.path)
+                    .await
+                    .map_err(|e| DomainError::internal(format!("failed to read upload: {e}")))?,
+            ),
+        };
+
+        let mut child = Command::new(self.command.as_ref())
+            .arg(self.model.as_ref())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| DomainError::internal(format!("failed to start classifier: {e}")))?;
+
+        // Feed stdin concurrently with reading stdout so a large image cannot
+        // deadlock against a full output pipe.
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        let feed = tokio::spawn(async move {
+            let _ = stdin.write_all(&source).await;
+        });
+
+        let output = tokio::time::timeout(TOOL_TIMEOUT, child.wait_with_output())
+            .await
+            .map_err(|_| DomainError::internal("classifier timed out"))?
+            .map_err(|e| DomainError::internal(format!("classifier failed: {e}")))?;
+        let _ = feed.await;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(DomainError::internal(format!("classifier exited with {}: {}", output.status, stderr.trim())));
+        }
+        let labels = parse_labels(&output.stdout)?;
+        debug!(labels = labels.len(), "image classified");
+        Ok(labels)
+    }
+}
+
+/// Parse the helper's stdout, dropping labels with a score outside `0.0..=1.0`.
+fn parse_labels(stdout: &[u8]) -> Result<Vec<MediaLabel>, DomainError> {
+    let labels: Vec<MediaLabel> = serde_json::from_slice(stdout)
+        .map_err(|e| DomainError::internal(format!("classifier returned bad JSON: {e}")))?;
+    Ok(labels.into_iter().filter(|l| (0.0..=1.0).contains(&l.score)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn missing_runner_is_an_error() {
+        let classifier = OnnxNsfwClassifier::new("/nonexistent/nsfw", "/nonexistent/model.onnx");
+        let data = MediaData::Memory(Bytes::from_static(b"\x89PNG"));
+        assert!(classifier.classify(&mime::IMAGE_PNG, &data).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn non_images_are_not_classified() {
+        let classifier = OnnxNsfwClassifier::new("/nonexistent/nsfw", "/nonexistent/model.onnx");
+        let data = MediaData::Memory(Bytes::from_static(b"%PDF-1.7"));
+        let labels = classifier.classify(&mime::APPLICATION_PDF, &data).await.unwrap();
+        assert!(labels.is_empty());
+    }
+
+    #[test]
+    fn out_of_range_scores_are_dropped() {
+        let labels = parse_labels(br#"[{"label":"porn","score":0.97},{"label":"bogus","score":3.0}]"#).unwrap();
+        assert_eq!(labels, vec![MediaLabel { label: "porn".into(), score: 0.97 }]);
+    }
+}
@@ -0,0 +1,89 @@
+//! TTL cache in front of a `DnsblChecker`.
+//!
+//! DNSBL lookups cost a DNS round-trip per post; listings change slowly, so
+//! results are cached in-process in a `DashMap` for `ttl`. Lookup errors are
+//! never cached.
+//!
+//! # INVARIANT
+//! Raw IPs are never kept beyond the request. Cache keys are the SHA-256 of
+//! the address, so the cache cannot be dumped back into a list of posters.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use dashmap::DashMap;
+use domains::{errors::DomainError, ports::DnsblChecker};
+use sha2::{Digest, Sha256};
+
+/// Upper bound on cached entries before expired ones are swept.
+const MAX_ENTRIES: usize = 50_000;
+
+/// Caches the verdicts of an inner `DnsblChecker`.
+pub struct CachedDnsblChecker {
+    inner:   Arc<dyn DnsblChecker>,
+    ttl:     Duration,
+    entries: DashMap<String, (bool, Instant)>,
+}
+
+impl CachedDnsblChecker {
+    /// Wrap `inner`, caching each verdict for `ttl`.
+    pub fn new(inner: Arc<dyn DnsblChecker>, ttl: Duration) -> Self {
+        Self { inner, ttl, entries: DashMap::new() }
+    }
+
+    fn key(ip: &str) -> String {
+        hex::encode(Sha256::digest(ip.as_bytes()))
+    }
+}
+
+#[async_trait]
+impl DnsblChecker for CachedDnsblChecker {
+    async fn is_blocked(&self, ip: &str) -> Result<bool, DomainError> {
+        let key = Self::key(ip);
+        if let Some(entry) = self.entries.get(&key) {
+            let (listed, cached_at) = *entry.value();
+            if cached_at.elapsed() < self.ttl {
+                return Ok(listed);
+            }
+        }
+
+        let listed = self.inner.is_blocked(ip).await?;
+        if self.entries.len() >= MAX_ENTRIES {
+            let ttl = self.ttl;
+            self.entries.retain(|_, (_, at)| at.elapsed() < ttl);
+        }
+        self.entries.insert(key, (listed, Instant::now()));
+        Ok(listed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use domains::ports::MockDnsblChecker;
+
+    #[tokio::test]
+    async fn second_lookup_is_served_from_cache() {
+        let mut inner = MockDnsblChecker::new();
+        inner.expect_is_blocked().times(1).returning(|_| Ok(true));
+        let cached = CachedDnsblChecker::new(Arc::new(inner), Duration::from_secs(60));
+
+        assert!(cached.is_blocked("192.0.2.1").await.unwrap());
+        assert!(cached.is_blocked("192.0.2.1").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn errors_are_not_cached() {
+        let mut inner = MockDnsblChecker::new();
+        let mut calls = 0;
+        inner.expect_is_blocked().times(2).returning(move |_| {
+            calls += 1;
+            if calls == 1 { Err(DomainError::internal("timeout")) } else { Ok(false) }
+        });
+        let cached = CachedDnsblChecker::new(Arc::new(inner), Duration::from_secs(60));
+
+        assert!(cached.is_blocked("192.0.2.1").await.is_err());
+        assert!(!cached.is_blocked("192.0.2.1").await.unwrap());
+    }
+}
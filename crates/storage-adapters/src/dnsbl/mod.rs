@@ -1,4 +1,4 @@
-//! DNSBL (DNS Block List) adapter.
+//! DNSBL (DNS Block List) and IP reputation adapters.
 //!
 //! Provides `SpamhausDnsblChecker` which queries the Spamhaus ZEN composite list
 //! by performing a DNS A-record lookup for the reversed IP address against the
@@ -6,6 +6,11 @@
 //!
 //! A `127.0.0.x` response indicates the IP is listed; NXDOMAIN means it is clean.
 //!
+//! The other checkers compose with it:
+//! - `TorExitListChecker` (`tor.rs`) — matches against a mirrored Tor exit list
+//! - `CompositeDnsblChecker` — listed if any inner checker lists the IP
+//! - `CachedDnsblChecker` (`cached.rs`) — TTL cache in front of any checker
+//!
 //! **Fail-open**: DNS timeouts, network errors, and resolution failures all return
 //! `Ok(false)` — a degraded DNSBL must never block legitimate posts.
 //!
@@ -14,10 +19,17 @@
 //! The composition root wires `NoopDnsblChecker` (always returns `false`) when
 //! the feature is disabled, keeping the `PostService` generic signature stable.
 
+pub mod cached;
+pub mod tor;
+
+pub use cached::CachedDnsblChecker;
+pub use tor::TorExitListChecker;
+
 use async_trait::async_trait;
 use domains::{errors::DomainError, ports::DnsblChecker};
 use std::net::Ipv4Addr;
 use std::str::FromStr;
+use std::sync::Arc;
 
 /// Queries the Spamhaus ZEN composite DNSBL.
 ///
@@ -108,6 +120,63 @@ impl DnsblChecker for NoopDnsblChecker {
     }
 }
 
+/// Fans a lookup out to several checkers; the IP is listed if any of them lists it.
+///
+/// Used to combine the configured DNSBL zones with the Tor exit list. An error
+/// from one source is logged and treated as "not listed" by that source.
+#[derive(Clone, Default)]
+pub struct CompositeDnsblChecker {
+    checkers: Vec<Arc<dyn DnsblChecker>>,
+}
+
+impl CompositeDnsblChecker {
+    /// Create an empty composite. An empty composite lists nothing.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a checker to the composite.
+    pub fn with(mut self, checker: Arc<dyn DnsblChecker>) -> Self {
+        self.checkers.push(checker);
+        self
+    }
+
+    /// Add one `SpamhausDnsblChecker` per zone in `zones`.
+    pub fn with_zones<I, S>(self, zones: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        zones.into_iter().fold(self, |acc, zone| {
+            acc.with(Arc::new(SpamhausDnsblChecker::with_zone(zone)))
+        })
+    }
+
+    /// Number of sources in this composite.
+    pub fn len(&self) -> usize {
+        self.checkers.len()
+    }
+
+    /// Returns `true` if no sources are configured.
+    pub fn is_empty(&self) -> bool {
+        self.checkers.is_empty()
+    }
+}
+
+#[async_trait]
+impl DnsblChecker for CompositeDnsblChecker {
+    async fn is_blocked(&self, ip: &str) -> Result<bool, DomainError> {
+        for checker in &self.checkers {
+            match checker.is_blocked(ip).await {
+                Ok(true) => return Ok(true),
+                Ok(false) => {}
+                Err(e) => tracing::warn!(error = %e, "IP reputation source failed (fail-open)"),
+            }
+        }
+        Ok(false)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -132,4 +201,18 @@ mod tests {
         assert!(!checker.is_blocked("1.2.3.4").await.unwrap());
         assert!(!checker.is_blocked("0.0.0.0").await.unwrap());
     }
+
+    #[tokio::test]
+    async fn composite_listed_if_any_source_lists() {
+        let mut failing = domains::ports::MockDnsblChecker::new();
+        failing.expect_is_blocked().returning(|_| Err(DomainError::internal("timeout")));
+        let mut listed = domains::ports::MockDnsblChecker::new();
+        listed.expect_is_blocked().returning(|_| Ok(true));
+
+        let composite = CompositeDnsblChecker::new()
+            .with(Arc::new(failing))
+            .with(Arc::new(listed));
+        assert!(composite.is_blocked("192.0.2.1").await.unwrap());
+        assert!(!CompositeDnsblChecker::new().is_blocked("192.0.2.1").await.unwrap());
+    }
 }
@@ -0,0 +1,128 @@
+//! Tor exit node list checker.
+//!
+//! Reads the Tor Project's bulk exit list from a local file and reports any
+//! address on it as listed. The file is expected to be refreshed out of band
+//! (e.g. a cron job fetching `https://check.torproject.org/torbulkexitlist`);
+//! this adapter re-reads it at most once per `refresh` interval.
+//!
+//! Two formats are accepted, one entry per line:
+//! - the bulk list: a bare IP address per line
+//! - the `exit-addresses` document: `ExitAddress <ip> <timestamp>` lines
+//!
+//! Blank lines, `#` comments, and any other lines are ignored.
+//!
+//! **Fail-open**: a missing or unreadable file leaves the previously loaded set
+//! in place (empty on first load) and never blocks posting.
+
+use std::collections::HashSet;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use domains::{errors::DomainError, ports::DnsblChecker};
+use tracing::{info, warn};
+
+/// Checks IPs against a locally mirrored Tor exit list.
+pub struct TorExitListChecker {
+    path:    PathBuf,
+    refresh: Duration,
+    state:   RwLock<TorExitState>,
+}
+
+struct TorExitState {
+    exits:     HashSet<IpAddr>,
+    loaded_at: Option<Instant>,
+}
+
+impl TorExitListChecker {
+    /// Create a checker backed by the exit list at `path`, re-read at most
+    /// once every `refresh`.
+    pub fn new(path: impl Into<PathBuf>, refresh: Duration) -> Self {
+        Self {
+            path: path.into(),
+            refresh,
+            state: RwLock::new(TorExitState { exits: HashSet::new(), loaded_at: None }),
+        }
+    }
+
+    /// Parse an exit list document into a set of addresses.
+    fn parse(contents: &str) -> HashSet<IpAddr> {
+        contents
+            .lines()
+            .filter_map(|line| {
+                let line = line.trim();
+                let candidate = match line.strip_prefix("ExitAddress ") {
+                    Some(rest) => rest.split_whitespace().next()?,
+                    None => line,
+                };
+                candidate.parse::<IpAddr>().ok()
+            })
+            .collect()
+    }
+
+    fn is_stale(&self) -> bool {
+        let state = self.state.read().unwrap_or_else(|e| e.into_inner());
+        state.loaded_at.is_none_or(|at| at.elapsed() >= self.refresh)
+    }
+
+    /// Re-read the exit list from disk. Keeps the previous set on failure.
+    pub async fn reload(&self) {
+        let result = tokio::fs::read_to_string(&self.path).await;
+        let mut state = self.state.write().unwrap_or_else(|e| e.into_inner());
+        // Stamp the attempt even on failure so a missing file is not re-read
+        // on every post.
+        state.loaded_at = Some(Instant::now());
+        match result {
+            Ok(contents) => {
+                state.exits = Self::parse(&contents);
+                info!(count = state.exits.len(), "Tor exit list loaded");
+            }
+            Err(e) => {
+                warn!(path = %self.path.display(), error = %e, "Tor exit list unreadable (fail-open)");
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl DnsblChecker for TorExitListChecker {
+    /// Returns `true` if `ip` is a known Tor exit node.
+    async fn is_blocked(&self, ip: &str) -> Result<bool, DomainError> {
+        let Ok(addr) = ip.parse::<IpAddr>() else {
+            return Ok(false);
+        };
+        if self.is_stale() {
+            self.reload().await;
+        }
+        let state = self.state.read().unwrap_or_else(|e| e.into_inner());
+        Ok(state.exits.contains(&addr))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_accepts_bulk_and_exit_address_formats() {
+        let doc = "# comment\n\
+                   198.51.100.7\n\
+                   ExitNode 0011BD2485AD45D984EC4159C88FC066E5E3300E\n\
+                   ExitAddress 203.0.113.9 2026-10-01 12:00:00\n\
+                   \n\
+                   2001:db8::1\n";
+        let set = TorExitListChecker::parse(doc);
+        assert_eq!(set.len(), 3);
+        assert!(set.contains(&"198.51.100.7".parse().unwrap()));
+        assert!(set.contains(&"203.0.113.9".parse().unwrap()));
+        assert!(set.contains(&"2001:db8::1".parse().unwrap()));
+    }
+
+    #[tokio::test]
+    async fn missing_file_fails_open() {
+        let checker = TorExitListChecker::new("/nonexistent/torbulkexitlist", Duration::from_secs(60));
+        assert!(!checker.is_blocked("198.51.100.7").await.unwrap());
+    }
+}
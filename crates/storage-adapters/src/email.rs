@@ -0,0 +1,436 @@
+//! Report emails over SMTP.
+//!
+//! `SmtpNotifier` is a `Notifier` sending each `NotifyEvent` as one
+//! plain-text email, in its own SMTP session: `EHLO`, `STARTTLS` (per
+//! `SmtpSecurity`), `AUTH PLAIN` when credentials are set, then
+//! `MAIL FROM` / `RCPT TO` / `DATA` / `QUIT`. Server certificates are checked
+//! against the Mozilla root store. Credentials are refused without TLS.
+//!
+//! The email names the post by ID and the reason given; with a `site_url`
+//! it links to the report queue at `/mod/flags`. Reporter IP hashes are
+//! never sent.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chrono::Utc;
+use domains::errors::DomainError;
+use domains::models::Flag;
+use domains::ports::{Notifier, NotifyEvent};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio_rustls::rustls::pki_types::ServerName;
+use tokio_rustls::rustls::{ClientConfig, RootCertStore};
+use tokio_rustls::TlsConnector;
+use uuid::Uuid;
+
+/// Subject prefix of every email.
+const SUBJECT_PREFIX: &str = "[rusty-board]";
+
+/// Longest report reason quoted in a subject, in characters.
+const SUBJECT_REASON_CHARS: usize = 60;
+
+/// Longest SMTP reply line read, in bytes.
+const MAX_REPLY_LINE: u64 = 4_096;
+
+/// Encryption of the SMTP connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmtpSecurity {
+    /// Plain connection upgraded with `STARTTLS` before anything is sent.
+    StartTls,
+    /// TLS from the first byte.
+    Tls,
+    /// No encryption.
+    None,
+}
+
+/// Server, sender and limits of an `SmtpNotifier`.
+#[derive(Debug, Clone)]
+pub struct SmtpSettings {
+    pub host:        String,
+    pub port:        u16,
+    pub security:    SmtpSecurity,
+    /// `(username, password)` for `AUTH PLAIN`; `None` skips authentication.
+    pub credentials: Option<(String, String)>,
+    /// `From` header: `addr@example.com` or `Name <addr@example.com>`.
+    pub from:        String,
+    /// Public origin of the site, linked to from every email.
+    pub site_url:    Option<String>,
+    /// Time limit of one whole SMTP session.
+    pub timeout:     Duration,
+}
+
+/// `Notifier` sending emails through one SMTP server.
+pub struct SmtpNotifier {
+    settings: SmtpSettings,
+    /// Envelope sender: the address in `settings.from`.
+    sender:   String,
+    tls:      TlsConnector,
+}
+
+impl SmtpNotifier {
+    /// A notifier for `settings`. Fails on a malformed sender address, or on
+    /// credentials with `SmtpSecurity::None`.
+    pub fn new(settings: SmtpSettings) -> Result<Self, DomainError> {
+        let sender = envelope_address(&settings.from)
+            .ok_or_else(|| DomainError::internal(format!("smtp: malformed from address {:?}", settings.from)))?;
+        if settings.credentials.is_some() && settings.security == SmtpSecurity::None {
+            return Err(DomainError::internal("smtp: credentials are only sent over tls or starttls"));
+        }
+        let provider = Arc::new(tokio_rustls::rustls::crypto::ring::default_provider());
+        let config = ClientConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()
+            .map_err(|e| DomainError::internal(format!("smtp tls: {e}")))?
+            .with_root_certificates(RootCertStore { roots: webpki_roots::TLS_SERVER_ROOTS.to_vec() })
+            .with_no_client_auth();
+        Ok(Self { settings, sender, tls: TlsConnector::from(Arc::new(config)) })
+    }
+
+    /// Subject and body lines of the email for `event`.
+    pub fn email(&self, event: &NotifyEvent) -> (String, Vec<String>) {
+        let (subject, mut body) = match event {
+            NotifyEvent::ReportFiled { flag } => (
+                format!("Post reported: {}", one_line(&flag.reason, SUBJECT_REASON_CHARS)),
+                vec![
+                    "A post was reported.".to_owned(),
+                    String::new(),
+                    format!("Reason: {}", one_line(&flag.reason, usize::MAX)),
+                    format!("Post:   {}", flag.post_id),
+                    format!("Filed:  {}", flag.created_at.format("%Y-%m-%d %H:%M UTC")),
+                ],
+            ),
+            NotifyEvent::ReportThreshold { post_id, reports } => (
+                format!("Post reported {reports} times"),
+                vec![format!("Post {post_id} now has {reports} pending reports.")],
+            ),
+            NotifyEvent::ReportDigest { flags } => {
+                let mut body = vec![
+                    match flags.len() {
+                        1 => "1 post was reported since the last digest.".to_owned(),
+                        n => format!("{n} posts were reported since the last digest."),
+                    },
+                    String::new(),
+                ];
+                body.extend(flags.iter().map(digest_line));
+                (format!("{} new reports", flags.len()), body)
+            }
+        };
+        if let Some(site_url) = &self.settings.site_url {
+            body.push(String::new());
+            body.push(format!("Review the report queue: {}/mod/flags", site_url.trim_end_matches('/')));
+        }
+        (format!("{SUBJECT_PREFIX} {subject}"), body)
+    }
+
+    /// The whole message for `recipient`, dot-stuffed and ending in the
+    /// `DATA` terminator.
+    fn message(&self, event: &NotifyEvent, recipient: &str) -> String {
+        let (subject, body) = self.email(event);
+        let domain = self.sender.rsplit_once('@').map_or("localhost", |(_, domain)| domain);
+        let mut message = [
+            format!("From: {}", self.settings.from),
+            format!("To: {recipient}"),
+            format!("Subject: {}", encode_header(&subject)),
+            format!("Date: {}", Utc::now().to_rfc2822()),
+            format!("Message-ID: <{}@{domain}>", Uuid::new_v4()),
+            "MIME-Version: 1.0".to_owned(),
+            "Content-Type: text/plain; charset=UTF-8".to_owned(),
+            "Content-Transfer-Encoding: 8bit".to_owned(),
+            String::new(),
+        ]
+        .join("\r\n");
+        for line in body.iter().flat_map(|text| text.split('\n')) {
+            message.push_str("\r\n");
+            if line.starts_with('.') {
+                message.push('.');
+            }
+            message.push_str(line);
+        }
+        message.push_str("\r\n.\r\n");
+        message
+    }
+
+    async fn session(&self, recipient: &str, message: &str) -> std::io::Result<()> {
+        let SmtpSettings { host, port, security, .. } = &self.settings;
+        let tcp = TcpStream::connect((host.as_str(), *port)).await?;
+        let mut smtp = Smtp::new(Box::new(tcp));
+        if *security == SmtpSecurity::Tls {
+            smtp = smtp.upgrade(&self.tls, host).await?;
+        }
+        smtp.reply(220).await?;
+        let hello = format!("EHLO {}", self.sender.rsplit_once('@').map_or("localhost", |(_, domain)| domain));
+        let capabilities = smtp.command(&hello, 250).await?;
+        if *security == SmtpSecurity::StartTls {
+            if !capabilities.iter().any(|c| c.eq_ignore_ascii_case("STARTTLS")) {
+                return Err(std::io::Error::other("server does not offer STARTTLS"));
+            }
+            smtp.command("STARTTLS", 220).await?;
+            smtp = smtp.upgrade(&self.tls, host).await?;
+            smtp.command(&hello, 250).await?;
+        }
+        if let Some((username, password)) = &self.settings.credentials {
+            let token = BASE64.encode(format!("\0{username}\0{password}"));
+            smtp.command(&format!("AUTH PLAIN {token}"), 235).await?;
+        }
+        smtp.command(&format!("MAIL FROM:<{}>", self.sender), 250).await?;
+        smtp.command(&format!("RCPT TO:<{recipient}>"), 250).await?;
+        smtp.command("DATA", 354).await?;
+        smtp.send(message).await?;
+        smtp.reply(250).await?;
+        // The message is accepted; a failed goodbye changes nothing.
+        let _ = smtp.command("QUIT", 221).await;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Notifier for SmtpNotifier {
+    async fn notify(&self, event: &NotifyEvent, recipient: &str) -> Result<(), DomainError> {
+        let recipient = recipient.trim();
+        if envelope_address(recipient).as_deref() != Some(recipient) {
+            return Err(DomainError::internal(format!("smtp: malformed recipient {recipient:?}")));
+        }
+        let message = self.message(event, recipient);
+        match tokio::time::timeout(self.settings.timeout, self.session(recipient, &message)).await {
+            Ok(result) => result.map_err(|e| DomainError::internal(format!("smtp {}: {e}", self.settings.host))),
+            Err(_) => Err(DomainError::internal(format!("smtp {}: timed out", self.settings.host))),
+        }
+    }
+}
+
+trait Stream: AsyncRead + AsyncWrite + Unpin + Send {}
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> Stream for T {}
+
+/// One SMTP connection, plain or TLS.
+struct Smtp {
+    conn: BufReader<Box<dyn Stream>>,
+}
+
+impl Smtp {
+    fn new(stream: Box<dyn Stream>) -> Self {
+        Self { conn: BufReader::new(stream) }
+    }
+
+    async fn upgrade(self, tls: &TlsConnector, host: &str) -> std::io::Result<Self> {
+        let name = ServerName::try_from(host.to_owned()).map_err(std::io::Error::other)?;
+        let stream = tls.connect(name, self.conn.into_inner()).await?;
+        Ok(Self::new(Box::new(stream)))
+    }
+
+    async fn send(&mut self, data: &str) -> std::io::Result<()> {
+        let stream = self.conn.get_mut();
+        stream.write_all(data.as_bytes()).await?;
+        stream.flush().await
+    }
+
+    /// Send `line` and read the reply, which must have code `expected`.
+    async fn command(&mut self, line: &str, expected: u16) -> std::io::Result<Vec<String>> {
+        self.send(&format!("{line}\r\n")).await?;
+        self.reply(expected).await
+    }
+
+    /// Read one reply, which must have code `expected`. Returns the text of
+    /// each of its lines.
+    async fn reply(&mut self, expected: u16) -> std::io::Result<Vec<String>> {
+        let mut lines = Vec::new();
+        loop {
+            let mut line = String::new();
+            (&mut self.conn).take(MAX_REPLY_LINE).read_line(&mut line).await?;
+            if line.is_empty() {
+                return Err(std::io::Error::other("server closed the connection"));
+            }
+            let line = line.trim_end();
+            let code = line.get(..3).and_then(|code| code.parse::<u16>().ok());
+            let text = line.get(4..).unwrap_or_default().to_owned();
+            let last = line.as_bytes().get(3) != Some(&b'-');
+            lines.push(text);
+            if last {
+                return match code {
+                    Some(code) if code == expected => Ok(lines),
+                    _ => Err(std::io::Error::other(format!("expected {expected}, got {line:?}"))),
+                };
+            }
+        }
+    }
+}
+
+/// The bare address in `from` (`addr` or `Name <addr>`), if it looks like one.
+fn envelope_address(from: &str) -> Option<String> {
+    let address = match from.rsplit_once('<') {
+        Some((_, rest)) => rest.strip_suffix('>')?,
+        None => from.trim(),
+    };
+    let (local, domain) = address.split_once('@')?;
+    let bad = |c: char| c.is_whitespace() || c.is_control() || matches!(c, '<' | '>' | '@' | ',');
+    if local.is_empty() || domain.is_empty() || address.chars().filter(|&c| c != '@').any(bad) {
+        return None;
+    }
+    (!domain.contains('@')).then(|| address.to_owned())
+}
+
+/// `text` on one line, cut to `max` characters.
+fn one_line(text: &str, max: usize) -> String {
+    let line: String = text.chars().map(|c| if c.is_control() { ' ' } else { c }).collect();
+    let line = line.trim();
+    if line.chars().count() <= max {
+        return line.to_owned();
+    }
+    let mut cut: String = line.chars().take(max).collect();
+    cut.push('…');
+    cut
+}
+
+fn digest_line(flag: &Flag) -> String {
+    format!(
+        "- {}  post {}: {}",
+        flag.created_at.format("%Y-%m-%d %H:%M UTC"),
+        flag.post_id,
+        one_line(&flag.reason, 200)
+    )
+}
+
+/// `text` as a header value: unchanged when ASCII, otherwise RFC 2047
+/// encoded words, folded so no line passes 76 characters.
+fn encode_header(text: &str) -> String {
+    if text.is_ascii() {
+        return text.to_owned();
+    }
+    // 45 bytes encode to 60 characters; with `=?UTF-8?B?` and `?=`, 72.
+    let mut words = Vec::new();
+    let mut chunk = String::new();
+    for c in text.chars() {
+        if chunk.len() + c.len_utf8() > 45 {
+            words.push(format!("=?UTF-8?B?{}?=", BASE64.encode(&chunk)));
+            chunk.clear();
+        }
+        chunk.push(c);
+    }
+    words.push(format!("=?UTF-8?B?{}?=", BASE64.encode(&chunk)));
+    words.join("\r\n ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use domains::models::{FlagId, FlagStatus, IpHash, PostId};
+    use std::sync::Mutex;
+    use tokio::net::TcpListener;
+
+    fn flag(reason: &str) -> Flag {
+        Flag {
+            id:               FlagId::new(),
+            post_id:          PostId::new(),
+            reason:           reason.to_owned(),
+            reporter_ip_hash: IpHash::new("reporter-hash"),
+            status:           FlagStatus::Pending,
+            resolved_by:      None,
+            created_at:       Utc::now(),
+        }
+    }
+
+    fn settings(port: u16) -> SmtpSettings {
+        SmtpSettings {
+            host:        "127.0.0.1".to_owned(),
+            port,
+            security:    SmtpSecurity::None,
+            credentials: None,
+            from:        "rusty-board <board@example.com>".to_owned(),
+            site_url:    Some("https://board.example/".to_owned()),
+            timeout:     Duration::from_secs(5),
+        }
+    }
+
+    /// An SMTP server accepting mail for everyone but `refused@example.com`,
+    /// recording each line it receives.
+    async fn smtp_server() -> (u16, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let log = seen.clone();
+        tokio::spawn(async move {
+            while let Ok((socket, _)) = listener.accept().await {
+                let (read, mut write) = socket.into_split();
+                let mut lines = BufReader::new(read).lines();
+                write.write_all(b"220 mail.test ESMTP\r\n").await.unwrap();
+                let mut data = false;
+                while let Ok(Some(line)) = lines.next_line().await {
+                    log.lock().unwrap().push(line.clone());
+                    let reply: &[u8] = match line.as_str() {
+                        "." if data => {
+                            data = false;
+                            b"250 queued\r\n"
+                        }
+                        _ if data => continue,
+                        l if l.starts_with("EHLO") => b"250-mail.test\r\n250 8BITMIME\r\n",
+                        "RCPT TO:<refused@example.com>" => b"550 no such user\r\n",
+                        "DATA" => {
+                            data = true;
+                            b"354 go ahead\r\n"
+                        }
+                        "QUIT" => b"221 bye\r\n",
+                        _ => b"250 ok\r\n",
+                    };
+                    write.write_all(reply).await.unwrap();
+                }
+            }
+        });
+        (port, seen)
+    }
+
+    #[tokio::test]
+    async fn report_is_sent_as_one_email() {
+        let (port, seen) = smtp_server().await;
+        let notifier = SmtpNotifier::new(settings(port)).unwrap();
+        let flag = flag("spam\nlinks\n.everywhere");
+        notifier.notify(&NotifyEvent::ReportFiled { flag: flag.clone() }, "mods@example.com").await.unwrap();
+
+        let lines = seen.lock().unwrap().clone();
+        assert_eq!(lines[0], "EHLO example.com");
+        assert!(lines.contains(&"MAIL FROM:<board@example.com>".to_owned()));
+        assert!(lines.contains(&"RCPT TO:<mods@example.com>".to_owned()));
+        assert!(lines.contains(&"Subject: [rusty-board] Post reported: spam links .everywhere".to_owned()));
+        assert!(lines.contains(&format!("Post:   {}", flag.post_id)));
+        assert!(lines.contains(&"Review the report queue: https://board.example/mod/flags".to_owned()));
+        assert!(!lines.iter().any(|l| l.contains("reporter-hash")));
+        assert_eq!(lines.last().unwrap(), "QUIT");
+    }
+
+    #[tokio::test]
+    async fn refused_recipients_fail_the_delivery() {
+        let (port, _) = smtp_server().await;
+        let notifier = SmtpNotifier::new(settings(port)).unwrap();
+        let event = NotifyEvent::ReportThreshold { post_id: PostId::new(), reports: 3 };
+        let err = notifier.notify(&event, "refused@example.com").await.unwrap_err();
+        assert!(err.to_string().contains("550"));
+        assert!(notifier.notify(&event, "mods@example.com\r\nRCPT TO:<x@y>").await.is_err());
+    }
+
+    #[test]
+    fn digests_list_every_report() {
+        let notifier = SmtpNotifier::new(SmtpSettings { site_url: None, ..settings(25) }).unwrap();
+        let event = NotifyEvent::ReportDigest { flags: vec![flag("spam"), flag(".off topic")] };
+        let (subject, body) = notifier.email(&event);
+        assert_eq!(subject, "[rusty-board] 2 new reports");
+        assert_eq!(body.len(), 4);
+        let message = notifier.message(&event, "mods@example.com");
+        assert!(message.ends_with("\r\n.\r\n"));
+        assert!(!message.contains("/mod/flags"));
+    }
+
+    #[test]
+    fn addresses_and_headers_are_checked() {
+        assert_eq!(envelope_address("rusty-board <board@example.com>").as_deref(), Some("board@example.com"));
+        assert_eq!(envelope_address("board@example.com").as_deref(), Some("board@example.com"));
+        assert_eq!(envelope_address("no address"), None);
+        assert_eq!(envelope_address("a@b@c"), None);
+        let plain = SmtpSettings { credentials: Some(("u".to_owned(), "p".to_owned())), ..settings(25) };
+        assert!(SmtpNotifier::new(plain).is_err());
+
+        let encoded = encode_header(&"ж".repeat(40));
+        assert!(encoded.split("\r\n ").all(|word| word.starts_with("=?UTF-8?B?") && word.len() <= 76));
+        assert_eq!(encoded.split("\r\n ").count(), 2);
+    }
+}
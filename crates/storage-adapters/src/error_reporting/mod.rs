@@ -0,0 +1,24 @@
+//! Error reporter adapters.
+//!
+//! - `NoopErrorReporter` — discards reports; always compiled
+//! - `SentryErrorReporter` (`sentry.rs`) — sends reports to Sentry
+//!   (`sentry` feature)
+//!
+//! Failures are logged whichever reporter is wired; the reporter only adds a
+//! place where operators get notified of them.
+
+#[cfg(feature = "sentry")]
+pub mod sentry;
+
+#[cfg(feature = "sentry")]
+pub use self::sentry::SentryErrorReporter;
+
+use domains::ports::{ErrorReport, ErrorReporter};
+
+/// No-op reporter — used when no error-tracking service is configured.
+#[derive(Clone, Default)]
+pub struct NoopErrorReporter;
+
+impl ErrorReporter for NoopErrorReporter {
+    fn report(&self, _report: &ErrorReport) {}
+}
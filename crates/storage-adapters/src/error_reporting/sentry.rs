@@ -0,0 +1,51 @@
+//! Sentry error reporter.
+//!
+//! Each `ErrorReport` becomes one Sentry event: the message as the event
+//! message, the context entries as tags (so events can be searched by
+//! `request_id` or `job`), and level `fatal` for panics, `error` otherwise.
+//! The Sentry client queues events and sends them from its own thread, so
+//! `report` never blocks the request or job that failed.
+
+use std::time::Duration;
+
+use domains::ports::{ErrorReport, ErrorReporter};
+use sentry::protocol::{Event, Level};
+
+/// Sends error reports to the Sentry project identified by a DSN.
+pub struct SentryErrorReporter {
+    /// Keeps the client alive; dropping it would stop event delivery.
+    guard: sentry::ClientInitGuard,
+}
+
+impl SentryErrorReporter {
+    /// Initialise the Sentry client for `dsn`.
+    ///
+    /// `environment` tags every event (e.g. `production`, `staging`).
+    /// Fails if the DSN cannot be parsed.
+    pub fn new(dsn: &str, environment: Option<String>) -> anyhow::Result<Self> {
+        let dsn = dsn.parse().map_err(|e| anyhow::anyhow!("invalid SENTRY_DSN: {e}"))?;
+        let guard = sentry::init(sentry::ClientOptions {
+            dsn: Some(dsn),
+            environment: environment.map(Into::into),
+            release: sentry::release_name!(),
+            ..Default::default()
+        });
+        Ok(Self { guard })
+    }
+}
+
+impl ErrorReporter for SentryErrorReporter {
+    fn report(&self, report: &ErrorReport) {
+        let event = Event {
+            message: Some(report.message.clone()),
+            level: if report.panic { Level::Fatal } else { Level::Error },
+            tags: report.context.iter().map(|(k, v)| ((*k).to_owned(), v.clone())).collect(),
+            ..Default::default()
+        };
+        sentry::capture_event(event);
+    }
+
+    fn flush(&self, timeout: Duration) {
+        self.guard.flush(Some(timeout));
+    }
+}
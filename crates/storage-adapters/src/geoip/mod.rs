@@ -0,0 +1,54 @@
+//! IP → ASN resolution over a MaxMind DB (feature: `geoip-asn`).
+//!
+//! Backs ASN bans: the poster's raw IP is looked up in a GeoLite2-ASN (or
+//! compatible `.mmdb`) database, loaded fully into memory at startup. Lookups
+//! are pure in-memory tree walks, so no caching layer is needed.
+//!
+//! **Fail-open**: unparsable addresses and addresses absent from the database
+//! resolve to `None`.
+
+use std::net::IpAddr;
+use std::path::Path;
+
+use async_trait::async_trait;
+use domains::{errors::DomainError, ports::AsnResolver};
+use maxminddb::{geoip2, Reader};
+
+/// Resolves IPs to autonomous system numbers using a MaxMind ASN database.
+pub struct MaxmindAsnResolver {
+    reader: Reader<Vec<u8>>,
+}
+
+impl MaxmindAsnResolver {
+    /// Load the database at `path` into memory.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, DomainError> {
+        let reader = Reader::open_readfile(path.as_ref()).map_err(|e| {
+            DomainError::internal(format!("ASN database {}: {e}", path.as_ref().display()))
+        })?;
+        Ok(Self { reader })
+    }
+}
+
+#[async_trait]
+impl AsnResolver for MaxmindAsnResolver {
+    async fn lookup_asn(&self, ip: &str) -> Result<Option<u32>, DomainError> {
+        let Ok(addr) = ip.parse::<IpAddr>() else {
+            return Ok(None);
+        };
+        let record = self
+            .reader
+            .lookup::<geoip2::Asn>(addr)
+            .map_err(|e| DomainError::internal(format!("ASN lookup: {e}")))?;
+        Ok(record.and_then(|r| r.autonomous_system_number))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn open_missing_database_is_an_error() {
+        assert!(MaxmindAsnResolver::open("/nonexistent/GeoLite2-ASN.mmdb").is_err());
+    }
+}
@@ -0,0 +1,102 @@
+//! In-memory cache store adapter.
+//!
+//! Implements `CacheStore` with a `DashMap` of values and expiry instants.
+//!
+//! # Limitations
+//! - **Single-instance only.** Each process has its own cache, so an
+//!   invalidation on one instance is not seen by the others; entries there
+//!   stay stale until their TTL runs out.
+//! - Entries are expired lazily on read. Once the map grows past
+//!   `SWEEP_THRESHOLD` entries, a write also sweeps every expired entry.
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use dashmap::DashMap;
+use domains::errors::DomainError;
+use domains::ports::CacheStore;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Entry count above which `set()` sweeps expired entries before inserting.
+const SWEEP_THRESHOLD: usize = 4096;
+
+/// Process-local `CacheStore`.
+#[derive(Clone, Default)]
+pub struct InMemoryCacheStore {
+    entries: Arc<DashMap<String, (Bytes, Instant)>>,
+}
+
+impl InMemoryCacheStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl CacheStore for InMemoryCacheStore {
+    async fn get(&self, key: &str) -> Result<Option<Bytes>, DomainError> {
+        let now = Instant::now();
+        if let Some(entry) = self.entries.get(key) {
+            if entry.1 > now {
+                return Ok(Some(entry.0.clone()));
+            }
+        } else {
+            return Ok(None);
+        }
+        // Expired: drop it, unless a writer replaced it in the meantime.
+        self.entries.remove_if(key, |_, (_, expires)| *expires <= now);
+        Ok(None)
+    }
+
+    async fn set(&self, key: &str, value: Bytes, ttl: Duration) -> Result<(), DomainError> {
+        if self.entries.len() > SWEEP_THRESHOLD {
+            let now = Instant::now();
+            self.entries.retain(|_, (_, expires)| *expires > now);
+        }
+        self.entries.insert(key.to_owned(), (value, Instant::now() + ttl));
+        Ok(())
+    }
+
+    async fn invalidate(&self, key: &str) -> Result<(), DomainError> {
+        self.entries.remove(key);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn set_then_get_returns_value() {
+        let store = InMemoryCacheStore::new();
+        store.set("k", Bytes::from_static(b"v"), Duration::from_secs(60)).await.unwrap();
+        assert_eq!(store.get("k").await.unwrap(), Some(Bytes::from_static(b"v")));
+        assert_eq!(store.get("other").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn expired_entry_is_a_miss_and_is_dropped() {
+        let store = InMemoryCacheStore::new();
+        store.set("k", Bytes::from_static(b"v"), Duration::ZERO).await.unwrap();
+        assert_eq!(store.get("k").await.unwrap(), None);
+        assert!(store.entries.is_empty());
+    }
+
+    #[tokio::test]
+    async fn invalidate_removes_entry() {
+        let store = InMemoryCacheStore::new();
+        store.set("k", Bytes::from_static(b"v"), Duration::from_secs(60)).await.unwrap();
+        store.invalidate("k").await.unwrap();
+        store.invalidate("missing").await.unwrap();
+        assert_eq!(store.get("k").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn clones_share_entries() {
+        let store = InMemoryCacheStore::new();
+        let other = store.clone();
+        store.set("k", Bytes::from_static(b"v"), Duration::from_secs(60)).await.unwrap();
+        assert!(other.get("k").await.unwrap().is_some());
+    }
+}
@@ -0,0 +1,138 @@
+//! In-process event bus adapter.
+//!
+//! Implements `EventBus` over a `tokio::sync::broadcast` channel. Each
+//! subscriber gets its own task that receives events in publish order and
+//! awaits `EventSubscriber::handle` for one before taking the next, so a slow
+//! subscriber delays only itself.
+//!
+//! # Limitations
+//! - **Single-instance only.** Subscribers see the events of their own
+//!   process; with several instances, each runs its own subscribers.
+//! - A subscriber more than `capacity` events behind skips the oldest ones;
+//!   the skip is logged.
+//! - `subscribe` spawns onto the current Tokio runtime and must be called
+//!   from within one.
+
+use domains::ports::{DomainEvent, EventBus, EventSubscriber};
+use std::sync::Arc;
+use tokio::sync::broadcast::{self, error::RecvError};
+
+/// Process-local `EventBus`.
+#[derive(Clone)]
+pub struct InMemoryEventBus {
+    sender: broadcast::Sender<Arc<DomainEvent>>,
+}
+
+impl InMemoryEventBus {
+    /// A bus buffering up to `capacity` events for each subscriber.
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity.max(1));
+        Self { sender }
+    }
+}
+
+impl EventBus for InMemoryEventBus {
+    fn publish(&self, event: DomainEvent) {
+        // `send` fails only when nobody is subscribed.
+        let _ = self.sender.send(Arc::new(event));
+    }
+
+    fn subscribe(&self, subscriber: Arc<dyn EventSubscriber>) {
+        let mut events = self.sender.subscribe();
+        tokio::spawn(async move {
+            loop {
+                match events.recv().await {
+                    Ok(event) => {
+                        if let Err(e) = subscriber.handle(&event).await {
+                            tracing::warn!(subscriber = subscriber.name(), error = %e, "event subscriber failed");
+                        }
+                    }
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::warn!(subscriber = subscriber.name(), skipped, "event subscriber fell behind; events dropped");
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use domains::errors::DomainError;
+    use domains::models::{Ban, BanId, IpHash, UserId};
+    use tokio::sync::mpsc;
+
+    /// Forwards the IP hash of every `BanIssued` it sees.
+    struct Forward(mpsc::UnboundedSender<String>);
+
+    #[async_trait]
+    impl EventSubscriber for Forward {
+        fn name(&self) -> &str { "forward" }
+
+        async fn handle(&self, event: &DomainEvent) -> Result<(), DomainError> {
+            if let DomainEvent::BanIssued { ban } = event {
+                if ban.ip_hash.0 == "fail" {
+                    return Err(DomainError::internal("subscriber failed"));
+                }
+                let _ = self.0.send(ban.ip_hash.0.clone());
+            }
+            Ok(())
+        }
+    }
+
+    fn ban(ip_hash: &str) -> DomainEvent {
+        DomainEvent::BanIssued {
+            ban: Ban {
+                id:         BanId::new(),
+                ip_hash:    IpHash::new(ip_hash),
+                banned_by:  UserId(uuid::Uuid::new_v4()),
+                reason:     String::new(),
+                expires_at: None,
+                created_at: chrono::Utc::now(),
+                shadow:     false,
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn every_subscriber_receives_events_in_order() {
+        let bus = InMemoryEventBus::new(16);
+        let (tx_a, mut rx_a) = mpsc::unbounded_channel();
+        let (tx_b, mut rx_b) = mpsc::unbounded_channel();
+        bus.subscribe(Arc::new(Forward(tx_a)));
+        bus.subscribe(Arc::new(Forward(tx_b)));
+
+        bus.publish(ban("one"));
+        bus.publish(ban("two"));
+        for rx in [&mut rx_a, &mut rx_b] {
+            assert_eq!(rx.recv().await.unwrap(), "one");
+            assert_eq!(rx.recv().await.unwrap(), "two");
+        }
+    }
+
+    #[tokio::test]
+    async fn subscriber_error_does_not_stop_delivery() {
+        let bus = InMemoryEventBus::new(16);
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        bus.subscribe(Arc::new(Forward(tx)));
+
+        bus.publish(ban("fail"));
+        bus.publish(ban("after"));
+        assert_eq!(rx.recv().await.unwrap(), "after");
+    }
+
+    #[test]
+    fn publish_without_subscribers_is_dropped() {
+        InMemoryEventBus::new(1).publish(ban("nobody"));
+    }
+
+    #[test]
+    fn events_serialize_with_a_type_tag() {
+        let json = serde_json::to_value(ban("abc")).unwrap();
+        assert_eq!(json["type"], "ban_issued");
+        assert_eq!(json["ban"]["ip_hash"], "abc");
+    }
+}
@@ -5,11 +5,17 @@
 //!
 //! | Adapter | Port | Notes |
 //! |---------|------|-------|
+//! | `InMemoryCacheStore` | `CacheStore` | DashMap with per-entry TTL; single-instance only |
+//! | `InMemoryEventBus` | `EventBus` | Tokio broadcast channel, one task per subscriber; single-instance only |
 //! | `InMemoryRateLimiter` | `RateLimiter` | DashMap sliding window; single-instance only |
 //! | `InMemorySessionRepository` | `SessionRepository` | DashMap; single-instance only |
 
+pub mod cache_store;
+pub mod event_bus;
 pub mod rate_limiter;
 pub mod session_repository;
 
+pub use cache_store::InMemoryCacheStore;
+pub use event_bus::InMemoryEventBus;
 pub use rate_limiter::InMemoryRateLimiter;
 pub use session_repository::InMemorySessionRepository;
@@ -1,26 +1,53 @@
 //! `storage-adapters` — concrete implementations of domain storage ports.
 //!
 //! All modules here are feature-gated except `media` (image processing is always
-//! compiled) and `cache` (in-process BoardConfig cache). The composition root
-//! selects which concrete adapters to instantiate based on active Cargo features.
+//! compiled) and `cache` (in-process BoardConfig cache). Cargo features decide
+//! which adapters are compiled in; the composition root registers them in a
+//! `registry::Registry` and builds the ones its settings name.
 //!
 //! # Feature flags
 //! - `db-postgres` — PostgreSQL repositories via sqlx
 //! - `media-s3` — S3-compatible object storage
 //! - `media-local` — local filesystem media storage
-//! - `video` — video keyframe extraction via ffmpeg-next
-//! - `documents` — PDF first-page rendering via pdfium-render
-//! - `redis` — Redis rate limiter via deadpool-redis
+//! - `video` — WebM/MP4 poster frames and duration via the ffmpeg/ffprobe CLI
+//! - `audio` — MP3/Ogg/FLAC decoding, cover art or waveform thumbnails via symphonia
+//! - `documents` — PDF first-page rendering via pdfium-render, EPUB cover extraction
+//! - `redis` — Redis rate limiter and cache store via deadpool-redis
+//! - `geoip-asn` — IP → ASN resolution via a MaxMind database (for ASN bans)
+//! - `nsfw-onnx` — NSFW image classification via an external ONNX runner
+//! - `sentry` — error reporting to Sentry
+//! - `plugins-wasm` — sandboxed WebAssembly post filters and event hooks via wasmtime
+//! - `plugins-lua` — sandboxed Lua post filter scripts via mlua
+//! - `webhooks` — signed outgoing webhook notifications via reqwest
+//! - `notify-discord` — the `rb-notify-discord` plugin: new-thread announcements on Discord and IRC
+//! - `matrix-bridge` — Matrix client and relay for bridging threads to Matrix rooms
+//! - `notify-email` — report emails to moderators over SMTP
 
 pub mod cache;
+pub mod classifier;
 pub mod dnsbl;
+pub mod error_reporting;
 pub mod media;
+pub mod plugins;
+pub mod registry;
+
+#[cfg(feature = "notify-email")]
+pub mod email;
+
+#[cfg(feature = "geoip-asn")]
+pub mod geoip;
 
 #[cfg(feature = "db-postgres")]
 pub mod postgres;
 
+#[cfg(feature = "matrix-bridge")]
+pub mod matrix;
+
 #[cfg(feature = "redis")]
 pub mod redis;
 
+#[cfg(feature = "webhooks")]
+pub mod webhooks;
+
 pub mod in_memory;
 pub mod stubs;
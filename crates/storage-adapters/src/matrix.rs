@@ -0,0 +1,457 @@
+//! Matrix bridge adapter (client-server API v3).
+//!
+//! - `MatrixClient` — the calls the bridge needs: `whoami`, `join`, `send`
+//!   and a filtered `/sync` long poll, authenticated with an access token.
+//! - `MatrixRelay` — an `EventSubscriber` sending each new post of a bridged
+//!   thread to its room as `Name No.123: body`. Posts tagged `matrix` came
+//!   from the room and are not sent back. A staff edit of a recently relayed
+//!   post replaces its message (`m.replace`).
+//!
+//! The other direction, room messages becoming posts, needs `PostService`
+//! and runs in the server binary (`matrix_bridge.rs`).
+//!
+//! Only text is bridged: `m.text` and `m.emote` messages from the room
+//! (without reply quotes and edits), post bodies to the room.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use domains::errors::DomainError;
+use domains::models::{Post, PostId, ThreadId};
+use domains::ports::{DomainEvent, EventSubscriber};
+use reqwest::{Method, Url};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+/// Tag on posts relayed from Matrix.
+pub const BRIDGE_TAG: &str = "matrix";
+
+/// Time limit of a request other than `/sync`.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Relayed messages remembered for edits; older posts are not edited.
+const RELAYED_KEPT: usize = 1000;
+
+/// A message posted in a room.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoomMessage {
+    pub room_id:  String,
+    pub event_id: String,
+    /// Matrix user ID, e.g. `@alice:example.org`.
+    pub sender:   String,
+    /// Plain text, reply quote removed; emotes as `* text`.
+    pub body:     String,
+}
+
+/// One `/sync` response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyncBatch {
+    /// `since` token of the next sync.
+    pub next_batch: String,
+    pub messages:   Vec<RoomMessage>,
+}
+
+/// Authenticated client of one homeserver.
+#[derive(Clone)]
+pub struct MatrixClient {
+    base:  Url,
+    token: String,
+    http:  reqwest::Client,
+}
+
+impl MatrixClient {
+    /// A client of `homeserver` (e.g. `https://matrix.example.org`).
+    pub fn new(homeserver: &str, access_token: &str) -> Result<Self, DomainError> {
+        let base = Url::parse(homeserver).map_err(|e| DomainError::internal(format!("matrix homeserver url: {e}")))?;
+        if base.cannot_be_a_base() || !matches!(base.scheme(), "http" | "https") {
+            return Err(DomainError::internal("matrix homeserver url must be http or https"));
+        }
+        let http = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| DomainError::internal(format!("matrix client: {e}")))?;
+        Ok(Self { base, token: access_token.to_owned(), http })
+    }
+
+    /// The user ID the access token belongs to.
+    pub async fn whoami(&self) -> Result<String, DomainError> {
+        let response = self.call(Method::GET, &["account", "whoami"], None, None).await?;
+        string(&response, "user_id")
+    }
+
+    /// Join `room` (an ID or `#alias:server`), returning its room ID. Joining
+    /// a room already joined is a no-op.
+    pub async fn join(&self, room: &str) -> Result<String, DomainError> {
+        let response = self.call(Method::POST, &["join", room], Some(json!({})), None).await?;
+        string(&response, "room_id")
+    }
+
+    /// Post a message of `msgtype` (`m.text`, `m.notice`) to `room_id`,
+    /// returning its event ID.
+    pub async fn send(&self, room_id: &str, msgtype: &str, body: &str) -> Result<String, DomainError> {
+        self.send_content(room_id, json!({ "msgtype": msgtype, "body": body })).await
+    }
+
+    /// Replace the text of message `event_id` in `room_id` with `body`.
+    /// Clients without edit support show the fallback `* body`.
+    pub async fn replace(&self, room_id: &str, event_id: &str, msgtype: &str, body: &str) -> Result<(), DomainError> {
+        let content = json!({
+            "msgtype": msgtype,
+            "body": format!("* {body}"),
+            "m.new_content": { "msgtype": msgtype, "body": body },
+            "m.relates_to": { "rel_type": "m.replace", "event_id": event_id },
+        });
+        self.send_content(room_id, content).await?;
+        Ok(())
+    }
+
+    async fn send_content(&self, room_id: &str, content: Value) -> Result<String, DomainError> {
+        let txn = Uuid::new_v4().simple().to_string();
+        let response =
+            self.call(Method::PUT, &["rooms", room_id, "send", "m.room.message", &txn], Some(content), None).await?;
+        string(&response, "event_id")
+    }
+
+    /// Messages in `rooms` since `since`, waiting up to `timeout` for one.
+    /// Without `since` the response holds recent history.
+    pub async fn sync(
+        &self,
+        since: Option<&str>,
+        timeout: Duration,
+        rooms: &[String],
+    ) -> Result<SyncBatch, DomainError> {
+        let filter = json!({
+            "presence":     { "types": [] },
+            "account_data": { "types": [] },
+            "room": {
+                "rooms":        rooms,
+                "timeline":     { "types": ["m.room.message"], "limit": 50 },
+                "state":        { "types": [] },
+                "ephemeral":    { "types": [] },
+                "account_data": { "types": [] },
+            },
+        });
+        let mut query = vec![("filter", filter.to_string()), ("timeout", timeout.as_millis().to_string())];
+        if let Some(since) = since {
+            query.push(("since", since.to_owned()));
+        }
+        let response = self.call(Method::GET, &["sync"], None, Some((&query, timeout + REQUEST_TIMEOUT))).await?;
+        parse_sync(&response)
+    }
+
+    /// Call `/_matrix/client/v3/{path}`; path segments are percent-encoded.
+    async fn call(
+        &self,
+        method: Method,
+        path: &[&str],
+        body: Option<Value>,
+        query: Option<(&[(&str, String)], Duration)>,
+    ) -> Result<Value, DomainError> {
+        let mut url = self.base.clone();
+        {
+            // `new` rules out URLs that cannot be a base.
+            let mut segments = url.path_segments_mut().map_err(|_| DomainError::internal("matrix homeserver url"))?;
+            segments.pop_if_empty().extend(["_matrix", "client", "v3"]).extend(path);
+        }
+        let mut request = self.http.request(method, url).bearer_auth(&self.token);
+        if let Some((query, timeout)) = query {
+            request = request.query(query).timeout(timeout);
+        }
+        if let Some(body) = body {
+            request = request.header(reqwest::header::CONTENT_TYPE, "application/json").body(body.to_string());
+        }
+        let response = request.send().await.map_err(|e| DomainError::internal(format!("matrix: {}", e.without_url())))?;
+        let status = response.status();
+        let bytes = response.bytes().await.map_err(|e| DomainError::internal(format!("matrix: {e}")))?;
+        let json: Value = serde_json::from_slice(&bytes).unwrap_or(Value::Null);
+        if !status.is_success() {
+            return Err(DomainError::internal(format!(
+                "matrix {path}: {status} {} {}",
+                json["errcode"].as_str().unwrap_or_default(),
+                json["error"].as_str().unwrap_or_default(),
+                path = path.first().copied().unwrap_or_default(),
+            )));
+        }
+        Ok(json)
+    }
+}
+
+fn string(json: &Value, field: &str) -> Result<String, DomainError> {
+    json[field]
+        .as_str()
+        .map(str::to_owned)
+        .ok_or_else(|| DomainError::internal(format!("matrix response has no `{field}`")))
+}
+
+/// Read the messages of a `/sync` response.
+pub fn parse_sync(json: &Value) -> Result<SyncBatch, DomainError> {
+    let next_batch = string(json, "next_batch")?;
+    let mut messages = Vec::new();
+    if let Some(rooms) = json["rooms"]["join"].as_object() {
+        for (room_id, room) in rooms {
+            for event in room["timeline"]["events"].as_array().into_iter().flatten() {
+                if let Some(message) = room_message(room_id, event) {
+                    messages.push(message);
+                }
+            }
+        }
+    }
+    Ok(SyncBatch { next_batch, messages })
+}
+
+fn room_message(room_id: &str, event: &Value) -> Option<RoomMessage> {
+    if event["type"] != "m.room.message" || event["content"]["m.relates_to"]["rel_type"] == "m.replace" {
+        return None;
+    }
+    let content = &event["content"];
+    let text = content["body"].as_str()?;
+    let text = match content["m.relates_to"]["m.in_reply_to"].is_object() {
+        true => strip_reply_quote(text),
+        false => text,
+    };
+    let body = match content["msgtype"].as_str()? {
+        "m.text" => text.to_owned(),
+        "m.emote" => format!("* {text}"),
+        _ => return None,
+    };
+    Some(RoomMessage {
+        room_id:  room_id.to_owned(),
+        event_id: event["event_id"].as_str()?.to_owned(),
+        sender:   event["sender"].as_str()?.to_owned(),
+        body,
+    })
+}
+
+/// `body` without the `> <@user> quoted` lines clients put before a reply.
+fn strip_reply_quote(body: &str) -> &str {
+    let mut rest = body;
+    while let Some(line_end) = rest.strip_prefix('>').and_then(|_| rest.find('\n')) {
+        rest = &rest[line_end + 1..];
+    }
+    rest.trim_start_matches('\n')
+}
+
+/// `EventSubscriber` sending the posts of bridged threads to their rooms.
+pub struct MatrixRelay {
+    client:  MatrixClient,
+    /// Room IDs of each bridged thread.
+    rooms:   HashMap<ThreadId, Vec<String>>,
+    /// Post, room ID and event ID of the last `RELAYED_KEPT` messages sent.
+    relayed: Mutex<VecDeque<(PostId, String, String)>>,
+}
+
+impl MatrixRelay {
+    pub fn new(client: MatrixClient, rooms: HashMap<ThreadId, Vec<String>>) -> Self {
+        Self { client, rooms, relayed: Mutex::default() }
+    }
+
+    fn remember(&self, post_id: PostId, room_id: &str, event_id: String) {
+        let mut relayed = self.relayed.lock().unwrap_or_else(|e| e.into_inner());
+        if relayed.len() == RELAYED_KEPT {
+            relayed.pop_front();
+        }
+        relayed.push_back((post_id, room_id.to_owned(), event_id));
+    }
+
+    /// Rooms and event IDs `post_id` was relayed as.
+    fn relayed(&self, post_id: PostId) -> Vec<(String, String)> {
+        let relayed = self.relayed.lock().unwrap_or_else(|e| e.into_inner());
+        relayed.iter().filter(|(id, ..)| *id == post_id).map(|(_, room, event)| (room.clone(), event.clone())).collect()
+    }
+}
+
+#[async_trait]
+impl EventSubscriber for MatrixRelay {
+    fn name(&self) -> &str {
+        "matrix-bridge"
+    }
+
+    async fn handle(&self, event: &DomainEvent) -> Result<(), DomainError> {
+        match event {
+            DomainEvent::PostCreated { post, .. } => {
+                let Some(rooms) = self.rooms.get(&post.thread_id) else {
+                    return Ok(());
+                };
+                if post.tags.iter().any(|t| t == BRIDGE_TAG) {
+                    return Ok(());
+                }
+                let text = relay_text(post);
+                for room_id in rooms {
+                    let event_id = self.client.send(room_id, "m.text", &text).await?;
+                    self.remember(post.id, room_id, event_id);
+                }
+                Ok(())
+            }
+            DomainEvent::PostEdited { post, .. } => {
+                let text = relay_text(post);
+                for (room_id, event_id) in self.relayed(post.id) {
+                    self.client.replace(&room_id, &event_id, "m.text", &text).await?;
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// How `post` reads in the room: `Name!trip No.123: body`.
+pub fn relay_text(post: &Post) -> String {
+    let name = post.name.as_deref().filter(|n| !n.is_empty()).unwrap_or("Anonymous");
+    let trip = post.tripcode.as_deref().map(|t| format!("!{t}")).unwrap_or_default();
+    let body = match post.body.trim() {
+        "" => "(file)",
+        body => body,
+    };
+    format!("{name}{trip} No.{}: {body}", post.post_number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use domains::models::{IpHash, PostId};
+    use std::sync::{Arc, Mutex};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    fn post(thread_id: ThreadId, tags: &[&str]) -> Post {
+        Post {
+            id: PostId::new(), thread_id, body: "hello room".to_owned(), ip_hash: IpHash::new("h"),
+            name: Some("anon".to_owned()), tripcode: Some("Ab12".to_owned()), email: None, created_at: Utc::now(),
+            post_number: 42, pinned: false, tags: tags.iter().map(|t| t.to_string()).collect(), commands: vec![], subject: None, capcode: None, deleted: None, edited_at: None,
+        }
+    }
+
+    /// A homeserver answering every request with `{"event_id": "$1"}` and
+    /// recording request lines and bodies.
+    async fn homeserver() -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let log = seen.clone();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut buf = vec![0u8; 16 * 1024];
+                let mut len = 0;
+                let request = loop {
+                    len += socket.read(&mut buf[len..]).await.unwrap();
+                    let text = String::from_utf8_lossy(&buf[..len]).into_owned();
+                    if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                        let length = head
+                            .lines()
+                            .find_map(|l| l.to_ascii_lowercase().strip_prefix("content-length: ").map(str::to_owned))
+                            .map_or(0, |v| v.parse().unwrap());
+                        if body.len() >= length {
+                            break format!("{} {body}", head.lines().next().unwrap());
+                        }
+                    }
+                };
+                log.lock().unwrap().push(request);
+                let reply = r#"{"event_id":"$1"}"#;
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\
+                     connection: close\r\n\r\n{reply}",
+                    reply.len()
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+        (url, seen)
+    }
+
+    #[test]
+    fn sync_keeps_text_messages_only() {
+        let sync = json!({
+            "next_batch": "s2",
+            "rooms": { "join": { "!room:example.org": { "timeline": { "events": [
+                { "type": "m.room.message", "event_id": "$a", "sender": "@alice:example.org",
+                  "content": { "msgtype": "m.text", "body": "hi thread" } },
+                { "type": "m.room.message", "event_id": "$b", "sender": "@alice:example.org",
+                  "content": { "msgtype": "m.emote", "body": "waves" } },
+                { "type": "m.room.message", "event_id": "$c", "sender": "@bot:example.org",
+                  "content": { "msgtype": "m.notice", "body": "beep" } },
+                { "type": "m.room.message", "event_id": "$d", "sender": "@alice:example.org",
+                  "content": { "msgtype": "m.text", "body": "* hi thread!",
+                               "m.relates_to": { "rel_type": "m.replace", "event_id": "$a" } } },
+                { "type": "m.room.message", "event_id": "$e", "sender": "@bob:example.org",
+                  "content": { "msgtype": "m.text", "body": "> <@alice:example.org> hi thread\n\nhello",
+                               "m.relates_to": { "m.in_reply_to": { "event_id": "$a" } } } },
+                { "type": "m.room.message", "event_id": "$f", "sender": "@bob:example.org",
+                  "content": { "msgtype": "m.image", "body": "cat.png" } },
+            ] } } } },
+        });
+        let batch = parse_sync(&sync).unwrap();
+        assert_eq!(batch.next_batch, "s2");
+        let bodies: Vec<_> = batch.messages.iter().map(|m| (m.sender.as_str(), m.body.as_str())).collect();
+        assert_eq!(bodies, [
+            ("@alice:example.org", "hi thread"),
+            ("@alice:example.org", "* waves"),
+            ("@bob:example.org", "hello"),
+        ]);
+        assert_eq!(batch.messages[0].room_id, "!room:example.org");
+        assert!(parse_sync(&json!({})).is_err());
+    }
+
+    #[test]
+    fn relay_text_names_the_poster() {
+        assert_eq!(relay_text(&post(ThreadId::new(), &[])), "anon!Ab12 No.42: hello room");
+        let mut anonymous = post(ThreadId::new(), &[]);
+        (anonymous.name, anonymous.tripcode, anonymous.body) = (None, None, String::new());
+        assert_eq!(relay_text(&anonymous), "Anonymous No.42: (file)");
+    }
+
+    #[tokio::test]
+    async fn posts_of_bridged_threads_are_sent_to_their_room() {
+        let (url, seen) = homeserver().await;
+        let thread_id = ThreadId::new();
+        let relay = MatrixRelay::new(
+            MatrixClient::new(&url, "token").unwrap(),
+            HashMap::from([(thread_id, vec!["!room:example.org".to_owned()])]),
+        );
+        let created = |post| DomainEvent::PostCreated { board_id: domains::models::BoardId::new(), post };
+
+        relay.handle(&created(post(thread_id, &[]))).await.unwrap();
+        relay.handle(&created(post(thread_id, &[BRIDGE_TAG]))).await.unwrap();
+        relay.handle(&created(post(ThreadId::new(), &[]))).await.unwrap();
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 1, "{seen:?}");
+        let sent = "PUT /_matrix/client/v3/rooms/!room:example.org/send/m.room.message/";
+        assert!(seen[0].starts_with(sent), "{}", seen[0]);
+        assert!(seen[0].ends_with(r#"{"body":"anon!Ab12 No.42: hello room","msgtype":"m.text"}"#), "{}", seen[0]);
+    }
+
+    #[tokio::test]
+    async fn staff_edits_replace_the_relayed_message() {
+        let (url, seen) = homeserver().await;
+        let thread_id = ThreadId::new();
+        let relay = MatrixRelay::new(
+            MatrixClient::new(&url, "token").unwrap(),
+            HashMap::from([(thread_id, vec!["!room:example.org".to_owned()])]),
+        );
+        let board_id = domains::models::BoardId::new();
+        let relayed = post(thread_id, &[]);
+        relay.handle(&DomainEvent::PostCreated { board_id, post: relayed.clone() }).await.unwrap();
+
+        let edited = Post { body: "[redacted]".to_owned(), ..relayed };
+        relay.handle(&DomainEvent::PostEdited { board_id, post: edited }).await.unwrap();
+        relay.handle(&DomainEvent::PostEdited { board_id, post: post(thread_id, &[]) }).await.unwrap();
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 2, "{seen:?}");
+        let body = seen[1].split_once(" HTTP/1.1 ").unwrap().1;
+        let content: Value = serde_json::from_str(body).unwrap();
+        assert_eq!(content["m.new_content"]["body"], "anon!Ab12 No.42: [redacted]");
+        assert_eq!(content["m.relates_to"], json!({ "rel_type": "m.replace", "event_id": "$1" }));
+    }
+
+    #[tokio::test]
+    async fn path_segments_are_escaped() {
+        let (url, seen) = homeserver().await;
+        let client = MatrixClient::new(&format!("{url}/"), "token").unwrap();
+        let _ = client.join("#chat room:example.org").await;
+        assert!(seen.lock().unwrap()[0].starts_with("POST /_matrix/client/v3/join/%23chat%20room:example.org "));
+    }
+}
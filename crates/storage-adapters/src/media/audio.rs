@@ -0,0 +1,314 @@
+//! Audio media processor: decode, measure duration, album art or waveform thumbnail.
+//!
+//! This module is only compiled when the `audio` feature is active.
+//! Decoding uses `symphonia` (pure Rust), so no system libraries or external
+//! binaries are required. MP3, Ogg Vorbis, and FLAC are supported.
+//!
+//! The thumbnail is the embedded cover art when the file carries one (ID3 `APIC`,
+//! FLAC `PICTURE`, Vorbis `METADATA_BLOCK_PICTURE`); otherwise a peak waveform
+//! of the whole track is drawn.
+//!
+//! # Metadata
+//! Audio originals are stored as uploaded. Tags (title, artist, cover art)
+//! are what listeners expect to keep and carry no location data, so
+//! `RawMedia::strip_metadata` does not apply here.
+
+use std::io::Cursor;
+
+use async_trait::async_trait;
+use domains::errors::DomainError;
+use domains::models::{MediaKey, ThumbnailSpec};
+use domains::ports::{MediaData, MediaProcessor, ProcessedMedia, RawMedia};
+use image::{DynamicImage, Rgb, RgbImage};
+use mime::Mime;
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{DecoderOptions, CODEC_TYPE_NULL};
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::{MediaSource, MediaSourceStream};
+use symphonia::core::meta::{MetadataOptions, Visual};
+use symphonia::core::probe::Hint;
+
+use super::images::encode_thumbnail;
+
+const SUPPORTED_AUDIO_MIMES: &[&str] = &[
+    "audio/mpeg",
+    "audio/ogg",
+    "audio/flac",
+];
+
+/// Height of the generated waveform image in pixels.
+const WAVEFORM_HEIGHT_PX: u32 = 100;
+
+/// Frames folded into one peak value while decoding. Keeps memory bounded
+/// (~26k floats for ten minutes at 44.1 kHz) regardless of track length.
+const PEAK_BLOCK_FRAMES: usize = 1024;
+
+/// Media processor for MP3, Ogg Vorbis, and FLAC uploads.
+///
+/// 1. Validates MIME type
+/// 2. Decodes the whole stream (rejects undecodable files) and records the duration
+/// 3. Uses embedded cover art as the thumbnail, or draws a waveform
+/// 4. Computes SHA-256 content hash of the original
+pub struct AudioMediaProcessor {
+    thumbnail: ThumbnailSpec,
+}
+
+impl AudioMediaProcessor {
+    /// Create a new processor with the default thumbnail spec (320px wide PNG).
+    pub fn new() -> Self {
+        Self { thumbnail: ThumbnailSpec::default() }
+    }
+
+    /// Thumbnail uploads whose board has no override with `spec`.
+    pub fn with_thumbnail(self, spec: ThumbnailSpec) -> Self {
+        Self { thumbnail: spec }
+    }
+}
+
+impl Default for AudioMediaProcessor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl MediaProcessor for AudioMediaProcessor {
+    async fn process(&self, input: RawMedia) -> Result<ProcessedMedia, DomainError> {
+        let mime_str = input.mime.to_string();
+
+        // Step 1: Validate MIME type
+        if !self.accepts(&input.mime) {
+            return Err(DomainError::Validation(
+                domains::errors::ValidationError::DisallowedMime { mime: mime_str },
+            ));
+        }
+        let ext = extension_for_mime(&mime_str);
+
+        // Step 2–3: Decoding a full track is CPU-bound; keep it off the runtime.
+        // Spooled uploads are decoded straight from disk.
+        let source: Box<dyn MediaSource> = match &input.data {
+            MediaData::Memory(bytes) => Box::new(Cursor::new(bytes.clone())),
+            MediaData::Spooled(file) => Box::new(std::fs::File::open(&file.path).map_err(|e| {
+                DomainError::media_processing(format!("failed to read upload: {e}"))
+            })?),
+        };
+        let spec = input.thumbnail.unwrap_or(self.thumbnail);
+        let (duration_ms, thumb_bytes) = tokio::task::spawn_blocking(move || {
+            let decoded = decode(source, ext)?;
+            let thumb = match decoded.cover_art.as_deref().and_then(|b| image::load_from_memory(b).ok()) {
+                Some(art) => art,
+                None => DynamicImage::ImageRgb8(render_waveform(&decoded.peaks, spec.max_width, WAVEFORM_HEIGHT_PX)),
+            };
+            Ok::<_, DomainError>((decoded.duration_ms, encode_thumbnail(&thumb, spec)?))
+        })
+        .await
+        .map_err(|e| DomainError::media_processing(format!("audio worker failed: {e}")))??;
+
+        // Step 4: Compute content hash of the stored original
+        let hash = super::content_hash(&input.data);
+        let md5 = super::upload_md5(&input.data);
+
+        let size_kb = (input.data.len() as u32).div_ceil(1024);
+        let key_base = uuid::Uuid::new_v4().to_string();
+        let original_key = MediaKey::new(format!("{key_base}.{ext}"));
+        let thumbnail_key = MediaKey::new(format!("{key_base}_thumb.{}", spec.format.extension()));
+
+        Ok(ProcessedMedia {
+            original_key,
+            original_data: input.data,
+            thumbnail_key: Some(thumbnail_key),
+            thumbnail_data: Some(thumb_bytes),
+            thumbnail_task: None,
+            hash,
+            size_kb,
+            duration_ms,
+            page_count: None,
+            md5,
+            phash: None,
+            width: None,
+            height: None,
+            stored_mime: None,
+        })
+    }
+
+    fn accepts(&self, mime: &Mime) -> bool {
+        SUPPORTED_AUDIO_MIMES.contains(&mime.as_ref())
+    }
+}
+
+/// What a full decode pass yields.
+struct DecodedAudio {
+    duration_ms: Option<u32>,
+    cover_art:   Option<Box<[u8]>>,
+    /// Peak absolute amplitude (0.0–1.0) per `PEAK_BLOCK_FRAMES` frames.
+    peaks:       Vec<f32>,
+}
+
+/// Decode every packet of the default track, collecting cover art, duration, and peaks.
+fn decode(source: Box<dyn MediaSource>, ext: &str) -> Result<DecodedAudio, DomainError> {
+    let bad = |e: SymphoniaError| DomainError::media_processing(format!("failed to decode audio: {e}"));
+
+    let mss = MediaSourceStream::new(source, Default::default());
+    let mut hint = Hint::new();
+    hint.with_extension(ext);
+    let mut probed = symphonia::default::get_probe()
+        .format(&hint, mss, &FormatOptions::default(), &MetadataOptions::default())
+        .map_err(bad)?;
+
+    // Cover art may sit in a container-level tag (ID3v2 ahead of MP3 frames)
+    // or in the format's own metadata (FLAC/Vorbis blocks).
+    let mut cover_art = probed.metadata.get()
+        .and_then(|m| m.current().and_then(|r| pick_cover(r.visuals())));
+    let mut format = probed.format;
+    if cover_art.is_none() {
+        cover_art = format.metadata().current().and_then(|r| pick_cover(r.visuals()));
+    }
+
+    let track = format
+        .tracks()
+        .iter()
+        .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or_else(|| DomainError::media_processing("file contains no audio track"))?;
+    let track_id = track.id;
+    let sample_rate = track.codec_params.sample_rate;
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(bad)?;
+
+    let mut peaks = Vec::new();
+    let mut block_peak = 0.0f32;
+    let mut block_len = 0usize;
+    let mut total_frames = 0u64;
+    let mut samples: Option<SampleBuffer<f32>> = None;
+
+    loop {
+        let packet = match format.next_packet() {
+            Ok(p) => p,
+            Err(SymphoniaError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(SymphoniaError::ResetRequired) => break,
+            Err(e) => return Err(bad(e)),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+        let decoded = match decoder.decode(&packet) {
+            Ok(buf) => buf,
+            // A corrupt packet is skipped, as players do.
+            Err(SymphoniaError::DecodeError(_)) => continue,
+            Err(e) => return Err(bad(e)),
+        };
+
+        let spec = *decoded.spec();
+        let channels = spec.channels.count().max(1);
+        let buf = samples.get_or_insert_with(|| SampleBuffer::new(decoded.capacity() as u64, spec));
+        if buf.capacity() < decoded.capacity() * channels {
+            *buf = SampleBuffer::new(decoded.capacity() as u64, spec);
+        }
+        buf.copy_interleaved_ref(decoded);
+
+        for frame in buf.samples().chunks(channels) {
+            let peak = frame.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+            block_peak = block_peak.max(peak);
+            block_len += 1;
+            if block_len == PEAK_BLOCK_FRAMES {
+                peaks.push(block_peak.min(1.0));
+                block_peak = 0.0;
+                block_len = 0;
+            }
+        }
+        total_frames += (buf.samples().len() / channels) as u64;
+    }
+    if block_len > 0 {
+        peaks.push(block_peak.min(1.0));
+    }
+    if total_frames == 0 {
+        return Err(DomainError::media_processing("audio file has no decodable samples"));
+    }
+
+    let duration_ms = sample_rate
+        .filter(|rate| *rate > 0)
+        .map(|rate| (total_frames * 1000 / rate as u64).min(u32::MAX as u64) as u32);
+
+    Ok(DecodedAudio { duration_ms, cover_art, peaks })
+}
+
+/// Prefer the front cover; fall back to any embedded picture.
+fn pick_cover(visuals: &[Visual]) -> Option<Box<[u8]>> {
+    use symphonia::core::meta::StandardVisualKey;
+    visuals
+        .iter()
+        .find(|v| v.usage == Some(StandardVisualKey::FrontCover))
+        .or_else(|| visuals.first())
+        .map(|v| v.data.clone())
+}
+
+/// Draw a centred, mirrored peak waveform across `width` columns.
+fn render_waveform(peaks: &[f32], width: u32, height: u32) -> RgbImage {
+    let background = Rgb([0x22, 0x22, 0x22]);
+    let foreground = Rgb([0x8a, 0xb4, 0xf8]);
+    let mut img = RgbImage::from_pixel(width, height, background);
+    if peaks.is_empty() {
+        return img;
+    }
+
+    let mid = height as f32 / 2.0;
+    for x in 0..width {
+        // Fold the peak blocks that fall into this column.
+        let start = x as usize * peaks.len() / width as usize;
+        let end = ((x as usize + 1) * peaks.len() / width as usize).max(start + 1).min(peaks.len());
+        let peak = peaks[start.min(peaks.len() - 1)..end].iter().fold(0.0f32, |m, p| m.max(*p));
+        let half = (peak * mid).max(0.5);
+        let top = (mid - half).floor().max(0.0) as u32;
+        let bottom = ((mid + half).ceil() as u32).min(height);
+        for y in top..bottom {
+            img.put_pixel(x, y, foreground);
+        }
+    }
+    img
+}
+
+fn extension_for_mime(mime: &str) -> &'static str {
+    match mime {
+        "audio/mpeg" => "mp3",
+        "audio/ogg"  => "ogg",
+        "audio/flac" => "flac",
+        _            => "bin",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_waveform_draws_louder_columns_taller() {
+        let peaks = [0.1, 1.0];
+        let img = render_waveform(&peaks, 2, 100);
+        let height = |x| (0..100).filter(|y| img.get_pixel(x, *y)[2] == 0xf8).count();
+        assert!(height(1) > height(0));
+        assert_eq!(height(1), 100);
+    }
+
+    #[test]
+    fn accepts_only_supported_audio() {
+        let p = AudioMediaProcessor::new();
+        assert!(p.accepts(&"audio/mpeg".parse().unwrap()));
+        assert!(p.accepts(&"audio/flac".parse().unwrap()));
+        assert!(!p.accepts(&"audio/wav".parse().unwrap()));
+        assert!(!p.accepts(&mime::IMAGE_PNG));
+    }
+
+    #[tokio::test]
+    async fn undecodable_audio_is_a_processing_error() {
+        let input = RawMedia {
+            filename:       "song.mp3".to_owned(),
+            mime:           "audio/mpeg".parse().unwrap(),
+            data:           bytes::Bytes::from_static(b"ID3\x04\x00\x00\x00\x00\x00\x00not audio").into(),
+            strip_metadata: true,
+            thumbnail:      None,
+        };
+        let err = AudioMediaProcessor::new().process(input).await.unwrap_err();
+        assert!(matches!(err, DomainError::MediaProcessing { .. }), "got {err:?}");
+    }
+}
@@ -0,0 +1,261 @@
+//! Public media URLs: CDN base and expiring signatures.
+//!
+//! `MediaUrls` builds the URL a browser uses for a stored key. By default that
+//! is `/media/{key}`, served by the app itself. With `MEDIA_CDN_URL` set the
+//! base is the CDN, which pulls from the app (or the bucket) on a miss.
+//!
+//! With `MEDIA_URL_SIGNING_KEY` set every URL carries
+//! `?expires={unix}&sig={hex}`, where `sig` is HMAC-SHA256 over
+//! `{key}:{expires}`. Links copied to another site stop working once they
+//! expire. `expires` is rounded up to a multiple of the TTL, so a file keeps
+//! one URL for at least a full TTL and CDN and browser caches stay warm. The
+//! `/media` route checks signatures; a CDN can check the same token at its
+//! edge.
+//!
+//! `CdnMediaStorage` wraps any `MediaStorage` so `get_url` answers with these
+//! URLs instead of the backend's own (S3 presigned, IPFS gateway) once a CDN
+//! or signing is configured.
+
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use domains::errors::DomainError;
+use domains::models::MediaKey;
+use domains::ports::{MediaStorage, StoredMedia};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Builds, and checks, the public URLs of stored media.
+#[derive(Clone)]
+pub struct MediaUrls {
+    base:    String,
+    signing: Option<Arc<[u8]>>,
+    ttl:     Duration,
+}
+
+impl Default for MediaUrls {
+    /// Unsigned `/media/{key}` URLs served by the app.
+    fn default() -> Self {
+        Self::new("/media")
+    }
+}
+
+impl MediaUrls {
+    /// Unsigned URLs under `base`, e.g. `https://cdn.example.com/media`.
+    pub fn new(base: impl Into<String>) -> Self {
+        Self {
+            base:    base.into().trim_end_matches('/').to_owned(),
+            signing: None,
+            ttl:     Duration::from_secs(86_400),
+        }
+    }
+
+    /// Sign URLs with `key`; each stays valid for at least `ttl`.
+    /// An empty key leaves URLs unsigned.
+    pub fn with_signing(mut self, key: &[u8], ttl: Duration) -> Self {
+        self.signing = (!key.is_empty()).then(|| Arc::from(key));
+        self.ttl = ttl.max(Duration::from_secs(1));
+        self
+    }
+
+    /// Whether URLs carry a signature that the `/media` route must check.
+    pub fn is_signed(&self) -> bool {
+        self.signing.is_some()
+    }
+
+    /// `scheme://host[:port]` of the base when media is served from another
+    /// origin, e.g. a CDN; `None` for a path on this site.
+    pub fn origin(&self) -> Option<&str> {
+        let (scheme, rest) = self.base.split_once("://")?;
+        if !matches!(scheme, "http" | "https") || rest.is_empty() {
+            return None;
+        }
+        let end = scheme.len() + 3 + rest.find('/').unwrap_or(rest.len());
+        Some(&self.base[..end])
+    }
+
+    /// The public URL of `key`.
+    pub fn url(&self, key: &MediaKey) -> String {
+        self.url_at(key, self.ttl, now())
+    }
+
+    /// The public URL of `key`, valid for at least `ttl` when signed.
+    pub fn url_for(&self, key: &MediaKey, ttl: Duration) -> String {
+        self.url_at(key, ttl.max(Duration::from_secs(1)), now())
+    }
+
+    fn url_at(&self, key: &MediaKey, ttl: Duration, now: u64) -> String {
+        let url = format!("{}/{}", self.base, key.0);
+        let Some(signing) = &self.signing else {
+            return url;
+        };
+        let ttl = ttl.as_secs().max(1);
+        let expires = (now / ttl + 2) * ttl;
+        format!("{url}?expires={expires}&sig={}", hex::encode(sign(signing, &key.0, expires).finalize().into_bytes()))
+    }
+
+    /// Whether `expires` and `sig` are a current signature for `key`.
+    /// Always true when URLs are unsigned.
+    pub fn verify(&self, key: &str, expires: Option<u64>, sig: Option<&str>) -> bool {
+        self.verify_at(key, expires, sig, now())
+    }
+
+    fn verify_at(&self, key: &str, expires: Option<u64>, sig: Option<&str>, now: u64) -> bool {
+        let Some(signing) = &self.signing else {
+            return true;
+        };
+        let (Some(expires), Some(sig)) = (expires, sig) else {
+            return false;
+        };
+        let Ok(sig) = hex::decode(sig) else {
+            return false;
+        };
+        expires > now && sign(signing, key, expires).verify_slice(&sig).is_ok()
+    }
+}
+
+fn sign(signing: &[u8], key: &str, expires: u64) -> HmacSha256 {
+    // HMAC accepts keys of any length; `with_signing` rules out an empty one.
+    let mut mac = HmacSha256::new_from_slice(signing).expect("HMAC takes any key length");
+    mac.update(key.as_bytes());
+    mac.update(b":");
+    mac.update(expires.to_string().as_bytes());
+    mac
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
+
+/// `MediaStorage` whose `get_url` returns `MediaUrls` URLs; everything else
+/// goes to the wrapped backend.
+#[derive(Clone)]
+pub struct CdnMediaStorage<S> {
+    inner: S,
+    /// `None` keeps the backend's own URLs.
+    urls:  Option<MediaUrls>,
+}
+
+impl<S> CdnMediaStorage<S> {
+    pub fn new(inner: S, urls: Option<MediaUrls>) -> Self {
+        Self { inner, urls }
+    }
+}
+
+#[async_trait]
+impl<S: MediaStorage> MediaStorage for CdnMediaStorage<S> {
+    async fn store(&self, key: &MediaKey, data: Bytes, content_type: &str) -> Result<(), DomainError> {
+        self.inner.store(key, data, content_type).await
+    }
+
+    async fn store_file(&self, key: &MediaKey, path: &Path, content_type: &str) -> Result<(), DomainError> {
+        self.inner.store_file(key, path, content_type).await
+    }
+
+    async fn get_url(&self, key: &MediaKey, ttl: Duration) -> Result<String, DomainError> {
+        match &self.urls {
+            Some(urls) => Ok(urls.url_for(key, ttl)),
+            None => self.inner.get_url(key, ttl).await,
+        }
+    }
+
+    async fn fetch(&self, key: &MediaKey) -> Result<Bytes, DomainError> {
+        self.inner.fetch(key).await
+    }
+
+    async fn delete(&self, key: &MediaKey) -> Result<(), DomainError> {
+        self.inner.delete(key).await
+    }
+
+    async fn exists(&self, key: &MediaKey) -> Result<bool, DomainError> {
+        self.inner.exists(key).await
+    }
+
+    async fn list(&self) -> Result<Vec<StoredMedia>, DomainError> {
+        self.inner.list().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key() -> MediaKey { MediaKey::new("uploads/a.png") }
+
+    fn parts(url: &str) -> (u64, String) {
+        let query = url.split_once('?').unwrap().1;
+        let (expires, sig) = query.split_once('&').unwrap();
+        (expires.strip_prefix("expires=").unwrap().parse().unwrap(), sig.strip_prefix("sig=").unwrap().to_owned())
+    }
+
+    #[test]
+    fn unsigned_urls_join_base_and_key() {
+        assert_eq!(MediaUrls::default().url(&key()), "/media/uploads/a.png");
+        let urls = MediaUrls::new("https://cdn.example.com/media/");
+        assert_eq!(urls.url(&key()), "https://cdn.example.com/media/uploads/a.png");
+        assert!(urls.verify("uploads/a.png", None, None));
+        assert!(!urls.is_signed());
+    }
+
+    #[test]
+    fn origin_is_set_for_absolute_bases_only() {
+        assert_eq!(MediaUrls::default().origin(), None);
+        assert_eq!(MediaUrls::new("https://cdn.example.com:8443/media").origin(), Some("https://cdn.example.com:8443"));
+        assert_eq!(MediaUrls::new("https://cdn.example.com").origin(), Some("https://cdn.example.com"));
+        assert_eq!(MediaUrls::new("ftp://cdn.example.com/media").origin(), None);
+    }
+
+    #[test]
+    fn signed_url_verifies_until_it_expires() {
+        let urls = MediaUrls::new("/media").with_signing(b"secret", Duration::from_secs(60));
+        let url = urls.url_at(&key(), Duration::from_secs(60), 1_000);
+        assert!(url.starts_with("/media/uploads/a.png?expires="));
+        let (expires, sig) = parts(&url);
+        assert!(expires >= 1_060);
+        assert!(urls.verify_at("uploads/a.png", Some(expires), Some(&sig), 1_000));
+        assert!(!urls.verify_at("uploads/a.png", Some(expires), Some(&sig), expires));
+    }
+
+    #[test]
+    fn signature_is_bound_to_key_expiry_and_secret() {
+        let urls = MediaUrls::new("/media").with_signing(b"secret", Duration::from_secs(60));
+        let (expires, sig) = parts(&urls.url_at(&key(), Duration::from_secs(60), 1_000));
+        assert!(!urls.verify_at("uploads/b.png", Some(expires), Some(&sig), 1_000));
+        assert!(!urls.verify_at("uploads/a.png", Some(expires + 60), Some(&sig), 1_000));
+        assert!(!urls.verify_at("uploads/a.png", Some(expires), Some("zz"), 1_000));
+        assert!(!urls.verify_at("uploads/a.png", None, None, 1_000));
+        let other = MediaUrls::new("/media").with_signing(b"other", Duration::from_secs(60));
+        assert!(!other.verify_at("uploads/a.png", Some(expires), Some(&sig), 1_000));
+    }
+
+    #[test]
+    fn urls_are_stable_within_a_ttl_window() {
+        let urls = MediaUrls::new("/media").with_signing(b"secret", Duration::from_secs(60));
+        let ttl = Duration::from_secs(60);
+        assert_eq!(urls.url_at(&key(), ttl, 1_020), urls.url_at(&key(), ttl, 1_079));
+        assert_ne!(urls.url_at(&key(), ttl, 1_079), urls.url_at(&key(), ttl, 1_080));
+    }
+
+    #[tokio::test]
+    async fn storage_get_url_uses_configured_urls() {
+        let mut inner = domains::ports::MockMediaStorage::new();
+        inner.expect_get_url().returning(|key, _| Ok(format!("https://bucket/{key}")));
+        let plain = CdnMediaStorage::new(inner, None);
+        assert_eq!(plain.get_url(&key(), Duration::from_secs(60)).await.unwrap(), "https://bucket/uploads/a.png");
+
+        let cdn = CdnMediaStorage::new(plain, Some(MediaUrls::new("https://cdn.example.com")));
+        assert_eq!(cdn.get_url(&key(), Duration::from_secs(60)).await.unwrap(), "https://cdn.example.com/uploads/a.png");
+    }
+
+    #[test]
+    fn empty_signing_key_leaves_urls_unsigned() {
+        let urls = MediaUrls::default().with_signing(b"", Duration::from_secs(60));
+        assert!(!urls.is_signed());
+        assert_eq!(urls.url(&key()), "/media/uploads/a.png");
+    }
+}
@@ -0,0 +1,89 @@
+//! Dispatching media processor.
+//!
+//! Each optional media feature ships its own `MediaProcessor`. The composition
+//! root chains the compiled-in ones into a `CompositeMediaProcessor`, which
+//! hands every upload to the first processor that accepts its MIME type.
+
+use async_trait::async_trait;
+use domains::errors::{DomainError, ValidationError};
+use domains::ports::{MediaProcessor, ProcessedMedia, RawMedia};
+use mime::Mime;
+
+/// Routes each upload to the first registered processor accepting its MIME type.
+pub struct CompositeMediaProcessor {
+    processors: Vec<Box<dyn MediaProcessor>>,
+}
+
+impl CompositeMediaProcessor {
+    /// Start a chain with `first` as the highest-priority processor.
+    pub fn new(first: impl MediaProcessor) -> Self {
+        Self { processors: vec![Box::new(first)] }
+    }
+
+    /// Append a processor, consulted after all previously added ones.
+    pub fn with(mut self, processor: impl MediaProcessor) -> Self {
+        self.processors.push(Box::new(processor));
+        self
+    }
+}
+
+#[async_trait]
+impl MediaProcessor for CompositeMediaProcessor {
+    async fn process(&self, input: RawMedia) -> Result<ProcessedMedia, DomainError> {
+        match self.processors.iter().find(|p| p.accepts(&input.mime)) {
+            Some(processor) => processor.process(input).await,
+            None => Err(DomainError::Validation(ValidationError::DisallowedMime {
+                mime: input.mime.to_string(),
+            })),
+        }
+    }
+
+    fn accepts(&self, mime: &Mime) -> bool {
+        self.processors.iter().any(|p| p.accepts(mime))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use domains::ports::MockMediaProcessor;
+
+    fn only(mime: &'static str) -> MockMediaProcessor {
+        let mut p = MockMediaProcessor::new();
+        p.expect_accepts().returning(move |m| m.as_ref() == mime);
+        p
+    }
+
+    #[tokio::test]
+    async fn routes_to_first_accepting_processor() {
+        let images = only("image/png");
+        let mut audio = only("audio/ogg");
+        audio.expect_process().times(1).returning(|_| Err(DomainError::media_processing("audio")));
+        let chain = CompositeMediaProcessor::new(images).with(audio);
+
+        let input = RawMedia {
+            filename:       "a.ogg".to_owned(),
+            mime:           "audio/ogg".parse().unwrap(),
+            data:           bytes::Bytes::new().into(),
+            strip_metadata: true,
+            thumbnail:      None,
+        };
+        let err = chain.process(input).await.unwrap_err();
+        assert!(matches!(err, DomainError::MediaProcessing { ref reason } if reason == "audio"));
+    }
+
+    #[tokio::test]
+    async fn unaccepted_mime_is_disallowed() {
+        let chain = CompositeMediaProcessor::new(only("image/png"));
+        assert!(!chain.accepts(&"video/webm".parse().unwrap()));
+        let input = RawMedia {
+            filename:       "a.webm".to_owned(),
+            mime:           "video/webm".parse().unwrap(),
+            data:           bytes::Bytes::new().into(),
+            strip_metadata: true,
+            thumbnail:      None,
+        };
+        let err = chain.process(input).await.unwrap_err();
+        assert!(matches!(err, DomainError::Validation(ValidationError::DisallowedMime { .. })));
+    }
+}
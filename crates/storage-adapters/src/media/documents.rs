@@ -1,39 +1,71 @@
-//! PDF document processor using pdfium-render for first-page thumbnail extraction.
+//! Document processor: PDF first-page thumbnails via PDFium, EPUB cover art.
 //!
 //! This module is only compiled when the `documents` feature is active.
 //!
+//! - **PDF** — the first page is rasterised with `pdfium-render` and the page
+//!   count is recorded. PDFium is loaded dynamically at startup from
+//!   `Settings.pdfium_library_path` (or the system library path). If it cannot
+//!   be loaded, PDF uploads fail with a processing error; EPUB keeps working.
+//! - **EPUB** — the cover image named in the OPF package document is used as
+//!   the thumbnail. Books without a cover are stored without one.
+//!
+//! Originals are stored as uploaded.
+//!
 //! # Licensing note
 //! pdfium-render requires a pre-built PDFium binary. PDFium is BSD-licensed.
-//! Validate the distribution model before shipping. Treat this feature as
-//! experimental for v1.0.
-//!
 //! See `TECHNICALSPECS.md §2` for notes on PDFium binary distribution.
 
-// TODO(v1.0): Implement DocumentMediaProcessor using pdfium-render first-page rendering.
-// Validate licensing and binary distribution before implementing.
+use std::io::{Cursor, Read};
+use std::path::Path;
+use std::sync::Arc;
 
 use async_trait::async_trait;
+use bytes::Bytes;
 use domains::errors::DomainError;
+use domains::models::{MediaKey, ThumbnailSpec};
 use domains::ports::{MediaProcessor, ProcessedMedia, RawMedia};
 use mime::Mime;
+use pdfium_render::prelude::{PdfRenderConfig, Pdfium};
+use tracing::warn;
 
-const SUPPORTED_DOCUMENT_MIMES: &[&str] = &["application/pdf"];
+use super::images::encode_thumbnail;
 
-/// Media processor for PDF documents.
-///
-/// Renders the first page as a PNG thumbnail using PDFium.
-pub struct DocumentMediaProcessor;
+const SUPPORTED_DOCUMENT_MIMES: &[&str] = &["application/pdf", "application/epub+zip"];
+
+/// Upper bound on any single file read out of an EPUB archive. Guards against
+/// zip bombs; real cover images and package documents are far smaller.
+const MAX_EPUB_ENTRY_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Media processor for PDF and EPUB documents.
+pub struct DocumentMediaProcessor {
+    pdfium:    Option<Arc<Pdfium>>,
+    thumbnail: ThumbnailSpec,
+}
 
 impl DocumentMediaProcessor {
-    /// Create a new document processor.
-    pub fn new() -> Self {
-        Self
+    /// Create a processor, binding PDFium from `library_path` (a file or the
+    /// directory containing it) or, when `None`, from the system library path.
+    ///
+    /// A failed bind is logged and leaves PDF rendering disabled.
+    pub fn new(library_path: Option<&Path>) -> Self {
+        let bindings = match library_path {
+            Some(p) if p.is_dir() => Pdfium::bind_to_library(Pdfium::pdfium_platform_library_name_at_path(p)),
+            Some(p) => Pdfium::bind_to_library(p),
+            None => Pdfium::bind_to_system_library(),
+        };
+        let pdfium = match bindings {
+            Ok(b) => Some(Arc::new(Pdfium::new(b))),
+            Err(e) => {
+                warn!(error = %e, "PDFium library not available — PDF uploads will be rejected");
+                None
+            }
+        };
+        Self { pdfium, thumbnail: ThumbnailSpec::default() }
     }
-}
 
-impl Default for DocumentMediaProcessor {
-    fn default() -> Self {
-        Self::new()
+    /// Thumbnail uploads whose board has no override with `spec`.
+    pub fn with_thumbnail(self, spec: ThumbnailSpec) -> Self {
+        Self { thumbnail: spec, ..self }
     }
 }
 
@@ -42,23 +74,275 @@ impl MediaProcessor for DocumentMediaProcessor {
     async fn process(&self, input: RawMedia) -> Result<ProcessedMedia, DomainError> {
         let mime_str = input.mime.to_string();
 
-        // Delegate images to ImageMediaProcessor
-        if super::images::ImageMediaProcessor::new().accepts(&input.mime) {
-            return super::images::ImageMediaProcessor::new().process(input).await;
-        }
-
+        // Step 1: Validate MIME type
         if !self.accepts(&input.mime) {
             return Err(DomainError::Validation(
                 domains::errors::ValidationError::DisallowedMime { mime: mime_str },
             ));
         }
 
-        // TODO(v1.0): Render first PDF page as PNG thumbnail using pdfium-render
-        Err(DomainError::media_processing("PDF processing not yet implemented"))
+        // Step 2: Render a preview. Both paths parse untrusted archives/documents,
+        // so they run on the blocking pool.
+        let data = super::load(&input.data).await?;
+        let spec = input.thumbnail.unwrap_or(self.thumbnail);
+        let (thumb, page_count) = if mime_str == "application/pdf" {
+            let pdfium = self.pdfium.clone().ok_or_else(|| {
+                DomainError::media_processing("PDF rendering is not available on this server")
+            })?;
+            tokio::task::spawn_blocking(move || render_pdf(&pdfium, &data, spec))
+                .await
+                .map_err(|e| DomainError::media_processing(format!("document worker failed: {e}")))??
+        } else {
+            let cover = tokio::task::spawn_blocking(move || epub_cover(&data))
+                .await
+                .map_err(|e| DomainError::media_processing(format!("document worker failed: {e}")))??;
+            let thumb = match cover.as_deref().and_then(|b| image::load_from_memory(b).ok()) {
+                Some(img) => Some(encode_thumbnail(&img, spec)?),
+                None => None,
+            };
+            (thumb, None)
+        };
+
+        // Step 3: Compute content hash of the stored original
+        let hash = super::content_hash(&input.data);
+        let md5 = super::upload_md5(&input.data);
+
+        let size_kb = (input.data.len() as u32).div_ceil(1024);
+        let ext = extension_for_mime(&mime_str);
+        let key_base = uuid::Uuid::new_v4().to_string();
+        let original_key = MediaKey::new(format!("{key_base}.{ext}"));
+        let thumbnail_key = thumb.as_ref().map(|_| MediaKey::new(format!("{key_base}_thumb.{}", spec.format.extension())));
+
+        Ok(ProcessedMedia {
+            original_key,
+            original_data: input.data,
+            thumbnail_key,
+            thumbnail_data: thumb,
+            thumbnail_task: None,
+            hash,
+            size_kb,
+            duration_ms: None,
+            page_count,
+            md5,
+            phash: None,
+            width: None,
+            height: None,
+            stored_mime: None,
+        })
     }
 
     fn accepts(&self, mime: &Mime) -> bool {
-        super::images::ImageMediaProcessor::new().accepts(mime)
-            || SUPPORTED_DOCUMENT_MIMES.contains(&mime.as_ref())
+        SUPPORTED_DOCUMENT_MIMES.contains(&mime.as_ref())
+    }
+}
+
+/// Rasterise the first page and count pages.
+fn render_pdf(pdfium: &Pdfium, data: &[u8], spec: ThumbnailSpec) -> Result<(Option<Bytes>, Option<u32>), DomainError> {
+    let bad = |e: pdfium_render::prelude::PdfiumError| {
+        DomainError::media_processing(format!("failed to read PDF: {e}"))
+    };
+    let document = pdfium.load_pdf_from_byte_slice(data, None).map_err(bad)?;
+    let pages = document.pages();
+    let page_count = pages.len() as u32;
+    let first = pages.first().map_err(bad)?;
+    let bitmap = first
+        .render_with_config(&PdfRenderConfig::new().set_target_width(spec.max_width as i32))
+        .map_err(bad)?;
+    let thumb = encode_thumbnail(&bitmap.as_image(), spec)?;
+    Ok((Some(thumb), Some(page_count)))
+}
+
+/// Extract the cover image bytes of an EPUB, if it declares one.
+///
+/// Follows `META-INF/container.xml` to the OPF package document, then looks
+/// for the EPUB 3 `properties="cover-image"` manifest item, falling back to
+/// the EPUB 2 `<meta name="cover" content="…">` reference.
+fn epub_cover(data: &[u8]) -> Result<Option<Vec<u8>>, DomainError> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(data))
+        .map_err(|e| DomainError::media_processing(format!("failed to read EPUB: {e}")))?;
+
+    let container = read_entry(&mut archive, "META-INF/container.xml")?
+        .ok_or_else(|| DomainError::media_processing("EPUB is missing META-INF/container.xml"))?;
+    let container = String::from_utf8_lossy(&container);
+    let Some(opf_path) = tags(&container, "rootfile").find_map(|t| attr(t, "full-path")) else {
+        return Err(DomainError::media_processing("EPUB container names no package document"));
+    };
+    let Some(opf) = read_entry(&mut archive, &opf_path)? else {
+        return Err(DomainError::media_processing("EPUB package document is missing"));
+    };
+    let opf = String::from_utf8_lossy(&opf);
+
+    let items: Vec<&str> = tags(&opf, "item").collect();
+    let href = items
+        .iter()
+        .find(|t| attr(t, "properties").is_some_and(|p| p.split_whitespace().any(|p| p == "cover-image")))
+        .and_then(|t| attr(t, "href"))
+        .or_else(|| {
+            let id = tags(&opf, "meta")
+                .find(|t| attr(t, "name").as_deref() == Some("cover"))
+                .and_then(|t| attr(t, "content"))?;
+            items.iter().find(|t| attr(t, "id").as_deref() == Some(id.as_str())).and_then(|t| attr(t, "href"))
+        });
+    let Some(href) = href else {
+        return Ok(None);
+    };
+
+    // Manifest hrefs are relative to the package document's directory.
+    let base = opf_path.rsplit_once('/').map(|(dir, _)| dir).unwrap_or("");
+    let cover_path = if base.is_empty() { href } else { format!("{base}/{href}") };
+    read_entry(&mut archive, &cover_path)
+}
+
+/// Read one archive member, bounded by `MAX_EPUB_ENTRY_BYTES`. `None` if absent.
+fn read_entry(
+    archive: &mut zip::ZipArchive<Cursor<&[u8]>>,
+    name: &str,
+) -> Result<Option<Vec<u8>>, DomainError> {
+    let entry = match archive.by_name(name) {
+        Ok(entry) => entry,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(DomainError::media_processing(format!("failed to read EPUB: {e}"))),
+    };
+    let mut buf = Vec::new();
+    entry
+        .take(MAX_EPUB_ENTRY_BYTES + 1)
+        .read_to_end(&mut buf)
+        .map_err(|e| DomainError::media_processing(format!("failed to read EPUB: {e}")))?;
+    if buf.len() as u64 > MAX_EPUB_ENTRY_BYTES {
+        return Err(DomainError::media_processing(format!("EPUB entry '{name}' is too large")));
+    }
+    Ok(Some(buf))
+}
+
+/// Iterate the attribute text of every `<name …>` start tag in `xml`.
+///
+/// Deliberately minimal: the package documents only need attribute lookups on
+/// a handful of flat elements, so no XML parser is pulled in. Namespace
+/// prefixes (`<opf:item>`) are accepted.
+fn tags<'a>(xml: &'a str, name: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+    xml.split('<').skip(1).filter_map(move |chunk| {
+        let body = chunk.split('>').next()?;
+        let tag = body.split_whitespace().next()?;
+        let local = tag.rsplit(':').next()?;
+        (local == name).then_some(&body[tag.len()..])
+    })
+}
+
+/// Value of attribute `name` in a start tag's attribute text, with the basic
+/// XML entities unescaped.
+fn attr(tag: &str, name: &str) -> Option<String> {
+    let mut rest = tag;
+    while let Some(pos) = rest.find(name) {
+        let before_ok = rest[..pos].chars().last().is_none_or(char::is_whitespace);
+        let after = rest[pos + name.len()..].trim_start();
+        if before_ok {
+            if let Some(value) = after.strip_prefix('=') {
+                let value = value.trim_start();
+                let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+                let end = value[1..].find(quote)?;
+                return Some(
+                    value[1..=end]
+                        .replace("&quot;", "\"")
+                        .replace("&apos;", "'")
+                        .replace("&lt;", "<")
+                        .replace("&gt;", ">")
+                        .replace("&amp;", "&"),
+                );
+            }
+        }
+        rest = &rest[pos + name.len()..];
+    }
+    None
+}
+
+fn extension_for_mime(mime: &str) -> &'static str {
+    match mime {
+        "application/pdf"      => "pdf",
+        "application/epub+zip" => "epub",
+        _                      => "bin",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    fn epub(opf: &str, cover: Option<&[u8]>) -> Bytes {
+        let mut w = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let stored = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+        w.start_file("mimetype", stored).unwrap();
+        w.write_all(b"application/epub+zip").unwrap();
+        w.start_file("META-INF/container.xml", SimpleFileOptions::default()).unwrap();
+        w.write_all(br#"<?xml version="1.0"?><container><rootfiles><rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/></rootfiles></container>"#).unwrap();
+        w.start_file("OEBPS/content.opf", SimpleFileOptions::default()).unwrap();
+        w.write_all(opf.as_bytes()).unwrap();
+        if let Some(cover) = cover {
+            w.start_file("OEBPS/images/cover.png", SimpleFileOptions::default()).unwrap();
+            w.write_all(cover).unwrap();
+        }
+        Bytes::from(w.finish().unwrap().into_inner())
+    }
+
+    fn png() -> Vec<u8> {
+        let mut buf = Cursor::new(Vec::new());
+        image::DynamicImage::new_rgb8(640, 960).write_to(&mut buf, image::ImageFormat::Png).unwrap();
+        buf.into_inner()
+    }
+
+    fn raw(data: Bytes) -> RawMedia {
+        RawMedia {
+            filename:       "book.epub".to_owned(),
+            mime:           "application/epub+zip".parse().unwrap(),
+            data:           data.into(),
+            strip_metadata: true,
+            thumbnail:      None,
+        }
+    }
+
+    #[tokio::test]
+    async fn epub3_cover_image_becomes_thumbnail() {
+        let opf = r#"<package><manifest>
+            <item id="c" href="images/cover.png" media-type="image/png" properties="cover-image"/>
+        </manifest></package>"#;
+        let processor = DocumentMediaProcessor { pdfium: None, thumbnail: ThumbnailSpec::default() };
+        let out = processor.process(raw(epub(opf, Some(&png())))).await.unwrap();
+
+        let thumb = image::load_from_memory(out.thumbnail_data.as_ref().unwrap()).unwrap();
+        assert_eq!(thumb.width(), 320);
+        assert!(out.original_key.0.ends_with(".epub"));
+        assert_eq!(out.page_count, None);
+    }
+
+    #[test]
+    fn epub2_cover_meta_is_followed() {
+        let opf = r#"<opf:package><opf:metadata><meta content="cover-img" name="cover"/></opf:metadata>
+            <opf:manifest><opf:item href="images/cover.png" id="cover-img" media-type="image/png"/></opf:manifest></opf:package>"#;
+        let cover = epub_cover(&epub(opf, Some(b"PNGDATA"))).unwrap();
+        assert_eq!(cover.as_deref(), Some(&b"PNGDATA"[..]));
+    }
+
+    #[tokio::test]
+    async fn epub_without_cover_has_no_thumbnail() {
+        let opf = r#"<package><manifest><item id="t" href="text.xhtml"/></manifest></package>"#;
+        let processor = DocumentMediaProcessor { pdfium: None, thumbnail: ThumbnailSpec::default() };
+        let out = processor.process(raw(epub(opf, None))).await.unwrap();
+        assert!(out.thumbnail_key.is_none());
+        assert!(out.thumbnail_data.is_none());
+    }
+
+    #[tokio::test]
+    async fn pdf_without_pdfium_is_a_processing_error() {
+        let processor = DocumentMediaProcessor { pdfium: None, thumbnail: ThumbnailSpec::default() };
+        let input = RawMedia {
+            filename:       "paper.pdf".to_owned(),
+            mime:           "application/pdf".parse().unwrap(),
+            data:           Bytes::from_static(b"%PDF-1.7\n").into(),
+            strip_metadata: true,
+            thumbnail:      None,
+        };
+        let err = processor.process(input).await.unwrap_err();
+        assert!(matches!(err, DomainError::MediaProcessing { .. }), "got {err:?}");
     }
 }
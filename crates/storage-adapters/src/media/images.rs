@@ -3,23 +3,28 @@
 //! This module is **always compiled** — image support is not feature-gated.
 //! All uploaded images go through this processor regardless of other active features.
 //!
-//! # INVARIANT: EXIF stripping is unconditional
-//! EXIF metadata is stripped from every image regardless of board configuration,
-//! Settings, or any operator toggle. It is a hard-coded business rule, not a
-//! configurable parameter. This protects poster privacy.
+//! # INVARIANT: metadata stripping is on by default
+//! EXIF/XMP/GPS metadata is stripped from every stored original unless the
+//! board owner has explicitly set `BoardConfig::strip_metadata = false`
+//! (carried on `RawMedia::strip_metadata`). There is no Settings or operator
+//! toggle. Thumbnails are always generated from decoded pixels and never carry
+//! metadata.
 
 use async_trait::async_trait;
 use bytes::Bytes;
 use domains::errors::DomainError;
-use domains::models::{ContentHash, MediaKey};
-use domains::ports::{MediaProcessor, ProcessedMedia, RawMedia};
+use domains::models::{MediaKey, ThumbnailFormat, ThumbnailSpec};
+use domains::ports::{MediaData, MediaProcessor, ProcessedMedia, RawMedia, ThumbnailTask};
+use image::codecs::avif::AvifEncoder;
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::webp::WebPEncoder;
+use image::imageops::FilterType;
 use image::ImageFormat;
 use mime::Mime;
-use sha2::{Digest, Sha256};
 use std::io::Cursor;
+use std::sync::Arc;
 
-/// Thumbnail width in pixels. Matches `Settings.thumbnail_width_px` default.
-const THUMBNAIL_WIDTH_PX: u32 = 320;
+use super::thumbnail_queue::{QueuedThumbnail, ThumbnailQueue, Thumbnailer};
 
 /// Supported image MIME types.
 const SUPPORTED_MIMES: &[&str] = &[
@@ -29,28 +34,105 @@ const SUPPORTED_MIMES: &[&str] = &[
     "image/webp",
 ];
 
+/// Format large originals are transcoded to (see `Reencode`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReencodeFormat {
+    /// Lossless WebP. Mostly pays off for PNG screenshots and drawings.
+    WebP,
+    /// Lossy AVIF at `Reencode::quality`.
+    Avif,
+}
+
+impl ReencodeFormat {
+    fn mime(self) -> &'static str {
+        match self {
+            Self::WebP => "image/webp",
+            Self::Avif => "image/avif",
+        }
+    }
+}
+
+/// Transcode large JPEG/PNG originals before they are stored.
+///
+/// The transcoded file is kept only when it is smaller than the original;
+/// boards that keep originals as uploaded (`strip_metadata = false`) are
+/// never transcoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reencode {
+    /// Target format.
+    pub format:    ReencodeFormat,
+    /// AVIF quality, 1–100. WebP output is lossless and ignores it.
+    pub quality:   u8,
+    /// Uploads smaller than this many bytes keep their own format.
+    pub min_bytes: u64,
+}
+
+/// AVIF encoder speed, 1 (slowest) to 10. 8 keeps a large photo to a few seconds.
+const AVIF_SPEED: u8 = 8;
+
 /// Media processor that handles images using the `image` crate.
 ///
 /// Processes JPEG, PNG, GIF, and WebP:
 /// 1. Validates MIME type
-/// 2. Strips EXIF metadata by re-encoding through the `image` crate
-/// 3. Generates a 320px-wide thumbnail as PNG
-/// 4. Computes SHA-256 content hash of the re-encoded original
+/// 2. Strips EXIF/XMP/GPS metadata by re-encoding through the `image` crate
+///    (skipped when `RawMedia::strip_metadata` is false — the original is kept as uploaded)
+/// 3. Generates a thumbnail as described by `RawMedia::thumbnail` (or the processor's
+///    default `ThumbnailSpec`) with the configured `Thumbnailer` — left as a
+///    `ProcessedMedia::thumbnail_task` for the caller to submit when a
+///    `ThumbnailQueue` is attached, otherwise before returning
+/// 4. Computes SHA-256 content hash of the stored original
+///
+/// With `with_reencode`, large JPEG/PNG originals are transcoded to WebP or
+/// AVIF in step 2; `ProcessedMedia::stored_mime` then names the new format.
+///
+/// Decoding and encoding run on the blocking thread pool.
 pub struct ImageMediaProcessor {
-    thumbnail_width: u32,
+    thumbnail:   ThumbnailSpec,
+    queue:       Option<ThumbnailQueue>,
+    thumbnailer: Thumbnailer,
+    reencode:    Option<Reencode>,
 }
 
 impl ImageMediaProcessor {
-    /// Create a new processor with the default thumbnail width (320px).
+    /// Create a new processor with the default thumbnail spec (320px wide PNG).
     pub fn new() -> Self {
         Self {
-            thumbnail_width: THUMBNAIL_WIDTH_PX,
+            thumbnail:   ThumbnailSpec::default(),
+            queue:       None,
+            thumbnailer: Thumbnailer::Image,
+            reencode:    None,
         }
     }
 
     /// Create a processor with a custom thumbnail width (for testing).
-    pub fn with_thumbnail_width(thumbnail_width: u32) -> Self {
-        Self { thumbnail_width }
+    pub fn with_thumbnail_width(max_width: u32) -> Self {
+        Self::new().with_thumbnail(ThumbnailSpec { max_width, ..ThumbnailSpec::default() })
+    }
+
+    /// Thumbnail uploads whose board has no override with `spec`.
+    pub fn with_thumbnail(mut self, spec: ThumbnailSpec) -> Self {
+        self.thumbnail = spec;
+        self
+    }
+
+    /// Transcode large JPEG/PNG originals as configured by `reencode`.
+    pub fn with_reencode(mut self, reencode: Reencode) -> Self {
+        self.reencode = Some(reencode);
+        self
+    }
+
+    /// Render thumbnails with `thumbnailer` instead of the `image` crate.
+    pub fn with_thumbnailer(mut self, thumbnailer: Thumbnailer) -> Self {
+        self.thumbnailer = thumbnailer;
+        self
+    }
+
+    /// Defer thumbnail generation to `queue`. `ProcessedMedia::thumbnail_data`
+    /// is then `None` and `thumbnail_task` renders and stores the file once
+    /// the caller submits it.
+    pub fn with_queue(mut self, queue: ThumbnailQueue) -> Self {
+        self.queue = Some(queue);
+        self
     }
 }
 
@@ -66,69 +148,108 @@ impl MediaProcessor for ImageMediaProcessor {
         let mime_str = input.mime.to_string();
 
         // Step 1: Validate MIME type
-        if !self.accepts(&input.mime) {
-            return Err(DomainError::Validation(
-                domains::errors::ValidationError::DisallowedMime { mime: mime_str.clone() },
-            ));
-        }
-
-        // Step 2: Decode image (this strips EXIF by going through image decode/encode)
-        // INVARIANT: We re-encode through the `image` crate which discards all
-        // metadata including EXIF. This is the only place this happens.
-        let img = image::load_from_memory(&input.data).map_err(|e| {
-            DomainError::media_processing(format!("failed to decode image: {e}"))
-        })?;
-
-        // Step 3: Re-encode original as PNG (EXIF stripped by the decode/encode cycle)
         let format = match mime_str.as_str() {
             "image/jpeg" => ImageFormat::Jpeg,
             "image/png"  => ImageFormat::Png,
             "image/gif"  => ImageFormat::Gif,
             "image/webp" => ImageFormat::WebP,
-            _ => unreachable!("MIME already validated above"),
+            _ => {
+                return Err(DomainError::Validation(
+                    domains::errors::ValidationError::DisallowedMime { mime: mime_str },
+                ))
+            }
         };
-        let mut original_buf = Cursor::new(Vec::new());
-        img.write_to(&mut original_buf, format).map_err(|e| {
-            DomainError::media_processing(format!("failed to re-encode image: {e}"))
-        })?;
-        let original_bytes = Bytes::from(original_buf.into_inner());
-
-        // Step 4: Compute content hash of re-encoded original
-        let hash = {
-            let mut hasher = Sha256::new();
-            hasher.update(&original_bytes);
-            ContentHash::new(hex::encode(hasher.finalize()))
+
+        // Step 2: Decode (validates the payload) and, unless the board opted
+        // out, re-encode the original in its own format. The `image` crate
+        // writes pixels only, so EXIF/XMP/GPS blocks are discarded. This is the
+        // only place this happens. The `image` backend renders the thumbnail
+        // from the same decode when there is no queue to hand it to.
+        let data = super::load(&input.data).await?;
+        let md5 = super::upload_md5(&input.data);
+        let strip = input.strip_metadata;
+        let spec = input.thumbnail.unwrap_or(self.thumbnail);
+        let inline_spec = (self.queue.is_none() && matches!(self.thumbnailer, Thumbnailer::Image))
+            .then_some(spec);
+        let source = data.clone();
+        let reencode = self
+            .reencode
+            .filter(|r| strip && matches!(format, ImageFormat::Jpeg | ImageFormat::Png) && data.len() as u64 >= r.min_bytes);
+        let (stripped, transcoded, inline_thumb, phash, (width, height)) = tokio::task::spawn_blocking(move || {
+            let img = image::load_from_memory(&source).map_err(|e| {
+                DomainError::media_processing(format!("failed to decode image: {e}"))
+            })?;
+            let stripped = if strip {
+                let mut original_buf = Cursor::new(Vec::new());
+                img.write_to(&mut original_buf, format).map_err(|e| {
+                    DomainError::media_processing(format!("failed to re-encode image: {e}"))
+                })?;
+                Some(Bytes::from(original_buf.into_inner()))
+            } else {
+                None
+            };
+            // Keep a transcode only if it actually saves space.
+            let baseline = stripped.as_ref().map_or(source.len(), Bytes::len);
+            let (stripped, transcoded) = match reencode.map(|r| transcode(&img, r)).transpose()? {
+                Some(bytes) if bytes.len() < baseline => (Some(bytes), reencode.map(|r| r.format)),
+                _ => (stripped, None),
+            };
+            let thumb = inline_spec.map(|s| encode_thumbnail(&img, s)).transpose()?;
+            Ok::<_, DomainError>((stripped, transcoded, thumb, perceptual_hash(&img), (img.width(), img.height())))
+        })
+        .await
+        .map_err(|e| DomainError::media_processing(format!("image worker failed: {e}")))??;
+
+        // Step 3: Compute content hash of the stored original
+        let (original, stored_bytes, hash) = match stripped {
+            Some(bytes) => {
+                let hash = super::hash_bytes(&bytes);
+                (MediaData::Memory(bytes.clone()), bytes, hash)
+            }
+            None => {
+                let hash = super::content_hash(&input.data);
+                (input.data, data, hash)
+            }
         };
 
-        // Step 5: Generate thumbnail
-        let thumb_img = img.thumbnail(self.thumbnail_width, u32::MAX);
-        let mut thumb_buf = Cursor::new(Vec::new());
-        thumb_img.write_to(&mut thumb_buf, ImageFormat::Png).map_err(|e| {
-            DomainError::media_processing(format!("failed to generate thumbnail: {e}"))
-        })?;
-
-        // Step 6: Compress thumbnail with oxipng
-        let raw_thumb_bytes = thumb_buf.into_inner();
-        let thumb_bytes = oxipng::optimize_from_memory(
-            &raw_thumb_bytes,
-            &oxipng::Options::default(),
-        )
-        .unwrap_or(raw_thumb_bytes); // fall back to uncompressed if optimisation fails
-        let thumb_bytes = Bytes::from(thumb_bytes);
-
-        let size_kb = (original_bytes.len() as u32).div_ceil(1024);
-        let ext = extension_for_mime(&mime_str);
+        let size_kb = (original.len() as u32).div_ceil(1024);
+        let stored_mime = transcoded.map(|f| f.mime().to_owned());
+        let ext = extension_for_mime(stored_mime.as_deref().unwrap_or(&mime_str));
         let key_base = format!("{}", uuid::Uuid::new_v4());
         let original_key = MediaKey::new(format!("{key_base}.{ext}"));
-        let thumbnail_key = MediaKey::new(format!("{key_base}_thumb.png"));
+        let thumbnail_key = MediaKey::new(format!("{key_base}_thumb.{}", spec.format.extension()));
+
+        // Step 4: Leave the thumbnail for the queue. The caller submits it
+        // once it knows the upload is kept, under the key it ends up with.
+        let (thumb_bytes, thumb_task) = match (inline_thumb, &self.queue) {
+            (Some(thumb), _) => (Some(thumb), None),
+            (None, Some(queue)) => {
+                let task = QueuedThumbnail {
+                    queue:       queue.clone(),
+                    source:      stored_bytes,
+                    spec,
+                    thumbnailer: self.thumbnailer.clone(),
+                };
+                (None, Some(Arc::new(task) as Arc<dyn ThumbnailTask>))
+            }
+            (None, None) => (Some(self.thumbnailer.render(stored_bytes, spec).await?), None),
+        };
 
         Ok(ProcessedMedia {
             original_key,
-            original_data: original_bytes,
+            original_data: original,
             thumbnail_key: Some(thumbnail_key),
-            thumbnail_data: Some(thumb_bytes),
+            thumbnail_data: thumb_bytes,
+            thumbnail_task: thumb_task,
             hash,
             size_kb,
+            duration_ms: None,
+            page_count: None,
+            md5,
+            phash: Some(phash),
+            width: Some(width),
+            height: Some(height),
+            stored_mime,
         })
     }
 
@@ -137,12 +258,224 @@ impl MediaProcessor for ImageMediaProcessor {
     }
 }
 
+/// Scale `img` down to fit `spec`'s bounds and encode it in `spec.format`.
+/// PNG output is oxipng-compressed.
+///
+/// Shared with the other processors so every thumbnail goes through the same pipeline.
+pub(super) fn encode_thumbnail(img: &image::DynamicImage, spec: ThumbnailSpec) -> Result<Bytes, DomainError> {
+    let (width, height) = spec.bounds();
+    let thumb_img = img.thumbnail(width, height);
+    let mut thumb_buf = Cursor::new(Vec::new());
+    let result = match spec.format {
+        ThumbnailFormat::Png => thumb_img.write_to(&mut thumb_buf, ImageFormat::Png),
+        // JPEG has no alpha channel; transparency is flattened to black.
+        ThumbnailFormat::Jpeg => image::DynamicImage::ImageRgb8(thumb_img.to_rgb8())
+            .write_with_encoder(JpegEncoder::new_with_quality(&mut thumb_buf, spec.quality.clamp(1, 100))),
+        ThumbnailFormat::Webp if thumb_img.color().has_alpha() => image::DynamicImage::ImageRgba8(thumb_img.to_rgba8())
+            .write_with_encoder(WebPEncoder::new_lossless(&mut thumb_buf)),
+        ThumbnailFormat::Webp => image::DynamicImage::ImageRgb8(thumb_img.to_rgb8())
+            .write_with_encoder(WebPEncoder::new_lossless(&mut thumb_buf)),
+    };
+    result.map_err(|e| DomainError::media_processing(format!("failed to generate thumbnail: {e}")))?;
+
+    let raw_thumb_bytes = thumb_buf.into_inner();
+    if spec.format != ThumbnailFormat::Png {
+        return Ok(Bytes::from(raw_thumb_bytes));
+    }
+    let thumb_bytes = oxipng::optimize_from_memory(
+        &raw_thumb_bytes,
+        &oxipng::Options::default(),
+    )
+    .unwrap_or(raw_thumb_bytes); // fall back to uncompressed if optimisation fails
+    Ok(Bytes::from(thumb_bytes))
+}
+
+/// Encode `img` in the `reencode` target format.
+fn transcode(img: &image::DynamicImage, reencode: Reencode) -> Result<Bytes, DomainError> {
+    let mut buf = Cursor::new(Vec::new());
+    let result = match reencode.format {
+        ReencodeFormat::WebP => img.write_with_encoder(WebPEncoder::new_lossless(&mut buf)),
+        ReencodeFormat::Avif => {
+            let quality = reencode.quality.clamp(1, 100);
+            img.write_with_encoder(AvifEncoder::new_with_speed_quality(&mut buf, AVIF_SPEED, quality))
+        }
+    };
+    result.map_err(|e| DomainError::media_processing(format!("failed to transcode image: {e}")))?;
+    Ok(Bytes::from(buf.into_inner()))
+}
+
+/// 64-bit difference hash ("dHash") of `img`.
+///
+/// The image is shrunk to 9×8 greyscale and each bit records whether a pixel
+/// is brighter than its right-hand neighbour. Re-encoding, resizing, and small
+/// edits flip only a few bits, so near-identical images sit a short Hamming
+/// distance apart.
+pub(crate) fn perceptual_hash(img: &image::DynamicImage) -> u64 {
+    let small = img.resize_exact(9, 8, FilterType::Triangle).to_luma8();
+    let mut hash = 0u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            hash <<= 1;
+            if small.get_pixel(x, y)[0] > small.get_pixel(x + 1, y)[0] {
+                hash |= 1;
+            }
+        }
+    }
+    hash
+}
+
 fn extension_for_mime(mime: &str) -> &'static str {
     match mime {
         "image/jpeg" => "jpg",
         "image/png"  => "png",
         "image/gif"  => "gif",
         "image/webp" => "webp",
+        "image/avif" => "avif",
         _            => "bin",
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A small JPEG with an `APP1` EXIF segment (carrying a fake GPS tag
+    /// marker) spliced in right after the SOI marker.
+    fn jpeg_with_exif() -> Bytes {
+        let img = image::DynamicImage::new_rgb8(8, 8);
+        let mut buf = Cursor::new(Vec::new());
+        img.write_to(&mut buf, ImageFormat::Jpeg).unwrap();
+        let plain = buf.into_inner();
+
+        let payload = b"Exif\0\0GPSLatitude";
+        let len = (payload.len() + 2) as u16;
+        let mut out = plain[..2].to_vec();
+        out.extend_from_slice(&[0xFF, 0xE1]);
+        out.extend_from_slice(&len.to_be_bytes());
+        out.extend_from_slice(payload);
+        out.extend_from_slice(&plain[2..]);
+        Bytes::from(out)
+    }
+
+    fn raw(data: Bytes, strip_metadata: bool) -> RawMedia {
+        RawMedia { filename: "photo.jpg".to_owned(), mime: mime::IMAGE_JPEG, data: data.into(), strip_metadata, thumbnail: None }
+    }
+
+    fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+        haystack.windows(needle.len()).any(|w| w == needle)
+    }
+
+    #[tokio::test]
+    async fn process_strips_exif_from_original() {
+        let input = jpeg_with_exif();
+        assert!(contains(&input, b"GPSLatitude"));
+
+        let out = ImageMediaProcessor::new().process(raw(input, true)).await.unwrap();
+        assert!(!contains(out.original_data.head(), b"Exif"));
+        assert!(!contains(out.original_data.head(), b"GPSLatitude"));
+        assert!(!contains(out.thumbnail_data.as_ref().unwrap(), b"GPSLatitude"));
+    }
+
+    #[tokio::test]
+    async fn process_keeps_original_when_stripping_disabled() {
+        let input = jpeg_with_exif();
+        let out = ImageMediaProcessor::new().process(raw(input.clone(), false)).await.unwrap();
+        assert_eq!(out.original_data, MediaData::Memory(input));
+        assert!(!contains(out.thumbnail_data.as_ref().unwrap(), b"GPSLatitude"));
+    }
+
+    #[tokio::test]
+    async fn queued_thumbnail_waits_for_submit_and_uses_its_key() {
+        let (done_tx, mut done_rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut storage = domains::ports::MockMediaStorage::new();
+        storage.expect_store().times(1).returning(move |key, _, _| {
+            done_tx.send(key.0.clone()).unwrap();
+            Ok(())
+        });
+        let queue = ThumbnailQueue::start(Arc::new(storage), 1, 4);
+
+        let processor = ImageMediaProcessor::new().with_queue(queue);
+        let out = processor.process(raw(jpeg_with_exif(), true)).await.unwrap();
+        assert!(out.thumbnail_key.is_some());
+        assert!(out.thumbnail_data.is_none());
+        tokio::task::yield_now().await;
+        assert!(done_rx.try_recv().is_err(), "nothing is queued before submit");
+
+        let task = out.thumbnail_task.unwrap();
+        assert!(task.submit(MediaKey::new("site/a_thumb.png")).await.unwrap().is_none());
+        assert_eq!(done_rx.recv().await.unwrap(), "site/a_thumb.png");
+    }
+
+    #[tokio::test]
+    async fn board_thumbnail_spec_sets_size_and_format() {
+        let spec = ThumbnailSpec { max_width: 32, max_height: 16, format: ThumbnailFormat::Jpeg, quality: 70 };
+        let input = RawMedia { thumbnail: Some(spec), ..png_raw(gradient_png(64), true) };
+        let out = ImageMediaProcessor::new().process(input).await.unwrap();
+
+        assert!(out.thumbnail_key.unwrap().0.ends_with("_thumb.jpg"));
+        let thumb = out.thumbnail_data.unwrap();
+        assert_eq!(image::guess_format(&thumb).unwrap(), ImageFormat::Jpeg);
+        let thumb = image::load_from_memory(&thumb).unwrap();
+        assert_eq!((thumb.width(), thumb.height()), (16, 16));
+    }
+
+    #[tokio::test]
+    async fn process_records_md5_of_the_upload() {
+        let input = jpeg_with_exif();
+        let expected = hex::encode(<md5::Md5 as sha2::Digest>::digest(&input));
+        let out = ImageMediaProcessor::new().process(raw(input, true)).await.unwrap();
+        assert_eq!(out.md5, expected);
+        assert!(out.phash.is_some());
+    }
+
+    fn gradient_png(size: u32) -> Bytes {
+        let img = image::DynamicImage::ImageRgb8(image::RgbImage::from_fn(size, size, |x, y| {
+            image::Rgb([(x * 3) as u8, (y * 3) as u8, ((x * y) % 251) as u8])
+        }));
+        let mut buf = Cursor::new(Vec::new());
+        img.write_to(&mut buf, ImageFormat::Png).unwrap();
+        Bytes::from(buf.into_inner())
+    }
+
+    fn png_raw(data: Bytes, strip_metadata: bool) -> RawMedia {
+        RawMedia { filename: "shot.png".to_owned(), mime: mime::IMAGE_PNG, data: data.into(), strip_metadata, thumbnail: None }
+    }
+
+    const AVIF: Reencode = Reencode { format: ReencodeFormat::Avif, quality: 60, min_bytes: 0 };
+
+    #[tokio::test]
+    async fn large_png_is_reencoded_to_avif() {
+        let input = gradient_png(64);
+        let out = ImageMediaProcessor::new().with_reencode(AVIF).process(png_raw(input.clone(), true)).await.unwrap();
+        assert_eq!(out.stored_mime.as_deref(), Some("image/avif"));
+        assert!(out.original_key.0.ends_with(".avif"));
+        assert!(out.original_data.len() < input.len() as u64);
+    }
+
+    #[tokio::test]
+    async fn small_or_unstripped_uploads_keep_their_format() {
+        let input = gradient_png(64);
+        let small = Reencode { min_bytes: input.len() as u64 + 1, ..AVIF };
+        let out = ImageMediaProcessor::new().with_reencode(small).process(png_raw(input.clone(), true)).await.unwrap();
+        assert_eq!(out.stored_mime, None);
+        assert!(out.original_key.0.ends_with(".png"));
+
+        let out = ImageMediaProcessor::new().with_reencode(AVIF).process(png_raw(input.clone(), false)).await.unwrap();
+        assert_eq!(out.stored_mime, None);
+        assert_eq!(out.original_data, MediaData::Memory(input));
+    }
+
+    #[test]
+    fn perceptual_hash_survives_resizing() {
+        let gradient = image::DynamicImage::ImageRgb8(image::RgbImage::from_fn(64, 64, |x, y| {
+            image::Rgb([(x * 4) as u8, (y * 4) as u8, ((x ^ y) * 4) as u8])
+        }));
+        let resized = gradient.resize_exact(48, 48, FilterType::Lanczos3);
+        let flipped = gradient.fliph();
+
+        let distance = |a: u64, b: u64| (a ^ b).count_ones();
+        let original = perceptual_hash(&gradient);
+        assert!(distance(original, perceptual_hash(&resized)) <= 4);
+        assert!(distance(original, perceptual_hash(&flipped)) > 16);
+    }
+}
@@ -0,0 +1,320 @@
+//! IPFS media storage adapter (`media-ipfs` feature).
+//!
+//! Talks to a local Kubo node over its HTTP RPC API. Each upload is written to
+//! the node's mutable file system (MFS) under `mfs_root/<key>`, which keeps the
+//! caller-chosen `MediaKey` addressable, and its CID is pinned so the content
+//! survives garbage collection even if the MFS entry is removed by hand.
+//! `get_url` returns `<gateway>/ipfs/<cid>`, so media can be served by any
+//! public or self-hosted gateway.
+//!
+//! The RPC API is unauthenticated and must not be exposed beyond localhost.
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use domains::errors::DomainError;
+use domains::models::MediaKey;
+use domains::ports::{MediaStorage, StoredMedia};
+use http_body_util::{BodyExt, Full};
+use hyper::{Method, Request, StatusCode};
+use hyper_util::client::legacy::{connect::HttpConnector, Client};
+use hyper_util::rt::TokioExecutor;
+use serde::Deserialize;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, instrument};
+
+/// IPFS-backed media storage.
+///
+/// CIDs are cached after the first lookup; keys are never rewritten with
+/// different content, so a cached CID stays valid until the key is deleted.
+#[derive(Clone)]
+pub struct IpfsMediaStorage {
+    client:   Client<HttpConnector, Full<Bytes>>,
+    api_url:  String,
+    gateway:  String,
+    mfs_root: String,
+    cids:     Arc<DashMap<MediaKey, String>>,
+}
+
+/// Error body returned by the Kubo RPC API.
+#[derive(Deserialize)]
+struct RpcError {
+    #[serde(rename = "Message")]
+    message: String,
+}
+
+/// `files/stat` response.
+#[derive(Deserialize)]
+struct Stat {
+    #[serde(rename = "Hash")]
+    hash:  String,
+    #[serde(rename = "Mtime", default)]
+    mtime: Option<i64>,
+}
+
+/// `files/ls` response.
+#[derive(Deserialize)]
+struct Listing {
+    #[serde(rename = "Entries", default)]
+    entries: Option<Vec<ListEntry>>,
+}
+
+#[derive(Deserialize)]
+struct ListEntry {
+    #[serde(rename = "Name")]
+    name: String,
+    /// 0 = file, 1 = directory.
+    #[serde(rename = "Type")]
+    kind: u8,
+}
+
+/// Failure of a single RPC call.
+enum CallError {
+    /// The node answered with an error; `missing` when the path does not exist.
+    Rpc { message: String, missing: bool },
+    /// The node could not be reached or the response was unreadable.
+    Transport(String),
+}
+
+impl CallError {
+    fn into_domain(self, command: &str) -> DomainError {
+        match self {
+            Self::Rpc { message, .. } => DomainError::internal(format!("IPFS {command} failed: {message}")),
+            Self::Transport(e) => DomainError::internal(format!("IPFS {command} failed: {e}")),
+        }
+    }
+}
+
+impl IpfsMediaStorage {
+    /// Create a new `IpfsMediaStorage`.
+    ///
+    /// `api_url` is the Kubo RPC endpoint (e.g. `http://127.0.0.1:5001`).
+    /// `gateway` is the URL prefix for public links (e.g. `https://ipfs.io`).
+    /// `mfs_root` is the MFS directory holding uploads (e.g. `/rusty-board`).
+    pub fn new(api_url: String, gateway: String, mfs_root: String) -> Self {
+        Self {
+            client:   Client::builder(TokioExecutor::new()).build_http(),
+            api_url:  api_url.trim_end_matches('/').to_owned(),
+            gateway:  gateway.trim_end_matches('/').to_owned(),
+            mfs_root: format!("/{}", mfs_root.trim_matches('/')),
+            cids:     Arc::new(DashMap::new()),
+        }
+    }
+
+    fn mfs_path(&self, key: &MediaKey) -> String {
+        format!("{}/{}", self.mfs_root, key.0)
+    }
+
+    /// POST `/api/v0/<command>?<args>`, optionally with one multipart file part.
+    async fn call(
+        &self,
+        command: &str,
+        args: &[(&str, &str)],
+        file: Option<Bytes>,
+    ) -> Result<Bytes, CallError> {
+        let query: Vec<String> = args
+            .iter()
+            .map(|(name, value)| format!("{name}={}", encode_arg(value)))
+            .collect();
+        let uri = format!("{}/api/v0/{command}?{}", self.api_url, query.join("&"));
+
+        let builder = Request::builder().method(Method::POST).uri(uri);
+        let request = match file {
+            Some(data) => {
+                let boundary = format!("rusty-board-{}", uuid::Uuid::new_v4().simple());
+                let mut body = Vec::with_capacity(data.len() + 256);
+                body.extend_from_slice(format!(
+                    "--{boundary}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"file\"\r\n\
+                     Content-Type: application/octet-stream\r\n\r\n"
+                ).as_bytes());
+                body.extend_from_slice(&data);
+                body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+                builder
+                    .header("content-type", format!("multipart/form-data; boundary={boundary}"))
+                    .body(Full::new(Bytes::from(body)))
+            }
+            None => builder.body(Full::new(Bytes::new())),
+        }
+        .map_err(|e| CallError::Transport(e.to_string()))?;
+
+        let response = self
+            .client
+            .request(request)
+            .await
+            .map_err(|e| CallError::Transport(e.to_string()))?;
+        let status = response.status();
+        let body = response
+            .into_body()
+            .collect()
+            .await
+            .map_err(|e| CallError::Transport(e.to_string()))?
+            .to_bytes();
+
+        if status == StatusCode::OK {
+            return Ok(body);
+        }
+        let message = serde_json::from_slice::<RpcError>(&body)
+            .map(|e| e.message)
+            .unwrap_or_else(|_| format!("HTTP {status}"));
+        let missing = message.contains("does not exist") || message.contains("not pinned");
+        Err(CallError::Rpc { message, missing })
+    }
+
+    async fn stat(&self, key: &MediaKey) -> Result<Option<Stat>, DomainError> {
+        match self.call("files/stat", &[("arg", &self.mfs_path(key))], None).await {
+            Ok(body) => serde_json::from_slice(&body)
+                .map(Some)
+                .map_err(|e| DomainError::internal(format!("IPFS files/stat returned bad JSON: {e}"))),
+            Err(CallError::Rpc { missing: true, .. }) => Ok(None),
+            Err(e) => Err(e.into_domain("files/stat")),
+        }
+    }
+
+    async fn cid(&self, key: &MediaKey) -> Result<String, DomainError> {
+        if let Some(cid) = self.cids.get(key) {
+            return Ok(cid.clone());
+        }
+        let stat = self
+            .stat(key)
+            .await?
+            .ok_or_else(|| DomainError::not_found(key.to_string()))?;
+        self.cids.insert(key.clone(), stat.hash.clone());
+        Ok(stat.hash)
+    }
+}
+
+#[async_trait]
+impl MediaStorage for IpfsMediaStorage {
+    #[instrument(skip(self, data), fields(key = %key))]
+    async fn store(
+        &self,
+        key: &MediaKey,
+        data: Bytes,
+        _content_type: &str,
+    ) -> Result<(), DomainError> {
+        let path = self.mfs_path(key);
+        self.call(
+            "files/write",
+            &[("arg", &path), ("create", "true"), ("parents", "true"), ("truncate", "true"), ("cid-version", "1")],
+            Some(data),
+        )
+        .await
+        .map_err(|e| e.into_domain("files/write"))?;
+
+        let cid = self
+            .stat(key)
+            .await?
+            .ok_or_else(|| DomainError::internal(format!("IPFS lost {path} right after writing it")))?
+            .hash;
+        self.call("pin/add", &[("arg", &cid)], None)
+            .await
+            .map_err(|e| e.into_domain("pin/add"))?;
+        debug!(key = %key, cid = %cid, "media pinned to IPFS");
+        self.cids.insert(key.clone(), cid);
+        Ok(())
+    }
+
+    #[instrument(skip(self, path), fields(key = %key))]
+    async fn store_file(
+        &self,
+        key: &MediaKey,
+        path: &Path,
+        content_type: &str,
+    ) -> Result<(), DomainError> {
+        let data = tokio::fs::read(path)
+            .await
+            .map_err(|e| DomainError::internal(format!("failed to read upload: {e}")))?;
+        self.store(key, Bytes::from(data), content_type).await
+    }
+
+    async fn get_url(&self, key: &MediaKey, _ttl: Duration) -> Result<String, DomainError> {
+        // Content-addressed links never expire. TTL is not applicable.
+        Ok(format!("{}/ipfs/{}", self.gateway, self.cid(key).await?))
+    }
+
+    #[instrument(skip(self), fields(key = %key))]
+    async fn fetch(&self, key: &MediaKey) -> Result<Bytes, DomainError> {
+        match self.call("files/read", &[("arg", &self.mfs_path(key))], None).await {
+            Ok(data) => Ok(data),
+            Err(CallError::Rpc { missing: true, .. }) => Err(DomainError::not_found(key.to_string())),
+            Err(e) => Err(e.into_domain("files/read")),
+        }
+    }
+
+    #[instrument(skip(self), fields(key = %key))]
+    async fn delete(&self, key: &MediaKey) -> Result<(), DomainError> {
+        // Idempotent delete — a missing entry or pin is fine.
+        let Some(stat) = self.stat(key).await? else {
+            self.cids.remove(key);
+            return Ok(());
+        };
+        match self.call("pin/rm", &[("arg", &stat.hash)], None).await {
+            Ok(_) | Err(CallError::Rpc { missing: true, .. }) => {}
+            Err(e) => return Err(e.into_domain("pin/rm")),
+        }
+        match self.call("files/rm", &[("arg", &self.mfs_path(key))], None).await {
+            Ok(_) | Err(CallError::Rpc { missing: true, .. }) => {}
+            Err(e) => return Err(e.into_domain("files/rm")),
+        }
+        self.cids.remove(key);
+        debug!(key = %key, cid = %stat.hash, "media unpinned from IPFS");
+        Ok(())
+    }
+
+    #[instrument(skip(self), fields(key = %key))]
+    async fn exists(&self, key: &MediaKey) -> Result<bool, DomainError> {
+        Ok(self.stat(key).await?.is_some())
+    }
+
+    /// MFS records a modification time only when the node is configured to;
+    /// entries without one are reported as modified now, so the orphan sweep's
+    /// grace period never lets it remove them.
+    #[instrument(skip(self))]
+    async fn list(&self) -> Result<Vec<StoredMedia>, DomainError> {
+        let mut objects = Vec::new();
+        let mut dirs = vec![String::new()];
+        while let Some(dir) = dirs.pop() {
+            let path = if dir.is_empty() { self.mfs_root.clone() } else { format!("{}/{dir}", self.mfs_root) };
+            let listing: Listing = match self.call("files/ls", &[("arg", &path), ("long", "true")], None).await {
+                Ok(body) => serde_json::from_slice(&body)
+                    .map_err(|e| DomainError::internal(format!("IPFS files/ls returned bad JSON: {e}")))?,
+                // Nothing has been stored yet.
+                Err(CallError::Rpc { missing: true, .. }) => continue,
+                Err(e) => return Err(e.into_domain("files/ls")),
+            };
+            for entry in listing.entries.unwrap_or_default() {
+                let name = if dir.is_empty() { entry.name } else { format!("{dir}/{}", entry.name) };
+                if entry.kind == 1 {
+                    dirs.push(name);
+                    continue;
+                }
+                let key = MediaKey::new(name);
+                let modified = self
+                    .stat(&key)
+                    .await?
+                    .and_then(|s| s.mtime)
+                    .and_then(|secs| DateTime::from_timestamp(secs, 0))
+                    .unwrap_or_else(Utc::now);
+                objects.push(StoredMedia { key, modified });
+            }
+        }
+        Ok(objects)
+    }
+}
+
+/// Percent-encode an RPC query argument, keeping RFC 3986 unreserved characters and `/`.
+fn encode_arg(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                out.push(byte as char)
+            }
+            _ => out.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    out
+}
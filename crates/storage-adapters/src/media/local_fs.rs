@@ -8,8 +8,8 @@ use async_trait::async_trait;
 use bytes::Bytes;
 use domains::errors::DomainError;
 use domains::models::MediaKey;
-use domains::ports::MediaStorage;
-use std::path::PathBuf;
+use domains::ports::{MediaStorage, StoredMedia};
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 use tokio::fs;
 use tracing::{debug, instrument};
@@ -18,6 +18,7 @@ use tracing::{debug, instrument};
 ///
 /// Files are stored under `base_path/<key>`. The `get_url` method returns
 /// a static path prefixed by `public_url_base` (e.g. `/media/`). TTL is ignored.
+#[derive(Clone)]
 pub struct LocalFsMediaStorage {
     base_path:       PathBuf,
     public_url_base: String,
@@ -55,6 +56,26 @@ impl MediaStorage for LocalFsMediaStorage {
         Ok(())
     }
 
+    #[instrument(skip(self, path), fields(key = %key))]
+    async fn store_file(
+        &self,
+        key: &MediaKey,
+        path: &Path,
+        _content_type: &str,
+    ) -> Result<(), DomainError> {
+        let file_path = self.base_path.join(&key.0);
+        if let Some(parent) = file_path.parent() {
+            fs::create_dir_all(parent)
+                .await
+                .map_err(|e| DomainError::internal(format!("failed to create directory: {e}")))?;
+        }
+        fs::copy(path, &file_path)
+            .await
+            .map_err(|e| DomainError::internal(format!("failed to copy file: {e}")))?;
+        debug!(key = %key, path = %file_path.display(), "media stored locally");
+        Ok(())
+    }
+
     async fn get_url(&self, key: &MediaKey, _ttl: Duration) -> Result<String, DomainError> {
         // Local filesystem returns a static path. TTL is not applicable.
         let url = format!(
@@ -65,6 +86,15 @@ impl MediaStorage for LocalFsMediaStorage {
         Ok(url)
     }
 
+    #[instrument(skip(self), fields(key = %key))]
+    async fn fetch(&self, key: &MediaKey) -> Result<Bytes, DomainError> {
+        match fs::read(self.base_path.join(&key.0)).await {
+            Ok(data) => Ok(Bytes::from(data)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Err(DomainError::not_found(key.to_string())),
+            Err(e) => Err(DomainError::internal(format!("failed to read file: {e}"))),
+        }
+    }
+
     #[instrument(skip(self), fields(key = %key))]
     async fn delete(&self, key: &MediaKey) -> Result<(), DomainError> {
         let file_path = self.base_path.join(&key.0);
@@ -80,4 +110,44 @@ impl MediaStorage for LocalFsMediaStorage {
             Err(e) => Err(DomainError::internal(format!("failed to delete file: {e}"))),
         }
     }
+
+    #[instrument(skip(self), fields(key = %key))]
+    async fn exists(&self, key: &MediaKey) -> Result<bool, DomainError> {
+        fs::try_exists(self.base_path.join(&key.0))
+            .await
+            .map_err(|e| DomainError::internal(format!("failed to stat file: {e}")))
+    }
+
+    #[instrument(skip(self))]
+    async fn list(&self) -> Result<Vec<StoredMedia>, DomainError> {
+        let io_err = |e: std::io::Error| DomainError::internal(format!("failed to list media: {e}"));
+        let mut objects = Vec::new();
+        let mut dirs = vec![self.base_path.clone()];
+        while let Some(dir) = dirs.pop() {
+            let mut entries = match fs::read_dir(&dir).await {
+                Ok(entries) => entries,
+                // Nothing has been stored yet.
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(io_err(e)),
+            };
+            while let Some(entry) = entries.next_entry().await.map_err(io_err)? {
+                let meta = entry.metadata().await.map_err(io_err)?;
+                if meta.is_dir() {
+                    dirs.push(entry.path());
+                    continue;
+                }
+                let path = entry.path();
+                let Ok(relative) = path.strip_prefix(&self.base_path) else { continue };
+                // Keys always use `/`, whatever the platform separator.
+                let key = relative
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                let modified = meta.modified().map_err(io_err)?;
+                objects.push(StoredMedia { key: MediaKey::new(key), modified: modified.into() });
+            }
+        }
+        Ok(objects)
+    }
 }
@@ -1,18 +1,34 @@
 //! Media processing adapters.
 //!
-//! The composition root selects a concrete `MediaProcessor` implementation based
-//! on active features:
+//! The composition root chains the processors of the active features into a
+//! `CompositeMediaProcessor`, which dispatches each upload by MIME type:
 //! - `ImageMediaProcessor` — always available (JPEG, PNG, GIF, WebP)
-//! - `VideoMediaProcessor` — adds video support (`video` feature)
-//! - `FullMediaProcessor` — adds video + PDF support (`video` + `documents` features)
+//! - `VideoMediaProcessor` — WebM/MP4 (`video` feature)
+//! - `AudioMediaProcessor` — MP3, Ogg Vorbis, FLAC (`audio` feature)
+//! - `DocumentMediaProcessor` — PDF (`documents` feature)
 //!
-//! All processors unconditionally strip EXIF metadata from images.
+//! Image thumbnails are rendered with the `image` crate, or with libvips when
+//! the `thumbs-vips` feature selects `Thumbnailer::Vips`.
+//!
+//! All processors strip EXIF/XMP/GPS metadata from images unless the board has
+//! opted out via `BoardConfig::strip_metadata`.
+//!
+//! `cdn` builds the public URLs of stored media (CDN base, signed links).
 
+pub mod cdn;
+pub mod composite;
 pub mod images;
+pub mod thumbnail_queue;
+
+#[cfg(feature = "thumbs-vips")]
+pub mod vips;
 
 #[cfg(feature = "video")]
 pub mod videos;
 
+#[cfg(feature = "audio")]
+pub mod audio;
+
 #[cfg(feature = "documents")]
 pub mod documents;
 
@@ -22,4 +38,53 @@ pub mod s3;
 #[cfg(feature = "media-local")]
 pub mod local_fs;
 
-pub use images::ImageMediaProcessor;
+#[cfg(feature = "media-ipfs")]
+pub mod ipfs;
+
+pub use composite::CompositeMediaProcessor;
+pub use images::{ImageMediaProcessor, Reencode, ReencodeFormat};
+pub use thumbnail_queue::{ThumbnailQueue, Thumbnailer};
+
+use bytes::Bytes;
+use domains::errors::DomainError;
+use domains::models::ContentHash;
+use domains::ports::MediaData;
+use md5::Md5;
+use sha2::{Digest, Sha256};
+
+/// Bring upload contents into memory, reading a spooled file from disk.
+///
+/// Used by decoders that need the whole file as a slice; others read the
+/// spooled path directly.
+pub(crate) async fn load(data: &MediaData) -> Result<Bytes, DomainError> {
+    match data {
+        MediaData::Memory(bytes) => Ok(bytes.clone()),
+        MediaData::Spooled(file) => tokio::fs::read(&file.path)
+            .await
+            .map(Bytes::from)
+            .map_err(|e| DomainError::media_processing(format!("failed to read upload: {e}"))),
+    }
+}
+
+/// SHA-256 of an original that is stored as uploaded. Spooled uploads were
+/// hashed while they streamed in, so the file is not read again.
+pub(crate) fn content_hash(data: &MediaData) -> ContentHash {
+    match data {
+        MediaData::Memory(bytes) => hash_bytes(bytes),
+        MediaData::Spooled(file) => file.hash.clone(),
+    }
+}
+
+/// SHA-256 of `bytes` as a `ContentHash`.
+pub(crate) fn hash_bytes(bytes: &[u8]) -> ContentHash {
+    ContentHash::new(hex::encode(Sha256::digest(bytes)))
+}
+
+/// Hex MD5 of the upload as received, for hash-ban matching. Spooled uploads
+/// were hashed while they streamed in, so the file is not read again.
+pub(crate) fn upload_md5(data: &MediaData) -> String {
+    match data {
+        MediaData::Memory(bytes) => hex::encode(Md5::digest(bytes)),
+        MediaData::Spooled(file) => file.md5.clone(),
+    }
+}
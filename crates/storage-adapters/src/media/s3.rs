@@ -7,7 +7,8 @@ use async_trait::async_trait;
 use bytes::Bytes;
 use domains::errors::DomainError;
 use domains::models::MediaKey;
-use domains::ports::MediaStorage;
+use domains::ports::{MediaStorage, StoredMedia};
+use std::path::Path;
 use std::time::Duration;
 use tracing::{debug, instrument};
 
@@ -15,6 +16,7 @@ use tracing::{debug, instrument};
 ///
 /// Wraps an `aws_sdk_s3::Client` pre-configured with bucket name and credentials.
 /// The bucket must exist before the application starts.
+#[derive(Clone)]
 pub struct S3MediaStorage {
     client:   aws_sdk_s3::Client,
     bucket:   String,
@@ -54,6 +56,30 @@ impl MediaStorage for S3MediaStorage {
         Ok(())
     }
 
+    #[instrument(skip(self, path), fields(key = %key, content_type = content_type))]
+    async fn store_file(
+        &self,
+        key: &MediaKey,
+        path: &Path,
+        content_type: &str,
+    ) -> Result<(), DomainError> {
+        // Streams the file from disk instead of buffering it.
+        let body = aws_sdk_s3::primitives::ByteStream::from_path(path)
+            .await
+            .map_err(|e| DomainError::internal(format!("failed to open upload: {e}")))?;
+        self.client
+            .put_object()
+            .bucket(&self.bucket)
+            .key(&key.0)
+            .content_type(content_type)
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| DomainError::internal(format!("S3 put_object failed: {e}")))?;
+        debug!(key = %key, "media stored to S3");
+        Ok(())
+    }
+
     #[instrument(skip(self), fields(key = %key))]
     async fn get_url(&self, key: &MediaKey, ttl: Duration) -> Result<String, DomainError> {
         let presigned = self
@@ -70,6 +96,23 @@ impl MediaStorage for S3MediaStorage {
         Ok(presigned.uri().to_string())
     }
 
+    #[instrument(skip(self), fields(key = %key))]
+    async fn fetch(&self, key: &MediaKey) -> Result<Bytes, DomainError> {
+        let object = match self.client.get_object().bucket(&self.bucket).key(&key.0).send().await {
+            Ok(object) => object,
+            Err(e) if e.as_service_error().is_some_and(|e| e.is_no_such_key()) => {
+                return Err(DomainError::not_found(key.to_string()))
+            }
+            Err(e) => return Err(DomainError::internal(format!("S3 get_object failed: {e}"))),
+        };
+        let data = object
+            .body
+            .collect()
+            .await
+            .map_err(|e| DomainError::internal(format!("S3 get_object body failed: {e}")))?;
+        Ok(data.into_bytes())
+    }
+
     #[instrument(skip(self), fields(key = %key))]
     async fn delete(&self, key: &MediaKey) -> Result<(), DomainError> {
         self.client
@@ -82,4 +125,37 @@ impl MediaStorage for S3MediaStorage {
         debug!(key = %key, "media deleted from S3");
         Ok(())
     }
+
+    #[instrument(skip(self), fields(key = %key))]
+    async fn exists(&self, key: &MediaKey) -> Result<bool, DomainError> {
+        match self.client.head_object().bucket(&self.bucket).key(&key.0).send().await {
+            Ok(_) => Ok(true),
+            Err(e) if e.as_service_error().is_some_and(|e| e.is_not_found()) => Ok(false),
+            Err(e) => Err(DomainError::internal(format!("S3 head_object failed: {e}"))),
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn list(&self) -> Result<Vec<StoredMedia>, DomainError> {
+        let mut objects = Vec::new();
+        let mut pages = self
+            .client
+            .list_objects_v2()
+            .bucket(&self.bucket)
+            .into_paginator()
+            .send();
+        while let Some(page) = pages.next().await {
+            let page = page
+                .map_err(|e| DomainError::internal(format!("S3 list_objects_v2 failed: {e}")))?;
+            for object in page.contents() {
+                let (Some(key), Some(modified)) = (object.key(), object.last_modified()) else {
+                    continue;
+                };
+                let modified = chrono::DateTime::from_timestamp(modified.secs(), modified.subsec_nanos())
+                    .unwrap_or_default();
+                objects.push(StoredMedia { key: MediaKey::new(key), modified });
+            }
+        }
+        Ok(objects)
+    }
 }
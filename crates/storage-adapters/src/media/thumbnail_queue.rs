@@ -0,0 +1,298 @@
+//! Background thumbnail generation.
+//!
+//! `ImageMediaProcessor` leaves thumbnail work to a `ThumbnailQueue` so a post
+//! is saved without waiting for the resize and PNG optimisation. A fixed pool
+//! of worker tasks drains a bounded channel; each job decodes and encodes on
+//! the blocking thread pool and writes the result through `MediaStorage`.
+//!
+//! The processor returns the work as a `QueuedThumbnail`; `PostService`
+//! submits it only once deduplication has settled on the key the attachment
+//! keeps, so duplicates and rejected uploads never reach the queue.
+//!
+//! Until a job finishes the thumbnail key does not exist in storage; the media
+//! route serves a placeholder for missing `*_thumb.*` keys in the meantime.
+//! A full queue is reported back to the caller, which then generates the
+//! thumbnail inline instead — uploads slow down rather than lose thumbnails.
+//!
+//! On shutdown `drain` closes the queue and waits for queued jobs to finish,
+//! so a deploy does not leave posts without thumbnails.
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use domains::errors::DomainError;
+use domains::models::{MediaKey, ThumbnailSpec};
+use domains::ports::{MediaStorage, ThumbnailTask};
+use tokio::sync::{mpsc, Mutex, Notify};
+use tracing::{debug, warn};
+
+use super::images::encode_thumbnail;
+
+/// One thumbnail to render and store.
+pub(crate) struct ThumbnailJob {
+    /// Storage key the thumbnail is written to.
+    pub key:    MediaKey,
+    /// Encoded source image (the stored original).
+    pub source: Bytes,
+    /// Size, format and quality of the thumbnail.
+    pub spec:   ThumbnailSpec,
+    /// Backend that renders the thumbnail.
+    pub thumbnailer: Thumbnailer,
+}
+
+/// Backend used to render image thumbnails. Cheap to clone.
+#[derive(Clone, Debug, Default)]
+pub enum Thumbnailer {
+    /// Decode and resize with the `image` crate on the blocking thread pool.
+    #[default]
+    Image,
+    /// Pipe the source through the libvips CLI (feature: `thumbs-vips`).
+    #[cfg(feature = "thumbs-vips")]
+    Vips(super::vips::VipsThumbnailer),
+}
+
+impl Thumbnailer {
+    /// Render `source` as a thumbnail described by `spec`.
+    pub(crate) async fn render(&self, source: Bytes, spec: ThumbnailSpec) -> Result<Bytes, DomainError> {
+        match self {
+            Self::Image => render(source, spec).await,
+            #[cfg(feature = "thumbs-vips")]
+            Self::Vips(vips) => vips.render(source, spec).await,
+        }
+    }
+}
+
+/// A thumbnail waiting to be submitted to a `ThumbnailQueue`.
+pub(crate) struct QueuedThumbnail {
+    /// Queue the job is offered to.
+    pub queue:       ThumbnailQueue,
+    /// Encoded source image (the stored original).
+    pub source:      Bytes,
+    /// Size, format and quality of the thumbnail.
+    pub spec:        ThumbnailSpec,
+    /// Backend that renders the thumbnail.
+    pub thumbnailer: Thumbnailer,
+}
+
+impl std::fmt::Debug for QueuedThumbnail {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("QueuedThumbnail")
+            .field("source_len", &self.source.len())
+            .field("spec", &self.spec)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl ThumbnailTask for QueuedThumbnail {
+    async fn submit(&self, key: MediaKey) -> Result<Option<Bytes>, DomainError> {
+        let job = ThumbnailJob {
+            key,
+            source:      self.source.clone(),
+            spec:        self.spec,
+            thumbnailer: self.thumbnailer.clone(),
+        };
+        match self.queue.try_enqueue(job) {
+            Ok(()) => Ok(None),
+            Err(job) => job.thumbnailer.render(job.source, job.spec).await.map(Some),
+        }
+    }
+}
+
+/// Handle to the background thumbnail workers. Cheap to clone.
+#[derive(Clone)]
+pub struct ThumbnailQueue {
+    tx:    mpsc::Sender<ThumbnailJob>,
+    state: Arc<QueueState>,
+}
+
+/// Bookkeeping shared by the handles and workers, used by `drain`.
+#[derive(Default)]
+struct QueueState {
+    /// Jobs queued or being rendered.
+    pending: AtomicUsize,
+    /// Set by `drain`; later jobs are handed back to the caller.
+    closed:  AtomicBool,
+    /// Woken when `pending` drops to zero.
+    idle:    Notify,
+}
+
+impl ThumbnailQueue {
+    /// Spawn `workers` tasks (at least one) storing thumbnails in `storage`,
+    /// with room for `capacity` pending jobs.
+    ///
+    /// Must be called from within a Tokio runtime.
+    pub fn start(storage: Arc<dyn MediaStorage>, workers: usize, capacity: usize) -> Self {
+        let (tx, rx) = mpsc::channel::<ThumbnailJob>(capacity.max(1));
+        let rx = Arc::new(Mutex::new(rx));
+        let state = Arc::new(QueueState::default());
+        for _ in 0..workers.max(1) {
+            let rx = rx.clone();
+            let storage = storage.clone();
+            let state = state.clone();
+            tokio::spawn(async move {
+                loop {
+                    // Hold the lock only while waiting for the next job.
+                    let Some(job) = rx.lock().await.recv().await else { break };
+                    let key = job.key.clone();
+                    if let Err(e) = run(job, storage.as_ref()).await {
+                        warn!(key = %key, error = %e, "background thumbnail failed");
+                    }
+                    if state.pending.fetch_sub(1, Ordering::AcqRel) == 1 {
+                        state.idle.notify_waiters();
+                    }
+                }
+            });
+        }
+        Self { tx, state }
+    }
+
+    /// Queue `job` without waiting. A full (or drained) queue hands the job back.
+    pub(crate) fn try_enqueue(&self, job: ThumbnailJob) -> Result<(), ThumbnailJob> {
+        if self.state.closed.load(Ordering::Acquire) {
+            return Err(job);
+        }
+        self.state.pending.fetch_add(1, Ordering::AcqRel);
+        self.tx.try_send(job).map_err(|e| {
+            self.state.pending.fetch_sub(1, Ordering::AcqRel);
+            match e {
+                mpsc::error::TrySendError::Full(job) | mpsc::error::TrySendError::Closed(job) => job,
+            }
+        })
+    }
+
+    /// Stop accepting jobs and wait up to `timeout` for queued ones to finish.
+    ///
+    /// Jobs offered afterwards are handed back, so callers render them inline.
+    /// Returns how many jobs were still unfinished when the timeout expired.
+    pub async fn drain(&self, timeout: Duration) -> usize {
+        self.state.closed.store(true, Ordering::Release);
+        let idle = async {
+            loop {
+                // Register for the wake-up before checking, so a worker
+                // finishing in between is not missed.
+                let notified = self.state.idle.notified();
+                tokio::pin!(notified);
+                notified.as_mut().enable();
+                if self.state.pending.load(Ordering::Acquire) == 0 {
+                    return;
+                }
+                notified.await;
+            }
+        };
+        match tokio::time::timeout(timeout, idle).await {
+            Ok(()) => 0,
+            Err(_) => self.state.pending.load(Ordering::Acquire),
+        }
+    }
+}
+
+/// Render `job` on the blocking pool and store the result.
+async fn run(job: ThumbnailJob, storage: &dyn MediaStorage) -> Result<(), DomainError> {
+    let ThumbnailJob { key, source, spec, thumbnailer } = job;
+    let thumb = thumbnailer.render(source, spec).await?;
+    storage.store(&key, thumb, spec.format.mime()).await?;
+    debug!(key = %key, "thumbnail stored");
+    Ok(())
+}
+
+/// Decode `source` and encode its thumbnail off the async runtime.
+pub(crate) async fn render(source: Bytes, spec: ThumbnailSpec) -> Result<Bytes, DomainError> {
+    tokio::task::spawn_blocking(move || {
+        let img = image::load_from_memory(&source).map_err(|e| {
+            DomainError::media_processing(format!("failed to decode image: {e}"))
+        })?;
+        encode_thumbnail(&img, spec)
+    })
+    .await
+    .map_err(|e| DomainError::media_processing(format!("thumbnail worker failed: {e}")))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use domains::ports::MockMediaStorage;
+    use std::io::Cursor;
+
+    fn png(width: u32, height: u32) -> Bytes {
+        let mut buf = Cursor::new(Vec::new());
+        image::DynamicImage::new_rgb8(width, height)
+            .write_to(&mut buf, image::ImageFormat::Png)
+            .unwrap();
+        Bytes::from(buf.into_inner())
+    }
+
+    fn width(max_width: u32) -> ThumbnailSpec {
+        ThumbnailSpec { max_width, ..ThumbnailSpec::default() }
+    }
+
+    #[tokio::test]
+    async fn worker_stores_rendered_thumbnail() {
+        let (done_tx, mut done_rx) = mpsc::unbounded_channel();
+        let mut storage = MockMediaStorage::new();
+        storage.expect_store().times(1).returning(move |key, data, content_type| {
+            done_tx.send((key.0.clone(), data, content_type.to_owned())).unwrap();
+            Ok(())
+        });
+        let queue = ThumbnailQueue::start(Arc::new(storage), 1, 4);
+
+        let job = ThumbnailJob { key: MediaKey::new("a_thumb.png"), source: png(64, 32), spec: width(16), thumbnailer: Thumbnailer::Image };
+        assert!(queue.try_enqueue(job).is_ok());
+
+        let (key, data, content_type) = done_rx.recv().await.unwrap();
+        assert_eq!(key, "a_thumb.png");
+        assert_eq!(content_type, "image/png");
+        assert_eq!(image::load_from_memory(&data).unwrap().width(), 16);
+    }
+
+    #[tokio::test]
+    async fn full_queue_hands_the_job_back() {
+        // No runtime worker can pick the job up before the second send:
+        // the current-thread runtime only runs them when this test yields.
+        let queue = ThumbnailQueue::start(Arc::new(MockMediaStorage::new()), 1, 1);
+        let job = || ThumbnailJob { key: MediaKey::new("k"), source: Bytes::new(), spec: width(16), thumbnailer: Thumbnailer::Image };
+        assert!(queue.try_enqueue(job()).is_ok());
+        assert!(queue.try_enqueue(job()).is_err());
+    }
+
+    #[tokio::test]
+    async fn drain_waits_for_queued_jobs_then_refuses_more() {
+        let (done_tx, mut done_rx) = mpsc::unbounded_channel();
+        let mut storage = MockMediaStorage::new();
+        storage.expect_store().times(2).returning(move |key, _, _| {
+            done_tx.send(key.0.clone()).unwrap();
+            Ok(())
+        });
+        let queue = ThumbnailQueue::start(Arc::new(storage), 1, 4);
+        let job = |key: &str| ThumbnailJob { key: MediaKey::new(key), source: png(8, 8), spec: width(4), thumbnailer: Thumbnailer::Image };
+        assert!(queue.try_enqueue(job("a")).is_ok());
+        assert!(queue.try_enqueue(job("b")).is_ok());
+
+        assert_eq!(queue.drain(Duration::from_secs(10)).await, 0);
+        assert_eq!(done_rx.try_recv().unwrap(), "a");
+        assert_eq!(done_rx.try_recv().unwrap(), "b");
+        assert!(queue.try_enqueue(job("c")).is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn drain_reports_jobs_left_at_the_timeout() {
+        // The store blocks until the test releases it, holding the job in flight.
+        let (release_tx, release_rx) = std::sync::mpsc::channel::<()>();
+        let release_rx = std::sync::Mutex::new(release_rx);
+        let mut storage = MockMediaStorage::new();
+        storage.expect_store().returning(move |_, _, _| {
+            release_rx.lock().unwrap().recv().unwrap();
+            Ok(())
+        });
+        let queue = ThumbnailQueue::start(Arc::new(storage), 1, 4);
+        let job = ThumbnailJob { key: MediaKey::new("k"), source: png(8, 8), spec: width(4), thumbnailer: Thumbnailer::Image };
+        assert!(queue.try_enqueue(job).is_ok());
+
+        assert_eq!(queue.drain(Duration::from_millis(50)).await, 1);
+        release_tx.send(()).unwrap();
+        assert_eq!(queue.drain(Duration::from_secs(10)).await, 0);
+    }
+}
@@ -1,38 +1,126 @@
-//! Video media processor using ffmpeg-next for keyframe extraction.
+//! Video media processor: probe duration, extract a poster frame, strip metadata.
 //!
 //! This module is only compiled when the `video` feature is active.
-//! It extends image processing with video thumbnail generation by extracting
-//! the first keyframe of a video as a PNG thumbnail.
+//! It extends image processing with WebM/MP4 support by invoking the `ffprobe`
+//! and `ffmpeg` command-line tools as subprocesses. No libav* libraries are
+//! linked into the binary — the tools only need to be installed on the host
+//! (`apt-get install ffmpeg`), and their paths are configurable via
+//! `Settings.ffmpeg_path` / `Settings.ffprobe_path`.
 //!
-//! # Build requirements
-//! Requires libav* system libraries: `libavcodec-dev libavformat-dev libavutil-dev libswscale-dev`
-//! See `TECHNICALSPECS.md §8` for Docker build instructions.
+//! # INVARIANT: metadata stripping is on by default
+//! Container-level metadata (MP4 `udta`/`©xyz` location atoms, WebM tags,
+//! chapters) is dropped with a stream-copy remux unless the board has set
+//! `BoardConfig::strip_metadata = false`. Streams are never re-encoded.
 
-// TODO(v1.0): Implement VideoMediaProcessor using ffmpeg-next keyframe extraction.
-// Validate the ffmpeg build in the target Docker environment before implementing.
-// If ffmpeg-next does not build cleanly, defer the video feature to v1.1.
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
 
 use async_trait::async_trait;
+use bytes::Bytes;
 use domains::errors::DomainError;
-use domains::ports::{MediaProcessor, ProcessedMedia, RawMedia};
+use domains::models::{MediaKey, ThumbnailSpec};
+use domains::ports::{MediaData, MediaProcessor, ProcessedMedia, RawMedia};
 use mime::Mime;
+use tokio::process::Command;
+
+use super::images::{encode_thumbnail, ImageMediaProcessor};
 
 const SUPPORTED_VIDEO_MIMES: &[&str] = &[
     "video/mp4",
     "video/webm",
-    "video/ogg",
 ];
 
-/// Media processor that handles images and extracts video thumbnails via ffmpeg.
+/// Upper bound on a single ffmpeg/ffprobe invocation. Hostile inputs can make
+/// the demuxer spin; the child is killed when this elapses.
+const TOOL_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Media processor that handles images and WebM/MP4 video.
 ///
-/// Extends `ImageMediaProcessor` for image types. For video types, extracts
-/// the first keyframe as a PNG thumbnail.
-pub struct VideoMediaProcessor;
+/// Images are delegated to `ImageMediaProcessor`. For video:
+/// 1. Validates MIME type
+/// 2. Probes the container with `ffprobe` (rejects undecodable files) and records the duration
+/// 3. Extracts the first video frame with `ffmpeg` and thumbnails it like an image
+/// 4. Remuxes the original without metadata (skipped when `RawMedia::strip_metadata` is false)
+/// 5. Computes SHA-256 content hash of the stored original
+pub struct VideoMediaProcessor {
+    ffmpeg:          PathBuf,
+    ffprobe:         PathBuf,
+    thumbnail:       ThumbnailSpec,
+    images:          ImageMediaProcessor,
+}
 
 impl VideoMediaProcessor {
-    /// Create a new video processor.
+    /// Create a processor that runs `ffmpeg` and `ffprobe` from `PATH`.
     pub fn new() -> Self {
-        Self
+        Self::with_binaries("ffmpeg", "ffprobe")
+    }
+
+    /// Create a processor using explicit `ffmpeg` and `ffprobe` executables.
+    pub fn with_binaries(ffmpeg: impl Into<PathBuf>, ffprobe: impl Into<PathBuf>) -> Self {
+        Self {
+            ffmpeg:          ffmpeg.into(),
+            ffprobe:         ffprobe.into(),
+            thumbnail:       ThumbnailSpec::default(),
+            images:          ImageMediaProcessor::new(),
+        }
+    }
+
+    /// Thumbnail uploads whose board has no override with `spec`.
+    pub fn with_thumbnail(self, spec: ThumbnailSpec) -> Self {
+        Self { thumbnail: spec, images: self.images.with_thumbnail(spec), ..self }
+    }
+
+    /// Probe the container and return its duration in milliseconds, if known.
+    async fn probe_duration_ms(&self, path: &Path) -> Result<Option<u32>, DomainError> {
+        let out = run_tool(
+            Command::new(&self.ffprobe)
+                .args(["-v", "error", "-show_entries", "format=duration",
+                       "-of", "default=noprint_wrappers=1:nokey=1"])
+                .arg(path),
+        )
+        .await?;
+        Ok(parse_duration_ms(&String::from_utf8_lossy(&out)))
+    }
+
+    /// Decode the first video frame as a full-size PNG.
+    async fn poster_frame(&self, path: &Path) -> Result<Vec<u8>, DomainError> {
+        let out = run_tool(
+            Command::new(&self.ffmpeg)
+                .args(["-v", "error", "-i"])
+                .arg(path)
+                .args(["-map", "0:v:0", "-frames:v", "1", "-f", "image2pipe", "-c:v", "png", "-"]),
+        )
+        .await?;
+        if out.is_empty() {
+            return Err(DomainError::media_processing("video has no decodable frames"));
+        }
+        Ok(out)
+    }
+
+    /// Stream-copy `input` into `output` with all container metadata and chapters dropped.
+    async fn remux_without_metadata(
+        &self,
+        input: &Path,
+        output: &Path,
+        format: &str,
+    ) -> Result<Bytes, DomainError> {
+        let mut cmd = Command::new(&self.ffmpeg);
+        cmd.args(["-v", "error", "-y", "-i"])
+            .arg(input)
+            .args(["-map", "0", "-map_metadata", "-1", "-map_chapters", "-1", "-c", "copy"]);
+        if format == "mp4" {
+            // Move the index to the front so browsers can start playback before
+            // the whole file has downloaded.
+            cmd.args(["-movflags", "+faststart"]);
+        }
+        cmd.args(["-f", format]).arg(output);
+        run_tool(&mut cmd).await?;
+
+        let data = tokio::fs::read(output).await.map_err(|e| {
+            DomainError::media_processing(format!("failed to read remuxed video: {e}"))
+        })?;
+        Ok(Bytes::from(data))
     }
 }
 
@@ -48,22 +136,179 @@ impl MediaProcessor for VideoMediaProcessor {
         let mime_str = input.mime.to_string();
 
         // Delegate images to ImageMediaProcessor
-        if super::images::ImageMediaProcessor::new().accepts(&input.mime) {
-            return super::images::ImageMediaProcessor::new().process(input).await;
+        if self.images.accepts(&input.mime) {
+            return self.images.process(input).await;
         }
 
+        // Step 1: Validate MIME type
         if !self.accepts(&input.mime) {
             return Err(DomainError::Validation(
                 domains::errors::ValidationError::DisallowedMime { mime: mime_str },
             ));
         }
+        let ext = extension_for_mime(&mime_str);
+
+        // ffmpeg needs a seekable input (MP4 indexes may sit at the end of
+        // the file). Spooled uploads are read in place; in-memory ones are
+        // written to a temp file first.
+        let key_base = uuid::Uuid::new_v4().to_string();
+        let mut _spool = None;
+        let source: &Path = match &input.data {
+            MediaData::Spooled(file) => &file.path,
+            MediaData::Memory(bytes) => {
+                let tmp = TempFile::new(format!("rb-{key_base}.{ext}"));
+                tokio::fs::write(&tmp.0, bytes).await.map_err(|e| {
+                    DomainError::media_processing(format!("failed to spool video: {e}"))
+                })?;
+                &_spool.insert(tmp).0
+            }
+        };
+
+        // Step 2: Probe — also rejects files ffmpeg cannot demux
+        let duration_ms = self.probe_duration_ms(source).await?;
 
-        // TODO(v1.0): Extract first keyframe using ffmpeg-next and return as thumbnail
-        Err(DomainError::media_processing("video processing not yet implemented"))
+        // Step 3: Poster frame → shared thumbnail pipeline
+        let frame = self.poster_frame(source).await?;
+        let img = image::load_from_memory(&frame).map_err(|e| {
+            DomainError::media_processing(format!("failed to decode poster frame: {e}"))
+        })?;
+        let spec = input.thumbnail.unwrap_or(self.thumbnail);
+        let thumb_bytes = encode_thumbnail(&img, spec)?;
+
+        // Step 4–5: Strip container metadata unless the board opted out, and
+        // hash the stored original
+        let (original, hash) = if input.strip_metadata {
+            let stripped = TempFile::new(format!("rb-{key_base}-clean.{ext}"));
+            let bytes = self.remux_without_metadata(source, &stripped.0, ext).await?;
+            let hash = super::hash_bytes(&bytes);
+            (MediaData::Memory(bytes), hash)
+        } else {
+            let hash = super::content_hash(&input.data);
+            (input.data.clone(), hash)
+        };
+
+        let size_kb = (original.len() as u32).div_ceil(1024);
+        let original_key = MediaKey::new(format!("{key_base}.{ext}"));
+        let thumbnail_key = MediaKey::new(format!("{key_base}_thumb.{}", spec.format.extension()));
+
+        Ok(ProcessedMedia {
+            original_key,
+            original_data: original,
+            thumbnail_key: Some(thumbnail_key),
+            thumbnail_data: Some(thumb_bytes),
+            thumbnail_task: None,
+            hash,
+            size_kb,
+            duration_ms,
+            page_count: None,
+            md5: super::upload_md5(&input.data),
+            phash: None,
+            // The poster frame is decoded at the video's native resolution.
+            width: Some(img.width()),
+            height: Some(img.height()),
+            stored_mime: None,
+        })
     }
 
     fn accepts(&self, mime: &Mime) -> bool {
-        super::images::ImageMediaProcessor::new().accepts(mime)
-            || SUPPORTED_VIDEO_MIMES.contains(&mime.as_ref())
+        self.images.accepts(mime) || SUPPORTED_VIDEO_MIMES.contains(&mime.as_ref())
+    }
+}
+
+/// Run an ffmpeg-family tool to completion and return its stdout.
+///
+/// A missing executable, a non-zero exit, or exceeding `TOOL_TIMEOUT` all map
+/// to `DomainError::MediaProcessing` carrying the tool's stderr.
+async fn run_tool(cmd: &mut Command) -> Result<Vec<u8>, DomainError> {
+    let child = cmd
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| DomainError::media_processing(format!("failed to start ffmpeg: {e}")))?;
+
+    let output = tokio::time::timeout(TOOL_TIMEOUT, child.wait_with_output())
+        .await
+        .map_err(|_| DomainError::media_processing("video processing timed out"))?
+        .map_err(|e| DomainError::media_processing(format!("ffmpeg failed: {e}")))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(DomainError::media_processing(format!(
+            "ffmpeg rejected the file: {}",
+            stderr.trim()
+        )));
+    }
+    Ok(output.stdout)
+}
+
+/// Parse ffprobe's `format=duration` output (seconds, e.g. `"12.345000"`).
+///
+/// Returns `None` for `N/A`, negative, or unparsable values.
+fn parse_duration_ms(output: &str) -> Option<u32> {
+    let secs: f64 = output.trim().parse().ok()?;
+    if !secs.is_finite() || secs < 0.0 {
+        return None;
+    }
+    Some((secs * 1000.0).round().min(u32::MAX as f64) as u32)
+}
+
+fn extension_for_mime(mime: &str) -> &'static str {
+    match mime {
+        "video/mp4"  => "mp4",
+        "video/webm" => "webm",
+        _            => "bin",
+    }
+}
+
+/// A path under the system temp dir that is removed when dropped.
+struct TempFile(PathBuf);
+
+impl TempFile {
+    fn new(name: String) -> Self {
+        Self(std::env::temp_dir().join(name))
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_duration_ms_reads_ffprobe_seconds() {
+        assert_eq!(parse_duration_ms("12.345000\n"), Some(12_345));
+        assert_eq!(parse_duration_ms("0.0005"), Some(1));
+        assert_eq!(parse_duration_ms("N/A"), None);
+        assert_eq!(parse_duration_ms("-1"), None);
+    }
+
+    #[test]
+    fn accepts_webm_mp4_and_images() {
+        let p = VideoMediaProcessor::new();
+        assert!(p.accepts(&"video/webm".parse().unwrap()));
+        assert!(p.accepts(&"video/mp4".parse().unwrap()));
+        assert!(p.accepts(&mime::IMAGE_PNG));
+        assert!(!p.accepts(&"video/x-msvideo".parse().unwrap()));
+    }
+
+    #[tokio::test]
+    async fn missing_ffmpeg_is_a_processing_error() {
+        let p = VideoMediaProcessor::with_binaries("/nonexistent/ffmpeg", "/nonexistent/ffprobe");
+        let input = RawMedia {
+            filename:       "clip.webm".to_owned(),
+            mime:           "video/webm".parse().unwrap(),
+            data:           Bytes::from_static(&[0x1A, 0x45, 0xDF, 0xA3]).into(),
+            strip_metadata: true,
+            thumbnail:      None,
+        };
+        let err = p.process(input).await.unwrap_err();
+        assert!(matches!(err, DomainError::MediaProcessing { .. }), "got {err:?}");
     }
 }
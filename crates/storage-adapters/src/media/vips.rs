@@ -0,0 +1,124 @@
+//! libvips thumbnail backend.
+//!
+//! This module is only compiled when the `thumbs-vips` feature is active.
+//! Thumbnails are rendered by the `vips` command-line tool (`thumbnail_source`),
+//! which shrinks JPEG and WebP sources during decode and streams the rest, so a
+//! large upload is never held as a full-size bitmap. Like the `video` feature,
+//! nothing is linked into the binary — the runtime image needs the libvips
+//! tools installed (`apt-get install libvips-tools`), and the executable path is
+//! configurable via `Settings.vips_path`.
+//!
+//! Only the thumbnail step moves to libvips. Validation, metadata stripping and
+//! the perceptual hash still go through the `image` crate.
+
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::Duration;
+
+use bytes::Bytes;
+use domains::errors::DomainError;
+use domains::models::{ThumbnailFormat, ThumbnailSpec};
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+/// Upper bound on a single `vips` invocation; the child is killed when this elapses.
+const TOOL_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Height bound passed to `vips thumbnail` for specs without a height limit.
+const UNBOUNDED_HEIGHT: &str = "10000000";
+
+/// Renders thumbnails by piping the source through `vips thumbnail_source`.
+/// Cheap to clone.
+#[derive(Clone, Debug)]
+pub struct VipsThumbnailer {
+    vips: Arc<PathBuf>,
+}
+
+impl VipsThumbnailer {
+    /// Create a thumbnailer that runs `vips` from `PATH`.
+    pub fn new() -> Self {
+        Self::with_binary("vips")
+    }
+
+    /// Create a thumbnailer using an explicit `vips` executable.
+    pub fn with_binary(vips: impl Into<PathBuf>) -> Self {
+        Self { vips: Arc::new(vips.into()) }
+    }
+
+    /// Scale `source` down to fit `spec` and encode it in `spec.format` with all
+    /// metadata stripped. PNG output is oxipng-compressed.
+    pub(crate) async fn render(&self, source: Bytes, spec: ThumbnailSpec) -> Result<Bytes, DomainError> {
+        let output_format = match spec.format {
+            ThumbnailFormat::Png => ".png[strip]".to_owned(),
+            ThumbnailFormat::Jpeg => format!(".jpg[Q={},strip]", spec.quality.clamp(1, 100)),
+            ThumbnailFormat::Webp => ".webp[lossless,strip]".to_owned(),
+        };
+        let height = match spec.max_height {
+            0 => UNBOUNDED_HEIGHT.to_owned(),
+            h => h.to_string(),
+        };
+        let mut child = Command::new(self.vips.as_ref())
+            .args(["thumbnail_source", "[descriptor=0]", &output_format])
+            .arg(spec.max_width.to_string())
+            .args(["--height", &height, "--size", "down"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| DomainError::media_processing(format!("failed to start vips: {e}")))?;
+
+        // Feed stdin concurrently with reading stdout so a large source cannot
+        // deadlock against a full output pipe.
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        let feed = tokio::spawn(async move {
+            // vips may exit before reading everything (e.g. on a bad header);
+            // its exit status reports that, so a broken pipe is not an error here.
+            let _ = stdin.write_all(&source).await;
+        });
+
+        let output = tokio::time::timeout(TOOL_TIMEOUT, child.wait_with_output())
+            .await
+            .map_err(|_| DomainError::media_processing("thumbnail generation timed out"))?
+            .map_err(|e| DomainError::media_processing(format!("vips failed: {e}")))?;
+        let _ = feed.await;
+
+        if !output.status.success() || output.stdout.is_empty() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(DomainError::media_processing(format!(
+                "vips rejected the file: {}",
+                stderr.trim()
+            )));
+        }
+
+        if spec.format != ThumbnailFormat::Png {
+            return Ok(Bytes::from(output.stdout));
+        }
+        let png = tokio::task::spawn_blocking(move || {
+            oxipng::optimize_from_memory(&output.stdout, &oxipng::Options::default())
+                .unwrap_or(output.stdout) // fall back to uncompressed if optimisation fails
+        })
+        .await
+        .map_err(|e| DomainError::media_processing(format!("thumbnail worker failed: {e}")))?;
+        Ok(Bytes::from(png))
+    }
+}
+
+impl Default for VipsThumbnailer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn missing_vips_is_a_processing_error() {
+        let vips = VipsThumbnailer::with_binary("/nonexistent/vips");
+        let err = vips.render(Bytes::from_static(b"\x89PNG"), ThumbnailSpec::default()).await.unwrap_err();
+        assert!(matches!(err, DomainError::MediaProcessing { .. }), "got {err:?}");
+    }
+}
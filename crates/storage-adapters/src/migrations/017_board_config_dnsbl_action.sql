@@ -0,0 +1,9 @@
+-- Migration 017: Add dnsbl_action to board_configs
+--
+-- Controls what happens to posters whose IP is listed by a DNSBL zone or the
+-- Tor exit list: 'off' (ignored), 'score' (spam-score penalty), or 'block'.
+-- Default 'block' matches the BoardConfig Rust default and the previous
+-- behaviour of the DNSBL check.
+
+ALTER TABLE board_configs ADD COLUMN dnsbl_action TEXT NOT NULL DEFAULT 'block'
+    CHECK (dnsbl_action IN ('off', 'score', 'block'));
@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS idx_asn_bans_asn;
+DROP TABLE IF EXISTS asn_bans;
@@ -0,0 +1,15 @@
+-- Migration 018: ASN bans table
+--
+-- Bans an entire autonomous system (e.g. a VPN or hosting provider) rather
+-- than a single IP hash. The poster's raw IP is resolved to its ASN at post
+-- time; only the ASN is stored, never the address.
+-- expires_at NULL = permanent ban. Expiry is filtered at query time as in 008.
+CREATE TABLE IF NOT EXISTS asn_bans (
+    id          UUID        PRIMARY KEY DEFAULT gen_random_uuid(),
+    asn         BIGINT      NOT NULL CHECK (asn BETWEEN 0 AND 4294967295),
+    reason      TEXT        NOT NULL,
+    expires_at  TIMESTAMPTZ,
+    banned_by   UUID        NOT NULL REFERENCES users(id),
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+CREATE INDEX IF NOT EXISTS idx_asn_bans_asn ON asn_bans(asn);
@@ -0,0 +1,7 @@
+-- Migration 019: Add strip_metadata to board_configs
+--
+-- When true (the default, matching the BoardConfig Rust default), EXIF/XMP/GPS
+-- metadata is stripped from uploaded images before the original is stored.
+-- Board owners may disable it to keep originals byte-for-byte.
+
+ALTER TABLE board_configs ADD COLUMN strip_metadata BOOLEAN NOT NULL DEFAULT true;
@@ -0,0 +1,6 @@
+-- Migration 020: Add duration_ms to attachments
+--
+-- Playback length of audio/video attachments in milliseconds, as probed at
+-- upload time. NULL for still images and for media whose duration is unknown.
+
+ALTER TABLE attachments ADD COLUMN duration_ms INTEGER CHECK (duration_ms >= 0);
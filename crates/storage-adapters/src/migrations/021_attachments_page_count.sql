@@ -0,0 +1,6 @@
+-- Migration 021: Add page_count to attachments
+--
+-- Number of pages of document attachments (PDF), read at upload time.
+-- NULL for images, audio, video, and documents without a page model (EPUB).
+
+ALTER TABLE attachments ADD COLUMN page_count INTEGER CHECK (page_count >= 0);
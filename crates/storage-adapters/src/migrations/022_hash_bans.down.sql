@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS idx_hash_bans_md5;
+DROP TABLE IF EXISTS hash_bans;
+ALTER TABLE attachments DROP COLUMN phash;
+ALTER TABLE attachments DROP COLUMN md5;
@@ -0,0 +1,23 @@
+-- Migration 022: Upload hashes and hash bans
+--
+-- attachments.md5 is the MD5 of the file as uploaded; attachments.phash is a
+-- 64-bit difference hash of still images, stored as the signed bit pattern.
+-- Both are NULL for attachments uploaded before this migration.
+--
+-- hash_bans reject re-uploads: an exact md5 match, or a phash within the
+-- configured Hamming distance (compared in the application).
+-- expires_at NULL = permanent ban. Expiry is filtered at query time as in 008.
+ALTER TABLE attachments ADD COLUMN md5 TEXT;
+ALTER TABLE attachments ADD COLUMN phash BIGINT;
+
+CREATE TABLE IF NOT EXISTS hash_bans (
+    id          UUID        PRIMARY KEY DEFAULT gen_random_uuid(),
+    md5         TEXT,
+    phash       BIGINT,
+    reason      TEXT        NOT NULL,
+    expires_at  TIMESTAMPTZ,
+    banned_by   UUID        NOT NULL REFERENCES users(id),
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT now(),
+    CHECK (md5 IS NOT NULL OR phash IS NOT NULL)
+);
+CREATE INDEX IF NOT EXISTS idx_hash_bans_md5 ON hash_bans(md5);
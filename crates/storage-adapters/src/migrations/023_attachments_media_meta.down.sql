@@ -0,0 +1,4 @@
+ALTER TABLE attachments DROP COLUMN height;
+ALTER TABLE attachments DROP COLUMN width;
+ALTER TABLE attachments DROP COLUMN size_bytes;
+ALTER TABLE attachments DROP COLUMN original_filename;
@@ -0,0 +1,10 @@
+-- Migration 023: Upload metadata on attachments
+--
+-- The file info line ("cat.jpg (123 KB, 1920x1080)") needs the filename as
+-- uploaded and the exact byte size; width/height are set for images and video.
+-- All NULL for attachments uploaded before this migration.
+
+ALTER TABLE attachments ADD COLUMN original_filename TEXT;
+ALTER TABLE attachments ADD COLUMN size_bytes BIGINT CHECK (size_bytes >= 0);
+ALTER TABLE attachments ADD COLUMN width INTEGER CHECK (width > 0);
+ALTER TABLE attachments ADD COLUMN height INTEGER CHECK (height > 0);
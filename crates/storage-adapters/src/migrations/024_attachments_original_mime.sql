@@ -0,0 +1,7 @@
+-- Migration 024: Original format of re-encoded attachments
+--
+-- With REENCODE_FORMAT set, large JPEG/PNG uploads are stored as WebP or AVIF.
+-- `mime` holds the stored format; this column records what was uploaded.
+-- NULL when the file was stored in its own format.
+
+ALTER TABLE attachments ADD COLUMN original_mime TEXT;
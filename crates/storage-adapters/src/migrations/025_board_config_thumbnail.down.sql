@@ -0,0 +1,4 @@
+ALTER TABLE board_configs DROP COLUMN thumbnail_quality;
+ALTER TABLE board_configs DROP COLUMN thumbnail_format;
+ALTER TABLE board_configs DROP COLUMN thumbnail_max_height;
+ALTER TABLE board_configs DROP COLUMN thumbnail_max_width;
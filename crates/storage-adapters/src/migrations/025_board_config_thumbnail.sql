@@ -0,0 +1,10 @@
+-- Migration 025: Per-board thumbnail override
+--
+-- NULL thumbnail_max_width means the board uses the global THUMBNAIL_* settings.
+-- When set, the other three columns complete the override; thumbnail_max_height
+-- 0 means no height limit.
+
+ALTER TABLE board_configs ADD COLUMN thumbnail_max_width  INTEGER  CHECK (thumbnail_max_width > 0);
+ALTER TABLE board_configs ADD COLUMN thumbnail_max_height INTEGER  CHECK (thumbnail_max_height >= 0);
+ALTER TABLE board_configs ADD COLUMN thumbnail_format     TEXT;
+ALTER TABLE board_configs ADD COLUMN thumbnail_quality    SMALLINT CHECK (thumbnail_quality BETWEEN 1 AND 100);
@@ -0,0 +1,3 @@
+ALTER TABLE board_configs DROP COLUMN classifier_threshold;
+ALTER TABLE board_configs DROP COLUMN classifier_action;
+ALTER TABLE attachments DROP COLUMN labels;
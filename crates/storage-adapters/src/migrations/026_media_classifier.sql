@@ -0,0 +1,15 @@
+-- Migration 026: Upload classification
+--
+-- attachments.labels holds the MediaClassifier output as a JSON array of
+-- {"label", "score"} objects; NULL when no classifier ran.
+--
+-- classifier_action decides what a board does with labels scoring at least
+-- classifier_threshold: 'off' (no classification), 'label' (record only),
+-- 'spoiler' (auto-spoiler), or 'flag' (file a moderation flag).
+
+ALTER TABLE attachments ADD COLUMN labels JSONB;
+
+ALTER TABLE board_configs ADD COLUMN classifier_action TEXT NOT NULL DEFAULT 'off'
+    CHECK (classifier_action IN ('off', 'label', 'spoiler', 'flag'));
+ALTER TABLE board_configs ADD COLUMN classifier_threshold REAL NOT NULL DEFAULT 0.8
+    CHECK (classifier_threshold BETWEEN 0 AND 1);
@@ -0,0 +1,2 @@
+ALTER TABLE board_configs DROP COLUMN quota_action;
+ALTER TABLE board_configs DROP COLUMN media_quota_mb;
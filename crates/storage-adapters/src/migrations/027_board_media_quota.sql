@@ -0,0 +1,13 @@
+-- Migration 027: Per-board media quotas
+--
+-- media_quota_mb caps the bytes of stored originals across a board's posts;
+-- 0 means unlimited. Usage is summed from attachments on demand, so there is
+-- no counter to keep in step with deletions and cascades.
+--
+-- quota_action decides what an upload past the quota does: 'reject' the post,
+-- or 'prune' the board's oldest non-sticky threads until it fits.
+
+ALTER TABLE board_configs ADD COLUMN media_quota_mb INTEGER NOT NULL DEFAULT 0
+    CHECK (media_quota_mb >= 0);
+ALTER TABLE board_configs ADD COLUMN quota_action TEXT NOT NULL DEFAULT 'reject'
+    CHECK (quota_action IN ('reject', 'prune'));
@@ -0,0 +1,6 @@
+-- Fails if two sites have a board with the same slug; delete one first.
+ALTER TABLE boards DROP CONSTRAINT boards_site_slug_key;
+ALTER TABLE boards ADD CONSTRAINT boards_slug_key UNIQUE (slug);
+ALTER TABLE boards DROP COLUMN site_id;
+DROP INDEX IF EXISTS idx_sites_host;
+DROP TABLE IF EXISTS sites;
@@ -0,0 +1,26 @@
+-- Migration 028: Sites (multi-tenant hosting)
+--
+-- A site is an independent imageboard selected by the request's Host header.
+-- Boards belong to one site, and slugs are unique per site rather than
+-- globally, so two sites can both have a /b/.
+--
+-- The default site (nil UUID) serves every host no other site claims. Its
+-- host starts empty; existing boards move onto it. media_prefix is the
+-- directory a site's uploads are stored under ('' = the media root).
+CREATE TABLE IF NOT EXISTS sites (
+    id           UUID        PRIMARY KEY DEFAULT gen_random_uuid(),
+    host         TEXT        NOT NULL DEFAULT '',
+    name         TEXT        NOT NULL CHECK (length(name) BETWEEN 1 AND 64),
+    media_prefix TEXT        NOT NULL DEFAULT ''
+                             CHECK (media_prefix ~ '^[a-z0-9_-]{0,32}$'),
+    created_at   TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_sites_host ON sites(host) WHERE host <> '';
+
+INSERT INTO sites (id, name) VALUES ('00000000-0000-0000-0000-000000000000', 'rusty-board')
+    ON CONFLICT DO NOTHING;
+
+ALTER TABLE boards ADD COLUMN site_id UUID NOT NULL
+    DEFAULT '00000000-0000-0000-0000-000000000000' REFERENCES sites(id);
+ALTER TABLE boards DROP CONSTRAINT boards_slug_key;
+ALTER TABLE boards ADD CONSTRAINT boards_site_slug_key UNIQUE (site_id, slug);
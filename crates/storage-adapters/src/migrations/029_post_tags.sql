@@ -0,0 +1,6 @@
+-- Migration 029: Post tags
+--
+-- Labels attached by operator post filters (WASM or Lua plugins) when the
+-- post was submitted. Shown to staff only; empty for most posts.
+
+ALTER TABLE posts ADD COLUMN tags TEXT[] NOT NULL DEFAULT '{}';
@@ -0,0 +1,13 @@
+-- Migration 030: ActivityPub followers
+--
+-- Remote actors following a board with federation_enabled. inbox is where
+-- new posts are delivered: the follower's shared inbox when its instance
+-- has one. A board's followers go with it.
+
+CREATE TABLE IF NOT EXISTS remote_followers (
+    board_id   UUID        NOT NULL REFERENCES boards(id) ON DELETE CASCADE,
+    actor_id   TEXT        NOT NULL,
+    inbox      TEXT        NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (board_id, actor_id)
+);
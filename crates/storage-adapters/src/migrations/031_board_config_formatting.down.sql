@@ -0,0 +1,4 @@
+ALTER TABLE board_configs DROP COLUMN greentext_enabled;
+ALTER TABLE board_configs DROP COLUMN code_enabled;
+ALTER TABLE board_configs DROP COLUMN spoilers_enabled;
+ALTER TABLE board_configs DROP COLUMN markup_enabled;
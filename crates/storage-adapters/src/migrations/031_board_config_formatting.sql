@@ -0,0 +1,10 @@
+-- Migration 031: Add per-board post formatting toggles to board_configs
+--
+-- Post bodies are rendered on the server by rb-format. Each column turns one
+-- group of formatting on or off for the board; all default to true, matching
+-- the BoardConfig Rust default. Quote links are always rendered.
+
+ALTER TABLE board_configs ADD COLUMN markup_enabled    BOOLEAN NOT NULL DEFAULT true;
+ALTER TABLE board_configs ADD COLUMN spoilers_enabled  BOOLEAN NOT NULL DEFAULT true;
+ALTER TABLE board_configs ADD COLUMN code_enabled      BOOLEAN NOT NULL DEFAULT true;
+ALTER TABLE board_configs ADD COLUMN greentext_enabled BOOLEAN NOT NULL DEFAULT true;
@@ -0,0 +1,7 @@
+-- Migration 032: Add code_highlighting to board_configs
+--
+-- When true, fenced code blocks that name their language (```rust) are
+-- syntax-highlighted by rb-format. Off by default, matching the BoardConfig
+-- Rust default; meant for programming boards.
+
+ALTER TABLE board_configs ADD COLUMN code_highlighting BOOLEAN NOT NULL DEFAULT false;
@@ -0,0 +1,2 @@
+ALTER TABLE posts DROP COLUMN commands;
+ALTER TABLE board_configs DROP COLUMN commands_enabled;
@@ -0,0 +1,9 @@
+-- Migration 033: Post commands
+--
+-- board_configs.commands_enabled turns on `!roll` and `!fortune` lines; off
+-- by default, matching the BoardConfig Rust default. posts.commands holds the
+-- results computed when the post was made (a JSON array of CommandResult),
+-- so a roll never changes after the fact.
+
+ALTER TABLE board_configs ADD COLUMN commands_enabled BOOLEAN NOT NULL DEFAULT false;
+ALTER TABLE posts ADD COLUMN commands JSONB NOT NULL DEFAULT '[]';
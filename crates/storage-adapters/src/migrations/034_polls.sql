@@ -0,0 +1,24 @@
+-- Migration 034: Thread polls
+--
+-- board_configs.polls_enabled lets the OP of a new thread attach a poll; off
+-- by default, matching the BoardConfig Rust default. A thread has at most one
+-- poll. poll_votes holds one row per voter: the poster's daily IP hash, the
+-- same value the thread's ID badges are derived from. option_index points
+-- into polls.options. Deleting the thread removes the poll and its votes.
+
+ALTER TABLE board_configs ADD COLUMN polls_enabled BOOLEAN NOT NULL DEFAULT false;
+
+CREATE TABLE IF NOT EXISTS polls (
+    thread_id  UUID        PRIMARY KEY REFERENCES threads(id) ON DELETE CASCADE,
+    question   TEXT        NOT NULL,
+    options    TEXT[]      NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE TABLE IF NOT EXISTS poll_votes (
+    thread_id    UUID        NOT NULL REFERENCES polls(thread_id) ON DELETE CASCADE,
+    ip_hash      TEXT        NOT NULL,
+    option_index SMALLINT    NOT NULL,
+    created_at   TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (thread_id, ip_hash)
+);
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS post_reactions;
+ALTER TABLE board_configs DROP COLUMN reactions_enabled;
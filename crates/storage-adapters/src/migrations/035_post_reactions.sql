@@ -0,0 +1,17 @@
+-- Migration 035: Post reactions
+--
+-- board_configs.reactions_enabled lets posters react to posts with one of a
+-- fixed set of reactions; off by default, matching the BoardConfig Rust
+-- default. post_reactions holds one row per post and reacting IP hash, so a
+-- poster has one reaction per post. reaction is the Reaction's snake_case
+-- name. Deleting the post removes its reactions.
+
+ALTER TABLE board_configs ADD COLUMN reactions_enabled BOOLEAN NOT NULL DEFAULT false;
+
+CREATE TABLE IF NOT EXISTS post_reactions (
+    post_id    UUID        NOT NULL REFERENCES posts(id) ON DELETE CASCADE,
+    ip_hash    TEXT        NOT NULL,
+    reaction   TEXT        NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (post_id, ip_hash)
+);
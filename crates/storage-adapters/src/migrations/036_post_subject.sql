@@ -0,0 +1,6 @@
+-- Migration 036: Post subject
+--
+-- posts.subject holds the subject an OP was posted with. Replies never have
+-- one, and existing posts keep NULL.
+
+ALTER TABLE posts ADD COLUMN subject TEXT;
@@ -0,0 +1,2 @@
+UPDATE posts SET tripcode = '!!!! ' || capcode WHERE capcode IS NOT NULL;
+ALTER TABLE posts DROP COLUMN capcode;
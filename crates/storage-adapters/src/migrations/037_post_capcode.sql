@@ -0,0 +1,11 @@
+-- Migration 037: Post capcode
+--
+-- posts.capcode holds a verified staff capcode role (e.g. 'Admin'). Capcodes
+-- used to be stored in posts.tripcode as '!!!! <Role>'; those rows move
+-- over, so a tripcode can no longer be mistaken for a capcode.
+
+ALTER TABLE posts ADD COLUMN capcode TEXT;
+
+UPDATE posts
+SET    capcode = substr(tripcode, 6), tripcode = NULL
+WHERE  tripcode LIKE '!!!! %';
@@ -0,0 +1 @@
+ALTER TABLE board_configs DROP COLUMN tripcode_algorithm;
@@ -0,0 +1,9 @@
+-- Migration 038: Per-board tripcode algorithm
+--
+-- tripcode_algorithm picks how `Name#password` trips are computed: 'sha256'
+-- (the default, matching the BoardConfig Rust default) or 'crypt', the
+-- classic Shift-JIS + DES crypt(3) algorithm, so trips made on other
+-- imageboards carry over.
+
+ALTER TABLE board_configs ADD COLUMN tripcode_algorithm TEXT NOT NULL DEFAULT 'sha256'
+    CHECK (tripcode_algorithm IN ('sha256', 'crypt'));
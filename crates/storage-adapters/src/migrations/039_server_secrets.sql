@@ -0,0 +1,11 @@
+-- Migration 039: Server secrets
+--
+-- Long-lived secrets the server generates for itself on first use, such as
+-- the pepper for `##` secure tripcodes when TRIPCODE_PEPPER is unset. They
+-- live in the database so they survive restarts and are shared by every
+-- instance. A row is never rewritten; deleting it rotates the secret.
+CREATE TABLE IF NOT EXISTS server_secrets (
+    name       TEXT        PRIMARY KEY,
+    value      TEXT        NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
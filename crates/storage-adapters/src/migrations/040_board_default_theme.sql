@@ -0,0 +1,8 @@
+-- Migration 040: Per-board default theme
+--
+-- default_theme is the stylesheet a board's pages load for viewers who have
+-- not picked a theme in the settings panel: 'futaba' (the default, matching
+-- the BoardConfig Rust default), 'yotsuba', 'tomorrow' or 'dark'.
+
+ALTER TABLE board_configs ADD COLUMN default_theme TEXT NOT NULL DEFAULT 'futaba'
+    CHECK (default_theme IN ('futaba', 'yotsuba', 'tomorrow', 'dark'));
@@ -0,0 +1,8 @@
+-- Migration 041: Per-board default locale
+--
+-- default_locale is the UI language for viewers whose Accept-Language names
+-- no supported locale: 'en' (the default, matching the BoardConfig Rust
+-- default) or 'es'.
+
+ALTER TABLE board_configs ADD COLUMN default_locale TEXT NOT NULL DEFAULT 'en'
+    CHECK (default_locale IN ('en', 'es'));
@@ -0,0 +1,8 @@
+-- Migration 042: Board directory category
+--
+-- category groups the board in the homepage directory. Free text of at most
+-- 32 characters; '' (the default, matching the BoardConfig Rust default)
+-- lists the board under "Other".
+
+ALTER TABLE board_configs ADD COLUMN category TEXT NOT NULL DEFAULT ''
+    CHECK (char_length(category) <= 32);
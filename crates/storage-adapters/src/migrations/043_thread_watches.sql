@@ -0,0 +1,14 @@
+-- Migration 043: Thread watch lists
+--
+-- thread_watches holds one row per anonymous watcher token and watched
+-- thread. last_seen_post is the highest post number the watcher has seen;
+-- posts numbered after it count as unread. Deleting the thread removes its
+-- watches.
+
+CREATE TABLE IF NOT EXISTS thread_watches (
+    watcher        UUID        NOT NULL,
+    thread_id      UUID        NOT NULL REFERENCES threads(id) ON DELETE CASCADE,
+    last_seen_post BIGINT      NOT NULL DEFAULT 0,
+    created_at     TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (watcher, thread_id)
+);
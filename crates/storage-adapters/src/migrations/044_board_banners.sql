@@ -0,0 +1,15 @@
+-- Migration 044: Rotating board banners
+--
+-- board_banners records the banner images of each board; the images
+-- themselves live in media storage at media_key (under banners/). Deleting
+-- the board removes its rows; the images are left for the operator.
+
+CREATE TABLE IF NOT EXISTS board_banners (
+    id           UUID        PRIMARY KEY,
+    board_id     UUID        NOT NULL REFERENCES boards(id) ON DELETE CASCADE,
+    media_key    TEXT        NOT NULL,
+    content_type TEXT        NOT NULL,
+    created_at   TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS board_banners_board_idx ON board_banners (board_id, created_at);
@@ -0,0 +1,15 @@
+-- Migration 045: Blotter announcements
+--
+-- announcements holds the short dated entries shown at the top of board
+-- pages. board_id NULL means the entry is shown on every board of the site.
+-- Deleting the board removes its entries.
+
+CREATE TABLE IF NOT EXISTS announcements (
+    id         UUID        PRIMARY KEY,
+    site_id    UUID        NOT NULL REFERENCES sites(id) ON DELETE CASCADE,
+    board_id   UUID        REFERENCES boards(id) ON DELETE CASCADE,
+    message    TEXT        NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS announcements_site_idx ON announcements (site_id, created_at DESC);
@@ -0,0 +1,19 @@
+-- Migration 046: Static pages
+--
+-- static_pages holds admin-edited pages (rules, FAQ, contact) in Markdown.
+-- board_id NULL makes it a site page; slugs are unique per site for site
+-- pages and per board for board pages. Deleting the site or board removes
+-- its pages.
+
+CREATE TABLE IF NOT EXISTS static_pages (
+    id         UUID        PRIMARY KEY,
+    site_id    UUID        NOT NULL REFERENCES sites(id) ON DELETE CASCADE,
+    board_id   UUID        REFERENCES boards(id) ON DELETE CASCADE,
+    slug       TEXT        NOT NULL,
+    title      TEXT        NOT NULL,
+    body       TEXT        NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS static_pages_site_slug_idx  ON static_pages (site_id, slug) WHERE board_id IS NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS static_pages_board_slug_idx ON static_pages (board_id, slug) WHERE board_id IS NOT NULL;
@@ -0,0 +1 @@
+ALTER TABLE board_configs DROP COLUMN overboard_hidden;
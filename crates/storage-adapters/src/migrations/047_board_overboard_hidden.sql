@@ -0,0 +1,7 @@
+-- Migration 047: Hide boards from the overboard catalog
+--
+-- overboard_hidden leaves the board's threads out of the /all catalog of
+-- recently bumped threads. false (the default, matching the BoardConfig Rust
+-- default) lists the board.
+
+ALTER TABLE board_configs ADD COLUMN overboard_hidden BOOLEAN NOT NULL DEFAULT false;
@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS idx_posts_created;
+DROP TABLE IF EXISTS board_stats;
@@ -0,0 +1,21 @@
+-- Migration 048: Board statistics
+--
+-- board_stats holds each board's activity per hour and per day, rolled up
+-- from posts and attachments by the stats job. Rows are recomputed while
+-- their hour or day is recent and kept afterwards, so they outlive pruned
+-- threads. Hours and days without posts have no row. Deleting the board
+-- removes its rows.
+
+CREATE TABLE IF NOT EXISTS board_stats (
+    board_id       UUID        NOT NULL REFERENCES boards(id) ON DELETE CASCADE,
+    period         TEXT        NOT NULL CHECK (period IN ('hour', 'day')),
+    bucket_start   TIMESTAMPTZ NOT NULL,
+    posts          BIGINT      NOT NULL CHECK (posts >= 0),
+    unique_posters BIGINT      NOT NULL CHECK (unique_posters >= 0),
+    media_count    BIGINT      NOT NULL CHECK (media_count >= 0),
+    media_bytes    BIGINT      NOT NULL CHECK (media_bytes >= 0),
+    PRIMARY KEY (board_id, period, bucket_start)
+);
+
+-- The rollup reads recent posts by creation time.
+CREATE INDEX IF NOT EXISTS idx_posts_created ON posts (created_at);
@@ -0,0 +1 @@
+ALTER TABLE sites DROP COLUMN notice_enabled, DROP COLUMN notice;
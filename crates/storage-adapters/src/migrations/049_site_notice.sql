@@ -0,0 +1,10 @@
+-- Migration 049: Site notice (MOTD)
+--
+-- A short admin-set message, e.g. a maintenance notice, shown at the top of
+-- every page of the site and in the meta block of JSON responses while
+-- notice_enabled is true. The message is kept when the notice is switched
+-- off, so it can be switched on again unchanged.
+
+ALTER TABLE sites
+    ADD COLUMN notice         TEXT    NOT NULL DEFAULT '' CHECK (char_length(notice) <= 500),
+    ADD COLUMN notice_enabled BOOLEAN NOT NULL DEFAULT false;
@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS idx_bans_shadow;
+ALTER TABLE bans DROP COLUMN shadow;
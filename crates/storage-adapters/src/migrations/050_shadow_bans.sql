@@ -0,0 +1,9 @@
+-- Migration 050: Shadowbans
+--
+-- A shadowban lets the poster keep posting, but their posts are shown only to
+-- themselves (by IP hash) and to staff. Public reads skip posts whose IP hash
+-- has an active shadowban; the partial index keeps that check cheap.
+
+ALTER TABLE bans ADD COLUMN shadow BOOLEAN NOT NULL DEFAULT false;
+
+CREATE INDEX IF NOT EXISTS idx_bans_shadow ON bans(ip_hash) WHERE shadow;
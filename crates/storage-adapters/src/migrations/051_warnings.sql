@@ -0,0 +1,17 @@
+-- Migration 051: Warnings
+--
+-- A moderator's warning to the poster behind an IP hash, optionally about one
+-- post. It is shown on their next page load and blocks posting until they
+-- acknowledge it (acknowledged_at set). The partial index serves the
+-- pending-warning lookup made on page loads and posts.
+CREATE TABLE IF NOT EXISTS warnings (
+    id              UUID        PRIMARY KEY DEFAULT gen_random_uuid(),
+    ip_hash         TEXT        NOT NULL,
+    post_id         UUID        REFERENCES posts(id) ON DELETE SET NULL,
+    reason          TEXT        NOT NULL,
+    issued_by       UUID        NOT NULL REFERENCES users(id),
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT now(),
+    acknowledged_at TIMESTAMPTZ
+);
+CREATE INDEX IF NOT EXISTS idx_warnings_pending ON warnings(ip_hash, created_at)
+    WHERE acknowledged_at IS NULL;
@@ -0,0 +1,5 @@
+ALTER TABLE board_configs DROP COLUMN public_log;
+DROP INDEX IF EXISTS idx_audit_board;
+UPDATE audit_logs SET action = 'ban_ip' WHERE action = 'shadowban_ip';
+ALTER TABLE audit_logs DROP COLUMN reason;
+ALTER TABLE audit_logs DROP COLUMN board_id;
@@ -0,0 +1,22 @@
+-- Migration 052: Board-scoped moderation log
+--
+-- audit_logs gains the board an action was taken on (NULL for site-wide
+-- actions such as bans) and the reason given for it, replacing the lookup of
+-- board_id in details. Existing rows are backfilled from details, and
+-- ban_ip entries for shadowbans become shadowban_ip so they are never shown
+-- in a public log.
+--
+-- board_configs.public_log publishes the board's log at /board/{slug}/log
+-- without actors, IP hashes or details. false (the default, matching the
+-- BoardConfig Rust default) keeps it staff-only.
+
+ALTER TABLE audit_logs ADD COLUMN board_id UUID REFERENCES boards(id) ON DELETE SET NULL;
+ALTER TABLE audit_logs ADD COLUMN reason TEXT;
+
+UPDATE audit_logs a SET board_id = b.id FROM boards b WHERE b.id::text = a.details->>'board_id';
+UPDATE audit_logs SET reason = details->>'reason' WHERE details ? 'reason';
+UPDATE audit_logs SET action = 'shadowban_ip' WHERE action = 'ban_ip' AND details->>'shadow' = 'true';
+
+CREATE INDEX IF NOT EXISTS idx_audit_board ON audit_logs(board_id, created_at DESC) WHERE board_id IS NOT NULL;
+
+ALTER TABLE board_configs ADD COLUMN public_log BOOLEAN NOT NULL DEFAULT false;
@@ -0,0 +1,15 @@
+-- Migration 053: TOTP two-factor auth
+--
+-- One row per account that has started enrolling. Login asks for a code only
+-- once enabled_at is set, which happens when the user confirms a code from
+-- their authenticator app. recovery_codes holds the SHA-256 hex of each unused
+-- recovery code; last_step is the 30-second step of the last accepted code,
+-- so an intercepted code cannot be replayed.
+CREATE TABLE IF NOT EXISTS staff_totp (
+    user_id        UUID        PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    secret         TEXT        NOT NULL,
+    enabled_at     TIMESTAMPTZ,
+    recovery_codes TEXT[]      NOT NULL DEFAULT '{}',
+    last_step      BIGINT,
+    created_at     TIMESTAMPTZ NOT NULL DEFAULT now()
+);
@@ -0,0 +1,17 @@
+-- Migration 054: API keys for bots and bridges
+--
+-- key_hash is the SHA-256 hex of the key; the key itself is never stored.
+-- prefix keeps its first characters so admins can tell keys apart. scopes
+-- holds 'read' and/or 'post'. Revoked keys stay listed with revoked_at set.
+CREATE TABLE IF NOT EXISTS api_keys (
+    id                 UUID        PRIMARY KEY,
+    name               TEXT        NOT NULL,
+    prefix             TEXT        NOT NULL,
+    key_hash           TEXT        NOT NULL UNIQUE,
+    scopes             TEXT[]      NOT NULL,
+    rate_limit_per_min INTEGER     NOT NULL CHECK (rate_limit_per_min > 0),
+    created_by         UUID        NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at         TIMESTAMPTZ NOT NULL DEFAULT now(),
+    last_used_at       TIMESTAMPTZ,
+    revoked_at         TIMESTAMPTZ
+);
@@ -0,0 +1,20 @@
+DROP TRIGGER  IF EXISTS trg_announcements_touch_boards ON announcements;
+DROP TRIGGER  IF EXISTS trg_board_configs_touch_board ON board_configs;
+DROP TRIGGER  IF EXISTS trg_boards_settings_modified_at ON boards;
+DROP TRIGGER  IF EXISTS trg_threads_touch_board ON threads;
+DROP TRIGGER  IF EXISTS trg_post_reactions_touch_thread ON post_reactions;
+DROP TRIGGER  IF EXISTS trg_attachments_touch_thread ON attachments;
+DROP TRIGGER  IF EXISTS trg_poll_votes_touch_thread ON poll_votes;
+DROP TRIGGER  IF EXISTS trg_polls_touch_thread ON polls;
+DROP TRIGGER  IF EXISTS trg_posts_touch_thread ON posts;
+DROP TRIGGER  IF EXISTS trg_threads_modified_at ON threads;
+DROP FUNCTION IF EXISTS touch_boards_by_announcement();
+DROP FUNCTION IF EXISTS touch_board_settings();
+DROP FUNCTION IF EXISTS boards_set_settings_modified_at();
+DROP FUNCTION IF EXISTS touch_board_by_thread();
+DROP FUNCTION IF EXISTS touch_thread_by_post_id();
+DROP FUNCTION IF EXISTS touch_thread_by_thread_id();
+DROP FUNCTION IF EXISTS threads_set_modified_at();
+ALTER TABLE boards DROP COLUMN settings_modified_at;
+ALTER TABLE boards DROP COLUMN modified_at;
+ALTER TABLE threads DROP COLUMN modified_at;
@@ -0,0 +1,154 @@
+-- Migration 055: Last-modified timestamps for conditional GET
+--
+-- threads.modified_at moves whenever anything on the thread's page changes:
+-- the thread row itself (bump, sticky, lock, cycle), its posts, and their
+-- attachments, reactions and poll. boards.modified_at moves whenever any of
+-- the board's threads change, including deletions, which is what the index
+-- and catalog show. boards.settings_modified_at moves on changes shown on
+-- every page of the board: its config, blotter (board and site-wide
+-- entries), slug, title and rules; those move modified_at too.
+--
+-- Triggers keep the columns, so every write path counts, moderation and
+-- maintenance jobs included. Existing rows start at the migration time.
+
+ALTER TABLE threads ADD COLUMN modified_at TIMESTAMPTZ NOT NULL DEFAULT now();
+ALTER TABLE boards ADD COLUMN modified_at TIMESTAMPTZ NOT NULL DEFAULT now();
+ALTER TABLE boards ADD COLUMN settings_modified_at TIMESTAMPTZ NOT NULL DEFAULT now();
+
+-- Thread row changes.
+CREATE OR REPLACE FUNCTION threads_set_modified_at()
+RETURNS TRIGGER LANGUAGE plpgsql AS $$
+BEGIN
+    NEW.modified_at = now();
+    RETURN NEW;
+END;
+$$;
+
+CREATE TRIGGER trg_threads_modified_at
+    BEFORE UPDATE ON threads
+    FOR EACH ROW EXECUTE FUNCTION threads_set_modified_at();
+
+-- Posts, polls and poll votes: rows with a thread_id.
+CREATE OR REPLACE FUNCTION touch_thread_by_thread_id()
+RETURNS TRIGGER LANGUAGE plpgsql AS $$
+BEGIN
+    IF TG_OP IN ('UPDATE', 'DELETE') THEN
+        UPDATE threads SET modified_at = now() WHERE id = OLD.thread_id;
+    END IF;
+    IF TG_OP = 'INSERT' OR (TG_OP = 'UPDATE' AND NEW.thread_id IS DISTINCT FROM OLD.thread_id) THEN
+        UPDATE threads SET modified_at = now() WHERE id = NEW.thread_id;
+    END IF;
+    RETURN NULL;
+END;
+$$;
+
+CREATE TRIGGER trg_posts_touch_thread
+    AFTER INSERT OR UPDATE OR DELETE ON posts
+    FOR EACH ROW EXECUTE FUNCTION touch_thread_by_thread_id();
+
+CREATE TRIGGER trg_polls_touch_thread
+    AFTER INSERT OR UPDATE OR DELETE ON polls
+    FOR EACH ROW EXECUTE FUNCTION touch_thread_by_thread_id();
+
+CREATE TRIGGER trg_poll_votes_touch_thread
+    AFTER INSERT OR UPDATE OR DELETE ON poll_votes
+    FOR EACH ROW EXECUTE FUNCTION touch_thread_by_thread_id();
+
+-- Attachments and reactions: rows with a post_id.
+CREATE OR REPLACE FUNCTION touch_thread_by_post_id()
+RETURNS TRIGGER LANGUAGE plpgsql AS $$
+BEGIN
+    IF TG_OP IN ('UPDATE', 'DELETE') THEN
+        UPDATE threads SET modified_at = now()
+        WHERE id = (SELECT thread_id FROM posts WHERE id = OLD.post_id);
+    END IF;
+    IF TG_OP = 'INSERT' OR (TG_OP = 'UPDATE' AND NEW.post_id IS DISTINCT FROM OLD.post_id) THEN
+        UPDATE threads SET modified_at = now()
+        WHERE id = (SELECT thread_id FROM posts WHERE id = NEW.post_id);
+    END IF;
+    RETURN NULL;
+END;
+$$;
+
+CREATE TRIGGER trg_attachments_touch_thread
+    AFTER INSERT OR UPDATE OR DELETE ON attachments
+    FOR EACH ROW EXECUTE FUNCTION touch_thread_by_post_id();
+
+CREATE TRIGGER trg_post_reactions_touch_thread
+    AFTER INSERT OR UPDATE OR DELETE ON post_reactions
+    FOR EACH ROW EXECUTE FUNCTION touch_thread_by_post_id();
+
+-- Threads: the board's index and catalog.
+CREATE OR REPLACE FUNCTION touch_board_by_thread()
+RETURNS TRIGGER LANGUAGE plpgsql AS $$
+BEGIN
+    IF TG_OP IN ('UPDATE', 'DELETE') THEN
+        UPDATE boards SET modified_at = now() WHERE id = OLD.board_id;
+    END IF;
+    IF TG_OP = 'INSERT' OR (TG_OP = 'UPDATE' AND NEW.board_id IS DISTINCT FROM OLD.board_id) THEN
+        UPDATE boards SET modified_at = now() WHERE id = NEW.board_id;
+    END IF;
+    RETURN NULL;
+END;
+$$;
+
+CREATE TRIGGER trg_threads_touch_board
+    AFTER INSERT OR UPDATE OR DELETE ON threads
+    FOR EACH ROW EXECUTE FUNCTION touch_board_by_thread();
+
+-- Board-wide changes. post_counter moves on every post, so only the columns
+-- shown on pages count for the board row itself.
+CREATE OR REPLACE FUNCTION boards_set_settings_modified_at()
+RETURNS TRIGGER LANGUAGE plpgsql AS $$
+BEGIN
+    NEW.modified_at = now();
+    NEW.settings_modified_at = now();
+    RETURN NEW;
+END;
+$$;
+
+CREATE TRIGGER trg_boards_settings_modified_at
+    BEFORE UPDATE ON boards
+    FOR EACH ROW
+    WHEN ((OLD.slug, OLD.title, OLD.rules) IS DISTINCT FROM (NEW.slug, NEW.title, NEW.rules))
+    EXECUTE FUNCTION boards_set_settings_modified_at();
+
+CREATE OR REPLACE FUNCTION touch_board_settings()
+RETURNS TRIGGER LANGUAGE plpgsql AS $$
+DECLARE
+    row_board UUID;
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        row_board = OLD.board_id;
+    ELSE
+        row_board = NEW.board_id;
+    END IF;
+    UPDATE boards SET modified_at = now(), settings_modified_at = now() WHERE id = row_board;
+    RETURN NULL;
+END;
+$$;
+
+CREATE TRIGGER trg_board_configs_touch_board
+    AFTER INSERT OR UPDATE ON board_configs
+    FOR EACH ROW EXECUTE FUNCTION touch_board_settings();
+
+-- Site-wide blotter entries (board_id NULL) show on every board of the site.
+CREATE OR REPLACE FUNCTION touch_boards_by_announcement()
+RETURNS TRIGGER LANGUAGE plpgsql AS $$
+DECLARE
+    entry announcements;
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        entry = OLD;
+    ELSE
+        entry = NEW;
+    END IF;
+    UPDATE boards SET modified_at = now(), settings_modified_at = now()
+    WHERE CASE WHEN entry.board_id IS NULL THEN site_id = entry.site_id ELSE id = entry.board_id END;
+    RETURN NULL;
+END;
+$$;
+
+CREATE TRIGGER trg_announcements_touch_boards
+    AFTER INSERT OR UPDATE OR DELETE ON announcements
+    FOR EACH ROW EXECUTE FUNCTION touch_boards_by_announcement();
@@ -0,0 +1,13 @@
+-- Migration 056: Post tombstones
+--
+-- Deleting a post no longer removes its row: deleted_at and deleted_by
+-- ('user' for the poster, 'staff' for a moderator) turn it into a tombstone
+-- that keeps its number and place in the thread and renders as "[deleted]".
+-- The purge job removes tombstones older than DELETED_POST_RETENTION_DAYS;
+-- the partial index serves it.
+ALTER TABLE posts ADD COLUMN deleted_at TIMESTAMPTZ;
+ALTER TABLE posts ADD COLUMN deleted_by TEXT CHECK (deleted_by IN ('user', 'staff'));
+ALTER TABLE posts ADD CONSTRAINT posts_deleted_by_set
+    CHECK ((deleted_at IS NULL) = (deleted_by IS NULL));
+CREATE INDEX IF NOT EXISTS idx_posts_deleted_at ON posts(deleted_at)
+    WHERE deleted_at IS NOT NULL;
@@ -0,0 +1,16 @@
+-- Migration 057: Post revisions
+--
+-- Staff can edit a post's body and subject, e.g. to redact personal details.
+-- Each edit keeps the text it replaced in post_revisions, by the editing
+-- account; posts.edited_at marks the post "edited by staff". Revisions go
+-- with their post.
+ALTER TABLE posts ADD COLUMN edited_at TIMESTAMPTZ;
+CREATE TABLE IF NOT EXISTS post_revisions (
+    id         UUID        PRIMARY KEY DEFAULT gen_random_uuid(),
+    post_id    UUID        NOT NULL REFERENCES posts(id) ON DELETE CASCADE,
+    body       TEXT        NOT NULL,
+    subject    TEXT,
+    edited_by  UUID        NOT NULL REFERENCES users(id),
+    edited_at  TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+CREATE INDEX IF NOT EXISTS idx_post_revisions_post ON post_revisions(post_id, edited_at);
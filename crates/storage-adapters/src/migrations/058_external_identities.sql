@@ -0,0 +1,14 @@
+-- Migration 058: accounts of an external identity provider (SSO)
+--
+-- An SSO login is matched to its account by the provider's issuer and
+-- subject (the ID token's iss and sub), never by username, so a provider
+-- account cannot take over a local one that happens to share its name.
+CREATE TABLE IF NOT EXISTS external_identities (
+    issuer     TEXT        NOT NULL,
+    subject    TEXT        NOT NULL,
+    user_id    UUID        NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (issuer, subject)
+);
+
+CREATE INDEX IF NOT EXISTS idx_external_identities_user ON external_identities (user_id);
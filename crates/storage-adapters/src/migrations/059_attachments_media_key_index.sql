@@ -0,0 +1,5 @@
+-- Migration 059: look up attachments by storage key
+--
+-- The local media route reads the MIME type an upload was stored with to set
+-- Content-Type, once per request for a file.
+CREATE INDEX IF NOT EXISTS idx_attachments_media_key ON attachments (media_key);
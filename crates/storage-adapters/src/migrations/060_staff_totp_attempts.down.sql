@@ -0,0 +1,3 @@
+ALTER TABLE staff_totp
+    DROP COLUMN IF EXISTS locked_until,
+    DROP COLUMN IF EXISTS attempts;
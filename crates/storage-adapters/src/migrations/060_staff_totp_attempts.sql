@@ -0,0 +1,9 @@
+-- Migration 060: limit guesses at TOTP codes per account
+--
+-- attempts counts codes tried since the last accepted one; reaching the
+-- service's limit (TOTP_MAX_ATTEMPTS) locks the factor until locked_until,
+-- so a stolen password alone is not enough to brute-force the code. Both are
+-- reset when a code is accepted.
+ALTER TABLE staff_totp
+    ADD COLUMN IF NOT EXISTS attempts     INTEGER     NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS locked_until TIMESTAMPTZ;
@@ -0,0 +1,2 @@
+DROP TRIGGER  IF EXISTS trg_bans_touch_threads ON bans;
+DROP FUNCTION IF EXISTS touch_threads_by_shadowban();
@@ -0,0 +1,31 @@
+-- Migration 061: Shadowbans move modified_at
+--
+-- A shadowban hides its IP hash's posts from everyone else without touching
+-- them, so the triggers of migration 055 never saw one issued or lifted and
+-- conditional GETs kept answering 304 with the old pages. Touching the
+-- threads with posts from the hash moves their modified_at, and through
+-- trg_threads_touch_board that of their boards.
+--
+-- A shadowban that runs out on its own (expires_at passes) is a change no
+-- write marks; its pages move again with the next post or when the retention
+-- sweep deletes the ban.
+
+CREATE OR REPLACE FUNCTION touch_threads_by_shadowban()
+RETURNS TRIGGER LANGUAGE plpgsql AS $$
+BEGIN
+    IF TG_OP IN ('UPDATE', 'DELETE') AND OLD.shadow THEN
+        UPDATE threads SET modified_at = now()
+        WHERE id IN (SELECT thread_id FROM posts WHERE ip_hash = OLD.ip_hash);
+    END IF;
+    IF TG_OP IN ('INSERT', 'UPDATE') AND NEW.shadow
+       AND (TG_OP = 'INSERT' OR NOT OLD.shadow OR NEW.ip_hash IS DISTINCT FROM OLD.ip_hash) THEN
+        UPDATE threads SET modified_at = now()
+        WHERE id IN (SELECT thread_id FROM posts WHERE ip_hash = NEW.ip_hash);
+    END IF;
+    RETURN NULL;
+END;
+$$;
+
+CREATE TRIGGER trg_bans_touch_threads
+    AFTER INSERT OR UPDATE OR DELETE ON bans
+    FOR EACH ROW EXECUTE FUNCTION touch_threads_by_shadowban();
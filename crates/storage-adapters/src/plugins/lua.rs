@@ -0,0 +1,288 @@
+//! Lua post filter scripts run with mlua (Lua 5.4).
+//!
+//! Every `*.lua` file in `LUA_SCRIPT_DIR` is one script. A script defines a
+//! global function `on_post(post)`, where `post` is the `PostSubmission` as a
+//! table (`board_id`, `thread_id`, `body`, `name`, `email`, `ip_hash`,
+//! `file_count`, `is_staff`; absent values are `nil`). It returns:
+//!
+//! - `nil` — accept the post unchanged
+//! - `{ action = "accept", tags = { "link" } }` — accept and tag it
+//! - `{ action = "rewrite", body = "...", tags = { ... } }` — replace the body
+//! - `{ action = "reject", reason = "..." }` — refuse the post
+//!
+//! i.e. a `FilterVerdict` as a table. `tags` is optional.
+//!
+//! ```lua
+//! function on_post(post)
+//!   if post.body:find("https?://") and not post.is_staff then
+//!     return { action = "accept", tags = { "link" } }
+//!   end
+//! end
+//! ```
+//!
+//! # Sandbox
+//! - Only the `string`, `table`, `math` and `utf8` libraries are loaded, and
+//!   `load`, `loadfile` and `dofile` are removed: no `io`, `os`, `require`
+//!   or `debug`.
+//! - Each call runs in a fresh Lua state: globals do not survive between calls.
+//! - Each call may run for `LuaLimits::timeout` of wall time and allocate up
+//!   to `LuaLimits::memory_bytes`; going over raises a Lua error.
+//! - Calls run on the blocking thread pool.
+//!
+//! A Lua error, a limit hit or a malformed return value is returned as an
+//! error, which `PostService` logs and otherwise ignores.
+
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use domains::errors::DomainError;
+use domains::ports::{FilterVerdict, PostFilter, PostSubmission};
+use mlua::{Function, HookTriggers, Lua, LuaOptions, LuaSerdeExt, StdLib, Value, VmState};
+
+const ON_POST: &str = "on_post";
+
+/// How many VM instructions run between time budget checks.
+const INSTRUCTIONS_PER_CHECK: u32 = 1_000;
+
+/// Per-call resource limits.
+#[derive(Debug, Clone, Copy)]
+pub struct LuaLimits {
+    /// Wall time per call.
+    pub timeout:      Duration,
+    /// Most memory the Lua state may allocate.
+    pub memory_bytes: usize,
+}
+
+/// One loaded script.
+pub struct LuaScript {
+    name:   String,
+    source: String,
+    limits: LuaLimits,
+}
+
+/// Load every `*.lua` file in `dir`, in file name order.
+///
+/// A file that fails to run or does not define `on_post` is logged and
+/// skipped. Returns an error only if `dir` cannot be read.
+pub fn load_scripts(dir: &Path, limits: LuaLimits) -> Result<Vec<Arc<LuaScript>>, DomainError> {
+    let mut paths: Vec<_> = std::fs::read_dir(dir)
+        .map_err(|e| DomainError::internal(format!("reading script directory {}: {e}", dir.display())))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| p.extension().and_then(|e| e.to_str()) == Some("lua"))
+        .collect();
+    paths.sort();
+
+    let mut scripts = Vec::new();
+    for path in paths {
+        let name = path.file_stem().map_or_else(String::new, |s| s.to_string_lossy().into_owned());
+        let loaded = std::fs::read_to_string(&path)
+            .map_err(|e| DomainError::internal(e.to_string()))
+            .and_then(|source| LuaScript::new(name, source, limits));
+        match loaded {
+            Ok(script) => {
+                tracing::info!(script = %script.name, "lua script loaded");
+                scripts.push(Arc::new(script));
+            }
+            Err(e) => tracing::error!(path = %path.display(), error = %e, "lua script failed to load; skipped"),
+        }
+    }
+    Ok(scripts)
+}
+
+impl LuaScript {
+    /// Run `source` once and check that it defines `on_post`.
+    pub fn new(name: impl Into<String>, source: impl Into<String>, limits: LuaLimits) -> Result<Self, DomainError> {
+        let script = Self { name: name.into(), source: source.into(), limits };
+        script.instantiate()?;
+        Ok(script)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Run `on_post` on `post`.
+    pub fn filter_post(&self, post: &PostSubmission) -> Result<FilterVerdict, DomainError> {
+        let (lua, on_post) = self.instantiate()?;
+        let options = mlua::serde::ser::Options::new().serialize_none_to_null(false);
+        let input = lua.to_value_with(post, options).map_err(|e| self.error(e))?;
+        match on_post.call::<Value>(input).map_err(|e| self.error(e))? {
+            Value::Nil => Ok(FilterVerdict::accept()),
+            verdict => lua
+                .from_value(verdict)
+                .map_err(|e| DomainError::internal(format!("script {}: invalid verdict: {e}", self.name))),
+        }
+    }
+
+    /// A fresh sandboxed state with the script loaded and its budget started.
+    fn instantiate(&self) -> Result<(Lua, Function), DomainError> {
+        let libs = StdLib::STRING | StdLib::TABLE | StdLib::MATH | StdLib::UTF8;
+        let lua = Lua::new_with(libs, LuaOptions::default()).map_err(|e| self.error(e))?;
+        lua.set_memory_limit(self.limits.memory_bytes).map_err(|e| self.error(e))?;
+        let globals = lua.globals();
+        for unsafe_global in ["load", "loadfile", "dofile"] {
+            globals.set(unsafe_global, Value::Nil).map_err(|e| self.error(e))?;
+        }
+
+        let timeout = self.limits.timeout;
+        let started = Instant::now();
+        lua.set_hook(HookTriggers::new().every_nth_instruction(INSTRUCTIONS_PER_CHECK), move |_, _| {
+            if started.elapsed() > timeout {
+                return Err(mlua::Error::runtime("time budget exceeded"));
+            }
+            Ok(VmState::Continue)
+        })
+        .map_err(|e| self.error(e))?;
+
+        lua.load(&self.source).set_name(&self.name).exec().map_err(|e| self.error(e))?;
+        let on_post = match globals.get::<Value>(ON_POST).map_err(|e| self.error(e))? {
+            Value::Function(f) => f,
+            _ => return Err(DomainError::internal(format!("script {}: does not define `{ON_POST}`", self.name))),
+        };
+        Ok((lua, on_post))
+    }
+
+    fn error(&self, e: mlua::Error) -> DomainError {
+        DomainError::internal(format!("script {}: {e}", self.name))
+    }
+}
+
+/// `PostFilter` backed by a script's `on_post`.
+#[derive(Clone)]
+pub struct LuaPostFilter(pub Arc<LuaScript>);
+
+#[async_trait]
+impl PostFilter for LuaPostFilter {
+    fn name(&self) -> &str {
+        self.0.name()
+    }
+
+    async fn check(&self, post: &PostSubmission) -> Result<FilterVerdict, DomainError> {
+        let script = self.0.clone();
+        let post = post.clone();
+        tokio::task::spawn_blocking(move || script.filter_post(&post))
+            .await
+            .map_err(|e| DomainError::internal(format!("script task failed: {e}")))?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use domains::models::{BoardId, IpHash};
+
+    const LIMITS: LuaLimits = LuaLimits { timeout: Duration::from_millis(200), memory_bytes: 4 << 20 };
+
+    fn submission() -> PostSubmission {
+        PostSubmission {
+            board_id:   BoardId::new(),
+            thread_id:  None,
+            body:       "see https://example.com".to_owned(),
+            name:       None,
+            email:      None,
+            ip_hash:    IpHash::new("abc"),
+            file_count: 0,
+            is_staff:   false,
+        }
+    }
+
+    fn run(source: &str) -> Result<FilterVerdict, DomainError> {
+        LuaScript::new("test", source, LIMITS)?.filter_post(&submission())
+    }
+
+    #[test]
+    fn nil_accepts_the_post() {
+        assert_eq!(run("function on_post(post) end").unwrap(), FilterVerdict::accept());
+    }
+
+    #[test]
+    fn verdict_tables_are_read() {
+        let tag = r#"function on_post(post)
+            if post.body:find("https?://") and post.name == nil then
+                return { action = "accept", tags = { "link" } }
+            end
+        end"#;
+        assert_eq!(run(tag).unwrap(), FilterVerdict::Accept { tags: vec!["link".to_owned()] });
+
+        let rewrite = r#"function on_post(post)
+            return { action = "rewrite", body = post.body:upper() }
+        end"#;
+        assert_eq!(run(rewrite).unwrap(), FilterVerdict::Rewrite {
+            body: "SEE HTTPS://EXAMPLE.COM".to_owned(),
+            tags: vec![],
+        });
+
+        let reject = r#"function on_post(post) return { action = "reject", reason = "no" } end"#;
+        assert_eq!(run(reject).unwrap(), FilterVerdict::Reject { reason: "no".to_owned() });
+    }
+
+    #[test]
+    fn malformed_verdict_is_an_error() {
+        assert!(run(r#"function on_post(post) return { action = "explode" } end"#).is_err());
+        assert!(run(r#"function on_post(post) return 42 end"#).is_err());
+    }
+
+    #[test]
+    fn scripts_without_on_post_are_refused() {
+        assert!(LuaScript::new("test", "x = 1", LIMITS).is_err());
+        assert!(LuaScript::new("test", "function on_post(", LIMITS).is_err());
+    }
+
+    #[test]
+    fn time_and_memory_budgets_are_enforced() {
+        assert!(run("function on_post(post) while true do end end").is_err());
+        let hog = r#"function on_post(post)
+            local t = {}
+            for i = 1, 1e9 do t[i] = string.rep("x", 1024) .. i end
+        end"#;
+        assert!(run(hog).is_err());
+    }
+
+    #[test]
+    fn sandbox_has_no_io_or_loaders() {
+        let probe = r#"function on_post(post)
+            if io or os or require or debug or load or loadfile or dofile then
+                return { action = "reject", reason = "escaped" }
+            end
+        end"#;
+        assert_eq!(run(probe).unwrap(), FilterVerdict::accept());
+    }
+
+    #[test]
+    fn globals_do_not_survive_between_calls() {
+        let script = LuaScript::new(
+            "test",
+            r#"function on_post(post)
+                seen = (seen or 0) + 1
+                return { action = "accept", tags = { tostring(seen) } }
+            end"#,
+            LIMITS,
+        )
+        .unwrap();
+        for _ in 0..2 {
+            assert_eq!(script.filter_post(&submission()).unwrap(), FilterVerdict::Accept { tags: vec!["1".to_owned()] });
+        }
+    }
+
+    #[tokio::test]
+    async fn post_filter_runs_the_script() {
+        let script = Arc::new(LuaScript::new("test", "function on_post(post) end", LIMITS).unwrap());
+        assert_eq!(LuaPostFilter(script).check(&submission()).await.unwrap(), FilterVerdict::accept());
+    }
+
+    #[test]
+    fn load_scripts_skips_broken_files() {
+        let dir = std::env::temp_dir().join(format!("rb-lua-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("b.lua"), "function on_post(post) end").unwrap();
+        std::fs::write(dir.join("a.lua"), "function on_post(post) end").unwrap();
+        std::fs::write(dir.join("broken.lua"), "not lua").unwrap();
+        std::fs::write(dir.join("notes.txt"), "ignored").unwrap();
+
+        let names: Vec<_> = load_scripts(&dir, LIMITS).unwrap().iter().map(|s| s.name().to_owned()).collect();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(names, ["a", "b"]);
+    }
+}
@@ -0,0 +1,27 @@
+//! Operator plugins: post filters and event hooks loaded at startup.
+//!
+//! - `WasmPlugin` (`wasm.rs`) — sandboxed WebAssembly modules run with
+//!   wasmtime (`plugins-wasm` feature), wrapped as `WasmPostFilter` and
+//!   `WasmEventSubscriber`
+//! - `LuaScript` (`lua.rs`) — sandboxed Lua scripts run with mlua
+//!   (`plugins-lua` feature), wrapped as `LuaPostFilter`
+//! - `NotifyDiscord` (`notify_discord.rs`) — the built-in `rb-notify-discord`
+//!   plugin announcing new threads on Discord and IRC (`notify-discord`
+//!   feature)
+//!
+//! Without the first two features no plugins are loaded and posting is
+//! unfiltered.
+
+#[cfg(feature = "plugins-lua")]
+pub mod lua;
+#[cfg(feature = "notify-discord")]
+pub mod notify_discord;
+#[cfg(feature = "plugins-wasm")]
+pub mod wasm;
+
+#[cfg(feature = "plugins-lua")]
+pub use lua::{load_scripts, LuaLimits, LuaPostFilter, LuaScript};
+#[cfg(feature = "notify-discord")]
+pub use notify_discord::{NotifyConfig, NotifyDiscord};
+#[cfg(feature = "plugins-wasm")]
+pub use wasm::{load_dir, WasmEventSubscriber, WasmLimits, WasmPlugin, WasmPostFilter};
@@ -0,0 +1,566 @@
+//! `rb-notify-discord`: new-thread announcements on Discord and IRC.
+//!
+//! `NotifyDiscord` is an `EventSubscriber`. On `ThreadCreated` it loads the
+//! board, the OP and its first attachment, and posts an announcement to every
+//! target whose `boards` list names the board (or is empty):
+//!
+//! - **Discord** — an embed through a channel webhook: the subject as a link
+//!   to the thread, the OP snippet, the thumbnail and `/{board}/`. Mentions
+//!   in posts never ping anyone.
+//! - **IRC** — one `PRIVMSG`: `[/{board}/] subject — snippet <url>`. Each
+//!   announcement connects, registers `nick`, joins the channel, sends and
+//!   quits. Plain-text connections only; use a local TLS tunnel for servers
+//!   that require TLS.
+//!
+//! The subject is the first line of the OP (posts have no subject field),
+//! the snippet the rest of it, cut to `snippet_chars`. Spoilered thumbnails
+//! are not shown.
+//!
+//! Configured under `[plugins.rb-notify-discord]`:
+//!
+//! ```toml
+//! [plugins.rb-notify-discord]
+//! site_url = "https://board.example.com"
+//!
+//! [[plugins.rb-notify-discord.targets]]
+//! boards = ["g", "tech"]
+//! discord_webhook = "https://discord.com/api/webhooks/<id>/<token>"
+//!
+//! [[plugins.rb-notify-discord.targets]]
+//! irc = { server = "irc.libera.chat:6667", nick = "rb-notify", channel = "#example" }
+//! ```
+//!
+//! A failed target is logged by the event bus and does not stop the others.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use domains::errors::DomainError;
+use domains::models::{Attachment, Board, Post, Thread};
+use domains::ports::{BoardRepository, DomainEvent, EventSubscriber, PostRepository};
+use serde::Deserialize;
+use serde_json::json;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+
+use crate::media::cdn::MediaUrls;
+
+/// Plugin name: its `[plugins.<name>]` section and its name in logs.
+pub const NAME: &str = "rb-notify-discord";
+
+/// Longest subject, in characters.
+const SUBJECT_CHARS: usize = 100;
+
+/// Longest IRC message text, in bytes; a whole line may be 512.
+const IRC_TEXT_BYTES: usize = 400;
+
+/// `[plugins.rb-notify-discord]`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct NotifyConfig {
+    /// Public base URL of the site, e.g. `https://board.example.com`; thread
+    /// links and relative media URLs are built on it.
+    pub site_url:      String,
+    /// Longest OP snippet, in characters. Default: 200.
+    #[serde(default = "default_snippet_chars")]
+    pub snippet_chars: usize,
+    /// Time limit of one announcement to one target, in seconds. Default: 10.
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs:  u64,
+    #[serde(default)]
+    pub targets:       Vec<NotifyTarget>,
+}
+
+fn default_snippet_chars() -> usize {
+    200
+}
+
+fn default_timeout_secs() -> u64 {
+    10
+}
+
+/// Where announcements of some boards go.
+#[derive(Debug, Clone, Deserialize)]
+pub struct NotifyTarget {
+    /// Board slugs announced here; all boards when empty.
+    #[serde(default)]
+    pub boards:          Vec<String>,
+    /// Discord channel webhook URL.
+    #[serde(default)]
+    pub discord_webhook: Option<String>,
+    #[serde(default)]
+    pub irc:             Option<IrcTarget>,
+}
+
+/// An IRC channel.
+#[derive(Debug, Clone, Deserialize)]
+pub struct IrcTarget {
+    /// `host:port` of a plain-text IRC server.
+    pub server:  String,
+    pub nick:    String,
+    /// Channel, with its `#`.
+    pub channel: String,
+}
+
+/// A new thread, ready to announce.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Announcement {
+    pub board:     String,
+    pub subject:   String,
+    pub snippet:   String,
+    pub url:       String,
+    pub thumbnail: Option<String>,
+}
+
+/// The `rb-notify-discord` event subscriber.
+pub struct NotifyDiscord {
+    config: NotifyConfig,
+    boards: Arc<dyn BoardRepository>,
+    posts:  Arc<dyn PostRepository>,
+    urls:   MediaUrls,
+    client: reqwest::Client,
+}
+
+impl NotifyDiscord {
+    /// Fails on a target with neither `discord_webhook` nor `irc`, or an IRC
+    /// target with a malformed nick or channel.
+    pub fn new(
+        config: NotifyConfig,
+        boards: Arc<dyn BoardRepository>,
+        posts: Arc<dyn PostRepository>,
+        urls: MediaUrls,
+    ) -> Result<Self, DomainError> {
+        for (n, target) in config.targets.iter().enumerate() {
+            if target.discord_webhook.is_none() && target.irc.is_none() {
+                return Err(DomainError::internal(format!("{NAME}: target {n} has neither discord_webhook nor irc")));
+            }
+            if let Some(irc) = &target.irc {
+                let bad = |s: &str| s.is_empty() || s.chars().any(|c| c.is_whitespace() || c.is_control() || c == ',');
+                if bad(&irc.nick) || bad(&irc.channel) || !irc.channel.starts_with(['#', '&']) {
+                    return Err(DomainError::internal(format!("{NAME}: target {n}: malformed irc nick or channel")));
+                }
+            }
+        }
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_secs))
+            .build()
+            .map_err(|e| DomainError::internal(format!("{NAME}: http client: {e}")))?;
+        Ok(Self { config, boards, posts, urls, client })
+    }
+
+    /// Whether any target would announce a thread on some board.
+    pub fn has_targets(&self) -> bool {
+        !self.config.targets.is_empty()
+    }
+
+    /// Announce `thread` to the targets of its board.
+    async fn announce_thread(&self, thread: &Thread) -> Result<(), DomainError> {
+        let board = self.boards.find_by_id(thread.board_id).await?;
+        let targets: Vec<&NotifyTarget> = self
+            .config
+            .targets
+            .iter()
+            .filter(|t| t.boards.is_empty() || t.boards.iter().any(|b| b.eq_ignore_ascii_case(board.slug.as_str())))
+            .collect();
+        if targets.is_empty() {
+            return Ok(());
+        }
+
+        let op = match thread.op_post_id {
+            Some(id) => Some(self.posts.find_by_id(id).await?),
+            None => None,
+        };
+        let attachment = match &op {
+            Some(op) => self.posts.find_attachments_by_post_ids(&[op.id]).await?.remove(&op.id).unwrap_or_default(),
+            None => Vec::new(),
+        };
+        let announcement = self.announcement(&board, thread, op.as_ref(), attachment.first());
+
+        let timeout = Duration::from_secs(self.config.timeout_secs);
+        let mut failures = Vec::new();
+        for target in targets {
+            if let Some(webhook) = &target.discord_webhook {
+                if let Err(e) = self.send_discord(webhook, &announcement).await {
+                    failures.push(format!("discord: {e}"));
+                }
+            }
+            if let Some(irc) = &target.irc {
+                if let Err(e) = send_irc(irc, &irc_text(&announcement), timeout).await {
+                    failures.push(format!("irc {}: {e}", irc.server));
+                }
+            }
+        }
+        match failures.is_empty() {
+            true => Ok(()),
+            false => Err(DomainError::internal(failures.join("; "))),
+        }
+    }
+
+    /// The announcement of `thread`, opened by `op` with `attachment` first.
+    pub fn announcement(
+        &self,
+        board: &Board,
+        thread: &Thread,
+        op: Option<&Post>,
+        attachment: Option<&Attachment>,
+    ) -> Announcement {
+        let site = self.config.site_url.trim_end_matches('/');
+        let body = op.map_or("", |op| op.body.as_str());
+        let mut lines = body.lines().map(str::trim).filter(|l| !l.is_empty());
+        let subject = lines.next().map_or_else(|| "New thread".to_owned(), |l| truncate(l, SUBJECT_CHARS));
+        let rest: Vec<&str> = lines.collect();
+        let thumbnail = attachment
+            .filter(|a| !a.spoiler)
+            .and_then(|a| a.thumbnail_key.as_ref())
+            .map(|key| match self.urls.url(key) {
+                url if url.starts_with('/') => format!("{site}{url}"),
+                url => url,
+            });
+        Announcement {
+            board: board.slug.as_str().to_owned(),
+            subject,
+            snippet: truncate(&rest.join(" "), self.config.snippet_chars),
+            url: format!("{site}/board/{}/thread/{}", board.slug.as_str(), thread.id.0),
+            thumbnail,
+        }
+    }
+
+    async fn send_discord(&self, webhook: &str, announcement: &Announcement) -> Result<(), DomainError> {
+        let response = self
+            .client
+            .post(webhook)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(discord_payload(announcement).to_string())
+            .send()
+            .await
+            .map_err(|e| DomainError::internal(e.without_url().to_string()))?;
+        match response.status() {
+            status if status.is_success() => Ok(()),
+            status => Err(DomainError::internal(format!("webhook answered {status}"))),
+        }
+    }
+}
+
+#[async_trait]
+impl EventSubscriber for NotifyDiscord {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    async fn handle(&self, event: &DomainEvent) -> Result<(), DomainError> {
+        match event {
+            DomainEvent::ThreadCreated { thread } => self.announce_thread(thread).await,
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Discord webhook body for `announcement`.
+pub fn discord_payload(announcement: &Announcement) -> serde_json::Value {
+    let mut embed = json!({
+        "title":       announcement.subject,
+        "url":         announcement.url,
+        "description": announcement.snippet,
+        "footer":      { "text": format!("/{}/", announcement.board) },
+    });
+    if let Some(thumbnail) = &announcement.thumbnail {
+        embed["thumbnail"] = json!({ "url": thumbnail });
+    }
+    json!({ "embeds": [embed], "allowed_mentions": { "parse": [] } })
+}
+
+/// IRC message text for `announcement`: one line, at most `IRC_TEXT_BYTES`.
+pub fn irc_text(announcement: &Announcement) -> String {
+    let clean = |s: &str| s.chars().map(|c| if c.is_control() { ' ' } else { c }).collect::<String>();
+    let head = format!("[/{}/] {}", clean(&announcement.board), clean(&announcement.subject));
+    let url = format!(" <{}>", clean(&announcement.url));
+    let mut text = match announcement.snippet.is_empty() {
+        true => head,
+        false => format!("{head} — {}", clean(&announcement.snippet)),
+    };
+    let budget = IRC_TEXT_BYTES.saturating_sub(url.len());
+    if text.len() > budget {
+        let mut cut = budget.saturating_sub('…'.len_utf8());
+        while !text.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        text.truncate(cut);
+        text.push('…');
+    }
+    text + &url
+}
+
+/// Send `text` to `target` in one connection: register, join, say, quit.
+async fn send_irc(target: &IrcTarget, text: &str, timeout: Duration) -> Result<(), DomainError> {
+    let session = async {
+        let (read, mut write) = TcpStream::connect(&target.server).await?.into_split();
+        let mut lines = BufReader::new(read).lines();
+        let nick = &target.nick;
+        write.write_all(format!("NICK {nick}\r\nUSER {nick} 0 * :{NAME}\r\n").as_bytes()).await?;
+        loop {
+            let Some(line) = lines.next_line().await? else {
+                return Err(std::io::Error::other("server closed the connection"));
+            };
+            if let Some(token) = line.strip_prefix("PING ") {
+                write.write_all(format!("PONG {token}\r\n").as_bytes()).await?;
+                continue;
+            }
+            if line.starts_with("ERROR") {
+                return Err(std::io::Error::other(line));
+            }
+            match line.split(' ').nth(1) {
+                Some("001") => break,
+                Some(code @ ("432" | "433" | "465")) => {
+                    return Err(std::io::Error::other(format!("registration refused ({code})")));
+                }
+                _ => {}
+            }
+        }
+        let channel = &target.channel;
+        write.write_all(format!("JOIN {channel}\r\nPRIVMSG {channel} :{text}\r\nQUIT\r\n").as_bytes()).await?;
+        write.shutdown().await?;
+        // Drain until the server closes, so the message is not cut off by a reset.
+        while lines.next_line().await?.is_some() {}
+        Ok(())
+    };
+    match tokio::time::timeout(timeout, session).await {
+        Ok(result) => result.map_err(|e| DomainError::internal(e.to_string())),
+        Err(_) => Err(DomainError::internal("timed out")),
+    }
+}
+
+/// `s` cut to `max` characters, with an ellipsis when cut.
+fn truncate(s: &str, max: usize) -> String {
+    match s.char_indices().nth(max) {
+        Some(_) => {
+            let kept: String = s.chars().take(max.saturating_sub(1)).collect();
+            format!("{}…", kept.trim_end())
+        }
+        None => s.to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use domains::models::{BoardId, ContentHash, IpHash, MediaKey, PostId, SiteId, Slug, ThreadId};
+    use domains::ports::{MockBoardRepository, MockPostRepository};
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+    use tokio::net::TcpListener;
+
+    fn config(targets: Vec<NotifyTarget>) -> NotifyConfig {
+        NotifyConfig { site_url: "https://board.example/".to_owned(), snippet_chars: 20, timeout_secs: 5, targets }
+    }
+
+    fn board(slug: &str) -> Board {
+        Board {
+            id:         BoardId::new(),
+            site_id:    SiteId::DEFAULT,
+            slug:       Slug::new(slug).unwrap(),
+            title:      "Technology".to_owned(),
+            rules:      String::new(),
+            created_at: Utc::now(),
+        }
+    }
+
+    fn thread(board_id: BoardId) -> Thread {
+        Thread {
+            id: ThreadId::new(), board_id, op_post_id: Some(PostId::new()), reply_count: 0, bumped_at: Utc::now(),
+            sticky: false, closed: false, cycle: false, created_at: Utc::now(),
+        }
+    }
+
+    fn op(thread: &Thread, body: &str) -> Post {
+        Post {
+            id: thread.op_post_id.unwrap(), thread_id: thread.id, body: body.to_owned(), ip_hash: IpHash::new("h"),
+            name: None, tripcode: None, email: None, created_at: Utc::now(), post_number: 1, pinned: false,
+            tags: vec![], commands: vec![], subject: None, capcode: None, deleted: None, edited_at: None,
+        }
+    }
+
+    fn attachment(post_id: PostId, spoiler: bool) -> Attachment {
+        Attachment {
+            id: uuid::Uuid::new_v4(), post_id, filename: "cat.png".to_owned(), mime: "image/png".to_owned(),
+            hash: ContentHash::new("c"), size_kb: 1, media_key: MediaKey::new("a/cat.png"),
+            thumbnail_key: Some(MediaKey::new("a/thumb.png")), spoiler, duration_ms: None, page_count: None,
+            md5: None, phash: None, meta: None,
+        }
+    }
+
+    fn plugin(config: NotifyConfig, boards: MockBoardRepository, posts: MockPostRepository) -> NotifyDiscord {
+        NotifyDiscord::new(config, Arc::new(boards), Arc::new(posts), MediaUrls::default()).unwrap()
+    }
+
+    fn irc_target(server: &str, boards: &[&str]) -> NotifyTarget {
+        NotifyTarget {
+            boards:          boards.iter().map(|b| b.to_string()).collect(),
+            discord_webhook: None,
+            irc:             Some(IrcTarget {
+                server:  server.to_owned(),
+                nick:    "rb-notify".to_owned(),
+                channel: "#board".to_owned(),
+            }),
+        }
+    }
+
+    /// An IRC server welcoming every client and recording the lines it sends.
+    async fn irc_server() -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let log = seen.clone();
+        tokio::spawn(async move {
+            while let Ok((socket, _)) = listener.accept().await {
+                let (read, mut write) = socket.into_split();
+                let mut lines = BufReader::new(read).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    if line.starts_with("USER ") {
+                        write.write_all(b"PING :abc\r\n:irc.test 001 rb-notify :Welcome\r\n").await.unwrap();
+                    }
+                    let quit = line == "QUIT";
+                    log.lock().unwrap().push(line);
+                    if quit {
+                        break;
+                    }
+                }
+            }
+        });
+        (addr, seen)
+    }
+
+    #[test]
+    fn announcement_splits_subject_and_snippet() {
+        let notify = plugin(config(vec![]), MockBoardRepository::new(), MockPostRepository::new());
+        let board = board("g");
+        let thread = thread(board.id);
+        let op = op(&thread, "\n  Rust thread  \nwhat are you working on this week, anon?\n\n>nothing");
+        let a = notify.announcement(&board, &thread, Some(&op), Some(&attachment(op.id, false)));
+        assert_eq!(a.subject, "Rust thread");
+        assert_eq!(a.snippet, "what are you workin…");
+        assert_eq!(a.url, format!("https://board.example/board/g/thread/{}", thread.id.0));
+        assert_eq!(a.thumbnail.as_deref(), Some("https://board.example/media/a/thumb.png"));
+
+        let spoiler = notify.announcement(&board, &thread, Some(&op), Some(&attachment(op.id, true)));
+        assert_eq!(spoiler.thumbnail, None);
+        let empty = notify.announcement(&board, &thread, None, None);
+        assert_eq!((empty.subject.as_str(), empty.snippet.as_str()), ("New thread", ""));
+    }
+
+    #[test]
+    fn discord_payload_is_an_embed_without_mentions() {
+        let a = Announcement {
+            board:     "g".to_owned(),
+            subject:   "@everyone look".to_owned(),
+            snippet:   "hi".to_owned(),
+            url:       "https://board.example/board/g/thread/1".to_owned(),
+            thumbnail: Some("https://cdn.example/t.png".to_owned()),
+        };
+        let payload = discord_payload(&a);
+        assert_eq!(payload["allowed_mentions"]["parse"], json!([]));
+        let embed = &payload["embeds"][0];
+        assert_eq!(embed["title"], "@everyone look");
+        assert_eq!(embed["url"], a.url);
+        assert_eq!(embed["thumbnail"]["url"], "https://cdn.example/t.png");
+        assert_eq!(embed["footer"]["text"], "/g/");
+    }
+
+    #[test]
+    fn irc_text_is_one_bounded_line() {
+        let a = Announcement {
+            board:     "g".to_owned(),
+            subject:   "hi\r\nQUIT".to_owned(),
+            snippet:   "é".repeat(400),
+            url:       "https://board.example/board/g/thread/1".to_owned(),
+            thumbnail: None,
+        };
+        let text = irc_text(&a);
+        assert!(!text.contains(['\r', '\n']));
+        assert!(text.starts_with("[/g/] hi  QUIT — é"));
+        assert!(text.ends_with("… <https://board.example/board/g/thread/1>"));
+        assert!(text.len() <= IRC_TEXT_BYTES);
+    }
+
+    #[test]
+    fn config_is_read_from_a_plugin_section() {
+        let config: NotifyConfig = serde_json::from_value(json!({
+            "site_url": "https://board.example",
+            "targets": [{ "boards": ["g"], "discord_webhook": "https://discord.test/api/webhooks/1/x" }],
+        }))
+        .unwrap();
+        assert_eq!((config.snippet_chars, config.timeout_secs), (200, 10));
+        assert_eq!(config.targets[0].boards, ["g"]);
+
+        let bare = NotifyTarget { boards: vec![], discord_webhook: None, irc: None };
+        let refused = NotifyDiscord::new(
+            self::config(vec![bare]),
+            Arc::new(MockBoardRepository::new()),
+            Arc::new(MockPostRepository::new()),
+            MediaUrls::default(),
+        );
+        assert!(refused.is_err());
+        let mut bad_channel = irc_target("127.0.0.1:1", &[]);
+        bad_channel.irc.as_mut().unwrap().channel = "board".to_owned();
+        assert!(NotifyDiscord::new(
+            self::config(vec![bad_channel]),
+            Arc::new(MockBoardRepository::new()),
+            Arc::new(MockPostRepository::new()),
+            MediaUrls::default(),
+        )
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn new_threads_are_announced_on_irc() {
+        let (server, seen) = irc_server().await;
+        let board = board("g");
+        let thread = thread(board.id);
+        let post = op(&thread, "Rust thread\nwhat are you working on");
+
+        let mut boards = MockBoardRepository::new();
+        let found = board.clone();
+        boards.expect_find_by_id().returning(move |_| Ok(found.clone()));
+        let mut posts = MockPostRepository::new();
+        posts.expect_find_by_id().returning(move |_| Ok(post.clone()));
+        posts.expect_find_attachments_by_post_ids().returning(|_| Ok(HashMap::new()));
+        let notify = plugin(config(vec![irc_target(&server, &["G"])]), boards, posts);
+
+        notify.handle(&DomainEvent::ThreadCreated { thread: thread.clone() }).await.unwrap();
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen[0], "NICK rb-notify");
+        assert!(seen.contains(&"PONG :abc".to_owned()));
+        assert!(seen.contains(&"JOIN #board".to_owned()));
+        let message = seen.iter().find(|l| l.starts_with("PRIVMSG")).unwrap();
+        assert!(message.starts_with("PRIVMSG #board :[/g/] Rust thread — what are you"), "{message}");
+        assert!(message.ends_with(&format!("/board/g/thread/{}>", thread.id.0)));
+    }
+
+    #[tokio::test]
+    async fn other_boards_are_not_announced() {
+        let found = board("b");
+        let mut boards = MockBoardRepository::new();
+        boards.expect_find_by_id().returning(move |_| Ok(found.clone()));
+        // No post lookups expected: the mock panics on any call.
+        let notify = plugin(config(vec![irc_target("127.0.0.1:1", &["g"])]), boards, MockPostRepository::new());
+
+        let event = DomainEvent::ThreadCreated { thread: thread(BoardId::new()) };
+        notify.handle(&event).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn unreachable_targets_are_errors() {
+        let found = board("g");
+        let thread = thread(found.id);
+        let post = op(&thread, "hello");
+        let mut boards = MockBoardRepository::new();
+        boards.expect_find_by_id().returning(move |_| Ok(found.clone()));
+        let mut posts = MockPostRepository::new();
+        posts.expect_find_by_id().returning(move |_| Ok(post.clone()));
+        posts.expect_find_attachments_by_post_ids().returning(|_| Ok(HashMap::new()));
+        let notify = plugin(config(vec![irc_target("127.0.0.1:1", &[])]), boards, posts);
+
+        let err = notify.handle(&DomainEvent::ThreadCreated { thread }).await.unwrap_err();
+        assert!(err.to_string().contains("irc 127.0.0.1:1"), "{err}");
+    }
+}
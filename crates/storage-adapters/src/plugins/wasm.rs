@@ -0,0 +1,325 @@
+//! WebAssembly plugins run with wasmtime.
+//!
+//! Every `*.wasm` (or `*.wat`) file in `WASM_PLUGIN_DIR` is one plugin. A
+//! plugin is a core WebAssembly module with **no imports** — no WASI, no host
+//! functions — so it can compute but not touch files, the network or the
+//! clock. It exports:
+//!
+//! | Export | Signature | Purpose |
+//! |--------|-----------|---------|
+//! | `memory` | memory | Input and output buffers |
+//! | `rb_alloc` | `(len: i32) -> i32` | Return a buffer of `len` bytes for the input |
+//! | `rb_filter_post` | `(ptr: i32, len: i32) -> i64` | Optional. Input: `PostSubmission` JSON. Returns `ptr << 32 \| len` of a `FilterVerdict` JSON |
+//! | `rb_on_event` | `(ptr: i32, len: i32)` | Optional. Input: `DomainEvent` JSON |
+//!
+//! A plugin exporting `rb_filter_post` becomes a `WasmPostFilter`; one
+//! exporting `rb_on_event` is subscribed to the event bus as a
+//! `WasmEventSubscriber`. A plugin may do both.
+//!
+//! # Sandbox
+//! - Each call runs in a fresh instance: no state survives between calls.
+//! - Each call gets `WasmLimits::fuel` units of fuel (roughly one per
+//!   instruction); running out traps.
+//! - Linear memory is capped at `WasmLimits::memory_bytes`.
+//! - Calls run on the blocking thread pool.
+//!
+//! A trap, a limit hit or malformed output is returned as an error, which
+//! `PostService` and the event bus log and otherwise ignore.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use domains::errors::DomainError;
+use domains::ports::{DomainEvent, EventSubscriber, FilterVerdict, PostFilter, PostSubmission};
+use wasmtime::{Config, Engine, Instance, Memory, Module, Store, StoreLimits, StoreLimitsBuilder};
+
+const ALLOC: &str = "rb_alloc";
+const FILTER_POST: &str = "rb_filter_post";
+const ON_EVENT: &str = "rb_on_event";
+
+/// Per-call resource limits.
+#[derive(Debug, Clone, Copy)]
+pub struct WasmLimits {
+    /// Fuel per call; exhausting it traps.
+    pub fuel:         u64,
+    /// Largest linear memory a plugin may grow to.
+    pub memory_bytes: usize,
+}
+
+/// One compiled plugin module.
+pub struct WasmPlugin {
+    name:   String,
+    engine: Engine,
+    module: Module,
+    limits: WasmLimits,
+}
+
+/// An engine with fuel metering, shared by every plugin.
+pub fn engine() -> Result<Engine, DomainError> {
+    let mut config = Config::new();
+    config.consume_fuel(true);
+    Engine::new(&config).map_err(|e| DomainError::internal(format!("wasm engine: {e}")))
+}
+
+/// Load every `*.wasm` and `*.wat` file in `dir`, in file name order.
+///
+/// A file that fails to compile or does not follow the plugin interface is
+/// logged and skipped. Returns an error only if `dir` cannot be read.
+pub fn load_dir(dir: &Path, limits: WasmLimits) -> Result<Vec<Arc<WasmPlugin>>, DomainError> {
+    let engine = engine()?;
+    let mut paths: Vec<_> = std::fs::read_dir(dir)
+        .map_err(|e| DomainError::internal(format!("reading plugin directory {}: {e}", dir.display())))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| matches!(p.extension().and_then(|e| e.to_str()), Some("wasm" | "wat")))
+        .collect();
+    paths.sort();
+
+    let mut plugins = Vec::new();
+    for path in paths {
+        let name = path.file_stem().map_or_else(String::new, |s| s.to_string_lossy().into_owned());
+        let loaded = std::fs::read(&path)
+            .map_err(|e| DomainError::internal(e.to_string()))
+            .and_then(|bytes| WasmPlugin::new(&engine, name, &bytes, limits));
+        match loaded {
+            Ok(plugin) => {
+                tracing::info!(
+                    plugin = %plugin.name,
+                    filter = plugin.filters_posts(),
+                    events = plugin.handles_events(),
+                    "wasm plugin loaded"
+                );
+                plugins.push(Arc::new(plugin));
+            }
+            Err(e) => tracing::error!(path = %path.display(), error = %e, "wasm plugin failed to load; skipped"),
+        }
+    }
+    Ok(plugins)
+}
+
+impl WasmPlugin {
+    /// Compile `bytes` (binary or text format) and check its interface.
+    pub fn new(engine: &Engine, name: impl Into<String>, bytes: &[u8], limits: WasmLimits) -> Result<Self, DomainError> {
+        let module = Module::new(engine, bytes).map_err(|e| DomainError::internal(format!("compile: {e}")))?;
+        if let Some(import) = module.imports().next() {
+            return Err(DomainError::internal(format!(
+                "plugins may not import anything (imports {}::{})",
+                import.module(),
+                import.name()
+            )));
+        }
+        let plugin = Self { name: name.into(), engine: engine.clone(), module, limits };
+        for required in ["memory", ALLOC] {
+            if plugin.module.get_export(required).is_none() {
+                return Err(DomainError::internal(format!("missing export `{required}`")));
+            }
+        }
+        if !plugin.filters_posts() && !plugin.handles_events() {
+            return Err(DomainError::internal(format!("exports neither `{FILTER_POST}` nor `{ON_EVENT}`")));
+        }
+        Ok(plugin)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Whether the module exports `rb_filter_post`.
+    pub fn filters_posts(&self) -> bool {
+        self.module.get_export(FILTER_POST).is_some()
+    }
+
+    /// Whether the module exports `rb_on_event`.
+    pub fn handles_events(&self) -> bool {
+        self.module.get_export(ON_EVENT).is_some()
+    }
+
+    /// Run `rb_filter_post` on `post`.
+    pub fn filter_post(&self, post: &PostSubmission) -> Result<FilterVerdict, DomainError> {
+        let input = serde_json::to_vec(post).map_err(|e| DomainError::internal(e.to_string()))?;
+        let (mut store, instance, memory, ptr, len) = self.instantiate(&input)?;
+        let packed = instance
+            .get_typed_func::<(i32, i32), i64>(&mut store, FILTER_POST)
+            .and_then(|f| f.call(&mut store, (ptr, len)))
+            .map_err(|e| self.error(FILTER_POST, e))?;
+        let (out_ptr, out_len) = ((packed >> 32) as u32 as usize, packed as u32 as usize);
+        let output = memory
+            .data(&store)
+            .get(out_ptr..out_ptr.saturating_add(out_len))
+            .ok_or_else(|| DomainError::internal(format!("plugin {}: output out of bounds", self.name)))?;
+        serde_json::from_slice(output)
+            .map_err(|e| DomainError::internal(format!("plugin {}: invalid verdict: {e}", self.name)))
+    }
+
+    /// Run `rb_on_event` on `event`.
+    pub fn on_event(&self, event: &DomainEvent) -> Result<(), DomainError> {
+        let input = serde_json::to_vec(event).map_err(|e| DomainError::internal(e.to_string()))?;
+        let (mut store, instance, _, ptr, len) = self.instantiate(&input)?;
+        instance
+            .get_typed_func::<(i32, i32), ()>(&mut store, ON_EVENT)
+            .and_then(|f| f.call(&mut store, (ptr, len)))
+            .map_err(|e| self.error(ON_EVENT, e))
+    }
+
+    /// A fresh instance with `input` copied into a buffer from `rb_alloc`.
+    fn instantiate(&self, input: &[u8]) -> Result<(Store<StoreLimits>, Instance, Memory, i32, i32), DomainError> {
+        let limits = StoreLimitsBuilder::new().memory_size(self.limits.memory_bytes).instances(1).build();
+        let mut store = Store::new(&self.engine, limits);
+        store.limiter(|limits| limits);
+        store.set_fuel(self.limits.fuel).map_err(|e| self.error("set_fuel", e))?;
+        let instance = Instance::new(&mut store, &self.module, &[]).map_err(|e| self.error("instantiate", e))?;
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or_else(|| DomainError::internal(format!("plugin {}: `memory` is not a memory", self.name)))?;
+        let len = i32::try_from(input.len())
+            .map_err(|_| DomainError::internal(format!("plugin {}: input too large", self.name)))?;
+        let ptr = instance
+            .get_typed_func::<i32, i32>(&mut store, ALLOC)
+            .and_then(|f| f.call(&mut store, len))
+            .map_err(|e| self.error(ALLOC, e))?;
+        memory.write(&mut store, ptr as u32 as usize, input).map_err(|e| self.error(ALLOC, e.into()))?;
+        Ok((store, instance, memory, ptr, len))
+    }
+
+    fn error(&self, call: &str, e: wasmtime::Error) -> DomainError {
+        DomainError::internal(format!("plugin {} `{call}`: {e}", self.name))
+    }
+}
+
+/// `PostFilter` backed by a plugin's `rb_filter_post`.
+#[derive(Clone)]
+pub struct WasmPostFilter(pub Arc<WasmPlugin>);
+
+#[async_trait]
+impl PostFilter for WasmPostFilter {
+    fn name(&self) -> &str {
+        self.0.name()
+    }
+
+    async fn check(&self, post: &PostSubmission) -> Result<FilterVerdict, DomainError> {
+        let plugin = self.0.clone();
+        let post = post.clone();
+        tokio::task::spawn_blocking(move || plugin.filter_post(&post))
+            .await
+            .map_err(|e| DomainError::internal(format!("plugin task failed: {e}")))?
+    }
+}
+
+/// `EventSubscriber` backed by a plugin's `rb_on_event`.
+#[derive(Clone)]
+pub struct WasmEventSubscriber(pub Arc<WasmPlugin>);
+
+#[async_trait]
+impl EventSubscriber for WasmEventSubscriber {
+    fn name(&self) -> &str {
+        self.0.name()
+    }
+
+    async fn handle(&self, event: &DomainEvent) -> Result<(), DomainError> {
+        let plugin = self.0.clone();
+        let event = event.clone();
+        tokio::task::spawn_blocking(move || plugin.on_event(&event))
+            .await
+            .map_err(|e| DomainError::internal(format!("plugin task failed: {e}")))?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use domains::models::{BoardId, IpHash};
+
+    const LIMITS: WasmLimits = WasmLimits { fuel: 1_000_000, memory_bytes: 1 << 20 };
+
+    /// A filter whose verdict is the JSON `verdict`, stored at offset 1024.
+    fn fixed_verdict(verdict: &str) -> String {
+        format!(
+            r#"(module
+                (memory (export "memory") 1)
+                (data (i32.const 1024) "{escaped}")
+                (func (export "rb_alloc") (param i32) (result i32) i32.const 0)
+                (func (export "rb_filter_post") (param i32 i32) (result i64)
+                    i64.const {packed}))"#,
+            escaped = verdict.replace('"', "\\\""),
+            packed = (1024i64 << 32) | verdict.len() as i64,
+        )
+    }
+
+    fn plugin(wat: &str) -> Result<WasmPlugin, DomainError> {
+        WasmPlugin::new(&engine()?, "test", wat.as_bytes(), LIMITS)
+    }
+
+    fn submission() -> PostSubmission {
+        PostSubmission {
+            board_id:   BoardId::new(),
+            thread_id:  None,
+            body:       "hello".to_owned(),
+            name:       None,
+            email:      None,
+            ip_hash:    IpHash::new("abc"),
+            file_count: 0,
+            is_staff:   false,
+        }
+    }
+
+    #[test]
+    fn filter_returns_the_plugin_verdict() {
+        let reject = plugin(&fixed_verdict(r#"{"action":"reject","reason":"no"}"#)).unwrap();
+        assert!(reject.filters_posts());
+        assert!(!reject.handles_events());
+        assert_eq!(reject.filter_post(&submission()).unwrap(), FilterVerdict::Reject { reason: "no".to_owned() });
+    }
+
+    #[test]
+    fn input_is_copied_into_plugin_memory() {
+        // Traps unless the input starts with `{`.
+        let wat = r#"(module
+            (memory (export "memory") 1)
+            (func (export "rb_alloc") (param i32) (result i32) i32.const 64)
+            (func (export "rb_on_event") (param i32 i32)
+                (if (i32.ne (i32.load8_u (local.get 0)) (i32.const 123)) (then unreachable))))"#;
+        let hook = plugin(wat).unwrap();
+        let event = DomainEvent::PostDeleted {
+            board_id:  BoardId::new(),
+            thread_id: domains::models::ThreadId::new(),
+            post_id:   domains::models::PostId::new(),
+        };
+        hook.on_event(&event).unwrap();
+    }
+
+    #[test]
+    fn running_out_of_fuel_is_an_error() {
+        let wat = r#"(module
+            (memory (export "memory") 1)
+            (func (export "rb_alloc") (param i32) (result i32) i32.const 0)
+            (func (export "rb_filter_post") (param i32 i32) (result i64)
+                (loop br 0) i64.const 0))"#;
+        assert!(plugin(wat).unwrap().filter_post(&submission()).is_err());
+    }
+
+    #[test]
+    fn malformed_output_is_an_error() {
+        let bad = plugin(&fixed_verdict(r#"{"action":"explode"}"#)).unwrap();
+        assert!(bad.filter_post(&submission()).is_err());
+    }
+
+    #[test]
+    fn modules_with_imports_or_no_hooks_are_refused() {
+        let imports = r#"(module
+            (import "wasi_snapshot_preview1" "fd_write" (func (param i32 i32 i32 i32) (result i32)))
+            (memory (export "memory") 1)
+            (func (export "rb_alloc") (param i32) (result i32) i32.const 0)
+            (func (export "rb_on_event") (param i32 i32)))"#;
+        assert!(plugin(imports).is_err());
+        let no_hooks = r#"(module
+            (memory (export "memory") 1)
+            (func (export "rb_alloc") (param i32) (result i32) i32.const 0))"#;
+        assert!(plugin(no_hooks).is_err());
+    }
+
+    #[tokio::test]
+    async fn post_filter_runs_the_plugin() {
+        let accept = Arc::new(plugin(&fixed_verdict(r#"{"action":"accept"}"#)).unwrap());
+        assert_eq!(WasmPostFilter(accept).check(&submission()).await.unwrap(), FilterVerdict::accept());
+    }
+}
@@ -5,3 +5,4 @@
 
 pub mod connection;
 pub mod repositories;
+pub mod secrets;
@@ -0,0 +1,104 @@
+//! PostgreSQL implementation of `AnnouncementRepository`.
+//! Uses runtime sqlx queries — no query! macros.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domains::errors::DomainError;
+use domains::models::{Announcement, AnnouncementId, BoardId, SiteId};
+use domains::ports::AnnouncementRepository;
+use sqlx::PgPool;
+use tracing::instrument;
+use uuid::Uuid;
+
+/// PostgreSQL-backed `AnnouncementRepository`.
+#[derive(Clone)]
+pub struct PgAnnouncementRepository {
+    pool: PgPool,
+}
+
+impl PgAnnouncementRepository {
+    /// Construct a `PgAnnouncementRepository` backed by the given connection pool.
+    pub fn new(pool: PgPool) -> Self { Self { pool } }
+}
+
+/// Columns of `announcements`, in the order `AnnouncementRow` expects them.
+const COLUMNS: &str = "id, site_id, board_id, message, created_at";
+
+type AnnouncementRow = (Uuid, Uuid, Option<Uuid>, String, DateTime<Utc>);
+
+fn to_announcement((id, site_id, board_id, message, created_at): AnnouncementRow) -> Announcement {
+    Announcement {
+        id: AnnouncementId(id),
+        site_id: SiteId(site_id),
+        board_id: board_id.map(BoardId),
+        message,
+        created_at,
+    }
+}
+
+#[async_trait]
+impl AnnouncementRepository for PgAnnouncementRepository {
+    #[instrument(skip(self, announcement), fields(announcement_id = %announcement.id, site_id = %announcement.site_id))]
+    async fn create(&self, announcement: &Announcement) -> Result<(), DomainError> {
+        sqlx::query(
+            "INSERT INTO announcements (id, site_id, board_id, message, created_at)
+             VALUES ($1, $2, $3, $4, $5)"
+        )
+        .bind(announcement.id.0)
+        .bind(announcement.site_id.0)
+        .bind(announcement.board_id.map(|b| b.0))
+        .bind(&announcement.message)
+        .bind(announcement.created_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+        Ok(())
+    }
+
+    #[instrument(skip(self), fields(site_id = %site_id, announcement_id = %id))]
+    async fn delete(&self, site_id: SiteId, id: AnnouncementId) -> Result<Announcement, DomainError> {
+        let row: Option<AnnouncementRow> = sqlx::query_as(&format!(
+            "DELETE FROM announcements WHERE site_id = $1 AND id = $2 RETURNING {COLUMNS}"
+        ))
+        .bind(site_id.0)
+        .bind(id.0)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+        row.map(to_announcement).ok_or_else(|| DomainError::not_found(format!("announcement {id}")))
+    }
+
+    #[instrument(skip(self), fields(site_id = %site_id))]
+    async fn list(&self, site_id: SiteId) -> Result<Vec<Announcement>, DomainError> {
+        let rows: Vec<AnnouncementRow> = sqlx::query_as(&format!(
+            "SELECT {COLUMNS} FROM announcements WHERE site_id = $1 ORDER BY created_at DESC"
+        ))
+        .bind(site_id.0)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+        Ok(rows.into_iter().map(to_announcement).collect())
+    }
+
+    #[instrument(skip(self), fields(site_id = %site_id, board_id = %board_id))]
+    async fn list_for_board(
+        &self,
+        site_id: SiteId,
+        board_id: BoardId,
+        limit: u32,
+    ) -> Result<Vec<Announcement>, DomainError> {
+        let rows: Vec<AnnouncementRow> = sqlx::query_as(&format!(
+            "SELECT {COLUMNS} FROM announcements
+             WHERE site_id = $1 AND (board_id IS NULL OR board_id = $2)
+             ORDER BY created_at DESC
+             LIMIT $3"
+        ))
+        .bind(site_id.0)
+        .bind(board_id.0)
+        .bind(i64::from(limit))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+        Ok(rows.into_iter().map(to_announcement).collect())
+    }
+}
@@ -0,0 +1,125 @@
+//! PostgreSQL implementation of `ApiKeyRepository`.
+//! Uses runtime sqlx queries — no query! macros.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domains::errors::DomainError;
+use domains::models::{ApiKey, ApiKeyId, UserId};
+use domains::ports::ApiKeyRepository;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// PostgreSQL-backed `ApiKeyRepository`.
+#[derive(Clone)]
+pub struct PgApiKeyRepository {
+    pool: PgPool,
+}
+
+impl PgApiKeyRepository {
+    /// Construct a `PgApiKeyRepository` backed by the given connection pool.
+    pub fn new(pool: PgPool) -> Self { Self { pool } }
+}
+
+const COLUMNS: &str =
+    "id, name, prefix, key_hash, scopes, rate_limit_per_min, created_by, created_at, last_used_at, revoked_at";
+
+#[derive(sqlx::FromRow)]
+struct ApiKeyRow {
+    id:                 Uuid,
+    name:               String,
+    prefix:             String,
+    key_hash:           String,
+    scopes:             Vec<String>,
+    rate_limit_per_min: i32,
+    created_by:         Uuid,
+    created_at:         DateTime<Utc>,
+    last_used_at:       Option<DateTime<Utc>>,
+    revoked_at:         Option<DateTime<Utc>>,
+}
+
+fn api_key_from_row(r: ApiKeyRow) -> ApiKey {
+    ApiKey {
+        id:                 ApiKeyId(r.id),
+        name:               r.name,
+        prefix:             r.prefix,
+        key_hash:           r.key_hash,
+        // An unknown scope grants nothing rather than failing the lookup.
+        scopes:             r.scopes.iter().filter_map(|s| s.parse().ok()).collect(),
+        rate_limit_per_min: r.rate_limit_per_min.max(0) as u32,
+        created_by:         UserId(r.created_by),
+        created_at:         r.created_at,
+        last_used_at:       r.last_used_at,
+        revoked_at:         r.revoked_at,
+    }
+}
+
+#[async_trait]
+impl ApiKeyRepository for PgApiKeyRepository {
+    async fn save(&self, key: &ApiKey) -> Result<(), DomainError> {
+        let scopes: Vec<&str> = key.scopes.iter().map(|s| s.as_str()).collect();
+        sqlx::query(
+            "INSERT INTO api_keys (id, name, prefix, key_hash, scopes, rate_limit_per_min, created_by,
+                                   created_at, last_used_at, revoked_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+             ON CONFLICT (id) DO UPDATE SET
+                 name               = EXCLUDED.name,
+                 scopes             = EXCLUDED.scopes,
+                 rate_limit_per_min = EXCLUDED.rate_limit_per_min,
+                 last_used_at       = EXCLUDED.last_used_at,
+                 revoked_at         = EXCLUDED.revoked_at"
+        )
+        .bind(key.id.0)
+        .bind(&key.name)
+        .bind(&key.prefix)
+        .bind(&key.key_hash)
+        .bind(&scopes)
+        .bind(key.rate_limit_per_min as i32)
+        .bind(key.created_by.0)
+        .bind(key.created_at)
+        .bind(key.last_used_at)
+        .bind(key.revoked_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn find_by_hash(&self, key_hash: &str) -> Result<Option<ApiKey>, DomainError> {
+        let row = sqlx::query_as::<_, ApiKeyRow>(&format!("SELECT {COLUMNS} FROM api_keys WHERE key_hash = $1"))
+            .bind(key_hash)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| DomainError::internal(e.to_string()))?;
+        Ok(row.map(api_key_from_row))
+    }
+
+    async fn list(&self) -> Result<Vec<ApiKey>, DomainError> {
+        let rows = sqlx::query_as::<_, ApiKeyRow>(&format!("SELECT {COLUMNS} FROM api_keys ORDER BY created_at DESC"))
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| DomainError::internal(e.to_string()))?;
+        Ok(rows.into_iter().map(api_key_from_row).collect())
+    }
+
+    async fn revoke(&self, id: ApiKeyId, at: DateTime<Utc>) -> Result<ApiKey, DomainError> {
+        let row = sqlx::query_as::<_, ApiKeyRow>(&format!(
+            "UPDATE api_keys SET revoked_at = COALESCE(revoked_at, $2) WHERE id = $1 RETURNING {COLUMNS}"
+        ))
+        .bind(id.0)
+        .bind(at)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+        row.map(api_key_from_row).ok_or_else(|| DomainError::not_found(id.to_string()))
+    }
+
+    async fn touch(&self, id: ApiKeyId, at: DateTime<Utc>) -> Result<(), DomainError> {
+        sqlx::query("UPDATE api_keys SET last_used_at = $2 WHERE id = $1")
+            .bind(id.0)
+            .bind(at)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| DomainError::internal(e.to_string()))?;
+        Ok(())
+    }
+}
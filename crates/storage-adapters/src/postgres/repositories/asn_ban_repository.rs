@@ -0,0 +1,116 @@
+//! PostgreSQL implementation of `AsnBanRepository`.
+//! Uses runtime sqlx queries — no query! macros.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domains::errors::DomainError;
+use domains::models::{AsnBan, BanId, Page, Paginated, UserId};
+use domains::ports::AsnBanRepository;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// PostgreSQL-backed `AsnBanRepository`.
+#[derive(Clone)]
+pub struct PgAsnBanRepository {
+    pool: PgPool,
+}
+
+impl PgAsnBanRepository {
+    /// Construct a `PgAsnBanRepository` backed by the given connection pool.
+    pub fn new(pool: PgPool) -> Self { Self { pool } }
+}
+
+#[derive(sqlx::FromRow)]
+struct AsnBanRow {
+    id:         Uuid,
+    asn:        i64,
+    banned_by:  Uuid,
+    reason:     String,
+    expires_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+}
+
+fn asn_ban_from_row(r: AsnBanRow) -> AsnBan {
+    AsnBan {
+        id:         BanId(r.id),
+        // The column CHECK constrains asn to the u32 range.
+        asn:        r.asn as u32,
+        banned_by:  UserId(r.banned_by),
+        reason:     r.reason,
+        expires_at: r.expires_at,
+        created_at: r.created_at,
+    }
+}
+
+#[async_trait]
+impl AsnBanRepository for PgAsnBanRepository {
+    async fn find_active_by_asn(&self, asn: u32) -> Result<Option<AsnBan>, DomainError> {
+        let row = sqlx::query_as::<_, AsnBanRow>(
+            "SELECT id, asn, banned_by, reason, expires_at, created_at
+             FROM asn_bans
+             WHERE asn = $1
+               AND (expires_at IS NULL OR expires_at > now())
+             ORDER BY created_at DESC LIMIT 1"
+        )
+        .bind(asn as i64)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+        Ok(row.map(asn_ban_from_row))
+    }
+
+    async fn save(&self, ban: &AsnBan) -> Result<BanId, DomainError> {
+        sqlx::query(
+            "INSERT INTO asn_bans (id, asn, banned_by, reason, expires_at, created_at)
+             VALUES ($1, $2, $3, $4, $5, $6)"
+        )
+        .bind(ban.id.0)
+        .bind(ban.asn as i64)
+        .bind(ban.banned_by.0)
+        .bind(&ban.reason)
+        .bind(ban.expires_at)
+        .bind(ban.created_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+        Ok(ban.id)
+    }
+
+    async fn expire(&self, id: BanId) -> Result<(), DomainError> {
+        let result = sqlx::query(
+            "UPDATE asn_bans SET expires_at = now() WHERE id = $1"
+        )
+        .bind(id.0)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+        if result.rows_affected() == 0 {
+            return Err(DomainError::not_found(id.to_string()));
+        }
+        Ok(())
+    }
+
+    async fn find_all(&self, page: Page) -> Result<Paginated<AsnBan>, DomainError> {
+        let page_size = Page::DEFAULT_PAGE_SIZE;
+        let offset = page.offset(page_size) as i64;
+        let limit  = page_size as i64;
+
+        let rows = sqlx::query_as::<_, AsnBanRow>(
+            "SELECT id, asn, banned_by, reason, expires_at, created_at \
+             FROM asn_bans ORDER BY created_at DESC LIMIT $1 OFFSET $2"
+        )
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+
+        let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM asn_bans")
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| DomainError::internal(e.to_string()))?;
+
+        let items = rows.into_iter().map(asn_ban_from_row).collect();
+        Ok(Paginated::new(items, total as u64, page, page_size))
+    }
+}
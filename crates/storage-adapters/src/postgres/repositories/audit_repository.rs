@@ -4,12 +4,15 @@
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use domains::errors::DomainError;
-use domains::models::{AuditAction, AuditEntry, IpHash, Page, Paginated, UserId};
+use domains::models::{AuditAction, AuditEntry, BoardId, IpHash, Page, Paginated, UserId};
 use domains::ports::AuditRepository;
 use sqlx::PgPool;
 use std::str::FromStr;
 use uuid::Uuid;
 
+/// Columns of an `AuditRow`.
+const COLUMNS: &str = "id, actor_id, actor_ip_hash, action, target_id, target_type, board_id, reason, details, created_at";
+
 /// PostgreSQL-backed `AuditRepository`.
 #[derive(Clone)]
 pub struct PgAuditRepository {
@@ -29,6 +32,8 @@ struct AuditRow {
     action:         String,
     target_id:      Option<Uuid>,
     target_type:    Option<String>,
+    board_id:       Option<Uuid>,
+    reason:         Option<String>,
     details:        Option<serde_json::Value>,
     created_at:     DateTime<Utc>,
 }
@@ -42,6 +47,8 @@ fn audit_from_row(r: AuditRow) -> Result<AuditEntry, DomainError> {
                             .map_err(|e| DomainError::internal(e.to_string()))?,
         target_id:      r.target_id,
         target_type:    r.target_type,
+        board_id:       r.board_id.map(BoardId),
+        reason:         r.reason,
         details:        r.details,
         created_at:     r.created_at,
     })
@@ -52,8 +59,8 @@ impl AuditRepository for PgAuditRepository {
     async fn record(&self, entry: &AuditEntry) -> Result<(), DomainError> {
         sqlx::query(
             "INSERT INTO audit_logs
-             (id, actor_id, actor_ip_hash, action, target_id, target_type, details, created_at)
-             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)"
+             (id, actor_id, actor_ip_hash, action, target_id, target_type, board_id, reason, details, created_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)"
         )
         .bind(entry.id)
         .bind(entry.actor_id.map(|u| u.0))
@@ -61,6 +68,8 @@ impl AuditRepository for PgAuditRepository {
         .bind(entry.action.to_string())
         .bind(entry.target_id)
         .bind(&entry.target_type)
+        .bind(entry.board_id.map(|b| b.0))
+        .bind(&entry.reason)
         .bind(&entry.details)
         .bind(entry.created_at)
         .execute(&self.pool)
@@ -70,10 +79,9 @@ impl AuditRepository for PgAuditRepository {
     }
 
     async fn find_recent(&self, limit: u32) -> Result<Vec<AuditEntry>, DomainError> {
-        let rows = sqlx::query_as::<_, AuditRow>(
-            "SELECT id, actor_id, actor_ip_hash, action, target_id, target_type, details, created_at \
-             FROM audit_logs ORDER BY created_at DESC LIMIT $1"
-        )
+        let rows = sqlx::query_as::<_, AuditRow>(&format!(
+            "SELECT {COLUMNS} FROM audit_logs ORDER BY created_at DESC LIMIT $1"
+        ))
         .bind(limit as i64)
         .fetch_all(&self.pool)
         .await
@@ -86,11 +94,10 @@ impl AuditRepository for PgAuditRepository {
         let offset = page.offset(page_size) as i64;
         let limit  = page_size as i64;
 
-        let rows = sqlx::query_as::<_, AuditRow>(
-            "SELECT id, actor_id, actor_ip_hash, action, target_id, target_type, details, created_at \
-             FROM audit_logs WHERE actor_id = $1 \
+        let rows = sqlx::query_as::<_, AuditRow>(&format!(
+            "SELECT {COLUMNS} FROM audit_logs WHERE actor_id = $1 \
              ORDER BY created_at DESC LIMIT $2 OFFSET $3"
-        )
+        ))
         .bind(actor_id.0)
         .bind(limit)
         .bind(offset)
@@ -117,11 +124,10 @@ impl AuditRepository for PgAuditRepository {
         let offset = page.offset(page_size) as i64;
         let limit  = page_size as i64;
 
-        let rows = sqlx::query_as::<_, AuditRow>(
-            "SELECT id, actor_id, actor_ip_hash, action, target_id, target_type, details, created_at \
-             FROM audit_logs WHERE target_id = $1 \
+        let rows = sqlx::query_as::<_, AuditRow>(&format!(
+            "SELECT {COLUMNS} FROM audit_logs WHERE target_id = $1 \
              ORDER BY created_at DESC LIMIT $2 OFFSET $3"
-        )
+        ))
         .bind(target_id)
         .bind(limit)
         .bind(offset)
@@ -148,9 +154,9 @@ impl AuditRepository for PgAuditRepository {
         let offset    = page.offset(page_size) as i64;
         let limit     = page_size as i64;
 
-        let rows = sqlx::query_as::<_, AuditRow>(
-            "SELECT id, actor_id, actor_ip_hash, action, target_id, target_type, details, created_at              FROM audit_logs ORDER BY created_at DESC LIMIT $1 OFFSET $2"
-        )
+        let rows = sqlx::query_as::<_, AuditRow>(&format!(
+            "SELECT {COLUMNS} FROM audit_logs ORDER BY created_at DESC LIMIT $1 OFFSET $2"
+        ))
         .bind(limit)
         .bind(offset)
         .fetch_all(&self.pool)
@@ -168,20 +174,41 @@ impl AuditRepository for PgAuditRepository {
 
     async fn find_by_board(
         &self,
-        board_id: domains::models::BoardId,
+        board_id: BoardId,
+        page: Page,
+    ) -> Result<Paginated<AuditEntry>, DomainError> {
+        self.find_in_board(board_id, None, page).await
+    }
+
+    async fn find_public_by_board(
+        &self,
+        board_id: BoardId,
+        page: Page,
+    ) -> Result<Paginated<AuditEntry>, DomainError> {
+        let actions: Vec<String> = AuditAction::PUBLIC.iter().map(ToString::to_string).collect();
+        self.find_in_board(board_id, Some(&actions), page).await
+    }
+}
+
+impl PgAuditRepository {
+    /// A page of the board's entries, limited to `actions` when given.
+    async fn find_in_board(
+        &self,
+        board_id: BoardId,
+        actions: Option<&[String]>,
         page: Page,
     ) -> Result<Paginated<AuditEntry>, DomainError> {
         let page_size = Page::DEFAULT_PAGE_SIZE;
         let offset    = page.offset(page_size) as i64;
         let limit     = page_size as i64;
-        let board_str = board_id.0.to_string();
 
-        // Scoped by board_id stored in the JSON details column.
-        // Covers all moderation actions that include board context.
-        let rows = sqlx::query_as::<_, AuditRow>(
-            "SELECT id, actor_id, actor_ip_hash, action, target_id, target_type, details, created_at              FROM audit_logs              WHERE details->>'board_id' = $1              ORDER BY created_at DESC LIMIT $2 OFFSET $3"
-        )
-        .bind(&board_str)
+        let rows = sqlx::query_as::<_, AuditRow>(&format!(
+            "SELECT {COLUMNS} FROM audit_logs \
+             WHERE board_id = $1 AND ($2::text[] IS NULL OR action = ANY($2)) \
+             ORDER BY created_at DESC LIMIT $3 OFFSET $4"
+        ))
+        .bind(board_id.0)
+        .bind(actions)
         .bind(limit)
         .bind(offset)
         .fetch_all(&self.pool)
@@ -189,9 +216,10 @@ impl AuditRepository for PgAuditRepository {
         .map_err(|e| DomainError::internal(e.to_string()))?;
 
         let total: i64 = sqlx::query_scalar(
-            "SELECT COUNT(*) FROM audit_logs WHERE details->>'board_id' = $1"
+            "SELECT COUNT(*) FROM audit_logs WHERE board_id = $1 AND ($2::text[] IS NULL OR action = ANY($2))"
         )
-        .bind(&board_str)
+        .bind(board_id.0)
+        .bind(actions)
         .fetch_one(&self.pool)
         .await
         .map_err(|e| DomainError::internal(e.to_string()))?;
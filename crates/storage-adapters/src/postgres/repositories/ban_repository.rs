@@ -28,6 +28,7 @@ struct BanRow {
     reason:     String,
     expires_at: Option<DateTime<Utc>>,
     created_at: DateTime<Utc>,
+    shadow:     bool,
 }
 
 fn ban_from_row(r: BanRow) -> Ban {
@@ -38,6 +39,7 @@ fn ban_from_row(r: BanRow) -> Ban {
         reason:     r.reason,
         expires_at: r.expires_at,
         created_at: r.created_at,
+        shadow:     r.shadow,
     }
 }
 
@@ -45,11 +47,11 @@ fn ban_from_row(r: BanRow) -> Ban {
 impl BanRepository for PgBanRepository {
     async fn find_active_by_ip(&self, ip_hash: &IpHash) -> Result<Option<Ban>, DomainError> {
         let row = sqlx::query_as::<_, BanRow>(
-            "SELECT id, ip_hash, banned_by, reason, expires_at, created_at
+            "SELECT id, ip_hash, banned_by, reason, expires_at, created_at, shadow
              FROM bans
              WHERE ip_hash = $1
                AND (expires_at IS NULL OR expires_at > now())
-             ORDER BY created_at DESC LIMIT 1"
+             ORDER BY shadow ASC, created_at DESC LIMIT 1"
         )
         .bind(&ip_hash.0)
         .fetch_optional(&self.pool)
@@ -60,8 +62,8 @@ impl BanRepository for PgBanRepository {
 
     async fn save(&self, ban: &Ban) -> Result<BanId, DomainError> {
         sqlx::query(
-            "INSERT INTO bans (id, ip_hash, banned_by, reason, expires_at, created_at)
-             VALUES ($1, $2, $3, $4, $5, $6)"
+            "INSERT INTO bans (id, ip_hash, banned_by, reason, expires_at, created_at, shadow)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)"
         )
         .bind(ban.id.0)
         .bind(&ban.ip_hash.0)
@@ -69,6 +71,7 @@ impl BanRepository for PgBanRepository {
         .bind(&ban.reason)
         .bind(ban.expires_at)
         .bind(ban.created_at)
+        .bind(ban.shadow)
         .execute(&self.pool)
         .await
         .map_err(|e| DomainError::internal(e.to_string()))?;
@@ -89,13 +92,26 @@ impl BanRepository for PgBanRepository {
         Ok(())
     }
 
+    async fn delete_expired(&self, older_than_days: u32) -> Result<u32, DomainError> {
+        let result = sqlx::query(
+            "DELETE FROM bans
+             WHERE expires_at IS NOT NULL
+               AND expires_at < now() - make_interval(days => $1)"
+        )
+        .bind(older_than_days as i32)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+        Ok(result.rows_affected() as u32)
+    }
+
     async fn find_all(&self, page: Page) -> Result<Paginated<Ban>, DomainError> {
         let page_size = Page::DEFAULT_PAGE_SIZE;
         let offset = page.offset(page_size) as i64;
         let limit  = page_size as i64;
 
         let rows = sqlx::query_as::<_, BanRow>(
-            "SELECT id, ip_hash, banned_by, reason, expires_at, created_at \
+            "SELECT id, ip_hash, banned_by, reason, expires_at, created_at, shadow \
              FROM bans ORDER BY created_at DESC LIMIT $1 OFFSET $2"
         )
         .bind(limit)
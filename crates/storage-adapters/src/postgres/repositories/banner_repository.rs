@@ -0,0 +1,95 @@
+//! PostgreSQL implementation of `BannerRepository`.
+//! Uses runtime sqlx queries — no query! macros.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domains::errors::DomainError;
+use domains::models::{BannerId, BoardBanner, BoardId, MediaKey};
+use domains::ports::BannerRepository;
+use sqlx::PgPool;
+use tracing::instrument;
+use uuid::Uuid;
+
+/// PostgreSQL-backed `BannerRepository`.
+#[derive(Clone)]
+pub struct PgBannerRepository {
+    pool: PgPool,
+}
+
+impl PgBannerRepository {
+    /// Construct a `PgBannerRepository` backed by the given connection pool.
+    pub fn new(pool: PgPool) -> Self { Self { pool } }
+}
+
+/// Columns of `board_banners`, in the order `BannerRow` expects them.
+const COLUMNS: &str = "id, board_id, media_key, content_type, created_at";
+
+type BannerRow = (Uuid, Uuid, String, String, DateTime<Utc>);
+
+fn to_banner((id, board_id, media_key, content_type, created_at): BannerRow) -> BoardBanner {
+    BoardBanner {
+        id: BannerId(id),
+        board_id: BoardId(board_id),
+        media_key: MediaKey::new(media_key),
+        content_type,
+        created_at,
+    }
+}
+
+#[async_trait]
+impl BannerRepository for PgBannerRepository {
+    #[instrument(skip(self, banner), fields(banner_id = %banner.id, board_id = %banner.board_id))]
+    async fn add(&self, banner: &BoardBanner) -> Result<(), DomainError> {
+        sqlx::query(
+            "INSERT INTO board_banners (id, board_id, media_key, content_type, created_at)
+             VALUES ($1, $2, $3, $4, $5)"
+        )
+        .bind(banner.id.0)
+        .bind(banner.board_id.0)
+        .bind(&banner.media_key.0)
+        .bind(&banner.content_type)
+        .bind(banner.created_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+        Ok(())
+    }
+
+    #[instrument(skip(self), fields(board_id = %board_id))]
+    async fn list(&self, board_id: BoardId) -> Result<Vec<BoardBanner>, DomainError> {
+        let rows: Vec<BannerRow> = sqlx::query_as(&format!(
+            "SELECT {COLUMNS} FROM board_banners WHERE board_id = $1 ORDER BY created_at"
+        ))
+        .bind(board_id.0)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+        Ok(rows.into_iter().map(to_banner).collect())
+    }
+
+    #[instrument(skip(self), fields(board_id = %board_id))]
+    async fn random(&self, board_id: BoardId) -> Result<Option<BoardBanner>, DomainError> {
+        // A board has at most a few dozen banners, so sorting them is cheap.
+        let row: Option<BannerRow> = sqlx::query_as(&format!(
+            "SELECT {COLUMNS} FROM board_banners WHERE board_id = $1 ORDER BY random() LIMIT 1"
+        ))
+        .bind(board_id.0)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+        Ok(row.map(to_banner))
+    }
+
+    #[instrument(skip(self), fields(board_id = %board_id, banner_id = %id))]
+    async fn remove(&self, board_id: BoardId, id: BannerId) -> Result<BoardBanner, DomainError> {
+        let row: Option<BannerRow> = sqlx::query_as(&format!(
+            "DELETE FROM board_banners WHERE board_id = $1 AND id = $2 RETURNING {COLUMNS}"
+        ))
+        .bind(board_id.0)
+        .bind(id.0)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+        row.map(to_banner).ok_or_else(|| DomainError::not_found(format!("banner {id}")))
+    }
+}
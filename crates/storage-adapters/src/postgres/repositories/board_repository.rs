@@ -5,7 +5,7 @@
 
 use async_trait::async_trait;
 use domains::errors::DomainError;
-use domains::models::{Board, BoardConfig, BoardId, FileSizeKb, Page, Paginated, Slug};
+use domains::models::{Board, BoardConfig, BoardId, FileSizeKb, Page, Paginated, SiteId, Slug, ThumbnailSpec};
 use domains::ports::BoardRepository;
 use sqlx::{PgPool, Row};
 use chrono;
@@ -33,6 +33,7 @@ fn map_sqlx_err(e: sqlx::Error, resource: impl Into<String>) -> DomainError {
 #[derive(sqlx::FromRow)]
 struct BoardRow {
     id:         Uuid,
+    site_id:    Uuid,
     slug:       String,
     title:      String,
     rules:      String,
@@ -42,6 +43,7 @@ struct BoardRow {
 fn board_from_row(r: BoardRow) -> Result<Board, DomainError> {
     Ok(Board {
         id:         BoardId(r.id),
+        site_id:    SiteId(r.site_id),
         slug:       Slug::new(&r.slug).map_err(|e| DomainError::internal(e.to_string()))?,
         title:      r.title,
         rules:      r.rules,
@@ -73,6 +75,30 @@ struct BoardConfigRow {
     federation_enabled:     bool,
     link_blacklist:              Vec<String>,
     name_rate_limit_window_secs: i32,
+    dnsbl_action:                String,
+    classifier_action:           String,
+    classifier_threshold:        f32,
+    media_quota_mb:              i32,
+    quota_action:                String,
+    strip_metadata:              bool,
+    markup_enabled:              bool,
+    spoilers_enabled:            bool,
+    code_enabled:                bool,
+    greentext_enabled:           bool,
+    code_highlighting:           bool,
+    commands_enabled:            bool,
+    polls_enabled:               bool,
+    reactions_enabled:           bool,
+    tripcode_algorithm:          String,
+    default_theme:               String,
+    default_locale:              String,
+    category:                    String,
+    overboard_hidden:            bool,
+    public_log:                  bool,
+    thumbnail_max_width:         Option<i32>,
+    thumbnail_max_height:        Option<i32>,
+    thumbnail_format:            Option<String>,
+    thumbnail_quality:           Option<i16>,
 }
 
 fn board_config_from_row(r: BoardConfigRow) -> BoardConfig {
@@ -99,6 +125,32 @@ fn board_config_from_row(r: BoardConfigRow) -> BoardConfig {
         federation_enabled:          r.federation_enabled,
         link_blacklist:              r.link_blacklist,
         name_rate_limit_window_secs: r.name_rate_limit_window_secs as u32,
+        dnsbl_action:                r.dnsbl_action.parse().unwrap_or_default(),
+        classifier_action:           r.classifier_action.parse().unwrap_or_default(),
+        classifier_threshold:        r.classifier_threshold,
+        media_quota_mb:              r.media_quota_mb as u32,
+        quota_action:                r.quota_action.parse().unwrap_or_default(),
+        strip_metadata:              r.strip_metadata,
+        markup_enabled:              r.markup_enabled,
+        spoilers_enabled:            r.spoilers_enabled,
+        code_enabled:                r.code_enabled,
+        greentext_enabled:           r.greentext_enabled,
+        code_highlighting:           r.code_highlighting,
+        commands_enabled:            r.commands_enabled,
+        polls_enabled:               r.polls_enabled,
+        reactions_enabled:           r.reactions_enabled,
+        tripcode_algorithm:          r.tripcode_algorithm.parse().unwrap_or_default(),
+        default_theme:               r.default_theme.parse().unwrap_or_default(),
+        default_locale:              r.default_locale.parse().unwrap_or_default(),
+        category:                    r.category,
+        overboard_hidden:            r.overboard_hidden,
+        public_log:                  r.public_log,
+        thumbnail:                   r.thumbnail_max_width.map(|max_width| ThumbnailSpec {
+            max_width:  max_width as u32,
+            max_height: r.thumbnail_max_height.unwrap_or(0) as u32,
+            format:     r.thumbnail_format.and_then(|f| f.parse().ok()).unwrap_or_default(),
+            quality:    r.thumbnail_quality.map_or(ThumbnailSpec::default().quality, |q| q as u8),
+        }),
     }
 }
 
@@ -107,7 +159,7 @@ impl BoardRepository for PgBoardRepository {
     #[instrument(skip(self), fields(board_id = %id))]
     async fn find_by_id(&self, id: BoardId) -> Result<Board, DomainError> {
         let row = sqlx::query_as::<_, BoardRow>(
-            "SELECT id, slug, title, rules, created_at FROM boards WHERE id = $1"
+            "SELECT id, site_id, slug, title, rules, created_at FROM boards WHERE id = $1"
         )
         .bind(id.0)
         .fetch_one(&self.pool)
@@ -116,11 +168,12 @@ impl BoardRepository for PgBoardRepository {
         board_from_row(row)
     }
 
-    #[instrument(skip(self), fields(slug = %slug))]
-    async fn find_by_slug(&self, slug: &Slug) -> Result<Board, DomainError> {
+    #[instrument(skip(self), fields(site_id = %site, slug = %slug))]
+    async fn find_by_slug(&self, site: SiteId, slug: &Slug) -> Result<Board, DomainError> {
         let row = sqlx::query_as::<_, BoardRow>(
-            "SELECT id, slug, title, rules, created_at FROM boards WHERE slug = $1"
+            "SELECT id, site_id, slug, title, rules, created_at FROM boards WHERE site_id = $1 AND slug = $2"
         )
+        .bind(site.0)
         .bind(slug.as_str())
         .fetch_one(&self.pool)
         .await
@@ -128,6 +181,35 @@ impl BoardRepository for PgBoardRepository {
         board_from_row(row)
     }
 
+    #[instrument(skip(self), fields(site_id = %site, page = page.0))]
+    async fn find_by_site(&self, site: SiteId, page: Page) -> Result<Paginated<Board>, DomainError> {
+        let page_size = Page::DEFAULT_PAGE_SIZE;
+        let offset = page.offset(page_size) as i64;
+        let limit  = page_size as i64;
+
+        let rows = sqlx::query_as::<_, BoardRow>(
+            "SELECT id, site_id, slug, title, rules, created_at FROM boards WHERE site_id = $1 \
+             ORDER BY created_at ASC LIMIT $2 OFFSET $3"
+        )
+        .bind(site.0)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+
+        let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM boards WHERE site_id = $1")
+            .bind(site.0)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| DomainError::internal(e.to_string()))?;
+
+        let items = rows.into_iter()
+            .map(board_from_row)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Paginated::new(items, total as u64, page, page_size))
+    }
+
     #[instrument(skip(self), fields(page = page.0))]
     async fn find_all(&self, page: Page) -> Result<Paginated<Board>, DomainError> {
         let page_size = Page::DEFAULT_PAGE_SIZE;
@@ -135,7 +217,7 @@ impl BoardRepository for PgBoardRepository {
         let limit  = page_size as i64;
 
         let rows = sqlx::query_as::<_, BoardRow>(
-            "SELECT id, slug, title, rules, created_at FROM boards \
+            "SELECT id, site_id, slug, title, rules, created_at FROM boards \
              ORDER BY created_at ASC LIMIT $1 OFFSET $2"
         )
         .bind(limit)
@@ -157,7 +239,7 @@ impl BoardRepository for PgBoardRepository {
 
     /// Upsert a board record and guarantee its `board_configs` row exists.
     ///
-    /// Conflicts on `slug` (the natural unique key) rather than `id`, so that
+    /// Conflicts on `(site_id, slug)` (the natural unique key) rather than `id`, so that
     /// re-running seeds or imports with fresh UUIDs updates the existing row
     /// instead of failing with a unique-constraint violation.
     ///
@@ -166,17 +248,19 @@ impl BoardRepository for PgBoardRepository {
     /// `board_configs` insert targets the correct primary key.
     ///
     /// Returns `DomainError::Internal` if either query fails.
+    #[instrument(skip(self, board), fields(board_id = %board.id))]
     async fn save(&self, board: &Board) -> Result<(), DomainError> {
-        // Upsert on slug (the natural key) so re-running seed doesn't fail on duplicate slugs.
+        // Upsert on the site's slug (the natural key) so re-running seed doesn't fail on duplicate slugs.
         // When slug already exists, update title/rules but keep the original id and created_at.
         let board_id: uuid::Uuid = sqlx::query_scalar(
-            "INSERT INTO boards (id, slug, title, rules, created_at)
-             VALUES ($1, $2, $3, $4, $5)
-             ON CONFLICT (slug) DO UPDATE
+            "INSERT INTO boards (id, site_id, slug, title, rules, created_at)
+             VALUES ($1, $2, $3, $4, $5, $6)
+             ON CONFLICT (site_id, slug) DO UPDATE
              SET title = EXCLUDED.title, rules = EXCLUDED.rules
              RETURNING id"
         )
         .bind(board.id.0)
+        .bind(board.site_id.0)
         .bind(board.slug.as_str())
         .bind(&board.title)
         .bind(&board.rules)
@@ -198,6 +282,7 @@ impl BoardRepository for PgBoardRepository {
         Ok(())
     }
 
+    #[instrument(skip(self), fields(board_id = %id))]
     async fn delete(&self, id: BoardId) -> Result<(), DomainError> {
         let result = sqlx::query("DELETE FROM boards WHERE id = $1")
             .bind(id.0)
@@ -218,7 +303,12 @@ impl BoardRepository for PgBoardRepository {
                     spam_filter_enabled, spam_score_threshold, duplicate_check,
                     forced_anon, allow_sage, allow_tripcodes, captcha_required, nsfw,
                     search_enabled, archive_enabled, federation_enabled,
-                    link_blacklist, name_rate_limit_window_secs
+                    link_blacklist, name_rate_limit_window_secs, dnsbl_action, strip_metadata,
+                    classifier_action, classifier_threshold, media_quota_mb, quota_action,
+                    thumbnail_max_width, thumbnail_max_height, thumbnail_format, thumbnail_quality,
+                    markup_enabled, spoilers_enabled, code_enabled, greentext_enabled, code_highlighting,
+                    commands_enabled, polls_enabled, reactions_enabled, tripcode_algorithm, default_theme,
+                    default_locale, category, overboard_hidden, public_log
              FROM board_configs WHERE board_id = $1"
         )
         .bind(board_id.0)
@@ -228,6 +318,7 @@ impl BoardRepository for PgBoardRepository {
         Ok(board_config_from_row(row))
     }
 
+    #[instrument(skip(self, config), fields(board_id = %board_id))]
     async fn save_config(&self, board_id: BoardId, config: &BoardConfig) -> Result<(), DomainError> {
         sqlx::query(
             "INSERT INTO board_configs (
@@ -236,8 +327,14 @@ impl BoardRepository for PgBoardRepository {
                 spam_filter_enabled, spam_score_threshold, duplicate_check,
                 forced_anon, allow_sage, allow_tripcodes, captcha_required, nsfw,
                 search_enabled, archive_enabled, federation_enabled,
-                link_blacklist, name_rate_limit_window_secs
-             ) VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$9,$10,$11,$12,$13,$14,$15,$16,$17,$18,$19,$20,$21,$22,$23)
+                link_blacklist, name_rate_limit_window_secs, dnsbl_action, strip_metadata,
+                thumbnail_max_width, thumbnail_max_height, thumbnail_format, thumbnail_quality,
+                classifier_action, classifier_threshold, media_quota_mb, quota_action,
+                markup_enabled, spoilers_enabled, code_enabled, greentext_enabled, code_highlighting,
+                commands_enabled, polls_enabled, reactions_enabled, tripcode_algorithm, default_theme,
+                default_locale, category, overboard_hidden, public_log
+             ) VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$9,$10,$11,$12,$13,$14,$15,$16,$17,$18,$19,$20,$21,$22,$23,$24,$25,
+                       $26,$27,$28,$29,$30,$31,$32,$33,$34,$35,$36,$37,$38,$39,$40,$41,$42,$43,$44,$45,$46,$47)
              ON CONFLICT (board_id) DO UPDATE SET
                 bump_limit = EXCLUDED.bump_limit,
                 max_threads = EXCLUDED.max_threads,
@@ -260,7 +357,31 @@ impl BoardRepository for PgBoardRepository {
                 archive_enabled = EXCLUDED.archive_enabled,
                 federation_enabled = EXCLUDED.federation_enabled,
                 link_blacklist = EXCLUDED.link_blacklist,
-                name_rate_limit_window_secs = EXCLUDED.name_rate_limit_window_secs"
+                name_rate_limit_window_secs = EXCLUDED.name_rate_limit_window_secs,
+                dnsbl_action = EXCLUDED.dnsbl_action,
+                strip_metadata = EXCLUDED.strip_metadata,
+                thumbnail_max_width = EXCLUDED.thumbnail_max_width,
+                thumbnail_max_height = EXCLUDED.thumbnail_max_height,
+                thumbnail_format = EXCLUDED.thumbnail_format,
+                thumbnail_quality = EXCLUDED.thumbnail_quality,
+                classifier_action = EXCLUDED.classifier_action,
+                classifier_threshold = EXCLUDED.classifier_threshold,
+                media_quota_mb = EXCLUDED.media_quota_mb,
+                quota_action = EXCLUDED.quota_action,
+                markup_enabled = EXCLUDED.markup_enabled,
+                spoilers_enabled = EXCLUDED.spoilers_enabled,
+                code_enabled = EXCLUDED.code_enabled,
+                greentext_enabled = EXCLUDED.greentext_enabled,
+                code_highlighting = EXCLUDED.code_highlighting,
+                commands_enabled = EXCLUDED.commands_enabled,
+                polls_enabled = EXCLUDED.polls_enabled,
+                reactions_enabled = EXCLUDED.reactions_enabled,
+                tripcode_algorithm = EXCLUDED.tripcode_algorithm,
+                default_theme = EXCLUDED.default_theme,
+                default_locale = EXCLUDED.default_locale,
+                category = EXCLUDED.category,
+                overboard_hidden = EXCLUDED.overboard_hidden,
+                public_log = EXCLUDED.public_log"
         )
         .bind(board_id.0)
         .bind(config.bump_limit as i32)
@@ -285,12 +406,55 @@ impl BoardRepository for PgBoardRepository {
         .bind(config.federation_enabled)
         .bind(&config.link_blacklist)
         .bind(config.name_rate_limit_window_secs as i32)
+        .bind(config.dnsbl_action.to_string())
+        .bind(config.strip_metadata)
+        .bind(config.thumbnail.map(|t| t.max_width as i32))
+        .bind(config.thumbnail.map(|t| t.max_height as i32))
+        .bind(config.thumbnail.map(|t| t.format.to_string()))
+        .bind(config.thumbnail.map(|t| t.quality as i16))
+        .bind(config.classifier_action.to_string())
+        .bind(config.classifier_threshold)
+        .bind(config.media_quota_mb as i32)
+        .bind(config.quota_action.to_string())
+        .bind(config.markup_enabled)
+        .bind(config.spoilers_enabled)
+        .bind(config.code_enabled)
+        .bind(config.greentext_enabled)
+        .bind(config.code_highlighting)
+        .bind(config.commands_enabled)
+        .bind(config.polls_enabled)
+        .bind(config.reactions_enabled)
+        .bind(config.tripcode_algorithm.to_string())
+        .bind(config.default_theme.to_string())
+        .bind(config.default_locale.to_string())
+        .bind(&config.category)
+        .bind(config.overboard_hidden)
+        .bind(config.public_log)
         .execute(&self.pool)
         .await
         .map_err(|e| DomainError::internal(e.to_string()))?;
         Ok(())
     }
 
+    #[instrument(skip(self), fields(board_id = %board_id))]
+    async fn media_usage_bytes(&self, board_id: BoardId) -> Result<u64, DomainError> {
+        // Deduplicated uploads share a media_key; count each stored file once.
+        // Rows from before migration 023 have no exact size, so fall back to size_kb.
+        let bytes: i64 = sqlx::query_scalar(
+            "SELECT COALESCE(SUM(bytes), 0)::BIGINT FROM (
+                 SELECT DISTINCT ON (a.media_key) COALESCE(a.size_bytes, a.size_kb::BIGINT * 1024) AS bytes
+                 FROM attachments a
+                 JOIN posts p   ON p.id = a.post_id
+                 JOIN threads t ON t.id = p.thread_id
+                 WHERE t.board_id = $1
+             ) files"
+        )
+        .bind(board_id.0)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+        Ok(bytes.max(0) as u64)
+    }
 }
 
 #[async_trait]
@@ -0,0 +1,46 @@
+//! PostgreSQL implementation of `ExternalIdentityRepository`.
+//! Uses runtime sqlx queries — no query! macros.
+
+use async_trait::async_trait;
+use domains::errors::DomainError;
+use domains::models::UserId;
+use domains::ports::ExternalIdentityRepository;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// PostgreSQL-backed `ExternalIdentityRepository`.
+#[derive(Clone)]
+pub struct PgExternalIdentityRepository {
+    pool: PgPool,
+}
+
+impl PgExternalIdentityRepository {
+    /// Construct a `PgExternalIdentityRepository` backed by the given connection pool.
+    pub fn new(pool: PgPool) -> Self { Self { pool } }
+}
+
+#[async_trait]
+impl ExternalIdentityRepository for PgExternalIdentityRepository {
+    async fn find_user(&self, issuer: &str, subject: &str) -> Result<Option<UserId>, DomainError> {
+        let user_id = sqlx::query_scalar::<_, Uuid>(
+            "SELECT user_id FROM external_identities WHERE issuer = $1 AND subject = $2"
+        )
+        .bind(issuer)
+        .bind(subject)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+        Ok(user_id.map(UserId))
+    }
+
+    async fn link(&self, issuer: &str, subject: &str, user_id: UserId) -> Result<(), DomainError> {
+        sqlx::query("INSERT INTO external_identities (issuer, subject, user_id) VALUES ($1, $2, $3)")
+            .bind(issuer)
+            .bind(subject)
+            .bind(user_id.0)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| DomainError::internal(e.to_string()))?;
+        Ok(())
+    }
+}
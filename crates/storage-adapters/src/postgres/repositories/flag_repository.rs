@@ -131,4 +131,15 @@ impl FlagRepository for PgFlagRepository {
         }
         Ok(())
     }
+
+    async fn count_pending_for_post(&self, post_id: PostId) -> Result<u64, DomainError> {
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM flags WHERE post_id = $1 AND status = 'pending'"
+        )
+        .bind(post_id.0)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+        Ok(count as u64)
+    }
 }
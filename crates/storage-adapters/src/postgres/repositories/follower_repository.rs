@@ -0,0 +1,82 @@
+//! PostgreSQL implementation of `FollowerRepository`.
+//! Uses runtime sqlx queries — no query! macros.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domains::errors::DomainError;
+use domains::models::{BoardId, RemoteFollower};
+use domains::ports::FollowerRepository;
+use sqlx::PgPool;
+use tracing::instrument;
+use uuid::Uuid;
+
+/// PostgreSQL-backed `FollowerRepository`.
+#[derive(Clone)]
+pub struct PgFollowerRepository {
+    pool: PgPool,
+}
+
+impl PgFollowerRepository {
+    /// Construct a `PgFollowerRepository` backed by the given connection pool.
+    pub fn new(pool: PgPool) -> Self { Self { pool } }
+}
+
+#[derive(sqlx::FromRow)]
+struct FollowerRow {
+    board_id:   Uuid,
+    actor_id:   String,
+    inbox:      String,
+    created_at: DateTime<Utc>,
+}
+
+#[async_trait]
+impl FollowerRepository for PgFollowerRepository {
+    #[instrument(skip(self, follower), fields(board_id = %follower.board_id))]
+    async fn save(&self, follower: &RemoteFollower) -> Result<(), DomainError> {
+        sqlx::query(
+            "INSERT INTO remote_followers (board_id, actor_id, inbox, created_at)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (board_id, actor_id) DO UPDATE SET inbox = EXCLUDED.inbox"
+        )
+        .bind(follower.board_id.0)
+        .bind(&follower.actor_id)
+        .bind(&follower.inbox)
+        .bind(follower.created_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+        Ok(())
+    }
+
+    #[instrument(skip(self), fields(board_id = %board_id))]
+    async fn delete(&self, board_id: BoardId, actor_id: &str) -> Result<(), DomainError> {
+        sqlx::query("DELETE FROM remote_followers WHERE board_id = $1 AND actor_id = $2")
+            .bind(board_id.0)
+            .bind(actor_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| DomainError::internal(e.to_string()))?;
+        Ok(())
+    }
+
+    #[instrument(skip(self), fields(board_id = %board_id))]
+    async fn find_by_board(&self, board_id: BoardId) -> Result<Vec<RemoteFollower>, DomainError> {
+        let rows = sqlx::query_as::<_, FollowerRow>(
+            "SELECT board_id, actor_id, inbox, created_at FROM remote_followers
+             WHERE board_id = $1 ORDER BY created_at"
+        )
+        .bind(board_id.0)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+        Ok(rows
+            .into_iter()
+            .map(|r| RemoteFollower {
+                board_id:   BoardId(r.board_id),
+                actor_id:   r.actor_id,
+                inbox:      r.inbox,
+                created_at: r.created_at,
+            })
+            .collect())
+    }
+}
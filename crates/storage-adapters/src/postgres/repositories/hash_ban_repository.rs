@@ -0,0 +1,132 @@
+//! PostgreSQL implementation of `HashBanRepository`.
+//! Uses runtime sqlx queries — no query! macros.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domains::errors::DomainError;
+use domains::models::{BanId, HashBan, Page, Paginated, UserId};
+use domains::ports::HashBanRepository;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// PostgreSQL-backed `HashBanRepository`.
+#[derive(Clone)]
+pub struct PgHashBanRepository {
+    pool: PgPool,
+}
+
+impl PgHashBanRepository {
+    /// Construct a `PgHashBanRepository` backed by the given connection pool.
+    pub fn new(pool: PgPool) -> Self { Self { pool } }
+}
+
+#[derive(sqlx::FromRow)]
+struct HashBanRow {
+    id:         Uuid,
+    md5:        Option<String>,
+    phash:      Option<i64>,
+    banned_by:  Uuid,
+    reason:     String,
+    expires_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+}
+
+fn hash_ban_from_row(r: HashBanRow) -> HashBan {
+    HashBan {
+        id:         BanId(r.id),
+        md5:        r.md5,
+        // Stored as the signed bit pattern; BIGINT has no unsigned form.
+        phash:      r.phash.map(|h| h as u64),
+        banned_by:  UserId(r.banned_by),
+        reason:     r.reason,
+        expires_at: r.expires_at,
+        created_at: r.created_at,
+    }
+}
+
+#[async_trait]
+impl HashBanRepository for PgHashBanRepository {
+    async fn find_active_by_md5(&self, md5: &str) -> Result<Option<HashBan>, DomainError> {
+        let row = sqlx::query_as::<_, HashBanRow>(
+            "SELECT id, md5, phash, banned_by, reason, expires_at, created_at
+             FROM hash_bans
+             WHERE md5 = $1
+               AND (expires_at IS NULL OR expires_at > now())
+             ORDER BY created_at DESC LIMIT 1"
+        )
+        .bind(md5)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+        Ok(row.map(hash_ban_from_row))
+    }
+
+    async fn find_active_perceptual(&self) -> Result<Vec<HashBan>, DomainError> {
+        let rows = sqlx::query_as::<_, HashBanRow>(
+            "SELECT id, md5, phash, banned_by, reason, expires_at, created_at
+             FROM hash_bans
+             WHERE phash IS NOT NULL
+               AND (expires_at IS NULL OR expires_at > now())"
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+        Ok(rows.into_iter().map(hash_ban_from_row).collect())
+    }
+
+    async fn save(&self, ban: &HashBan) -> Result<BanId, DomainError> {
+        sqlx::query(
+            "INSERT INTO hash_bans (id, md5, phash, banned_by, reason, expires_at, created_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)"
+        )
+        .bind(ban.id.0)
+        .bind(ban.md5.as_deref())
+        .bind(ban.phash.map(|h| h as i64))
+        .bind(ban.banned_by.0)
+        .bind(&ban.reason)
+        .bind(ban.expires_at)
+        .bind(ban.created_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+        Ok(ban.id)
+    }
+
+    async fn expire(&self, id: BanId) -> Result<(), DomainError> {
+        let result = sqlx::query(
+            "UPDATE hash_bans SET expires_at = now() WHERE id = $1"
+        )
+        .bind(id.0)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+        if result.rows_affected() == 0 {
+            return Err(DomainError::not_found(id.to_string()));
+        }
+        Ok(())
+    }
+
+    async fn find_all(&self, page: Page) -> Result<Paginated<HashBan>, DomainError> {
+        let page_size = Page::DEFAULT_PAGE_SIZE;
+        let offset = page.offset(page_size) as i64;
+        let limit  = page_size as i64;
+
+        let rows = sqlx::query_as::<_, HashBanRow>(
+            "SELECT id, md5, phash, banned_by, reason, expires_at, created_at \
+             FROM hash_bans ORDER BY created_at DESC LIMIT $1 OFFSET $2"
+        )
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+
+        let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM hash_bans")
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| DomainError::internal(e.to_string()))?;
+
+        let items = rows.into_iter().map(hash_ban_from_row).collect();
+        Ok(Paginated::new(items, total as u64, page, page_size))
+    }
+}
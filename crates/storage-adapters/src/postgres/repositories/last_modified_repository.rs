@@ -0,0 +1,119 @@
+//! PostgreSQL implementation of `LastModifiedRepository`.
+//! Uses runtime sqlx queries — no query! macros.
+//!
+//! The columns read here are maintained by the triggers of migration 055.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domains::errors::DomainError;
+use domains::models::{BoardId, ThreadId};
+use domains::ports::LastModifiedRepository;
+use sqlx::PgPool;
+
+/// PostgreSQL-backed `LastModifiedRepository`.
+#[derive(Clone)]
+pub struct PgLastModifiedRepository {
+    pool: PgPool,
+}
+
+impl PgLastModifiedRepository {
+    /// Construct a `PgLastModifiedRepository` backed by the given connection pool.
+    pub fn new(pool: PgPool) -> Self { Self { pool } }
+}
+
+#[async_trait]
+impl LastModifiedRepository for PgLastModifiedRepository {
+    async fn board_modified_at(&self, board_id: BoardId) -> Result<Option<DateTime<Utc>>, DomainError> {
+        sqlx::query_scalar("SELECT modified_at FROM boards WHERE id = $1")
+            .bind(board_id.0)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| DomainError::internal(e.to_string()))
+    }
+
+    async fn thread_modified_at(
+        &self,
+        board_id: BoardId,
+        thread_id: ThreadId,
+    ) -> Result<Option<DateTime<Utc>>, DomainError> {
+        sqlx::query_scalar(
+            "SELECT GREATEST(t.modified_at, b.settings_modified_at)
+             FROM threads t JOIN boards b ON b.id = t.board_id
+             WHERE t.id = $1 AND t.board_id = $2"
+        )
+        .bind(thread_id.0)
+        .bind(board_id.0)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))
+    }
+}
+
+/// Run against the migrated database in `TEST_DATABASE_URL`; each test
+/// passes without running when it is unset.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    async fn pool() -> Option<PgPool> {
+        let url = std::env::var("TEST_DATABASE_URL").ok()?;
+        Some(PgPool::connect(&url).await.expect("TEST_DATABASE_URL"))
+    }
+
+    #[tokio::test]
+    async fn shadowbans_move_the_pages_of_their_posts() {
+        let Some(pool) = pool().await else { return };
+        let repo = PgLastModifiedRepository::new(pool.clone());
+        let tag = Uuid::new_v4().simple().to_string();
+        let board_id: Uuid = sqlx::query_scalar("INSERT INTO boards (slug, title) VALUES ($1, 'test') RETURNING id")
+            .bind(&tag[..16])
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let thread_id: Uuid = sqlx::query_scalar("INSERT INTO threads (board_id) VALUES ($1) RETURNING id")
+            .bind(board_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO posts (thread_id, body, ip_hash, post_number) VALUES ($1, 'spam', $2, 1)")
+            .bind(thread_id)
+            .bind(format!("shadow-{tag}"))
+            .execute(&pool)
+            .await
+            .unwrap();
+        let user_id: Uuid = sqlx::query_scalar(
+            "INSERT INTO users (username, password_hash, role) VALUES ($1, 'x', 'admin') RETURNING id",
+        )
+        .bind(&tag[..16])
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        let (board, thread) = (BoardId(board_id), ThreadId(thread_id));
+        let stamps = || async {
+            (
+                repo.board_modified_at(board).await.unwrap().unwrap(),
+                repo.thread_modified_at(board, thread).await.unwrap().unwrap(),
+            )
+        };
+
+        let before = stamps().await;
+        let ban_id: Uuid = sqlx::query_scalar(
+            "INSERT INTO bans (ip_hash, reason, banned_by, shadow) VALUES ($1, 'spam', $2, true) RETURNING id",
+        )
+        .bind(format!("shadow-{tag}"))
+        .bind(user_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        let issued = stamps().await;
+        sqlx::query("UPDATE bans SET expires_at = now() WHERE id = $1").bind(ban_id).execute(&pool).await.unwrap();
+        let lifted = stamps().await;
+
+        sqlx::query("DELETE FROM boards WHERE id = $1").bind(board_id).execute(&pool).await.unwrap();
+        sqlx::query("DELETE FROM bans WHERE banned_by = $1").bind(user_id).execute(&pool).await.unwrap();
+        sqlx::query("DELETE FROM users WHERE id = $1").bind(user_id).execute(&pool).await.unwrap();
+        assert!(issued.0 > before.0 && issued.1 > before.1, "{before:?} {issued:?}");
+        assert!(lifted.0 > issued.0 && lifted.1 > issued.1, "{issued:?} {lifted:?}");
+    }
+}
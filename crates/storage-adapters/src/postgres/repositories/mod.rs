@@ -1,24 +1,56 @@
 //! PostgreSQL implementations of all domain repository ports.
 
+pub mod announcement_repository;
+pub mod api_key_repository;
 pub mod archive_repository;
+pub mod asn_ban_repository;
 pub mod audit_repository;
 pub mod ban_repository;
+pub mod banner_repository;
 pub mod board_repository;
+pub mod external_identity_repository;
 pub mod flag_repository;
+pub mod follower_repository;
+pub mod hash_ban_repository;
+pub mod last_modified_repository;
+pub mod poll_repository;
 pub mod post_repository;
+pub mod reaction_repository;
 pub mod session_repository;
+pub mod site_repository;
 pub mod staff_message_repository;
 pub mod staff_request_repository;
+pub mod static_page_repository;
+pub mod stats_repository;
 pub mod thread_repository;
+pub mod totp_repository;
 pub mod user_repository;
+pub mod warning_repository;
+pub mod watch_repository;
 
+pub use announcement_repository::PgAnnouncementRepository;
+pub use api_key_repository::PgApiKeyRepository;
+pub use asn_ban_repository::PgAsnBanRepository;
 pub use audit_repository::PgAuditRepository;
 pub use ban_repository::PgBanRepository;
+pub use banner_repository::PgBannerRepository;
 pub use board_repository::PgBoardRepository;
+pub use external_identity_repository::PgExternalIdentityRepository;
 pub use flag_repository::PgFlagRepository;
+pub use follower_repository::PgFollowerRepository;
+pub use hash_ban_repository::PgHashBanRepository;
+pub use last_modified_repository::PgLastModifiedRepository;
+pub use poll_repository::PgPollRepository;
 pub use post_repository::PgPostRepository;
+pub use reaction_repository::PgReactionRepository;
 pub use session_repository::PgSessionRepository;
+pub use site_repository::PgSiteRepository;
 pub use staff_message_repository::PgStaffMessageRepository;
 pub use staff_request_repository::PgStaffRequestRepository;
+pub use static_page_repository::PgStaticPageRepository;
+pub use stats_repository::PgStatsRepository;
 pub use thread_repository::PgThreadRepository;
+pub use totp_repository::PgTotpRepository;
 pub use user_repository::PgUserRepository;
+pub use warning_repository::PgWarningRepository;
+pub use watch_repository::PgWatchRepository;
@@ -0,0 +1,114 @@
+//! PostgreSQL implementation of `PollRepository`.
+//! Uses runtime sqlx queries — no query! macros.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domains::errors::{DomainError, ValidationError};
+use domains::models::{IpHash, Poll, PollOption, ThreadId};
+use domains::ports::PollRepository;
+use sqlx::PgPool;
+use tracing::instrument;
+
+/// PostgreSQL-backed `PollRepository`.
+#[derive(Clone)]
+pub struct PgPollRepository {
+    pool: PgPool,
+}
+
+impl PgPollRepository {
+    /// Construct a `PgPollRepository` backed by the given connection pool.
+    pub fn new(pool: PgPool) -> Self { Self { pool } }
+}
+
+#[derive(sqlx::FromRow)]
+struct PollRow {
+    question:   String,
+    options:    Vec<String>,
+    created_at: DateTime<Utc>,
+}
+
+#[async_trait]
+impl PollRepository for PgPollRepository {
+    #[instrument(skip(self, poll), fields(thread_id = %poll.thread_id))]
+    async fn save(&self, poll: &Poll) -> Result<(), DomainError> {
+        let options: Vec<&str> = poll.options.iter().map(|o| o.text.as_str()).collect();
+        sqlx::query("INSERT INTO polls (thread_id, question, options, created_at) VALUES ($1, $2, $3, $4)")
+            .bind(poll.thread_id.0)
+            .bind(&poll.question)
+            .bind(&options)
+            .bind(poll.created_at)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| match e.as_database_error().and_then(|d| d.code()).as_deref() {
+                Some("23505") => DomainError::Validation(ValidationError::InvalidContent {
+                    field:  "poll".to_owned(),
+                    reason: "the thread already has a poll".to_owned(),
+                }),
+                _ => DomainError::internal(e.to_string()),
+            })?;
+        Ok(())
+    }
+
+    #[instrument(skip(self), fields(thread_id = %thread_id))]
+    async fn find_by_thread(&self, thread_id: ThreadId) -> Result<Option<Poll>, DomainError> {
+        let Some(row) = sqlx::query_as::<_, PollRow>(
+            "SELECT question, options, created_at FROM polls WHERE thread_id = $1"
+        )
+        .bind(thread_id.0)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))? else {
+            return Ok(None);
+        };
+
+        let counts: Vec<(i16, i64)> = sqlx::query_as(
+            "SELECT option_index, COUNT(*) FROM poll_votes WHERE thread_id = $1 GROUP BY option_index"
+        )
+        .bind(thread_id.0)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+
+        let mut options: Vec<PollOption> =
+            row.options.into_iter().map(|text| PollOption { text, votes: 0 }).collect();
+        for (index, count) in counts {
+            if let Some(option) = options.get_mut(index as usize) {
+                option.votes = count as u64;
+            }
+        }
+        Ok(Some(Poll { thread_id, question: row.question, options, created_at: row.created_at }))
+    }
+
+    #[instrument(skip(self, voter), fields(thread_id = %thread_id, option = option))]
+    async fn vote(&self, thread_id: ThreadId, option: usize, voter: &IpHash) -> Result<bool, DomainError> {
+        let option_count: Option<i32> =
+            sqlx::query_scalar("SELECT cardinality(options) FROM polls WHERE thread_id = $1")
+                .bind(thread_id.0)
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| DomainError::internal(e.to_string()))?;
+        let Some(option_count) = option_count else {
+            return Err(DomainError::not_found(format!("poll in thread {thread_id}")));
+        };
+        if option >= option_count as usize {
+            return Err(DomainError::Validation(ValidationError::ValueOutOfRange {
+                field:  "option".to_owned(),
+                actual: option.to_string(),
+                min:    "0".to_owned(),
+                max:    (option_count - 1).to_string(),
+            }));
+        }
+
+        let result = sqlx::query(
+            "INSERT INTO poll_votes (thread_id, ip_hash, option_index) VALUES ($1, $2, $3)
+             ON CONFLICT (thread_id, ip_hash) DO NOTHING"
+        )
+        .bind(thread_id.0)
+        .bind(&voter.0)
+        .bind(option as i16)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+        Ok(result.rows_affected() == 1)
+    }
+}
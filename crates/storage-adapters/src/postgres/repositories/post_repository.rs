@@ -4,11 +4,22 @@
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use domains::errors::DomainError;
-use domains::models::{BoardId, ContentHash, IpHash, OverboardPost, Page, Paginated, Post, PostId, ThreadId};
+use domains::models::{
+    BoardId, ContentHash, DeletedBy, IpHash, OverboardPost, Page, Paginated, Post, PostId, PostMove, PostNumberRange, PostRevision,
+    PurgedPosts, SiteActivity, SiteId, ThreadId, Tombstone, UserId, Viewer,
+};
 use domains::ports::PostRepository;
 use sqlx::PgPool;
+use tracing::instrument;
 use uuid::Uuid;
 
+/// Matches a post `p` made from an IP hash under an active shadowban; public
+/// reads filter with `NOT` this.
+pub(crate) const SHADOWBANNED: &str =
+    "EXISTS (SELECT 1 FROM bans sb \
+             WHERE sb.shadow AND sb.ip_hash = p.ip_hash \
+               AND (sb.expires_at IS NULL OR sb.expires_at > now()))";
+
 /// PostgreSQL-backed `PostRepository`.
 #[derive(Clone)]
 pub struct PgPostRepository {
@@ -18,6 +29,133 @@ pub struct PgPostRepository {
 impl PgPostRepository {
     /// Construct a `PgPostRepository` backed by the given connection pool.
     pub fn new(pool: PgPool) -> Self { Self { pool } }
+
+    /// Posts across the site's boards, newest first, with their board's slug.
+    /// With `ip_hashes`, only the posts made from one of them.
+    async fn recent_posts(
+        &self,
+        site:      SiteId,
+        ip_hashes: Option<&[IpHash]>,
+        limit:     i64,
+        offset:    i64,
+    ) -> Result<Vec<OverboardPost>, DomainError> {
+        #[derive(sqlx::FromRow)]
+        struct OverboardRow {
+            id:          Uuid,
+            thread_id:   Uuid,
+            board_slug:  String,
+            body:        String,
+            name:        Option<String>,
+            tripcode:    Option<String>,
+            ip_hash:     String,
+            created_at:  chrono::DateTime<chrono::Utc>,
+            post_number: i64,
+            subject:     Option<String>,
+            capcode:     Option<String>,
+        }
+
+        // A lookup by IP hash is staff's, so it sees shadowbanned and deleted
+        // posts too.
+        let rows = sqlx::query_as::<_, OverboardRow>(&format!(
+            "SELECT p.id, p.thread_id, b.slug AS board_slug, p.body, p.name, p.tripcode, p.ip_hash, p.created_at, \
+                    p.post_number, p.subject, p.capcode \
+             FROM posts p \
+             JOIN threads t ON t.id = p.thread_id \
+             JOIN boards  b ON b.id = t.board_id \
+             WHERE b.site_id = $1 \
+               AND ($4::text[] IS NULL OR p.ip_hash = ANY($4)) \
+               AND ($4::text[] IS NOT NULL OR (NOT {SHADOWBANNED} AND p.deleted_at IS NULL)) \
+             ORDER BY p.created_at DESC LIMIT $2 OFFSET $3"
+        ))
+        .bind(site.0)
+        .bind(limit)
+        .bind(offset)
+        .bind(ip_hashes.map(|hashes| hashes.iter().map(|h| h.0.clone()).collect::<Vec<_>>()))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+
+        Ok(rows.into_iter().map(|r| OverboardPost {
+            id:          PostId(r.id),
+            thread_id:   ThreadId(r.thread_id),
+            board_slug:  r.board_slug,
+            body:        r.body,
+            name:        r.name,
+            tripcode:    r.tripcode,
+            ip_hash:     IpHash(r.ip_hash),
+            created_at:  r.created_at,
+            post_number: r.post_number as u64,
+            subject:     r.subject,
+            capcode:     r.capcode,
+        }).collect())
+    }
+
+    /// Attachments with the board their post is on, narrowed by a static
+    /// `WHERE` clause over `attachments a`.
+    async fn attachments_with_board(
+        &self,
+        filter: &'static str,
+    ) -> Result<Vec<(BoardId, domains::models::Attachment)>, DomainError> {
+        #[derive(sqlx::FromRow)]
+        struct Row {
+            board_id:      Uuid,
+            id:            Uuid,
+            post_id:       Uuid,
+            filename:      String,
+            mime:          String,
+            hash:          String,
+            size_kb:       i32,
+            media_key:     String,
+            thumbnail_key: Option<String>,
+            spoiler:       bool,
+            duration_ms:   Option<i32>,
+            page_count:    Option<i32>,
+            md5:           Option<String>,
+            phash:         Option<i64>,
+            original_filename: Option<String>,
+            size_bytes:    Option<i64>,
+            width:         Option<i32>,
+            height:        Option<i32>,
+            original_mime: Option<String>,
+            labels:        Option<sqlx::types::Json<Vec<domains::models::MediaLabel>>>,
+        }
+        let rows = sqlx::query_as::<_, Row>(&format!(
+            "SELECT t.board_id, a.id, a.post_id, a.filename, a.mime, a.hash, a.size_kb, a.media_key, a.thumbnail_key,
+                    a.spoiler, a.duration_ms, a.page_count, a.md5, a.phash,
+                    a.original_filename, a.size_bytes, a.width, a.height, a.original_mime, a.labels
+             FROM attachments a
+             JOIN posts p ON p.id = a.post_id
+             JOIN threads t ON t.id = p.thread_id
+             {filter}
+             ORDER BY a.id ASC"
+        ))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+
+        use domains::models::{Attachment, ContentHash, MediaKey};
+        Ok(rows
+            .into_iter()
+            .map(|r| {
+                (BoardId(r.board_id), Attachment {
+                    id:            r.id,
+                    post_id:       PostId(r.post_id),
+                    filename:      r.filename,
+                    mime:          r.mime,
+                    hash:          ContentHash::new(r.hash),
+                    size_kb:       r.size_kb as u32,
+                    media_key:     MediaKey::new(r.media_key),
+                    thumbnail_key: r.thumbnail_key.map(MediaKey::new),
+                    spoiler:       r.spoiler,
+                    duration_ms:   r.duration_ms.map(|d| d as u32),
+                    page_count:    r.page_count.map(|n| n as u32),
+                    md5:           r.md5,
+                    phash:         r.phash.map(|h| h as u64),
+                    meta:          media_meta(r.original_filename, r.size_bytes, r.width, r.height, r.original_mime, r.labels),
+                })
+            })
+            .collect())
+    }
 }
 
 fn map_err(e: sqlx::Error, resource: impl Into<String>) -> DomainError {
@@ -39,9 +177,19 @@ struct PostRow {
     created_at:  DateTime<Utc>,
     post_number: i64,
     pinned:      bool,
+    tags:        Vec<String>,
+    commands:    sqlx::types::Json<Vec<domains::models::CommandResult>>,
+    subject:     Option<String>,
+    capcode:     Option<String>,
+    deleted_at:  Option<DateTime<Utc>>,
+    deleted_by:  Option<String>,
+    edited_at:   Option<DateTime<Utc>>,
 }
 
 fn post_from_row(r: PostRow) -> Post {
+    // The table's CHECK constraints keep the two columns set together.
+    let deleted = r.deleted_at.zip(r.deleted_by.and_then(|by| by.parse().ok()))
+        .map(|(at, by)| Tombstone { by, at });
     Post {
         id:          PostId(r.id),
         thread_id:   ThreadId(r.thread_id),
@@ -53,14 +201,22 @@ fn post_from_row(r: PostRow) -> Post {
         created_at:  r.created_at,
         post_number: r.post_number as u64,
         pinned:      r.pinned,
+        tags:        r.tags,
+        commands:    r.commands.0,
+        subject:     r.subject,
+        capcode:     r.capcode,
+        deleted,
+        edited_at:   r.edited_at,
     }
 }
 
 #[async_trait]
 impl PostRepository for PgPostRepository {
+    #[instrument(skip(self), fields(post_id = %id))]
     async fn find_by_id(&self, id: PostId) -> Result<Post, DomainError> {
         let row = sqlx::query_as::<_, PostRow>(
-            "SELECT id, thread_id, body, ip_hash, name, tripcode, email, created_at, post_number, pinned \
+            "SELECT id, thread_id, body, ip_hash, name, tripcode, email, created_at, post_number, pinned, tags, \
+                    commands, subject, capcode, deleted_at, deleted_by, edited_at \
              FROM posts WHERE id = $1"
         )
         .bind(id.0)
@@ -70,13 +226,15 @@ impl PostRepository for PgPostRepository {
         Ok(post_from_row(row))
     }
 
+    #[instrument(skip(self), fields(thread_id = %thread_id, page = page.0))]
     async fn find_by_thread(&self, thread_id: ThreadId, page: Page) -> Result<Paginated<Post>, DomainError> {
         let page_size = Page::DEFAULT_PAGE_SIZE;
         let offset = page.offset(page_size) as i64;
         let limit  = page_size as i64;
 
         let rows = sqlx::query_as::<_, PostRow>(
-            "SELECT id, thread_id, body, ip_hash, name, tripcode, email, created_at, post_number, pinned \
+            "SELECT id, thread_id, body, ip_hash, name, tripcode, email, created_at, post_number, pinned, tags, \
+                    commands, subject, capcode, deleted_at, deleted_by, edited_at \
              FROM posts WHERE thread_id = $1 \
              ORDER BY post_number ASC LIMIT $2 OFFSET $3"
         )
@@ -101,7 +259,8 @@ impl PostRepository for PgPostRepository {
 
     async fn find_by_ip_hash(&self, ip_hash: &IpHash) -> Result<Vec<Post>, DomainError> {
         let rows = sqlx::query_as::<_, PostRow>(
-            "SELECT id, thread_id, body, ip_hash, name, tripcode, email, created_at, post_number, pinned \
+            "SELECT id, thread_id, body, ip_hash, name, tripcode, email, created_at, post_number, pinned, tags, \
+                    commands, subject, capcode, deleted_at, deleted_by, edited_at \
              FROM posts WHERE ip_hash = $1 ORDER BY created_at DESC"
         )
         .bind(&ip_hash.0)
@@ -111,6 +270,32 @@ impl PostRepository for PgPostRepository {
         Ok(rows.into_iter().map(post_from_row).collect())
     }
 
+    #[instrument(skip(self, ip_hashes), fields(site_id = %site, hashes = ip_hashes.len()))]
+    async fn find_by_ip_hashes(
+        &self,
+        site:      SiteId,
+        ip_hashes: &[IpHash],
+        page:      Page,
+    ) -> Result<Paginated<OverboardPost>, DomainError> {
+        let page_size = Page::DEFAULT_PAGE_SIZE;
+        let items = self.recent_posts(site, Some(ip_hashes), page_size as i64, page.offset(page_size) as i64).await?;
+
+        let total: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM posts p \
+             JOIN threads t ON t.id = p.thread_id \
+             JOIN boards  b ON b.id = t.board_id \
+             WHERE b.site_id = $1 AND p.ip_hash = ANY($2)"
+        )
+        .bind(site.0)
+        .bind(ip_hashes.iter().map(|h| h.0.clone()).collect::<Vec<_>>())
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+
+        Ok(Paginated::new(items, total as u64, page, page_size))
+    }
+
+    #[instrument(skip(self), fields(board_id = %board_id, limit = limit))]
     async fn find_recent_hashes(&self, board_id: BoardId, limit: u32) -> Result<Vec<ContentHash>, DomainError> {
         #[derive(sqlx::FromRow)]
         struct BodyRow { body: String }
@@ -136,6 +321,7 @@ impl PostRepository for PgPostRepository {
         Ok(hashes)
     }
 
+    #[instrument(skip(self, post), fields(thread_id = %post.thread_id))]
     async fn save(&self, post: &Post) -> Result<(PostId, u64), DomainError> {
         let row: (Uuid, i64) = sqlx::query_as(
             "WITH board_cte AS (
@@ -147,8 +333,8 @@ impl PostRepository for PgPostRepository {
                  WHERE  id = (SELECT board_id FROM board_cte)
                  RETURNING post_counter
              )
-             INSERT INTO posts (id, thread_id, post_number, body, ip_hash, name, tripcode, email, pinned, created_at)
-             SELECT $1, $2, bump.post_counter, $3, $4, $5, $6, $7, $8, $9
+             INSERT INTO posts (id, thread_id, post_number, body, ip_hash, name, tripcode, email, pinned, created_at, tags, commands, subject, capcode)
+             SELECT $1, $2, bump.post_counter, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13
              FROM   bump
              RETURNING id, post_number"
         )
@@ -161,45 +347,277 @@ impl PostRepository for PgPostRepository {
         .bind(&post.email)
         .bind(post.pinned)
         .bind(post.created_at)
+        .bind(&post.tags)
+        .bind(sqlx::types::Json(&post.commands))
+        .bind(&post.subject)
+        .bind(&post.capcode)
         .fetch_one(&self.pool)
         .await
         .map_err(|e| DomainError::internal(e.to_string()))?;
         Ok((PostId(row.0), row.1 as u64))
     }
 
-    async fn delete(&self, id: PostId) -> Result<(), DomainError> {
-        let result = sqlx::query("DELETE FROM posts WHERE id = $1")
-            .bind(id.0)
-            .execute(&self.pool)
-            .await
-            .map_err(|e| DomainError::internal(e.to_string()))?;
+    #[instrument(skip(self), fields(post_id = %id, %by))]
+    async fn delete(&self, id: PostId, by: DeletedBy) -> Result<(), DomainError> {
+        let db = |e: sqlx::Error| DomainError::internal(e.to_string());
+        let mut tx = self.pool.begin().await.map_err(db)?;
+        let result = sqlx::query(
+            "UPDATE posts SET deleted_at = now(), deleted_by = $2 WHERE id = $1 AND deleted_at IS NULL"
+        )
+        .bind(id.0)
+        .bind(by.to_string())
+        .execute(&mut *tx)
+        .await
+        .map_err(db)?;
         if result.rows_affected() == 0 {
             return Err(DomainError::not_found(id.to_string()));
         }
-        Ok(())
+        sqlx::query("DELETE FROM attachments WHERE post_id = $1")
+            .bind(id.0)
+            .execute(&mut *tx)
+            .await
+            .map_err(db)?;
+        tx.commit().await.map_err(db)
     }
 
+    #[instrument(skip(self, ip_hash), fields(thread_id = %thread_id))]
     async fn delete_by_ip_in_thread(
         &self,
         ip_hash: &IpHash,
         thread_id: ThreadId,
     ) -> Result<u64, DomainError> {
-        let result = sqlx::query(
-            "DELETE FROM posts WHERE ip_hash = $1 AND thread_id = $2"
+        let db = |e: sqlx::Error| DomainError::internal(e.to_string());
+        let mut tx = self.pool.begin().await.map_err(db)?;
+        let deleted: Vec<Uuid> = sqlx::query_scalar(
+            "UPDATE posts SET deleted_at = now(), deleted_by = 'staff' \
+             WHERE ip_hash = $1 AND thread_id = $2 AND deleted_at IS NULL \
+             RETURNING id"
         )
         .bind(&ip_hash.0)
         .bind(thread_id.0)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(db)?;
+        if !deleted.is_empty() {
+            sqlx::query("DELETE FROM attachments WHERE post_id = ANY($1)")
+                .bind(&deleted)
+                .execute(&mut *tx)
+                .await
+                .map_err(db)?;
+        }
+        tx.commit().await.map_err(db)?;
+        Ok(deleted.len() as u64)
+    }
+
+    #[instrument(skip(self, ip_hash), fields(board_id = %board_id, since = %since))]
+    async fn delete_by_ip_on_board(
+        &self,
+        ip_hash:  &IpHash,
+        board_id: BoardId,
+        since:    DateTime<Utc>,
+    ) -> Result<PurgedPosts, DomainError> {
+        let db = |e: sqlx::Error| DomainError::internal(e.to_string());
+        let mut tx = self.pool.begin().await.map_err(db)?;
+
+        let matched: Vec<(Uuid, Uuid, bool)> = sqlx::query_as(
+            "SELECT p.id, p.thread_id, t.op_post_id IS NOT DISTINCT FROM p.id \
+             FROM posts p JOIN threads t ON t.id = p.thread_id \
+             WHERE t.board_id = $1 AND p.ip_hash = $2 AND p.created_at >= $3 AND p.deleted_at IS NULL \
+             FOR UPDATE OF p"
+        )
+        .bind(board_id.0)
+        .bind(&ip_hash.0)
+        .bind(since)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(db)?;
+
+        // An opening post cannot go without its thread, so those threads are
+        // removed whole, replies from other posters included.
+        let threads: Vec<Uuid> = matched.iter().filter(|(_, _, is_op)| *is_op).map(|(_, t, _)| *t).collect();
+        let replies: Vec<Uuid> = matched.iter().filter(|(_, t, _)| !threads.contains(t)).map(|(p, _, _)| *p).collect();
+
+        let mut posts: Vec<(Uuid, Uuid)> = if threads.is_empty() {
+            Vec::new()
+        } else {
+            sqlx::query_as("SELECT thread_id, id FROM posts WHERE thread_id = ANY($1)")
+                .bind(&threads)
+                .fetch_all(&mut *tx)
+                .await
+                .map_err(db)?
+        };
+        posts.extend(matched.iter().filter(|(p, _, _)| replies.contains(p)).map(|(p, t, _)| (*t, *p)));
+
+        let post_ids: Vec<Uuid> = posts.iter().map(|(_, p)| *p).collect();
+        let attachments = if post_ids.is_empty() { Vec::new() } else { attachments_of_posts(&mut *tx, &post_ids).await? };
+
+        if !threads.is_empty() {
+            sqlx::query("DELETE FROM threads WHERE id = ANY($1)")
+                .bind(&threads)
+                .execute(&mut *tx)
+                .await
+                .map_err(db)?;
+        }
+        if !replies.is_empty() {
+            sqlx::query("UPDATE posts SET deleted_at = now(), deleted_by = 'staff' WHERE id = ANY($1)")
+                .bind(&replies)
+                .execute(&mut *tx)
+                .await
+                .map_err(db)?;
+            sqlx::query("DELETE FROM attachments WHERE post_id = ANY($1)")
+                .bind(&replies)
+                .execute(&mut *tx)
+                .await
+                .map_err(db)?;
+        }
+        tx.commit().await.map_err(db)?;
+
+        Ok(PurgedPosts {
+            posts: posts.into_iter().map(|(t, p)| (ThreadId(t), PostId(p))).collect(),
+            threads: threads.into_iter().map(ThreadId).collect(),
+            attachments,
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn purge_deleted(&self, older_than_days: u32) -> Result<u32, DomainError> {
+        let result = sqlx::query(
+            "DELETE FROM posts p \
+             WHERE p.deleted_at < now() - make_interval(days => $1) \
+               AND NOT EXISTS (SELECT 1 FROM threads t WHERE t.op_post_id = p.id)"
+        )
+        .bind(older_than_days as i32)
         .execute(&self.pool)
         .await
         .map_err(|e| DomainError::internal(e.to_string()))?;
-        Ok(result.rows_affected())
+        Ok(result.rows_affected() as u32)
+    }
+
+    #[instrument(skip(self, body, subject), fields(post_id = %id, edited_by = %edited_by))]
+    async fn edit(
+        &self,
+        id:        PostId,
+        body:      &str,
+        subject:   Option<String>,
+        edited_by: UserId,
+    ) -> Result<(), DomainError> {
+        let db = |e: sqlx::Error| DomainError::internal(e.to_string());
+        let mut tx = self.pool.begin().await.map_err(db)?;
+        // Copy the current text out before it is replaced; the row lock keeps
+        // two concurrent edits from recording the same revision.
+        let saved = sqlx::query(
+            "INSERT INTO post_revisions (post_id, body, subject, edited_by) \
+             SELECT id, body, subject, $2 FROM posts \
+             WHERE id = $1 AND deleted_at IS NULL \
+             FOR UPDATE"
+        )
+        .bind(id.0)
+        .bind(edited_by.0)
+        .execute(&mut *tx)
+        .await
+        .map_err(db)?;
+        if saved.rows_affected() == 0 {
+            return Err(DomainError::not_found(id.to_string()));
+        }
+        sqlx::query("UPDATE posts SET body = $2, subject = $3, edited_at = now() WHERE id = $1")
+            .bind(id.0)
+            .bind(body)
+            .bind(subject)
+            .execute(&mut *tx)
+            .await
+            .map_err(db)?;
+        tx.commit().await.map_err(db)
     }
 
+    #[instrument(skip(self), fields(post_id = %id))]
+    async fn find_revisions(&self, id: PostId) -> Result<Vec<PostRevision>, DomainError> {
+        let rows: Vec<(Uuid, String, Option<String>, Uuid, DateTime<Utc>)> = sqlx::query_as(
+            "SELECT id, body, subject, edited_by, edited_at FROM post_revisions \
+             WHERE post_id = $1 ORDER BY edited_at DESC, id"
+        )
+        .bind(id.0)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+        Ok(rows
+            .into_iter()
+            .map(|(rev_id, body, subject, edited_by, edited_at)| PostRevision {
+                id: rev_id,
+                post_id: id,
+                body,
+                subject,
+                edited_by: UserId(edited_by),
+                edited_at,
+            })
+            .collect())
+    }
+
+    #[instrument(skip(self, post_move), fields(from = %post_move.from, to = %post_move.to, count = post_move.post_ids.len()))]
+    async fn move_posts(&self, post_move: &PostMove) -> Result<(), DomainError> {
+        let db = |e: sqlx::Error| DomainError::internal(e.to_string());
+        let mut tx = self.pool.begin().await.map_err(db)?;
+
+        if let Some(thread) = &post_move.new_thread {
+            sqlx::query(
+                "INSERT INTO threads (id, board_id, op_post_id, reply_count, bumped_at, sticky, closed, cycle, created_at) \
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)"
+            )
+            .bind(thread.id.0)
+            .bind(thread.board_id.0)
+            .bind(thread.op_post_id.map(|p| p.0))
+            .bind(thread.reply_count as i32)
+            .bind(thread.bumped_at)
+            .bind(thread.sticky)
+            .bind(thread.closed)
+            .bind(thread.cycle)
+            .bind(thread.created_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(db)?;
+        }
+
+        let ids: Vec<Uuid> = post_move.post_ids.iter().map(|p| p.0).collect();
+        let moved = sqlx::query("UPDATE posts SET thread_id = $1 WHERE id = ANY($2) AND thread_id = $3")
+            .bind(post_move.to.0)
+            .bind(&ids)
+            .bind(post_move.from.0)
+            .execute(&mut *tx)
+            .await
+            .map_err(db)?
+            .rows_affected();
+        if moved != ids.len() as u64 {
+            // Dropping `tx` rolls the move back.
+            return Err(DomainError::not_found(format!("posts of thread {}", post_move.from)));
+        }
+
+        for (id, body) in &post_move.bodies {
+            sqlx::query("UPDATE posts SET body = $2 WHERE id = $1")
+                .bind(id.0)
+                .bind(body)
+                .execute(&mut *tx)
+                .await
+                .map_err(db)?;
+        }
+
+        sqlx::query(
+            "UPDATE threads t SET reply_count = (SELECT COUNT(*) - 1 FROM posts p WHERE p.thread_id = t.id) \
+             WHERE t.id = ANY($1)"
+        )
+        .bind(vec![post_move.from.0, post_move.to.0])
+        .execute(&mut *tx)
+        .await
+        .map_err(db)?;
+
+        tx.commit().await.map_err(db)
+    }
+
+    #[instrument(skip(self, attachments), fields(count = attachments.len()))]
     async fn save_attachments(&self, attachments: &[domains::models::Attachment]) -> Result<(), DomainError> {
         for a in attachments {
             sqlx::query(
-                "INSERT INTO attachments (id, post_id, filename, mime, hash, size_kb, media_key, thumbnail_key, spoiler) \
-                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)"
+                "INSERT INTO attachments (id, post_id, filename, mime, hash, size_kb, media_key, thumbnail_key, spoiler, duration_ms, page_count, md5, phash, \
+                 original_filename, size_bytes, width, height, original_mime, labels) \
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19)"
             )
             .bind(a.id)
             .bind(a.post_id.0)
@@ -210,6 +628,19 @@ impl PostRepository for PgPostRepository {
             .bind(&a.media_key.0)
             .bind(a.thumbnail_key.as_ref().map(|k| &k.0))
             .bind(a.spoiler)
+            // INTEGER column: anything past ~24 days is clamped rather than
+            // wrapped negative, which the `duration_ms >= 0` check would refuse.
+            .bind(a.duration_ms.map(|d| i32::try_from(d).unwrap_or(i32::MAX)))
+            .bind(a.page_count.map(|n| n as i32))
+            .bind(a.md5.as_deref())
+            // Stored as the signed bit pattern; BIGINT has no unsigned form.
+            .bind(a.phash.map(|h| h as i64))
+            .bind(a.meta.as_ref().map(|m| &m.original_filename))
+            .bind(a.meta.as_ref().map(|m| m.size_bytes as i64))
+            .bind(a.meta.as_ref().and_then(|m| m.width).map(|w| w as i32))
+            .bind(a.meta.as_ref().and_then(|m| m.height).map(|h| h as i32))
+            .bind(a.meta.as_ref().and_then(|m| m.original_mime.as_deref()))
+            .bind(a.meta.as_ref().filter(|m| !m.labels.is_empty()).map(|m| sqlx::types::Json(&m.labels)))
             .execute(&self.pool)
             .await
             .map_err(|e| DomainError::internal(e.to_string()))?;
@@ -217,6 +648,7 @@ impl PostRepository for PgPostRepository {
         Ok(())
     }
 
+    #[instrument(skip(self, post_ids), fields(count = post_ids.len()))]
     async fn find_attachments_by_post_ids(
         &self,
         post_ids: &[PostId],
@@ -226,101 +658,64 @@ impl PostRepository for PgPostRepository {
         }
         let ids: Vec<Uuid> = post_ids.iter().map(|p| p.0).collect();
 
-        #[derive(sqlx::FromRow)]
-        struct AttachRow {
-            id:            Uuid,
-            post_id:       Uuid,
-            filename:      String,
-            mime:          String,
-            hash:          String,
-            size_kb:       i32,
-            media_key:     String,
-            thumbnail_key: Option<String>,
-            spoiler:       bool,
+        let mut map: std::collections::HashMap<PostId, Vec<domains::models::Attachment>> = std::collections::HashMap::new();
+        for a in attachments_of_posts(&self.pool, &ids).await? {
+            map.entry(a.post_id).or_default().push(a);
         }
+        Ok(map)
+    }
 
-        let rows = sqlx::query_as::<_, AttachRow>(
-            "SELECT id, post_id, filename, mime, hash, size_kb, media_key, thumbnail_key, spoiler \
-             FROM attachments WHERE post_id = ANY($1) ORDER BY id ASC"
-        )
-        .bind(&ids)
-        .fetch_all(&self.pool)
+    #[instrument(skip(self), fields(site_id = %site, page = page.0))]
+    async fn find_overboard(&self, site: SiteId, page: Page) -> Result<Paginated<OverboardPost>, DomainError> {
+        let page_size = Page::DEFAULT_PAGE_SIZE;
+        let items = self.recent_posts(site, None, page_size as i64, page.offset(page_size) as i64).await?;
+
+        let total: i64 = sqlx::query_scalar(&format!(
+            "SELECT COUNT(*) FROM posts p \
+             JOIN threads t ON t.id = p.thread_id \
+             JOIN boards  b ON b.id = t.board_id \
+             WHERE b.site_id = $1 AND NOT {SHADOWBANNED} AND p.deleted_at IS NULL"
+        ))
+        .bind(site.0)
+        .fetch_one(&self.pool)
         .await
         .map_err(|e| DomainError::internal(e.to_string()))?;
 
-        use domains::models::{Attachment, ContentHash, MediaKey};
-        let mut map: std::collections::HashMap<PostId, Vec<Attachment>> = std::collections::HashMap::new();
-        for r in rows {
-            let a = Attachment {
-                id:            r.id,
-                post_id:       PostId(r.post_id),
-                filename:      r.filename,
-                mime:          r.mime,
-                hash:          ContentHash::new(r.hash),
-                size_kb:       r.size_kb as u32,
-                media_key:     MediaKey::new(r.media_key),
-                thumbnail_key: r.thumbnail_key.map(MediaKey::new),
-                spoiler:       r.spoiler,
-            };
-            map.entry(PostId(r.post_id)).or_default().push(a);
-        }
-        Ok(map)
+        Ok(Paginated::new(items, total as u64, page, page_size))
     }
 
-    async fn find_overboard(&self, page: Page) -> Result<Paginated<OverboardPost>, DomainError> {
-        let page_size = Page::DEFAULT_PAGE_SIZE;
-        let offset = page.offset(page_size) as i64;
-        let limit  = page_size as i64;
-
-        #[derive(sqlx::FromRow)]
-        struct OverboardRow {
-            id:          Uuid,
-            thread_id:   Uuid,
-            board_slug:  String,
-            body:        String,
-            name:        Option<String>,
-            tripcode:    Option<String>,
-            ip_hash:     String,
-            created_at:  chrono::DateTime<chrono::Utc>,
-            post_number: i64,
-        }
+    #[instrument(skip(self), fields(site_id = %site, recent))]
+    async fn find_site_activity(&self, site: SiteId, recent: u32) -> Result<SiteActivity, DomainError> {
+        let recent_posts = self.recent_posts(site, None, i64::from(recent), 0).await?;
 
-        let rows = sqlx::query_as::<_, OverboardRow>(
-            "SELECT p.id, p.thread_id, b.slug AS board_slug, p.body, p.name, p.tripcode, p.ip_hash, p.created_at, p.post_number \
+        let (total_threads, total_posts, posts_last_day): (i64, i64, i64) = sqlx::query_as(
+            "SELECT (SELECT COUNT(*) FROM threads t JOIN boards b ON b.id = t.board_id WHERE b.site_id = $1), \
+                    COUNT(*), \
+                    COUNT(*) FILTER (WHERE p.created_at > now() - interval '1 day') \
              FROM posts p \
              JOIN threads t ON t.id = p.thread_id \
              JOIN boards  b ON b.id = t.board_id \
-             ORDER BY p.created_at DESC LIMIT $1 OFFSET $2"
+             WHERE b.site_id = $1"
         )
-        .bind(limit)
-        .bind(offset)
-        .fetch_all(&self.pool)
+        .bind(site.0)
+        .fetch_one(&self.pool)
         .await
         .map_err(|e| DomainError::internal(e.to_string()))?;
 
-        let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM posts")
-            .fetch_one(&self.pool)
-            .await
-            .map_err(|e| DomainError::internal(e.to_string()))?;
-
-        let items = rows.into_iter().map(|r| OverboardPost {
-            id:          PostId(r.id),
-            thread_id:   ThreadId(r.thread_id),
-            board_slug:  r.board_slug,
-            body:        r.body,
-            name:        r.name,
-            tripcode:    r.tripcode,
-            ip_hash:     IpHash(r.ip_hash),
-            created_at:  r.created_at,
-            post_number: r.post_number as u64,
-        }).collect();
-        Ok(Paginated::new(items, total as u64, page, page_size))
+        Ok(SiteActivity {
+            recent_posts,
+            total_threads:  total_threads as u64,
+            total_posts:    total_posts as u64,
+            posts_last_day: posts_last_day as u64,
+        })
     }
 
     /// Full-text search using PostgreSQL's `plainto_tsquery` against the GIN index on `posts.body`.
     ///
     /// Results are ranked by `ts_rank` descending. Scoped to a single board via the
-    /// `threads.board_id` join. Returns an empty page when no results match.
+    /// `threads.board_id` join; tombstones and shadowbanned posts are left out.
+    /// Returns an empty page when no results match.
+    #[instrument(skip(self, query), fields(board_id = %board_id, page = page.0))]
     async fn search_fulltext(
         &self,
         board_id: BoardId,
@@ -333,17 +728,20 @@ impl PostRepository for PgPostRepository {
 
         // Uses the GIN index on posts.body (created in migration 006).
         // `plainto_tsquery` safely handles user input without injection risk.
-        let rows = sqlx::query_as::<_, PostRow>(
+        let rows = sqlx::query_as::<_, PostRow>(&format!(
             "SELECT p.id, p.thread_id, p.body, p.ip_hash, p.name, p.tripcode, p.email,
-                    p.created_at, p.post_number
+                    p.created_at, p.post_number, p.pinned, p.tags, p.commands, p.subject, p.capcode,
+                    p.deleted_at, p.deleted_by, p.edited_at
              FROM   posts p
              JOIN   threads t ON t.id = p.thread_id
              WHERE  t.board_id = $1
+               AND  p.deleted_at IS NULL
+               AND  NOT {SHADOWBANNED}
                AND  to_tsvector('english', p.body) @@ plainto_tsquery('english', $2)
              ORDER  BY ts_rank(to_tsvector('english', p.body),
                                plainto_tsquery('english', $2)) DESC
              LIMIT $3 OFFSET $4",
-        )
+        ))
         .bind(board_id.0)
         .bind(query)
         .bind(limit)
@@ -352,13 +750,15 @@ impl PostRepository for PgPostRepository {
         .await
         .map_err(|e| DomainError::internal(e.to_string()))?;
 
-        let total: i64 = sqlx::query_scalar(
+        let total: i64 = sqlx::query_scalar(&format!(
             "SELECT COUNT(*)
              FROM   posts p
              JOIN   threads t ON t.id = p.thread_id
              WHERE  t.board_id = $1
+               AND  p.deleted_at IS NULL
+               AND  NOT {SHADOWBANNED}
                AND  to_tsvector('english', p.body) @@ plainto_tsquery('english', $2)",
-        )
+        ))
         .bind(board_id.0)
         .bind(query)
         .fetch_one(&self.pool)
@@ -369,9 +769,11 @@ impl PostRepository for PgPostRepository {
         Ok(Paginated::new(items, total as u64, page, page_size))
     }
 
+    #[instrument(skip(self), fields(thread_id = %thread_id))]
     async fn find_all_by_thread(&self, thread_id: ThreadId) -> Result<Vec<Post>, DomainError> {
         let rows = sqlx::query_as::<_, PostRow>(
-            "SELECT id, thread_id, body, ip_hash, name, tripcode, email, created_at, post_number, pinned
+            "SELECT id, thread_id, body, ip_hash, name, tripcode, email, created_at, post_number, pinned, tags,
+                    commands, subject, capcode, deleted_at, deleted_by, edited_at
              FROM   posts
              WHERE  thread_id = $1
              ORDER  BY post_number ASC
@@ -385,6 +787,36 @@ impl PostRepository for PgPostRepository {
         Ok(rows.into_iter().map(post_from_row).collect())
     }
 
+    #[instrument(skip(self, viewer), fields(thread_id = %thread_id))]
+    async fn find_visible_by_thread(&self, thread_id: ThreadId, viewer: &Viewer) -> Result<Vec<Post>, DomainError> {
+        let own = match viewer {
+            Viewer::Staff => return self.find_all_by_thread(thread_id).await,
+            Viewer::Poster(ip_hash) => Some(ip_hash.0.as_str()),
+            Viewer::Public => None,
+        };
+        let rows = sqlx::query_as::<_, PostRow>(&format!(
+            "SELECT id, thread_id, body, ip_hash, name, tripcode, email, created_at, post_number, pinned, tags,
+                    commands, subject, capcode, deleted_at, deleted_by, edited_at
+             FROM   posts p
+             WHERE  thread_id = $1
+               AND  (p.ip_hash = $2 OR NOT {SHADOWBANNED})
+             ORDER  BY post_number ASC
+             LIMIT  500",
+        ))
+        .bind(thread_id.0)
+        .bind(own)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+
+        Ok(rows.into_iter().map(|r| {
+            let mut post = post_from_row(r);
+            post.redact();
+            post
+        }).collect())
+    }
+
+    #[instrument(skip(self), fields(board_id = %board_id, post_number = post_number))]
     async fn find_thread_id_by_post_number(
         &self,
         board_id: BoardId,
@@ -409,6 +841,7 @@ impl PostRepository for PgPostRepository {
         Ok(row.map(|(id,)| ThreadId(id)))
     }
 
+    #[instrument(skip(self), fields(post_id = %id))]
     async fn set_pinned(&self, id: PostId, pinned: bool) -> Result<(), DomainError> {
         sqlx::query("UPDATE posts SET pinned = $2 WHERE id = $1")
             .bind(id.0)
@@ -419,29 +852,37 @@ impl PostRepository for PgPostRepository {
         Ok(())
     }
 
-    async fn find_oldest_unpinned_reply(
+    #[instrument(skip(self), fields(thread_id = %thread_id, keep))]
+    async fn find_cycle_excess(
         &self,
         thread_id: ThreadId,
-    ) -> Result<Option<PostId>, DomainError> {
-        // Returns the OP-excluded, non-pinned reply with the smallest post_number.
-        // The OP is the post with the minimum post_number in the thread.
-        let row: Option<(Uuid,)> = sqlx::query_as(
-            "SELECT id FROM posts
-             WHERE  thread_id = $1
-               AND  pinned = FALSE
-               AND  post_number > (
-                   SELECT MIN(post_number) FROM posts WHERE thread_id = $1
-               )
+        keep:      u32,
+    ) -> Result<Vec<PostId>, DomainError> {
+        // The OP is the post with the minimum post_number in the thread; every
+        // other post is a reply. Counts live replies rather than trusting
+        // threads.reply_count, which only grows.
+        let rows: Vec<(Uuid,)> = sqlx::query_as(
+            "WITH replies AS (
+                 SELECT id, pinned, post_number FROM posts
+                 WHERE  thread_id = $1
+                   AND  post_number > (
+                       SELECT MIN(post_number) FROM posts WHERE thread_id = $1
+                   )
+             )
+             SELECT id FROM replies
+             WHERE  pinned = FALSE
              ORDER  BY post_number ASC
-             LIMIT  1",
+             LIMIT  GREATEST((SELECT COUNT(*) FROM replies) - $2, 0)",
         )
         .bind(thread_id.0)
-        .fetch_optional(&self.pool)
+        .bind(i64::from(keep))
+        .fetch_all(&self.pool)
         .await
         .map_err(|e| DomainError::internal(e.to_string()))?;
-        Ok(row.map(|(id,)| PostId(id)))
+        Ok(rows.into_iter().map(|(id,)| PostId(id)).collect())
     }
 
+    #[instrument(skip(self, hash))]
     async fn find_attachment_by_hash(
         &self,
         hash: &domains::models::ContentHash,
@@ -457,9 +898,20 @@ impl PostRepository for PgPostRepository {
             media_key:     String,
             thumbnail_key: Option<String>,
             spoiler:       bool,
+            duration_ms:   Option<i32>,
+            page_count:    Option<i32>,
+            md5:           Option<String>,
+            phash:         Option<i64>,
+            original_filename: Option<String>,
+            size_bytes:    Option<i64>,
+            width:         Option<i32>,
+            height:        Option<i32>,
+            original_mime: Option<String>,
+            labels:        Option<sqlx::types::Json<Vec<domains::models::MediaLabel>>>,
         }
         let row = sqlx::query_as::<_, AttRow>(
-            "SELECT id, post_id, filename, mime, hash, size_kb, media_key, thumbnail_key, spoiler
+            "SELECT id, post_id, filename, mime, hash, size_kb, media_key, thumbnail_key, spoiler, duration_ms, page_count, md5, phash,
+                    original_filename, size_bytes, width, height, original_mime, labels
              FROM attachments WHERE hash = $1 LIMIT 1",
         )
         .bind(&hash.0)
@@ -477,9 +929,15 @@ impl PostRepository for PgPostRepository {
             media_key:     domains::models::MediaKey::new(r.media_key),
             thumbnail_key: r.thumbnail_key.map(domains::models::MediaKey::new),
             spoiler:       r.spoiler,
+            duration_ms:   r.duration_ms.map(|d| d as u32),
+            page_count:    r.page_count.map(|n| n as u32),
+            md5:           r.md5,
+            phash:         r.phash.map(|h| h as u64),
+            meta:          media_meta(r.original_filename, r.size_bytes, r.width, r.height, r.original_mime, r.labels),
         }))
     }
 
+    #[instrument(skip(self), fields(post_id = %id))]
     async fn delete_by_id(&self, id: PostId) -> Result<(), DomainError> {
         sqlx::query("DELETE FROM posts WHERE id = $1")
             .bind(id.0)
@@ -488,4 +946,450 @@ impl PostRepository for PgPostRepository {
             .map_err(|e| DomainError::internal(e.to_string()))?;
         Ok(())
     }
+
+    async fn find_referenced_media_keys(
+        &self,
+    ) -> Result<std::collections::HashSet<domains::models::MediaKey>, DomainError> {
+        let keys: Vec<String> = sqlx::query_scalar(
+            "SELECT media_key FROM attachments
+             UNION
+             SELECT thumbnail_key FROM attachments WHERE thumbnail_key IS NOT NULL",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+        Ok(keys.into_iter().map(domains::models::MediaKey::new).collect())
+    }
+
+    async fn find_thumbnailed_attachments(
+        &self,
+    ) -> Result<Vec<(BoardId, domains::models::Attachment)>, DomainError> {
+        self.attachments_with_board("WHERE a.thumbnail_key IS NOT NULL").await
+    }
+
+    async fn find_all_attachments(
+        &self,
+    ) -> Result<Vec<(BoardId, domains::models::Attachment)>, DomainError> {
+        self.attachments_with_board("").await
+    }
+
+    async fn find_media_mime(
+        &self,
+        media_key: &domains::models::MediaKey,
+    ) -> Result<Option<String>, DomainError> {
+        sqlx::query_scalar("SELECT mime FROM attachments WHERE media_key = $1 LIMIT 1")
+            .bind(&media_key.0)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| DomainError::internal(e.to_string()))
+    }
+
+    async fn set_thumbnail_key(
+        &self,
+        media_key: &domains::models::MediaKey,
+        thumbnail_key: &domains::models::MediaKey,
+    ) -> Result<u64, DomainError> {
+        let result = sqlx::query("UPDATE attachments SET thumbnail_key = $2 WHERE media_key = $1")
+            .bind(&media_key.0)
+            .bind(&thumbnail_key.0)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| DomainError::internal(e.to_string()))?;
+        Ok(result.rows_affected())
+    }
+
+    #[instrument(skip(self, post), fields(post_id = %post.id))]
+    async fn restore(&self, post: &Post) -> Result<(), DomainError> {
+        sqlx::query(
+            "WITH inserted AS (
+                 INSERT INTO posts (id, thread_id, post_number, body, ip_hash, name, tripcode, email, pinned, created_at, tags, commands, subject, capcode,
+                                    deleted_at, deleted_by, edited_at)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17)
+                 RETURNING thread_id
+             )
+             UPDATE boards
+             SET    post_counter = GREATEST(post_counter, $3)
+             WHERE  id = (SELECT t.board_id FROM threads t JOIN inserted i ON t.id = i.thread_id)"
+        )
+        .bind(post.id.0)
+        .bind(post.thread_id.0)
+        .bind(post.post_number as i64)
+        .bind(&post.body)
+        .bind(&post.ip_hash.0)
+        .bind(&post.name)
+        .bind(&post.tripcode)
+        .bind(&post.email)
+        .bind(post.pinned)
+        .bind(post.created_at)
+        .bind(&post.tags)
+        .bind(sqlx::types::Json(&post.commands))
+        .bind(&post.subject)
+        .bind(&post.capcode)
+        .bind(post.deleted.map(|t| t.at))
+        .bind(post.deleted.map(|t| t.by.to_string()))
+        .bind(post.edited_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+        Ok(())
+    }
+
+    #[instrument(skip(self), fields(board_id = %board_id))]
+    async fn find_number_range(&self, board_id: BoardId) -> Result<Option<PostNumberRange>, DomainError> {
+        let (low, high, count): (Option<i64>, Option<i64>, i64) = sqlx::query_as(
+            "SELECT MIN(p.post_number), MAX(p.post_number), COUNT(*)
+             FROM   posts p
+             JOIN   threads t ON t.id = p.thread_id
+             WHERE  t.board_id = $1",
+        )
+        .bind(board_id.0)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+
+        Ok(low.zip(high).map(|(low, high)| PostNumberRange {
+            low:   low as u64,
+            high:  high as u64,
+            count: count as u64,
+        }))
+    }
+
+    #[instrument(skip(self), fields(board_id = %board_id, first = first, last = last))]
+    async fn find_by_number_range(
+        &self,
+        board_id: BoardId,
+        first: u64,
+        last: u64,
+        limit: u32,
+    ) -> Result<Vec<Post>, DomainError> {
+        let rows = sqlx::query_as::<_, PostRow>(&format!(
+            "SELECT p.id, p.thread_id, p.body, p.ip_hash, p.name, p.tripcode, p.email, p.created_at,
+                    p.post_number, p.pinned, p.tags, p.commands, p.subject, p.capcode,
+                    p.deleted_at, p.deleted_by, p.edited_at
+             FROM   posts p
+             JOIN   threads t ON t.id = p.thread_id
+             WHERE  t.board_id = $1
+               AND  p.post_number BETWEEN $2 AND $3
+               AND  p.deleted_at IS NULL
+               AND  NOT {SHADOWBANNED}
+             ORDER  BY p.post_number ASC
+             LIMIT  $4",
+        ))
+        .bind(board_id.0)
+        .bind(first.min(i64::MAX as u64) as i64)
+        .bind(last.min(i64::MAX as u64) as i64)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+
+        Ok(rows.into_iter().map(post_from_row).collect())
+    }
+
+    #[instrument(skip(self), fields(post_id = %id))]
+    async fn find_visible_by_id(&self, id: PostId) -> Result<Option<Post>, DomainError> {
+        let row = sqlx::query_as::<_, PostRow>(&format!(
+            "SELECT p.id, p.thread_id, p.body, p.ip_hash, p.name, p.tripcode, p.email, p.created_at,
+                    p.post_number, p.pinned, p.tags, p.commands, p.subject, p.capcode,
+                    p.deleted_at, p.deleted_by, p.edited_at
+             FROM   posts p
+             WHERE  p.id = $1
+               AND  p.deleted_at IS NULL
+               AND  NOT {SHADOWBANNED}",
+        ))
+        .bind(id.0)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+        Ok(row.map(post_from_row))
+    }
+
+    #[instrument(skip(self, ids), fields(thread_id = %thread_id, count = ids.len()))]
+    async fn find_by_ids(&self, thread_id: ThreadId, ids: &[PostId]) -> Result<Vec<Post>, DomainError> {
+        let ids: Vec<Uuid> = ids.iter().map(|id| id.0).collect();
+        let rows = sqlx::query_as::<_, PostRow>(
+            "SELECT id, thread_id, body, ip_hash, name, tripcode, email, created_at, post_number, pinned, tags,
+                    commands, subject, capcode, deleted_at, deleted_by, edited_at
+             FROM   posts
+             WHERE  thread_id = $1 AND id = ANY($2)
+             ORDER  BY post_number ASC",
+        )
+        .bind(thread_id.0)
+        .bind(ids)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+
+        Ok(rows.into_iter().map(post_from_row).collect())
+    }
+
+    #[instrument(skip(self, post_numbers), fields(thread_id = %thread_id, count = post_numbers.len()))]
+    async fn find_by_numbers(&self, thread_id: ThreadId, post_numbers: &[u64]) -> Result<Vec<Post>, DomainError> {
+        let numbers: Vec<i64> = post_numbers.iter().map(|&n| n.min(i64::MAX as u64) as i64).collect();
+        let rows = sqlx::query_as::<_, PostRow>(
+            "SELECT id, thread_id, body, ip_hash, name, tripcode, email, created_at, post_number, pinned, tags,
+                    commands, subject, capcode, deleted_at, deleted_by, edited_at
+             FROM   posts
+             WHERE  thread_id = $1 AND post_number = ANY($2)
+             ORDER  BY post_number ASC",
+        )
+        .bind(thread_id.0)
+        .bind(numbers)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+
+        Ok(rows.into_iter().map(post_from_row).collect())
+    }
+
+    #[instrument(skip(self, post_numbers), fields(thread_id = %thread_id, count = post_numbers.len()))]
+    async fn find_quoting(&self, thread_id: ThreadId, post_numbers: &[u64]) -> Result<Vec<Post>, DomainError> {
+        if post_numbers.is_empty() {
+            return Ok(Vec::new());
+        }
+        // Digits only, so the pattern needs no escaping.
+        let numbers: Vec<String> = post_numbers.iter().map(u64::to_string).collect();
+        let pattern = format!(">>({})([^0-9]|$)", numbers.join("|"));
+        let rows = sqlx::query_as::<_, PostRow>(
+            "SELECT id, thread_id, body, ip_hash, name, tripcode, email, created_at, post_number, pinned, tags,
+                    commands, subject, capcode, deleted_at, deleted_by, edited_at
+             FROM   posts
+             WHERE  thread_id = $1 AND body ~ $2
+             ORDER  BY post_number ASC",
+        )
+        .bind(thread_id.0)
+        .bind(pattern)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+
+        Ok(rows.into_iter().map(post_from_row).collect())
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct AttachRow {
+    id:            Uuid,
+    post_id:       Uuid,
+    filename:      String,
+    mime:          String,
+    hash:          String,
+    size_kb:       i32,
+    media_key:     String,
+    thumbnail_key: Option<String>,
+    spoiler:       bool,
+    duration_ms:   Option<i32>,
+    page_count:    Option<i32>,
+    md5:           Option<String>,
+    phash:         Option<i64>,
+    original_filename: Option<String>,
+    size_bytes:    Option<i64>,
+    width:         Option<i32>,
+    height:        Option<i32>,
+    original_mime: Option<String>,
+    labels:        Option<sqlx::types::Json<Vec<domains::models::MediaLabel>>>,
+}
+
+/// Attachments of the posts in `post_ids`, on the pool or inside a transaction.
+async fn attachments_of_posts<'e, E: sqlx::PgExecutor<'e>>(
+    db:       E,
+    post_ids: &[Uuid],
+) -> Result<Vec<domains::models::Attachment>, DomainError> {
+    let rows = sqlx::query_as::<_, AttachRow>(
+        "SELECT id, post_id, filename, mime, hash, size_kb, media_key, thumbnail_key, spoiler, duration_ms, page_count, md5, phash, \
+                original_filename, size_bytes, width, height, original_mime, labels \
+         FROM attachments WHERE post_id = ANY($1) ORDER BY id ASC"
+    )
+    .bind(post_ids)
+    .fetch_all(db)
+    .await
+    .map_err(|e| DomainError::internal(e.to_string()))?;
+
+    use domains::models::{Attachment, ContentHash, MediaKey};
+    Ok(rows
+        .into_iter()
+        .map(|r| Attachment {
+            id:            r.id,
+            post_id:       PostId(r.post_id),
+            filename:      r.filename,
+            mime:          r.mime,
+            hash:          ContentHash::new(r.hash),
+            size_kb:       r.size_kb as u32,
+            media_key:     MediaKey::new(r.media_key),
+            thumbnail_key: r.thumbnail_key.map(MediaKey::new),
+            spoiler:       r.spoiler,
+            duration_ms:   r.duration_ms.map(|d| d as u32),
+            page_count:    r.page_count.map(|n| n as u32),
+            md5:           r.md5,
+            phash:         r.phash.map(|h| h as u64),
+            meta:          media_meta(r.original_filename, r.size_bytes, r.width, r.height, r.original_mime, r.labels),
+        })
+        .collect())
+}
+
+/// Rebuild `MediaMeta` from its attachment columns; `None` for rows written
+/// before migration 023.
+fn media_meta(
+    original_filename: Option<String>,
+    size_bytes: Option<i64>,
+    width: Option<i32>,
+    height: Option<i32>,
+    original_mime: Option<String>,
+    labels: Option<sqlx::types::Json<Vec<domains::models::MediaLabel>>>,
+) -> Option<domains::models::MediaMeta> {
+    Some(domains::models::MediaMeta {
+        original_filename: original_filename?,
+        size_bytes:        size_bytes? as u64,
+        width:             width.map(|w| w as u32),
+        height:            height.map(|h| h as u32),
+        original_mime,
+        labels:            labels.map(|l| l.0).unwrap_or_default(),
+    })
+}
+
+/// Run against the migrated database in `TEST_DATABASE_URL`; each test
+/// passes without running when it is unset.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn pool() -> Option<PgPool> {
+        let url = std::env::var("TEST_DATABASE_URL").ok()?;
+        Some(PgPool::connect(&url).await.expect("TEST_DATABASE_URL"))
+    }
+
+    /// A board whose post No.1 is from a shadowbanned IP hash and No.2 from
+    /// another; both mention "needle". Removed again by `drop_board`.
+    async fn board_with_a_shadowbanned_post(pool: &PgPool) -> (BoardId, Uuid) {
+        let tag = Uuid::new_v4().simple().to_string();
+        let board_id: Uuid = sqlx::query_scalar("INSERT INTO boards (slug, title) VALUES ($1, 'test') RETURNING id")
+            .bind(&tag[..16])
+            .fetch_one(pool)
+            .await
+            .unwrap();
+        let thread_id: Uuid = sqlx::query_scalar("INSERT INTO threads (board_id) VALUES ($1) RETURNING id")
+            .bind(board_id)
+            .fetch_one(pool)
+            .await
+            .unwrap();
+        for (number, ip_hash) in [(1_i64, format!("shadow-{tag}")), (2, format!("plain-{tag}"))] {
+            sqlx::query("INSERT INTO posts (thread_id, body, ip_hash, post_number) VALUES ($1, 'a needle here', $2, $3)")
+                .bind(thread_id)
+                .bind(ip_hash)
+                .bind(number)
+                .execute(pool)
+                .await
+                .unwrap();
+        }
+        let user_id: Uuid = sqlx::query_scalar(
+            "INSERT INTO users (username, password_hash, role) VALUES ($1, 'x', 'admin') RETURNING id",
+        )
+        .bind(&tag[..16])
+        .fetch_one(pool)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO bans (ip_hash, reason, banned_by, shadow) VALUES ($1, 'spam', $2, true)")
+            .bind(format!("shadow-{tag}"))
+            .bind(user_id)
+            .execute(pool)
+            .await
+            .unwrap();
+        (BoardId(board_id), user_id)
+    }
+
+    async fn drop_board(pool: &PgPool, (board_id, user_id): (BoardId, Uuid)) {
+        sqlx::query("DELETE FROM boards WHERE id = $1").bind(board_id.0).execute(pool).await.unwrap();
+        sqlx::query("DELETE FROM bans WHERE banned_by = $1").bind(user_id).execute(pool).await.unwrap();
+        sqlx::query("DELETE FROM users WHERE id = $1").bind(user_id).execute(pool).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn search_leaves_out_shadowbanned_posts() {
+        let Some(pool) = pool().await else { return };
+        let fixture = board_with_a_shadowbanned_post(&pool).await;
+        let repo = PgPostRepository::new(pool.clone());
+
+        let found = repo.search_fulltext(fixture.0, "needle", Page::new(1)).await.unwrap();
+        drop_board(&pool, fixture).await;
+        assert_eq!(found.items.iter().map(|p| p.post_number).collect::<Vec<_>>(), [2]);
+        assert_eq!(found.total, 1);
+    }
+
+    #[tokio::test]
+    async fn number_ranges_leave_out_shadowbanned_posts() {
+        let Some(pool) = pool().await else { return };
+        let fixture = board_with_a_shadowbanned_post(&pool).await;
+        let repo = PgPostRepository::new(pool.clone());
+
+        let range = repo.find_by_number_range(fixture.0, 1, 2, 10).await.unwrap();
+        let single = repo.find_by_number_range(fixture.0, 1, 1, 1).await.unwrap();
+        drop_board(&pool, fixture).await;
+        assert_eq!(range.iter().map(|p| p.post_number).collect::<Vec<_>>(), [2]);
+        assert!(single.is_empty());
+    }
+
+    #[tokio::test]
+    async fn lookups_by_id_leave_out_shadowbanned_and_deleted_posts() {
+        let Some(pool) = pool().await else { return };
+        let fixture = board_with_a_shadowbanned_post(&pool).await;
+        let repo = PgPostRepository::new(pool.clone());
+        let ids: Vec<Uuid> = sqlx::query_scalar(
+            "SELECT p.id FROM posts p JOIN threads t ON t.id = p.thread_id \
+             WHERE t.board_id = $1 ORDER BY p.post_number",
+        )
+        .bind(fixture.0 .0)
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        let (shadowbanned, plain) = (PostId(ids[0]), PostId(ids[1]));
+
+        let hidden = repo.find_visible_by_id(shadowbanned).await.unwrap();
+        let shown = repo.find_visible_by_id(plain).await.unwrap();
+        repo.delete(plain, DeletedBy::Staff).await.unwrap();
+        let deleted = repo.find_visible_by_id(plain).await.unwrap();
+        drop_board(&pool, fixture).await;
+        assert!(hidden.is_none());
+        assert_eq!(shown.map(|p| p.post_number), Some(2));
+        assert!(deleted.is_none());
+    }
+
+    #[tokio::test]
+    async fn selections_are_found_past_the_500th_post() {
+        let Some(pool) = pool().await else { return };
+        let fixture = board_with_a_shadowbanned_post(&pool).await;
+        let repo = PgPostRepository::new(pool.clone());
+        let thread_id: Uuid = sqlx::query_scalar("SELECT id FROM threads WHERE board_id = $1")
+            .bind(fixture.0 .0)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO posts (thread_id, body, ip_hash, post_number) \
+             SELECT $1, '>>' || (n - 1), 'h', n FROM generate_series(3, 600) AS n",
+        )
+        .bind(thread_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+        let ids: Vec<Uuid> = sqlx::query_scalar(
+            "SELECT id FROM posts WHERE thread_id = $1 AND post_number IN (2, 599) ORDER BY post_number",
+        )
+        .bind(thread_id)
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        let thread = ThreadId(thread_id);
+
+        let selected = repo.find_by_ids(thread, &[PostId(ids[1]), PostId(ids[0]), PostId::new()]).await.unwrap();
+        let numbered = repo.find_by_numbers(thread, &[1, 598, 9999]).await.unwrap();
+        let elsewhere = repo.find_by_ids(ThreadId::new(), &[PostId(ids[0])]).await.unwrap();
+        let quoting = repo.find_quoting(thread, &[2, 598]).await.unwrap();
+        drop_board(&pool, fixture).await;
+        let numbers = |posts: Vec<Post>| posts.iter().map(|p| p.post_number).collect::<Vec<_>>();
+        assert_eq!(numbers(selected), [2, 599]);
+        assert_eq!(numbers(numbered), [1, 598]);
+        assert!(elsewhere.is_empty());
+        // `>>20` and the like do not quote No.2.
+        assert_eq!(numbers(quoting), [3, 599]);
+    }
 }
@@ -0,0 +1,80 @@
+//! PostgreSQL implementation of `ReactionRepository`.
+//! Uses runtime sqlx queries — no query! macros.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use domains::errors::DomainError;
+use domains::models::{IpHash, PostId, Reaction, ReactionCount};
+use domains::ports::ReactionRepository;
+use sqlx::PgPool;
+use tracing::{instrument, warn};
+use uuid::Uuid;
+
+/// PostgreSQL-backed `ReactionRepository`.
+#[derive(Clone)]
+pub struct PgReactionRepository {
+    pool: PgPool,
+}
+
+impl PgReactionRepository {
+    /// Construct a `PgReactionRepository` backed by the given connection pool.
+    pub fn new(pool: PgPool) -> Self { Self { pool } }
+}
+
+#[async_trait]
+impl ReactionRepository for PgReactionRepository {
+    #[instrument(skip(self, voter), fields(post_id = %post_id, reaction = %reaction))]
+    async fn react(&self, post_id: PostId, reaction: Reaction, voter: &IpHash) -> Result<bool, DomainError> {
+        let result = sqlx::query(
+            "INSERT INTO post_reactions (post_id, ip_hash, reaction) VALUES ($1, $2, $3)
+             ON CONFLICT (post_id, ip_hash) DO NOTHING"
+        )
+        .bind(post_id.0)
+        .bind(&voter.0)
+        .bind(reaction.as_str())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| match e.as_database_error().and_then(|d| d.code()).as_deref() {
+            // 23503 foreign_key_violation — the post is gone.
+            Some("23503") => DomainError::not_found(format!("post {post_id}")),
+            _ => DomainError::internal(e.to_string()),
+        })?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    #[instrument(skip(self, post_ids), fields(posts = post_ids.len()))]
+    async fn counts_by_posts(
+        &self,
+        post_ids: &[PostId],
+    ) -> Result<HashMap<PostId, Vec<ReactionCount>>, DomainError> {
+        if post_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let ids: Vec<Uuid> = post_ids.iter().map(|id| id.0).collect();
+        let rows: Vec<(Uuid, String, i64)> = sqlx::query_as(
+            "SELECT post_id, reaction, COUNT(*) FROM post_reactions
+             WHERE post_id = ANY($1) GROUP BY post_id, reaction"
+        )
+        .bind(&ids)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+
+        let mut counts: HashMap<PostId, Vec<ReactionCount>> = HashMap::new();
+        for (post_id, name, count) in rows {
+            match Reaction::from_str(&name) {
+                Ok(reaction) => counts
+                    .entry(PostId(post_id))
+                    .or_default()
+                    .push(ReactionCount { reaction, count: count as u64 }),
+                Err(e) => warn!(%post_id, error = %e, "skipping stored reaction"),
+            }
+        }
+        for list in counts.values_mut() {
+            list.sort_by_key(|c| Reaction::ALL.iter().position(|r| *r == c.reaction));
+        }
+        Ok(counts)
+    }
+}
@@ -66,7 +66,7 @@ impl SessionRepository for PgSessionRepository {
         .fetch_optional(&self.pool)
         .await
         .map_err(|e| DomainError::internal(e.to_string()))?
-        .ok_or_else(|| DomainError::auth())?;
+        .ok_or_else(DomainError::auth)?;
 
         Ok(Session {
             session_id:  row.session_id,
@@ -0,0 +1,143 @@
+//! PostgreSQL implementation of `SiteRepository`.
+//! Uses runtime sqlx queries — no query! macros.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domains::errors::{DomainError, ValidationError};
+use domains::models::{Site, SiteId};
+use domains::ports::SiteRepository;
+use sqlx::PgPool;
+use tracing::instrument;
+use uuid::Uuid;
+
+/// PostgreSQL-backed `SiteRepository`.
+#[derive(Clone)]
+pub struct PgSiteRepository {
+    pool: PgPool,
+}
+
+impl PgSiteRepository {
+    /// Construct a `PgSiteRepository` backed by the given connection pool.
+    pub fn new(pool: PgPool) -> Self { Self { pool } }
+}
+
+#[derive(sqlx::FromRow)]
+struct SiteRow {
+    id:             Uuid,
+    host:           String,
+    name:           String,
+    media_prefix:   String,
+    created_at:     DateTime<Utc>,
+    notice:         String,
+    notice_enabled: bool,
+}
+
+fn site_from_row(r: SiteRow) -> Site {
+    Site {
+        id:             SiteId(r.id),
+        host:           r.host,
+        name:           r.name,
+        media_prefix:   r.media_prefix,
+        created_at:     r.created_at,
+        notice:         r.notice,
+        notice_enabled: r.notice_enabled,
+    }
+}
+
+/// `DomainError::Validation` naming the rule a write broke.
+fn invalid(field: &str, reason: &str) -> DomainError {
+    DomainError::Validation(ValidationError::InvalidContent { field: field.to_owned(), reason: reason.to_owned() })
+}
+
+/// The Postgres SQLSTATE of a failed query, if it came from the database.
+fn sqlstate(e: &sqlx::Error) -> Option<String> {
+    e.as_database_error().and_then(|d| d.code()).map(|c| c.into_owned())
+}
+
+#[async_trait]
+impl SiteRepository for PgSiteRepository {
+    #[instrument(skip(self), fields(site_id = %id))]
+    async fn find_by_id(&self, id: SiteId) -> Result<Site, DomainError> {
+        sqlx::query_as::<_, SiteRow>(
+            "SELECT id, host, name, media_prefix, created_at, notice, notice_enabled FROM sites WHERE id = $1"
+        )
+        .bind(id.0)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?
+        .map(site_from_row)
+        .ok_or_else(|| DomainError::not_found(format!("site/{id}")))
+    }
+
+    #[instrument(skip(self))]
+    async fn find_by_host(&self, host: &str) -> Result<Option<Site>, DomainError> {
+        if host.is_empty() {
+            return Ok(None);
+        }
+        let row = sqlx::query_as::<_, SiteRow>(
+            "SELECT id, host, name, media_prefix, created_at, notice, notice_enabled FROM sites WHERE host = $1"
+        )
+        .bind(host)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+        Ok(row.map(site_from_row))
+    }
+
+    #[instrument(skip(self))]
+    async fn find_all(&self) -> Result<Vec<Site>, DomainError> {
+        let rows = sqlx::query_as::<_, SiteRow>(
+            "SELECT id, host, name, media_prefix, created_at, notice, notice_enabled FROM sites
+             ORDER BY id = $1 DESC, created_at ASC"
+        )
+        .bind(SiteId::DEFAULT.0)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+        Ok(rows.into_iter().map(site_from_row).collect())
+    }
+
+    #[instrument(skip(self, site), fields(site_id = %site.id))]
+    async fn save(&self, site: &Site) -> Result<(), DomainError> {
+        sqlx::query(
+            "INSERT INTO sites (id, host, name, media_prefix, created_at, notice, notice_enabled)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)
+             ON CONFLICT (id) DO UPDATE
+             SET host = EXCLUDED.host, name = EXCLUDED.name, media_prefix = EXCLUDED.media_prefix,
+                 notice = EXCLUDED.notice, notice_enabled = EXCLUDED.notice_enabled"
+        )
+        .bind(site.id.0)
+        .bind(&site.host)
+        .bind(&site.name)
+        .bind(&site.media_prefix)
+        .bind(site.created_at)
+        .bind(&site.notice)
+        .bind(site.notice_enabled)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| match sqlstate(&e).as_deref() {
+            Some("23505") => invalid("host", "another site already uses this host"),
+            _ => DomainError::internal(e.to_string()),
+        })?;
+        Ok(())
+    }
+
+    #[instrument(skip(self), fields(site_id = %id))]
+    async fn delete(&self, id: SiteId) -> Result<(), DomainError> {
+        if id == SiteId::DEFAULT {
+            return Err(invalid("site", "the default site cannot be deleted"));
+        }
+        let result = sqlx::query("DELETE FROM sites WHERE id = $1")
+            .bind(id.0)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| match sqlstate(&e).as_deref() {
+                Some("23503") => invalid("site", "the site still has boards; delete them first"),
+                _ => DomainError::internal(e.to_string()),
+            })?;
+        if result.rows_affected() == 0 {
+            return Err(DomainError::not_found(format!("site/{id}")));
+        }
+        Ok(())
+    }
+}
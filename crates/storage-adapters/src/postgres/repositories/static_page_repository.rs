@@ -0,0 +1,129 @@
+//! PostgreSQL implementation of `StaticPageRepository`.
+//! Uses runtime sqlx queries — no query! macros.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domains::errors::{DomainError, ValidationError};
+use domains::models::{BoardId, PageId, SiteId, StaticPage};
+use domains::ports::StaticPageRepository;
+use sqlx::PgPool;
+use tracing::instrument;
+use uuid::Uuid;
+
+/// PostgreSQL-backed `StaticPageRepository`.
+#[derive(Clone)]
+pub struct PgStaticPageRepository {
+    pool: PgPool,
+}
+
+impl PgStaticPageRepository {
+    /// Construct a `PgStaticPageRepository` backed by the given connection pool.
+    pub fn new(pool: PgPool) -> Self { Self { pool } }
+}
+
+/// Columns of `static_pages`, in the order `PageRow` expects them.
+const COLUMNS: &str = "id, site_id, board_id, slug, title, body, updated_at";
+
+type PageRow = (Uuid, Uuid, Option<Uuid>, String, String, String, DateTime<Utc>);
+
+fn to_page((id, site_id, board_id, slug, title, body, updated_at): PageRow) -> StaticPage {
+    StaticPage {
+        id: PageId(id),
+        site_id: SiteId(site_id),
+        board_id: board_id.map(BoardId),
+        slug,
+        title,
+        body,
+        updated_at,
+    }
+}
+
+#[async_trait]
+impl StaticPageRepository for PgStaticPageRepository {
+    #[instrument(skip(self), fields(site_id = %site_id))]
+    async fn find(
+        &self,
+        site_id: SiteId,
+        board_id: Option<BoardId>,
+        slug: &str,
+    ) -> Result<Option<StaticPage>, DomainError> {
+        let row: Option<PageRow> = sqlx::query_as(&format!(
+            "SELECT {COLUMNS} FROM static_pages
+             WHERE site_id = $1 AND board_id IS NOT DISTINCT FROM $2 AND slug = $3"
+        ))
+        .bind(site_id.0)
+        .bind(board_id.map(|b| b.0))
+        .bind(slug)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+        Ok(row.map(to_page))
+    }
+
+    #[instrument(skip(self), fields(site_id = %site_id, page_id = %id))]
+    async fn find_by_id(&self, site_id: SiteId, id: PageId) -> Result<StaticPage, DomainError> {
+        let row: Option<PageRow> = sqlx::query_as(&format!(
+            "SELECT {COLUMNS} FROM static_pages WHERE site_id = $1 AND id = $2"
+        ))
+        .bind(site_id.0)
+        .bind(id.0)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+        row.map(to_page).ok_or_else(|| DomainError::not_found(format!("page {id}")))
+    }
+
+    #[instrument(skip(self), fields(site_id = %site_id))]
+    async fn list(&self, site_id: SiteId) -> Result<Vec<StaticPage>, DomainError> {
+        let rows: Vec<PageRow> = sqlx::query_as(&format!(
+            "SELECT {COLUMNS} FROM static_pages WHERE site_id = $1
+             ORDER BY board_id NULLS FIRST, slug"
+        ))
+        .bind(site_id.0)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+        Ok(rows.into_iter().map(to_page).collect())
+    }
+
+    #[instrument(skip(self, page), fields(page_id = %page.id, site_id = %page.site_id))]
+    async fn save(&self, page: &StaticPage) -> Result<(), DomainError> {
+        sqlx::query(
+            "INSERT INTO static_pages (id, site_id, board_id, slug, title, body, updated_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)
+             ON CONFLICT (id) DO UPDATE
+             SET slug = EXCLUDED.slug, title = EXCLUDED.title, body = EXCLUDED.body,
+                 updated_at = EXCLUDED.updated_at"
+        )
+        .bind(page.id.0)
+        .bind(page.site_id.0)
+        .bind(page.board_id.map(|b| b.0))
+        .bind(&page.slug)
+        .bind(&page.title)
+        .bind(&page.body)
+        .bind(page.updated_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| match e.as_database_error().and_then(|d| d.code()).as_deref() {
+            Some("23505") => DomainError::Validation(ValidationError::InvalidContent {
+                field:  "slug".to_owned(),
+                reason: "another page already uses this slug".to_owned(),
+            }),
+            _ => DomainError::internal(e.to_string()),
+        })?;
+        Ok(())
+    }
+
+    #[instrument(skip(self), fields(site_id = %site_id, page_id = %id))]
+    async fn delete(&self, site_id: SiteId, id: PageId) -> Result<StaticPage, DomainError> {
+        let row: Option<PageRow> = sqlx::query_as(&format!(
+            "DELETE FROM static_pages WHERE site_id = $1 AND id = $2 RETURNING {COLUMNS}"
+        ))
+        .bind(site_id.0)
+        .bind(id.0)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+        row.map(to_page).ok_or_else(|| DomainError::not_found(format!("page {id}")))
+    }
+}
@@ -0,0 +1,115 @@
+//! PostgreSQL implementation of `StatsRepository`.
+//! Uses runtime sqlx queries — no query! macros.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domains::errors::DomainError;
+use domains::models::{BoardId, SiteId, StatsBucket, StatsPeriod};
+use domains::ports::StatsRepository;
+use sqlx::PgPool;
+use tracing::instrument;
+use uuid::Uuid;
+
+/// PostgreSQL-backed `StatsRepository`.
+#[derive(Clone)]
+pub struct PgStatsRepository {
+    pool: PgPool,
+}
+
+impl PgStatsRepository {
+    /// Construct a `PgStatsRepository` backed by the given connection pool.
+    pub fn new(pool: PgPool) -> Self { Self { pool } }
+}
+
+/// Columns of `board_stats`, in the order `StatsRow` expects them.
+const COLUMNS: &str = "s.board_id, s.period, s.bucket_start, s.posts, s.unique_posters, s.media_count, s.media_bytes";
+
+type StatsRow = (Uuid, String, DateTime<Utc>, i64, i64, i64, i64);
+
+fn to_bucket((board_id, period, start, posts, unique_posters, media_count, media_bytes): StatsRow) -> StatsBucket {
+    StatsBucket {
+        board_id: BoardId(board_id),
+        period: if period == "day" { StatsPeriod::Day } else { StatsPeriod::Hour },
+        start,
+        posts: posts as u64,
+        unique_posters: unique_posters as u64,
+        media_count: media_count as u64,
+        media_bytes: media_bytes as u64,
+    }
+}
+
+#[async_trait]
+impl StatsRepository for PgStatsRepository {
+    #[instrument(skip(self))]
+    async fn rollup(&self, since: DateTime<Utc>) -> Result<u64, DomainError> {
+        let mut written = 0;
+        for period in [StatsPeriod::Hour, StatsPeriod::Day] {
+            // Buckets are truncated in UTC, whatever the session time zone.
+            let result = sqlx::query(
+                "INSERT INTO board_stats
+                   (board_id, period, bucket_start, posts, unique_posters, media_count, media_bytes)
+                 SELECT t.board_id, $1,
+                        date_trunc($1, p.created_at AT TIME ZONE 'UTC') AT TIME ZONE 'UTC' AS bucket_start,
+                        COUNT(*), COUNT(DISTINCT p.ip_hash),
+                        COALESCE(SUM(a.files), 0), COALESCE(SUM(a.bytes), 0)
+                 FROM posts p
+                 JOIN threads t ON t.id = p.thread_id
+                 LEFT JOIN LATERAL (
+                   SELECT COUNT(*) AS files, SUM(COALESCE(size_bytes, 0)) AS bytes
+                   FROM attachments WHERE post_id = p.id
+                 ) a ON true
+                 WHERE p.created_at >= date_trunc($1, $2 AT TIME ZONE 'UTC') AT TIME ZONE 'UTC'
+                 GROUP BY t.board_id, bucket_start
+                 ON CONFLICT (board_id, period, bucket_start) DO UPDATE SET
+                   posts          = EXCLUDED.posts,
+                   unique_posters = EXCLUDED.unique_posters,
+                   media_count    = EXCLUDED.media_count,
+                   media_bytes    = EXCLUDED.media_bytes"
+            )
+            .bind(period.as_str())
+            .bind(since)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| DomainError::internal(e.to_string()))?;
+            written += result.rows_affected();
+        }
+        Ok(written)
+    }
+
+    #[instrument(skip(self), fields(board_id = %board_id, period = period.as_str()))]
+    async fn board_series(
+        &self,
+        board_id: BoardId,
+        period: StatsPeriod,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<StatsBucket>, DomainError> {
+        let rows: Vec<StatsRow> = sqlx::query_as(&format!(
+            "SELECT {COLUMNS} FROM board_stats s
+             WHERE s.board_id = $1 AND s.period = $2 AND s.bucket_start >= $3
+             ORDER BY s.bucket_start"
+        ))
+        .bind(board_id.0)
+        .bind(period.as_str())
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+        Ok(rows.into_iter().map(to_bucket).collect())
+    }
+
+    #[instrument(skip(self), fields(site_id = %site_id))]
+    async fn site_daily(&self, site_id: SiteId, since: DateTime<Utc>) -> Result<Vec<StatsBucket>, DomainError> {
+        let rows: Vec<StatsRow> = sqlx::query_as(&format!(
+            "SELECT {COLUMNS} FROM board_stats s
+             JOIN boards b ON b.id = s.board_id
+             WHERE b.site_id = $1 AND s.period = 'day' AND s.bucket_start >= $2
+             ORDER BY s.bucket_start, s.board_id"
+        ))
+        .bind(site_id.0)
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+        Ok(rows.into_iter().map(to_bucket).collect())
+    }
+}
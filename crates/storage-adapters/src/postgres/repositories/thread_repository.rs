@@ -5,9 +5,11 @@ use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use domains::errors::DomainError;
 use domains::models::{
-    BoardId, IpHash, MediaKey, Page, Paginated, PostId, Thread, ThreadId, ThreadSummary,
+    BoardId, CatalogSort, IpHash, MediaKey, OverboardThread, Page, Paginated, PostId, SiteId, Thread,
+    ThreadId, ThreadSummary,
 };
 use domains::ports::ThreadRepository;
+use super::post_repository::SHADOWBANNED;
 use sqlx::PgPool;
 use tracing::instrument;
 use uuid::Uuid;
@@ -67,13 +69,68 @@ struct ThreadSummaryRow {
     bumped_at:      DateTime<Utc>,
     op_body:        Option<String>,
     thumbnail_key:  Option<String>,
+    thumbnail_spoiler: Option<bool>,
     op_name:        Option<String>,
     op_tripcode:    Option<String>,
+    op_subject:     Option<String>,
+    op_capcode:     Option<String>,
     op_created_at:  Option<DateTime<Utc>>,
     op_post_number: Option<i64>,
     op_ip_hash:     Option<String>,
 }
 
+/// Columns of a `ThreadSummaryRow`, for a query over `threads t` joined with
+/// the OP post `p` and its first attachment `a`. A deleted OP's text is left
+/// out, as `Post::redact` does.
+const SUMMARY_COLUMNS: &str =
+    "t.id AS thread_id, t.board_id, t.reply_count, t.sticky, t.closed, t.bumped_at,
+     CASE WHEN p.deleted_at IS NULL THEN p.body END AS op_body,
+     CASE WHEN p.deleted_at IS NULL THEN p.name END AS op_name,
+     CASE WHEN p.deleted_at IS NULL THEN p.tripcode END AS op_tripcode,
+     CASE WHEN p.deleted_at IS NULL THEN p.subject END AS op_subject,
+     CASE WHEN p.deleted_at IS NULL THEN p.capcode END AS op_capcode,
+     p.created_at AS op_created_at, p.post_number AS op_post_number,
+     p.ip_hash AS op_ip_hash, a.thumbnail_key, a.spoiler AS thumbnail_spoiler";
+
+/// Joins the OP post and first attachment thumbnail for `SUMMARY_COLUMNS`.
+const SUMMARY_JOINS: &str =
+    "LEFT JOIN posts p ON p.id = t.op_post_id
+     LEFT JOIN LATERAL (
+       SELECT thumbnail_key, spoiler FROM attachments
+       WHERE post_id = t.op_post_id
+       ORDER BY id ASC LIMIT 1
+     ) a ON true";
+
+fn summary_from_row(r: ThreadSummaryRow) -> ThreadSummary {
+    let epoch = DateTime::from_timestamp(0, 0).unwrap_or_default();
+    ThreadSummary {
+        thread_id:      ThreadId(r.thread_id),
+        board_id:       BoardId(r.board_id),
+        op_body:        r.op_body.unwrap_or_default(),
+        thumbnail_key:  r.thumbnail_key.map(MediaKey::new),
+        thumbnail_spoiler: r.thumbnail_spoiler.unwrap_or(false),
+        reply_count:    r.reply_count as u32,
+        sticky:         r.sticky,
+        closed:         r.closed,
+        bumped_at:      r.bumped_at,
+        op_name:        r.op_name,
+        op_tripcode:    r.op_tripcode,
+        op_subject:     r.op_subject,
+        op_capcode:     r.op_capcode,
+        op_created_at:  r.op_created_at.unwrap_or(epoch),
+        op_post_number: r.op_post_number.unwrap_or(0) as u64,
+        op_ip_hash:     IpHash(r.op_ip_hash.unwrap_or_default()),
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct OverboardThreadRow {
+    #[sqlx(flatten)]
+    summary:     ThreadSummaryRow,
+    board_slug:  String,
+    board_title: String,
+}
+
 #[async_trait]
 impl ThreadRepository for PgThreadRepository {
     #[instrument(skip(self), fields(thread_id = %id))]
@@ -119,45 +176,88 @@ impl ThreadRepository for PgThreadRepository {
         Ok(Paginated::new(items, total as u64, page, page_size))
     }
 
-    #[instrument(skip(self), fields(board_id = %board_id))]
-    async fn find_catalog(&self, board_id: BoardId) -> Result<Vec<ThreadSummary>, DomainError> {
-        // Left join with op post and first attachment thumbnail
-        let rows = sqlx::query_as::<_, ThreadSummaryRow>(
-            "SELECT t.id AS thread_id, t.board_id, t.reply_count, t.sticky, t.closed, t.bumped_at,
-                    p.body AS op_body, p.name AS op_name, p.tripcode AS op_tripcode,
-                    p.created_at AS op_created_at, p.post_number AS op_post_number,
-                    p.ip_hash AS op_ip_hash, a.thumbnail_key
+    #[instrument(skip(self), fields(board_id = %board_id, %sort))]
+    async fn find_catalog(&self, board_id: BoardId, sort: CatalogSort) -> Result<Vec<ThreadSummary>, DomainError> {
+        let order = match sort {
+            CatalogSort::Bump => "t.bumped_at DESC",
+            CatalogSort::ReplyCount => "t.reply_count DESC, t.bumped_at DESC",
+            CatalogSort::Creation => "t.created_at DESC",
+            CatalogSort::Random => "random()",
+        };
+        let rows = sqlx::query_as::<_, ThreadSummaryRow>(&format!(
+            "SELECT {SUMMARY_COLUMNS}
              FROM threads t
-             LEFT JOIN posts p ON p.id = t.op_post_id
-             LEFT JOIN LATERAL (
-               SELECT thumbnail_key FROM attachments
-               WHERE post_id = t.op_post_id
-               ORDER BY id ASC LIMIT 1
-             ) a ON true
-             WHERE t.board_id = $1
-             ORDER BY t.sticky DESC, t.bumped_at DESC"
-        )
+             {SUMMARY_JOINS}
+             WHERE t.board_id = $1 AND NOT {SHADOWBANNED}
+             ORDER BY t.sticky DESC, {order}"
+        ))
+        .bind(board_id.0)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+
+        Ok(rows.into_iter().map(summary_from_row).collect())
+    }
+
+    #[instrument(skip(self, ids), fields(board_id = %board_id, count = ids.len()))]
+    async fn find_summaries(&self, board_id: BoardId, ids: &[ThreadId]) -> Result<Vec<ThreadSummary>, DomainError> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let ids: Vec<Uuid> = ids.iter().map(|id| id.0).collect();
+        let rows = sqlx::query_as::<_, ThreadSummaryRow>(&format!(
+            "SELECT {SUMMARY_COLUMNS}
+             FROM threads t
+             {SUMMARY_JOINS}
+             WHERE t.board_id = $1 AND t.id = ANY($2) AND NOT {SHADOWBANNED}"
+        ))
         .bind(board_id.0)
+        .bind(&ids)
         .fetch_all(&self.pool)
         .await
         .map_err(|e| DomainError::internal(e.to_string()))?;
 
-        let epoch = DateTime::from_timestamp(0, 0).unwrap_or_default();
-        Ok(rows.into_iter().map(|r| ThreadSummary {
-            thread_id:      ThreadId(r.thread_id),
-            board_id:       BoardId(r.board_id),
-            op_body:        r.op_body.unwrap_or_default(),
-            thumbnail_key:  r.thumbnail_key.map(MediaKey::new),
-            reply_count:    r.reply_count as u32,
-            sticky:         r.sticky,
-            closed:         r.closed,
-            bumped_at:      r.bumped_at,
-            op_name:        r.op_name,
-            op_tripcode:    r.op_tripcode,
-            op_created_at:  r.op_created_at.unwrap_or(epoch),
-            op_post_number: r.op_post_number.unwrap_or(0) as u64,
-            op_ip_hash:     IpHash(r.op_ip_hash.unwrap_or_default()),
-        }).collect())
+        Ok(rows.into_iter().map(summary_from_row).collect())
+    }
+
+    #[instrument(skip(self), fields(site_id = %site, page = page.0))]
+    async fn find_overboard_threads(&self, site: SiteId, page: Page) -> Result<Paginated<OverboardThread>, DomainError> {
+        let page_size = OverboardThread::PAGE_SIZE;
+        // Boards without a config row take the defaults, which list them.
+        let rows = sqlx::query_as::<_, OverboardThreadRow>(&format!(
+            "SELECT {SUMMARY_COLUMNS}, b.slug AS board_slug, b.title AS board_title
+             FROM threads t
+             JOIN boards b ON b.id = t.board_id
+             LEFT JOIN board_configs c ON c.board_id = b.id
+             {SUMMARY_JOINS}
+             WHERE b.site_id = $1 AND NOT COALESCE(c.overboard_hidden, false) AND NOT {SHADOWBANNED}
+             ORDER BY t.bumped_at DESC LIMIT $2 OFFSET $3"
+        ))
+        .bind(site.0)
+        .bind(page_size as i64)
+        .bind(page.offset(page_size) as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+
+        let total: i64 = sqlx::query_scalar(&format!(
+            "SELECT COUNT(*) FROM threads t \
+             JOIN boards b ON b.id = t.board_id \
+             LEFT JOIN board_configs c ON c.board_id = b.id \
+             LEFT JOIN posts p ON p.id = t.op_post_id \
+             WHERE b.site_id = $1 AND NOT COALESCE(c.overboard_hidden, false) AND NOT {SHADOWBANNED}"
+        ))
+        .bind(site.0)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+
+        let items = rows.into_iter().map(|r| OverboardThread {
+            summary:     summary_from_row(r.summary),
+            board_slug:  r.board_slug,
+            board_title: r.board_title,
+        }).collect();
+        Ok(Paginated::new(items, total as u64, page, page_size))
     }
 
     #[instrument(skip(self, thread), fields(board_id = %thread.board_id))]
@@ -212,6 +312,7 @@ impl ThreadRepository for PgThreadRepository {
         Ok(())
     }
 
+    #[instrument(skip(self), fields(thread_id = %id))]
     async fn set_sticky(&self, id: ThreadId, sticky: bool) -> Result<(), DomainError> {
         sqlx::query("UPDATE threads SET sticky = $2 WHERE id = $1")
             .bind(id.0)
@@ -222,6 +323,7 @@ impl ThreadRepository for PgThreadRepository {
         Ok(())
     }
 
+    #[instrument(skip(self), fields(thread_id = %id))]
     async fn set_closed(&self, id: ThreadId, closed: bool) -> Result<(), DomainError> {
         sqlx::query("UPDATE threads SET closed = $2 WHERE id = $1")
             .bind(id.0)
@@ -232,6 +334,7 @@ impl ThreadRepository for PgThreadRepository {
         Ok(())
     }
 
+    #[instrument(skip(self), fields(thread_id = %id))]
     async fn set_cycle(&self, id: ThreadId, cycle: bool) -> Result<(), DomainError> {
         sqlx::query("UPDATE threads SET cycle = $2 WHERE id = $1")
             .bind(id.0)
@@ -242,6 +345,7 @@ impl ThreadRepository for PgThreadRepository {
         Ok(())
     }
 
+    #[instrument(skip(self), fields(board_id = %board_id))]
     async fn count_by_board(&self, board_id: BoardId) -> Result<u32, DomainError> {
         let count: i64 = sqlx::query_scalar(
             "SELECT COUNT(*) FROM threads WHERE board_id = $1"
@@ -271,6 +375,7 @@ impl ThreadRepository for PgThreadRepository {
         Ok(result.rows_affected() as u32)
     }
 
+    #[instrument(skip(self), fields(thread_id = %id))]
     async fn delete(&self, id: ThreadId) -> Result<(), DomainError> {
         let result = sqlx::query("DELETE FROM threads WHERE id = $1")
             .bind(id.0)
@@ -283,6 +388,7 @@ impl ThreadRepository for PgThreadRepository {
         Ok(())
     }
 
+    #[instrument(skip(self), fields(board_id = %board_id))]
     async fn find_oldest_for_archive(
         &self,
         board_id: BoardId,
@@ -0,0 +1,227 @@
+//! PostgreSQL implementation of `TotpRepository`.
+//! Uses runtime sqlx queries — no query! macros.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domains::errors::DomainError;
+use domains::models::{StaffTotp, UserId};
+use domains::ports::TotpRepository;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// PostgreSQL-backed `TotpRepository`.
+#[derive(Clone)]
+pub struct PgTotpRepository {
+    pool: PgPool,
+}
+
+impl PgTotpRepository {
+    /// Construct a `PgTotpRepository` backed by the given connection pool.
+    pub fn new(pool: PgPool) -> Self { Self { pool } }
+}
+
+#[derive(sqlx::FromRow)]
+struct TotpRow {
+    user_id:        Uuid,
+    secret:         String,
+    enabled_at:     Option<DateTime<Utc>>,
+    recovery_codes: Vec<String>,
+    last_step:      Option<i64>,
+    locked_until:   Option<DateTime<Utc>>,
+}
+
+fn totp_from_row(r: TotpRow) -> StaffTotp {
+    StaffTotp {
+        user_id:        UserId(r.user_id),
+        secret:         r.secret,
+        enabled_at:     r.enabled_at,
+        recovery_codes: r.recovery_codes,
+        last_step:      r.last_step,
+        locked_until:   r.locked_until,
+    }
+}
+
+#[async_trait]
+impl TotpRepository for PgTotpRepository {
+    async fn find(&self, user_id: UserId) -> Result<Option<StaffTotp>, DomainError> {
+        let row = sqlx::query_as::<_, TotpRow>(
+            "SELECT user_id, secret, enabled_at, recovery_codes, last_step, locked_until
+             FROM staff_totp WHERE user_id = $1"
+        )
+        .bind(user_id.0)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+        Ok(row.map(totp_from_row))
+    }
+
+    async fn save(&self, totp: &StaffTotp) -> Result<(), DomainError> {
+        sqlx::query(
+            "INSERT INTO staff_totp (user_id, secret, enabled_at, recovery_codes, last_step)
+             VALUES ($1, $2, $3, $4, $5)
+             ON CONFLICT (user_id) DO UPDATE SET
+                 secret         = EXCLUDED.secret,
+                 enabled_at     = EXCLUDED.enabled_at,
+                 recovery_codes = EXCLUDED.recovery_codes,
+                 last_step      = EXCLUDED.last_step"
+        )
+        .bind(totp.user_id.0)
+        .bind(&totp.secret)
+        .bind(totp.enabled_at)
+        .bind(&totp.recovery_codes)
+        .bind(totp.last_step)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn claim_attempt(
+        &self,
+        user_id: UserId,
+        limit: u32,
+        now: DateTime<Utc>,
+        locked_until: DateTime<Utc>,
+    ) -> Result<bool, DomainError> {
+        // SET expressions see the row as it was, so both columns restart the
+        // count the same way when an old lock has run out.
+        let result = sqlx::query(
+            "UPDATE staff_totp SET
+                 attempts     = CASE WHEN locked_until <= $3 THEN 1 ELSE attempts + 1 END,
+                 locked_until = CASE WHEN (CASE WHEN locked_until <= $3 THEN 1 ELSE attempts + 1 END) >= $2
+                                     THEN $4 ELSE NULL END
+             WHERE user_id = $1 AND (locked_until IS NULL OR locked_until <= $3)"
+        )
+        .bind(user_id.0)
+        .bind(i32::try_from(limit).unwrap_or(i32::MAX))
+        .bind(now)
+        .bind(locked_until)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn consume_step(&self, user_id: UserId, step: i64) -> Result<bool, DomainError> {
+        let result = sqlx::query(
+            "UPDATE staff_totp SET last_step = $2, attempts = 0, locked_until = NULL
+             WHERE user_id = $1 AND (last_step IS NULL OR last_step < $2)"
+        )
+        .bind(user_id.0)
+        .bind(step)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn consume_recovery_code(&self, user_id: UserId, hash: &str) -> Result<bool, DomainError> {
+        let result = sqlx::query(
+            "UPDATE staff_totp SET recovery_codes = array_remove(recovery_codes, $2), attempts = 0, locked_until = NULL
+             WHERE user_id = $1 AND $2 = ANY(recovery_codes)"
+        )
+        .bind(user_id.0)
+        .bind(hash)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn delete(&self, user_id: UserId) -> Result<(), DomainError> {
+        sqlx::query("DELETE FROM staff_totp WHERE user_id = $1")
+            .bind(user_id.0)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| DomainError::internal(e.to_string()))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn pool() -> Option<PgPool> {
+        let url = std::env::var("TEST_DATABASE_URL").ok()?;
+        Some(PgPool::connect(&url).await.expect("TEST_DATABASE_URL"))
+    }
+
+    /// An account with an enabled factor holding the recovery code hash "r1".
+    /// Removed again, factor and all, by deleting the user.
+    async fn account_with_factor(repo: &PgTotpRepository) -> UserId {
+        let tag = Uuid::new_v4().simple().to_string();
+        let user_id: Uuid = sqlx::query_scalar(
+            "INSERT INTO users (username, password_hash, role) VALUES ($1, 'x', 'admin') RETURNING id",
+        )
+        .bind(&tag[..16])
+        .fetch_one(&repo.pool)
+        .await
+        .unwrap();
+        let user_id = UserId(user_id);
+        repo.save(&StaffTotp {
+            user_id,
+            secret:         "JBSWY3DPEHPK3PXP".into(),
+            enabled_at:     Some(Utc::now()),
+            recovery_codes: vec!["r1".into()],
+            last_step:      Some(10),
+            locked_until:   None,
+        })
+        .await
+        .unwrap();
+        user_id
+    }
+
+    async fn drop_account(repo: &PgTotpRepository, user_id: UserId) {
+        sqlx::query("DELETE FROM users WHERE id = $1").bind(user_id.0).execute(&repo.pool).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn a_step_or_recovery_code_is_spent_once() {
+        let Some(pool) = pool().await else { return };
+        let repo = PgTotpRepository::new(pool);
+        let user_id = account_with_factor(&repo).await;
+
+        assert!(!repo.consume_step(user_id, 10).await.unwrap());
+        let (a, b) = tokio::join!(repo.consume_step(user_id, 11), repo.consume_step(user_id, 11));
+        assert!(a.unwrap() ^ b.unwrap(), "exactly one concurrent use of a step wins");
+
+        let (a, b) = tokio::join!(repo.consume_recovery_code(user_id, "r1"), repo.consume_recovery_code(user_id, "r1"));
+        assert!(a.unwrap() ^ b.unwrap(), "exactly one concurrent use of a recovery code wins");
+        assert!(repo.find(user_id).await.unwrap().unwrap().recovery_codes.is_empty());
+
+        drop_account(&repo, user_id).await;
+    }
+
+    #[tokio::test]
+    async fn the_limit_th_attempt_locks_until_the_lock_runs_out() {
+        let Some(pool) = pool().await else { return };
+        let repo = PgTotpRepository::new(pool);
+        let user_id = account_with_factor(&repo).await;
+        let now = Utc::now();
+        let until = now + chrono::Duration::minutes(15);
+
+        for _ in 0..3 {
+            assert!(repo.claim_attempt(user_id, 3, now, until).await.unwrap());
+        }
+        assert!(!repo.claim_attempt(user_id, 3, now, until).await.unwrap());
+        let locked = repo.find(user_id).await.unwrap().unwrap().locked_until.unwrap();
+        assert!((locked - until).num_milliseconds().abs() < 1);
+
+        // Once the lock has passed the count starts over.
+        let later = until + chrono::Duration::seconds(1);
+        assert!(repo.claim_attempt(user_id, 3, later, later).await.unwrap());
+        assert!(repo.find(user_id).await.unwrap().unwrap().locked_until.is_none());
+
+        // An accepted code clears the count.
+        let until = later + chrono::Duration::minutes(15);
+        assert!(repo.claim_attempt(user_id, 3, later, until).await.unwrap());
+        assert!(repo.consume_step(user_id, 20).await.unwrap());
+        for _ in 0..2 {
+            assert!(repo.claim_attempt(user_id, 3, later, until).await.unwrap());
+        }
+        assert!(repo.find(user_id).await.unwrap().unwrap().locked_until.is_none());
+
+        drop_account(&repo, user_id).await;
+    }
+}
@@ -0,0 +1,95 @@
+//! PostgreSQL implementation of `WarningRepository`.
+//! Uses runtime sqlx queries — no query! macros.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domains::errors::DomainError;
+use domains::models::{BanId, IpHash, PostId, UserId, Warning};
+use domains::ports::WarningRepository;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// PostgreSQL-backed `WarningRepository`.
+#[derive(Clone)]
+pub struct PgWarningRepository {
+    pool: PgPool,
+}
+
+impl PgWarningRepository {
+    /// Construct a `PgWarningRepository` backed by the given connection pool.
+    pub fn new(pool: PgPool) -> Self { Self { pool } }
+}
+
+#[derive(sqlx::FromRow)]
+struct WarningRow {
+    id:              Uuid,
+    ip_hash:         String,
+    post_id:         Option<Uuid>,
+    issued_by:       Uuid,
+    reason:          String,
+    created_at:      DateTime<Utc>,
+    acknowledged_at: Option<DateTime<Utc>>,
+}
+
+fn warning_from_row(r: WarningRow) -> Warning {
+    Warning {
+        id:              BanId(r.id),
+        ip_hash:         IpHash::new(r.ip_hash),
+        post_id:         r.post_id.map(PostId),
+        issued_by:       UserId(r.issued_by),
+        reason:          r.reason,
+        created_at:      r.created_at,
+        acknowledged_at: r.acknowledged_at,
+    }
+}
+
+#[async_trait]
+impl WarningRepository for PgWarningRepository {
+    async fn find_pending_by_ip(&self, ip_hash: &IpHash) -> Result<Option<Warning>, DomainError> {
+        let row = sqlx::query_as::<_, WarningRow>(
+            "SELECT id, ip_hash, post_id, issued_by, reason, created_at, acknowledged_at
+             FROM warnings
+             WHERE ip_hash = $1 AND acknowledged_at IS NULL
+             ORDER BY created_at ASC LIMIT 1"
+        )
+        .bind(&ip_hash.0)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+        Ok(row.map(warning_from_row))
+    }
+
+    async fn save(&self, warning: &Warning) -> Result<BanId, DomainError> {
+        sqlx::query(
+            "INSERT INTO warnings (id, ip_hash, post_id, issued_by, reason, created_at, acknowledged_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)"
+        )
+        .bind(warning.id.0)
+        .bind(&warning.ip_hash.0)
+        .bind(warning.post_id.map(|p| p.0))
+        .bind(warning.issued_by.0)
+        .bind(&warning.reason)
+        .bind(warning.created_at)
+        .bind(warning.acknowledged_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+        Ok(warning.id)
+    }
+
+    async fn acknowledge(&self, id: BanId, ip_hash: &IpHash) -> Result<(), DomainError> {
+        let result = sqlx::query(
+            "UPDATE warnings SET acknowledged_at = now()
+             WHERE id = $1 AND ip_hash = $2 AND acknowledged_at IS NULL"
+        )
+        .bind(id.0)
+        .bind(&ip_hash.0)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+        if result.rows_affected() == 0 {
+            return Err(DomainError::not_found(id.to_string()));
+        }
+        Ok(())
+    }
+}
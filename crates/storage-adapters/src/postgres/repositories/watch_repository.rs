@@ -0,0 +1,118 @@
+//! PostgreSQL implementation of `WatchRepository`.
+//! Uses runtime sqlx queries — no query! macros.
+
+use async_trait::async_trait;
+use domains::errors::DomainError;
+use domains::models::{ThreadId, WatchedThread, WatcherToken};
+use domains::ports::WatchRepository;
+use sqlx::PgPool;
+use tracing::instrument;
+use uuid::Uuid;
+
+/// Characters of the OP body used as the title of a thread without a subject.
+const TITLE_CHARS: i32 = 80;
+
+/// PostgreSQL-backed `WatchRepository`.
+#[derive(Clone)]
+pub struct PgWatchRepository {
+    pool: PgPool,
+}
+
+impl PgWatchRepository {
+    /// Construct a `PgWatchRepository` backed by the given connection pool.
+    pub fn new(pool: PgPool) -> Self { Self { pool } }
+}
+
+#[async_trait]
+impl WatchRepository for PgWatchRepository {
+    #[instrument(skip(self, watcher), fields(thread_id = %thread_id))]
+    async fn watch(&self, watcher: WatcherToken, thread_id: ThreadId) -> Result<(), DomainError> {
+        sqlx::query(
+            "INSERT INTO thread_watches (watcher, thread_id, last_seen_post)
+             VALUES ($1, $2, (SELECT COALESCE(MAX(post_number), 0) FROM posts WHERE thread_id = $2))
+             ON CONFLICT (watcher, thread_id) DO NOTHING"
+        )
+        .bind(watcher.0)
+        .bind(thread_id.0)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| match e.as_database_error().and_then(|d| d.code()).as_deref() {
+            // 23503 foreign_key_violation — the thread is gone.
+            Some("23503") => DomainError::not_found(format!("thread {thread_id}")),
+            _ => DomainError::internal(e.to_string()),
+        })?;
+        Ok(())
+    }
+
+    #[instrument(skip(self, watcher), fields(thread_id = %thread_id))]
+    async fn unwatch(&self, watcher: WatcherToken, thread_id: ThreadId) -> Result<(), DomainError> {
+        sqlx::query("DELETE FROM thread_watches WHERE watcher = $1 AND thread_id = $2")
+            .bind(watcher.0)
+            .bind(thread_id.0)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| DomainError::internal(e.to_string()))?;
+        Ok(())
+    }
+
+    #[instrument(skip(self, watcher))]
+    async fn count(&self, watcher: WatcherToken) -> Result<u64, DomainError> {
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM thread_watches WHERE watcher = $1")
+            .bind(watcher.0)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| DomainError::internal(e.to_string()))?;
+        Ok(count as u64)
+    }
+
+    #[instrument(skip(self, watcher), fields(thread_id = %thread_id, post_number))]
+    async fn mark_seen(&self, watcher: WatcherToken, thread_id: ThreadId, post_number: u64) -> Result<bool, DomainError> {
+        let result = sqlx::query(
+            "UPDATE thread_watches SET last_seen_post = GREATEST(last_seen_post, $3)
+             WHERE watcher = $1 AND thread_id = $2"
+        )
+        .bind(watcher.0)
+        .bind(thread_id.0)
+        .bind(post_number as i64)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    #[instrument(skip(self, watcher))]
+    async fn list(&self, watcher: WatcherToken) -> Result<Vec<WatchedThread>, DomainError> {
+        let rows: Vec<(Uuid, String, String, i64, chrono::DateTime<chrono::Utc>)> = sqlx::query_as(
+            "SELECT t.id, b.slug,
+                    CASE WHEN op.deleted_at IS NULL
+                         THEN COALESCE(NULLIF(op.subject, ''), LEFT(op.body, $2), '') ELSE '' END,
+                    (SELECT COUNT(*) FROM posts p
+                     WHERE p.thread_id = t.id AND p.post_number > w.last_seen_post
+                       AND p.deleted_at IS NULL),
+                    t.bumped_at
+             FROM thread_watches w
+             JOIN threads t ON t.id = w.thread_id
+             JOIN boards  b ON b.id = t.board_id
+             LEFT JOIN posts op ON op.id = t.op_post_id
+             WHERE w.watcher = $1"
+        )
+        .bind(watcher.0)
+        .bind(TITLE_CHARS)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?;
+
+        let mut threads: Vec<WatchedThread> = rows
+            .into_iter()
+            .map(|(id, board_slug, title, unread, bumped_at)| WatchedThread {
+                thread_id: ThreadId(id),
+                board_slug,
+                title,
+                unread: unread as u64,
+                bumped_at,
+            })
+            .collect();
+        threads.sort_by(|a, b| (b.unread > 0).cmp(&(a.unread > 0)).then(b.bumped_at.cmp(&a.bumped_at)));
+        Ok(threads)
+    }
+}
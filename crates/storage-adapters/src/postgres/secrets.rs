@@ -0,0 +1,27 @@
+//! Server-generated secrets persisted in the `server_secrets` table.
+//!
+//! Used for secrets that must stay stable across restarts but that operators
+//! may not have configured, e.g. the secure-tripcode pepper.
+
+use sqlx::PgPool;
+
+/// Return the secret stored under `name`, generating and storing one first if
+/// none exists yet.
+///
+/// A new secret is 64 hex characters (two v4 UUIDs, 244 random bits) made by
+/// PostgreSQL. Concurrent callers agree on whichever row was inserted first.
+pub async fn load_or_create_secret(pool: &PgPool, name: &str) -> Result<String, sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO server_secrets (name, value)
+         VALUES ($1, replace(gen_random_uuid()::text || gen_random_uuid()::text, '-', ''))
+         ON CONFLICT (name) DO NOTHING"
+    )
+    .bind(name)
+    .execute(pool)
+    .await?;
+
+    sqlx::query_scalar("SELECT value FROM server_secrets WHERE name = $1")
+        .bind(name)
+        .fetch_one(pool)
+        .await
+}
@@ -0,0 +1,73 @@
+//! Redis cache store adapter.
+//!
+//! Implements `CacheStore` with plain `SET … EX` / `GET` / `DEL`. Keys are
+//! namespaced as `cache:{key}` so they cannot collide with rate-limit keys
+//! on a shared Redis database.
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use deadpool_redis::Pool;
+use domains::errors::DomainError;
+use domains::ports::CacheStore;
+use std::time::Duration;
+use tracing::instrument;
+
+/// Redis-backed `CacheStore`, shared by every instance pointing at the same Redis.
+pub struct RedisCacheStore {
+    pool: Pool,
+}
+
+impl RedisCacheStore {
+    pub fn new(pool: Pool) -> Self {
+        Self { pool }
+    }
+
+    fn redis_key(key: &str) -> String {
+        format!("cache:{key}")
+    }
+
+    async fn conn(&self) -> Result<deadpool_redis::Connection, DomainError> {
+        self.pool
+            .get()
+            .await
+            .map_err(|e| DomainError::internal(format!("redis pool error: {e}")))
+    }
+}
+
+#[async_trait]
+impl CacheStore for RedisCacheStore {
+    #[instrument(skip(self))]
+    async fn get(&self, key: &str) -> Result<Option<Bytes>, DomainError> {
+        use deadpool_redis::redis::AsyncCommands;
+        let mut conn = self.conn().await?;
+        let value: Option<Vec<u8>> = conn
+            .get(Self::redis_key(key))
+            .await
+            .map_err(|e| DomainError::internal(format!("redis get error: {e}")))?;
+        Ok(value.map(Bytes::from))
+    }
+
+    #[instrument(skip(self, value))]
+    async fn set(&self, key: &str, value: Bytes, ttl: Duration) -> Result<(), DomainError> {
+        use deadpool_redis::redis::AsyncCommands;
+        // Redis rejects `EX 0`; round sub-second TTLs up to one second.
+        let secs = ttl.as_secs().max(1);
+        let mut conn = self.conn().await?;
+        let _: () = conn
+            .set_ex(Self::redis_key(key), value.as_ref(), secs)
+            .await
+            .map_err(|e| DomainError::internal(format!("redis set error: {e}")))?;
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    async fn invalidate(&self, key: &str) -> Result<(), DomainError> {
+        use deadpool_redis::redis::AsyncCommands;
+        let mut conn = self.conn().await?;
+        let _: () = conn
+            .del(Self::redis_key(key))
+            .await
+            .map_err(|e| DomainError::internal(format!("redis del error: {e}")))?;
+        Ok(())
+    }
+}
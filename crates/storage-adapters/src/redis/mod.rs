@@ -1,10 +1,14 @@
-//! Redis rate limiter adapter (`redis` feature).
+//! Redis adapters (`redis` feature).
 //!
 //! Implements `RateLimiter` using a sliding window counter stored in Redis.
 //! Each key is `rl:{ip_hash}:{board_id}` with a TTL equal to the window.
+//! `RedisCacheStore` (in `cache_store`) implements `CacheStore`.
 
+pub mod cache_store;
 pub mod connection;
 
+pub use cache_store::RedisCacheStore;
+
 use async_trait::async_trait;
 use deadpool_redis::Pool;
 use domains::errors::DomainError;
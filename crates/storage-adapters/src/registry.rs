@@ -0,0 +1,134 @@
+//! Backends selected by name at runtime.
+//!
+//! A `Registry` maps backend names (`local`, `s3`, `redis`, ...) to
+//! constructors. The composition root registers one constructor per backend
+//! compiled into the binary — `#[cfg(feature)]` appears only at registration —
+//! and builds the one its settings name. A binary built with several backends
+//! can then switch between them through configuration, without recompiling.
+//!
+//! When no name is configured the first registered backend is used, so
+//! registration order is the default precedence.
+
+use std::future::Future;
+use std::pin::Pin;
+
+/// Future returned by a backend constructor.
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+type Constructor<C, T> = Box<dyn for<'a> Fn(&'a C) -> BoxFuture<'a, anyhow::Result<T>> + Send + Sync>;
+
+/// Named constructors of one kind of backend, building a `T` from config `C`.
+pub struct Registry<C: ?Sized, T> {
+    kind:     &'static str,
+    backends: Vec<(&'static str, Constructor<C, T>)>,
+}
+
+impl<C: ?Sized, T> Registry<C, T> {
+    /// An empty registry; `kind` (e.g. `"media storage"`) names it in errors.
+    pub fn new(kind: &'static str) -> Self {
+        Self { kind, backends: Vec::new() }
+    }
+
+    /// Register `constructor` under `name`, replacing any earlier one.
+    pub fn register<F>(mut self, name: &'static str, constructor: F) -> Self
+    where
+        F: for<'a> Fn(&'a C) -> BoxFuture<'a, anyhow::Result<T>> + Send + Sync + 'static,
+    {
+        self.backends.retain(|(n, _)| *n != name);
+        self.backends.push((name, Box::new(constructor)));
+        self
+    }
+
+    /// Registered names, in registration order.
+    pub fn names(&self) -> Vec<&'static str> {
+        self.backends.iter().map(|(name, _)| *name).collect()
+    }
+
+    /// The backend `requested` names (case-insensitive), or the first
+    /// registered one when `None`.
+    pub fn select(&self, requested: Option<&str>) -> anyhow::Result<&'static str> {
+        self.find(requested).map(|(name, _)| *name)
+    }
+
+    /// Build the backend `requested` names (see `select`), returning its name
+    /// with it.
+    pub async fn build(&self, requested: Option<&str>, config: &C) -> anyhow::Result<(&'static str, T)> {
+        let (name, constructor) = self.find(requested)?;
+        let backend = constructor(config)
+            .await
+            .map_err(|e| e.context(format!("failed to build {} backend `{name}`", self.kind)))?;
+        Ok((name, backend))
+    }
+
+    fn find(&self, requested: Option<&str>) -> anyhow::Result<&(&'static str, Constructor<C, T>)> {
+        let found = match requested.map(str::trim) {
+            Some(name) => self.backends.iter().find(|(n, _)| n.eq_ignore_ascii_case(name)),
+            None => self.backends.first(),
+        };
+        match found {
+            Some(backend) => Ok(backend),
+            None => Err(anyhow::anyhow!(
+                "{} backend {:?} is not available; this build has: {}",
+                self.kind,
+                requested.unwrap_or_default(),
+                match self.names().join(", ") {
+                    names if names.is_empty() => "none".to_owned(),
+                    names => names,
+                },
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn double(n: &u32) -> BoxFuture<'_, anyhow::Result<u32>> {
+        Box::pin(async move { Ok(n * 2) })
+    }
+
+    fn square(n: &u32) -> BoxFuture<'_, anyhow::Result<u32>> {
+        Box::pin(async move { Ok(n * n) })
+    }
+
+    fn failing(_: &u32) -> BoxFuture<'_, anyhow::Result<u32>> {
+        Box::pin(async { Err(anyhow::anyhow!("unreachable")) })
+    }
+
+    fn registry() -> Registry<u32, u32> {
+        Registry::new("number").register("double", double).register("square", square).register("failing", failing)
+    }
+
+    #[tokio::test]
+    async fn first_registered_is_the_default() {
+        assert_eq!(registry().build(None, &3).await.unwrap(), ("double", 6));
+    }
+
+    #[tokio::test]
+    async fn backends_are_chosen_by_name() {
+        assert_eq!(registry().build(Some("square"), &3).await.unwrap(), ("square", 9));
+        assert_eq!(registry().select(Some(" SQUARE ")).unwrap(), "square");
+    }
+
+    #[tokio::test]
+    async fn unknown_names_list_the_available_backends() {
+        let err = registry().build(Some("cube"), &3).await.unwrap_err().to_string();
+        assert!(err.contains("\"cube\""), "{err}");
+        assert!(err.contains("double, square, failing"), "{err}");
+        let empty = Registry::<u32, u32>::new("number");
+        assert!(empty.select(None).unwrap_err().to_string().contains("has: none"));
+    }
+
+    #[tokio::test]
+    async fn constructor_errors_name_the_backend() {
+        let err = registry().build(Some("failing"), &3).await.unwrap_err();
+        assert_eq!(err.to_string(), "failed to build number backend `failing`");
+    }
+
+    #[test]
+    fn registering_a_name_again_replaces_it() {
+        let registry = registry().register("double", square);
+        assert_eq!(registry.names(), ["square", "failing", "double"]);
+    }
+}
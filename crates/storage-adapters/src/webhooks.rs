@@ -0,0 +1,429 @@
+//! Outgoing webhooks.
+//!
+//! `WebhookSubscriber` is an `EventSubscriber` that POSTs a JSON document to
+//! an operator's URL for each selected event:
+//!
+//! ```json
+//! {"id": "<delivery uuid>", "event": "ban_issued", "created_at": "...", "data": {"ban": {...}}}
+//! ```
+//!
+//! | Event | `data` |
+//! |-------|--------|
+//! | `thread_created` | `thread` |
+//! | `flag_filed` | `flag`: `id`, `post_id`, `reason`, `created_at` |
+//! | `ban_issued` | `ban`: `id`, `banned_by`, `reason`, `expires_at`, `created_at` |
+//!
+//! IP hashes are never sent. Other events are not delivered.
+//!
+//! # Signing
+//! Every request carries `X-Rusty-Board-Signature: sha256=<hex>`, the
+//! HMAC-SHA256 of the raw body under the webhook's secret, plus
+//! `X-Rusty-Board-Event` and `X-Rusty-Board-Delivery` (the `id`, unchanged
+//! across retries, for de-duplication). Receivers should compare the
+//! signature in constant time and may reject stale `created_at` values.
+//!
+//! # Delivery
+//! Each delivery runs in its own task, so a slow endpoint does not hold up the
+//! event bus. A connection error, timeout, `408`, `429` or `5xx` is retried up
+//! to `WebhookPolicy::max_attempts` times, waiting `backoff`, then twice that,
+//! and so on (at most ten minutes). Any other non-`2xx` status is final.
+//! Every attempt is logged with the endpoint's host, never its full URL,
+//! which may embed a token. Pending retries are lost on shutdown.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use chrono::Utc;
+use domains::errors::DomainError;
+use domains::ports::{DomainEvent, EventSubscriber};
+use hmac::{Hmac, Mac};
+use reqwest::{StatusCode, Url};
+use serde_json::json;
+use sha2::Sha256;
+use uuid::Uuid;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Events a webhook can subscribe to.
+pub const WEBHOOK_EVENTS: [&str; 3] = ["thread_created", "flag_filed", "ban_issued"];
+
+/// Longest wait between two attempts.
+const MAX_BACKOFF: Duration = Duration::from_secs(600);
+
+/// Timeouts and retries shared by all webhooks.
+#[derive(Debug, Clone, Copy)]
+pub struct WebhookPolicy {
+    /// Attempts per delivery, the first included.
+    pub max_attempts: u32,
+    /// Wait before the first retry; doubles for each later one.
+    pub backoff:      Duration,
+    /// Time limit of one request.
+    pub timeout:      Duration,
+}
+
+/// One payload on its way to a webhook.
+#[derive(Debug, Clone)]
+pub struct Delivery {
+    pub id:    Uuid,
+    pub event: &'static str,
+    pub body:  Vec<u8>,
+}
+
+/// `EventSubscriber` delivering events to one webhook URL.
+#[derive(Clone)]
+pub struct WebhookSubscriber {
+    name:   String,
+    url:    Url,
+    secret: Arc<[u8]>,
+    /// Subscribed events; all of `WEBHOOK_EVENTS` when empty.
+    events: Vec<&'static str>,
+    policy: WebhookPolicy,
+    client: reqwest::Client,
+}
+
+impl WebhookSubscriber {
+    /// A webhook posting to `url`, signed with `secret`, for `events` (all
+    /// when empty). Fails on a malformed URL, an empty secret or an unknown
+    /// event name.
+    pub fn new(url: &str, secret: &[u8], events: &[String], policy: WebhookPolicy) -> Result<Self, DomainError> {
+        let url = Url::parse(url).map_err(|e| DomainError::internal(format!("webhook url: {e}")))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(DomainError::internal(format!("webhook for {}: url must be http or https", host(&url))));
+        }
+        if secret.is_empty() {
+            return Err(DomainError::internal(format!("webhook for {}: secret is empty", host(&url))));
+        }
+        let events = events
+            .iter()
+            .map(|name| {
+                WEBHOOK_EVENTS.iter().copied().find(|e| e.eq_ignore_ascii_case(name.trim())).ok_or_else(|| {
+                    DomainError::internal(format!(
+                        "webhook event {name:?} is unknown; expected one of: {}",
+                        WEBHOOK_EVENTS.join(", ")
+                    ))
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        let client = reqwest::Client::builder()
+            .timeout(policy.timeout)
+            .user_agent(concat!("rusty-board/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .map_err(|e| DomainError::internal(format!("webhook client: {e}")))?;
+        Ok(Self {
+            name: format!("webhook {}", host(&url)),
+            url,
+            secret: Arc::from(secret),
+            events,
+            policy: WebhookPolicy { max_attempts: policy.max_attempts.max(1), ..policy },
+            client,
+        })
+    }
+
+    /// The delivery of `event`, or `None` if this webhook does not take it.
+    pub fn delivery(&self, event: &DomainEvent) -> Option<Delivery> {
+        let kind = event.kind();
+        if !self.events.is_empty() && !self.events.contains(&kind) {
+            return None;
+        }
+        let data = match event {
+            DomainEvent::ThreadCreated { thread } => json!({ "thread": thread }),
+            DomainEvent::FlagFiled { flag } => json!({ "flag": {
+                "id":         flag.id,
+                "post_id":    flag.post_id,
+                "reason":     flag.reason,
+                "created_at": flag.created_at,
+            }}),
+            DomainEvent::BanIssued { ban } => json!({ "ban": {
+                "id":         ban.id,
+                "banned_by":  ban.banned_by,
+                "reason":     ban.reason,
+                "expires_at": ban.expires_at,
+                "created_at": ban.created_at,
+                "shadow":     ban.shadow,
+            }}),
+            _ => return None,
+        };
+        let id = Uuid::new_v4();
+        let body = json!({ "id": id, "event": kind, "created_at": Utc::now(), "data": data });
+        Some(Delivery { id, event: kind, body: body.to_string().into_bytes() })
+    }
+
+    /// `X-Rusty-Board-Signature` of `body`.
+    pub fn signature(&self, body: &[u8]) -> String {
+        // HMAC accepts keys of any length; `new` rules out an empty one.
+        let mut mac = HmacSha256::new_from_slice(&self.secret).expect("HMAC takes any key length");
+        mac.update(body);
+        format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+    }
+
+    /// Send `delivery`, retrying as the policy allows. Returns the attempts
+    /// made on success.
+    pub async fn deliver(&self, delivery: &Delivery) -> Result<u32, DomainError> {
+        let signature = self.signature(&delivery.body);
+        let host = host(&self.url);
+        let mut attempt = 1;
+        loop {
+            let started = Instant::now();
+            let failure = match self.send(delivery, &signature).await {
+                Ok(status) if status.is_success() => {
+                    tracing::info!(
+                        webhook = %host, event = delivery.event, delivery = %delivery.id, status = status.as_u16(),
+                        attempt, elapsed_ms = started.elapsed().as_millis() as u64, "webhook delivered"
+                    );
+                    return Ok(attempt);
+                }
+                Ok(status) if !is_retryable(status) => {
+                    return Err(DomainError::internal(format!(
+                        "webhook {host}: delivery {} refused with {status}",
+                        delivery.id
+                    )));
+                }
+                Ok(status) => status.to_string(),
+                Err(e) => e.to_string(),
+            };
+            if attempt >= self.policy.max_attempts {
+                return Err(DomainError::internal(format!(
+                    "webhook {host}: delivery {} abandoned after {attempt} attempts: {failure}",
+                    delivery.id
+                )));
+            }
+            let wait = backoff(self.policy.backoff, attempt);
+            tracing::warn!(
+                webhook = %host, event = delivery.event, delivery = %delivery.id, attempt, error = %failure,
+                retry_in_ms = wait.as_millis() as u64, "webhook delivery failed; retrying"
+            );
+            tokio::time::sleep(wait).await;
+            attempt += 1;
+        }
+    }
+
+    async fn send(&self, delivery: &Delivery, signature: &str) -> Result<StatusCode, reqwest::Error> {
+        let response = self
+            .client
+            .post(self.url.clone())
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header("X-Rusty-Board-Event", delivery.event)
+            .header("X-Rusty-Board-Delivery", delivery.id.to_string())
+            .header("X-Rusty-Board-Signature", signature)
+            .body(delivery.body.clone())
+            .send()
+            .await?;
+        Ok(response.status())
+    }
+}
+
+#[async_trait]
+impl EventSubscriber for WebhookSubscriber {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn handle(&self, event: &DomainEvent) -> Result<(), DomainError> {
+        let Some(delivery) = self.delivery(event) else {
+            return Ok(());
+        };
+        let webhook = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = webhook.deliver(&delivery).await {
+                tracing::error!(event = delivery.event, delivery = %delivery.id, error = %e, "webhook delivery failed");
+            }
+        });
+        Ok(())
+    }
+}
+
+fn host(url: &Url) -> String {
+    match (url.host_str(), url.port()) {
+        (Some(host), Some(port)) => format!("{host}:{port}"),
+        (Some(host), None) => host.to_owned(),
+        (None, _) => "<no host>".to_owned(),
+    }
+}
+
+fn is_retryable(status: StatusCode) -> bool {
+    status.is_server_error() || matches!(status, StatusCode::REQUEST_TIMEOUT | StatusCode::TOO_MANY_REQUESTS)
+}
+
+/// Wait after failed attempt number `attempt` (1-based).
+fn backoff(base: Duration, attempt: u32) -> Duration {
+    base.saturating_mul(1 << (attempt - 1).min(20)).min(MAX_BACKOFF)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use domains::models::{Ban, BanId, BoardId, Flag, FlagId, FlagStatus, IpHash, PostId, ThreadId, UserId};
+    use std::sync::Mutex;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    const POLICY: WebhookPolicy =
+        WebhookPolicy { max_attempts: 3, backoff: Duration::from_millis(10), timeout: Duration::from_secs(5) };
+
+    fn ban() -> DomainEvent {
+        DomainEvent::BanIssued {
+            ban: Ban {
+                id:         BanId::new(),
+                ip_hash:    IpHash::new("secret-hash"),
+                banned_by:  UserId::new(),
+                reason:     "spam".to_owned(),
+                expires_at: None,
+                created_at: Utc::now(),
+                shadow:     false,
+            },
+        }
+    }
+
+    fn flag() -> DomainEvent {
+        DomainEvent::FlagFiled {
+            flag: Flag {
+                id:               FlagId::new(),
+                post_id:          PostId::new(),
+                reason:           "off-topic".to_owned(),
+                reporter_ip_hash: IpHash::new("reporter-hash"),
+                status:           FlagStatus::Pending,
+                resolved_by:      None,
+                created_at:       Utc::now(),
+            },
+        }
+    }
+
+    fn webhook(url: &str, events: &[&str]) -> WebhookSubscriber {
+        let events: Vec<String> = events.iter().map(|e| e.to_string()).collect();
+        WebhookSubscriber::new(url, b"secret", &events, POLICY).unwrap()
+    }
+
+    /// A request as the test server saw it: lower-cased headers and body.
+    type Received = (Vec<(String, String)>, Vec<u8>);
+
+    /// An HTTP server answering with `statuses` in turn (the last one
+    /// repeats), recording each request.
+    async fn server(statuses: &'static [u16]) -> (String, Arc<Mutex<Vec<Received>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let log = received.clone();
+        tokio::spawn(async move {
+            for n in 0.. {
+                let Ok((mut socket, _)) = listener.accept().await else { return };
+                let mut buf = Vec::new();
+                let mut chunk = [0u8; 4096];
+                let (head_len, headers) = loop {
+                    let read = socket.read(&mut chunk).await.unwrap();
+                    buf.extend_from_slice(&chunk[..read]);
+                    if let Some(end) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+                        let head = String::from_utf8_lossy(&buf[..end]).into_owned();
+                        let headers: Vec<(String, String)> = head
+                            .lines()
+                            .skip(1)
+                            .filter_map(|l| l.split_once(": "))
+                            .map(|(k, v)| (k.to_ascii_lowercase(), v.to_owned()))
+                            .collect();
+                        break (end + 4, headers);
+                    }
+                };
+                let length: usize =
+                    headers.iter().find(|(k, _)| k == "content-length").map_or(0, |(_, v)| v.parse().unwrap());
+                while buf.len() < head_len + length {
+                    let read = socket.read(&mut chunk).await.unwrap();
+                    buf.extend_from_slice(&chunk[..read]);
+                }
+                log.lock().unwrap().push((headers, buf[head_len..].to_vec()));
+                let status = statuses[n.min(statuses.len() - 1)];
+                let reply = format!("HTTP/1.1 {status} X\r\ncontent-length: 0\r\nconnection: close\r\n\r\n");
+                socket.write_all(reply.as_bytes()).await.unwrap();
+            }
+        });
+        (url, received)
+    }
+
+    fn header<'a>(received: &'a Received, name: &str) -> &'a str {
+        received.0.iter().find(|(k, _)| k == name).map(|(_, v)| v.as_str()).unwrap()
+    }
+
+    #[test]
+    fn payloads_leave_out_ip_hashes() {
+        let hook = webhook("https://example.com/hook", &[]);
+        for event in [ban(), flag()] {
+            let delivery = hook.delivery(&event).unwrap();
+            let body = String::from_utf8(delivery.body).unwrap();
+            assert!(!body.contains("hash"), "{body}");
+            let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+            assert_eq!(json["event"], event.kind());
+            assert_eq!(json["id"], delivery.id.to_string());
+        }
+        let body: serde_json::Value = serde_json::from_slice(&hook.delivery(&flag()).unwrap().body).unwrap();
+        assert_eq!(body["data"]["flag"]["reason"], "off-topic");
+    }
+
+    #[test]
+    fn event_filter_selects_deliveries() {
+        let hook = webhook("https://example.com/hook", &["Ban_Issued"]);
+        assert!(hook.delivery(&ban()).is_some());
+        assert!(hook.delivery(&flag()).is_none());
+        let deleted =
+            DomainEvent::PostDeleted { board_id: BoardId::new(), thread_id: ThreadId::new(), post_id: PostId::new() };
+        assert!(webhook("https://example.com/hook", &[]).delivery(&deleted).is_none());
+    }
+
+    #[test]
+    fn misconfiguration_is_refused() {
+        let none: &[String] = &[];
+        assert!(WebhookSubscriber::new("not a url", b"secret", none, POLICY).is_err());
+        assert!(WebhookSubscriber::new("ftp://example.com", b"secret", none, POLICY).is_err());
+        assert!(WebhookSubscriber::new("https://example.com", b"", none, POLICY).is_err());
+        let err = WebhookSubscriber::new("https://example.com", b"secret", &["post_created".to_owned()], POLICY);
+        assert!(err.err().unwrap().to_string().contains("thread_created, flag_filed, ban_issued"));
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        let base = Duration::from_secs(2);
+        assert_eq!(backoff(base, 1), Duration::from_secs(2));
+        assert_eq!(backoff(base, 3), Duration::from_secs(8));
+        assert_eq!(backoff(base, 40), MAX_BACKOFF);
+    }
+
+    #[tokio::test]
+    async fn deliveries_are_signed() {
+        let (url, received) = server(&[204]).await;
+        let hook = webhook(&url, &[]);
+        let delivery = hook.delivery(&ban()).unwrap();
+        assert_eq!(hook.deliver(&delivery).await.unwrap(), 1);
+
+        let received = received.lock().unwrap();
+        let request = &received[0];
+        assert_eq!(request.1, delivery.body);
+        assert_eq!(header(request, "content-type"), "application/json");
+        assert_eq!(header(request, "x-rusty-board-event"), "ban_issued");
+        assert_eq!(header(request, "x-rusty-board-delivery"), delivery.id.to_string());
+        let mut mac = HmacSha256::new_from_slice(b"secret").unwrap();
+        mac.update(&delivery.body);
+        let expected = format!("sha256={}", hex::encode(mac.finalize().into_bytes()));
+        assert_eq!(header(request, "x-rusty-board-signature"), expected);
+    }
+
+    #[tokio::test]
+    async fn server_errors_are_retried() {
+        let (url, received) = server(&[503, 429, 200]).await;
+        let hook = webhook(&url, &[]);
+        assert_eq!(hook.deliver(&hook.delivery(&ban()).unwrap()).await.unwrap(), 3);
+        let received = received.lock().unwrap();
+        assert_eq!(header(&received[0], "x-rusty-board-delivery"), header(&received[2], "x-rusty-board-delivery"));
+    }
+
+    #[tokio::test]
+    async fn retries_stop_at_max_attempts_or_a_client_error() {
+        let (url, received) = server(&[500]).await;
+        let hook = webhook(&url, &[]);
+        let err = hook.deliver(&hook.delivery(&ban()).unwrap()).await.unwrap_err();
+        assert!(err.to_string().contains("after 3 attempts"), "{err}");
+        assert_eq!(received.lock().unwrap().len(), 3);
+
+        let (url, received) = server(&[410]).await;
+        let hook = webhook(&url, &[]);
+        assert!(hook.deliver(&hook.delivery(&ban()).unwrap()).await.is_err());
+        assert_eq!(received.lock().unwrap().len(), 1);
+    }
+}
@@ -235,7 +235,10 @@ if [ -n "${THREAD_ID:-}" ]; then
   # Use a timestamp in the body to avoid duplicate-content detection on re-runs.
   # Use staff auth token (if available) to bypass rate limiting.
   UNIQUE_BODY="live-test reply $(date +%s)"
-  POST_ARGS=(-X POST -F "thread_id=$THREAD_ID" -F "body=$UNIQUE_BODY")
+  # Without a token, a form post needs the csrf cookie and the same value in
+  # the header (double-submit); any 64 hex digits will do.
+  CSRF=$(printf '%064d' 0)
+  POST_ARGS=(-X POST -F "thread_id=$THREAD_ID" -F "body=$UNIQUE_BODY" -b "csrf=$CSRF" -H "X-CSRF-Token: $CSRF")
   [ -n "${TOKEN:-}" ] && POST_ARGS+=(-H "Authorization: Bearer $TOKEN")
   PS=$(curl -s -o /dev/null -w "%{http_code}" "${POST_ARGS[@]}" "$BASE/board/$SLUG/post")
   if [ "$PS" = "303" ] || [ "$PS" = "302" ]; then
@@ -0,0 +1,72 @@
+#!/usr/bin/env python3
+# scripts/nsfw_classify.py — Example NSFW runner for the `nsfw-onnx` feature.
+#
+# Reads one image on stdin, runs it through an ONNX image classifier and prints
+# the class scores as JSON on stdout:
+#
+#   [{"label": "porn", "score": 0.97}, {"label": "neutral", "score": 0.02}, ...]
+#
+# Defaults match the GantMan/nsfw_model MobileNet export (224x224 RGB input
+# scaled to 0..1, NHWC, five softmax outputs). Other models can be used by
+# adjusting the environment variables below.
+#
+# Environment variables:
+#   NSFW_LABELS      — Comma-separated class names in output order
+#                      (default: drawings,hentai,neutral,porn,sexy)
+#   NSFW_INPUT_SIZE  — Square input size in pixels (default: 224)
+#   NSFW_LAYOUT      — NHWC or NCHW (default: NHWC)
+#
+# Usage (as invoked by rusty-board):
+#   ./scripts/nsfw_classify.py ./nsfw.onnx < image.jpg
+#
+# Requires: pip install onnxruntime pillow numpy
+#
+# Exit codes:
+#   0 — success
+#   1 — bad arguments, unreadable image or model failure
+
+import io
+import json
+import os
+import sys
+
+import numpy as np
+import onnxruntime as ort
+from PIL import Image
+
+
+def main() -> int:
+    if len(sys.argv) != 2:
+        print("usage: nsfw_classify.py <model.onnx> < image", file=sys.stderr)
+        return 1
+
+    labels = os.environ.get("NSFW_LABELS", "drawings,hentai,neutral,porn,sexy").split(",")
+    size = int(os.environ.get("NSFW_INPUT_SIZE", "224"))
+    layout = os.environ.get("NSFW_LAYOUT", "NHWC").upper()
+
+    try:
+        image = Image.open(io.BytesIO(sys.stdin.buffer.read()))
+        image.seek(0)  # first frame of animated GIF/WebP
+        image = image.convert("RGB").resize((size, size), Image.BILINEAR)
+    except Exception as e:  # noqa: BLE001 — any decode failure is reported the same way
+        print(f"unreadable image: {e}", file=sys.stderr)
+        return 1
+
+    tensor = np.asarray(image, dtype=np.float32)[np.newaxis, ...] / 255.0
+    if layout == "NCHW":
+        tensor = tensor.transpose(0, 3, 1, 2)
+
+    session = ort.InferenceSession(sys.argv[1], providers=["CPUExecutionProvider"])
+    input_name = session.get_inputs()[0].name
+    scores = session.run(None, {input_name: tensor})[0][0]
+
+    result = [
+        {"label": label.strip(), "score": round(float(score), 4)}
+        for label, score in zip(labels, scores)
+    ]
+    json.dump(result, sys.stdout)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
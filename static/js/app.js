@@ -41,30 +41,81 @@
     }
   });
 
-  // ── Image expand ─────────────────────────────────────────────────────────────
-  // Clicking a thumbnail expands it to full size in-place.
-
-  document.addEventListener("click", function (e) {
-    const thumb = e.target.closest(".attachment img");
-    if (!thumb) return;
-    e.preventDefault();
+  // ── Click-to-expand files ───────────────────────────────────────────────────
+  // Clicking an image thumbnail swaps in the full image in place instead of
+  // opening a new tab; clicking again restores the thumbnail. A video
+  // thumbnail is replaced by a playing <video> with a [−] link that puts the
+  // thumbnail back. Other files (PDFs) still open in a new tab. Returns true
+  // when it handled the click. Exposed so the thread page's own image-link
+  // handler can defer to it.
+
+  function expandVideo(link) {
+    const collapse = document.createElement("a");
+    collapse.href = "javascript:void(0)";
+    collapse.className = "video-collapse";
+    collapse.textContent = "[−]";
+
+    const video = document.createElement("video");
+    video.className = "post-video post-video--expanded";
+    video.src = link.href;
+    video.controls = true;
+    video.autoplay = true;
+    video.loop = true;
+
+    collapse.addEventListener("click", function () {
+      video.pause();
+      video.remove();
+      collapse.remove();
+      link.hidden = false;
+    });
+    link.hidden = true;
+    link.after(collapse, video);
+    return true;
+  }
 
-    const link = thumb.closest("a.attachment");
-    if (!link) return;
+  window.rbExpandImage = function (link) {
+    const mime = link.dataset.mime || "";
+    const thumb = link.querySelector("img.post-thumb");
+    if (!thumb || thumb.classList.contains("spoiler-thumb")) return false;
+    if (mime.startsWith("video/")) return expandVideo(link);
+    if (!mime.startsWith("image/")) return false;
 
-    if (thumb.dataset.expanded === "1") {
-      // Collapse back to thumbnail
-      thumb.style.maxWidth = "";
-      thumb.style.maxHeight = "";
-      thumb.dataset.expanded = "";
+    if (thumb.classList.contains("post-thumb--expanded")) {
+      thumb.src = thumb.dataset.thumbSrc;
+      thumb.classList.remove("post-thumb--expanded");
     } else {
-      // Expand to full
-      thumb.style.maxWidth = "100%";
-      thumb.style.maxHeight = "none";
-      thumb.dataset.expanded = "1";
+      thumb.dataset.thumbSrc = thumb.src;
+      thumb.src = link.href;
+      thumb.classList.add("post-thumb--expanded");
     }
+    return true;
+  };
+
+  document.addEventListener("click", function (e) {
+    if (e.defaultPrevented) return;
+    const link = e.target.closest("a.image-link");
+    if (link && window.rbExpandImage(link)) e.preventDefault();
   });
 
+  // ── Collapsed post forms ───────────────────────────────────────────────────
+  // Post forms marked data-mobile-collapse start closed on small screens so
+  // the posts are what the reader sees first.
+
+  const narrow = window.matchMedia("(max-width: 600px)");
+
+  function collapsePostForms() {
+    if (!narrow.matches) return;
+    document.querySelectorAll("details[data-mobile-collapse]").forEach(function (d) {
+      d.open = false;
+    });
+  }
+
+  if (document.readyState === "loading") {
+    document.addEventListener("DOMContentLoaded", collapsePostForms);
+  } else {
+    collapsePostForms();
+  }
+
   // ── Reply form prefill ────────────────────────────────────────────────────────
   // Clicking >>N in a post pre-fills the reply textarea with a quote reference.
 
@@ -96,6 +147,18 @@
     });
   });
 
+  // ── Spoiler images ────────────────────────────────────────────────────────────
+  // Spoilered attachments render a placeholder; the first click swaps in the
+  // real thumbnail instead of following the link.
+
+  document.addEventListener("click", function (e) {
+    const thumb = e.target.closest("img.spoiler-thumb");
+    if (!thumb || !thumb.dataset.thumb) return;
+    e.preventDefault();
+    thumb.src = thumb.dataset.thumb;
+    thumb.classList.remove("spoiler-thumb");
+  });
+
   // ── Spoiler text toggle ───────────────────────────────────────────────────────
 
   document.addEventListener("click", function (e) {
@@ -96,6 +96,18 @@
     });
   });
 
+  // ── Spoiler images ────────────────────────────────────────────────────────────
+  // Spoilered attachments render a placeholder; the first click swaps in the
+  // real thumbnail instead of following the link.
+
+  document.addEventListener("click", function (e) {
+    const thumb = e.target.closest("img.spoiler-thumb");
+    if (!thumb || !thumb.dataset.thumb) return;
+    e.preventDefault();
+    thumb.src = thumb.dataset.thumb;
+    thumb.classList.remove("spoiler-thumb");
+  });
+
   // ── Spoiler text toggle ───────────────────────────────────────────────────────
 
   document.addEventListener("click", function (e) {